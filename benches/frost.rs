@@ -0,0 +1,250 @@
+//! FROST keygen / sign / combine benchmarks at n=15, t=10
+//!
+//! Run with `cargo bench --bench frost`. The combine group compares schnorr_fun's
+//! verifying combiner against the batched helper used by `dkg-broadcast`, and the
+//! coefficient groups compare per-party, batched and cached computation. The
+//! local signing group runs `frost_sign_local_core` on one thread and on the
+//! default rayon pool.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use frostdao::crypto::birkhoff::{
+    cached_birkhoff_coefficients, compute_birkhoff_coefficients, BirkhoffParameter,
+};
+use frostdao::crypto::helpers::{
+    aggregate_signature_shares, cached_lagrange_coefficients, lagrange_coefficient_at_zero,
+    lagrange_coefficients_at_zero,
+};
+use schnorr_fun::binonce::NonceKeyPair;
+use schnorr_fun::frost::{self, chilldkg::simplepedpop};
+use schnorr_fun::Message;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+
+const N: u32 = 15;
+const T: u32 = 10;
+
+fn bench_keygen(c: &mut Criterion) {
+    let frost = frost::new_with_deterministic_nonces::<Sha256>();
+    c.bench_function("keygen n=15 t=10", |b| {
+        b.iter(|| simplepedpop::simulate_keygen(&frost.schnorr, T, N, N, &mut rand::thread_rng()))
+    });
+}
+
+fn bench_sign_and_combine(c: &mut Criterion) {
+    let frost = frost::new_with_deterministic_nonces::<Sha256>();
+    let mut rng = rand::thread_rng();
+    let (shared_key, shares) = simplepedpop::simulate_keygen(&frost.schnorr, T, N, N, &mut rng);
+    let shared_key = shared_key.into_xonly();
+    let signers: Vec<_> = shares
+        .into_iter()
+        .take(T as usize)
+        .map(|share| share.into_xonly())
+        .collect();
+    let message = Message::raw(b"frostdao benchmark sighash.......");
+
+    let nonces: Vec<NonceKeyPair> = signers
+        .iter()
+        .map(|_| NonceKeyPair::random(&mut rng))
+        .collect();
+    let public_nonces: BTreeMap<_, _> = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(share, nonce)| (share.index(), nonce.public()))
+        .collect();
+    let coord_session = frost.coordinator_sign_session(&shared_key, public_nonces, message);
+    let sign_session = frost.party_sign_session(
+        shared_key.public_key(),
+        coord_session.parties(),
+        coord_session.agg_binonce(),
+        message,
+    );
+
+    c.bench_function("sign t=10 (all parties)", |b| {
+        b.iter(|| {
+            signers
+                .iter()
+                .zip(&nonces)
+                .map(|(share, nonce)| sign_session.sign(share, nonce.clone()))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let sig_shares: BTreeMap<_, _> = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(share, nonce)| (share.index(), sign_session.sign(share, nonce.clone())))
+        .collect();
+    let share_list: Vec<_> = sig_shares.values().copied().collect();
+
+    let mut group = c.benchmark_group("combine t=10");
+    group.bench_function("verify_and_combine", |b| {
+        b.iter(|| {
+            coord_session
+                .verify_and_combine_signature_shares(&shared_key, sig_shares.clone())
+                .unwrap()
+        })
+    });
+    group.bench_function("aggregate_signature_shares", |b| {
+        b.iter(|| aggregate_signature_shares(black_box(&share_list)))
+    });
+    group.finish();
+}
+
+fn bench_coefficients(c: &mut Criterion) {
+    let indices: Vec<u32> = (1..=T).collect();
+
+    let mut group = c.benchmark_group("lagrange t=10");
+    group.bench_function("per party", |b| {
+        b.iter(|| {
+            indices
+                .iter()
+                .map(|&i| lagrange_coefficient_at_zero(i, black_box(&indices)).unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("batched", |b| {
+        b.iter(|| lagrange_coefficients_at_zero(black_box(&indices)).unwrap())
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| cached_lagrange_coefficients(black_box(&indices)).unwrap())
+    });
+    group.finish();
+
+    // HTSS signer set: one rank-0 party, the rest rank 1
+    let signers: Vec<(u32, u32)> = (1..=T).map(|i| (i, u32::from(i > 1))).collect();
+    let params: Vec<BirkhoffParameter> = signers
+        .iter()
+        .map(|&(x, rank)| BirkhoffParameter::new(x, rank))
+        .collect();
+
+    let mut group = c.benchmark_group("birkhoff t=10");
+    group.bench_function("solve", |b| {
+        b.iter(|| compute_birkhoff_coefficients(black_box(&params)).unwrap())
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| cached_birkhoff_coefficients(black_box(&signers)).unwrap())
+    });
+    group.finish();
+}
+
+/// `frost_sign_local_core` needs the chain backend, which only exists with `network`
+#[cfg(feature = "network")]
+mod local_signing {
+    use super::*;
+    use bitcoin::key::XOnlyPublicKey;
+    use bitcoin::{Address, Network};
+    use frostdao::btc::transaction::{ChainBackend, FeeEstimate, UtxoResponse, UtxoStatus};
+    use frostdao::protocol::{dkg_tx, keygen};
+    use frostdao::storage::{MemoryStorage, Storage};
+
+    /// One confirmed UTXO on every address; broadcasts are accepted and dropped
+    struct BenchChain;
+
+    impl ChainBackend for BenchChain {
+        fn utxos(&self, _address: &str) -> anyhow::Result<Vec<UtxoResponse>> {
+            Ok(vec![UtxoResponse {
+                txid: "aa".repeat(32),
+                vout: 0,
+                status: UtxoStatus {
+                    confirmed: true,
+                    block_height: Some(1),
+                },
+                value: 1_000_000,
+            }])
+        }
+
+        fn fee_estimates(&self) -> anyhow::Result<FeeEstimate> {
+            Ok(FeeEstimate {
+                fastest_fee: 2,
+                half_hour_fee: 2,
+                hour_fee: 1,
+                economy_fee: 1,
+                minimum_fee: 1,
+            })
+        }
+
+        fn broadcast(&self, _raw_tx_hex: &str) -> anyhow::Result<String> {
+            Ok(String::new())
+        }
+    }
+
+    pub fn bench_sign_all_local(c: &mut Criterion) {
+        let parties: Vec<MemoryStorage> = (0..N).map(|_| MemoryStorage::new()).collect();
+        let round1: Vec<String> = parties
+            .iter()
+            .enumerate()
+            .map(|(i, storage)| {
+                keygen::round1_core(T, N, i as u32 + 1, 0, false, storage)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        let round2: Vec<String> = parties
+            .iter()
+            .map(|storage| {
+                keygen::round2_core(&round1.join(" "), storage)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        for storage in &parties {
+            keygen::finalize_core(&round2.join(" "), storage).unwrap();
+        }
+
+        let shared_key: frost::SharedKey<secp256kfun::marker::EvenY> =
+            bincode::deserialize(&parties[0].read("shared_key.bin").unwrap()).unwrap();
+        let xonly = XOnlyPublicKey::from_slice(&shared_key.public_key().to_xonly_bytes()).unwrap();
+        let to = Address::p2tr(
+            &bitcoin::secp256k1::Secp256k1::new(),
+            xonly,
+            None,
+            Network::Testnet,
+        )
+        .to_string();
+        let sign = || {
+            let signers: Vec<(u32, &dyn Storage)> = (1..=T)
+                .map(|i| (i, &parties[i as usize - 1] as &dyn Storage))
+                .collect();
+            dkg_tx::frost_sign_local_core(
+                "bench",
+                &parties[0],
+                &signers,
+                &to,
+                10_000,
+                Some((0, 1)),
+                Some(2),
+                Network::Testnet,
+                true,
+                None,
+                &BenchChain,
+            )
+            .unwrap()
+        };
+
+        let mut group = c.benchmark_group("frost_sign_all_local t=10");
+        let single = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        group.bench_function("1 thread", |b| b.iter(|| single.install(sign)));
+        group.bench_function("rayon pool", |b| b.iter(sign));
+        group.finish();
+    }
+}
+
+#[cfg(feature = "network")]
+criterion_group!(
+    benches,
+    bench_keygen,
+    bench_sign_and_combine,
+    bench_coefficients,
+    local_signing::bench_sign_all_local
+);
+#[cfg(not(feature = "network"))]
+criterion_group!(
+    benches,
+    bench_keygen,
+    bench_sign_and_combine,
+    bench_coefficients
+);
+criterion_main!(benches);
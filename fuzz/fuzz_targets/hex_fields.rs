@@ -0,0 +1,24 @@
+//! Hex/bincode fields inside payloads: nonces, shares and party indices
+#![no_main]
+
+use frostdao::protocol::payload::{decode_hex_field, share_index};
+use libfuzzer_sys::fuzz_target;
+use schnorr_fun::binonce::Nonce;
+use secp256kfun::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    // Raw bytes as hex, so bincode sees arbitrary input
+    let hex: String = data.iter().map(|b| format!("{:02x}", b)).collect();
+    let _ = decode_hex_field::<Nonce>("nonce", &hex);
+    let _ = decode_hex_field::<Scalar<Public, Zero>>("signature share", &hex);
+    let _ = decode_hex_field::<Scalar<Secret, Zero>>("keygen share", &hex);
+
+    // And the text itself, for malformed hex
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = decode_hex_field::<Nonce>("nonce", text);
+    }
+    if let Some(index) = data.get(..4) {
+        let index = u32::from_le_bytes(index.try_into().unwrap());
+        assert_eq!(share_index(index).is_ok(), index != 0);
+    }
+});
@@ -0,0 +1,10 @@
+//! NIP-44 decryption of encrypted keygen shares and Nostr DMs
+#![no_main]
+
+use frostdao::crypto::nip44;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: ([u8; 32], &str)| {
+    let (key, payload) = input;
+    let _ = nip44::decrypt(&key, payload);
+});
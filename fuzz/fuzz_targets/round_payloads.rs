@@ -0,0 +1,24 @@
+//! Batch validation of pasted keygen and signing payloads
+#![no_main]
+
+use frostdao::protocol::keygen::{Round1Output, Round2Output};
+use frostdao::protocol::payload::{parse_payloads, Expected};
+use frostdao::protocol::signing::{NonceOutput, SignatureShareOutput};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    let _ =
+        parse_payloads::<Round1Output>(data, &Expected::new("round 1 outputs", &["keygen_round1"]));
+    let _ =
+        parse_payloads::<Round2Output>(data, &Expected::new("round 2 outputs", &["keygen_round2"]));
+    let session = Expected {
+        session: Some("fuzz"),
+        sighash: Some("00"),
+        ..Expected::new("signing nonces", &["signing_nonce"])
+    };
+    let _ = parse_payloads::<NonceOutput>(data, &session);
+    let _ = parse_payloads::<SignatureShareOutput>(
+        data,
+        &Expected::new("signature shares", &["signing_share"]),
+    );
+});
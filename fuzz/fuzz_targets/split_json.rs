@@ -0,0 +1,15 @@
+//! `parse_space_separated_json`: the splitter every pasted batch goes through
+#![no_main]
+
+use frostdao::protocol::keygen::parse_space_separated_json;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    if let Ok(values) = parse_space_separated_json::<serde_json::Value>(data) {
+        // Whatever was split out must split the same way when re-joined
+        let rejoined: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let again = parse_space_separated_json::<serde_json::Value>(&rejoined.join(" "))
+            .expect("re-joined batch parses");
+        assert_eq!(again, values);
+    }
+});
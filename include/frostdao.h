@@ -0,0 +1,72 @@
+/*
+ * FrostDAO C API (src/ffi.rs)
+ *
+ * Link against the frostdao cdylib/staticlib. Every function returns a
+ * heap-allocated JSON envelope that must be released with
+ * frostdao_string_free():
+ *
+ *   {"ok":true,"output":"...","result":...}
+ *   {"ok":false,"code":"invalid_payload","message":"..."}
+ *
+ * `wallet_dir` is a directory owned by the app (created if missing). All
+ * strings are NUL-terminated UTF-8; arguments documented as optional may
+ * be NULL.
+ */
+
+#ifndef FROSTDAO_H
+#define FROSTDAO_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+void frostdao_string_free(char *ptr);
+
+/* Keygen */
+char *frostdao_keygen_round1(const char *wallet_dir, uint32_t threshold,
+                             uint32_t n_parties, uint32_t my_index,
+                             uint32_t rank, bool hierarchical);
+char *frostdao_keygen_round2(const char *wallet_dir, const char *data);
+char *frostdao_keygen_finalize(const char *wallet_dir, const char *data);
+
+/* Message signing */
+char *frostdao_generate_nonce(const char *wallet_dir, const char *session);
+char *frostdao_sign(const char *wallet_dir, const char *session,
+                    const char *message, const char *nonces);
+char *frostdao_combine(const char *wallet_dir, const char *shares);
+char *frostdao_verify(const char *signature, const char *public_key,
+                      const char *message);
+
+/* Reshare */
+char *frostdao_reshare_round1(const char *wallet_dir, uint32_t new_threshold,
+                              uint32_t new_n_parties, uint32_t my_old_index,
+                              const char *new_identities);
+char *frostdao_reshare_finalize(const char *source_dir, const char *target_dir,
+                                uint32_t my_new_index, uint32_t my_rank,
+                                bool hierarchical, const char *round1_data,
+                                bool force_overwrite,
+                                bool allow_threshold_decrease);
+
+/* HD derivation and transactions */
+char *frostdao_derive_address(const char *wallet_dir, uint32_t change,
+                              uint32_t index, const char *network);
+/* Only in builds with the `network` feature; fee_rate 0 = estimate,
+ * from_script_address optional */
+char *frostdao_build_tx(const char *wallet_dir, const char *to_address,
+                        uint64_t amount_sats, uint64_t fee_rate,
+                        const char *network, const char *from_script_address);
+char *frostdao_tx_nonce(const char *wallet_dir, const char *session_id);
+/* merkle_root optional (NULL for key-path spends); tx is the frostdao_build_tx
+ * result to review, NULL only for the party that built it */
+char *frostdao_tx_sign(const char *wallet_dir, const char *session_id,
+                       const char *sighash, const char *nonces,
+                       const char *merkle_root, const char *tx);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FROSTDAO_H */
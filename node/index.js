@@ -0,0 +1,108 @@
+// FrostDAO for Node.js
+//
+// Thin wrapper over the WASM module built by scripts/node-build.sh. Wallet
+// state is kept in the same folder layout as the CLI (.frost_state/<name>),
+// so a service and the `frostdao` binary can work on the same wallets.
+
+"use strict";
+
+const fs = require("fs");
+const path = require("path");
+const frost = require("./pkg/frostdao.js");
+
+/** FrostStore over one wallet folder: one file per key, raw bytes on disk */
+class FileStore {
+  constructor(dir) {
+    this.dir = dir;
+    fs.mkdirSync(dir, { recursive: true });
+  }
+
+  file(key) {
+    if (key.includes("/") || key.includes("\\") || key.startsWith(".")) {
+      throw new Error(`Invalid storage key: ${key}`);
+    }
+    return path.join(this.dir, key);
+  }
+
+  get(key) {
+    const file = this.file(key);
+    return fs.existsSync(file) ? fs.readFileSync(file).toString("base64") : undefined;
+  }
+
+  set(key, value) {
+    fs.writeFileSync(this.file(key), Buffer.from(value, "base64"));
+  }
+
+  // Overwrite before unlinking, like the CLI, so spent nonces don't linger
+  delete(key) {
+    const file = this.file(key);
+    if (fs.existsSync(file)) {
+      fs.writeFileSync(file, Buffer.alloc(fs.statSync(file).size));
+      fs.unlinkSync(file);
+    }
+  }
+}
+
+/**
+ * Open (or create) wallet `name` under `root`. `onEvent`, if given, receives
+ * each ProtocolEvent of keygen and signing steps as it happens.
+ */
+function openWallet(name, { root = ".frost_state", onEvent } = {}) {
+  const store = new FileStore(path.join(root, name));
+  const target = (targetName) => new FileStore(path.join(root, targetName));
+
+  return {
+    name,
+    store,
+
+    keygenRound1({ threshold, nParties, myIndex, rank = 0, hierarchical = false }) {
+      return frost.wasm_store_keygen_round1(store, threshold, nParties, myIndex, rank, hierarchical, onEvent);
+    },
+    keygenRound2(round1) {
+      return frost.wasm_store_keygen_round2(store, round1, onEvent);
+    },
+    keygenFinalize(round2) {
+      return frost.wasm_store_keygen_finalize(store, round2, onEvent);
+    },
+
+    nonce(session) {
+      return frost.wasm_store_generate_nonce(store, session, onEvent);
+    },
+    sign(session, message, nonces) {
+      return frost.wasm_store_sign(store, session, message, nonces, onEvent);
+    },
+    combine(shares) {
+      return frost.wasm_store_combine(store, shares, onEvent);
+    },
+
+    reshareRound1({ newThreshold, newParties, myOldIndex }) {
+      return frost.wasm_store_reshare_round1(store, name, newThreshold, newParties, myOldIndex);
+    },
+    reshareVerify({ myNewIndex, round1, allowThresholdDecrease = false }) {
+      return frost.wasm_store_reshare_verify(store, myNewIndex, round1, allowThresholdDecrease);
+    },
+    reshareFinalize(targetName, {
+      myNewIndex, rank = 0, hierarchical = false, round1, force = false, allowThresholdDecrease = false,
+    }) {
+      return frost.wasm_store_reshare_finalize(
+        store, target(targetName), targetName, myNewIndex, rank, hierarchical, round1, force,
+        allowThresholdDecrease
+      );
+    },
+    recoverRound1(lostIndex) {
+      return frost.wasm_store_recover_round1(store, name, lostIndex);
+    },
+    recoverFinalize(targetName, { myIndex, round1, force = false }) {
+      return frost.wasm_store_recover_finalize(
+        store, target(targetName), name, targetName, myIndex, round1, force
+      );
+    },
+  };
+}
+
+/** Check a combined signature; needs no wallet */
+function verify(signature, publicKey, message) {
+  return frost.wasm_store_verify(signature, publicKey, message);
+}
+
+module.exports = { FileStore, openWallet, verify, FrostError: frost.FrostError };
@@ -0,0 +1,37 @@
+"""Simulate a 2-of-3 group in one process: keygen, signing, bad payloads.
+
+Run after `maturin develop` (or `pip install .`): `pytest python/tests`.
+"""
+
+import pytest
+
+import frostdao
+
+
+@pytest.fixture
+def group(tmp_path):
+    dirs = [str(tmp_path / f"party{i}") for i in (1, 2, 3)]
+    round1 = [frostdao.keygen_round1(d, 2, 3, i)["result"] for i, d in enumerate(dirs, 1)]
+    round2 = [frostdao.keygen_round2(d, round1)["result"] for d in dirs]
+    keys = {frostdao.keygen_finalize(d, round2)["result"]["public_key"] for d in dirs}
+    assert len(keys) == 1
+    return dirs
+
+
+def test_two_of_three_signature(group):
+    signers, message = group[:2], "treasury payout #1"
+
+    nonces = [frostdao.generate_nonce(d, "s1")["result"] for d in signers]
+    shares = [frostdao.sign(d, "s1", message, nonces)["result"] for d in signers]
+    signature = frostdao.combine(signers[0], shares)["result"]
+
+    result = frostdao.verify(signature["signature"], signature["public_key"], message)
+    assert result["result"] == "VALID"
+
+
+def test_bad_payload_raises_frost_error(group):
+    frostdao.generate_nonce(group[0], "s2")
+    with pytest.raises(frostdao.FrostError) as err:
+        frostdao.sign(group[0], "s2", "msg", ['{"type":"nonce"'])
+    code, message = err.value.args
+    assert code == "invalid_payload", message
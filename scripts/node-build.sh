@@ -0,0 +1,29 @@
+#!/bin/bash
+# Build FrostDAO WASM module for Node.js
+# Usage: ./scripts/node-build.sh
+
+set -e
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+PROJECT_ROOT="$(dirname "$SCRIPT_DIR")"
+NODE_DIR="$PROJECT_ROOT/node"
+
+cd "$PROJECT_ROOT"
+
+echo "FrostDAO Node Build"
+echo "==================="
+
+# Check for wasm-pack
+if ! command -v wasm-pack &> /dev/null; then
+    echo "Installing wasm-pack..."
+    cargo install wasm-pack
+fi
+
+# No HTTP or sync inside the module: services do their own networking
+echo "Building WASM module..."
+wasm-pack build --target nodejs --out-dir "$NODE_DIR/pkg" -- --no-default-features
+
+echo ""
+echo "WASM module built to: $NODE_DIR/pkg/"
+echo "Use it with:"
+echo '  const { openWallet } = require("./node");'
@@ -0,0 +1,150 @@
+//! Bitcoin Amounts
+//!
+//! Parsing and display of amounts with explicit units. A bare integer is sats
+//! (as the CLI always accepted); anything with a decimal point must say
+//! whether it is `btc` or `sat`, so `0.001` can't be misread by a factor of
+//! 10^8. Display is always sats followed by the exact BTC value.
+
+use anyhow::{Context, Result};
+
+pub const SATS_PER_BTC: u64 = 100_000_000;
+
+/// 21 million BTC; nothing larger is a valid amount
+pub const MAX_SATS: u64 = 21_000_000 * SATS_PER_BTC;
+
+/// Parse `1000`, `1_000_000`, `1000sat`, `1000 sats` or `0.001btc` into sats
+pub fn parse_sats(input: &str) -> Result<u64> {
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let number = number.trim();
+    if number.is_empty() {
+        anyhow::bail!("Amount '{}' has no number", input);
+    }
+    if number.starts_with('_') || number.ends_with('_') || number.contains("__") {
+        anyhow::bail!("Misplaced '_' in amount '{}'", input);
+    }
+    let number = number.replace('_', "");
+
+    let sats = match unit.to_ascii_lowercase().as_str() {
+        "" if number.contains('.') => anyhow::bail!(
+            "Ambiguous amount '{}': add a unit, e.g. {}btc or {}sat",
+            input,
+            number,
+            number
+        ),
+        "" | "sat" | "sats" => {
+            if number.contains('.') {
+                anyhow::bail!("Amount '{}' has a fraction of a sat", input);
+            }
+            parse_digits(&number, input)?
+        }
+        "btc" => parse_btc(&number, input)?,
+        other => anyhow::bail!("Unknown unit '{}' in '{}'. Use sat or btc", other, input),
+    };
+
+    if sats > MAX_SATS {
+        anyhow::bail!("Amount '{}' is more than 21 million BTC", input);
+    }
+    Ok(sats)
+}
+
+fn parse_digits(digits: &str, input: &str) -> Result<u64> {
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        anyhow::bail!("Invalid amount '{}'", input);
+    }
+    digits
+        .parse()
+        .with_context(|| format!("Amount '{}' is too large", input))
+}
+
+/// Exact BTC to sats: no floating point
+fn parse_btc(number: &str, input: &str) -> Result<u64> {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if fraction.len() > 8 {
+        anyhow::bail!("Amount '{}' has more than 8 decimal places", input);
+    }
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        parse_digits(whole, input)?
+    };
+    let fraction = if fraction.is_empty() {
+        0
+    } else {
+        parse_digits(&format!("{:0<8}", fraction), input)?
+    };
+    whole
+        .checked_mul(SATS_PER_BTC)
+        .and_then(|sats| sats.checked_add(fraction))
+        .with_context(|| format!("Amount '{}' is too large", input))
+}
+
+/// `0.00012345 BTC`
+pub fn format_btc(sats: u64) -> String {
+    format!("{}.{:08} BTC", sats / SATS_PER_BTC, sats % SATS_PER_BTC)
+}
+
+/// `12345 sats (0.00012345 BTC)`, the form amounts are shown in everywhere
+pub fn format_amount(sats: u64) -> String {
+    format!("{} sats ({})", sats, format_btc(sats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_sats("1000").unwrap(), 1000);
+        assert_eq!(parse_sats("1_000_000").unwrap(), 1_000_000);
+        assert_eq!(parse_sats("1000sat").unwrap(), 1000);
+        assert_eq!(parse_sats("1000 sats").unwrap(), 1000);
+        assert_eq!(parse_sats("0.001btc").unwrap(), 100_000);
+        assert_eq!(parse_sats("0.001 BTC").unwrap(), 100_000);
+        assert_eq!(parse_sats("1btc").unwrap(), SATS_PER_BTC);
+        assert_eq!(parse_sats(".5btc").unwrap(), 50_000_000);
+        assert_eq!(parse_sats("0.00000001btc").unwrap(), 1);
+        assert_eq!(parse_sats("21_000_000btc").unwrap(), MAX_SATS);
+    }
+
+    #[test]
+    fn test_rejects_ambiguous_and_invalid() {
+        let err = parse_sats("0.001").unwrap_err().to_string();
+        assert!(err.contains("Ambiguous"), "{}", err);
+        assert!(err.contains("0.001btc"), "{}", err);
+
+        for bad in [
+            "",
+            "btc",
+            "1.5sat",
+            "0.000000001btc",
+            "1_000_",
+            "_1000",
+            "1__000",
+            "1,000",
+            "-5",
+            "5usd",
+            "21000001btc",
+            "99999999999999999999",
+        ] {
+            assert!(parse_sats(bad).is_err(), "accepted {:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_btc(0), "0.00000000 BTC");
+        assert_eq!(format_btc(12_345), "0.00012345 BTC");
+        assert_eq!(format_btc(150_000_000), "1.50000000 BTC");
+        assert_eq!(format_amount(100_000), "100000 sats (0.00100000 BTC)");
+        for sats in [1, 12_345, 100_000_000, MAX_SATS] {
+            assert_eq!(
+                parse_sats(&format_btc(sats).replace(' ', "")).unwrap(),
+                sats
+            );
+        }
+    }
+}
@@ -0,0 +1,1418 @@
+//! Compact Block Filter Light Client (BIP-157/158)
+//!
+//! A neutrino-style backend for parties who do not want an indexer (Esplora,
+//! Electrum) to learn their addresses. Block headers and basic block filters
+//! are fetched from Bitcoin P2P peers, each filter is matched locally against
+//! every script in the wallet's [`ScriptIndex`], and only blocks that match
+//! are downloaded in full. Peers learn which blocks were fetched, never which
+//! scripts were looked for.
+//!
+//! Checks:
+//! - headers must link up, carry the difficulty the retarget rules require
+//!   and valid proof of work, and pass through the built-in checkpoints; a
+//!   competing branch replaces ours only with more work, and the scan rewinds
+//!   past it
+//! - headers are fetched from every peer, and every peer must follow the
+//!   resulting tip
+//! - filter headers (`getcfheaders`) are asked from every peer and must agree,
+//!   so one lying peer cannot hide a transaction while another is honest
+//! - each filter must hash into that filter header chain, and each matched
+//!   block must match its header's merkle root
+//!
+//! Headers are shared per network (`.frost_state/filter_headers_<network>.bin`);
+//! scan progress and the coins found are per wallet
+//! (`filter_scan_<network>.json`). Peers must serve filters
+//! (`bitcoind -blockfilterindex=1 -peerblockfilters=1`).
+
+use crate::btc::esplora;
+use crate::btc::hd_address::parse_network;
+use crate::btc::http;
+use crate::btc::script_index::ScriptIndex;
+use crate::protocol::keygen::get_state_dir;
+use crate::protocol::wallet::STATE_ROOT;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use bitcoin::bip158::{BlockFilter, FilterHeader};
+use bitcoin::block::Header;
+use bitcoin::blockdata::constants::genesis_block;
+use bitcoin::consensus::{deserialize, serialize};
+use bitcoin::hashes::Hash;
+use bitcoin::p2p::message::{NetworkMessage, RawNetworkMessage};
+use bitcoin::p2p::message_blockdata::{GetHeadersMessage, Inventory};
+use bitcoin::p2p::message_filter::{CFHeaders, CFilter, GetCFHeaders, GetCFilters};
+use bitcoin::p2p::message_network::VersionMessage;
+use bitcoin::p2p::{Address as P2pAddress, Magic, ServiceFlags};
+use bitcoin::params::Params;
+use bitcoin::pow::Work;
+use bitcoin::{Address, Block, BlockHash, CompactTarget, Network};
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Basic filter type (BIP-158)
+const BASIC_FILTER: u8 = 0;
+
+/// P2P protocol version sent in `version` (BIP-157 peers need 70016)
+const P2P_VERSION: u32 = 70016;
+
+/// Most headers in one `headers` message
+const MAX_HEADERS: usize = 2000;
+
+/// Most filters asked for in one `getcfilters`
+const FILTER_BATCH: u32 = 1000;
+
+/// Bitcoin Core's historical mainnet checkpoints
+///
+/// Difficulty was low enough in early history that a branch from there is
+/// cheap to mine; every header chain must pass through these blocks.
+const MAINNET_CHECKPOINTS: &[(u32, &str)] = &[
+    (
+        11111,
+        "0000000069e244f73d78e8fd29ba2fd2ed618bd6fa2ee92559f542fdb26e7c1d",
+    ),
+    (
+        33333,
+        "000000002dd5588a74784eaa7ab0507a18ad16a236e7b1ce69f00d7ddfb5d0a6",
+    ),
+    (
+        74000,
+        "0000000000573993a3c9e41ce34471c079dcf5f52a0e824a81e7f953b8661a20",
+    ),
+    (
+        105000,
+        "00000000000291ce28027faea320c8d2b054b2e0fe44a773f3eefb151d6bdc97",
+    ),
+    (
+        134444,
+        "00000000000005b12ffd4cd315cd34ffd4a594f430ac814c91184a0d42d2b0fe",
+    ),
+    (
+        168000,
+        "000000000000099e61ea72015e79632f216fe6cb33d7899acb35b75c8303b763",
+    ),
+    (
+        193000,
+        "000000000000059f452a5f7340de6682a977387c17010ff6e6c3bd83ca8b1317",
+    ),
+    (
+        210000,
+        "000000000000048b95347e83192f69cf0366076336c639f9b7228e9ba171342e",
+    ),
+    (
+        216116,
+        "00000000000001b4f4b433e81ee46494af945cf96014816a4e2370f11b23df4e",
+    ),
+    (
+        225430,
+        "00000000000001c108384350f74090433e7fcf79a606b8e797f065b130575932",
+    ),
+    (
+        250000,
+        "000000000000003887df1f29024b06fc2200b55f8af8f35453d7be294df2d214",
+    ),
+    (
+        279000,
+        "0000000000000001ae8c72a0b0c301f67e3afca10e819efa9041e458e9bd7e40",
+    ),
+    (
+        295000,
+        "00000000000000004d9b4ef50f0f9d686fd69db2e03af35a100370c64632a983",
+    ),
+];
+
+/// Bitcoin Core's historical testnet3 checkpoint
+const TESTNET_CHECKPOINTS: &[(u32, &str)] = &[(
+    546,
+    "000000002a936ca763904c3c35fce2f3556c559c0214345d31b1bcebf76acb70",
+)];
+
+fn checkpoints(network: Network) -> Vec<(u32, BlockHash)> {
+    let table = match network {
+        Network::Bitcoin => MAINNET_CHECKPOINTS,
+        Network::Testnet => TESTNET_CHECKPOINTS,
+        _ => &[],
+    };
+    table
+        .iter()
+        .map(|(height, hash)| (*height, hash.parse().expect("valid checkpoint hash")))
+        .collect()
+}
+
+/// Scanned blocks remembered for reorg detection
+const RECENT_BLOCKS: usize = 100;
+
+/// Largest P2P message accepted (a full block with witnesses)
+const MAX_MESSAGE_BYTES: usize = 5_000_000;
+
+/// Default P2P port for `network`
+pub fn default_port(network: Network) -> u16 {
+    match network {
+        Network::Bitcoin => 8333,
+        Network::Signet => 38333,
+        Network::Regtest => 18444,
+        _ => 18333,
+    }
+}
+
+fn headers_file(network: Network) -> String {
+    format!("filter_headers_{}.bin", esplora::network_key(network))
+}
+
+/// Wallet file holding scan progress and the coins found for `network`
+pub fn scan_file(network: Network) -> String {
+    format!("filter_scan_{}.json", esplora::network_key(network))
+}
+
+// ============================================================================
+// P2P
+// ============================================================================
+
+fn write_message(stream: &mut impl Write, magic: Magic, payload: NetworkMessage) -> Result<()> {
+    stream.write_all(&serialize(&RawNetworkMessage::new(magic, payload)))?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Read one message; None if it is one this client cannot decode
+fn read_message(stream: &mut impl Read, magic: Magic) -> Result<Option<NetworkMessage>> {
+    let mut bytes = vec![0u8; 24];
+    stream.read_exact(&mut bytes)?;
+    if bytes[..4] != magic.to_bytes() {
+        bail!(crate::error::Error::Protocol(
+            "Peer is on a different network".to_string()
+        ));
+    }
+    let length = u32::from_le_bytes(bytes[16..20].try_into()?) as usize;
+    if length > MAX_MESSAGE_BYTES {
+        bail!(crate::error::Error::Protocol(format!(
+            "Peer sent a {}-byte message",
+            length
+        )));
+    }
+    bytes.resize(24 + length, 0);
+    stream.read_exact(&mut bytes[24..])?;
+    Ok(deserialize::<RawNetworkMessage>(&bytes)
+        .ok()
+        .map(RawNetworkMessage::into_payload))
+}
+
+/// A connected peer that serves compact block filters
+pub struct Peer {
+    pub address: String,
+    stream: TcpStream,
+    magic: Magic,
+}
+
+impl Peer {
+    /// Connect to `host[:port]` and complete the version handshake
+    pub fn connect(address: &str, network: Network) -> Result<Self> {
+        let timeouts = http::timeouts();
+        let target = if address
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| port.parse::<u16>().is_ok() && !address.ends_with(']'))
+        {
+            address.to_string()
+        } else {
+            format!("{}:{}", address, default_port(network))
+        };
+        let socket = target
+            .to_socket_addrs()
+            .with_context(|| format!("Cannot resolve {}", target))?
+            .next()
+            .with_context(|| format!("No address for {}", target))?;
+        let stream = TcpStream::connect_timeout(&socket, timeouts.connect).context(
+            crate::error::Error::Network(format!("Cannot reach peer {}", target)),
+        )?;
+        stream.set_read_timeout(Some(timeouts.read))?;
+        stream.set_write_timeout(Some(timeouts.read))?;
+        let mut peer = Self {
+            address: target,
+            stream,
+            magic: Magic::from(network),
+        };
+        peer.handshake(socket)?;
+        Ok(peer)
+    }
+
+    fn handshake(&mut self, socket: SocketAddr) -> Result<()> {
+        let unspecified = SocketAddr::from(([0, 0, 0, 0], 0));
+        let mut version = VersionMessage::new(
+            ServiceFlags::NONE,
+            SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+            P2pAddress::new(&socket, ServiceFlags::NONE),
+            P2pAddress::new(&unspecified, ServiceFlags::NONE),
+            rand::random(),
+            concat!("/frostdao:", env!("CARGO_PKG_VERSION"), "/").to_string(),
+            0,
+        );
+        version.version = P2P_VERSION;
+        self.send(NetworkMessage::Version(version))?;
+
+        let services = self.wait_for("version", |message| match message {
+            NetworkMessage::Version(version) => Some(version.services),
+            _ => None,
+        })?;
+        self.send(NetworkMessage::Verack)?;
+        self.wait_for("verack", |message| {
+            matches!(message, NetworkMessage::Verack).then_some(())
+        })?;
+        if !services.has(ServiceFlags::COMPACT_FILTERS) {
+            bail!(crate::error::Error::User(format!(
+                "Peer {} does not serve compact block filters \
+                 (bitcoind needs -blockfilterindex=1 -peerblockfilters=1)",
+                self.address
+            )));
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, payload: NetworkMessage) -> Result<()> {
+        write_message(&mut self.stream, self.magic, payload)
+    }
+
+    /// Read messages until `pick` accepts one, answering pings on the way
+    fn wait_for<T>(
+        &mut self,
+        what: &str,
+        mut pick: impl FnMut(NetworkMessage) -> Option<T>,
+    ) -> Result<T> {
+        let deadline = Instant::now() + http::timeouts().read;
+        while Instant::now() < deadline {
+            let message = match read_message(&mut self.stream, self.magic) {
+                Ok(Some(message)) => message,
+                Ok(None) => continue,
+                Err(e)
+                    if e.downcast_ref::<std::io::Error>().is_some_and(|e| {
+                        matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+                    }) =>
+                {
+                    break
+                }
+                Err(e) => {
+                    return Err(e.context(crate::error::Error::Network(format!(
+                        "Lost peer {}",
+                        self.address
+                    ))))
+                }
+            };
+            if let NetworkMessage::Ping(nonce) = message {
+                self.send(NetworkMessage::Pong(nonce))?;
+                continue;
+            }
+            if let Some(value) = pick(message) {
+                return Ok(value);
+            }
+        }
+        bail!(crate::error::Error::Network(format!(
+            "Peer {} sent no {} in time",
+            self.address, what
+        )))
+    }
+
+    /// Headers following the first locator hash the peer knows
+    pub fn headers(&mut self, locator: Vec<BlockHash>) -> Result<Vec<Header>> {
+        self.send(NetworkMessage::GetHeaders(GetHeadersMessage::new(
+            locator,
+            BlockHash::all_zeros(),
+        )))?;
+        self.wait_for("headers", |message| match message {
+            NetworkMessage::Headers(headers) => Some(headers),
+            _ => None,
+        })
+    }
+
+    /// Filter hashes of blocks `start..=stop`, with the filter header before them
+    pub fn filter_headers(&mut self, start: u32, stop_hash: BlockHash) -> Result<CFHeaders> {
+        self.send(NetworkMessage::GetCFHeaders(GetCFHeaders {
+            filter_type: BASIC_FILTER,
+            start_height: start,
+            stop_hash,
+        }))?;
+        self.wait_for("cfheaders", |message| match message {
+            NetworkMessage::CFHeaders(headers) if headers.stop_hash == stop_hash => Some(headers),
+            _ => None,
+        })
+    }
+
+    /// The `count` filters of blocks `start..=stop`
+    pub fn filters(
+        &mut self,
+        start: u32,
+        stop_hash: BlockHash,
+        count: u32,
+    ) -> Result<Vec<CFilter>> {
+        self.send(NetworkMessage::GetCFilters(GetCFilters {
+            filter_type: BASIC_FILTER,
+            start_height: start,
+            stop_hash,
+        }))?;
+        (0..count)
+            .map(|_| {
+                self.wait_for("cfilter", |message| match message {
+                    NetworkMessage::CFilter(filter) if filter.filter_type == BASIC_FILTER => {
+                        Some(filter)
+                    }
+                    _ => None,
+                })
+            })
+            .collect()
+    }
+
+    /// A full block with witnesses
+    pub fn block(&mut self, hash: BlockHash) -> Result<Block> {
+        self.send(NetworkMessage::GetData(vec![Inventory::WitnessBlock(hash)]))?;
+        self.wait_for("block", |message| match message {
+            NetworkMessage::Block(block) if block.block_hash() == hash => Some(block),
+            _ => None,
+        })
+    }
+}
+
+// ============================================================================
+// Header chain
+// ============================================================================
+
+/// Best header chain known for a network, indexed by height
+pub struct HeaderChain {
+    network: Network,
+    headers: Vec<Header>,
+    checkpoints: Vec<(u32, BlockHash)>,
+}
+
+impl HeaderChain {
+    /// Load the stored chain, or start from the genesis block
+    pub fn load(storage: &dyn Storage, network: Network) -> Result<Self> {
+        let genesis = genesis_block(network).header;
+        let file = headers_file(network);
+        let mut headers = vec![genesis];
+        if storage.exists(&file) {
+            let bytes = storage.read(&file)?;
+            headers = bytes
+                .chunks(80)
+                .map(deserialize::<Header>)
+                .collect::<Result<_, _>>()
+                .with_context(|| format!("Corrupt {}", file))?;
+            if headers.first() != Some(&genesis) {
+                bail!("{} does not start at the {} genesis block", file, network);
+            }
+        }
+        Ok(Self {
+            network,
+            headers,
+            checkpoints: checkpoints(network),
+        })
+    }
+
+    pub fn save(&self, storage: &dyn Storage) -> Result<()> {
+        let bytes: Vec<u8> = self.headers.iter().flat_map(serialize).collect();
+        storage.write(&headers_file(self.network), &bytes)
+    }
+
+    pub fn tip_height(&self) -> u32 {
+        (self.headers.len() - 1) as u32
+    }
+
+    pub fn hash_at(&self, height: u32) -> Option<BlockHash> {
+        self.headers.get(height as usize).map(Header::block_hash)
+    }
+
+    fn height_of(&self, hash: BlockHash) -> Option<u32> {
+        (0..self.headers.len())
+            .rev()
+            .find(|&height| self.headers[height].block_hash() == hash)
+            .map(|height| height as u32)
+    }
+
+    /// Tip, then exponentially sparser hashes back to genesis
+    fn locator(&self) -> Vec<BlockHash> {
+        let mut locator = Vec::new();
+        let mut height = self.tip_height() as i64;
+        let mut step = 1;
+        while height > 0 {
+            locator.push(self.headers[height as usize].block_hash());
+            if locator.len() >= 10 {
+                step *= 2;
+            }
+            height -= step;
+        }
+        locator.push(self.headers[0].block_hash());
+        locator
+    }
+
+    /// Add headers from a peer
+    ///
+    /// Returns the fork height if they replace blocks of our chain, which they
+    /// only do with more total work.
+    pub fn extend(&mut self, new: &[Header]) -> Result<Option<u32>> {
+        let Some(first) = new.first() else {
+            return Ok(None);
+        };
+        let fork = self
+            .height_of(first.prev_blockhash)
+            .context(crate::error::Error::Protocol(
+                "Peer sent headers that do not connect to our chain".to_string(),
+            ))?;
+        let params = Params::new(self.network);
+        let at = |height: u32| {
+            if height <= fork {
+                self.headers[height as usize]
+            } else {
+                new[(height - fork - 1) as usize]
+            }
+        };
+        let mut previous = first.prev_blockhash;
+        for (height, header) in (fork + 1..).zip(new) {
+            if header.prev_blockhash != previous {
+                bail!(crate::error::Error::Protocol(
+                    "Peer sent headers that do not link up".to_string()
+                ));
+            }
+            if header.bits != required_bits(&params, height, header.time, at) {
+                bail!(crate::error::Error::Protocol(format!(
+                    "Header {} at block {} has the wrong difficulty",
+                    header.block_hash(),
+                    height
+                )));
+            }
+            if header.validate_pow(header.target()).is_err() {
+                bail!(crate::error::Error::Protocol(format!(
+                    "Header {} has invalid proof of work",
+                    header.block_hash()
+                )));
+            }
+            if let Some((_, hash)) = self.checkpoints.iter().find(|(h, _)| *h == height) {
+                if *hash != header.block_hash() {
+                    bail!(crate::error::Error::Protocol(format!(
+                        "Header at block {} does not match checkpoint {}",
+                        height, hash
+                    )));
+                }
+            }
+            previous = header.block_hash();
+        }
+
+        if fork == self.tip_height() {
+            self.headers.extend_from_slice(new);
+            return Ok(None);
+        }
+        let replaced = &self.headers[fork as usize + 1..];
+        if replaced.len() >= new.len() && replaced.starts_with(new) {
+            // Headers we already have
+            return Ok(None);
+        }
+        let tip = self.tip_height();
+        if let Some((height, _)) = self.checkpoints.iter().rev().find(|(h, _)| *h <= tip) {
+            if fork < *height {
+                bail!(crate::error::Error::Protocol(format!(
+                    "Peer's branch from block {} replaces checkpoint block {}",
+                    fork, height
+                )));
+            }
+        }
+        if total_work(new) <= total_work(replaced) {
+            bail!(crate::error::Error::Protocol(format!(
+                "Peer's branch from block {} has less work than ours",
+                fork
+            )));
+        }
+        self.headers.truncate(fork as usize + 1);
+        self.headers.extend_from_slice(new);
+        Ok(Some(fork))
+    }
+
+    /// Fetch headers from `peer` until it has no more; returns the lowest fork height
+    pub fn sync(&mut self, peer: &mut Peer) -> Result<Option<u32>> {
+        let mut fork: Option<u32> = None;
+        loop {
+            let headers = peer.headers(self.locator())?;
+            if let Some(height) = self.extend(&headers)? {
+                fork = Some(fork.map_or(height, |f| f.min(height)));
+            }
+            if headers.len() < MAX_HEADERS {
+                return Ok(fork);
+            }
+        }
+    }
+
+    /// Check that every peer's best chain contains our tip
+    ///
+    /// Run after syncing from all of them, so a peer serving a branch the
+    /// others do not follow is caught.
+    pub fn check_tip(&self, peers: &mut [Peer]) -> Result<()> {
+        let tip = self.tip_height();
+        let Some(parent) = tip.checked_sub(1).and_then(|height| self.hash_at(height)) else {
+            return Ok(());
+        };
+        let tip_hash = self.hash_at(tip);
+        for peer in peers.iter_mut() {
+            let headers = peer.headers(vec![parent])?;
+            if headers.first().map(Header::block_hash) != tip_hash {
+                bail!(crate::error::Error::Protocol(format!(
+                    "Peers disagree on the chain tip: {} does not follow block {}; \
+                     retry if a block was just found",
+                    peer.address, tip
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Difficulty the header at `height` must carry (Bitcoin Core's
+/// `GetNextWorkRequired`); `at` returns any earlier header of the branch
+fn required_bits(
+    params: &Params,
+    height: u32,
+    time: u32,
+    at: impl Fn(u32) -> Header,
+) -> CompactTarget {
+    let previous = at(height - 1);
+    let interval = params.difficulty_adjustment_interval() as u32;
+    if !height.is_multiple_of(interval) {
+        if !params.allow_min_difficulty_blocks {
+            return previous.bits;
+        }
+        // Testnet: a block 20 minutes late may use the minimum difficulty,
+        // otherwise the last block that did not
+        let min_bits = params.max_attainable_target.to_compact_lossy();
+        if u64::from(time) > u64::from(previous.time) + params.pow_target_spacing * 2 {
+            return min_bits;
+        }
+        let mut h = height - 1;
+        while !h.is_multiple_of(interval) && at(h).bits == min_bits {
+            h -= 1;
+        }
+        return at(h).bits;
+    }
+    let first = at(height - interval);
+    let timespan = (i64::from(previous.time) - i64::from(first.time)).max(0) as u64;
+    // BIP-94 (testnet4) retargets from the period's first block
+    let base = if params.network == Network::Testnet4 {
+        first.bits
+    } else {
+        previous.bits
+    };
+    CompactTarget::from_next_work_required(base, timespan, params)
+}
+
+fn total_work(headers: &[Header]) -> Work {
+    headers
+        .iter()
+        .fold(Work::from_be_bytes([0; 32]), |sum, header| {
+            sum + header.work()
+        })
+}
+
+// ============================================================================
+// Wallet scan
+// ============================================================================
+
+/// A wallet output found in a matched block
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FilterUtxo {
+    pub txid: String,
+    pub vout: u32,
+    pub value: u64,
+    pub script_pubkey: String,
+    pub height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spent_height: Option<u32>,
+}
+
+/// A scanned block, kept to notice reorgs and chain filter headers
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScannedBlock {
+    pub height: u32,
+    pub block_hash: String,
+    pub filter_header: String,
+}
+
+/// Scan progress of one wallet on one network
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FilterScan {
+    /// First block scanned
+    pub start_height: u32,
+    /// Next block to scan
+    pub next_height: u32,
+    /// Last scanned blocks, oldest first
+    pub recent: Vec<ScannedBlock>,
+    pub utxos: Vec<FilterUtxo>,
+}
+
+impl FilterScan {
+    fn new(start_height: u32) -> Self {
+        Self {
+            start_height,
+            next_height: start_height,
+            recent: Vec::new(),
+            utxos: Vec::new(),
+        }
+    }
+
+    /// Undo blocks that are no longer in `chain`; returns the height scanning resumes at
+    fn rewind(&mut self, chain: &HeaderChain) -> Option<u32> {
+        let scanned = self.recent.len();
+        while let Some(last) = self.recent.last() {
+            if chain.hash_at(last.height).map(|h| h.to_string()) == Some(last.block_hash.clone()) {
+                break;
+            }
+            self.recent.pop();
+        }
+        if self.recent.len() == scanned {
+            return None;
+        }
+        let keep = match self.recent.last() {
+            Some(last) => last.height,
+            None => {
+                // Deeper than we remember: start over
+                *self = Self::new(self.start_height);
+                return Some(self.start_height);
+            }
+        };
+        self.utxos.retain(|utxo| utxo.height <= keep);
+        for utxo in &mut self.utxos {
+            if utxo.spent_height.is_some_and(|h| h > keep) {
+                utxo.spent_height = None;
+            }
+        }
+        self.next_height = keep + 1;
+        Some(self.next_height)
+    }
+
+    /// Record outputs paying the wallet and spends of its coins
+    fn apply(&mut self, block: &Block, height: u32, index: &ScriptIndex) -> Vec<String> {
+        let mut events = Vec::new();
+        for tx in &block.txdata {
+            let txid = tx.compute_txid().to_string();
+            for input in &tx.input {
+                let spent = input.previous_output;
+                if let Some(utxo) = self.utxos.iter_mut().find(|u| {
+                    u.spent_height.is_none()
+                        && u.vout == spent.vout
+                        && u.txid == spent.txid.to_string()
+                }) {
+                    utxo.spent_height = Some(height);
+                    events.push(format!(
+                        "-{} sats spent from {}:{} in {}",
+                        utxo.value, utxo.txid, utxo.vout, txid
+                    ));
+                }
+            }
+            for (vout, output) in tx.output.iter().enumerate() {
+                let Some(owner) = index.owner(&output.script_pubkey) else {
+                    continue;
+                };
+                if self
+                    .utxos
+                    .iter()
+                    .any(|u| u.txid == txid && u.vout == vout as u32)
+                {
+                    continue;
+                }
+                self.utxos.push(FilterUtxo {
+                    txid: txid.clone(),
+                    vout: vout as u32,
+                    value: output.value.to_sat(),
+                    script_pubkey: hex::encode(output.script_pubkey.as_bytes()),
+                    height,
+                    spent_height: None,
+                });
+                events.push(format!(
+                    "+{} sats to {}:{} ({})",
+                    output.value.to_sat(),
+                    txid,
+                    vout,
+                    owner.origin
+                ));
+            }
+        }
+        events
+    }
+
+    pub fn unspent(&self) -> impl Iterator<Item = &FilterUtxo> {
+        self.utxos.iter().filter(|u| u.spent_height.is_none())
+    }
+}
+
+/// Filter headers of a batch, checked to be the same from every peer
+fn agreed_filter_headers(
+    peers: &mut [Peer],
+    start: u32,
+    stop: u32,
+    stop_hash: BlockHash,
+) -> Result<(FilterHeader, Vec<FilterHeader>)> {
+    let mut agreed: Option<(String, FilterHeader, Vec<FilterHeader>)> = None;
+    for peer in peers.iter_mut() {
+        let response = peer.filter_headers(start, stop_hash)?;
+        if response.filter_hashes.len() != (stop - start + 1) as usize {
+            bail!(crate::error::Error::Protocol(format!(
+                "Peer {} sent {} filter hashes for blocks {}..={}",
+                peer.address,
+                response.filter_hashes.len(),
+                start,
+                stop
+            )));
+        }
+        let mut previous = response.previous_filter_header;
+        let headers: Vec<FilterHeader> = response
+            .filter_hashes
+            .iter()
+            .map(|hash| {
+                previous = hash.filter_header(&previous);
+                previous
+            })
+            .collect();
+        match &agreed {
+            None => {
+                agreed = Some((
+                    peer.address.clone(),
+                    response.previous_filter_header,
+                    headers,
+                ))
+            }
+            Some((first, prev, expected))
+                if *prev != response.previous_filter_header || *expected != headers =>
+            {
+                bail!(crate::error::Error::Protocol(format!(
+                    "Peers {} and {} disagree on the filters of blocks {}..={}; \
+                     refusing to trust either",
+                    first, peer.address, start, stop
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+    let (_, previous, headers) = agreed.context("No peers")?;
+    Ok((previous, headers))
+}
+
+#[derive(Serialize, Debug)]
+pub struct FilterSyncOutput {
+    pub network: String,
+    pub peers: Vec<String>,
+    pub tip_height: u32,
+    pub start_height: u32,
+    /// Blocks whose filter matched a wallet script in this run
+    pub matched_blocks: Vec<u32>,
+    pub utxos: Vec<FilterUtxo>,
+    pub balance_sats: u64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Core function for dkg-filter-sync
+///
+/// Headers are synced from every peer, whose tips must agree, and saved in
+/// `chain_storage`; filter headers must agree across all `peers`. Progress is saved in
+/// `wallet_storage` after every batch, so an interrupted scan resumes.
+/// `from_height` sets (or, if different, restarts) where scanning begins;
+/// without it a new scan starts at the current tip.
+pub fn filter_sync_core(
+    network: Network,
+    peers: &mut [Peer],
+    chain_storage: &dyn Storage,
+    wallet_storage: &dyn Storage,
+    index: &ScriptIndex,
+    from_height: Option<u32>,
+) -> Result<CommandResult> {
+    fn log(out: &mut String, line: String) {
+        println!("{}", line);
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    let mut out = String::new();
+    if peers.len() < 2 {
+        bail!(crate::error::Error::User(
+            "Give at least two --peer so headers and filters are cross-checked".to_string()
+        ));
+    }
+    log(&mut out, "Compact Filter Sync\n".to_string());
+    log(
+        &mut out,
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".to_string(),
+    );
+
+    let mut chain = HeaderChain::load(chain_storage, network)?;
+    let before = chain.tip_height();
+    let mut fork: Option<u32> = None;
+    for peer in peers.iter_mut() {
+        if let Some(height) = chain.sync(peer)? {
+            fork = Some(fork.map_or(height, |f| f.min(height)));
+        }
+    }
+    chain.check_tip(peers)?;
+    chain.save(chain_storage)?;
+    let tip = chain.tip_height();
+    log(
+        &mut out,
+        format!(
+            "Headers: {} (+{}), tip agreed by {} peers",
+            tip,
+            tip.saturating_sub(before),
+            peers.len()
+        ),
+    );
+    if let Some(fork) = fork {
+        log(
+            &mut out,
+            format!("⚠️  Reorg: blocks after {} replaced", fork),
+        );
+    }
+
+    let file = scan_file(network);
+    let mut scan = if wallet_storage.exists(&file) {
+        serde_json::from_slice(&wallet_storage.read(&file)?)
+            .with_context(|| format!("Failed to parse {}", file))?
+    } else {
+        FilterScan::new(from_height.unwrap_or(tip))
+    };
+    if from_height.is_some_and(|h| h != scan.start_height) {
+        scan = FilterScan::new(from_height.unwrap_or(tip));
+    }
+    if let Some(resume) = scan.rewind(&chain) {
+        log(
+            &mut out,
+            format!("⚠️  Rescanning from block {} after a reorg", resume),
+        );
+    }
+    if scan.next_height == scan.start_height && from_height.is_none() {
+        log(
+            &mut out,
+            format!(
+                "Scanning from block {}; pass --from-height to include older history",
+                scan.start_height
+            ),
+        );
+    }
+
+    let scripts: Vec<Vec<u8>> = index
+        .scripts
+        .keys()
+        .map(hex::decode)
+        .collect::<Result<_, _>>()?;
+    let mut matched_blocks = Vec::new();
+    while scan.next_height <= tip {
+        let start = scan.next_height;
+        let stop = tip.min(start + FILTER_BATCH - 1);
+        let stop_hash = chain.hash_at(stop).context("Header chain too short")?;
+        let (previous, filter_headers) = agreed_filter_headers(peers, start, stop, stop_hash)?;
+        if let Some(last) = scan.recent.last() {
+            if last.height + 1 == start && last.filter_header != previous.to_string() {
+                bail!(crate::error::Error::Protocol(format!(
+                    "Filter header chain breaks at block {}",
+                    start
+                )));
+            }
+        }
+
+        let filters = peers[0].filters(start, stop_hash, stop - start + 1)?;
+        let mut previous = previous;
+        for ((height, filter), expected) in (start..=stop).zip(filters).zip(filter_headers) {
+            let hash = chain.hash_at(height).context("Header chain too short")?;
+            let block_filter = BlockFilter::new(&filter.filter);
+            if filter.block_hash != hash || block_filter.filter_header(&previous) != expected {
+                bail!(crate::error::Error::Protocol(format!(
+                    "Peer {} sent a filter for block {} that does not match the agreed filter headers",
+                    peers[0].address, height
+                )));
+            }
+            previous = expected;
+
+            if block_filter.match_any(&hash, scripts.iter().map(Vec::as_slice))? {
+                let block = peers[0].block(hash)?;
+                if !block.check_merkle_root() || !block.check_witness_commitment() {
+                    bail!(crate::error::Error::Protocol(format!(
+                        "Block {} does not match its header",
+                        hash
+                    )));
+                }
+                matched_blocks.push(height);
+                for event in scan.apply(&block, height, index) {
+                    log(&mut out, format!("   block {}: {}", height, event));
+                }
+            }
+            scan.recent.push(ScannedBlock {
+                height,
+                block_hash: hash.to_string(),
+                filter_header: expected.to_string(),
+            });
+        }
+        let excess = scan.recent.len().saturating_sub(RECENT_BLOCKS);
+        scan.recent.drain(..excess);
+        scan.next_height = stop + 1;
+        wallet_storage.write(&file, serde_json::to_string_pretty(&scan)?.as_bytes())?;
+        log(&mut out, format!("Scanned to block {}", stop));
+    }
+    if !wallet_storage.exists(&file) {
+        wallet_storage.write(&file, serde_json::to_string_pretty(&scan)?.as_bytes())?;
+    }
+
+    let utxos: Vec<FilterUtxo> = scan.unspent().cloned().collect();
+    let balance_sats = utxos.iter().map(|u| u.value).sum();
+    log(
+        &mut out,
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".to_string(),
+    );
+    for utxo in &utxos {
+        let address = hex::decode(&utxo.script_pubkey)
+            .ok()
+            .and_then(|script| {
+                Address::from_script(&bitcoin::ScriptBuf::from_bytes(script), network).ok()
+            })
+            .map_or(utxo.script_pubkey.clone(), |a| a.to_string());
+        log(
+            &mut out,
+            format!(
+                "   {}:{}  {} sats  {} (block {})",
+                utxo.txid, utxo.vout, utxo.value, address, utxo.height
+            ),
+        );
+    }
+    log(
+        &mut out,
+        format!(
+            "Balance: {} sats in {} UTXOs (blocks {}..={})",
+            balance_sats,
+            utxos.len(),
+            scan.start_height,
+            tip
+        ),
+    );
+
+    let result = FilterSyncOutput {
+        network: esplora::network_key(network).to_string(),
+        peers: peers.iter().map(|p| p.address.clone()).collect(),
+        tip_height: tip,
+        start_height: scan.start_height,
+        matched_blocks,
+        utxos,
+        balance_sats,
+        event_type: "filter_sync".to_string(),
+    };
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+/// CLI wrapper for dkg-filter-sync
+pub fn filter_sync(
+    name: &str,
+    network: &str,
+    peers: &[String],
+    from_height: Option<u32>,
+) -> Result<()> {
+    let network = parse_network(network)?;
+    let wallet_storage = FileStorage::new(&get_state_dir(name))?;
+    let chain_storage = FileStorage::new(STATE_ROOT)?;
+    let index = ScriptIndex::load(&wallet_storage)?;
+    let mut connected = peers
+        .iter()
+        .map(|peer| Peer::connect(peer, network))
+        .collect::<Result<Vec<_>>>()?;
+    let result = filter_sync_core(
+        network,
+        &mut connected,
+        &chain_storage,
+        &wallet_storage,
+        &index,
+        from_height,
+    )?;
+    println!("{}\n", result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::script_index::OwnedScript;
+    use crate::storage::MemoryStorage;
+    use bitcoin::bip158::FilterHash;
+    use bitcoin::block::Version;
+    use bitcoin::script::Builder;
+    use bitcoin::{
+        absolute::LockTime, transaction, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn,
+        TxMerkleNode, TxOut, Witness,
+    };
+    use std::collections::{BTreeMap, HashMap};
+    use std::net::TcpListener;
+
+    fn script(byte: u8) -> ScriptBuf {
+        let mut bytes = vec![0x51, 0x20];
+        bytes.extend([byte; 32]);
+        ScriptBuf::from_bytes(bytes)
+    }
+
+    fn tx(input: OutPoint, tag: i64, outputs: &[(ScriptBuf, u64)]) -> Transaction {
+        Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: input,
+                script_sig: Builder::new().push_int(tag).push_int(1).into_script(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: outputs
+                .iter()
+                .map(|(script, value)| TxOut {
+                    value: Amount::from_sat(*value),
+                    script_pubkey: script.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Mine a regtest block on `prev`, tagging its coinbase with `tag`
+    fn mine(prev: &Header, tag: i64, pay_to: ScriptBuf, mut txs: Vec<Transaction>) -> Block {
+        txs.insert(0, tx(OutPoint::null(), tag, &[(pay_to, 50_000)]));
+        let mut block = Block {
+            header: Header {
+                version: Version::from_consensus(4),
+                prev_blockhash: prev.block_hash(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: prev.time + 600,
+                bits: prev.bits,
+                nonce: 0,
+            },
+            txdata: txs,
+        };
+        block.header.merkle_root = block.compute_merkle_root().unwrap();
+        while block.header.validate_pow(block.header.target()).is_err() {
+            block.header.nonce += 1;
+        }
+        block
+    }
+
+    fn filters(blocks: &[Block]) -> Vec<BlockFilter> {
+        let outputs: HashMap<OutPoint, ScriptBuf> = blocks
+            .iter()
+            .flat_map(|block| &block.txdata)
+            .flat_map(|tx| {
+                let txid = tx.compute_txid();
+                tx.output.iter().enumerate().map(move |(vout, out)| {
+                    (OutPoint::new(txid, vout as u32), out.script_pubkey.clone())
+                })
+            })
+            .collect();
+        blocks
+            .iter()
+            .map(|block| {
+                BlockFilter::new_script_filter(block, |outpoint| {
+                    Ok::<_, bitcoin::bip158::Error>(outputs[outpoint].clone())
+                })
+                .unwrap()
+            })
+            .collect()
+    }
+
+    /// A peer serving `blocks` (genesis first); `lie_at` swaps one filter hash
+    fn serve(blocks: Vec<Block>, lie_at: Option<usize>) -> Peer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let magic = Magic::from(Network::Regtest);
+        std::thread::spawn(move || {
+            let filters = filters(&blocks);
+            let mut hashes: Vec<FilterHash> = filters
+                .iter()
+                .map(|filter| FilterHash::hash(&filter.content))
+                .collect();
+            if let Some(height) = lie_at {
+                hashes[height] = FilterHash::hash(b"nothing to see here");
+            }
+            let mut previous = FilterHeader::all_zeros();
+            let headers: Vec<FilterHeader> = hashes
+                .iter()
+                .map(|hash| {
+                    previous = hash.filter_header(&previous);
+                    previous
+                })
+                .collect();
+            let height_of = |hash: &BlockHash| blocks.iter().position(|b| b.block_hash() == *hash);
+
+            let (mut stream, _) = listener.accept().unwrap();
+            let send = |stream: &mut TcpStream, message| write_message(stream, magic, message);
+            while let Ok(message) = read_message(&mut stream, magic) {
+                match message.unwrap() {
+                    NetworkMessage::Version(_) => {
+                        let mut services = ServiceFlags::NETWORK;
+                        services.add(ServiceFlags::WITNESS);
+                        services.add(ServiceFlags::COMPACT_FILTERS);
+                        let any = SocketAddr::from(([0, 0, 0, 0], 0));
+                        let version = VersionMessage::new(
+                            services,
+                            0,
+                            P2pAddress::new(&any, ServiceFlags::NONE),
+                            P2pAddress::new(&any, services),
+                            1,
+                            "/mock/".to_string(),
+                            blocks.len() as i32 - 1,
+                        );
+                        send(&mut stream, NetworkMessage::Version(version)).unwrap();
+                        send(&mut stream, NetworkMessage::Verack).unwrap();
+                        send(&mut stream, NetworkMessage::Ping(7)).unwrap();
+                    }
+                    NetworkMessage::GetHeaders(request) => {
+                        let known = request
+                            .locator_hashes
+                            .iter()
+                            .find_map(&height_of)
+                            .unwrap_or(0);
+                        let headers = blocks[known + 1..].iter().map(|b| b.header).collect();
+                        send(&mut stream, NetworkMessage::Headers(headers)).unwrap();
+                    }
+                    NetworkMessage::GetCFHeaders(request) => {
+                        let start = request.start_height as usize;
+                        let stop = height_of(&request.stop_hash).unwrap();
+                        let response = CFHeaders {
+                            filter_type: BASIC_FILTER,
+                            stop_hash: request.stop_hash,
+                            previous_filter_header: if start == 0 {
+                                FilterHeader::all_zeros()
+                            } else {
+                                headers[start - 1]
+                            },
+                            filter_hashes: hashes[start..=stop].to_vec(),
+                        };
+                        send(&mut stream, NetworkMessage::CFHeaders(response)).unwrap();
+                    }
+                    NetworkMessage::GetCFilters(request) => {
+                        let stop = height_of(&request.stop_hash).unwrap();
+                        for height in request.start_height as usize..=stop {
+                            let filter = CFilter {
+                                filter_type: BASIC_FILTER,
+                                block_hash: blocks[height].block_hash(),
+                                filter: filters[height].content.clone(),
+                            };
+                            send(&mut stream, NetworkMessage::CFilter(filter)).unwrap();
+                        }
+                    }
+                    NetworkMessage::GetData(items) => {
+                        for item in items {
+                            if let Inventory::WitnessBlock(hash) = item {
+                                let block = blocks[height_of(&hash).unwrap()].clone();
+                                send(&mut stream, NetworkMessage::Block(block)).unwrap();
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+        Peer::connect(&address, Network::Regtest).unwrap()
+    }
+
+    fn index() -> ScriptIndex {
+        let owned = |origin: &str| OwnedScript {
+            origin: origin.to_string(),
+            descriptor: String::new(),
+        };
+        ScriptIndex {
+            stamp: String::new(),
+            root_key: String::new(),
+            scripts: BTreeMap::from([
+                (hex::encode(script(1).as_bytes()), owned("root")),
+                (hex::encode(script(2).as_bytes()), owned("m/44'/0'/0'/1/0")),
+            ]),
+        }
+    }
+
+    fn sync(
+        peers: &mut [Peer],
+        chain: &MemoryStorage,
+        wallet: &MemoryStorage,
+        from_height: Option<u32>,
+    ) -> Result<serde_json::Value> {
+        let result = filter_sync_core(
+            Network::Regtest,
+            peers,
+            chain,
+            wallet,
+            &index(),
+            from_height,
+        )?;
+        Ok(serde_json::from_str(&result.result).unwrap())
+    }
+
+    #[test]
+    fn test_scan_finds_coins_spends_and_reorgs() {
+        let foreign = script(9);
+        let genesis = genesis_block(Network::Regtest);
+        let b1 = mine(&genesis.header, 1, foreign.clone(), vec![]);
+        let b2 = mine(&b1.header, 2, script(1), vec![]);
+        let spend = tx(
+            OutPoint::new(b2.txdata[0].compute_txid(), 0),
+            0,
+            &[(script(2), 30_000), (foreign.clone(), 19_000)],
+        );
+        let b3 = mine(&b2.header, 3, foreign.clone(), vec![spend]);
+        let b4 = mine(&b3.header, 4, foreign.clone(), vec![]);
+        let chain = vec![genesis.clone(), b1.clone(), b2.clone(), b3, b4];
+
+        let chain_storage = MemoryStorage::new();
+        let wallet = MemoryStorage::new();
+        let mut peers = [serve(chain.clone(), None), serve(chain.clone(), None)];
+        let result = sync(&mut peers, &chain_storage, &wallet, Some(1)).unwrap();
+        assert_eq!(result["tip_height"], 4);
+        assert_eq!(result["matched_blocks"], serde_json::json!([2, 3]));
+        assert_eq!(result["balance_sats"], 30_000);
+        assert_eq!(result["utxos"][0]["height"], 3);
+
+        // One peer alone is not enough
+        let mut peers = [serve(chain.clone(), None)];
+        assert!(sync(&mut peers, &chain_storage, &wallet, None).is_err());
+
+        // Nothing new: headers and scan resume from storage
+        let mut peers = [serve(chain.clone(), None), serve(chain.clone(), None)];
+        let result = sync(&mut peers, &chain_storage, &wallet, None).unwrap();
+        assert_eq!(result["matched_blocks"], serde_json::json!([]));
+        assert_eq!(result["balance_sats"], 30_000);
+
+        // A longer branch from block 2 drops the spend: the coin at block 2 is back
+        let c3 = mine(&b2.header, 33, foreign.clone(), vec![]);
+        let c4 = mine(&c3.header, 34, foreign.clone(), vec![]);
+        let c5 = mine(&c4.header, 35, foreign.clone(), vec![]);
+        let branch = vec![genesis, b1, b2, c3, c4, c5];
+        let mut peers = [serve(branch.clone(), None), serve(branch.clone(), None)];
+        let result = sync(&mut peers, &chain_storage, &wallet, None).unwrap();
+        assert_eq!(result["tip_height"], 5);
+        assert_eq!(result["balance_sats"], 50_000);
+        assert_eq!(result["utxos"][0]["height"], 2);
+
+        // A lying peer next to an honest one is caught
+        let wallet = MemoryStorage::new();
+        let mut peers = [serve(branch.clone(), None), serve(branch, Some(2))];
+        let err = sync(&mut peers, &chain_storage, &wallet, Some(1)).unwrap_err();
+        assert!(err.to_string().contains("disagree"), "{}", err);
+    }
+
+    #[test]
+    fn test_header_chain_prefers_more_work() {
+        let foreign = script(9);
+        let genesis = genesis_block(Network::Regtest);
+        let b1 = mine(&genesis.header, 1, foreign.clone(), vec![]);
+        let b2 = mine(&b1.header, 2, foreign.clone(), vec![]);
+        let b3 = mine(&b2.header, 3, foreign.clone(), vec![]);
+        let mut chain = HeaderChain::load(&MemoryStorage::new(), Network::Regtest).unwrap();
+        assert_eq!(
+            chain.extend(&[b1.header, b2.header, b3.header]).unwrap(),
+            None
+        );
+        // Already known
+        assert_eq!(chain.extend(&[b2.header]).unwrap(), None);
+
+        let c2 = mine(&b1.header, 22, foreign.clone(), vec![]);
+        assert!(chain.extend(&[c2.header]).is_err());
+        let c3 = mine(&c2.header, 23, foreign.clone(), vec![]);
+        let c4 = mine(&c3.header, 24, foreign.clone(), vec![]);
+        assert_eq!(
+            chain.extend(&[c2.header, c3.header, c4.header]).unwrap(),
+            Some(1)
+        );
+        assert_eq!(chain.hash_at(4), Some(c4.block_hash()));
+
+        let mut broken = c4.header;
+        broken.prev_blockhash = b3.block_hash();
+        broken.bits = bitcoin::CompactTarget::from_consensus(0x1d00ffff);
+        assert!(chain.extend(&[broken]).is_err());
+    }
+
+    #[test]
+    fn test_peers_must_agree_on_the_tip() {
+        let foreign = script(9);
+        let genesis = genesis_block(Network::Regtest);
+        let b1 = mine(&genesis.header, 1, foreign.clone(), vec![]);
+        let b2 = mine(&b1.header, 2, foreign.clone(), vec![]);
+        let lagging = vec![genesis.clone(), b1.clone()];
+        let chain = vec![genesis, b1, b2];
+
+        let mut peers = [serve(chain, None), serve(lagging, None)];
+        let err = sync(
+            &mut peers,
+            &MemoryStorage::new(),
+            &MemoryStorage::new(),
+            Some(1),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("chain tip"), "{}", err);
+    }
+
+    #[test]
+    fn test_header_chain_checks_difficulty_and_checkpoints() {
+        let foreign = script(9);
+        let genesis = genesis_block(Network::Regtest);
+        let b1 = mine(&genesis.header, 1, foreign.clone(), vec![]);
+        let b2 = mine(&b1.header, 2, foreign.clone(), vec![]);
+
+        // Regtest never retargets: a header claiming other bits is refused
+        let mut harder = b2.header;
+        harder.bits = bitcoin::CompactTarget::from_consensus(0x207ffffe);
+        while harder.validate_pow(harder.target()).is_err() {
+            harder.nonce += 1;
+        }
+        let mut chain = HeaderChain::load(&MemoryStorage::new(), Network::Regtest).unwrap();
+        let err = chain.extend(&[b1.header, harder]).unwrap_err();
+        assert!(err.to_string().contains("difficulty"), "{}", err);
+
+        // A header off a checkpoint is refused, and so is a branch around it
+        chain.checkpoints = vec![(2, b2.block_hash())];
+        let c2 = mine(&b1.header, 22, foreign.clone(), vec![]);
+        let err = chain.extend(&[b1.header, c2.header]).unwrap_err();
+        assert!(err.to_string().contains("checkpoint"), "{}", err);
+        chain.extend(&[b1.header, b2.header]).unwrap();
+        let c3 = mine(&c2.header, 23, foreign.clone(), vec![]);
+        chain.checkpoints = vec![(1, b1.block_hash())];
+        assert!(chain.extend(&[c2.header, c3.header]).is_ok());
+        chain.checkpoints = vec![(2, c2.block_hash())];
+        let d1 = mine(&genesis.header, 31, foreign, vec![]);
+        let err = chain.extend(&[d1.header]).unwrap_err();
+        assert!(err.to_string().contains("replaces checkpoint"), "{}", err);
+    }
+
+    #[test]
+    fn test_required_bits_follows_retarget_rules() {
+        let header = |bits: u32, time: u32| Header {
+            version: Version::from_consensus(4),
+            prev_blockhash: BlockHash::all_zeros(),
+            merkle_root: TxMerkleNode::all_zeros(),
+            time,
+            bits: bitcoin::CompactTarget::from_consensus(bits),
+            nonce: 0,
+        };
+
+        // Mainnet: a period mined in half the time halves the target
+        let mainnet = Params::new(Network::Bitcoin);
+        let period: Vec<Header> = (0..2016)
+            .map(|height| header(0x1c00ffff, if height == 2015 { 604_800 } else { 0 }))
+            .collect();
+        let at = |height: u32| period[height as usize];
+        assert_eq!(
+            required_bits(&mainnet, 2015, 0, at).to_consensus(),
+            0x1c00ffff
+        );
+        assert_eq!(
+            required_bits(&mainnet, 2016, 604_801, at).to_consensus(),
+            0x1b7fff80
+        );
+
+        // Testnet: late blocks may drop to the minimum, others keep the real one
+        let testnet = Params::new(Network::Testnet);
+        let blocks = [
+            header(0x1c00ffff, 0),
+            header(0x1c00ffff, 600),
+            header(0x1d00ffff, 2000),
+        ];
+        let at = |height: u32| blocks[height as usize];
+        assert_eq!(
+            required_bits(&testnet, 3, 3201, at).to_consensus(),
+            0x1d00ffff
+        );
+        assert_eq!(
+            required_bits(&testnet, 3, 2600, at).to_consensus(),
+            0x1c00ffff
+        );
+    }
+}
@@ -0,0 +1,478 @@
+//! Watch-Only Wallet Export
+//!
+//! On chain a threshold wallet is a single Taproot key, so desktop wallets
+//! can follow it as an ordinary single-key watch-only wallet:
+//!
+//! - **root**: `tr(GROUP_KEY)`, the address `dkg-build-tx` spends from
+//! - **HD**: the account key `m/44'/0'/0'` as an xpub with its chain code,
+//!   `tr(xpub/0/*)` for receive and `tr(xpub/1/*)` for change
+//! - **script trees**: `rawtr(OUTPUT_KEY)` per recorded script address
+//!
+//! HD keys here are normalised to even Y at every level (see `crypto::hd`),
+//! where BIP-32 keeps the parity of the branch key. When a branch key has odd
+//! Y the account xpub would derive other addresses, so that branch is
+//! exported as its own xpub (`m/44'/0'/0'/c`) instead. The export checks
+//! every descriptor against this wallet's own derivation before writing it.
+//!
+//! The file follows Specter Desktop's wallet backup layout (`label`,
+//! `blockheight`, `descriptor`), which Sparrow also imports, and carries a
+//! `descriptors` list ready for Bitcoin Core's `importdescriptors`.
+
+use crate::btc::hd_address::{load_hd_context, parse_network};
+use crate::btc::script_index::{ScriptIndex, LOOKAHEAD};
+use crate::crypto::hd::{derive_at_path, derive_child_pubkey, DerivationPath, HdContext};
+use crate::protocol::keygen::{get_state_dir, HdMetadata, HtssMetadata};
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use bitcoin::bip32::{ChainCode, ChildNumber, Xpub};
+use bitcoin::key::{Parity, XOnlyPublicKey};
+use bitcoin::secp256k1::{PublicKey, Secp256k1};
+use bitcoin::{Address, Network, NetworkKind};
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const INPUT_CHARSET: &str =
+    "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn poly_mod(mut c: u64, val: u64) -> u64 {
+    let c0 = c >> 35;
+    c = ((c & 0x7ffffffff) << 5) ^ val;
+    for (bit, generator) in [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if c0 & (1 << bit) != 0 {
+            c ^= generator;
+        }
+    }
+    c
+}
+
+/// BIP-380 descriptor checksum (8 characters)
+pub fn descriptor_checksum(descriptor: &str) -> Result<String> {
+    let mut c = 1u64;
+    let mut class = 0u64;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let pos = INPUT_CHARSET
+            .find(ch)
+            .with_context(|| format!("Invalid character {:?} in descriptor", ch))?
+            as u64;
+        c = poly_mod(c, pos & 31);
+        class = class * 3 + (pos >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = poly_mod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = poly_mod(c, class);
+    }
+    for _ in 0..8 {
+        c = poly_mod(c, 0);
+    }
+    c ^= 1;
+    Ok((0..8)
+        .map(|j| CHECKSUM_CHARSET[((c >> (5 * (7 - j))) & 31) as usize] as char)
+        .collect())
+}
+
+/// `descriptor#checksum`
+pub fn with_checksum(descriptor: &str) -> Result<String> {
+    Ok(format!(
+        "{}#{}",
+        descriptor,
+        descriptor_checksum(descriptor)?
+    ))
+}
+
+/// One entry of a Bitcoin Core `importdescriptors` request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ImportDescriptor {
+    pub desc: String,
+    /// Rescan from this time (0: the whole chain)
+    pub timestamp: u64,
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<[u32; 2]>,
+    pub label: String,
+}
+
+/// Watch-only export of a threshold wallet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchOnlyExport {
+    /// Wallet name, shown by the importing wallet
+    pub label: String,
+    /// Block height to scan from (0: the whole chain)
+    pub blockheight: u64,
+    /// Main descriptor: HD receive chain, or the root key without HD
+    pub descriptor: String,
+    /// HD change chain, when HD derivation is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_descriptor: Option<String>,
+    /// The group key's own address, where dkg-build-tx spends from
+    pub root_descriptor: String,
+    /// Who can spend, e.g. "2-of-3 FROST threshold (one Taproot key on chain)"
+    pub policy: String,
+    pub network: String,
+    /// Group public key (x-only hex)
+    pub group_key: String,
+    /// Account-level path the xpubs stand for
+    pub derivation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xpub: Option<String>,
+    /// Set when the HD chains cannot share one xpub
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Everything above, ready for Bitcoin Core's importdescriptors
+    pub descriptors: Vec<ImportDescriptor>,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Account key as a BIP-32 xpub (depth 3, path m/44'/0'/0')
+fn account_xpub(context: &HdContext, network: Network) -> Result<Xpub> {
+    let xonly = XOnlyPublicKey::from_slice(&context.master_pubkey_bytes)?;
+    Ok(Xpub {
+        network: NetworkKind::from(network),
+        depth: 3,
+        parent_fingerprint: Default::default(),
+        child_number: ChildNumber::from_hardened_idx(0)?,
+        public_key: PublicKey::from_x_only_public_key(xonly, Parity::Even),
+        chain_code: ChainCode::from(context.chain_code),
+    })
+}
+
+/// This wallet's branch key `m/44'/0'/0'/change` (even Y) as an xpub
+fn branch_xpub(context: &HdContext, account: &Xpub, change: u32) -> Result<Xpub> {
+    let master = Point::<EvenY>::from_xonly_bytes(context.master_pubkey_bytes)
+        .context("Invalid master public key bytes")?;
+    let (branch, chain_code, _, _) = derive_child_pubkey(&master, &context.chain_code, change)?;
+    let xonly = XOnlyPublicKey::from_slice(&branch.to_xonly_bytes())?;
+    Ok(Xpub {
+        network: account.network,
+        depth: 4,
+        parent_fingerprint: account.fingerprint(),
+        child_number: ChildNumber::from_normal_idx(change)?,
+        public_key: PublicKey::from_x_only_public_key(xonly, Parity::Even),
+        chain_code: ChainCode::from(chain_code),
+    })
+}
+
+/// Does `xpub/steps/i` give this wallet's key at `change/i`?
+fn derives_like_wallet(
+    context: &HdContext,
+    xpub: &Xpub,
+    steps: &[u32],
+    change: u32,
+) -> Result<bool> {
+    let secp = Secp256k1::verification_only();
+    for index in [0, 1] {
+        let path: Vec<ChildNumber> = steps
+            .iter()
+            .chain([index].iter())
+            .map(|&i| ChildNumber::from_normal_idx(i))
+            .collect::<std::result::Result<_, _>>()?;
+        let derived = xpub.derive_pub(&secp, &path)?;
+        let ours = derive_at_path(
+            context,
+            &DerivationPath {
+                change,
+                address_index: index,
+            },
+        )?;
+        if derived.public_key.x_only_public_key().0.serialize() != ours.public_key.to_xonly_bytes()
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Core function for dkg-export-watch-only
+pub fn export_watch_only_core(
+    wallet_name: &str,
+    network_str: &str,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let network = parse_network(network_str)?;
+    let index = ScriptIndex::load(storage)?;
+    let metadata: HtssMetadata = serde_json::from_slice(
+        &storage
+            .read("htss_metadata.json")
+            .context("No wallet metadata found. Run keygen-finalize first.")?,
+    )
+    .context("Failed to parse htss_metadata.json")?;
+    let policy = format!(
+        "{}-of-{} FROST {}threshold (one Taproot key on chain)",
+        metadata.threshold,
+        metadata.party_ranks.len(),
+        if metadata.hierarchical {
+            "hierarchical "
+        } else {
+            ""
+        }
+    );
+
+    let root = with_checksum(&format!("tr({})", index.root_key))?;
+    let mut descriptors = vec![ImportDescriptor {
+        desc: root.clone(),
+        timestamp: 0,
+        active: false,
+        internal: None,
+        range: None,
+        label: format!("{} (root)", wallet_name),
+    }];
+    let mut export = WatchOnlyExport {
+        label: wallet_name.to_string(),
+        blockheight: 0,
+        descriptor: root.clone(),
+        change_descriptor: None,
+        root_descriptor: root,
+        policy,
+        network: network_str.to_string(),
+        group_key: index.root_key.clone(),
+        derivation: "m/44'/0'/0'".to_string(),
+        xpub: None,
+        note: None,
+        descriptors: Vec::new(),
+        event_type: "dkg_watch_only".to_string(),
+    };
+
+    if let Ok(context) = load_hd_context(storage) {
+        let hd: HdMetadata = serde_json::from_slice(&storage.read("hd_metadata.json")?)
+            .context("Failed to parse hd_metadata.json")?;
+        let account = account_xpub(&context, network)?;
+        let mut chains = Vec::new();
+        for change in [0, 1] {
+            chains.push(
+                if derives_like_wallet(&context, &account, &[change], change)? {
+                    format!("tr({}/{}/*)", account, change)
+                } else {
+                    let branch = branch_xpub(&context, &account, change)?;
+                    if !derives_like_wallet(&context, &branch, &[], change)? {
+                        anyhow::bail!(
+                            "HD branch {} does not follow BIP-32; cannot export it",
+                            change
+                        );
+                    }
+                    export.note = Some(
+                        "This wallet's HD chains follow separate branch xpubs: import \
+                     change_descriptor as well, or change addresses will not be seen"
+                            .to_string(),
+                    );
+                    format!("tr({}/*)", branch)
+                },
+            );
+        }
+        let receive = with_checksum(&chains[0])?;
+        let change = with_checksum(&chains[1])?;
+        descriptors.push(ImportDescriptor {
+            desc: receive.clone(),
+            timestamp: 0,
+            active: true,
+            internal: Some(false),
+            range: Some([0, hd.derived_count + LOOKAHEAD - 1]),
+            label: format!("{} (receive)", wallet_name),
+        });
+        descriptors.push(ImportDescriptor {
+            desc: change.clone(),
+            timestamp: 0,
+            active: true,
+            internal: Some(true),
+            range: Some([0, LOOKAHEAD - 1]),
+            label: format!("{} (change)", wallet_name),
+        });
+        export.descriptor = receive;
+        export.change_descriptor = Some(change);
+        export.xpub = Some(account.to_string());
+    }
+
+    for owned in index.scripts.values() {
+        if owned.descriptor.starts_with("rawtr(") {
+            descriptors.push(ImportDescriptor {
+                desc: with_checksum(&owned.descriptor)?,
+                timestamp: 0,
+                active: false,
+                internal: None,
+                range: None,
+                label: format!("{} ({})", wallet_name, owned.origin),
+            });
+        }
+    }
+    export.descriptors = descriptors;
+
+    let secp = Secp256k1::verification_only();
+    let root_address = Address::p2tr(
+        &secp,
+        XOnlyPublicKey::from_slice(&hex::decode(&export.group_key)?)?,
+        None,
+        network,
+    );
+    let mut out = String::new();
+    out.push_str("Watch-Only Export\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("Wallet:  {}\n", wallet_name));
+    out.push_str(&format!("Policy:  {}\n", export.policy));
+    out.push_str(&format!("Network: {}\n", network_str));
+    out.push_str(&format!("Root:    {}\n\n", root_address));
+    out.push_str(&format!("Descriptor:        {}\n", export.descriptor));
+    if let Some(change) = &export.change_descriptor {
+        out.push_str(&format!("Change descriptor: {}\n", change));
+        out.push_str(&format!("Root descriptor:   {}\n", export.root_descriptor));
+    }
+    if let Some(note) = &export.note {
+        out.push_str(&format!("\n⚠️  {}\n", note));
+    }
+    out.push_str(&format!(
+        "\n{} descriptors for Bitcoin Core importdescriptors\n",
+        export.descriptors.len()
+    ));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&export)?,
+    })
+}
+
+/// CLI wrapper for dkg-export-watch-only
+pub fn export_watch_only(name: &str, network: &str, out: Option<&Path>) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let result = export_watch_only_core(name, network, &storage)?;
+    print!("{}", result.output);
+    match out {
+        Some(path) => {
+            std::fs::write(path, &result.result)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Export written to {}", path.display());
+        }
+        None => println!("\n{}", result.result),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::hd_address::derive_address_at_path;
+    use crate::storage::MemoryStorage;
+    use schnorr_fun::frost::{self, chilldkg::simplepedpop};
+    use sha2::Sha256;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_descriptor_checksum() {
+        // BIP-380 test vector
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert!(descriptor_checksum("raw(é)").is_err());
+    }
+
+    /// Address a `tr(XPUB/.../*)` descriptor gives at `index`
+    fn descriptor_address(descriptor: &str, index: u32) -> String {
+        let body = descriptor.split('#').next().unwrap();
+        let body = body.strip_prefix("tr(").unwrap().strip_suffix(")").unwrap();
+        let mut parts = body.split('/');
+        let xpub = Xpub::from_str(parts.next().unwrap()).unwrap();
+        let path: Vec<ChildNumber> = parts
+            .map(|step| match step {
+                "*" => ChildNumber::from_normal_idx(index).unwrap(),
+                step => ChildNumber::from_normal_idx(step.parse().unwrap()).unwrap(),
+            })
+            .collect();
+        let secp = Secp256k1::new();
+        let key = xpub.derive_pub(&secp, &path).unwrap().public_key;
+        Address::p2tr(&secp, key.x_only_public_key().0, None, Network::Testnet).to_string()
+    }
+
+    #[test]
+    fn test_export_descriptors_derive_wallet_addresses() {
+        // Branch keys have odd Y about half the time; try enough wallets to
+        // see both the shared and the per-branch xpub layout
+        let frost = frost::new_with_deterministic_nonces::<Sha256>();
+        for _ in 0..8 {
+            let (shared_key, _) =
+                simplepedpop::simulate_keygen(&frost.schnorr, 2, 3, 3, &mut rand::thread_rng());
+            let storage = MemoryStorage::new();
+            storage
+                .write(
+                    "shared_key.bin",
+                    &bincode::serialize(&shared_key.into_xonly()).unwrap(),
+                )
+                .unwrap();
+            let hd = HdMetadata {
+                chain_code: hex::encode(rand::random::<[u8; 32]>()),
+                hd_enabled: true,
+                mnemonic_hint: None,
+                derived_count: 5,
+            };
+            storage
+                .write("hd_metadata.json", &serde_json::to_vec(&hd).unwrap())
+                .unwrap();
+            let metadata = HtssMetadata {
+                my_index: 1,
+                my_rank: 0,
+                threshold: 2,
+                hierarchical: false,
+                party_ranks: [(1, 0), (2, 0), (3, 0)].into_iter().collect(),
+                wallet_version: crate::storage::migrations::CURRENT_WALLET_VERSION,
+                signing_requirement: Default::default(),
+                created_at: None,
+                refreshed_at: None,
+            };
+            storage
+                .write(
+                    "htss_metadata.json",
+                    &serde_json::to_vec(&metadata).unwrap(),
+                )
+                .unwrap();
+
+            let result = export_watch_only_core("treasury", "testnet", &storage).unwrap();
+            let export: WatchOnlyExport = serde_json::from_str(&result.result).unwrap();
+            assert_eq!(
+                export.policy,
+                "2-of-3 FROST threshold (one Taproot key on chain)"
+            );
+            assert!(export
+                .root_descriptor
+                .starts_with(&format!("tr({})#", export.group_key)));
+            let change = export.change_descriptor.as_deref().unwrap();
+            assert_eq!(
+                export.note.is_some(),
+                !(export.descriptor.contains("/0/*") && change.contains("/1/*"))
+            );
+            for index in [0, 3, 7] {
+                assert_eq!(
+                    descriptor_address(&export.descriptor, index),
+                    derive_address_at_path(&storage, 0, index, Network::Testnet)
+                        .unwrap()
+                        .0
+                );
+                assert_eq!(
+                    descriptor_address(change, index),
+                    derive_address_at_path(&storage, 1, index, Network::Testnet)
+                        .unwrap()
+                        .0
+                );
+            }
+            for entry in &export.descriptors {
+                let (body, checksum) = entry.desc.split_once('#').unwrap();
+                assert_eq!(descriptor_checksum(body).unwrap(), checksum);
+            }
+            assert_eq!(export.descriptors[1].range, Some([0, 5 + LOOKAHEAD - 1]));
+        }
+    }
+}
@@ -0,0 +1,774 @@
+//! Electrum Server Backend
+//!
+//! Self-hosted stacks usually run electrs or Fulcrum rather than Esplora.
+//! Setting a network's backend to `tcp://host:port` or `ssl://host:port`
+//! (`backend-config`, or `--esplora-url` for one run) sends every chain
+//! query over the Electrum protocol instead: newline-delimited JSON-RPC on
+//! one connection, addresses looked up by script hash.
+//!
+//! - **ChainBackend**: UTXOs, fee estimates and broadcast ([`ElectrumBackend`])
+//! - **status**: tip height and confirmation status of a transaction
+//! - **subscriptions**: `blockchain.scripthash.subscribe` for every script of
+//!   a wallet ([`watch_wallet_core`], `dkg-watch-wallet`)
+//!
+//! TLS certificates are verified against the system roots, so a server with
+//! a self-signed certificate has to be reached over `tcp://` (LAN, Tor or an
+//! SSH tunnel) instead.
+
+use crate::btc::esplora;
+use crate::btc::hd_address::parse_network;
+use crate::btc::http::{self, Stream};
+use crate::btc::script_index::ScriptIndex;
+use crate::btc::transaction::{ChainBackend, FeeEstimate, TxStatus, UtxoResponse, UtxoStatus};
+use crate::protocol::keygen::get_state_dir;
+use crate::storage::FileStorage;
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use bitcoin::hashes::{sha256, sha256d, Hash};
+use bitcoin::{Address, Network, OutPoint, Script, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::time::{Duration, Instant};
+
+/// Protocol version asked for in `server.version`
+const PROTOCOL_VERSION: &str = "1.4";
+
+/// An Electrum server address (`tcp://host:port` or `ssl://host:port`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElectrumServer {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+}
+
+impl ElectrumServer {
+    /// Parse `tcp://host:port` or `ssl://host:port`
+    pub fn parse(url: &str) -> Result<Self> {
+        let url = url.trim();
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("ssl://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("tcp://") {
+            (false, rest)
+        } else {
+            bail!("Electrum URL must start with tcp:// or ssl://");
+        };
+        let rest = rest.trim_end_matches('/');
+        let (host, port) = rest
+            .rsplit_once(':')
+            .with_context(|| format!("Electrum URL '{}' needs a port", url))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() || host.contains('@') || host.contains('/') {
+            bail!("Invalid Electrum host in '{}'", url);
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port: port
+                .parse()
+                .with_context(|| format!("Invalid Electrum port in '{}'", url))?,
+            tls,
+        })
+    }
+
+    /// The URL again, for display and config
+    pub fn describe(&self) -> String {
+        let scheme = if self.tls { "ssl" } else { "tcp" };
+        if self.host.contains(':') {
+            format!("{}://[{}]:{}", scheme, self.host, self.port)
+        } else {
+            format!("{}://{}:{}", scheme, self.host, self.port)
+        }
+    }
+
+    /// Open a connection and negotiate the protocol version
+    pub fn connect(&self) -> Result<ElectrumClient> {
+        let timeouts = http::timeouts();
+        let stream = Stream::connect(&self.host, self.port, self.tls, &self.describe())?;
+        let mut client = ElectrumClient {
+            stream: BufReader::new(stream),
+            next_id: 0,
+            pending: String::new(),
+            notifications: VecDeque::new(),
+            read_timeout: timeouts.read,
+        };
+        client.call(
+            "server.version",
+            json!([
+                concat!("frostdao ", env!("CARGO_PKG_VERSION")),
+                PROTOCOL_VERSION
+            ]),
+        )?;
+        Ok(client)
+    }
+}
+
+/// The Electrum server configured for `network`, if its backend is one
+pub fn configured(network: Network) -> Result<Option<ElectrumServer>> {
+    esplora::electrum_url(network)?
+        .map(|url| ElectrumServer::parse(&url))
+        .transpose()
+}
+
+/// Whether `network`'s chain queries go to an Electrum server
+pub fn is_configured(network: Network) -> bool {
+    configured(network).is_ok_and(|server| server.is_some())
+}
+
+/// Electrum script hash: SHA-256 of the scriptPubKey, byte-reversed, hex
+pub fn script_hash(script: &Script) -> String {
+    let mut hash = sha256::Hash::hash(script.as_bytes()).to_byte_array();
+    hash.reverse();
+    hex::encode(hash)
+}
+
+/// A `blockchain.scripthash.subscribe` notification: the script's history changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptHashUpdate {
+    pub script_hash: String,
+    /// Hash of the script's history, None when it has none
+    pub status: Option<String>,
+}
+
+/// Confirmed and mempool balance of a script (`blockchain.scripthash.get_balance`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ScriptBalance {
+    pub confirmed: i64,
+    pub unconfirmed: i64,
+}
+
+/// One connection to an Electrum server
+pub struct ElectrumClient {
+    stream: BufReader<Stream>,
+    next_id: u64,
+    /// Part of a line cut off by a read timeout
+    pending: String,
+    /// Notifications that arrived while waiting for a response
+    notifications: VecDeque<ScriptHashUpdate>,
+    read_timeout: Duration,
+}
+
+impl ElectrumClient {
+    /// Send one request and wait for its response
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        let stream = self.stream.get_mut();
+        stream.write_all(format!("{}\n", request).as_bytes())?;
+        stream.flush()?;
+
+        let deadline = Instant::now() + self.read_timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(message) = self.read_message(remaining)? else {
+                bail!(crate::error::Error::Network(format!(
+                    "Electrum server did not answer {} in time",
+                    method
+                )));
+            };
+            if message.get("id").and_then(Value::as_u64) != Some(id) {
+                self.queue_notification(&message);
+                continue;
+            }
+            if let Some(error) = message.get("error").filter(|e| !e.is_null()) {
+                let text = error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string());
+                bail!(crate::error::Error::Network(format!(
+                    "Electrum {} failed: {}",
+                    method, text
+                )));
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    /// Read one JSON line, or None if nothing complete arrives within `timeout`
+    fn read_message(&mut self, timeout: Duration) -> Result<Option<Value>> {
+        self.stream
+            .get_ref()
+            .tcp()
+            .set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        match self.stream.read_line(&mut self.pending) {
+            Ok(0) => bail!(crate::error::Error::Network(
+                "Electrum server closed the connection".to_string()
+            )),
+            Ok(_) if self.pending.ends_with('\n') => {
+                let line = std::mem::take(&mut self.pending);
+                Ok(Some(serde_json::from_str(&line).with_context(|| {
+                    format!("Electrum server sent invalid JSON: {}", line.trim())
+                })?))
+            }
+            Ok(_) => Ok(None),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn queue_notification(&mut self, message: &Value) {
+        if message.get("method").and_then(Value::as_str) != Some("blockchain.scripthash.subscribe")
+        {
+            return;
+        }
+        let params = message.get("params").and_then(Value::as_array);
+        if let Some(script_hash) = params
+            .and_then(|p| p.first())
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        {
+            let status = params
+                .and_then(|p| p.get(1))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            self.notifications.push_back(ScriptHashUpdate {
+                script_hash,
+                status,
+            });
+        }
+    }
+
+    /// Current chain tip height
+    pub fn tip_height(&mut self) -> Result<u64> {
+        let header = self.call("blockchain.headers.subscribe", json!([]))?;
+        header
+            .get("height")
+            .and_then(Value::as_u64)
+            .context("Electrum server sent no tip height")
+    }
+
+    /// Unspent outputs paying `script`
+    pub fn list_unspent(&mut self, script: &Script) -> Result<Vec<UtxoResponse>> {
+        #[derive(Deserialize)]
+        struct Unspent {
+            tx_hash: String,
+            tx_pos: u32,
+            height: i64,
+            value: u64,
+        }
+        let unspent: Vec<Unspent> = serde_json::from_value(self.call(
+            "blockchain.scripthash.listunspent",
+            json!([script_hash(script)]),
+        )?)
+        .context("Failed to parse Electrum listunspent")?;
+        Ok(unspent
+            .into_iter()
+            .map(|u| UtxoResponse {
+                txid: u.tx_hash,
+                vout: u.tx_pos,
+                value: u.value,
+                status: UtxoStatus {
+                    confirmed: u.height > 0,
+                    block_height: (u.height > 0).then_some(u.height as u64),
+                },
+            })
+            .collect())
+    }
+
+    /// Confirmed and mempool balance of `script`
+    pub fn balance(&mut self, script: &Script) -> Result<ScriptBalance> {
+        serde_json::from_value(self.call(
+            "blockchain.scripthash.get_balance",
+            json!([script_hash(script)]),
+        )?)
+        .context("Failed to parse Electrum balance")
+    }
+
+    /// Fee rates in sat/vB for the usual targets
+    ///
+    /// Electrum reports BTC/kvB, and -1 when the server has no estimate; the
+    /// relay fee is then the floor.
+    pub fn fee_estimates(&mut self) -> Result<FeeEstimate> {
+        let relay = self
+            .call("blockchain.relayfee", json!([]))
+            .ok()
+            .and_then(|fee| fee.as_f64())
+            .map_or(1, sat_per_vbyte);
+        let mut rate = |blocks: u32| -> Result<u64> {
+            let fee = self.call("blockchain.estimatefee", json!([blocks]))?;
+            Ok(fee
+                .as_f64()
+                .filter(|fee| *fee > 0.0)
+                .map_or(relay, sat_per_vbyte)
+                .max(relay))
+        };
+        Ok(FeeEstimate {
+            fastest_fee: rate(1)?,
+            half_hour_fee: rate(3)?,
+            hour_fee: rate(6)?,
+            economy_fee: rate(144)?,
+            minimum_fee: relay,
+        })
+    }
+
+    /// Broadcast a signed transaction; returns its txid
+    pub fn broadcast(&mut self, raw_tx_hex: &str) -> Result<String> {
+        let txid = self.call("blockchain.transaction.broadcast", json!([raw_tx_hex]))?;
+        txid.as_str()
+            .map(str::to_string)
+            .context("Electrum server sent no txid")
+    }
+
+    /// Confirmation status of a transaction, None if the server does not know it
+    ///
+    /// Electrum has no txid index: the height comes from the history of the
+    /// transaction's first output script.
+    pub fn tx_status(&mut self, txid: &str) -> Result<Option<TxStatus>> {
+        let Ok(raw) = self.call("blockchain.transaction.get", json!([txid])) else {
+            return Ok(None);
+        };
+        let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(
+            raw.as_str()
+                .context("Electrum server sent no transaction")?,
+        )?;
+        let output = tx.output.first().context("Transaction has no outputs")?;
+        let history = self.call(
+            "blockchain.scripthash.get_history",
+            json!([script_hash(&output.script_pubkey)]),
+        )?;
+        let height = history
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|entry| entry.get("tx_hash").and_then(Value::as_str) == Some(txid))
+            .and_then(|entry| entry.get("height"))
+            .and_then(Value::as_i64)
+            .unwrap_or(0);
+        if height <= 0 {
+            return Ok(Some(TxStatus::default()));
+        }
+        let header_hex = self.call("blockchain.block.header", json!([height]))?;
+        let header = hex::decode(
+            header_hex
+                .as_str()
+                .context("Electrum server sent no header")?,
+        )?;
+        if header.len() != 80 {
+            bail!("Electrum server sent a {}-byte block header", header.len());
+        }
+        Ok(Some(TxStatus {
+            confirmed: true,
+            block_height: Some(height as u64),
+            block_hash: Some(sha256d::Hash::hash(&header).to_string()),
+            block_time: Some(u32::from_le_bytes(header[68..72].try_into()?) as u64),
+        }))
+    }
+
+    /// A transaction by txid
+    pub fn transaction(&mut self, txid: &str) -> Result<Transaction> {
+        let raw = self.call("blockchain.transaction.get", json!([txid]))?;
+        Ok(bitcoin::consensus::encode::deserialize_hex(
+            raw.as_str()
+                .context("Electrum server sent no transaction")?,
+        )?)
+    }
+
+    /// Txid spending `outpoint`, None while it is unspent
+    ///
+    /// Electrum has no spend index either: the spender is looked for in the
+    /// history of the script the outpoint pays.
+    pub fn spender(&mut self, outpoint: &OutPoint) -> Result<Option<String>> {
+        let funding = self.transaction(&outpoint.txid.to_string())?;
+        let output = funding
+            .output
+            .get(outpoint.vout as usize)
+            .with_context(|| format!("{} has no output {}", outpoint.txid, outpoint.vout))?;
+        let history = self.call(
+            "blockchain.scripthash.get_history",
+            json!([script_hash(&output.script_pubkey)]),
+        )?;
+        let candidates: Vec<String> = history
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.get("tx_hash").and_then(Value::as_str))
+            .filter(|txid| *txid != outpoint.txid.to_string())
+            .map(str::to_string)
+            .collect();
+        for txid in candidates {
+            let tx = self.transaction(&txid)?;
+            if tx
+                .input
+                .iter()
+                .any(|input| input.previous_output == *outpoint)
+            {
+                return Ok(Some(txid));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Subscribe to a script's history; returns its current status
+    pub fn subscribe(&mut self, script: &Script) -> Result<Option<String>> {
+        let status = self.call(
+            "blockchain.scripthash.subscribe",
+            json!([script_hash(script)]),
+        )?;
+        Ok(status.as_str().map(str::to_string))
+    }
+
+    /// Next subscription notification, waiting at most `timeout`
+    pub fn next_update(&mut self, timeout: Duration) -> Result<Option<ScriptHashUpdate>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(update) = self.notifications.pop_front() {
+                return Ok(Some(update));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            if let Some(message) = self.read_message(remaining)? {
+                self.queue_notification(&message);
+            }
+        }
+    }
+}
+
+/// BTC/kvB to whole sat/vB, rounded up
+fn sat_per_vbyte(btc_per_kvb: f64) -> u64 {
+    let sat_per_kvb = (btc_per_kvb * 100_000_000.0).round() as u64;
+    sat_per_kvb.div_ceil(1000).max(1)
+}
+
+/// [`ChainBackend`] over an Electrum server, one connection per call
+pub struct ElectrumBackend {
+    pub server: ElectrumServer,
+}
+
+impl ChainBackend for ElectrumBackend {
+    fn utxos(&self, address: &str) -> Result<Vec<UtxoResponse>> {
+        let script = address
+            .parse::<Address<_>>()
+            .with_context(|| format!("Invalid address {}", address))?
+            .assume_checked()
+            .script_pubkey();
+        self.server.connect()?.list_unspent(&script)
+    }
+
+    fn fee_estimates(&self) -> Result<FeeEstimate> {
+        self.server.connect()?.fee_estimates()
+    }
+
+    fn broadcast(&self, raw_tx_hex: &str) -> Result<String> {
+        self.server.connect()?.broadcast(raw_tx_hex)
+    }
+
+    fn tip_height(&self) -> Result<u64> {
+        self.server.connect()?.tip_height()
+    }
+
+    fn tx_status(&self, txid: &str) -> Result<Option<TxStatus>> {
+        self.server.connect()?.tx_status(txid)
+    }
+
+    fn spender(&self, outpoint: &OutPoint) -> Result<Option<String>> {
+        self.server.connect()?.spender(outpoint)
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Core function for backend-status on an Electrum server
+pub fn status_core(network: Network, server: &ElectrumServer) -> Result<CommandResult> {
+    let mut client = server.connect()?;
+    let tip_height = client.tip_height()?;
+    let fees = client.fee_estimates()?;
+
+    let mut out = String::new();
+    out.push_str("Electrum Backend Status\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("Network: {}\n", network));
+    out.push_str(&format!("Server:  {}\n", server.describe()));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("✅ Tip height: {}\n", tip_height));
+    out.push_str(&format!(
+        "   Fees (sat/vB): fastest {}, 30 min {}, 1 h {}, economy {}, minimum {}\n",
+        fees.fastest_fee, fees.half_hour_fee, fees.hour_fee, fees.economy_fee, fees.minimum_fee
+    ));
+
+    let result = json!({
+        "network": network.to_string(),
+        "url": server.describe(),
+        "source": "electrum",
+        "tip_height": tip_height,
+        "fastest_fee": fees.fastest_fee,
+        "half_hour_fee": fees.half_hour_fee,
+        "hour_fee": fees.hour_fee,
+        "economy_fee": fees.economy_fee,
+        "minimum_fee": fees.minimum_fee,
+        "type": "backend_status",
+    });
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+#[derive(Serialize, Debug)]
+pub struct WalletWatchOutput {
+    pub wallet: String,
+    pub network: String,
+    pub scripts: usize,
+    /// Balance changes seen, in order
+    pub updates: Vec<WalletWatchUpdate>,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WalletWatchUpdate {
+    pub address: String,
+    pub origin: String,
+    pub balance: ScriptBalance,
+}
+
+/// Core function for dkg-watch-wallet
+///
+/// Subscribes to every script in the wallet's [`ScriptIndex`] and reports each
+/// balance change as it is announced. `timeout_secs = 0` watches until
+/// interrupted; lines are printed as they happen and collected in the output.
+pub fn watch_wallet_core(
+    wallet_name: &str,
+    network: Network,
+    server: &ElectrumServer,
+    timeout_secs: u64,
+    index: &ScriptIndex,
+) -> Result<CommandResult> {
+    fn log(out: &mut String, line: String) {
+        println!("{}", line);
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    let mut out = String::new();
+    let mut client = server.connect()?;
+    log(&mut out, "Wallet Watch\n".to_string());
+    log(
+        &mut out,
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".to_string(),
+    );
+    log(&mut out, format!("Wallet: {}", wallet_name));
+    log(&mut out, format!("Server: {}", server.describe()));
+
+    // Script hash -> (script, address, origin)
+    let mut watched = BTreeMap::new();
+    let mut funded = 0;
+    for (script_hex, owned) in &index.scripts {
+        let script = bitcoin::ScriptBuf::from_bytes(hex::decode(script_hex)?);
+        let address = Address::from_script(&script, network)
+            .map(|a| a.to_string())
+            .unwrap_or_else(|_| script_hex.clone());
+        if client.subscribe(&script)?.is_some() {
+            funded += 1;
+            let balance = client.balance(&script)?;
+            log(
+                &mut out,
+                format!(
+                    "   {}  {} confirmed, {} unconfirmed  ({})",
+                    address, balance.confirmed, balance.unconfirmed, owned.origin
+                ),
+            );
+        }
+        watched.insert(
+            script_hash(&script),
+            (script, address, owned.origin.clone()),
+        );
+    }
+    log(
+        &mut out,
+        format!(
+            "Watching {} scripts ({} with history)\n",
+            watched.len(),
+            funded
+        ),
+    );
+
+    let started = Instant::now();
+    let mut updates = Vec::new();
+    loop {
+        let wait = if timeout_secs == 0 {
+            Duration::from_secs(60)
+        } else {
+            Duration::from_secs(timeout_secs).saturating_sub(started.elapsed())
+        };
+        if wait.is_zero() {
+            break;
+        }
+        let Some(update) = client.next_update(wait)? else {
+            continue;
+        };
+        let Some((script, address, origin)) = watched.get(&update.script_hash) else {
+            continue;
+        };
+        let balance = client.balance(script)?;
+        log(
+            &mut out,
+            format!(
+                "🔔 {}  {} confirmed, {} unconfirmed  ({})",
+                address, balance.confirmed, balance.unconfirmed, origin
+            ),
+        );
+        updates.push(WalletWatchUpdate {
+            address: address.clone(),
+            origin: origin.clone(),
+            balance,
+        });
+    }
+
+    let result = WalletWatchOutput {
+        wallet: wallet_name.to_string(),
+        network: network.to_string(),
+        scripts: watched.len(),
+        updates,
+        event_type: "wallet_watch".to_string(),
+    };
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+/// CLI wrapper for dkg-watch-wallet
+pub fn watch_wallet(name: &str, network: &str, timeout_secs: u64) -> Result<()> {
+    let network = parse_network(network)?;
+    let Some(server) = configured(network)? else {
+        bail!(crate::error::Error::User(format!(
+            "dkg-watch-wallet needs an Electrum backend: frostdao backend-config --network {} \
+             --url ssl://host:50002",
+            esplora::network_key(network)
+        )));
+    };
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let index = ScriptIndex::load(&storage)?;
+    let result = watch_wallet_core(name, network, &server, timeout_secs, &index)?;
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}\n", result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Answer one connection like an Electrum server would
+    fn mock_server() -> ElectrumServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            for line in BufReader::new(stream).lines() {
+                let request: Value = serde_json::from_str(&line.unwrap()).unwrap();
+                let params = &request["params"];
+                let result = match request["method"].as_str().unwrap() {
+                    "server.version" => json!(["mock 1.0", "1.4"]),
+                    "blockchain.scripthash.subscribe" => json!("5f0a"),
+                    "blockchain.scripthash.listunspent" => {
+                        // A notification ahead of the response
+                        let note = json!({
+                            "jsonrpc": "2.0",
+                            "method": "blockchain.scripthash.subscribe",
+                            "params": [params[0], "77aa"],
+                        });
+                        writeln!(writer, "{}", note).unwrap();
+                        json!([
+                            {"tx_hash": "aa".repeat(32), "tx_pos": 1, "height": 0, "value": 5000},
+                            {"tx_hash": "bb".repeat(32), "tx_pos": 0, "height": 812345, "value": 7000},
+                        ])
+                    }
+                    "blockchain.relayfee" => json!(0.00001),
+                    "blockchain.estimatefee" => match params[0].as_u64().unwrap() {
+                        1 => json!(0.0002),
+                        144 => json!(-1),
+                        _ => json!(0.000101),
+                    },
+                    "blockchain.headers.subscribe" => json!({"height": 812400, "hex": ""}),
+                    _ => {
+                        let error = json!({"jsonrpc": "2.0", "id": request["id"],
+                            "error": {"code": -32601, "message": "unknown method"}});
+                        writeln!(writer, "{}", error).unwrap();
+                        continue;
+                    }
+                };
+                let response = json!({"jsonrpc": "2.0", "id": request["id"], "result": result});
+                writeln!(writer, "{}", response).unwrap();
+            }
+        });
+        ElectrumServer::parse(&format!("tcp://127.0.0.1:{}", port)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_server_url() {
+        let server = ElectrumServer::parse("ssl://electrum.example:50002").unwrap();
+        assert!(server.tls);
+        assert_eq!(server.port, 50002);
+        assert_eq!(server.describe(), "ssl://electrum.example:50002");
+        let server = ElectrumServer::parse("tcp://[::1]:50001/").unwrap();
+        assert_eq!(server.host, "::1");
+        assert_eq!(server.describe(), "tcp://[::1]:50001");
+
+        assert!(ElectrumServer::parse("https://electrum.example:50002").is_err());
+        assert!(ElectrumServer::parse("tcp://electrum.example").is_err());
+        assert!(ElectrumServer::parse("tcp://user@electrum.example:50001").is_err());
+    }
+
+    #[test]
+    fn test_script_hash_matches_protocol_docs() {
+        // Example from the Electrum protocol documentation (1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa)
+        let script =
+            bitcoin::ScriptBuf::from_hex("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac")
+                .unwrap();
+        assert_eq!(
+            script_hash(&script),
+            "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161"
+        );
+    }
+
+    #[test]
+    fn test_client_against_mock_server() {
+        let mut client = mock_server().connect().unwrap();
+        let script = bitcoin::ScriptBuf::from_hex(
+            "51200f0c8db753acbd17343a39c2f3f4e35e4be6da749f9e35137ab220e7b238a667",
+        )
+        .unwrap();
+
+        assert_eq!(client.subscribe(&script).unwrap().as_deref(), Some("5f0a"));
+
+        let utxos = client.list_unspent(&script).unwrap();
+        assert_eq!(utxos.len(), 2);
+        assert!(!utxos[0].status.confirmed);
+        assert_eq!(utxos[0].vout, 1);
+        assert!(utxos[1].status.confirmed);
+        assert_eq!(utxos[1].status.block_height, Some(812345));
+        assert_eq!(utxos.iter().map(|u| u.value).sum::<u64>(), 12000);
+
+        // The notification sent ahead of the listunspent response was kept
+        let update = client
+            .next_update(Duration::from_millis(10))
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.script_hash, script_hash(&script));
+        assert_eq!(update.status.as_deref(), Some("77aa"));
+        assert!(client
+            .next_update(Duration::from_millis(10))
+            .unwrap()
+            .is_none());
+
+        // BTC/kvB to sat/vB, rounded up; no estimate falls back to the relay fee
+        let fees = client.fee_estimates().unwrap();
+        assert_eq!(fees.fastest_fee, 20);
+        assert_eq!(fees.half_hour_fee, 11);
+        assert_eq!(fees.economy_fee, 1);
+        assert_eq!(fees.minimum_fee, 1);
+
+        assert_eq!(client.tip_height().unwrap(), 812400);
+        let err = client.call("blockchain.nonsense", json!([])).unwrap_err();
+        assert!(err.to_string().contains("unknown method"));
+    }
+}
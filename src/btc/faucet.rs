@@ -0,0 +1,465 @@
+//! Testnet/Signet Faucet
+//!
+//! `dkg-faucet --name <wallet>` asks a faucet for coins to the group's
+//! Taproot address and polls the chain backend until they arrive, so a demo
+//! goes from keygen to a funded wallet without anyone leaving the terminal.
+//!
+//! Public faucets differ (and many sit behind a captcha), so the faucet is
+//! configured per network in `faucet.json` (`faucet-config`) or given for one
+//! run with `--faucet-url`:
+//!
+//! - a URL containing `{address}` is fetched with GET, with `{address}` and
+//!   `{amount}` filled in
+//! - any other URL gets a POST of `{"address": ..., "amount": ...}`
+//!
+//! A txid in the answer (the whole body, or a `txid` field) is reported, but
+//! arrival is detected by a new UTXO on the address either way. Mainnet is
+//! refused. Under `--simulate` the simulated chain pays out and no faucet
+//! needs configuring.
+
+use crate::btc::hd_address::parse_network;
+use crate::btc::http;
+use crate::btc::schnorr::{get_dkg_address_core, BitcoinAddressOutput};
+use crate::btc::simulate;
+use crate::btc::transaction::{ChainBackend, MempoolBackend};
+use crate::error::Error;
+use crate::protocol::keygen::get_state_dir;
+use crate::protocol::wallet::STATE_ROOT;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use bitcoin::Network;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const FAUCET_CONFIG_FILE: &str = "faucet.json";
+
+/// Default poll interval in seconds
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 10;
+
+/// Default time to wait for the coins, in seconds
+pub const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// Per-network faucets (`.frost_state/faucet.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaucetConfig {
+    /// Keyed by network name (testnet, signet, regtest)
+    #[serde(default)]
+    pub faucets: BTreeMap<String, Faucet>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Faucet {
+    /// GET URL with `{address}`, or a URL to POST `{"address", "amount"}` to
+    pub url: String,
+    /// Sats to ask for; the faucet decides when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_sats: Option<u64>,
+}
+
+/// Load the faucet config (empty if none)
+pub fn load_config(storage: &dyn Storage) -> Result<FaucetConfig> {
+    if !storage.exists(FAUCET_CONFIG_FILE) {
+        return Ok(FaucetConfig::default());
+    }
+    serde_json::from_slice(&storage.read(FAUCET_CONFIG_FILE)?)
+        .with_context(|| format!("Failed to parse {}", FAUCET_CONFIG_FILE))
+}
+
+fn save_config(storage: &dyn Storage, config: &FaucetConfig) -> Result<()> {
+    storage.write(
+        FAUCET_CONFIG_FILE,
+        serde_json::to_string_pretty(config)?.as_bytes(),
+    )
+}
+
+/// Network name for a faucet network; mainnet has no faucet
+fn faucet_network(network: &str) -> Result<(Network, &'static str)> {
+    match parse_network(network)? {
+        Network::Bitcoin => bail!(Error::User(
+            "There is no faucet for mainnet; use testnet, signet or regtest".to_string()
+        )),
+        Network::Signet => Ok((Network::Signet, "signet")),
+        Network::Regtest => Ok((Network::Regtest, "regtest")),
+        _ => Ok((Network::Testnet, "testnet")),
+    }
+}
+
+/// A txid in a faucet's answer: the whole body, or a `txid` field
+pub fn parse_txid(body: &str) -> Option<String> {
+    let is_txid = |s: &str| s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit());
+    let body = body.trim().trim_matches('"');
+    if is_txid(body) {
+        return Some(body.to_lowercase());
+    }
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value
+        .get("txid")
+        .and_then(|txid| txid.as_str())
+        .filter(|txid| is_txid(txid))
+        .map(str::to_lowercase)
+}
+
+/// Ask `faucet` for coins to `address`; returns the txid if it names one
+pub fn request_coins(faucet: &Faucet, address: &str) -> Result<Option<String>> {
+    if simulate::is_simulated() {
+        let sats = faucet.amount_sats.unwrap_or(simulate::DEMO_FUNDING_SATS);
+        return simulate::fund(address, sats).map(Some);
+    }
+    let client = http::client();
+    let amount = faucet
+        .amount_sats
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+    let request = if faucet.url.contains("{address}") {
+        client.get(
+            faucet
+                .url
+                .replace("{address}", address)
+                .replace("{amount}", &amount),
+        )
+    } else {
+        let mut body = serde_json::json!({ "address": address });
+        if let Some(sats) = faucet.amount_sats {
+            body["amount"] = sats.into();
+        }
+        client.post(&faucet.url).json(&body)
+    };
+
+    let response = http::send(&client, request).context("Failed to reach the faucet")?;
+    let status = response.status();
+    let body = response.text().unwrap_or_default();
+    if !status.is_success() {
+        bail!(Error::Network(format!(
+            "Faucet refused the request ({}): {}",
+            status,
+            body.trim()
+        )));
+    }
+    Ok(parse_txid(&body))
+}
+
+/// Options for [`faucet_core_with_backend`]
+#[derive(Debug, Clone)]
+pub struct FaucetOptions {
+    /// Poll interval in seconds
+    pub interval_secs: u64,
+    /// Give up after this many seconds (0 = never)
+    pub timeout_secs: u64,
+}
+
+/// Output from dkg-faucet command
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FaucetOutput {
+    pub address: String,
+    pub network: String,
+    pub txid: Option<String>,
+    pub received_sats: u64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Request coins for `address` and wait until new UTXOs show up in `backend`
+///
+/// Progress lines are printed as they happen (this is a long-running
+/// command) and also collected in the output.
+pub fn faucet_core_with_backend(
+    address: &str,
+    network: &str,
+    faucet: &Faucet,
+    opts: &FaucetOptions,
+    backend: &dyn ChainBackend,
+) -> Result<CommandResult> {
+    fn log(out: &mut String, line: String) {
+        println!("{}", line);
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    let mut out = String::new();
+    log(&mut out, "Faucet Request\n".to_string());
+    log(
+        &mut out,
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".to_string(),
+    );
+    log(&mut out, format!("Network: {}", network));
+    log(&mut out, format!("Address: {}", address));
+    log(&mut out, format!("Faucet:  {}", faucet.url));
+
+    // Coins already there are not the faucet's
+    let known: BTreeSet<(String, u32)> = backend
+        .utxos(address)
+        .context("Cannot read the address's UTXOs before asking the faucet")?
+        .into_iter()
+        .map(|utxo| (utxo.txid, utxo.vout))
+        .collect();
+
+    let txid = request_coins(faucet, address)?;
+    match &txid {
+        Some(txid) => log(&mut out, format!("✓ Faucet sent {}", txid)),
+        None => log(&mut out, "✓ Faucet accepted the request".to_string()),
+    }
+    log(
+        &mut out,
+        format!(
+            "Waiting for the coins, polling every {}s\n",
+            opts.interval_secs
+        ),
+    );
+
+    let interval = Duration::from_secs(opts.interval_secs.max(1));
+    let started = Instant::now();
+    let received_sats = loop {
+        match backend.utxos(address) {
+            Ok(utxos) => {
+                let received: u64 = utxos
+                    .iter()
+                    .filter(|utxo| !known.contains(&(utxo.txid.clone(), utxo.vout)))
+                    .map(|utxo| utxo.value)
+                    .sum();
+                if received > 0 {
+                    log(&mut out, format!("✅ Received {} sats", received));
+                    break received;
+                }
+            }
+            Err(e) => log(&mut out, format!("⚠️  Poll failed: {}", e)),
+        }
+
+        if opts.timeout_secs > 0 && started.elapsed() >= Duration::from_secs(opts.timeout_secs) {
+            bail!(Error::Network(format!(
+                "Timed out after {}s waiting for the faucet's coins to reach {}",
+                opts.timeout_secs, address
+            )));
+        }
+        thread::sleep(interval);
+    };
+
+    let output = FaucetOutput {
+        address: address.to_string(),
+        network: network.to_string(),
+        txid,
+        received_sats,
+        event_type: "dkg_faucet".to_string(),
+    };
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&output)?,
+    })
+}
+
+/// Core function for setting (or with `url = None`, clearing) a network's faucet
+pub fn configure_core(
+    network: &str,
+    url: Option<&str>,
+    amount_sats: Option<u64>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let (_, key) = faucet_network(network)?;
+    let mut config = load_config(storage)?;
+
+    let mut out = String::new();
+    out.push_str("Faucet Configured\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("Network: {}\n", key));
+    let faucet = match url {
+        Some(url) => {
+            let parsed = reqwest::Url::parse(url.trim())
+                .with_context(|| format!("Invalid faucet URL '{}'", url))?;
+            if parsed.scheme() != "https" && parsed.scheme() != "http" {
+                bail!("Faucet URL must start with http:// or https://");
+            }
+            let faucet = Faucet {
+                url: url.trim().to_string(),
+                amount_sats,
+            };
+            config.faucets.insert(key.to_string(), faucet.clone());
+            out.push_str(&format!("URL:     {}\n", faucet.url));
+            if let Some(sats) = amount_sats {
+                out.push_str(&format!("Amount:  {} sats\n", sats));
+            }
+            Some(faucet)
+        }
+        None => {
+            config.faucets.remove(key);
+            out.push_str("URL:     none\n");
+            None
+        }
+    };
+    save_config(storage, &config)?;
+
+    let result = serde_json::json!({
+        "network": key,
+        "url": faucet.as_ref().map(|f| f.url.clone()),
+        "amount_sats": faucet.and_then(|f| f.amount_sats),
+        "type": "faucet_config",
+    });
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+/// Set or clear the faucet for a network
+pub fn configure(network: &str, url: Option<&str>, amount_sats: Option<u64>) -> Result<()> {
+    let storage = FileStorage::new(STATE_ROOT)?;
+    let result = configure_core(network, url, amount_sats, &storage)?;
+    print!("{}", result.output);
+    println!("{}", result.result);
+    Ok(())
+}
+
+/// CLI wrapper for dkg-faucet
+pub fn dkg_faucet(
+    name: &str,
+    network: &str,
+    faucet_url: Option<&str>,
+    amount_sats: Option<u64>,
+    opts: &FaucetOptions,
+) -> Result<()> {
+    let (network, key) = faucet_network(network)?;
+    let state_dir = get_state_dir(name);
+    if !std::path::Path::new(&state_dir).exists() {
+        bail!(Error::Storage(format!("Wallet '{}' not found", name)));
+    }
+    let storage = FileStorage::new(&state_dir)?;
+    let address: BitcoinAddressOutput =
+        serde_json::from_str(&get_dkg_address_core(network, &storage)?.result)?;
+
+    let configured = load_config(&FileStorage::new(STATE_ROOT)?)?
+        .faucets
+        .remove(key);
+    let faucet = match (faucet_url, configured) {
+        _ if simulate::is_simulated() => Faucet {
+            url: format!("{} faucet", simulate::LABEL),
+            amount_sats,
+        },
+        (Some(url), configured) => Faucet {
+            url: url.to_string(),
+            amount_sats: amount_sats.or(configured.and_then(|f| f.amount_sats)),
+        },
+        (None, Some(mut faucet)) => {
+            faucet.amount_sats = amount_sats.or(faucet.amount_sats);
+            faucet
+        }
+        (None, None) => bail!(Error::User(format!(
+            "No faucet configured for {}. Set one with: frostdao faucet-config --network {} --url <faucet URL>",
+            key, key
+        ))),
+    };
+
+    let cmd_result = faucet_core_with_backend(
+        &address.address,
+        key,
+        &faucet,
+        opts,
+        &MempoolBackend::new(network),
+    )?;
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}\n", cmd_result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::transaction::{FeeEstimate, UtxoResponse};
+    use crate::storage::MemoryStorage;
+    use std::cell::Cell;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Faucet answering one request with `body`; returns its URL
+    fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/claim", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+        });
+        url
+    }
+
+    /// Chain with one old UTXO, where the faucet's coins land on the third poll
+    struct SlowChain {
+        polls: Cell<u32>,
+    }
+
+    impl ChainBackend for SlowChain {
+        fn utxos(&self, _address: &str) -> Result<Vec<UtxoResponse>> {
+            self.polls.set(self.polls.get() + 1);
+            let utxo = |txid: &str, value| {
+                serde_json::from_value(serde_json::json!({
+                    "txid": txid, "vout": 0, "value": value,
+                    "status": {"confirmed": false}
+                }))
+                .unwrap()
+            };
+            let mut utxos = vec![utxo("aa", 1_000)];
+            if self.polls.get() >= 3 {
+                utxos.push(utxo("bb", 50_000));
+            }
+            Ok(utxos)
+        }
+        fn fee_estimates(&self) -> Result<FeeEstimate> {
+            unreachable!()
+        }
+        fn broadcast(&self, _raw_tx_hex: &str) -> Result<String> {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_faucet_request_and_arrival() {
+        let txid = "ab".repeat(32);
+        assert_eq!(parse_txid(&format!("\"{}\"\n", txid)), Some(txid.clone()));
+        assert_eq!(
+            parse_txid(&format!(r#"{{"txid":"{}"}}"#, txid.to_uppercase())),
+            Some(txid.clone())
+        );
+        assert_eq!(parse_txid("Payment sent"), None);
+
+        let faucet = Faucet {
+            url: serve(
+                r#"{"txid":"abababababababababababababababababababababababababababababababab"}"#,
+            ),
+            amount_sats: Some(50_000),
+        };
+        let opts = FaucetOptions {
+            interval_secs: 0,
+            timeout_secs: 0,
+        };
+        let chain = SlowChain {
+            polls: Cell::new(0),
+        };
+        let result =
+            faucet_core_with_backend("tb1ptest", "signet", &faucet, &opts, &chain).unwrap();
+        let output: FaucetOutput = serde_json::from_str(&result.result).unwrap();
+        assert_eq!(output.txid, Some(txid));
+        assert_eq!(output.received_sats, 50_000);
+    }
+
+    #[test]
+    fn test_faucet_config() {
+        let storage = MemoryStorage::new();
+        let url = "https://faucet.example.org/api/claim";
+        configure_core("signet", Some(url), Some(10_000), &storage).unwrap();
+        let config = load_config(&storage).unwrap();
+        assert_eq!(config.faucets["signet"].url, url);
+        assert_eq!(config.faucets["signet"].amount_sats, Some(10_000));
+
+        assert!(configure_core("mainnet", Some(url), None, &storage).is_err());
+        assert!(configure_core("testnet", Some("ftp://x"), None, &storage).is_err());
+
+        configure_core("signet", None, None, &storage).unwrap();
+        assert!(load_config(&storage).unwrap().faucets.is_empty());
+    }
+}
@@ -0,0 +1,583 @@
+//! Fiat Exchange Rates
+//!
+//! Optional BTC price lookup so balances can be shown, and send amounts
+//! entered, in a fiat currency. Opt in with `fiat-config`; the settings live
+//! in `.frost_state/fiat.json` and fetched prices are cached in
+//! `fiat_rates.json` for `cache_secs` to avoid a request per command.
+//!
+//! Fiat is only ever a way of choosing an amount: transactions are built in
+//! sats and the sat amount is always printed next to the fiat value.
+
+use crate::btc::amount::format_amount;
+use crate::protocol::health::now;
+use crate::protocol::wallet::STATE_ROOT;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub const FIAT_CONFIG_FILE: &str = "fiat.json";
+pub const RATE_CACHE_FILE: &str = "fiat_rates.json";
+
+const MEMPOOL_PRICES_URL: &str = "https://mempool.space/api/v1/prices";
+const COINGECKO_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+/// Currencies asked for in one request, so switching currency hits the cache
+const COMMON_CURRENCIES: &[&str] = &["USD", "EUR", "GBP", "CAD", "CHF", "AUD", "JPY"];
+
+const SATS_PER_BTC: f64 = 100_000_000.0;
+
+/// Where BTC prices come from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RateProvider {
+    /// mempool.space `/api/v1/prices` (USD, EUR, GBP, CAD, CHF, AUD, JPY)
+    #[default]
+    Mempool,
+    /// CoinGecko simple price API (most currencies)
+    CoinGecko,
+}
+
+impl RateProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mempool => "mempool",
+            Self::CoinGecko => "coingecko",
+        }
+    }
+}
+
+impl FromStr for RateProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "mempool" | "mempool.space" => Ok(Self::Mempool),
+            "coingecko" => Ok(Self::CoinGecko),
+            other => anyhow::bail!("Unknown rate provider '{}'. Use: mempool, coingecko", other),
+        }
+    }
+}
+
+/// Fiat display settings (`.frost_state/fiat.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FiatConfig {
+    /// ISO 4217 code, upper case
+    pub currency: String,
+    #[serde(default)]
+    pub provider: RateProvider,
+    /// How long a fetched price is reused
+    #[serde(default = "default_cache_secs")]
+    pub cache_secs: u64,
+}
+
+fn default_cache_secs() -> u64 {
+    300
+}
+
+impl Default for FiatConfig {
+    fn default() -> Self {
+        Self {
+            currency: "USD".to_string(),
+            provider: RateProvider::default(),
+            cache_secs: default_cache_secs(),
+        }
+    }
+}
+
+/// Load the fiat config; `None` if fiat display is not enabled
+pub fn load_config(storage: &dyn Storage) -> Result<Option<FiatConfig>> {
+    if !storage.exists(FIAT_CONFIG_FILE) {
+        return Ok(None);
+    }
+    let json = String::from_utf8(storage.read(FIAT_CONFIG_FILE)?)?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .context("Failed to parse fiat.json")
+}
+
+/// Prices from one fetch (`fiat_rates.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct RateCache {
+    provider: RateProvider,
+    fetched_at: u64,
+    /// Price of 1 BTC by currency code
+    rates: BTreeMap<String, f64>,
+}
+
+/// Price of 1 BTC in one currency
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Rate {
+    pub currency: String,
+    pub btc_price: f64,
+    pub provider: RateProvider,
+    pub fetched_at: u64,
+    /// The provider could not be reached and an expired cached price is used
+    pub stale: bool,
+}
+
+impl Rate {
+    /// Sats for a fiat value, rounded to the nearest sat
+    pub fn to_sats(&self, value: f64) -> u64 {
+        (value / self.btc_price * SATS_PER_BTC).round() as u64
+    }
+
+    pub fn to_fiat(&self, sats: u64) -> f64 {
+        sats as f64 / SATS_PER_BTC * self.btc_price
+    }
+
+    /// e.g. "1 BTC = 70190.00 USD (mempool, 2m ago)"
+    pub fn describe(&self) -> String {
+        let age = now().saturating_sub(self.fetched_at);
+        let age = match age {
+            0..=59 => "just now".to_string(),
+            60..=3599 => format!("{}m ago", age / 60),
+            3600..=86399 => format!("{}h ago", age / 3600),
+            _ => format!("{}d ago", age / 86400),
+        };
+        format!(
+            "1 BTC = {} ({}, {}{})",
+            format_fiat(self.btc_price, &self.currency),
+            self.provider.name(),
+            age,
+            if self.stale {
+                ", provider unreachable"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+/// A fiat amount such as `50USD`, `12.5 eur` or `100 JPY`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiatAmount {
+    pub value: f64,
+    pub currency: String,
+}
+
+impl FromStr for FiatAmount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| c.is_ascii_alphabetic())
+            .with_context(|| format!("'{}' has no currency, e.g. 50USD", s))?;
+        let (number, currency) = s.split_at(split);
+        if matches!(
+            currency.trim().to_ascii_lowercase().as_str(),
+            "btc" | "sat" | "sats"
+        ) {
+            anyhow::bail!("'{}' is a bitcoin amount; use --amount", s);
+        }
+        let value: f64 = number
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid fiat amount '{}'", s))?;
+        if !value.is_finite() || value <= 0.0 {
+            anyhow::bail!("Fiat amount must be positive");
+        }
+        Ok(Self {
+            value,
+            currency: parse_currency(currency)?,
+        })
+    }
+}
+
+/// Normalize an ISO 4217 code
+pub fn parse_currency(code: &str) -> Result<String> {
+    let code = code.trim();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        anyhow::bail!(
+            "Currency must be a 3-letter code such as USD or EUR, got '{}'",
+            code
+        );
+    }
+    Ok(code.to_ascii_uppercase())
+}
+
+/// Two decimals, or none for currencies without minor units
+pub fn format_fiat(value: f64, currency: &str) -> String {
+    match currency {
+        "JPY" | "KRW" => format!("{:.0} {}", value, currency),
+        _ => format!("{:.2} {}", value, currency),
+    }
+}
+
+// ============================================================================
+// Providers
+// ============================================================================
+
+/// Parse `{"time": ..., "USD": 70190, "EUR": 64820, ...}`
+pub fn parse_mempool_prices(json: &str) -> Result<BTreeMap<String, f64>> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("Failed to parse mempool.space prices")?;
+    let object = value
+        .as_object()
+        .context("mempool.space prices are not an object")?;
+    Ok(object
+        .iter()
+        .filter(|(key, _)| key.as_str() != "time")
+        .filter_map(|(key, price)| Some((key.to_ascii_uppercase(), price.as_f64()?)))
+        .filter(|(_, price)| *price > 0.0)
+        .collect())
+}
+
+/// Parse `{"bitcoin": {"usd": 70190, "eur": 64820}}`
+pub fn parse_coingecko_prices(json: &str) -> Result<BTreeMap<String, f64>> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("Failed to parse CoinGecko prices")?;
+    let object = value["bitcoin"]
+        .as_object()
+        .context("CoinGecko response has no bitcoin prices")?;
+    Ok(object
+        .iter()
+        .filter_map(|(key, price)| Some((key.to_ascii_uppercase(), price.as_f64()?)))
+        .filter(|(_, price)| *price > 0.0)
+        .collect())
+}
+
+fn fetch_rates(provider: RateProvider, currency: &str) -> Result<BTreeMap<String, f64>> {
+    let client = crate::btc::http::client();
+    let request = match provider {
+        RateProvider::Mempool => client.get(MEMPOOL_PRICES_URL),
+        RateProvider::CoinGecko => {
+            let mut currencies: Vec<String> = COMMON_CURRENCIES
+                .iter()
+                .map(|c| c.to_ascii_lowercase())
+                .collect();
+            if !COMMON_CURRENCIES.contains(&currency) {
+                currencies.push(currency.to_ascii_lowercase());
+            }
+            client.get(COINGECKO_PRICE_URL).query(&[
+                ("ids", "bitcoin".to_string()),
+                ("vs_currencies", currencies.join(",")),
+            ])
+        }
+    };
+    let response = request
+        .send()
+        .with_context(|| format!("Failed to fetch BTC price from {}", provider.name()))?;
+    if !response.status().is_success() {
+        anyhow::bail!("{} price API error {}", provider.name(), response.status());
+    }
+    let body = response.text()?;
+    match provider {
+        RateProvider::Mempool => parse_mempool_prices(&body),
+        RateProvider::CoinGecko => parse_coingecko_prices(&body),
+    }
+}
+
+// ============================================================================
+// Lookup
+// ============================================================================
+
+/// Price of 1 BTC in `currency`, from the cache while it is fresh
+///
+/// `fetch` is called on a cache miss; if it fails, an expired cached price
+/// for the currency is returned marked `stale` rather than failing.
+pub fn rate_with(
+    config: &FiatConfig,
+    currency: &str,
+    storage: &dyn Storage,
+    fetch: impl FnOnce(RateProvider, &str) -> Result<BTreeMap<String, f64>>,
+) -> Result<Rate> {
+    let cached: Option<RateCache> = storage
+        .read(RATE_CACHE_FILE)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .filter(|cache: &RateCache| cache.provider == config.provider);
+    let cached_rate = |cache: &RateCache, stale: bool| {
+        cache.rates.get(currency).map(|&btc_price| Rate {
+            currency: currency.to_string(),
+            btc_price,
+            provider: cache.provider,
+            fetched_at: cache.fetched_at,
+            stale,
+        })
+    };
+
+    if let Some(cache) = &cached {
+        if now().saturating_sub(cache.fetched_at) < config.cache_secs {
+            if let Some(rate) = cached_rate(cache, false) {
+                return Ok(rate);
+            }
+        }
+    }
+
+    match fetch(config.provider, currency) {
+        Ok(rates) => {
+            let cache = RateCache {
+                provider: config.provider,
+                fetched_at: now(),
+                rates,
+            };
+            storage.write(RATE_CACHE_FILE, &serde_json::to_vec_pretty(&cache)?)?;
+            cached_rate(&cache, false).with_context(|| {
+                format!(
+                    "{} has no BTC price in {}",
+                    config.provider.name(),
+                    currency
+                )
+            })
+        }
+        Err(e) => cached
+            .as_ref()
+            .and_then(|cache| cached_rate(cache, true))
+            .ok_or(e),
+    }
+}
+
+/// Price of 1 BTC in `currency` (default: the configured one)
+pub fn rate(currency: Option<&str>) -> Result<Rate> {
+    let storage = FileStorage::new(STATE_ROOT)?;
+    let config = load_config(&storage)?.unwrap_or_default();
+    let currency = match currency {
+        Some(code) => parse_currency(code)?,
+        None => config.currency.clone(),
+    };
+    rate_with(&config, &currency, &storage, fetch_rates)
+}
+
+/// The configured currency's rate, or `None` if fiat display is off or unavailable
+pub fn display_rate() -> Option<Rate> {
+    let storage = FileStorage::new(STATE_ROOT).ok()?;
+    let config = load_config(&storage).ok()??;
+    rate_with(&config, &config.currency, &storage, fetch_rates).ok()
+}
+
+/// `≈ 12.34 USD` for a balance, when fiat display is on
+pub fn balance_line(sats: u64) -> Option<String> {
+    let rate = display_rate()?;
+    Some(format!(
+        "≈ {}  ({})",
+        format_fiat(rate.to_fiat(sats), &rate.currency),
+        rate.describe()
+    ))
+}
+
+/// Sats for a command's `--amount` / `--amount-fiat` pair
+///
+/// A fiat amount is converted at the current rate and the conversion is
+/// printed to stderr so the sat amount can be checked before signing.
+pub fn resolve_amount(sats: Option<u64>, fiat: Option<&str>) -> Result<u64> {
+    match (sats, fiat) {
+        (Some(sats), None) => Ok(sats),
+        (None, Some(fiat)) => {
+            let amount: FiatAmount = fiat.parse()?;
+            let rate = rate(Some(&amount.currency))?;
+            let sats = rate.to_sats(amount.value);
+            if sats == 0 {
+                anyhow::bail!(
+                    "{} is less than 1 sat",
+                    format_fiat(amount.value, &amount.currency)
+                );
+            }
+            eprintln!(
+                "💱 {} = {}  ({})",
+                format_fiat(amount.value, &amount.currency),
+                format_amount(sats),
+                rate.describe()
+            );
+            if rate.stale {
+                eprintln!("⚠️  Using an expired exchange rate; check the sat amount");
+            }
+            Ok(sats)
+        }
+        _ => anyhow::bail!("Give exactly one of --amount or --amount-fiat"),
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Core function for enabling (or with `currency = None`, disabling) fiat display
+pub fn configure_core(
+    currency: Option<&str>,
+    provider: Option<RateProvider>,
+    cache_secs: Option<u64>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    out.push_str("Fiat Display Configured\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+
+    let Some(currency) = currency else {
+        storage.delete(FIAT_CONFIG_FILE).ok();
+        storage.delete(RATE_CACHE_FILE).ok();
+        out.push_str("Fiat display disabled\n");
+        return Ok(CommandResult {
+            output: out,
+            result: serde_json::json!({"enabled": false, "type": "fiat_config"}).to_string(),
+        });
+    };
+
+    let defaults = FiatConfig::default();
+    let config = FiatConfig {
+        currency: parse_currency(currency)?,
+        provider: provider.unwrap_or(defaults.provider),
+        cache_secs: cache_secs.unwrap_or(defaults.cache_secs),
+    };
+    if config.provider == RateProvider::Mempool
+        && !COMMON_CURRENCIES.contains(&config.currency.as_str())
+    {
+        anyhow::bail!(
+            "mempool.space only prices {}. Use --provider coingecko for {}.",
+            COMMON_CURRENCIES.join(", "),
+            config.currency
+        );
+    }
+    storage.write(
+        FIAT_CONFIG_FILE,
+        serde_json::to_string_pretty(&config)?.as_bytes(),
+    )?;
+
+    out.push_str(&format!("Currency: {}\n", config.currency));
+    out.push_str(&format!("Provider: {}\n", config.provider.name()));
+    out.push_str(&format!("Cache:    {}s\n", config.cache_secs));
+    out.push_str("\nBalances now show an approximate fiat value; send commands\n");
+    out.push_str("accept --amount-fiat (the sat amount is always shown).\n");
+
+    let mut result = serde_json::to_value(&config)?;
+    result["enabled"] = true.into();
+    result["type"] = "fiat_config".into();
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+/// Enable or disable fiat display
+pub fn configure(
+    currency: Option<&str>,
+    provider: Option<RateProvider>,
+    cache_secs: Option<u64>,
+) -> Result<()> {
+    let storage = FileStorage::new(STATE_ROOT)?;
+    let result = configure_core(currency, provider, cache_secs, &storage)?;
+    print!("{}", result.output);
+    println!("{}", result.result);
+    Ok(())
+}
+
+/// Print the current BTC price
+pub fn show_rate(currency: Option<&str>) -> Result<()> {
+    let rate = rate(currency)?;
+    println!("{}", rate.describe());
+    if rate.stale {
+        eprintln!("⚠️  Could not reach the provider; this price has expired");
+    }
+    let mut result = serde_json::to_value(&rate)?;
+    result["type"] = "fiat_rate".into();
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn prices(usd: f64) -> BTreeMap<String, f64> {
+        BTreeMap::from([("USD".to_string(), usd), ("EUR".to_string(), usd * 0.9)])
+    }
+
+    #[test]
+    fn test_parse_fiat_amount() {
+        let amount: FiatAmount = "50USD".parse().unwrap();
+        assert_eq!(amount.value, 50.0);
+        assert_eq!(amount.currency, "USD");
+        let amount: FiatAmount = " 12.5 eur".parse().unwrap();
+        assert_eq!(amount.value, 12.5);
+        assert_eq!(amount.currency, "EUR");
+
+        assert!("50".parse::<FiatAmount>().is_err());
+        assert!("USD".parse::<FiatAmount>().is_err());
+        assert!("-5USD".parse::<FiatAmount>().is_err());
+        assert!("5DOLLARS".parse::<FiatAmount>().is_err());
+        assert!("0.001btc".parse::<FiatAmount>().is_err());
+    }
+
+    #[test]
+    fn test_conversion() {
+        let rate = Rate {
+            currency: "USD".to_string(),
+            btc_price: 50_000.0,
+            provider: RateProvider::Mempool,
+            fetched_at: now(),
+            stale: false,
+        };
+        assert_eq!(rate.to_sats(50.0), 100_000);
+        assert_eq!(rate.to_fiat(100_000), 50.0);
+        assert_eq!(format_fiat(rate.to_fiat(12_345), "USD"), "6.17 USD");
+        assert_eq!(format_fiat(1234.4, "JPY"), "1234 JPY");
+    }
+
+    #[test]
+    fn test_parse_provider_responses() {
+        let rates = parse_mempool_prices(r#"{"time":1700000000,"USD":37000,"EUR":34000}"#).unwrap();
+        assert_eq!(rates["USD"], 37000.0);
+        assert!(!rates.contains_key("TIME"));
+
+        let rates = parse_coingecko_prices(r#"{"bitcoin":{"usd":37000.5,"brl":180000}}"#).unwrap();
+        assert_eq!(rates["USD"], 37000.5);
+        assert_eq!(rates["BRL"], 180000.0);
+        assert!(parse_coingecko_prices(r#"{"error":"rate limited"}"#).is_err());
+    }
+
+    #[test]
+    fn test_rate_cache() {
+        let storage = MemoryStorage::new();
+        let config = FiatConfig::default();
+
+        let rate = rate_with(&config, "USD", &storage, |_, _| Ok(prices(40_000.0))).unwrap();
+        assert_eq!(rate.btc_price, 40_000.0);
+        assert!(!rate.stale);
+
+        // Fresh cache: no fetch, other cached currencies too
+        let rate = rate_with(&config, "EUR", &storage, |_, _| panic!("fetched")).unwrap();
+        assert_eq!(rate.btc_price, 36_000.0);
+
+        // Expired cache and provider down: stale price instead of an error
+        let expired = FiatConfig {
+            cache_secs: 0,
+            ..config.clone()
+        };
+        let rate = rate_with(&expired, "USD", &storage, |_, _| anyhow::bail!("offline")).unwrap();
+        assert!(rate.stale);
+        assert_eq!(rate.btc_price, 40_000.0);
+
+        // Expired cache and provider up: refreshed
+        let rate = rate_with(&expired, "USD", &storage, |_, _| Ok(prices(41_000.0))).unwrap();
+        assert_eq!(rate.btc_price, 41_000.0);
+
+        // Unknown currency
+        assert!(rate_with(&expired, "XYZ", &storage, |_, _| Ok(prices(1.0))).is_err());
+    }
+
+    #[test]
+    fn test_configure() {
+        let storage = MemoryStorage::new();
+        assert_eq!(load_config(&storage).unwrap(), None);
+
+        assert!(configure_core(Some("BRL"), None, None, &storage).is_err());
+        configure_core(
+            Some("brl"),
+            Some(RateProvider::CoinGecko),
+            Some(60),
+            &storage,
+        )
+        .unwrap();
+        let config = load_config(&storage).unwrap().unwrap();
+        assert_eq!(config.currency, "BRL");
+        assert_eq!(config.provider, RateProvider::CoinGecko);
+        assert_eq!(config.cache_secs, 60);
+
+        configure_core(None, None, None, &storage).unwrap();
+        assert_eq!(load_config(&storage).unwrap(), None);
+    }
+}
@@ -14,7 +14,7 @@
 //! ```
 
 use crate::crypto::hd::{derive_at_path, DerivationPath, HdContext};
-use crate::protocol::keygen::HdMetadata;
+use crate::protocol::keygen::{require_secp256k1, HdMetadata};
 use crate::storage::Storage;
 use crate::CommandResult;
 use anyhow::{Context, Result};
@@ -120,6 +120,8 @@ pub fn load_hd_context(storage: &dyn Storage) -> Result<HdContext> {
         anyhow::bail!("HD derivation is not enabled for this wallet");
     }
 
+    require_secp256k1(storage)?;
+
     // Load shared key for master pubkey
     let shared_key_bytes = storage.read("shared_key.bin")?;
     let shared_key: SharedKey<EvenY> =
@@ -0,0 +1,463 @@
+//! Resilient HTTP for Chain Backends
+//!
+//! Esplora instances rate limit (429) and occasionally time out. Every chain
+//! request goes through [`send`], which per host:
+//!
+//! - spaces requests at least [`RetryPolicy::min_interval`] apart
+//! - retries 429 and 5xx answers, timeouts and refused connections with
+//!   exponential backoff, honouring `Retry-After`
+//! - after [`RetryPolicy::breaker_threshold`] failures in a row stops
+//!   sending for [`RetryPolicy::breaker_cooldown`] and fails fast with
+//!   [`CircuitOpen`]
+//!
+//! POST requests (broadcasts) are only retried when the backend cannot have
+//! processed them: refused connections and 429s.
+//!
+//! Every outbound client comes from [`client`], which applies the run's
+//! [`Timeouts`] (`--connect-timeout`, `--read-timeout`). [`send`] also stops
+//! retrying once the `--deadline` for the whole operation has passed, so a
+//! flaky backend cannot stall a command for minutes.
+
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::{Method, StatusCode};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Retry, rate limit and circuit breaker settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per request, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled on each further retry
+    pub base_delay: Duration,
+    /// Upper bound for a single backoff or `Retry-After` wait
+    pub max_delay: Duration,
+    /// Minimum gap between requests to the same host
+    pub min_interval: Duration,
+    /// Consecutive failures that open the circuit
+    pub breaker_threshold: u32,
+    /// How long an open circuit rejects requests
+    pub breaker_cooldown: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            min_interval: Duration::from_millis(200),
+            breaker_threshold: 8,
+            breaker_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before retry number `retry` (1-based)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u32 << retry.saturating_sub(1).min(16);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Connect, read and overall deadlines for outbound requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Establishing the TCP/TLS connection
+    pub connect: Duration,
+    /// A single request, from sending it to reading the whole body
+    pub read: Duration,
+    /// One [`send`] including all retries and backoff
+    pub deadline: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(10),
+            read: Duration::from_secs(30),
+            deadline: Duration::from_secs(60),
+        }
+    }
+}
+
+static TIMEOUTS: RwLock<Option<Timeouts>> = RwLock::new(None);
+
+/// Use `timeouts` for every request for the rest of this run
+pub fn set_timeouts(timeouts: Timeouts) {
+    *TIMEOUTS.write().unwrap_or_else(|e| e.into_inner()) = Some(timeouts);
+}
+
+/// The run's timeouts (defaults unless overridden on the command line)
+pub fn timeouts() -> Timeouts {
+    TIMEOUTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or_default()
+}
+
+/// HTTP client with the run's connect and read timeouts
+pub fn client() -> Client {
+    let timeouts = timeouts();
+    Client::builder()
+        .connect_timeout(timeouts.connect)
+        .timeout(timeouts.read)
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// A raw TCP connection, TLS-wrapped or not, for protocols that aren't
+/// plain HTTP requests (Electrum, Nostr relay websockets)
+pub(crate) enum Stream {
+    Tcp(TcpStream),
+    Tls(Box<native_tls::TlsStream<TcpStream>>),
+}
+
+impl Stream {
+    /// Connect to `host:port` with the run's timeouts; `describe` names the
+    /// server in errors
+    pub(crate) fn connect(host: &str, port: u16, tls: bool, describe: &str) -> Result<Self> {
+        let timeouts = timeouts();
+        let address = (host, port)
+            .to_socket_addrs()
+            .with_context(|| format!("Cannot resolve {}", host))?
+            .next()
+            .with_context(|| format!("No address for {}", host))?;
+        let tcp = TcpStream::connect_timeout(&address, timeouts.connect).context(
+            crate::error::Error::Network(format!("Cannot reach {}", describe)),
+        )?;
+        tcp.set_read_timeout(Some(timeouts.read))?;
+        tcp.set_write_timeout(Some(timeouts.read))?;
+        if !tls {
+            return Ok(Self::Tcp(tcp));
+        }
+        let connector = native_tls::TlsConnector::new()?;
+        let tls = connector.connect(host, tcp).map_err(|e| {
+            crate::error::Error::Network(format!("TLS handshake with {} failed: {}", describe, e))
+        })?;
+        Ok(Self::Tls(Box::new(tls)))
+    }
+
+    pub(crate) fn tcp(&self) -> &TcpStream {
+        match self {
+            Self::Tcp(tcp) => tcp,
+            Self::Tls(tls) => tls.get_ref(),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Tcp(tcp) => tcp.read(buf),
+            Self::Tls(tls) => tls.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Tcp(tcp) => tcp.write(buf),
+            Self::Tls(tls) => tls.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Tcp(tcp) => tcp.flush(),
+            Self::Tls(tls) => tls.flush(),
+        }
+    }
+}
+
+/// A host's circuit is open; no request was sent
+#[derive(Debug)]
+pub struct CircuitOpen {
+    pub host: String,
+    pub retry_in: Duration,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} keeps failing; not contacting it for another {}s",
+            self.host,
+            self.retry_in.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// The operation deadline passed before a request could be sent
+#[derive(Debug)]
+pub struct DeadlineExceeded {
+    pub host: String,
+    pub deadline: Duration,
+}
+
+impl fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Gave up on {} after {}s (--deadline)",
+            self.host,
+            self.deadline.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+/// Rate limit and breaker bookkeeping for one host
+#[derive(Debug, Default)]
+struct HostState {
+    next_slot: Option<Instant>,
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+static HOSTS: Mutex<BTreeMap<String, HostState>> = Mutex::new(BTreeMap::new());
+
+fn with_host<T>(host: &str, f: impl FnOnce(&mut HostState) -> T) -> T {
+    let mut hosts = HOSTS.lock().unwrap_or_else(|e| e.into_inner());
+    f(hosts.entry(host.to_string()).or_default())
+}
+
+/// Reserve the next request slot for `host`; returns how long to wait for it
+fn reserve_slot(host: &str, policy: &RetryPolicy) -> Result<Duration> {
+    let now = Instant::now();
+    with_host(host, |state| {
+        if let Some(until) = state.open_until {
+            if until > now {
+                return Err(CircuitOpen {
+                    host: host.to_string(),
+                    retry_in: until - now,
+                }
+                .into());
+            }
+            // Half open: let one request through, the next failure reopens
+            state.open_until = None;
+            state.failures = policy.breaker_threshold.saturating_sub(1);
+        }
+        let slot = state.next_slot.filter(|slot| *slot > now).unwrap_or(now);
+        state.next_slot = Some(slot + policy.min_interval);
+        Ok(slot - now)
+    })
+}
+
+fn record(host: &str, policy: &RetryPolicy, ok: bool) {
+    with_host(host, |state| {
+        if ok {
+            state.failures = 0;
+            return;
+        }
+        state.failures += 1;
+        if state.failures >= policy.breaker_threshold {
+            state.open_until = Some(Instant::now() + policy.breaker_cooldown);
+        }
+    })
+}
+
+fn retryable_status(status: StatusCode, idempotent: bool) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (idempotent
+            && matches!(
+                status,
+                StatusCode::INTERNAL_SERVER_ERROR
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ))
+}
+
+fn retryable_error(err: &reqwest::Error, idempotent: bool) -> bool {
+    err.is_connect() || (idempotent && err.is_timeout())
+}
+
+/// `Retry-After` in seconds, if the backend sent one
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Send `request` with the default [`RetryPolicy`]
+pub fn send(client: &Client, request: RequestBuilder) -> Result<Response> {
+    send_with(client, request, &RetryPolicy::default())
+}
+
+/// Send `request`, retrying transient failures per `policy`
+///
+/// Returns the last response once retries run out or the deadline passes,
+/// so callers still see the backend's status and body; transport errors are
+/// returned as is.
+pub fn send_with(
+    client: &Client,
+    request: RequestBuilder,
+    policy: &RetryPolicy,
+) -> Result<Response> {
+    let timeouts = timeouts();
+    let deadline = Instant::now() + timeouts.deadline;
+    let mut request = request.build().context("Invalid request")?;
+    let url = request.url();
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let idempotent = request.method() != Method::POST;
+
+    let mut attempt = 1;
+    loop {
+        std::thread::sleep(reserve_slot(&host, policy)?);
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            bail!(DeadlineExceeded {
+                host,
+                deadline: timeouts.deadline,
+            });
+        }
+        // The last attempt only gets what is left of the deadline
+        *request.timeout_mut() = Some(remaining.min(timeouts.read));
+        let this_try = request
+            .try_clone()
+            .context("Request body cannot be retried")?;
+        let last = attempt >= policy.max_attempts;
+        let result = client.execute(this_try);
+        let wait = match &result {
+            Ok(response) if retryable_status(response.status(), idempotent) => {
+                record(&host, policy, false);
+                retry_after(response).unwrap_or_else(|| policy.backoff(attempt))
+            }
+            Ok(_) => {
+                record(&host, policy, true);
+                return Ok(result?);
+            }
+            Err(err) => {
+                record(&host, policy, false);
+                if !retryable_error(err, idempotent) {
+                    return Ok(result?);
+                }
+                policy.backoff(attempt)
+            }
+        };
+        let wait = wait.min(policy.max_delay);
+        if last || Instant::now() + wait >= deadline {
+            return Ok(result?);
+        }
+        std::thread::sleep(wait);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Answer one connection per status in `statuses`; returns the base URL
+    fn serve(statuses: Vec<u16>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                let body = status.to_string();
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+        url
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            min_interval: Duration::ZERO,
+            breaker_threshold: 3,
+            breaker_cooldown: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(10), policy.max_delay);
+    }
+
+    #[test]
+    fn test_retries_rate_limited_requests() {
+        let url = serve(vec![429, 503, 200]);
+        let client = Client::new();
+        let response = send_with(&client, client.get(&url), &fast_policy()).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_post_not_retried_on_server_error() {
+        let url = serve(vec![500, 200]);
+        let client = Client::new();
+        let response = send_with(&client, client.post(&url).body("tx"), &fast_policy()).unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_deadline_stops_retrying() {
+        let url = serve(vec![503, 503, 503]);
+        let client = Client::new();
+        let policy = RetryPolicy {
+            base_delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(5),
+            ..fast_policy()
+        };
+        // A 5 s backoff cannot fit in a 1 s deadline: return the first answer
+        set_timeouts(Timeouts {
+            deadline: Duration::from_secs(1),
+            ..Timeouts::default()
+        });
+        let started = Instant::now();
+        let response = send_with(&client, client.get(&url), &policy);
+        set_timeouts(Timeouts::default());
+        assert_eq!(response.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_circuit_opens_after_repeated_failures() {
+        let url = serve(vec![503, 503, 503]);
+        let client = Client::new();
+        let policy = fast_policy();
+        let response = send_with(&client, client.get(&url), &policy).unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let err = send_with(&client, client.get(&url), &policy).unwrap_err();
+        assert!(err.downcast_ref::<CircuitOpen>().is_some());
+    }
+}
@@ -0,0 +1,288 @@
+//! Inscription-Aware UTXO Protection
+//!
+//! A treasury address can receive outputs carrying ordinals inscriptions, runes,
+//! or rare sats. Coin selection here is "spend everything", so without a check
+//! those collectibles would silently be merged into a payment or burned as fee.
+//!
+//! Before coin selection, each UTXO is looked up on an ord-compatible API
+//! (`GET /output/<txid>:<vout>` with `Accept: application/json`). Outputs with
+//! inscriptions, runes, or a rare sat (first sat of a block or better) are
+//! frozen unless the caller explicitly passes `allow_inscribed`.
+//!
+//! If the API cannot be reached the check fails closed: the spend is refused
+//! with a hint to use `--allow-inscribed`, rather than guessing.
+
+#[cfg(feature = "network")]
+use crate::btc::transaction::UtxoResponse;
+#[cfg(feature = "network")]
+use anyhow::{Context, Result};
+use bitcoin::Network;
+#[cfg(feature = "network")]
+use serde::Deserialize;
+
+// ord explorer endpoints (JSON API)
+const ORD_MAINNET_API: &str = "https://ordinals.com";
+const ORD_TESTNET_API: &str = "https://testnet.ordinals.com";
+const ORD_SIGNET_API: &str = "https://signet.ordinals.com";
+
+/// Blocks per subsidy halving epoch
+const SUBSIDY_HALVING_INTERVAL: u64 = 210_000;
+/// Initial block subsidy in sats
+const INITIAL_SUBSIDY: u64 = 50 * 100_000_000;
+
+/// ord API base for a network, None where no public indexer exists (regtest)
+pub fn ord_api_base(network: Network) -> Option<&'static str> {
+    match network {
+        Network::Bitcoin => Some(ORD_MAINNET_API),
+        Network::Testnet => Some(ORD_TESTNET_API),
+        Network::Signet => Some(ORD_SIGNET_API),
+        _ => None,
+    }
+}
+
+/// Subset of ord's `/output` JSON response
+#[derive(Debug, Deserialize)]
+#[cfg(feature = "network")]
+struct OrdOutputResponse {
+    #[serde(default)]
+    inscriptions: Vec<String>,
+    #[serde(default)]
+    runes: serde_json::Value,
+    #[serde(default)]
+    sat_ranges: Option<Vec<(u64, u64)>>,
+}
+
+/// Collectible content found on a UTXO
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UtxoInscriptionInfo {
+    /// Outpoint as `txid:vout`
+    pub outpoint: String,
+    /// Inscription IDs carried by the output
+    pub inscriptions: Vec<String>,
+    /// Output holds rune balances
+    pub has_runes: bool,
+    /// Output contains at least one uncommon-or-better sat
+    pub has_rare_sats: bool,
+}
+
+impl UtxoInscriptionInfo {
+    /// Whether coin selection should freeze this output by default
+    pub fn is_protected(&self) -> bool {
+        !self.inscriptions.is_empty() || self.has_runes || self.has_rare_sats
+    }
+
+    /// Short reason string for display
+    pub fn reason(&self) -> String {
+        let mut reasons = Vec::new();
+        if !self.inscriptions.is_empty() {
+            reasons.push(format!("{} inscription(s)", self.inscriptions.len()));
+        }
+        if self.has_runes {
+            reasons.push("runes".to_string());
+        }
+        if self.has_rare_sats {
+            reasons.push("rare sats".to_string());
+        }
+        reasons.join(", ")
+    }
+}
+
+// ============================================================================
+// Rare Sat Detection
+// ============================================================================
+
+/// Block subsidy at a given height
+fn subsidy(height: u64) -> u64 {
+    let epoch = height / SUBSIDY_HALVING_INTERVAL;
+    if epoch >= 64 {
+        0
+    } else {
+        INITIAL_SUBSIDY >> epoch
+    }
+}
+
+/// Sat number of the first sat mined in `height`
+pub fn first_sat_of_height(height: u64) -> u64 {
+    let mut sat = 0u64;
+    let mut remaining = height;
+    let mut epoch_start = 0u64;
+    while remaining > 0 {
+        let blocks = remaining.min(SUBSIDY_HALVING_INTERVAL);
+        sat += blocks * subsidy(epoch_start);
+        remaining -= blocks;
+        epoch_start += SUBSIDY_HALVING_INTERVAL;
+    }
+    sat
+}
+
+/// Height of the block that mined `sat`
+fn height_of_sat(sat: u64) -> u64 {
+    let mut epoch_first_sat = 0u64;
+    let mut epoch_height = 0u64;
+    loop {
+        let reward = subsidy(epoch_height);
+        if reward == 0 {
+            return epoch_height;
+        }
+        let epoch_sats = reward * SUBSIDY_HALVING_INTERVAL;
+        if sat < epoch_first_sat + epoch_sats {
+            return epoch_height + (sat - epoch_first_sat) / reward;
+        }
+        epoch_first_sat += epoch_sats;
+        epoch_height += SUBSIDY_HALVING_INTERVAL;
+    }
+}
+
+/// Whether the half-open sat range [start, end) contains the first sat of a block
+///
+/// Every first-of-block sat is at least "uncommon" in ordinal theory.
+pub fn range_contains_rare_sat(start: u64, end: u64) -> bool {
+    if start >= end {
+        return false;
+    }
+    let height = height_of_sat(start);
+    first_sat_of_height(height) == start || first_sat_of_height(height + 1) < end
+}
+
+// ============================================================================
+// API Lookup
+// ============================================================================
+
+/// Look up a single UTXO on the ord API
+#[cfg(feature = "network")]
+pub fn check_utxo(txid: &str, vout: u32, api_base: &str) -> Result<UtxoInscriptionInfo> {
+    let client = crate::btc::http::client();
+    let outpoint = format!("{}:{}", txid, vout);
+    let url = format!("{}/output/{}", api_base, outpoint);
+
+    let response = client
+        .get(&url)
+        .header("Accept", "application/json")
+        .send()
+        .with_context(|| format!("Failed to query ord API for {}", outpoint))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("ord API error {} for {}", response.status(), outpoint);
+    }
+
+    let output: OrdOutputResponse = response
+        .json()
+        .context("Failed to parse ord output response")?;
+
+    let has_runes = match &output.runes {
+        serde_json::Value::Object(map) => !map.is_empty(),
+        serde_json::Value::Array(list) => !list.is_empty(),
+        _ => false,
+    };
+    let has_rare_sats = output
+        .sat_ranges
+        .unwrap_or_default()
+        .iter()
+        .any(|&(start, end)| range_contains_rare_sat(start, end));
+
+    Ok(UtxoInscriptionInfo {
+        outpoint,
+        inscriptions: output.inscriptions,
+        has_runes,
+        has_rare_sats,
+    })
+}
+
+/// Remove protected UTXOs from a coin selection candidate list
+///
+/// Appends a short report to `out`. With `allow_inscribed` the check is skipped
+/// entirely (the caller has opted in to spending collectibles).
+#[cfg(feature = "network")]
+pub fn filter_protected_utxos<'a>(
+    utxos: Vec<&'a UtxoResponse>,
+    network: Network,
+    allow_inscribed: bool,
+    out: &mut String,
+) -> Result<Vec<&'a UtxoResponse>> {
+    if allow_inscribed {
+        out.push_str("⚠️  --allow-inscribed: inscription/rare-sat protection disabled\n");
+        return Ok(utxos);
+    }
+
+    if crate::btc::simulate::is_simulated() {
+        out.push_str("Simulated chain - skipping inscription check\n");
+        return Ok(utxos);
+    }
+
+    let Some(api_base) = ord_api_base(network) else {
+        out.push_str("No ord indexer for this network - skipping inscription check\n");
+        return Ok(utxos);
+    };
+
+    let mut spendable = Vec::with_capacity(utxos.len());
+    let mut frozen = 0;
+    for utxo in utxos {
+        let info = check_utxo(&utxo.txid, utxo.vout, api_base).map_err(|e| {
+            anyhow::anyhow!(
+                "Could not verify {}:{} is free of inscriptions: {}\n\
+                 Re-run with --allow-inscribed to skip this check.",
+                utxo.txid,
+                utxo.vout,
+                e
+            )
+        })?;
+        if info.is_protected() {
+            frozen += 1;
+            out.push_str(&format!(
+                "❄️  Frozen {} ({} sats): {}\n",
+                info.outpoint,
+                utxo.value,
+                info.reason()
+            ));
+        } else {
+            spendable.push(utxo);
+        }
+    }
+
+    if frozen > 0 {
+        out.push_str(&format!(
+            "   {} UTXO(s) excluded from coin selection (use --allow-inscribed to spend)\n",
+            frozen
+        ));
+    }
+
+    Ok(spendable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rare_sat_detection() {
+        // Epoch boundaries
+        assert_eq!(first_sat_of_height(0), 0);
+        assert_eq!(first_sat_of_height(1), INITIAL_SUBSIDY);
+        assert_eq!(
+            first_sat_of_height(SUBSIDY_HALVING_INTERVAL + 1),
+            SUBSIDY_HALVING_INTERVAL * INITIAL_SUBSIDY + INITIAL_SUBSIDY / 2
+        );
+        assert_eq!(height_of_sat(INITIAL_SUBSIDY - 1), 0);
+        assert_eq!(height_of_sat(first_sat_of_height(840_000)), 840_000);
+
+        // First sat of a block is rare, a range crossing a block boundary is too
+        let block_start = first_sat_of_height(840_000);
+        assert!(range_contains_rare_sat(block_start, block_start + 1));
+        assert!(range_contains_rare_sat(block_start - 10, block_start + 10));
+        assert!(!range_contains_rare_sat(
+            block_start + 1,
+            block_start + 1000
+        ));
+        assert!(!range_contains_rare_sat(5, 5));
+
+        // Protection flags
+        let clean = UtxoInscriptionInfo::default();
+        assert!(!clean.is_protected());
+        let inscribed = UtxoInscriptionInfo {
+            inscriptions: vec!["abc i0".to_string()],
+            ..Default::default()
+        };
+        assert!(inscribed.is_protected());
+        assert_eq!(inscribed.reason(), "1 inscription(s)");
+    }
+}
@@ -0,0 +1,425 @@
+//! Lightning Channel Funding
+//!
+//! Funds Lightning channels from a threshold wallet through the peer's PSBT
+//! funding flow (LND `openchannel --psbt`, CLN `fundchannel_start` /
+//! `fundchannel_complete`). This is fund-only: the treasury pays for the
+//! channel, and the channel itself belongs to a Lightning node.
+//!
+//! 1. `dkg-ln-funding-address` builds the 2-of-2 funding output for our
+//!    node's funding key and the peer's, and checks it against the address
+//!    the peer asks to be funded
+//! 2. `dkg-build-tx --to <funding address>`, then nonces and signature shares
+//!    as for any spend
+//! 3. `dkg-ln-fund-psbt` combines the shares into a finalized PSBT for the
+//!    peer *without broadcasting*: the peer publishes it once it holds a
+//!    signed commitment transaction, or the funds could be stuck
+//!
+//! Simple taproot channels fund a P2TR output whose internal key is the
+//! BIP-327 MuSig2 aggregate of the sorted funding keys, with the BIP-86 tweak.
+//! Anchor channels fund the BOLT 3 P2WSH `2 <key1> <key2> 2 CHECKMULTISIG`.
+//!
+//! The threshold key signs the funding transaction and nothing else. Our side
+//! of the 2-of-2 is the node's own funding key, which signs every commitment
+//! update, so once opened the channel's funds are controlled by that node, not
+//! by the group. The group key is refused as a funding key: frostdao never
+//! signs commitment updates, so such a channel could never be used or closed
+//! cooperatively.
+
+use crate::btc::amount::format_amount;
+use crate::btc::hd_address::parse_network;
+use crate::crypto::helpers::tagged_hash;
+use crate::protocol::dkg_tx::combine_transaction;
+use crate::protocol::keygen::{get_state_dir, require_secp256k1};
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use bitcoin::psbt::Psbt;
+use bitcoin::script::{Builder, ScriptBuf};
+use bitcoin::{opcodes, Address, Amount, Network, TxOut, XOnlyPublicKey};
+use schnorr_fun::frost::SharedKey;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Kind of channel the funding output is for
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelType {
+    /// Simple taproot channel: MuSig2 key-path P2TR
+    Taproot,
+    /// Anchor (or legacy) channel: P2WSH 2-of-2 multisig
+    Anchors,
+}
+
+impl FromStr for ChannelType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "taproot" => Ok(ChannelType::Taproot),
+            "anchors" | "anchor" | "legacy" => Ok(ChannelType::Anchors),
+            other => bail!(
+                "Unknown channel type '{}' (expected taproot or anchors)",
+                other
+            ),
+        }
+    }
+}
+
+/// Funding output of a channel
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FundingOutput {
+    pub channel_type: ChannelType,
+    pub local_key: String,
+    pub remote_key: String,
+    pub address: String,
+    pub script_pubkey: String,
+    /// MuSig2 aggregate key before the BIP-86 tweak (taproot channels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_key: Option<String>,
+    /// `2 <key1> <key2> 2 CHECKMULTISIG` (anchor channels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness_script: Option<String>,
+}
+
+/// Signed funding transaction, ready for the peer
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FundingPsbtOutput {
+    pub psbt: String,
+    pub raw_tx: String,
+    pub txid: String,
+    pub funding_address: String,
+    pub funding_output_index: u32,
+    pub amount_sats: u64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Parse a 33-byte compressed public key (hex)
+pub fn parse_funding_key(key_hex: &str) -> Result<Point> {
+    let bytes: [u8; 33] = hex::decode(key_hex.trim())
+        .context("Funding key is not hex")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Funding key must be 33 bytes (compressed)"))?;
+    Point::from_bytes(bytes).context("Funding key is not a valid public key")
+}
+
+/// BIP-327 KeyAgg of `keys`, in the order given
+pub fn key_agg(keys: &[Point]) -> Result<Point> {
+    let encoded: Vec<[u8; 33]> = keys.iter().map(|key| key.to_bytes()).collect();
+    let list_hash = tagged_hash("KeyAgg list", &encoded.concat());
+    // The second distinct key gets coefficient 1
+    let second = encoded.iter().find(|key| **key != encoded[0]);
+    let terms = keys.iter().zip(&encoded).map(|(key, bytes)| {
+        let coefficient = if Some(bytes) == second {
+            Scalar::<Public, Zero>::from(1u32)
+        } else {
+            let hash = tagged_hash("KeyAgg coefficient", &[&list_hash[..], bytes].concat());
+            Scalar::<Secret, Zero>::from_bytes_mod_order(hash).public()
+        };
+        g!(coefficient * key)
+    });
+    terms
+        .fold(Point::<NonNormal, Public, Zero>::zero(), |acc, term| {
+            g!(acc + term)
+        })
+        .normalize()
+        .non_zero()
+        .context("Aggregate key is infinity")
+}
+
+/// Funding output for a channel between `local` and `remote`
+pub fn funding_output(
+    channel_type: ChannelType,
+    local: &Point,
+    remote: &Point,
+    network: Network,
+) -> Result<FundingOutput> {
+    // Both channel types order the keys lexicographically
+    let mut keys = [*local, *remote];
+    keys.sort_by_key(|key| key.to_bytes());
+
+    let (address, internal_key, witness_script) = match channel_type {
+        ChannelType::Taproot => {
+            let internal = XOnlyPublicKey::from_slice(&key_agg(&keys)?.to_xonly_bytes())?;
+            let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+            let address = Address::p2tr(&secp, internal, None, network);
+            (address, Some(internal.to_string()), None)
+        }
+        ChannelType::Anchors => {
+            let script = Builder::new()
+                .push_int(2)
+                .push_slice(keys[0].to_bytes())
+                .push_slice(keys[1].to_bytes())
+                .push_int(2)
+                .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+                .into_script();
+            let address = Address::p2wsh(&script, network);
+            (address, None, Some(hex::encode(script.as_bytes())))
+        }
+    };
+
+    Ok(FundingOutput {
+        channel_type,
+        local_key: hex::encode(local.to_bytes()),
+        remote_key: hex::encode(remote.to_bytes()),
+        script_pubkey: hex::encode(address.script_pubkey().as_bytes()),
+        address: address.to_string(),
+        internal_key,
+        witness_script,
+    })
+}
+
+/// The wallet's group key as a compressed funding key
+fn group_funding_key(storage: &dyn Storage) -> Result<Point> {
+    require_secp256k1(storage)?;
+    let shared_key: SharedKey<EvenY> = bincode::deserialize(&storage.read("shared_key.bin")?)?;
+    Ok(shared_key.public_key().normalize())
+}
+
+/// Core function for `dkg-ln-funding-address`
+///
+/// `local_key` is our node's funding key; the group key is refused (see the
+/// module docs: funding is the only thing the group signs). With `expect`,
+/// fails unless the peer's funding address is the one computed here.
+pub fn funding_address_core(
+    peer_key: &str,
+    local_key: &str,
+    channel_type: ChannelType,
+    network: &str,
+    expect: Option<&str>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let network = parse_network(network)?;
+    let remote = parse_funding_key(peer_key)?;
+    let local = parse_funding_key(local_key)?;
+    if local == group_funding_key(storage)? {
+        bail!(crate::error::Error::User(
+            "The group key can't be the channel's funding key: frostdao only funds channels and never signs their commitment updates. Pass your Lightning node's funding key".into()
+        ));
+    }
+    if local == remote {
+        bail!("Local and peer funding keys are the same");
+    }
+    let funding = funding_output(channel_type, &local, &remote, network)?;
+
+    let mut out = String::new();
+    out.push_str("Lightning Channel Funding Output\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("  Channel type: {:?}\n", channel_type));
+    out.push_str(&format!("  Node key:     {}\n", funding.local_key));
+    out.push_str(&format!("  Peer key:     {}\n", funding.remote_key));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    if let Some(internal) = &funding.internal_key {
+        out.push_str(&format!("🔑 MuSig2 internal key: {}\n", internal));
+    }
+    out.push_str(&format!("📍 Funding address: {}\n", funding.address));
+
+    if let Some(expected) = expect {
+        let expected = Address::from_str(expected.trim())
+            .context("Invalid expected funding address")?
+            .require_network(network)
+            .context("Expected funding address is for another network")?;
+        if expected.to_string() != funding.address {
+            bail!(
+                "The peer's funding address {} is not the 2-of-2 of these keys ({}); don't fund it",
+                expected,
+                funding.address
+            );
+        }
+        out.push_str("✓ Matches the address the peer asked to be funded\n");
+    }
+
+    out.push_str("\n🧠 Next steps:\n");
+    out.push_str(&format!(
+        "   • dkg-build-tx --to {} --amount <channel capacity>\n",
+        funding.address
+    ));
+    out.push_str("   • Nonces and signature shares as usual (dkg-nonce, dkg-sign)\n");
+    out.push_str("   • dkg-ln-fund-psbt instead of dkg-broadcast: the peer publishes it\n");
+    out.push_str("\nℹ️  Fund-only: once open, the channel is controlled by the node's key,\n");
+    out.push_str("   not by the group.\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&funding)?,
+    })
+}
+
+/// Core function for `dkg-ln-fund-psbt`
+///
+/// Combines a session's signature shares like `dkg-broadcast`, checks the
+/// transaction funds `funding_address`, and returns it as a finalized PSBT
+/// (plus raw hex) for the peer instead of broadcasting.
+pub fn fund_psbt_core(
+    session_id: &str,
+    unsigned_tx_hex: &str,
+    shares_data: &str,
+    funding_address: &str,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    out.push_str("Lightning Channel Funding PSBT\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+
+    let session: serde_json::Value = serde_json::from_slice(
+        &storage
+            .read(&format!("dkg_session_{}.json", session_id))
+            .with_context(|| format!("No session {}. Did you run dkg-build-tx?", session_id))?,
+    )?;
+    let network = parse_network(session["network"].as_str().unwrap_or("testnet"))?;
+    let funding_script = Address::from_str(funding_address.trim())
+        .context("Invalid funding address")?
+        .require_network(network)
+        .context("Funding address is for another network than the session")?
+        .script_pubkey();
+
+    let tx = combine_transaction(session_id, unsigned_tx_hex, shares_data, storage, &mut out)?;
+    if tx.input.len() != 1 {
+        bail!(
+            "The funding transaction has {} inputs but only one can be signed per session; \
+             consolidate first",
+            tx.input.len()
+        );
+    }
+    let (funding_index, funding_txout) = tx
+        .output
+        .iter()
+        .enumerate()
+        .find(|(_, output)| output.script_pubkey == funding_script)
+        .context("The transaction doesn't pay the funding address")?;
+
+    // The peer checks the PSBT's inputs against their previous outputs
+    let prevout = &session["prevouts"][0];
+    let witness_utxo = TxOut {
+        value: Amount::from_sat(
+            prevout["value"]
+                .as_u64()
+                .context("Session has no prevouts")?,
+        ),
+        script_pubkey: ScriptBuf::from_bytes(hex::decode(
+            prevout["script_pubkey"].as_str().unwrap_or_default(),
+        )?),
+    };
+    let mut unsigned = tx.clone();
+    unsigned.input[0].witness.clear();
+    let mut psbt = Psbt::from_unsigned_tx(unsigned)?;
+    psbt.inputs[0].witness_utxo = Some(witness_utxo);
+    psbt.inputs[0].final_script_witness = Some(tx.input[0].witness.clone());
+    let psbt_base64 = base64::engine::general_purpose::STANDARD.encode(psbt.serialize());
+
+    let output = FundingPsbtOutput {
+        psbt: psbt_base64,
+        raw_tx: bitcoin::consensus::encode::serialize_hex(&tx),
+        txid: tx.compute_txid().to_string(),
+        funding_address: funding_address.trim().to_string(),
+        funding_output_index: funding_index as u32,
+        amount_sats: funding_txout.value.to_sat(),
+        event_type: "ln_funding_psbt".to_string(),
+    };
+
+    out.push_str(&format!(
+        "✓ Funds {} to {} (output {})\n",
+        format_amount(output.amount_sats),
+        output.funding_address,
+        funding_index
+    ));
+    out.push_str(&format!("TxID: {}\n\n", output.txid));
+    out.push_str("⚠️  Not broadcast. Give the PSBT to the peer and let it publish:\n");
+    out.push_str("   • LND: paste it at the openchannel --psbt prompt\n");
+    out.push_str("   • CLN: fundchannel_complete <peer id> <psbt>, then sendpsbt\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&output)?,
+    })
+}
+
+/// CLI wrapper for `dkg-ln-funding-address`
+pub fn funding_address(
+    name: &str,
+    peer_key: &str,
+    local_key: &str,
+    channel_type: ChannelType,
+    network: &str,
+    expect: Option<&str>,
+) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result =
+        funding_address_core(peer_key, local_key, channel_type, network, expect, &storage)?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", cmd_result.result);
+    Ok(())
+}
+
+/// CLI wrapper for `dkg-ln-fund-psbt`
+pub fn fund_psbt(
+    name: &str,
+    session_id: &str,
+    unsigned_tx_hex: &str,
+    shares_data: &str,
+    funding_address: &str,
+) -> Result<()> {
+    let shares_data = crate::qr::resolve_payload(shares_data)?;
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = fund_psbt_core(
+        session_id,
+        unsigned_tx_hex,
+        &shares_data,
+        funding_address,
+        &storage,
+    )?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", cmd_result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(hex_key: &str) -> Point {
+        parse_funding_key(hex_key).unwrap()
+    }
+
+    #[test]
+    fn test_key_agg_matches_bip327_vectors() {
+        let x = [
+            key("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+            key("03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659"),
+            key("023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66"),
+        ];
+        let agg = |keys: &[Point]| hex::encode_upper(key_agg(keys).unwrap().to_xonly_bytes());
+        assert_eq!(
+            agg(&[x[0], x[1], x[2]]),
+            "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C"
+        );
+        assert_eq!(
+            agg(&[x[2], x[1], x[0]]),
+            "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B"
+        );
+        assert_eq!(
+            agg(&[x[0], x[0], x[0]]),
+            "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935"
+        );
+    }
+
+    #[test]
+    fn test_funding_output_ignores_key_order() {
+        let a = key("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9");
+        let b = key("03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659");
+        for channel_type in [ChannelType::Taproot, ChannelType::Anchors] {
+            let ours = funding_output(channel_type, &a, &b, Network::Testnet).unwrap();
+            let theirs = funding_output(channel_type, &b, &a, Network::Testnet).unwrap();
+            assert_eq!(ours.address, theirs.address);
+        }
+        let taproot = funding_output(ChannelType::Taproot, &a, &b, Network::Testnet).unwrap();
+        assert!(taproot.address.starts_with("tb1p"));
+        let anchors = funding_output(ChannelType::Anchors, &a, &b, Network::Testnet).unwrap();
+        assert!(anchors.address.starts_with("tb1q"));
+        assert!(anchors.witness_script.unwrap().starts_with("5221"));
+    }
+}
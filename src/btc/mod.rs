@@ -2,12 +2,61 @@
 //!
 //! This module provides Bitcoin-specific functionality:
 //!
+//! - **amount**: Amount parsing with `sat`/`btc` units and display
+//! - **broadcast**: Pushing transactions to the backend and extra endpoints at once
+//! - **compact_filters**: BIP-157/158 light client over P2P peers, no indexer needed
+//! - **descriptor**: Output descriptors and watch-only export for desktop wallets
+//! - **electrum**: Electrum server (electrs/Fulcrum) backend and scripthash subscriptions
+//! - **esplora**: Per-network Esplora endpoints and backend status
+//! - **faucet**: Testnet/signet faucet requests for demo wallets
+//! - **fiat**: Optional BTC exchange rates for fiat display and amount entry
 //! - **hd_address**: BIP-32/BIP-44 HD address derivation
+//! - **http**: Retries, backoff, rate limiting and circuit breaking for chain requests
+//! - **inscriptions**: Ordinals/runes/rare-sat UTXO protection for coin selection
+//! - **lightning**: Fund-only channel funding outputs and PSBTs for a Lightning peer
+//! - **monitor**: Mempool polling and confirmation notifications
+//! - **rebroadcast**: Pending transactions: rebroadcast, eviction and conflict detection
+//! - **sighash**: Step-by-step BIP-341 sighash recomputation, to check what is signed
+//! - **spend_plan**: Key path vs script path selection for script addresses
+//! - **simulate**: Local fake chain for offline demos (`--simulate`)
+//! - **script_index**: Every scriptPubKey a wallet owns, for change and history detection
 //! - **schnorr**: BIP-340 Schnorr signatures and Taproot addresses
-//! - **taproot_scripts**: Taproot script building (timelocks, HTLC, recovery)
+//! - **taproot_scripts**: Miniscript policies compiled to Taproot script leaves (timelocks, HTLC, recovery)
+//! - **taproot_tree**: Persisted taproot spend info per script address
 //! - **transaction**: Transaction building, signing, and broadcasting
+//! - **tx_decode**: Human-readable transactions, with a wallet's inputs and outputs marked
 
+pub mod amount;
+#[cfg(feature = "network")]
+pub mod broadcast;
+#[cfg(feature = "network")]
+pub mod compact_filters;
+pub mod descriptor;
+#[cfg(feature = "network")]
+pub mod electrum;
+#[cfg(feature = "network")]
+pub mod esplora;
+#[cfg(feature = "network")]
+pub mod faucet;
+#[cfg(feature = "network")]
+pub mod fiat;
 pub mod hd_address;
+#[cfg(feature = "network")]
+pub mod http;
+pub mod inscriptions;
+pub mod lightning;
+#[cfg(feature = "network")]
+pub mod monitor;
+#[cfg(feature = "network")]
+pub mod rebroadcast;
 pub mod schnorr;
+pub mod script_index;
+pub mod sighash;
+#[cfg(feature = "network")]
+pub mod simulate;
+pub mod spend_plan;
 pub mod taproot_scripts;
+pub mod taproot_tree;
+#[cfg(feature = "network")]
 pub mod transaction;
+pub mod tx_decode;
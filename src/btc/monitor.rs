@@ -0,0 +1,349 @@
+//! Mempool Monitoring
+//!
+//! Polls the mempool.space backend for the confirmation status of a broadcast
+//! transaction. Used by:
+//!
+//! - `dkg-watch --txid` (blocking poll until the target confirmation count)
+//! - the TUI, which runs [`spawn_watcher`] in a background thread per broadcast
+//!
+//! On first confirmation the wallet's webhooks (see [`crate::notify`]) and a
+//! best-effort desktop notification can be fired via [`notify_confirmation`].
+
+use crate::btc::transaction::{explorer_tx_url, fetch_tip_height, fetch_tx_status, TxStatus};
+use crate::notify::{self, NotifyEvent, Webhook};
+use crate::protocol::keygen::get_state_dir;
+use crate::storage::FileStorage;
+use crate::CommandResult;
+use anyhow::Result;
+use bitcoin::Network;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::Sender;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Default poll interval in seconds
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 30;
+
+/// Where a watched transaction currently is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchState {
+    /// Backend does not know the transaction
+    NotFound,
+    /// In the mempool, unconfirmed
+    Pending,
+    /// Mined
+    Confirmed {
+        block_height: u64,
+        confirmations: u64,
+    },
+}
+
+/// Update sent from a background watcher
+#[derive(Debug, Clone)]
+pub struct TxWatchUpdate {
+    pub txid: String,
+    pub state: WatchState,
+}
+
+/// Output from dkg-watch command
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatchOutput {
+    pub txid: String,
+    pub confirmed: bool,
+    pub block_height: Option<u64>,
+    pub block_hash: Option<String>,
+    pub confirmations: u64,
+    pub network: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Number of confirmations given a status and the current tip height
+pub fn confirmations(status: &TxStatus, tip_height: u64) -> u64 {
+    match (status.confirmed, status.block_height) {
+        (true, Some(height)) if tip_height >= height => tip_height - height + 1,
+        (true, _) => 1,
+        _ => 0,
+    }
+}
+
+/// Query the backend once and classify the transaction
+pub fn poll_tx_state(txid: &str, network: Network) -> Result<(WatchState, Option<TxStatus>)> {
+    let Some(status) = fetch_tx_status(txid, network)? else {
+        return Ok((WatchState::NotFound, None));
+    };
+    if !status.confirmed {
+        return Ok((WatchState::Pending, Some(status)));
+    }
+
+    // Tip lookup is only for the confirmation count; fall back to 1
+    let tip = fetch_tip_height(network).unwrap_or(0);
+    let state = WatchState::Confirmed {
+        block_height: status.block_height.unwrap_or(0),
+        confirmations: confirmations(&status, tip),
+    };
+    Ok((state, Some(status)))
+}
+
+/// Spawn a background thread that reports the state of `txid` every `interval`
+///
+/// The thread exits after reporting the first confirmation, or as soon as the
+/// receiving end of `sender` has been dropped. Network errors are retried.
+pub fn spawn_watcher(
+    txid: String,
+    network: Network,
+    interval: Duration,
+    sender: Sender<TxWatchUpdate>,
+) -> JoinHandle<()> {
+    thread::spawn(move || loop {
+        if let Ok((state, _)) = poll_tx_state(&txid, network) {
+            let confirmed = matches!(state, WatchState::Confirmed { .. });
+            let update = TxWatchUpdate {
+                txid: txid.clone(),
+                state,
+            };
+            if sender.send(update).is_err() || confirmed {
+                return;
+            }
+        }
+        thread::sleep(interval);
+    })
+}
+
+// ============================================================================
+// Notifications
+// ============================================================================
+
+/// Fire notifications for a first confirmation
+///
+/// Failures are reported in the returned lines rather than as errors so that a
+/// broken webhook never hides the confirmation itself.
+pub fn notify_confirmation(
+    wallet: &str,
+    txid: &str,
+    block_height: u64,
+    network: Network,
+    webhooks: &[Webhook],
+    desktop: bool,
+) -> Vec<String> {
+    let event = NotifyEvent::TxConfirmed {
+        txid: txid.to_string(),
+        block_height,
+        network: network.to_string(),
+    };
+    let mut report = notify::dispatch(webhooks, wallet, &event);
+
+    if desktop {
+        if let Err(e) = notify::desktop_notify("FrostDAO", &event.message(wallet)) {
+            report.push(format!("⚠️  Desktop notification failed: {}", e));
+        }
+    }
+
+    report
+}
+
+// ============================================================================
+// dkg-watch
+// ============================================================================
+
+/// Options for [`watch_tx_core`]
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Poll interval in seconds
+    pub interval_secs: u64,
+    /// Give up after this many seconds (0 = never)
+    pub timeout_secs: u64,
+    /// Confirmations to wait for
+    pub target_confirmations: u64,
+    /// Label used in notification messages (wallet name)
+    pub label: String,
+    /// Webhooks fired on first confirmation
+    pub webhooks: Vec<Webhook>,
+    /// Show a desktop notification on first confirmation
+    pub desktop: bool,
+}
+
+/// Core function for watching a transaction until it confirms
+///
+/// `timeout_secs = 0` waits indefinitely. This is a long-running command, so
+/// each progress line is passed to `on_line` as it happens and also collected
+/// in the output.
+pub fn watch_tx_core(
+    txid: &str,
+    network: Network,
+    opts: &WatchOptions,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<CommandResult> {
+    let WatchOptions {
+        interval_secs,
+        timeout_secs,
+        target_confirmations,
+        ..
+    } = *opts;
+    let mut log = |out: &mut String, line: String| {
+        on_line(&line);
+        out.push_str(&line);
+        out.push('\n');
+    };
+
+    let mut out = String::new();
+
+    log(&mut out, "Transaction Watch\n".to_string());
+    log(
+        &mut out,
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━".to_string(),
+    );
+    log(&mut out, format!("TxID: {}", txid));
+    log(
+        &mut out,
+        format!("Explorer: {}", explorer_tx_url(txid, network)),
+    );
+    log(
+        &mut out,
+        format!(
+            "Waiting for {} confirmation(s), polling every {}s\n",
+            target_confirmations, interval_secs
+        ),
+    );
+
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let started = Instant::now();
+    let mut last_state: Option<WatchState> = None;
+    let mut notified = false;
+
+    let final_status = loop {
+        match poll_tx_state(txid, network) {
+            Ok((state, status)) => {
+                if last_state.as_ref() != Some(&state) {
+                    let line = match &state {
+                        WatchState::NotFound => "❓ Not found on backend (yet)".to_string(),
+                        WatchState::Pending => "⏳ In mempool, unconfirmed".to_string(),
+                        WatchState::Confirmed {
+                            block_height,
+                            confirmations,
+                        } => format!(
+                            "✅ Confirmed in block {} ({} confirmation(s))",
+                            block_height, confirmations
+                        ),
+                    };
+                    log(&mut out, line);
+                }
+
+                if let WatchState::Confirmed {
+                    block_height,
+                    confirmations,
+                } = state
+                {
+                    if !notified {
+                        notified = true;
+                        for line in notify_confirmation(
+                            &opts.label,
+                            txid,
+                            block_height,
+                            network,
+                            &opts.webhooks,
+                            opts.desktop,
+                        ) {
+                            log(&mut out, line);
+                        }
+                    }
+                    if confirmations >= target_confirmations {
+                        break (status.unwrap_or_default(), confirmations);
+                    }
+                }
+                last_state = Some(state);
+            }
+            Err(e) => log(&mut out, format!("⚠️  Poll failed: {}", e)),
+        }
+
+        if timeout_secs > 0 && started.elapsed() >= Duration::from_secs(timeout_secs) {
+            anyhow::bail!(
+                "Timed out after {}s waiting for {} to confirm",
+                timeout_secs,
+                txid
+            );
+        }
+        thread::sleep(interval);
+    };
+
+    let (status, confs) = final_status;
+    let output = WatchOutput {
+        txid: txid.to_string(),
+        confirmed: status.confirmed,
+        block_height: status.block_height,
+        block_hash: status.block_hash,
+        confirmations: confs,
+        network: network.to_string(),
+        event_type: "tx_watch".to_string(),
+    };
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&output)?,
+    })
+}
+
+/// CLI wrapper for dkg-watch
+///
+/// With `wallet`, that wallet's configured webhooks are fired too; `webhook`
+/// adds a one-off URL whose payload format is guessed from the host.
+#[allow(clippy::too_many_arguments)]
+pub fn dkg_watch(
+    txid: &str,
+    network: Network,
+    interval_secs: u64,
+    timeout_secs: u64,
+    target_confirmations: u64,
+    wallet: Option<&str>,
+    webhook: Option<&str>,
+    desktop: bool,
+) -> Result<()> {
+    let mut webhooks = match wallet {
+        Some(name) => {
+            let storage = FileStorage::new(&get_state_dir(name))?;
+            notify::load_webhooks(&storage)?
+        }
+        None => Vec::new(),
+    };
+    if let Some(url) = webhook {
+        webhooks.push(Webhook {
+            url: url.to_string(),
+            kind: notify::WebhookKind::from_url(url),
+            events: Vec::new(),
+        });
+    }
+
+    let opts = WatchOptions {
+        interval_secs,
+        timeout_secs,
+        target_confirmations,
+        label: wallet.unwrap_or("frostdao").to_string(),
+        webhooks,
+        desktop,
+    };
+    let cmd_result = watch_tx_core(txid, network, &opts, &mut |line| println!("{}", line))?;
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}\n", cmd_result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_count() {
+        let pending = TxStatus::default();
+        assert_eq!(confirmations(&pending, 800_000), 0);
+
+        let mined = TxStatus {
+            confirmed: true,
+            block_height: Some(800_000),
+            ..Default::default()
+        };
+        assert_eq!(confirmations(&mined, 800_000), 1);
+        assert_eq!(confirmations(&mined, 800_005), 6);
+        // Tip lookup failed (0) or lagging backend: still counts as confirmed
+        assert_eq!(confirmations(&mined, 0), 1);
+    }
+}
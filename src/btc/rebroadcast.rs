@@ -0,0 +1,547 @@
+//! Rebroadcast and Stuck Transactions
+//!
+//! Every transaction a wallet broadcasts is kept in its folder's
+//! `pending_txs.json` until the backend reports it confirmed. Checking the
+//! list (`dkg-rebroadcast`, and the TUI every
+//! [`REBROADCAST_INTERVAL_SECS`]) sorts each transaction into one of
+//!
+//! - **unconfirmed**: in the mempool; pushed again so nodes that dropped or
+//!   never saw it relay it
+//! - **evicted**: the backend no longer knows it but its coins are unspent
+//!   (mempool full, expiry, node restart); pushed again
+//! - **conflicted**: another transaction spends one of its coins, so it can
+//!   never confirm; kept until forgotten (`--forget`), never pushed
+//! - **confirmed**: mined; dropped from the list
+//!
+//! Transactions unconfirmed for longer than [`STUCK_AFTER_SECS`] are flagged
+//! as stuck: rebroadcasting will not help if the fee is too low.
+
+use crate::btc::broadcast::EndpointResult;
+use crate::btc::esplora::{self, network_key};
+use crate::btc::hd_address::parse_network;
+use crate::btc::simulate;
+use crate::btc::transaction::{ChainBackend, MempoolBackend};
+use crate::protocol::keygen::get_state_dir;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use bitcoin::{Network, Transaction};
+use serde::{Deserialize, Serialize};
+
+pub const PENDING_TXS_FILE: &str = "pending_txs.json";
+
+/// Seconds unconfirmed after which a transaction counts as stuck
+pub const STUCK_AFTER_SECS: u64 = 24 * 60 * 60;
+
+/// Seconds between the TUI's background checks
+pub const REBROADCAST_INTERVAL_SECS: u64 = 30 * 60;
+
+/// What the backend says about a pending transaction
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxFate {
+    Unconfirmed,
+    Evicted,
+    Conflicted,
+    Confirmed,
+}
+
+impl TxFate {
+    /// Whether the transaction can still confirm and is worth pushing again
+    pub fn is_live(self) -> bool {
+        matches!(self, TxFate::Unconfirmed | TxFate::Evicted)
+    }
+}
+
+/// A broadcast transaction waiting to confirm
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PendingTx {
+    pub txid: String,
+    pub raw_tx: String,
+    /// `mainnet`, `testnet`, `signet` or `regtest`
+    pub network: String,
+    /// Unix time of the first broadcast
+    pub broadcast_at: u64,
+    /// Unix time it was last pushed again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rebroadcast_at: Option<u64>,
+    #[serde(default)]
+    pub rebroadcasts: u32,
+    pub state: TxFate,
+    /// The transaction that spent its coins instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+}
+
+/// A wallet folder's `pending_txs.json`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingTxs {
+    #[serde(default)]
+    pub txs: Vec<PendingTx>,
+}
+
+impl PendingTxs {
+    pub fn load(storage: &dyn Storage) -> Result<Self> {
+        if !storage.exists(PENDING_TXS_FILE) {
+            return Ok(Self::default());
+        }
+        serde_json::from_slice(&storage.read(PENDING_TXS_FILE)?)
+            .with_context(|| format!("{} is damaged", PENDING_TXS_FILE))
+    }
+
+    fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.write(
+            PENDING_TXS_FILE,
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// Stop tracking `txid`; false if it was not tracked
+    pub fn forget(&mut self, txid: &str) -> bool {
+        let before = self.txs.len();
+        self.txs.retain(|tx| tx.txid != txid);
+        self.txs.len() != before
+    }
+}
+
+/// Start tracking a transaction the backend accepted
+///
+/// Nothing is recorded while simulating, so demo transactions never reach a
+/// real network's rebroadcast.
+pub fn record(storage: &dyn Storage, raw_tx_hex: &str, network: Network, now: u64) -> Result<()> {
+    if simulate::is_simulated() {
+        return Ok(());
+    }
+    let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(raw_tx_hex)
+        .context("Broadcast transaction does not decode")?;
+    let txid = tx.compute_txid().to_string();
+    let mut pending = PendingTxs::load(storage)?;
+    if pending.txs.iter().any(|tx| tx.txid == txid) {
+        return Ok(());
+    }
+    pending.txs.push(PendingTx {
+        txid,
+        raw_tx: raw_tx_hex.to_string(),
+        network: network_key(network).to_string(),
+        broadcast_at: now,
+        rebroadcast_at: None,
+        rebroadcasts: 0,
+        state: TxFate::Unconfirmed,
+        replaced_by: None,
+    });
+    pending.save(storage)
+}
+
+/// Where the backend puts `tx`: its fate, block height and replacement
+fn classify(
+    tx: &Transaction,
+    backend: &dyn ChainBackend,
+) -> Result<(TxFate, Option<u64>, Option<String>)> {
+    let txid = tx.compute_txid().to_string();
+    if let Some(status) = backend.tx_status(&txid)? {
+        return Ok(match status.confirmed {
+            true => (TxFate::Confirmed, status.block_height, None),
+            false => (TxFate::Unconfirmed, None, None),
+        });
+    }
+    for input in &tx.input {
+        match backend.spender(&input.previous_output)? {
+            // Spent by this very transaction: the status lookup lagged
+            Some(spender) if spender == txid => return Ok((TxFate::Unconfirmed, None, None)),
+            Some(spender) => return Ok((TxFate::Conflicted, None, Some(spender))),
+            None => {}
+        }
+    }
+    Ok((TxFate::Evicted, None, None))
+}
+
+/// What one check found for a pending transaction
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct TxCheck {
+    pub txid: String,
+    /// What the backend says now
+    pub state: TxFate,
+    /// State recorded by the previous check
+    pub previous: TxFate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+    /// Seconds since the first broadcast
+    pub age_secs: u64,
+    pub stuck: bool,
+    /// Answer of each endpoint it was pushed to again
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rebroadcast: Vec<EndpointResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TxCheck {
+    /// Whether any endpoint took the transaction when it was pushed again
+    pub fn rebroadcast_accepted(&self) -> bool {
+        self.rebroadcast.iter().any(|r| r.accepted)
+    }
+}
+
+/// Check the pending transactions on `network` (or just `txid`), push the
+/// live ones again and save what was found
+///
+/// An unreachable backend stops the check and is returned as the error;
+/// anything else wrong with one transaction is reported in its [`TxCheck`].
+pub fn check_pending(
+    storage: &dyn Storage,
+    network: Network,
+    backend: &dyn ChainBackend,
+    txid: Option<&str>,
+    now: u64,
+) -> Result<Vec<TxCheck>> {
+    let mut pending = PendingTxs::load(storage)?;
+    let mut checks = Vec::new();
+    let mut unreachable = None;
+
+    for entry in pending.txs.iter_mut() {
+        if entry.network != network_key(network) || txid.is_some_and(|t| t != entry.txid) {
+            continue;
+        }
+        let mut check = TxCheck {
+            txid: entry.txid.clone(),
+            state: entry.state,
+            previous: entry.state,
+            block_height: None,
+            replaced_by: entry.replaced_by.clone(),
+            age_secs: now.saturating_sub(entry.broadcast_at),
+            stuck: false,
+            rebroadcast: Vec::new(),
+            error: None,
+        };
+
+        let found = bitcoin::consensus::encode::deserialize_hex::<Transaction>(&entry.raw_tx)
+            .context("Stored transaction does not decode")
+            .and_then(|tx| classify(&tx, backend));
+        match found {
+            Ok((state, block_height, replaced_by)) => {
+                check.state = state;
+                check.block_height = block_height;
+                check.replaced_by = replaced_by;
+            }
+            Err(e) if esplora::is_unreachable(&e) => {
+                unreachable = Some(e);
+                break;
+            }
+            Err(e) => check.error = Some(format!("{:#}", e)),
+        }
+
+        if check.error.is_none() && check.state.is_live() {
+            let report = backend.broadcast_report(&entry.raw_tx);
+            check.rebroadcast = report.results.clone();
+            entry.rebroadcast_at = Some(now);
+            entry.rebroadcasts += 1;
+            match report.result() {
+                Ok(_) => entry.state = TxFate::Unconfirmed,
+                Err(e) => {
+                    check.error = Some(format!("{:#}", e));
+                    entry.state = check.state;
+                }
+            }
+            check.stuck = check.age_secs >= STUCK_AFTER_SECS;
+        } else if check.error.is_none() {
+            entry.state = check.state;
+            entry.replaced_by = check.replaced_by.clone();
+        }
+        checks.push(check);
+    }
+
+    pending.txs.retain(|tx| tx.state != TxFate::Confirmed);
+    pending.save(storage)?;
+    match unreachable {
+        Some(e) => Err(e),
+        None => Ok(checks),
+    }
+}
+
+/// Output from dkg-rebroadcast command
+#[derive(Serialize, Debug, Clone)]
+pub struct RebroadcastOutput {
+    pub network: String,
+    pub txs: Vec<TxCheck>,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Describe an age in hours, or minutes under an hour
+fn describe_age(secs: u64) -> String {
+    match secs / 3600 {
+        0 => format!("{}m", secs / 60),
+        hours => format!("{}h", hours),
+    }
+}
+
+/// Core function for dkg-rebroadcast
+pub fn rebroadcast_core(
+    storage: &dyn Storage,
+    network: Network,
+    backend: &dyn ChainBackend,
+    txid: Option<&str>,
+    now: u64,
+) -> Result<CommandResult> {
+    let checks = check_pending(storage, network, backend, txid, now)?;
+    if let (Some(txid), true) = (txid, checks.is_empty()) {
+        bail!(crate::error::Error::User(format!(
+            "No pending {} transaction {}",
+            network_key(network),
+            txid
+        )));
+    }
+
+    let mut out = String::new();
+    out.push_str("Pending Transactions\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    if checks.is_empty() {
+        out.push_str("Nothing waiting to confirm.\n");
+    }
+    for check in &checks {
+        out.push_str(&format!(
+            "\n{} (broadcast {} ago)\n",
+            check.txid,
+            describe_age(check.age_secs)
+        ));
+        let line = match check.state {
+            TxFate::Confirmed => format!(
+                "✅ Confirmed in block {}; no longer tracked",
+                check.block_height.unwrap_or(0)
+            ),
+            TxFate::Unconfirmed => "⏳ In mempool, unconfirmed".to_string(),
+            TxFate::Evicted => "⚠️  Dropped from the mempool; its coins are unspent".to_string(),
+            TxFate::Conflicted => format!(
+                "❌ Replaced by {}; it can no longer confirm (forget it with --forget)",
+                check
+                    .replaced_by
+                    .as_deref()
+                    .unwrap_or("another transaction")
+            ),
+        };
+        out.push_str(&format!("   {}\n", line));
+        if !check.rebroadcast.is_empty() {
+            out.push_str("   Pushed again:\n");
+            for result in &check.rebroadcast {
+                out.push_str(&format!("      {}\n", result.describe()));
+            }
+        }
+        if check.stuck {
+            out.push_str(
+                "   ⏰ Stuck for over a day: the fee is likely too low for the current mempool\n",
+            );
+        }
+        if let Some(error) = &check.error {
+            out.push_str(&format!("   ⚠️  {}\n", error));
+        }
+    }
+
+    let output = RebroadcastOutput {
+        network: network_key(network).to_string(),
+        txs: checks,
+        event_type: "rebroadcast".to_string(),
+    };
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&output)?,
+    })
+}
+
+/// CLI wrapper for dkg-rebroadcast
+pub fn dkg_rebroadcast(name: &str, network: &str, txid: Option<&str>, forget: bool) -> Result<()> {
+    let network = parse_network(network)?;
+    let storage = FileStorage::new(&get_state_dir(name))?;
+
+    if forget {
+        let txid = txid.context("--forget needs --txid")?;
+        let mut pending = PendingTxs::load(&storage)?;
+        if !pending.forget(txid) {
+            bail!(crate::error::Error::User(format!(
+                "No pending transaction {}",
+                txid
+            )));
+        }
+        pending.save(&storage)?;
+        println!("Stopped tracking {}", txid);
+        return Ok(());
+    }
+    if simulate::is_simulated() {
+        bail!(crate::error::Error::User(
+            "Simulated transactions are not tracked for rebroadcast".to_string()
+        ));
+    }
+
+    let result = rebroadcast_core(
+        &storage,
+        network,
+        &MempoolBackend::new(network),
+        txid,
+        crate::protocol::health::now(),
+    )?;
+    println!("{}", result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}\n", result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::transaction::TxStatus;
+    use crate::storage::MemoryStorage;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::hashes::Hash;
+    use bitcoin::transaction::Version;
+    use bitcoin::{Amount, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid, Witness};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// Chain whose answers the test sets
+    #[derive(Default)]
+    struct FakeChain {
+        statuses: HashMap<String, TxStatus>,
+        spenders: HashMap<OutPoint, String>,
+        reject: bool,
+        pushed: RefCell<Vec<String>>,
+    }
+
+    impl ChainBackend for FakeChain {
+        fn utxos(&self, _: &str) -> Result<Vec<crate::btc::transaction::UtxoResponse>> {
+            Ok(Vec::new())
+        }
+
+        fn fee_estimates(&self) -> Result<crate::btc::transaction::FeeEstimate> {
+            bail!("no fees")
+        }
+
+        fn broadcast(&self, raw_tx_hex: &str) -> Result<String> {
+            self.pushed.borrow_mut().push(raw_tx_hex.to_string());
+            if self.reject {
+                bail!("min relay fee not met");
+            }
+            let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(raw_tx_hex)?;
+            Ok(tx.compute_txid().to_string())
+        }
+
+        fn tx_status(&self, txid: &str) -> Result<Option<TxStatus>> {
+            Ok(self.statuses.get(txid).cloned())
+        }
+
+        fn spender(&self, outpoint: &OutPoint) -> Result<Option<String>> {
+            Ok(self.spenders.get(outpoint).cloned())
+        }
+    }
+
+    fn spend(seed: u8) -> (Transaction, OutPoint) {
+        let outpoint = OutPoint::new(Txid::from_byte_array([seed; 32]), 0);
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::from_slice(&[[seed; 64]]),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(10_000),
+                script_pubkey: ScriptBuf::new_op_return([seed]),
+            }],
+        };
+        (tx, outpoint)
+    }
+
+    fn hex(tx: &Transaction) -> String {
+        bitcoin::consensus::encode::serialize_hex(tx)
+    }
+
+    #[test]
+    fn test_pending_transactions_are_pushed_until_confirmed() {
+        let storage = MemoryStorage::new();
+        let (tx, _) = spend(1);
+        let txid = tx.compute_txid().to_string();
+        record(&storage, &hex(&tx), Network::Testnet, 1_000).unwrap();
+        record(&storage, &hex(&tx), Network::Testnet, 2_000).unwrap();
+        assert_eq!(PendingTxs::load(&storage).unwrap().txs.len(), 1);
+
+        // In the mempool: pushed again, still tracked
+        let mut chain = FakeChain::default();
+        chain.statuses.insert(txid.clone(), TxStatus::default());
+        let checks = check_pending(&storage, Network::Testnet, &chain, None, 3_000).unwrap();
+        assert_eq!(checks[0].state, TxFate::Unconfirmed);
+        assert!(checks[0].rebroadcast_accepted());
+        assert_eq!(chain.pushed.borrow().len(), 1);
+        let pending = PendingTxs::load(&storage).unwrap();
+        assert_eq!(pending.txs[0].rebroadcasts, 1);
+        assert_eq!(pending.txs[0].rebroadcast_at, Some(3_000));
+
+        // Other networks are left alone
+        let checks = check_pending(&storage, Network::Signet, &chain, None, 3_000).unwrap();
+        assert!(checks.is_empty());
+
+        // A day later it is stuck
+        let checks = check_pending(&storage, Network::Testnet, &chain, None, 90_000).unwrap();
+        assert!(checks[0].stuck);
+
+        // Confirmed: reported once, then dropped
+        chain.statuses.insert(
+            txid.clone(),
+            TxStatus {
+                confirmed: true,
+                block_height: Some(800_000),
+                ..Default::default()
+            },
+        );
+        let checks = check_pending(&storage, Network::Testnet, &chain, None, 95_000).unwrap();
+        assert_eq!(checks[0].state, TxFate::Confirmed);
+        assert_eq!(checks[0].block_height, Some(800_000));
+        assert_eq!(chain.pushed.borrow().len(), 2);
+        assert!(PendingTxs::load(&storage).unwrap().txs.is_empty());
+    }
+
+    #[test]
+    fn test_evicted_and_conflicted_transactions() {
+        let storage = MemoryStorage::new();
+        let (evicted, _) = spend(1);
+        let (replaced, replaced_input) = spend(2);
+        record(&storage, &hex(&evicted), Network::Testnet, 0).unwrap();
+        record(&storage, &hex(&replaced), Network::Testnet, 0).unwrap();
+
+        // The backend knows neither; the second one's coin went elsewhere
+        let mut chain = FakeChain::default();
+        chain.spenders.insert(replaced_input, "ab".repeat(32));
+        let checks = check_pending(&storage, Network::Testnet, &chain, None, 60).unwrap();
+        assert_eq!(checks[0].state, TxFate::Evicted);
+        assert_eq!(checks[0].previous, TxFate::Unconfirmed);
+        assert!(checks[0].rebroadcast_accepted());
+        assert_eq!(checks[1].state, TxFate::Conflicted);
+        assert_eq!(checks[1].replaced_by, Some("ab".repeat(32)));
+        assert!(checks[1].rebroadcast.is_empty());
+        assert_eq!(*chain.pushed.borrow(), vec![hex(&evicted)]);
+
+        // Pushed back in, the evicted one counts as unconfirmed again; the
+        // conflicted one stays conflicted and is never pushed
+        let pending = PendingTxs::load(&storage).unwrap();
+        assert_eq!(pending.txs[0].state, TxFate::Unconfirmed);
+        assert_eq!(pending.txs[1].state, TxFate::Conflicted);
+
+        // An evicted transaction the backend refuses keeps its state
+        chain.reject = true;
+        let checks = check_pending(&storage, Network::Testnet, &chain, None, 120).unwrap();
+        assert!(!checks[0].rebroadcast_accepted());
+        assert!(checks[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("min relay fee"));
+        let mut pending = PendingTxs::load(&storage).unwrap();
+        assert_eq!(pending.txs[0].state, TxFate::Evicted);
+
+        let txid = replaced.compute_txid().to_string();
+        assert!(pending.forget(&txid));
+        assert!(!pending.forget(&txid));
+        assert_eq!(pending.txs.len(), 1);
+    }
+}
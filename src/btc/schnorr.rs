@@ -12,6 +12,8 @@
 //! References:
 //! - BIP340: https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki
 
+use crate::crypto::secret::{hex_secret, SecretString};
+use crate::protocol::keygen::require_secp256k1;
 use crate::storage::{FileStorage, Storage};
 use crate::CommandResult;
 use anyhow::{Context, Result};
@@ -21,6 +23,7 @@ use bitcoin::Network;
 use rand::RngCore;
 use secp256kfun::prelude::*;
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
 // sha2 no longer needed - using shared tagged_hash from crypto_helpers
 
 const STATE_DIR: &str = ".frost_state";
@@ -85,12 +88,18 @@ pub struct BitcoinSignatureOutput {
 }
 
 /// Stored key material
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize)]
 struct StoredBitcoinKey {
     secret_key_bytes: Vec<u8>,
     public_key_bytes: Vec<u8>,
 }
 
+impl Drop for StoredBitcoinKey {
+    fn drop(&mut self) {
+        self.secret_key_bytes.zeroize();
+    }
+}
+
 // ============================================================================
 // Key Generation
 // ============================================================================
@@ -152,7 +161,7 @@ pub fn generate_keypair_core(storage: &dyn Storage) -> Result<CommandResult> {
         secret_key_bytes: secret_bytes.to_vec(),
         public_key_bytes: pubkey_bytes.to_vec(),
     };
-    let stored_json = serde_json::to_string(&stored_key)?;
+    let stored_json = SecretString::new(serde_json::to_string(&stored_key)?);
     storage.write("bitcoin_keypair.json", stored_json.as_bytes())?;
 
     out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
@@ -226,7 +235,7 @@ pub fn import_key_core(secret_hex: &str, storage: &dyn Storage) -> Result<Comman
         secret_key_bytes: final_secret.to_bytes().to_vec(),
         public_key_bytes: pubkey_bytes.to_vec(),
     };
-    let stored_json = serde_json::to_string(&stored_key)?;
+    let stored_json = SecretString::new(serde_json::to_string(&stored_key)?);
     storage.write("bitcoin_keypair.json", stored_json.as_bytes())?;
 
     out.push_str("Key imported successfully!\n\n");
@@ -245,6 +254,17 @@ pub fn import_key_core(secret_hex: &str, storage: &dyn Storage) -> Result<Comman
     })
 }
 
+/// Secret key saved by `btc-keygen` or `btc-import-key`, as hex
+pub fn stored_secret_hex(storage: &dyn Storage) -> Result<SecretString> {
+    let stored_json = SecretString::from_utf8(
+        storage
+            .read_secret("bitcoin_keypair.json")
+            .context("No Bitcoin key found. Run btc-keygen or btc-import-key first")?,
+    )?;
+    let stored_key: StoredBitcoinKey = serde_json::from_str(&stored_json)?;
+    Ok(hex_secret(&stored_key.secret_key_bytes))
+}
+
 /// CLI wrapper for key import
 pub fn import_key(secret_hex: &str) -> Result<()> {
     let storage = FileStorage::new(STATE_DIR)?;
@@ -272,15 +292,17 @@ pub fn sign_message_core(
     out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     // Load keypair
-    let stored_json = String::from_utf8(storage.read("bitcoin_keypair.json")?)?;
+    let stored_json = SecretString::from_utf8(storage.read_secret("bitcoin_keypair.json")?)?;
     let stored_key: StoredBitcoinKey = serde_json::from_str(&stored_json)?;
 
     let secret_bytes: [u8; 32] = stored_key
         .secret_key_bytes
+        .as_slice()
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid secret key length"))?;
     let pubkey_bytes: [u8; 32] = stored_key
         .public_key_bytes
+        .as_slice()
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid public key length"))?;
 
@@ -312,6 +334,7 @@ pub fn sign_message_core(
 
     // Step 2: Generate nonce
     let k_bytes = nonce_hash(&masked_secret, &pubkey_bytes, message);
+    masked_secret.zeroize();
     let mut k_scalar: Scalar<Secret, NonZero> = Scalar::from_bytes(k_bytes)
         .ok_or_else(|| anyhow::anyhow!("Invalid nonce bytes"))?
         .non_zero()
@@ -520,9 +543,9 @@ pub fn verify_signature_hex(
 pub fn get_public_key_core(storage: &dyn Storage) -> Result<CommandResult> {
     let mut out = String::new();
 
-    let stored_json = String::from_utf8(
+    let stored_json = SecretString::from_utf8(
         storage
-            .read("bitcoin_keypair.json")
+            .read_secret("bitcoin_keypair.json")
             .context("No keypair found. Run btc-keygen first.")?,
     )?;
     let stored_key: StoredBitcoinKey = serde_json::from_str(&stored_json)?;
@@ -638,15 +661,16 @@ pub fn get_address_core(network: Network, storage: &dyn Storage) -> Result<Comma
     out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     // Load keypair
-    let stored_json = String::from_utf8(
+    let stored_json = SecretString::from_utf8(
         storage
-            .read("bitcoin_keypair.json")
+            .read_secret("bitcoin_keypair.json")
             .context("No keypair found. Run btc-keygen first.")?,
     )?;
     let stored_key: StoredBitcoinKey = serde_json::from_str(&stored_json)?;
 
     let pubkey_bytes: [u8; 32] = stored_key
         .public_key_bytes
+        .as_slice()
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid public key length"))?;
 
@@ -740,6 +764,8 @@ pub fn get_dkg_address_core(network: Network, storage: &dyn Storage) -> Result<C
     out.push_str("DKG Group Taproot Address (P2TR)\n\n");
     out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
+    require_secp256k1(storage)?;
+
     // Load shared key from DKG
     let shared_key_bytes = storage
         .read("shared_key.bin")
@@ -805,12 +831,10 @@ pub fn get_dkg_address_testnet(name: &str) -> Result<()> {
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!(
+        anyhow::bail!(crate::error::Error::Storage(format!(
             "Wallet '{}' not found at {}. Did you run keygen-finalize with --name {}?",
-            name,
-            state_dir,
-            name
-        );
+            name, state_dir, name
+        )));
     }
 
     let storage = FileStorage::new(&state_dir)?;
@@ -0,0 +1,375 @@
+//! Owned Script Index
+//!
+//! Every scriptPubKey a wallet can receive to or spend from, derived from its
+//! own key material:
+//!
+//! - **root**: key-path output of the group key
+//! - **HD**: receive (`m/44'/0'/0'/0/*`) and change (`m/44'/0'/0'/1/*`) outputs
+//! - **script trees**: every address recorded in `taproot_trees.json`
+//!
+//! Each entry carries an output descriptor (`tr(KEY)` for key-path outputs,
+//! `rawtr(OUTPUT_KEY)` for script trees, whose leaves stay in
+//! `taproot_trees.json`). scriptPubKeys are the same on every network, so one
+//! index serves them all.
+//!
+//! The index lives in `script_index.json` and is rebuilt whenever the key
+//! material or address records it was built from change, so callers look a
+//! script up instead of comparing against one address.
+
+use crate::btc::hd_address::load_hd_context;
+use crate::btc::taproot_tree::{load_taproot_trees, TAPROOT_TREES_FILE};
+use crate::crypto::hd::{derive_at_path, DerivationPath};
+use crate::protocol::keygen::HdMetadata;
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use bitcoin::hashes::Hash;
+use bitcoin::key::{TapTweak, XOnlyPublicKey};
+use bitcoin::{Script, ScriptBuf};
+use schnorr_fun::frost::SharedKey;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// File in the wallet directory holding the index
+pub const SCRIPT_INDEX_FILE: &str = "script_index.json";
+
+/// HD addresses past the last one handed out (receive) or from the start
+/// (change) that are recognised without being listed
+pub const LOOKAHEAD: u32 = 20;
+
+/// What a wallet script is
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OwnedScript {
+    /// Where it comes from: "root", an HD path, or "<key>, script tree: <description>"
+    pub origin: String,
+    /// Output descriptor, without checksum
+    pub descriptor: String,
+}
+
+/// Key behind a wallet script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKey {
+    /// Key-path output of the group key
+    Root,
+    /// Key-path output of an HD-derived key
+    Hd(DerivationPath),
+    /// Output with a script tree, spent with the spend info in `taproot_trees.json`
+    Tree,
+}
+
+impl OwnedScript {
+    /// What key the script pays to, read back from its descriptor and origin
+    pub fn key(&self) -> Result<ScriptKey> {
+        if self.descriptor.starts_with("rawtr(") {
+            return Ok(ScriptKey::Tree);
+        }
+        if self.origin == "root" {
+            return Ok(ScriptKey::Root);
+        }
+        let path = self
+            .origin
+            .strip_prefix("m/44'/0'/0'/")
+            .and_then(|rest| rest.split_once('/'))
+            .and_then(|(change, index)| Some((change.parse().ok()?, index.parse().ok()?)));
+        match path {
+            Some((change, address_index)) => Ok(ScriptKey::Hd(DerivationPath {
+                change,
+                address_index,
+            })),
+            None => anyhow::bail!("Unknown script origin '{}'", self.origin),
+        }
+    }
+}
+
+/// All scripts of one wallet, keyed by scriptPubKey hex
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScriptIndex {
+    /// Hash of the files the index was built from
+    pub stamp: String,
+    /// Group (root) x-only key (hex)
+    pub root_key: String,
+    pub scripts: BTreeMap<String, OwnedScript>,
+}
+
+impl ScriptIndex {
+    /// Load the index, rebuilding and saving it if it is missing or stale
+    pub fn load(storage: &dyn Storage) -> Result<Self> {
+        let stamp = source_stamp(storage)?;
+        if storage.exists(SCRIPT_INDEX_FILE) {
+            if let Ok(index) = serde_json::from_slice::<Self>(&storage.read(SCRIPT_INDEX_FILE)?) {
+                if index.stamp == stamp {
+                    return Ok(index);
+                }
+            }
+        }
+        let index = Self::build(storage, stamp)?;
+        storage.write(
+            SCRIPT_INDEX_FILE,
+            serde_json::to_string_pretty(&index)?.as_bytes(),
+        )?;
+        Ok(index)
+    }
+
+    fn build(storage: &dyn Storage, stamp: String) -> Result<Self> {
+        let shared_key: SharedKey<EvenY> = bincode::deserialize(
+            &storage
+                .read("shared_key.bin")
+                .context("No DKG shared key found. Run keygen-finalize first.")?,
+        )
+        .context("Failed to deserialize shared key")?;
+        let root_key = XOnlyPublicKey::from_slice(&shared_key.public_key().to_xonly_bytes())?;
+        let mut index = Self {
+            stamp,
+            root_key: hex::encode(root_key.serialize()),
+            scripts: BTreeMap::new(),
+        };
+        index.insert_key(root_key, "root".to_string());
+
+        // Only a wallet without HD metadata, or with HD turned off, has no HD scripts
+        let hd: Option<HdMetadata> = if storage.exists("hd_metadata.json") {
+            Some(
+                serde_json::from_slice(&storage.read("hd_metadata.json")?)
+                    .context("Failed to parse hd_metadata.json")?,
+            )
+        } else {
+            None
+        };
+        if let Some(hd) = hd.filter(|hd| hd.hd_enabled) {
+            let context = load_hd_context(storage)?;
+            let receive = hd
+                .derived_count
+                .checked_add(LOOKAHEAD)
+                .context("derived_count in hd_metadata.json is out of range")?;
+            let paths = (0..receive)
+                .map(DerivationPath::receive)
+                .chain((0..LOOKAHEAD).map(DerivationPath::change));
+            for path in paths {
+                let derived = derive_at_path(&context, &path)?;
+                let key = XOnlyPublicKey::from_slice(&derived.public_key.to_xonly_bytes())?;
+                index.insert_key(key, path.to_full_string());
+            }
+        }
+
+        for tree in load_taproot_trees(storage)?.into_values() {
+            let key = match tree.derivation_path {
+                Some((change, address_index)) => DerivationPath {
+                    change,
+                    address_index,
+                }
+                .to_full_string(),
+                None => "root".to_string(),
+            };
+            let output_key = tree
+                .script_pubkey
+                .strip_prefix("5120")
+                .filter(|key| key.len() == 64)
+                .with_context(|| {
+                    format!(
+                        "Script tree '{}' in {} is not a P2TR output",
+                        tree.description, TAPROOT_TREES_FILE
+                    )
+                })?;
+            index.scripts.insert(
+                tree.script_pubkey.clone(),
+                OwnedScript {
+                    origin: format!("{}, script tree: {}", key, tree.description),
+                    descriptor: format!("rawtr({})", output_key),
+                },
+            );
+        }
+        Ok(index)
+    }
+
+    /// Record the key-path output of `key`
+    fn insert_key(&mut self, key: XOnlyPublicKey, origin: String) {
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        self.scripts.insert(
+            hex::encode(ScriptBuf::new_p2tr(&secp, key, None).as_bytes()),
+            OwnedScript {
+                origin,
+                descriptor: format!("tr({})", hex::encode(key.serialize())),
+            },
+        );
+    }
+
+    /// Also recognise the root key tweaked with `merkle_root`, for a script
+    /// tree that is not recorded locally (not saved)
+    pub fn with_root_tree(mut self, merkle_root: [u8; 32]) -> Result<Self> {
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let root_key = XOnlyPublicKey::from_slice(&hex::decode(&self.root_key)?)?;
+        let (output_key, _) = root_key.tap_tweak(
+            &secp,
+            Some(bitcoin::TapNodeHash::from_byte_array(merkle_root)),
+        );
+        self.scripts
+            .entry(hex::encode(
+                ScriptBuf::new_p2tr_tweaked(output_key).as_bytes(),
+            ))
+            .or_insert_with(|| OwnedScript {
+                origin: "root, script tree".to_string(),
+                descriptor: format!("rawtr({})", output_key),
+            });
+        Ok(self)
+    }
+
+    /// The wallet script for a scriptPubKey, if it is one
+    pub fn owner(&self, script: &Script) -> Option<&OwnedScript> {
+        self.scripts.get(&hex::encode(script.as_bytes()))
+    }
+
+    /// Like [`Self::owner`], for a scriptPubKey in hex
+    pub fn owner_hex(&self, script_hex: &str) -> Option<&OwnedScript> {
+        self.scripts.get(&script_hex.to_ascii_lowercase())
+    }
+}
+
+/// Hash of every file the index is derived from
+fn source_stamp(storage: &dyn Storage) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for file in ["shared_key.bin", "hd_metadata.json", TAPROOT_TREES_FILE] {
+        let bytes = if storage.exists(file) {
+            storage.read(file)?
+        } else {
+            Vec::new()
+        };
+        hasher.update((bytes.len() as u64).to_be_bytes());
+        hasher.update(&bytes);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::hd_address::derive_address_at_path;
+    use crate::storage::MemoryStorage;
+    use bitcoin::{Address, Network};
+    use schnorr_fun::frost::{self, chilldkg::simplepedpop};
+    use sha2::Sha256;
+    use std::str::FromStr;
+
+    fn script(address: &str) -> ScriptBuf {
+        Address::from_str(address)
+            .unwrap()
+            .assume_checked()
+            .script_pubkey()
+    }
+
+    #[test]
+    fn test_index_covers_hd_paths_and_follows_metadata() {
+        let frost = frost::new_with_deterministic_nonces::<Sha256>();
+        let (shared_key, _) =
+            simplepedpop::simulate_keygen(&frost.schnorr, 2, 3, 3, &mut rand::thread_rng());
+        let storage = MemoryStorage::new();
+        storage
+            .write(
+                "shared_key.bin",
+                &bincode::serialize(&shared_key.into_xonly()).unwrap(),
+            )
+            .unwrap();
+        let mut hd = HdMetadata {
+            chain_code: hex::encode([7u8; 32]),
+            hd_enabled: true,
+            mnemonic_hint: None,
+            derived_count: 2,
+        };
+        storage
+            .write("hd_metadata.json", &serde_json::to_vec(&hd).unwrap())
+            .unwrap();
+
+        let index = ScriptIndex::load(&storage).unwrap();
+        let root = XOnlyPublicKey::from_slice(&hex::decode(&index.root_key).unwrap()).unwrap();
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let root_script = ScriptBuf::new_p2tr(&secp, root, None);
+        assert_eq!(index.owner(&root_script).unwrap().origin, "root");
+        assert_eq!(
+            index.owner(&root_script).unwrap().key().unwrap(),
+            ScriptKey::Root
+        );
+
+        let (change, _) = derive_address_at_path(&storage, 1, 3, Network::Testnet).unwrap();
+        let owner = index.owner(&script(&change)).unwrap();
+        assert_eq!(owner.origin, "m/44'/0'/0'/1/3");
+        assert_eq!(
+            owner.key().unwrap(),
+            ScriptKey::Hd(DerivationPath::change(3))
+        );
+        assert!(owner.descriptor.starts_with("tr("));
+        // Same script on every network
+        let (mainnet, _) = derive_address_at_path(&storage, 1, 3, Network::Bitcoin).unwrap();
+        assert_eq!(script(&mainnet), script(&change));
+
+        let (beyond, _) =
+            derive_address_at_path(&storage, 0, 2 + LOOKAHEAD, Network::Testnet).unwrap();
+        assert!(index.owner(&script(&beyond)).is_none());
+        assert!(index
+            .owner(&script("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"))
+            .is_none());
+
+        // Handing out another receive address rebuilds the index on next load
+        hd.derived_count = 3;
+        storage
+            .write("hd_metadata.json", &serde_json::to_vec(&hd).unwrap())
+            .unwrap();
+        let index = ScriptIndex::load(&storage).unwrap();
+        assert!(index.owner(&script(&beyond)).is_some());
+
+        // A script tree on the root key the signer was not told about
+        let tree = index.clone().with_root_tree([0x42; 32]).unwrap();
+        assert_eq!(tree.scripts.len(), index.scripts.len() + 1);
+    }
+
+    #[test]
+    fn test_broken_records_are_errors_not_gaps() {
+        let frost = frost::new_with_deterministic_nonces::<Sha256>();
+        let (shared_key, _) =
+            simplepedpop::simulate_keygen(&frost.schnorr, 2, 3, 3, &mut rand::thread_rng());
+        let storage = MemoryStorage::new();
+        storage
+            .write(
+                "shared_key.bin",
+                &bincode::serialize(&shared_key.into_xonly()).unwrap(),
+            )
+            .unwrap();
+        // No HD metadata: root only
+        assert_eq!(ScriptIndex::load(&storage).unwrap().scripts.len(), 1);
+
+        // A corrupt hd_metadata.json is not read as "no HD addresses"
+        storage.write("hd_metadata.json", b"{").unwrap();
+        assert!(ScriptIndex::load(&storage).is_err());
+        let hd = HdMetadata {
+            chain_code: hex::encode([7u8; 32]),
+            hd_enabled: true,
+            mnemonic_hint: None,
+            derived_count: u32::MAX,
+        };
+        storage
+            .write("hd_metadata.json", &serde_json::to_vec(&hd).unwrap())
+            .unwrap();
+        let err = ScriptIndex::load(&storage).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+        storage.delete("hd_metadata.json").unwrap();
+
+        // Nor does a script tree with a short scriptPubKey panic
+        storage
+            .write(
+                TAPROOT_TREES_FILE,
+                serde_json::json!({
+                    "51": {
+                        "address": "",
+                        "script_pubkey": "51",
+                        "internal_key": "",
+                        "merkle_root": null,
+                        "description": "broken",
+                        "leaves": [],
+                    }
+                })
+                .to_string()
+                .as_bytes(),
+            )
+            .unwrap();
+        let err = ScriptIndex::load(&storage).unwrap_err();
+        assert!(err.to_string().contains("not a P2TR output"), "{}", err);
+    }
+}
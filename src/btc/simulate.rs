@@ -0,0 +1,297 @@
+//! Simulated Chain
+//!
+//! With the global `--simulate` flag every chain request (UTXOs, fees,
+//! transaction status, tip height, broadcast) is answered by a fake chain in
+//! `.frost_state/simulated_chain.json` instead of Esplora, so the whole
+//! keygen → fund → sign → broadcast → confirm flow runs without a network.
+//! It is meant for classrooms and flights, and nothing it prints is real:
+//!
+//! - the first time an address is looked up it is handed
+//!   [`DEMO_FUNDING_SATS`] in one confirmed UTXO (`dkg-faucet` adds more)
+//! - a block is "mined" every [`BLOCK_SECS`] seconds of wall-clock time
+//! - a broadcast must spend UTXOs the simulated chain knows; its outputs
+//!   become UTXOs that confirm with the next block
+//!
+//! No signature or script checks are made. The CLI prints a banner on
+//! stderr and the TUI labels its title bar while simulating.
+
+use crate::btc::transaction::{FeeEstimate, TxStatus, UtxoResponse, UtxoStatus};
+use crate::error::Error;
+use crate::protocol::health::now;
+use crate::protocol::wallet::STATE_ROOT;
+use crate::storage::{FileStorage, Storage};
+use anyhow::{bail, Context, Result};
+use bitcoin::{Address, Network, Transaction};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const SIM_CHAIN_FILE: &str = "simulated_chain.json";
+
+/// Coins an address gets the first time it is looked up
+pub const DEMO_FUNDING_SATS: u64 = 100_000;
+
+/// Seconds between simulated blocks
+pub const BLOCK_SECS: u64 = 10;
+
+/// Height of the simulated chain when it is created
+const START_HEIGHT: u64 = 100_000;
+
+/// Printed wherever a real chain would be named
+pub const LABEL: &str = "SIMULATED";
+
+static SIMULATE: AtomicBool = AtomicBool::new(false);
+
+/// Answer chain requests from the simulated chain for the rest of this run (`--simulate`)
+pub fn set_simulate(simulate: bool) {
+    SIMULATE.store(simulate, Ordering::Relaxed);
+}
+
+pub fn is_simulated() -> bool {
+    SIMULATE.load(Ordering::Relaxed)
+}
+
+/// One unspent output on the simulated chain
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimUtxo {
+    pub address: String,
+    pub txid: String,
+    pub vout: u32,
+    pub value: u64,
+    /// Block the output confirms in
+    pub height: u64,
+}
+
+/// The simulated chain's state
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimChain {
+    /// Unix time of the first block; one more every [`BLOCK_SECS`]
+    pub started_at: u64,
+    pub utxos: Vec<SimUtxo>,
+    /// Txid -> block it confirms in
+    pub txs: BTreeMap<String, u64>,
+    /// Addresses already handed their demo coins
+    pub funded: BTreeSet<String>,
+}
+
+impl SimChain {
+    pub fn load(storage: &dyn Storage, now: u64) -> Result<Self> {
+        if !storage.exists(SIM_CHAIN_FILE) {
+            return Ok(Self {
+                started_at: now,
+                ..Self::default()
+            });
+        }
+        serde_json::from_slice(&storage.read(SIM_CHAIN_FILE)?)
+            .with_context(|| format!("{} is damaged", SIM_CHAIN_FILE))
+    }
+
+    fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.write(
+            SIM_CHAIN_FILE,
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    pub fn tip(&self, now: u64) -> u64 {
+        START_HEIGHT + now.saturating_sub(self.started_at) / BLOCK_SECS
+    }
+
+    /// Add a funding output of `value` to `address`, confirmed at the tip
+    pub fn fund(&mut self, address: &str, value: u64, now: u64) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(b"frostdao-simulated-funding");
+        hasher.update(address.as_bytes());
+        hasher.update(self.txs.len().to_le_bytes());
+        let txid = hex::encode(hasher.finalize());
+        let height = self.tip(now);
+        self.utxos.push(SimUtxo {
+            address: address.to_string(),
+            txid: txid.clone(),
+            vout: 0,
+            value,
+            height,
+        });
+        self.txs.insert(txid.clone(), height);
+        self.funded.insert(address.to_string());
+        txid
+    }
+
+    /// Spend a transaction's inputs and add its outputs; returns its txid
+    pub fn broadcast(&mut self, raw_tx_hex: &str, network: Network, now: u64) -> Result<String> {
+        let bytes = hex::decode(raw_tx_hex.trim()).context("Transaction is not valid hex")?;
+        let tx: Transaction =
+            bitcoin::consensus::deserialize(&bytes).context("Transaction does not decode")?;
+        let txid = tx.compute_txid().to_string();
+        if self.txs.contains_key(&txid) {
+            return Ok(txid);
+        }
+
+        for input in &tx.input {
+            let outpoint = &input.previous_output;
+            let spent = self
+                .utxos
+                .iter()
+                .position(|u| u.txid == outpoint.txid.to_string() && u.vout == outpoint.vout);
+            match spent {
+                Some(index) => {
+                    self.utxos.remove(index);
+                }
+                None => bail!(Error::Network(format!(
+                    "Simulated broadcast rejected: {} is not an unspent output on the simulated chain",
+                    outpoint
+                ))),
+            }
+        }
+
+        let height = self.tip(now) + 1;
+        for (vout, output) in tx.output.iter().enumerate() {
+            if let Ok(address) = Address::from_script(&output.script_pubkey, network) {
+                self.utxos.push(SimUtxo {
+                    address: address.to_string(),
+                    txid: txid.clone(),
+                    vout: vout as u32,
+                    value: output.value.to_sat(),
+                    height,
+                });
+            }
+        }
+        self.txs.insert(txid.clone(), height);
+        Ok(txid)
+    }
+
+    pub fn utxos(&self, address: &str, now: u64) -> Vec<UtxoResponse> {
+        let tip = self.tip(now);
+        self.utxos
+            .iter()
+            .filter(|u| u.address == address)
+            .map(|u| UtxoResponse {
+                txid: u.txid.clone(),
+                vout: u.vout,
+                value: u.value,
+                status: UtxoStatus {
+                    confirmed: u.height <= tip,
+                    block_height: (u.height <= tip).then_some(u.height),
+                },
+            })
+            .collect()
+    }
+
+    pub fn tx_status(&self, txid: &str, now: u64) -> Option<TxStatus> {
+        let height = *self.txs.get(txid)?;
+        let confirmed = height <= self.tip(now);
+        Some(TxStatus {
+            confirmed,
+            block_height: confirmed.then_some(height),
+            block_hash: confirmed.then(|| hex::encode(Sha256::digest(height.to_le_bytes()))),
+            block_time: confirmed.then(|| self.started_at + (height - START_HEIGHT) * BLOCK_SECS),
+        })
+    }
+}
+
+fn with_chain<T>(f: impl FnOnce(&mut SimChain, u64) -> Result<T>) -> Result<T> {
+    let storage = FileStorage::new(STATE_ROOT)?;
+    let now = now();
+    let mut chain = SimChain::load(&storage, now)?;
+    let result = f(&mut chain, now)?;
+    chain.save(&storage)?;
+    Ok(result)
+}
+
+/// UTXOs of `address`, handing it demo coins the first time it is seen
+pub fn fetch_utxos(address: &str) -> Result<Vec<UtxoResponse>> {
+    with_chain(|chain, now| {
+        if !chain.funded.contains(address) && chain.utxos(address, now).is_empty() {
+            chain.fund(address, DEMO_FUNDING_SATS, now);
+        }
+        Ok(chain.utxos(address, now))
+    })
+}
+
+/// Send `value` sats to `address` (the simulated `dkg-faucet`); returns the txid
+pub fn fund(address: &str, value: u64) -> Result<String> {
+    with_chain(|chain, now| Ok(chain.fund(address, value, now)))
+}
+
+pub fn fetch_fee_estimates() -> FeeEstimate {
+    FeeEstimate {
+        fastest_fee: 5,
+        half_hour_fee: 3,
+        hour_fee: 2,
+        economy_fee: 1,
+        minimum_fee: 1,
+    }
+}
+
+pub fn fetch_tx_status(txid: &str) -> Result<Option<TxStatus>> {
+    with_chain(|chain, now| Ok(chain.tx_status(txid, now)))
+}
+
+pub fn fetch_tip_height() -> Result<u64> {
+    with_chain(|chain, now| Ok(chain.tip(now)))
+}
+
+pub fn broadcast_transaction(raw_tx_hex: &str, network: Network) -> Result<String> {
+    with_chain(|chain, now| chain.broadcast(raw_tx_hex, network, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::transaction::Version;
+    use bitcoin::{Amount, OutPoint, ScriptBuf, Sequence, TxIn, TxOut, Txid, Witness};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_simulated_chain_spends_and_confirms() {
+        let storage = MemoryStorage::new();
+        let mut chain = SimChain::load(&storage, 1_000).unwrap();
+        let from = "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c";
+        let to = Address::from_str("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx")
+            .unwrap()
+            .assume_checked();
+
+        let funding = chain.fund(from, 50_000, 1_000);
+        let utxos = chain.utxos(from, 1_000);
+        assert_eq!(utxos.len(), 1);
+        assert!(utxos[0].status.confirmed);
+
+        let spend = |value: u64| Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::from_str(&funding).unwrap(), 0),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(value),
+                script_pubkey: to.script_pubkey(),
+            }],
+        };
+        let raw = bitcoin::consensus::encode::serialize_hex(&spend(49_000));
+        let txid = chain.broadcast(&raw, Network::Testnet, 1_000).unwrap();
+        assert!(chain.utxos(from, 1_000).is_empty());
+
+        // Unconfirmed until the next simulated block
+        assert!(!chain.tx_status(&txid, 1_000).unwrap().confirmed);
+        assert!(!chain.utxos(&to.to_string(), 1_000)[0].status.confirmed);
+        let later = 1_000 + BLOCK_SECS;
+        assert!(chain.tx_status(&txid, later).unwrap().confirmed);
+        assert_eq!(chain.utxos(&to.to_string(), later)[0].value, 49_000);
+
+        // Spending the same coins again is rejected
+        let double = bitcoin::consensus::encode::serialize_hex(&spend(48_000));
+        let err = chain
+            .broadcast(&double, Network::Testnet, later)
+            .unwrap_err();
+        assert!(err.to_string().contains("not an unspent output"));
+
+        chain.save(&storage).unwrap();
+        assert_eq!(SimChain::load(&storage, later).unwrap(), chain);
+    }
+}
@@ -0,0 +1,912 @@
+//! Spend Path Planning
+//!
+//! A script address can be spent through its key path (one signature for the
+//! tweaked output key) or through any leaf of its script tree (the leaf
+//! script, its control block and whatever the script asks for). The planner
+//! lists every path with its witness weight and whether it can be used right
+//! now, then picks the cheapest usable one unless `--spend-path
+//! keypath|leaf:<n>` names a path. `dkg-spend-plan` shows the choice;
+//! `dkg-build-tx` builds, sizes and signs the transaction along it.
+//!
+//! A path is unavailable when:
+//!
+//! - **key**: the wallet does not hold the key it needs
+//! - **timelock**: an absolute lock height is above the chain tip, or the
+//!   coins have fewer confirmations than a relative lock asks for
+//! - **preimage**: a hash lock has no (or the wrong) preimage
+//! - **script**: the leaf is neither miniscript nor one of the fixed
+//!   templates earlier versions wrote
+//!
+//! Miniscript leaves (everything [`taproot_scripts`] compiles) are satisfied
+//! against the wallet state, which gives the exact witness and its weight.
+//!
+//! [`taproot_scripts`]: crate::btc::taproot_scripts
+
+use crate::btc::taproot_tree::TaprootTreeRecord;
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::key::XOnlyPublicKey;
+use bitcoin::opcodes::all::*;
+use bitcoin::script::Instruction;
+use bitcoin::taproot::TapLeafHash;
+use bitcoin::{absolute, relative, Script, VarInt};
+use miniscript::{Miniscript, Preimage32, Satisfier, Tap, Terminal};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Witness weight of a key-path spend: item count and a 64-byte signature
+pub const KEY_PATH_WITNESS_WEIGHT: u64 = 66;
+
+/// Which way an output is spent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendPath {
+    KeyPath,
+    /// Leaf index in the tree's record
+    Leaf(usize),
+}
+
+impl FromStr for SpendPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        if s == "keypath" {
+            return Ok(SpendPath::KeyPath);
+        }
+        match s.strip_prefix("leaf:").map(str::parse) {
+            Some(Ok(index)) => Ok(SpendPath::Leaf(index)),
+            _ => bail!(crate::error::Error::User(format!(
+                "Unknown spend path '{}' (keypath or leaf:<n>)",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for SpendPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpendPath::KeyPath => write!(f, "keypath"),
+            SpendPath::Leaf(index) => write!(f, "leaf:{}", index),
+        }
+    }
+}
+
+/// What a leaf script asks for, read from the fixed templates earlier
+/// versions built before leaves were compiled from miniscript policies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeafConditions {
+    /// x-only key that must sign
+    pub key: [u8; 32],
+    /// `OP_CHECKLOCKTIMEVERIFY` height
+    pub lock_height: Option<u32>,
+    /// `OP_CHECKSEQUENCEVERIFY` blocks
+    pub relative_blocks: Option<u16>,
+    /// `OP_SHA256` hash the preimage must match
+    pub hash: Option<[u8; 32]>,
+}
+
+impl LeafConditions {
+    /// Read a leaf script; None if it is not a known template
+    pub fn parse(script: &Script) -> Option<Self> {
+        let ops = script.instructions().collect::<Result<Vec<_>, _>>().ok()?;
+        let (key, prefix) = match ops.as_slice() {
+            [prefix @ .., Instruction::PushBytes(key), Instruction::Op(OP_CHECKSIG)] => {
+                (key.as_bytes().try_into().ok()?, prefix)
+            }
+            _ => return None,
+        };
+        let mut conditions = LeafConditions {
+            key,
+            lock_height: None,
+            relative_blocks: None,
+            hash: None,
+        };
+        match prefix {
+            [] => {}
+            [n, Instruction::Op(OP_CLTV), Instruction::Op(OP_DROP)] => {
+                conditions.lock_height = Some(n.script_num()?.try_into().ok()?);
+            }
+            [n, Instruction::Op(OP_CSV), Instruction::Op(OP_DROP)] => {
+                conditions.relative_blocks = Some(n.script_num()?.try_into().ok()?);
+            }
+            [Instruction::Op(OP_SHA256), Instruction::PushBytes(hash), Instruction::Op(OP_EQUALVERIFY)] =>
+            {
+                conditions.hash = Some(hash.as_bytes().try_into().ok()?);
+            }
+            _ => return None,
+        }
+        Some(conditions)
+    }
+
+    /// One line, e.g. "key 1a2b…, after block 900000"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("key {}…", &hex::encode(self.key)[..8])];
+        if let Some(height) = self.lock_height {
+            parts.push(format!("after block {}", height));
+        }
+        if let Some(blocks) = self.relative_blocks {
+            parts.push(format!("{} blocks after confirmation", blocks));
+        }
+        if let Some(hash) = &self.hash {
+            parts.push(format!("preimage of {}…", &hex::encode(hash)[..8]));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Chain and wallet state the paths are checked against
+#[derive(Debug, Clone, Default)]
+pub struct SpendState {
+    /// Current chain tip, if known
+    pub tip_height: Option<u64>,
+    /// Confirmations of the youngest coin being spent, if known
+    pub confirmations: Option<u64>,
+    /// Hash-lock preimage, if one was given
+    pub preimage: Option<Vec<u8>>,
+    /// x-only keys the wallet can sign for
+    pub keys: Vec<[u8; 32]>,
+}
+
+/// One way to spend the output
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PathOption {
+    /// `keypath` or `leaf:<n>`
+    pub path: String,
+    pub description: String,
+    /// Witness weight per input (weight units)
+    pub witness_weight: u64,
+    pub available: bool,
+    /// Why the path cannot be used now
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasons: Vec<String>,
+}
+
+/// Output from dkg-spend-plan command
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SpendPlanOutput {
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tip_height: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u64>,
+    pub options: Vec<PathOption>,
+    /// Path to spend with, None if none is usable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<String>,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Length of a witness item with its size prefix
+fn item_weight(len: usize) -> u64 {
+    (VarInt(len as u64).size() + len) as u64
+}
+
+/// Why a key can't sign, if it can't
+fn key_reason(key: &[u8; 32], state: &SpendState) -> Option<String> {
+    (!state.keys.contains(key)).then(|| {
+        format!(
+            "needs a signature from {}, which this wallet does not hold",
+            hex::encode(key)
+        )
+    })
+}
+
+/// Why an absolute lock blocks the spend, if it does
+fn lock_height_reason(height: u32, state: &SpendState) -> Option<String> {
+    match state.tip_height {
+        Some(tip) if tip >= height as u64 => None,
+        Some(tip) => Some(format!(
+            "timelock not met: spendable from block {}, tip is {} ({} to go)",
+            height,
+            tip,
+            height as u64 - tip
+        )),
+        None => Some(format!(
+            "timelock unknown: spendable from block {}, tip height unknown",
+            height
+        )),
+    }
+}
+
+/// Why a relative lock blocks the spend, if it does
+fn relative_blocks_reason(blocks: u16, state: &SpendState) -> Option<String> {
+    match state.confirmations {
+        Some(confs) if confs >= blocks as u64 => None,
+        Some(confs) => Some(format!(
+            "timelock not met: needs {} confirmations, the coins have {}",
+            blocks, confs
+        )),
+        None => Some(format!(
+            "timelock unknown: needs {} confirmations, coins not confirmed",
+            blocks
+        )),
+    }
+}
+
+/// Why a hash lock blocks the spend, if it does
+fn preimage_reason(hash: &[u8; 32], state: &SpendState) -> Option<String> {
+    match &state.preimage {
+        Some(preimage) if sha256::Hash::hash(preimage).to_byte_array() == *hash => None,
+        Some(_) => Some(format!(
+            "wrong preimage: it does not hash to {}",
+            hex::encode(hash)
+        )),
+        None => Some("missing preimage (pass --preimage)".to_string()),
+    }
+}
+
+/// Answers a miniscript's questions from the spend state, with placeholder
+/// signatures for the keys the wallet holds
+struct StateSatisfier<'a>(&'a SpendState);
+
+impl Satisfier<XOnlyPublicKey> for StateSatisfier<'_> {
+    fn lookup_tap_leaf_script_sig(
+        &self,
+        key: &XOnlyPublicKey,
+        _: &TapLeafHash,
+    ) -> Option<bitcoin::taproot::Signature> {
+        if !self.0.keys.contains(&key.serialize()) {
+            return None;
+        }
+        Some(bitcoin::taproot::Signature {
+            signature: bitcoin::secp256k1::schnorr::Signature::from_slice(&[1; 64]).ok()?,
+            sighash_type: bitcoin::TapSighashType::Default,
+        })
+    }
+
+    fn lookup_sha256(&self, hash: &sha256::Hash) -> Option<Preimage32> {
+        let preimage: Preimage32 = self.0.preimage.as_deref()?.try_into().ok()?;
+        (sha256::Hash::hash(&preimage) == *hash).then_some(preimage)
+    }
+
+    fn check_older(&self, lock: relative::LockTime) -> bool {
+        match lock {
+            relative::LockTime::Blocks(height) => {
+                relative_blocks_reason(height.value(), self.0).is_none()
+            }
+            relative::LockTime::Time(_) => false,
+        }
+    }
+
+    fn check_after(&self, lock: absolute::LockTime) -> bool {
+        lock.is_block_height() && lock_height_reason(lock.to_consensus_u32(), self.0).is_none()
+    }
+}
+
+/// Witness weight of the stack a miniscript leaf consumes, and why it
+/// cannot be satisfied now if it can't
+fn miniscript_leaf(
+    miniscript: &Miniscript<XOnlyPublicKey, Tap>,
+    state: &SpendState,
+) -> (u64, Vec<String>) {
+    if let Ok(stack) = miniscript.satisfy(StateSatisfier(state)) {
+        return (
+            stack.iter().map(|item| item_weight(item.len())).sum(),
+            Vec::new(),
+        );
+    }
+
+    let mut reasons = Vec::new();
+    for node in miniscript.iter() {
+        let reason = match &node.node {
+            Terminal::PkK(key) | Terminal::PkH(key) => key_reason(&key.serialize(), state),
+            Terminal::MultiA(thresh) => {
+                let held = thresh
+                    .iter()
+                    .filter(|key| state.keys.contains(&XOnlyPublicKey::serialize(key)))
+                    .count();
+                (held < thresh.k()).then(|| {
+                    format!(
+                        "needs {} of {} signatures, this wallet holds {}",
+                        thresh.k(),
+                        thresh.n(),
+                        held
+                    )
+                })
+            }
+            Terminal::After(lock) if lock.is_block_height() => {
+                lock_height_reason(lock.to_consensus_u32(), state)
+            }
+            Terminal::After(lock) => Some(format!(
+                "timelock uses a timestamp ({}), which is not checked",
+                lock.to_consensus_u32()
+            )),
+            Terminal::Older(lock) if lock.is_height_locked() => {
+                relative_blocks_reason(lock.to_consensus_u32() as u16, state)
+            }
+            Terminal::Older(_) => {
+                Some("relative timelock uses time, which is not checked".to_string())
+            }
+            Terminal::Sha256(hash) => match &state.preimage {
+                Some(preimage) if preimage.len() != 32 => {
+                    Some("wrong preimage: it must be 32 bytes".to_string())
+                }
+                _ => preimage_reason(&hash.to_byte_array(), state),
+            },
+            _ => None,
+        };
+        reasons.extend(reason);
+    }
+    if reasons.is_empty() {
+        reasons.push("cannot be satisfied with what this wallet holds".to_string());
+    }
+    let weight = miniscript.max_satisfaction_size().unwrap_or(0) as u64;
+    (weight, reasons)
+}
+
+/// Witness weight of the stack a legacy template leaf consumes, and why it
+/// cannot be satisfied now if it can't
+fn template_leaf(conditions: &LeafConditions, state: &SpendState) -> (u64, Vec<String>) {
+    let mut weight = item_weight(64);
+    let mut reasons: Vec<String> = key_reason(&conditions.key, state).into_iter().collect();
+    if let Some(height) = conditions.lock_height {
+        reasons.extend(lock_height_reason(height, state));
+    }
+    if let Some(blocks) = conditions.relative_blocks {
+        reasons.extend(relative_blocks_reason(blocks, state));
+    }
+    if let Some(hash) = &conditions.hash {
+        weight += item_weight(state.preimage.as_ref().map_or(32, Vec::len));
+        reasons.extend(preimage_reason(hash, state));
+    }
+    (weight, reasons)
+}
+
+/// Answers a miniscript's questions when the leaf is really spent: the one
+/// signature the session produced, and timelocks as met since the spending
+/// transaction carries them (see [`leaf_locks`])
+struct SigningSatisfier<'a> {
+    key: [u8; 32],
+    signature: [u8; 64],
+    preimage: Option<&'a [u8]>,
+}
+
+impl Satisfier<XOnlyPublicKey> for SigningSatisfier<'_> {
+    fn lookup_tap_leaf_script_sig(
+        &self,
+        key: &XOnlyPublicKey,
+        _: &TapLeafHash,
+    ) -> Option<bitcoin::taproot::Signature> {
+        if key.serialize() != self.key {
+            return None;
+        }
+        Some(bitcoin::taproot::Signature {
+            signature: bitcoin::secp256k1::schnorr::Signature::from_slice(&self.signature).ok()?,
+            sighash_type: bitcoin::TapSighashType::Default,
+        })
+    }
+
+    fn lookup_sha256(&self, hash: &sha256::Hash) -> Option<Preimage32> {
+        let preimage: Preimage32 = self.preimage?.try_into().ok()?;
+        (sha256::Hash::hash(&preimage) == *hash).then_some(preimage)
+    }
+
+    fn check_older(&self, lock: relative::LockTime) -> bool {
+        matches!(lock, relative::LockTime::Blocks(_))
+    }
+
+    fn check_after(&self, lock: absolute::LockTime) -> bool {
+        lock.is_block_height()
+    }
+}
+
+/// Witness items a leaf consumes, in push order, when `key` signs it with
+/// `signature`; the script and control block go on top of these
+pub fn leaf_stack(
+    script: &Script,
+    key: &[u8; 32],
+    signature: &[u8; 64],
+    preimage: Option<&[u8]>,
+) -> Result<Vec<Vec<u8>>> {
+    if let Ok(miniscript) = Miniscript::<XOnlyPublicKey, Tap>::parse(script) {
+        let satisfier = SigningSatisfier {
+            key: *key,
+            signature: *signature,
+            preimage,
+        };
+        return miniscript.satisfy(satisfier).map_err(|e| {
+            crate::error::Error::User(format!(
+                "Leaf {} cannot be satisfied with one signature from {}{}: {}",
+                miniscript,
+                hex::encode(key),
+                if preimage.is_some() {
+                    " and the preimage"
+                } else {
+                    ""
+                },
+                e
+            ))
+            .into()
+        });
+    }
+
+    let conditions = LeafConditions::parse(script).ok_or_else(|| {
+        crate::error::Error::User("Unrecognized leaf script: cannot tell what it needs".into())
+    })?;
+    if let Some(reason) = key_reason(
+        &conditions.key,
+        &SpendState {
+            keys: vec![*key],
+            ..Default::default()
+        },
+    ) {
+        bail!(crate::error::Error::User(format!("Leaf {}", reason)));
+    }
+    let mut stack = vec![signature.to_vec()];
+    if let Some(hash) = &conditions.hash {
+        let state = SpendState {
+            preimage: preimage.map(<[u8]>::to_vec),
+            ..Default::default()
+        };
+        if let Some(reason) = preimage_reason(hash, &state) {
+            bail!(crate::error::Error::User(format!("Leaf has a {}", reason)));
+        }
+        // The hash check runs first, so the preimage goes on top
+        stack.extend(state.preimage);
+    }
+    Ok(stack)
+}
+
+/// Lock height and relative lock (blocks) the transaction spending a leaf
+/// must carry for the leaf's timelocks to pass
+pub fn leaf_locks(script: &Script) -> Result<(Option<u32>, Option<u16>)> {
+    let Ok(miniscript) = Miniscript::<XOnlyPublicKey, Tap>::parse(script) else {
+        return Ok(LeafConditions::parse(script)
+            .map_or((None, None), |c| (c.lock_height, c.relative_blocks)));
+    };
+    let mut lock_height = None;
+    let mut relative_blocks = None;
+    for node in miniscript.iter() {
+        match &node.node {
+            Terminal::After(lock) if lock.is_block_height() => {
+                lock_height = lock_height.max(Some(lock.to_consensus_u32()));
+            }
+            Terminal::Older(lock) if lock.is_height_locked() => {
+                relative_blocks = relative_blocks.max(Some(lock.to_consensus_u32() as u16));
+            }
+            Terminal::After(_) | Terminal::Older(_) => bail!(crate::error::Error::User(
+                "Leaves with time-based locks cannot be spent here; only block heights".into()
+            )),
+            _ => {}
+        }
+    }
+    Ok((lock_height, relative_blocks))
+}
+
+/// Every path of `tree`, checked against `state`
+pub fn plan_paths(tree: &TaprootTreeRecord, state: &SpendState) -> Result<Vec<PathOption>> {
+    let internal_key: [u8; 32] = tree.internal_key()?.serialize();
+    let reasons: Vec<String> = key_reason(&internal_key, state).into_iter().collect();
+    let mut options = vec![PathOption {
+        path: SpendPath::KeyPath.to_string(),
+        description: "Key path (one signature, no script revealed)".to_string(),
+        witness_weight: KEY_PATH_WITNESS_WEIGHT,
+        available: reasons.is_empty(),
+        reasons,
+    }];
+
+    for (index, leaf) in tree.leaves.iter().enumerate() {
+        let script = bitcoin::ScriptBuf::from_bytes(
+            hex::decode(&leaf.script).context("Invalid leaf script hex")?,
+        );
+        let control_block_len = leaf.control_block.len() / 2;
+
+        let (description, stack_weight, reasons) =
+            match Miniscript::<XOnlyPublicKey, Tap>::parse(&script) {
+                Ok(miniscript) => {
+                    let (weight, reasons) = miniscript_leaf(&miniscript, state);
+                    (miniscript.to_string(), weight, reasons)
+                }
+                Err(_) => match LeafConditions::parse(&script) {
+                    Some(conditions) => {
+                        let (weight, reasons) = template_leaf(&conditions, state);
+                        (conditions.describe(), weight, reasons)
+                    }
+                    None => (
+                        leaf.script.clone(),
+                        item_weight(64),
+                        vec!["unrecognized leaf script: cannot tell what it needs".to_string()],
+                    ),
+                },
+            };
+        // Item count, script and control block, then what the script consumes
+        let weight = 1 + item_weight(script.len()) + item_weight(control_block_len) + stack_weight;
+
+        options.push(PathOption {
+            path: SpendPath::Leaf(index).to_string(),
+            description: match &leaf.label {
+                Some(label) => format!("Leaf {} ({}): {}", index, label, description),
+                None => format!("Leaf {}: {}", index, description),
+            },
+            witness_weight: weight,
+            available: reasons.is_empty(),
+            reasons,
+        });
+    }
+    Ok(options)
+}
+
+/// The cheapest usable path, or `wanted` if it is usable
+pub fn select_path(options: &[PathOption], wanted: Option<SpendPath>) -> Result<&PathOption> {
+    if let Some(wanted) = wanted {
+        let name = wanted.to_string();
+        let option = options.iter().find(|o| o.path == name).ok_or_else(|| {
+            crate::error::Error::User(format!(
+                "No {} here: the tree has {} leaves",
+                name,
+                options.len() - 1
+            ))
+        })?;
+        if !option.available {
+            bail!(crate::error::Error::User(format!(
+                "{} is unavailable: {}",
+                name,
+                option.reasons.join("; ")
+            )));
+        }
+        return Ok(option);
+    }
+    // Stable, so the key path wins a tie
+    options
+        .iter()
+        .filter(|o| o.available)
+        .min_by_key(|o| o.witness_weight)
+        .ok_or_else(|| {
+            crate::error::Error::User(format!(
+                "No usable spend path: {}",
+                options
+                    .iter()
+                    .map(|o| format!("{} ({})", o.path, o.reasons.join("; ")))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .into()
+        })
+}
+
+/// Core function for dkg-spend-plan: list the paths of `tree` and pick one
+pub fn spend_plan_core(
+    tree: &TaprootTreeRecord,
+    state: &SpendState,
+    wanted: Option<SpendPath>,
+) -> Result<CommandResult> {
+    let options = plan_paths(tree, state)?;
+    let selected = select_path(&options, wanted);
+
+    let mut out = String::new();
+    out.push_str("Spend Paths\n\n");
+    out.push_str(&format!("Address: {}\n", tree.address));
+    out.push_str(&format!("Condition: {}\n", tree.description));
+    if let Some(tip) = state.tip_height {
+        out.push_str(&format!("Tip height: {}\n", tip));
+    }
+    if let Some(confs) = state.confirmations {
+        out.push_str(&format!("Youngest coin: {} confirmations\n", confs));
+    }
+    out.push('\n');
+    for option in &options {
+        let mark = if option.available { "✅" } else { "❌" };
+        out.push_str(&format!(
+            "{} {:<8} {} ({} WU)\n",
+            mark, option.path, option.description, option.witness_weight
+        ));
+        for reason in &option.reasons {
+            out.push_str(&format!("      {}\n", reason));
+        }
+    }
+    out.push('\n');
+    match &selected {
+        Ok(option) => {
+            out.push_str(&format!("Selected: {}\n", option.path));
+            out.push_str(&format!(
+                "Spend it with: frostdao dkg-build-tx --from {} --spend-path {} ...\n",
+                tree.address, option.path
+            ));
+        }
+        Err(e) => out.push_str(&format!("⚠️  {}\n", e)),
+    }
+
+    let output = SpendPlanOutput {
+        address: tree.address.clone(),
+        tip_height: state.tip_height,
+        confirmations: state.confirmations,
+        selected: selected.ok().map(|o| o.path.clone()),
+        options,
+        event_type: "spend_plan".to_string(),
+    };
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&output)?,
+    })
+}
+
+/// State of `tree`'s coins `utxos` at `tip_height`, for a wallet holding the
+/// key the tree was built on
+#[cfg(feature = "network")]
+pub fn spend_state<'a>(
+    tree: &TaprootTreeRecord,
+    utxos: impl IntoIterator<Item = &'a crate::btc::transaction::UtxoResponse>,
+    tip_height: Option<u64>,
+    preimage: Option<&str>,
+    storage: &dyn crate::storage::Storage,
+) -> Result<SpendState> {
+    let confirmations = tip_height.and_then(|tip| {
+        utxos
+            .into_iter()
+            .filter_map(|utxo| utxo.status.block_height)
+            .map(|height| tip.saturating_sub(height) + 1)
+            .min()
+    });
+    Ok(SpendState {
+        tip_height,
+        confirmations,
+        preimage: preimage
+            .map(|p| hex::decode(p.trim()).context("Preimage must be hex"))
+            .transpose()?,
+        keys: vec![crate::btc::taproot_tree::wallet_key(
+            storage,
+            tree.derivation_path,
+        )?],
+    })
+}
+
+/// CLI wrapper for dkg-spend-plan: checks the paths of one of the wallet's
+/// script addresses against the chain tip and the confirmations of its coins
+#[cfg(feature = "network")]
+pub fn dkg_spend_plan(
+    wallet_name: &str,
+    address: &str,
+    wanted: Option<&str>,
+    preimage: Option<&str>,
+    network: &str,
+) -> Result<()> {
+    use crate::btc::transaction::{fetch_tip_height, fetch_utxos};
+
+    let network = crate::btc::hd_address::parse_network(network)?;
+    let wanted = wanted.map(SpendPath::from_str).transpose()?;
+    let storage =
+        crate::storage::FileStorage::new(&crate::protocol::keygen::get_state_dir(wallet_name))?;
+    let tree = crate::btc::taproot_tree::load_taproot_trees(&storage)?
+        .into_values()
+        .find(|tree| tree.address == address)
+        .with_context(|| {
+            format!(
+                "{} is not a script address of this wallet (see dkg-list-script-addresses)",
+                address
+            )
+        })?;
+
+    let tip_height = fetch_tip_height(network)?;
+    let state = spend_state(
+        &tree,
+        &fetch_utxos(address, network)?,
+        Some(tip_height),
+        preimage,
+        &storage,
+    )?;
+
+    let result = spend_plan_core(&tree, &state, wanted)?;
+    println!("{}", result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}\n", result.result);
+
+    let plan: SpendPlanOutput = serde_json::from_str(&result.result)?;
+    if plan.selected.is_none() {
+        // Same error, now as the exit status
+        select_path(&plan.options, wanted)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::taproot_scripts::SpendingPolicy;
+    use bitcoin::script::Builder;
+    use bitcoin::taproot::{LeafVersion, TaprootBuilder};
+    use bitcoin::Network;
+
+    const KEY: [u8; 32] = [
+        0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b,
+        0x07, 0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8,
+        0x17, 0x98,
+    ];
+    const OTHER: [u8; 32] = [
+        0xc6, 0x04, 0x7f, 0x94, 0x41, 0xed, 0x7d, 0x6d, 0x30, 0x45, 0x40, 0x6e, 0x95, 0xc0, 0x7c,
+        0xd8, 0x5c, 0x77, 0x8e, 0x4b, 0x8c, 0xef, 0x3c, 0xa7, 0xab, 0xac, 0x09, 0xb9, 0x5c, 0x70,
+        0x9e, 0xe5,
+    ];
+
+    fn tree(policy: &str) -> TaprootTreeRecord {
+        let internal_key = XOnlyPublicKey::from_slice(&KEY).unwrap();
+        let policy = SpendingPolicy::parse(policy).unwrap();
+        let spend_info = policy.build_taproot_spend_info(&internal_key).unwrap();
+        TaprootTreeRecord::from_spend_info(
+            &spend_info,
+            Network::Regtest,
+            &policy.description(),
+            None,
+        )
+        .unwrap()
+    }
+
+    fn state(tip: u64, confirmations: u64) -> SpendState {
+        SpendState {
+            tip_height: Some(tip),
+            confirmations: Some(confirmations),
+            preimage: None,
+            keys: vec![KEY],
+        }
+    }
+
+    /// `<height> OP_CLTV OP_DROP <key> OP_CHECKSIG`, as earlier versions built it
+    fn legacy_cltv_script(height: i64, key: &[u8; 32]) -> bitcoin::ScriptBuf {
+        Builder::new()
+            .push_int(height)
+            .push_opcode(OP_CLTV)
+            .push_opcode(OP_DROP)
+            .push_slice(key)
+            .push_opcode(OP_CHECKSIG)
+            .into_script()
+    }
+
+    #[test]
+    fn test_spend_path_parses() {
+        assert_eq!("keypath".parse::<SpendPath>().unwrap(), SpendPath::KeyPath);
+        assert_eq!("leaf:1".parse::<SpendPath>().unwrap(), SpendPath::Leaf(1));
+        assert!("leaf:x".parse::<SpendPath>().is_err());
+        assert!("script".parse::<SpendPath>().is_err());
+    }
+
+    #[test]
+    fn test_leaf_templates_parse() {
+        let cltv = legacy_cltv_script(900_000, &KEY);
+        let parsed = LeafConditions::parse(&cltv).unwrap();
+        assert_eq!(parsed.key, KEY);
+        assert_eq!(parsed.lock_height, Some(900_000));
+
+        let claim = Builder::new()
+            .push_opcode(OP_SHA256)
+            .push_slice([5; 32])
+            .push_opcode(OP_EQUALVERIFY)
+            .push_slice(KEY)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        assert_eq!(LeafConditions::parse(&claim).unwrap().hash, Some([5; 32]));
+
+        // A tree written by an earlier version still gets planned
+        let internal_key = XOnlyPublicKey::from_slice(&KEY).unwrap();
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, legacy_cltv_script(1_000, &KEY))
+            .unwrap()
+            .finalize(&bitcoin::secp256k1::Secp256k1::new(), internal_key)
+            .unwrap();
+        let tree =
+            TaprootTreeRecord::from_spend_info(&spend_info, Network::Regtest, "legacy", None)
+                .unwrap();
+        assert_eq!(
+            tree.leaves[0].leaf_version,
+            LeafVersion::TapScript.to_consensus()
+        );
+        let options = plan_paths(&tree, &state(900, 3)).unwrap();
+        assert!(options[1].description.contains("after block 1000"));
+        assert!(options[1].reasons[0].contains("100 to go"));
+        let options = plan_paths(&tree, &state(1_000, 3)).unwrap();
+        assert!(options[1].available);
+    }
+
+    #[test]
+    fn test_planner_prefers_key_path_and_explains_locked_leaves() {
+        let preimage = [7u8; 32];
+        let tree = tree(&format!(
+            "or(and(pk({}),sha256({})),and(pk({}),after(1000)))",
+            hex::encode(KEY),
+            hex::encode(sha256::Hash::hash(&preimage).to_byte_array()),
+            hex::encode(OTHER)
+        ));
+
+        let options = plan_paths(&tree, &state(900, 3)).unwrap();
+        assert_eq!(select_path(&options, None).unwrap().path, "keypath");
+        let claim = options
+            .iter()
+            .position(|o| o.description.contains("sha256("))
+            .unwrap();
+        let refund = options
+            .iter()
+            .position(|o| o.description.contains("after("))
+            .unwrap();
+        assert!(!options[claim].available);
+        assert_eq!(
+            options[claim].reasons,
+            ["missing preimage (pass --preimage)"]
+        );
+        assert!(options[refund].reasons[0].contains("does not hold"));
+        assert!(options[refund].reasons[1].contains("100 to go"));
+        let claim_path = SpendPath::Leaf(claim - 1);
+        let err = select_path(&options, Some(claim_path)).unwrap_err();
+        assert!(err.to_string().contains("missing preimage"));
+
+        let mut wrong = state(900, 3);
+        wrong.preimage = Some(b"secret".to_vec());
+        let options = plan_paths(&tree, &wrong).unwrap();
+        assert_eq!(
+            options[claim].reasons,
+            ["wrong preimage: it must be 32 bytes"]
+        );
+
+        let mut with_preimage = state(900, 3);
+        with_preimage.preimage = Some(preimage.to_vec());
+        let options = plan_paths(&tree, &with_preimage).unwrap();
+        assert!(options[claim].available);
+        assert!(options[claim].witness_weight > options[0].witness_weight);
+        assert_eq!(
+            select_path(&options, Some(claim_path)).unwrap().path,
+            claim_path.to_string()
+        );
+        assert!(select_path(&options, Some(SpendPath::Leaf(5))).is_err());
+    }
+
+    #[test]
+    fn test_planner_falls_back_to_a_leaf_without_the_internal_key() {
+        let mut tree = tree(&format!("and(pk({}),older(6))", hex::encode(KEY)));
+        let mut state = state(900, 3);
+        // Pretend the internal key is someone else's
+        tree.internal_key = hex::encode(OTHER);
+
+        let options = plan_paths(&tree, &state).unwrap();
+        assert!(!options[0].available);
+        assert!(options[1].reasons[0].contains("needs 6 confirmations"));
+        assert!(select_path(&options, None).is_err());
+
+        state.confirmations = Some(6);
+        let options = plan_paths(&tree, &state).unwrap();
+        let selected = select_path(&options, None).unwrap();
+        assert_eq!(selected.path, "leaf:0");
+        // Item count, signature, script and control block
+        let leaf = &tree.leaves[0];
+        assert_eq!(
+            selected.witness_weight,
+            1 + 65 + 1 + (leaf.script.len() / 2) as u64 + 1 + (leaf.control_block.len() / 2) as u64
+        );
+    }
+
+    #[test]
+    fn test_leaf_stack_and_locks_for_a_real_spend() {
+        let preimage = [7u8; 32];
+        let signature = [9u8; 64];
+        let tree = tree(&format!(
+            "or(and(pk({}),sha256({})),and(pk({}),after(1000)))",
+            hex::encode(KEY),
+            hex::encode(sha256::Hash::hash(&preimage).to_byte_array()),
+            hex::encode(KEY)
+        ));
+        let script = |index: usize| {
+            bitcoin::ScriptBuf::from_bytes(hex::decode(&tree.leaves[index].script).unwrap())
+        };
+        let claim = (0..2)
+            .find(|&i| leaf_locks(&script(i)).unwrap() == (None, None))
+            .unwrap();
+        let refund = 1 - claim;
+        assert_eq!(leaf_locks(&script(refund)).unwrap(), (Some(1000), None));
+
+        let stack = leaf_stack(&script(claim), &KEY, &signature, Some(&preimage)).unwrap();
+        assert!(stack.contains(&preimage.to_vec()) && stack.contains(&signature.to_vec()));
+        assert!(leaf_stack(&script(claim), &KEY, &signature, None).is_err());
+        assert!(leaf_stack(&script(refund), &OTHER, &signature, None).is_err());
+        assert_eq!(
+            leaf_stack(&script(refund), &KEY, &signature, None).unwrap(),
+            [signature.to_vec()]
+        );
+
+        let legacy = legacy_cltv_script(900_000, &KEY);
+        assert_eq!(leaf_locks(&legacy).unwrap(), (Some(900_000), None));
+        assert_eq!(
+            leaf_stack(&legacy, &KEY, &signature, None).unwrap(),
+            [signature.to_vec()]
+        );
+    }
+}
@@ -1,234 +1,193 @@
 //! Taproot Script Building
 //!
-//! This module provides builders for Taproot spending conditions:
-//! - Timelocks (absolute CLTV and relative CSV)
-//! - Recovery scripts (fallback after timeout)
-//! - HTLC (Hash Time-Locked Contracts)
+//! Spending conditions are written as miniscript policies and compiled into
+//! Taproot script leaves:
+//! - Timelocks: `and(pk(A),after(850000))` (CLTV), `and(pk(A),older(144))` (CSV)
+//! - Recovery: `or(pk(A),and(pk(R),after(900000)))`
+//! - HTLC: `or(and(pk(A),sha256(H)),and(pk(B),after(900000)))`
+//! - Anything else the policy language can express, e.g.
+//!   `or(9@pk(A),and(thresh(2,pk(B),pk(C),pk(D)),older(4320)))`
+//!
+//! The wallet's group key stays the internal key, so the quorum can always
+//! key-path spend. Every top-level `or`/`thresh(1,...)` branch becomes its own
+//! leaf, placed in the tree by its `n@` odds so likely branches get shorter
+//! control blocks. Several policies combine into one tree with
+//! [`SpendingPolicy::any_of`], e.g. an HTLC next to a recovery path; preset
+//! branches carry labels ("HTLC claim", "Recovery") that are persisted with
+//! each leaf.
 
 use anyhow::{Context, Result};
 use bitcoin::key::XOnlyPublicKey;
-use bitcoin::opcodes::all::*;
-use bitcoin::script::Builder;
 use bitcoin::secp256k1::Secp256k1;
 use bitcoin::taproot::{TaprootBuilder, TaprootSpendInfo};
 use bitcoin::{Address, Network, ScriptBuf};
+use miniscript::policy::Concrete;
+use miniscript::{Miniscript, Tap, Threshold};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Probabilities are scaled to integer weights for the Huffman tree
+const LEAF_WEIGHT_SCALE: f64 = 1_000_000.0;
 
-/// Script configuration for spending conditions
+/// One compiled script leaf
 #[derive(Clone, Debug)]
-pub enum SpendingCondition {
-    /// No script - key path only
-    KeyPathOnly,
-
-    /// Absolute timelock (CHECKLOCKTIMEVERIFY)
-    /// Funds can only be spent after block height
-    TimelockAbsolute {
-        /// Block height when funds become spendable
-        lock_height: u32,
-        /// Recipient public key (x-only, 32 bytes)
-        recipient_pubkey: [u8; 32],
-    },
-
-    /// Relative timelock (CHECKSEQUENCEVERIFY)
-    /// Funds can only be spent N blocks after UTXO confirmation
-    TimelockRelative {
-        /// Number of blocks to wait
-        blocks: u16,
-        /// Recipient public key (x-only, 32 bytes)
-        recipient_pubkey: [u8; 32],
-    },
-
-    /// Recovery script with timeout fallback
-    /// Primary owner can spend anytime, recovery key can spend after timeout
-    Recovery {
-        /// Primary owner public key
-        owner_pubkey: [u8; 32],
-        /// Recovery public key (backup/inheritance)
-        recovery_pubkey: [u8; 32],
-        /// Block height when recovery becomes active
-        timeout_height: u32,
-    },
+pub struct PolicyLeaf {
+    /// Policy branch this leaf satisfies
+    pub policy: Concrete<XOnlyPublicKey>,
+    /// Compiled tapscript
+    pub miniscript: Miniscript<XOnlyPublicKey, Tap>,
+    /// Share of spends expected to use this leaf, from the policy's odds
+    pub probability: f64,
+    /// What the leaf is for, for preset branches
+    pub label: Option<String>,
+}
 
-    /// Hash Time-Locked Contract
-    /// Recipient can claim with preimage, sender can refund after timeout
-    Htlc {
-        /// Hash of the preimage (SHA256)
-        hash: [u8; 32],
-        /// Recipient public key (claims with preimage)
-        recipient_pubkey: [u8; 32],
-        /// Refund public key (claims after timeout)
-        refund_pubkey: [u8; 32],
-        /// Block height when refund becomes possible
-        timeout_height: u32,
-    },
+impl PolicyLeaf {
+    /// Leaf script
+    pub fn script(&self) -> ScriptBuf {
+        self.miniscript.encode()
+    }
+
+    /// Largest witness the leaf script can consume (bytes, with length
+    /// prefixes; excludes the script and control block)
+    pub fn max_satisfaction_size(&self) -> Option<usize> {
+        self.miniscript.max_satisfaction_size().ok()
+    }
 }
 
-impl SpendingCondition {
-    /// Build a timelock script with CHECKLOCKTIMEVERIFY
-    /// Script: <height> OP_CLTV OP_DROP <pubkey> OP_CHECKSIG
-    pub fn build_cltv_script(lock_height: u32, pubkey: &[u8; 32]) -> ScriptBuf {
-        Builder::new()
-            .push_int(lock_height as i64)
-            .push_opcode(OP_CLTV)
-            .push_opcode(OP_DROP)
-            .push_slice(pubkey)
-            .push_opcode(OP_CHECKSIG)
-            .into_script()
+/// Spending policy for a script address
+#[derive(Clone, Debug)]
+pub struct SpendingPolicy {
+    /// None for key path only
+    policy: Option<Concrete<XOnlyPublicKey>>,
+    /// Leaf labels, in branch order
+    labels: Vec<Option<String>>,
+}
+
+impl SpendingPolicy {
+    /// No script tree, only the key path
+    pub fn key_path_only() -> Self {
+        Self {
+            policy: None,
+            labels: Vec::new(),
+        }
     }
 
-    /// Build a relative timelock script with CHECKSEQUENCEVERIFY
-    /// Script: <blocks> OP_CSV OP_DROP <pubkey> OP_CHECKSIG
-    pub fn build_csv_script(blocks: u16, pubkey: &[u8; 32]) -> ScriptBuf {
-        Builder::new()
-            .push_int(blocks as i64)
-            .push_opcode(OP_CSV)
-            .push_opcode(OP_DROP)
-            .push_slice(pubkey)
-            .push_opcode(OP_CHECKSIG)
-            .into_script()
+    /// Parse a miniscript policy, e.g. `and(pk(<hex>),older(4320))`
+    ///
+    /// Keys are x-only hex. Every branch must compile: it needs a signature,
+    /// can't repeat a key, and can't mix block heights with timestamps.
+    pub fn parse(text: &str) -> Result<Self> {
+        let policy = Concrete::<XOnlyPublicKey>::from_str(text.trim()).map_err(|e| {
+            crate::error::Error::User(format!("Invalid policy '{}': {}", text.trim(), e))
+        })?;
+        let policy = Self {
+            policy: Some(policy),
+            labels: Vec::new(),
+        };
+        policy.leaves()?;
+        Ok(policy)
     }
 
-    /// Build a recovery script (owner can spend anytime)
-    /// Script: <owner_pubkey> OP_CHECKSIG
-    pub fn build_owner_script(owner_pubkey: &[u8; 32]) -> ScriptBuf {
-        Builder::new()
-            .push_slice(owner_pubkey)
-            .push_opcode(OP_CHECKSIG)
-            .into_script()
+    /// Label the branches in order; extra labels are ignored
+    pub fn with_labels(mut self, labels: &[&str]) -> Self {
+        self.labels = labels.iter().map(|label| Some(label.to_string())).collect();
+        self
     }
 
-    /// Build a recovery fallback script (recovery key after timeout)
-    /// Script: <timeout> OP_CLTV OP_DROP <recovery_pubkey> OP_CHECKSIG
-    pub fn build_recovery_script(timeout_height: u32, recovery_pubkey: &[u8; 32]) -> ScriptBuf {
-        Builder::new()
-            .push_int(timeout_height as i64)
-            .push_opcode(OP_CLTV)
-            .push_opcode(OP_DROP)
-            .push_slice(recovery_pubkey)
-            .push_opcode(OP_CHECKSIG)
-            .into_script()
+    /// One tree holding every branch of every part
+    ///
+    /// Parts share the tree evenly; within a part its own odds apply. Key
+    /// path only parts add no leaves.
+    pub fn any_of(parts: Vec<SpendingPolicy>) -> Self {
+        let mut policies = Vec::new();
+        let mut labels = Vec::new();
+        for part in parts {
+            let Some(policy) = part.policy else {
+                continue;
+            };
+            let branches = branch_count(&policy);
+            labels.extend((0..branches).map(|i| part.labels.get(i).cloned().flatten()));
+            policies.push(Arc::new(policy));
+        }
+        let policy = match policies.len() {
+            0 => None,
+            1 => policies.pop().map(Arc::unwrap_or_clone),
+            _ => Some(Concrete::Thresh(Threshold::or_n(policies))),
+        };
+        Self { policy, labels }
     }
 
-    /// Build HTLC claim script (recipient claims with preimage)
-    /// Script: OP_SHA256 <hash> OP_EQUALVERIFY <recipient_pubkey> OP_CHECKSIG
-    pub fn build_htlc_claim_script(hash: &[u8; 32], recipient_pubkey: &[u8; 32]) -> ScriptBuf {
-        Builder::new()
-            .push_opcode(OP_SHA256)
-            .push_slice(hash)
-            .push_opcode(OP_EQUALVERIFY)
-            .push_slice(recipient_pubkey)
-            .push_opcode(OP_CHECKSIG)
-            .into_script()
+    /// The policy text, or "Key path only"
+    pub fn description(&self) -> String {
+        match &self.policy {
+            Some(policy) => policy.to_string(),
+            None => "Key path only".to_string(),
+        }
     }
 
-    /// Build HTLC refund script (sender refunds after timeout)
-    /// Script: <timeout> OP_CLTV OP_DROP <refund_pubkey> OP_CHECKSIG
-    pub fn build_htlc_refund_script(timeout_height: u32, refund_pubkey: &[u8; 32]) -> ScriptBuf {
-        Builder::new()
-            .push_int(timeout_height as i64)
-            .push_opcode(OP_CLTV)
-            .push_opcode(OP_DROP)
-            .push_slice(refund_pubkey)
-            .push_opcode(OP_CHECKSIG)
-            .into_script()
+    /// Compile every top-level branch of the policy into a leaf
+    pub fn leaves(&self) -> Result<Vec<PolicyLeaf>> {
+        let Some(policy) = &self.policy else {
+            return Ok(Vec::new());
+        };
+        let mut branches = Vec::new();
+        flatten_branches(policy, 1.0, &mut branches);
+        branches
+            .into_iter()
+            .enumerate()
+            .map(|(index, (probability, policy))| {
+                let miniscript = policy.compile::<Tap>().map_err(|e| {
+                    crate::error::Error::User(format!(
+                        "Cannot compile policy branch {}: {}",
+                        policy, e
+                    ))
+                })?;
+                Ok(PolicyLeaf {
+                    policy,
+                    miniscript,
+                    probability,
+                    label: self.labels.get(index).cloned().flatten(),
+                })
+            })
+            .collect()
     }
 
-    /// Build Taproot spend info with the configured scripts
+    /// Build Taproot spend info with the compiled leaves
     ///
     /// Returns the TaprootSpendInfo containing:
-    /// - Internal key (for key path spending if enabled)
-    /// - Script tree with all configured spending conditions
+    /// - Internal key (for key path spending)
+    /// - Script tree with one leaf per policy branch
     pub fn build_taproot_spend_info(
         &self,
         internal_key: &XOnlyPublicKey,
     ) -> Result<TaprootSpendInfo> {
         let secp = Secp256k1::new();
-
-        match self {
-            SpendingCondition::KeyPathOnly => {
-                // No scripts, just key path
-                TaprootBuilder::new()
-                    .finalize(&secp, *internal_key)
-                    .map_err(|e| anyhow::anyhow!("Failed to finalize taproot: {:?}", e))
-            }
-
-            SpendingCondition::TimelockAbsolute {
-                lock_height,
-                recipient_pubkey,
-            } => {
-                let script = Self::build_cltv_script(*lock_height, recipient_pubkey);
-                TaprootBuilder::new()
-                    .add_leaf(0, script)
-                    .map_err(|e| anyhow::anyhow!("Failed to add leaf: {:?}", e))?
-                    .finalize(&secp, *internal_key)
-                    .map_err(|e| anyhow::anyhow!("Failed to finalize taproot: {:?}", e))
-            }
-
-            SpendingCondition::TimelockRelative {
-                blocks,
-                recipient_pubkey,
-            } => {
-                let script = Self::build_csv_script(*blocks, recipient_pubkey);
-                TaprootBuilder::new()
-                    .add_leaf(0, script)
-                    .map_err(|e| anyhow::anyhow!("Failed to add leaf: {:?}", e))?
-                    .finalize(&secp, *internal_key)
-                    .map_err(|e| anyhow::anyhow!("Failed to finalize taproot: {:?}", e))
-            }
-
-            SpendingCondition::Recovery {
-                owner_pubkey,
-                recovery_pubkey,
-                timeout_height,
-            } => {
-                // Two leaves: owner (no timelock) and recovery (with timelock)
-                let owner_script = Self::build_owner_script(owner_pubkey);
-                let recovery_script = Self::build_recovery_script(*timeout_height, recovery_pubkey);
-
-                TaprootBuilder::new()
-                    .add_leaf(1, owner_script)
-                    .map_err(|e| anyhow::anyhow!("Failed to add owner leaf: {:?}", e))?
-                    .add_leaf(1, recovery_script)
-                    .map_err(|e| anyhow::anyhow!("Failed to add recovery leaf: {:?}", e))?
-                    .finalize(&secp, *internal_key)
-                    .map_err(|e| anyhow::anyhow!("Failed to finalize taproot: {:?}", e))
-            }
-
-            SpendingCondition::Htlc {
-                hash,
-                recipient_pubkey,
-                refund_pubkey,
-                timeout_height,
-            } => {
-                // Two leaves: claim (with preimage) and refund (after timeout)
-                let claim_script = Self::build_htlc_claim_script(hash, recipient_pubkey);
-                let refund_script = Self::build_htlc_refund_script(*timeout_height, refund_pubkey);
-
-                TaprootBuilder::new()
-                    .add_leaf(1, claim_script)
-                    .map_err(|e| anyhow::anyhow!("Failed to add claim leaf: {:?}", e))?
-                    .add_leaf(1, refund_script)
-                    .map_err(|e| anyhow::anyhow!("Failed to add refund leaf: {:?}", e))?
-                    .finalize(&secp, *internal_key)
-                    .map_err(|e| anyhow::anyhow!("Failed to finalize taproot: {:?}", e))
-            }
-        }
+        let leaves = self.leaves()?;
+        let builder = if leaves.is_empty() {
+            TaprootBuilder::new()
+        } else {
+            TaprootBuilder::with_huffman_tree(leaves.iter().map(|leaf| {
+                let weight = (leaf.probability * LEAF_WEIGHT_SCALE).round() as u32;
+                (weight.max(1), leaf.script())
+            }))
+            .map_err(|e| anyhow::anyhow!("Failed to build script tree: {:?}", e))?
+        };
+        builder
+            .finalize(&secp, *internal_key)
+            .map_err(|e| anyhow::anyhow!("Failed to finalize taproot: {:?}", e))
     }
 
-    /// Generate the P2TR address for this spending condition
+    /// Generate the P2TR address for this policy
+    ///
+    /// The output key is already tweaked with the script tree, so it must not
+    /// be tweaked again (Address::p2tr would apply a second key-path tweak).
     pub fn to_address(&self, internal_key: &XOnlyPublicKey, network: Network) -> Result<Address> {
-        let secp = Secp256k1::new();
         let spend_info = self.build_taproot_spend_info(internal_key)?;
-        let output_key = spend_info.output_key();
-
-        Ok(Address::p2tr(
-            &secp,
-            output_key.to_x_only_public_key(),
-            None,
-            network,
-        ))
+        Ok(Address::p2tr_tweaked(spend_info.output_key(), network))
     }
 
-    /// Get the script pubkey for this spending condition
+    /// Get the script pubkey for this policy
     pub fn script_pubkey(&self, internal_key: &XOnlyPublicKey) -> Result<ScriptBuf> {
         let spend_info = self.build_taproot_spend_info(internal_key)?;
         let output_key = spend_info.output_key();
@@ -236,6 +195,35 @@ impl SpendingCondition {
     }
 }
 
+/// Number of leaves a policy flattens into
+fn branch_count(policy: &Concrete<XOnlyPublicKey>) -> usize {
+    let mut branches = Vec::new();
+    flatten_branches(policy, 1.0, &mut branches);
+    branches.len()
+}
+
+/// Split top-level disjunctions into leaf policies with their probabilities
+fn flatten_branches(
+    policy: &Concrete<XOnlyPublicKey>,
+    probability: f64,
+    out: &mut Vec<(f64, Concrete<XOnlyPublicKey>)>,
+) {
+    match policy {
+        Concrete::Or(branches) => {
+            let total: usize = branches.iter().map(|(odds, _)| odds).sum();
+            for (odds, branch) in branches {
+                flatten_branches(branch, probability * *odds as f64 / total as f64, out);
+            }
+        }
+        Concrete::Thresh(thresh) if thresh.is_or() => {
+            for branch in thresh.iter() {
+                flatten_branches(branch, probability / thresh.n() as f64, out);
+            }
+        }
+        _ => out.push((probability, policy.clone())),
+    }
+}
+
 /// Parse a hex public key string into 32-byte array
 pub fn parse_pubkey_hex(hex_str: &str) -> Result<[u8; 32]> {
     let bytes = hex::decode(hex_str).context("Invalid hex")?;
@@ -266,6 +254,37 @@ pub enum ScriptTypeInput {
     Recovery,
     /// Hash Time-Locked Contract
     Htlc,
+    /// Arbitrary miniscript policy
+    Policy,
+}
+
+impl ScriptTypeInput {
+    /// Parse a CLI script type name
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "none" | "key-path" => Ok(Self::None),
+            "timelock-absolute" | "cltv" => Ok(Self::TimelockAbsolute),
+            "timelock-relative" | "csv" => Ok(Self::TimelockRelative),
+            "recovery" => Ok(Self::Recovery),
+            "htlc" => Ok(Self::Htlc),
+            "policy" => Ok(Self::Policy),
+            _ => anyhow::bail!(
+                "Unknown script type '{}'. Use: timelock-absolute, timelock-relative, recovery, htlc, or policy",
+                name
+            ),
+        }
+    }
+
+    /// Labels of the leaves a preset compiles to, in branch order
+    pub fn leaf_labels(&self) -> &'static [&'static str] {
+        match self {
+            Self::None | Self::Policy => &[],
+            Self::TimelockAbsolute => &["Absolute timelock"],
+            Self::TimelockRelative => &["Relative timelock"],
+            Self::Recovery => &["Owner", "Recovery"],
+            Self::Htlc => &["HTLC claim", "HTLC refund"],
+        }
+    }
 }
 
 /// Parameters for building spending conditions
@@ -285,6 +304,8 @@ pub struct ScriptParams {
     pub htlc_hash: Option<[u8; 32]>,
     /// HTLC: refund pubkey (x-only, 32 bytes)
     pub htlc_refund_pubkey: Option<[u8; 32]>,
+    /// Policy: miniscript policy text
+    pub policy: Option<String>,
 }
 
 impl ScriptParams {
@@ -330,34 +351,35 @@ impl ScriptParams {
             } else {
                 Some(parse_pubkey_hex(htlc_refund_pubkey)?)
             },
+            policy: None,
         })
     }
 
-    /// Convert to SpendingCondition
+    /// Convert to a SpendingPolicy
     ///
-    /// The recipient_pubkey is the key that will be able to spend under the conditions.
-    pub fn to_spending_condition(&self, recipient_pubkey: &[u8; 32]) -> Result<SpendingCondition> {
-        match self.script_type {
-            ScriptTypeInput::None => Ok(SpendingCondition::KeyPathOnly),
+    /// The recipient_pubkey is the key that will be able to spend under the
+    /// conditions. Presets expand to policies:
+    /// - timelock-absolute: `and(pk(recipient),after(height))`
+    /// - timelock-relative: `and(pk(recipient),older(blocks))`
+    /// - recovery: `or(pk(recipient),and(pk(recovery),after(timeout)))`
+    /// - htlc: `or(and(pk(recipient),sha256(hash)),and(pk(refund),after(timeout)))`
+    pub fn to_policy(&self, recipient_pubkey: &[u8; 32]) -> Result<SpendingPolicy> {
+        let recipient = hex::encode(recipient_pubkey);
+        let text = match self.script_type {
+            ScriptTypeInput::None => return Ok(SpendingPolicy::key_path_only()),
 
             ScriptTypeInput::TimelockAbsolute => {
                 let height = self.timelock_height.ok_or_else(|| {
                     anyhow::anyhow!("Block height required for absolute timelock")
                 })?;
-                Ok(SpendingCondition::TimelockAbsolute {
-                    lock_height: height,
-                    recipient_pubkey: *recipient_pubkey,
-                })
+                format!("and(pk({}),after({}))", recipient, height)
             }
 
             ScriptTypeInput::TimelockRelative => {
                 let blocks = self
                     .timelock_blocks
                     .ok_or_else(|| anyhow::anyhow!("Block count required for relative timelock"))?;
-                Ok(SpendingCondition::TimelockRelative {
-                    blocks,
-                    recipient_pubkey: *recipient_pubkey,
-                })
+                format!("and(pk({}),older({}))", recipient, blocks)
             }
 
             ScriptTypeInput::Recovery => {
@@ -367,11 +389,12 @@ impl ScriptParams {
                 let recovery_pk = self
                     .recovery_pubkey
                     .ok_or_else(|| anyhow::anyhow!("Recovery pubkey required"))?;
-                Ok(SpendingCondition::Recovery {
-                    owner_pubkey: *recipient_pubkey,
-                    recovery_pubkey: recovery_pk,
-                    timeout_height: timeout,
-                })
+                format!(
+                    "or(pk({}),and(pk({}),after({})))",
+                    recipient,
+                    hex::encode(recovery_pk),
+                    timeout
+                )
             }
 
             ScriptTypeInput::Htlc => {
@@ -384,95 +407,109 @@ impl ScriptParams {
                 let refund_pk = self
                     .htlc_refund_pubkey
                     .ok_or_else(|| anyhow::anyhow!("Refund pubkey required for HTLC"))?;
-                Ok(SpendingCondition::Htlc {
-                    hash,
-                    recipient_pubkey: *recipient_pubkey,
-                    refund_pubkey: refund_pk,
-                    timeout_height: timeout,
-                })
+                format!(
+                    "or(and(pk({}),sha256({})),and(pk({}),after({})))",
+                    recipient,
+                    hex::encode(hash),
+                    hex::encode(refund_pk),
+                    timeout
+                )
             }
-        }
+
+            ScriptTypeInput::Policy => self
+                .policy
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("Policy required"))?,
+        };
+        Ok(SpendingPolicy::parse(&text)?.with_labels(self.script_type.leaf_labels()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bitcoin::opcodes::all::*;
 
-    fn test_pubkey() -> [u8; 32] {
-        let mut pubkey = [0u8; 32];
-        pubkey[31] = 1;
-        pubkey
+    /// x-only keys of G and 2G
+    const KEY_A: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const KEY_B: &str = "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+
+    fn key(hex_str: &str) -> [u8; 32] {
+        parse_pubkey_hex(hex_str).unwrap()
     }
 
     fn test_internal_key() -> XOnlyPublicKey {
-        let bytes = hex::decode("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
-            .unwrap();
-        XOnlyPublicKey::from_slice(&bytes).unwrap()
+        XOnlyPublicKey::from_slice(&key(KEY_A)).unwrap()
     }
 
     #[test]
-    fn test_script_building() {
-        let pubkey = test_pubkey();
-        let hash = [0xab; 32];
-
-        // CLTV script
-        let cltv = SpendingCondition::build_cltv_script(800000, &pubkey);
-        assert!(cltv.as_bytes().contains(&0xb1)); // OP_CLTV
-        assert!(cltv.as_bytes().contains(&0xac)); // OP_CHECKSIG
-
-        // CSV script
-        let csv = SpendingCondition::build_csv_script(144, &pubkey);
-        assert!(csv.as_bytes().contains(&0xb2)); // OP_CSV
-
-        // Owner script (no timelock)
-        let owner = SpendingCondition::build_owner_script(&pubkey);
-        assert!(!owner.as_bytes().contains(&0xb1));
-        assert!(!owner.as_bytes().contains(&0xb2));
-
-        // HTLC claim script
-        let htlc = SpendingCondition::build_htlc_claim_script(&hash, &pubkey);
-        assert!(htlc.as_bytes().contains(&0xa8)); // OP_SHA256
-        assert!(htlc.as_bytes().contains(&0x88)); // OP_EQUALVERIFY
+    fn test_policy_compilation() {
+        // Single branch: one leaf with CLTV and a signature check
+        let policy = SpendingPolicy::parse(&format!("and(pk({}),after(800000))", KEY_A)).unwrap();
+        let leaves = policy.leaves().unwrap();
+        assert_eq!(leaves.len(), 1);
+        let script = leaves[0].script();
+        assert!(script.as_bytes().contains(&OP_CLTV.to_u8()));
+        assert!(script.as_bytes().contains(&OP_CHECKSIGVERIFY.to_u8()));
+        assert!(leaves[0].max_satisfaction_size().unwrap() >= 64);
+
+        // Top-level or: one leaf per branch, weighted by odds
+        let policy = SpendingPolicy::parse(&format!(
+            "or(9@pk({}),1@and(pk({}),older(4320)))",
+            KEY_A, KEY_B
+        ))
+        .unwrap();
+        let leaves = policy.leaves().unwrap();
+        assert_eq!(leaves.len(), 2);
+        assert!((leaves[0].probability - 0.9).abs() < 1e-9);
+        assert!(leaves[1].script().as_bytes().contains(&OP_CSV.to_u8()));
+
+        // Hash locks compile to SHA256 checks
+        let policy = SpendingPolicy::parse(&format!(
+            "or(and(pk({}),sha256({})),and(pk({}),after(100)))",
+            KEY_A,
+            hex::encode([0xab; 32]),
+            KEY_B
+        ))
+        .unwrap();
+        let leaves = policy.leaves().unwrap();
+        assert!(leaves[0].script().as_bytes().contains(&OP_SHA256.to_u8()));
+
+        // Invalid policies are user errors
+        assert!(SpendingPolicy::parse("and(pk(zz),after(1))").is_err());
+        assert!(SpendingPolicy::parse("after(100)").is_err());
+        assert!(SpendingPolicy::parse(&format!("pk({})", KEY_A))
+            .unwrap()
+            .description()
+            .starts_with("pk("));
     }
 
     #[test]
-    fn test_spending_conditions_and_addresses() {
+    fn test_policies_and_addresses() {
         let internal_key = test_internal_key();
-        let recipient = test_pubkey();
-        let mut recovery_pk = test_pubkey();
-        recovery_pk[0] = 0x02;
-
-        // Test all spending condition variants build successfully
-        let conditions: Vec<SpendingCondition> = vec![
-            SpendingCondition::KeyPathOnly,
-            SpendingCondition::TimelockAbsolute {
-                lock_height: 850000,
-                recipient_pubkey: recipient,
-            },
-            SpendingCondition::TimelockRelative {
-                blocks: 144,
-                recipient_pubkey: recipient,
-            },
-            SpendingCondition::Recovery {
-                owner_pubkey: recipient,
-                recovery_pubkey: recovery_pk,
-                timeout_height: 900000,
-            },
-            SpendingCondition::Htlc {
-                hash: [0xde; 32],
-                recipient_pubkey: recipient,
-                refund_pubkey: recovery_pk,
-                timeout_height: 100,
-            },
+
+        let policies = vec![
+            SpendingPolicy::key_path_only(),
+            SpendingPolicy::parse(&format!("and(pk({}),after(850000))", KEY_A)).unwrap(),
+            SpendingPolicy::parse(&format!("and(pk({}),older(144))", KEY_A)).unwrap(),
+            SpendingPolicy::parse(&format!(
+                "or(pk({}),and(pk({}),after(900000)))",
+                KEY_A, KEY_B
+            ))
+            .unwrap(),
         ];
 
-        for condition in &conditions {
-            assert!(condition.build_taproot_spend_info(&internal_key).is_ok());
+        for policy in &policies {
+            assert!(policy.build_taproot_spend_info(&internal_key).is_ok());
         }
+        assert!(policies[0]
+            .build_taproot_spend_info(&internal_key)
+            .unwrap()
+            .merkle_root()
+            .is_none());
 
         // Test address generation for different networks
-        let key_path = &conditions[0];
+        let key_path = &policies[0];
         assert!(key_path
             .to_address(&internal_key, Network::Bitcoin)
             .unwrap()
@@ -484,11 +521,11 @@ mod tests {
             .to_string()
             .starts_with("tb1p"));
 
-        // Different conditions produce different addresses
-        let addr1 = conditions[0]
+        // Different policies produce different addresses
+        let addr1 = policies[0]
             .to_address(&internal_key, Network::Testnet)
             .unwrap();
-        let addr2 = conditions[1]
+        let addr2 = policies[1]
             .to_address(&internal_key, Network::Testnet)
             .unwrap();
         assert_ne!(addr1.to_string(), addr2.to_string());
@@ -501,9 +538,8 @@ mod tests {
 
     #[test]
     fn test_script_params_parsing() {
-        let valid_pk = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
         let valid_hash = "0000000000000000000000000000000000000000000000000000000000000001";
-        let recipient = test_pubkey();
+        let recipient = key(KEY_A);
 
         // Valid parsing for all types
         assert!(ScriptParams::from_strings(ScriptTypeInput::None, "", "", "", "", "", "").is_ok());
@@ -535,30 +571,14 @@ mod tests {
             .timelock_blocks,
             Some(144)
         );
-        assert!(ScriptParams::from_strings(
-            ScriptTypeInput::Recovery,
-            "",
-            "",
-            "4320",
-            valid_pk,
-            "",
-            ""
-        )
-        .unwrap()
-        .recovery_pubkey
-        .is_some());
-        assert!(ScriptParams::from_strings(
-            ScriptTypeInput::Htlc,
-            "",
-            "",
-            "144",
-            "",
-            valid_hash,
-            valid_pk
-        )
-        .unwrap()
-        .htlc_hash
-        .is_some());
+        let recovery =
+            ScriptParams::from_strings(ScriptTypeInput::Recovery, "", "", "4320", KEY_B, "", "")
+                .unwrap();
+        assert!(recovery.recovery_pubkey.is_some());
+        let htlc =
+            ScriptParams::from_strings(ScriptTypeInput::Htlc, "", "", "144", "", valid_hash, KEY_B)
+                .unwrap();
+        assert!(htlc.htlc_hash.is_some());
 
         // Invalid inputs
         assert!(ScriptParams::from_strings(
@@ -583,18 +603,47 @@ mod tests {
         .is_err());
         assert!(parse_pubkey_hex("short").is_err());
 
-        // Conversion to SpendingCondition
+        // Presets expand to policies
         let params = ScriptParams {
             script_type: ScriptTypeInput::TimelockAbsolute,
             timelock_height: Some(850000),
             ..Default::default()
         };
-        match params.to_spending_condition(&recipient).unwrap() {
-            SpendingCondition::TimelockAbsolute { lock_height, .. } => {
-                assert_eq!(lock_height, 850000)
-            }
-            _ => panic!("Wrong type"),
-        }
+        assert_eq!(
+            params.to_policy(&recipient).unwrap().description(),
+            format!("and(pk({}),after(850000))", KEY_A)
+        );
+        assert_eq!(
+            recovery
+                .to_policy(&recipient)
+                .unwrap()
+                .leaves()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            htlc.to_policy(&recipient).unwrap().leaves().unwrap().len(),
+            2
+        );
+        assert!(ScriptParams::default()
+            .to_policy(&recipient)
+            .unwrap()
+            .leaves()
+            .unwrap()
+            .is_empty());
+
+        // Arbitrary policies
+        let params = ScriptParams {
+            script_type: ScriptTypeInput::Policy,
+            policy: Some(format!("and(pk({}),older(4320))", KEY_B)),
+            ..Default::default()
+        };
+        assert!(params.to_policy(&recipient).is_ok());
+        assert_eq!(
+            ScriptTypeInput::from_name("policy").unwrap(),
+            ScriptTypeInput::Policy
+        );
 
         // Missing required field
         let bad_params = ScriptParams {
@@ -602,6 +651,11 @@ mod tests {
             timelock_height: None,
             ..Default::default()
         };
-        assert!(bad_params.to_spending_condition(&recipient).is_err());
+        assert!(bad_params.to_policy(&recipient).is_err());
+        let bad_params = ScriptParams {
+            script_type: ScriptTypeInput::Policy,
+            ..Default::default()
+        };
+        assert!(bad_params.to_policy(&recipient).is_err());
     }
 }
@@ -0,0 +1,513 @@
+//! Taproot Tree Persistence
+//!
+//! A script-augmented P2TR output commits to a merkle root of script leaves.
+//! Once the funding transaction is created, that tree cannot be recovered from
+//! the chain, so without it the script paths (and the tweak needed for a
+//! key-path spend) are lost. This module stores the full spend info for every
+//! script address a wallet hands out, keyed by scriptPubKey:
+//!
+//! - **internal key**: x-only key the output was tweaked from
+//! - **merkle root**: root of the script tree (None for key-path only)
+//! - **leaves**: each script with its leaf version and control block, plus
+//!   the label and policy branch it was compiled from
+//!
+//! Records live in `taproot_trees.json` in the wallet directory.
+
+use crate::btc::hd_address::{load_hd_context, parse_network};
+use crate::btc::taproot_scripts::{ScriptParams, SpendingPolicy};
+use crate::crypto::hd::{derive_at_path, DerivationPath};
+use crate::protocol::keygen::require_secp256k1;
+use crate::storage::Storage;
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use bitcoin::hashes::Hash;
+use bitcoin::key::XOnlyPublicKey;
+use bitcoin::taproot::{ControlBlock, LeafVersion, TaprootSpendInfo};
+use bitcoin::{Address, Network, Script, ScriptBuf, Witness};
+use schnorr_fun::frost::SharedKey;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// File in the wallet directory holding all persisted taproot trees
+pub const TAPROOT_TREES_FILE: &str = "taproot_trees.json";
+
+/// A single script leaf with everything needed to spend it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TaprootLeafRecord {
+    /// Leaf script (hex)
+    pub script: String,
+    /// Leaf version (0xc0 for tapscript)
+    pub leaf_version: u8,
+    /// Serialized control block proving the leaf is in the tree (hex)
+    pub control_block: String,
+    /// What the leaf is for, e.g. "HTLC claim"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Policy branch the leaf was compiled from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+}
+
+impl TaprootLeafRecord {
+    /// The label, or the policy branch, or the script
+    pub fn summary(&self) -> String {
+        match (&self.label, &self.policy) {
+            (Some(label), Some(policy)) => format!("{}: {}", label, policy),
+            (Some(label), None) => label.clone(),
+            (None, Some(policy)) => policy.clone(),
+            (None, None) => self.script.clone(),
+        }
+    }
+}
+
+/// Spend info for one P2TR output
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TaprootTreeRecord {
+    /// Address the output pays to
+    pub address: String,
+    /// Output scriptPubKey (hex) - the lookup key
+    pub script_pubkey: String,
+    /// Internal (untweaked) x-only key (hex)
+    pub internal_key: String,
+    /// Script tree merkle root (hex), None for key-path only outputs
+    pub merkle_root: Option<String>,
+    /// Human readable description of the spending condition
+    pub description: String,
+    /// HD path (change, index) of the internal key, None for the root key
+    #[serde(default)]
+    pub derivation_path: Option<(u32, u32)>,
+    /// Script leaves with control blocks
+    pub leaves: Vec<TaprootLeafRecord>,
+}
+
+impl TaprootTreeRecord {
+    /// Capture the full spend info for an output
+    pub fn from_spend_info(
+        spend_info: &TaprootSpendInfo,
+        network: Network,
+        description: &str,
+        derivation_path: Option<(u32, u32)>,
+    ) -> Result<Self> {
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let address = Address::p2tr(
+            &secp,
+            spend_info.internal_key(),
+            spend_info.merkle_root(),
+            network,
+        );
+        let script_pubkey = ScriptBuf::new_p2tr_tweaked(spend_info.output_key());
+
+        let mut leaves = Vec::new();
+        for (script, version) in spend_info.script_map().keys() {
+            let control_block = spend_info
+                .control_block(&(script.clone(), *version))
+                .ok_or_else(|| anyhow::anyhow!("Missing control block for leaf"))?;
+            leaves.push(TaprootLeafRecord {
+                script: hex::encode(script.as_bytes()),
+                leaf_version: version.to_consensus(),
+                control_block: hex::encode(control_block.serialize()),
+                label: None,
+                policy: None,
+            });
+        }
+
+        Ok(Self {
+            address: address.to_string(),
+            script_pubkey: hex::encode(script_pubkey.as_bytes()),
+            internal_key: hex::encode(spend_info.internal_key().serialize()),
+            merkle_root: spend_info
+                .merkle_root()
+                .map(|root| hex::encode(root.to_byte_array())),
+            description: description.to_string(),
+            derivation_path,
+            leaves,
+        })
+    }
+
+    /// Internal key as a bitcoin x-only key
+    pub fn internal_key(&self) -> Result<XOnlyPublicKey> {
+        let bytes = hex::decode(&self.internal_key).context("Invalid internal key hex")?;
+        XOnlyPublicKey::from_slice(&bytes).context("Invalid internal key")
+    }
+
+    /// Merkle root as raw bytes
+    pub fn merkle_root_bytes(&self) -> Result<Option<[u8; 32]>> {
+        self.merkle_root
+            .as_ref()
+            .map(|root| {
+                hex::decode(root)
+                    .context("Invalid merkle root hex")?
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Merkle root must be 32 bytes"))
+            })
+            .transpose()
+    }
+
+    /// Look up the leaf for a script (default tapscript version)
+    pub fn leaf_for_script(&self, script: &Script) -> Option<&TaprootLeafRecord> {
+        let script_hex = hex::encode(script.as_bytes());
+        self.leaves.iter().find(|leaf| leaf.script == script_hex)
+    }
+
+    /// Build the script-path witness for a leaf: `<stack items...> <script> <control block>`
+    ///
+    /// `stack` holds the satisfying items (signatures, preimages) in push order.
+    pub fn script_path_witness(&self, leaf_index: usize, stack: &[Vec<u8>]) -> Result<Witness> {
+        let leaf = self.leaves.get(leaf_index).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Leaf {} not found (tree has {} leaves)",
+                leaf_index,
+                self.leaves.len()
+            )
+        })?;
+
+        let script = hex::decode(&leaf.script).context("Invalid leaf script hex")?;
+        let control_block_bytes =
+            hex::decode(&leaf.control_block).context("Invalid control block hex")?;
+        // Validate before handing out a witness the network would reject
+        ControlBlock::decode(&control_block_bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid control block: {:?}", e))?;
+        LeafVersion::from_consensus(leaf.leaf_version)
+            .map_err(|e| anyhow::anyhow!("Invalid leaf version: {:?}", e))?;
+
+        let mut witness = Witness::new();
+        for item in stack {
+            witness.push(item);
+        }
+        witness.push(script);
+        witness.push(control_block_bytes);
+        Ok(witness)
+    }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Load all persisted taproot trees, keyed by scriptPubKey hex
+pub fn load_taproot_trees(storage: &dyn Storage) -> Result<BTreeMap<String, TaprootTreeRecord>> {
+    if !storage.exists(TAPROOT_TREES_FILE) {
+        return Ok(BTreeMap::new());
+    }
+    let json = String::from_utf8(storage.read(TAPROOT_TREES_FILE)?)?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", TAPROOT_TREES_FILE))
+}
+
+/// Persist a taproot tree (replaces any record for the same scriptPubKey)
+pub fn save_taproot_tree(storage: &dyn Storage, record: &TaprootTreeRecord) -> Result<()> {
+    let mut trees = load_taproot_trees(storage)?;
+    trees.insert(record.script_pubkey.clone(), record.clone());
+    storage.write(
+        TAPROOT_TREES_FILE,
+        serde_json::to_string_pretty(&trees)?.as_bytes(),
+    )
+}
+
+/// Find the persisted tree for an output scriptPubKey
+pub fn find_taproot_tree(
+    storage: &dyn Storage,
+    script_pubkey: &Script,
+) -> Result<Option<TaprootTreeRecord>> {
+    let mut trees = load_taproot_trees(storage)?;
+    Ok(trees.remove(&hex::encode(script_pubkey.as_bytes())))
+}
+
+/// Build the spend info for a spending policy, persist it and return the record
+pub fn register_spending_policy(
+    storage: &dyn Storage,
+    policy: &SpendingPolicy,
+    internal_key: &XOnlyPublicKey,
+    network: Network,
+    derivation_path: Option<(u32, u32)>,
+) -> Result<TaprootTreeRecord> {
+    let spend_info = policy.build_taproot_spend_info(internal_key)?;
+    let mut record = TaprootTreeRecord::from_spend_info(
+        &spend_info,
+        network,
+        &policy.description(),
+        derivation_path,
+    )?;
+    // Records follow the tree's order, so match compiled leaves by script
+    let compiled = policy.leaves()?;
+    for leaf in &mut record.leaves {
+        if let Some(compiled) = compiled
+            .iter()
+            .find(|compiled| hex::encode(compiled.script().as_bytes()) == leaf.script)
+        {
+            leaf.label = compiled.label.clone();
+            leaf.policy = Some(compiled.policy.to_string());
+        }
+    }
+    save_taproot_tree(storage, &record)?;
+    Ok(record)
+}
+
+/// The wallet's x-only group key: the root key, or the HD-derived key at
+/// `derivation_path`
+pub fn wallet_key(storage: &dyn Storage, derivation_path: Option<(u32, u32)>) -> Result<[u8; 32]> {
+    match derivation_path {
+        Some((change, address_index)) => {
+            let context = load_hd_context(storage)?;
+            let path = DerivationPath {
+                change,
+                address_index,
+            };
+            Ok(derive_at_path(&context, &path)?.public_key.to_xonly_bytes())
+        }
+        None => {
+            require_secp256k1(storage)?;
+            let shared_key_bytes = storage
+                .read("shared_key.bin")
+                .context("No DKG shared key found. Run keygen-finalize first.")?;
+            let shared_key: SharedKey<EvenY> = bincode::deserialize(&shared_key_bytes)?;
+            Ok(shared_key.public_key().to_xonly_bytes())
+        }
+    }
+}
+
+// ============================================================================
+// CLI Core Functions
+// ============================================================================
+
+/// Core function for dkg-script-address command
+///
+/// Builds a script-augmented address on the wallet key (root, or HD-derived when
+/// `derivation_path` is set) and persists its taproot tree. Every entry of
+/// `params` adds its leaves to the one tree. Leaves that need a recipient key
+/// default to the same wallet key.
+pub fn script_address_core(
+    params: &[ScriptParams],
+    recipient_pubkey: Option<[u8; 32]>,
+    derivation_path: Option<(u32, u32)>,
+    network_str: &str,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    let network = parse_network(network_str)?;
+
+    let internal_key_bytes = wallet_key(storage, derivation_path)?;
+    let internal_key = XOnlyPublicKey::from_slice(&internal_key_bytes)?;
+    let recipient = recipient_pubkey.unwrap_or(internal_key_bytes);
+
+    let policy = SpendingPolicy::any_of(
+        params
+            .iter()
+            .map(|params| params.to_policy(&recipient))
+            .collect::<Result<_>>()?,
+    );
+    let leaves = policy.leaves()?;
+    let record =
+        register_spending_policy(storage, &policy, &internal_key, network, derivation_path)?;
+
+    out.push_str("Taproot Script Address\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("Condition: {}\n", record.description));
+    if let Some((change, index)) = derivation_path {
+        out.push_str(&format!("HD path: {}/{}\n", change, index));
+    }
+    out.push_str(&format!("Network: {}\n", network_str));
+    out.push_str(&format!("Internal key: {}\n", record.internal_key));
+    if let Some(root) = &record.merkle_root {
+        out.push_str(&format!("Merkle root: {}\n", root));
+    }
+    out.push_str(&format!("Address: {}\n\n", record.address));
+
+    out.push_str("Leaves:\n");
+    for (i, leaf) in record.leaves.iter().enumerate() {
+        out.push_str(&format!(
+            "  [{}] {}\n",
+            i,
+            leaf.label.as_deref().unwrap_or("Policy leaf")
+        ));
+        out.push_str(&format!("      script: {}\n", leaf.script));
+        if let Some(compiled) = leaves
+            .iter()
+            .find(|compiled| hex::encode(compiled.script().as_bytes()) == leaf.script)
+        {
+            out.push_str(&format!("      miniscript: {}\n", compiled.miniscript));
+            if let Some(size) = compiled.max_satisfaction_size() {
+                out.push_str(&format!(
+                    "      max witness: {} WU plus script and control block\n",
+                    size
+                ));
+            }
+        }
+    }
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!(
+        "🧠 Spend info saved to {} - back it up with the wallet.\n",
+        TAPROOT_TREES_FILE
+    ));
+    out.push_str("   Without it, the script paths of this address cannot be spent.\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&record)?,
+    })
+}
+
+/// Core function for dkg-list-script-addresses command
+pub fn list_script_addresses_core(storage: &dyn Storage) -> Result<CommandResult> {
+    let mut out = String::new();
+    let trees = load_taproot_trees(storage)?;
+
+    out.push_str("Taproot Script Addresses\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    if trees.is_empty() {
+        out.push_str("No script addresses recorded.\n");
+    }
+    for record in trees.values() {
+        let path = record
+            .derivation_path
+            .map(|(change, index)| format!("{}/{}", change, index))
+            .unwrap_or_else(|| "root".to_string());
+        out.push_str(&format!(
+            "  {}  [{}] {} ({} leaves)\n",
+            record.address,
+            path,
+            record.description,
+            record.leaves.len()
+        ));
+        for (i, leaf) in record.leaves.iter().enumerate() {
+            if leaf.label.is_some() || leaf.policy.is_some() {
+                out.push_str(&format!("      [{}] {}\n", i, leaf.summary()));
+            }
+        }
+    }
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+
+    let records: Vec<&TaprootTreeRecord> = trees.values().collect();
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&records)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_taproot_tree_persistence() {
+        let internal_key = XOnlyPublicKey::from_slice(
+            &hex::decode("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap(),
+        )
+        .unwrap();
+        let policy = SpendingPolicy::parse(&format!(
+            "or(pk({}),and(pk({}),after(900000)))",
+            "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+        ))
+        .unwrap();
+
+        let storage = MemoryStorage::new();
+        let record = register_spending_policy(
+            &storage,
+            &policy,
+            &internal_key,
+            Network::Testnet,
+            Some((0, 3)),
+        )
+        .unwrap();
+
+        // Record matches the address the policy produces
+        let address = policy.to_address(&internal_key, Network::Testnet).unwrap();
+        assert_eq!(record.address, address.to_string());
+        assert_eq!(record.leaves.len(), 2);
+        assert!(record.merkle_root_bytes().unwrap().is_some());
+
+        // Lookup by scriptPubKey returns the stored tree
+        let found = find_taproot_tree(&storage, &address.script_pubkey())
+            .unwrap()
+            .unwrap();
+        assert_eq!(found, record);
+        assert_eq!(found.derivation_path, Some((0, 3)));
+
+        // Control blocks verify against the output key
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let output_key = policy
+            .build_taproot_spend_info(&internal_key)
+            .unwrap()
+            .output_key()
+            .to_x_only_public_key();
+        for leaf in &found.leaves {
+            let cb = ControlBlock::decode(&hex::decode(&leaf.control_block).unwrap()).unwrap();
+            let script = ScriptBuf::from_bytes(hex::decode(&leaf.script).unwrap());
+            assert!(cb.verify_taproot_commitment(&secp, output_key, &script));
+        }
+
+        // Script-path witness: stack items, then script, then control block
+        let witness = found.script_path_witness(0, &[vec![0u8; 64]]).unwrap();
+        assert_eq!(witness.len(), 3);
+        assert!(found.script_path_witness(5, &[]).is_err());
+
+        // Unknown outputs are not found
+        let key_path = SpendingPolicy::key_path_only()
+            .script_pubkey(&internal_key)
+            .unwrap();
+        assert!(find_taproot_tree(&storage, &key_path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_multi_leaf_tree_keeps_leaf_labels() {
+        use crate::btc::taproot_scripts::{ScriptParams, ScriptTypeInput};
+
+        let wallet = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let other = "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+        let internal_key = XOnlyPublicKey::from_slice(&hex::decode(wallet).unwrap()).unwrap();
+        let recipient: [u8; 32] = hex::decode(wallet).unwrap().try_into().unwrap();
+
+        let htlc = ScriptParams::from_strings(
+            ScriptTypeInput::Htlc,
+            "",
+            "",
+            "1000",
+            "",
+            &"ab".repeat(32),
+            other,
+        )
+        .unwrap();
+        let recovery =
+            ScriptParams::from_strings(ScriptTypeInput::Recovery, "", "", "900000", other, "", "")
+                .unwrap();
+        let policy = SpendingPolicy::any_of(vec![
+            htlc.to_policy(&recipient).unwrap(),
+            recovery.to_policy(&recipient).unwrap(),
+        ]);
+
+        let storage = MemoryStorage::new();
+        let record =
+            register_spending_policy(&storage, &policy, &internal_key, Network::Testnet, None)
+                .unwrap();
+        assert_eq!(record.leaves.len(), 4);
+        let mut labels: Vec<&str> = record
+            .leaves
+            .iter()
+            .map(|leaf| leaf.label.as_deref().unwrap())
+            .collect();
+        labels.sort();
+        assert_eq!(labels, ["HTLC claim", "HTLC refund", "Owner", "Recovery"]);
+        let claim = record
+            .leaves
+            .iter()
+            .find(|leaf| leaf.label.as_deref() == Some("HTLC claim"))
+            .unwrap();
+        assert!(claim.policy.as_ref().unwrap().contains("sha256("));
+        assert!(claim.summary().starts_with("HTLC claim: and("));
+
+        // Metadata survives the round trip; older records without it still load
+        let found = find_taproot_tree(&storage, &policy.script_pubkey(&internal_key).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(found, record);
+        let mut json = serde_json::to_value(&record.leaves[0]).unwrap();
+        json.as_object_mut().unwrap().remove("label");
+        json.as_object_mut().unwrap().remove("policy");
+        let legacy: TaprootLeafRecord = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.label, None);
+        assert_eq!(legacy.summary(), legacy.script);
+    }
+}
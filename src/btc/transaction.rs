@@ -1,12 +1,21 @@
 //! Bitcoin Transaction Building and Broadcasting
 //!
 //! This module implements full Bitcoin transaction lifecycle for Taproot:
-//! - UTXO fetching from mempool.space API
+//! - UTXO fetching from an Esplora API (mempool.space unless configured) or
+//!   an Electrum server
 //! - Transaction construction
 //! - BIP341 sighash computation
 //! - Schnorr signing
 //! - Transaction broadcasting
 
+use crate::btc::amount::format_amount;
+use crate::btc::broadcast::{self, BroadcastReport};
+use crate::btc::electrum::{self, ElectrumBackend};
+use crate::btc::esplora;
+use crate::btc::http;
+use crate::btc::simulate;
+use crate::crypto::secret::SecretString;
+use crate::protocol::keygen::require_secp256k1;
 use crate::storage::{FileStorage, Storage};
 use crate::CommandResult;
 use anyhow::{Context, Result};
@@ -18,19 +27,14 @@ use bitcoin::script::ScriptBuf;
 use bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
 use bitcoin::transaction::Version;
 use bitcoin::{Amount, Network, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
-use reqwest::blocking::Client;
 use secp256kfun::prelude::*;
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
 // sha2 no longer needed - using shared tagged_hash from crypto_helpers
 use std::str::FromStr;
 
 const STATE_DIR: &str = ".frost_state";
 
-// Mempool.space API endpoints
-const MEMPOOL_TESTNET_API: &str = "https://mempool.space/testnet/api";
-const MEMPOOL_SIGNET_API: &str = "https://mempool.space/signet/api";
-const MEMPOOL_MAINNET_API: &str = "https://mempool.space/api";
-
 // ============================================================================
 // API Response Types
 // ============================================================================
@@ -50,6 +54,15 @@ pub struct UtxoStatus {
     pub block_height: Option<u64>,
 }
 
+/// Confirmation status of a transaction (`GET /tx/:txid/status`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TxStatus {
+    pub confirmed: bool,
+    pub block_height: Option<u64>,
+    pub block_hash: Option<String>,
+    pub block_time: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 pub struct FeeEstimate {
@@ -96,25 +109,22 @@ pub struct BalanceOutput {
 // Stored Key Structure (must match bitcoin_schnorr.rs)
 // ============================================================================
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize)]
 struct StoredBitcoinKey {
     secret_key_bytes: Vec<u8>,
     public_key_bytes: Vec<u8>,
 }
 
+impl Drop for StoredBitcoinKey {
+    fn drop(&mut self) {
+        self.secret_key_bytes.zeroize();
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
-fn get_api_base(network: Network) -> &'static str {
-    match network {
-        Network::Bitcoin => MEMPOOL_MAINNET_API,
-        Network::Testnet => MEMPOOL_TESTNET_API,
-        Network::Signet => MEMPOOL_SIGNET_API,
-        _ => MEMPOOL_TESTNET_API,
-    }
-}
-
 fn network_name(network: Network) -> &'static str {
     match network {
         Network::Bitcoin => "mainnet",
@@ -171,6 +181,7 @@ fn sign_bip340(
     }
 
     let k_bytes = nonce_hash(&masked_secret, pubkey_bytes, message);
+    masked_secret.zeroize();
     let mut k_scalar: Scalar<Secret, NonZero> = Scalar::from_bytes(k_bytes)
         .ok_or_else(|| anyhow::anyhow!("Invalid nonce bytes"))?
         .non_zero()
@@ -209,19 +220,28 @@ fn sign_bip340(
 
 /// Fetch UTXOs for an address
 pub fn fetch_utxos(address: &str, network: Network) -> Result<Vec<UtxoResponse>> {
-    let client = Client::new();
-    let api_base = get_api_base(network);
-    let url = format!("{}/address/{}/utxo", api_base, address);
+    if simulate::is_simulated() {
+        return simulate::fetch_utxos(address);
+    }
+    if let Some(server) = electrum::configured(network)? {
+        return ElectrumBackend { server }.utxos(address);
+    }
+    let client = http::client();
+    let endpoint = esplora::resolve(network)?;
 
-    let response = client
-        .get(&url)
-        .send()
-        .context("Failed to fetch UTXOs from mempool.space")?;
+    let response = http::send(
+        &client,
+        endpoint.get(&client, &format!("/address/{}/utxo", address)),
+    )
+    .context("Failed to fetch UTXOs")?;
 
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().unwrap_or_default();
-        anyhow::bail!("API error {}: {}", status, body);
+        anyhow::bail!(crate::error::Error::Network(format!(
+            "API error {}: {}",
+            status, body
+        )));
     }
 
     let utxos: Vec<UtxoResponse> = response.json().context("Failed to parse UTXO response")?;
@@ -230,13 +250,16 @@ pub fn fetch_utxos(address: &str, network: Network) -> Result<Vec<UtxoResponse>>
 
 /// Fetch recommended fees
 pub fn fetch_fee_estimates(network: Network) -> Result<FeeEstimate> {
-    let client = Client::new();
-    let api_base = get_api_base(network);
-    let url = format!("{}/v1/fees/recommended", api_base);
+    if simulate::is_simulated() {
+        return Ok(simulate::fetch_fee_estimates());
+    }
+    if let Some(server) = electrum::configured(network)? {
+        return server.connect()?.fee_estimates();
+    }
+    let client = http::client();
+    let endpoint = esplora::resolve(network)?;
 
-    let response = client
-        .get(&url)
-        .send()
+    let response = http::send(&client, endpoint.get(&client, "/v1/fees/recommended"))
         .context("Failed to fetch fee estimates")?;
 
     if !response.status().is_success() {
@@ -254,28 +277,243 @@ pub fn fetch_fee_estimates(network: Network) -> Result<FeeEstimate> {
     Ok(fees)
 }
 
-/// Broadcast a transaction
-pub fn broadcast_transaction(raw_tx_hex: &str, network: Network) -> Result<String> {
-    let client = Client::new();
-    let api_base = get_api_base(network);
-    let url = format!("{}/tx", api_base);
+/// Fetch confirmation status of a transaction
+///
+/// Returns `Ok(None)` if the backend does not know the txid (not yet
+/// propagated, or evicted from the mempool).
+pub fn fetch_tx_status(txid: &str, network: Network) -> Result<Option<TxStatus>> {
+    if simulate::is_simulated() {
+        return simulate::fetch_tx_status(txid);
+    }
+    if let Some(server) = electrum::configured(network)? {
+        return server.connect()?.tx_status(txid);
+    }
+    let client = http::client();
+    let endpoint = esplora::resolve(network)?;
 
-    let response = client
-        .post(&url)
-        .body(raw_tx_hex.to_string())
-        .send()
-        .context("Failed to broadcast transaction")?;
+    let response = http::send(
+        &client,
+        endpoint.get(&client, &format!("/tx/{}/status", txid)),
+    )
+    .context("Failed to fetch transaction status")?;
 
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().unwrap_or_default();
-        anyhow::bail!("Broadcast failed {}: {}", status, body);
+        anyhow::bail!(crate::error::Error::Network(format!(
+            "API error {}: {}",
+            status, body
+        )));
+    }
+
+    let status: TxStatus = response
+        .json()
+        .context("Failed to parse transaction status")?;
+    Ok(Some(status))
+}
+
+/// Fetch a transaction by txid
+///
+/// Returns `Ok(None)` if the backend does not know it. The simulated chain
+/// keeps no transactions, so nothing is found while simulating.
+pub fn fetch_transaction(txid: &str, network: Network) -> Result<Option<Transaction>> {
+    if simulate::is_simulated() {
+        return Ok(None);
+    }
+    if let Some(server) = electrum::configured(network)? {
+        return server.connect()?.transaction(txid).map(Some);
+    }
+    let client = http::client();
+    let endpoint = esplora::resolve(network)?;
+
+    let response = http::send(&client, endpoint.get(&client, &format!("/tx/{}/hex", txid)))
+        .context("Failed to fetch transaction")?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        anyhow::bail!(crate::error::Error::Network(format!(
+            "API error {}: {}",
+            status, body
+        )));
+    }
+
+    let hex = response.text().context("Failed to read transaction")?;
+    let tx = bitcoin::consensus::encode::deserialize_hex(hex.trim())
+        .context("Failed to parse transaction")?;
+    Ok(Some(tx))
+}
+
+/// Txid of the transaction spending `outpoint`, None while it is unspent
+///
+/// Used to tell a transaction that fell out of the mempool from one that was
+/// replaced by another spend of the same coins.
+pub fn fetch_spender(outpoint: &OutPoint, network: Network) -> Result<Option<String>> {
+    if simulate::is_simulated() {
+        return Ok(None);
+    }
+    if let Some(server) = electrum::configured(network)? {
+        return server.connect()?.spender(outpoint);
+    }
+    #[derive(Deserialize)]
+    struct Outspend {
+        spent: bool,
+        txid: Option<String>,
+    }
+    let client = http::client();
+    let endpoint = esplora::resolve(network)?;
+
+    let response = http::send(
+        &client,
+        endpoint.get(
+            &client,
+            &format!("/tx/{}/outspend/{}", outpoint.txid, outpoint.vout),
+        ),
+    )
+    .context("Failed to fetch outspend")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        anyhow::bail!(crate::error::Error::Network(format!(
+            "API error {}: {}",
+            status, body
+        )));
+    }
+
+    let outspend: Outspend = response.json().context("Failed to parse outspend")?;
+    Ok(outspend.txid.filter(|_| outspend.spent))
+}
+
+/// Fetch the current chain tip height
+pub fn fetch_tip_height(network: Network) -> Result<u64> {
+    if simulate::is_simulated() {
+        return simulate::fetch_tip_height();
+    }
+    if let Some(server) = electrum::configured(network)? {
+        return server.connect()?.tip_height();
+    }
+    let client = http::client();
+    let endpoint = esplora::resolve(network)?;
+
+    let response = http::send(&client, endpoint.get(&client, "/blocks/tip/height"))
+        .context("Failed to fetch tip height")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(crate::error::Error::Network(format!(
+            "API error {}",
+            response.status()
+        )));
+    }
+
+    let height = response.text().context("Failed to read tip height")?;
+    height.trim().parse().context("Failed to parse tip height")
+}
+
+/// Block explorer URL for a transaction
+pub fn explorer_tx_url(txid: &str, network: Network) -> String {
+    if simulate::is_simulated() {
+        return format!("({} chain, no explorer) {}", simulate::LABEL, txid);
     }
+    match network {
+        Network::Bitcoin => format!("https://mempool.space/tx/{}", txid),
+        Network::Signet => format!("https://mempool.space/signet/tx/{}", txid),
+        _ => format!("https://mempool.space/testnet/tx/{}", txid),
+    }
+}
+
+/// Broadcast a transaction
+///
+/// Goes to the backend and every extra endpoint in `broadcast.json` (see
+/// [`broadcast`]); succeeds if any of them takes it.
+pub fn broadcast_transaction(raw_tx_hex: &str, network: Network) -> Result<String> {
+    if simulate::is_simulated() {
+        return simulate::broadcast_transaction(raw_tx_hex, network);
+    }
+    broadcast::broadcast_all(raw_tx_hex, network).result()
+}
 
-    let txid = response
-        .text()
-        .context("Failed to get txid from response")?;
-    Ok(txid.trim().to_string())
+/// Chain access used when building and broadcasting wallet transactions
+///
+/// [`MempoolBackend`] talks to the configured Esplora API or Electrum server; tests
+/// substitute an in-memory chain so whole signing sessions run offline.
+pub trait ChainBackend {
+    fn utxos(&self, address: &str) -> Result<Vec<UtxoResponse>>;
+    fn fee_estimates(&self) -> Result<FeeEstimate>;
+    /// Broadcast a signed transaction; returns its txid
+    fn broadcast(&self, raw_tx_hex: &str) -> Result<String>;
+
+    /// Height of the chain tip
+    fn tip_height(&self) -> Result<u64> {
+        anyhow::bail!("This backend cannot report the chain tip")
+    }
+
+    /// Broadcast and report how each endpoint answered
+    fn broadcast_report(&self, raw_tx_hex: &str) -> BroadcastReport {
+        BroadcastReport::single("backend", raw_tx_hex, self.broadcast(raw_tx_hex))
+    }
+
+    /// Confirmation status of a transaction, None if the backend does not know it
+    fn tx_status(&self, _txid: &str) -> Result<Option<TxStatus>> {
+        anyhow::bail!("This backend cannot look up transactions")
+    }
+
+    /// Txid spending `outpoint`, None while it is unspent
+    fn spender(&self, _outpoint: &OutPoint) -> Result<Option<String>> {
+        anyhow::bail!("This backend cannot look up spends")
+    }
+}
+
+/// [`ChainBackend`] over the configured backend for `network` (see [`esplora::resolve`]
+/// and [`electrum::configured`])
+pub struct MempoolBackend {
+    pub network: Network,
+}
+
+impl MempoolBackend {
+    pub fn new(network: Network) -> Self {
+        Self { network }
+    }
+}
+
+impl ChainBackend for MempoolBackend {
+    fn utxos(&self, address: &str) -> Result<Vec<UtxoResponse>> {
+        fetch_utxos(address, self.network)
+    }
+
+    fn fee_estimates(&self) -> Result<FeeEstimate> {
+        fetch_fee_estimates(self.network)
+    }
+
+    fn tip_height(&self) -> Result<u64> {
+        fetch_tip_height(self.network)
+    }
+
+    fn broadcast(&self, raw_tx_hex: &str) -> Result<String> {
+        broadcast_transaction(raw_tx_hex, self.network)
+    }
+
+    fn broadcast_report(&self, raw_tx_hex: &str) -> BroadcastReport {
+        if simulate::is_simulated() {
+            let answer = simulate::broadcast_transaction(raw_tx_hex, self.network);
+            return BroadcastReport::single(simulate::LABEL, raw_tx_hex, answer);
+        }
+        broadcast::broadcast_all(raw_tx_hex, self.network)
+    }
+
+    fn tx_status(&self, txid: &str) -> Result<Option<TxStatus>> {
+        fetch_tx_status(txid, self.network)
+    }
+
+    fn spender(&self, outpoint: &OutPoint) -> Result<Option<String>> {
+        fetch_spender(outpoint, self.network)
+    }
 }
 
 // ============================================================================
@@ -289,15 +527,16 @@ pub fn check_balance_core(network: Network, storage: &dyn Storage) -> Result<Com
     out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     // Load keypair
-    let stored_json = String::from_utf8(
+    let stored_json = SecretString::from_utf8(
         storage
-            .read("bitcoin_keypair.json")
+            .read_secret("bitcoin_keypair.json")
             .context("No keypair found. Run btc-keygen first.")?,
     )?;
     let stored_key: StoredBitcoinKey = serde_json::from_str(&stored_json)?;
 
     let pubkey_bytes: [u8; 32] = stored_key
         .public_key_bytes
+        .as_slice()
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid public key length"))?;
 
@@ -309,7 +548,7 @@ pub fn check_balance_core(network: Network, storage: &dyn Storage) -> Result<Com
     out.push_str(&format!("Network: {}\n", network_name(network)));
     out.push_str(&format!("Address: {}\n\n", address));
 
-    out.push_str("Fetching UTXOs from mempool.space...\n");
+    out.push_str("Fetching UTXOs...\n");
 
     let utxos = fetch_utxos(&address.to_string(), network)?;
 
@@ -320,14 +559,12 @@ pub fn check_balance_core(network: Network, storage: &dyn Storage) -> Result<Com
     out.push_str(&format!("\nTotal UTXOs: {}\n", utxos.len()));
     out.push_str(&format!("Confirmed UTXOs: {}\n", confirmed_utxos.len()));
     out.push_str(&format!(
-        "\nTotal Balance: {} sats ({:.8} BTC)\n",
-        total_balance,
-        total_balance as f64 / 100_000_000.0
+        "\nTotal Balance: {}\n",
+        format_amount(total_balance)
     ));
     out.push_str(&format!(
-        "Confirmed Balance: {} sats ({:.8} BTC)\n",
-        confirmed_balance,
-        confirmed_balance as f64 / 100_000_000.0
+        "Confirmed Balance: {}\n",
+        format_amount(confirmed_balance)
     ));
 
     if !utxos.is_empty() {
@@ -368,11 +605,20 @@ pub fn check_balance_testnet() -> Result<()> {
     let storage = FileStorage::new(STATE_DIR)?;
     let cmd_result = check_balance_core(Network::Testnet, &storage)?;
     println!("{}", cmd_result.output);
+    print_fiat_balance(&cmd_result.result);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("{}\n", cmd_result.result);
     Ok(())
 }
 
+/// Fiat value of a balance result, when fiat display is configured
+fn print_fiat_balance(result_json: &str) {
+    let sats = serde_json::from_str::<BalanceOutput>(result_json).map(|b| b.balance_sats);
+    if let Some(line) = sats.ok().and_then(crate::btc::fiat::balance_line) {
+        println!("Fiat:    {}", line);
+    }
+}
+
 // ============================================================================
 // DKG Balance Check
 // ============================================================================
@@ -385,6 +631,8 @@ pub fn check_dkg_balance_core(network: Network, storage: &dyn Storage) -> Result
     out.push_str("DKG Group Balance Check\n\n");
     out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
+    require_secp256k1(storage)?;
+
     // Load DKG shared key
     let shared_key_bytes = storage
         .read("shared_key.bin")
@@ -405,7 +653,7 @@ pub fn check_dkg_balance_core(network: Network, storage: &dyn Storage) -> Result
     out.push_str(&format!("Network: {}\n", network_name(network)));
     out.push_str(&format!("DKG Address: {}\n\n", address));
 
-    out.push_str("Fetching UTXOs from mempool.space...\n");
+    out.push_str("Fetching UTXOs...\n");
 
     let utxos = fetch_utxos(&address.to_string(), network)?;
 
@@ -416,14 +664,12 @@ pub fn check_dkg_balance_core(network: Network, storage: &dyn Storage) -> Result
     out.push_str(&format!("\nTotal UTXOs: {}\n", utxos.len()));
     out.push_str(&format!("Confirmed UTXOs: {}\n", confirmed_utxos.len()));
     out.push_str(&format!(
-        "\nTotal Balance: {} sats ({:.8} BTC)\n",
-        total_balance,
-        total_balance as f64 / 100_000_000.0
+        "\nTotal Balance: {}\n",
+        format_amount(total_balance)
     ));
     out.push_str(&format!(
-        "Confirmed Balance: {} sats ({:.8} BTC)\n",
-        confirmed_balance,
-        confirmed_balance as f64 / 100_000_000.0
+        "Confirmed Balance: {}\n",
+        format_amount(confirmed_balance)
     ));
 
     if !utxos.is_empty() {
@@ -467,17 +713,16 @@ pub fn check_dkg_balance_testnet(name: &str) -> Result<()> {
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!(
+        anyhow::bail!(crate::error::Error::Storage(format!(
             "Wallet '{}' not found at {}. Did you run keygen-finalize with --name {}?",
-            name,
-            state_dir,
-            name
-        );
+            name, state_dir, name
+        )));
     }
 
     let storage = FileStorage::new(&state_dir)?;
     let cmd_result = check_dkg_balance_core(Network::Testnet, &storage)?;
     println!("{}", cmd_result.output);
+    print_fiat_balance(&cmd_result.result);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("{}\n", cmd_result.result);
     Ok(())
@@ -492,6 +737,7 @@ pub fn send_transaction_core(
     amount_sats: u64,
     fee_rate: Option<u64>, // sats/vbyte
     network: Network,
+    allow_inscribed: bool,
     storage: &dyn Storage,
 ) -> Result<CommandResult> {
     let mut out = String::new();
@@ -500,20 +746,21 @@ pub fn send_transaction_core(
     out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     // Load keypair
-    let stored_json = String::from_utf8(
+    let stored_json = SecretString::from_utf8(
         storage
-            .read("bitcoin_keypair.json")
+            .read_secret("bitcoin_keypair.json")
             .context("No keypair found. Run btc-keygen first.")?,
     )?;
     let stored_key: StoredBitcoinKey = serde_json::from_str(&stored_json)?;
 
     let secret_bytes: [u8; 32] = stored_key
         .secret_key_bytes
-        .clone()
+        .as_slice()
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid secret key length"))?;
     let pubkey_bytes: [u8; 32] = stored_key
         .public_key_bytes
+        .as_slice()
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid public key length"))?;
 
@@ -531,7 +778,7 @@ pub fn send_transaction_core(
     out.push_str(&format!("Network: {}\n", network_name(network)));
     out.push_str(&format!("From: {}\n", from_address));
     out.push_str(&format!("To: {}\n", dest_address));
-    out.push_str(&format!("Amount: {} sats\n\n", amount_sats));
+    out.push_str(&format!("Amount: {}\n\n", format_amount(amount_sats)));
 
     // Fetch UTXOs
     out.push_str("Fetching UTXOs...\n");
@@ -547,6 +794,17 @@ pub fn send_transaction_core(
         anyhow::bail!("No confirmed UTXOs. Wait for confirmations.");
     }
 
+    // Freeze inscribed / rare-sat outputs
+    let confirmed_utxos = crate::btc::inscriptions::filter_protected_utxos(
+        confirmed_utxos,
+        network,
+        allow_inscribed,
+        &mut out,
+    )?;
+    if confirmed_utxos.is_empty() {
+        anyhow::bail!("No spendable UTXOs left after freezing inscribed outputs.");
+    }
+
     let total_available: u64 = confirmed_utxos.iter().map(|u| u.value).sum();
     out.push_str(&format!("Available balance: {} sats\n", total_available));
 
@@ -565,11 +823,11 @@ pub fn send_transaction_core(
     out.push_str(&format!("Estimated fee: {} sats\n\n", estimated_fee));
 
     if total_available < amount_sats + estimated_fee {
-        anyhow::bail!(
-            "Insufficient funds. Need {} sats (amount + fee), have {} sats",
-            amount_sats + estimated_fee,
-            total_available
-        );
+        return Err(crate::error::Error::InsufficientFunds {
+            needed: amount_sats + estimated_fee,
+            available: total_available,
+        }
+        .into());
     }
 
     // Select UTXOs (simple: use all for now)
@@ -701,12 +959,12 @@ pub fn send_transaction_core(
     let raw_tx = bitcoin::consensus::encode::serialize_hex(&tx);
     let txid = tx.compute_txid();
 
-    out.push_str(&format!("\nTransaction built successfully!\n"));
+    out.push_str("\nTransaction built successfully!\n");
     out.push_str(&format!("TxID: {}\n", txid));
     out.push_str(&format!("Size: {} bytes\n", raw_tx.len() / 2));
 
     // Calculate actual fee
-    let actual_fee = selected_amount - amount_sats - change_amount.max(0);
+    let actual_fee = selected_amount - amount_sats - change_amount;
 
     out.push_str(&format!("Actual fee: {} sats\n\n", actual_fee));
 
@@ -715,7 +973,7 @@ pub fn send_transaction_core(
 
     match broadcast_transaction(&raw_tx, network) {
         Ok(broadcast_txid) => {
-            out.push_str(&format!("\nTransaction broadcast successfully!\n"));
+            out.push_str("\nTransaction broadcast successfully!\n");
             out.push_str(&format!("TxID: {}\n", broadcast_txid));
 
             let explorer_url = match network {
@@ -752,13 +1010,19 @@ pub fn send_transaction_core(
 }
 
 /// CLI wrapper for sending on testnet
-pub fn send_testnet(to_address: &str, amount_sats: u64, fee_rate: Option<u64>) -> Result<()> {
+pub fn send_testnet(
+    to_address: &str,
+    amount_sats: u64,
+    fee_rate: Option<u64>,
+    allow_inscribed: bool,
+) -> Result<()> {
     let storage = FileStorage::new(STATE_DIR)?;
     let cmd_result = send_transaction_core(
         to_address,
         amount_sats,
         fee_rate,
         Network::Testnet,
+        allow_inscribed,
         &storage,
     )?;
     println!("{}", cmd_result.output);
@@ -769,10 +1033,21 @@ pub fn send_testnet(to_address: &str, amount_sats: u64, fee_rate: Option<u64>) -
 }
 
 /// CLI wrapper for sending on signet
-pub fn send_signet(to_address: &str, amount_sats: u64, fee_rate: Option<u64>) -> Result<()> {
+pub fn send_signet(
+    to_address: &str,
+    amount_sats: u64,
+    fee_rate: Option<u64>,
+    allow_inscribed: bool,
+) -> Result<()> {
     let storage = FileStorage::new(STATE_DIR)?;
-    let cmd_result =
-        send_transaction_core(to_address, amount_sats, fee_rate, Network::Signet, &storage)?;
+    let cmd_result = send_transaction_core(
+        to_address,
+        amount_sats,
+        fee_rate,
+        Network::Signet,
+        allow_inscribed,
+        &storage,
+    )?;
     println!("{}", cmd_result.output);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("Copy this JSON:");
@@ -0,0 +1,519 @@
+//! Transaction Decoding
+//!
+//! `tx-decode` (and the TUI's Decode Transaction screen) prints a raw
+//! transaction for a human checking what they are asked to sign or what was
+//! broadcast: version, lock time, every input and output, size, and the fee
+//! once the spent outputs are known.
+//!
+//! Spent outputs come from the `prevouts` of a `dkg-build-tx` output when
+//! that is what was pasted, otherwise from the backend (network builds). With
+//! a wallet, inputs and outputs paying one of its scripts ([`ScriptIndex`])
+//! are marked, and what the transaction does to the wallet is summed up.
+
+use crate::btc::amount::format_amount;
+use crate::btc::script_index::ScriptIndex;
+use crate::protocol::dkg_tx::BuildTxOutput;
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::{Address, Network, OutPoint, Script, Transaction, TxOut};
+use serde::{Deserialize, Serialize};
+
+/// Weight a key-path Taproot signature adds to an unsigned input (item
+/// count, length and 64-byte signature)
+const KEY_SPEND_WITNESS_WEIGHT: u64 = 66;
+
+/// An input as decoded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DecodedInput {
+    pub txid: String,
+    pub vout: u32,
+    pub sequence: u32,
+    /// Signals replace-by-fee (BIP-125)
+    pub rbf: bool,
+    pub witness_items: usize,
+    /// Spent amount, if the spent output is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Origin in the wallet's script index, if it spends a wallet script
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+}
+
+/// An output as decoded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DecodedOutput {
+    pub value: u64,
+    /// p2tr, p2wpkh, p2wsh, p2pkh, p2sh, op_return or nonstandard
+    pub script_type: String,
+    pub script_pubkey: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Origin in the wallet's script index, if it pays a wallet script
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+}
+
+/// What a transaction does to one wallet
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WalletEffect {
+    pub name: String,
+    /// Sats spent from the wallet's scripts (known inputs only)
+    pub spent: u64,
+    /// Sats paid to the wallet's scripts
+    pub received: u64,
+    /// Every input was resolved, so `spent` is complete
+    pub complete: bool,
+}
+
+/// Output from tx-decode command
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DecodedTx {
+    pub txid: String,
+    pub wtxid: String,
+    pub version: i32,
+    pub lock_time: u32,
+    pub size: usize,
+    pub weight: u64,
+    pub vsize: u64,
+    /// Every input carries a witness or scriptSig
+    pub signed: bool,
+    /// vsize once signed, counting key-path signatures for unsigned inputs
+    pub signed_vsize: u64,
+    pub inputs: Vec<DecodedInput>,
+    pub outputs: Vec<DecodedOutput>,
+    /// Known once every spent output is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_sats: Option<u64>,
+    /// sat/vB over `signed_vsize`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<WalletEffect>,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// Kind of a scriptPubKey
+pub fn script_type(script: &Script) -> &'static str {
+    if script.is_p2tr() {
+        "p2tr"
+    } else if script.is_p2wpkh() {
+        "p2wpkh"
+    } else if script.is_p2wsh() {
+        "p2wsh"
+    } else if script.is_p2pkh() {
+        "p2pkh"
+    } else if script.is_p2sh() {
+        "p2sh"
+    } else if script.is_op_return() {
+        "op_return"
+    } else {
+        "nonstandard"
+    }
+}
+
+/// Parse a raw transaction (hex) or a `dkg-build-tx` output, with the spent
+/// outputs the latter carries
+pub fn parse_input(data: &str) -> Result<(Transaction, Vec<TxOut>)> {
+    let data = data.trim();
+    if data.starts_with('{') {
+        let built: BuildTxOutput =
+            serde_json::from_str(data).context("Not a dkg-build-tx output")?;
+        let tx = bitcoin::consensus::encode::deserialize_hex(&built.unsigned_tx)
+            .context("Invalid unsigned transaction")?;
+        let prevouts = built
+            .prevouts
+            .iter()
+            .map(|p| p.to_txout())
+            .collect::<Result<Vec<_>>>()?;
+        return Ok((tx, prevouts));
+    }
+    let tx = bitcoin::consensus::encode::deserialize_hex(data)
+        .context("Not a raw transaction in hex")?;
+    Ok((tx, Vec::new()))
+}
+
+/// Describe a lock time
+fn describe_lock_time(lock_time: LockTime) -> String {
+    match lock_time {
+        LockTime::Blocks(height) if height.to_consensus_u32() == 0 => "none".to_string(),
+        LockTime::Blocks(height) => format!("block {}", height),
+        LockTime::Seconds(time) => format!("unix time {}", time),
+    }
+}
+
+/// Decode `tx` for `network`
+///
+/// `prevouts` are the spent outputs in input order, if known (from a
+/// `dkg-build-tx` output); otherwise each is asked of `lookup`, whose
+/// failures leave the input unresolved and are reported in the output.
+pub fn decode_core(
+    tx: &Transaction,
+    prevouts: &[TxOut],
+    network: Network,
+    wallet: Option<(&str, &ScriptIndex)>,
+    lookup: &dyn Fn(&OutPoint) -> Result<Option<TxOut>>,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    let address = |script: &Script| {
+        Address::from_script(script, network)
+            .ok()
+            .map(|a| a.to_string())
+    };
+    let owner = |script: &Script| {
+        wallet.and_then(|(_, index)| index.owner(script).map(|owned| owned.origin.clone()))
+    };
+
+    let unsigned_inputs = tx
+        .input
+        .iter()
+        .filter(|input| input.witness.is_empty() && input.script_sig.is_empty())
+        .count() as u64;
+    let weight = tx.weight().to_wu();
+    let marker = match tx.input.iter().all(|input| input.witness.is_empty()) {
+        true if unsigned_inputs > 0 => 2,
+        _ => 0,
+    };
+    let signed_vsize = (weight + marker + unsigned_inputs * KEY_SPEND_WITNESS_WEIGHT).div_ceil(4);
+
+    out.push_str("Transaction\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("TxID:      {}\n", tx.compute_txid()));
+    out.push_str(&format!("Version:   {}\n", tx.version.0));
+    out.push_str(&format!(
+        "Lock time: {}\n",
+        describe_lock_time(tx.lock_time)
+    ));
+    out.push_str(&format!(
+        "Size:      {} bytes, {} vB, {} WU\n",
+        tx.total_size(),
+        tx.vsize(),
+        weight
+    ));
+    if unsigned_inputs > 0 {
+        out.push_str(&format!(
+            "Unsigned:  {} of {} inputs; about {} vB once signed (key path)\n",
+            unsigned_inputs,
+            tx.input.len(),
+            signed_vsize
+        ));
+    }
+
+    out.push_str(&format!("\nInputs ({}):\n", tx.input.len()));
+    let mut inputs = Vec::with_capacity(tx.input.len());
+    for (i, input) in tx.input.iter().enumerate() {
+        let outpoint = input.previous_output;
+        let spent = match prevouts.get(i) {
+            Some(prevout) => Some(prevout.clone()),
+            None => match lookup(&outpoint) {
+                Ok(found) => found,
+                Err(e) => {
+                    out.push_str(&format!("   ⚠️  #{}: lookup failed: {:#}\n", i, e));
+                    None
+                }
+            },
+        };
+        let decoded = DecodedInput {
+            txid: outpoint.txid.to_string(),
+            vout: outpoint.vout,
+            sequence: input.sequence.0,
+            rbf: input.sequence.is_rbf(),
+            witness_items: input.witness.len(),
+            value: spent.as_ref().map(|s| s.value.to_sat()),
+            address: spent.as_ref().and_then(|s| address(&s.script_pubkey)),
+            wallet: spent.as_ref().and_then(|s| owner(&s.script_pubkey)),
+        };
+
+        out.push_str(&format!("   #{} {}:{}\n", i, decoded.txid, decoded.vout));
+        match (&decoded.value, &decoded.address) {
+            (Some(value), Some(address)) => out.push_str(&format!(
+                "      {} from {}\n",
+                format_amount(*value),
+                address
+            )),
+            (Some(value), None) => out.push_str(&format!("      {}\n", format_amount(*value))),
+            _ => out.push_str("      spent output unknown\n"),
+        }
+        let mut flags = vec![format!("sequence {:#010x}", decoded.sequence)];
+        if decoded.rbf {
+            flags.push("RBF".to_string());
+        }
+        if let Some(lock) = input.sequence.to_relative_lock_time() {
+            flags.push(format!("relative lock {}", lock));
+        }
+        flags.push(match decoded.witness_items {
+            0 if input.script_sig.is_empty() => "unsigned".to_string(),
+            0 => "scriptSig".to_string(),
+            n => format!("{} witness item(s)", n),
+        });
+        out.push_str(&format!("      {}\n", flags.join(", ")));
+        if let Some(origin) = &decoded.wallet {
+            out.push_str(&format!("      ◀ wallet ({})\n", origin));
+        }
+        inputs.push(decoded);
+    }
+
+    out.push_str(&format!("\nOutputs ({}):\n", tx.output.len()));
+    let mut outputs = Vec::with_capacity(tx.output.len());
+    for (i, output) in tx.output.iter().enumerate() {
+        let decoded = DecodedOutput {
+            value: output.value.to_sat(),
+            script_type: script_type(&output.script_pubkey).to_string(),
+            script_pubkey: hex::encode(output.script_pubkey.as_bytes()),
+            address: address(&output.script_pubkey),
+            wallet: owner(&output.script_pubkey),
+        };
+        let payee = match &decoded.address {
+            Some(address) => address.clone(),
+            None => format!("{} {}", decoded.script_type, decoded.script_pubkey),
+        };
+        out.push_str(&format!(
+            "   #{} {} to {}\n",
+            i,
+            format_amount(decoded.value),
+            payee
+        ));
+        if let Some(origin) = &decoded.wallet {
+            out.push_str(&format!("      ◀ wallet ({})\n", origin));
+        }
+        outputs.push(decoded);
+    }
+
+    let paid: u64 = outputs.iter().map(|o| o.value).sum();
+    let spent: Option<u64> = inputs.iter().map(|i| i.value).sum();
+    let fee_sats = spent.and_then(|spent| spent.checked_sub(paid));
+    let fee_rate = fee_sats.map(|fee| fee as f64 / signed_vsize as f64);
+    out.push('\n');
+    match (spent, fee_sats, fee_rate) {
+        (Some(_), Some(fee), Some(rate)) => out.push_str(&format!(
+            "Fee: {} ({:.1} sat/vB)\n",
+            format_amount(fee),
+            rate
+        )),
+        (Some(spent), _, _) => out.push_str(&format!(
+            "⚠️  Pays out {} sats but only spends {}\n",
+            paid, spent
+        )),
+        _ => out.push_str("Fee: unknown (not every spent output was found)\n"),
+    }
+
+    let effect = wallet.map(|(name, _)| WalletEffect {
+        name: name.to_string(),
+        spent: inputs
+            .iter()
+            .filter(|i| i.wallet.is_some())
+            .filter_map(|i| i.value)
+            .sum(),
+        received: outputs
+            .iter()
+            .filter(|o| o.wallet.is_some())
+            .map(|o| o.value)
+            .sum(),
+        complete: spent.is_some(),
+    });
+    if let Some(effect) = &effect {
+        out.push_str(&format!("\nWallet '{}':\n", effect.name));
+        out.push_str(&format!("   Spends:   {}\n", format_amount(effect.spent)));
+        out.push_str(&format!(
+            "   Receives: {}\n",
+            format_amount(effect.received)
+        ));
+        if !effect.complete {
+            out.push_str("   (inputs whose spent output is unknown are not counted)\n");
+        }
+    }
+
+    let output = DecodedTx {
+        txid: tx.compute_txid().to_string(),
+        wtxid: tx.compute_wtxid().to_string(),
+        version: tx.version.0,
+        lock_time: tx.lock_time.to_consensus_u32(),
+        size: tx.total_size(),
+        weight,
+        vsize: tx.vsize() as u64,
+        signed: unsigned_inputs == 0,
+        signed_vsize,
+        inputs,
+        outputs,
+        fee_sats,
+        fee_rate,
+        wallet: effect,
+        event_type: "tx_decode".to_string(),
+    };
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&output)?,
+    })
+}
+
+/// [`decode_core`] for pasted `data`, looking spent outputs up on the
+/// backend when built with the `network` feature
+///
+/// Shared by the CLI and the TUI. Lookups are cached per transaction.
+pub fn decode_with_backend(
+    data: &str,
+    network: Network,
+    wallet: Option<(&str, &ScriptIndex)>,
+) -> Result<CommandResult> {
+    let (tx, prevouts) = parse_input(data)?;
+    #[cfg(feature = "network")]
+    let lookup = {
+        let cache = std::cell::RefCell::new(std::collections::HashMap::new());
+        move |outpoint: &OutPoint| -> Result<Option<TxOut>> {
+            let txid = outpoint.txid.to_string();
+            if !cache.borrow().contains_key(&txid) {
+                let found = crate::btc::transaction::fetch_transaction(&txid, network)?;
+                cache.borrow_mut().insert(txid.clone(), found);
+            }
+            Ok(cache.borrow()[&txid]
+                .as_ref()
+                .and_then(|tx| tx.output.get(outpoint.vout as usize).cloned()))
+        }
+    };
+    #[cfg(not(feature = "network"))]
+    let lookup = |_: &OutPoint| -> Result<Option<TxOut>> { Ok(None) };
+    decode_core(&tx, &prevouts, network, wallet, &lookup)
+}
+
+/// CLI wrapper for tx-decode
+pub fn tx_decode(data: &str, name: Option<&str>, network: &str) -> Result<()> {
+    let network = crate::btc::hd_address::parse_network(network)?;
+    let data = crate::qr::resolve_payload(data)?;
+    let index = match name {
+        Some(name) => {
+            let storage =
+                crate::storage::FileStorage::new(&crate::protocol::keygen::get_state_dir(name))?;
+            Some((name, ScriptIndex::load(&storage)?))
+        }
+        None => None,
+    };
+    let wallet = index.as_ref().map(|(name, index)| (*name, index));
+    let result = decode_with_backend(&data, network, wallet)?;
+    println!("{}", result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}\n", result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::script_index::OwnedScript;
+    use bitcoin::hashes::Hash;
+    use bitcoin::transaction::Version;
+    use bitcoin::{Amount, ScriptBuf, Sequence, TxIn, Txid, WPubkeyHash, Witness};
+    use std::collections::BTreeMap;
+
+    fn p2tr(byte: u8) -> ScriptBuf {
+        let mut bytes = vec![0x51, 0x20];
+        bytes.extend([byte; 32]);
+        ScriptBuf::from_bytes(bytes)
+    }
+
+    #[test]
+    fn test_decode_marks_wallet_scripts_and_computes_fee() {
+        let ours = p2tr(7);
+        let index = ScriptIndex {
+            stamp: String::new(),
+            root_key: String::new(),
+            scripts: BTreeMap::from([(
+                hex::encode(ours.as_bytes()),
+                OwnedScript {
+                    origin: "m/44'/0'/0'/1/0".to_string(),
+                    descriptor: String::new(),
+                },
+            )]),
+        };
+        let input = |seed: u8| TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array([seed; 32]), 1),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        };
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![input(1), input(2)],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(60_000),
+                    script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([3; 20])),
+                },
+                TxOut {
+                    value: Amount::from_sat(39_000),
+                    script_pubkey: ours.clone(),
+                },
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: ScriptBuf::new_op_return([1, 2, 3]),
+                },
+            ],
+        };
+
+        // First spent output given, second found by the lookup
+        let given = [TxOut {
+            value: Amount::from_sat(50_000),
+            script_pubkey: ours.clone(),
+        }];
+        let lookup = |outpoint: &OutPoint| {
+            assert_eq!(outpoint.txid, Txid::from_byte_array([2; 32]));
+            Ok(Some(TxOut {
+                value: Amount::from_sat(51_000),
+                script_pubkey: p2tr(9),
+            }))
+        };
+        let result = decode_core(
+            &tx,
+            &given,
+            Network::Testnet,
+            Some(("treasury", &index)),
+            &lookup,
+        )
+        .unwrap();
+        let decoded: DecodedTx = serde_json::from_str(&result.result).unwrap();
+
+        assert!(!decoded.signed);
+        assert!(decoded.inputs.iter().all(|i| i.rbf && i.witness_items == 0));
+        assert_eq!(decoded.inputs[0].wallet.as_deref(), Some("m/44'/0'/0'/1/0"));
+        assert_eq!(decoded.inputs[1].wallet, None);
+        assert_eq!(decoded.inputs[1].value, Some(51_000));
+        let types: Vec<&str> = decoded
+            .outputs
+            .iter()
+            .map(|o| o.script_type.as_str())
+            .collect();
+        assert_eq!(types, ["p2wpkh", "p2tr", "op_return"]);
+        assert!(decoded.outputs[0]
+            .address
+            .as_deref()
+            .unwrap()
+            .starts_with("tb1q"));
+        assert_eq!(
+            decoded.outputs[1].wallet.as_deref(),
+            Some("m/44'/0'/0'/1/0")
+        );
+        assert_eq!(decoded.outputs[2].address, None);
+
+        // Two key-path signatures (66 WU each) plus the segwit marker
+        assert_eq!(
+            decoded.signed_vsize,
+            (decoded.weight + 2 + 2 * 66).div_ceil(4)
+        );
+        assert_eq!(decoded.fee_sats, Some(2_000));
+        assert!(result.output.contains("◀ wallet (m/44'/0'/0'/1/0)"));
+        let effect = decoded.wallet.unwrap();
+        assert_eq!((effect.spent, effect.received), (50_000, 39_000));
+        assert!(effect.complete);
+
+        // Without the second spent output the fee stays unknown
+        let failing = |_: &OutPoint| -> Result<Option<TxOut>> { anyhow::bail!("backend down") };
+        let result = decode_core(&tx, &given, Network::Testnet, None, &failing).unwrap();
+        let decoded: DecodedTx = serde_json::from_str(&result.result).unwrap();
+        assert_eq!(decoded.fee_sats, None);
+        assert!(decoded.wallet.is_none());
+        assert!(result.output.contains("#1: lookup failed: backend down"));
+    }
+}
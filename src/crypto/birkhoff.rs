@@ -13,6 +13,8 @@ use anyhow::{bail, Result};
 use nalgebra::DMatrix;
 use secp256kfun::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
 
 /// A Birkhoff parameter represents a share's position in the interpolation.
 /// - `x`: The x-coordinate (party index as scalar)
@@ -68,6 +70,177 @@ pub fn validate_signer_set(ranks: &[u32], threshold: u32) -> Result<()> {
     Ok(())
 }
 
+/// Checks a per-rank signing requirement on top of the threshold.
+///
+/// `requirement` maps a rank to how many signers of exactly that rank must
+/// take part, e.g. `{0: 1, 1: 2}` for one rank-0 and two rank-1 signers.
+///
+/// # Examples
+/// - ranks [0,1,1] with {0: 1, 1: 2}: Valid
+/// - ranks [1,1,2] with {0: 1, 1: 2}: Invalid (missing one rank-0 signer)
+pub fn check_signing_requirement(ranks: &[u32], requirement: &BTreeMap<u32, u32>) -> Result<()> {
+    let missing: Vec<String> = requirement
+        .iter()
+        .filter_map(|(&rank, &need)| {
+            let have = ranks.iter().filter(|&&r| r == rank).count() as u32;
+            (have < need).then(|| signer_count(need - have, rank))
+        })
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "Signing requirement not met: missing {} (requires {})",
+            missing.join(" and "),
+            describe_signing_requirement(requirement)
+        );
+    }
+    Ok(())
+}
+
+/// Human-readable requirement, e.g. "1 rank-0 and 2 rank-1 signers"
+pub fn describe_signing_requirement(requirement: &BTreeMap<u32, u32>) -> String {
+    let parts: Vec<String> = requirement
+        .iter()
+        .map(|(rank, need)| format!("{} rank-{}", need, rank))
+        .collect();
+    let signers = match requirement.values().sum::<u32>() {
+        1 => "signer",
+        _ => "signers",
+    };
+    format!("{} {}", parts.join(" and "), signers)
+}
+
+fn signer_count(count: u32, rank: u32) -> String {
+    match count {
+        1 => format!("one rank-{} signer", rank),
+        n => format!("{} rank-{} signers", n, rank),
+    }
+}
+
+/// One slot of the `rank[i] <= i` check, signers sorted by rank
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankSlot {
+    pub position: u32,
+    pub index: u32,
+    pub rank: u32,
+    /// Whether this slot satisfies the rule (slots past the threshold always do)
+    pub ok: bool,
+}
+
+/// How much weight one signer's share gets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignerCoefficient {
+    pub index: u32,
+    pub rank: u32,
+    /// λᵢ(0), the field scalar applied to this share when signing (hex)
+    pub lagrange: String,
+    /// λᵢ(0) as a decimal, for reading
+    pub lagrange_approx: f64,
+    /// Birkhoff coefficient for the (index, rank) set; `None` if singular
+    pub birkhoff: Option<f64>,
+}
+
+/// Coefficients for a signer set and whether the set may sign
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoefficientPreview {
+    pub threshold: u32,
+    pub hierarchical: bool,
+    /// By party index
+    pub signers: Vec<SignerCoefficient>,
+    /// The HTSS rank check, signers sorted by rank
+    pub rank_slots: Vec<RankSlot>,
+    /// Why the set can't sign; empty if it can
+    pub problems: Vec<String>,
+}
+
+impl CoefficientPreview {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Interpolation coefficients for a signer set, with the reasons it is
+/// invalid if it is.
+///
+/// `signers` are (index, rank) pairs. Shares are combined with the Lagrange
+/// coefficients; in HTSS mode the set must also pass [`validate_signer_set`],
+/// and the Birkhoff coefficients show the same set through the ranks.
+pub fn preview_coefficients(
+    signers: &[(u32, u32)],
+    threshold: u32,
+    hierarchical: bool,
+) -> Result<CoefficientPreview> {
+    let mut signers = signers.to_vec();
+    signers.sort_unstable();
+    if signers.is_empty() {
+        bail!("No signers given");
+    }
+    if signers.iter().any(|&(index, _)| index == 0) {
+        bail!("Party indices start at 1");
+    }
+    if signers.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+        bail!("A party appears twice in the signer set");
+    }
+
+    let indices: Vec<u32> = signers.iter().map(|&(index, _)| index).collect();
+    let lagrange = crate::crypto::helpers::cached_lagrange_coefficients(&indices)?;
+    let birkhoff = cached_birkhoff_coefficients(&signers).ok();
+
+    let mut by_rank = signers.clone();
+    by_rank.sort_by_key(|&(index, rank)| (rank, index));
+    let rank_slots: Vec<RankSlot> = by_rank
+        .iter()
+        .enumerate()
+        .map(|(position, &(index, rank))| RankSlot {
+            position: position as u32,
+            index,
+            rank,
+            ok: position as u32 >= threshold || rank <= position as u32,
+        })
+        .collect();
+
+    let mut problems = Vec::new();
+    if signers.len() < threshold as usize {
+        problems.push(format!(
+            "Not enough signers: {} of the {} needed",
+            signers.len(),
+            threshold
+        ));
+    }
+    if hierarchical {
+        for slot in rank_slots.iter().filter(|slot| !slot.ok) {
+            problems.push(format!(
+                "Party {} has rank {} but sits at position {} once sorted by rank \
+                 (needs rank <= {}): add a signer of rank {} or lower",
+                slot.index, slot.rank, slot.position, slot.position, slot.position
+            ));
+        }
+        if birkhoff.is_none() {
+            problems.push(
+                "The Birkhoff matrix for these (index, rank) pairs is singular, so \
+                 they can't recover the key"
+                    .to_string(),
+            );
+        }
+    }
+
+    Ok(CoefficientPreview {
+        threshold,
+        hierarchical,
+        signers: signers
+            .iter()
+            .map(|&(index, rank)| SignerCoefficient {
+                index,
+                rank,
+                lagrange: hex::encode(lagrange[&index].to_bytes()),
+                lagrange_approx: compute_lagrange_coefficient(index, &indices),
+                birkhoff: birkhoff.as_ref().map(|coefficients| coefficients[&index]),
+            })
+            .collect(),
+        rank_slots,
+        problems,
+    })
+}
+
 /// Computes the factorial coefficient for Birkhoff matrix entry.
 /// Returns n! / (n-k)! = n * (n-1) * ... * (n-k+1)
 fn falling_factorial(n: u32, k: u32) -> f64 {
@@ -170,6 +343,39 @@ pub fn compute_birkhoff_coefficients(params: &[BirkhoffParameter]) -> Result<Vec
     Ok(coefficients)
 }
 
+/// Birkhoff coefficients keyed by sorted (index, rank) set
+type BirkhoffCache = HashMap<Vec<(u32, u32)>, Vec<f64>>;
+
+/// Process-wide coefficient cache
+static BIRKHOFF_CACHE: OnceLock<Mutex<BirkhoffCache>> = OnceLock::new();
+
+/// Birkhoff coefficients for a signer set, cached per set.
+///
+/// The SVD behind [`compute_birkhoff_coefficients`] dominates HTSS combine
+/// time, so each (index, rank) set is solved once per process. The result maps
+/// party index to its coefficient.
+pub fn cached_birkhoff_coefficients(signers: &[(u32, u32)]) -> Result<BTreeMap<u32, f64>> {
+    let mut key = signers.to_vec();
+    key.sort_unstable();
+
+    let cache = BIRKHOFF_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if !cache.contains_key(&key) {
+        let params: Vec<BirkhoffParameter> = key
+            .iter()
+            .map(|&(x, rank)| BirkhoffParameter::new(x, rank))
+            .collect();
+        let coefficients = compute_birkhoff_coefficients(&params)?;
+        cache.insert(key.clone(), coefficients);
+    }
+
+    Ok(key
+        .iter()
+        .map(|&(x, _)| x)
+        .zip(cache[&key].iter().copied())
+        .collect())
+}
+
 /// Computes the Birkhoff coefficient for a single party in a signing session.
 ///
 /// This is the multiplier that should be applied to a party's signature share
@@ -179,22 +385,13 @@ pub fn compute_single_birkhoff_coefficient(
     _my_rank: u32,
     all_signers: &[(u32, u32)], // (index, rank) pairs
 ) -> Result<f64> {
-    // Find my position in the signer list
-    let my_position = all_signers
-        .iter()
-        .position(|(idx, _)| *idx == my_index)
-        .ok_or_else(|| anyhow::anyhow!("My index {} not found in signers list", my_index))?;
+    // Coefficients for the whole set (cached per signer set)
+    let coefficients = cached_birkhoff_coefficients(all_signers)?;
 
-    // Build parameters
-    let params: Vec<BirkhoffParameter> = all_signers
-        .iter()
-        .map(|(x, rank)| BirkhoffParameter::new(*x, *rank))
-        .collect();
-
-    // Compute all coefficients
-    let coefficients = compute_birkhoff_coefficients(&params)?;
-
-    Ok(coefficients[my_position])
+    coefficients
+        .get(&my_index)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("My index {} not found in signers list", my_index))
 }
 
 /// Converts a floating-point Birkhoff coefficient to a Scalar.
@@ -390,6 +587,67 @@ mod tests {
         assert!(validate_signer_set(&[0, 1], 3).is_err());
     }
 
+    #[test]
+    fn test_signing_requirement() {
+        let requirement = BTreeMap::from([(0, 1), (1, 2)]);
+        assert!(check_signing_requirement(&[0, 1, 1], &requirement).is_ok());
+        assert!(check_signing_requirement(&[1, 0, 1, 2], &requirement).is_ok());
+        assert!(check_signing_requirement(&[2, 2, 2], &BTreeMap::new()).is_ok());
+
+        let err = check_signing_requirement(&[1, 1, 2], &requirement).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Signing requirement not met: missing one rank-0 signer \
+             (requires 1 rank-0 and 2 rank-1 signers)"
+        );
+        let err = check_signing_requirement(&[2, 2, 2], &requirement).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("missing one rank-0 signer and 2 rank-1 signers"));
+    }
+
+    #[test]
+    fn test_preview_coefficients() {
+        // TSS 2-of-3 with parties 1 and 3: λ₁ = 3/2, λ₃ = -1/2
+        let preview = preview_coefficients(&[(3, 0), (1, 0)], 2, false).unwrap();
+        assert!(preview.is_valid());
+        let weights: Vec<(u32, f64)> = preview
+            .signers
+            .iter()
+            .map(|s| (s.index, s.lagrange_approx))
+            .collect();
+        assert_eq!(weights, vec![(1, 1.5), (3, -0.5)]);
+        let three = Scalar::<Secret, Zero>::from(3u32);
+        let half = Scalar::<Secret, Zero>::from(2u32)
+            .non_zero()
+            .unwrap()
+            .invert();
+        let three_halves = s!(three * half);
+        assert_eq!(
+            preview.signers[0].lagrange,
+            hex::encode(three_halves.to_bytes())
+        );
+        assert!((preview.signers[0].birkhoff.unwrap() - 1.5).abs() < 1e-9);
+
+        // HTSS [1,1,2]: nobody of rank 0 to fill position 0
+        let preview = preview_coefficients(&[(2, 1), (3, 1), (4, 2)], 3, true).unwrap();
+        assert!(!preview.is_valid());
+        assert_eq!(
+            preview.rank_slots.iter().map(|s| s.ok).collect::<Vec<_>>(),
+            vec![false, true, true]
+        );
+        assert!(preview.problems[0].starts_with("Party 2 has rank 1 but sits at position 0"));
+        assert!(preview_coefficients(&[(1, 0), (2, 1), (4, 2)], 3, true)
+            .unwrap()
+            .is_valid());
+
+        assert!(!preview_coefficients(&[(1, 0)], 2, false)
+            .unwrap()
+            .is_valid());
+        assert!(preview_coefficients(&[(1, 0), (1, 0)], 2, false).is_err());
+        assert!(preview_coefficients(&[(0, 0)], 1, false).is_err());
+    }
+
     #[test]
     fn test_birkhoff_reduces_to_lagrange() {
         // When all ranks are 0, Birkhoff should equal Lagrange
@@ -415,6 +673,12 @@ mod tests {
                 lagrange_coeff
             );
         }
+
+        // Cached lookup is order-independent and matches the direct solve
+        let cached = cached_birkhoff_coefficients(&[(3, 0), (1, 0), (2, 0)]).unwrap();
+        for (i, param) in params.iter().enumerate() {
+            assert!((cached[&param.x] - birkhoff_coeffs[i]).abs() < 1e-12);
+        }
     }
 
     #[test]
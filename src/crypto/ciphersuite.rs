@@ -0,0 +1,638 @@
+//! FROST Ciphersuites
+//!
+//! The group operations and hashes FROST needs, abstracted so the DKG and
+//! signing in `protocol::keygen` and `protocol::signing` work on several
+//! curves:
+//!
+//! - **Secp256k1**: BIP340 challenge and x-only keys, for Bitcoin/Nostr
+//! - **Ed25519**: RFC 8032 challenge, so signatures verify as plain ed25519
+//!   (Solana, Substrate, SSH certificates)
+//! - **Ristretto255**: the prime-order group over Curve25519 (RFC 9591)
+//!
+//! Signing hashes (H1, H3, H4, H5) follow each suite's RFC 9591 definition,
+//! keyed by its `contextString`; secp256k1 uses the BIP340 variant's context,
+//! since its challenge is the BIP340 one rather than the RFC's H2.
+//!
+//! Points and scalars travel as bytes; every decode rejects invalid and
+//! (for Ed25519) small-order encodings.
+
+use crate::crypto::secret::SecretBytes;
+use anyhow::Context;
+use curve25519_dalek_ng::constants::{ED25519_BASEPOINT_POINT, RISTRETTO_BASEPOINT_POINT};
+use curve25519_dalek_ng::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek_ng::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek_ng::scalar::Scalar as DalekScalar;
+use curve25519_dalek_ng::traits::Identity;
+use rand::RngCore;
+use schnorr_fun::fun::marker::*;
+use schnorr_fun::{Message, Schnorr, Signature};
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt::Debug;
+
+/// Curve choice recorded in a wallet
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Curve {
+    #[default]
+    Secp256k1,
+    Ed25519,
+    Ristretto255,
+}
+
+impl Curve {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Curve::Secp256k1 => Secp256k1::NAME,
+            Curve::Ed25519 => Ed25519::NAME,
+            Curve::Ristretto255 => Ristretto255::NAME,
+        }
+    }
+}
+
+impl std::str::FromStr for Curve {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "secp256k1" => Ok(Curve::Secp256k1),
+            "ed25519" => Ok(Curve::Ed25519),
+            "ristretto255" | "ristretto" => Ok(Curve::Ristretto255),
+            other => anyhow::bail!(
+                "Unknown curve '{}' (expected secp256k1, ed25519 or ristretto255)",
+                other
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for Curve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Run `$body` with `$C` bound to the ciphersuite for `$curve`
+macro_rules! with_suite {
+    ($curve:expr, $C:ident => $body:expr) => {
+        match $curve {
+            $crate::crypto::ciphersuite::Curve::Secp256k1 => {
+                type $C = $crate::crypto::ciphersuite::Secp256k1;
+                $body
+            }
+            $crate::crypto::ciphersuite::Curve::Ed25519 => {
+                type $C = $crate::crypto::ciphersuite::Ed25519;
+                $body
+            }
+            $crate::crypto::ciphersuite::Curve::Ristretto255 => {
+                type $C = $crate::crypto::ciphersuite::Ristretto255;
+                $body
+            }
+        }
+    };
+}
+pub(crate) use with_suite;
+
+/// Group and hash operations for one FROST ciphersuite
+pub trait Ciphersuite {
+    const NAME: &'static str;
+    /// RFC 9591 `contextString`
+    const CONTEXT: &'static str;
+    type Scalar: Copy + PartialEq + Debug;
+    type Point: Copy + PartialEq + Debug;
+
+    fn random_scalar() -> Self::Scalar;
+    fn scalar_from_u32(value: u32) -> Self::Scalar;
+    fn scalar_add(a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar;
+    fn scalar_mul(a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar;
+    fn scalar_neg(a: &Self::Scalar) -> Self::Scalar;
+    /// `None` for zero
+    fn scalar_invert(a: &Self::Scalar) -> Option<Self::Scalar>;
+    fn scalar_to_bytes(a: &Self::Scalar) -> Vec<u8>;
+    /// Rejects non-canonical encodings
+    fn scalar_from_bytes(bytes: &[u8]) -> Option<Self::Scalar>;
+
+    fn identity() -> Self::Point;
+    fn base_mul(a: &Self::Scalar) -> Self::Point;
+    fn point_add(a: &Self::Point, b: &Self::Point) -> Self::Point;
+    fn point_mul(a: &Self::Scalar, p: &Self::Point) -> Self::Point;
+    fn point_to_bytes(p: &Self::Point) -> Vec<u8>;
+    /// Rejects invalid, identity and small-order encodings
+    fn point_from_bytes(bytes: &[u8]) -> Option<Self::Point>;
+
+    /// Length of an encoded point
+    fn point_len() -> usize {
+        Self::point_to_bytes(&Self::base_mul(&Self::scalar_from_u32(1))).len()
+    }
+
+    /// RFC 9591 hash onto the scalar field under `contextString ‖ label`
+    /// (H1 with "rho", H3 with "nonce")
+    fn hash_to_field(label: &str, input: &[u8]) -> Self::Scalar;
+
+    /// RFC 9591 hash to bytes under `contextString ‖ label` (H4 with "msg",
+    /// H5 with "com")
+    fn hash(label: &str, input: &[u8]) -> Vec<u8>;
+
+    /// Domain-separated hash of several parts onto the scalar field (the
+    /// "dkg" hash); each part is length-prefixed so no two inputs collide
+    fn hash_to_scalar(domain: &str, parts: &[&[u8]]) -> Self::Scalar {
+        let mut input = Vec::new();
+        for part in std::iter::once(domain.as_bytes()).chain(parts.iter().copied()) {
+            input.extend((part.len() as u64).to_be_bytes());
+            input.extend(part);
+        }
+        Self::hash_to_field("dkg", &input)
+    }
+
+    /// Signature challenge `c`, as the curve's native verifier computes it
+    fn challenge(nonce: &Self::Point, public_key: &Self::Point, message: &[u8]) -> Self::Scalar;
+
+    /// Keys and nonces that must be negated before use (BIP340's even-y rule)
+    fn needs_negation(_point: &Self::Point) -> bool {
+        false
+    }
+
+    /// Public key in the encoding verifiers expect
+    fn public_key_bytes(public_key: &Self::Point) -> Vec<u8> {
+        Self::point_to_bytes(public_key)
+    }
+
+    /// `R ‖ z` in the curve's signature encoding
+    fn signature_bytes(nonce: &Self::Point, z: &Self::Scalar) -> Vec<u8> {
+        let mut bytes = Self::point_to_bytes(nonce);
+        bytes.extend(Self::scalar_to_bytes(z));
+        bytes
+    }
+
+    /// Verify a finished signature the way an outside verifier would
+    fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool;
+}
+
+// ============================================================================
+// secp256k1
+// ============================================================================
+
+pub struct Secp256k1;
+
+type SecpScalar = Scalar<Public, Zero>;
+type SecpPoint = Point<Normal, Public, Zero>;
+
+/// RFC 9380 `expand_message_xmd` with SHA-256, for outputs up to 64 bytes
+fn expand_message_xmd(message: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    debug_assert!(len <= 64 && dst.len() < 256);
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b0: [u8; 32] = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(message)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize()
+        .into();
+    let mut uniform = Vec::with_capacity(64);
+    let mut previous = [0u8; 32];
+    for i in 1..=len.div_ceil(32) as u8 {
+        let mixed: Vec<u8> = b0.iter().zip(previous).map(|(a, b)| a ^ b).collect();
+        previous = Sha256::new()
+            .chain_update(&mixed)
+            .chain_update([i])
+            .chain_update(&dst_prime)
+            .finalize()
+            .into();
+        uniform.extend(previous);
+    }
+    uniform.truncate(len);
+    uniform
+}
+
+fn tagged_sha256(tag: &str, parts: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+impl Ciphersuite for Secp256k1 {
+    const NAME: &'static str = "secp256k1";
+    const CONTEXT: &'static str = "FROST-secp256k1-SHA256-TR-v1";
+    type Scalar = SecpScalar;
+    type Point = SecpPoint;
+
+    fn random_scalar() -> SecpScalar {
+        Scalar::<Secret, NonZero>::random(&mut rand::thread_rng())
+            .public()
+            .mark_zero()
+    }
+
+    fn scalar_from_u32(value: u32) -> SecpScalar {
+        Scalar::<Secret, Zero>::from(value).public()
+    }
+
+    fn scalar_add(a: &SecpScalar, b: &SecpScalar) -> SecpScalar {
+        s!(a + b).public()
+    }
+
+    fn scalar_mul(a: &SecpScalar, b: &SecpScalar) -> SecpScalar {
+        s!(a * b).public()
+    }
+
+    fn scalar_neg(a: &SecpScalar) -> SecpScalar {
+        s!(-a).public()
+    }
+
+    fn scalar_invert(a: &SecpScalar) -> Option<SecpScalar> {
+        a.non_zero().map(|a| a.invert().mark_zero())
+    }
+
+    fn scalar_to_bytes(a: &SecpScalar) -> Vec<u8> {
+        a.to_bytes().to_vec()
+    }
+
+    fn scalar_from_bytes(bytes: &[u8]) -> Option<SecpScalar> {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        Scalar::<Public, Zero>::from_bytes(bytes)
+    }
+
+    fn identity() -> SecpPoint {
+        Point::zero()
+    }
+
+    fn base_mul(a: &SecpScalar) -> SecpPoint {
+        g!(a * G).normalize()
+    }
+
+    fn point_add(a: &SecpPoint, b: &SecpPoint) -> SecpPoint {
+        g!(a + b).normalize()
+    }
+
+    fn point_mul(a: &SecpScalar, p: &SecpPoint) -> SecpPoint {
+        g!(a * p).normalize()
+    }
+
+    fn point_to_bytes(p: &SecpPoint) -> Vec<u8> {
+        p.to_bytes().to_vec()
+    }
+
+    fn point_from_bytes(bytes: &[u8]) -> Option<SecpPoint> {
+        let bytes: [u8; 33] = bytes.try_into().ok()?;
+        Point::<Normal, Public, NonZero>::from_bytes(bytes).map(|p| p.mark_zero())
+    }
+
+    /// `hash_to_field` of RFC 9380: 48 uniform bytes, reduced mod n
+    fn hash_to_field(label: &str, input: &[u8]) -> SecpScalar {
+        let dst = [Self::CONTEXT.as_bytes(), label.as_bytes()].concat();
+        let uniform = expand_message_xmd(input, &dst, 48);
+        let mut high = [0u8; 32];
+        high[16..].copy_from_slice(&uniform[..16]);
+        let low: [u8; 32] = uniform[16..].try_into().expect("32 bytes");
+        // high·2²⁵⁶ + low, with 2²⁵⁶ = 2·2²⁵⁵ and 2²⁵⁵ < n
+        let mut half = [0u8; 32];
+        half[0] = 0x80;
+        let half = Scalar::<Public, Zero>::from_bytes_mod_order(half);
+        let shift = Self::scalar_add(&half, &half);
+        Self::scalar_add(
+            &Self::scalar_mul(&Scalar::from_bytes_mod_order(high), &shift),
+            &Scalar::from_bytes_mod_order(low),
+        )
+    }
+
+    fn hash(label: &str, input: &[u8]) -> Vec<u8> {
+        Sha256::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(label)
+            .chain_update(input)
+            .finalize()
+            .to_vec()
+    }
+
+    fn challenge(nonce: &SecpPoint, public_key: &SecpPoint, message: &[u8]) -> SecpScalar {
+        let r = x_only(nonce);
+        let p = x_only(public_key);
+        Scalar::from_bytes_mod_order(tagged_sha256("BIP0340/challenge", &[&r, &p, message]))
+    }
+
+    fn needs_negation(point: &SecpPoint) -> bool {
+        point.non_zero().is_some_and(|p| !p.is_y_even())
+    }
+
+    fn public_key_bytes(public_key: &SecpPoint) -> Vec<u8> {
+        x_only(public_key).to_vec()
+    }
+
+    fn signature_bytes(nonce: &SecpPoint, z: &SecpScalar) -> Vec<u8> {
+        let mut bytes = x_only(nonce).to_vec();
+        bytes.extend(z.to_bytes());
+        bytes
+    }
+
+    fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        let Some(public_key) = <[u8; 32]>::try_from(public_key)
+            .ok()
+            .and_then(Point::<EvenY, Public>::from_xonly_bytes)
+        else {
+            return false;
+        };
+        let Some(signature) = <[u8; 64]>::try_from(signature)
+            .ok()
+            .and_then(Signature::from_bytes)
+        else {
+            return false;
+        };
+        Schnorr::<Sha256>::verify_only().verify(&public_key, Message::raw(message), &signature)
+    }
+}
+
+fn x_only(point: &SecpPoint) -> [u8; 32] {
+    let bytes = point.to_bytes();
+    bytes[1..].try_into().expect("33-byte encoding")
+}
+
+// ============================================================================
+// Curve25519 (Ed25519 and Ristretto255 share the scalar field)
+// ============================================================================
+
+fn random_dalek_scalar() -> DalekScalar {
+    let mut wide = [0u8; 64];
+    rand::thread_rng().fill_bytes(&mut wide);
+    DalekScalar::from_bytes_mod_order_wide(&wide)
+}
+
+fn sha512(parts: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn sha512_to_scalar(parts: &[&[u8]]) -> DalekScalar {
+    DalekScalar::from_bytes_mod_order_wide(&sha512(parts))
+}
+
+fn dalek_scalar_from_bytes(bytes: &[u8]) -> Option<DalekScalar> {
+    DalekScalar::from_canonical_bytes(bytes.try_into().ok()?)
+}
+
+pub struct Ed25519;
+
+impl Ciphersuite for Ed25519 {
+    const NAME: &'static str = "ed25519";
+    const CONTEXT: &'static str = "FROST-ED25519-SHA512-v1";
+    type Scalar = DalekScalar;
+    type Point = EdwardsPoint;
+
+    fn random_scalar() -> DalekScalar {
+        random_dalek_scalar()
+    }
+
+    fn scalar_from_u32(value: u32) -> DalekScalar {
+        DalekScalar::from(value)
+    }
+
+    fn scalar_add(a: &DalekScalar, b: &DalekScalar) -> DalekScalar {
+        a + b
+    }
+
+    fn scalar_mul(a: &DalekScalar, b: &DalekScalar) -> DalekScalar {
+        a * b
+    }
+
+    fn scalar_neg(a: &DalekScalar) -> DalekScalar {
+        -a
+    }
+
+    fn scalar_invert(a: &DalekScalar) -> Option<DalekScalar> {
+        (*a != DalekScalar::zero()).then(|| a.invert())
+    }
+
+    fn scalar_to_bytes(a: &DalekScalar) -> Vec<u8> {
+        a.to_bytes().to_vec()
+    }
+
+    fn scalar_from_bytes(bytes: &[u8]) -> Option<DalekScalar> {
+        dalek_scalar_from_bytes(bytes)
+    }
+
+    fn identity() -> EdwardsPoint {
+        EdwardsPoint::identity()
+    }
+
+    fn base_mul(a: &DalekScalar) -> EdwardsPoint {
+        a * ED25519_BASEPOINT_POINT
+    }
+
+    fn point_add(a: &EdwardsPoint, b: &EdwardsPoint) -> EdwardsPoint {
+        a + b
+    }
+
+    fn point_mul(a: &DalekScalar, p: &EdwardsPoint) -> EdwardsPoint {
+        a * p
+    }
+
+    fn point_to_bytes(p: &EdwardsPoint) -> Vec<u8> {
+        p.compress().to_bytes().to_vec()
+    }
+
+    fn point_from_bytes(bytes: &[u8]) -> Option<EdwardsPoint> {
+        if bytes.len() != 32 {
+            return None;
+        }
+        CompressedEdwardsY::from_slice(bytes)
+            .decompress()
+            .filter(|p| !p.is_small_order() && p.is_torsion_free())
+    }
+
+    fn hash_to_field(label: &str, input: &[u8]) -> DalekScalar {
+        sha512_to_scalar(&[Self::CONTEXT.as_bytes(), label.as_bytes(), input])
+    }
+
+    fn hash(label: &str, input: &[u8]) -> Vec<u8> {
+        sha512(&[Self::CONTEXT.as_bytes(), label.as_bytes(), input]).to_vec()
+    }
+
+    /// RFC 8032: SHA-512(R ‖ A ‖ M)
+    fn challenge(nonce: &EdwardsPoint, public_key: &EdwardsPoint, message: &[u8]) -> DalekScalar {
+        sha512_to_scalar(&[
+            nonce.compress().as_bytes(),
+            public_key.compress().as_bytes(),
+            message,
+        ])
+    }
+
+    fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        verify_schnorr::<Self>(public_key, message, signature)
+    }
+}
+
+pub struct Ristretto255;
+
+impl Ciphersuite for Ristretto255 {
+    const NAME: &'static str = "ristretto255";
+    const CONTEXT: &'static str = "FROST-RISTRETTO255-SHA512-v1";
+    type Scalar = DalekScalar;
+    type Point = RistrettoPoint;
+
+    fn random_scalar() -> DalekScalar {
+        random_dalek_scalar()
+    }
+
+    fn scalar_from_u32(value: u32) -> DalekScalar {
+        DalekScalar::from(value)
+    }
+
+    fn scalar_add(a: &DalekScalar, b: &DalekScalar) -> DalekScalar {
+        a + b
+    }
+
+    fn scalar_mul(a: &DalekScalar, b: &DalekScalar) -> DalekScalar {
+        a * b
+    }
+
+    fn scalar_neg(a: &DalekScalar) -> DalekScalar {
+        -a
+    }
+
+    fn scalar_invert(a: &DalekScalar) -> Option<DalekScalar> {
+        (*a != DalekScalar::zero()).then(|| a.invert())
+    }
+
+    fn scalar_to_bytes(a: &DalekScalar) -> Vec<u8> {
+        a.to_bytes().to_vec()
+    }
+
+    fn scalar_from_bytes(bytes: &[u8]) -> Option<DalekScalar> {
+        dalek_scalar_from_bytes(bytes)
+    }
+
+    fn identity() -> RistrettoPoint {
+        RistrettoPoint::identity()
+    }
+
+    fn base_mul(a: &DalekScalar) -> RistrettoPoint {
+        a * RISTRETTO_BASEPOINT_POINT
+    }
+
+    fn point_add(a: &RistrettoPoint, b: &RistrettoPoint) -> RistrettoPoint {
+        a + b
+    }
+
+    fn point_mul(a: &DalekScalar, p: &RistrettoPoint) -> RistrettoPoint {
+        a * p
+    }
+
+    fn point_to_bytes(p: &RistrettoPoint) -> Vec<u8> {
+        p.compress().to_bytes().to_vec()
+    }
+
+    fn point_from_bytes(bytes: &[u8]) -> Option<RistrettoPoint> {
+        if bytes.len() != 32 {
+            return None;
+        }
+        CompressedRistretto::from_slice(bytes)
+            .decompress()
+            .filter(|p| *p != RistrettoPoint::identity())
+    }
+
+    fn hash_to_field(label: &str, input: &[u8]) -> DalekScalar {
+        sha512_to_scalar(&[Self::CONTEXT.as_bytes(), label.as_bytes(), input])
+    }
+
+    fn hash(label: &str, input: &[u8]) -> Vec<u8> {
+        sha512(&[Self::CONTEXT.as_bytes(), label.as_bytes(), input]).to_vec()
+    }
+
+    /// RFC 9591 FROST(ristretto255, SHA-512) H2
+    fn challenge(
+        nonce: &RistrettoPoint,
+        public_key: &RistrettoPoint,
+        message: &[u8],
+    ) -> DalekScalar {
+        sha512_to_scalar(&[
+            Self::CONTEXT.as_bytes(),
+            b"chal",
+            nonce.compress().as_bytes(),
+            public_key.compress().as_bytes(),
+            message,
+        ])
+    }
+
+    fn verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        verify_schnorr::<Self>(public_key, message, signature)
+    }
+}
+
+/// `z·B == R + c·A` for suites with full point encodings
+fn verify_schnorr<C: Ciphersuite>(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let point_len = public_key.len();
+    if signature.len() <= point_len {
+        return false;
+    }
+    let (Some(public_key), Some(nonce), Some(z)) = (
+        C::point_from_bytes(public_key),
+        C::point_from_bytes(&signature[..point_len]),
+        C::scalar_from_bytes(&signature[point_len..]),
+    ) else {
+        return false;
+    };
+    let c = C::challenge(&nonce, &public_key, message);
+    C::base_mul(&z) == C::point_add(&nonce, &C::point_mul(&c, &public_key))
+}
+
+/// Point from its hex encoding
+pub fn decode_point<C: Ciphersuite>(hex_str: &str) -> anyhow::Result<C::Point> {
+    let bytes = hex::decode(hex_str).context("Invalid point hex")?;
+    C::point_from_bytes(&bytes).with_context(|| format!("Invalid {} point", C::NAME))
+}
+
+/// Scalar from its hex encoding; the decoded bytes are wiped after use
+pub fn decode_scalar<C: Ciphersuite>(hex_str: &str) -> anyhow::Result<C::Scalar> {
+    let bytes = SecretBytes::new(hex::decode(hex_str).context("Invalid scalar hex")?);
+    C::scalar_from_bytes(&bytes).with_context(|| format!("Invalid {} scalar", C::NAME))
+}
+
+/// Verify a signature under a curve chosen at runtime
+pub fn verify(curve: Curve, public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    match curve {
+        Curve::Secp256k1 => Secp256k1::verify(public_key, message, signature),
+        Curve::Ed25519 => Ed25519::verify(public_key, message, signature),
+        Curve::Ristretto255 => Ristretto255::verify(public_key, message, signature),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_verifies_rfc8032_vector() {
+        // RFC 8032 section 7.1, TEST 1 (empty message)
+        let public_key =
+            hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+                .unwrap();
+        let signature = hex::decode(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        )
+        .unwrap();
+        assert!(Ed25519::verify(&public_key, b"", &signature));
+        assert!(!Ed25519::verify(&public_key, b"x", &signature));
+
+        // Small-order points are never accepted as keys
+        assert!(Ed25519::point_from_bytes(&[0u8; 32]).is_none());
+        assert_eq!("ED25519".parse::<Curve>().unwrap(), Curve::Ed25519);
+    }
+
+    #[test]
+    fn test_expand_message_xmd_rfc9380_vector() {
+        // RFC 9380 appendix K.1, empty message, len_in_bytes = 0x20
+        let uniform = expand_message_xmd(b"", b"QUUX-V01-CS02-with-expander-SHA256-128", 32);
+        assert_eq!(
+            hex::encode(uniform),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+    }
+}
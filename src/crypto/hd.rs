@@ -72,17 +72,19 @@ impl DerivationPath {
         }
     }
 
-    /// Format as BIP-44 style string (relative to account)
-    pub fn to_string(&self) -> String {
-        format!("{}/{}", self.change, self.address_index)
-    }
-
     /// Format as full BIP-44 path (assuming Bitcoin mainnet account 0)
     pub fn to_full_string(&self) -> String {
         format!("m/44'/0'/0'/{}/{}", self.change, self.address_index)
     }
 }
 
+/// Format as BIP-44 style string (relative to account)
+impl std::fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.change, self.address_index)
+    }
+}
+
 /// Derived key information
 #[derive(Clone, Debug)]
 pub struct DerivedKeyInfo {
@@ -148,6 +150,9 @@ pub fn derive_child_tweak(
     Ok((tweak, ir))
 }
 
+/// Child derivation result: (child_pubkey, new_chain_code, tweak, parity_flip)
+pub type DerivedChild = (Point<EvenY>, [u8; 32], Scalar<Public, Zero>, bool);
+
 /// Derive a child public key from parent public key
 ///
 /// child_pubkey = parent_pubkey + tweak * G
@@ -157,7 +162,7 @@ pub fn derive_child_pubkey(
     parent_pubkey: &Point<EvenY>,
     chain_code: &[u8; 32],
     index: u32,
-) -> Result<(Point<EvenY>, [u8; 32], Scalar<Public, Zero>, bool)> {
+) -> Result<DerivedChild> {
     let (tweak, new_chain_code) = derive_child_tweak(chain_code, parent_pubkey, index)?;
 
     // child_pubkey = parent_pubkey + tweak * G
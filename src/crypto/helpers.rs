@@ -6,14 +6,20 @@
 //! ## Functions
 //!
 //! - **PairedSecretShare helpers**: construct, negate, convert shares
-//! - **Lagrange interpolation**: field-safe computation for threshold schemes
+//! - **Lagrange interpolation**: field-safe computation for threshold schemes,
+//!   with a batched variant and a per-signer-set cache
+//! - **Signature aggregation**: batched summing of signature shares
 //! - **Tagged hash**: BIP340-style tagged hashing for Bitcoin protocols
 
+use crate::crypto::ciphersuite::{Ciphersuite, Secp256k1};
+use crate::crypto::secret::SecretBytes;
 use anyhow::Result;
 use schnorr_fun::frost::{PairedSecretShare, SharedKey};
 use schnorr_fun::fun::marker::*;
 use secp256kfun::prelude::*;
 use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
 
 // ============================================================================
 // Tagged Hash (BIP340)
@@ -31,8 +37,8 @@ use sha2::{Digest, Sha256};
 pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
     let tag_hash = Sha256::digest(tag.as_bytes());
     let mut hasher = Sha256::new();
-    hasher.update(&tag_hash);
-    hasher.update(&tag_hash);
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
     hasher.update(data);
     hasher.finalize().into()
 }
@@ -41,12 +47,14 @@ pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
 // Lagrange Interpolation
 // ============================================================================
 
-/// Compute Lagrange coefficient for party_index at target_x.
+/// Compute Lagrange coefficient for party_index at target_x, in any ciphersuite's field.
 ///
 /// λ_i(x) = Π_{j≠i} (x - j) / (i - j)
 ///
-/// Uses field arithmetic directly to avoid integer overflow for large party counts.
-/// Previous implementations using i64 accumulation then truncating to u32 silently
+/// This is the one Lagrange implementation: the secp256k1 helpers below, the
+/// batched variant and the ed25519/ristretto255 protocols all go through it.
+/// It works purely in the scalar field, so any u32 indices are safe. Previous
+/// implementations using i64 accumulation then truncating to u32 silently
 /// corrupted results for 14+ parties (13! = 6,227,020,800 > u32::MAX).
 ///
 /// # Arguments
@@ -55,42 +63,55 @@ pub fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
 /// * `target_x` - The x-coordinate at which to evaluate (0 for secret recovery)
 ///
 /// # Returns
-/// The Lagrange coefficient as a field scalar
-pub fn lagrange_coefficient_at(
+/// The Lagrange coefficient as a field scalar; an error for duplicate indices
+pub fn lagrange_coefficient_in<C: Ciphersuite>(
     party_index: u32,
     all_indices: &[u32],
     target_x: u32,
-) -> Result<Scalar<Secret, Zero>> {
-    let mut numerator: Scalar<Secret, Zero> = Scalar::from(1u32);
-    let mut denominator: Scalar<Secret, Zero> = Scalar::from(1u32);
-
-    let i_scalar: Scalar<Secret, Zero> = Scalar::from(party_index);
-    let x_scalar: Scalar<Secret, Zero> = Scalar::from(target_x);
-
-    for &other_index in all_indices {
-        if other_index == party_index {
-            continue;
-        }
-
-        let j_scalar: Scalar<Secret, Zero> = Scalar::from(other_index);
+) -> Result<C::Scalar> {
+    check_distinct(all_indices)?;
+    let (numerator, denominator) = lagrange_terms::<C>(party_index, all_indices, target_x);
+    let denom_inv = C::scalar_invert(&denominator)
+        .ok_or_else(|| anyhow::anyhow!("Lagrange denominator is zero - duplicate indices?"))?;
+    Ok(C::scalar_mul(&numerator, &denom_inv))
+}
 
-        // numerator *= (x - j)
-        let x_minus_j = s!(x_scalar - j_scalar);
-        numerator = s!(numerator * x_minus_j);
+/// Numerator Π (x - j) and denominator Π (i - j) of λ_i(x), over j ≠ i
+fn lagrange_terms<C: Ciphersuite>(
+    party_index: u32,
+    all_indices: &[u32],
+    target_x: u32,
+) -> (C::Scalar, C::Scalar) {
+    let i_scalar = C::scalar_from_u32(party_index);
+    let x_scalar = C::scalar_from_u32(target_x);
+    all_indices.iter().filter(|&&j| j != party_index).fold(
+        (C::scalar_from_u32(1), C::scalar_from_u32(1)),
+        |(numerator, denominator), &j| {
+            let minus_j = C::scalar_neg(&C::scalar_from_u32(j));
+            (
+                C::scalar_mul(&numerator, &C::scalar_add(&x_scalar, &minus_j)),
+                C::scalar_mul(&denominator, &C::scalar_add(&i_scalar, &minus_j)),
+            )
+        },
+    )
+}
 
-        // denominator *= (i - j)
-        let i_minus_j = s!(i_scalar - j_scalar);
-        denominator = s!(denominator * i_minus_j);
+fn check_distinct(all_indices: &[u32]) -> Result<()> {
+    let mut sorted = all_indices.to_vec();
+    sorted.sort_unstable();
+    if sorted.windows(2).any(|w| w[0] == w[1]) {
+        anyhow::bail!("Duplicate party index in signer set");
     }
+    Ok(())
+}
 
-    // Invert denominator and multiply
-    let denom_nonzero = denominator
-        .non_zero()
-        .ok_or_else(|| anyhow::anyhow!("Lagrange denominator is zero - duplicate indices?"))?;
-    let denom_inv = denom_nonzero.invert();
-    let result = s!(numerator * denom_inv);
-
-    Ok(result)
+/// [`lagrange_coefficient_in`] for secp256k1
+pub fn lagrange_coefficient_at(
+    party_index: u32,
+    all_indices: &[u32],
+    target_x: u32,
+) -> Result<Scalar<Secret, Zero>> {
+    Ok(lagrange_coefficient_in::<Secp256k1>(party_index, all_indices, target_x)?.secret())
 }
 
 /// Compute Lagrange coefficient at x=0 (for secret/share reconstruction).
@@ -106,6 +127,110 @@ pub fn lagrange_coefficient_at_zero(
     lagrange_coefficient_at(party_index, all_indices, 0)
 }
 
+/// Compute λ_i(0) for every index in a signer set with a single field inversion.
+///
+/// Equivalent to calling [`lagrange_coefficient_at_zero`] per party, but uses
+/// Montgomery's batch-inversion trick: the n denominators are multiplied
+/// together, inverted once, and unwound. Returns coefficients in the order of
+/// `all_indices`.
+pub fn lagrange_coefficients_at_zero(all_indices: &[u32]) -> Result<Vec<Scalar<Secret, Zero>>> {
+    check_distinct(all_indices)?;
+
+    let mut numerators = Vec::with_capacity(all_indices.len());
+    let mut denominators: Vec<Scalar<Secret, NonZero>> = Vec::with_capacity(all_indices.len());
+
+    for &i in all_indices {
+        let (numerator, denominator) = lagrange_terms::<Secp256k1>(i, all_indices, 0);
+        numerators.push(numerator.secret());
+        denominators.push(
+            denominator.secret().non_zero().ok_or_else(|| {
+                anyhow::anyhow!("Lagrange denominator is zero - duplicate indices?")
+            })?,
+        );
+    }
+
+    // prefix[k] = d_0 * ... * d_{k-1}
+    let mut prefix: Vec<Scalar<Secret, NonZero>> = Vec::with_capacity(denominators.len());
+    let mut acc: Scalar<Secret, NonZero> = Scalar::one();
+    for d in &denominators {
+        prefix.push(acc);
+        acc = s!(acc * d);
+    }
+
+    // Walk back: inv holds (d_0 * ... * d_k)^-1
+    let mut inv = acc.invert();
+    let mut coefficients = vec![Scalar::<Secret, Zero>::zero(); denominators.len()];
+    for k in (0..denominators.len()).rev() {
+        let d_inv = s!(inv * prefix[k]);
+        coefficients[k] = s!(numerators[k] * d_inv);
+        inv = s!(inv * denominators[k]);
+    }
+
+    Ok(coefficients)
+}
+
+/// Lagrange coefficients keyed by sorted signer set
+type LagrangeCache = HashMap<Vec<u32>, Vec<Scalar<Secret, Zero>>>;
+
+/// Process-wide coefficient cache
+static LAGRANGE_CACHE: OnceLock<Mutex<LagrangeCache>> = OnceLock::new();
+
+/// Lagrange coefficients at x=0 for a signer set, cached per set.
+///
+/// Repeated signing with the same parties (e.g. one session per input, or the
+/// TUI's local signing loop) reuses the coefficients instead of recomputing
+/// them. The result maps party index to λ_i(0).
+pub fn cached_lagrange_coefficients(
+    all_indices: &[u32],
+) -> Result<BTreeMap<u32, Scalar<Secret, Zero>>> {
+    let mut key = all_indices.to_vec();
+    key.sort_unstable();
+
+    let cache = LAGRANGE_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if !cache.contains_key(&key) {
+        let coefficients = lagrange_coefficients_at_zero(&key)?;
+        cache.insert(key.clone(), coefficients);
+    }
+
+    Ok(key
+        .iter()
+        .copied()
+        .zip(cache[&key].iter().copied())
+        .collect())
+}
+
+// ============================================================================
+// Signature Aggregation
+// ============================================================================
+
+/// Sum signature shares in one pass.
+///
+/// Accumulates in the secret domain and converts the marker once at the end,
+/// instead of converting back to `Public` after every addition.
+pub fn aggregate_signature_shares<'a>(
+    shares: impl IntoIterator<Item = &'a Scalar<Public, Zero>>,
+) -> Scalar<Public, Zero> {
+    shares
+        .into_iter()
+        .fold(Scalar::<Secret, Zero>::zero(), |acc, share| s!(acc + share))
+        .public()
+}
+
+/// Decode bincode-hex signature shares and aggregate them.
+pub fn aggregate_signature_shares_hex<'a>(
+    shares_hex: impl IntoIterator<Item = &'a str>,
+) -> Result<Scalar<Public, Zero>> {
+    let shares = shares_hex
+        .into_iter()
+        .map(|share_hex| {
+            let bytes = hex::decode(share_hex)?;
+            Ok(bincode::deserialize::<Scalar<Public, Zero>>(&bytes)?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(aggregate_signature_shares(&shares))
+}
+
 // ============================================================================
 // PairedSecretShare Helpers
 // ============================================================================
@@ -130,6 +255,7 @@ pub fn construct_paired_secret_share(
     paired_bytes.extend_from_slice(&share.to_bytes()); // share: 32 bytes
     paired_bytes.extend_from_slice(&group_public_key.to_xonly_bytes()); // pubkey: 32 bytes
 
+    let paired_bytes = SecretBytes::new(paired_bytes);
     let paired: PairedSecretShare<EvenY> = bincode::deserialize(&paired_bytes)?;
     Ok(paired)
 }
@@ -158,10 +284,30 @@ pub fn negate_paired_secret_share(
     negated_bytes.extend_from_slice(&negated_share_nonzero.to_bytes()); // negated share
     negated_bytes.extend_from_slice(&paired_share.public_key().to_xonly_bytes()); // pubkey unchanged
 
+    let negated_bytes = SecretBytes::new(negated_bytes);
     let negated_paired: PairedSecretShare<EvenY> = bincode::deserialize(&negated_bytes)?;
     Ok(negated_paired)
 }
 
+/// Re-pair a share with a different public key, keeping index and share value.
+///
+/// Used to sign in a session bound to the taproot output key while the share
+/// itself stays untweaked (the tweak is applied when shares are combined).
+pub fn pair_with_public_key(
+    paired_share: &PairedSecretShare<EvenY>,
+    public_key: &Point<EvenY>,
+) -> Result<PairedSecretShare<EvenY>> {
+    let secret_share = paired_share.secret_share();
+
+    let mut paired_bytes = Vec::with_capacity(96);
+    paired_bytes.extend_from_slice(&secret_share.index.to_bytes());
+    paired_bytes.extend_from_slice(&secret_share.share.to_bytes());
+    paired_bytes.extend_from_slice(&public_key.to_xonly_bytes());
+
+    let paired_bytes = SecretBytes::new(paired_bytes);
+    Ok(bincode::deserialize(&paired_bytes)?)
+}
+
 /// Convert a Zero-variant scalar share to NonZero for use in PairedSecretShare.
 ///
 /// Returns an error if the share is actually zero (extremely unlikely in practice).
@@ -189,6 +335,51 @@ pub fn construct_shared_key(public_key: &Point<EvenY>) -> Result<SharedKey<EvenY
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_lagrange_matches_schnorr_fun() {
+        use crate::crypto::ciphersuite::Ed25519;
+        use secp256kfun::poly;
+
+        // The last set's products are far beyond i64
+        let sets: [&[u32]; 3] = [
+            &[1, 2, 3],
+            &[2, 5, 7, 11],
+            &[1, 1_000, 65_537, 4_000_000_000, u32::MAX],
+        ];
+        let node = |j: u32| Scalar::<Secret, Zero>::from(j).non_zero().unwrap().public();
+        for indices in sets {
+            let batched = lagrange_coefficients_at_zero(indices).unwrap();
+            for (k, &i) in indices.iter().enumerate() {
+                let expected =
+                    poly::eval_basis_poly_at_0(node(i), indices.iter().map(|&j| node(j)));
+                assert_eq!(lagrange_coefficient_at_zero(i, indices).unwrap(), expected);
+                assert_eq!(batched[k], expected);
+
+                let basis = poly::lagrange_basis_poly(node(i), indices.iter().map(|&j| node(j)));
+                for x in [7u32, 1_000_000] {
+                    let at_x = poly::scalar::eval(&basis, Scalar::<Public, Zero>::from(x));
+                    assert_eq!(lagrange_coefficient_at(i, indices, x).unwrap(), at_x);
+                }
+            }
+        }
+
+        // Other fields: the coefficients still reconstruct f(0) from f(i)
+        let f = |x: u32| {
+            Ed25519::scalar_add(
+                &Ed25519::scalar_from_u32(42),
+                &Ed25519::scalar_mul(&Ed25519::scalar_from_u32(x), &Ed25519::scalar_from_u32(9)),
+            )
+        };
+        let indices = [3u32, 8, 4_000_000_000];
+        let reconstructed = indices.iter().fold(Ed25519::scalar_from_u32(0), |acc, &i| {
+            let lambda = lagrange_coefficient_in::<Ed25519>(i, &indices, 0).unwrap();
+            Ed25519::scalar_add(&acc, &Ed25519::scalar_mul(&lambda, &f(i)))
+        });
+        assert_eq!(reconstructed, Ed25519::scalar_from_u32(42));
+
+        assert!(lagrange_coefficient_at_zero(1, &[1, 2, 2]).is_err());
+    }
+
     #[test]
     fn test_crypto_helpers() {
         // Tagged hash determinism
@@ -214,6 +405,30 @@ mod tests {
         }
         assert_eq!(sum15.to_bytes(), one.to_bytes());
 
+        // Batched and cached coefficients match the per-party computation
+        let signers = vec![2u32, 5, 7, 11];
+        let batched = lagrange_coefficients_at_zero(&signers).unwrap();
+        let cached = cached_lagrange_coefficients(&[11, 2, 7, 5]).unwrap();
+        for (k, &i) in signers.iter().enumerate() {
+            let expected = lagrange_coefficient_at_zero(i, &signers).unwrap();
+            assert_eq!(batched[k].to_bytes(), expected.to_bytes());
+            assert_eq!(cached[&i].to_bytes(), expected.to_bytes());
+        }
+        assert!(lagrange_coefficients_at_zero(&[1, 1]).is_err());
+
+        // Aggregation equals a plain sum
+        let shares: Vec<Scalar<Public, Zero>> = (1u32..=4).map(Scalar::from).collect();
+        let ten: Scalar<Public, Zero> = Scalar::from(10u32);
+        assert_eq!(aggregate_signature_shares(&shares), ten);
+        let hexes: Vec<String> = shares
+            .iter()
+            .map(|s| hex::encode(bincode::serialize(s).unwrap()))
+            .collect();
+        assert_eq!(
+            aggregate_signature_shares_hex(hexes.iter().map(String::as_str)).unwrap(),
+            ten
+        );
+
         // Paired secret share construction and negation
         let mut rng = rand::thread_rng();
         let share = Scalar::<Secret, NonZero>::random(&mut rng);
@@ -17,8 +17,13 @@
 //!
 //! // Restore share from mnemonic
 //! let restored = mnemonic_to_share(&mnemonic)?;
+//!
+//! // With a passphrase, the words alone are not the share
+//! let mnemonic = share_to_mnemonic_with_passphrase(&share_bytes, "passphrase")?;
+//! let restored = mnemonic_to_share_with_passphrase(&mnemonic, "passphrase")?;
 //! ```
 
+use crate::crypto::secret::SecretBytes;
 use anyhow::Result;
 use bip39::{Language, Mnemonic};
 use hmac::{Hmac, Mac};
@@ -73,7 +78,7 @@ pub fn share_to_mnemonic(share_bytes: &[u8; 32]) -> Result<Mnemonic> {
 ///
 /// Extracts the original entropy (share bytes) from the mnemonic.
 pub fn mnemonic_to_share(mnemonic: &Mnemonic) -> Result<[u8; 32]> {
-    let entropy = mnemonic.to_entropy();
+    let entropy = SecretBytes::new(mnemonic.to_entropy());
     if entropy.len() != 32 {
         anyhow::bail!(
             "Expected 32-byte entropy (24 words), got {} bytes",
@@ -85,6 +90,92 @@ pub fn mnemonic_to_share(mnemonic: &Mnemonic) -> Result<[u8; 32]> {
     Ok(bytes)
 }
 
+/// PBKDF2 rounds for the passphrase mask, as in BIP-39's seed derivation
+const PASSPHRASE_ROUNDS: u32 = 2048;
+
+/// PBKDF2 salt prefix for the passphrase mask (BIP-39 uses "mnemonic")
+const PASSPHRASE_SALT: &str = "frostdao share mnemonic";
+
+/// Salt naming one share: the group's x-only key and the share's index
+///
+/// BIP-39 salts with the mnemonic; a share's words are masked, so the salt
+/// comes from what is public about the share instead. A dictionary built
+/// for one backup then says nothing about another.
+pub fn share_salt(group_public_key: &[u8; 32], index: u32) -> Vec<u8> {
+    let mut salt = group_public_key.to_vec();
+    salt.extend_from_slice(&index.to_be_bytes());
+    salt
+}
+
+/// 32-byte mask derived from `passphrase` and `salt` with PBKDF2-HMAC-SHA512
+fn passphrase_mask(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mac = |data: &[u8]| {
+        let mut hmac = Hmac::<Sha512>::new_from_slice(passphrase.as_bytes())
+            .expect("HMAC accepts any key length");
+        hmac.update(data);
+        let block: [u8; 64] = hmac.finalize().into_bytes().into();
+        block
+    };
+    let mut u = mac(&[PASSPHRASE_SALT.as_bytes(), salt, &1u32.to_be_bytes()].concat());
+    let mut t = u;
+    for _ in 1..PASSPHRASE_ROUNDS {
+        u = mac(&u);
+        t.iter_mut().zip(u.iter()).for_each(|(t, u)| *t ^= u);
+    }
+    let mut mask = [0u8; 32];
+    mask.copy_from_slice(&t[..32]);
+    t.zeroize();
+    u.zeroize();
+    mask
+}
+
+fn xor_mask(bytes: &[u8; 32], passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut mask = passphrase_mask(passphrase, salt);
+    let mut masked = *bytes;
+    masked
+        .iter_mut()
+        .zip(mask.iter())
+        .for_each(|(b, m)| *b ^= m);
+    mask.zeroize();
+    masked
+}
+
+/// Convert a share to 24 words that need `passphrase` to read back
+///
+/// The words encode the share XOR a PBKDF2 mask of the passphrase salted
+/// with `salt` (see [`share_salt`]), so the word list alone is not the share.
+/// Any passphrase decodes to *some* value; callers keep a check of the share
+/// to catch a wrong one. An empty passphrase gives the same words as
+/// [`share_to_mnemonic`].
+pub fn share_to_mnemonic_with_passphrase(
+    share_bytes: &[u8; 32],
+    passphrase: &str,
+    salt: &[u8],
+) -> Result<Mnemonic> {
+    if passphrase.is_empty() {
+        return share_to_mnemonic(share_bytes);
+    }
+    let mut masked = xor_mask(share_bytes, passphrase, salt);
+    let result = share_to_mnemonic(&masked);
+    masked.zeroize();
+    result
+}
+
+/// Inverse of [`share_to_mnemonic_with_passphrase`]
+pub fn mnemonic_to_share_with_passphrase(
+    mnemonic: &Mnemonic,
+    passphrase: &str,
+    salt: &[u8],
+) -> Result<[u8; 32]> {
+    let mut bytes = mnemonic_to_share(mnemonic)?;
+    if passphrase.is_empty() {
+        return Ok(bytes);
+    }
+    let share = xor_mask(&bytes, passphrase, salt);
+    bytes.zeroize();
+    Ok(share)
+}
+
 /// Parse mnemonic from space-separated words
 pub fn parse_mnemonic(words: &str) -> Result<Mnemonic> {
     Mnemonic::parse_in(Language::English, words)
@@ -138,8 +229,10 @@ pub fn seed_to_master_key(seed: &[u8; 64]) -> Result<([u8; 32], [u8; 32])> {
 /// Convenience function for new wallet creation.
 pub fn generate_master_from_mnemonic(passphrase: &str) -> Result<(Mnemonic, [u8; 32], [u8; 32])> {
     let mnemonic = generate_mnemonic()?;
-    let seed = mnemonic_to_seed(&mnemonic, passphrase);
-    let (master_key, chain_code) = seed_to_master_key(&seed)?;
+    let mut seed = mnemonic_to_seed(&mnemonic, passphrase);
+    let keys = seed_to_master_key(&seed);
+    seed.zeroize();
+    let (master_key, chain_code) = keys?;
     Ok((mnemonic, master_key, chain_code))
 }
 
@@ -149,8 +242,10 @@ pub fn restore_master_from_words(
     passphrase: &str,
 ) -> Result<(Mnemonic, [u8; 32], [u8; 32])> {
     let mnemonic = parse_mnemonic(words)?;
-    let seed = mnemonic_to_seed(&mnemonic, passphrase);
-    let (master_key, chain_code) = seed_to_master_key(&seed)?;
+    let mut seed = mnemonic_to_seed(&mnemonic, passphrase);
+    let keys = seed_to_master_key(&seed);
+    seed.zeroize();
+    let (master_key, chain_code) = keys?;
     Ok((mnemonic, master_key, chain_code))
 }
 
@@ -217,6 +312,53 @@ mod tests {
         assert_eq!(restored, original_share);
     }
 
+    #[test]
+    fn test_passphrase_mnemonic_roundtrip() {
+        let original_share = [0x42u8; 32];
+        let salt = share_salt(&[7u8; 32], 2);
+
+        let plain = share_to_mnemonic(&original_share).unwrap();
+        let empty = share_to_mnemonic_with_passphrase(&original_share, "", &salt).unwrap();
+        assert_eq!(plain, empty);
+
+        let protected =
+            share_to_mnemonic_with_passphrase(&original_share, "hunter2", &salt).unwrap();
+        assert_ne!(protected, plain);
+        assert_eq!(
+            mnemonic_to_share_with_passphrase(&protected, "hunter2", &salt).unwrap(),
+            original_share
+        );
+        assert_ne!(
+            mnemonic_to_share_with_passphrase(&protected, "hunter3", &salt).unwrap(),
+            original_share
+        );
+        assert_ne!(mnemonic_to_share(&protected).unwrap(), original_share);
+    }
+
+    #[test]
+    fn test_passphrase_mask_is_salted_per_share() {
+        let original_share = [0x42u8; 32];
+        let salt = share_salt(&[7u8; 32], 2);
+        let protected =
+            share_to_mnemonic_with_passphrase(&original_share, "hunter2", &salt).unwrap();
+
+        // Same share and passphrase under another index or group: other words
+        for other in [share_salt(&[7u8; 32], 3), share_salt(&[8u8; 32], 2)] {
+            assert_ne!(
+                share_to_mnemonic_with_passphrase(&original_share, "hunter2", &other).unwrap(),
+                protected
+            );
+            assert_ne!(
+                mnemonic_to_share_with_passphrase(&protected, "hunter2", &other).unwrap(),
+                original_share
+            );
+        }
+        assert_eq!(
+            mnemonic_to_share_with_passphrase(&protected, "hunter2", &salt).unwrap(),
+            original_share
+        );
+    }
+
     #[test]
     fn test_parse_valid_mnemonic() {
         // Standard BIP-39 test vector
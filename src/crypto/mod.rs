@@ -3,11 +3,19 @@
 //! This module provides the core cryptographic building blocks for FROST/HTSS:
 //!
 //! - **birkhoff**: Birkhoff interpolation for hierarchical threshold schemes
+//! - **ciphersuite**: Curve abstraction for FROST (secp256k1, ed25519, ristretto255)
 //! - **hd**: BIP-32/BIP-44 hierarchical deterministic key derivation
 //! - **helpers**: Utility functions (tagged hash, Lagrange coefficients, etc.)
 //! - **mnemonic**: BIP-39 mnemonic seed phrase generation and parsing
+//! - **nip44**: NIP-44 v2 encryption between two Nostr keys
+//! - **slip39**: SLIP-39 Shamir mnemonics for share backups
+//! - **secret**: Zeroize-on-drop wrappers for secret buffers
 
 pub mod birkhoff;
+pub mod ciphersuite;
 pub mod hd;
 pub mod helpers;
 pub mod mnemonic;
+pub mod nip44;
+pub mod secret;
+pub mod slip39;
@@ -0,0 +1,202 @@
+//! NIP-44 (v2) Encrypted Payloads
+//!
+//! The Nostr encryption scheme for messages between two secp256k1 keys:
+//!
+//! ```text
+//! conversation_key = HKDF-extract(salt = "nip44-v2", ECDH x-coordinate)
+//! chacha_key ‖ chacha_nonce ‖ hmac_key = HKDF-expand(conversation_key, nonce, 76)
+//! payload = base64(0x02 ‖ nonce ‖ ChaCha20(padded plaintext) ‖ HMAC(nonce ‖ ciphertext))
+//! ```
+//!
+//! The conversation key is symmetric (A→B equals B→A), so a party only needs
+//! the other side's x-only public key. Plaintexts are padded to a bucketed
+//! length so ciphertext size reveals little about the content.
+
+use crate::crypto::secret::SecretBytes;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use chacha20::cipher::{KeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use schnorr_fun::fun::marker::*;
+use secp256kfun::prelude::*;
+use sha2::Sha256;
+
+const VERSION: u8 = 2;
+const SALT: &[u8] = b"nip44-v2";
+const MAX_PLAINTEXT_LEN: usize = 65535;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+/// Shared key for a pair of parties (same from either side)
+pub fn conversation_key(
+    secret: &Scalar<Secret, NonZero>,
+    their_public: &Point<EvenY, Public>,
+) -> SecretBytes {
+    let shared = g!(secret * their_public).normalize();
+    // Only the x-coordinate is used, so the sign of `secret` doesn't matter
+    let shared_x = SecretBytes::new(shared.to_xonly_bytes().to_vec());
+    SecretBytes::new(hmac(SALT, &[shared_x.expose()]).to_vec())
+}
+
+/// ChaCha20 key, ChaCha20 nonce and HMAC key for one message
+fn message_keys(conversation_key: &[u8], nonce: &[u8; 32]) -> SecretBytes {
+    // HKDF-expand to 76 bytes: three HMAC blocks
+    let mut okm = Vec::with_capacity(96);
+    let mut block: Vec<u8> = Vec::new();
+    for counter in 1u8..=3 {
+        block = hmac(conversation_key, &[&block, nonce, &[counter]]).to_vec();
+        okm.extend_from_slice(&block);
+    }
+    okm.truncate(76);
+    SecretBytes::new(okm)
+}
+
+fn padded_len(len: usize) -> usize {
+    if len <= 32 {
+        return 32;
+    }
+    let next_power = 1usize << (usize::BITS - (len - 1).leading_zeros());
+    let chunk = if next_power <= 256 {
+        32
+    } else {
+        next_power / 8
+    };
+    chunk * ((len - 1) / chunk + 1)
+}
+
+/// Encrypt with a caller-chosen nonce (test vectors); use `encrypt` otherwise
+pub fn encrypt_with_nonce(
+    conversation_key: &[u8],
+    plaintext: &str,
+    nonce: &[u8; 32],
+) -> Result<String> {
+    let len = plaintext.len();
+    if len == 0 || len > MAX_PLAINTEXT_LEN {
+        bail!("NIP-44 plaintext must be 1..={} bytes", MAX_PLAINTEXT_LEN);
+    }
+    let keys = message_keys(conversation_key, nonce);
+
+    // Encrypted in place, so the plaintext copy doesn't outlive this call
+    let mut padded = vec![0u8; 2 + padded_len(len)];
+    padded[..2].copy_from_slice(&(len as u16).to_be_bytes());
+    padded[2..2 + len].copy_from_slice(plaintext.as_bytes());
+    ChaCha20::new(keys[..32].into(), keys[32..44].into()).apply_keystream(&mut padded);
+
+    let mac = hmac(&keys[44..76], &[nonce, &padded]);
+    let mut payload = Vec::with_capacity(1 + 32 + padded.len() + 32);
+    payload.push(VERSION);
+    payload.extend_from_slice(nonce);
+    payload.extend_from_slice(&padded);
+    payload.extend_from_slice(&mac);
+    Ok(base64::engine::general_purpose::STANDARD.encode(payload))
+}
+
+/// Encrypt a message under a conversation key
+pub fn encrypt(conversation_key: &[u8], plaintext: &str) -> Result<String> {
+    let mut nonce = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    encrypt_with_nonce(conversation_key, plaintext, &nonce)
+}
+
+/// Decrypt and authenticate a NIP-44 payload
+pub fn decrypt(conversation_key: &[u8], payload: &str) -> Result<SecretBytes> {
+    if payload.starts_with('#') {
+        bail!("Unsupported NIP-44 version");
+    }
+    if !(132..=87472).contains(&payload.len()) {
+        bail!("Invalid NIP-44 payload length");
+    }
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .context("NIP-44 payload is not base64")?;
+    if data.len() < 99 || data[0] != VERSION {
+        bail!("Unsupported NIP-44 version");
+    }
+
+    let nonce: [u8; 32] = data[1..33].try_into().expect("length checked");
+    let (ciphertext, mac) = data[33..].split_at(data.len() - 33 - 32);
+    let keys = message_keys(conversation_key, &nonce);
+
+    let mut check =
+        <HmacSha256 as Mac>::new_from_slice(&keys[44..76]).expect("HMAC accepts any key length");
+    check.update(&nonce);
+    check.update(ciphertext);
+    check
+        .verify_slice(mac)
+        .map_err(|_| anyhow::anyhow!("NIP-44 MAC mismatch: wrong key or tampered payload"))?;
+
+    let mut padded = ciphertext.to_vec();
+    ChaCha20::new(keys[..32].into(), keys[32..44].into()).apply_keystream(&mut padded);
+    let padded = SecretBytes::new(padded);
+
+    let len = u16::from_be_bytes([padded[0], padded[1]]) as usize;
+    if len == 0 || padded.len() != 2 + padded_len(len) {
+        bail!("Invalid NIP-44 padding");
+    }
+    Ok(SecretBytes::new(padded[2..2 + len].to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(last: u8) -> Scalar<Secret, NonZero> {
+        let mut bytes = [0u8; 32];
+        bytes[31] = last;
+        Scalar::from_bytes(bytes).unwrap().non_zero().unwrap()
+    }
+
+    #[test]
+    fn test_nip44_vector_and_round_trip() {
+        // First vector from the NIP-44 spec (sec1 = 1, sec2 = 2, nonce = 1)
+        let key = conversation_key(
+            &scalar(1),
+            &g!(scalar(2) * G)
+                .normalize()
+                .into_point_with_even_y()
+                .0
+                .public(),
+        );
+        assert_eq!(
+            hex::encode(key.expose()),
+            "c41c775356fd92eadc63ff5a0dc1da211b268cbea22316767095b2871ea1412d"
+        );
+        let mut nonce = [0u8; 32];
+        nonce[31] = 1;
+        let payload = encrypt_with_nonce(&key, "a", &nonce).unwrap();
+        assert_eq!(
+            payload,
+            "AgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABee0G5VSK0/9YypIObAtDKfYEAjD35uVkHyB0F4DwrcNaCXlCWZKaArsGrY6M9wnuTMxWfp1RTN9Xga8no+kF5Vsb"
+        );
+        assert_eq!(decrypt(&key, &payload).unwrap().expose(), b"a");
+
+        // Symmetric key, padding buckets, and tamper detection
+        let other = conversation_key(
+            &scalar(2),
+            &g!(scalar(1) * G)
+                .normalize()
+                .into_point_with_even_y()
+                .0
+                .public(),
+        );
+        assert_eq!(other, key);
+        let long = "x".repeat(300);
+        let payload = encrypt(&key, &long).unwrap();
+        assert_eq!(decrypt(&other, &payload).unwrap().expose(), long.as_bytes());
+        assert_eq!(padded_len(33), 64);
+        assert_eq!(padded_len(300), 320);
+        let mut tampered = payload.into_bytes();
+        tampered[60] ^= 1;
+        assert!(decrypt(&key, &String::from_utf8(tampered).unwrap()).is_err());
+    }
+}
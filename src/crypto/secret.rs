@@ -0,0 +1,150 @@
+//! Zeroize-on-Drop Secret Buffers
+//!
+//! Secret shares, polynomial shares, nonces and private keys pass through plain
+//! byte and string buffers on their way to and from storage (bincode blobs, hex
+//! strings, JSON state). These wrappers scrub that memory when dropped so the
+//! material does not linger in freed heap pages.
+//!
+//! - **SecretBytes**: serialized secrets (bincode, raw key bytes)
+//! - **SecretString**: hex/JSON encodings of secrets
+//!
+//! Neither type implements `Display`, and `Debug` is redacted, so secrets can't
+//! leak through `{:?}` in logs or error messages.
+//!
+//! Note: `secp256kfun::Scalar` is `Copy` and cannot be scrubbed; keep scalars
+//! short-lived and wrap their encodings instead.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+use zeroize::Zeroize;
+
+/// Byte buffer that is zeroized on drop
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Borrow the secret bytes
+    pub fn expose(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Hex-encode into a scrubbed string
+    pub fn to_hex(&self) -> SecretString {
+        SecretString(hex::encode(&self.0))
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes([REDACTED; {}])", self.0.len())
+    }
+}
+
+/// String that is zeroized on drop
+///
+/// Serializes transparently so it can sit inside secret JSON state.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(s: String) -> Self {
+        Self(s)
+    }
+
+    /// Borrow the secret string
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Decode UTF-8 bytes without leaving an unscrubbed copy behind
+    pub fn from_utf8(bytes: SecretBytes) -> Result<Self> {
+        let s = std::str::from_utf8(&bytes)?;
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl Deref for SecretString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString([REDACTED; {}])", self.0.len())
+    }
+}
+
+/// bincode-serialize a secret value into a scrubbed buffer
+pub fn serialize_secret<T: Serialize>(value: &T) -> Result<SecretBytes> {
+    Ok(SecretBytes(bincode::serialize(value)?))
+}
+
+/// JSON-serialize a secret value into a scrubbed string
+pub fn to_json_secret<T: Serialize>(value: &T) -> Result<SecretString> {
+    Ok(SecretString(serde_json::to_string_pretty(value)?))
+}
+
+/// Hex-encode secret bytes into a scrubbed string
+pub fn hex_secret(bytes: &[u8]) -> SecretString {
+    SecretString(hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_buffers() {
+        let secret = SecretBytes::new(vec![0xAB; 32]);
+        assert_eq!(secret.len(), 32);
+        assert_eq!(format!("{:?}", secret), "SecretBytes([REDACTED; 32])");
+        assert_eq!(secret.to_hex().expose(), "ab".repeat(32));
+
+        let json = SecretString::from_utf8(SecretBytes::new(b"{\"a\":1}".to_vec())).unwrap();
+        assert_eq!(&*json, "{\"a\":1}");
+        assert!(!format!("{:?}", json).contains("a\":1"));
+        assert!(SecretString::from_utf8(SecretBytes::new(vec![0xff])).is_err());
+    }
+}
@@ -0,0 +1,552 @@
+//! SLIP-39 Shamir Mnemonics
+//!
+//! The share format hardware wallets (Trezor) and steel-backup kits use: a
+//! secret is encrypted with a passphrase (4-round Feistel over
+//! PBKDF2-HMAC-SHA256), split k-of-m with Shamir over GF(256), and each
+//! share written as words from a 1024-word list with an RS1024 checksum.
+//! A 256-bit secret gives 33-word shares, a 128-bit one 20 words.
+//!
+//! Only single-group splits are made and read (group threshold 1 of 1),
+//! which is all a party's share backup needs. As the spec intends, any
+//! passphrase decrypts to *some* secret; callers check the result.
+//!
+//! Spec: <https://github.com/satoshilabs/slips/blob/master/slip-0039.md>
+
+use anyhow::{bail, Context, Result};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::sync::OnceLock;
+use zeroize::Zeroize;
+
+/// The SLIP-39 wordlist, one word per line
+const WORDLIST_TXT: &str = include_str!("slip39_wordlist.txt");
+
+const RADIX_BITS: usize = 10;
+const CHECKSUM_WORDS: usize = 3;
+/// Identifier, extendable flag and iteration exponent, then the group and member parameters
+const METADATA_WORDS: usize = 4 + CHECKSUM_WORDS;
+const BASE_ITERATIONS: u32 = 10_000;
+const ROUNDS: u8 = 4;
+const DIGEST_LENGTH: usize = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const MAX_SHARES: u8 = 16;
+
+/// RS1024 generator (SLIP-39 "Checksum")
+const GEN: [u32; 10] = [
+    0xE0E040, 0x1C1C080, 0x3838100, 0x7070200, 0xE0E0009, 0x1C0C2412, 0x38086C24, 0x3090FC48,
+    0x21B1F890, 0x3F3F120,
+];
+
+fn wordlist() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORDLIST_TXT.lines().collect())
+}
+
+/// One decoded share mnemonic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    /// Random 15-bit id shared by all shares of one split
+    pub identifier: u16,
+    pub extendable: bool,
+    /// PBKDF2 runs 10000 << e iterations in total
+    pub iteration_exponent: u8,
+    pub group_index: u8,
+    pub group_threshold: u8,
+    pub group_count: u8,
+    pub member_index: u8,
+    pub member_threshold: u8,
+    pub value: Vec<u8>,
+}
+
+impl Drop for Share {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+// ============================================================================
+// Checksum and word encoding
+// ============================================================================
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        b"shamir_extendable"
+    } else {
+        b"shamir"
+    }
+}
+
+fn polymod(values: impl IntoIterator<Item = u32>) -> u32 {
+    let mut chk = 1u32;
+    for value in values {
+        let top = chk >> 20;
+        chk = ((chk & 0xFFFFF) << 10) ^ value;
+        for (i, generator) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+fn checksum(data: &[u32], extendable: bool) -> [u32; 3] {
+    let values = customization(extendable)
+        .iter()
+        .map(|&b| b as u32)
+        .chain(data.iter().copied())
+        .chain([0, 0, 0]);
+    let chk = polymod(values) ^ 1;
+    [(chk >> 20) & 1023, (chk >> 10) & 1023, chk & 1023]
+}
+
+fn checksum_ok(data: &[u32], extendable: bool) -> bool {
+    let values = customization(extendable)
+        .iter()
+        .map(|&b| b as u32)
+        .chain(data.iter().copied());
+    polymod(values) == 1
+}
+
+/// Big-endian bytes as 10-bit words, left-padded with zero bits
+fn bytes_to_words(bytes: &[u8]) -> Vec<u32> {
+    let bits = bytes.len() * 8;
+    let count = bits.div_ceil(RADIX_BITS);
+    let mut words = vec![0u32; count];
+    for bit in 0..bits {
+        if bytes[bit / 8] >> (7 - bit % 8) & 1 == 1 {
+            let pos = count * RADIX_BITS - bits + bit;
+            words[pos / RADIX_BITS] |= 1 << (RADIX_BITS - 1 - pos % RADIX_BITS);
+        }
+    }
+    words
+}
+
+impl Share {
+    /// The share as space-separated words
+    pub fn to_mnemonic(&self) -> String {
+        let id_exp = (self.identifier as u32) << 5
+            | (self.extendable as u32) << 4
+            | self.iteration_exponent as u32;
+        let params = (self.group_index as u32) << 16
+            | (self.group_threshold as u32 - 1) << 12
+            | (self.group_count as u32 - 1) << 8
+            | (self.member_index as u32) << 4
+            | (self.member_threshold as u32 - 1);
+        let mut data = vec![id_exp >> 10, id_exp & 1023, params >> 10, params & 1023];
+        data.extend(bytes_to_words(&self.value));
+        let chk = checksum(&data, self.extendable);
+        data.extend(chk);
+        let words = wordlist();
+        data.iter()
+            .map(|&i| words[i as usize])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parse and checksum one share mnemonic
+    pub fn from_mnemonic(mnemonic: &str) -> Result<Self> {
+        let words = wordlist();
+        let data = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                words
+                    .binary_search(&word.as_str())
+                    .map(|i| i as u32)
+                    .map_err(|_| anyhow::anyhow!("'{}' is not a SLIP-39 word", word))
+            })
+            .collect::<Result<Vec<u32>>>()?;
+        // 128 bits of secret is the shortest share SLIP-39 allows
+        if data.len() < METADATA_WORDS + 13 {
+            bail!("Too few words for a SLIP-39 share ({})", data.len());
+        }
+        let padding = RADIX_BITS * (data.len() - METADATA_WORDS) % 16;
+        if padding > 8 {
+            bail!("Invalid SLIP-39 share length ({} words)", data.len());
+        }
+
+        let id_exp = data[0] << 10 | data[1];
+        let extendable = (id_exp >> 4) & 1 == 1;
+        if !checksum_ok(&data, extendable) {
+            bail!("SLIP-39 checksum failed (a word is wrong or missing)");
+        }
+        let params = data[2] << 10 | data[3];
+
+        let value_words = &data[4..data.len() - CHECKSUM_WORDS];
+        let value_bits = value_words.len() * RADIX_BITS - padding;
+        let mut value = vec![0u8; value_bits / 8];
+        for (w, &word) in value_words.iter().enumerate() {
+            for b in 0..RADIX_BITS {
+                let pos = w * RADIX_BITS + b;
+                let set = word >> (RADIX_BITS - 1 - b) & 1 == 1;
+                if pos < padding {
+                    if set {
+                        bail!("Invalid padding in SLIP-39 share");
+                    }
+                } else if set {
+                    let bit = pos - padding;
+                    value[bit / 8] |= 1 << (7 - bit % 8);
+                }
+            }
+        }
+
+        let share = Share {
+            identifier: (id_exp >> 5) as u16,
+            extendable,
+            iteration_exponent: (id_exp & 0xF) as u8,
+            group_index: (params >> 16) as u8,
+            group_threshold: ((params >> 12) & 0xF) as u8 + 1,
+            group_count: ((params >> 8) & 0xF) as u8 + 1,
+            member_index: ((params >> 4) & 0xF) as u8,
+            member_threshold: (params & 0xF) as u8 + 1,
+            value,
+        };
+        if share.group_threshold > share.group_count {
+            bail!("SLIP-39 share has group threshold above its group count");
+        }
+        Ok(share)
+    }
+}
+
+// ============================================================================
+// Shamir over GF(256)
+// ============================================================================
+
+/// (exp, log) tables for GF(256) with the Rijndael polynomial, generator 3
+fn tables() -> &'static ([u8; 255], [u8; 256]) {
+    static TABLES: OnceLock<([u8; 255], [u8; 256])> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let (mut exp, mut log) = ([0u8; 255], [0u8; 256]);
+        let mut poly: u16 = 1;
+        for (i, entry) in exp.iter_mut().enumerate() {
+            *entry = poly as u8;
+            log[poly as usize] = i as u8;
+            let mut doubled = poly << 1;
+            if doubled & 0x100 != 0 {
+                doubled ^= 0x11B;
+            }
+            poly ^= doubled;
+        }
+        (exp, log)
+    })
+}
+
+/// Value at `x` of the polynomial through `shares` (Lagrange over GF(256))
+fn interpolate(shares: &[(u8, Vec<u8>)], x: u8) -> Result<Vec<u8>> {
+    if let Some((_, value)) = shares.iter().find(|(xi, _)| *xi == x) {
+        return Ok(value.clone());
+    }
+    let (exp, log) = tables();
+    let len = shares[0].1.len();
+    if shares.iter().any(|(_, value)| value.len() != len) {
+        bail!("SLIP-39 shares have different lengths");
+    }
+    let log_prod: u32 = shares
+        .iter()
+        .map(|(xi, _)| log[(xi ^ x) as usize] as u32)
+        .sum();
+    let mut result = vec![0u8; len];
+    for (xi, value) in shares {
+        let others: u32 = shares
+            .iter()
+            .map(|(xj, _)| log[(xj ^ xi) as usize] as u32)
+            .sum();
+        let log_basis =
+            (log_prod + 255 * shares.len() as u32 - log[(xi ^ x) as usize] as u32 - others) % 255;
+        for (r, &v) in result.iter_mut().zip(value) {
+            if v != 0 {
+                *r ^= exp[((log[v as usize] as u32 + log_basis) % 255) as usize];
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn digest(random: &[u8], secret: &[u8]) -> [u8; DIGEST_LENGTH] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random).expect("HMAC accepts any key length");
+    mac.update(secret);
+    let full = mac.finalize().into_bytes();
+    let mut out = [0u8; DIGEST_LENGTH];
+    out.copy_from_slice(&full[..DIGEST_LENGTH]);
+    out
+}
+
+fn split_secret(threshold: u8, count: u8, secret: &[u8]) -> Result<Vec<(u8, Vec<u8>)>> {
+    if threshold == 1 {
+        return Ok((0..count).map(|i| (i, secret.to_vec())).collect());
+    }
+    let mut rng = rand::thread_rng();
+    let random_count = threshold - 2;
+    let mut shares: Vec<(u8, Vec<u8>)> = (0..random_count)
+        .map(|i| {
+            let mut value = vec![0u8; secret.len()];
+            rng.fill_bytes(&mut value);
+            (i, value)
+        })
+        .collect();
+    let mut random_part = vec![0u8; secret.len() - DIGEST_LENGTH];
+    rng.fill_bytes(&mut random_part);
+    let mut digest_share = digest(&random_part, secret).to_vec();
+    digest_share.extend_from_slice(&random_part);
+    random_part.zeroize();
+
+    let mut base = shares.clone();
+    base.push((DIGEST_INDEX, digest_share));
+    base.push((SECRET_INDEX, secret.to_vec()));
+    for i in random_count..count {
+        shares.push((i, interpolate(&base, i)?));
+    }
+    for (_, value) in &mut base {
+        value.zeroize();
+    }
+    Ok(shares)
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, Vec<u8>)]) -> Result<Vec<u8>> {
+    if threshold == 1 {
+        return Ok(shares[0].1.clone());
+    }
+    let secret = interpolate(shares, SECRET_INDEX)?;
+    let mut digest_share = interpolate(shares, DIGEST_INDEX)?;
+    let ok = digest(&digest_share[DIGEST_LENGTH..], &secret) == digest_share[..DIGEST_LENGTH];
+    digest_share.zeroize();
+    if !ok {
+        bail!("SLIP-39 shares don't fit together (digest mismatch)");
+    }
+    Ok(secret)
+}
+
+// ============================================================================
+// Encryption
+// ============================================================================
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    let mut block = 1u32;
+    while out.len() < len {
+        let mac = |data: &[u8]| -> [u8; 32] {
+            let mut mac =
+                Hmac::<Sha256>::new_from_slice(password).expect("HMAC accepts any key length");
+            mac.update(data);
+            mac.finalize().into_bytes().into()
+        };
+        let mut u = mac(&[salt, &block.to_be_bytes()].concat());
+        let mut t = u;
+        for _ in 1..iterations {
+            u = mac(&u);
+            t.iter_mut().zip(u.iter()).for_each(|(t, u)| *t ^= u);
+        }
+        let take = (len - out.len()).min(32);
+        out.extend_from_slice(&t[..take]);
+        t.zeroize();
+        u.zeroize();
+        block += 1;
+    }
+    out
+}
+
+fn feistel(
+    data: &[u8],
+    passphrase: &[u8],
+    share: &Share,
+    rounds: impl Iterator<Item = u8>,
+) -> Vec<u8> {
+    let half = data.len() / 2;
+    let (mut left, mut right) = (data[..half].to_vec(), data[half..].to_vec());
+    let mut salt = Vec::new();
+    if !share.extendable {
+        salt.extend_from_slice(b"shamir");
+        salt.extend_from_slice(&share.identifier.to_be_bytes());
+    }
+    let iterations = (BASE_ITERATIONS << share.iteration_exponent) / ROUNDS as u32;
+    for round in rounds {
+        let password = [&[round][..], passphrase].concat();
+        let f = pbkdf2_sha256(&password, &[&salt[..], &right].concat(), iterations, half);
+        let next: Vec<u8> = left.iter().zip(&f).map(|(l, f)| l ^ f).collect();
+        left.zeroize();
+        left = std::mem::replace(&mut right, next);
+    }
+    let mut out = right;
+    out.extend_from_slice(&left);
+    left.zeroize();
+    out
+}
+
+// ============================================================================
+// Split and combine
+// ============================================================================
+
+/// Split `secret` into `count` share mnemonics, any `threshold` of which restore it
+///
+/// `secret` must be at least 16 bytes and of even length; `passphrase` may
+/// be empty. Shares are extendable, one group, iteration exponent 1.
+pub fn split(secret: &[u8], threshold: u8, count: u8, passphrase: &[u8]) -> Result<Vec<String>> {
+    if secret.len() < 16 || !secret.len().is_multiple_of(2) {
+        bail!("SLIP-39 secrets are at least 16 bytes, of even length");
+    }
+    if threshold == 0 || threshold > count || count > MAX_SHARES {
+        bail!(
+            "Need 1 <= threshold <= shares <= {} (got {}-of-{})",
+            MAX_SHARES,
+            threshold,
+            count
+        );
+    }
+    let mut template = Share {
+        identifier: (rand::thread_rng().next_u32() & 0x7FFF) as u16,
+        extendable: true,
+        iteration_exponent: 1,
+        group_index: 0,
+        group_threshold: 1,
+        group_count: 1,
+        member_index: 0,
+        member_threshold: threshold,
+        value: Vec::new(),
+    };
+    let encrypted = feistel(secret, passphrase, &template, 0..ROUNDS);
+    let mnemonics = split_secret(threshold, count, &encrypted)?
+        .into_iter()
+        .map(|(index, value)| {
+            template.member_index = index;
+            template.value = value;
+            template.to_mnemonic()
+        })
+        .collect();
+    Ok(mnemonics)
+}
+
+/// Restore the secret from share mnemonics of one single-group split
+pub fn combine(mnemonics: &[&str], passphrase: &[u8]) -> Result<Vec<u8>> {
+    let shares = mnemonics
+        .iter()
+        .enumerate()
+        .map(|(i, m)| Share::from_mnemonic(m).with_context(|| format!("Share #{}", i + 1)))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(first) = shares.first() else {
+        bail!("No SLIP-39 shares given");
+    };
+    if first.group_count != 1 || first.group_threshold != 1 {
+        bail!("Multi-group SLIP-39 backups are not supported");
+    }
+    for share in &shares {
+        if (share.identifier, share.extendable, share.iteration_exponent)
+            != (first.identifier, first.extendable, first.iteration_exponent)
+            || share.member_threshold != first.member_threshold
+            || share.group_index != first.group_index
+        {
+            bail!("SLIP-39 shares come from different splits");
+        }
+    }
+    let mut indexed: Vec<(u8, Vec<u8>)> = Vec::new();
+    for share in &shares {
+        if indexed.iter().any(|(x, _)| *x == share.member_index) {
+            bail!("SLIP-39 share {} was given twice", share.member_index + 1);
+        }
+        indexed.push((share.member_index, share.value.clone()));
+    }
+    if indexed.len() < first.member_threshold as usize {
+        bail!(
+            "Need {} SLIP-39 shares, got {}",
+            first.member_threshold,
+            indexed.len()
+        );
+    }
+    indexed.truncate(first.member_threshold as usize);
+
+    let mut encrypted = recover_secret(first.member_threshold, &indexed)?;
+    for (_, value) in &mut indexed {
+        value.zeroize();
+    }
+    let secret = feistel(&encrypted, passphrase, first, (0..ROUNDS).rev());
+    encrypted.zeroize();
+    Ok(secret)
+}
+
+/// A share mnemonic as numbered words in three columns, for writing down
+pub fn format_grid(mnemonic: &str) -> String {
+    let words: Vec<&str> = mnemonic.split_whitespace().collect();
+    let rows = words.len().div_ceil(3);
+    (0..rows)
+        .map(|row| {
+            (0..3)
+                .filter_map(|col| {
+                    let idx = row + col * rows;
+                    words.get(idx).map(|w| format!("{:2}. {:10}", idx + 1, w))
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether `text` looks like a SLIP-39 share (every word on the list)
+pub fn is_slip39(text: &str) -> bool {
+    let words = wordlist();
+    let mut count = 0;
+    text.split_whitespace().all(|word| {
+        count += 1;
+        words.binary_search(&word.to_lowercase().as_str()).is_ok()
+    }) && count >= METADATA_WORDS + 13
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wordlist() {
+        let words = wordlist();
+        assert_eq!(words.len(), 1024);
+        assert!(words.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_spec_vectors() {
+        // SLIP-39 test vectors 1 (no sharing) and 4 (2-of-3), passphrase "TREZOR"
+        let single = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+        assert_eq!(
+            hex::encode(combine(&[single], b"TREZOR").unwrap()),
+            "bb54aac4b89dc868ba37d9cc21b2cece"
+        );
+        let shares = [
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ];
+        assert_eq!(
+            hex::encode(combine(&shares, b"TREZOR").unwrap()),
+            "b43ceb7e57a0ea8766221624d01b0864"
+        );
+        assert!(combine(&shares[..1], b"TREZOR").is_err());
+
+        // A changed word fails the checksum
+        let typo = single.replacen("fridge", "friar", 1);
+        assert!(Share::from_mnemonic(&typo).is_err());
+        assert_eq!(Share::from_mnemonic(single).unwrap().to_mnemonic(), single);
+    }
+
+    #[test]
+    fn test_split_and_combine_256_bits() {
+        let secret = [0x5Au8; 32];
+        let shares = split(&secret, 2, 3, b"pass").unwrap();
+        assert!(shares
+            .iter()
+            .all(|s| s.split(' ').count() == 33 && is_slip39(s)));
+        let any_two = [shares[2].as_str(), shares[0].as_str()];
+        assert_eq!(combine(&any_two, b"pass").unwrap(), secret);
+        assert_ne!(combine(&any_two, b"wrong").unwrap(), secret);
+
+        let other = split(&secret, 2, 3, b"pass").unwrap();
+        assert!(combine(&[shares[0].as_str(), other[1].as_str()], b"pass").is_err());
+        assert!(!is_slip39("1: abandon ability"));
+    }
+}
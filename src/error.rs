@@ -0,0 +1,164 @@
+//! Error kinds and process exit codes
+//!
+//! Library functions return `anyhow::Result`. Failures a script may want to
+//! react to are raised as an [`Error`] inside that chain, and [`kind_of`]
+//! walks the chain to classify any error: ours by variant, HTTP failures as
+//! network errors, file I/O as storage errors and malformed JSON as user
+//! errors. The CLI exits with [`ErrorKind::exit_code`] and, with `--json`,
+//! prints [`to_json`].
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
+
+/// What went wrong, as far as a caller is concerned
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Anything not classified below
+    Other,
+    /// Bad arguments or pasted input
+    User,
+    /// A share, proof or signature failed verification
+    Protocol,
+    /// An API, relay or sync backend could not be reached or refused
+    Network,
+    /// Wallet files are missing, unreadable or insecure
+    Storage,
+    /// The wallet cannot cover the amount plus fee
+    InsufficientFunds,
+}
+
+impl ErrorKind {
+    /// Process exit code; 2 matches clap's code for bad arguments
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::User => 2,
+            ErrorKind::Protocol => 3,
+            ErrorKind::Network => 4,
+            ErrorKind::Storage => 5,
+            ErrorKind::InsufficientFunds => 6,
+        }
+    }
+}
+
+/// A classified failure, carried inside an `anyhow::Error`
+#[derive(Debug)]
+pub enum Error {
+    User(String),
+    Protocol(String),
+    Network(String),
+    Storage(String),
+    InsufficientFunds { needed: u64, available: u64 },
+}
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::User(_) => ErrorKind::User,
+            Error::Protocol(_) => ErrorKind::Protocol,
+            Error::Network(_) => ErrorKind::Network,
+            Error::Storage(_) => ErrorKind::Storage,
+            Error::InsufficientFunds { .. } => ErrorKind::InsufficientFunds,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::User(message)
+            | Error::Protocol(message)
+            | Error::Network(message)
+            | Error::Storage(message) => f.write_str(message),
+            Error::InsufficientFunds { needed, available } => write!(
+                f,
+                "Insufficient funds. Need {} sats, have {} sats",
+                needed, available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The kind of the outermost classifiable cause
+pub fn kind_of(err: &anyhow::Error) -> ErrorKind {
+    for cause in err.chain() {
+        if let Some(error) = cause.downcast_ref::<Error>() {
+            return error.kind();
+        }
+        #[cfg(feature = "network")]
+        if cause.is::<reqwest::Error>()
+            || cause.is::<crate::btc::http::CircuitOpen>()
+            || cause.is::<crate::btc::http::DeadlineExceeded>()
+        {
+            return ErrorKind::Network;
+        }
+        if cause.downcast_ref::<std::io::Error>().is_some() {
+            return ErrorKind::Storage;
+        }
+        if cause.downcast_ref::<serde_json::Error>().is_some() {
+            return ErrorKind::User;
+        }
+    }
+    ErrorKind::Other
+}
+
+/// `{"error": {"kind", "exit_code", "message"}}` for `--json` output
+///
+/// Insufficient funds also carries `needed` and `available` in sats.
+pub fn to_json(err: &anyhow::Error) -> Value {
+    let kind = kind_of(err);
+    let mut error = json!({
+        "kind": kind,
+        "exit_code": kind.exit_code(),
+        "message": format!("{:#}", err),
+    });
+    if let Some(Error::InsufficientFunds { needed, available }) =
+        err.chain().find_map(|cause| cause.downcast_ref::<Error>())
+    {
+        error["needed"] = json!(needed);
+        error["available"] = json!(available);
+    }
+    json!({ "error": error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_kind_of_walks_the_chain() {
+        let err = anyhow::Error::new(Error::Protocol("Share verification failed".into()))
+            .context("Finalize failed");
+        assert_eq!(kind_of(&err), ErrorKind::Protocol);
+
+        let io = std::fs::read("/nonexistent/frostdao").context("Failed to read wallet");
+        assert_eq!(kind_of(&io.unwrap_err()), ErrorKind::Storage);
+
+        let bad_json = serde_json::from_str::<Value>("{").context("Invalid --data");
+        assert_eq!(kind_of(&bad_json.unwrap_err()), ErrorKind::User);
+
+        assert_eq!(kind_of(&anyhow::anyhow!("boom")), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_insufficient_funds_json() {
+        let err: anyhow::Error = Error::InsufficientFunds {
+            needed: 1500,
+            available: 1000,
+        }
+        .into();
+        let value = to_json(&err);
+        assert_eq!(value["error"]["kind"], "insufficient_funds");
+        assert_eq!(value["error"]["exit_code"], 6);
+        assert_eq!(value["error"]["needed"], 1500);
+        assert_eq!(
+            value["error"]["message"],
+            "Insufficient funds. Need 1500 sats, have 1000 sats"
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! C FFI for Mobile Wallets
+//!
+//! A plain C ABI over the protocol cores, so iOS (Swift) and Android
+//! (Kotlin/JNI) apps can link the `cdylib` instead of reimplementing FROST.
+//! The declarations live in `include/frostdao.h`.
+//!
+//! Conventions:
+//! - Wallets are addressed by directory (inside the app's sandbox), not by
+//!   name under `.frost_state`; the directory is created if needed.
+//! - Every string argument is NUL-terminated UTF-8; optional ones may be NULL.
+//! - Every call returns a JSON envelope the caller must release with
+//!   `frostdao_string_free`:
+//!   `{"ok":true,"output":"...","result":...}` (`result` parsed as in WASM) or
+//!   `{"ok":false,"code":"...","message":"..."}`, with the same error codes
+//!   as the WASM `FrostError`.
+//! - Panics are caught and reported with code `panic`; none cross the boundary.
+
+use crate::btc::hd_address;
+use crate::protocol::{keygen, reshare, signing};
+use crate::storage::FileStorage;
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Borrow a required string argument
+unsafe fn arg<'a>(name: &str, ptr: *const c_char) -> Result<&'a str> {
+    if ptr.is_null() {
+        bail!("Invalid argument: {} is NULL", name);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .with_context(|| format!("Invalid argument: {} is not UTF-8", name))
+}
+
+/// Borrow an optional string argument (NULL or empty means none)
+unsafe fn opt_arg<'a>(name: &str, ptr: *const c_char) -> Result<Option<&'a str>> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(arg(name, ptr)?).filter(|value| !value.is_empty()))
+}
+
+/// Last path component, used where the cores want a wallet name
+fn wallet_name(wallet_dir: &str) -> String {
+    std::path::Path::new(wallet_dir)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| wallet_dir.to_string())
+}
+
+/// Run a core and wrap its outcome in the JSON envelope
+fn respond(call: impl FnOnce() -> Result<CommandResult>) -> *mut c_char {
+    let envelope = match catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(cmd_result)) => serde_json::json!({
+            "ok": true,
+            "output": cmd_result.output,
+            "result": crate::wasm::structured_result(cmd_result.result),
+        }),
+        Ok(Err(e)) => {
+            let message = format!("{:#}", e);
+            serde_json::json!({
+                "ok": false,
+                "code": crate::wasm::error_code(&message),
+                "message": message,
+            })
+        }
+        Err(_) => serde_json::json!({
+            "ok": false,
+            "code": "panic",
+            "message": "frostdao panicked; the wallet state may need dkg-doctor",
+        }),
+    };
+    // serde_json escapes NUL, so this cannot fail
+    CString::new(envelope.to_string())
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Release a string returned by any `frostdao_*` function
+///
+/// # Safety
+/// `ptr` must come from this library and not be freed twice. NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_string_free(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+// ============================================================================
+// Keygen
+// ============================================================================
+
+/// Keygen round 1 (secp256k1)
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_keygen_round1(
+    wallet_dir: *const c_char,
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    rank: u32,
+    hierarchical: bool,
+) -> *mut c_char {
+    respond(|| {
+        let storage = FileStorage::new(arg("wallet_dir", wallet_dir)?)?;
+        keygen::round1_core(threshold, n_parties, my_index, rank, hierarchical, &storage)
+    })
+}
+
+/// Keygen round 2: `data` holds every party's round 1 output
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_keygen_round2(
+    wallet_dir: *const c_char,
+    data: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let storage = FileStorage::new(arg("wallet_dir", wallet_dir)?)?;
+        keygen::round2_core(arg("data", data)?, &storage)
+    })
+}
+
+/// Keygen finalize: `data` holds the round 2 outputs
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_keygen_finalize(
+    wallet_dir: *const c_char,
+    data: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let storage = FileStorage::new(arg("wallet_dir", wallet_dir)?)?;
+        keygen::finalize_core(arg("data", data)?, &storage)
+    })
+}
+
+// ============================================================================
+// Signing
+// ============================================================================
+
+/// Signing nonce for `session`
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_generate_nonce(
+    wallet_dir: *const c_char,
+    session: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let storage = FileStorage::new(arg("wallet_dir", wallet_dir)?)?;
+        signing::generate_nonce_core(arg("session", session)?, &storage)
+    })
+}
+
+/// Signature share over `message`, given every signer's nonce
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_sign(
+    wallet_dir: *const c_char,
+    session: *const c_char,
+    message: *const c_char,
+    nonces: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let storage = FileStorage::new(arg("wallet_dir", wallet_dir)?)?;
+        signing::create_signature_share_core(
+            arg("session", session)?,
+            arg("message", message)?,
+            arg("nonces", nonces)?,
+            &storage,
+        )
+    })
+}
+
+/// Combine signature shares into the final signature
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_combine(
+    wallet_dir: *const c_char,
+    shares: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let storage = FileStorage::new(arg("wallet_dir", wallet_dir)?)?;
+        signing::combine_signatures_core(arg("shares", shares)?, &storage)
+    })
+}
+
+/// Check a BIP340 signature; needs no wallet
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_verify(
+    signature: *const c_char,
+    public_key: *const c_char,
+    message: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        signing::verify_signature_core(
+            arg("signature", signature)?,
+            arg("public_key", public_key)?,
+            arg("message", message)?,
+        )
+    })
+}
+
+// ============================================================================
+// Reshare
+// ============================================================================
+
+/// Reshare round 1: sub-shares of this party's share for the new group
+///
+/// `new_identities` is NULL or comma-separated `<index>=<key>` entries for
+/// new parties whose identity isn't the one pinned at their index.
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings; `new_identities`
+/// may be NULL.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_reshare_round1(
+    wallet_dir: *const c_char,
+    new_threshold: u32,
+    new_n_parties: u32,
+    my_old_index: u32,
+    new_identities: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let wallet_dir = arg("wallet_dir", wallet_dir)?;
+        let storage = FileStorage::new(wallet_dir)?;
+        let new_identities: Vec<String> = opt_arg("new_identities", new_identities)?
+            .map(|list| list.split(',').map(|e| e.trim().to_string()).collect())
+            .unwrap_or_default();
+        reshare::reshare_round1_with_storage(
+            &wallet_name(wallet_dir),
+            new_threshold,
+            new_n_parties,
+            my_old_index,
+            &new_identities,
+            &storage,
+            &storage,
+        )
+    })
+}
+
+/// Reshare finalize: combine sub-shares into a new wallet at `target_dir`
+///
+/// With `force_overwrite` a wallet already at `target_dir` is replaced whole.
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_reshare_finalize(
+    source_dir: *const c_char,
+    target_dir: *const c_char,
+    my_new_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    round1_data: *const c_char,
+    force_overwrite: bool,
+    allow_threshold_decrease: bool,
+) -> *mut c_char {
+    respond(|| {
+        let target_dir = arg("target_dir", target_dir)?;
+        reshare::reshare_finalize_in_dirs(
+            arg("source_dir", source_dir)?,
+            target_dir,
+            &wallet_name(target_dir),
+            my_new_index,
+            my_rank,
+            hierarchical,
+            arg("round1_data", round1_data)?,
+            force_overwrite,
+            allow_threshold_decrease,
+        )
+    })
+}
+
+// ============================================================================
+// HD derivation and transactions
+// ============================================================================
+
+/// Taproot address at m/44'/0'/0'/`change`/`index` on `network`
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_derive_address(
+    wallet_dir: *const c_char,
+    change: u32,
+    index: u32,
+    network: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let storage = FileStorage::new(arg("wallet_dir", wallet_dir)?)?;
+        hd_address::derive_address_core(change, index, arg("network", network)?, &storage)
+    })
+}
+
+/// Unsigned transaction and sighash for a DKG signing session
+///
+/// `fee_rate` of 0 uses the current estimate; `from_script_address` may be NULL.
+/// Fetches UTXOs over HTTP, so it is only built with the `network` feature.
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings (or NULL where noted).
+#[cfg(feature = "network")]
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_build_tx(
+    wallet_dir: *const c_char,
+    to_address: *const c_char,
+    amount_sats: u64,
+    fee_rate: u64,
+    network: *const c_char,
+    from_script_address: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let wallet_dir = arg("wallet_dir", wallet_dir)?;
+        let storage = FileStorage::new(wallet_dir)?;
+        crate::protocol::dkg_tx::build_unsigned_tx_core(
+            &wallet_name(wallet_dir),
+            arg("to_address", to_address)?,
+            amount_sats,
+            (fee_rate > 0).then_some(fee_rate),
+            hd_address::parse_network(arg("network", network)?)?,
+            false,
+            opt_arg("from_script_address", from_script_address)?,
+            None,
+            None,
+            None,
+            &storage,
+        )
+    })
+}
+
+/// Nonce for a transaction session from `frostdao_build_tx`
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_tx_nonce(
+    wallet_dir: *const c_char,
+    session_id: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let wallet_dir = arg("wallet_dir", wallet_dir)?;
+        let storage = FileStorage::new(wallet_dir)?;
+        crate::protocol::dkg_tx::dkg_generate_nonce_core(
+            &wallet_name(wallet_dir),
+            arg("session_id", session_id)?,
+            &storage,
+        )
+    })
+}
+
+/// Signature share over a transaction sighash, given every signer's nonce
+///
+/// `tx` is the dkg-build-tx output JSON, reviewed before signing; NULL only
+/// works for the party that built the transaction. `merkle_root` may be NULL
+/// for key-path outputs.
+///
+/// # Safety
+/// String arguments must be valid NUL-terminated strings (or NULL where noted).
+#[no_mangle]
+pub unsafe extern "C" fn frostdao_tx_sign(
+    wallet_dir: *const c_char,
+    session_id: *const c_char,
+    sighash: *const c_char,
+    nonces: *const c_char,
+    merkle_root: *const c_char,
+    tx: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let wallet_dir = arg("wallet_dir", wallet_dir)?;
+        let storage = FileStorage::new(wallet_dir)?;
+        let tx: Option<crate::protocol::dkg_tx::BuildTxOutput> =
+            opt_arg("tx", tx)?.map(serde_json::from_str).transpose()?;
+        crate::protocol::dkg_tx::dkg_sign_core(
+            &wallet_name(wallet_dir),
+            arg("session_id", session_id)?,
+            arg("sighash", sighash)?,
+            arg("nonces", nonces)?,
+            opt_arg("merkle_root", merkle_root)?,
+            tx.as_ref(),
+            false,
+            &storage,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Call through the C ABI and parse the envelope
+    fn call(ptr: *mut c_char) -> serde_json::Value {
+        assert!(!ptr.is_null());
+        let json = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string();
+        unsafe { frostdao_string_free(ptr) };
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_ffi_envelopes() {
+        let dir = std::env::temp_dir().join(format!("frostdao_ffi_{}", std::process::id()));
+        let dir_c = CString::new(dir.to_string_lossy().as_bytes()).unwrap();
+
+        let round1 = call(unsafe { frostdao_keygen_round1(dir_c.as_ptr(), 2, 3, 1, 0, false) });
+        assert_eq!(round1["ok"], true);
+        assert_eq!(round1["result"]["party_index"], 1);
+        assert_eq!(round1["result"]["type"], "keygen_round1");
+
+        let garbage = CString::new("{\"type\":\"keygen_round2\"").unwrap();
+        let round2 = call(unsafe { frostdao_keygen_round2(dir_c.as_ptr(), garbage.as_ptr()) });
+        assert_eq!(round2["ok"], false);
+        assert_eq!(round2["code"], "invalid_payload");
+
+        let null = call(unsafe { frostdao_keygen_round2(dir_c.as_ptr(), std::ptr::null()) });
+        assert_eq!(
+            (null["code"].as_str(), null["message"].as_str()),
+            (
+                Some("invalid_input"),
+                Some("Invalid argument: data is NULL")
+            )
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,9 +1,24 @@
 use wasm_bindgen::prelude::*;
 
+// Air-gapped builds must not link any HTTP client
+#[cfg(all(feature = "offline", feature = "network"))]
+compile_error!(
+    "the `offline` feature excludes networking: build with --no-default-features --features offline"
+);
+
 pub mod btc;
 pub mod crypto;
+pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ffi;
+pub mod notify;
 pub mod protocol;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod qr;
 pub mod storage;
+#[cfg(feature = "sync")]
+pub mod sync;
 pub mod wasm;
 
 // Re-export WASM functions
@@ -1,22 +1,127 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::path::PathBuf;
 
 // Use library crate for core functionality
-use frostdao::btc::{schnorr as bitcoin_schnorr, transaction as bitcoin_tx};
-use frostdao::protocol::{dkg_tx, keygen, recovery, reshare, signing};
-use frostdao::storage::Storage; // For HD commands
+#[cfg(feature = "network")]
+use frostdao::btc::amount;
+#[cfg(feature = "network")]
+use frostdao::btc::fiat::RateProvider;
+use frostdao::btc::lightning::{self, ChannelType};
+use frostdao::btc::schnorr as bitcoin_schnorr;
+#[cfg(feature = "network")]
+use frostdao::btc::transaction as bitcoin_tx;
+use frostdao::crypto::ciphersuite::Curve;
+use frostdao::protocol::relay::Relays;
+#[cfg(feature = "network")]
+use frostdao::protocol::relay_client;
+use frostdao::protocol::{
+    backup, dealer, device, directory, dkg_tx, doctor, fingerprint, health, identity, keygen,
+    keygen_resume, nostr, offline, reconstruct, recovery, reshare, session_report, signing, wallet,
+};
+use frostdao::storage::ephemeral::{absolute_path, EphemeralState};
+use std::time::Duration;
+
+mod prompt;
 
 // TUI is CLI-only, not part of lib
+#[cfg(feature = "network")]
 mod tui;
 
 #[derive(Parser)]
 #[command(name = "frostdao")]
 #[command(about = "FrostDAO - FROST threshold signatures for Bitcoin", long_about = None)]
 struct Cli {
+    /// Skip the step-by-step explanations; print only warnings and results
+    #[arg(long, global = true)]
+    quiet: bool,
+
+    /// Load secrets from files other users can read (warns instead of refusing)
+    #[arg(long, global = true)]
+    insecure_permissions: bool,
+
+    /// Print round payloads as compact `cbor:` tokens instead of JSON
+    #[arg(long, global = true)]
+    compact: bool,
+
+    /// Payload protocol version to write (1 while other parties still run an older frostdao)
+    #[arg(long, global = true, default_value_t = frostdao::protocol::payload::PROTOCOL_VERSION)]
+    protocol_version: u32,
+
+    /// On failure, print the error as a JSON object on stderr
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Esplora API base URL for this run, overriding backend-config (may contain user:pass@)
+    #[cfg(feature = "network")]
+    #[arg(long, global = true)]
+    esplora_url: Option<String>,
+
+    /// Seconds to wait for a network connection
+    #[cfg(feature = "network")]
+    #[arg(long, global = true, default_value_t = 10)]
+    connect_timeout: u64,
+
+    /// Seconds to wait for a single network request to complete
+    #[cfg(feature = "network")]
+    #[arg(long, global = true, default_value_t = 30)]
+    read_timeout: u64,
+
+    /// Seconds a network operation may take including retries
+    #[cfg(feature = "network")]
+    #[arg(long, global = true, default_value_t = 60)]
+    deadline: u64,
+
+    /// Keep all wallet state in memory for this run (demos); it vanishes on exit
+    #[arg(long, global = true)]
+    ephemeral: bool,
+
+    /// Use a local simulated chain (fake coins, blocks and broadcasts) for offline demos
+    #[cfg(feature = "network")]
+    #[arg(long, global = true)]
+    simulate: bool,
+
+    /// Wait up to SECS (default 600) for another frostdao using the same wallet instead of failing
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        num_args = 0..=1,
+        default_missing_value = "600"
+    )]
+    wait: Option<u64>,
+
+    /// Remove the wallet's lock before starting (when the process holding it is gone)
+    #[arg(long, global = true)]
+    force_unlock: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Relays a Nostr-capable command talks to instead of printing its events
+#[cfg(feature = "network")]
+#[derive(clap::Args)]
+struct RelayArgs {
+    /// Publish to and read from the relays saved with relay-config
+    #[arg(long)]
+    nostr: bool,
+
+    /// Use this relay (wss://...) instead of the saved ones; repeatable, implies --nostr
+    #[arg(long = "relay", value_name = "URL")]
+    relays: Vec<String>,
+}
+
+#[cfg(feature = "network")]
+impl RelayArgs {
+    fn connect(&self) -> Result<Option<Relays<'static>>> {
+        match relay_client::resolve(self.nostr, &self.relays)? {
+            Some(urls) => Ok(Some(relay_client::connect_all(&urls)?)),
+            None => Ok(None),
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Round 1 of keygen: Generate polynomial and commitments
@@ -44,6 +149,23 @@ enum Commands {
         /// Enable hierarchical threshold secret sharing (HTSS)
         #[arg(long, default_value = "false")]
         hierarchical: bool,
+
+        /// Number of shares you hold (takes indices my-index..my-index+weight-1)
+        #[arg(long, default_value = "1")]
+        weight: u32,
+
+        /// Key curve: secp256k1 (Bitcoin/Nostr), ed25519 or ristretto255
+        #[arg(long, default_value = "secp256k1")]
+        curve: Curve,
+
+        /// Also show the result as a (possibly animated) QR code
+        #[arg(long)]
+        qr: bool,
+
+        /// INSECURE, demo only: derive the polynomial from this hex seed for reproducible workshop transcripts
+        #[cfg(feature = "demo-seed")]
+        #[arg(long)]
+        seed: Option<String>,
     },
 
     /// Round 2 of keygen: Exchange shares
@@ -52,9 +174,25 @@ enum Commands {
         #[arg(long)]
         name: String,
 
-        /// JSON with all commitments from round 1 (paste from webpage)
+        /// JSON with all commitments from round 1 (paste from webpage, ur: frames or @file); prompted for if omitted
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Also show the result as a (possibly animated) QR code
         #[arg(long)]
-        data: String,
+        qr: bool,
+
+        /// Send each share as a NIP-17 private DM instead of one broadcast
+        #[arg(long)]
+        dm: bool,
+
+        #[cfg(feature = "network")]
+        #[command(flatten)]
+        relay: RelayArgs,
     },
 
     /// Finalize keygen: Validate and combine shares
@@ -63,13 +201,52 @@ enum Commands {
         #[arg(long)]
         name: String,
 
-        /// JSON with all shares sent to you (paste from webpage)
+        /// JSON with all shares sent to you (paste from webpage, ur: frames or @file); prompted for if omitted, unless relays are used
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        #[cfg(feature = "network")]
+        #[command(flatten)]
+        relay: RelayArgs,
+
+        /// With relays: seconds to wait for Round 2 DMs not there yet
+        #[cfg(feature = "network")]
+        #[arg(long, value_name = "SECS", default_value = "60")]
+        listen: u64,
+    },
+
+    /// Show which keygen round a wallet stopped in, and its last output
+    KeygenStatus {
+        /// Wallet/session name
+        #[arg(long)]
+        name: String,
+    },
+
+    /// Continue an interrupted keygen with the other parties' outputs
+    KeygenResume {
+        /// Wallet/session name
+        #[arg(long)]
+        name: String,
+
+        /// Every party's Round 1 or Round 2 outputs (JSON, ur: frames or @file); prompted for if omitted
         #[arg(long)]
-        data: String,
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
     },
 
     /// Generate nonce for signing session
     GenerateNonce {
+        /// Wallet name (defaults to the .frost_state root)
+        #[arg(long)]
+        name: Option<String>,
+
         /// Signing session ID (must be unique per signature)
         #[arg(long)]
         session: String,
@@ -77,6 +254,10 @@ enum Commands {
 
     /// Create signature share
     Sign {
+        /// Wallet name (defaults to the .frost_state root)
+        #[arg(long)]
+        name: Option<String>,
+
         /// Signing session ID
         #[arg(long)]
         session: String,
@@ -85,19 +266,31 @@ enum Commands {
         #[arg(long)]
         message: String,
 
-        /// JSON with nonces and group key (paste from webpage)
+        /// JSON with nonces and group key (paste from webpage); prompted for if omitted
         #[arg(long)]
-        data: String,
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
     },
 
     /// Combine signature shares into final signature
     Combine {
-        /// JSON with all signature shares (includes message, paste from webpage)
+        /// Wallet name (defaults to the .frost_state root)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// JSON with all signature shares (includes message, paste from webpage); prompted for if omitted
         #[arg(long)]
-        data: String,
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
     },
 
-    /// Verify a Schnorr signature
+    /// Verify a Schnorr signature (BIP340, or ed25519/ristretto255 with --curve)
     Verify {
         /// Signature hex (64 bytes / 128 hex chars)
         #[arg(long)]
@@ -110,6 +303,10 @@ enum Commands {
         /// Message that was signed
         #[arg(long)]
         message: String,
+
+        /// Curve of the signing wallet
+        #[arg(long, default_value = "secp256k1")]
+        curve: Curve,
     },
 
     // ========================================================================
@@ -196,6 +393,7 @@ enum Commands {
     },
 
     /// Check DKG group balance (testnet). Without --name, lists all wallets.
+    #[cfg(feature = "network")]
     DkgBalance {
         /// Wallet/session name (optional - lists wallets if not provided)
         #[arg(long)]
@@ -212,293 +410,1804 @@ enum Commands {
         name: String,
     },
 
-    // ========================================================================
-    // HD Key Derivation (BIP-32/BIP-44) Commands
-    // ========================================================================
-    /// Derive address at BIP-44 path (m/44'/0'/0'/change/index)
-    DkgDeriveAddress {
-        /// Wallet name
+    /// Show the ceremony fingerprint (emoji + code) to compare with the other parties
+    DkgFingerprint {
+        /// Wallet/session name
         #[arg(long)]
         name: String,
+    },
 
-        /// Change level (0=external/receive, 1=internal/change)
-        #[arg(long, default_value = "0")]
-        change: u32,
+    /// Split an existing BIP-340 key into t-of-n party folders (trusted dealer)
+    DkgSplitKey {
+        /// Wallet name (creates .frost_state/<name>/party<i>/ folders)
+        #[arg(long)]
+        name: String,
 
-        /// Address index
-        #[arg(long, default_value = "0")]
-        index: u32,
+        /// Threshold (minimum signers needed)
+        #[arg(long)]
+        threshold: u32,
 
-        /// Network (testnet, mainnet, signet)
-        #[arg(long, default_value = "testnet")]
-        network: String,
-    },
+        /// Total number of parties
+        #[arg(long)]
+        n_parties: u32,
 
-    /// List multiple derived addresses
-    DkgListAddresses {
-        /// Wallet name
+        /// Secret key in hex (defaults to the key saved by btc-keygen)
         #[arg(long)]
-        name: String,
+        secret: Option<String>,
 
-        /// Number of addresses to derive
-        #[arg(long, default_value = "10")]
-        count: u32,
+        /// HTSS ranks, one per party, e.g. 0,1,1 (makes the group hierarchical)
+        #[arg(long, value_delimiter = ',')]
+        ranks: Option<Vec<u32>>,
+    },
 
-        /// Network (testnet, mainnet, signet)
-        #[arg(long, default_value = "testnet")]
-        network: String,
+    /// Combine threshold shares into the full secret key, gift-wrapped to a Nostr key
+    DkgReconstruct {
+        /// Share folders, e.g. .frost_state/treasury/party1 .frost_state/treasury/party3
+        #[arg(long, num_args = 1.., required = true)]
+        shares: Vec<String>,
+
+        /// Recipient Nostr public key (npub or hex) the key is encrypted to
+        #[arg(long)]
+        to: String,
+
+        /// File to write the gift wrap to
+        #[arg(long)]
+        out: String,
     },
 
-    /// Generate BIP-39 mnemonic backup for share
-    DkgGenerateMnemonic {
-        /// Wallet name
+    /// Open a dkg-reconstruct gift wrap with this wallet's Nostr key
+    DkgReconstructOpen {
+        /// Wallet holding the recipient Nostr key
         #[arg(long)]
         name: String,
-    },
 
-    /// Reshare Round 1: Old party generates sub-shares for new parties
-    ReshareRound1 {
-        /// Source wallet name (existing wallet to reshare from)
+        /// Gift wrap file from dkg-reconstruct
         #[arg(long)]
-        source: String,
+        file: String,
+    },
 
-        /// New threshold for reshared wallet
+    /// Check a wallet folder for missing or damaged files and stale state
+    DkgDoctor {
+        /// Wallet/session name
         #[arg(long)]
-        new_threshold: u32,
+        name: String,
 
-        /// New total number of parties
+        /// Apply the safe repairs it offers
         #[arg(long)]
-        new_n_parties: u32,
+        fix: bool,
+    },
 
-        /// Your old party index
+    /// Check share age, signing participation and backups against the health policy
+    DkgHealth {
+        /// Wallet name
         #[arg(long)]
-        my_index: u32,
+        name: String,
     },
 
-    /// Reshare Finalize: New party combines sub-shares
-    ReshareFinalize {
-        /// Source wallet name
+    /// Set when wallets are flagged by dkg-health (shows the policy without flags)
+    HealthConfig {
+        /// Warn when shares were not refreshed by a reshare for this many days
         #[arg(long)]
-        source: String,
+        max_share_age_days: Option<u64>,
 
-        /// Target wallet name (new wallet to create)
+        /// Warn when a party sat out this many signing sessions in a row (0 = never)
         #[arg(long)]
-        target: String,
+        max_missed_sessions: Option<u64>,
+    },
 
-        /// Your new party index
+    /// Move a legacy wallet's share from the wallet root into a party folder
+    DkgMigrateLayout {
+        /// Wallet/session name
         #[arg(long)]
-        my_index: u32,
+        name: String,
+    },
 
-        /// Your HTSS rank (0 = highest)
-        #[arg(long, default_value = "0")]
-        rank: u32,
+    /// Rename a wallet
+    DkgRename {
+        /// Current wallet name
+        #[arg(long)]
+        name: String,
 
-        /// Enable hierarchical mode
-        #[arg(long, default_value = "false")]
-        hierarchical: bool,
+        /// New wallet name
+        #[arg(long)]
+        new_name: String,
+    },
 
-        /// JSON with round1 outputs from old parties
+    /// Move a wallet to .frost_state/.archive (keeps every file)
+    DkgArchive {
+        /// Wallet name
         #[arg(long)]
-        data: String,
+        name: String,
     },
 
-    /// Recovery Round 1: Helper party generates sub-share for lost party
-    RecoverRound1 {
+    /// Shred and delete a wallet
+    DkgDelete {
         /// Wallet name
         #[arg(long)]
         name: String,
 
-        /// Index of the party who lost their share
+        /// Repeat the wallet name to confirm
         #[arg(long)]
-        lost_index: u32,
+        confirm_name: String,
     },
 
-    /// Recovery Finalize: Lost party combines sub-shares to recover
-    RecoverFinalize {
-        /// Source wallet name (the wallet to recover into)
+    /// Show the weight each signer's share gets and whether the set can sign
+    DkgCoefficients {
+        /// Wallet name
         #[arg(long)]
-        source: String,
+        name: String,
 
-        /// Target wallet name (new wallet file to create)
+        /// Signing party indices, e.g. 1,3,5
         #[arg(long)]
-        target: String,
+        signers: String,
+    },
 
-        /// Your party index (the one being recovered)
+    /// Require signers of specific ranks, e.g. one rank-0 and two rank-1
+    DkgSigningRequirement {
+        /// Wallet name
         #[arg(long)]
-        my_index: u32,
-
-        /// Your HTSS rank (0 = highest)
-        #[arg(long, default_value = "0")]
-        rank: u32,
-
-        /// Enable hierarchical mode
-        #[arg(long, default_value = "false")]
-        hierarchical: bool,
+        name: String,
 
-        /// JSON with round1 outputs from helper parties
+        /// Signers needed per rank as <rank>:<count>,..., e.g. 0:1,1:2
         #[arg(long)]
-        data: String,
+        set: Option<String>,
 
-        /// Force overwrite if target wallet exists
-        #[arg(long, default_value = "false")]
-        force: bool,
+        /// Remove the requirement
+        #[arg(long)]
+        clear: bool,
     },
 
-    /// Interactive Terminal UI for wallet management
-    Tui,
-
-    /// Check Bitcoin balance (testnet)
-    BtcBalance,
-
-    /// Send Bitcoin on testnet
-    BtcSend {
-        /// Recipient address
+    /// Show or edit a wallet's notes (description, contacts, date, tags)
+    DkgNotes {
+        /// Wallet name
         #[arg(long)]
-        to: String,
+        name: String,
 
-        /// Amount in satoshis
+        /// Free-text description
         #[arg(long)]
-        amount: u64,
+        description: Option<String>,
 
-        /// Fee rate in sats/vbyte (optional, defaults to recommended)
+        /// How to reach a party, as <index>=<contact> (empty contact removes it)
         #[arg(long)]
-        fee_rate: Option<u64>,
-    },
+        contact: Vec<String>,
 
-    /// Send Bitcoin on signet
-    BtcSendSignet {
-        /// Recipient address
+        /// Creation date, e.g. 2024-03-01
         #[arg(long)]
-        to: String,
+        created: Option<String>,
 
-        /// Amount in satoshis
+        /// Add a tag
         #[arg(long)]
-        amount: u64,
+        tag: Vec<String>,
 
-        /// Fee rate in sats/vbyte (optional, defaults to recommended)
+        /// Remove a tag
         #[arg(long)]
-        fee_rate: Option<u64>,
+        untag: Vec<String>,
     },
 
-    // ========================================================================
-    // DKG Threshold Transaction Commands
-    // ========================================================================
-    /// Build unsigned transaction for DKG threshold signing
-    DkgBuildTx {
+    /// Show or edit the party directory: who holds each share and how to reach them
+    DkgParty {
         /// Wallet name
         #[arg(long)]
         name: String,
 
-        /// Recipient address
+        /// Party index to edit; omit to show the directory
         #[arg(long)]
-        to: String,
+        index: Option<u32>,
 
-        /// Amount in satoshis
-        #[arg(long)]
-        amount: u64,
+        /// Who holds the share, e.g. "Alice (CFO)"
+        #[arg(long, requires = "index")]
+        holder: Option<String>,
 
-        /// Fee rate in sats/vbyte (optional)
-        #[arg(long)]
-        fee_rate: Option<u64>,
+        /// How to reach them, e.g. signal:+1 555 0100 or an email address
+        #[arg(long, requires = "index")]
+        contact: Option<String>,
 
-        /// Network (testnet, signet, mainnet)
-        #[arg(long, default_value = "testnet")]
-        network: String,
+        /// Nostr key to message them at (npub or hex; empty removes it)
+        #[arg(long, requires = "index")]
+        npub: Option<String>,
+
+        /// Last-known availability, e.g. "weekdays, UTC evenings"
+        #[arg(long, requires = "index")]
+        availability: Option<String>,
+
+        /// Record that the holder confirmed they have their share
+        #[arg(long, requires = "index")]
+        share_confirmed: bool,
+
+        /// Remove the party's entry
+        #[arg(long, requires = "index")]
+        clear: bool,
     },
 
-    /// Generate nonce for DKG transaction signing
-    DkgNonce {
-        /// Wallet name
+    /// Show your identity key and the pinned party identities
+    DkgIdentity {
+        /// Wallet/session name
         #[arg(long)]
         name: String,
 
-        /// Session ID from dkg-build-tx
+        /// Pin a party's identity key: <index>=<npub or public key hex>
         #[arg(long)]
-        session: String,
+        trust: Option<String>,
     },
 
-    /// Create signature share for DKG transaction
-    DkgSign {
-        /// Wallet name
+    /// Generate a Nostr identity for a wallet (before keygen-round1)
+    NostrKeygen {
+        /// Wallet/session name
         #[arg(long)]
         name: String,
 
-        /// Session ID
+        /// Replace an existing key
         #[arg(long)]
-        session: String,
+        force: bool,
+    },
 
-        /// Sighash to sign (32 bytes hex)
+    /// Import an existing Nostr key (nsec or hex) as a wallet's identity
+    NostrImport {
+        /// Wallet/session name
         #[arg(long)]
-        sighash: String,
+        name: String,
 
-        /// JSON with nonces from all signing parties
+        /// Secret key as nsec1... or hex
         #[arg(long)]
-        data: String,
+        key: String,
+
+        /// Replace an existing key
+        #[arg(long)]
+        force: bool,
     },
 
-    /// Combine signature shares and broadcast transaction
-    DkgBroadcast {
+    /// Publish a wallet's group info as a replaceable Nostr event
+    DkgGroupPublish {
         /// Wallet name
         #[arg(long)]
         name: String,
 
-        /// Session ID
+        /// Also show the event as a (possibly animated) QR code
         #[arg(long)]
-        session: String,
+        qr: bool,
 
-        /// Unsigned transaction hex
+        #[cfg(feature = "network")]
+        #[command(flatten)]
+        relay: RelayArgs,
+    },
+
+    /// Verify a published group info event and import it as a watch-only wallet
+    DkgGroupImport {
+        /// Wallet name to import into
         #[arg(long)]
-        unsigned_tx: String,
+        name: String,
 
-        /// JSON with signature shares from all parties
+        /// The group info event JSON (ur: frames or @file also accepted); prompted for if omitted, unless relays are used
         #[arg(long)]
-        data: String,
+        data: Option<String>,
 
-        /// Network (testnet, signet, mainnet)
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Expected author (npub or hex), confirmed with its owner
+        #[arg(long)]
+        from: Option<String>,
+
+        #[cfg(feature = "network")]
+        #[command(flatten)]
+        relay: RelayArgs,
+    },
+
+    // ========================================================================
+    // HD Key Derivation (BIP-32/BIP-44) Commands
+    // ========================================================================
+    /// Derive address at BIP-44 path (m/44'/0'/0'/change/index)
+    DkgDeriveAddress {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Change level (0=external/receive, 1=internal/change)
+        #[arg(long, default_value = "0")]
+        change: u32,
+
+        /// Address index
+        #[arg(long, default_value = "0")]
+        index: u32,
+
+        /// Network (testnet, mainnet, signet)
         #[arg(long, default_value = "testnet")]
         network: String,
     },
-}
-
-fn main() -> Result<()> {
-    let cli = Cli::parse();
 
-    match cli.command {
-        Commands::KeygenRound1 {
+    /// List multiple derived addresses
+    DkgListAddresses {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Number of addresses to derive
+        #[arg(long, default_value = "10")]
+        count: u32,
+
+        /// Network (testnet, mainnet, signet)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+    },
+
+    /// Export descriptors for watch-only tracking in Sparrow, Specter or Bitcoin Core
+    DkgExportWatchOnly {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Network (testnet, mainnet, signet)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Write the export JSON to this file instead of printing it
+        #[arg(long, value_parser = absolute_path)]
+        out: Option<PathBuf>,
+    },
+
+    /// Create a script-augmented Taproot address and persist its script tree
+    DkgScriptAddress {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Script types, comma separated for one tree with all their leaves
+        /// (timelock-absolute, timelock-relative, recovery, htlc)
+        #[arg(long, required_unless_present = "policy")]
+        script_type: Option<String>,
+
+        /// Miniscript policy with x-only hex keys, e.g. "and(pk(<key>),older(4320))";
+        /// repeat to add more leaves
+        #[arg(long)]
+        policy: Vec<String>,
+
+        /// Absolute timelock: block height
+        #[arg(long, default_value = "")]
+        lock_height: String,
+
+        /// Relative timelock: number of blocks
+        #[arg(long, default_value = "")]
+        blocks: String,
+
+        /// Recovery/HTLC: timeout block height
+        #[arg(long, default_value = "")]
+        timeout: String,
+
+        /// Recovery: recovery pubkey (x-only hex)
+        #[arg(long, default_value = "")]
+        recovery_pubkey: String,
+
+        /// HTLC: SHA256 hash (hex)
+        #[arg(long, default_value = "")]
+        htlc_hash: String,
+
+        /// HTLC: refund pubkey (x-only hex)
+        #[arg(long, default_value = "")]
+        refund_pubkey: String,
+
+        /// Recipient/owner pubkey for the leaves (x-only hex, defaults to the wallet key)
+        #[arg(long)]
+        recipient_pubkey: Option<String>,
+
+        /// HD change level (use with --index to build on a derived key)
+        #[arg(long)]
+        change: Option<u32>,
+
+        /// HD address index
+        #[arg(long)]
+        index: Option<u32>,
+
+        /// Network (testnet, mainnet, signet)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+    },
+
+    /// List script addresses with persisted taproot trees
+    DkgListScriptAddresses {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+    },
+
+    /// Show how a script address can be spent: key path or which leaf, and why not
+    #[cfg(feature = "network")]
+    DkgSpendPlan {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Script address from dkg-script-address
+        #[arg(long)]
+        address: String,
+
+        /// Use this path instead of the cheapest: keypath or leaf:<n>
+        #[arg(long)]
+        spend_path: Option<String>,
+
+        /// Preimage (hex) for a hash-locked leaf
+        #[arg(long)]
+        preimage: Option<String>,
+
+        /// Network (testnet, signet, mainnet, regtest)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+    },
+
+    /// Generate BIP-39 mnemonic backup for share
+    DkgGenerateMnemonic {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Protect the words with the passphrase in FROSTDAO_MNEMONIC_PASSPHRASE
+        #[arg(long)]
+        passphrase: bool,
+    },
+
+    /// Restore your share from a dkg-generate-mnemonic backup
+    DkgRestoreMnemonic {
+        /// Wallet name (the folder still holding the public files)
+        #[arg(long)]
+        name: String,
+
+        /// The 24 words, space-separated
+        #[arg(long)]
+        words: String,
+
+        /// The words were protected with the passphrase in FROSTDAO_MNEMONIC_PASSPHRASE
+        #[arg(long)]
+        passphrase: bool,
+
+        /// Check code shown with the words, only as a hint (the share is checked against shared_key.bin)
+        #[arg(long)]
+        check: Option<String>,
+
+        /// Overwrite a share already in the folder
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Split your share into a k-of-m Shamir backup of 24-word pieces
+    DkgBackupSplit {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Pieces needed to restore
+        #[arg(long, default_value = "2")]
+        threshold: u32,
+
+        /// Pieces to write
+        #[arg(long, default_value = "3")]
+        pieces: u32,
+
+        /// Piece format: bip39 (24 words) or slip39 (33-word SLIP-39 shares)
+        #[arg(long, default_value = "bip39")]
+        format: backup::BackupFormat,
+
+        /// Protect slip39 pieces with the passphrase in FROSTDAO_MNEMONIC_PASSPHRASE
+        #[arg(long)]
+        passphrase: bool,
+    },
+
+    /// Restore your share from dkg-backup-split pieces
+    DkgBackupRestore {
+        /// Wallet name (the folder still holding the public files)
+        #[arg(long)]
+        name: String,
+
+        /// One piece as written on paper, '<number>: <24 words>' or a SLIP-39 share; repeat for each
+        #[arg(long = "piece", required = true)]
+        pieces: Vec<String>,
+
+        /// SLIP-39 pieces were made with the passphrase in FROSTDAO_MNEMONIC_PASSPHRASE
+        #[arg(long)]
+        passphrase: bool,
+
+        /// Check code printed on the pieces, only as a hint (the share is checked against shared_key.bin)
+        #[arg(long)]
+        check: Option<String>,
+
+        /// Overwrite a share already in the folder
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Reshare Round 1: Old party generates sub-shares for new parties
+    ReshareRound1 {
+        /// Source wallet name (existing wallet to reshare from)
+        #[arg(long)]
+        source: String,
+
+        /// New threshold for reshared wallet
+        #[arg(long)]
+        new_threshold: u32,
+
+        /// New total number of parties
+        #[arg(long)]
+        new_n_parties: u32,
+
+        /// Your old party index
+        #[arg(long)]
+        my_index: u32,
+
+        /// Identity key of a new party as <index>=<key> (hex or npub), for a
+        /// joining party or one whose index changes; repeat per party
+        #[arg(long = "new-identity")]
+        new_identity: Vec<String>,
+    },
+
+    /// Reshare Finalize: New party combines sub-shares
+    ReshareFinalize {
+        /// Source wallet name
+        #[arg(long)]
+        source: String,
+
+        /// Target wallet name (new wallet to create)
+        #[arg(long)]
+        target: String,
+
+        /// Your new party index
+        #[arg(long)]
+        my_index: u32,
+
+        /// Your HTSS rank (0 = highest)
+        #[arg(long, default_value = "0")]
+        rank: u32,
+
+        /// Enable hierarchical mode
+        #[arg(long, default_value = "false")]
+        hierarchical: bool,
+
+        /// JSON with round1 outputs from old parties; prompted for if omitted
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Accept a new threshold lower than the source wallet's
+        #[arg(long)]
+        allow_threshold_decrease: bool,
+
+        /// Check the sub-shares and the group key without writing the target
+        #[arg(long)]
+        verify_only: bool,
+    },
+
+    /// Recovery Round 1: Helper party generates sub-share for lost party
+    RecoverRound1 {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Index of the party who lost their share
+        #[arg(long)]
+        lost_index: u32,
+    },
+
+    /// Recovery request: Lost party asks helpers for sub-shares over Nostr
+    RecoverRequest {
+        /// Wallet name (the wallet to recover into)
+        #[arg(long)]
+        name: String,
+
+        /// Your party index (the one being recovered)
+        #[arg(long)]
+        my_index: u32,
+
+        /// Also show the event as a (possibly animated) QR code
+        #[arg(long)]
+        qr: bool,
+
+        #[cfg(feature = "network")]
+        #[command(flatten)]
+        relay: RelayArgs,
+    },
+
+    /// Recovery respond: Helper answers a recovery request with a gift-wrapped sub-share
+    RecoverRespond {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// The recovery request event JSON (ur: frames or @file also accepted); prompted for if omitted, unless relays are used
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Requester's new Nostr key (npub or hex), confirmed with them, if not the pinned one
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Answer without asking for approval
+        #[arg(long)]
+        yes: bool,
+
+        #[cfg(feature = "network")]
+        #[command(flatten)]
+        relay: RelayArgs,
+
+        /// With relays: seconds to wait for new requests not there yet
+        #[cfg(feature = "network")]
+        #[arg(long, value_name = "SECS", default_value = "60")]
+        listen: u64,
+    },
+
+    /// Recovery Finalize: Lost party combines sub-shares to recover
+    RecoverFinalize {
+        /// Source wallet name (the wallet to recover into)
+        #[arg(long)]
+        source: String,
+
+        /// Target wallet name (new wallet file to create)
+        #[arg(long)]
+        target: String,
+
+        /// Your party index (the one being recovered)
+        #[arg(long)]
+        my_index: u32,
+
+        /// Your HTSS rank (0 = highest)
+        #[arg(long, default_value = "0")]
+        rank: u32,
+
+        /// Enable hierarchical mode
+        #[arg(long, default_value = "false")]
+        hierarchical: bool,
+
+        /// Round1 outputs from helper parties, or their recover-respond gift wraps; prompted for if omitted, unless relays are used
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Force overwrite if target wallet exists
+        #[arg(long, default_value = "false")]
+        force: bool,
+
+        #[cfg(feature = "network")]
+        #[command(flatten)]
+        relay: RelayArgs,
+
+        /// With relays: seconds to wait for sub-shares not there yet
+        #[cfg(feature = "network")]
+        #[arg(long, value_name = "SECS", default_value = "60")]
+        listen: u64,
+    },
+
+    /// Print a shell completion script (e.g. `frostdao completions bash > ~/.local/share/bash-completion/completions/frostdao`)
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Interactive Terminal UI for wallet management
+    #[cfg(feature = "network")]
+    Tui {
+        /// Seconds between background balance refreshes of all wallets (0 = off)
+        #[arg(long, default_value_t = tui::app::DEFAULT_REFRESH_SECS)]
+        refresh_secs: u64,
+
+        /// Show a desktop notification when a broadcast transaction confirms
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// Check Bitcoin balance (testnet)
+    #[cfg(feature = "network")]
+    BtcBalance,
+
+    /// Send Bitcoin on testnet
+    #[cfg(feature = "network")]
+    BtcSend {
+        /// Recipient address
+        #[arg(long)]
+        to: String,
+
+        /// Amount: 1000, 1_000_000, 1000sat or 0.001btc (bare decimals are rejected)
+        #[arg(
+            long,
+            value_parser = amount::parse_sats,
+            required_unless_present = "amount_fiat",
+            conflicts_with = "amount_fiat"
+        )]
+        amount: Option<u64>,
+
+        /// Amount in fiat, e.g. 50USD (converted at the current rate; sats are shown)
+        #[arg(long)]
+        amount_fiat: Option<String>,
+
+        /// Fee rate in sats/vbyte (optional, defaults to recommended)
+        #[arg(long)]
+        fee_rate: Option<u64>,
+
+        /// Allow spending UTXOs that carry inscriptions, runes or rare sats
+        #[arg(long)]
+        allow_inscribed: bool,
+    },
+
+    /// Send Bitcoin on signet
+    #[cfg(feature = "network")]
+    BtcSendSignet {
+        /// Recipient address
+        #[arg(long)]
+        to: String,
+
+        /// Amount: 1000, 1_000_000, 1000sat or 0.001btc (bare decimals are rejected)
+        #[arg(
+            long,
+            value_parser = amount::parse_sats,
+            required_unless_present = "amount_fiat",
+            conflicts_with = "amount_fiat"
+        )]
+        amount: Option<u64>,
+
+        /// Amount in fiat, e.g. 50USD (converted at the current rate; sats are shown)
+        #[arg(long)]
+        amount_fiat: Option<String>,
+
+        /// Fee rate in sats/vbyte (optional, defaults to recommended)
+        #[arg(long)]
+        fee_rate: Option<u64>,
+
+        /// Allow spending UTXOs that carry inscriptions, runes or rare sats
+        #[arg(long)]
+        allow_inscribed: bool,
+    },
+
+    // ========================================================================
+    // DKG Threshold Transaction Commands
+    // ========================================================================
+    /// Build unsigned transaction for DKG threshold signing
+    #[cfg(feature = "network")]
+    DkgBuildTx {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Recipient address
+        #[arg(long)]
+        to: String,
+
+        /// Amount: 1000, 1_000_000, 1000sat or 0.001btc (bare decimals are rejected)
+        #[arg(
+            long,
+            value_parser = amount::parse_sats,
+            required_unless_present = "amount_fiat",
+            conflicts_with = "amount_fiat"
+        )]
+        amount: Option<u64>,
+
+        /// Amount in fiat, e.g. 50USD (converted at the current rate; sats are shown)
+        #[arg(long)]
+        amount_fiat: Option<String>,
+
+        /// Fee rate in sats/vbyte (optional)
+        #[arg(long)]
+        fee_rate: Option<u64>,
+
+        /// Network (testnet, signet, mainnet)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Allow spending UTXOs that carry inscriptions, runes or rare sats
+        #[arg(long)]
+        allow_inscribed: bool,
+
+        /// Spend from this script address (from dkg-script-address) instead of the key-path address
+        #[arg(long)]
+        from: Option<String>,
+
+        /// What the payment is for, shown to every signer (e.g. "Q3 contributor payout")
+        #[arg(long)]
+        memo: Option<String>,
+
+        /// Spend --from this way instead of the cheapest: keypath or leaf:<n>
+        #[arg(long, requires = "from")]
+        spend_path: Option<String>,
+
+        /// Preimage (hex) for a hash-locked leaf of --from
+        #[arg(long, requires = "from")]
+        preimage: Option<String>,
+    },
+
+    /// Show any JSON payload (or @file) as a QR code, animated when it is long
+    QrShow {
+        /// Payload to encode, or @path to read it from a file; prompted for if omitted
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+    },
+
+    /// Reassemble scanned QR frames (ur: strings or @file) back into JSON
+    QrDecode {
+        /// Scanned ur: frames, whitespace-separated, or @path to a scan export; prompted for if omitted
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Write the decoded JSON here instead of printing it
+        #[arg(long)]
+        output: Option<String>,
+    },
+
+    /// Show a raw transaction's inputs, outputs, size and fee
+    TxDecode {
+        /// Raw transaction hex, or a dkg-build-tx output; prompted for if omitted
+        #[arg(long)]
+        hex: Option<String>,
+
+        /// Read --hex from this file (`-` for stdin)
+        #[arg(long, conflicts_with = "hex", value_parser = absolute_path)]
+        hex_file: Option<PathBuf>,
+
+        /// Mark the inputs and outputs that belong to this wallet
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Network for addresses and spent-output lookups (testnet, signet, mainnet, regtest)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+    },
+
+    /// Recompute a BIP-341 sighash step by step to check what you are asked to sign
+    SighashExplain {
+        /// Unsigned transaction hex from dkg-build-tx
+        #[arg(long)]
+        unsigned_tx: String,
+
+        /// Input whose sighash to recompute
+        #[arg(long, default_value = "0")]
+        input: usize,
+
+        /// Spent outputs as a JSON array of {value, script_pubkey}, or the dkg-build-tx output
+        #[arg(long)]
+        prevouts: String,
+
+        /// Sighash you were asked to sign; taken from a dkg-build-tx --prevouts if omitted
+        #[arg(long)]
+        sighash: Option<String>,
+
+        /// default, all, none or single, the last three optionally with |anyonecanpay
+        #[arg(long, default_value = "default")]
+        sighash_type: String,
+
+        /// Tapleaf hash, for a script-path spend
+        #[arg(long)]
+        leaf_hash: Option<String>,
+    },
+
+    /// Generate nonce for DKG transaction signing
+    DkgNonce {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Session ID from dkg-build-tx
+        #[arg(long)]
+        session: String,
+
+        /// Also show the result as a (possibly animated) QR code
+        #[arg(long)]
+        qr: bool,
+    },
+
+    /// Create signature share for DKG transaction
+    DkgSign {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Session ID
+        #[arg(long)]
+        session: String,
+
+        /// Sighash to sign (32 bytes hex); taken from --tx when omitted
+        #[arg(long)]
+        sighash: Option<String>,
+
+        /// The dkg-build-tx output (JSON, ur: frames or @file), reviewed before signing
+        #[arg(long)]
+        tx: Option<String>,
+
+        /// Sign --sighash without a transaction to review (it could commit to any spend)
+        #[arg(long, requires = "sighash")]
+        unsafe_bare_sighash: bool,
+
+        /// JSON with nonces from all signing parties (or ur: frames, @file); prompted for if omitted
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Script tree merkle root of the spent output (hex, shown by dkg-build-tx)
+        #[arg(long)]
+        merkle_root: Option<String>,
+
+        /// Also show the result as a (possibly animated) QR code
+        #[arg(long)]
+        qr: bool,
+    },
+
+    /// Air-gapped signing: nonce or signature share from a session file, no network access
+    DkgSignOffline {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Session file: dkg-build-tx output, plus all signers' nonces for the share step
+        #[arg(long)]
+        input: String,
+
+        /// Write the result JSON here instead of printing it
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Also show the result as a (possibly animated) QR code
+        #[arg(long)]
+        qr: bool,
+    },
+
+    /// Hand this party's nonces and signing to an external signing device (experimental)
+    DeviceEnroll {
+        /// Wallet name (the party folder the device's share belongs to)
+        #[arg(long)]
+        name: String,
+
+        /// Program speaking the device protocol, run once per request (split on spaces)
+        #[arg(long, conflicts_with = "serial")]
+        command: Option<String>,
+
+        /// Serial port the device is on, already configured (e.g. with stty)
+        #[arg(long)]
+        serial: Option<String>,
+    },
+
+    /// Reference signing device: answer device requests on stdin/stdout from a folder
+    DeviceEmulate {
+        /// Folder holding the device's paired_secret_share.bin
+        #[arg(long)]
+        dir: String,
+
+        /// Approve sign requests without asking (testing only)
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Show (and check) the 2-of-2 funding address of a channel the wallet funds
+    DkgLnFundingAddress {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Peer's funding public key (33 bytes compressed, hex)
+        #[arg(long)]
+        peer_key: String,
+
+        /// Your Lightning node's funding public key (33 bytes compressed, hex);
+        /// the group only funds the channel, so its key is refused here
+        #[arg(long)]
+        local_key: String,
+
+        /// Channel type: taproot (MuSig2 P2TR) or anchors (P2WSH 2-of-2)
+        #[arg(long, default_value = "taproot")]
+        channel_type: ChannelType,
+
+        /// Network (testnet, signet, mainnet, regtest)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Funding address the peer asked for; fails unless it matches
+        #[arg(long)]
+        expect: Option<String>,
+    },
+
+    /// Combine signature shares into a channel funding PSBT for the peer (no broadcast)
+    DkgLnFundPsbt {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Session ID
+        #[arg(long)]
+        session: String,
+
+        /// Unsigned transaction hex
+        #[arg(long)]
+        unsigned_tx: String,
+
+        /// JSON with signature shares from all parties; prompted for if omitted
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Channel funding address the transaction must pay
+        #[arg(long)]
+        funding_address: String,
+    },
+
+    /// Combine signature shares and broadcast transaction
+    #[cfg(feature = "network")]
+    DkgBroadcast {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Session ID
+        #[arg(long)]
+        session: String,
+
+        /// Unsigned transaction hex
+        #[arg(long)]
+        unsigned_tx: String,
+
+        /// JSON with signature shares from all parties; prompted for if omitted
+        #[arg(long)]
+        data: Option<String>,
+
+        /// Read --data from this file or folder instead (`-` for stdin)
+        #[arg(long, conflicts_with = "data", value_parser = absolute_path)]
+        data_file: Option<PathBuf>,
+
+        /// Network (testnet, signet, mainnet)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+    },
+
+    /// Sweep every coin of a wallet to a new group key's wallet and retire it
+    #[cfg(feature = "network")]
+    DkgRotate {
+        /// Old wallet, whose coins are swept
+        #[arg(long)]
+        name: String,
+
+        /// New wallet, whose root address receives the coins
+        #[arg(long)]
+        to: String,
+
+        /// Old wallet's signing party indices, e.g. 1,3 (exactly threshold)
+        #[arg(long, value_delimiter = ',', required_unless_present = "dry_run")]
+        signers: Vec<u32>,
+
+        /// Also sweep the first N receive and change HD addresses (the script index is always swept)
+        #[arg(long, default_value_t = 0)]
+        hd_count: u32,
+
+        /// Fee rate in sats/vbyte (optional)
+        #[arg(long)]
+        fee_rate: Option<u64>,
+
+        /// Network (testnet, signet, mainnet)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Allow sweeping UTXOs that carry inscriptions, runes or rare sats
+        #[arg(long)]
+        allow_inscribed: bool,
+
+        /// Show the sweep plan without signing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show, export or import the public report of a broadcast signing session
+    DkgSessionReport {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Session ID
+        #[arg(long, required_unless_present = "import")]
+        session: Option<String>,
+
+        /// Also write the report as JSON to this file, for the co-signers
+        #[arg(long, conflicts_with = "import", value_parser = absolute_path)]
+        out: Option<PathBuf>,
+
+        /// Check a co-signer's report against this wallet and keep it
+        #[arg(long, conflicts_with = "session", value_parser = absolute_path)]
+        import: Option<PathBuf>,
+    },
+
+    /// Set or reset the Esplora API or Electrum server used for a network
+    #[cfg(feature = "network")]
+    BackendConfig {
+        /// Network (testnet, signet, mainnet, regtest)
+        #[arg(long)]
+        network: String,
+
+        /// Esplora base URL, e.g. https://esplora.example.org/api, or an Electrum
+        /// server as tcp://host:50001 or ssl://host:50002
+        #[arg(long, required_unless_present = "reset", conflicts_with = "reset")]
+        url: Option<String>,
+
+        /// Basic auth username (password from FROSTDAO_ESPLORA_PASSWORD)
+        #[arg(long, conflicts_with = "reset")]
+        username: Option<String>,
+
+        /// Go back to mempool.space for this network
+        #[arg(long)]
+        reset: bool,
+    },
+
+    /// Add or remove extra endpoints that every broadcast is also sent to
+    #[cfg(feature = "network")]
+    BroadcastConfig {
+        /// Network (testnet, signet, mainnet, regtest)
+        #[arg(long)]
+        network: String,
+
+        /// Esplora URL, tcp:// or ssl:// Electrum server, bitcoind+http://host:port,
+        /// or a preset: mempool, blockstream
+        #[arg(
+            long,
+            conflicts_with = "remove",
+            required_unless_present_any = ["remove", "reset"]
+        )]
+        add: Option<String>,
+
+        /// RPC or basic auth username (password from FROSTDAO_BITCOIND_PASSWORD
+        /// or FROSTDAO_ESPLORA_PASSWORD)
+        #[arg(long, requires = "add")]
+        username: Option<String>,
+
+        /// Endpoint (or preset) to remove
+        #[arg(long)]
+        remove: Option<String>,
+
+        /// Remove every extra endpoint of this network
+        #[arg(long, conflicts_with_all = ["add", "remove"])]
+        reset: bool,
+    },
+
+    /// Add or remove the Nostr relays used with --nostr
+    #[cfg(feature = "network")]
+    RelayConfig {
+        /// Relay URL (wss://...)
+        #[arg(
+            long,
+            conflicts_with = "remove",
+            required_unless_present_any = ["remove", "reset"]
+        )]
+        add: Option<String>,
+
+        /// Relay to remove
+        #[arg(long)]
+        remove: Option<String>,
+
+        /// Forget every saved relay
+        #[arg(long, conflicts_with_all = ["add", "remove"])]
+        reset: bool,
+    },
+
+    /// Check the chain backend: tip height and fee estimates
+    #[cfg(feature = "network")]
+    BackendStatus {
+        /// Network (testnet, signet, mainnet, regtest)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+    },
+
+    /// Show balances in a fiat currency as well (or turn it off)
+    #[cfg(feature = "network")]
+    FiatConfig {
+        /// Currency code, e.g. USD or EUR
+        #[arg(long, required_unless_present = "disable", conflicts_with = "disable")]
+        currency: Option<String>,
+
+        /// Price source: mempool or coingecko (default: mempool)
+        #[arg(long)]
+        provider: Option<RateProvider>,
+
+        /// Seconds to reuse a fetched price (default: 300)
+        #[arg(long)]
+        cache_secs: Option<u64>,
+
+        /// Stop showing fiat values
+        #[arg(long)]
+        disable: bool,
+    },
+
+    /// Print the current BTC price
+    #[cfg(feature = "network")]
+    FiatRate {
+        /// Currency code (default: the configured one, else USD)
+        #[arg(long)]
+        currency: Option<String>,
+    },
+
+    /// Request testnet/signet coins for a wallet's group address and wait for them
+    #[cfg(feature = "network")]
+    DkgFaucet {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Network (testnet, signet, regtest)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Faucet URL for this run, overriding faucet-config
+        #[arg(long)]
+        faucet_url: Option<String>,
+
+        /// Sats to ask for (default: the configured amount, else the faucet's)
+        #[arg(long)]
+        amount: Option<u64>,
+
+        /// Poll interval in seconds
+        #[arg(long, default_value_t = frostdao::btc::faucet::DEFAULT_POLL_INTERVAL_SECS)]
+        interval: u64,
+
+        /// Give up after this many seconds (0 = wait forever)
+        #[arg(long, default_value_t = frostdao::btc::faucet::DEFAULT_TIMEOUT_SECS)]
+        timeout: u64,
+    },
+
+    /// Set or clear the faucet dkg-faucet uses for a network
+    #[cfg(feature = "network")]
+    FaucetConfig {
+        /// Network (testnet, signet, regtest)
+        #[arg(long)]
+        network: String,
+
+        /// Faucet URL: GET with {address} (and {amount}) in it, else POST {"address", "amount"}
+        #[arg(long, required_unless_present = "reset", conflicts_with = "reset")]
+        url: Option<String>,
+
+        /// Sats to ask for by default
+        #[arg(long, conflicts_with = "reset")]
+        amount: Option<u64>,
+
+        /// Remove the faucet for this network
+        #[arg(long)]
+        reset: bool,
+    },
+
+    /// Find wallet coins with BIP-157/158 block filters from P2P peers (no indexer)
+    #[cfg(feature = "network")]
+    DkgFilterSync {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Network (testnet, signet, mainnet, regtest)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Peer serving block filters, host[:port]; give at least two to cross-check
+        #[arg(long = "peer", required = true)]
+        peers: Vec<String>,
+
+        /// Block to start scanning at (default: current tip on the first run)
+        #[arg(long)]
+        from_height: Option<u32>,
+    },
+
+    /// Follow every address of a wallet on an Electrum server and report balance changes
+    #[cfg(feature = "network")]
+    DkgWatchWallet {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Network whose backend is an Electrum server (tcp:// or ssl://)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Stop after this many seconds (0 = watch until interrupted)
+        #[arg(long, default_value = "0")]
+        timeout: u64,
+    },
+
+    /// Check a wallet's unconfirmed transactions and broadcast them again
+    #[cfg(feature = "network")]
+    DkgRebroadcast {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Network (testnet, signet, mainnet, regtest)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Only this transaction
+        #[arg(long)]
+        txid: Option<String>,
+
+        /// Stop tracking --txid (e.g. after it was replaced)
+        #[arg(long, requires = "txid")]
+        forget: bool,
+    },
+
+    /// Watch a broadcast transaction until it confirms
+    #[cfg(feature = "network")]
+    DkgWatch {
+        /// Transaction ID to watch
+        #[arg(long)]
+        txid: String,
+
+        /// Network (testnet, signet, mainnet)
+        #[arg(long, default_value = "testnet")]
+        network: String,
+
+        /// Poll interval in seconds
+        #[arg(long, default_value_t = frostdao::btc::monitor::DEFAULT_POLL_INTERVAL_SECS)]
+        interval: u64,
+
+        /// Give up after this many seconds (0 = wait forever)
+        #[arg(long, default_value = "0")]
+        timeout: u64,
+
+        /// Confirmations to wait for before exiting
+        #[arg(long, default_value = "1")]
+        confirmations: u64,
+
+        /// Wallet whose configured webhooks fire on confirmation
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Extra webhook URL to POST to on first confirmation (Slack/Discord/generic)
+        #[arg(long)]
+        webhook: Option<String>,
+
+        /// Show a desktop notification on first confirmation
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// Add a webhook fired on signing events (session created, share submitted, tx confirmed)
+    DkgWebhookAdd {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Webhook URL
+        #[arg(long)]
+        url: String,
+
+        /// Payload format: slack, discord or generic (guessed from URL if omitted)
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Comma-separated events to subscribe to (default: all)
+        #[arg(long, value_delimiter = ',')]
+        events: Vec<String>,
+    },
+
+    /// Remove a configured webhook
+    DkgWebhookRemove {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Webhook URL to remove
+        #[arg(long)]
+        url: String,
+    },
+
+    /// List configured webhooks
+    DkgWebhookList {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+    },
+
+    /// Configure encrypted sync of wallet state (S3, WebDAV/Nextcloud, or a folder)
+    #[cfg(feature = "sync")]
+    DkgSyncConfig {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Backend: s3, webdav, nextcloud or dir
+        #[arg(long)]
+        backend: String,
+
+        /// Bucket URL, WebDAV collection URL, or directory path
+        #[arg(long)]
+        url: String,
+
+        /// S3 region (default: us-east-1)
+        #[arg(long)]
+        region: Option<String>,
+    },
+
+    /// Encrypt and upload wallet state (nonces are never synced)
+    #[cfg(feature = "sync")]
+    DkgSyncPush {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Overwrite the remote even if it has changes not present locally
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Download and decrypt wallet state from the sync remote
+    #[cfg(feature = "sync")]
+    DkgSyncPull {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+
+        /// Discard local changes that were never pushed
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Compare local wallet state with the sync remote
+    #[cfg(feature = "sync")]
+    DkgSyncStatus {
+        /// Wallet name
+        #[arg(long)]
+        name: String,
+    },
+}
+
+/// Subcommands that only read a wallet and so never wait for its lock
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "keygen-status",
+    "dkg-address",
+    "dkg-balance",
+    "dkg-fingerprint",
+    "dkg-health",
+    "dkg-coefficients",
+    "dkg-list-addresses",
+    "dkg-list-script-addresses",
+    "dkg-spend-plan",
+    "dkg-watch",
+    "dkg-webhook-list",
+    "dkg-sync-status",
+    "tx-decode",
+];
+
+/// Subcommands that create the `--name` wallet, locking its folder from the start
+const CREATING_COMMANDS: &[&str] = &["keygen-round1", "dkg-split-key"];
+
+/// A wallet folder a subcommand changes, and whether the command may create it
+struct LockTarget {
+    dir: PathBuf,
+    create: bool,
+}
+
+/// The wallet folder a name stands for; `<wallet>/party<i>` shares the wallet's lock
+fn wallet_dir(name: &str) -> PathBuf {
+    PathBuf::from(keygen::get_state_dir(
+        name.split('/').next().unwrap_or(name),
+    ))
+}
+
+/// The subcommand and every wallet folder it changes
+///
+/// That is the `--name` wallet, the `--source` and `--target` wallets of
+/// resharing and recovery, and the share folders of `dkg-reconstruct`.
+fn wallets_to_lock(matches: &clap::ArgMatches) -> Option<(String, Vec<LockTarget>)> {
+    let (command, sub) = matches.subcommand()?;
+    if READ_ONLY_COMMANDS.contains(&command) {
+        return None;
+    }
+    let arg = |id: &str| sub.try_get_one::<String>(id).ok().flatten();
+    let mut targets = Vec::new();
+    if let Some(name) = arg("name") {
+        targets.push(LockTarget {
+            dir: wallet_dir(name),
+            create: CREATING_COMMANDS.contains(&command),
+        });
+    }
+    if let Some(source) = arg("source") {
+        targets.push(LockTarget {
+            dir: wallet_dir(source),
+            create: false,
+        });
+    }
+    if let Some(target) = arg("target") {
+        targets.push(LockTarget {
+            dir: wallet_dir(target),
+            create: true,
+        });
+    }
+    if let Ok(Some(shares)) = sub.try_get_many::<String>("shares") {
+        targets.extend(shares.map(|folder| LockTarget {
+            dir: PathBuf::from(folder),
+            create: false,
+        }));
+    }
+    (!targets.is_empty()).then(|| (command.to_string(), targets))
+}
+
+/// Lock every folder in `targets` for this run
+///
+/// Paths are resolved here, after `--ephemeral` has moved the working
+/// directory, and locked in sorted order so two commands waiting on the same
+/// pair can't deadlock.
+fn lock_wallets(
+    command: &str,
+    mut targets: Vec<LockTarget>,
+    wait: Option<u64>,
+    force_unlock: bool,
+) -> Result<Vec<frostdao::storage::lock::WalletLock>> {
+    let state_root = std::fs::canonicalize(frostdao::protocol::wallet::STATE_ROOT).ok();
+    for target in &mut targets {
+        // A party folder of a wallet in the state root is covered by the
+        // wallet's lock, as with `--name <wallet>/party<i>`
+        let in_wallet = target
+            .dir
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("party"))
+            && state_root.is_some()
+            && target
+                .dir
+                .parent()
+                .and_then(|wallet| wallet.parent())
+                .and_then(|root| std::fs::canonicalize(root).ok())
+                == state_root;
+        if in_wallet {
+            target.dir.pop();
+        }
+        // One folder named two ways must be locked once, not wait on itself
+        if let Ok(dir) = std::fs::canonicalize(&target.dir) {
+            target.dir = dir;
+        }
+    }
+    targets.sort_by(|a, b| a.dir.cmp(&b.dir));
+    targets.dedup_by(|a, b| a.dir == b.dir);
+
+    let mut locks = Vec::new();
+    for target in &targets {
+        locks.extend(lock_wallet(command, target, wait, force_unlock)?);
+    }
+    Ok(locks)
+}
+
+/// Take the advisory lock of a wallet folder for this run (see storage::lock)
+///
+/// A missing folder is skipped (the command reports it) unless the command
+/// creates it, in which case it is made and locked.
+fn lock_wallet(
+    command: &str,
+    target: &LockTarget,
+    wait: Option<u64>,
+    force_unlock: bool,
+) -> Result<Option<frostdao::storage::lock::WalletLock>> {
+    use frostdao::storage::lock;
+
+    let dir = &target.dir;
+    if !dir.is_dir() && !target.create {
+        return Ok(None);
+    }
+    if force_unlock {
+        if let Some(holder) = lock::force_unlock(dir)? {
+            eprintln!(
+                "🔓 Removed the lock held by `{}` (pid {})",
+                holder.command, holder.pid
+            );
+        }
+    }
+    if let (Some(secs), Some(holder)) = (wait, lock::holder_of(dir)) {
+        if !holder.is_stale() {
+            eprintln!(
+                "⏳ Waiting up to {}s for `{}` (pid {}) to finish with {}",
+                secs,
+                holder.command,
+                holder.pid,
+                dir.display()
+            );
+        }
+    }
+    let wait = std::time::Duration::from_secs(wait.unwrap_or(0));
+    let held = if target.create {
+        lock::WalletLock::create(dir, command, wait)?
+    } else {
+        lock::WalletLock::acquire(dir, command, wait)?
+    };
+    if let Some(stale) = &held.replaced {
+        eprintln!(
+            "🔓 Took over a stale lock left by `{}` (pid {} is no longer running)",
+            stale.command, stale.pid
+        );
+    }
+    Ok(Some(held))
+}
+
+fn main() {
+    let matches = Cli::command().get_matches();
+    let wallets = wallets_to_lock(&matches);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let json = cli.json;
+    if let Err(err) = run(cli, wallets) {
+        if json {
+            eprintln!("{}", frostdao::error::to_json(&err));
+        } else {
+            eprintln!("Error: {:?}", err);
+        }
+        std::process::exit(frostdao::error::kind_of(&err).exit_code());
+    }
+}
+
+fn run(cli: Cli, wallets: Option<(String, Vec<LockTarget>)>) -> Result<()> {
+    frostdao::protocol::reporter::set_quiet(cli.quiet);
+    frostdao::protocol::compact::set_compact(cli.compact);
+    frostdao::protocol::payload::set_output_version(cli.protocol_version)?;
+    frostdao::storage::set_insecure_permissions(cli.insecure_permissions);
+    #[cfg(feature = "network")]
+    frostdao::btc::esplora::set_url_override(cli.esplora_url);
+    #[cfg(feature = "network")]
+    frostdao::btc::http::set_timeouts(frostdao::btc::http::Timeouts {
+        connect: std::time::Duration::from_secs(cli.connect_timeout),
+        read: std::time::Duration::from_secs(cli.read_timeout),
+        deadline: std::time::Duration::from_secs(cli.deadline),
+    });
+    #[cfg(feature = "network")]
+    if cli.simulate {
+        frostdao::btc::simulate::set_simulate(true);
+        eprintln!(
+            "🧪 SIMULATED CHAIN - coins, blocks and broadcasts are fake and stay on this machine"
+        );
+    }
+
+    let _ephemeral = if cli.ephemeral {
+        let state = EphemeralState::enter()?;
+        eprintln!("🫥 EPHEMERAL - wallets live in memory only and vanish when frostdao exits");
+        Some(state)
+    } else {
+        None
+    };
+
+    // One frostdao at a time changes a wallet
+    let locks = match wallets {
+        Some((command, targets)) => lock_wallets(&command, targets, cli.wait, cli.force_unlock)?,
+        None => Vec::new(),
+    };
+
+    // Migration: plaintext secrets from older versions never stay on disk.
+    // Each wallet is locked while it is cleaned or upgraded; one another
+    // frostdao is using waits for a later run. A folder that can't be
+    // scanned is reported, not a reason to refuse an unrelated command.
+    let held: Vec<_> = locks.iter().map(|lock| lock.dir().to_path_buf()).collect();
+    match keygen::shred_legacy_secret_files(&held) {
+        Ok(lines) => lines.iter().for_each(|line| eprintln!("{}", line)),
+        Err(e) => eprintln!("⚠️  Couldn't scan for old plaintext secrets: {:#}", e),
+    }
+    match keygen::migrate_wallets(&held) {
+        Ok(lines) => lines.iter().for_each(|line| eprintln!("{}", line)),
+        Err(e) => eprintln!("⚠️  Couldn't check wallets for upgrades: {:#}", e),
+    }
+
+    match cli.command {
+        Commands::KeygenRound1 {
             name,
             threshold,
             n_parties,
             my_index,
             rank,
             hierarchical,
+            weight,
+            curve,
+            qr,
+            #[cfg(feature = "demo-seed")]
+            seed,
+        } => {
+            #[cfg(not(feature = "demo-seed"))]
+            let seed: Option<String> = None;
+            keygen::round1(
+                &name,
+                threshold,
+                n_parties,
+                my_index,
+                rank,
+                hierarchical,
+                weight,
+                curve,
+                qr,
+                seed.as_deref(),
+            )?;
+        }
+        Commands::KeygenRound2 {
+            name,
+            data,
+            data_file,
+            qr,
+            dm,
+            #[cfg(feature = "network")]
+            relay,
+        } => {
+            #[cfg(feature = "network")]
+            let mut relays = relay.connect()?;
+            #[cfg(not(feature = "network"))]
+            let mut relays: Option<Relays> = None;
+            let data = prompt::data(data, data_file, "every party's Round 1 output")?;
+            keygen::round2(&name, &data, qr, dm, relays.as_mut())?;
+        }
+        Commands::KeygenFinalize {
+            name,
+            data,
+            data_file,
+            #[cfg(feature = "network")]
+            relay,
+            #[cfg(feature = "network")]
+            listen,
         } => {
-            keygen::round1(&name, threshold, n_parties, my_index, rank, hierarchical)?;
+            #[cfg(feature = "network")]
+            let mut relays = relay.connect()?;
+            #[cfg(not(feature = "network"))]
+            let mut relays: Option<Relays> = None;
+            #[cfg(not(feature = "network"))]
+            let listen = 0;
+            let data = match relays {
+                Some(_) => prompt::optional_data(data, data_file)?,
+                None => Some(prompt::data(
+                    data,
+                    data_file,
+                    "the Round 2 shares sent to you",
+                )?),
+            };
+            keygen::finalize(
+                &name,
+                data.as_deref(),
+                relays.as_mut(),
+                Duration::from_secs(listen),
+            )?;
         }
-        Commands::KeygenRound2 { name, data } => {
-            keygen::round2(&name, &data)?;
+        Commands::KeygenStatus { name } => {
+            keygen_resume::status(&name)?;
         }
-        Commands::KeygenFinalize { name, data } => {
-            keygen::finalize(&name, &data)?;
+        Commands::KeygenResume {
+            name,
+            data,
+            data_file,
+        } => {
+            let data = prompt::data(data, data_file, "every party's Round 1 or Round 2 outputs")?;
+            keygen_resume::resume(&name, &data)?;
         }
-        Commands::GenerateNonce { session } => {
-            signing::generate_nonce(&session)?;
+        Commands::GenerateNonce { name, session } => {
+            signing::generate_nonce(name.as_deref(), &session)?;
         }
         Commands::Sign {
+            name,
             session,
             message,
             data,
+            data_file,
         } => {
-            signing::create_signature_share(&session, &message, &data)?;
+            let data = prompt::data(data, data_file, "the nonces and group key")?;
+            signing::create_signature_share(name.as_deref(), &session, &message, &data)?;
         }
-        Commands::Combine { data } => {
-            signing::combine_signatures(&data)?;
+        Commands::Combine {
+            name,
+            data,
+            data_file,
+        } => {
+            let data = prompt::data(data, data_file, "every signature share")?;
+            signing::combine_signatures(name.as_deref(), &data)?;
         }
         Commands::Verify {
             signature,
             public_key,
             message,
+            curve,
         } => {
-            signing::verify_signature(&signature, &public_key, &message)?;
+            signing::verify_signature(curve, &signature, &public_key, &message)?;
         }
 
         // Bitcoin Schnorr (BIP340) commands
@@ -547,6 +2256,7 @@ fn main() -> Result<()> {
             Some(n) => bitcoin_schnorr::get_dkg_address_testnet(&n)?,
             None => keygen::print_wallet_list()?,
         },
+        #[cfg(feature = "network")]
         Commands::DkgBalance { name } => match name {
             Some(n) => bitcoin_tx::check_dkg_balance_testnet(&n)?,
             None => keygen::print_wallet_list()?,
@@ -557,6 +2267,131 @@ fn main() -> Result<()> {
         Commands::DkgInfo { name } => {
             keygen::regenerate_group_info(&name)?;
         }
+        Commands::DkgFingerprint { name } => {
+            fingerprint::fingerprint(&name)?;
+        }
+        Commands::DkgSplitKey {
+            name,
+            threshold,
+            n_parties,
+            secret,
+            ranks,
+        } => {
+            dealer::split_key(&name, secret.as_deref(), threshold, n_parties, ranks)?;
+        }
+        Commands::DkgReconstruct { shares, to, out } => {
+            reconstruct::reconstruct(&shares, &to, &out)?;
+        }
+        Commands::DkgReconstructOpen { name, file } => {
+            reconstruct::open(&name, &file)?;
+        }
+        Commands::DkgDoctor { name, fix } => {
+            doctor::doctor(&name, fix)?;
+        }
+        Commands::DkgHealth { name } => {
+            health::health(&name)?;
+        }
+        Commands::HealthConfig {
+            max_share_age_days,
+            max_missed_sessions,
+        } => {
+            health::configure(max_share_age_days, max_missed_sessions)?;
+        }
+        Commands::DkgMigrateLayout { name } => {
+            wallet::migrate_layout(&name)?;
+        }
+        Commands::DkgRename { name, new_name } => {
+            wallet::rename_wallet(&name, &new_name)?;
+        }
+        Commands::DkgArchive { name } => {
+            wallet::archive_wallet(&name)?;
+        }
+        Commands::DkgDelete { name, confirm_name } => {
+            wallet::delete_wallet(&name, &confirm_name)?;
+        }
+        Commands::DkgCoefficients { name, signers } => {
+            signing::coefficients(&name, &signers)?;
+        }
+        Commands::DkgSigningRequirement { name, set, clear } => {
+            wallet::signing_requirement(&name, set.as_deref(), clear)?;
+        }
+        Commands::DkgNotes {
+            name,
+            description,
+            contact,
+            created,
+            tag,
+            untag,
+        } => {
+            let edit = wallet::NotesEdit {
+                description,
+                contacts: contact,
+                created,
+                tags: tag,
+                untags: untag,
+            };
+            wallet::notes(&name, &edit)?;
+        }
+        Commands::DkgParty {
+            name,
+            index,
+            holder,
+            contact,
+            npub,
+            availability,
+            share_confirmed,
+            clear,
+        } => {
+            let edit = index.map(|index| directory::PartyEdit {
+                index,
+                holder,
+                contact,
+                npub,
+                availability,
+                share_confirmed,
+                clear,
+            });
+            directory::party(&name, edit.as_ref())?;
+        }
+        Commands::DkgIdentity { name, trust } => {
+            identity::identity(&name, trust.as_deref())?;
+        }
+        Commands::NostrKeygen { name, force } => {
+            nostr::nostr_keygen(&name, force)?;
+        }
+        Commands::NostrImport { name, key, force } => {
+            nostr::nostr_import(&name, &key, force)?;
+        }
+        Commands::DkgGroupPublish {
+            name,
+            qr,
+            #[cfg(feature = "network")]
+            relay,
+        } => {
+            #[cfg(feature = "network")]
+            let mut relays = relay.connect()?;
+            #[cfg(not(feature = "network"))]
+            let mut relays: Option<Relays> = None;
+            nostr::group_publish(&name, qr, relays.as_mut())?;
+        }
+        Commands::DkgGroupImport {
+            name,
+            data,
+            data_file,
+            from,
+            #[cfg(feature = "network")]
+            relay,
+        } => {
+            #[cfg(feature = "network")]
+            let mut relays = relay.connect()?;
+            #[cfg(not(feature = "network"))]
+            let mut relays: Option<Relays> = None;
+            let data = match relays {
+                Some(_) => prompt::optional_data(data, data_file)?,
+                None => Some(prompt::data(data, data_file, "the group info event")?),
+            };
+            nostr::group_import(&name, data.as_deref(), from.as_deref(), relays.as_mut())?;
+        }
 
         // HD Key Derivation commands
         Commands::DkgDeriveAddress {
@@ -586,36 +2421,121 @@ fn main() -> Result<()> {
             let result = hd_address::list_addresses_core(count, &network, &storage)?;
             println!("{}", result.output);
         }
-        Commands::DkgGenerateMnemonic { name } => {
-            use frostdao::crypto::mnemonic;
+        Commands::DkgExportWatchOnly { name, network, out } => {
+            frostdao::btc::descriptor::export_watch_only(&name, &network, out.as_deref())?;
+        }
+        Commands::DkgScriptAddress {
+            name,
+            script_type,
+            policy,
+            lock_height,
+            blocks,
+            timeout,
+            recovery_pubkey,
+            htlc_hash,
+            refund_pubkey,
+            recipient_pubkey,
+            change,
+            index,
+            network,
+        } => {
+            use frostdao::btc::taproot_scripts::{parse_pubkey_hex, ScriptParams, ScriptTypeInput};
+            use frostdao::btc::taproot_tree;
             use frostdao::storage::FileStorage;
 
+            let mut params = Vec::new();
+            for script_type in script_type.iter().flat_map(|types| types.split(',')) {
+                params.push(ScriptParams::from_strings(
+                    ScriptTypeInput::from_name(script_type.trim())?,
+                    &lock_height,
+                    &blocks,
+                    &timeout,
+                    &recovery_pubkey,
+                    &htlc_hash,
+                    &refund_pubkey,
+                )?);
+            }
+            params.extend(policy.into_iter().map(|policy| ScriptParams {
+                script_type: ScriptTypeInput::Policy,
+                policy: Some(policy),
+                ..Default::default()
+            }));
+            let recipient = recipient_pubkey
+                .as_deref()
+                .map(parse_pubkey_hex)
+                .transpose()?;
+            let derivation_path = match (change, index) {
+                (None, None) => None,
+                (change, index) => Some((change.unwrap_or(0), index.unwrap_or(0))),
+            };
+
             let state_dir = keygen::get_state_dir(&name);
             let storage = FileStorage::new(&state_dir)?;
+            let result = taproot_tree::script_address_core(
+                &params,
+                recipient,
+                derivation_path,
+                &network,
+                &storage,
+            )?;
+            println!("{}", result.output);
+            println!("📋 Taproot tree:");
+            println!("{}", result.result);
+        }
+        Commands::DkgListScriptAddresses { name } => {
+            use frostdao::btc::taproot_tree;
+            use frostdao::storage::FileStorage;
 
-            // Load the secret share
-            let paired_share_bytes = storage.read("paired_secret_share.bin")?;
-            let paired_share: schnorr_fun::frost::PairedSecretShare<secp256kfun::marker::EvenY> =
-                bincode::deserialize(&paired_share_bytes)?;
-
-            // Get share bytes
-            let share_bytes: [u8; 32] = paired_share.secret_share().share.to_bytes();
-
-            // Generate mnemonic from share
-            let mnemonic_result = mnemonic::share_to_mnemonic(&share_bytes)?;
-
-            println!("BIP-39 Mnemonic Backup for Wallet '{}'\n", name);
-            println!(
-                "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
-            );
-            println!("WARNING: This mnemonic backs up YOUR SECRET SHARE only.");
-            println!("         Recovery still requires threshold shares from other parties.\n");
-            println!("{}\n", mnemonic::format_mnemonic_grid(&mnemonic_result));
-            println!(
-                "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"
-            );
-            println!("\nWrite down these 24 words and store them securely!");
-            println!("Never share them with anyone.");
+            let state_dir = keygen::get_state_dir(&name);
+            let storage = FileStorage::new(&state_dir)?;
+            let result = taproot_tree::list_script_addresses_core(&storage)?;
+            println!("{}", result.output);
+        }
+        #[cfg(feature = "network")]
+        Commands::DkgSpendPlan {
+            name,
+            address,
+            spend_path,
+            preimage,
+            network,
+        } => {
+            frostdao::btc::spend_plan::dkg_spend_plan(
+                &name,
+                &address,
+                spend_path.as_deref(),
+                preimage.as_deref(),
+                &network,
+            )?;
+        }
+        Commands::DkgGenerateMnemonic { name, passphrase } => {
+            backup::generate_mnemonic(&name, passphrase)?;
+        }
+        Commands::DkgRestoreMnemonic {
+            name,
+            words,
+            passphrase,
+            check,
+            force,
+        } => {
+            backup::restore_mnemonic(&name, &words, passphrase, check.as_deref(), force)?;
+        }
+        Commands::DkgBackupSplit {
+            name,
+            threshold,
+            pieces,
+            format,
+            passphrase,
+        } => {
+            backup::backup_split(&name, threshold, pieces, format, passphrase)?;
+        }
+        Commands::DkgBackupRestore {
+            name,
+            pieces,
+            passphrase,
+            check,
+            force,
+        } => {
+            backup::backup_restore(&name, &pieces, passphrase, check.as_deref(), force)?;
         }
 
         Commands::ReshareRound1 {
@@ -623,8 +2543,15 @@ fn main() -> Result<()> {
             new_threshold,
             new_n_parties,
             my_index,
+            new_identity,
         } => {
-            reshare::reshare_round1(&source, new_threshold, new_n_parties, my_index)?;
+            reshare::reshare_round1(
+                &source,
+                new_threshold,
+                new_n_parties,
+                my_index,
+                &new_identity,
+            )?;
         }
         Commands::ReshareFinalize {
             source,
@@ -633,12 +2560,68 @@ fn main() -> Result<()> {
             rank,
             hierarchical,
             data,
+            data_file,
+            allow_threshold_decrease,
+            verify_only,
         } => {
-            reshare::reshare_finalize(&source, &target, my_index, rank, hierarchical, &data)?;
+            let data = prompt::data(data, data_file, "the old parties' Round 1 outputs")?;
+            reshare::reshare_finalize(
+                &source,
+                &target,
+                my_index,
+                rank,
+                hierarchical,
+                &data,
+                allow_threshold_decrease,
+                verify_only,
+            )?;
         }
         Commands::RecoverRound1 { name, lost_index } => {
             recovery::recover_round1(&name, lost_index)?;
         }
+        Commands::RecoverRequest {
+            name,
+            my_index,
+            qr,
+            #[cfg(feature = "network")]
+            relay,
+        } => {
+            #[cfg(feature = "network")]
+            let mut relays = relay.connect()?;
+            #[cfg(not(feature = "network"))]
+            let mut relays: Option<Relays> = None;
+            recovery::recover_request(&name, my_index, qr, relays.as_mut())?;
+        }
+        Commands::RecoverRespond {
+            name,
+            data,
+            data_file,
+            from,
+            yes,
+            #[cfg(feature = "network")]
+            relay,
+            #[cfg(feature = "network")]
+            listen,
+        } => {
+            #[cfg(feature = "network")]
+            let mut relays = relay.connect()?;
+            #[cfg(not(feature = "network"))]
+            let mut relays: Option<Relays> = None;
+            #[cfg(not(feature = "network"))]
+            let listen = 0;
+            let data = match relays {
+                Some(_) => prompt::optional_data(data, data_file)?,
+                None => Some(prompt::data(data, data_file, "the recovery request event")?),
+            };
+            recovery::recover_respond(
+                &name,
+                data.as_deref(),
+                from.as_deref(),
+                yes,
+                relays.as_mut(),
+                Duration::from_secs(listen),
+            )?;
+        }
         Commands::RecoverFinalize {
             source,
             target,
@@ -646,72 +2629,215 @@ fn main() -> Result<()> {
             rank,
             hierarchical,
             data,
+            data_file,
             force,
+            #[cfg(feature = "network")]
+            relay,
+            #[cfg(feature = "network")]
+            listen,
         } => {
+            #[cfg(feature = "network")]
+            let mut relays = relay.connect()?;
+            #[cfg(not(feature = "network"))]
+            let mut relays: Option<Relays> = None;
+            #[cfg(not(feature = "network"))]
+            let listen = 0;
+            let data = match relays {
+                Some(_) => prompt::optional_data(data, data_file)?,
+                None => Some(prompt::data(
+                    data,
+                    data_file,
+                    "the helper parties' Round 1 outputs",
+                )?),
+            };
             recovery::recover_finalize(
                 &source,
                 &target,
                 my_index,
                 rank,
                 hierarchical,
-                &data,
+                data.as_deref(),
                 force,
+                relays.as_mut(),
+                Duration::from_secs(listen),
             )?;
         }
-        Commands::Tui => {
-            tui::run_tui()?;
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "frostdao",
+                &mut std::io::stdout(),
+            );
+        }
+        #[cfg(feature = "network")]
+        Commands::Tui {
+            refresh_secs,
+            notify,
+        } => {
+            tui::run_tui(refresh_secs, notify)?;
         }
+        #[cfg(feature = "network")]
         Commands::BtcBalance => {
             bitcoin_tx::check_balance_testnet()?;
         }
+        #[cfg(feature = "network")]
         Commands::BtcSend {
             to,
             amount,
+            amount_fiat,
             fee_rate,
+            allow_inscribed,
         } => {
-            bitcoin_tx::send_testnet(&to, amount, fee_rate)?;
+            let amount = frostdao::btc::fiat::resolve_amount(amount, amount_fiat.as_deref())?;
+            bitcoin_tx::send_testnet(&to, amount, fee_rate, allow_inscribed)?;
         }
+        #[cfg(feature = "network")]
         Commands::BtcSendSignet {
             to,
             amount,
+            amount_fiat,
             fee_rate,
+            allow_inscribed,
         } => {
-            bitcoin_tx::send_signet(&to, amount, fee_rate)?;
+            let amount = frostdao::btc::fiat::resolve_amount(amount, amount_fiat.as_deref())?;
+            bitcoin_tx::send_signet(&to, amount, fee_rate, allow_inscribed)?;
         }
 
         // DKG Threshold Transaction commands
+        #[cfg(feature = "network")]
         Commands::DkgBuildTx {
             name,
             to,
             amount,
+            amount_fiat,
             fee_rate,
             network,
+            allow_inscribed,
+            from,
+            memo,
+            spend_path,
+            preimage,
         } => {
+            let amount = frostdao::btc::fiat::resolve_amount(amount, amount_fiat.as_deref())?;
             let net = match network.as_str() {
                 "mainnet" => bitcoin::Network::Bitcoin,
                 "signet" => bitcoin::Network::Signet,
                 _ => bitcoin::Network::Testnet,
             };
-            dkg_tx::build_unsigned_tx(&name, &to, amount, fee_rate, net)?;
+            dkg_tx::build_unsigned_tx(
+                &name,
+                &to,
+                amount,
+                fee_rate,
+                net,
+                allow_inscribed,
+                from.as_deref(),
+                memo.as_deref(),
+                spend_path.as_deref().map(str::parse).transpose()?,
+                preimage.as_deref(),
+            )?;
+        }
+        Commands::QrShow { data, data_file } => {
+            let data = prompt::data(data, data_file, "the payload to encode")?;
+            frostdao::qr::show_qr(&frostdao::qr::resolve_payload(&data)?)?;
+        }
+        Commands::QrDecode {
+            data,
+            data_file,
+            output,
+        } => {
+            let data = prompt::data(data, data_file, "the scanned ur: frames")?;
+            let decoded = frostdao::qr::resolve_payload(&data)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, format!("{}\n", decoded))?;
+                    println!("💾 Wrote {}", path);
+                }
+                None => println!("{}", decoded),
+            }
+        }
+        Commands::TxDecode {
+            hex,
+            hex_file,
+            name,
+            network,
+        } => {
+            let data = prompt::data(hex, hex_file, "the raw transaction hex")?;
+            frostdao::btc::tx_decode::tx_decode(&data, name.as_deref(), &network)?;
+        }
+        Commands::SighashExplain {
+            unsigned_tx,
+            input,
+            prevouts,
+            sighash,
+            sighash_type,
+            leaf_hash,
+        } => {
+            let prevouts = prompt::data(Some(prevouts), None, "the spent outputs")?;
+            frostdao::btc::sighash::sighash_explain(
+                &unsigned_tx,
+                input,
+                &prevouts,
+                &sighash_type,
+                leaf_hash.as_deref(),
+                sighash.as_deref(),
+            )?;
         }
-        Commands::DkgNonce { name, session } => {
-            dkg_tx::dkg_generate_nonce(&name, &session)?;
+        Commands::DkgNonce { name, session, qr } => {
+            dkg_tx::dkg_generate_nonce(&name, &session, qr)?;
         }
         Commands::DkgSign {
             name,
             session,
             sighash,
+            tx,
+            unsafe_bare_sighash,
             data,
+            data_file,
+            merkle_root,
+            qr,
+        } => {
+            let data = prompt::data(data, data_file, "every signer's nonce")?;
+            dkg_tx::dkg_sign(
+                &name,
+                &session,
+                sighash.as_deref(),
+                &data,
+                merkle_root.as_deref(),
+                tx.as_deref(),
+                unsafe_bare_sighash,
+                qr,
+            )?;
+        }
+        Commands::DkgSignOffline {
+            name,
+            input,
+            output,
+            qr,
         } => {
-            dkg_tx::dkg_sign(&name, &session, &sighash, &data)?;
+            offline::dkg_sign_offline(&name, &input, output.as_deref(), qr)?;
         }
+        Commands::DeviceEnroll {
+            name,
+            command,
+            serial,
+        } => {
+            device::enroll(&name, command.as_deref(), serial.as_deref())?;
+        }
+        Commands::DeviceEmulate { dir, yes } => {
+            device::emulate(&dir, yes)?;
+        }
+        #[cfg(feature = "network")]
         Commands::DkgBroadcast {
             name,
             session,
             unsigned_tx,
             data,
+            data_file,
             network,
         } => {
+            let data = prompt::data(data, data_file, "every signature share")?;
             let net = match network.as_str() {
                 "mainnet" => bitcoin::Network::Bitcoin,
                 "signet" => bitcoin::Network::Signet,
@@ -719,6 +2845,277 @@ fn main() -> Result<()> {
             };
             dkg_tx::dkg_broadcast(&name, &session, &unsigned_tx, &data, net)?;
         }
+        #[cfg(feature = "network")]
+        Commands::DkgRotate {
+            name,
+            to,
+            signers,
+            hd_count,
+            fee_rate,
+            network,
+            allow_inscribed,
+            dry_run,
+        } => {
+            let net = match network.as_str() {
+                "mainnet" => bitcoin::Network::Bitcoin,
+                "signet" => bitcoin::Network::Signet,
+                _ => bitcoin::Network::Testnet,
+            };
+            frostdao::protocol::rotation::rotate(
+                &name,
+                &to,
+                &signers,
+                hd_count,
+                fee_rate,
+                net,
+                allow_inscribed,
+                dry_run,
+            )?;
+        }
+        Commands::DkgSessionReport {
+            name,
+            session,
+            out,
+            import,
+        } => {
+            session_report::session_report(
+                &name,
+                session.as_deref(),
+                out.as_deref(),
+                import.as_deref(),
+            )?;
+        }
+        Commands::DkgLnFundingAddress {
+            name,
+            peer_key,
+            local_key,
+            channel_type,
+            network,
+            expect,
+        } => {
+            lightning::funding_address(
+                &name,
+                &peer_key,
+                &local_key,
+                channel_type,
+                &network,
+                expect.as_deref(),
+            )?;
+        }
+        Commands::DkgLnFundPsbt {
+            name,
+            session,
+            unsigned_tx,
+            data,
+            data_file,
+            funding_address,
+        } => {
+            let data = prompt::data(data, data_file, "every signature share")?;
+            lightning::fund_psbt(&name, &session, &unsigned_tx, &data, &funding_address)?;
+        }
+        #[cfg(feature = "network")]
+        Commands::BackendConfig {
+            network,
+            url,
+            username,
+            reset: _,
+        } => {
+            frostdao::btc::esplora::configure(&network, url.as_deref(), username.as_deref())?;
+        }
+        #[cfg(feature = "network")]
+        Commands::BroadcastConfig {
+            network,
+            add,
+            username,
+            remove,
+            reset: _,
+        } => {
+            frostdao::btc::broadcast::configure(
+                &network,
+                add.as_deref(),
+                username.as_deref(),
+                remove.as_deref(),
+            )?;
+        }
+        #[cfg(feature = "network")]
+        Commands::RelayConfig {
+            add,
+            remove,
+            reset: _,
+        } => {
+            relay_client::configure(add.as_deref(), remove.as_deref())?;
+        }
+        #[cfg(feature = "network")]
+        Commands::BackendStatus { network } => {
+            frostdao::btc::esplora::backend_status(&network)?;
+        }
+        #[cfg(feature = "network")]
+        Commands::FiatConfig {
+            currency,
+            provider,
+            cache_secs,
+            disable: _,
+        } => {
+            frostdao::btc::fiat::configure(currency.as_deref(), provider, cache_secs)?;
+        }
+        #[cfg(feature = "network")]
+        Commands::FiatRate { currency } => {
+            frostdao::btc::fiat::show_rate(currency.as_deref())?;
+        }
+        #[cfg(feature = "network")]
+        Commands::DkgFaucet {
+            name,
+            network,
+            faucet_url,
+            amount,
+            interval,
+            timeout,
+        } => {
+            let opts = frostdao::btc::faucet::FaucetOptions {
+                interval_secs: interval,
+                timeout_secs: timeout,
+            };
+            frostdao::btc::faucet::dkg_faucet(
+                &name,
+                &network,
+                faucet_url.as_deref(),
+                amount,
+                &opts,
+            )?;
+        }
+        #[cfg(feature = "network")]
+        Commands::FaucetConfig {
+            network,
+            url,
+            amount,
+            reset: _,
+        } => {
+            frostdao::btc::faucet::configure(&network, url.as_deref(), amount)?;
+        }
+        #[cfg(feature = "network")]
+        Commands::DkgFilterSync {
+            name,
+            network,
+            peers,
+            from_height,
+        } => {
+            frostdao::btc::compact_filters::filter_sync(&name, &network, &peers, from_height)?;
+        }
+        #[cfg(feature = "network")]
+        Commands::DkgWatchWallet {
+            name,
+            network,
+            timeout,
+        } => {
+            frostdao::btc::electrum::watch_wallet(&name, &network, timeout)?;
+        }
+        #[cfg(feature = "network")]
+        Commands::DkgRebroadcast {
+            name,
+            network,
+            txid,
+            forget,
+        } => {
+            frostdao::btc::rebroadcast::dkg_rebroadcast(&name, &network, txid.as_deref(), forget)?;
+        }
+        #[cfg(feature = "network")]
+        Commands::DkgWatch {
+            txid,
+            network,
+            interval,
+            timeout,
+            confirmations,
+            name,
+            webhook,
+            notify,
+        } => {
+            let net = match network.as_str() {
+                "mainnet" => bitcoin::Network::Bitcoin,
+                "signet" => bitcoin::Network::Signet,
+                _ => bitcoin::Network::Testnet,
+            };
+            frostdao::btc::monitor::dkg_watch(
+                &txid,
+                net,
+                interval,
+                timeout,
+                confirmations,
+                name.as_deref(),
+                webhook.as_deref(),
+                notify,
+            )?;
+        }
+        Commands::DkgWebhookAdd {
+            name,
+            url,
+            kind,
+            events,
+        } => {
+            let storage = frostdao::storage::FileStorage::new(&keygen::get_state_dir(&name))?;
+            let result =
+                frostdao::notify::add_webhook_core(&url, kind.as_deref(), &events, &storage)?;
+            println!("{}", result.output);
+        }
+        Commands::DkgWebhookRemove { name, url } => {
+            let storage = frostdao::storage::FileStorage::new(&keygen::get_state_dir(&name))?;
+            let result = frostdao::notify::remove_webhook_core(&url, &storage)?;
+            println!("{}", result.output);
+        }
+        Commands::DkgWebhookList { name } => {
+            let storage = frostdao::storage::FileStorage::new(&keygen::get_state_dir(&name))?;
+            let result = frostdao::notify::list_webhooks_core(&storage)?;
+            println!("{}", result.output);
+        }
+        #[cfg(feature = "sync")]
+        Commands::DkgSyncConfig {
+            name,
+            backend,
+            url,
+            region,
+        } => {
+            let storage = frostdao::storage::FileStorage::new(&keygen::get_state_dir(&name))?;
+            let result =
+                frostdao::sync::configure_sync_core(&backend, &url, region.as_deref(), &storage)?;
+            println!("{}", result.output);
+        }
+        #[cfg(feature = "sync")]
+        Commands::DkgSyncPush { name, force } => {
+            let state_dir = keygen::get_state_dir(&name);
+            let (key, remote) = frostdao::sync::connect(&state_dir, &name)?;
+            let result = frostdao::sync::sync_push_core(
+                std::path::Path::new(&state_dir),
+                &name,
+                &key,
+                remote.as_ref(),
+                force,
+            )?;
+            println!("{}", result.output);
+        }
+        #[cfg(feature = "sync")]
+        Commands::DkgSyncPull { name, force } => {
+            let state_dir = keygen::get_state_dir(&name);
+            let (key, remote) = frostdao::sync::connect(&state_dir, &name)?;
+            let result = frostdao::sync::sync_pull_core(
+                std::path::Path::new(&state_dir),
+                &name,
+                &key,
+                remote.as_ref(),
+                force,
+            )?;
+            println!("{}", result.output);
+        }
+        #[cfg(feature = "sync")]
+        Commands::DkgSyncStatus { name } => {
+            let state_dir = keygen::get_state_dir(&name);
+            let (key, remote) = frostdao::sync::connect(&state_dir, &name)?;
+            let result = frostdao::sync::sync_status_core(
+                std::path::Path::new(&state_dir),
+                &name,
+                &key,
+                remote.as_ref(),
+            )?;
+            println!("{}", result.output);
+        }
     }
 
     Ok(())
@@ -0,0 +1,426 @@
+//! Signing Notifications
+//!
+//! Per-wallet webhooks so distributed signers get pinged instead of polling
+//! the coordinator. Configured in `webhooks.json` in the wallet state directory
+//! and fired on:
+//!
+//! - `session_created`: `dkg-build-tx` created a new signing session
+//! - `share_submitted`: a party produced its signature share (`dkg-sign`)
+//! - `tx_confirmed`: a watched transaction got its first confirmation
+//!
+//! Payloads are shaped for the target: Slack (`text`), Discord (`content`), or
+//! a generic JSON body with the event name and fields. Delivery failures are
+//! reported in the command output but never fail the command itself.
+
+#[cfg(feature = "network")]
+use crate::btc::http;
+use crate::storage::Storage;
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Storage key for the webhook list
+pub const WEBHOOKS_FILE: &str = "webhooks.json";
+
+/// Event names accepted in `--events`
+pub const EVENT_NAMES: [&str; 3] = ["session_created", "share_submitted", "tx_confirmed"];
+
+/// Payload format of a webhook endpoint
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Slack,
+    Discord,
+    Generic,
+}
+
+impl WebhookKind {
+    /// Parse a kind name from the CLI
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "slack" => Ok(Self::Slack),
+            "discord" => Ok(Self::Discord),
+            "generic" | "http" => Ok(Self::Generic),
+            other => anyhow::bail!(
+                "Unknown webhook kind '{}'. Use slack, discord or generic",
+                other
+            ),
+        }
+    }
+
+    /// Guess the kind from a webhook URL
+    pub fn from_url(url: &str) -> Self {
+        if url.contains("hooks.slack.com") {
+            Self::Slack
+        } else if url.contains("discord.com/api/webhooks")
+            || url.contains("discordapp.com/api/webhooks")
+        {
+            Self::Discord
+        } else {
+            Self::Generic
+        }
+    }
+}
+
+/// A configured webhook endpoint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub url: String,
+    pub kind: WebhookKind,
+    /// Events to fire on; empty means all events
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl Webhook {
+    /// Whether this webhook subscribes to `event`
+    pub fn wants(&self, event: &NotifyEvent) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event.name())
+    }
+}
+
+/// Something signers should hear about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifyEvent {
+    SessionCreated {
+        session_id: String,
+        to_address: String,
+        amount_sats: u64,
+    },
+    ShareSubmitted {
+        session_id: String,
+        party_index: u32,
+    },
+    TxConfirmed {
+        txid: String,
+        block_height: u64,
+        network: String,
+    },
+}
+
+impl NotifyEvent {
+    /// Stable event name used in config and generic payloads
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SessionCreated { .. } => "session_created",
+            Self::ShareSubmitted { .. } => "share_submitted",
+            Self::TxConfirmed { .. } => "tx_confirmed",
+        }
+    }
+
+    /// Human-readable one-line message
+    pub fn message(&self, wallet: &str) -> String {
+        match self {
+            Self::SessionCreated {
+                session_id,
+                to_address,
+                amount_sats,
+            } => format!(
+                "[{}] New signing session {}: send {} sats to {}. Run dkg-nonce to join.",
+                wallet, session_id, amount_sats, to_address
+            ),
+            Self::ShareSubmitted {
+                session_id,
+                party_index,
+            } => format!(
+                "[{}] Party {} submitted a signature share for session {}",
+                wallet, party_index, session_id
+            ),
+            Self::TxConfirmed {
+                txid,
+                block_height,
+                network,
+            } => format!(
+                "[{}] Transaction {} confirmed in block {} ({})",
+                wallet, txid, block_height, network
+            ),
+        }
+    }
+
+    /// Event fields for generic payloads
+    fn data(&self) -> serde_json::Value {
+        match self {
+            Self::SessionCreated {
+                session_id,
+                to_address,
+                amount_sats,
+            } => serde_json::json!({
+                "session_id": session_id,
+                "to_address": to_address,
+                "amount_sats": amount_sats,
+            }),
+            Self::ShareSubmitted {
+                session_id,
+                party_index,
+            } => serde_json::json!({
+                "session_id": session_id,
+                "party_index": party_index,
+            }),
+            Self::TxConfirmed {
+                txid,
+                block_height,
+                network,
+            } => serde_json::json!({
+                "txid": txid,
+                "block_height": block_height,
+                "network": network,
+            }),
+        }
+    }
+}
+
+/// Build the JSON body for a webhook kind
+pub fn build_payload(kind: WebhookKind, wallet: &str, event: &NotifyEvent) -> serde_json::Value {
+    let message = event.message(wallet);
+    match kind {
+        WebhookKind::Slack => serde_json::json!({ "text": message }),
+        WebhookKind::Discord => serde_json::json!({ "content": message }),
+        WebhookKind::Generic => serde_json::json!({
+            "event": event.name(),
+            "wallet": wallet,
+            "message": message,
+            "data": event.data(),
+        }),
+    }
+}
+
+// ============================================================================
+// Delivery
+// ============================================================================
+
+/// POST a JSON payload to a webhook URL
+#[cfg(feature = "network")]
+pub fn post_webhook(url: &str, payload: &serde_json::Value) -> Result<()> {
+    let response = http::client()
+        .post(url)
+        .json(payload)
+        .send()
+        .context("Failed to send webhook")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Webhook returned {}", response.status());
+    }
+    Ok(())
+}
+
+/// Best-effort desktop notification (notify-send on Linux, osascript on macOS)
+pub fn desktop_notify(title: &str, body: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {:?} with title {:?}",
+            body.replace('"', "'"),
+            title.replace('"', "'")
+        );
+        std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .status()
+    } else {
+        std::process::Command::new("notify-send")
+            .args([title, body])
+            .status()
+    }
+    .context("Failed to launch desktop notifier")?;
+
+    if !status.success() {
+        anyhow::bail!("Desktop notifier exited with {}", status);
+    }
+    Ok(())
+}
+
+/// Send `event` to every subscribed webhook, returning one report line per delivery
+#[cfg(feature = "network")]
+pub fn dispatch(webhooks: &[Webhook], wallet: &str, event: &NotifyEvent) -> Vec<String> {
+    webhooks
+        .iter()
+        .filter(|hook| hook.wants(event))
+        .map(|hook| {
+            let payload = build_payload(hook.kind, wallet, event);
+            match post_webhook(&hook.url, &payload) {
+                Ok(()) => format!("🔔 Notified {} webhook ({})", event.name(), hook.url),
+                Err(e) => format!("⚠️  Webhook {} failed: {}", hook.url, e),
+            }
+        })
+        .collect()
+}
+
+/// Fire `event` to the wallet's configured webhooks, appending report lines to `out`
+#[cfg(feature = "network")]
+pub fn notify_wallet(storage: &dyn Storage, wallet: &str, event: &NotifyEvent, out: &mut String) {
+    let webhooks = match load_webhooks(storage) {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            out.push_str(&format!("⚠️  Could not load {}: {}\n", WEBHOOKS_FILE, e));
+            return;
+        }
+    };
+    for line in dispatch(&webhooks, wallet, event) {
+        out.push_str(&line);
+        out.push('\n');
+    }
+}
+
+// ============================================================================
+// Config
+// ============================================================================
+
+/// Load configured webhooks (empty if none)
+pub fn load_webhooks(storage: &dyn Storage) -> Result<Vec<Webhook>> {
+    if !storage.exists(WEBHOOKS_FILE) {
+        return Ok(Vec::new());
+    }
+    let json = String::from_utf8(storage.read(WEBHOOKS_FILE)?)?;
+    serde_json::from_str(&json).context("Failed to parse webhooks.json")
+}
+
+/// Save the webhook list
+pub fn save_webhooks(storage: &dyn Storage, webhooks: &[Webhook]) -> Result<()> {
+    storage.write(
+        WEBHOOKS_FILE,
+        serde_json::to_string_pretty(webhooks)?.as_bytes(),
+    )
+}
+
+/// Core function for adding a webhook
+pub fn add_webhook_core(
+    url: &str,
+    kind: Option<&str>,
+    events: &[String],
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        anyhow::bail!("Webhook URL must start with http:// or https://");
+    }
+    for event in events {
+        if !EVENT_NAMES.contains(&event.as_str()) {
+            anyhow::bail!(
+                "Unknown event '{}'. Valid events: {}",
+                event,
+                EVENT_NAMES.join(", ")
+            );
+        }
+    }
+    let kind = match kind {
+        Some(name) => WebhookKind::from_name(name)?,
+        None => WebhookKind::from_url(url),
+    };
+
+    let mut webhooks = load_webhooks(storage)?;
+    if webhooks.iter().any(|hook| hook.url == url) {
+        anyhow::bail!("Webhook {} is already configured", url);
+    }
+    let webhook = Webhook {
+        url: url.to_string(),
+        kind,
+        events: events.to_vec(),
+    };
+    webhooks.push(webhook.clone());
+    save_webhooks(storage, &webhooks)?;
+
+    let mut out = String::new();
+    out.push_str("Webhook Added\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("URL:    {}\n", webhook.url));
+    out.push_str(&format!("Kind:   {:?}\n", webhook.kind));
+    out.push_str(&format!(
+        "Events: {}\n",
+        if webhook.events.is_empty() {
+            "all".to_string()
+        } else {
+            webhook.events.join(", ")
+        }
+    ));
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&webhook)?,
+    })
+}
+
+/// Core function for removing a webhook by URL
+pub fn remove_webhook_core(url: &str, storage: &dyn Storage) -> Result<CommandResult> {
+    let mut webhooks = load_webhooks(storage)?;
+    let before = webhooks.len();
+    webhooks.retain(|hook| hook.url != url);
+    if webhooks.len() == before {
+        anyhow::bail!("No webhook configured for {}", url);
+    }
+    save_webhooks(storage, &webhooks)?;
+
+    Ok(CommandResult {
+        output: format!("✓ Removed webhook {}\n", url),
+        result: serde_json::to_string_pretty(&webhooks)?,
+    })
+}
+
+/// Core function for listing webhooks
+pub fn list_webhooks_core(storage: &dyn Storage) -> Result<CommandResult> {
+    let webhooks = load_webhooks(storage)?;
+    let mut out = String::new();
+    out.push_str("Configured Webhooks\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    if webhooks.is_empty() {
+        out.push_str("No webhooks configured. Add one with dkg-webhook-add.\n");
+    }
+    for hook in &webhooks {
+        let events = if hook.events.is_empty() {
+            "all".to_string()
+        } else {
+            hook.events.join(", ")
+        };
+        out.push_str(&format!("{:?}  {}  [{}]\n", hook.kind, hook.url, events));
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&webhooks)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_webhook_config_and_payloads() {
+        let storage = MemoryStorage::new();
+        assert!(load_webhooks(&storage).unwrap().is_empty());
+
+        add_webhook_core(
+            "https://hooks.slack.com/services/T/B/X",
+            None,
+            &[],
+            &storage,
+        )
+        .unwrap();
+        add_webhook_core(
+            "https://example.com/hook",
+            Some("generic"),
+            &["tx_confirmed".to_string()],
+            &storage,
+        )
+        .unwrap();
+        assert!(add_webhook_core("https://example.com/hook", None, &[], &storage).is_err());
+        assert!(add_webhook_core("https://x.com", None, &["bogus".to_string()], &storage).is_err());
+
+        let hooks = load_webhooks(&storage).unwrap();
+        assert_eq!(hooks.len(), 2);
+        assert_eq!(hooks[0].kind, WebhookKind::Slack);
+
+        let share = NotifyEvent::ShareSubmitted {
+            session_id: "abc".to_string(),
+            party_index: 2,
+        };
+        assert!(hooks[0].wants(&share));
+        assert!(!hooks[1].wants(&share));
+
+        let slack = build_payload(WebhookKind::Slack, "treasury", &share);
+        assert!(slack["text"].as_str().unwrap().contains("Party 2"));
+        let generic = build_payload(WebhookKind::Generic, "treasury", &share);
+        assert_eq!(generic["event"], "share_submitted");
+        assert_eq!(generic["data"]["party_index"], 2);
+
+        remove_webhook_core("https://example.com/hook", &storage).unwrap();
+        assert_eq!(load_webhooks(&storage).unwrap().len(), 1);
+    }
+}
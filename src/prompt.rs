@@ -0,0 +1,64 @@
+//! Interactive prompts for arguments left off the command line
+
+use anyhow::{bail, Context, Result};
+use dialoguer::Input;
+use frostdao::error::Error;
+use frostdao::protocol::compact;
+use frostdao::protocol::payload::read_payload_files;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+
+/// The payload from `--data` or `--data-file`, or asked for when both were
+/// left off in a terminal
+///
+/// Round payloads can exceed the shell's argument limit, so either option
+/// may be `-` to read stdin instead. Long payloads are also easier to paste
+/// into a prompt than to quote. `cbor:` compact payloads are expanded to
+/// JSON here, so every command accepts them. `what` names the payload, e.g.
+/// "the Round 1 outputs".
+pub fn data(data: Option<String>, data_file: Option<PathBuf>, what: &str) -> Result<String> {
+    compact::expand(&raw_data(data, data_file, what)?)
+}
+
+/// Like [`data`], but `None` rather than a prompt when both were left off,
+/// for commands that can fetch the payload from relays instead
+pub fn optional_data(data: Option<String>, data_file: Option<PathBuf>) -> Result<Option<String>> {
+    if data.is_none() && data_file.is_none() {
+        return Ok(None);
+    }
+    self::data(data, data_file, "").map(Some)
+}
+
+fn raw_data(data: Option<String>, data_file: Option<PathBuf>, what: &str) -> Result<String> {
+    if let Some(path) = data_file {
+        if path == Path::new("-") {
+            return read_stdin();
+        }
+        return read_payload_files(&path);
+    }
+    match data {
+        Some(data) if data == "-" => read_stdin(),
+        Some(data) => Ok(data),
+        None if std::io::stdin().is_terminal() => {
+            let data: String = Input::new()
+                .with_prompt(format!("Paste {} (JSON, ur: frames or @file)", what))
+                .interact_text()?;
+            Ok(data)
+        }
+        None => bail!(Error::User(format!(
+            "--data or --data-file is required: {}",
+            what
+        ))),
+    }
+}
+
+fn read_stdin() -> Result<String> {
+    let mut data = String::new();
+    std::io::stdin()
+        .read_to_string(&mut data)
+        .context("Failed to read --data from stdin")?;
+    if data.trim().is_empty() {
+        bail!(Error::User("No --data on stdin".into()));
+    }
+    Ok(data)
+}
@@ -0,0 +1,92 @@
+//! Wallet Audit Log
+//!
+//! `audit_log.json` is an append-only list of security-relevant changes to a
+//! wallet, such as a reshare moving from 3-of-5 to 2-of-5. A reshare carries
+//! the source wallet's log over to the new wallet, so the log follows the
+//! group key rather than one folder.
+
+use crate::protocol::health::now;
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const AUDIT_LOG_FILE: &str = "audit_log.json";
+
+/// One recorded change
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// Unix time in seconds
+    pub timestamp: u64,
+    /// What happened, e.g. `reshare`
+    pub event: String,
+    /// Event-specific fields
+    pub details: serde_json::Value,
+}
+
+/// Entries in `storage`, oldest first; empty if there is no log yet
+pub fn load(storage: &dyn Storage) -> Result<Vec<AuditEntry>> {
+    if !storage.exists(AUDIT_LOG_FILE) {
+        return Ok(Vec::new());
+    }
+    serde_json::from_slice(&storage.read(AUDIT_LOG_FILE)?)
+        .with_context(|| format!("{} is damaged", AUDIT_LOG_FILE))
+}
+
+/// Append an entry stamped with the current time
+pub fn append(
+    storage: &dyn Storage,
+    event: &str,
+    details: serde_json::Value,
+) -> Result<AuditEntry> {
+    let mut entries = load(storage)?;
+    let entry = AuditEntry {
+        timestamp: now(),
+        event: event.to_string(),
+        details,
+    };
+    entries.push(entry.clone());
+    storage.write(
+        AUDIT_LOG_FILE,
+        serde_json::to_string_pretty(&entries)?.as_bytes(),
+    )?;
+    Ok(entry)
+}
+
+/// Copy `source`'s log to `target`, replacing whatever `target` had
+pub fn carry(source: &dyn Storage, target: &dyn Storage) -> Result<()> {
+    let entries = load(source)?;
+    if entries.is_empty() {
+        return Ok(());
+    }
+    target.write(
+        AUDIT_LOG_FILE,
+        serde_json::to_string_pretty(&entries)?.as_bytes(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_append_and_carry() {
+        let source = MemoryStorage::new();
+        assert!(load(&source).unwrap().is_empty());
+
+        append(&source, "reshare", serde_json::json!({"new_threshold": 2})).unwrap();
+        append(&source, "reshare", serde_json::json!({"new_threshold": 3})).unwrap();
+        let entries = load(&source).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].details["new_threshold"], 3);
+
+        let target = MemoryStorage::new();
+        carry(&source, &target).unwrap();
+        append(&target, "reshare", serde_json::json!({})).unwrap();
+        assert_eq!(load(&target).unwrap().len(), 3);
+        assert_eq!(load(&source).unwrap().len(), 2);
+
+        source.write(AUDIT_LOG_FILE, b"not json").unwrap();
+        assert!(load(&source).is_err());
+    }
+}
@@ -0,0 +1,922 @@
+//! Backups of a Single Share
+//!
+//! `dkg-generate-mnemonic` writes a party's share as one 24-word phrase: a
+//! single paper that gives the share to whoever finds it and loses it when
+//! it is lost. `dkg-backup-split` instead makes the share the constant term
+//! of a random polynomial of degree k-1 and hands out pieces f(1)..f(m), each
+//! written as 24 words. Any k pieces give the share back with Lagrange
+//! interpolation at 0 (`dkg-backup-restore`); fewer say nothing about it.
+//!
+//! A restore only writes a share whose image share*G is the one the group key
+//! (`shared_key.bin`) commits this party to, so too few or mixed-up pieces
+//! are caught before anything is written. Every piece is also printed with a
+//! check code, a hash of the share's public image and index, for the holder
+//! to tell papers apart; it and the k-of-m are kept in `share_backup.json`
+//! and only feed the hints of a failed restore.
+//!
+//! With `--format slip39` the pieces are SLIP-39 shares instead (33 words
+//! each, see `crypto::slip39`), the format Trezor and steel-backup kits
+//! read, optionally under a passphrase. Restore tells the two formats apart
+//! by their words.
+//!
+//! The single phrase itself can be passphrase protected: the words then hold
+//! the share XOR a mask derived from the passphrase, salted with the group
+//! key and party index (see `crypto::mnemonic`). A wrong passphrase decodes
+//! to a share the group key doesn't match, and `mnemonic_backup.json` notes
+//! whether one was used so the error can say so.
+
+use crate::crypto::helpers::{
+    construct_paired_secret_share, lagrange_coefficients_at_zero, share_to_nonzero, tagged_hash,
+};
+use crate::crypto::mnemonic;
+use crate::crypto::secret::serialize_secret;
+use crate::crypto::slip39;
+use crate::protocol::audit;
+use crate::protocol::health;
+use crate::protocol::keygen::{get_state_dir, require_secp256k1, HtssMetadata};
+use crate::protocol::payload;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use schnorr_fun::frost::{PairedSecretShare, SharedKey};
+use schnorr_fun::fun::poly;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// k-of-m and check code of the last split, next to the share
+pub const SHARE_BACKUP_FILE: &str = "share_backup.json";
+
+/// Check code of the last mnemonic and whether it needs a passphrase
+pub const MNEMONIC_BACKUP_FILE: &str = "mnemonic_backup.json";
+
+/// Environment variable holding the mnemonic passphrase
+pub const MNEMONIC_PASSPHRASE_ENV: &str = "FROSTDAO_MNEMONIC_PASSPHRASE";
+
+/// At most this many pieces, so each x fits the paper label
+const MAX_PIECES: u32 = 16;
+
+/// How `dkg-backup-split` writes its pieces
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFormat {
+    /// `<x>: <24 BIP-39 words>`, Shamir over the secp256k1 scalar field
+    #[default]
+    Bip39,
+    /// SLIP-39 share mnemonics, Shamir over GF(256)
+    Slip39,
+}
+
+impl std::str::FromStr for BackupFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "bip39" => Ok(BackupFormat::Bip39),
+            "slip39" => Ok(BackupFormat::Slip39),
+            other => bail!(
+                "Unknown backup format '{}' (expected bip39 or slip39)",
+                other
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for BackupFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BackupFormat::Bip39 => "bip39",
+            BackupFormat::Slip39 => "slip39",
+        })
+    }
+}
+
+/// What `dkg-backup-split` records about a split (nothing secret)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ShareBackup {
+    pub threshold: u32,
+    pub pieces: u32,
+    #[serde(default)]
+    pub format: BackupFormat,
+    /// Whether the pieces need a passphrase (slip39 only)
+    #[serde(default)]
+    pub passphrase: bool,
+    /// See [`check_code`]
+    pub check: String,
+    pub created_at: u64,
+}
+
+/// What `dkg-generate-mnemonic` records about a mnemonic (nothing secret)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MnemonicBackup {
+    /// Whether the words need a passphrase
+    pub passphrase: bool,
+    /// See [`check_code`]
+    pub check: String,
+    pub created_at: u64,
+}
+
+impl MnemonicBackup {
+    pub fn load(storage: &dyn Storage) -> Result<Option<Self>> {
+        if !storage.exists(MNEMONIC_BACKUP_FILE) {
+            return Ok(None);
+        }
+        serde_json::from_slice(&storage.read(MNEMONIC_BACKUP_FILE)?)
+            .map(Some)
+            .with_context(|| format!("{} is damaged", MNEMONIC_BACKUP_FILE))
+    }
+}
+
+impl ShareBackup {
+    pub fn load(storage: &dyn Storage) -> Result<Option<Self>> {
+        if !storage.exists(SHARE_BACKUP_FILE) {
+            return Ok(None);
+        }
+        serde_json::from_slice(&storage.read(SHARE_BACKUP_FILE)?)
+            .map(Some)
+            .with_context(|| format!("{} is damaged", SHARE_BACKUP_FILE))
+    }
+}
+
+/// 8 hex characters identifying the share at `index` without revealing it
+pub fn check_code(index: u32, share: &Scalar<Secret, NonZero>) -> String {
+    let image = g!(share * G).normalize();
+    let mut data = index.to_be_bytes().to_vec();
+    data.extend_from_slice(&image.to_bytes());
+    hex::encode(&tagged_hash("frostdao/share-backup", &data)[..4])
+}
+
+/// Deal `share` into `pieces` pieces, any `threshold` of which restore it
+///
+/// Returns (x, f(x)) for x = 1..=pieces.
+pub fn split_share(
+    share: &Scalar<Secret, NonZero>,
+    threshold: u32,
+    pieces: u32,
+) -> Result<Vec<(u32, Scalar<Secret, Zero>)>> {
+    if threshold < 2 || threshold > pieces || pieces > MAX_PIECES {
+        bail!(crate::error::Error::User(format!(
+            "Need 2 <= threshold <= pieces <= {} (got {}-of-{})",
+            MAX_PIECES, threshold, pieces
+        )));
+    }
+    let secret_poly = poly::scalar::generate_shamir_sharing_poly(
+        share.mark_zero(),
+        threshold as usize,
+        &mut rand::thread_rng(),
+    );
+    Ok((1..=pieces)
+        .map(|x| {
+            let x_scalar = Scalar::<Public, Zero>::from(x)
+                .non_zero()
+                .expect("piece numbers start at 1");
+            (x, poly::scalar::eval(&secret_poly, x_scalar))
+        })
+        .collect())
+}
+
+/// Interpolate pieces (x, f(x)) back to f(0)
+pub fn combine_pieces(pieces: &[(u32, Scalar<Secret, Zero>)]) -> Result<Scalar<Secret, Zero>> {
+    let xs: Vec<u32> = pieces.iter().map(|(x, _)| *x).collect();
+    let coefficients = lagrange_coefficients_at_zero(&xs)?;
+    Ok(pieces
+        .iter()
+        .zip(coefficients)
+        .fold(Scalar::zero(), |sum, ((_, value), lambda)| {
+            s!(sum + lambda * value)
+        }))
+}
+
+/// Parse a piece as written on paper: `<x>: <24 words>`
+pub fn parse_piece(text: &str) -> Result<(u32, Scalar<Secret, Zero>)> {
+    let (x, words) = text
+        .split_once(':')
+        .context("Write each piece as '<number>: <24 words>'")?;
+    let x: u32 = x
+        .trim()
+        .parse()
+        .with_context(|| format!("'{}' is not a piece number", x.trim()))?;
+    if x == 0 || x > MAX_PIECES {
+        bail!("Piece number {} is out of range (1-{})", x, MAX_PIECES);
+    }
+    let phrase = mnemonic::parse_mnemonic(words.trim())
+        .with_context(|| format!("Piece {} is not a valid 24-word phrase", x))?;
+    let bytes = mnemonic::mnemonic_to_share(&phrase)?;
+    let value = Scalar::from_bytes(bytes)
+        .with_context(|| format!("Piece {} is not a valid share value", x))?;
+    Ok((x, value))
+}
+
+fn load_share(storage: &dyn Storage) -> Result<(u32, Scalar<Secret, NonZero>)> {
+    require_secp256k1(storage)?;
+    let paired_share: PairedSecretShare<EvenY> =
+        bincode::deserialize(&storage.read_secret("paired_secret_share.bin")?)?;
+    let htss: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    let share = share_to_nonzero(paired_share.secret_share().share)?;
+    Ok((htss.my_index, share))
+}
+
+/// Core function for `dkg-backup-split`
+///
+/// The pieces are in `output` only, for the holder to write down; `result`
+/// is the [`ShareBackup`] record. Only slip39 pieces take a `passphrase`.
+pub fn split_core(
+    threshold: u32,
+    pieces: u32,
+    format: BackupFormat,
+    passphrase: &str,
+    storage: &dyn Storage,
+    now: u64,
+) -> Result<CommandResult> {
+    let (index, share) = load_share(storage)?;
+    let written: Vec<(u32, String)> = match format {
+        BackupFormat::Bip39 => {
+            if !passphrase.is_empty() {
+                bail!(crate::error::Error::User(
+                    "bip39 pieces take no passphrase; use --format slip39".to_string()
+                ));
+            }
+            split_share(&share, threshold, pieces)?
+                .into_iter()
+                .map(|(x, value)| {
+                    let phrase = mnemonic::share_to_mnemonic(&value.to_bytes())?;
+                    Ok((x, mnemonic::format_mnemonic_grid(&phrase)))
+                })
+                .collect::<Result<_>>()?
+        }
+        BackupFormat::Slip39 => {
+            if threshold < 1 || threshold > pieces || pieces > MAX_PIECES {
+                bail!(crate::error::Error::User(format!(
+                    "Need 1 <= threshold <= pieces <= {} (got {}-of-{})",
+                    MAX_PIECES, threshold, pieces
+                )));
+            }
+            let mut secret = share.to_bytes();
+            let shares = slip39::split(
+                &secret,
+                threshold as u8,
+                pieces as u8,
+                passphrase.as_bytes(),
+            );
+            secret.zeroize();
+            (1..=pieces)
+                .zip(shares?)
+                .map(|(x, words)| (x, slip39::format_grid(&words)))
+                .collect()
+        }
+    };
+    let record = ShareBackup {
+        threshold,
+        pieces,
+        format,
+        passphrase: !passphrase.is_empty(),
+        check: check_code(index, &share),
+        created_at: now,
+    };
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "Shamir Backup of Party {}'s Share ({}-of-{})\n\n",
+        index, threshold, pieces
+    ));
+    out.push_str(&format!(
+        "WARNING: Any {} pieces together ARE your share. Keep them in different places.\n",
+        threshold
+    ));
+    out.push_str("         Recovery of the group still requires threshold shares.\n");
+    for (x, grid) in &written {
+        out.push_str(
+            "\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
+        );
+        out.push_str(&format!(
+            "Piece {} of {} · any {} restore · party {} · check {}\n\n",
+            x, pieces, threshold, index, record.check
+        ));
+        out.push_str(&format!("{}\n", grid));
+    }
+    out.push_str(
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n",
+    );
+    out.push_str("Write each piece on its own paper, with its label line.\n");
+    if record.passphrase {
+        out.push_str("Keep the passphrase apart from the pieces: nothing can recover it.\n");
+    }
+    out.push_str("Restore with: frostdao dkg-backup-restore --name <wallet> \\\n");
+    match format {
+        BackupFormat::Bip39 => {
+            out.push_str("    --piece '1: <24 words>' --piece '2: <24 words>' ...\n")
+        }
+        BackupFormat::Slip39 => out.push_str(&format!(
+            "    --piece '<33 words>' --piece '<33 words>' ...{}\n",
+            if record.passphrase {
+                " --passphrase"
+            } else {
+                ""
+            }
+        )),
+    }
+
+    storage.write(
+        SHARE_BACKUP_FILE,
+        serde_json::to_string_pretty(&record)?.as_bytes(),
+    )?;
+    audit::append(
+        storage,
+        "backup_split",
+        serde_json::json!({
+            "threshold": threshold,
+            "pieces": pieces,
+            "format": format,
+            "check": record.check,
+        }),
+    )?;
+    health::record_backup(storage)?;
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&record)?,
+    })
+}
+
+/// The folder a share is restored into, and what its share must look like
+struct RestoreTarget {
+    index: u32,
+    /// share*G the group key commits this party's share to
+    image: Point<Normal, Public, Zero>,
+    shared_key: SharedKey<EvenY>,
+}
+
+impl RestoreTarget {
+    fn open(storage: &dyn Storage, force: bool) -> Result<Self> {
+        require_secp256k1(storage)?;
+        if storage.exists("paired_secret_share.bin") && !force {
+            bail!("This folder already holds a share. Use --force to overwrite.");
+        }
+        let htss: HtssMetadata = serde_json::from_slice(
+            &storage
+                .read("htss_metadata.json")
+                .context("No htss_metadata.json here; restore into the party's wallet folder")?,
+        )?;
+        let shared_key: SharedKey<EvenY> = bincode::deserialize(
+            &storage
+                .read("shared_key.bin")
+                .context("No shared_key.bin here; restore into the party's wallet folder")?,
+        )?;
+        let image = shared_key
+            .share_image(payload::share_index(htss.my_index)?)
+            .image;
+        Ok(Self {
+            index: htss.my_index,
+            image,
+            shared_key,
+        })
+    }
+
+    /// Whether `share` is this party's share of the group key
+    ///
+    /// Compares share*G with the image the key polynomial commits to, so a
+    /// restore can't be talked into a wrong share by a check code.
+    fn holds(&self, share: &Scalar<Secret, NonZero>) -> bool {
+        g!(share * G).normalize().mark_zero() == self.image
+    }
+
+    fn install(&self, storage: &dyn Storage, share: Scalar<Secret, NonZero>) -> Result<()> {
+        let paired =
+            construct_paired_secret_share(self.index, share, &self.shared_key.public_key())?;
+        storage.write("paired_secret_share.bin", &serialize_secret(&paired)?)
+    }
+}
+
+/// Check code written on the paper: `check` if given, else the saved one
+///
+/// Only a hint for the holder: the restored share is verified against the
+/// group key, not against this code.
+fn paper_check(check: Option<&str>, saved: Option<&str>) -> Option<String> {
+    check
+        .map(|check| check.trim().to_lowercase())
+        .or(saved.map(str::to_string))
+}
+
+/// Output lines for a verified share, noting a paper code that disagrees
+fn restored_lines(index: u32, from: &str, check: &str, paper: Option<&str>) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("✓ Party {}'s share restored\n", index));
+    out.push_str(&format!(
+        "   From {}, matches the group key (check {})\n",
+        from, check
+    ));
+    if let Some(paper) = paper.filter(|paper| *paper != check) {
+        out.push_str(&format!(
+            "⚠️  The check code given was {}; the paper copy of it is probably mistyped\n",
+            paper
+        ));
+    }
+    out.push_str("📝 Recorded in the audit log\n");
+    out
+}
+
+/// Core function for `dkg-backup-restore`
+///
+/// Rebuilds `paired_secret_share.bin` from pieces in a folder that still has
+/// the public files (`shared_key.bin`, `htss_metadata.json`). The result must
+/// be this party's share of the group key; `check` (or the code in
+/// `share_backup.json`) only helps explain a failure. SLIP-39 pieces are
+/// recognised by their words; `passphrase` only applies to them.
+pub fn restore_core(
+    pieces: &[String],
+    passphrase: &str,
+    check: Option<&str>,
+    force: bool,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let target = RestoreTarget::open(storage, force)?;
+    let record = ShareBackup::load(storage)?;
+    let paper = paper_check(check, record.as_ref().map(|record| record.check.as_str()));
+
+    if !pieces.is_empty() && pieces.iter().all(|text| slip39::is_slip39(text)) {
+        return restore_slip39(pieces, passphrase, &target, paper, record, storage);
+    }
+    if !passphrase.is_empty() {
+        bail!("Only SLIP-39 pieces take a passphrase");
+    }
+
+    let mut parsed: Vec<(u32, Scalar<Secret, Zero>)> = Vec::new();
+    for text in pieces {
+        let piece = parse_piece(text)?;
+        if parsed.iter().any(|(x, _)| *x == piece.0) {
+            bail!("Piece {} was given twice", piece.0);
+        }
+        parsed.push(piece);
+    }
+    if parsed.len() < 2 {
+        bail!("A share backup needs at least 2 pieces to restore");
+    }
+
+    let share = share_to_nonzero(combine_pieces(&parsed)?)
+        .ok()
+        .filter(|share| target.holds(share));
+    let Some(share) = share else {
+        let needed = record
+            .map(|record| format!(" (this backup needs {})", record.threshold))
+            .unwrap_or_default();
+        bail!(
+            "Pieces don't restore party {}'s share: too few pieces{}, pieces from different \
+             splits or another party, or a typo",
+            target.index,
+            needed
+        );
+    };
+
+    target.install(storage, share)?;
+    let check = check_code(target.index, &share);
+    let xs: Vec<u32> = parsed.iter().map(|(x, _)| *x).collect();
+    audit::append(
+        storage,
+        "backup_restore",
+        serde_json::json!({ "pieces": xs, "check": check }),
+    )?;
+
+    Ok(CommandResult {
+        output: restored_lines(
+            target.index,
+            &format!("pieces {:?}", xs),
+            &check,
+            paper.as_deref(),
+        ),
+        result: check,
+    })
+}
+
+fn restore_slip39(
+    pieces: &[String],
+    passphrase: &str,
+    target: &RestoreTarget,
+    paper: Option<String>,
+    record: Option<ShareBackup>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let texts: Vec<&str> = pieces.iter().map(String::as_str).collect();
+    let mut bytes = slip39::combine(&texts, passphrase.as_bytes())?;
+    let share = <[u8; 32]>::try_from(bytes.as_slice())
+        .ok()
+        .and_then(Scalar::<Secret, Zero>::from_bytes)
+        .and_then(|share| share.non_zero())
+        .filter(|share| target.holds(share));
+    bytes.zeroize();
+    let Some(share) = share else {
+        let hint = match (
+            passphrase.is_empty(),
+            record.map(|record| record.passphrase),
+        ) {
+            (true, Some(true)) => "this backup was made with a passphrase",
+            (false, Some(false)) => "this backup was made without a passphrase",
+            _ => "wrong passphrase, or pieces from another party's backup",
+        };
+        bail!(
+            "SLIP-39 pieces don't restore party {}'s share ({})",
+            target.index,
+            hint
+        );
+    };
+
+    target.install(storage, share)?;
+    let check = check_code(target.index, &share);
+    audit::append(
+        storage,
+        "backup_restore",
+        serde_json::json!({
+            "format": BackupFormat::Slip39,
+            "pieces": pieces.len(),
+            "check": check,
+        }),
+    )?;
+
+    Ok(CommandResult {
+        output: restored_lines(
+            target.index,
+            &format!("{} SLIP-39 pieces", pieces.len()),
+            &check,
+            paper.as_deref(),
+        ),
+        result: check,
+    })
+}
+
+/// Core function for `dkg-generate-mnemonic`
+///
+/// With a non-empty `passphrase` the words only give the share back together
+/// with it. The check code is saved to `mnemonic_backup.json` and shown with
+/// the words, so a restore can tell a wrong passphrase from the right one.
+pub fn mnemonic_core(passphrase: &str, storage: &dyn Storage, now: u64) -> Result<CommandResult> {
+    let (index, share) = load_share(storage)?;
+    let shared_key: SharedKey<EvenY> = bincode::deserialize(&storage.read("shared_key.bin")?)?;
+    let salt = mnemonic::share_salt(&shared_key.public_key().to_xonly_bytes(), index);
+    let phrase = mnemonic::share_to_mnemonic_with_passphrase(&share.to_bytes(), passphrase, &salt)?;
+    let record = MnemonicBackup {
+        passphrase: !passphrase.is_empty(),
+        check: check_code(index, &share),
+        created_at: now,
+    };
+
+    let mut out = String::new();
+    out.push_str(
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
+    );
+    out.push_str("WARNING: This mnemonic backs up YOUR SECRET SHARE only.\n");
+    out.push_str("         Recovery still requires threshold shares from other parties.\n\n");
+    out.push_str(&format!("{}\n\n", mnemonic::format_mnemonic_grid(&phrase)));
+    out.push_str(&format!("Party {} · check {}", index, record.check));
+    if record.passphrase {
+        out.push_str(" · passphrase protected");
+    }
+    out.push('\n');
+    out.push_str(
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
+    );
+    out.push_str("\nWrite down these 24 words and the check code, and store them securely!\n");
+    if record.passphrase {
+        out.push_str("Keep the passphrase apart from the words: without it they are useless,\n");
+        out.push_str("and nothing can recover a forgotten passphrase.\n");
+    } else {
+        out.push_str("Never share them with anyone.\n");
+    }
+
+    storage.write(
+        MNEMONIC_BACKUP_FILE,
+        serde_json::to_string_pretty(&record)?.as_bytes(),
+    )?;
+    health::record_backup(storage)?;
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&record)?,
+    })
+}
+
+/// Core function for `dkg-restore-mnemonic`
+///
+/// Like [`restore_core`], for words from `dkg-generate-mnemonic`. A wrong
+/// `passphrase` decodes to a different value, which doesn't match the group
+/// key.
+pub fn restore_mnemonic_core(
+    words: &str,
+    passphrase: &str,
+    check: Option<&str>,
+    force: bool,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let target = RestoreTarget::open(storage, force)?;
+    let record = MnemonicBackup::load(storage)?;
+    let paper = paper_check(check, record.as_ref().map(|record| record.check.as_str()));
+
+    let phrase = mnemonic::parse_mnemonic(words.trim())?;
+    let salt = mnemonic::share_salt(
+        &target.shared_key.public_key().to_xonly_bytes(),
+        target.index,
+    );
+    let mut bytes = mnemonic::mnemonic_to_share_with_passphrase(&phrase, passphrase, &salt)?;
+    let share = Scalar::<Secret, Zero>::from_bytes(bytes)
+        .and_then(|share| share.non_zero())
+        .filter(|share| target.holds(share));
+    bytes.zeroize();
+    let Some(share) = share else {
+        let hint = match (
+            passphrase.is_empty(),
+            record.map(|record| record.passphrase),
+        ) {
+            (true, Some(true)) => "this backup was made with a passphrase",
+            (false, Some(false)) => "this backup was made without a passphrase",
+            _ => "wrong passphrase, or words from another backup",
+        };
+        bail!(
+            "Mnemonic doesn't restore party {}'s share ({})",
+            target.index,
+            hint
+        );
+    };
+
+    target.install(storage, share)?;
+    let check = check_code(target.index, &share);
+    audit::append(
+        storage,
+        "mnemonic_restore",
+        serde_json::json!({ "check": check, "passphrase": !passphrase.is_empty() }),
+    )?;
+
+    Ok(CommandResult {
+        output: restored_lines(target.index, "the mnemonic", &check, paper.as_deref()),
+        result: check,
+    })
+}
+
+fn wallet_storage(name: &str) -> Result<FileStorage> {
+    let state_dir = get_state_dir(name);
+    if !std::path::Path::new(&state_dir).exists() {
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}.",
+            name, state_dir
+        ))
+        .into());
+    }
+    FileStorage::new(&state_dir)
+}
+
+/// CLI wrapper for split_core
+pub fn backup_split(
+    name: &str,
+    threshold: u32,
+    pieces: u32,
+    format: BackupFormat,
+    with_passphrase: bool,
+) -> Result<()> {
+    let storage = wallet_storage(name)?;
+    let mut passphrase = passphrase(with_passphrase)?;
+    let cmd_result = split_core(
+        threshold,
+        pieces,
+        format,
+        &passphrase,
+        &storage,
+        health::now(),
+    );
+    passphrase.zeroize();
+    println!("{}", cmd_result?.output);
+    println!("💾 Check code and k-of-m saved to {}", SHARE_BACKUP_FILE);
+    Ok(())
+}
+
+/// Passphrase from [`MNEMONIC_PASSPHRASE_ENV`], required when `wanted`
+fn passphrase(wanted: bool) -> Result<String> {
+    if !wanted {
+        return Ok(String::new());
+    }
+    let passphrase = std::env::var(MNEMONIC_PASSPHRASE_ENV)
+        .with_context(|| format!("Set {} to the passphrase", MNEMONIC_PASSPHRASE_ENV))?;
+    if passphrase.is_empty() {
+        bail!("{} is empty", MNEMONIC_PASSPHRASE_ENV);
+    }
+    Ok(passphrase)
+}
+
+/// CLI wrapper for mnemonic_core
+pub fn generate_mnemonic(name: &str, with_passphrase: bool) -> Result<()> {
+    let storage = wallet_storage(name)?;
+    let mut passphrase = passphrase(with_passphrase)?;
+    let cmd_result = mnemonic_core(&passphrase, &storage, health::now());
+    passphrase.zeroize();
+    println!("BIP-39 Mnemonic Backup for Wallet '{}'\n", name);
+    println!("{}", cmd_result?.output);
+    Ok(())
+}
+
+/// CLI wrapper for restore_mnemonic_core
+pub fn restore_mnemonic(
+    name: &str,
+    words: &str,
+    with_passphrase: bool,
+    check: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let storage = wallet_storage(name)?;
+    let mut passphrase = passphrase(with_passphrase)?;
+    let cmd_result = restore_mnemonic_core(words, &passphrase, check, force, &storage);
+    passphrase.zeroize();
+    println!("{}", cmd_result?.output);
+    Ok(())
+}
+
+/// CLI wrapper for restore_core
+pub fn backup_restore(
+    name: &str,
+    pieces: &[String],
+    with_passphrase: bool,
+    check: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let storage = wallet_storage(name)?;
+    let mut passphrase = passphrase(with_passphrase)?;
+    let cmd_result = restore_core(pieces, &passphrase, check, force, &storage);
+    passphrase.zeroize();
+    println!("{}", cmd_result?.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::dealer;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_backup_split_and_restore() {
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        let secret = "0000000000000000000000000000000000000000000000000000000000000007";
+        dealer::split_key_core("treasury", secret, 2, None, &root, &parties).unwrap();
+        let party = &storages[1];
+        let original = party.read("paired_secret_share.bin").unwrap();
+
+        let (index, share) = load_share(party).unwrap();
+        let dealt = split_share(&share, 2, 3).unwrap();
+        let record: ShareBackup = serde_json::from_str(
+            &split_core(2, 3, BackupFormat::Bip39, "", party, 100)
+                .unwrap()
+                .result,
+        )
+        .unwrap();
+        assert_eq!(record.check, check_code(index, &share));
+        assert_eq!(ShareBackup::load(party).unwrap(), Some(record.clone()));
+
+        let written = |x: usize| {
+            let (x, value) = &dealt[x - 1];
+            let phrase = mnemonic::share_to_mnemonic(&value.to_bytes()).unwrap();
+            format!("{}: {}", x, phrase)
+        };
+        party.delete("paired_secret_share.bin").unwrap();
+
+        // One piece is not enough; two are caught by the check if mixed up
+        assert!(restore_core(&[written(1)], "", None, false, party).is_err());
+        let other = split_share(&share, 2, 3).unwrap();
+        let stray = format!(
+            "2: {}",
+            mnemonic::share_to_mnemonic(&other[1].1.to_bytes()).unwrap()
+        );
+        assert!(restore_core(&[written(1), stray], "", None, false, party).is_err());
+        assert!(!party.exists("paired_secret_share.bin"));
+
+        restore_core(&[written(3), written(1)], "", None, false, party).unwrap();
+        assert_eq!(party.read("paired_secret_share.bin").unwrap(), original);
+        assert!(restore_core(&[written(1), written(2)], "", None, false, party).is_err());
+
+        // Without share_backup.json the group key still vouches for the
+        // share; a mistyped paper code is only pointed out
+        party.delete(SHARE_BACKUP_FILE).unwrap();
+        restore_core(&[written(1), written(2)], "", None, true, party).unwrap();
+        assert_eq!(party.read("paired_secret_share.bin").unwrap(), original);
+        let output = restore_core(&[written(1), written(2)], "", Some("0badc0de"), true, party)
+            .unwrap()
+            .output;
+        assert!(output.contains("0badc0de"), "{}", output);
+
+        // Another party's pieces don't pass, even with a check code to match
+        let (other_index, other_share) = load_share(&storages[0]).unwrap();
+        let others = split_share(&other_share, 2, 2).unwrap();
+        let other_pieces: Vec<String> = others
+            .iter()
+            .map(|(x, value)| {
+                let phrase = mnemonic::share_to_mnemonic(&value.to_bytes()).unwrap();
+                format!("{}: {}", x, phrase)
+            })
+            .collect();
+        let other_check = check_code(other_index, &other_share);
+        assert!(restore_core(&other_pieces, "", Some(&other_check), true, party).is_err());
+        assert_eq!(party.read("paired_secret_share.bin").unwrap(), original);
+    }
+
+    #[test]
+    fn test_ranked_share_restore() {
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        let secret = "000000000000000000000000000000000000000000000000000000000000000d";
+        dealer::split_key_core("treasury", secret, 2, Some(vec![0, 1, 1]), &root, &parties)
+            .unwrap();
+
+        for party in &storages {
+            let original = party.read("paired_secret_share.bin").unwrap();
+            let (_, share) = load_share(party).unwrap();
+            let pieces: Vec<String> = split_share(&share, 2, 2)
+                .unwrap()
+                .iter()
+                .map(|(x, value)| {
+                    let phrase = mnemonic::share_to_mnemonic(&value.to_bytes()).unwrap();
+                    format!("{}: {}", x, phrase)
+                })
+                .collect();
+            party.delete("paired_secret_share.bin").unwrap();
+            restore_core(&pieces, "", None, false, party).unwrap();
+            assert_eq!(party.read("paired_secret_share.bin").unwrap(), original);
+        }
+    }
+
+    #[test]
+    fn test_mnemonic_passphrase_restore() {
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..2).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        let secret = "0000000000000000000000000000000000000000000000000000000000000009";
+        dealer::split_key_core("treasury", secret, 2, None, &root, &parties).unwrap();
+        let party = &storages[0];
+        let original = party.read("paired_secret_share.bin").unwrap();
+
+        let output = mnemonic_core("correct horse", party, 100).unwrap().output;
+        let record = MnemonicBackup::load(party).unwrap().unwrap();
+        assert!(record.passphrase);
+        let (index, share) = load_share(party).unwrap();
+        let shared_key: SharedKey<EvenY> =
+            bincode::deserialize(&party.read("shared_key.bin").unwrap()).unwrap();
+        let salt = mnemonic::share_salt(&shared_key.public_key().to_xonly_bytes(), index);
+        let words =
+            mnemonic::share_to_mnemonic_with_passphrase(&share.to_bytes(), "correct horse", &salt)
+                .unwrap()
+                .to_string();
+        assert!(output.contains(&format!("check {}", record.check)));
+        assert!(output.contains(words.split(' ').next().unwrap()));
+        party.delete("paired_secret_share.bin").unwrap();
+
+        // A wrong or missing passphrase is caught before anything is written
+        let err = restore_mnemonic_core(&words, "", None, false, party).unwrap_err();
+        assert!(
+            err.to_string().contains("made with a passphrase"),
+            "{}",
+            err
+        );
+        assert!(restore_mnemonic_core(&words, "wrong horse", None, false, party).is_err());
+        assert!(!party.exists("paired_secret_share.bin"));
+
+        restore_mnemonic_core(&words, "correct horse", None, false, party).unwrap();
+        assert_eq!(party.read("paired_secret_share.bin").unwrap(), original);
+    }
+
+    #[test]
+    fn test_slip39_split_and_restore() {
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        let secret = "000000000000000000000000000000000000000000000000000000000000000b";
+        dealer::split_key_core("treasury", secret, 2, None, &root, &parties).unwrap();
+        let party = &storages[2];
+        let original = party.read("paired_secret_share.bin").unwrap();
+
+        // bip39 pieces have no passphrase to put them under
+        assert!(split_core(2, 3, BackupFormat::Bip39, "pw", party, 100).is_err());
+        let output = split_core(2, 3, BackupFormat::Slip39, "pw", party, 100)
+            .unwrap()
+            .output;
+        let record = ShareBackup::load(party).unwrap().unwrap();
+        assert_eq!(record.format, BackupFormat::Slip39);
+        assert!(record.passphrase);
+        assert!(output.contains(&format!("check {}", record.check)));
+
+        assert!(output.contains("33. "));
+
+        // Fresh pieces of the same share restore it just the same
+        let (_, share) = load_share(party).unwrap();
+        let mut shares = slip39::split(&share.to_bytes(), 2, 3, b"pw").unwrap();
+        party.delete("paired_secret_share.bin").unwrap();
+
+        assert!(restore_core(&shares[..1], "pw", None, false, party).is_err());
+        let err = restore_core(&shares[..2], "", None, false, party).unwrap_err();
+        assert!(
+            err.to_string().contains("made with a passphrase"),
+            "{}",
+            err
+        );
+        assert!(!party.exists("paired_secret_share.bin"));
+
+        shares.swap(0, 2);
+        restore_core(&shares[..2], "pw", None, false, party).unwrap();
+        assert_eq!(party.read("paired_secret_share.bin").unwrap(), original);
+    }
+}
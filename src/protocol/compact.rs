@@ -0,0 +1,297 @@
+//! Compact Payload Encoding
+//!
+//! Round payloads are JSON whose bulk is hex (bincode-hex shares, nonces,
+//! commitments), so about half of every pasted character is redundant. With
+//! the global `--compact` flag, payloads are printed as a single token:
+//!
+//! ```text
+//! cbor:<base64url of CBOR>
+//! ```
+//!
+//! The CBOR is the payload's JSON value behind the self-describe tag
+//! (`d9 d9 f7`), with every lowercase hex string stored as a byte string.
+//! Decoding turns byte strings back into lowercase hex, so the JSON a command
+//! sees is the same apart from key order and whitespace. A QR of a compact
+//! payload carries the CBOR bytes directly instead of the base64 text.
+//!
+//! Input needs no flag: [`expand`] replaces any `cbor:` tokens in `--data`
+//! with their JSON, and JSON passes through untouched, so compact and plain
+//! payloads can be mixed in one paste.
+
+use crate::qr::ur::{cbor_bytes, cbor_header, cbor_read_header, CBOR_ARRAY, CBOR_BYTES, CBOR_UINT};
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde_json::{Map, Number, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Text prefix of a compact payload
+pub const PREFIX: &str = "cbor:";
+
+/// CBOR self-describe tag 55799, marking compact bytes
+pub const MAGIC: [u8; 3] = [0xd9, 0xd9, 0xf7];
+
+const CBOR_NEGATIVE: u8 = 1;
+const CBOR_TEXT: u8 = 3;
+const CBOR_MAP: u8 = 5;
+const CBOR_SIMPLE: u8 = 7;
+const FALSE: u64 = 20;
+const TRUE: u64 = 21;
+const NULL: u64 = 22;
+const FLOAT64: u8 = 0xfb;
+
+/// Nesting beyond this is rejected rather than recursed into
+const MAX_DEPTH: usize = 64;
+
+static COMPACT: AtomicBool = AtomicBool::new(false);
+
+/// Print payloads in compact form for the rest of this run (`--compact`)
+pub fn set_compact(compact: bool) {
+    COMPACT.store(compact, Ordering::Relaxed);
+}
+
+pub fn is_compact() -> bool {
+    COMPACT.load(Ordering::Relaxed)
+}
+
+fn is_lower_hex(s: &str) -> bool {
+    !s.is_empty()
+        && s.len().is_multiple_of(2)
+        && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(CBOR_SIMPLE << 5 | NULL as u8),
+        Value::Bool(false) => out.push(CBOR_SIMPLE << 5 | FALSE as u8),
+        Value::Bool(true) => out.push(CBOR_SIMPLE << 5 | TRUE as u8),
+        Value::Number(n) => {
+            if let Some(n) = n.as_u64() {
+                cbor_header(CBOR_UINT, n, out);
+            } else if let Some(n) = n.as_i64() {
+                cbor_header(CBOR_NEGATIVE, !(n as u64), out);
+            } else {
+                out.push(FLOAT64);
+                out.extend(n.as_f64().unwrap_or_default().to_bits().to_be_bytes());
+            }
+        }
+        Value::String(s) if is_lower_hex(s) => {
+            // Checked above, so decoding cannot fail
+            cbor_bytes(&hex::decode(s).unwrap_or_default(), out);
+        }
+        Value::String(s) => {
+            cbor_header(CBOR_TEXT, s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(items) => {
+            cbor_header(CBOR_ARRAY, items.len() as u64, out);
+            for item in items {
+                write_value(item, out);
+            }
+        }
+        Value::Object(map) => {
+            cbor_header(CBOR_MAP, map.len() as u64, out);
+            for (key, item) in map {
+                cbor_header(CBOR_TEXT, key.len() as u64, out);
+                out.extend_from_slice(key.as_bytes());
+                write_value(item, out);
+            }
+        }
+    }
+}
+
+fn read_slice<'a>(data: &'a [u8], pos: &mut usize, len: u64) -> Result<&'a [u8]> {
+    let end = pos
+        .checked_add(usize::try_from(len)?)
+        .context("Compact payload is truncated")?;
+    let slice = data
+        .get(*pos..end)
+        .context("Compact payload is truncated")?;
+    *pos = end;
+    Ok(slice)
+}
+
+fn read_text(data: &[u8], pos: &mut usize, len: u64) -> Result<String> {
+    String::from_utf8(read_slice(data, pos, len)?.to_vec())
+        .context("Compact payload has a text field that is not UTF-8")
+}
+
+fn read_value(data: &[u8], pos: &mut usize, depth: usize) -> Result<Value> {
+    if depth > MAX_DEPTH {
+        bail!("Compact payload is nested too deeply");
+    }
+    let start = *pos;
+    let (major, value) = cbor_read_header(data, pos)?;
+    Ok(match major {
+        CBOR_UINT => Value::from(value),
+        CBOR_NEGATIVE => Value::from(
+            i64::try_from(value)
+                .map(|v| -1 - v)
+                .context("Compact payload has an out-of-range integer")?,
+        ),
+        CBOR_BYTES => Value::String(hex::encode(read_slice(data, pos, value)?)),
+        CBOR_TEXT => Value::String(read_text(data, pos, value)?),
+        CBOR_ARRAY => {
+            let mut items = Vec::new();
+            for _ in 0..value {
+                items.push(read_value(data, pos, depth + 1)?);
+            }
+            Value::Array(items)
+        }
+        CBOR_MAP => {
+            let mut map = Map::new();
+            for _ in 0..value {
+                let (key_major, len) = cbor_read_header(data, pos)?;
+                if key_major != CBOR_TEXT {
+                    bail!("Compact payload has a non-text object key");
+                }
+                let key = read_text(data, pos, len)?;
+                map.insert(key, read_value(data, pos, depth + 1)?);
+            }
+            Value::Object(map)
+        }
+        CBOR_SIMPLE if data[start] == FLOAT64 => Value::Number(
+            Number::from_f64(f64::from_bits(value))
+                .context("Compact payload has a non-finite number")?,
+        ),
+        CBOR_SIMPLE => match value {
+            FALSE => Value::Bool(false),
+            TRUE => Value::Bool(true),
+            NULL => Value::Null,
+            _ => bail!("Compact payload has an unsupported simple value"),
+        },
+        _ => bail!("Compact payload has unsupported CBOR major type {}", major),
+    })
+}
+
+/// CBOR bytes of a JSON payload, starting with [`MAGIC`]
+pub fn to_bytes(json: &str) -> Result<Vec<u8>> {
+    let value: Value = serde_json::from_str(json).context("Payload is not JSON")?;
+    let mut out = MAGIC.to_vec();
+    write_value(&value, &mut out);
+    Ok(out)
+}
+
+/// JSON text of compact bytes from [`to_bytes`]
+pub fn from_bytes(bytes: &[u8]) -> Result<String> {
+    let body = bytes
+        .strip_prefix(&MAGIC[..])
+        .context("Not a compact payload")?;
+    let mut pos = 0;
+    let value = read_value(body, &mut pos, 0)?;
+    if pos != body.len() {
+        bail!("Compact payload has trailing bytes");
+    }
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// `cbor:` token for a JSON payload
+pub fn encode(json: &str) -> Result<String> {
+    Ok(format!(
+        "{}{}",
+        PREFIX,
+        URL_SAFE_NO_PAD.encode(to_bytes(json)?)
+    ))
+}
+
+/// JSON text of a `cbor:` token
+pub fn decode(token: &str) -> Result<String> {
+    if !is_compact_token(token) {
+        bail!("Not a compact payload");
+    }
+    let encoded = &token[PREFIX.len()..];
+    let bytes = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .context("Compact payload is not valid base64url")?;
+    from_bytes(&bytes)
+}
+
+/// Whether `token` is a `cbor:` token
+pub fn is_compact_token(token: &str) -> bool {
+    token
+        .get(..PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(PREFIX))
+}
+
+/// A payload as it should be printed: compact with `--compact`, else unchanged
+pub fn present(json: &str) -> String {
+    if !is_compact() {
+        return json.to_string();
+    }
+    encode(json).unwrap_or_else(|_| json.to_string())
+}
+
+/// Replace every `cbor:` token in pasted data with its JSON
+///
+/// Everything else, whitespace included, is left as it was.
+pub fn expand(data: &str) -> Result<String> {
+    if !data.split_whitespace().any(is_compact_token) {
+        return Ok(data.to_string());
+    }
+    let mut out = String::with_capacity(data.len() * 2);
+    let mut rest = data;
+    let mut n = 0;
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let token = &rest[..end];
+        n += 1;
+        if is_compact_token(token) {
+            out.push_str(&decode(token).with_context(|| format!("Token #{}", n))?);
+        } else {
+            out.push_str(token);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_size() {
+        let json = serde_json::json!({
+            "type": "keygen_round1",
+            "party_index": 2,
+            "offset": -7,
+            "ratio": 0.5,
+            "ok": true,
+            "note": null,
+            "label": "Alice",
+            "upper": "ABCD",
+            "commitments": ["02".to_string() + &"ab".repeat(32), "03".to_string() + &"cd".repeat(32)],
+            "share": "5f".repeat(120),
+        });
+        let text = serde_json::to_string_pretty(&json).unwrap();
+
+        let token = encode(&text).unwrap();
+        assert!(token.starts_with(PREFIX));
+        assert!(!token.contains(char::is_whitespace));
+        assert!(token.len() * 10 < text.len() * 8, "compact form is smaller");
+
+        let decoded: Value = serde_json::from_str(&decode(&token).unwrap()).unwrap();
+        assert_eq!(decoded, json);
+        let decoded: Value =
+            serde_json::from_str(&from_bytes(&to_bytes(&text).unwrap()).unwrap()).unwrap();
+        assert_eq!(decoded, json);
+    }
+
+    #[test]
+    fn test_expand_mixed_batch() {
+        let a = r#"{"nonce":"aa","party_index":1}"#;
+        let b = r#"{"party_index":2,"nonce":"bb"}"#;
+        let data = format!(
+            "{}\n{}",
+            encode(a).unwrap().replacen("cbor:", "CBOR:", 1),
+            b
+        );
+        assert_eq!(expand(&data).unwrap(), format!("{}\n{}", a, b));
+        assert_eq!(expand(b).unwrap(), b);
+        assert!(expand("cbor:!!!").is_err());
+        assert!(decode("cbor:").is_err());
+    }
+}
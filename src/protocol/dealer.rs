@@ -0,0 +1,330 @@
+//! Trusted Dealer Split
+//!
+//! Turns an existing BIP-340 secret key (e.g. from `btc-keygen`) into a
+//! t-of-n group. The key becomes the constant term of a random polynomial of
+//! degree t-1 and party i gets f(i), written to `party<i>/` folders in the
+//! same layout keygen produces. The group key, and so the taproot address,
+//! stays the same: an existing treasury moves into threshold custody without
+//! moving any coins.
+//!
+//! Unlike DKG, the dealer machine sees the whole key. The group is only as
+//! safe as that machine until the original key and the dealer's copies of
+//! the party folders are destroyed.
+
+use crate::crypto::helpers::{construct_paired_secret_share, share_to_nonzero};
+use crate::crypto::secret::{serialize_secret, SecretString};
+use crate::protocol::audit;
+use crate::protocol::fingerprint;
+use crate::protocol::health;
+use crate::protocol::identity::{IdentityKey, Roster};
+use crate::protocol::keygen::{self, get_state_dir, GroupInfo, HtssMetadata, PartyInfo};
+use crate::protocol::wallet;
+use crate::storage::journal::Journaled;
+use crate::storage::{lock, migrations, FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use schnorr_fun::frost::SharedKey;
+use schnorr_fun::fun::poly;
+use secp256kfun::prelude::*;
+use std::collections::BTreeMap;
+
+/// Public files copied to the wallet root (no secret shares)
+const ROOT_FILES: &[&str] = &[
+    "shared_key.bin",
+    "htss_metadata.json",
+    "hd_metadata.json",
+    "group_info.json",
+];
+
+/// Parse a 32-byte hex secret key, negated if needed so its public key has even Y
+fn parse_secret(secret_hex: &str) -> Result<(Scalar<Secret, NonZero>, bool)> {
+    let secret_bytes: [u8; 32] = hex::decode(secret_hex.trim())
+        .context("Invalid hex string for secret key")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Secret key must be exactly 32 bytes"))?;
+    let secret: Scalar<Secret, NonZero> = Scalar::from_bytes(secret_bytes)
+        .context("Invalid secret key bytes")?
+        .non_zero()
+        .context("Secret key is zero (invalid)")?;
+    if g!(secret * G).normalize().is_y_even() {
+        Ok((secret, false))
+    } else {
+        Ok((-secret, true))
+    }
+}
+
+/// Core function for `dkg-split-key`
+///
+/// `parties[i - 1]` receives party i's folder, `root` the public files.
+/// `ranks` (one per party) makes the group hierarchical.
+pub fn split_key_core(
+    name: &str,
+    secret_hex: &str,
+    threshold: u32,
+    ranks: Option<Vec<u32>>,
+    root: &dyn Storage,
+    parties: &[&dyn Storage],
+) -> Result<CommandResult> {
+    let n_parties = parties.len() as u32;
+    if threshold == 0 || threshold > n_parties {
+        bail!(
+            "Threshold must be between 1 and the number of parties ({})",
+            n_parties
+        );
+    }
+    let hierarchical = ranks.is_some();
+    let ranks = ranks.unwrap_or_else(|| vec![0; n_parties as usize]);
+    if ranks.len() != parties.len() {
+        bail!(
+            "Got {} ranks for {} parties; give one rank per party",
+            ranks.len(),
+            n_parties
+        );
+    }
+
+    let (secret, negated) = parse_secret(secret_hex)?;
+    let secret_poly = poly::scalar::generate_shamir_sharing_poly(
+        secret.mark_zero(),
+        threshold as usize,
+        &mut rand::thread_rng(),
+    );
+    let shared_key: SharedKey<EvenY> =
+        SharedKey::from_poly(poly::scalar::to_point_poly(&secret_poly))
+            .non_zero()
+            .context("Shared key is zero")?
+            .into_xonly();
+    let group_key = shared_key.public_key();
+    if group_key != g!(secret * G).normalize() {
+        bail!("Dealt key doesn't match the secret key");
+    }
+    let pubkey_bytes = group_key.to_xonly_bytes();
+    let pubkey_hex = hex::encode(pubkey_bytes);
+
+    use bitcoin::{Address, Network, XOnlyPublicKey};
+    let xonly_pk = XOnlyPublicKey::from_slice(&pubkey_bytes)?;
+    let secp = bitcoin::secp256k1::Secp256k1::new();
+    let address_testnet = Address::p2tr(&secp, xonly_pk, None, Network::Testnet).to_string();
+    let address_mainnet = Address::p2tr(&secp, xonly_pk, None, Network::Bitcoin).to_string();
+
+    let mut out = String::new();
+    let mode_name = if hierarchical { "HTSS" } else { "TSS" };
+    out.push_str(&format!("FROST {} - Split Existing Key\n\n", mode_name));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("  Wallet: {}\n", name));
+    out.push_str(&format!("  Config: {}-of-{}\n", threshold, n_parties));
+    if hierarchical {
+        out.push_str(&format!("  Ranks: {:?}\n", ranks));
+    }
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    out.push_str("⚠️  TRUSTED DEALER: this machine knows the whole secret key.\n");
+    out.push_str("⚠️  Anyone who copied it, or the party folders written here, can spend\n");
+    out.push_str("⚠️  alone. Unlike keygen, the threshold only protects the wallet once\n");
+    out.push_str("⚠️  every copy of the original key and of the folders here is gone.\n\n");
+    if negated {
+        out.push_str("Negating secret key to ensure even Y (BIP340)\n\n");
+    }
+
+    // The dealer makes every identity, so each party starts with all of them pinned
+    let identities: Vec<IdentityKey> = parties.iter().map(|_| IdentityKey::generate()).collect();
+    let mut roster = Roster::default();
+    for (i, identity) in (1..).zip(&identities) {
+        roster.pin(i, &identity.public_hex())?;
+    }
+
+    let mut group_parties = Vec::new();
+    let mut shares = Vec::new();
+    for i in 1..=n_parties {
+        let share = share_to_nonzero(poly::scalar::eval(
+            &secret_poly,
+            Scalar::<Public, Zero>::from(i),
+        ))?;
+        group_parties.push(PartyInfo {
+            index: i,
+            rank: ranks[i as usize - 1],
+            verification_share: hex::encode(g!(share * G).normalize().to_bytes()),
+            identity: Some(identities[i as usize - 1].public_hex()),
+        });
+        shares.push(construct_paired_secret_share(i, share, &group_key)?);
+    }
+    group_parties.sort_by(|a, b| a.rank.cmp(&b.rank).then(a.index.cmp(&b.index)));
+    let group_info = GroupInfo {
+        name: name.to_string(),
+        group_public_key: pubkey_hex.clone(),
+        taproot_address_testnet: address_testnet.clone(),
+        taproot_address_mainnet: address_mainnet.clone(),
+        threshold,
+        total_parties: n_parties,
+        hierarchical,
+        parties: group_parties,
+        directory: BTreeMap::new(),
+    };
+    let party_ranks: BTreeMap<u32, u32> = (1..).zip(ranks.iter().copied()).collect();
+
+    out.push_str("⚙️  Dealing shares:\n");
+    for (i, ((storage, share), identity)) in (1..).zip(parties.iter().zip(&shares).zip(&identities))
+    {
+        // Each folder lands whole or not at all
+        let journaled = Journaled::new(*storage, "split-key");
+        let storage: &dyn Storage = &journaled;
+
+        storage.write("paired_secret_share.bin", &serialize_secret(share)?)?;
+        storage.write("shared_key.bin", &bincode::serialize(&shared_key)?)?;
+        let htss = HtssMetadata {
+            my_index: i,
+            my_rank: party_ranks[&i],
+            threshold,
+            hierarchical,
+            party_ranks: party_ranks.clone(),
+            wallet_version: migrations::CURRENT_WALLET_VERSION,
+            signing_requirement: BTreeMap::new(),
+            created_at: Some(health::now()),
+            refreshed_at: None,
+        };
+        storage.write(
+            "htss_metadata.json",
+            serde_json::to_string_pretty(&htss)?.as_bytes(),
+        )?;
+        storage.write(
+            "hd_metadata.json",
+            serde_json::to_string_pretty(&keygen::new_hd_metadata(&group_key))?.as_bytes(),
+        )?;
+        storage.write(
+            "group_info.json",
+            serde_json::to_string_pretty(&group_info)?.as_bytes(),
+        )?;
+        identity.save(storage)?;
+        roster.save(storage)?;
+        audit::append(
+            storage,
+            "split-key",
+            serde_json::json!({
+                "threshold": threshold,
+                "n_parties": n_parties,
+                "group_public_key": pubkey_hex,
+                "my_index": i,
+            }),
+        )?;
+
+        journaled.commit()?;
+        out.push_str(&format!("   Party {}: ✓\n", i));
+    }
+
+    // Like keygen's local mode, the root only gets the public files
+    for file in ROOT_FILES {
+        root.write(file, &parties[0].read(file)?)?;
+    }
+    let code = fingerprint::ceremony_fingerprint(root)?.code;
+
+    out.push_str("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str("❄️  Key split complete! The address is unchanged.\n\n");
+    out.push_str(&format!("📍 Public Key: {}\n", pubkey_hex));
+    out.push_str(&format!("📍 Testnet Address: {}\n", address_testnet));
+    out.push_str(&format!("📍 Mainnet Address: {}\n", address_mainnet));
+    out.push_str(&format!("🔑 Fingerprint: {}\n", code));
+    out.push_str("\n🧠 Next steps:\n");
+    out.push_str("   • Hand each party<i>/ folder to its holder over a secure channel\n");
+    out.push_str("   • Each holder checks dkg-fingerprint shows the same code\n");
+    out.push_str("   • Then shred the original key and every party folder left here\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&serde_json::json!({
+            "wallet": name,
+            "group_public_key": pubkey_hex,
+            "threshold": threshold,
+            "n_parties": n_parties,
+            "fingerprint": code,
+        }))?,
+    })
+}
+
+/// CLI wrapper for `dkg-split-key`
+///
+/// Without `secret_hex` it splits the key saved by `btc-keygen`.
+pub fn split_key(
+    name: &str,
+    secret_hex: Option<&str>,
+    threshold: u32,
+    n_parties: u32,
+    ranks: Option<Vec<u32>>,
+) -> Result<()> {
+    wallet::validate_wallet_name(name)?;
+    let state_dir = get_state_dir(name);
+    if !lock::is_unused(std::path::Path::new(&state_dir)) {
+        bail!("Wallet '{}' already exists at {}", name, state_dir);
+    }
+    let secret = match secret_hex {
+        Some(secret_hex) => SecretString::new(secret_hex.to_string()),
+        None => crate::btc::schnorr::stored_secret_hex(&FileStorage::new(".frost_state")?)?,
+    };
+
+    let root = FileStorage::new(&state_dir)?;
+    let storages = (1..=n_parties)
+        .map(|i| FileStorage::new(&format!("{}/party{}", state_dir, i)))
+        .collect::<Result<Vec<_>>>()?;
+    let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+    let cmd_result = split_key_core(name, &secret, threshold, ranks, &root, &parties)?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", cmd_result.result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::signing;
+    use crate::storage::MemoryStorage;
+
+    const SECRET: &str = "0000000000000000000000000000000000000000000000000000000000000006";
+
+    #[test]
+    fn test_split_key_keeps_the_address_and_signs() {
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        let result = split_key_core("treasury", SECRET, 2, None, &root, &parties).unwrap();
+
+        // 6*G has odd Y, so the dealer negates the key; the x-only key is the same
+        let (secret, negated) = parse_secret(SECRET).unwrap();
+        assert!(negated);
+        let expected = hex::encode(g!(secret * G).normalize().to_xonly_bytes());
+        let info: GroupInfo =
+            serde_json::from_slice(&root.read("group_info.json").unwrap()).unwrap();
+        assert_eq!(info.group_public_key, expected);
+        assert!(result.result.contains(&expected));
+        assert!(!root.exists("paired_secret_share.bin"));
+        assert_eq!(
+            audit::load(&storages[1]).unwrap()[0].event,
+            "split-key".to_string()
+        );
+
+        // Any two parties sign for the original key
+        let signers = [&storages[0], &storages[2]];
+        let nonces: Vec<String> = signers
+            .iter()
+            .map(|p| signing::generate_nonce_core("s1", *p).unwrap().result)
+            .collect();
+        let shares: Vec<String> = signers
+            .iter()
+            .map(|p| {
+                signing::create_signature_share_core("s1", "migrate", &nonces.join(" "), *p)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        let signature = signing::combine_signatures_core(&shares.join(" "), signers[0]).unwrap();
+        assert!(signature.output.contains("FROST SIGNATURE VALID"));
+    }
+
+    #[test]
+    fn test_split_key_rejects_bad_config() {
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..2).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        assert!(split_key_core("w", SECRET, 3, None, &root, &parties).is_err());
+        assert!(split_key_core("w", SECRET, 2, Some(vec![0]), &root, &parties).is_err());
+        assert!(split_key_core("w", "zz", 2, None, &root, &parties).is_err());
+        assert!(!storages[0].exists("paired_secret_share.bin"));
+    }
+}
@@ -0,0 +1,720 @@
+//! External Signing Devices (experimental)
+//!
+//! A party's share can live on dedicated hardware instead of in the wallet
+//! folder. The folder then keeps only the public files plus `device.json`,
+//! and `dkg-nonce` / `dkg-sign` hand the two secret operations to the device:
+//!
+//! ```text
+//! host                                   device
+//!  │  {"type":"get_info"}                  │
+//!  │ ────────────────────────────────────► │
+//!  │  {"type":"info", group key, index}    │
+//!  │ ◄──────────────────────────────────── │
+//!  │  {"type":"nonce", session}            │  keeps the secret nonce
+//!  │ ────────────────────────────────────► │
+//!  │  {"type":"nonce", public nonce}       │
+//!  │ ◄──────────────────────────────────── │
+//!  │  {"type":"sign", sighash, signers,    │  shows the sighash, asks,
+//!  │   aggregate nonce, merkle root, memo} │  signs, deletes the nonce
+//!  │ ────────────────────────────────────► │
+//!  │  {"type":"signature_share", share}    │
+//!  │ ◄──────────────────────────────────── │
+//! ```
+//!
+//! Each message is one line of JSON. The device is reached either by running
+//! a program once per request (HWI style: request on stdin, reply on stdout)
+//! or through a serial port configured beforehand (e.g. `stty raw 115200`).
+//! The device tweaks its own key for the merkle root it is shown, so a host
+//! can't make it sign for an output key other than the group's.
+//!
+//! `device-emulate` is a reference device over a folder holding
+//! `paired_secret_share.bin`, useful to test the protocol or to build one.
+
+use crate::crypto::helpers::{negate_paired_secret_share, pair_with_public_key};
+use crate::crypto::secret::serialize_secret;
+use crate::protocol::audit;
+use crate::protocol::dkg_tx::{parse_merkle_root, SessionKey};
+use crate::protocol::keygen::{get_state_dir, require_secp256k1, HtssMetadata};
+use crate::protocol::payload::{decode_hex_field, share_index};
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use schnorr_fun::binonce::{Nonce, NonceKeyPair};
+use schnorr_fun::frost::{self, PairedSecretShare, SharedKey};
+use schnorr_fun::Message;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader, Write};
+
+/// Where a wallet's device is reached, next to the public files
+pub const DEVICE_FILE: &str = "device.json";
+
+/// Version of the request/response messages
+pub const DEVICE_PROTOCOL_VERSION: u32 = 1;
+
+/// Longest session id the device accepts
+const MAX_SESSION_LEN: usize = 64;
+
+/// How the host reaches the device
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Connection {
+    /// A program run once per request, one JSON line in and out
+    Command { program: String, args: Vec<String> },
+    /// A serial port (or any character device), one JSON line each way
+    Serial { path: String },
+}
+
+impl std::fmt::Display for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Connection::Command { program, args } if args.is_empty() => {
+                write!(f, "command `{}`", program)
+            }
+            Connection::Command { program, args } => {
+                write!(f, "command `{} {}`", program, args.join(" "))
+            }
+            Connection::Serial { path } => write!(f, "serial port {}", path),
+        }
+    }
+}
+
+/// `device.json`: the device a party's share lives on (nothing secret)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeviceConfig {
+    pub connection: Connection,
+    /// Party index the device reported at enrollment
+    pub party_index: u32,
+    /// x-only group public key (hex) the device reported at enrollment
+    pub group_public_key: String,
+    pub enrolled_at: u64,
+}
+
+impl DeviceConfig {
+    pub fn load(storage: &dyn Storage) -> Result<Self> {
+        serde_json::from_slice(&storage.read(DEVICE_FILE)?)
+            .with_context(|| format!("{} is damaged", DEVICE_FILE))
+    }
+}
+
+/// Host to device
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeviceRequest {
+    GetInfo,
+    /// Commit to a fresh nonce for `session`
+    Nonce {
+        session: String,
+    },
+    /// Sign `sighash` with the nonce committed to for `session`
+    Sign {
+        session: String,
+        /// 32-byte BIP-341 sighash (hex), shown on the device
+        sighash: String,
+        /// Script tree root of the spent output (hex), if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        merkle_root: Option<String>,
+        /// Party indices signing this session
+        signers: Vec<u32>,
+        /// Aggregate of the signers' nonces (bincode hex)
+        agg_nonce: String,
+        /// Session memo, shown on the device as the host's description
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memo: Option<String>,
+        /// The session spends a script leaf: sign for the untweaked group key
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        script_path: bool,
+    },
+}
+
+/// Device to host
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeviceResponse {
+    Info {
+        protocol: u32,
+        party_index: u32,
+        /// x-only group public key (hex)
+        group_public_key: String,
+    },
+    /// Public nonce (bincode hex)
+    Nonce { nonce: String },
+    /// Signature share (bincode hex)
+    SignatureShare { signature_share: String },
+    /// The request failed or the holder declined it
+    Error { reason: String },
+}
+
+/// One request line out, one reply line back
+pub trait Transport {
+    fn exchange(&mut self, request: &str) -> Result<String>;
+}
+
+struct CommandTransport<'a> {
+    program: &'a str,
+    args: &'a [String],
+}
+
+impl Transport for CommandTransport<'_> {
+    fn exchange(&mut self, request: &str) -> Result<String> {
+        let mut child = std::process::Command::new(self.program)
+            .args(self.args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run signing device `{}`", self.program))?;
+        let mut stdin = child.stdin.take().context("Device stdin unavailable")?;
+        writeln!(stdin, "{}", request)?;
+        drop(stdin);
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "Signing device `{}` failed ({})",
+                self.program,
+                output.status
+            );
+        }
+        String::from_utf8(output.stdout)?
+            .lines()
+            .next()
+            .map(str::to_string)
+            .context("Signing device gave no reply")
+    }
+}
+
+struct SerialTransport<'a> {
+    path: &'a str,
+}
+
+impl Transport for SerialTransport<'_> {
+    fn exchange(&mut self, request: &str) -> Result<String> {
+        let mut port = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(self.path)
+            .with_context(|| format!("Failed to open serial port {}", self.path))?;
+        writeln!(port, "{}", request)?;
+        port.flush()?;
+        let mut reply = String::new();
+        BufReader::new(port).read_line(&mut reply)?;
+        if reply.trim().is_empty() {
+            bail!("Signing device on {} gave no reply", self.path);
+        }
+        Ok(reply)
+    }
+}
+
+fn call(connection: &Connection, request: &DeviceRequest) -> Result<DeviceResponse> {
+    let line = serde_json::to_string(request)?;
+    let reply = match connection {
+        Connection::Command { program, args } => {
+            CommandTransport { program, args }.exchange(&line)?
+        }
+        Connection::Serial { path } => SerialTransport { path }.exchange(&line)?,
+    };
+    let response: DeviceResponse = serde_json::from_str(reply.trim())
+        .with_context(|| format!("Signing device sent an unknown reply: {}", reply.trim()))?;
+    if let DeviceResponse::Error { reason } = response {
+        bail!(crate::error::Error::Protocol(format!(
+            "Signing device refused: {}",
+            reason
+        )));
+    }
+    Ok(response)
+}
+
+fn unexpected(response: DeviceResponse) -> anyhow::Error {
+    crate::error::Error::Protocol(format!(
+        "Signing device sent an unexpected reply: {:?}",
+        response
+    ))
+    .into()
+}
+
+/// Whether this party's share lives on a signing device
+pub fn is_delegated(storage: &dyn Storage) -> bool {
+    storage.exists(DEVICE_FILE)
+}
+
+/// Fail commands that need the share in the folder when it is on a device
+pub fn require_local(storage: &dyn Storage, command: &str) -> Result<()> {
+    if is_delegated(storage) {
+        bail!(crate::error::Error::User(format!(
+            "This party's share is on a signing device ({}); {} needs it in the wallet folder. \
+             Sign Bitcoin transactions with dkg-nonce and dkg-sign.",
+            DEVICE_FILE, command
+        )));
+    }
+    Ok(())
+}
+
+/// Ask the device for a public nonce for `session` (bincode hex)
+pub(crate) fn request_nonce(storage: &dyn Storage, session: &str) -> Result<String> {
+    let config = DeviceConfig::load(storage)?;
+    let request = DeviceRequest::Nonce {
+        session: session.to_string(),
+    };
+    match call(&config.connection, &request)? {
+        DeviceResponse::Nonce { nonce } => {
+            let _: Nonce = decode_hex_field("The device's nonce", &nonce)?;
+            Ok(nonce)
+        }
+        other => Err(unexpected(other)),
+    }
+}
+
+/// Have the device sign; `request` must be a [`DeviceRequest::Sign`]
+///
+/// Returns the signature share (bincode hex).
+pub(crate) fn request_signature(storage: &dyn Storage, request: &DeviceRequest) -> Result<String> {
+    let config = DeviceConfig::load(storage)?;
+    match call(&config.connection, request)? {
+        DeviceResponse::SignatureShare { signature_share } => {
+            let _: Scalar<Public, Zero> =
+                decode_hex_field("The device's signature share", &signature_share)?;
+            Ok(signature_share)
+        }
+        other => Err(unexpected(other)),
+    }
+}
+
+/// Core function for `device-enroll`
+///
+/// Asks the device who it is and records it in `device.json` once its group
+/// key and party index match this wallet folder. From then on `dkg-nonce`
+/// and `dkg-sign` go to the device.
+pub fn enroll_core(
+    connection: Connection,
+    storage: &dyn Storage,
+    now: u64,
+) -> Result<CommandResult> {
+    require_secp256k1(storage)?;
+    let htss: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    let shared_key: SharedKey<EvenY> = bincode::deserialize(&storage.read("shared_key.bin")?)?;
+    let group_key = hex::encode(shared_key.public_key().to_xonly_bytes());
+
+    let (protocol, party_index, device_key) = match call(&connection, &DeviceRequest::GetInfo)? {
+        DeviceResponse::Info {
+            protocol,
+            party_index,
+            group_public_key,
+        } => (protocol, party_index, group_public_key),
+        other => return Err(unexpected(other)),
+    };
+    if protocol != DEVICE_PROTOCOL_VERSION {
+        bail!(crate::error::Error::Protocol(format!(
+            "Signing device speaks protocol {}, this frostdao speaks {}",
+            protocol, DEVICE_PROTOCOL_VERSION
+        )));
+    }
+    if !device_key.eq_ignore_ascii_case(&group_key) || party_index != htss.my_index {
+        bail!(crate::error::Error::Protocol(format!(
+            "Device holds party {} of group {}, but this folder is party {} of {}",
+            party_index, device_key, htss.my_index, group_key
+        )));
+    }
+
+    let config = DeviceConfig {
+        connection,
+        party_index,
+        group_public_key: group_key,
+        enrolled_at: now,
+    };
+    storage.write(
+        DEVICE_FILE,
+        serde_json::to_string_pretty(&config)?.as_bytes(),
+    )?;
+    audit::append(
+        storage,
+        "device_enroll",
+        serde_json::json!({ "connection": config.connection.to_string() }),
+    )?;
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "✓ Party {}'s share is on the device ({})\n",
+        party_index, config.connection
+    ));
+    out.push_str("   dkg-nonce and dkg-sign now ask the device\n");
+    if storage.exists("paired_secret_share.bin") {
+        out.push_str(
+            "⚠️  paired_secret_share.bin is still in this folder. It is no longer used;\n",
+        );
+        out.push_str("   make sure the device and its backup hold the share, then delete it.\n");
+    }
+    out.push_str("📝 Recorded in the audit log\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&config)?,
+    })
+}
+
+// ============================================================================
+// Device side (device-emulate)
+// ============================================================================
+
+/// Session ids name the device's nonce files, so the host gets no say in paths
+fn check_session(session: &str) -> Result<()> {
+    let valid = !session.is_empty()
+        && session.len() <= MAX_SESSION_LEN
+        && session
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        bail!(
+            "Session id must be 1-{} characters of A-Z, a-z, 0-9, _ or -",
+            MAX_SESSION_LEN
+        );
+    }
+    Ok(())
+}
+
+/// Answer one request the way a device would
+///
+/// `storage` holds the device's `paired_secret_share.bin` and its nonces.
+/// `approve` is shown what a sign request signs and says whether to go on.
+pub fn handle(
+    request: &DeviceRequest,
+    storage: &dyn Storage,
+    approve: &mut dyn FnMut(&[String]) -> bool,
+) -> DeviceResponse {
+    handle_request(request, storage, approve).unwrap_or_else(|e| DeviceResponse::Error {
+        reason: format!("{:#}", e),
+    })
+}
+
+fn handle_request(
+    request: &DeviceRequest,
+    storage: &dyn Storage,
+    approve: &mut dyn FnMut(&[String]) -> bool,
+) -> Result<DeviceResponse> {
+    let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(
+        &storage
+            .read_secret("paired_secret_share.bin")
+            .context("The device holds no share")?,
+    )?;
+    let party_index = {
+        let mut index = [0u8; 4];
+        index.copy_from_slice(&paired_share.index().to_bytes()[28..]);
+        u32::from_be_bytes(index)
+    };
+    let group_key = paired_share.public_key();
+
+    match request {
+        DeviceRequest::GetInfo => Ok(DeviceResponse::Info {
+            protocol: DEVICE_PROTOCOL_VERSION,
+            party_index,
+            group_public_key: hex::encode(group_key.to_xonly_bytes()),
+        }),
+        DeviceRequest::Nonce { session } => {
+            check_session(session)?;
+            let frost = frost::new_with_synthetic_nonces::<Sha256, rand::rngs::ThreadRng>();
+            let mut nonce_rng: rand_chacha::ChaCha20Rng =
+                frost.seed_nonce_rng(paired_share, session.as_bytes());
+            let nonce = frost.gen_nonce(&mut nonce_rng);
+            storage.write(
+                &format!("dkg_nonce_{}.bin", session),
+                &serialize_secret(&nonce)?,
+            )?;
+            Ok(DeviceResponse::Nonce {
+                nonce: hex::encode(bincode::serialize(&nonce.public())?),
+            })
+        }
+        DeviceRequest::Sign {
+            session,
+            sighash,
+            merkle_root,
+            signers,
+            agg_nonce,
+            memo,
+            script_path,
+        } => {
+            check_session(session)?;
+            let nonce_file = format!("dkg_nonce_{}.bin", session);
+            let nonce: NonceKeyPair = bincode::deserialize(
+                &storage
+                    .read_secret(&nonce_file)
+                    .with_context(|| format!("No nonce for session {}", session))?,
+            )?;
+            let sighash_bytes: [u8; 32] = hex::decode(sighash)?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Invalid sighash length"))?;
+            let merkle_root = merkle_root.as_deref().map(parse_merkle_root).transpose()?;
+            if !signers.contains(&party_index) {
+                bail!(
+                    "Party {} is not among the signers {:?}",
+                    party_index,
+                    signers
+                );
+            }
+            let parties = signers
+                .iter()
+                .map(|&i| share_index(i))
+                .collect::<Result<BTreeSet<_>>>()?;
+            let agg_binonce: Nonce<Zero> = decode_hex_field("Aggregate nonce", agg_nonce)?;
+
+            let mut shown = vec![
+                format!("Sign for party {}", party_index),
+                format!("Session: {}", session),
+                format!("Sighash: {}", sighash),
+            ];
+            if let Some(root) = &merkle_root {
+                shown.push(format!("Merkle root: {}", hex::encode(root)));
+            }
+            if *script_path {
+                shown.push("Spend path: script leaf".to_string());
+            }
+            shown.push(format!("Signers: {:?}", signers));
+            if let Some(memo) = memo {
+                shown.push(format!("Host says: {}", memo));
+            }
+            if !approve(&shown) {
+                bail!("Declined on the device");
+            }
+
+            let key = if *script_path {
+                SessionKey::Group
+            } else {
+                SessionKey::Output(merkle_root.as_ref())
+            };
+            let (tweaked_pubkey, parity_flip, _) = key.resolve(&group_key);
+            let signing_share = if parity_flip {
+                negate_paired_secret_share(&paired_share)?
+            } else {
+                paired_share
+            };
+            let signing_share = pair_with_public_key(&signing_share, &tweaked_pubkey)?;
+            let frost = frost::new_with_deterministic_nonces::<Sha256>();
+            let msg = Message::raw(&sighash_bytes);
+            let sign_session = frost.party_sign_session(tweaked_pubkey, parties, agg_binonce, msg);
+            let sig_share = sign_session.sign(&signing_share, nonce);
+            // Single use: a second signature with this nonce would leak the share
+            storage.delete(&nonce_file)?;
+
+            Ok(DeviceResponse::SignatureShare {
+                signature_share: hex::encode(bincode::serialize(&sig_share)?),
+            })
+        }
+    }
+}
+
+/// Serve requests line by line until `reader` ends
+pub fn serve(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    storage: &dyn Storage,
+    approve: &mut dyn FnMut(&[String]) -> bool,
+) -> Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<DeviceRequest>(&line) {
+            Ok(request) => handle(&request, storage, approve),
+            Err(e) => DeviceResponse::Error {
+                reason: format!("Unknown request: {}", e),
+            },
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Show a sign request on stderr and read y/N from the terminal
+fn approve_on_terminal(shown: &[String]) -> bool {
+    eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for line in shown {
+        eprintln!("{}", line);
+    }
+    eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    eprint!("Approve? [y/N] ");
+    // stdin carries the protocol, so the answer comes from the terminal
+    let Ok(tty) = std::fs::File::open("/dev/tty") else {
+        eprintln!("no terminal to confirm on; declined");
+        return false;
+    };
+    let mut answer = String::new();
+    BufReader::new(tty).read_line(&mut answer).is_ok()
+        && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// CLI wrapper for enroll_core
+pub fn enroll(name: &str, command: Option<&str>, serial: Option<&str>) -> Result<()> {
+    let connection = match (command, serial) {
+        (Some(command), None) => {
+            let mut words = command.split_whitespace().map(str::to_string);
+            let program = words
+                .next()
+                .context("--command is empty; give the device program to run")?;
+            Connection::Command {
+                program,
+                args: words.collect(),
+            }
+        }
+        (None, Some(path)) => Connection::Serial {
+            path: path.to_string(),
+        },
+        _ => bail!(crate::error::Error::User(
+            "Give exactly one of --command or --serial".to_string()
+        )),
+    };
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = enroll_core(connection, &storage, crate::protocol::health::now())?;
+    println!("{}", cmd_result.output);
+    Ok(())
+}
+
+/// CLI wrapper for the reference device: serve stdin/stdout from `dir`
+///
+/// With `yes`, sign requests are approved without asking (tests only).
+pub fn emulate(dir: &str, yes: bool) -> Result<()> {
+    let storage = FileStorage::new(dir)?;
+    let mut approve = |shown: &[String]| yes || approve_on_terminal(shown);
+    let stdin = std::io::stdin();
+    serve(stdin.lock(), std::io::stdout(), &storage, &mut approve)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::dealer;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_device_answers_and_single_use_nonce() {
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..2).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        let secret = "000000000000000000000000000000000000000000000000000000000000000d";
+        dealer::split_key_core("treasury", secret, 2, None, &root, &parties).unwrap();
+        let device = &storages[1];
+        let mut yes = |_: &[String]| true;
+
+        let mut replies = Vec::new();
+        let requests = format!(
+            "{}\n\n{}\nnot json\n",
+            serde_json::to_string(&DeviceRequest::GetInfo).unwrap(),
+            serde_json::to_string(&DeviceRequest::Nonce {
+                session: "s1".to_string()
+            })
+            .unwrap()
+        );
+        serve(requests.as_bytes(), &mut replies, device, &mut yes).unwrap();
+        let replies: Vec<DeviceResponse> = String::from_utf8(replies)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert!(matches!(
+            replies[0],
+            DeviceResponse::Info { party_index: 2, .. }
+        ));
+        let DeviceResponse::Nonce { nonce } = &replies[1] else {
+            panic!("{:?}", replies[1]);
+        };
+        assert!(matches!(replies[2], DeviceResponse::Error { .. }));
+
+        let sign = DeviceRequest::Sign {
+            session: "s1".to_string(),
+            sighash: "11".repeat(32),
+            merkle_root: None,
+            signers: vec![1, 2],
+            agg_nonce: nonce.clone(),
+            memo: Some("rent".to_string()),
+            script_path: false,
+        };
+        let mut shown = Vec::new();
+        let mut no = |lines: &[String]| {
+            shown = lines.to_vec();
+            false
+        };
+        assert!(matches!(
+            handle(&sign, device, &mut no),
+            DeviceResponse::Error { .. }
+        ));
+        assert!(shown.contains(&format!("Sighash: {}", "11".repeat(32))));
+        assert!(shown.contains(&"Host says: rent".to_string()));
+        assert!(device.exists("dkg_nonce_s1.bin"));
+
+        assert!(matches!(
+            handle(&sign, device, &mut yes),
+            DeviceResponse::SignatureShare { .. }
+        ));
+        // The nonce is gone, so the same request can't be signed twice
+        assert!(!device.exists("dkg_nonce_s1.bin"));
+        assert!(matches!(
+            handle(&sign, device, &mut yes),
+            DeviceResponse::Error { .. }
+        ));
+    }
+
+    #[test]
+    fn test_device_refuses_session_ids_that_are_paths() {
+        let dir = std::env::temp_dir().join(format!("frostdao_device_{}", std::process::id()));
+        let device_dir = dir.join("device");
+        std::fs::create_dir_all(&device_dir).unwrap();
+        let device = FileStorage::new(device_dir.to_str().unwrap()).unwrap();
+        let root = MemoryStorage::new();
+        let storages: Vec<MemoryStorage> = (0..2).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        let secret = "000000000000000000000000000000000000000000000000000000000000000d";
+        dealer::split_key_core("treasury", secret, 2, None, &root, &parties).unwrap();
+        device
+            .write(
+                "paired_secret_share.bin",
+                &storages[0].read("paired_secret_share.bin").unwrap(),
+            )
+            .unwrap();
+        std::fs::write(dir.join("dkg_nonce_victim.bin"), b"keep").unwrap();
+        let mut yes = |_: &[String]| true;
+
+        for session in ["../victim", "../../x", "", "a/b", &"s".repeat(65)] {
+            let nonce = DeviceRequest::Nonce {
+                session: session.to_string(),
+            };
+            assert!(
+                matches!(
+                    handle(&nonce, &device, &mut yes),
+                    DeviceResponse::Error { .. }
+                ),
+                "{:?}",
+                session
+            );
+            let sign = DeviceRequest::Sign {
+                session: session.to_string(),
+                sighash: "11".repeat(32),
+                merkle_root: None,
+                signers: vec![1],
+                agg_nonce: String::new(),
+                memo: None,
+                script_path: false,
+            };
+            assert!(matches!(
+                handle(&sign, &device, &mut yes),
+                DeviceResponse::Error { .. }
+            ));
+        }
+        // Nothing was written or deleted outside the device folder
+        assert_eq!(
+            std::fs::read(dir.join("dkg_nonce_victim.bin")).unwrap(),
+            b"keep"
+        );
+        assert!(!dir.join("x.bin").exists());
+        assert!(matches!(
+            handle(
+                &DeviceRequest::Nonce {
+                    session: "a1b2_c3-d4".to_string()
+                },
+                &device,
+                &mut yes
+            ),
+            DeviceResponse::Nonce { .. }
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+//! Party Contact Directory
+//!
+//! Who holds each share, how to reach them and when they are usually around,
+//! kept by party index in `group_info.json` (see [`PartyContact`]). Being part
+//! of the group info, the directory goes out with `dkg-group-publish` and is
+//! kept when `dkg-info` rebuilds the file.
+//!
+//! `dkg-party` edits an entry; signing and recovery use [`whom_to_contact`]
+//! to say who to ask when shares are missing. For parties without an entry,
+//! the contact from `dkg-notes` and the pinned identity's npub stand in.
+
+use crate::protocol::doctor;
+use crate::protocol::health::{self, describe_time};
+use crate::protocol::identity::Roster;
+use crate::protocol::keygen::{get_state_dir, GroupInfo, PartyContact};
+use crate::protocol::nostr;
+use crate::protocol::wallet::WalletNotes;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+
+/// Changes requested by `dkg-party` for one party
+#[derive(Debug, Clone, Default)]
+pub struct PartyEdit {
+    pub index: u32,
+    /// Each field: None leaves it, an empty string clears it
+    pub holder: Option<String>,
+    pub contact: Option<String>,
+    pub npub: Option<String>,
+    pub availability: Option<String>,
+    /// The holder confirmed they have their share
+    pub share_confirmed: bool,
+    /// Remove the whole entry
+    pub clear: bool,
+}
+
+fn load_group_info(storage: &dyn Storage) -> Result<GroupInfo> {
+    serde_json::from_slice(
+        &storage
+            .read("group_info.json")
+            .context("No group_info.json; run dkg-info first")?,
+    )
+    .context("group_info.json is damaged")
+}
+
+fn apply(entry: &mut PartyContact, edit: &PartyEdit, now: u64) -> Result<()> {
+    if let Some(holder) = &edit.holder {
+        entry.holder = holder.trim().to_string();
+    }
+    if let Some(contact) = &edit.contact {
+        entry.contact = contact.trim().to_string();
+    }
+    if let Some(npub) = &edit.npub {
+        entry.npub = match npub.trim() {
+            "" => None,
+            key => Some(nostr::npub(&nostr::parse_public_key(key)?)?),
+        };
+    }
+    if let Some(availability) = &edit.availability {
+        entry.availability = availability.trim().to_string();
+        entry.availability_at = Some(now).filter(|_| !entry.availability.is_empty());
+    }
+    if edit.share_confirmed {
+        entry.share_confirmed_at = Some(now);
+    }
+    Ok(())
+}
+
+/// One line about `entry`, e.g. `Alice · signal:+1 555 0100 · weekdays (2 days ago)`
+pub fn describe(entry: &PartyContact, now: u64) -> String {
+    let mut parts = Vec::new();
+    if !entry.holder.is_empty() {
+        parts.push(entry.holder.clone());
+    }
+    if !entry.contact.is_empty() {
+        parts.push(entry.contact.clone());
+    }
+    if let Some(npub) = &entry.npub {
+        parts.push(npub.clone());
+    }
+    if !entry.availability.is_empty() {
+        parts.push(format!(
+            "{} ({})",
+            entry.availability,
+            describe_time(entry.availability_at, now)
+        ));
+    }
+    if entry.share_confirmed_at.is_some() {
+        parts.push(format!(
+            "share confirmed {}",
+            describe_time(entry.share_confirmed_at, now)
+        ));
+    }
+    parts.join(" · ")
+}
+
+/// How to reach each of `parties`, by party index
+///
+/// Directory entries first; otherwise the `dkg-notes` contact and the
+/// pinned identity's npub. Parties with nothing on file are left out.
+pub fn contacts(storage: &dyn Storage, parties: &[u32], now: u64) -> BTreeMap<u32, String> {
+    let directory = load_group_info(storage)
+        .map(|info| info.directory)
+        .unwrap_or_default();
+    let notes = WalletNotes::load(storage).unwrap_or_default();
+    let roster = Roster::load(storage).ok().flatten().unwrap_or_default();
+
+    let mut found = BTreeMap::new();
+    for &index in parties {
+        let line = match directory.get(&index) {
+            Some(entry) if !entry.is_empty() => describe(entry, now),
+            _ => {
+                let mut fallback = Vec::new();
+                if let Some(contact) = notes.contacts.get(&index) {
+                    fallback.push(contact.clone());
+                }
+                if let Some(npub) = roster.0.get(&index).and_then(|k| nostr::npub(k).ok()) {
+                    fallback.push(npub);
+                }
+                fallback.join(" · ")
+            }
+        };
+        if !line.is_empty() {
+            found.insert(index, line);
+        }
+    }
+    found
+}
+
+/// "Whom to contact" block for `parties`, empty when there are none
+pub fn whom_to_contact(storage: &dyn Storage, parties: &[u32], now: u64) -> String {
+    if parties.is_empty() {
+        return String::new();
+    }
+    let found = contacts(storage, parties, now);
+    let mut out = String::from("📇 Whom to contact:\n");
+    for index in parties {
+        match found.get(index) {
+            Some(line) => out.push_str(&format!("   Party {}: {}\n", index, line)),
+            None => out.push_str(&format!(
+                "   Party {}: no contact on file (add one with dkg-party)\n",
+                index
+            )),
+        }
+    }
+    out
+}
+
+/// Core function for `dkg-party`: apply an edit, then show the directory
+///
+/// `storages` are every folder of the wallet holding `group_info.json` (the
+/// root and each party folder); all of them are updated together.
+pub fn party_core(
+    edit: Option<&PartyEdit>,
+    storages: &[&dyn Storage],
+    now: u64,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    let Some(first) = storages.first() else {
+        anyhow::bail!("No group_info.json found; run dkg-info first");
+    };
+    let mut info = load_group_info(*first)?;
+
+    if let Some(edit) = edit {
+        if edit.index == 0 || edit.index > info.total_parties {
+            return Err(crate::error::Error::User(format!(
+                "Party {} is not in this {}-party group",
+                edit.index, info.total_parties
+            ))
+            .into());
+        }
+        if edit.clear {
+            info.directory.remove(&edit.index);
+        } else {
+            let mut entry = info.directory.remove(&edit.index).unwrap_or_default();
+            apply(&mut entry, edit, now)?;
+            if !entry.is_empty() {
+                info.directory.insert(edit.index, entry);
+            }
+        }
+        for storage in storages {
+            let mut folder_info = load_group_info(*storage)?;
+            folder_info.directory = info.directory.clone();
+            storage.write(
+                "group_info.json",
+                serde_json::to_string_pretty(&folder_info)?.as_bytes(),
+            )?;
+        }
+        out.push_str(&format!(
+            "✓ Party {} updated ({} group_info.json)\n\n",
+            edit.index,
+            storages.len()
+        ));
+    }
+
+    let ranks: BTreeMap<u32, u32> = info.parties.iter().map(|p| (p.index, p.rank)).collect();
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    for index in 1..=info.total_parties {
+        let rank = match ranks.get(&index) {
+            Some(rank) if info.hierarchical => format!(" (rank {})", rank),
+            _ => String::new(),
+        };
+        let line = match info.directory.get(&index) {
+            Some(entry) => describe(entry, now),
+            None => "-".to_string(),
+        };
+        out.push_str(&format!("Party {}{}: {}\n", index, rank, line));
+    }
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    if info.directory.is_empty() {
+        out.push_str("\nNo entries yet. Add one with:\n");
+        out.push_str(
+            "  frostdao dkg-party --name <wallet> --index 2 --holder \"Alice\" --contact \"signal:...\"\n",
+        );
+    } else {
+        out.push_str(
+            "\nShare the updated group_info.json (or dkg-group-publish) with the group.\n",
+        );
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&info.directory)?,
+    })
+}
+
+/// CLI wrapper for `dkg-party`
+pub fn party(name: &str, edit: Option<&PartyEdit>) -> Result<()> {
+    let dir = std::path::PathBuf::from(get_state_dir(name));
+    if !dir.is_dir() {
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}",
+            name,
+            dir.display()
+        ))
+        .into());
+    }
+    let mut folders = vec![dir.clone()];
+    folders.extend(doctor::party_dirs(&dir)?.into_iter().map(|(_, path)| path));
+    let mut storages = Vec::new();
+    for folder in folders {
+        if folder.join("group_info.json").exists() {
+            storages.push(FileStorage::new(&folder.to_string_lossy())?);
+        }
+    }
+    let storages: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+    let result = party_core(edit, &storages, health::now())?;
+    println!("Party directory for '{}'\n", name);
+    println!("{}", result.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::keygen::PartyInfo;
+    use crate::storage::MemoryStorage;
+
+    fn wallet() -> MemoryStorage {
+        let storage = MemoryStorage::new();
+        let info = GroupInfo {
+            name: "treasury".to_string(),
+            group_public_key: "aa".to_string(),
+            taproot_address_testnet: "tb1p".to_string(),
+            taproot_address_mainnet: "bc1p".to_string(),
+            threshold: 2,
+            total_parties: 3,
+            hierarchical: false,
+            parties: (1..=3)
+                .map(|index| PartyInfo {
+                    index,
+                    rank: 0,
+                    verification_share: "unavailable".to_string(),
+                    identity: None,
+                })
+                .collect(),
+            directory: BTreeMap::new(),
+        };
+        storage
+            .write("group_info.json", &serde_json::to_vec(&info).unwrap())
+            .unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_party_directory_edits_every_folder() {
+        let (root, party) = (wallet(), wallet());
+        let storages: [&dyn Storage; 2] = [&root, &party];
+        let day = 86_400;
+
+        let edit = PartyEdit {
+            index: 2,
+            holder: Some("Alice".to_string()),
+            contact: Some(" signal:+1 555 0100 ".to_string()),
+            availability: Some("weekdays".to_string()),
+            share_confirmed: true,
+            ..Default::default()
+        };
+        party_core(Some(&edit), &storages, day).unwrap();
+        for storage in storages {
+            let entry = &load_group_info(storage).unwrap().directory[&2];
+            assert_eq!(entry.contact, "signal:+1 555 0100");
+            assert_eq!(entry.share_confirmed_at, Some(day));
+        }
+        assert_eq!(
+            contacts(&root, &[2], 3 * day)[&2],
+            "Alice · signal:+1 555 0100 · weekdays (2 days ago) · share confirmed 2 days ago"
+        );
+
+        // Notes stand in for parties without an entry
+        let mut notes = WalletNotes::default();
+        notes.contacts.insert(3, "bob@example.com".to_string());
+        notes.save(&root).unwrap();
+        let block = whom_to_contact(&root, &[1, 3], day);
+        assert!(block.contains("Party 1: no contact on file"));
+        assert!(block.contains("Party 3: bob@example.com"));
+
+        let bad = PartyEdit {
+            index: 4,
+            ..Default::default()
+        };
+        assert!(party_core(Some(&bad), &storages, day).is_err());
+
+        let clear = PartyEdit {
+            index: 2,
+            clear: true,
+            ..Default::default()
+        };
+        party_core(Some(&clear), &storages, day).unwrap();
+        assert!(load_group_info(&party).unwrap().directory.is_empty());
+    }
+}
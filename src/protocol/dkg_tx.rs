@@ -26,10 +26,37 @@
 //!           ↓
 //!         txid
 //! ```
-
-use crate::btc::transaction::{broadcast_transaction, fetch_fee_estimates, fetch_utxos};
-use crate::protocol::keygen::{get_state_dir, HtssMetadata};
+//!
+//! Without the `network` feature only the signer side (nonce, share) is built.
+
+#![cfg_attr(not(feature = "network"), allow(unused_imports))]
+
+use crate::btc::amount::format_amount;
+#[cfg(feature = "network")]
+use crate::btc::broadcast::EndpointResult;
+#[cfg(feature = "network")]
+use crate::btc::inscriptions::filter_protected_utxos;
+#[cfg(feature = "network")]
+use crate::btc::rebroadcast;
+use crate::btc::script_index::ScriptIndex;
+use crate::btc::spend_plan::{self, SpendPath};
+#[cfg(feature = "network")]
+use crate::btc::transaction::{explorer_tx_url, ChainBackend, MempoolBackend};
+use crate::crypto::secret::serialize_secret;
+use crate::notify::{self, NotifyEvent};
+use crate::protocol::audit;
+use crate::protocol::device;
+use crate::protocol::directory;
+use crate::protocol::health;
+use crate::protocol::identity::{self, Authenticated};
+use crate::protocol::keygen::{get_state_dir, require_secp256k1, HtssMetadata};
+use crate::protocol::payload::{
+    self, check_signer_set, decode_hex_field, parse_payloads, share_index, Expected,
+};
+use crate::protocol::session_report::SessionReport;
 use crate::protocol::signing::NonceOutput;
+use crate::protocol::wallet::Retirement;
+use crate::protocol::weighted;
 use crate::storage::{FileStorage, Storage};
 use crate::CommandResult;
 use anyhow::{Context, Result};
@@ -39,8 +66,11 @@ use bitcoin::hashes::Hash;
 use bitcoin::key::XOnlyPublicKey;
 use bitcoin::script::ScriptBuf;
 use bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
+use bitcoin::taproot::{ControlBlock, LeafVersion, TapLeafHash};
 use bitcoin::transaction::Version;
 use bitcoin::{Amount, Network, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+#[cfg(feature = "network")]
+use rayon::prelude::*;
 use schnorr_fun::frost::{self, PairedSecretShare, SharedKey};
 use schnorr_fun::Message;
 use secp256kfun::prelude::*;
@@ -54,12 +84,25 @@ use std::str::FromStr;
 // ============================================================================
 
 // Use shared tagged_hash from crypto helpers
-use crate::crypto::helpers::tagged_hash;
+use crate::crypto::helpers::{
+    aggregate_signature_shares, aggregate_signature_shares_hex, cached_lagrange_coefficients,
+    tagged_hash,
+};
 
-/// Compute the taptweak for a given internal public key (no script tree)
-/// tweak = tagged_hash("TapTweak", internal_pubkey)
-fn compute_taptweak(internal_pubkey: &[u8; 32]) -> Scalar<Public, Zero> {
-    let tweak_bytes = tagged_hash("TapTweak", internal_pubkey);
+/// Compute the taptweak for a given internal public key
+/// tweak = tagged_hash("TapTweak", internal_pubkey || merkle_root)
+///
+/// `merkle_root` is the script tree root for a script-augmented output
+/// (see btc::taproot_tree); None for a key-path only output.
+fn compute_taptweak(
+    internal_pubkey: &[u8; 32],
+    merkle_root: Option<&[u8; 32]>,
+) -> Scalar<Public, Zero> {
+    let mut tweak_input = internal_pubkey.to_vec();
+    if let Some(root) = merkle_root {
+        tweak_input.extend_from_slice(root);
+    }
+    let tweak_bytes = tagged_hash("TapTweak", &tweak_input);
     Scalar::from_bytes(tweak_bytes).expect("taptweak should be valid scalar")
 }
 
@@ -73,9 +116,12 @@ fn compute_taptweak(internal_pubkey: &[u8; 32]) -> Scalar<Public, Zero> {
 /// - If parity_flip is false: signature = σ + e*t (add tweak contribution)
 /// - If parity_flip is true: signature = σ - e*t (subtract tweak contribution)
 ///   AND secret shares must be negated before signing
-fn compute_tweaked_pubkey(internal_pubkey: &Point<EvenY>) -> (Point<EvenY>, bool) {
+pub(crate) fn compute_tweaked_pubkey(
+    internal_pubkey: &Point<EvenY>,
+    merkle_root: Option<&[u8; 32]>,
+) -> (Point<EvenY>, bool) {
     let pubkey_bytes: [u8; 32] = internal_pubkey.to_xonly_bytes();
-    let tweak = compute_taptweak(&pubkey_bytes);
+    let tweak = compute_taptweak(&pubkey_bytes, merkle_root);
     let tweaked = g!({ *internal_pubkey } + tweak * G).normalize();
     // Convert to NonZero and then to EvenY, tracking whether negation occurred
     let tweaked_nonzero = tweaked
@@ -108,10 +154,236 @@ pub struct BuildTxOutput {
     pub fee_sats: u64,
     /// Network
     pub network: String,
+    /// Script tree root of the spent output (absent for key-path only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merkle_root: Option<String>,
+    /// What the payment is for, as given to `--memo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Outputs spent by each input, so signers can recompute the sighash
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prevouts: Vec<SpentOutput>,
+    /// Script leaf the transaction spends through (absent for the key path)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leaf: Option<SpendLeaf>,
     #[serde(rename = "type")]
     pub event_type: String,
 }
 
+/// Output spent by a session's transaction
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SpentOutput {
+    pub value: u64,
+    /// Locking script (hex)
+    pub script_pubkey: String,
+}
+
+impl SpentOutput {
+    pub fn to_txout(&self) -> Result<TxOut> {
+        Ok(TxOut {
+            value: Amount::from_sat(self.value),
+            script_pubkey: ScriptBuf::from_bytes(
+                hex::decode(&self.script_pubkey).context("Invalid spent output script hex")?,
+            ),
+        })
+    }
+}
+
+/// BIP-341 key-path sighash of the first input, the one a session signs
+pub fn key_spend_sighash(tx: &Transaction, prevouts: &[SpentOutput]) -> Result<[u8; 32]> {
+    let prevouts = prevouts
+        .iter()
+        .map(SpentOutput::to_txout)
+        .collect::<Result<Vec<_>>>()?;
+    let sighash = SighashCache::new(tx)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), TapSighashType::Default)
+        .context("Failed to compute sighash")?;
+    Ok(*sighash.as_byte_array())
+}
+
+/// Script leaf a session spends through instead of the key path, copied
+/// from the source address's taproot tree record
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SpendLeaf {
+    /// Position in the tree record (see dkg-list-script-addresses)
+    pub index: usize,
+    /// Leaf script (hex)
+    pub script: String,
+    /// Leaf version (0xc0 for tapscript)
+    pub leaf_version: u8,
+    /// Control block proving the leaf is in the spent output's tree (hex)
+    pub control_block: String,
+    /// Preimage for a hash lock in the leaf (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preimage: Option<String>,
+}
+
+impl SpendLeaf {
+    /// Leaf `index` of `tree`
+    pub fn from_tree(
+        tree: &crate::btc::taproot_tree::TaprootTreeRecord,
+        index: usize,
+        preimage: Option<&str>,
+    ) -> Result<Self> {
+        let leaf = tree.leaves.get(index).with_context(|| {
+            crate::error::Error::User(format!(
+                "{} has no leaf {} (it has {})",
+                tree.address,
+                index,
+                tree.leaves.len()
+            ))
+        })?;
+        let preimage = preimage
+            .map(|p| {
+                hex::decode(p.trim())
+                    .map(hex::encode)
+                    .context("Preimage must be hex")
+            })
+            .transpose()?;
+        Ok(Self {
+            index,
+            script: leaf.script.clone(),
+            leaf_version: leaf.leaf_version,
+            control_block: leaf.control_block.clone(),
+            preimage,
+        })
+    }
+
+    pub fn script(&self) -> Result<ScriptBuf> {
+        Ok(ScriptBuf::from_bytes(
+            hex::decode(&self.script).context("Invalid leaf script hex")?,
+        ))
+    }
+
+    fn control_block(&self) -> Result<ControlBlock> {
+        ControlBlock::decode(
+            &hex::decode(&self.control_block).context("Invalid control block hex")?,
+        )
+        .map_err(|e| anyhow::anyhow!("Invalid control block: {:?}", e))
+    }
+
+    fn leaf_hash(&self) -> Result<TapLeafHash> {
+        let version = LeafVersion::from_consensus(self.leaf_version)
+            .map_err(|e| anyhow::anyhow!("Invalid leaf version: {:?}", e))?;
+        Ok(TapLeafHash::from_script(&self.script()?, version))
+    }
+
+    /// Refuse the leaf unless its control block commits it to the P2TR
+    /// output `script_pubkey`
+    pub fn check_commitment(&self, script_pubkey: &bitcoin::Script) -> Result<()> {
+        let output_key = script_pubkey
+            .is_p2tr()
+            .then(|| XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..]))
+            .transpose()?
+            .context("Script-path spends need a taproot output")?;
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        if !self
+            .control_block()?
+            .verify_taproot_commitment(&secp, output_key, &self.script()?)
+        {
+            anyhow::bail!(crate::error::Error::Protocol(format!(
+                "Leaf {} is not in the script tree of the output it spends; refusing to sign",
+                self.index
+            )));
+        }
+        Ok(())
+    }
+
+    /// Script-path witness: what the leaf consumes with `signature` from
+    /// `key`, then the script and the control block
+    pub fn witness(&self, key: &[u8; 32], signature: &[u8; 64]) -> Result<Witness> {
+        let script = self.script()?;
+        let preimage = self
+            .preimage
+            .as_deref()
+            .map(hex::decode)
+            .transpose()
+            .context("Invalid preimage hex")?;
+        let mut witness = Witness::new();
+        for item in spend_plan::leaf_stack(&script, key, signature, preimage.as_deref())? {
+            witness.push(item);
+        }
+        witness.push(script.as_bytes());
+        witness.push(hex::decode(&self.control_block).context("Invalid control block hex")?);
+        Ok(witness)
+    }
+}
+
+/// BIP-341 sighash of the first input, the one a session signs: the key
+/// path's, or the leaf's for a script-path spend
+pub fn spend_sighash(
+    tx: &Transaction,
+    prevouts: &[SpentOutput],
+    leaf: Option<&SpendLeaf>,
+) -> Result<[u8; 32]> {
+    let Some(leaf) = leaf else {
+        return key_spend_sighash(tx, prevouts);
+    };
+    let prevouts = prevouts
+        .iter()
+        .map(SpentOutput::to_txout)
+        .collect::<Result<Vec<_>>>()?;
+    let sighash = SighashCache::new(tx)
+        .taproot_script_spend_signature_hash(
+            0,
+            &Prevouts::All(&prevouts),
+            leaf.leaf_hash()?,
+            TapSighashType::Default,
+        )
+        .context("Failed to compute sighash")?;
+    Ok(*sighash.as_byte_array())
+}
+
+/// The BIP-340 signature in the first input's witness: the only item of a
+/// key-path witness, or the one signature a leaf consumed
+pub fn witness_signature(tx: &Transaction, leaf: Option<&SpendLeaf>) -> Option<Vec<u8>> {
+    let witness = &tx.input.first()?.witness;
+    match leaf {
+        None => witness.nth(0).map(<[u8]>::to_vec),
+        Some(_) => witness
+            .iter()
+            .take(witness.len().saturating_sub(2))
+            .find(|item| item.len() == 64)
+            .map(<[u8]>::to_vec),
+    }
+}
+
+/// Key a session's signature verifies under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionKey<'a> {
+    /// Key path: the group key tweaked with the script tree root (None for
+    /// a key-path only output)
+    Output(Option<&'a [u8; 32]>),
+    /// Script path: the untweaked group key, which the leaf checks
+    Group,
+}
+
+impl<'a> SessionKey<'a> {
+    /// Output key unless the session spends a leaf
+    pub fn of(merkle_root: Option<&'a [u8; 32]>, leaf: Option<&SpendLeaf>) -> Self {
+        match leaf {
+            Some(_) => SessionKey::Group,
+            None => SessionKey::Output(merkle_root),
+        }
+    }
+
+    /// The key, whether it was negated for even Y, and the tweak added to
+    /// the group key
+    pub(crate) fn resolve(
+        self,
+        group_key: &Point<EvenY>,
+    ) -> (Point<EvenY>, bool, Scalar<Public, Zero>) {
+        match self {
+            SessionKey::Output(merkle_root) => {
+                let (key, parity_flip) = compute_tweaked_pubkey(group_key, merkle_root);
+                let tweak = compute_taptweak(&group_key.to_xonly_bytes(), merkle_root);
+                (key, parity_flip, tweak)
+            }
+            SessionKey::Group => (*group_key, false, Scalar::zero()),
+        }
+    }
+}
+
 /// Output from dkg-sign command
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DkgSignatureShareOutput {
@@ -125,8 +397,35 @@ pub struct DkgSignatureShareOutput {
     pub sighash: String,
     /// Signature share (scalar hex)
     pub signature_share: String,
+    /// Party that combines and broadcasts, per [`SessionRoles`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregator: Option<u32>,
+    /// Payload protocol version (see protocol::payload); absent means v1
+    #[serde(
+        default = "crate::protocol::payload::legacy_version",
+        skip_serializing_if = "crate::protocol::payload::is_legacy_version"
+    )]
+    pub protocol_version: u32,
     #[serde(rename = "type")]
     pub event_type: String,
+    /// Sender's signature over this payload (see protocol::identity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+}
+
+impl Authenticated for DkgSignatureShareOutput {
+    fn signer_index(&self) -> u32 {
+        self.party_index
+    }
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+    fn set_auth(&mut self, auth: String) {
+        self.auth = Some(auth);
+    }
 }
 
 /// Output from dkg-broadcast command
@@ -140,14 +439,97 @@ pub struct BroadcastOutput {
     pub network: String,
     /// Explorer URL
     pub explorer_url: String,
+    /// Answer of each broadcast endpoint
+    #[cfg(feature = "network")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<EndpointResult>,
     #[serde(rename = "type")]
     pub event_type: String,
 }
 
+/// Who does what in a signing session, derived from the signer set alone
+///
+/// The signer with the lowest index combines the shares and broadcasts.
+/// Every signer sees the same nonces, so each works this out independently
+/// and automated parties never wait on each other to decide who combines.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SessionRoles {
+    /// Signing parties, ascending
+    pub signers: Vec<u32>,
+    /// Party that combines the shares and broadcasts
+    pub aggregator: u32,
+}
+
+impl SessionRoles {
+    pub fn from_signers(signers: impl IntoIterator<Item = u32>) -> Result<Self> {
+        let mut signers: Vec<u32> = signers.into_iter().collect();
+        signers.sort_unstable();
+        signers.dedup();
+        let aggregator = *signers.first().context("Session has no signers")?;
+        Ok(Self {
+            signers,
+            aggregator,
+        })
+    }
+
+    pub fn is_aggregator(&self, party_index: u32) -> bool {
+        self.aggregator == party_index
+    }
+
+    /// Roles recorded in the local session file by dkg-sign, if it ran here
+    pub fn load(storage: &dyn Storage, session_id: &str) -> Result<Option<Self>> {
+        let session_file = format!("dkg_session_{}.json", session_id);
+        if !storage.exists(&session_file) {
+            return Ok(None);
+        }
+        let session_data: serde_json::Value =
+            serde_json::from_slice(&storage.read(&session_file)?)?;
+        if session_data["aggregator"].is_null() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_value(session_data)?))
+    }
+
+    /// Add the roles (and the memo, if new) to the session file, creating it
+    /// on signers that did not build the transaction so any of them can
+    /// broadcast
+    fn record(
+        &self,
+        storage: &dyn Storage,
+        session_id: &str,
+        sighash_hex: &str,
+        merkle_root: Option<&[u8; 32]>,
+        leaf: Option<&SpendLeaf>,
+        memo: Option<&str>,
+    ) -> Result<()> {
+        let session_file = format!("dkg_session_{}.json", session_id);
+        let mut session_data = if storage.exists(&session_file) {
+            serde_json::from_slice(&storage.read(&session_file)?)?
+        } else {
+            serde_json::json!({
+                "session_id": session_id,
+                "sighash": sighash_hex,
+                "merkle_root": merkle_root.map(hex::encode),
+                "leaf": leaf,
+            })
+        };
+        session_data["signers"] = serde_json::json!(self.signers);
+        session_data["aggregator"] = serde_json::json!(self.aggregator);
+        if let (Some(memo), true) = (memo, session_data["memo"].is_null()) {
+            session_data["memo"] = serde_json::json!(memo);
+        }
+        storage.write(
+            &session_file,
+            serde_json::to_string_pretty(&session_data)?.as_bytes(),
+        )
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+#[cfg(feature = "network")]
 fn network_name(network: Network) -> &'static str {
     match network {
         Network::Bitcoin => "mainnet",
@@ -159,7 +541,8 @@ fn network_name(network: Network) -> &'static str {
 }
 
 /// Generate a session ID based on transaction details
-fn generate_session_id(to_address: &str, amount: u64) -> String {
+#[cfg(feature = "network")]
+pub(crate) fn generate_session_id(to_address: &str, amount: u64) -> String {
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
@@ -169,17 +552,276 @@ fn generate_session_id(to_address: &str, amount: u64) -> String {
     hex::encode(&hash[..8]) // First 8 bytes for readability
 }
 
+/// Parse a 32-byte taproot merkle root from hex
+pub fn parse_merkle_root(merkle_root_hex: &str) -> Result<[u8; 32]> {
+    hex::decode(merkle_root_hex)
+        .context("Invalid merkle root hex")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Merkle root must be 32 bytes"))
+}
+
+/// Merkle root recorded in the local session file by dkg-build-tx, if any
+pub(crate) fn session_merkle_root(
+    storage: &dyn Storage,
+    session_id: &str,
+) -> Result<Option<[u8; 32]>> {
+    let session_file = format!("dkg_session_{}.json", session_id);
+    if !storage.exists(&session_file) {
+        return Ok(None);
+    }
+    let session_data: serde_json::Value = serde_json::from_slice(&storage.read(&session_file)?)?;
+    session_data["merkle_root"]
+        .as_str()
+        .map(parse_merkle_root)
+        .transpose()
+}
+
+/// Script leaf the local session file says the session spends, if any
+pub(crate) fn session_leaf(storage: &dyn Storage, session_id: &str) -> Result<Option<SpendLeaf>> {
+    let session_file = format!("dkg_session_{}.json", session_id);
+    if !storage.exists(&session_file) {
+        return Ok(None);
+    }
+    let session_data: serde_json::Value = serde_json::from_slice(&storage.read(&session_file)?)?;
+    match session_data.get("leaf") {
+        Some(leaf) if !leaf.is_null() => Ok(Some(
+            serde_json::from_value(leaf.clone())
+                .with_context(|| format!("{} has an invalid 'leaf'", session_file))?,
+        )),
+        _ => Ok(None),
+    }
+}
+
+/// Longest memo a signing session accepts, in characters
+pub const MAX_MEMO_LEN: usize = 200;
+
+/// `memo` trimmed, or `None` if blank; it must fit on one line since every
+/// signer's terminal shows it
+pub fn check_memo(memo: &str) -> Result<Option<String>> {
+    let memo = memo.trim();
+    if memo.chars().count() > MAX_MEMO_LEN {
+        anyhow::bail!(crate::error::Error::User(format!(
+            "Memo is longer than {} characters",
+            MAX_MEMO_LEN
+        )));
+    }
+    if memo.chars().any(char::is_control) {
+        anyhow::bail!(crate::error::Error::User(
+            "Memo must be a single line of plain text".to_string()
+        ));
+    }
+    Ok((!memo.is_empty()).then(|| memo.to_string()))
+}
+
+/// Memo recorded in the local session file, if any
+pub fn session_memo(storage: &dyn Storage, session_id: &str) -> Result<Option<String>> {
+    let session_file = format!("dkg_session_{}.json", session_id);
+    if !storage.exists(&session_file) {
+        return Ok(None);
+    }
+    let session_data: serde_json::Value = serde_json::from_slice(&storage.read(&session_file)?)?;
+    Ok(session_data["memo"].as_str().map(str::to_string))
+}
+
+/// The session's memo: the local one, else the one the nonces carry from
+/// the builder's party. Memos that disagree are pointed out, not trusted.
+fn signing_memo(
+    storage: &dyn Storage,
+    session_id: &str,
+    nonce_outputs: &[NonceOutput],
+    out: &mut String,
+) -> Result<Option<String>> {
+    let local = session_memo(storage, session_id)?;
+    let mut carried: Vec<(u32, String)> = Vec::new();
+    for nonce in nonce_outputs {
+        if let Some(memo) = nonce.memo.as_deref().map(check_memo).transpose()?.flatten() {
+            carried.push((nonce.party_index, memo));
+        }
+    }
+    let memo = local.or_else(|| match carried.as_slice() {
+        [(_, first), rest @ ..] if rest.iter().all(|(_, m)| m == first) => Some(first.clone()),
+        _ => None,
+    });
+    for (party, other) in &carried {
+        if memo.as_ref() != Some(other) {
+            out.push_str(&format!(
+                "⚠️  Party {} labels this session \"{}\"; check with them what it pays\n",
+                party, other
+            ));
+        }
+    }
+    Ok(memo)
+}
+
+/// The dkg-build-tx output recorded in the local session file, if this
+/// party built the transaction
+pub fn session_build_output(
+    storage: &dyn Storage,
+    session_id: &str,
+) -> Result<Option<BuildTxOutput>> {
+    let session_file = format!("dkg_session_{}.json", session_id);
+    if !storage.exists(&session_file) {
+        return Ok(None);
+    }
+    let mut session_data: serde_json::Value =
+        serde_json::from_slice(&storage.read(&session_file)?)?;
+    if session_data["unsigned_tx"].is_null() {
+        return Ok(None);
+    }
+    session_data["type"] = serde_json::json!("dkg_build_tx");
+    Ok(Some(
+        serde_json::from_value(session_data)
+            .with_context(|| format!("{} is damaged", session_file))?,
+    ))
+}
+
+/// Decode a dkg-build-tx output for a signer and refuse it unless it adds up
+///
+/// Lists every output (destination or change), checks the transaction pays
+/// the stated amount, that every input spends from the stated address and
+/// that the sighash is this transaction's. The spent outputs must be
+/// included: BIP-341 sighashes commit to their amounts and scripts, so they
+/// are what makes the fee shown the real one.
+///
+/// Change is only recognised at scripts the signer derives itself (its
+/// [`ScriptIndex`]), so a coordinator cannot redirect it by naming a foreign
+/// address as the source.
+pub fn review_unsigned_tx(
+    tx: &BuildTxOutput,
+    storage: &dyn Storage,
+    out: &mut String,
+) -> Result<()> {
+    let network = crate::btc::hd_address::parse_network(&tx.network)?;
+    let unsigned: Transaction = bitcoin::consensus::encode::deserialize_hex(&tx.unsigned_tx)
+        .context("Invalid unsigned transaction")?;
+    let destination = Address::from_str(&tx.to_address)?
+        .require_network(network)
+        .context("Destination address is for a different network")?;
+    let source = Address::from_str(&tx.from_address)?
+        .require_network(network)
+        .context("Source address is for a different network")?;
+    let mut own = ScriptIndex::load(storage)?;
+    if let Some(root) = &tx.merkle_root {
+        own = own.with_root_tree(parse_merkle_root(root)?)?;
+    }
+    let Some(source_owner) = own.owner(&source.script_pubkey()) else {
+        anyhow::bail!(crate::error::Error::Protocol(format!(
+            "{} is not an address of this wallet; refusing to sign",
+            tx.from_address
+        )));
+    };
+
+    out.push_str(&format!(
+        "Transaction to approve ({} inputs):\n",
+        unsigned.input.len()
+    ));
+    let mut pays_destination = false;
+    for (i, output) in unsigned.output.iter().enumerate() {
+        let sats = output.value.to_sat();
+        let label = if output.script_pubkey == destination.script_pubkey() {
+            pays_destination |= sats == tx.amount_sats;
+            "destination".to_string()
+        } else if let Some(owner) = own.owner(&output.script_pubkey) {
+            format!("change ({})", owner.origin)
+        } else {
+            let payee = Address::from_script(&output.script_pubkey, network)
+                .map(|address| address.to_string())
+                .unwrap_or_else(|_| hex::encode(output.script_pubkey.as_bytes()));
+            anyhow::bail!(crate::error::Error::Protocol(format!(
+                "Output #{} pays {} sats to {}, which is neither the destination nor an \
+                 address of this wallet; refusing to sign",
+                i, sats, payee
+            )));
+        };
+        out.push_str(&format!("   #{} {:>12} sats  {}\n", i, sats, label));
+    }
+    if !pays_destination {
+        anyhow::bail!(
+            "Unsigned transaction does not pay {} sats to {}",
+            tx.amount_sats,
+            tx.to_address
+        );
+    }
+    out.push_str(&format!("   Destination: {}\n", tx.to_address));
+    out.push_str(&format!(
+        "   Change:      {} ({}, derived locally)\n",
+        tx.from_address, source_owner.origin
+    ));
+    if let Some(memo) = tx.memo.as_deref().map(check_memo).transpose()?.flatten() {
+        out.push_str(&format!("   Memo:        {}\n", memo));
+    }
+
+    if tx.prevouts.is_empty() {
+        anyhow::bail!(crate::error::Error::Protocol(
+            "The transaction doesn't list the outputs it spends, so neither its fee nor \
+             its sighash can be checked; refusing to sign (rebuild it with dkg-build-tx)"
+                .to_string()
+        ));
+    }
+    if tx.prevouts.len() != unsigned.input.len() {
+        anyhow::bail!(crate::error::Error::Protocol(format!(
+            "Transaction has {} inputs but {} spent outputs were given",
+            unsigned.input.len(),
+            tx.prevouts.len()
+        )));
+    }
+    let source_script = hex::encode(source.script_pubkey().as_bytes());
+    if let Some(i) = tx
+        .prevouts
+        .iter()
+        .position(|p| !p.script_pubkey.eq_ignore_ascii_case(&source_script))
+    {
+        anyhow::bail!(crate::error::Error::Protocol(format!(
+            "Input #{} does not spend from {}; refusing to sign",
+            i, tx.from_address
+        )));
+    }
+    let spent: u64 = tx.prevouts.iter().map(|p| p.value).sum();
+    let paid: u64 = unsigned.output.iter().map(|o| o.value.to_sat()).sum();
+    let fee = spent.checked_sub(paid).with_context(|| {
+        format!(
+            "Transaction pays out {} sats but only spends {}",
+            paid, spent
+        )
+    })?;
+    out.push_str(&format!("   Fee:         {} sats\n", fee));
+    if let Some(leaf) = &tx.leaf {
+        leaf.check_commitment(&source.script_pubkey())?;
+        out.push_str(&format!(
+            "   Spend path:  leaf {} ({})\n",
+            leaf.index,
+            leaf.script()?.to_asm_string()
+        ));
+    }
+    let sighash = hex::encode(spend_sighash(&unsigned, &tx.prevouts, tx.leaf.as_ref())?);
+    if !sighash.eq_ignore_ascii_case(&tx.sighash) {
+        anyhow::bail!(crate::error::Error::Protocol(format!(
+            "Sighash {} is not this transaction's ({}); refusing to sign",
+            tx.sighash, sighash
+        )));
+    }
+    out.push_str("   ✓ Sighash recomputed from the transaction\n\n");
+    Ok(())
+}
+
 // ============================================================================
 // Build Unsigned Transaction
 // ============================================================================
 
 /// Build an unsigned transaction and compute sighash for DKG signing
+#[cfg(feature = "network")]
+#[allow(clippy::too_many_arguments)]
 pub fn build_unsigned_tx(
     wallet_name: &str,
     to_address: &str,
     amount_sats: u64,
     fee_rate: Option<u64>,
     network: Network,
+    allow_inscribed: bool,
+    from_script_address: Option<&str>,
+    memo: Option<&str>,
+    spend_path: Option<SpendPath>,
+    preimage: Option<&str>,
 ) -> Result<()> {
     let state_dir = get_state_dir(wallet_name);
     let storage = FileStorage::new(&state_dir)?;
@@ -189,33 +831,110 @@ pub fn build_unsigned_tx(
         amount_sats,
         fee_rate,
         network,
+        allow_inscribed,
+        from_script_address,
+        memo,
+        spend_path,
+        preimage,
         &storage,
     )?;
 
     println!("{}", cmd_result.output);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Share this with all signing parties:");
-    println!("{}\n", cmd_result.result);
+    println!(
+        "{}\n",
+        crate::protocol::compact::present(&cmd_result.result)
+    );
+    if let Ok(metadata) =
+        serde_json::from_slice::<HtssMetadata>(&storage.read("htss_metadata.json")?)
+    {
+        let others: Vec<u32> = metadata
+            .party_ranks
+            .keys()
+            .copied()
+            .filter(|&index| index != metadata.my_index)
+            .collect();
+        print!(
+            "{}",
+            directory::whom_to_contact(&storage, &others, health::now())
+        );
+    }
 
     Ok(())
 }
 
 /// Core function for building unsigned transaction
+///
+/// UTXOs carrying inscriptions, runes or rare sats are frozen unless
+/// `allow_inscribed` is set. With `from_script_address`, the inputs come from a
+/// script address registered on the wallet's root key (dkg-script-address) and
+/// are spent along `spend_path`: the key path with its merkle root in the
+/// tweak, or a leaf of its tree (with `preimage` for a hash lock). Without
+/// one, the cheapest usable path is taken (see [`spend_plan`]). `memo` says
+/// what the payment is for; it is kept in the session file and shown to every
+/// signer.
+#[cfg(feature = "network")]
+#[allow(clippy::too_many_arguments)]
 pub fn build_unsigned_tx_core(
     wallet_name: &str,
     to_address: &str,
     amount_sats: u64,
     fee_rate: Option<u64>,
     network: Network,
+    allow_inscribed: bool,
+    from_script_address: Option<&str>,
+    memo: Option<&str>,
+    spend_path: Option<SpendPath>,
+    preimage: Option<&str>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    build_unsigned_tx_core_with_backend(
+        wallet_name,
+        to_address,
+        amount_sats,
+        fee_rate,
+        network,
+        allow_inscribed,
+        from_script_address,
+        memo,
+        spend_path,
+        preimage,
+        &MempoolBackend::new(network),
+        storage,
+    )
+}
+
+/// [`build_unsigned_tx_core`], reading UTXOs and fee rates from `backend`
+#[cfg(feature = "network")]
+#[allow(clippy::too_many_arguments)]
+pub fn build_unsigned_tx_core_with_backend(
+    wallet_name: &str,
+    to_address: &str,
+    amount_sats: u64,
+    fee_rate: Option<u64>,
+    network: Network,
+    allow_inscribed: bool,
+    from_script_address: Option<&str>,
+    memo: Option<&str>,
+    spend_path: Option<SpendPath>,
+    preimage: Option<&str>,
+    backend: &dyn ChainBackend,
     storage: &dyn Storage,
 ) -> Result<CommandResult> {
     let mut out = String::new();
+    let memo = memo.map(check_memo).transpose()?.flatten();
 
     out.push_str("DKG Transaction Builder\n\n");
     out.push_str(
         "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
     );
 
+    require_secp256k1(storage)?;
+    if let Some(retired) = Retirement::load(storage)? {
+        out.push_str(&retired.warning());
+    }
+
     // Load DKG shared key
     let shared_key_bytes = storage
         .read("shared_key.bin")
@@ -230,8 +949,44 @@ pub fn build_unsigned_tx_core(
 
     // Get our address
     let xonly_pubkey = XOnlyPublicKey::from_slice(&pubkey_bytes)?;
+    let source_tree = match from_script_address {
+        Some(address) => {
+            let tree = crate::btc::taproot_tree::load_taproot_trees(storage)?
+                .into_values()
+                .find(|tree| tree.address == address)
+                .with_context(|| {
+                    format!(
+                        "{} is not a script address of this wallet (see dkg-list-script-addresses)",
+                        address
+                    )
+                })?;
+            if tree.derivation_path.is_some() || tree.internal_key()? != xonly_pubkey {
+                anyhow::bail!(
+                    "{} is built on an HD-derived key; only script addresses on the wallet's root key can be spent here",
+                    address
+                );
+            }
+            Some(tree)
+        }
+        None if matches!(spend_path, Some(SpendPath::Leaf(_))) => {
+            anyhow::bail!(crate::error::Error::User(
+                "Only script addresses have leaves to spend; pass --from <script address>"
+                    .to_string()
+            ))
+        }
+        None => None,
+    };
+    let merkle_root = match &source_tree {
+        Some(tree) => tree.merkle_root_bytes()?,
+        None => None,
+    };
     let secp = bitcoin::secp256k1::Secp256k1::new();
-    let from_address = Address::p2tr(&secp, xonly_pubkey, None, network);
+    let from_address = Address::p2tr(
+        &secp,
+        xonly_pubkey,
+        merkle_root.map(bitcoin::TapNodeHash::from_byte_array),
+        network,
+    );
 
     // Parse destination address
     let dest_address = Address::from_str(to_address)
@@ -240,14 +995,17 @@ pub fn build_unsigned_tx_core(
         .context("Address network mismatch")?;
 
     out.push_str(&format!("Wallet: {}\n", wallet_name));
+    if let Some(memo) = &memo {
+        out.push_str(&format!("Memo: {}\n", memo));
+    }
     out.push_str(&format!("Network: {}\n", network_name(network)));
     out.push_str(&format!("From: {}\n", from_address));
     out.push_str(&format!("To: {}\n", dest_address));
-    out.push_str(&format!("Amount: {} sats\n\n", amount_sats));
+    out.push_str(&format!("Amount: {}\n\n", format_amount(amount_sats)));
 
     // Fetch UTXOs
     out.push_str("Fetching UTXOs...\n");
-    let utxos = fetch_utxos(&from_address.to_string(), network)?;
+    let utxos = backend.utxos(&from_address.to_string())?;
 
     if utxos.is_empty() {
         anyhow::bail!("No UTXOs found. Please fund the DKG address first.");
@@ -258,24 +1016,62 @@ pub fn build_unsigned_tx_core(
         anyhow::bail!("No confirmed UTXOs. Wait for confirmations.");
     }
 
+    let confirmed_utxos =
+        filter_protected_utxos(confirmed_utxos, network, allow_inscribed, &mut out)?;
+    if confirmed_utxos.is_empty() {
+        anyhow::bail!("No spendable UTXOs left after freezing inscribed outputs.");
+    }
+
     let total_available: u64 = confirmed_utxos.iter().map(|u| u.value).sum();
     out.push_str(&format!("Available balance: {} sats\n", total_available));
 
+    // A script address is spent the way the planner picks (or the caller
+    // names), checked against the tip and the coins' confirmations
+    let (spend_leaf, witness_weight) = match &source_tree {
+        Some(tree) => {
+            let state = spend_plan::spend_state(
+                tree,
+                confirmed_utxos.iter().copied(),
+                backend.tip_height().ok(),
+                preimage,
+                storage,
+            )?;
+            let options = spend_plan::plan_paths(tree, &state)?;
+            let selected = spend_plan::select_path(&options, spend_path)?;
+            out.push_str(&format!(
+                "Spend path: {} ({} WU witness)\n",
+                selected.description, selected.witness_weight
+            ));
+            let leaf = match selected.path.parse()? {
+                SpendPath::KeyPath => None,
+                SpendPath::Leaf(index) => Some(SpendLeaf::from_tree(tree, index, preimage)?),
+            };
+            (leaf, selected.witness_weight)
+        }
+        None => (None, spend_plan::KEY_PATH_WITNESS_WEIGHT),
+    };
+    let (lock_height, relative_blocks) = match &spend_leaf {
+        Some(leaf) => spend_plan::leaf_locks(&leaf.script()?)?,
+        None => (None, None),
+    };
+
     // Get fee rate
-    let fee_estimates = fetch_fee_estimates(network)?;
+    let fee_estimates = backend.fee_estimates()?;
     let fee_rate = fee_rate.unwrap_or(fee_estimates.half_hour_fee);
     out.push_str(&format!("Fee rate: {} sats/vbyte\n", fee_rate));
 
-    // Estimate tx size
-    let estimated_vsize: u64 = 10 + (confirmed_utxos.len() as u64 * 58) + (2 * 43);
+    // Estimate tx size: outpoint, sequence and empty scriptSig, plus the
+    // spend path's witness
+    let input_vsize = 41 + witness_weight.div_ceil(4);
+    let estimated_vsize: u64 = 10 + (confirmed_utxos.len() as u64 * input_vsize) + (2 * 43);
     let estimated_fee = estimated_vsize * fee_rate;
 
     if total_available < amount_sats + estimated_fee {
-        anyhow::bail!(
-            "Insufficient funds. Need {} sats, have {} sats",
-            amount_sats + estimated_fee,
-            total_available
-        );
+        return Err(crate::error::Error::InsufficientFunds {
+            needed: amount_sats + estimated_fee,
+            available: total_available,
+        }
+        .into());
     }
 
     // Build transaction inputs
@@ -289,7 +1085,9 @@ pub fn build_unsigned_tx_core(
         tx_inputs.push(TxIn {
             previous_output: outpoint,
             script_sig: ScriptBuf::new(),
-            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            // A relative lock is the sequence; an absolute one only needs it non-final
+            sequence: relative_blocks
+                .map_or(Sequence::ENABLE_RBF_NO_LOCKTIME, Sequence::from_height),
             witness: Witness::new(),
         });
 
@@ -299,6 +1097,18 @@ pub fn build_unsigned_tx_core(
         });
     }
 
+    // Consult the persisted taproot tree for the spent output, so the session
+    // carries the same spend info (merkle root, control blocks) it was funded with
+    let taproot_tree =
+        crate::btc::taproot_tree::find_taproot_tree(storage, &from_address.script_pubkey())?;
+    if let Some(tree) = &taproot_tree {
+        out.push_str(&format!(
+            "Taproot tree: {} ({} leaves)\n",
+            tree.description,
+            tree.leaves.len()
+        ));
+    }
+
     // Build outputs
     let selected_amount: u64 = confirmed_utxos.iter().map(|u| u.value).sum();
     let mut tx_outputs = Vec::new();
@@ -321,19 +1131,23 @@ pub fn build_unsigned_tx_core(
     // Create unsigned transaction
     let tx = Transaction {
         version: Version::TWO,
-        lock_time: LockTime::ZERO,
+        lock_time: match lock_height {
+            Some(height) => LockTime::from_height(height)?,
+            None => LockTime::ZERO,
+        },
         input: tx_inputs,
         output: tx_outputs,
     };
 
     // Compute sighash for first input (we'll handle multiple inputs later)
-    let mut sighash_cache = SighashCache::new(&tx);
-    let prevouts_slice = Prevouts::All(&prevouts);
-    let sighash = sighash_cache
-        .taproot_key_spend_signature_hash(0, &prevouts_slice, TapSighashType::Default)
-        .context("Failed to compute sighash")?;
-
-    let sighash_hex = hex::encode(sighash.as_byte_array());
+    let spent: Vec<SpentOutput> = prevouts
+        .iter()
+        .map(|p| SpentOutput {
+            value: p.value.to_sat(),
+            script_pubkey: hex::encode(p.script_pubkey.as_bytes()),
+        })
+        .collect();
+    let sighash_hex = hex::encode(spend_sighash(&tx, &spent, spend_leaf.as_ref())?);
 
     // Generate session ID
     let session_id = generate_session_id(to_address, amount_sats);
@@ -346,17 +1160,16 @@ pub fn build_unsigned_tx_core(
         "session_id": session_id,
         "sighash": sighash_hex,
         "unsigned_tx": unsigned_tx_hex,
-        "prevouts": prevouts.iter().map(|p| {
-            serde_json::json!({
-                "value": p.value.to_sat(),
-                "script_pubkey": hex::encode(p.script_pubkey.as_bytes())
-            })
-        }).collect::<Vec<_>>(),
+        "prevouts": spent,
         "from_address": from_address.to_string(),
         "to_address": dest_address.to_string(),
         "amount_sats": amount_sats,
         "fee_sats": estimated_fee,
         "network": network_name(network),
+        "merkle_root": merkle_root.map(hex::encode),
+        "taproot_tree": taproot_tree,
+        "leaf": spend_leaf,
+        "memo": memo,
     });
 
     storage.write(
@@ -366,6 +1179,9 @@ pub fn build_unsigned_tx_core(
 
     out.push_str(&format!("\nSession ID: {}\n", session_id));
     out.push_str(&format!("Sighash: {}\n", sighash_hex));
+    if let Some(root) = &merkle_root {
+        out.push_str(&format!("Merkle root: {}\n", hex::encode(root)));
+    }
     out.push_str(&format!("Estimated fee: {} sats\n\n", estimated_fee));
 
     out.push_str("🧠 Next steps:\n");
@@ -373,8 +1189,26 @@ pub fn build_unsigned_tx_core(
     out.push_str(
         "   2. Each party runs: frostdao dkg-nonce --name <wallet> --session <session_id>\n",
     );
-    out.push_str("   3. Exchange nonces, then run: frostdao dkg-sign ...\n");
-    out.push_str("   4. Coordinator runs: frostdao dkg-broadcast ...\n");
+    if let Some(root) = &merkle_root {
+        out.push_str(&format!(
+            "   3. Exchange nonces, then run: frostdao dkg-sign ... --merkle-root {}\n",
+            hex::encode(root)
+        ));
+    } else {
+        out.push_str("   3. Exchange nonces, then run: frostdao dkg-sign ...\n");
+    }
+    out.push_str("   4. The lowest-index signer runs: frostdao dkg-broadcast ...\n");
+
+    notify::notify_wallet(
+        storage,
+        wallet_name,
+        &NotifyEvent::SessionCreated {
+            session_id: session_id.clone(),
+            to_address: dest_address.to_string(),
+            amount_sats,
+        },
+        &mut out,
+    );
 
     let output = BuildTxOutput {
         session_id,
@@ -385,6 +1219,10 @@ pub fn build_unsigned_tx_core(
         amount_sats,
         fee_sats: estimated_fee,
         network: network_name(network).to_string(),
+        merkle_root: merkle_root.map(hex::encode),
+        memo,
+        prevouts: spent,
+        leaf: spend_leaf,
         event_type: "dkg_build_tx".to_string(),
     };
 
@@ -399,15 +1237,23 @@ pub fn build_unsigned_tx_core(
 // ============================================================================
 
 /// Generate nonce for DKG transaction signing
-pub fn dkg_generate_nonce(wallet_name: &str, session_id: &str) -> Result<()> {
+pub fn dkg_generate_nonce(wallet_name: &str, session_id: &str, qr: bool) -> Result<()> {
     let state_dir = get_state_dir(wallet_name);
     let storage = FileStorage::new(&state_dir)?;
-    let cmd_result = dkg_generate_nonce_core(wallet_name, session_id, &storage)?;
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        dkg_generate_nonce_core(wallet_name, session_id, slot)
+    })?;
 
     println!("{}", cmd_result.output);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Share this with other signing parties:");
-    println!("{}\n", cmd_result.result);
+    println!(
+        "{}\n",
+        crate::protocol::compact::present(&cmd_result.result)
+    );
+    if qr {
+        crate::qr::show_qr(&cmd_result.result)?;
+    }
 
     Ok(())
 }
@@ -438,46 +1284,62 @@ pub fn dkg_generate_nonce_core(
     );
     out.push_str(&format!("Wallet: {}\n", wallet_name));
     out.push_str(&format!("Session: {}\n", session_id));
+    let memo = session_memo(storage, session_id)?;
+    if let Some(memo) = &memo {
+        out.push_str(&format!("Memo: {}\n", memo));
+    }
     out.push_str(&format!(
         "Your index: {} (rank {})\n\n",
         htss_metadata.my_index, htss_metadata.my_rank
     ));
 
-    // Load paired secret share
-    let paired_share_bytes = storage
-        .read("paired_secret_share.bin")
-        .context("Failed to load secret share. Did you run keygen-finalize?")?;
-    let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
+    require_secp256k1(storage)?;
 
-    // Create FROST instance
-    let frost = frost::new_with_synthetic_nonces::<Sha256, rand::rngs::ThreadRng>();
+    let public_nonce_hex = if device::is_delegated(storage) {
+        // The device keeps the secret nonce
+        out.push_str("🔌 Asking the signing device for a nonce...\n");
+        device::request_nonce(storage, session_id)?
+    } else {
+        // Load paired secret share
+        let paired_share_bytes = storage
+            .read_secret("paired_secret_share.bin")
+            .context("Failed to load secret share. Did you run keygen-finalize?")?;
+        let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
 
-    // Seed nonce RNG with session ID
-    let mut nonce_rng: rand_chacha::ChaCha20Rng =
-        frost.seed_nonce_rng(paired_share, session_id.as_bytes());
+        // Create FROST instance
+        let frost = frost::new_with_synthetic_nonces::<Sha256, rand::rngs::ThreadRng>();
 
-    // Generate nonce
-    let nonce = frost.gen_nonce(&mut nonce_rng);
+        // Seed nonce RNG with session ID
+        let mut nonce_rng: rand_chacha::ChaCha20Rng =
+            frost.seed_nonce_rng(paired_share, session_id.as_bytes());
+
+        // Generate nonce
+        let nonce = frost.gen_nonce(&mut nonce_rng);
 
-    // Save nonce for later signing
-    let nonce_bytes = bincode::serialize(&nonce)?;
-    storage.write(&format!("dkg_nonce_{}.bin", session_id), &nonce_bytes)?;
+        // Save nonce for later signing
+        let nonce_bytes = serialize_secret(&nonce)?;
+        storage.write(&format!("dkg_nonce_{}.bin", session_id), &nonce_bytes)?;
 
-    // Serialize public nonce
-    let public_nonce = nonce.public();
-    let public_nonce_bytes = bincode::serialize(&public_nonce)?;
-    let public_nonce_hex = hex::encode(&public_nonce_bytes);
+        // Serialize public nonce
+        let public_nonce = nonce.public();
+        let public_nonce_bytes = bincode::serialize(&public_nonce)?;
+        hex::encode(&public_nonce_bytes)
+    };
 
     out.push_str("⚠️  NEVER reuse a nonce - it will leak your secret share!\n\n");
 
     // Create output compatible with existing NonceOutput
-    let output = NonceOutput {
+    let mut output = NonceOutput {
         party_index: htss_metadata.my_index,
         rank: htss_metadata.my_rank,
         session: session_id.to_string(),
         nonce: public_nonce_hex,
+        memo,
+        protocol_version: payload::output_version(),
         event_type: "dkg_nonce".to_string(),
+        auth: None,
     };
+    identity::sign_payload(&mut output, storage)?;
 
     Ok(CommandResult {
         output: out,
@@ -490,40 +1352,195 @@ pub fn dkg_generate_nonce_core(
 // ============================================================================
 
 /// Create signature share for DKG transaction
+///
+/// `tx` is the dkg-build-tx output (JSON, @file or ur: frames), needed unless
+/// this party built the transaction. Only with `unsafe_bare_sighash` does a
+/// `sighash` with no transaction to review get signed.
+#[allow(clippy::too_many_arguments)]
 pub fn dkg_sign(
     wallet_name: &str,
     session_id: &str,
-    sighash: &str,
+    sighash: Option<&str>,
     nonces_data: &str,
+    merkle_root: Option<&str>,
+    tx: Option<&str>,
+    unsafe_bare_sighash: bool,
+    qr: bool,
 ) -> Result<()> {
+    let nonces_data = crate::qr::resolve_payload(nonces_data)?;
     let state_dir = get_state_dir(wallet_name);
     let storage = FileStorage::new(&state_dir)?;
-    let cmd_result = dkg_sign_core(wallet_name, session_id, sighash, nonces_data, &storage)?;
+    // Extra shares of a weighted party have no session file of their own
+    let tx: Option<BuildTxOutput> = match tx {
+        Some(tx) => Some(
+            serde_json::from_str(&crate::qr::resolve_payload(tx)?)
+                .context("--tx is not a dkg-build-tx output")?,
+        ),
+        None => session_build_output(&storage, session_id)?,
+    };
+    let sighash = match (sighash, &tx) {
+        (Some(sighash), _) => sighash.to_string(),
+        (None, Some(tx)) => tx.sighash.clone(),
+        (None, None) => anyhow::bail!(crate::error::Error::User(
+            "Pass --tx with the dkg-build-tx output".to_string()
+        )),
+    };
+    let merkle_root = match merkle_root {
+        Some(root) => Some(root.to_string()),
+        None => session_merkle_root(&storage, session_id)?.map(hex::encode),
+    };
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        dkg_sign_core(
+            wallet_name,
+            session_id,
+            &sighash,
+            &nonces_data,
+            merkle_root.as_deref(),
+            tx.as_ref(),
+            unsafe_bare_sighash,
+            slot,
+        )
+    })?;
 
     println!("{}", cmd_result.output);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Share this signature share:");
-    println!("{}\n", cmd_result.result);
+    println!(
+        "{}\n",
+        crate::protocol::compact::present(&cmd_result.result)
+    );
+    if qr {
+        crate::qr::show_qr(&cmd_result.result)?;
+    }
 
     Ok(())
 }
 
 /// Core function for signature share creation
+///
+/// `merkle_root` is the spent output's script tree root (hex). When omitted,
+/// the transaction's or the one recorded by dkg-build-tx in the local session
+/// file is used, if any. `tx` (else the local session file) is reviewed with
+/// [`review_unsigned_tx`] and must be the transaction behind `sighash_hex`.
+/// With neither, signing is refused unless `bare_sighash` accepts signing
+/// whatever `sighash_hex` commits to.
+#[cfg_attr(not(feature = "network"), allow(unused_variables))]
+#[allow(clippy::too_many_arguments)]
 pub fn dkg_sign_core(
-    _wallet_name: &str,
+    wallet_name: &str,
     session_id: &str,
     sighash_hex: &str,
     nonces_data: &str,
+    merkle_root: Option<&str>,
+    tx: Option<&BuildTxOutput>,
+    bare_sighash: bool,
     storage: &dyn Storage,
 ) -> Result<CommandResult> {
     let mut out = String::new();
+    let nonce_outputs: Vec<NonceOutput> = parse_payloads(
+        nonces_data,
+        &Expected {
+            session: Some(session_id),
+            ..Expected::new("signing nonces", &["dkg_nonce"])
+        },
+    )?;
+    if let Some(note) = payload::downgrade_note(payload::reply_version(&nonce_outputs)) {
+        out.push_str(&note);
+    }
+    let local_tx;
+    let tx = match tx {
+        Some(tx) => Some(tx),
+        None => {
+            local_tx = session_build_output(storage, session_id)?;
+            local_tx.as_ref()
+        }
+    };
+    match tx {
+        Some(tx) => {
+            if tx.session_id != session_id {
+                anyhow::bail!(crate::error::Error::User(format!(
+                    "The transaction is for session {}, not {}",
+                    tx.session_id, session_id
+                )));
+            }
+            if !tx.sighash.eq_ignore_ascii_case(sighash_hex) {
+                anyhow::bail!(crate::error::Error::Protocol(format!(
+                    "The transaction's sighash is {}, not the {} being signed; refusing to sign",
+                    tx.sighash, sighash_hex
+                )));
+            }
+            review_unsigned_tx(tx, storage, &mut out)?;
+        }
+        None if bare_sighash => out.push_str(
+            "⚠️  Signing a bare sighash: nothing shows what it pays or that it is a \
+             transaction at all\n\n",
+        ),
+        None => anyhow::bail!(crate::error::Error::User(format!(
+            "No transaction to review for session {}: pass --tx with the dkg-build-tx \
+             output (--unsafe-bare-sighash signs the sighash unseen)",
+            session_id
+        ))),
+    }
+    let merkle_root = match merkle_root.or(tx.and_then(|tx| tx.merkle_root.as_deref())) {
+        Some(root) => Some(parse_merkle_root(root)?),
+        None => session_merkle_root(storage, session_id)?,
+    };
+    let leaf = match tx {
+        Some(tx) => tx.leaf.clone(),
+        None => session_leaf(storage, session_id)?,
+    };
+    let output = create_signature_share(
+        session_id,
+        sighash_hex,
+        &nonce_outputs,
+        merkle_root.as_ref(),
+        leaf.as_ref(),
+        storage,
+        &mut out,
+    )?;
+
+    #[cfg(feature = "network")]
+    notify::notify_wallet(
+        storage,
+        wallet_name,
+        &NotifyEvent::ShareSubmitted {
+            session_id: session_id.to_string(),
+            party_index: output.party_index,
+        },
+        &mut out,
+    );
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&output)?,
+    })
+}
 
+/// Create this party's signature share and consume its nonce
+///
+/// Purely local (storage only), so it is shared by `dkg-sign` and the
+/// air-gapped `dkg-sign-offline`. The share is made for the output key tweaked
+/// with `merkle_root` (None for a key-path only output), or for the untweaked
+/// group key when the session spends `leaf`.
+pub(crate) fn create_signature_share(
+    session_id: &str,
+    sighash_hex: &str,
+    nonce_outputs: &[NonceOutput],
+    merkle_root: Option<&[u8; 32]>,
+    leaf: Option<&SpendLeaf>,
+    storage: &dyn Storage,
+    out: &mut String,
+) -> Result<DkgSignatureShareOutput> {
     // Load HTSS metadata
     let htss_metadata: HtssMetadata = {
         let metadata_json = String::from_utf8(storage.read("htss_metadata.json")?)?;
         serde_json::from_str(&metadata_json)?
     };
 
+    // Nonces must come from the parties they name, or a spoofed nonce could
+    // steer the aggregate R
+    identity::verify_payloads(nonce_outputs, storage, out)?;
+
     let mode_name = if htss_metadata.hierarchical {
         "HTSS"
     } else {
@@ -535,15 +1552,7 @@ pub fn dkg_sign_core(
         "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
     );
 
-    // Load nonce
-    let nonce_bytes = storage
-        .read(&format!("dkg_nonce_{}.bin", session_id))
-        .context("Nonce not found. Did you run dkg-nonce?")?;
-    let nonce: schnorr_fun::binonce::NonceKeyPair = bincode::deserialize(&nonce_bytes)?;
-
-    // Load paired secret share
-    let paired_share_bytes = storage.read("paired_secret_share.bin")?;
-    let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
+    require_secp256k1(storage)?;
 
     // Load shared key
     let shared_key_bytes = storage.read("shared_key.bin")?;
@@ -554,13 +1563,22 @@ pub fn dkg_sign_core(
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid sighash length"))?;
 
-    // Parse nonces from other parties
-    let nonce_outputs: Vec<NonceOutput> =
-        crate::protocol::keygen::parse_space_separated_json(nonces_data)?;
-
+    let memo = signing_memo(storage, session_id, nonce_outputs, out)?;
     out.push_str(&format!("Session: {}\n", session_id));
+    if let Some(memo) = &memo {
+        out.push_str(&format!("Memo: {}\n", memo));
+    }
     out.push_str(&format!("Sighash: {}...\n", &sighash_hex[..16]));
-    out.push_str(&format!("Signers: {} parties\n\n", nonce_outputs.len()));
+    if let Some(root) = merkle_root {
+        out.push_str(&format!("Merkle root: {}\n", hex::encode(root)));
+    }
+    if let Some(leaf) = leaf {
+        out.push_str(&format!(
+            "Spend path: leaf {} (signed with the untweaked group key)\n",
+            leaf.index
+        ));
+    }
+    out.push_str(&format!("Signers: {} parties\n\n", nonce_outputs.len()));
 
     // Validate signer set in HTSS mode
     if htss_metadata.hierarchical {
@@ -568,20 +1586,20 @@ pub fn dkg_sign_core(
         crate::crypto::birkhoff::validate_signer_set(&ranks, htss_metadata.threshold)?;
         out.push_str("✓ HTSS signer set is valid\n\n");
     }
-
-    // Build nonces map
-    let mut nonces_map = BTreeMap::new();
-    for nonce_output in &nonce_outputs {
-        let nonce_bytes = hex::decode(&nonce_output.nonce)?;
-        let public_nonce: schnorr_fun::binonce::Nonce = bincode::deserialize(&nonce_bytes)?;
-
-        let share_index = Scalar::<Secret, Zero>::from(nonce_output.party_index)
-            .non_zero()
-            .expect("index should be nonzero")
-            .public();
-        nonces_map.insert(share_index, public_nonce);
+    if !htss_metadata.signing_requirement.is_empty() {
+        let signers: Vec<u32> = nonce_outputs.iter().map(|n| n.party_index).collect();
+        htss_metadata.check_signing_requirement(&signers)?;
+        out.push_str(&format!(
+            "✓ Signing requirement met ({})\n\n",
+            crate::crypto::birkhoff::describe_signing_requirement(
+                &htss_metadata.signing_requirement
+            )
+        ));
     }
 
+    let nonces_map = nonces_by_index(nonce_outputs)?;
+    let roles = SessionRoles::from_signers(nonce_outputs.iter().map(|n| n.party_index))?;
+
     // Create FROST instance
     let frost = frost::new_with_deterministic_nonces::<Sha256>();
 
@@ -589,39 +1607,72 @@ pub fn dkg_sign_core(
     // For Bitcoin Taproot, the message is the raw sighash bytes
     let msg = Message::raw(&sighash_bytes);
 
-    // IMPORTANT: For P2TR, we must sign against the TWEAKED public key Q, not the internal key P.
-    // The P2TR address is derived from Q = P + H("TapTweak", P) * G
+    // IMPORTANT: For a key-path spend, we must sign against the TWEAKED public key Q, not the
+    // internal key P. The P2TR address is derived from Q = P + H("TapTweak", P || merkle_root) * G
     // The signature must verify as: s*G = R + e*Q where e = H("BIP0340/challenge", R || Q || m)
+    // A leaf checks its signature against P itself, so a script-path spend signs for P.
     let internal_pubkey = shared_key.public_key();
-    let (tweaked_pubkey, parity_flip) = compute_tweaked_pubkey(&internal_pubkey);
+    let (tweaked_pubkey, parity_flip, _) =
+        SessionKey::of(merkle_root, leaf).resolve(&internal_pubkey);
 
     // Create coordinator session (still uses internal key for nonce aggregation)
-    let coord_session = frost.coordinator_sign_session(&shared_key, nonces_map.clone(), msg);
+    let coord_session = frost.coordinator_sign_session(&shared_key, nonces_map, msg);
 
     // Create party sign session with TWEAKED public key for correct challenge computation
     let agg_binonce = coord_session.agg_binonce();
     let parties = coord_session.parties();
     let sign_session = frost.party_sign_session(tweaked_pubkey, parties.clone(), agg_binonce, msg);
 
-    // CRITICAL: Handle taproot parity
-    // If parity_flip is true, the tweaked key was negated to achieve even Y.
-    // In this case, we need to sign with the NEGATED secret share.
-    // This ensures: σ = k - e*p (instead of k + e*p) when combined,
-    // which allows the final signature s = σ - e*t = k - e*p - e*t = k - e*(p+t) to verify.
-    let sig_share = if parity_flip {
-        let negated_paired = crate::crypto::helpers::negate_paired_secret_share(&paired_share)?;
-        sign_session.sign(&negated_paired, nonce)
+    let sig_share_hex = if device::is_delegated(storage) {
+        // The device tweaks its own key and handles the parity itself
+        out.push_str("🔌 Confirm the sighash on the signing device...\n");
+        device::request_signature(
+            storage,
+            &device::DeviceRequest::Sign {
+                session: session_id.to_string(),
+                sighash: sighash_hex.to_string(),
+                merkle_root: merkle_root.map(hex::encode),
+                signers: nonce_outputs.iter().map(|n| n.party_index).collect(),
+                agg_nonce: hex::encode(bincode::serialize(&agg_binonce)?),
+                memo: memo.clone(),
+                script_path: leaf.is_some(),
+            },
+        )?
     } else {
-        sign_session.sign(&paired_share, nonce)
+        // Load nonce
+        let nonce_bytes = storage
+            .read_secret(&format!("dkg_nonce_{}.bin", session_id))
+            .context("Nonce not found. Did you run dkg-nonce?")?;
+        let nonce: schnorr_fun::binonce::NonceKeyPair = bincode::deserialize(&nonce_bytes)?;
+
+        // Load paired secret share
+        let paired_share_bytes = storage.read_secret("paired_secret_share.bin")?;
+        let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
+
+        // CRITICAL: Handle taproot parity
+        // If parity_flip is true, the tweaked key was negated to achieve even Y.
+        // In this case, we need to sign with the NEGATED secret share.
+        // This ensures: σ = k - e*p (instead of k + e*p) when combined,
+        // which allows the final signature s = σ - e*t = k - e*p - e*t = k - e*(p+t) to verify.
+        let signing_share = if parity_flip {
+            crate::crypto::helpers::negate_paired_secret_share(&paired_share)?
+        } else {
+            paired_share
+        };
+        // The session is bound to Q while the share stays untweaked (broadcast adds
+        // e*t), so pair it with Q to satisfy schnorr_fun's session key check
+        let signing_share =
+            crate::crypto::helpers::pair_with_public_key(&signing_share, &tweaked_pubkey)?;
+        let sig_share = sign_session.sign(&signing_share, nonce);
+        hex::encode(bincode::serialize(&sig_share)?)
     };
-    let sig_share_hex = hex::encode(bincode::serialize(&sig_share)?);
 
     if parity_flip {
         out.push_str("📝 Note: Tweaked key has odd Y - using negated secret share\n\n");
     }
 
     // Save session data for combine step
-    let final_nonce = coord_session.final_nonce();
+    let final_nonce = sign_session.final_nonce();
     let final_nonce_bytes = bincode::serialize(&final_nonce)?;
     storage.write(
         &format!("dkg_final_nonce_{}.bin", session_id),
@@ -655,19 +1706,167 @@ pub fn dkg_sign_core(
 
     out.push_str("✓ Signature share created\n");
 
-    let output = DkgSignatureShareOutput {
+    roles.record(
+        storage,
+        session_id,
+        sighash_hex,
+        merkle_root,
+        leaf,
+        memo.as_deref(),
+    )?;
+    out.push_str(&format!(
+        "\n🤝 Aggregator: party {} (lowest index of signers {:?})\n",
+        roles.aggregator, roles.signers
+    ));
+    if roles.is_aggregator(htss_metadata.my_index) {
+        out.push_str("   That's you: collect every share, then run dkg-broadcast\n");
+    } else {
+        out.push_str(&format!(
+            "   Send your share to party {}, who broadcasts\n",
+            roles.aggregator
+        ));
+    }
+
+    let mut output = DkgSignatureShareOutput {
         party_index: htss_metadata.my_index,
         rank: htss_metadata.my_rank,
         session_id: session_id.to_string(),
         sighash: sighash_hex.to_string(),
         signature_share: sig_share_hex,
+        aggregator: Some(roles.aggregator),
+        protocol_version: payload::reply_version(nonce_outputs),
         event_type: "dkg_signature_share".to_string(),
+        auth: None,
     };
+    identity::sign_payload(&mut output, storage)?;
+    let signers: Vec<u32> = nonce_outputs.iter().map(|n| n.party_index).collect();
+    health::record_session(storage, session_id, &signers)?;
+    Ok(output)
+}
 
-    Ok(CommandResult {
-        output: out,
-        result: serde_json::to_string(&output)?,
-    })
+/// Public nonces keyed by share index, as the FROST sessions expect them
+fn nonces_by_index(
+    nonce_outputs: &[NonceOutput],
+) -> Result<BTreeMap<frost::ShareIndex, schnorr_fun::binonce::Nonce>> {
+    let mut nonces_map = BTreeMap::new();
+    for nonce_output in nonce_outputs {
+        let public_nonce: schnorr_fun::binonce::Nonce = decode_hex_field(
+            &format!("Party {}'s nonce", nonce_output.party_index),
+            &nonce_output.nonce,
+        )?;
+        nonces_map.insert(share_index(nonce_output.party_index)?, public_nonce);
+    }
+    Ok(nonces_map)
+}
+
+/// Combine signature shares into a BIP-340 signature for the P2TR output key
+///
+/// Shares are computed against the tweaked key Q with the untweaked secret
+/// shares, so the tweak contribution e*t is added (or subtracted when Q was
+/// negated for even Y) here. Returns the 64-byte signature and the parity flag.
+///
+/// `key` must match the one the signers used: the output key with the spent
+/// output's script tree root (None for a key-path only output), or the
+/// untweaked group key for a script-path spend, where no tweak is added.
+pub fn combine_signature_shares(
+    shared_key: &SharedKey<EvenY>,
+    nonce_outputs: &[NonceOutput],
+    sighash_bytes: &[u8; 32],
+    share_outputs: &[DkgSignatureShareOutput],
+    key: SessionKey,
+) -> Result<([u8; 64], bool)> {
+    // Nothing is summed until the shares provably belong to this session:
+    // one session, this sighash, and exactly the parties that sent nonces
+    let session_id = nonce_outputs
+        .first()
+        .map(|n| n.session.as_str())
+        .context("No nonces for this session")?;
+    if let Some(nonce) = nonce_outputs.iter().find(|n| n.session != session_id) {
+        anyhow::bail!(
+            "Party {}'s nonce is for session {}, not {}",
+            nonce.party_index,
+            nonce.session,
+            session_id
+        );
+    }
+    let sighash_hex = hex::encode(sighash_bytes);
+    for share in share_outputs {
+        if share.session_id != session_id {
+            anyhow::bail!(
+                "Party {}'s signature share is for session {}, not {}",
+                share.party_index,
+                share.session_id,
+                session_id
+            );
+        }
+        if !share.sighash.eq_ignore_ascii_case(&sighash_hex) {
+            anyhow::bail!(
+                "Party {}'s signature share signs {}, not this session's sighash {}",
+                share.party_index,
+                share.sighash,
+                sighash_hex
+            );
+        }
+    }
+    check_signer_set(
+        &share_outputs
+            .iter()
+            .map(|s| s.party_index)
+            .collect::<Vec<_>>(),
+        &nonce_outputs
+            .iter()
+            .map(|n| n.party_index)
+            .collect::<Vec<_>>(),
+    )?;
+
+    let (tweaked_pubkey, parity_flip, taptweak) = key.resolve(&shared_key.public_key());
+
+    // R must be bound exactly as the signers bound it: against Q, not P
+    let frost = frost::new_without_nonce_generation::<Sha256>();
+    let msg = Message::raw(sighash_bytes);
+    let nonces_map = nonces_by_index(nonce_outputs)?;
+    let coord_session = frost.coordinator_sign_session(shared_key, nonces_map, msg);
+    let final_nonce = frost
+        .party_sign_session(
+            tweaked_pubkey,
+            coord_session.parties(),
+            coord_session.agg_binonce(),
+            msg,
+        )
+        .final_nonce();
+    let sig_r_bytes = final_nonce.to_xonly_bytes();
+
+    // Shares were made for Q, so schnorr_fun's verifying combiner can't check them
+    let sig_shares_sum = aggregate_signature_shares_hex(
+        share_outputs
+            .iter()
+            .map(|share_output| share_output.signature_share.as_str()),
+    )?;
+
+    // e = H("BIP0340/challenge", R || Q || m)
+    let mut challenge_input = Vec::with_capacity(96);
+    challenge_input.extend_from_slice(&sig_r_bytes);
+    challenge_input.extend_from_slice(&tweaked_pubkey.to_xonly_bytes());
+    challenge_input.extend_from_slice(sighash_bytes);
+    let challenge_hash = tagged_hash("BIP0340/challenge", &challenge_input);
+    let challenge: Scalar<Public, Zero> = Scalar::from_bytes_mod_order(challenge_hash);
+
+    // CRITICAL: Handle parity correctly
+    // - If parity_flip is false (Q had even Y): s = σ + e*t
+    //   Combined shares σ = k + e*p, final s = k + e*p + e*t = k + e*(p+t) ✓
+    // - If parity_flip is true (Q had odd Y, was negated):  s = σ - e*t
+    //   Combined shares σ = k - e*p (shares were negated), final s = k - e*p - e*t = k - e*(p+t) ✓
+    let tweak_contribution = s!(challenge * taptweak);
+    let sig_s_final = if parity_flip {
+        s!(sig_shares_sum - tweak_contribution)
+    } else {
+        s!(sig_shares_sum + tweak_contribution)
+    };
+
+    let mut sig_64 = [0u8; 64];
+    sig_64[..32].copy_from_slice(&sig_r_bytes);
+    sig_64[32..].copy_from_slice(&sig_s_final.to_bytes());
+    Ok((sig_64, parity_flip))
 }
 
 // ============================================================================
@@ -675,6 +1874,7 @@ pub fn dkg_sign_core(
 // ============================================================================
 
 /// Combine signature shares and broadcast transaction
+#[cfg(feature = "network")]
 pub fn dkg_broadcast(
     wallet_name: &str,
     session_id: &str,
@@ -682,13 +1882,14 @@ pub fn dkg_broadcast(
     shares_data: &str,
     network: Network,
 ) -> Result<()> {
+    let shares_data = crate::qr::resolve_payload(shares_data)?;
     let state_dir = get_state_dir(wallet_name);
     let storage = FileStorage::new(&state_dir)?;
     let cmd_result = dkg_broadcast_core(
         wallet_name,
         session_id,
         unsigned_tx_hex,
-        shares_data,
+        &shares_data,
         network,
         &storage,
     )?;
@@ -702,13 +1903,36 @@ pub fn dkg_broadcast(
 }
 
 /// Core function for combining signatures and broadcasting
+#[cfg(feature = "network")]
 pub fn dkg_broadcast_core(
-    _wallet_name: &str,
+    wallet_name: &str,
     session_id: &str,
     unsigned_tx_hex: &str,
     shares_data: &str,
     network: Network,
     storage: &dyn Storage,
+) -> Result<CommandResult> {
+    dkg_broadcast_core_with_backend(
+        wallet_name,
+        session_id,
+        unsigned_tx_hex,
+        shares_data,
+        network,
+        &MempoolBackend::new(network),
+        storage,
+    )
+}
+
+/// [`dkg_broadcast_core`], broadcasting through `backend`
+#[cfg(feature = "network")]
+pub fn dkg_broadcast_core_with_backend(
+    wallet_name: &str,
+    session_id: &str,
+    unsigned_tx_hex: &str,
+    shares_data: &str,
+    network: Network,
+    backend: &dyn ChainBackend,
+    storage: &dyn Storage,
 ) -> Result<CommandResult> {
     let mut out = String::new();
 
@@ -717,6 +1941,99 @@ pub fn dkg_broadcast_core(
         "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
     );
 
+    let tx = combine_transaction(session_id, unsigned_tx_hex, shares_data, storage, &mut out)?;
+
+    // Serialize signed transaction
+    let raw_tx = bitcoin::consensus::encode::serialize_hex(&tx);
+    let txid = tx.compute_txid();
+
+    out.push_str("Broadcasting transaction...\n");
+
+    // Broadcast
+    let report = backend.broadcast_report(&raw_tx);
+    if report.results.len() > 1 {
+        out.push_str(&report.lines());
+    }
+    let endpoints = report.results.clone();
+    let broadcast_result = report.result();
+
+    let explorer_url = explorer_tx_url(&txid.to_string(), network);
+
+    let status = match &broadcast_result {
+        Ok(_) => "accepted".to_string(),
+        Err(e) => format!("failed: {}", e),
+    };
+    SessionReport::build(
+        session_id,
+        &tx,
+        shares_data,
+        network_name(network),
+        &status,
+        storage,
+    )?
+    .save(storage)?;
+    audit::append(
+        storage,
+        "broadcast",
+        serde_json::json!({
+            "session": session_id,
+            "txid": txid.to_string(),
+            "status": status,
+            "memo": session_memo(storage, session_id)?,
+        }),
+    )?;
+
+    match broadcast_result {
+        Ok(_) => {
+            rebroadcast::record(storage, &raw_tx, network, health::now())?;
+            out.push_str("\n✅ Transaction broadcast successfully!\n");
+            out.push_str(&format!("TxID: {}\n", txid));
+            out.push_str(&format!("Explorer: {}\n", explorer_url));
+            out.push_str(&format!(
+                "Watch: frostdao dkg-watch --txid {} --network {}\n",
+                txid,
+                network_name(network)
+            ));
+        }
+        Err(e) => {
+            out.push_str(&format!("\n⚠️ Broadcast failed: {}\n", e));
+            out.push_str("Raw transaction saved for manual broadcast.\n");
+        }
+    }
+    out.push_str(&format!(
+        "Session report: frostdao dkg-session-report --name {} --session {} --out report.json\n",
+        wallet_name, session_id
+    ));
+
+    let output = BroadcastOutput {
+        txid: txid.to_string(),
+        raw_tx,
+        network: network_name(network).to_string(),
+        explorer_url,
+        endpoints,
+        event_type: "dkg_broadcast".to_string(),
+    };
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&output)?,
+    })
+}
+
+/// Check the signature shares for a session and put the combined signature
+/// into its transaction's (first) input, without broadcasting it
+///
+/// Shared by `dkg-broadcast` and flows that hand the signed transaction to
+/// someone else, like a Lightning peer funding a channel.
+pub fn combine_transaction(
+    session_id: &str,
+    unsigned_tx_hex: &str,
+    shares_data: &str,
+    storage: &dyn Storage,
+    out: &mut String,
+) -> Result<Transaction> {
+    require_secp256k1(storage)?;
+
     // Load shared key
     let shared_key_bytes = storage.read("shared_key.bin")?;
     let shared_key: SharedKey<EvenY> = bincode::deserialize(&shared_key_bytes)?;
@@ -731,102 +2048,87 @@ pub fn dkg_broadcast_core(
     let sighash_bytes: [u8; 32] = hex::decode(sighash_hex)?
         .try_into()
         .map_err(|_| anyhow::anyhow!("Invalid sighash length in session file"))?;
+    let merkle_root = session_merkle_root(storage, session_id)?;
+    let leaf = session_leaf(storage, session_id)?;
+    if let Some(session_tx) = session_data["unsigned_tx"].as_str() {
+        if !session_tx.eq_ignore_ascii_case(unsigned_tx_hex.trim()) {
+            anyhow::bail!(
+                "The unsigned transaction does not match session {}; pass the unsigned_tx from its dkg-build-tx output",
+                session_id
+            );
+        }
+    }
 
     // Parse signature shares
-    let share_outputs: Vec<DkgSignatureShareOutput> =
-        crate::protocol::keygen::parse_space_separated_json(shares_data)?;
+    let share_outputs: Vec<DkgSignatureShareOutput> = parse_payloads(
+        shares_data,
+        &Expected {
+            session: Some(session_id),
+            sighash: Some(sighash_hex),
+            ..Expected::new("signature shares", &["dkg_signature_share"])
+        },
+    )?;
 
     out.push_str(&format!("Session: {}\n", session_id));
+    if let Some(root) = &merkle_root {
+        out.push_str(&format!("Merkle root: {}\n", hex::encode(root)));
+    }
+    if let Some(leaf) = &leaf {
+        out.push_str(&format!("Spend path: leaf {}\n", leaf.index));
+    }
     out.push_str(&format!("Shares received: {}\n\n", share_outputs.len()));
+    identity::verify_payloads(&share_outputs, storage, out)?;
+
+    // Every signer derived the aggregator from the same signer set
+    let roles = SessionRoles::from_signers(share_outputs.iter().map(|s| s.party_index))?;
+    if let Some(other) = share_outputs
+        .iter()
+        .find(|s| s.aggregator.is_some_and(|a| a != roles.aggregator))
+    {
+        anyhow::bail!(
+            "Party {} names party {} as aggregator, but the signers {:?} make it party {}",
+            other.party_index,
+            other.aggregator.unwrap_or_default(),
+            roles.signers,
+            roles.aggregator
+        );
+    }
+    let htss_metadata: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    if !roles.is_aggregator(htss_metadata.my_index) {
+        out.push_str(&format!(
+            "ℹ️  Party {} is this session's aggregator; broadcasting from party {} anyway\n\n",
+            roles.aggregator, htss_metadata.my_index
+        ));
+    }
 
     // Load saved nonces
     let nonces_json =
         String::from_utf8(storage.read(&format!("dkg_session_nonces_{}.json", session_id))?)?;
     let nonce_outputs: Vec<NonceOutput> = serde_json::from_str(&nonces_json)?;
 
-    // Rebuild nonces map
-    let mut nonces_map = BTreeMap::new();
-    for nonce_output in &nonce_outputs {
-        let nonce_bytes = hex::decode(&nonce_output.nonce)?;
-        let public_nonce: schnorr_fun::binonce::Nonce = bincode::deserialize(&nonce_bytes)?;
-        let share_index = Scalar::<Secret, Zero>::from(nonce_output.party_index)
-            .non_zero()
-            .expect("index should be nonzero")
-            .public();
-        nonces_map.insert(share_index, public_nonce);
-    }
-
-    // Create FROST instance
-    let frost = frost::new_with_synthetic_nonces::<Sha256, rand::rngs::ThreadRng>();
-
-    // Create message
-    let msg = Message::raw(&sighash_bytes);
-
-    // Compute the tweaked public key (same as in dkg_sign)
-    // IMPORTANT: Use the computed parity directly instead of reading from file.
-    // This allows non-signing coordinators to broadcast without having run dkg_sign.
-    let internal_pubkey = shared_key.public_key();
-    let internal_pubkey_bytes: [u8; 32] = internal_pubkey.to_xonly_bytes();
-    let (tweaked_pubkey, parity_flip) = compute_tweaked_pubkey(&internal_pubkey);
-    let taptweak = compute_taptweak(&internal_pubkey_bytes);
-
-    if parity_flip {
-        out.push_str("📝 Parity flip detected - will subtract tweak contribution\n\n");
-    }
-
-    // Recreate coordinator session
-    let coord_session = frost.coordinator_sign_session(&shared_key, nonces_map, msg);
-
-    // Parse signature shares (skip verification since shares were computed with tweaked key)
-    let mut sig_shares_sum: Scalar<Public, Zero> = Scalar::zero();
     for share_output in &share_outputs {
-        let share_bytes = hex::decode(&share_output.signature_share)?;
-        let sig_share: Scalar<Public, Zero> = bincode::deserialize(&share_bytes)?;
-        let sum = s!(sig_shares_sum + sig_share);
-        sig_shares_sum = sum.public(); // Convert back to Public marker
         out.push_str(&format!("   Party {}: ✓\n", share_output.party_index));
     }
-
     out.push_str("\nCombining signature shares...\n");
 
-    // Get the final nonce R from coordinator session
-    let final_nonce = coord_session.final_nonce();
-    let sig_r_bytes = final_nonce.to_xonly_bytes();
-
-    // Apply taptweak adjustment to s
-    // Compute e = H("BIP0340/challenge", R || Q || m)
-    let mut challenge_input = Vec::with_capacity(96);
-    challenge_input.extend_from_slice(&sig_r_bytes);
-    challenge_input.extend_from_slice(&tweaked_pubkey.to_xonly_bytes());
-    challenge_input.extend_from_slice(&sighash_bytes);
-    let challenge_hash = tagged_hash("BIP0340/challenge", &challenge_input);
-    let challenge: Scalar<Public, Zero> = Scalar::from_bytes_mod_order(challenge_hash);
-
-    // Compute e * t (the tweak contribution)
-    let tweak_contribution = s!(challenge * taptweak);
-
-    // CRITICAL: Handle parity correctly
-    // - If parity_flip is false (Q had even Y): s = σ + e*t
-    //   Combined shares σ = k + e*p, final s = k + e*p + e*t = k + e*(p+t) ✓
-    // - If parity_flip is true (Q had odd Y, was negated):  s = σ - e*t
-    //   Combined shares σ = k - e*p (shares were negated), final s = k - e*p - e*t = k - e*(p+t) ✓
-    let sig_s_final = if parity_flip {
-        s!(sig_shares_sum - tweak_contribution)
-    } else {
-        s!(sig_shares_sum + tweak_contribution)
-    };
-    let sig_s_bytes = sig_s_final.to_bytes();
+    // Compute the tweaked public key and parity here rather than reading them
+    // from file, so non-signing coordinators can broadcast without dkg-sign
+    let (sig_64, parity_flip) = combine_signature_shares(
+        &shared_key,
+        &nonce_outputs,
+        &sighash_bytes,
+        &share_outputs,
+        SessionKey::of(merkle_root.as_ref(), leaf.as_ref()),
+    )?;
 
+    if parity_flip {
+        out.push_str("📝 Parity flip detected - subtracted tweak contribution\n");
+    }
     out.push_str(&format!(
         "✓ Signature computed with taptweak (parity_flip={})!\n\n",
         parity_flip
     ));
 
-    // Combine R and s into 64-byte BIP340 signature
-    let mut sig_64 = [0u8; 64];
-    sig_64[..32].copy_from_slice(&sig_r_bytes);
-    sig_64[32..].copy_from_slice(&sig_s_bytes);
-
     // Parse unsigned transaction
     let tx_bytes = hex::decode(unsigned_tx_hex)?;
     let mut tx: Transaction = bitcoin::consensus::deserialize(&tx_bytes)?;
@@ -841,49 +2143,14 @@ pub fn dkg_broadcast_core(
     }
 
     // Add witness with signature
-    // For Taproot key-path spend, witness is just the signature
-    tx.input[0].witness = Witness::from_slice(&[&sig_64[..]]);
-
-    // Serialize signed transaction
-    let raw_tx = bitcoin::consensus::encode::serialize_hex(&tx);
-    let txid = tx.compute_txid();
-
-    out.push_str("Broadcasting transaction...\n");
-
-    // Broadcast
-    let broadcast_result = broadcast_transaction(&raw_tx, network);
-
-    let explorer_url = match network {
-        Network::Testnet => format!("https://mempool.space/testnet/tx/{}", txid),
-        Network::Signet => format!("https://mempool.space/signet/tx/{}", txid),
-        Network::Bitcoin => format!("https://mempool.space/tx/{}", txid),
-        _ => format!("https://mempool.space/testnet/tx/{}", txid),
-    };
-
-    match broadcast_result {
-        Ok(_) => {
-            out.push_str(&format!("\n✅ Transaction broadcast successfully!\n"));
-            out.push_str(&format!("TxID: {}\n", txid));
-            out.push_str(&format!("Explorer: {}\n", explorer_url));
-        }
-        Err(e) => {
-            out.push_str(&format!("\n⚠️ Broadcast failed: {}\n", e));
-            out.push_str("Raw transaction saved for manual broadcast.\n");
-        }
-    }
-
-    let output = BroadcastOutput {
-        txid: txid.to_string(),
-        raw_tx,
-        network: network_name(network).to_string(),
-        explorer_url,
-        event_type: "dkg_broadcast".to_string(),
+    // For Taproot key-path spend, witness is just the signature; a leaf also
+    // takes what its script consumes, the script and the control block
+    tx.input[0].witness = match &leaf {
+        Some(leaf) => leaf.witness(&shared_key.public_key().to_xonly_bytes(), &sig_64)?,
+        None => Witness::from_slice(&[&sig_64[..]]),
     };
 
-    Ok(CommandResult {
-        output: out,
-        result: serde_json::to_string(&output)?,
-    })
+    Ok(tx)
 }
 
 // ============================================================================
@@ -902,6 +2169,12 @@ pub struct AutoSignResult {
     pub network: String,
     pub explorer_url: String,
     pub signers: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Answer of each broadcast endpoint
+    #[cfg(feature = "network")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub endpoints: Vec<EndpointResult>,
     #[serde(rename = "type")]
     pub event_type: String,
 }
@@ -915,10 +2188,15 @@ pub struct AutoSignResult {
 /// 4. Combine signatures with taptweak adjustment
 /// 5. Broadcast or return ready-to-broadcast transaction
 ///
+/// Shares are read one party at a time; HD derivation, nonces and signature
+/// shares are then computed in parallel (rayon), collected in signer order.
+///
 /// ## HD Derivation
 /// If `derivation_path` is provided as `Some((change, address_index))`, the signing
 /// will use the HD-derived key at that BIP-44 path. Each party's secret share is
 /// tweaked locally using the same public derivation info.
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "network")]
 pub fn frost_sign_all_local(
     wallet_name: &str,
     to_address: &str,
@@ -927,57 +2205,76 @@ pub fn frost_sign_all_local(
     derivation_path: Option<(u32, u32)>, // Optional (change, address_index) for HD signing
     fee_rate: Option<u64>,
     network: Network,
+    allow_inscribed: bool,
+    memo: Option<&str>,
 ) -> Result<CommandResult> {
-    let mut out = String::new();
-
-    out.push_str("🔐 FROST Multi-Party Signing (Automated)\n\n");
-    out.push_str(
-        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
-    );
-
     if selected_parties.is_empty() {
         anyhow::bail!("No parties selected for signing");
     }
 
-    // Step 0: Load wallet metadata to validate threshold
     let state_dir = get_state_dir(wallet_name);
-
-    // Load metadata from first party to get threshold info
-    let first_party_idx = selected_parties[0];
-    let first_party_dir = format!("{}/party{}", state_dir, first_party_idx);
-    let first_party_storage = FileStorage::new(&first_party_dir)
-        .with_context(|| format!("Party {} folder not found", first_party_idx))?;
-
-    let wallet_metadata: HtssMetadata = {
-        let metadata_json = String::from_utf8(first_party_storage.read("htss_metadata.json")?)?;
-        serde_json::from_str(&metadata_json)?
-    };
-
-    // Validate threshold requirement - must be exactly threshold parties
-    if (selected_parties.len() as u32) != wallet_metadata.threshold {
-        anyhow::bail!(
-            "Must select exactly {} parties for signing (selected {})",
-            wallet_metadata.threshold,
-            selected_parties.len()
-        );
+    let main_storage = FileStorage::new(&state_dir)?;
+    let mut party_storages = Vec::with_capacity(selected_parties.len());
+    for &party_idx in selected_parties {
+        let party_dir = format!("{}/party{}", state_dir, party_idx);
+        if !std::path::Path::new(&party_dir).exists() {
+            anyhow::bail!("Party {} folder not found", party_idx);
+        }
+        party_storages.push((party_idx, FileStorage::new(&party_dir)?));
     }
+    let parties: Vec<(u32, &dyn Storage)> = party_storages
+        .iter()
+        .map(|(idx, storage)| (*idx, storage as &dyn Storage))
+        .collect();
 
-    // For HTSS mode, validate signer set ranks
-    if wallet_metadata.hierarchical {
-        let ranks: Vec<u32> = selected_parties
-            .iter()
-            .filter_map(|&idx| wallet_metadata.party_ranks.get(&idx).copied())
-            .collect();
+    frost_sign_local_core(
+        wallet_name,
+        &main_storage,
+        &parties,
+        to_address,
+        amount_sats,
+        derivation_path,
+        fee_rate,
+        network,
+        allow_inscribed,
+        memo,
+        &MempoolBackend::new(network),
+    )
+}
 
-        if ranks.len() != selected_parties.len() {
-            anyhow::bail!("Could not determine ranks for all selected parties");
-        }
+/// [`frost_sign_all_local`] over explicit storages and chain backend
+///
+/// `main_storage` holds the group's public files (shared key, HD metadata);
+/// `parties` pairs each selected party index with the storage of its share.
+#[allow(clippy::too_many_arguments)]
+#[cfg(feature = "network")]
+pub fn frost_sign_local_core(
+    wallet_name: &str,
+    main_storage: &dyn Storage,
+    parties: &[(u32, &dyn Storage)],
+    to_address: &str,
+    amount_sats: u64,
+    derivation_path: Option<(u32, u32)>,
+    fee_rate: Option<u64>,
+    network: Network,
+    allow_inscribed: bool,
+    memo: Option<&str>,
+    backend: &dyn ChainBackend,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    let memo = memo.map(check_memo).transpose()?.flatten();
 
-        crate::crypto::birkhoff::validate_signer_set(&ranks, wallet_metadata.threshold)
-            .context("HTSS signer set validation failed")?;
-    }
+    out.push_str("🔐 FROST Multi-Party Signing (Automated)\n\n");
+    out.push_str(
+        "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
+    );
+
+    let (selected_parties, wallet_metadata) = check_local_signers(parties)?;
 
     out.push_str(&format!("Wallet: {}\n", wallet_name));
+    if let Some(memo) = &memo {
+        out.push_str(&format!("Memo: {}\n", memo));
+    }
     out.push_str(&format!("Signing parties: {:?}\n", selected_parties));
     out.push_str(&format!(
         "Threshold: {}-of-{}\n",
@@ -985,9 +2282,12 @@ pub fn frost_sign_all_local(
         wallet_metadata.party_ranks.len()
     ));
     out.push_str(&format!("Destination: {}\n", to_address));
-    out.push_str(&format!("Amount: {} sats\n\n", amount_sats));
-    let main_storage = FileStorage::new(&state_dir)?;
+    out.push_str(&format!("Amount: {}\n\n", format_amount(amount_sats)));
 
+    require_secp256k1(main_storage)?;
+    if let Some(retired) = Retirement::load(main_storage)? {
+        out.push_str(&retired.warning());
+    }
     let shared_key_bytes = main_storage
         .read("shared_key.bin")
         .context("No DKG shared key found")?;
@@ -999,7 +2299,7 @@ pub fn frost_sign_all_local(
             out.push_str(&format!("📍 Using HD path: {}/{}\n", change, index));
 
             // Load HD context using the proper function (reads hd_metadata.json)
-            let hd_context = crate::btc::hd_address::load_hd_context(&main_storage)
+            let hd_context = crate::btc::hd_address::load_hd_context(main_storage)
                 .context("HD context not found. Wallet may not support HD derivation.")?;
 
             let path = crate::crypto::hd::DerivationPath {
@@ -1036,18 +2336,24 @@ pub fn frost_sign_all_local(
 
     // Step 2: Fetch UTXOs and build transaction
     out.push_str("📥 Fetching UTXOs...\n");
-    let utxos = fetch_utxos(&from_address.to_string(), network)?;
+    let utxos = backend.utxos(&from_address.to_string())?;
 
     let confirmed_utxos: Vec<_> = utxos.iter().filter(|u| u.status.confirmed).collect();
     if confirmed_utxos.is_empty() {
         anyhow::bail!("No confirmed UTXOs available");
     }
 
+    let confirmed_utxos =
+        filter_protected_utxos(confirmed_utxos, network, allow_inscribed, &mut out)?;
+    if confirmed_utxos.is_empty() {
+        anyhow::bail!("No spendable UTXOs left after freezing inscribed outputs");
+    }
+
     let total_available: u64 = confirmed_utxos.iter().map(|u| u.value).sum();
     out.push_str(&format!("   Available: {} sats\n", total_available));
 
     // Get fee rate
-    let fee_estimates = fetch_fee_estimates(network)?;
+    let fee_estimates = backend.fee_estimates()?;
     let fee_rate = fee_rate.unwrap_or(fee_estimates.half_hour_fee);
 
     // Estimate fee
@@ -1055,11 +2361,11 @@ pub fn frost_sign_all_local(
     let estimated_fee = estimated_vsize * fee_rate;
 
     if total_available < amount_sats + estimated_fee {
-        anyhow::bail!(
-            "Insufficient funds. Need {} sats, have {} sats",
-            amount_sats + estimated_fee,
-            total_available
-        );
+        return Err(crate::error::Error::InsufficientFunds {
+            needed: amount_sats + estimated_fee,
+            available: total_available,
+        }
+        .into());
     }
 
     // Build transaction inputs
@@ -1116,81 +2422,226 @@ pub fn frost_sign_all_local(
         .context("Failed to compute sighash")?;
 
     let sighash_bytes: [u8; 32] = *sighash.as_byte_array();
-    let sighash_hex = hex::encode(&sighash_bytes);
+    let sighash_hex = hex::encode(sighash_bytes);
 
     out.push_str(&format!("📝 Sighash: {}...\n\n", &sighash_hex[..16]));
 
     // Generate session ID
     let session_id = generate_session_id(to_address, amount_sats);
 
-    // Step 3: Load party shares and generate nonces
-    out.push_str("🔑 Generating nonces for all parties...\n");
+    let sig_64 = sign_key_spend_local(
+        parties,
+        hd_derived_info.as_ref(),
+        from_pubkey,
+        sighash_bytes,
+        &session_id,
+        &mut out,
+    )?;
 
-    let frost = frost::new_with_synthetic_nonces::<Sha256, rand::rngs::ThreadRng>();
+    // Step 6: Create signed transaction
+    let unsigned_tx_hex = bitcoin::consensus::encode::serialize_hex(&tx);
+    let tx_bytes = hex::decode(&unsigned_tx_hex)?;
+    let mut signed_tx: Transaction = bitcoin::consensus::deserialize(&tx_bytes)?;
 
-    let mut party_data: Vec<(
-        u32,
-        u32,
-        PairedSecretShare<EvenY>,
-        schnorr_fun::binonce::NonceKeyPair,
-    )> = Vec::new();
-    let mut nonces_map: BTreeMap<Scalar<Public, NonZero>, schnorr_fun::binonce::Nonce> =
-        BTreeMap::new();
-    let mut _nonce_outputs: Vec<NonceOutput> = Vec::new();
+    // Add witness
+    signed_tx.input[0].witness = Witness::from_slice(&[&sig_64[..]]);
 
-    for &party_idx in selected_parties {
-        let party_dir = format!("{}/party{}", state_dir, party_idx);
-        let party_storage = FileStorage::new(&party_dir)
-            .with_context(|| format!("Party {} folder not found", party_idx))?;
+    let raw_tx = bitcoin::consensus::encode::serialize_hex(&signed_tx);
+    let txid = signed_tx.compute_txid();
 
+    out.push_str("📡 Broadcasting transaction...\n");
+
+    // Broadcast
+    let explorer_url = explorer_tx_url(&txid.to_string(), network);
+
+    let report = backend.broadcast_report(&raw_tx);
+    if report.results.len() > 1 {
+        out.push_str(&report.lines());
+    }
+    let endpoints = report.results.clone();
+    let status = match report.result() {
+        Ok(_) => {
+            rebroadcast::record(main_storage, &raw_tx, network, health::now())?;
+            out.push_str("\n✅ Transaction broadcast successfully!\n");
+            out.push_str(&format!("   TxID: {}\n", txid));
+            out.push_str(&format!("   Explorer: {}\n", explorer_url));
+            "accepted".to_string()
+        }
+        Err(e) => {
+            out.push_str(&format!("\n⚠️ Broadcast failed: {}\n", e));
+            out.push_str("   Raw transaction saved for manual broadcast.\n");
+            format!("failed: {}", e)
+        }
+    };
+    for (_, storage) in parties {
+        audit::append(
+            *storage,
+            "broadcast",
+            serde_json::json!({
+                "session": session_id,
+                "txid": txid.to_string(),
+                "status": status,
+                "memo": memo,
+            }),
+        )?;
+    }
+
+    let output = AutoSignResult {
+        txid: txid.to_string(),
+        raw_tx,
+        from_address: from_address.to_string(),
+        to_address: dest_address.to_string(),
+        amount_sats,
+        fee_sats: estimated_fee,
+        network: network_name(network).to_string(),
+        explorer_url,
+        signers: selected_parties,
+        memo,
+        endpoints,
+        event_type: "frost_auto_sign".to_string(),
+    };
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&output)?,
+    })
+}
+
+/// Party indices and wallet metadata for a local signing, once the signer set is checked
+///
+/// There must be exactly threshold parties, and for HTSS wallets a valid
+/// rank mix that also meets the wallet's signing requirement.
+#[cfg(feature = "network")]
+pub(crate) fn check_local_signers(
+    parties: &[(u32, &dyn Storage)],
+) -> Result<(Vec<u32>, HtssMetadata)> {
+    let selected_parties: Vec<u32> = parties.iter().map(|(idx, _)| *idx).collect();
+    let Some(&(_, first_party_storage)) = parties.first() else {
+        anyhow::bail!("No parties selected for signing");
+    };
+
+    // Step 0: Load wallet metadata (from the first party) to validate threshold
+
+    let wallet_metadata: HtssMetadata = {
+        let metadata_json = String::from_utf8(first_party_storage.read("htss_metadata.json")?)?;
+        serde_json::from_str(&metadata_json)?
+    };
+
+    // Validate threshold requirement - must be exactly threshold parties
+    if (selected_parties.len() as u32) != wallet_metadata.threshold {
+        anyhow::bail!(
+            "Must select exactly {} parties for signing (selected {})",
+            wallet_metadata.threshold,
+            selected_parties.len()
+        );
+    }
+
+    // For HTSS mode, validate signer set ranks
+    if wallet_metadata.hierarchical {
+        let ranks: Vec<u32> = selected_parties
+            .iter()
+            .filter_map(|&idx| wallet_metadata.party_ranks.get(&idx).copied())
+            .collect();
+
+        if ranks.len() != selected_parties.len() {
+            anyhow::bail!("Could not determine ranks for all selected parties");
+        }
+
+        crate::crypto::birkhoff::validate_signer_set(&ranks, wallet_metadata.threshold)
+            .context("HTSS signer set validation failed")?;
+    }
+    wallet_metadata.check_signing_requirement(&selected_parties)?;
+
+    Ok((selected_parties, wallet_metadata))
+}
+
+/// Nonces, signature shares and taptweak for a key-path spend, all parties local
+///
+/// Signs `sighash_bytes` for the P2TR output of `from_pubkey` (the root key,
+/// or the HD child in `hd_derived_info`) and returns the BIP-340 signature.
+/// Shared by [`frost_sign_local_core`] and the rotation sweep.
+#[cfg(feature = "network")]
+pub(crate) fn sign_key_spend_local(
+    parties: &[(u32, &dyn Storage)],
+    hd_derived_info: Option<&crate::crypto::hd::DerivedKeyInfo>,
+    from_pubkey: Point<EvenY>,
+    sighash_bytes: [u8; 32],
+    session_id: &str,
+    out: &mut String,
+) -> Result<[u8; 64]> {
+    let sighash_hex = hex::encode(sighash_bytes);
+
+    // Step 3: Load party shares and generate nonces
+    out.push_str("🔑 Generating nonces for all parties...\n");
+
+    // Storage is read one party at a time; the crypto below runs in parallel
+    let mut loaded: Vec<(u32, u32, PairedSecretShare<EvenY>)> = Vec::with_capacity(parties.len());
+    for &(party_idx, party_storage) in parties {
         // Load metadata
         let metadata_json = String::from_utf8(party_storage.read("htss_metadata.json")?)?;
         let metadata: HtssMetadata = serde_json::from_str(&metadata_json)?;
 
         // Load paired secret share
         let paired_share_bytes = party_storage
-            .read("paired_secret_share.bin")
+            .read_secret("paired_secret_share.bin")
             .with_context(|| format!("Party {} secret share not found", party_idx))?;
         let root_paired_share: PairedSecretShare<EvenY> =
             bincode::deserialize(&paired_share_bytes)?;
+        loaded.push((party_idx, metadata.my_rank, root_paired_share));
+    }
 
-        // Apply HD derivation if specified
-        let paired_share = if let Some(ref derived_info) = hd_derived_info {
-            crate::crypto::hd::derive_share(&root_paired_share, derived_info)
-                .with_context(|| format!("Failed to derive HD share for party {}", party_idx))?
-        } else {
-            root_paired_share
-        };
+    // HD derivation and nonce generation per party, in signer order
+    let party_data: Vec<(
+        u32,
+        u32,
+        PairedSecretShare<EvenY>,
+        schnorr_fun::binonce::NonceKeyPair,
+    )> = loaded
+        .into_par_iter()
+        .map(|(party_idx, rank, root_paired_share)| {
+            // Apply HD derivation if specified
+            let paired_share = if let Some(derived_info) = hd_derived_info {
+                crate::crypto::hd::derive_share(&root_paired_share, derived_info)
+                    .with_context(|| format!("Failed to derive HD share for party {}", party_idx))?
+            } else {
+                root_paired_share
+            };
 
-        // Generate nonce (use the derived or root share)
-        let mut nonce_rng: rand_chacha::ChaCha20Rng =
-            frost.seed_nonce_rng(paired_share, session_id.as_bytes());
-        let nonce = frost.gen_nonce(&mut nonce_rng);
+            // Generate nonce (use the derived or root share); ThreadRng is per thread
+            let frost = frost::new_with_synthetic_nonces::<Sha256, rand::rngs::ThreadRng>();
+            let mut nonce_rng: rand_chacha::ChaCha20Rng =
+                frost.seed_nonce_rng(paired_share, session_id.as_bytes());
+            let nonce = frost.gen_nonce(&mut nonce_rng);
+            Ok((party_idx, rank, paired_share, nonce))
+        })
+        .collect::<Result<_>>()?;
 
+    let mut nonces_map: BTreeMap<Scalar<Public, NonZero>, schnorr_fun::binonce::Nonce> =
+        BTreeMap::new();
+    let mut _nonce_outputs: Vec<NonceOutput> = Vec::new();
+    for (party_idx, rank, _, nonce) in &party_data {
         // Store public nonce
         let public_nonce = nonce.public();
-        let share_index = Scalar::<Secret, Zero>::from(party_idx)
-            .non_zero()
-            .expect("index should be nonzero")
-            .public();
-        nonces_map.insert(share_index, public_nonce);
+        nonces_map.insert(share_index(*party_idx)?, public_nonce);
 
         // Create NonceOutput for compatibility
         let public_nonce_bytes = bincode::serialize(&public_nonce)?;
         let public_nonce_hex = hex::encode(&public_nonce_bytes);
         _nonce_outputs.push(NonceOutput {
-            party_index: party_idx,
-            rank: metadata.my_rank,
-            session: session_id.clone(),
+            party_index: *party_idx,
+            rank: *rank,
+            session: session_id.to_string(),
             nonce: public_nonce_hex,
+            memo: None,
+            protocol_version: payload::PROTOCOL_VERSION,
             event_type: "signing_nonce".to_string(),
+            // All parties are local, nothing crosses a channel to authenticate
+            auth: None,
         });
-
-        party_data.push((party_idx, metadata.my_rank, paired_share, nonce));
         out.push_str(&format!("   Party {}: ✓ nonce generated\n", party_idx));
     }
 
-    out.push_str("\n");
+    out.push('\n');
 
     // Step 4: Generate signature shares (manual aggregation for HD compatibility)
     out.push_str("✍️  Generating signature shares...\n");
@@ -1198,8 +2649,8 @@ pub fn frost_sign_all_local(
     // Compute tweaked public key for P2TR
     let internal_pubkey = from_pubkey;
     let internal_pubkey_bytes: [u8; 32] = internal_pubkey.to_xonly_bytes();
-    let (tweaked_pubkey, parity_flip) = compute_tweaked_pubkey(&internal_pubkey);
-    let taptweak = compute_taptweak(&internal_pubkey_bytes);
+    let (tweaked_pubkey, parity_flip) = compute_tweaked_pubkey(&internal_pubkey, None);
+    let taptweak = compute_taptweak(&internal_pubkey_bytes, None);
 
     // Manual nonce aggregation (bypasses SharedKey validation for HD compatibility)
     // Using simplified single-nonce aggregation: R = sum(R1_i)
@@ -1236,60 +2687,69 @@ pub fn frost_sign_all_local(
 
     // Generate signature shares manually (bypasses schnorr_fun session validation for HD compatibility)
     // Using single nonces (k1 only), signature share: s_i = k1_i + lambda_i * e * x_i
-    let mut _sig_shares: Vec<DkgSignatureShareOutput> = Vec::new();
-    let mut sig_shares_sum: Scalar<Public, Zero> = Scalar::zero();
-
-    for (party_idx, rank, paired_share, nonce) in party_data {
-        // Get secret share value
-        let secret_share = paired_share.secret_share();
-        let share_value = secret_share.share;
-
-        // Compute Lagrange coefficient for this party
-        let lambda =
-            crate::crypto::helpers::lagrange_coefficient_at_zero(party_idx, &party_indices)
-                .context("Failed to compute Lagrange coefficient")?;
-
-        // Get nonce secret k1 (using single nonce scheme)
-        // SecretNonce is a tuple struct with [Scalar; 2], access with .0[0]
-        let k1 = &nonce.secret.0[0];
-
-        // Apply nonce parity adjustment if R was negated for even Y
-        // BIP-340: if R has odd Y, we use -R, so we must also use -k
-        let effective_k1 = if nonce_parity_flip {
-            s!(-k1).public()
-        } else {
-            s!(k1).public()
-        };
+    // Lagrange coefficients for the whole signer set (one inversion, cached per set)
+    let lambdas = cached_lagrange_coefficients(&party_indices)
+        .context("Failed to compute Lagrange coefficients")?;
+
+    // Shares are computed in parallel but kept in signer order for aggregation
+    let computed: Vec<(u32, u32, Scalar<Public, Zero>)> = party_data
+        .into_par_iter()
+        .map(|(party_idx, rank, paired_share, nonce)| {
+            // Get secret share value
+            let secret_share = paired_share.secret_share();
+            let share_value = secret_share.share;
+
+            let lambda = lambdas[&party_idx];
+
+            // Get nonce secret k1 (using single nonce scheme)
+            // SecretNonce is a tuple struct with [Scalar; 2], access with .0[0]
+            let k1 = &nonce.secret.0[0];
+
+            // Apply nonce parity adjustment if R was negated for even Y
+            // BIP-340: if R has odd Y, we use -R, so we must also use -k
+            let effective_k1 = if nonce_parity_flip {
+                s!(-k1).public()
+            } else {
+                s!(k1).public()
+            };
 
-        // Compute signature share: s_i = k1_i + lambda_i * e * x_i
-        // Handle parity flip for the share (needed for even Y coordinate of public key)
-        let sig_share = if parity_flip {
-            s!(effective_k1 + lambda * challenge * { s!(-share_value) })
-        } else {
-            s!(effective_k1 + lambda * challenge * share_value)
-        };
+            // Compute signature share: s_i = k1_i + lambda_i * e * x_i
+            // Handle parity flip for the share (needed for even Y coordinate of public key)
+            let sig_share = if parity_flip {
+                s!(effective_k1 + lambda * challenge * { s!(-share_value) })
+            } else {
+                s!(effective_k1 + lambda * challenge * share_value)
+            };
+            (party_idx, rank, sig_share.public())
+        })
+        .collect();
 
-        // Add to running sum
-        let sum = s!(sig_shares_sum + sig_share);
-        sig_shares_sum = sum.public();
+    let mut _sig_shares: Vec<DkgSignatureShareOutput> = Vec::new();
+    let mut sig_shares: Vec<Scalar<Public, Zero>> = Vec::with_capacity(computed.len());
+    for (party_idx, rank, sig_share) in computed {
+        sig_shares.push(sig_share);
 
         let sig_share_hex = hex::encode(sig_share.to_bytes());
         _sig_shares.push(DkgSignatureShareOutput {
             party_index: party_idx,
             rank,
-            session_id: session_id.clone(),
+            session_id: session_id.to_string(),
             sighash: sighash_hex.clone(),
             signature_share: sig_share_hex,
+            aggregator: None,
+            protocol_version: payload::PROTOCOL_VERSION,
             event_type: "dkg_signature_share".to_string(),
+            auth: None,
         });
 
         out.push_str(&format!("   Party {}: ✓ share created\n", party_idx));
     }
 
-    out.push_str("\n");
+    out.push('\n');
 
     // Step 5: Combine signatures with taptweak
     out.push_str("🔗 Combining signature shares...\n");
+    let sig_shares_sum = aggregate_signature_shares(&sig_shares);
 
     // Compute tweak contribution e * t (challenge already computed above)
     let tweak_contribution = s!(challenge * taptweak);
@@ -1312,54 +2772,5 @@ pub fn frost_sign_all_local(
         parity_flip
     ));
 
-    // Step 6: Create signed transaction
-    let unsigned_tx_hex = bitcoin::consensus::encode::serialize_hex(&tx);
-    let tx_bytes = hex::decode(&unsigned_tx_hex)?;
-    let mut signed_tx: Transaction = bitcoin::consensus::deserialize(&tx_bytes)?;
-
-    // Add witness
-    signed_tx.input[0].witness = Witness::from_slice(&[&sig_64[..]]);
-
-    let raw_tx = bitcoin::consensus::encode::serialize_hex(&signed_tx);
-    let txid = signed_tx.compute_txid();
-
-    out.push_str("📡 Broadcasting transaction...\n");
-
-    // Broadcast
-    let explorer_url = match network {
-        Network::Testnet => format!("https://mempool.space/testnet/tx/{}", txid),
-        Network::Signet => format!("https://mempool.space/signet/tx/{}", txid),
-        Network::Bitcoin => format!("https://mempool.space/tx/{}", txid),
-        _ => format!("https://mempool.space/testnet/tx/{}", txid),
-    };
-
-    match broadcast_transaction(&raw_tx, network) {
-        Ok(_) => {
-            out.push_str(&format!("\n✅ Transaction broadcast successfully!\n"));
-            out.push_str(&format!("   TxID: {}\n", txid));
-            out.push_str(&format!("   Explorer: {}\n", explorer_url));
-        }
-        Err(e) => {
-            out.push_str(&format!("\n⚠️ Broadcast failed: {}\n", e));
-            out.push_str("   Raw transaction saved for manual broadcast.\n");
-        }
-    }
-
-    let output = AutoSignResult {
-        txid: txid.to_string(),
-        raw_tx,
-        from_address: from_address.to_string(),
-        to_address: dest_address.to_string(),
-        amount_sats,
-        fee_sats: estimated_fee,
-        network: network_name(network).to_string(),
-        explorer_url,
-        signers: selected_parties.to_vec(),
-        event_type: "frost_auto_sign".to_string(),
-    };
-
-    Ok(CommandResult {
-        output: out,
-        result: serde_json::to_string(&output)?,
-    })
+    Ok(sig_64)
 }
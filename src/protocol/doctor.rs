@@ -0,0 +1,955 @@
+//! Wallet Doctor
+//!
+//! `dkg-doctor` inspects a wallet folder and explains what it finds:
+//!
+//! - key files that are missing or no longer parse
+//! - `group_info.json` missing, damaged, or describing another key
+//! - secret round state left behind after keygen finished
+//! - signing sessions untouched for a week, with their secret nonces
+//! - damaged JSON metadata
+//! - party folders without a share or holding another key's share, and the
+//!   legacy layout (share in the wallet root, no `party*` folders)
+//! - a write-ahead journal or temp files left by a crash mid-write
+//! - folders and files other users on the machine can read (Unix)
+//!
+//! Only repairs that lose nothing are offered: derivable files are rebuilt or
+//! copied from a party folder, interrupted journaled writes are finished,
+//! permissions are narrowed to the owner, and obsolete secrets are shredded through [`FileStorage::delete`]. Everything
+//! else is explained and left alone.
+
+use crate::protocol::backup;
+use crate::protocol::device;
+use crate::protocol::keygen::{self, FrostKey, GroupInfo, HtssMetadata};
+use crate::storage::{self, journal, FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::Result;
+use schnorr_fun::frost::SharedKey;
+use schnorr_fun::fun::marker::EvenY;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Signing sessions untouched for this long are considered abandoned
+pub const STALE_SESSION_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Per-session files as (prefix, suffix), longer prefixes first
+const SESSION_FILES: &[(&str, &str)] = &[
+    ("dkg_session_nonces_", ".json"),
+    ("dkg_tweaked_pubkey_", ".bin"),
+    ("dkg_final_nonce_", ".bin"),
+    ("dkg_parity_flip_", ".bin"),
+    ("session_nonces_", ".json"),
+    ("frost_session_", ".json"),
+    ("frost_nonce_", ".json"),
+    ("dkg_session_", ".json"),
+    ("final_nonce_", ".bin"),
+    ("dkg_nonce_", ".bin"),
+    ("nonce_", ".bin"),
+];
+
+/// Session files holding a party's secret nonce
+const SECRET_NONCE_PREFIXES: &[&str] = &["nonce_", "dkg_nonce_", "frost_nonce_"];
+
+/// Secret keygen state that is obsolete once the wallet holds its share
+const ROUND_STATE_FILES: &[&str] = &[
+    "round1_state.json",
+    "my_secret_shares.json",
+    keygen::CURVE_ROUND1_STATE_FILE,
+];
+
+/// Plaintext secrets written by older versions
+const LEGACY_SECRET_FILES: &[&str] = &["share_hex.txt", "secret_coefficient.txt"];
+
+/// JSON metadata that must at least parse
+const JSON_FILES: &[&str] = &[
+    "hd_metadata.json",
+    "activity.json",
+    "identities.json",
+    "taproot_trees.json",
+    "webhooks.json",
+    "sync.json",
+    "sync_state.json",
+    backup::SHARE_BACKUP_FILE,
+    backup::MNEMONIC_BACKUP_FILE,
+    device::DEVICE_FILE,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn icon(&self) -> &'static str {
+        match self {
+            Self::Info => "ℹ️ ",
+            Self::Warning => "⚠️ ",
+            Self::Error => "❌",
+        }
+    }
+}
+
+/// A repair that loses nothing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Repair {
+    /// Rebuild the root `group_info.json` from the key and commitments in `source`
+    RegenerateGroupInfo { source: PathBuf },
+    /// Copy a public file from a party folder
+    Restore { from: PathBuf, to: PathBuf },
+    /// Overwrite and delete obsolete files
+    Shred(Vec<PathBuf>),
+    /// Finish the writes recorded in the folder's write-ahead journal
+    ReplayJournal { dir: PathBuf },
+    /// Make folders 0700 and files 0600
+    RestrictPermissions(Vec<PathBuf>),
+}
+
+/// One finding, with what it means
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    /// File or folder, relative to the wallet folder
+    pub path: String,
+    pub problem: String,
+    pub explanation: String,
+    pub repair: Option<Repair>,
+}
+
+impl Issue {
+    fn new(severity: Severity, path: &str, problem: &str, explanation: &str) -> Self {
+        Self {
+            severity,
+            path: path.to_string(),
+            problem: problem.to_string(),
+            explanation: explanation.to_string(),
+            repair: None,
+        }
+    }
+
+    fn with_repair(mut self, repair: Option<Repair>) -> Self {
+        self.repair = repair;
+        self
+    }
+}
+
+fn rel(wallet_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(wallet_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn storage_for(dir: &Path) -> Result<FileStorage> {
+    FileStorage::new(&dir.to_string_lossy())
+}
+
+/// `party<N>` subfolders, by index
+pub(crate) fn party_dirs(wallet_dir: &Path) -> Result<Vec<(u32, PathBuf)>> {
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(wallet_dir)? {
+        let path = entry?.path();
+        let index = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("party"))
+            .and_then(|n| n.parse::<u32>().ok());
+        if let (true, Some(index)) = (path.is_dir(), index) {
+            dirs.push((index, path));
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// X-only group key (hex) from `shared_key.bin`, if present and readable
+fn shared_key_hex(dir: &Path) -> Option<String> {
+    let bytes = std::fs::read(dir.join("shared_key.bin")).ok()?;
+    let shared_key: SharedKey<EvenY> = bincode::deserialize(&bytes).ok()?;
+    Some(hex::encode(shared_key.public_key().to_xonly_bytes()))
+}
+
+fn parses<T: serde::de::DeserializeOwned>(path: &Path) -> bool {
+    std::fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<T>(&bytes).ok())
+        .is_some()
+}
+
+fn modified_secs(path: &Path, now: u64) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(now)
+}
+
+/// Session id a per-session file belongs to, and whether it is a secret nonce
+pub(crate) fn session_of(file: &str) -> Option<(&str, bool)> {
+    SESSION_FILES.iter().find_map(|(prefix, suffix)| {
+        let session = file.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        Some((session, SECRET_NONCE_PREFIXES.contains(prefix)))
+    })
+}
+
+/// Checks that apply to the wallet root and to every party folder
+fn check_folder(wallet_dir: &Path, dir: &Path, now: u64, issues: &mut Vec<Issue>) -> Result<()> {
+    let journal_path = dir.join(journal::JOURNAL_FILE);
+    match journal::pending(&storage_for(dir)?) {
+        Ok(Some(entry)) => issues.push(
+            Issue::new(
+                Severity::Error,
+                &rel(wallet_dir, &journal_path),
+                &format!("interrupted {}", entry.operation),
+                &format!(
+                    "A crash or kill stopped this step after it had recorded its \
+                     writes ({}) but before all of them landed, so these files may \
+                     be stale or missing. Replaying the journal finishes the step.",
+                    entry.keys().join(", ")
+                ),
+            )
+            .with_repair(Some(Repair::ReplayJournal {
+                dir: dir.to_path_buf(),
+            })),
+        ),
+        Ok(None) => {}
+        Err(_) => issues.push(Issue::new(
+            Severity::Error,
+            &rel(wallet_dir, &journal_path),
+            "damaged journal",
+            "A step was interrupted mid-write and its journal can't be read, so \
+             it can't be finished from here. Compare this folder with another \
+             party's copy or a backup, or run the step again.",
+        )),
+    }
+
+    let mut temp_files = Vec::new();
+    let mut exposed: Vec<PathBuf> = storage::exposed_mode(dir)
+        .map(|_| dir.to_path_buf())
+        .into_iter()
+        .collect();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if !path.is_file() {
+            continue;
+        }
+        // The journal is replayed and the lock belongs to a running command
+        if name != journal::JOURNAL_FILE
+            && name != storage::lock::LOCK_FILE
+            && storage::is_scratch_file(name)
+        {
+            temp_files.push(path.clone());
+        }
+        if storage::exposed_mode(&path).is_some() {
+            exposed.push(path);
+        }
+    }
+    exposed.sort();
+    if !exposed.is_empty() {
+        let folder = match rel(wallet_dir, dir) {
+            folder if folder.is_empty() => ".".to_string(),
+            folder => folder,
+        };
+        let names: Vec<String> = exposed
+            .iter()
+            .map(|path| match path == dir {
+                true => "the folder itself".to_string(),
+                false => rel(dir, path),
+            })
+            .collect();
+        issues.push(
+            Issue::new(
+                Severity::Error,
+                &folder,
+                "readable by other users",
+                &format!(
+                    "Other accounts on this machine can open {} here, which \
+                     may include your share and signing nonces. frostdao refuses \
+                     to load secrets from such files.",
+                    names.join(", ")
+                ),
+            )
+            .with_repair(Some(Repair::RestrictPermissions(exposed))),
+        );
+    }
+
+    temp_files.sort();
+    for path in temp_files {
+        issues.push(
+            Issue::new(
+                Severity::Warning,
+                &rel(wallet_dir, &path),
+                "temp file from an interrupted write",
+                "The write it belonged to never completed, so the real file still \
+                 has its previous contents. The temp file may hold a copy of a \
+                 secret.",
+            )
+            .with_repair(Some(Repair::Shred(vec![path]))),
+        );
+    }
+
+    let finalized = dir.join("paired_secret_share.bin").exists()
+        || dir.join(keygen::FROST_KEY_FILE).exists()
+        || dir.join(device::DEVICE_FILE).exists();
+
+    let leftovers: Vec<PathBuf> = ROUND_STATE_FILES
+        .iter()
+        .filter(|_| finalized)
+        .chain(LEGACY_SECRET_FILES)
+        .map(|file| dir.join(file))
+        .filter(|path| path.exists())
+        .collect();
+    for path in &leftovers {
+        issues.push(
+            Issue::new(
+                Severity::Warning,
+                &rel(wallet_dir, path),
+                "secret round state left on disk",
+                "Keygen is finished, so this polynomial/share material is no longer \
+                 needed; it only widens what a stolen disk gives away.",
+            )
+            .with_repair(Some(Repair::Shred(vec![path.clone()]))),
+        );
+    }
+
+    let htss_path = dir.join("htss_metadata.json");
+    if htss_path.exists() && !parses::<HtssMetadata>(&htss_path) {
+        issues.push(Issue::new(
+            Severity::Error,
+            &rel(wallet_dir, &htss_path),
+            "damaged",
+            "Threshold and party ranks can't be read, so signing and listing this \
+             wallet will fail. Restore it from a backup or another device.",
+        ));
+    }
+    for file in JSON_FILES {
+        let path = dir.join(file);
+        if path.exists() && !parses::<serde_json::Value>(&path) {
+            issues.push(Issue::new(
+                Severity::Error,
+                &rel(wallet_dir, &path),
+                "not valid JSON",
+                "The file is damaged and commands reading it will fail. Restore it \
+                 from a backup or with dkg-sync-pull.",
+            ));
+        }
+    }
+
+    let mut sessions: BTreeMap<String, (Vec<PathBuf>, bool, u64)> = BTreeMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(file) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some((session, secret)) = session_of(file) else {
+            continue;
+        };
+        let modified = modified_secs(&path, now);
+        let (files, has_secret, newest) = sessions.entry(session.to_string()).or_default();
+        *has_secret |= secret;
+        *newest = (*newest).max(modified);
+        files.push(path);
+    }
+    for (session, (mut files, has_secret, newest)) in sessions {
+        let age = now.saturating_sub(newest);
+        if age < STALE_SESSION_SECS {
+            continue;
+        }
+        files.sort();
+        let (severity, explanation) = if has_secret {
+            (
+                Severity::Warning,
+                "Nothing has touched this signing session for over a week and its \
+                 secret nonce is still on disk. A nonce must never sign twice; \
+                 start over with a new session instead of resuming this one.",
+            )
+        } else {
+            (
+                Severity::Info,
+                "Nothing has touched this signing session for over a week; its \
+                 transaction inputs have likely changed. Start a new session.",
+            )
+        };
+        let display = rel(wallet_dir, &dir.join(format!("*{}*", session)));
+        issues.push(
+            Issue::new(
+                severity,
+                &display,
+                &format!("session abandoned {} days ago", age / 86_400),
+                explanation,
+            )
+            .with_repair(Some(Repair::Shred(files))),
+        );
+    }
+
+    Ok(())
+}
+
+/// Root key, group info and layout checks
+fn check_root(
+    wallet_dir: &Path,
+    name: &str,
+    parties: &[(u32, PathBuf)],
+    issues: &mut Vec<Issue>,
+) -> Result<()> {
+    let generic_key = wallet_dir.join(keygen::FROST_KEY_FILE);
+    if generic_key.exists() {
+        if FrostKey::load(&storage_for(wallet_dir)?).is_err() {
+            issues.push(Issue::new(
+                Severity::Error,
+                keygen::FROST_KEY_FILE,
+                "damaged",
+                "The group key can't be read. Restore it from a backup or copy it \
+                 from another party; it holds no secrets.",
+            ));
+        }
+        return Ok(());
+    }
+
+    // A party folder with a readable key can stand in for the root's public files
+    let donor = parties
+        .iter()
+        .find(|(_, dir)| shared_key_hex(dir).is_some())
+        .map(|(_, dir)| dir.clone());
+    let restore = |file: &str| {
+        donor.as_ref().map(|from| Repair::Restore {
+            from: from.join(file),
+            to: wallet_dir.join(file),
+        })
+    };
+
+    let key_path = wallet_dir.join("shared_key.bin");
+    let root_key = shared_key_hex(wallet_dir);
+    if key_path.exists() && root_key.is_none() {
+        issues.push(
+            Issue::new(
+                Severity::Error,
+                "shared_key.bin",
+                "damaged",
+                "The group public key can't be read, so the wallet doesn't list and \
+                 can't sign. It holds no secrets and is the same in every party folder.",
+            )
+            .with_repair(restore("shared_key.bin")),
+        );
+    } else if !key_path.exists() {
+        if donor.is_some() {
+            issues.push(
+                Issue::new(
+                    Severity::Error,
+                    "shared_key.bin",
+                    "missing",
+                    "The wallet root has no group public key, so it isn't listed. \
+                     Its party folders have one.",
+                )
+                .with_repair(restore("shared_key.bin")),
+            );
+        } else if wallet_dir.join("round1_state.json").exists()
+            || wallet_dir.join(keygen::CURVE_ROUND1_STATE_FILE).exists()
+        {
+            issues.push(Issue::new(
+                Severity::Info,
+                "round1_state.json",
+                "key generation not finished",
+                "Round 1 ran but keygen-finalize never did. Run keygen-status to see \
+                 where it stopped and keygen-resume to finish with the other parties, \
+                 or delete the folder to start over.",
+            ));
+            return Ok(());
+        } else {
+            issues.push(Issue::new(
+                Severity::Error,
+                ".",
+                "no key material",
+                "Neither shared_key.bin nor frost_key.json is here. If keygen never \
+                 started this folder is not a wallet yet; otherwise restore the \
+                 wallet from a backup.",
+            ));
+            return Ok(());
+        }
+    }
+
+    let htss_path = wallet_dir.join("htss_metadata.json");
+    if !htss_path.exists() {
+        issues.push(
+            Issue::new(
+                Severity::Warning,
+                "htss_metadata.json",
+                "missing",
+                "Threshold and party ranks are unknown, so listings show '?' and \
+                 signing can't pick a signer set.",
+            )
+            .with_repair(restore("htss_metadata.json")),
+        );
+    }
+
+    // Group info, when it can be rebuilt from a folder holding the round 1 commitments
+    let key = root_key.or_else(|| donor.as_deref().and_then(shared_key_hex));
+    let mut derived: Option<(PathBuf, GroupInfo)> = None;
+    for dir in std::iter::once(wallet_dir).chain(parties.iter().map(|(_, dir)| dir.as_path())) {
+        let Ok(storage) = storage_for(dir) else {
+            continue;
+        };
+        if let Ok(info) = keygen::build_group_info(name, &storage) {
+            if Some(&info.group_public_key) == key.as_ref() {
+                derived = Some((dir.to_path_buf(), info));
+                break;
+            }
+        }
+    }
+    let rebuild = derived
+        .as_ref()
+        .map(|(source, _)| Repair::RegenerateGroupInfo {
+            source: source.clone(),
+        });
+    let no_source = if derived.is_none() {
+        " No folder here has the round 1 commitments to rebuild it from; ask \
+         another party for their copy."
+    } else {
+        ""
+    };
+
+    let info_path = wallet_dir.join("group_info.json");
+    if !info_path.exists() {
+        issues.push(
+            Issue::new(
+                Severity::Warning,
+                "group_info.json",
+                "missing",
+                &format!(
+                    "The shareable summary (addresses, parties by rank) was never \
+                     written or was deleted.{}",
+                    no_source
+                ),
+            )
+            .with_repair(rebuild),
+        );
+    } else {
+        match std::fs::read(&info_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<GroupInfo>(&bytes).ok())
+        {
+            None => issues.push(
+                Issue::new(
+                    Severity::Error,
+                    "group_info.json",
+                    "damaged",
+                    &format!("The shareable summary can't be read.{}", no_source),
+                )
+                .with_repair(rebuild),
+            ),
+            Some(info) if key.as_ref().is_some_and(|k| *k != info.group_public_key) => issues.push(
+                Issue::new(
+                    Severity::Warning,
+                    "group_info.json",
+                    "describes a different key",
+                    &format!(
+                        "Its group key isn't this wallet's (left over from before a \
+                             reshare?), so its addresses are wrong.{}",
+                        no_source
+                    ),
+                )
+                .with_repair(rebuild),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    // Layout
+    if wallet_dir.join("paired_secret_share.bin").exists() {
+        if parties.is_empty() {
+            issues.push(Issue::new(
+                Severity::Info,
+                "paired_secret_share.bin",
+                "legacy layout (share in the wallet root)",
+                "The TUI shows this share as the legacy share (party 0) rather than \
+                 under a party folder. It works as is; frostdao dkg-migrate-layout \
+                 moves it into party folders.",
+            ));
+        } else {
+            issues.push(Issue::new(
+                Severity::Warning,
+                "paired_secret_share.bin",
+                "share in both the root and party folders",
+                "The TUI ignores the root share once party folders exist. Check \
+                 which party it belongs to before moving or deleting it.",
+            ));
+        }
+    }
+
+    for (index, dir) in parties {
+        let path = rel(wallet_dir, dir);
+        if !dir.join("paired_secret_share.bin").exists() {
+            if dir.join(device::DEVICE_FILE).exists() {
+                issues.push(Issue::new(
+                    Severity::Info,
+                    &path,
+                    "share on a signing device",
+                    "This party signs through the device in device.json; the folder \
+                     only holds the public files.",
+                ));
+            } else if dir.join("round1_state.json").exists() {
+                issues.push(Issue::new(
+                    Severity::Info,
+                    &path,
+                    "key generation not finished",
+                    "This party ran round 1 but never finalized.",
+                ));
+            } else {
+                issues.push(Issue::new(
+                    Severity::Warning,
+                    &path,
+                    "party folder without a share",
+                    "The TUI only lists party folders holding a share, so this one \
+                     is ignored.",
+                ));
+            }
+            continue;
+        }
+        let party_key = shared_key_hex(dir);
+        if party_key.is_some() && party_key != key {
+            issues.push(Issue::new(
+                Severity::Error,
+                &path,
+                "share belongs to a different key",
+                "Its shared_key.bin differs from the wallet's, so this party's \
+                 signature shares won't combine. It may predate a reshare.",
+            ));
+        }
+        let own_index = std::fs::read(dir.join("htss_metadata.json"))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<HtssMetadata>(&bytes).ok())
+            .map(|htss| htss.my_index);
+        if let Some(own_index) = own_index.filter(|i| i != index) {
+            issues.push(Issue::new(
+                Severity::Warning,
+                &path,
+                &format!("holds party {}'s share", own_index),
+                "The folder name and its HTSS metadata disagree; the TUI signs as \
+                 the folder's party.",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Inspect a wallet folder
+pub fn diagnose(wallet_dir: &Path, name: &str, now: u64) -> Result<Vec<Issue>> {
+    if !wallet_dir.is_dir() {
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}",
+            name,
+            wallet_dir.display()
+        ))
+        .into());
+    }
+
+    let parties = party_dirs(wallet_dir)?;
+    let mut issues = Vec::new();
+    check_root(wallet_dir, name, &parties, &mut issues)?;
+    check_folder(wallet_dir, wallet_dir, now, &mut issues)?;
+    for (_, dir) in &parties {
+        check_folder(wallet_dir, dir, now, &mut issues)?;
+    }
+
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    Ok(issues)
+}
+
+fn describe(wallet_dir: &Path, repair: &Repair) -> String {
+    match repair {
+        Repair::RegenerateGroupInfo { source } if source == wallet_dir => {
+            "rebuild from shared_key.bin and the round 1 commitments".to_string()
+        }
+        Repair::RegenerateGroupInfo { source } => {
+            format!("rebuild from {}/", rel(wallet_dir, source))
+        }
+        Repair::Restore { from, .. } => format!("copy from {}", rel(wallet_dir, from)),
+        Repair::ReplayJournal { .. } => "finish the journaled writes".to_string(),
+        Repair::RestrictPermissions(_) => "make them private to you".to_string(),
+        Repair::Shred(files) if files.len() == 1 => "shred the file".to_string(),
+        Repair::Shred(files) => format!("shred its {} files", files.len()),
+    }
+}
+
+/// Apply one repair
+pub fn apply_repair(wallet_dir: &Path, name: &str, repair: &Repair) -> Result<()> {
+    match repair {
+        Repair::RegenerateGroupInfo { source } => {
+            let info = keygen::build_group_info(name, &storage_for(source)?)?;
+            storage_for(wallet_dir)?.write(
+                "group_info.json",
+                serde_json::to_string_pretty(&info)?.as_bytes(),
+            )?;
+        }
+        Repair::Restore { from, to } => {
+            std::fs::copy(from, to)?;
+        }
+        Repair::ReplayJournal { dir } => {
+            journal::replay(&storage_for(dir)?)?;
+        }
+        Repair::RestrictPermissions(paths) => {
+            for path in paths {
+                storage::restrict_permissions(path)?;
+            }
+        }
+        Repair::Shred(files) => {
+            for path in files {
+                let (Some(dir), Some(file)) = (path.parent(), path.file_name()) else {
+                    continue;
+                };
+                storage_for(dir)?.delete(&file.to_string_lossy())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Core function for `dkg-doctor`: report issues and optionally repair them
+pub fn doctor_core(wallet_dir: &Path, name: &str, fix: bool, now: u64) -> Result<CommandResult> {
+    let mut out = String::new();
+    let issues = diagnose(wallet_dir, name, now)?;
+
+    out.push_str("FROST Wallet Doctor\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("  Wallet: {}\n", name));
+    out.push_str(&format!("  Folder: {}\n", wallet_dir.display()));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+
+    let mut repaired = 0;
+    let mut failed = 0;
+    for issue in &issues {
+        out.push_str(&format!(
+            "{} {}: {}\n",
+            issue.severity.icon(),
+            issue.path,
+            issue.problem
+        ));
+        out.push_str(&format!("   {}\n", issue.explanation));
+        if let Some(repair) = &issue.repair {
+            let action = describe(wallet_dir, repair);
+            if !fix {
+                out.push_str(&format!("   🔧 Repair: {}\n", action));
+            } else {
+                match apply_repair(wallet_dir, name, repair) {
+                    Ok(()) => {
+                        repaired += 1;
+                        out.push_str(&format!("   ✓ Repaired: {}\n", action));
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        out.push_str(&format!("   ❌ Repair failed ({}): {}\n", action, e));
+                    }
+                }
+            }
+        }
+        out.push('\n');
+    }
+
+    let count = |severity: Severity| issues.iter().filter(|i| i.severity == severity).count();
+    let repairable = issues.iter().filter(|i| i.repair.is_some()).count();
+    if issues.is_empty() {
+        out.push_str("✓ No problems found\n");
+    } else {
+        out.push_str(&format!(
+            "{} issue(s): {} error(s), {} warning(s), {} note(s)\n",
+            issues.len(),
+            count(Severity::Error),
+            count(Severity::Warning),
+            count(Severity::Info)
+        ));
+        if fix {
+            out.push_str(&format!("🔧 {} repaired, {} failed\n", repaired, failed));
+        } else if repairable > 0 {
+            out.push_str(&format!(
+                "🔧 {} can be repaired: run again with --fix\n",
+                repairable
+            ));
+        }
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&serde_json::json!({
+            "issues": issues.len(),
+            "errors": count(Severity::Error),
+            "repairable": repairable,
+            "repaired": repaired,
+        }))?,
+    })
+}
+
+/// CLI wrapper for `dkg-doctor`
+pub fn doctor(name: &str, fix: bool) -> Result<()> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let state_dir = keygen::get_state_dir(name);
+    let result = doctor_core(Path::new(&state_dir), name, fix, now)?;
+    println!("{}", result.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::keygen::{finalize_core, round1_core, round2_core};
+
+    fn temp_dir() -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir =
+            std::env::temp_dir().join(format!("frostdao_doctor_{}_{}", std::process::id(), nanos));
+        storage::create_private_dir(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_doctor_finds_and_repairs() {
+        // 2-of-2 wallet in the TUI layout: party folders, public files in the root
+        let wallet = temp_dir();
+        let parties: Vec<FileStorage> = (1..=2)
+            .map(|i| storage_for(&wallet.join(format!("party{}", i))).unwrap())
+            .collect();
+        let round1: Vec<String> = (1..=2)
+            .map(|i| round1_core(2, 2, i, 0, false, &parties[i as usize - 1]).unwrap())
+            .map(|r| r.result)
+            .collect();
+        let round2: Vec<String> = parties
+            .iter()
+            .map(|p| round2_core(&round1.join(" "), p).unwrap().result)
+            .collect();
+        for party in &parties {
+            finalize_core(&round2.join(" "), party).unwrap();
+            assert!(!party.exists("round1_state.json"));
+            // Older versions kept the round state after finalize
+            party.write("round1_state.json", b"{}").unwrap();
+        }
+        let root = storage_for(&wallet).unwrap();
+        for file in ["shared_key.bin", "htss_metadata.json"] {
+            root.write(file, &parties[0].read(file).unwrap()).unwrap();
+        }
+
+        root.write("identities.json", b"{").unwrap();
+        parties[1].write("dkg_nonce_abc.bin", b"secret").unwrap();
+        parties[1].write("dkg_session_abc.json", b"{}").unwrap();
+
+        let now = modified_secs(&wallet, 0) + STALE_SESSION_SECS + 60;
+        let issues = diagnose(&wallet, "w", now).unwrap();
+        let found: Vec<(&str, &str)> = issues
+            .iter()
+            .map(|i| (i.path.as_str(), i.problem.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("identities.json", "not valid JSON"),
+                ("group_info.json", "missing"),
+                (
+                    "party1/round1_state.json",
+                    "secret round state left on disk"
+                ),
+                (
+                    "party2/round1_state.json",
+                    "secret round state left on disk"
+                ),
+                ("party2/*abc*", "session abandoned 7 days ago"),
+            ]
+        );
+
+        let result = doctor_core(&wallet, "w", true, now).unwrap();
+        assert!(result.output.contains("4 repaired, 0 failed"));
+        let remaining = diagnose(&wallet, "w", now).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(!parties[1].exists("dkg_nonce_abc.bin"));
+        let info: GroupInfo =
+            serde_json::from_slice(&root.read("group_info.json").unwrap()).unwrap();
+        assert_eq!(Some(info.group_public_key), shared_key_hex(&wallet));
+
+        std::fs::remove_dir_all(&wallet).unwrap();
+    }
+
+    #[test]
+    fn test_doctor_finishes_interrupted_writes() {
+        let wallet = temp_dir();
+        let party = storage_for(&wallet.join("party1")).unwrap();
+        let entry = journal::JournalEntry {
+            operation: "keygen-finalize".to_string(),
+            writes: [("wallet_notes.json".to_string(), Some(hex::encode(b"{}")))]
+                .into_iter()
+                .collect(),
+        };
+        party
+            .write(journal::JOURNAL_FILE, &serde_json::to_vec(&entry).unwrap())
+            .unwrap();
+        std::fs::write(wallet.join("party1/.paired_secret_share.bin.tmp"), b"half").unwrap();
+
+        let issues = diagnose(&wallet, "w", 0).unwrap();
+        let found: Vec<(&str, &str)> = issues
+            .iter()
+            .filter(|i| i.path.starts_with("party1/."))
+            .map(|i| (i.path.as_str(), i.problem.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("party1/.journal.json", "interrupted keygen-finalize"),
+                (
+                    "party1/.paired_secret_share.bin.tmp",
+                    "temp file from an interrupted write"
+                ),
+            ]
+        );
+
+        doctor_core(&wallet, "w", true, 0).unwrap();
+        assert_eq!(party.read("wallet_notes.json").unwrap(), b"{}");
+        assert!(!party.exists(journal::JOURNAL_FILE));
+        assert!(!party.exists(".paired_secret_share.bin.tmp"));
+
+        std::fs::remove_dir_all(&wallet).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_doctor_restricts_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let wallet = temp_dir();
+        let party = storage_for(&wallet.join("party1")).unwrap();
+        party.write("paired_secret_share.bin", b"share").unwrap();
+        party.write("htss_metadata.json", b"{}").unwrap();
+        for (path, mode) in [
+            (wallet.join("party1"), 0o755),
+            (wallet.join("party1/paired_secret_share.bin"), 0o644),
+        ] {
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        let issues = diagnose(&wallet, "w", 0).unwrap();
+        let issue = issues
+            .iter()
+            .find(|i| i.problem == "readable by other users")
+            .unwrap();
+        assert_eq!(issue.path, "party1");
+        assert!(issue
+            .explanation
+            .contains("the folder itself, paired_secret_share.bin here"));
+
+        doctor_core(&wallet, "w", true, 0).unwrap();
+        assert_eq!(storage::exposed_mode(&wallet.join("party1")), None);
+        assert!(party.read_secret("paired_secret_share.bin").is_ok());
+        assert!(diagnose(&wallet, "w", 0)
+            .unwrap()
+            .iter()
+            .all(|i| i.problem != "readable by other users"));
+
+        std::fs::remove_dir_all(&wallet).unwrap();
+    }
+}
@@ -0,0 +1,278 @@
+//! Protocol Progress Events
+//!
+//! Cores report progress as typed [`ProtocolEvent`]s instead of writing text
+//! directly. An [`EventLog`] records them, hands each one to the caller's
+//! [`Reporter`] as it happens (TUI, WASM and daemon frontends render these
+//! natively), and renders the CLI's educational text from the same events.
+//!
+//! `*_core` functions keep returning that text in `CommandResult.output`;
+//! their `*_core_with_reporter` variants take the reporter.
+
+use crate::protocol::reporter::Reporter;
+use crate::CommandResult;
+use serde::Serialize;
+
+/// `lines` argument for events with nothing to add
+pub const NO_LINES: [&str; 0] = [];
+
+const DIVIDER: &str = "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━";
+
+/// One step of progress in a protocol round
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProtocolEvent {
+    /// A round began, e.g. "FROST Keygen (TSS) - Round 1"
+    Started { title: String },
+    /// A configuration or derived value
+    Detail { label: String, value: String },
+    /// A computation is under way (⚙️)
+    Progress { message: String, lines: Vec<String> },
+    /// One party's input was checked or processed
+    Party { index: u32, status: String },
+    /// A check passed or a value was computed (❄️)
+    Success { message: String, lines: Vec<String> },
+    /// Tutorial explanation of what just happened (🧠)
+    Explain { title: String, lines: Vec<String> },
+    /// Tutorial question for the reader (❓)
+    Question { title: String, lines: Vec<String> },
+    /// Something the user should look at (⚠️)
+    Warning { message: String, lines: Vec<String> },
+    /// The round's payload is ready to share (✉️)
+    Produced { message: String },
+    /// What to do next (➜)
+    NextStep { instruction: String },
+    /// Text that has no structured form yet (e.g. from shared helpers)
+    Note { text: String },
+    /// Section separator
+    Divider,
+}
+
+impl ProtocolEvent {
+    /// CLI text for this event
+    pub fn render(&self, out: &mut String) {
+        fn block(out: &mut String, head: String, lines: &[String], gap: bool) {
+            out.push_str(&head);
+            out.push('\n');
+            for line in lines {
+                if line.is_empty() {
+                    out.push('\n');
+                } else {
+                    out.push_str(&format!("   {}\n", line));
+                }
+            }
+            if gap {
+                out.push('\n');
+            }
+        }
+
+        match self {
+            Self::Started { title } => out.push_str(&format!("{}\n\n", title)),
+            Self::Detail { label, value } => out.push_str(&format!("  {}: {}\n", label, value)),
+            Self::Progress { message, lines } => {
+                block(out, format!("⚙️  {}", message), lines, !lines.is_empty())
+            }
+            Self::Party { index, status } => {
+                out.push_str(&format!("   Party {}: {}\n", index, status))
+            }
+            Self::Success { message, lines } => block(out, format!("❄️  {}", message), lines, true),
+            Self::Explain { title, lines } => block(out, format!("🧠 {}", title), lines, true),
+            Self::Question { title, lines } => block(out, format!("❓ {}", title), lines, true),
+            Self::Warning { message, lines } => {
+                block(out, format!("⚠️  {}", message), lines, false)
+            }
+            Self::Produced { message } => out.push_str(&format!("✉️  {}\n\n", message)),
+            Self::NextStep { instruction } => out.push_str(&format!("➜ {}\n", instruction)),
+            Self::Note { text } => out.push_str(text),
+            Self::Divider => {
+                out.push_str(DIVIDER);
+                out.push('\n');
+            }
+        }
+    }
+}
+
+fn owned<S: Into<String>>(lines: impl IntoIterator<Item = S>) -> Vec<String> {
+    lines.into_iter().map(Into::into).collect()
+}
+
+/// Events of one core call, with an optional live reporter
+#[derive(Default)]
+pub struct EventLog<'a> {
+    events: Vec<ProtocolEvent>,
+    reporter: Option<&'a mut dyn Reporter>,
+}
+
+impl<'a> EventLog<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log that also hands each event to `reporter` as it is emitted
+    pub fn with_reporter(reporter: &'a mut dyn Reporter) -> Self {
+        Self {
+            events: Vec::new(),
+            reporter: Some(reporter),
+        }
+    }
+
+    pub fn emit(&mut self, event: ProtocolEvent) {
+        if let Some(reporter) = self.reporter.as_mut() {
+            reporter.report(&event);
+        }
+        self.events.push(event);
+    }
+
+    /// Report the step's result and package it with the rendered text
+    pub fn finish(&mut self, result: String) -> CommandResult {
+        if let Some(reporter) = self.reporter.as_mut() {
+            reporter.result(&result);
+        }
+        CommandResult {
+            output: self.render(),
+            result,
+        }
+    }
+
+    pub fn events(&self) -> &[ProtocolEvent] {
+        &self.events
+    }
+
+    /// CLI text for everything emitted so far
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for event in &self.events {
+            event.render(&mut out);
+        }
+        out
+    }
+
+    pub fn started(&mut self, title: impl Into<String>) {
+        self.emit(ProtocolEvent::Started {
+            title: title.into(),
+        });
+    }
+
+    pub fn detail(&mut self, label: impl Into<String>, value: impl ToString) {
+        self.emit(ProtocolEvent::Detail {
+            label: label.into(),
+            value: value.to_string(),
+        });
+    }
+
+    pub fn progress<S: Into<String>>(
+        &mut self,
+        message: impl Into<String>,
+        lines: impl IntoIterator<Item = S>,
+    ) {
+        self.emit(ProtocolEvent::Progress {
+            message: message.into(),
+            lines: owned(lines),
+        });
+    }
+
+    pub fn party(&mut self, index: u32, status: impl Into<String>) {
+        self.emit(ProtocolEvent::Party {
+            index,
+            status: status.into(),
+        });
+    }
+
+    pub fn success<S: Into<String>>(
+        &mut self,
+        message: impl Into<String>,
+        lines: impl IntoIterator<Item = S>,
+    ) {
+        self.emit(ProtocolEvent::Success {
+            message: message.into(),
+            lines: owned(lines),
+        });
+    }
+
+    pub fn explain<S: Into<String>>(
+        &mut self,
+        title: impl Into<String>,
+        lines: impl IntoIterator<Item = S>,
+    ) {
+        self.emit(ProtocolEvent::Explain {
+            title: title.into(),
+            lines: owned(lines),
+        });
+    }
+
+    pub fn question<S: Into<String>>(
+        &mut self,
+        title: impl Into<String>,
+        lines: impl IntoIterator<Item = S>,
+    ) {
+        self.emit(ProtocolEvent::Question {
+            title: title.into(),
+            lines: owned(lines),
+        });
+    }
+
+    pub fn warning<S: Into<String>>(
+        &mut self,
+        message: impl Into<String>,
+        lines: impl IntoIterator<Item = S>,
+    ) {
+        self.emit(ProtocolEvent::Warning {
+            message: message.into(),
+            lines: owned(lines),
+        });
+    }
+
+    pub fn produced(&mut self, message: impl Into<String>) {
+        self.emit(ProtocolEvent::Produced {
+            message: message.into(),
+        });
+    }
+
+    pub fn next_step(&mut self, instruction: impl Into<String>) {
+        self.emit(ProtocolEvent::NextStep {
+            instruction: instruction.into(),
+        });
+    }
+
+    /// Free-form text, e.g. what a shared helper wrote into a `String`
+    pub fn note(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if !text.is_empty() {
+            self.emit(ProtocolEvent::Note { text });
+        }
+    }
+
+    pub fn divider(&mut self) {
+        self.emit(ProtocolEvent::Divider);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::reporter::RecordingReporter;
+
+    #[test]
+    fn test_reporter_sees_events_and_render_matches() {
+        let mut reporter = RecordingReporter::default();
+        let mut log = EventLog::with_reporter(&mut reporter);
+        log.started("FROST Keygen (TSS) - Round 1");
+        log.party(2, "Commitment validated");
+        log.explain("Why?", ["Because", "", "  • indented"]);
+        log.next_step("Run: frostdao keygen-round2");
+        let events = log.events().to_vec();
+        let cmd_result = log.finish("{}".to_string());
+        let seen = reporter.events;
+
+        assert_eq!(seen, events);
+        assert_eq!(reporter.result.as_deref(), Some("{}"));
+        assert_eq!(
+            cmd_result.output,
+            "FROST Keygen (TSS) - Round 1\n\n   Party 2: Commitment validated\n\
+             🧠 Why?\n   Because\n\n     • indented\n\n➜ Run: frostdao keygen-round2\n"
+        );
+        assert_eq!(
+            serde_json::to_value(&seen[1]).unwrap(),
+            serde_json::json!({"event": "party", "index": 2, "status": "Commitment validated"})
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! Ceremony Fingerprint
+//!
+//! A short value every party can read out over a phone call to confirm they
+//! all finished keygen with the same group key from the same Round 1
+//! commitments, before anyone funds the wallet. It is a tagged hash of the
+//! group key and the commitment set (sorted by party, so the order payloads
+//! were pasted in doesn't matter), shown two ways:
+//!
+//! - 8 emoji with names (48 bits), quick to compare aloud
+//! - a 16-digit hex code (64 bits), for a stricter check
+
+use crate::crypto::helpers::tagged_hash;
+use crate::protocol::keygen::{self, get_state_dir, parse_space_separated_json, FrostKey};
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use schnorr_fun::frost::SharedKey;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const TAG: &str = "FrostDAO/CeremonyFingerprint";
+
+/// Emoji and names, one per 6 bits (the SAS set used by Matrix)
+const EMOJI: [(&str, &str); 64] = [
+    ("🐶", "Dog"),
+    ("🐱", "Cat"),
+    ("🦁", "Lion"),
+    ("🐎", "Horse"),
+    ("🦄", "Unicorn"),
+    ("🐷", "Pig"),
+    ("🐘", "Elephant"),
+    ("🐰", "Rabbit"),
+    ("🐼", "Panda"),
+    ("🐓", "Rooster"),
+    ("🐧", "Penguin"),
+    ("🐢", "Turtle"),
+    ("🐟", "Fish"),
+    ("🐙", "Octopus"),
+    ("🦋", "Butterfly"),
+    ("🌷", "Flower"),
+    ("🌳", "Tree"),
+    ("🌵", "Cactus"),
+    ("🍄", "Mushroom"),
+    ("🌏", "Globe"),
+    ("🌙", "Moon"),
+    ("☁️", "Cloud"),
+    ("🔥", "Fire"),
+    ("🍌", "Banana"),
+    ("🍎", "Apple"),
+    ("🍓", "Strawberry"),
+    ("🌽", "Corn"),
+    ("🍕", "Pizza"),
+    ("🎂", "Cake"),
+    ("❤️", "Heart"),
+    ("😀", "Smiley"),
+    ("🤖", "Robot"),
+    ("🎩", "Hat"),
+    ("👓", "Glasses"),
+    ("🔧", "Spanner"),
+    ("🎅", "Santa"),
+    ("👍", "Thumbs Up"),
+    ("☂️", "Umbrella"),
+    ("⌛", "Hourglass"),
+    ("⏰", "Clock"),
+    ("🎁", "Gift"),
+    ("💡", "Light Bulb"),
+    ("📕", "Book"),
+    ("✏️", "Pencil"),
+    ("📎", "Paperclip"),
+    ("✂️", "Scissors"),
+    ("🔒", "Lock"),
+    ("🔑", "Key"),
+    ("🔨", "Hammer"),
+    ("☎️", "Telephone"),
+    ("🏁", "Flag"),
+    ("🚂", "Train"),
+    ("🚲", "Bicycle"),
+    ("✈️", "Aeroplane"),
+    ("🚀", "Rocket"),
+    ("🏆", "Trophy"),
+    ("⚽", "Ball"),
+    ("🎸", "Guitar"),
+    ("🎺", "Trumpet"),
+    ("🔔", "Bell"),
+    ("⚓", "Anchor"),
+    ("🎧", "Headphones"),
+    ("📁", "Folder"),
+    ("📌", "Pin"),
+];
+
+/// A wallet's ceremony fingerprint
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CeremonyFingerprint {
+    /// 16 hex digits in groups of 4, e.g. `3f2a-91bc-07de-55a1`
+    pub code: String,
+    pub emoji: Vec<String>,
+    pub names: Vec<String>,
+    /// False when the wallet kept no keygen commitments (e.g. after a
+    /// reshare); the fingerprint then covers the group key only
+    pub covers_commitments: bool,
+}
+
+impl CeremonyFingerprint {
+    fn from_hash(hash: &[u8; 32], covers_commitments: bool) -> Self {
+        let bits = hash[..6]
+            .iter()
+            .fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+        let (emoji, names) = (0..8)
+            .map(|i| EMOJI[((bits >> (42 - 6 * i)) & 0x3f) as usize])
+            .map(|(emoji, name)| (emoji.to_string(), name.to_string()))
+            .unzip();
+        let code = hash[..8]
+            .chunks(2)
+            .map(hex::encode)
+            .collect::<Vec<_>>()
+            .join("-");
+        Self {
+            code,
+            emoji,
+            names,
+            covers_commitments,
+        }
+    }
+
+    /// `🐶 🔑 ... (Dog, Key, ...)`
+    pub fn emoji_line(&self) -> String {
+        format!("{} ({})", self.emoji.join(" "), self.names.join(", "))
+    }
+}
+
+/// Group public key bytes of the finished wallet in `storage`
+fn group_key_bytes(storage: &dyn Storage) -> Result<Vec<u8>> {
+    if keygen::is_curve_wallet(storage) {
+        let key = FrostKey::load(storage)?;
+        let mut bytes = key.curve.to_string().into_bytes();
+        bytes.extend(hex::decode(&key.group_public_key)?);
+        return Ok(bytes);
+    }
+    let bytes = storage
+        .read("shared_key.bin")
+        .context("No group key here. Did you run keygen-finalize?")?;
+    let shared_key: SharedKey<EvenY> = bincode::deserialize(&bytes)?;
+    Ok(shared_key.public_key().to_xonly_bytes().to_vec())
+}
+
+/// Fingerprint of the wallet in `storage`
+pub fn ceremony_fingerprint(storage: &dyn Storage) -> Result<CeremonyFingerprint> {
+    let mut data = group_key_bytes(storage)?;
+    let covers_commitments = storage.exists("all_commitments.json");
+    if covers_commitments {
+        let json = String::from_utf8(storage.read("all_commitments.json")?)?;
+        let mut outputs = parse_space_separated_json::<Value>(&json)?;
+        outputs.sort_by_key(|output| output["party_index"].as_u64());
+        for output in outputs {
+            let party = output["party_index"]
+                .as_u64()
+                .context("all_commitments.json has an output without party_index")?;
+            data.extend((party as u32).to_be_bytes());
+            for field in ["keygen_input", "identity"] {
+                let value = output[field].as_str().unwrap_or_default();
+                data.extend((value.len() as u32).to_be_bytes());
+                data.extend(value.as_bytes());
+            }
+        }
+    }
+    Ok(CeremonyFingerprint::from_hash(
+        &tagged_hash(TAG, &data),
+        covers_commitments,
+    ))
+}
+
+/// Core function for `dkg-fingerprint`
+pub fn fingerprint_core(storage: &dyn Storage) -> Result<CommandResult> {
+    let fingerprint = ceremony_fingerprint(storage)?;
+    let mut out = String::new();
+    out.push_str("Ceremony Fingerprint\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("{}\n", fingerprint.emoji_line()));
+    out.push_str(&format!("Code: {}\n", fingerprint.code));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    if fingerprint.covers_commitments {
+        out.push_str("Every party should read out the same emoji (or code). If any differ,\n");
+        out.push_str("keygen went wrong or was tampered with: do not fund the wallet.\n");
+    } else {
+        out.push_str(
+            "⚠️  No keygen commitments in this wallet, so this covers the group key only.\n",
+        );
+    }
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&fingerprint)?,
+    })
+}
+
+pub fn fingerprint(name: &str) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = fingerprint_core(&storage)?;
+    println!("{}", cmd_result.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::keygen;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_parties_share_one_fingerprint() {
+        let parties: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+        let r1: Vec<String> = parties
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                keygen::round1_core(2, 3, i as u32 + 1, 0, false, p)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        // Each party pastes the Round 1 outputs in a different order
+        let r2: Vec<String> = parties
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let mut order = r1.clone();
+                order.rotate_left(i);
+                keygen::round2_core(&order.join(" "), p).unwrap().result
+            })
+            .collect();
+        let finalized: Vec<String> = parties
+            .iter()
+            .map(|p| keygen::finalize_core(&r2.join(" "), p).unwrap().result)
+            .collect();
+
+        let fingerprints: Vec<_> = parties
+            .iter()
+            .map(|p| ceremony_fingerprint(p).unwrap())
+            .collect();
+        assert!(fingerprints.iter().all(|f| f == &fingerprints[0]));
+        assert!(fingerprints[0].covers_commitments);
+        assert_eq!(fingerprints[0].emoji.len(), 8);
+        assert_eq!(fingerprints[0].code.len(), 19);
+        assert!(finalized[0].ends_with(&format!("Fingerprint: {}", fingerprints[0].code)));
+
+        // Without the commitments it still pins the group key, but differs
+        parties[0].delete("all_commitments.json").unwrap();
+        let key_only = ceremony_fingerprint(&parties[0]).unwrap();
+        assert!(!key_only.covers_commitments);
+        assert_ne!(key_only.code, fingerprints[0].code);
+        assert!(ceremony_fingerprint(&MemoryStorage::new()).is_err());
+    }
+}
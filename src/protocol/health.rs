@@ -0,0 +1,412 @@
+//! Wallet Health
+//!
+//! `dkg-health` checks a wallet against the policy in
+//! `.frost_state/health.json` (`health-config`) and warns when
+//!
+//! - its shares are older than the policy allows (counted from keygen, or
+//!   from the last reshare, which refreshes every share),
+//! - a party has not signed in the last N signing sessions, or
+//! - no mnemonic backup of the share was ever generated.
+//!
+//! Creation and refresh times live in `htss_metadata.json`. Signing
+//! participation and backups are recorded per wallet folder in
+//! `activity.json`: each party counts the sessions it signs in and notes who
+//! else signed. The TUI shows the same warnings above the wallet details.
+
+use crate::protocol::doctor;
+use crate::protocol::keygen::HtssMetadata;
+use crate::protocol::wallet::STATE_ROOT;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const HEALTH_POLICY_FILE: &str = "health.json";
+pub const ACTIVITY_FILE: &str = "activity.json";
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// When a wallet counts as needing attention (`.frost_state/health.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HealthPolicy {
+    /// Refresh (reshare) shares at least this often
+    pub max_share_age_days: u64,
+    /// Warn about a party that sat out this many sessions in a row
+    pub max_missed_sessions: u64,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        Self {
+            max_share_age_days: 365,
+            max_missed_sessions: 10,
+        }
+    }
+}
+
+/// Load the policy (defaults if none)
+pub fn load_policy(storage: &dyn Storage) -> Result<HealthPolicy> {
+    if !storage.exists(HEALTH_POLICY_FILE) {
+        return Ok(HealthPolicy::default());
+    }
+    serde_json::from_slice(&storage.read(HEALTH_POLICY_FILE)?)
+        .with_context(|| format!("{} is damaged", HEALTH_POLICY_FILE))
+}
+
+/// Signing sessions and backups seen by one wallet folder
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Activity {
+    /// Signing sessions this folder's share signed in
+    #[serde(default)]
+    pub sessions: u64,
+    /// Last session counted, so signing a session again counts once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_session: Option<String>,
+    /// Party index -> `sessions` when that party last signed alongside us
+    #[serde(default)]
+    pub last_signed: BTreeMap<u32, u64>,
+    /// Unix time a backup of the share (mnemonic or split) was last generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_at: Option<u64>,
+}
+
+impl Activity {
+    pub fn load(storage: &dyn Storage) -> Result<Self> {
+        if !storage.exists(ACTIVITY_FILE) {
+            return Ok(Self::default());
+        }
+        serde_json::from_slice(&storage.read(ACTIVITY_FILE)?)
+            .with_context(|| format!("{} is damaged", ACTIVITY_FILE))
+    }
+
+    fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.write(
+            ACTIVITY_FILE,
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// Sessions in a row `party` has not signed in
+    pub fn missed(&self, party: u32) -> u64 {
+        self.sessions - self.last_signed.get(&party).copied().unwrap_or(0)
+    }
+}
+
+/// Count a signing session this folder signed in, with everyone who signed
+pub fn record_session(storage: &dyn Storage, session: &str, signers: &[u32]) -> Result<()> {
+    let mut activity = Activity::load(storage)?;
+    if activity.last_session.as_deref() != Some(session) {
+        activity.sessions += 1;
+        activity.last_session = Some(session.to_string());
+    }
+    for &party in signers {
+        activity.last_signed.insert(party, activity.sessions);
+    }
+    activity.save(storage)
+}
+
+/// Note that a backup of this folder's share was generated (mnemonic or split)
+pub fn record_backup(storage: &dyn Storage) -> Result<()> {
+    let mut activity = Activity::load(storage)?;
+    activity.backup_at = Some(now());
+    activity.save(storage)
+}
+
+/// What `dkg-health` found
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refreshed_at: Option<u64>,
+    /// Days since keygen or the last reshare, if either is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_age_days: Option<u64>,
+    pub sessions: u64,
+    /// Party index -> sessions in a row it has not signed in
+    pub missed_sessions: BTreeMap<u32, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_at: Option<u64>,
+    pub warnings: Vec<String>,
+}
+
+/// Check the wallet folders holding `htss_metadata.json` (root and parties)
+pub fn check(storages: &[&dyn Storage], policy: &HealthPolicy, now: u64) -> Result<HealthReport> {
+    let Some(first) = storages.first() else {
+        bail!("No htss_metadata.json found; finish keygen first");
+    };
+    let metadata: HtssMetadata = serde_json::from_slice(&first.read("htss_metadata.json")?)
+        .context("htss_metadata.json is damaged")?;
+
+    // Folders of one wallet see the same sessions; the busiest saw the most
+    let mut activity = Activity::default();
+    let mut backup_at = None;
+    for storage in storages {
+        let folder = Activity::load(*storage)?;
+        backup_at = backup_at.max(folder.backup_at);
+        if folder.sessions >= activity.sessions {
+            activity = folder;
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let share_age_days = metadata
+        .refreshed_at
+        .or(metadata.created_at)
+        .map(|since| now.saturating_sub(since) / DAY_SECS);
+    if let Some(days) = share_age_days.filter(|&days| days > policy.max_share_age_days) {
+        warnings.push(format!(
+            "Shares are {} days old (policy: {}); refresh them with a reshare",
+            days, policy.max_share_age_days
+        ));
+    }
+
+    let missed_sessions: BTreeMap<u32, u64> = metadata
+        .party_ranks
+        .keys()
+        .map(|&party| (party, activity.missed(party)))
+        .collect();
+    for (party, missed) in &missed_sessions {
+        if *missed >= policy.max_missed_sessions && policy.max_missed_sessions > 0 {
+            warnings.push(format!(
+                "Party {} has not signed in the last {} sessions; check it still holds its share",
+                party, missed
+            ));
+        }
+    }
+
+    if backup_at.is_none() {
+        warnings.push(
+            "No mnemonic backup was ever generated; run dkg-generate-mnemonic or dkg-backup-split"
+                .to_string(),
+        );
+    }
+
+    Ok(HealthReport {
+        created_at: metadata.created_at,
+        refreshed_at: metadata.refreshed_at,
+        share_age_days,
+        sessions: activity.sessions,
+        missed_sessions,
+        backup_at,
+        warnings,
+    })
+}
+
+pub(crate) fn describe_time(time: Option<u64>, now: u64) -> String {
+    match time {
+        Some(time) => format!("{} days ago", now.saturating_sub(time) / DAY_SECS),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Core of `dkg-health`
+pub fn health_core(
+    storages: &[&dyn Storage],
+    policy: &HealthPolicy,
+    now: u64,
+) -> Result<CommandResult> {
+    let report = check(storages, policy, now)?;
+    let mut out = String::new();
+
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!(
+        "Created:       {}\n",
+        describe_time(report.created_at, now)
+    ));
+    out.push_str(&format!(
+        "Last reshare:  {}\n",
+        match report.refreshed_at {
+            Some(_) => describe_time(report.refreshed_at, now),
+            None => "never".to_string(),
+        }
+    ));
+    out.push_str(&format!(
+        "Backup:        {}\n",
+        match report.backup_at {
+            Some(_) => describe_time(report.backup_at, now),
+            None => "never".to_string(),
+        }
+    ));
+    out.push_str(&format!("Sessions:      {} signed\n", report.sessions));
+    for (party, missed) in &report.missed_sessions {
+        out.push_str(&format!(
+            "  Party {}: {}\n",
+            party,
+            match missed {
+                0 => "signed in the last session".to_string(),
+                n => format!("sat out the last {}", n),
+            }
+        ));
+    }
+    out.push_str(&format!(
+        "Policy:        refresh every {} days, flag {} missed sessions\n",
+        policy.max_share_age_days, policy.max_missed_sessions
+    ));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+
+    if report.warnings.is_empty() {
+        out.push_str("✓ Healthy\n");
+    }
+    for warning in &report.warnings {
+        out.push_str(&format!("⚠️  {}\n", warning));
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&report)?,
+    })
+}
+
+/// Wallet folders holding `htss_metadata.json`: the root, then party folders
+fn wallet_storages(dir: &Path) -> Result<Vec<FileStorage>> {
+    let mut folders = vec![dir.to_path_buf()];
+    folders.extend(doctor::party_dirs(dir)?.into_iter().map(|(_, path)| path));
+    let mut storages = Vec::new();
+    for folder in folders {
+        if folder.join("htss_metadata.json").exists() {
+            storages.push(FileStorage::new(&folder.to_string_lossy())?);
+        }
+    }
+    Ok(storages)
+}
+
+/// Warnings for the wallet in `dir`; none for watch-only or unreadable wallets
+pub fn wallet_warnings(dir: &Path, policy: &HealthPolicy, now: u64) -> Vec<String> {
+    let Ok(storages) = wallet_storages(dir) else {
+        return Vec::new();
+    };
+    if storages.is_empty() {
+        return Vec::new();
+    }
+    let storages: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+    check(&storages, policy, now)
+        .map(|report| report.warnings)
+        .unwrap_or_default()
+}
+
+/// CLI wrapper for `dkg-health`
+pub fn health(name: &str) -> Result<()> {
+    let dir = Path::new(STATE_ROOT).join(name);
+    if !dir.is_dir() {
+        bail!("Wallet '{}' not found", name);
+    }
+    let policy = load_policy(&FileStorage::new(STATE_ROOT)?)?;
+    let storages = wallet_storages(&dir)?;
+    let storages: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+    let result = health_core(&storages, &policy, now())?;
+    println!("Health of '{}'\n", name);
+    println!("{}", result.output);
+    Ok(())
+}
+
+/// CLI wrapper for `health-config`: change the policy, then show it
+pub fn configure(max_share_age_days: Option<u64>, max_missed_sessions: Option<u64>) -> Result<()> {
+    let storage = FileStorage::new(STATE_ROOT)?;
+    let mut policy = load_policy(&storage)?;
+    if let Some(days) = max_share_age_days {
+        policy.max_share_age_days = days;
+    }
+    if let Some(missed) = max_missed_sessions {
+        policy.max_missed_sessions = missed;
+    }
+    if max_share_age_days.is_some() || max_missed_sessions.is_some() {
+        storage.write(
+            HEALTH_POLICY_FILE,
+            serde_json::to_string_pretty(&policy)?.as_bytes(),
+        )?;
+        println!("✓ Health policy saved\n");
+    }
+    println!("Refresh shares every: {} days", policy.max_share_age_days);
+    println!(
+        "Flag a party after:   {} missed sessions",
+        policy.max_missed_sessions
+    );
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn wallet(created_at: Option<u64>, refreshed_at: Option<u64>) -> MemoryStorage {
+        let metadata = HtssMetadata {
+            my_index: 1,
+            my_rank: 0,
+            threshold: 2,
+            hierarchical: false,
+            party_ranks: BTreeMap::from([(1, 0), (2, 0), (3, 0)]),
+            wallet_version: crate::storage::migrations::CURRENT_WALLET_VERSION,
+            signing_requirement: BTreeMap::new(),
+            created_at,
+            refreshed_at,
+        };
+        let storage = MemoryStorage::new();
+        storage
+            .write(
+                "htss_metadata.json",
+                &serde_json::to_vec(&metadata).unwrap(),
+            )
+            .unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_health_warnings() {
+        let policy = HealthPolicy {
+            max_share_age_days: 30,
+            max_missed_sessions: 3,
+        };
+        let now = 100 * DAY_SECS;
+        let storage = wallet(Some(10 * DAY_SECS), None);
+        let check = |storage: &MemoryStorage| check(&[storage], &policy, now).unwrap();
+
+        let report = check(&storage);
+        assert_eq!(report.share_age_days, Some(90));
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[0].starts_with("Shares are 90 days old (policy: 30)"));
+        assert!(report.warnings[1].starts_with("No mnemonic backup"));
+
+        // Party 3 sits out every session; a repeated session counts once
+        for session in ["a", "b", "b", "c"] {
+            record_session(&storage, session, &[1, 2]).unwrap();
+        }
+        record_backup(&storage).unwrap();
+        let report = check(&storage);
+        assert_eq!(report.sessions, 3);
+        assert_eq!(
+            report.missed_sessions,
+            BTreeMap::from([(1, 0), (2, 0), (3, 3)])
+        );
+        assert!(report.backup_at.is_some());
+        assert_eq!(report.warnings.len(), 2);
+        assert!(report.warnings[1].starts_with("Party 3 has not signed in the last 3 sessions"));
+
+        // A reshare resets the share age
+        let refreshed = wallet(Some(10 * DAY_SECS), Some(95 * DAY_SECS));
+        record_backup(&refreshed).unwrap();
+        let report = check(&refreshed);
+        assert_eq!(report.share_age_days, Some(5));
+        assert!(report.warnings.is_empty());
+        assert!(health_core(&[&refreshed], &policy, now)
+            .unwrap()
+            .output
+            .contains("✓ Healthy"));
+    }
+}
@@ -0,0 +1,451 @@
+//! Party Identity Keys
+//!
+//! Round data is plain JSON, so nothing stops one participant from posting a
+//! payload with someone else's `party_index`. Each party therefore holds a
+//! long-term BIP340 identity key in its wallet directory and signs every
+//! payload it publishes: keygen rounds, nonces, signature shares, reshare
+//! and recovery sub-shares.
+//!
+//! Keygen Round 1 announces the key together with a proof of possession.
+//! Round 2 pins the roster (`identities.json`: party index → key), and from
+//! then on a payload is only accepted if it is signed by the key pinned for
+//! the index it claims. Compare the printed fingerprints out of band to rule
+//! out a substituted Round 1.
+//!
+//! The identity key doubles as the party's Nostr key (see `protocol::nostr`):
+//! Round 2 shares are NIP-44 encrypted to it.
+
+use crate::crypto::nip44;
+use crate::crypto::secret::SecretBytes;
+use crate::protocol::keygen::{get_state_dir, HtssMetadata};
+use crate::protocol::nostr;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use schnorr_fun::fun::marker::*;
+use schnorr_fun::{Message, Schnorr, Signature};
+use secp256kfun::prelude::*;
+use secp256kfun::KeyPair;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+
+/// Secret identity key (32-byte scalar)
+pub const IDENTITY_KEY_FILE: &str = "identity_key.bin";
+
+/// Pinned identity public keys, by party index
+pub const IDENTITY_ROSTER_FILE: &str = "identities.json";
+
+/// Domain separation for payload signatures
+const AUTH_TAG: &str = "frostdao/payload-auth";
+
+/// A payload that carries its sender's signature in an `auth` field
+pub trait Authenticated: Serialize {
+    /// Party index the payload claims to come from
+    fn signer_index(&self) -> u32;
+    /// Payload protocol version it was written at (see protocol::payload)
+    fn protocol_version(&self) -> u32;
+    fn auth(&self) -> Option<&str>;
+    fn set_auth(&mut self, auth: String);
+}
+
+/// Canonical bytes signed for a payload: its JSON minus `auth`, keys sorted
+fn signing_bytes<T: Serialize>(payload: &T) -> Result<Vec<u8>> {
+    let mut value = serde_json::to_value(payload)?;
+    if let Some(map) = value.as_object_mut() {
+        map.remove("auth");
+    }
+    Ok(serde_json::to_vec(&value)?)
+}
+
+fn schnorr() -> Schnorr<Sha256, schnorr_fun::nonce::Deterministic<Sha256>> {
+    schnorr_fun::new_with_deterministic_nonces::<Sha256>()
+}
+
+/// Comparable form of an identity key: its first 16 bytes (128 bits, so a
+/// look-alike key can't be ground out), in groups of four hex digits
+pub fn fingerprint(public_key_hex: &str) -> String {
+    public_key_hex
+        .as_bytes()
+        .chunks(4)
+        .take(8)
+        .map(|c| String::from_utf8_lossy(c).to_string())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// This party's signing identity for one wallet
+pub struct IdentityKey {
+    keypair: KeyPair<EvenY>,
+}
+
+impl IdentityKey {
+    /// Build from a raw 32-byte secret key
+    pub fn from_secret_bytes(bytes: &[u8]) -> Result<Self> {
+        let secret = Scalar::<Secret, NonZero>::from_slice(bytes).context("Invalid secret key")?;
+        Ok(Self {
+            keypair: schnorr().new_keypair(secret),
+        })
+    }
+
+    pub fn generate() -> Self {
+        Self::generate_with(&mut rand::thread_rng())
+    }
+
+    /// Draw the secret key from `rng`
+    pub fn generate_with(rng: &mut impl rand::RngCore) -> Self {
+        let secret = Scalar::<Secret, NonZero>::random(rng);
+        Self {
+            keypair: schnorr().new_keypair(secret),
+        }
+    }
+
+    /// The wallet's identity key, if it has one
+    pub fn load(storage: &dyn Storage) -> Result<Option<Self>> {
+        if !storage.exists(IDENTITY_KEY_FILE) {
+            return Ok(None);
+        }
+        let bytes = storage.read_secret(IDENTITY_KEY_FILE)?;
+        Ok(Some(
+            Self::from_secret_bytes(bytes.expose()).context("Corrupt identity key")?,
+        ))
+    }
+
+    /// Load the wallet's identity key, creating it on first use
+    pub fn load_or_create(storage: &dyn Storage) -> Result<Self> {
+        Self::load_or_create_with(storage, &mut rand::thread_rng())
+    }
+
+    /// [`Self::load_or_create`], drawing a new key from `rng`
+    pub fn load_or_create_with(
+        storage: &dyn Storage,
+        rng: &mut impl rand::RngCore,
+    ) -> Result<Self> {
+        if let Some(key) = Self::load(storage)? {
+            return Ok(key);
+        }
+        let key = Self::generate_with(rng);
+        key.save(storage)?;
+        Ok(key)
+    }
+
+    pub fn save(&self, storage: &dyn Storage) -> Result<()> {
+        let bytes = SecretBytes::new(self.keypair.secret_key().to_bytes().to_vec());
+        storage.write(IDENTITY_KEY_FILE, bytes.expose())
+    }
+
+    /// X-only public key, hex
+    pub fn public_hex(&self) -> String {
+        hex::encode(self.keypair.public_key().to_xonly_bytes())
+    }
+
+    /// NIP-44 conversation key shared with another party's identity
+    pub fn conversation_key(&self, their_public_hex: &str) -> Result<SecretBytes> {
+        let their_public = parse_public_key(their_public_hex).context("Malformed identity key")?;
+        Ok(nip44::conversation_key(
+            self.keypair.secret_key(),
+            &their_public,
+        ))
+    }
+
+    /// BIP340 signature over raw bytes (a Nostr event id), hex
+    pub fn sign_raw(&self, message: &[u8]) -> String {
+        let signature = schnorr().sign(&self.keypair, Message::raw(message));
+        hex::encode(signature.to_bytes())
+    }
+
+    /// Fill in the payload's `auth` field
+    pub fn sign<T: Authenticated>(&self, payload: &mut T) -> Result<()> {
+        let bytes = signing_bytes(payload)?;
+        let signature = schnorr().sign(&self.keypair, Message::new(AUTH_TAG, &bytes));
+        payload.set_auth(hex::encode(signature.to_bytes()));
+        Ok(())
+    }
+}
+
+/// Parse an x-only public key from hex
+pub fn parse_public_key(public_key_hex: &str) -> Option<Point<EvenY, Public>> {
+    hex::decode(public_key_hex)
+        .ok()
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+        .and_then(Point::<EvenY, Public>::from_xonly_bytes)
+}
+
+/// Check a payload's `auth` against a specific identity key
+pub fn verify_with_key<T: Authenticated>(payload: &T, public_key_hex: &str) -> Result<()> {
+    let index = payload.signer_index();
+    let auth = payload
+        .auth()
+        .with_context(|| format!("Payload from party {} is not signed", index))?;
+    let signature = hex::decode(auth)
+        .ok()
+        .and_then(|b| <[u8; 64]>::try_from(b).ok())
+        .and_then(Signature::from_bytes)
+        .with_context(|| format!("Malformed signature on party {}'s payload", index))?;
+    let public_key = parse_public_key(public_key_hex)
+        .with_context(|| format!("Malformed identity key for party {}", index))?;
+
+    let bytes = signing_bytes(payload)?;
+    if !schnorr().verify(&public_key, Message::new(AUTH_TAG, &bytes), &signature) {
+        bail!(
+            "Payload claiming party {} is not signed by that party's identity key",
+            index
+        );
+    }
+    Ok(())
+}
+
+/// Identity keys pinned for a wallet, by party index
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Roster(pub BTreeMap<u32, String>);
+
+impl Roster {
+    /// `None` for wallets created before payload signing existed
+    pub fn load(storage: &dyn Storage) -> Result<Option<Self>> {
+        if !storage.exists(IDENTITY_ROSTER_FILE) {
+            return Ok(None);
+        }
+        let json = String::from_utf8(storage.read(IDENTITY_ROSTER_FILE)?)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    pub fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.write(
+            IDENTITY_ROSTER_FILE,
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// Pin a key, refusing one identity for two indexes
+    pub fn pin(&mut self, index: u32, public_key_hex: &str) -> Result<()> {
+        if let Some((other, _)) = self
+            .0
+            .iter()
+            .find(|(i, key)| **i != index && key.as_str() == public_key_hex)
+        {
+            bail!(
+                "Parties {} and {} present the same identity key",
+                other,
+                index
+            );
+        }
+        self.0.insert(index, public_key_hex.to_string());
+        Ok(())
+    }
+
+    /// Accept a payload only if the pinned key for its index signed it
+    pub fn verify<T: Authenticated>(&self, payload: &T) -> Result<()> {
+        let index = payload.signer_index();
+        let key = self.0.get(&index).with_context(|| {
+            format!(
+                "Party {} has no pinned identity; add it with dkg-identity --trust {}=<key>",
+                index, index
+            )
+        })?;
+        verify_with_key(payload, key)
+    }
+
+    /// Fingerprint table for the educational output
+    pub fn describe(&self, out: &mut String) {
+        for (index, key) in &self.0 {
+            out.push_str(&format!("   Party {}: {}\n", index, fingerprint(key)));
+        }
+    }
+}
+
+/// Verify payloads against the wallet roster before they are used
+///
+/// Wallets from before payload signing have no roster; their payloads are
+/// accepted with a warning so existing wallets keep working.
+pub fn verify_payloads<T: Authenticated>(
+    payloads: &[T],
+    storage: &dyn Storage,
+    out: &mut String,
+) -> Result<()> {
+    let Some(roster) = Roster::load(storage)? else {
+        out.push_str("⚠️  Wallet has no identity roster: payloads are NOT authenticated\n");
+        out.push_str("   Pin party keys with: frostdao dkg-identity --trust <index>=<key>\n\n");
+        return Ok(());
+    };
+    for payload in payloads {
+        roster.verify(payload)?;
+    }
+    out.push_str(&format!(
+        "🪪 {} payload signature(s) verified against pinned identities\n\n",
+        payloads.len()
+    ));
+    Ok(())
+}
+
+/// Sign a payload with the wallet's identity key
+pub fn sign_payload<T: Authenticated>(payload: &mut T, storage: &dyn Storage) -> Result<()> {
+    IdentityKey::load_or_create(storage)?.sign(payload)
+}
+
+/// Give a reshared or recovered wallet this party's identity
+///
+/// The long-term key moves over from the source wallet. With `roster`
+/// (still-valid pins, e.g. recovery within the same group) the target is
+/// strict from the start; this party's own entry is always re-pinned.
+/// Returns the identity public key.
+pub fn carry_identity(
+    source: &dyn Storage,
+    target: &dyn Storage,
+    my_index: u32,
+    roster: Option<Roster>,
+) -> Result<String> {
+    IdentityKey::load_or_create(source)?;
+    target.write(
+        IDENTITY_KEY_FILE,
+        source.read_secret(IDENTITY_KEY_FILE)?.expose(),
+    )?;
+    let public_hex = IdentityKey::load_or_create(target)?.public_hex();
+
+    if let Some(mut roster) = roster {
+        roster.0.insert(my_index, public_hex.clone());
+        roster.save(target)?;
+    }
+    Ok(public_hex)
+}
+
+/// This party's index, once keygen has recorded it
+pub(crate) fn own_index(storage: &dyn Storage) -> Result<Option<u32>> {
+    if !storage.exists("htss_metadata.json") {
+        return Ok(None);
+    }
+    let metadata: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    Ok(Some(metadata.my_index))
+}
+
+/// Show this wallet's identity and roster, optionally pinning a party's key
+///
+/// `trust` is `<index>=<key>`, the key as hex or `npub`.
+pub fn identity_core(trust: Option<&str>, storage: &dyn Storage) -> Result<CommandResult> {
+    let mut out = String::new();
+    let me = IdentityKey::load_or_create(storage)?;
+    let mut roster = Roster::load(storage)?;
+
+    if let Some(trust) = trust {
+        let (index, key) = trust
+            .split_once('=')
+            .context("Expected --trust <index>=<public key hex>")?;
+        let index: u32 = index.trim().parse().context("Invalid party index")?;
+        let key = nostr::parse_public_key(key)?;
+
+        if let (None, Some(my_index)) = (&roster, own_index(storage)?) {
+            // Starting a roster: our own payloads must keep verifying too
+            let mut own = Roster::default();
+            own.pin(my_index, &me.public_hex())?;
+            roster = Some(own);
+        }
+        let roster = roster.get_or_insert_with(Roster::default);
+        let previous = roster.0.get(&index).cloned();
+        roster.pin(index, &key)?;
+        roster.save(storage)?;
+        match previous {
+            Some(old) if old != key => out.push_str(&format!(
+                "📌 Party {} re-pinned: {} → {}\n\n",
+                index,
+                fingerprint(&old),
+                fingerprint(&key)
+            )),
+            _ => out.push_str(&format!(
+                "📌 Party {} pinned: {}\n\n",
+                index,
+                fingerprint(&key)
+            )),
+        }
+    }
+
+    out.push_str("🪪 Your identity key:\n");
+    out.push_str(&format!("   {}\n", me.public_hex()));
+    out.push_str(&format!("   {}\n", nostr::npub(&me.public_hex())?));
+    out.push_str(&format!(
+        "   Fingerprint: {}\n\n",
+        fingerprint(&me.public_hex())
+    ));
+    match &roster {
+        Some(roster) => {
+            out.push_str("Pinned identities:\n");
+            roster.describe(&mut out);
+        }
+        None => out.push_str("⚠️  No identity roster: payloads are NOT authenticated\n"),
+    }
+
+    let result = serde_json::json!({
+        "identity": me.public_hex(),
+        "npub": nostr::npub(&me.public_hex())?,
+        "roster": roster.unwrap_or_default(),
+    });
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+/// CLI wrapper for identity_core
+pub fn identity(name: &str, trust: Option<&str>) -> Result<()> {
+    let state_dir = get_state_dir(name);
+    if !std::path::Path::new(&state_dir).exists() {
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}.",
+            name, state_dir
+        ))
+        .into());
+    }
+    let storage = FileStorage::new(&state_dir)?;
+    let cmd_result = identity_core(trust, &storage)?;
+    println!("{}", cmd_result.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::signing::NonceOutput;
+    use crate::storage::MemoryStorage;
+
+    fn nonce(index: u32) -> NonceOutput {
+        NonceOutput {
+            party_index: index,
+            rank: 0,
+            session: "s1".to_string(),
+            nonce: "00".to_string(),
+            memo: None,
+            protocol_version: crate::protocol::payload::PROTOCOL_VERSION,
+            event_type: "dkg_nonce".to_string(),
+            auth: None,
+        }
+    }
+
+    #[test]
+    fn test_roster_rejects_spoofed_and_tampered_payloads() {
+        let (alice_storage, bob_storage) = (MemoryStorage::new(), MemoryStorage::new());
+        let alice = IdentityKey::load_or_create(&alice_storage).unwrap();
+        let bob = IdentityKey::load_or_create(&bob_storage).unwrap();
+        assert_eq!(
+            IdentityKey::load_or_create(&alice_storage)
+                .unwrap()
+                .public_hex(),
+            alice.public_hex()
+        );
+
+        let mut roster = Roster::default();
+        roster.pin(1, &alice.public_hex()).unwrap();
+        roster.pin(2, &bob.public_hex()).unwrap();
+        assert!(roster.pin(3, &bob.public_hex()).is_err());
+
+        let mut genuine = nonce(1);
+        alice.sign(&mut genuine).unwrap();
+        roster.verify(&genuine).unwrap();
+
+        // Bob claiming Alice's index, an unsigned payload, and an edited one
+        let mut spoofed = nonce(1);
+        bob.sign(&mut spoofed).unwrap();
+        assert!(roster.verify(&spoofed).is_err());
+        assert!(roster.verify(&nonce(2)).is_err());
+        genuine.nonce = "01".to_string();
+        assert!(roster.verify(&genuine).is_err());
+    }
+}
@@ -1,4 +1,21 @@
-use crate::storage::{FileStorage, Storage};
+use crate::crypto::birkhoff;
+use crate::crypto::ciphersuite::{decode_point, decode_scalar, with_suite, Ciphersuite, Curve};
+use crate::crypto::nip44;
+use crate::crypto::secret::{hex_secret, serialize_secret, to_json_secret, SecretString};
+use crate::protocol::events::{EventLog, NO_LINES};
+use crate::protocol::fingerprint;
+use crate::protocol::health;
+use crate::protocol::identity::{self, Authenticated, IdentityKey, Roster};
+use crate::protocol::nostr;
+use crate::protocol::payload::{
+    self, decode_hex_field, parse_payload_values, parse_payloads, Expected,
+};
+use crate::protocol::relay::Relays;
+use crate::protocol::reporter::{cli_reporter, NoopReporter, Reporter};
+use crate::protocol::wallet::{Retirement, WalletNotes};
+use crate::protocol::weighted;
+use crate::storage::journal::Journaled;
+use crate::storage::{lock, migrations, FileStorage, Storage};
 use crate::CommandResult;
 use anyhow::{Context, Result};
 use schnorr_fun::frost::{
@@ -9,6 +26,7 @@ use secp256kfun::prelude::*;
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
 
 /// Parse space-separated JSON objects into a Vec
 /// Handles compact JSON where objects are separated by spaces
@@ -87,8 +105,38 @@ pub struct Round1Output {
     pub keygen_input: String, // Bincode hex
     #[serde(default)]
     pub hierarchical: bool, // Whether HTSS mode is enabled
+    /// Payload protocol version (see protocol::payload); absent means v1
+    #[serde(
+        default = "crate::protocol::payload::legacy_version",
+        skip_serializing_if = "crate::protocol::payload::is_legacy_version"
+    )]
+    pub protocol_version: u32,
     #[serde(rename = "type")]
     pub event_type: String,
+    /// Sender's identity public key (x-only hex), announced in Round 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+    /// Wallet curve; `None` means secp256k1 (see [`round1_curve_core`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<Curve>,
+    /// Sender's signature over this payload (see protocol::identity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+}
+
+impl Authenticated for Round1Output {
+    fn signer_index(&self) -> u32 {
+        self.party_index
+    }
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+    fn set_auth(&mut self, auth: String) {
+        self.auth = Some(auth);
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -106,14 +154,45 @@ pub struct CommitmentData {
 pub struct Round2Output {
     pub party_index: u32,
     pub shares: Vec<ShareData>,
+    /// Payload protocol version (see protocol::payload); absent means v1
+    #[serde(
+        default = "crate::protocol::payload::legacy_version",
+        skip_serializing_if = "crate::protocol::payload::is_legacy_version"
+    )]
+    pub protocol_version: u32,
     #[serde(rename = "type")]
     pub event_type: String,
+    /// Sender's signature over this payload (see protocol::identity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+}
+
+impl Authenticated for Round2Output {
+    fn signer_index(&self) -> u32 {
+        self.party_index
+    }
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+    fn set_auth(&mut self, auth: String) {
+        self.auth = Some(auth);
+    }
 }
 
+/// `ShareData::encryption` for shares NIP-44 encrypted to the recipient
+pub const SHARE_ENCRYPTION: &str = "nip44";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ShareData {
     pub to_index: u32,
-    pub share: String, // Bincode hex of secret scalar
+    /// Bincode hex of secret scalar, NIP-44 encrypted when `encryption` is set
+    pub share: String,
+    /// `None` for plaintext shares from older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -148,6 +227,41 @@ pub struct HtssMetadata {
     pub hierarchical: bool,
     /// Map of party_index -> rank for all participants
     pub party_ranks: std::collections::BTreeMap<u32, u32>,
+    /// On-disk format version (see `storage::migrations`); 0 if unversioned
+    #[serde(default)]
+    pub wallet_version: u32,
+    /// Signers required per rank on top of the threshold, e.g. {0: 1, 1: 2}
+    /// (set with `dkg-signing-requirement`); empty means any valid set
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub signing_requirement: BTreeMap<u32, u32>,
+    /// Unix time the group key was created; `None` for older wallets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// Unix time of the last reshare, which refreshed every share
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refreshed_at: Option<u64>,
+}
+
+impl HtssMetadata {
+    /// Enforce [`Self::signing_requirement`] for the parties at `signers`
+    ///
+    /// Ranks come from this wallet's own `party_ranks`, not from what the
+    /// other parties claim in their nonces.
+    pub fn check_signing_requirement(&self, signers: &[u32]) -> Result<()> {
+        if self.signing_requirement.is_empty() {
+            return Ok(());
+        }
+        let ranks = signers
+            .iter()
+            .map(|index| {
+                self.party_ranks
+                    .get(index)
+                    .copied()
+                    .ok_or_else(|| anyhow::anyhow!("Party {} is not in this wallet", index))
+            })
+            .collect::<Result<Vec<u32>>>()?;
+        birkhoff::check_signing_requirement(&ranks, &self.signing_requirement)
+    }
 }
 
 /// HD wallet metadata for BIP-32/BIP-44 key derivation
@@ -168,12 +282,31 @@ fn default_derived_count() -> u32 {
     5
 }
 
+/// HD metadata for a new wallet
+///
+/// The chain code is derived deterministically from the group public key.
+pub(crate) fn new_hd_metadata(group_public_key: &Point<EvenY>) -> HdMetadata {
+    let chain_code = crate::crypto::helpers::tagged_hash(
+        "FrostDAO/ChainCode",
+        &group_public_key.to_xonly_bytes(),
+    );
+    HdMetadata {
+        chain_code: hex::encode(chain_code),
+        hd_enabled: true,
+        mnemonic_hint: None,
+        derived_count: 10, // Default to 10 addresses
+    }
+}
+
 /// Party info for group_info.json
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PartyInfo {
     pub index: u32,
     pub rank: u32,
     pub verification_share: String,
+    /// Identity public key pinned at keygen (x-only hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
 }
 
 /// Group info stored after DKG finalize (shareable public info)
@@ -188,6 +321,38 @@ pub struct GroupInfo {
     pub hierarchical: bool,
     /// Parties sorted by rank (ascending)
     pub parties: Vec<PartyInfo>,
+    /// Who holds each share and how to reach them, by party index (`dkg-party`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub directory: BTreeMap<u32, PartyContact>,
+}
+
+/// Directory entry for one party, kept in `group_info.json` and shared with the group
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartyContact {
+    /// Who holds the share, e.g. `Alice (CFO)`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub holder: String,
+    /// How to reach them, e.g. `signal:+1 555 0100` or an email address
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub contact: String,
+    /// Nostr key to message them at (npub)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub npub: Option<String>,
+    /// Last-known availability, e.g. `weekdays, UTC evenings` or `away until 1 June`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub availability: String,
+    /// Unix time the availability was last updated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability_at: Option<u64>,
+    /// Unix time the holder confirmed they have their share
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_confirmed_at: Option<u64>,
+}
+
+impl PartyContact {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 /// Helper to get the state directory path for a given wallet name
@@ -195,6 +360,175 @@ pub fn get_state_dir(name: &str) -> String {
     format!(".frost_state/{}", name)
 }
 
+/// Indices of the parties whose share sits in `<wallet>/party<i>/` (wallets
+/// holding every party locally, as the TUI creates them), ascending. Folders
+/// are read directly, so any committee size is found.
+pub fn local_party_indices(name: &str) -> Vec<u32> {
+    party_folders(std::path::Path::new(&get_state_dir(name)))
+}
+
+fn party_folders(wallet_dir: &std::path::Path) -> Vec<u32> {
+    crate::protocol::doctor::party_dirs(wallet_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(index, path)| *index > 0 && path.join("paired_secret_share.bin").exists())
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Bitcoin, HD, reshare and recovery commands work on secp256k1 wallets only
+///
+/// Also refuses a wallet written by a newer, incompatible frostdao.
+pub fn require_secp256k1(storage: &dyn Storage) -> Result<()> {
+    migrations::check(storage)?;
+    let curve = wallet_curve(storage)?;
+    if curve != Curve::Secp256k1 {
+        anyhow::bail!(
+            "This wallet uses {} keys; this command needs a secp256k1 wallet",
+            curve
+        );
+    }
+    Ok(())
+}
+
+/// Plaintext secret files written by older versions.
+///
+/// - `share_hex.txt`: final share in hex (reshare/recovery "for verification")
+/// - `secret_coefficient.txt`: raw a₀ polynomial coefficient
+const LEGACY_PLAINTEXT_SECRET_FILES: [&str; 2] = ["share_hex.txt", "secret_coefficient.txt"];
+
+/// This party's Round 1 payload, kept so it can be re-shared (`keygen-status`)
+pub const ROUND1_OUTPUT_FILE: &str = "round1_output.json";
+
+/// This party's Round 2 payload, kept for parties still finalizing
+pub const ROUND2_OUTPUT_FILE: &str = "round2_output.json";
+
+/// Keygen-only secret state, obsolete once `paired_secret_share.bin` exists:
+/// the round 1 contributor state and the shares sent in round 2
+const KEYGEN_ROUND_SECRET_FILES: [&str; 2] = ["round1_state.json", "my_secret_shares.json"];
+
+/// Every wallet folder under `.frost_state`, each with the folders holding
+/// its state: its `party*` folders, then the wallet folder itself
+fn wallet_state_dirs() -> Result<Vec<(std::path::PathBuf, Vec<std::path::PathBuf>)>> {
+    let base_dir = std::path::Path::new(".frost_state");
+    if !base_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut wallets = Vec::new();
+    for entry in std::fs::read_dir(base_dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let mut dirs = Vec::new();
+        for sub in std::fs::read_dir(&path)? {
+            let sub = sub?.path();
+            let is_party_dir = sub
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("party"));
+            if sub.is_dir() && is_party_dir {
+                dirs.push(sub);
+            }
+        }
+        dirs.push(path.clone());
+        wallets.push((path, dirs));
+    }
+    Ok(wallets)
+}
+
+/// Visit the state folders of every wallet under `.frost_state`, each while
+/// holding that wallet's lock
+///
+/// `held` lists wallet folders this run has locked already (as canonical
+/// paths). Any other wallet is locked for its visit, and skipped if that
+/// fails, e.g. because another frostdao is using it; a later run gets to it.
+fn visit_wallets_locked(
+    held: &[std::path::PathBuf],
+    mut visit: impl FnMut(&std::path::Path),
+) -> Result<()> {
+    for (wallet, dirs) in wallet_state_dirs()? {
+        let is_held = std::fs::canonicalize(&wallet).is_ok_and(|dir| held.contains(&dir));
+        let _lock = if is_held {
+            None
+        } else {
+            match lock::WalletLock::acquire(&wallet, "upgrade", std::time::Duration::ZERO) {
+                Ok(lock) => Some(lock),
+                Err(_) => continue,
+            }
+        };
+        dirs.iter().for_each(|dir| visit(dir));
+    }
+    Ok(())
+}
+
+/// Shred plaintext secret files left behind by older versions.
+///
+/// Walks every wallet (and its `party*` folders) under `.frost_state`, and
+/// deletes legacy plaintext secrets plus keygen round state from finalized
+/// wallets. Deletion goes through [`FileStorage::delete`], which overwrites
+/// the file before unlinking. Each wallet is locked while it is cleaned (see
+/// [`visit_wallets_locked`] for `held`). Returns a line per file shredded or
+/// folder that couldn't be cleaned; one bad folder doesn't stop the others.
+pub fn shred_legacy_secret_files(held: &[std::path::PathBuf]) -> Result<Vec<String>> {
+    let mut report = Vec::new();
+    visit_wallets_locked(held, |dir| {
+        let dir_str = dir.to_string_lossy().to_string();
+        if let Err(e) = shred_folder(&dir_str, &mut report) {
+            report.push(format!(
+                "⚠️  {}: couldn't shred old secret files: {:#}",
+                dir_str, e
+            ));
+        }
+    })?;
+    Ok(report)
+}
+
+fn shred_folder(dir: &str, report: &mut Vec<String>) -> Result<()> {
+    let storage = FileStorage::new(dir)?;
+    let finalized = storage.exists("paired_secret_share.bin");
+
+    let obsolete = LEGACY_PLAINTEXT_SECRET_FILES
+        .iter()
+        .chain(KEYGEN_ROUND_SECRET_FILES.iter().filter(|_| finalized));
+    for file in obsolete {
+        if storage.exists(file) {
+            storage.delete(file)?;
+            report.push(format!(
+                "🔒 Shredded plaintext secret file: {}/{}",
+                dir, file
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Upgrade every wallet under `.frost_state` to the current format
+///
+/// Returns a line per folder upgraded or skipped. A wallet written by a newer
+/// frostdao is reported and left alone; commands on it fail with the same
+/// message instead of misreading it. Each wallet is locked while it is
+/// upgraded, as in [`shred_legacy_secret_files`].
+pub fn migrate_wallets(held: &[std::path::PathBuf]) -> Result<Vec<String>> {
+    let mut report = Vec::new();
+    visit_wallets_locked(held, |dir| {
+        let dir_str = dir.to_string_lossy().to_string();
+        let applied = FileStorage::new(&dir_str).and_then(|storage| migrations::migrate(&storage));
+        match applied {
+            Ok(applied) if applied.is_empty() => {}
+            Ok(applied) => report.push(format!(
+                "⬆️  {}: upgraded to wallet version {} ({})",
+                dir_str,
+                migrations::CURRENT_WALLET_VERSION,
+                applied.join("; ")
+            )),
+            Err(e) => report.push(format!("⚠️  {}: {:#}", dir_str, e)),
+        }
+    })?;
+    Ok(report)
+}
+
 /// List all available DKG wallets
 pub fn list_wallets() -> Result<Vec<WalletSummary>> {
     let base_dir = std::path::Path::new(".frost_state");
@@ -204,6 +538,8 @@ pub fn list_wallets() -> Result<Vec<WalletSummary>> {
     }
 
     let mut wallets = Vec::new();
+    let health_policy = health::load_policy(&FileStorage::new(".frost_state")?)?;
+    let now = health::now();
 
     for entry in std::fs::read_dir(base_dir)? {
         let entry = entry?;
@@ -220,9 +556,17 @@ pub fn list_wallets() -> Result<Vec<WalletSummary>> {
             .unwrap_or("unknown")
             .to_string();
 
-        // Check if it's a valid wallet (has shared_key.bin)
+        // Check if it's a valid wallet (has shared_key.bin, or frost_key.json off secp256k1)
         let shared_key_path = path.join("shared_key.bin");
-        if !shared_key_path.exists() {
+        let frost_key = FileStorage::new(&path.to_string_lossy())
+            .ok()
+            .and_then(|storage| FrostKey::load(&storage).ok());
+        // Watch-only wallets (dkg-group-import) have just the group info
+        let group_info = std::fs::read_to_string(path.join("group_info.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<GroupInfo>(&content).ok());
+        let watch_only = !shared_key_path.exists() && frost_key.is_none();
+        if watch_only && group_info.is_none() {
             continue;
         }
 
@@ -244,23 +588,13 @@ pub fn list_wallets() -> Result<Vec<WalletSummary>> {
             }
         } else {
             // Try group_info.json as fallback
-            let group_info_path = path.join("group_info.json");
-            if group_info_path.exists() {
-                if let Ok(content) = std::fs::read_to_string(&group_info_path) {
-                    if let Ok(info) = serde_json::from_str::<GroupInfo>(&content) {
-                        (
-                            Some(info.threshold),
-                            Some(info.total_parties),
-                            Some(info.hierarchical),
-                        )
-                    } else {
-                        (None, None, None)
-                    }
-                } else {
-                    (None, None, None)
-                }
-            } else {
-                (None, None, None)
+            match &group_info {
+                Some(info) => (
+                    Some(info.threshold),
+                    Some(info.total_parties),
+                    Some(info.hierarchical),
+                ),
+                None => (None, None, None),
             }
         };
 
@@ -290,12 +624,39 @@ pub fn list_wallets() -> Result<Vec<WalletSummary>> {
             None
         };
 
+        let address = address.or_else(|| {
+            group_info
+                .filter(|_| watch_only)
+                .map(|info| info.taproot_address_testnet)
+        });
+
+        // This party's Nostr identity, if the wallet has one
+        let npub = FileStorage::new(&path.to_string_lossy())
+            .ok()
+            .and_then(|storage| IdentityKey::load(&storage).ok().flatten())
+            .and_then(|key| crate::protocol::nostr::npub(&key.public_hex()).ok());
+
+        let notes = FileStorage::new(&path.to_string_lossy())
+            .ok()
+            .and_then(|storage| WalletNotes::load(&storage).ok())
+            .unwrap_or_default();
+
+        let retired = FileStorage::new(&path.to_string_lossy())
+            .ok()
+            .and_then(|storage| Retirement::load(&storage).ok().flatten());
+
         wallets.push(WalletSummary {
             name,
             threshold,
             total_parties,
             hierarchical,
             address,
+            npub,
+            curve: frost_key.map(|key| key.curve).unwrap_or_default(),
+            notes,
+            watch_only,
+            retired,
+            health: health::wallet_warnings(&path, &health_policy, now),
         });
     }
 
@@ -313,6 +674,16 @@ pub struct WalletSummary {
     pub total_parties: Option<u32>,
     pub hierarchical: Option<bool>,
     pub address: Option<String>,
+    pub npub: Option<String>,
+    pub curve: Curve,
+    /// Freeform notes from `dkg-notes`
+    pub notes: WalletNotes,
+    /// Only the group info is held (imported with `dkg-group-import`)
+    pub watch_only: bool,
+    /// Set once `dkg-rotate` has swept the wallet to a successor
+    pub retired: Option<Retirement>,
+    /// Warnings from the health policy (see `dkg-health`)
+    pub health: Vec<String>,
 }
 
 /// Print wallet list to console
@@ -341,7 +712,16 @@ pub fn print_wallet_list() -> Result<()> {
             _ => "?".to_string(),
         };
 
-        println!("  {} ({} {})", wallet.name, threshold_str, mode);
+        if wallet.watch_only {
+            println!("  {} ({} {}, watch-only)", wallet.name, threshold_str, mode);
+        } else if wallet.curve == Curve::Secp256k1 {
+            println!("  {} ({} {})", wallet.name, threshold_str, mode);
+        } else {
+            println!(
+                "  {} ({} {}, {})",
+                wallet.name, threshold_str, mode, wallet.curve
+            );
+        }
 
         if let Some(addr) = &wallet.address {
             let short_addr = if addr.len() > 20 {
@@ -351,6 +731,15 @@ pub fn print_wallet_list() -> Result<()> {
             };
             println!("    Address: {}", short_addr);
         }
+        if let Some(retired) = &wallet.retired {
+            println!("    Retired: swept to {}", retired.successor);
+        }
+        if !wallet.notes.description.is_empty() {
+            println!("    Note: {}", wallet.notes.description);
+        }
+        if !wallet.notes.tags.is_empty() {
+            println!("    Tags: {}", wallet.notes.tags.join(", "));
+        }
     }
 
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -375,23 +764,132 @@ pub fn round1_core(
     hierarchical: bool, // Whether HTSS mode is enabled
     storage: &dyn Storage,
 ) -> Result<CommandResult> {
-    let mut out = String::new();
+    round1_core_with_reporter(
+        threshold,
+        n_parties,
+        my_index,
+        my_rank,
+        hierarchical,
+        storage,
+        &mut NoopReporter,
+    )
+}
+
+/// [`round1_core`], reporting progress to `reporter`
+pub fn round1_core_with_reporter(
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let mut events = EventLog::with_reporter(reporter);
+    round1_with_rng(
+        threshold,
+        n_parties,
+        my_index,
+        my_rank,
+        hierarchical,
+        storage,
+        &mut events,
+        &mut rand::thread_rng(),
+    )
+}
+
+/// Domain separator for [`demo_seed_rng`]
+#[cfg(feature = "demo-seed")]
+const DEMO_SEED_TAG: &[u8] = b"frostdao/demo-seed/v1";
+
+/// RNG for party `my_index` derived from a shared workshop seed
+///
+/// Mixing in the index lets a whole class use one seed while every party
+/// still gets its own polynomial.
+#[cfg(feature = "demo-seed")]
+fn demo_seed_rng(seed: &[u8], my_index: u32) -> rand_chacha::ChaCha20Rng {
+    use rand::SeedableRng;
+    use sha2::Digest;
+
+    let mut hasher = Sha256::new();
+    hasher.update(DEMO_SEED_TAG);
+    hasher.update(seed);
+    hasher.update(my_index.to_be_bytes());
+    rand_chacha::ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+/// [`round1_core_with_reporter`] with the polynomial (and a new identity key)
+/// derived from `seed`, so a workshop transcript can be reproduced exactly
+///
+/// INSECURE: anyone who knows the seed can recompute this party's secret.
+/// Only compiled with the `demo-seed` feature; never use it for real funds.
+#[cfg(feature = "demo-seed")]
+#[allow(clippy::too_many_arguments)]
+pub fn round1_core_with_seed(
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    seed: &[u8],
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    if seed.is_empty() {
+        anyhow::bail!(crate::error::Error::User(
+            "The demo seed must not be empty".to_string()
+        ));
+    }
+    let mut events = EventLog::with_reporter(reporter);
+    events.warning(
+        "INSECURE DEMO SEED: this party's polynomial is derived from --seed",
+        [
+            "Anyone who knows the seed can recompute the secret share.",
+            "Use it for reproducible workshop transcripts only, never for real funds.",
+        ],
+    );
+    round1_with_rng(
+        threshold,
+        n_parties,
+        my_index,
+        my_rank,
+        hierarchical,
+        storage,
+        &mut events,
+        &mut demo_seed_rng(seed, my_index),
+    )
+}
 
+#[allow(clippy::too_many_arguments)]
+fn round1_with_rng(
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+    rng: &mut impl rand::RngCore,
+) -> Result<CommandResult> {
     let mode_name = if hierarchical { "HTSS" } else { "TSS" };
-    out.push_str(&format!("FROST Keygen ({}) - Round 1\n\n", mode_name));
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str("Configuration:\n");
-    out.push_str(&format!(
-        "  Threshold: {} (need {} parties to sign)\n",
-        threshold, threshold
-    ));
-    out.push_str(&format!("  Total parties: {}\n", n_parties));
-    out.push_str(&format!("  Your index: {}\n", my_index));
+    events.started(format!("FROST Keygen ({}) - Round 1", mode_name));
+    events.divider();
+    events.note("Configuration:\n");
+    events.detail(
+        "Threshold",
+        format!("{} (need {} parties to sign)", threshold, threshold),
+    );
+    events.detail("Total parties", n_parties);
+    events.detail("Your index", my_index);
     if hierarchical {
-        out.push_str(&format!("  Your rank: {} (HTSS mode)\n", my_rank));
-        out.push_str("  Note: Rank 0 = highest authority, higher ranks = lower authority\n");
+        events.detail("Your rank", format!("{} (HTSS mode)", my_rank));
+        events.detail(
+            "Note",
+            "Rank 0 = highest authority, higher ranks = lower authority",
+        );
     }
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    events.divider();
+    events.note("\n");
 
     if threshold > n_parties {
         anyhow::bail!("Threshold cannot exceed number of parties");
@@ -408,67 +906,79 @@ pub fn round1_core(
         .map(|i| Scalar::from(i).non_zero().expect("nonzero"))
         .collect();
 
-    out.push_str("⚙️  Using schnorr_fun's FROST implementation\n");
-    out.push_str("   Calling: Contributor::gen_keygen_input()\n\n");
-
-    out.push_str("⚙️  Generating random polynomial...\n");
-    out.push_str(&format!(
-        "   Degree: t-1 = {} (for threshold {})\n",
-        threshold - 1,
-        threshold
-    ));
-    out.push_str("   The polynomial f(x) = a0 + a1*x + a2*x² + ...\n");
-    out.push_str("   where a0 is your secret contribution\n\n");
+    events.progress(
+        "Using schnorr_fun's FROST implementation",
+        ["Calling: Contributor::gen_keygen_input()"],
+    );
+    events.progress(
+        "Generating random polynomial...",
+        [
+            &format!(
+                "Degree: t-1 = {} (for threshold {})",
+                threshold - 1,
+                threshold
+            ),
+            "The polynomial f(x) = a0 + a1*x + a2*x² + ...",
+            "where a0 is your secret contribution",
+        ],
+    );
 
     // Generate keygen input as a contributor
-    let mut rng = rand::thread_rng();
     let (contributor, keygen_input, secret_shares) = Contributor::gen_keygen_input(
         &frost.schnorr,
         threshold,
         &share_indices,
         my_index - 1, // Contributor uses 0-based indexing
-        &mut rng,
+        rng,
     );
 
-    out.push_str("❄️  Generated:\n");
-    out.push_str(&format!(
-        "   - {} polynomial commitments (public points)\n",
-        threshold
-    ));
-    out.push_str("   - Proof of Possession (PoP) signature\n");
-    out.push_str(&format!(
-        "   - {} secret shares (one for each party)\n\n",
-        n_parties
-    ));
-
-    out.push_str("🧠 What just happened:\n");
-    out.push_str(&format!(
-        "   1. Generated {} random polynomial coefficients [a₀, a₁, ..., a_{}]\n",
-        threshold,
-        threshold - 1
-    ));
-    out.push_str("      • a₀ is your SECRET contribution to the group key\n");
-    out.push_str("      • a₁, a₂, ... are random coefficients\n\n");
-    out.push_str(&format!(
-        "   2. Created {} commitments: [a₀*G, a₁*G, ..., a_{}*G]\n",
-        threshold,
-        threshold - 1
-    ));
-    out.push_str("      • These prove the polynomial without revealing it (safe to share!)\n");
-    out.push_str("      • Everyone combines a₀*G values to get the shared public key\n\n");
-    out.push_str(&format!(
-        "   3. Evaluated polynomial at {} indices to create secret shares\n",
-        n_parties
-    ));
-    out.push_str("      • Party i receives: f(i) = a₀ + a₁*i + a₂*i² + ...\n");
-    out.push_str("      • Each share is a point on your polynomial\n\n");
-    out.push_str("   4. Created Proof-of-Possession (PoP) signature\n");
-    out.push_str("      • This proves you know a₀ (your secret contribution)\n");
-    out.push_str("      • Prevents rogue-key and key-cancellation attacks\n\n");
-    out.push_str("❓ Think about it:\n");
-    out.push_str("   Why is it important to verify Proofs-of-Possession?\n");
-    out.push_str("   What could an attacker do if they could contribute a₀*G\n");
-    out.push_str("   without proving they know a₀?\n\n");
+    events.success(
+        "Generated:",
+        [
+            &format!("- {} polynomial commitments (public points)", threshold),
+            "- Proof of Possession (PoP) signature",
+            &format!("- {} secret shares (one for each party)", n_parties),
+        ],
+    );
+    events.explain(
+        "What just happened:",
+        [
+            &format!(
+                "1. Generated {} random polynomial coefficients [a₀, a₁, ..., a_{}]",
+                threshold,
+                threshold - 1
+            ),
+            "   • a₀ is your SECRET contribution to the group key",
+            "   • a₁, a₂, ... are random coefficients",
+            "",
+            &format!(
+                "2. Created {} commitments: [a₀*G, a₁*G, ..., a_{}*G]",
+                threshold,
+                threshold - 1
+            ),
+            "   • These prove the polynomial without revealing it (safe to share!)",
+            "   • Everyone combines a₀*G values to get the shared public key",
+            "",
+            &format!(
+                "3. Evaluated polynomial at {} indices to create secret shares",
+                n_parties
+            ),
+            "   • Party i receives: f(i) = a₀ + a₁*i + a₂*i² + ...",
+            "   • Each share is a point on your polynomial",
+            "",
+            "4. Created Proof-of-Possession (PoP) signature",
+            "   • This proves you know a₀ (your secret contribution)",
+            "   • Prevents rogue-key and key-cancellation attacks",
+        ],
+    );
+    events.question(
+        "Think about it:",
+        [
+            "Why is it important to verify Proofs-of-Possession?",
+            "What could an attacker do if they could contribute a₀*G",
+            "without proving they know a₀?",
+        ],
+    );
 
     // Serialize for output
     let keygen_input_bytes = bincode::serialize(&keygen_input)?;
@@ -487,48 +997,70 @@ pub fn round1_core(
             .map(|s| hex::encode(s.to_bytes()))
             .collect(),
     };
-    storage.write(
-        "round1_state.json",
-        serde_json::to_string_pretty(&state)?.as_bytes(),
-    )?;
+    storage.write("round1_state.json", to_json_secret(&state)?.as_bytes())?;
 
     // Save keygen shares for round 2
-    let shares_map: BTreeMap<String, String> = secret_shares
+    let shares_map: BTreeMap<String, SecretString> = secret_shares
         .into_iter()
-        .map(|(idx, share)| (hex::encode(idx.to_bytes()), hex::encode(share.to_bytes())))
+        .map(|(idx, share)| (hex::encode(idx.to_bytes()), hex_secret(&share.to_bytes())))
         .collect();
     storage.write(
         "my_secret_shares.json",
-        serde_json::to_string_pretty(&shares_map)?.as_bytes(),
+        to_json_secret(&shares_map)?.as_bytes(),
     )?;
 
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str("✉️  Your commitment generated!\n\n");
-
-    out.push_str("➜ Paste the result JSON into the webpage\n");
-    out.push_str(&format!(
-        "➜ Wait for all {} parties to post their commitments\n",
+    events.divider();
+    events.produced("Your commitment generated!");
+    events.next_step("Paste the result JSON into the webpage");
+    events.next_step(format!(
+        "Wait for all {} parties to post their commitments",
         n_parties
     ));
-    out.push_str("➜ Copy the \"all commitments\" JSON from webpage\n");
-    out.push_str("➜ Run: yushan keygen-round2 --data '<JSON>'\n");
+    events.next_step("Copy the \"all commitments\" JSON from webpage");
+    events.next_step("Run: yushan keygen-round2 --data '<JSON>'");
+
+    // Announce this party's identity key; its signature doubles as proof of possession
+    let identity_key = IdentityKey::load_or_create_with(storage, rng)?;
+    events.note(format!(
+        "\n🪪 Your identity fingerprint: {}\n   Read it to the other parties over a channel you trust\n",
+        identity::fingerprint(&identity_key.public_hex())
+    ));
 
     // Create JSON result for copy-pasting
-    let output = Round1Output {
+    let mut output = Round1Output {
         party_index: my_index,
         rank: my_rank,
         keygen_input: keygen_input_hex,
         hierarchical,
+        protocol_version: payload::output_version(),
         event_type: "keygen_round1".to_string(),
+        identity: Some(identity_key.public_hex()),
+        curve: None,
+        auth: None,
     };
+    identity_key.sign(&mut output)?;
     let result = serde_json::to_string(&output)?;
+    storage.write(ROUND1_OUTPUT_FILE, result.as_bytes())?;
 
-    Ok(CommandResult {
-        output: out,
-        result,
-    })
+    Ok(events.finish(result))
+}
+
+/// Whether `name` already holds wallet state
+///
+/// A folder holding only a Nostr key (nostr-keygen/nostr-import) is not a
+/// wallet yet, so round 1 may start in it.
+pub fn wallet_exists(name: &str) -> bool {
+    let state_dir = get_state_dir(name);
+    let path = std::path::Path::new(&state_dir);
+    let identity_only = std::fs::read_dir(path).is_ok_and(|entries| {
+        entries.filter_map(|e| e.ok()).all(|e| {
+            e.file_name() == identity::IDENTITY_KEY_FILE || lock::is_lock_file(&e.file_name())
+        })
+    });
+    path.exists() && !identity_only
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn round1(
     name: &str,
     threshold: u32,
@@ -536,12 +1068,35 @@ pub fn round1(
     my_index: u32,
     my_rank: u32,
     hierarchical: bool,
+    weight: u32,
+    curve: Curve,
+    qr: bool,
+    seed: Option<&str>,
 ) -> Result<()> {
+    if curve != Curve::Secp256k1 && hierarchical {
+        anyhow::bail!("HTSS is only available for secp256k1 wallets");
+    }
+    if curve != Curve::Secp256k1 && weight > 1 {
+        anyhow::bail!("Weighted shares are only available for secp256k1 wallets");
+    }
+    let seed = seed
+        .map(|seed| {
+            hex::decode(seed.trim()).map_err(|_| {
+                anyhow::anyhow!(crate::error::Error::User(
+                    "--seed must be hex, e.g. 00112233".to_string()
+                ))
+            })
+        })
+        .transpose()?;
+    if seed.is_some() && curve != Curve::Secp256k1 {
+        anyhow::bail!("--seed is only available for secp256k1 wallets");
+    }
+
     let state_dir = get_state_dir(name);
     let path = std::path::Path::new(&state_dir);
 
     // Check if folder exists and prompt for confirmation
-    if path.exists() {
+    if wallet_exists(name) {
         println!("⚠️  Wallet '{}' already exists at {}", name, state_dir);
         println!("   This will OVERWRITE your existing keys!");
         print!("   Replace? [y/N]: ");
@@ -556,48 +1111,228 @@ pub fn round1(
             return Ok(());
         }
 
-        // Remove existing folder
-        std::fs::remove_dir_all(path)?;
-        println!("   Removed existing wallet.\n");
+        // Remove existing folder, keeping this party's Nostr identity
+        let identity_key = IdentityKey::load(&FileStorage::new(&state_dir)?)?;
+        lock::clear_dir(path)?;
+        if let Some(key) = identity_key {
+            key.save(&FileStorage::new(&state_dir)?)?;
+        }
+        println!("   Removed existing wallet (Nostr identity kept).\n");
     }
 
     let storage = FileStorage::new(&state_dir)?;
-    let cmd_result = round1_core(
-        threshold,
-        n_parties,
-        my_index,
-        my_rank,
-        hierarchical,
-        &storage,
-    )?;
-    println!("{}", cmd_result.output);
+    let cmd_result = match curve {
+        Curve::Secp256k1 => {
+            // A weight above 1 gives this party the next indices too
+            let indices = weighted::allocate(&storage, my_index, weight, n_parties)?;
+            let mut reporter = cli_reporter();
+            weighted::run_slots(&storage, &indices, |index, slot| match &seed {
+                #[cfg(feature = "demo-seed")]
+                Some(seed) => round1_core_with_seed(
+                    threshold,
+                    n_parties,
+                    index,
+                    my_rank,
+                    hierarchical,
+                    seed,
+                    slot,
+                    reporter.as_mut(),
+                ),
+                _ => round1_core_with_reporter(
+                    threshold,
+                    n_parties,
+                    index,
+                    my_rank,
+                    hierarchical,
+                    slot,
+                    reporter.as_mut(),
+                ),
+            })?
+        }
+        _ => round1_curve_core_with_reporter(
+            curve,
+            threshold,
+            n_parties,
+            my_index,
+            &storage,
+            cli_reporter().as_mut(),
+        )?,
+    };
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Copy this JSON:");
-    println!("{}\n", cmd_result.result);
+    println!(
+        "{}\n",
+        crate::protocol::compact::present(&cmd_result.result)
+    );
+    if qr {
+        crate::qr::show_qr(&cmd_result.result)?;
+    }
     println!("💾 State saved to: {}/", state_dir);
     Ok(())
 }
 
+/// Check the pasted Round 1 set before the coordinator sees it: exactly one
+/// output for each index `1..=n_parties`, every party in the same mode, and
+/// (for HTSS) ranks that leave at least one group of `threshold` able to sign
+pub(crate) fn check_round1_set(
+    round1_outputs: &[Round1Output],
+    n_parties: u32,
+    threshold: u32,
+    hierarchical: bool,
+) -> Result<()> {
+    let user_error = |msg: String| anyhow::anyhow!(crate::error::Error::User(msg));
+    let with_or_without = |flag: bool| if flag { "with" } else { "without" };
+
+    let mut seen = BTreeSet::new();
+    for output in round1_outputs {
+        if output.party_index == 0 || output.party_index > n_parties {
+            return Err(user_error(format!(
+                "Round 1 output for party {} is out of range: this wallet has {} parties \
+                 (indices 1..={}). Check that everyone ran keygen-round1 with --n-parties {}",
+                output.party_index, n_parties, n_parties, n_parties
+            )));
+        }
+        if !seen.insert(output.party_index) {
+            return Err(user_error(format!(
+                "Round 1 output for party {} was pasted twice (or two parties chose \
+                 --my-index {}); paste each party's output exactly once",
+                output.party_index, output.party_index
+            )));
+        }
+        if output.hierarchical != hierarchical {
+            return Err(user_error(format!(
+                "Party {} ran keygen-round1 {} --hierarchical and you ran it {}; \
+                 every party must use the same mode",
+                output.party_index,
+                with_or_without(output.hierarchical),
+                with_or_without(hierarchical)
+            )));
+        }
+        if !hierarchical && output.rank != 0 {
+            return Err(user_error(format!(
+                "Party {} set --rank {} without --hierarchical; ranks only apply to HTSS wallets",
+                output.party_index, output.rank
+            )));
+        }
+    }
+
+    let missing: Vec<String> = (1..=n_parties)
+        .filter(|i| !seen.contains(i))
+        .map(|i| i.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(user_error(format!(
+            "Missing Round 1 output from part{} {}; paste every party's output, including yours",
+            if missing.len() == 1 { "y" } else { "ies" },
+            missing.join(", ")
+        )));
+    }
+
+    if hierarchical {
+        let ranks: Vec<u32> = round1_outputs.iter().map(|o| o.rank).collect();
+        crate::crypto::birkhoff::validate_signer_set(&ranks, threshold).map_err(|e| {
+            user_error(format!(
+                "With these ranks no group of {} parties could ever sign: {}",
+                threshold, e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+/// Pin every party's identity key; each Round 1 must be signed by the key it announces
+pub(crate) fn pin_round1_identities(
+    round1_outputs: &[Round1Output],
+    my_index: u32,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<(IdentityKey, Roster)> {
+    let identity_key = IdentityKey::load_or_create(storage)?;
+    let mut roster = Roster::default();
+    for output in round1_outputs {
+        let key = output.identity.as_deref().with_context(|| {
+            format!(
+                "Party {}'s Round 1 has no identity key (sent by an older frostdao?)",
+                output.party_index
+            )
+        })?;
+        identity::verify_with_key(output, key)?;
+        roster.pin(output.party_index, key)?;
+    }
+    if roster.0.get(&my_index) != Some(&identity_key.public_hex()) {
+        anyhow::bail!(
+            "Round 1 data for party {} does not carry your identity key",
+            my_index
+        );
+    }
+    roster.save(storage)?;
+
+    let mut pinned = String::from("🪪 Pinned party identities (check fingerprints out of band):\n");
+    roster.describe(&mut pinned);
+    pinned.push('\n');
+    events.note(pinned);
+    Ok((identity_key, roster))
+}
+
 pub fn round2_core(data: &str, storage: &dyn Storage) -> Result<CommandResult> {
-    let mut out = String::new();
+    round2_core_with_reporter(data, storage, &mut NoopReporter)
+}
+
+/// [`round2_core`], reporting progress to `reporter`
+pub fn round2_core_with_reporter(
+    data: &str,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let mut events = EventLog::with_reporter(reporter);
+    let events = &mut events;
+    if is_curve_wallet(storage) {
+        let state = load_curve_round1_state(storage)?;
+        return with_suite!(state.curve, C => round2_in::<C>(data, &state, storage, events));
+    }
 
-    out.push_str("FROST Keygen - Round 2\n\n");
+    events.started("FROST Keygen - Round 2");
 
     // Load state
-    let state_json = String::from_utf8(storage.read("round1_state.json")?)
+    let state_json = SecretString::from_utf8(storage.read_secret("round1_state.json")?)
         .context("Failed to load round 1 state. Did you run keygen-round1?")?;
     let state: Round1State = serde_json::from_str(&state_json)?;
 
     // Load my keygen shares (to send to other parties)
-    let shares_json = String::from_utf8(storage.read("my_secret_shares.json")?)?;
-    let shares_map: BTreeMap<String, String> = serde_json::from_str(&shares_json)?;
+    let shares_json = SecretString::from_utf8(storage.read_secret("my_secret_shares.json")?)?;
+    let shares_map: BTreeMap<String, SecretString> = serde_json::from_str(&shares_json)?;
 
     // Parse input - space-separated Round1Output objects
-    let round1_outputs: Vec<Round1Output> = parse_space_separated_json(data)?;
+    let round1_outputs: Vec<Round1Output> =
+        parse_payloads(data, &Expected::new("Round 1 outputs", &["keygen_round1"]))?;
+    if let Some(output) = round1_outputs
+        .iter()
+        .find(|o| o.curve.is_some_and(|c| c != Curve::Secp256k1))
+    {
+        anyhow::bail!(
+            "Party {} ran keygen-round1 with --curve {}; every party must use the same curve",
+            output.party_index,
+            output.curve.unwrap_or_default()
+        );
+    }
 
-    // Convert to expected format
-    let commitments: Vec<CommitmentData> = round1_outputs
-        .into_iter()
+    check_round1_set(
+        &round1_outputs,
+        state.n_parties,
+        state.threshold,
+        state.hierarchical,
+    )?;
+
+    let (identity_key, roster) =
+        pin_round1_identities(&round1_outputs, state.my_index, storage, events)?;
+    let protocol_version = payload::reply_version(&round1_outputs);
+    if let Some(note) = payload::downgrade_note(protocol_version) {
+        events.note(note);
+    }
+
+    // Convert to expected format
+    let commitments: Vec<CommitmentData> = round1_outputs
+        .into_iter()
         .map(|output| CommitmentData {
             index: output.party_index,
             data: output.keygen_input,
@@ -606,13 +1341,14 @@ pub fn round2_core(data: &str, storage: &dyn Storage) -> Result<CommandResult> {
 
     let input = Round1Input { commitments };
 
-    out.push_str(&format!(
+    events.note(format!(
         " Received {} commitments from other parties\n\n",
         input.commitments.len()
     ));
-
-    out.push_str("⚙️  Using schnorr_fun's FROST coordinator\n");
-    out.push_str("   This aggregates all commitments and validates them\n\n");
+    events.progress(
+        "Using schnorr_fun's FROST coordinator",
+        ["This aggregates all commitments and validates them"],
+    );
 
     // Create FROST instance
     let frost = frost::new_with_deterministic_nonces::<Sha256>();
@@ -620,40 +1356,51 @@ pub fn round2_core(data: &str, storage: &dyn Storage) -> Result<CommandResult> {
     // Create coordinator to aggregate inputs
     let mut coordinator = Coordinator::new(state.threshold, state.n_parties);
 
-    out.push_str("⚙️  Adding inputs to coordinator...\n");
+    events.progress("Adding inputs to coordinator...", NO_LINES);
     for commit_data in &input.commitments {
-        let keygen_input_bytes = hex::decode(&commit_data.data)?;
-        let keygen_input: KeygenInput = bincode::deserialize(&keygen_input_bytes)?;
+        let keygen_input: KeygenInput = decode_hex_field(
+            &format!("Party {}'s keygen input", commit_data.index),
+            &commit_data.data,
+        )?;
 
         coordinator
             .add_input(
                 &frost.schnorr,
-                commit_data.index - 1, // Coordinator uses 0-based indexing
+                // Coordinator uses 0-based indexing
+                commit_data
+                    .index
+                    .checked_sub(1)
+                    .context("Party index cannot be zero")?,
                 keygen_input,
             )
             .map_err(|e| anyhow::anyhow!("Failed to add input: {}", e))?;
 
-        out.push_str(&format!(
-            "    Party {}: Commitment validated\n",
-            commit_data.index
-        ));
+        events.party(commit_data.index, "Commitment validated");
     }
 
-    out.push_str("\n❄️  All commitments valid!\n\n");
-
-    out.push_str("✉️  Your keygen shares to send:\n");
-    out.push_str("🧠 Why send keygen shares?\n");
-    out.push_str(&format!(
-        "   Each party evaluates their polynomial at ALL {} party indices\n",
-        state.n_parties
-    ));
-    out.push_str("   Party i sends f_i(j) to party j\n");
-    out.push_str("   These keygen shares will be combined to create each party's\n");
-    out.push_str("   final secret share (without anyone knowing the full key!)\n\n");
-    out.push_str("❓ Think about it:\n");
-    out.push_str("   By broadcasting these keygen shares publicly on Nostr, we're\n");
-    out.push_str("   making a critical security mistake! Anyone can reconstruct\n");
-    out.push_str("   the full private key. What should be done instead?\n\n");
+    events.note("\n");
+    events.success("All commitments valid!", NO_LINES);
+    events.note("✉️  Your keygen shares to send:\n");
+    events.explain(
+        "Why send keygen shares?",
+        [
+            &format!(
+                "Each party evaluates their polynomial at ALL {} party indices",
+                state.n_parties
+            ),
+            "Party i sends f_i(j) to party j",
+            "These keygen shares will be combined to create each party's",
+            "final secret share (without anyone knowing the full key!)",
+        ],
+    );
+    events.explain(
+        "Why encrypt them?",
+        [
+            "Anyone holding every f_i(j) could reconstruct the full private",
+            "key, so each share is NIP-44 encrypted to its recipient's pinned",
+            "identity (Nostr) key. The output is safe to post publicly.",
+        ],
+    );
 
     // Create output with shares
     let mut shares = Vec::new();
@@ -662,80 +1409,287 @@ pub fn round2_core(data: &str, storage: &dyn Storage) -> Result<CommandResult> {
         let idx_scalar: Scalar<Public, NonZero> = Scalar::<NonZero>::from_slice(&idx_bytes[..32])
             .expect("share index cant be zero!")
             .public();
-        // Extract index value - scalars are big-endian, so small values are in last byte
-        let to_index = idx_scalar.to_bytes()[31] as u32;
-
-        out.push_str(&format!("   Share for Party {}: {}\n", to_index, share_hex));
+        // Extract index value - scalars are big-endian, so it is in the last 4 bytes
+        let to_index = u32::from_be_bytes(idx_scalar.to_bytes()[28..].try_into()?);
+
+        let recipient = roster
+            .0
+            .get(&to_index)
+            .with_context(|| format!("No Round 1 data from party {}", to_index))?;
+        let conversation_key = identity_key.conversation_key(recipient)?;
+        events.party(
+            to_index,
+            format!("share encrypted to {}", identity::fingerprint(recipient)),
+        );
 
         shares.push(ShareData {
             to_index,
-            share: share_hex,
+            share: nip44::encrypt(&conversation_key, share_hex.expose())?,
+            encryption: Some(SHARE_ENCRYPTION.to_string()),
         });
     }
 
-    out.push_str("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str("✉️  Your shares generated!\n\n");
-
-    out.push_str("➜ Paste the result JSON into the webpage\n");
-    out.push_str("➜ Wait for all parties to post their shares\n");
-    out.push_str(&format!(
-        "➜ Copy \"shares for Party {}\" JSON from webpage\n",
+    events.note("\n");
+    events.divider();
+    events.produced("Your shares generated!");
+    events.next_step("Paste the result JSON into the webpage");
+    events.next_step("Wait for all parties to post their shares");
+    events.next_step(format!(
+        "Copy \"shares for Party {}\" JSON from webpage",
         state.my_index
     ));
-    out.push_str("➜ Run: yushan keygen-finalize --data '<JSON>'\n");
+    events.next_step("Run: yushan keygen-finalize --data '<JSON>'");
 
     // Save all commitments for validation
     storage.write("all_commitments.json", data.as_bytes())?;
 
     // Create JSON result for copy-pasting
-    let output = Round2Output {
+    let mut output = Round2Output {
         party_index: state.my_index,
         shares,
+        protocol_version,
         event_type: "keygen_round2".to_string(),
+        auth: None,
     };
+    identity_key.sign(&mut output)?;
     let result = serde_json::to_string(&output)?;
+    storage.write(ROUND2_OUTPUT_FILE, result.as_bytes())?;
 
-    Ok(CommandResult {
-        output: out,
-        result,
-    })
+    Ok(events.finish(result))
 }
 
-pub fn round2(name: &str, data: &str) -> Result<()> {
+/// CLI wrapper for round2_core; with `relays` the shares go out as DMs
+/// published there (implies `dm`)
+pub fn round2(
+    name: &str,
+    data: &str,
+    qr: bool,
+    dm: bool,
+    relays: Option<&mut Relays>,
+) -> Result<()> {
     let state_dir = get_state_dir(name);
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!(
+        anyhow::bail!(crate::error::Error::Storage(format!(
             "Wallet '{}' not found at {}. Did you run keygen-round1 with --name {}?",
-            name,
-            state_dir,
-            name
-        );
+            name, state_dir, name
+        )));
     }
 
+    let data = crate::qr::resolve_payload(data)?;
     let storage = FileStorage::new(&state_dir)?;
-    let cmd_result = round2_core(data, &storage)?;
-    println!("{}", cmd_result.output);
+    let dm = dm || relays.is_some();
+    if dm && weighted::is_weighted(&storage) {
+        anyhow::bail!("--dm sends one party's shares; run weighted wallets without it");
+    }
+    let mut reporter = cli_reporter();
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        round2_core_with_reporter(&data, slot, reporter.as_mut())
+    })?;
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("📋 Copy this JSON:");
-    println!("{}\n", cmd_result.result);
+
+    let payload = if dm {
+        // One gift wrap per recipient instead of a room broadcast
+        let output: Round2Output = serde_json::from_str(&cmd_result.result)?;
+        let dms = nostr::dm_round2_shares(&output, &storage)?;
+        if let Some(relays) = relays {
+            println!("📨 NIP-17 DMs (kind 1059), one per party:");
+            for (share, dm) in output.shares.iter().zip(&dms) {
+                let accepted = relays.publish(dm)?;
+                println!(
+                    "   Party {}: published to {}",
+                    share.to_index,
+                    accepted.join(", ")
+                );
+            }
+            println!(
+                "\n🧠 Next: frostdao keygen-finalize --name {} --nostr",
+                name
+            );
+            println!("💾 State saved to: {}/", state_dir);
+            return Ok(());
+        }
+        println!("📨 NIP-17 DMs (kind 1059), one per party - publish each to relays:");
+        let mut events = Vec::new();
+        for (share, dm) in output.shares.iter().zip(&dms) {
+            let event = serde_json::to_string(dm)?;
+            println!("\n# Party {}\n{}", share.to_index, event);
+            events.push(event);
+        }
+        println!();
+        events.join(" ")
+    } else {
+        println!("📋 Copy this JSON:");
+        println!(
+            "{}\n",
+            crate::protocol::compact::present(&cmd_result.result)
+        );
+        cmd_result.result
+    };
+    if qr {
+        crate::qr::show_qr(&payload)?;
+    }
     println!("💾 State saved to: {}/", state_dir);
     Ok(())
 }
 
+/// Round 2 shares addressed to `my_index`, authenticated and decrypted
+///
+/// Accepts broadcast Round2Output objects and NIP-17 gift wraps (from
+/// `keygen-round2 --dm`) in any mix; a share seen both ways counts once.
+pub(crate) fn collect_shares_for_me(
+    data: &str,
+    my_index: u32,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<Vec<IncomingShare>> {
+    // Only accept shares signed by the identity pinned in Round 2
+    let roster =
+        Roster::load(storage)?.context("Identity roster missing. Did you run keygen-round2?")?;
+
+    // Parse shares sent to me - space-separated Round2Output objects, or
+    // NIP-17 gift wraps (keygen-round2 --dm) that each carry one
+    let identity_key = IdentityKey::load_or_create(storage)?;
+    let my_identity = identity_key.public_hex();
+    let mut values: Vec<serde_json::Value> = Vec::new();
+    let mut dm_count = 0;
+    for value in parse_space_separated_json::<serde_json::Value>(data)? {
+        if value.get("kind").and_then(|k| k.as_u64()) != Some(nostr::KIND_GIFT_WRAP) {
+            values.push(value);
+            continue;
+        }
+        let wrap: nostr::Event = serde_json::from_value(value)?;
+        if wrap.recipient() != Some(my_identity.as_str()) {
+            continue; // DM for another party
+        }
+        let (sender, message) = nostr::unwrap_gift(&identity_key, &wrap)?;
+        let output: serde_json::Value = serde_json::from_str(&message)?;
+        let party = output["party_index"].as_u64().unwrap_or_default();
+        if roster.0.get(&(party as u32)) != Some(&sender) {
+            anyhow::bail!(
+                "DM for party {}'s share was sent by a different Nostr key",
+                party
+            );
+        }
+        values.push(output);
+        dm_count += 1;
+    }
+    let round2_outputs: Vec<Round2Output> = parse_payload_values(
+        values,
+        &Expected::new("Round 2 outputs", &["keygen_round2"]),
+    )?;
+
+    for output in &round2_outputs {
+        roster.verify(output)?;
+    }
+    let mut verified = format!(
+        "🪪 {} Round 2 payload(s) signed by their pinned identities\n",
+        round2_outputs.len()
+    );
+    if dm_count > 0 {
+        verified.push_str(&format!("📨 {} of them arrived as NIP-17 DMs\n", dm_count));
+    }
+    verified.push('\n');
+    events.note(verified);
+
+    // Extract shares sent to my_index, decrypting with the sender's pinned key
+    let mut shares_for_me = Vec::new();
+    for output in round2_outputs {
+        for share in output.shares {
+            if share.to_index != my_index {
+                continue;
+            }
+            let share_hex = match share.encryption.as_deref() {
+                Some(SHARE_ENCRYPTION) => {
+                    let sender = roster.0.get(&output.party_index).with_context(|| {
+                        format!("Party {} has no pinned identity", output.party_index)
+                    })?;
+                    let plaintext =
+                        nip44::decrypt(&identity_key.conversation_key(sender)?, &share.share)
+                            .with_context(|| {
+                                format!(
+                                    "Cannot decrypt the share from party {}",
+                                    output.party_index
+                                )
+                            })?;
+                    String::from_utf8(plaintext.expose().to_vec())?
+                }
+                Some(other) => anyhow::bail!("Unknown share encryption '{}'", other),
+                None => {
+                    events.warning(
+                        format!("Party {} sent its share unencrypted", output.party_index),
+                        NO_LINES,
+                    );
+                    share.share
+                }
+            };
+            // The same share may arrive both broadcast and as a DM
+            if let Some(seen) = shares_for_me
+                .iter()
+                .find(|s: &&IncomingShare| s.from_index == output.party_index)
+            {
+                if seen.share != share_hex {
+                    anyhow::bail!("Party {} sent two different shares", output.party_index);
+                }
+                continue;
+            }
+            shares_for_me.push(IncomingShare {
+                from_index: output.party_index,
+                share: share_hex,
+            });
+        }
+    }
+
+    Ok(shares_for_me)
+}
+
 pub fn finalize_core(data: &str, storage: &dyn Storage) -> Result<CommandResult> {
-    let mut out = String::new();
+    finalize_core_with_reporter(data, storage, &mut NoopReporter)
+}
+
+/// [`finalize_core`], reporting progress to `reporter`
+///
+/// The share, group key and metadata are written through a [`Journaled`]
+/// store, so they land together or not at all.
+pub fn finalize_core_with_reporter(
+    data: &str,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let journaled = Journaled::new(storage, "keygen-finalize");
+    let cmd_result = finalize_steps(data, &journaled, reporter)?;
+    journaled.commit()?;
+    Ok(cmd_result)
+}
+
+fn finalize_steps(
+    data: &str,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let mut events = EventLog::with_reporter(reporter);
+    let events = &mut events;
+    if is_curve_wallet(storage) {
+        let state = load_curve_round1_state(storage)?;
+        return with_suite!(state.curve, C => finalize_in::<C>(data, &state, storage, events));
+    }
 
     // Load state
-    let state_json = String::from_utf8(storage.read("round1_state.json")?)?;
+    let state_json = SecretString::from_utf8(storage.read_secret("round1_state.json")?)?;
     let state: Round1State = serde_json::from_str(&state_json)?;
 
     let mode_name = if state.hierarchical { "HTSS" } else { "TSS" };
-    out.push_str(&format!("FROST Keygen ({}) - Finalize\n\n", mode_name));
+    events.started(format!("FROST Keygen ({}) - Finalize", mode_name));
 
     let commitments_json = String::from_utf8(storage.read("all_commitments.json")?)?;
     let round1_outputs: Vec<Round1Output> = parse_space_separated_json(&commitments_json)?;
+    check_round1_set(
+        &round1_outputs,
+        state.n_parties,
+        state.threshold,
+        state.hierarchical,
+    )?;
 
     // Collect party ranks for HTSS metadata
     let mut party_ranks = std::collections::BTreeMap::new();
@@ -752,85 +1706,91 @@ pub fn finalize_core(data: &str, storage: &dyn Storage) -> Result<CommandResult>
         .collect();
     let commitments_input = Round1Input { commitments };
 
-    // Parse shares sent to me - space-separated Round2Output objects
-    let round2_outputs: Vec<Round2Output> = parse_space_separated_json(data)?;
-
-    // Extract shares sent to my_index
-    let mut shares_for_me = Vec::new();
-    for output in round2_outputs {
-        for share in output.shares {
-            if share.to_index == state.my_index {
-                shares_for_me.push(IncomingShare {
-                    from_index: output.party_index,
-                    share: share.share,
-                });
-            }
-        }
-    }
-
+    let shares_for_me = collect_shares_for_me(data, state.my_index, storage, events)?;
     let shares_input = Round2Input { shares_for_me };
 
-    out.push_str(&format!(
+    events.note(format!(
         " Received {} keygen shares sent to you\n\n",
         shares_input.shares_for_me.len()
     ));
 
-    out.push_str("⚙️  Computing your final secret share:\n");
-    out.push_str("🧠 How it works:\n");
-    out.push_str("   Your final secret share = sum of all keygen shares received\n");
-    out.push_str(&format!(
-        "   secret_share = f₁({}) + f₂({}) + f₃({}) + ...\n",
-        state.my_index, state.my_index, state.my_index
-    ));
-    out.push_str("   \n");
-    out.push_str("   This is YOUR piece of the distributed private key!\n");
-    out.push_str(&format!(
-        "   With {} secret shares, you can reconstruct the full key.\n\n",
-        state.threshold
-    ));
+    events.progress("Computing your final secret share:", NO_LINES);
+    events.explain(
+        "How it works:",
+        [
+            "Your final secret share = sum of all keygen shares received",
+            &format!(
+                "secret_share = f₁({}) + f₂({}) + f₃({}) + ...",
+                state.my_index, state.my_index, state.my_index
+            ),
+            "",
+            "This is YOUR piece of the distributed private key!",
+            &format!(
+                "With {} secret shares, you can reconstruct the full key.",
+                state.threshold
+            ),
+        ],
+    );
 
     // Collect keygen shares into a vector
     let mut secret_share_inputs = Vec::new();
     for incoming in &shares_input.shares_for_me {
-        let share_bytes = hex::decode(&incoming.share)?;
-        let share: Scalar<Secret, Zero> = bincode::deserialize(&share_bytes)?;
+        let share: Scalar<Secret, Zero> = decode_hex_field(
+            &format!("Keygen share from party {}", incoming.from_index),
+            &incoming.share,
+        )?;
         secret_share_inputs.push(share);
-        out.push_str(&format!(
-            "   + Party {}'s keygen share\n",
-            incoming.from_index
-        ));
+        events.party(incoming.from_index, "keygen share added");
     }
 
-    out.push_str("\n⚙️  Computing shared public key:\n");
-    out.push_str("🧠 How the group public key is created:\n");
-    out.push_str("   PublicKey = sum of all parties' a₀*G commitments\n");
-    out.push_str("   PK = (a₀)₁*G + (a₀)₂*G + (a₀)₃*G + ...\n");
-    out.push_str("   \n");
-    out.push_str("   Since PK = (a₀)₁ + (a₀)₂ + ... times G,\n");
-    out.push_str("   and the private key = (a₀)₁ + (a₀)₂ + ...,\n");
-    out.push_str("   this IS the public key for the distributed private key!\n\n");
+    events.note("\n");
+    events.progress("Computing shared public key:", NO_LINES);
+    events.explain(
+        "How the group public key is created:",
+        [
+            "PublicKey = sum of all parties' a₀*G commitments",
+            "PK = (a₀)₁*G + (a₀)₂*G + (a₀)₃*G + ...",
+            "",
+            "Since PK = (a₀)₁ + (a₀)₂ + ... times G,",
+            "and the private key = (a₀)₁ + (a₀)₂ + ...,",
+            "this IS the public key for the distributed private key!",
+        ],
+    );
 
     // Reconstruct all KeygenInputs to get the aggregated key
     let frost = frost::new_with_deterministic_nonces::<Sha256>();
     let mut coordinator = Coordinator::new(state.threshold, state.n_parties);
 
     for commit_data in &commitments_input.commitments {
-        let keygen_input_bytes = hex::decode(&commit_data.data)?;
-        let keygen_input: KeygenInput = bincode::deserialize(&keygen_input_bytes)?;
+        let keygen_input: KeygenInput = decode_hex_field(
+            &format!("Party {}'s keygen input", commit_data.index),
+            &commit_data.data,
+        )?;
         coordinator
-            .add_input(&frost.schnorr, commit_data.index - 1, keygen_input)
+            .add_input(
+                &frost.schnorr,
+                commit_data
+                    .index
+                    .checked_sub(1)
+                    .context("Party index cannot be zero")?,
+                keygen_input,
+            )
             .map_err(|e| anyhow::anyhow!("Failed to add input: {}", e))?;
     }
 
     let agg_input = coordinator.finish().context("Coordinator not finished")?;
 
-    out.push_str("⚙️  Verifying keygen shares against commitments:\n");
-    out.push_str("🧠 Critical security check!\n");
-    out.push_str("   For each share f_i(j) received from party i:\n");
-    out.push_str("   • Verify: f_i(j)*G == C_0 + C_1*j + C_2*j² + ...\n");
-    out.push_str("   • Where [C_0, C_1, C_2, ...] are party i's commitments from Round 1\n");
-    out.push_str("   • This proves the share is consistent with the polynomial!\n");
-    out.push_str("   • Prevents malicious parties from sending bad shares\n\n");
+    events.progress("Verifying keygen shares against commitments:", NO_LINES);
+    events.explain(
+        "Critical security check!",
+        [
+            "For each share f_i(j) received from party i:",
+            "• Verify: f_i(j)*G == C_0 + C_1*j + C_2*j² + ...",
+            "• Where [C_0, C_1, C_2, ...] are party i's commitments from Round 1",
+            "• This proves the share is consistent with the polynomial!",
+            "• Prevents malicious parties from sending bad shares",
+        ],
+    );
 
     // Use SimplePedPop utility functions to properly create and pair the secret share
     let my_share_index = Scalar::<Secret, Zero>::from(state.my_index)
@@ -840,14 +1800,20 @@ pub fn finalize_core(data: &str, storage: &dyn Storage) -> Result<CommandResult>
 
     let secret_share = simplepedpop::collect_secret_inputs(my_share_index, secret_share_inputs);
 
-    out.push_str("⚙️  Calling simplepedpop::receive_secret_share()...\n");
-    out.push_str("   This verifies all shares and pairs them with the commitments\n\n");
+    events.progress(
+        "Calling simplepedpop::receive_secret_share()...",
+        ["This verifies all shares and pairs them with the commitments"],
+    );
 
     let paired_share = simplepedpop::receive_secret_share(&frost.schnorr, &agg_input, secret_share)
-        .map_err(|e| anyhow::anyhow!("Share verification failed: {:?}", e))?;
+        .map_err(|e| {
+            crate::error::Error::Protocol(format!("Share verification failed: {:?}", e))
+        })?;
 
-    out.push_str("❄️  All shares verified successfully!\n");
-    out.push_str("   Every share is cryptographically valid\n\n");
+    events.success(
+        "All shares verified successfully!",
+        ["Every share is cryptographically valid"],
+    );
 
     let shared_key = agg_input.shared_key();
 
@@ -861,16 +1827,24 @@ pub fn finalize_core(data: &str, storage: &dyn Storage) -> Result<CommandResult>
         .context("Shared key is zero")?
         .into_xonly();
 
-    // Display clean hex (just the raw bytes, no metadata)
-    let final_share_hex = hex::encode(xonly_paired_share.secret_share().share.to_bytes());
+    // The secret share is never echoed; show the public verification share
+    // (share * G) so parties can still check their share against commitments
+    let share_scalar = xonly_paired_share.secret_share().share;
+    let verification_share_hex = hex::encode(g!(share_scalar * G).normalize().to_bytes());
     let public_key_hex = hex::encode(xonly_shared_key.public_key().to_bytes());
 
     // Save bincode format for loading later (includes type info for deserialization)
-    let final_share_bytes = bincode::serialize(&xonly_paired_share)?;
+    let final_share_bytes = serialize_secret(&xonly_paired_share)?;
     let public_key_bytes = bincode::serialize(&xonly_shared_key)?;
     storage.write("paired_secret_share.bin", &final_share_bytes)?;
     storage.write("shared_key.bin", &public_key_bytes)?;
 
+    // Round state and keygen shares were only needed to get here; shred them
+    // now rather than leave them in plaintext beside the share
+    for file in KEYGEN_ROUND_SECRET_FILES {
+        storage.delete(file)?;
+    }
+
     // Save HTSS metadata
     let htss_metadata = HtssMetadata {
         my_index: state.my_index,
@@ -878,6 +1852,10 @@ pub fn finalize_core(data: &str, storage: &dyn Storage) -> Result<CommandResult>
         threshold: state.threshold,
         hierarchical: state.hierarchical,
         party_ranks,
+        wallet_version: migrations::CURRENT_WALLET_VERSION,
+        signing_requirement: BTreeMap::new(),
+        created_at: Some(health::now()),
+        refreshed_at: None,
     };
     storage.write(
         "htss_metadata.json",
@@ -885,85 +1863,162 @@ pub fn finalize_core(data: &str, storage: &dyn Storage) -> Result<CommandResult>
     )?;
 
     // Generate and save HD metadata for BIP-32/BIP-44 derivation
-    // Chain code is derived deterministically from group public key
-    let chain_code = crate::crypto::helpers::tagged_hash(
-        "FrostDAO/ChainCode",
-        &xonly_shared_key.public_key().to_xonly_bytes(),
-    );
-    let hd_metadata = HdMetadata {
-        chain_code: hex::encode(chain_code),
-        hd_enabled: true,
-        mnemonic_hint: None,
-        derived_count: 10, // Default to 10 addresses
-    };
+    let hd_metadata = new_hd_metadata(&xonly_shared_key.public_key());
     storage.write(
         "hd_metadata.json",
         serde_json::to_string_pretty(&hd_metadata)?.as_bytes(),
     )?;
 
-    out.push_str("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str("❄️  Key generation complete!\n");
-    out.push_str("   HD derivation enabled for multiple addresses.\n");
-    out.push_str("   Compare public keys with other tables to verify!\n\n");
+    events.note("\n");
+    events.divider();
+    events.success(
+        "Key generation complete!",
+        [
+            "HD derivation enabled for multiple addresses.",
+            "Compare public keys with other tables to verify!",
+        ],
+    );
 
     if state.hierarchical {
-        out.push_str("🔐 HTSS Configuration:\n");
-        out.push_str(&format!("   Your rank: {}\n", state.my_rank));
-        out.push_str("   Party ranks: ");
         let ranks_str: Vec<String> = htss_metadata
             .party_ranks
             .iter()
             .map(|(idx, rank)| format!("P{}=r{}", idx, rank))
             .collect();
-        out.push_str(&ranks_str.join(", "));
-        out.push_str("\n\n");
-        out.push_str("🧠 HTSS Signing Rules:\n");
-        out.push_str("   To sign, signers' ranks (sorted) must satisfy: rank[i] <= i\n");
-        out.push_str("   Example: [0,1,1] valid, [1,1,2] invalid (rank 1 > position 0)\n\n");
+        events.note("🔐 HTSS Configuration:\n");
+        events.detail("Your rank", state.my_rank);
+        events.detail("Party ranks", ranks_str.join(", "));
+        events.note("\n");
+        events.explain(
+            "HTSS Signing Rules:",
+            [
+                "To sign, signers' ranks (sorted) must satisfy: rank[i] <= i",
+                "Example: [0,1,1] valid, [1,1,2] invalid (rank 1 > position 0)",
+            ],
+        );
     }
 
+    let code = report_fingerprint(storage, events)?;
+
     // Create result with the keys
     let result = format!(
-        "Secret Share: {}\nPublic Key: {}\nMode: {}",
-        final_share_hex, public_key_hex, mode_name
+        "Verification Share: {}\nPublic Key: {}\nMode: {}\nFingerprint: {}",
+        verification_share_hex, public_key_hex, mode_name, code
     );
 
-    Ok(CommandResult {
-        output: out,
-        result,
+    Ok(events.finish(result))
+}
+
+/// Show the ceremony fingerprint for parties to compare; returns its code
+fn report_fingerprint(storage: &dyn Storage, events: &mut EventLog) -> Result<String> {
+    let fingerprint = fingerprint::ceremony_fingerprint(storage)?;
+    events.success(
+        "Ceremony fingerprint - compare it with every party before funding the wallet:",
+        [
+            fingerprint.emoji_line(),
+            format!("Code: {}", fingerprint.code),
+            "Check it again any time with dkg-fingerprint.".to_string(),
+        ],
+    );
+    Ok(fingerprint.code)
+}
+
+/// Round 2 DMs for this wallet's party on `relays`
+///
+/// Waits until `until` for one from every party in the roster (this one
+/// included); only Round 2 outputs sealed by the sender's pinned key count.
+pub fn fetch_round2_dms(
+    relays: &mut Relays,
+    storage: &dyn Storage,
+    until: Instant,
+) -> Result<Vec<nostr::Event>> {
+    let roster =
+        Roster::load(storage)?.context("Identity roster missing. Did you run keygen-round2?")?;
+    let me = IdentityKey::load_or_create(storage)?;
+    nostr::collect_dms(relays, &me, roster.0.len(), until, |sender, message| {
+        let output: serde_json::Value = serde_json::from_str(message).ok()?;
+        if output["type"] != "keygen_round2" {
+            return None;
+        }
+        let party = output["party_index"].as_u64()? as u32;
+        (roster.0.get(&party).map(String::as_str) == Some(sender)).then(|| party.to_string())
     })
 }
 
-pub fn finalize(name: &str, data: &str) -> Result<()> {
+/// CLI wrapper for finalize_core; with `relays` the Round 2 DMs are fetched
+/// from there (waiting up to `listen`) and added to `data`
+pub fn finalize(
+    name: &str,
+    data: Option<&str>,
+    relays: Option<&mut Relays>,
+    listen: Duration,
+) -> Result<()> {
     let state_dir = get_state_dir(name);
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!(
+        anyhow::bail!(crate::error::Error::Storage(format!(
             "Wallet '{}' not found at {}. Did you run keygen-round1 with --name {}?",
-            name,
-            state_dir,
-            name
-        );
+            name, state_dir, name
+        )));
     }
 
     let storage = FileStorage::new(&state_dir)?;
-    let cmd_result = finalize_core(data, &storage)?;
-
-    // Generate group_info.json
-    generate_group_info(name, &storage)?;
+    let mut data = match data {
+        Some(data) => crate::qr::resolve_payload(data)?,
+        None => String::new(),
+    };
+    if let Some(relays) = relays {
+        let dms = fetch_round2_dms(relays, &storage, Instant::now() + listen)?;
+        println!(
+            "📡 {} Round 2 DM(s) fetched from {}",
+            dms.len(),
+            relays.urls().join(", ")
+        );
+        data = format!("{} {}", data, nostr::events_data(&dms)?);
+    }
+    let mut reporter = cli_reporter();
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        finalize_core_with_reporter(&data, slot, reporter.as_mut())
+    })?;
+
+    // Generate group_info.json (Taproot addresses, so secp256k1 only)
+    let bitcoin = !is_curve_wallet(&storage);
+    if bitcoin {
+        generate_group_info(name, &storage)?;
+    }
 
-    println!("{}", cmd_result.output);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Your keys:");
     println!("{}\n", cmd_result.result);
     println!("💾 Wallet saved to: {}/", state_dir);
-    println!("📄 Group info: {}/group_info.json", state_dir);
+    if bitcoin {
+        println!("📄 Group info: {}/group_info.json", state_dir);
+    }
     Ok(())
 }
 
 /// Generate group_info.json with parties ordered by rank
-fn generate_group_info(name: &str, storage: &dyn Storage) -> Result<()> {
+///
+/// The party directory of an existing `group_info.json` is kept.
+pub(crate) fn generate_group_info(name: &str, storage: &dyn Storage) -> Result<()> {
+    let mut group_info = build_group_info(name, storage)?;
+    if let Some(existing) = storage
+        .read("group_info.json")
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<GroupInfo>(&bytes).ok())
+    {
+        group_info.directory = existing.directory;
+    }
+    storage.write(
+        "group_info.json",
+        serde_json::to_string_pretty(&group_info)?.as_bytes(),
+    )?;
+    Ok(())
+}
+
+/// Group info derived from the key, HTSS metadata and round 1 commitments in `storage`
+pub fn build_group_info(name: &str, storage: &dyn Storage) -> Result<GroupInfo> {
     // Load HTSS metadata
     let htss_json = String::from_utf8(storage.read("htss_metadata.json")?)?;
     let htss: HtssMetadata = serde_json::from_str(&htss_json)?;
@@ -1017,13 +2072,14 @@ fn generate_group_info(name: &str, storage: &dyn Storage) -> Result<()> {
             index: output.party_index,
             rank: output.rank,
             verification_share,
+            identity: output.identity.clone(),
         });
     }
 
     // Sort parties by rank (ascending), then by index
     parties.sort_by(|a, b| a.rank.cmp(&b.rank).then(a.index.cmp(&b.index)));
 
-    let group_info = GroupInfo {
+    Ok(GroupInfo {
         name: name.to_string(),
         group_public_key: public_key_hex,
         taproot_address_testnet: address_testnet,
@@ -1032,14 +2088,8 @@ fn generate_group_info(name: &str, storage: &dyn Storage) -> Result<()> {
         total_parties: parties.len() as u32,
         hierarchical: htss.hierarchical,
         parties,
-    };
-
-    storage.write(
-        "group_info.json",
-        serde_json::to_string_pretty(&group_info)?.as_bytes(),
-    )?;
-
-    Ok(())
+        directory: BTreeMap::new(),
+    })
 }
 
 /// Regenerate group_info.json for an existing wallet
@@ -1048,10 +2098,15 @@ pub fn regenerate_group_info(name: &str) -> Result<()> {
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!("Wallet '{}' not found at {}.", name, state_dir);
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}.",
+            name, state_dir
+        ))
+        .into());
     }
 
     let storage = FileStorage::new(&state_dir)?;
+    require_secp256k1(&storage)?;
     generate_group_info(name, &storage)?;
 
     // Read and display the generated info
@@ -1079,9 +2134,13 @@ pub fn regenerate_group_info(name: &str) -> Result<()> {
         } else {
             party.verification_share.clone()
         };
+        let holder = match info.directory.get(&party.index) {
+            Some(entry) if !entry.holder.is_empty() => format!(" - {}", entry.holder),
+            _ => String::new(),
+        };
         println!(
-            "  Party {} (rank {}): {}",
-            party.index, party.rank, share_display
+            "  Party {} (rank {}): {}{}",
+            party.index, party.rank, share_display, holder
         );
     }
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -1211,3 +2270,639 @@ pub fn generate_all_parties(
         result: format!("Wallet: {}\nPublic Key: {}", name, public_key),
     })
 }
+
+// ============================================================================
+// Other ciphersuites (ed25519, ristretto255)
+// ============================================================================
+//
+// `keygen-round1 --curve ed25519|ristretto255` runs the same rounds as above,
+// generic over `crypto::ciphersuite::Ciphersuite` instead of schnorr_fun:
+// Pedersen DKG with a proof of possession of each a₀, shares NIP-44
+// encrypted to the pinned identity keys. These wallets are plain t-of-n (no
+// HTSS, Taproot or HD).
+
+/// Secret polynomial between keygen-round1 and keygen-finalize
+pub const CURVE_ROUND1_STATE_FILE: &str = "frost_round1_state.json";
+/// Public result of the DKG ([`FrostKey`])
+pub const FROST_KEY_FILE: &str = "frost_key.json";
+/// This party's final secret share
+pub const FROST_SECRET_SHARE_FILE: &str = "frost_secret_share.json";
+
+const POP_DOMAIN: &str = "frostdao/keygen-pop";
+
+/// Group key and every party's verification share, identical for all parties
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FrostKey {
+    pub curve: Curve,
+    pub threshold: u32,
+    pub n_parties: u32,
+    /// Group public key (hex, curve point encoding)
+    pub group_public_key: String,
+    /// party index -> share·B (hex)
+    pub verification_shares: BTreeMap<u32, String>,
+}
+
+impl FrostKey {
+    pub fn load(storage: &dyn Storage) -> Result<Self> {
+        migrations::check(storage)?;
+        let json = String::from_utf8(storage.read(FROST_KEY_FILE)?)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Group key in the encoding the curve's verifiers expect (hex)
+    pub fn public_key_hex(&self) -> Result<String> {
+        with_suite!(self.curve, C => {
+            let point = decode_point::<C>(&self.group_public_key)?;
+            Ok(hex::encode(C::public_key_bytes(&point)))
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CurveRound1State {
+    curve: Curve,
+    my_index: u32,
+    threshold: u32,
+    n_parties: u32,
+    /// Polynomial coefficients a₀..a_{t-1} (hex)
+    coefficients: Vec<SecretString>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CurveSecretShare {
+    index: u32,
+    share: SecretString,
+}
+
+/// Curve of the wallet (or keygen in progress) in `storage`
+pub fn wallet_curve(storage: &dyn Storage) -> Result<Curve> {
+    if storage.exists(FROST_KEY_FILE) {
+        return Ok(FrostKey::load(storage)?.curve);
+    }
+    if storage.exists(CURVE_ROUND1_STATE_FILE) {
+        return Ok(load_curve_round1_state(storage)?.curve);
+    }
+    Ok(Curve::Secp256k1)
+}
+
+/// Whether `storage` holds a wallet (or keygen) on another ciphersuite
+pub fn is_curve_wallet(storage: &dyn Storage) -> bool {
+    storage.exists(FROST_KEY_FILE) || storage.exists(CURVE_ROUND1_STATE_FILE)
+}
+
+fn load_curve_round1_state(storage: &dyn Storage) -> Result<CurveRound1State> {
+    let json = SecretString::from_utf8(storage.read_secret(CURVE_ROUND1_STATE_FILE)?)
+        .context("Failed to load round 1 state. Did you run keygen-round1?")?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// This party's index and final share on a ciphersuite wallet
+pub(crate) fn load_curve_secret_share<C: Ciphersuite>(
+    storage: &dyn Storage,
+) -> Result<(u32, C::Scalar)> {
+    let json = SecretString::from_utf8(
+        storage
+            .read_secret(FROST_SECRET_SHARE_FILE)
+            .context("Failed to load secret share. Did you run keygen-finalize?")?,
+    )?;
+    let share: CurveSecretShare = serde_json::from_str(&json)?;
+    Ok((share.index, decode_scalar::<C>(share.share.expose())?))
+}
+
+/// f(x) for coefficients [a₀, a₁, ...]
+pub(crate) fn evaluate<C: Ciphersuite>(coefficients: &[C::Scalar], x: u32) -> C::Scalar {
+    let x = C::scalar_from_u32(x);
+    coefficients
+        .iter()
+        .rev()
+        .fold(C::scalar_from_u32(0), |acc, a| {
+            C::scalar_add(&C::scalar_mul(&acc, &x), a)
+        })
+}
+
+/// f(x)·B from the commitments [a₀·B, a₁·B, ...]
+fn evaluate_commitments<C: Ciphersuite>(commitments: &[C::Point], x: u32) -> C::Point {
+    let x = C::scalar_from_u32(x);
+    commitments.iter().rev().fold(C::identity(), |acc, a| {
+        C::point_add(&C::point_mul(&x, &acc), a)
+    })
+}
+
+fn pop_challenge<C: Ciphersuite>(index: u32, a0: &C::Point, nonce: &C::Point) -> C::Scalar {
+    C::hash_to_scalar(
+        POP_DOMAIN,
+        &[
+            &index.to_be_bytes(),
+            &C::point_to_bytes(a0),
+            &C::point_to_bytes(nonce),
+        ],
+    )
+}
+
+/// One party's Round 1 contribution: polynomial commitments and PoP of a₀
+struct Contribution<C: Ciphersuite> {
+    commitments: Vec<C::Point>,
+    pop_nonce: C::Point,
+    pop_z: C::Scalar,
+}
+
+impl<C: Ciphersuite> Contribution<C> {
+    /// hex(A₀ ‖ … ‖ A_{t-1} ‖ R ‖ z)
+    fn to_hex(&self) -> String {
+        let mut bytes = Vec::new();
+        for point in self.commitments.iter().chain([&self.pop_nonce]) {
+            bytes.extend(C::point_to_bytes(point));
+        }
+        bytes.extend(C::scalar_to_bytes(&self.pop_z));
+        hex::encode(bytes)
+    }
+
+    fn from_hex(hex_str: &str, threshold: u32) -> Result<Self> {
+        let bytes = hex::decode(hex_str).context("Invalid keygen input hex")?;
+        let point_len = C::point_len();
+        let points_len = (threshold as usize + 1) * point_len;
+        if bytes.len() <= points_len {
+            anyhow::bail!("Keygen input too short for threshold {}", threshold);
+        }
+        let mut points = bytes[..points_len]
+            .chunks(point_len)
+            .map(|chunk| C::point_from_bytes(chunk).context("Invalid commitment point"))
+            .collect::<Result<Vec<_>>>()?;
+        let pop_nonce = points.pop().expect("threshold + 1 points");
+        let pop_z = C::scalar_from_bytes(&bytes[points_len..]).context("Invalid PoP scalar")?;
+        Ok(Self {
+            commitments: points,
+            pop_nonce,
+            pop_z,
+        })
+    }
+
+    /// z·B == R + c·A₀
+    fn verify_pop(&self, index: u32) -> bool {
+        let a0 = &self.commitments[0];
+        let c = pop_challenge::<C>(index, a0, &self.pop_nonce);
+        C::base_mul(&self.pop_z) == C::point_add(&self.pop_nonce, &C::point_mul(&c, a0))
+    }
+}
+
+/// Round 1 contributions, checked against the curve and party count of this keygen
+fn parse_contributions<C: Ciphersuite>(
+    outputs: &[Round1Output],
+    state: &CurveRound1State,
+) -> Result<BTreeMap<u32, Contribution<C>>> {
+    check_round1_set(outputs, state.n_parties, state.threshold, false)?;
+    let mut contributions = BTreeMap::new();
+    for output in outputs {
+        if output.curve != Some(state.curve) {
+            anyhow::bail!(
+                "Party {} ran keygen-round1 for {}, not {}",
+                output.party_index,
+                output.curve.unwrap_or_default(),
+                state.curve
+            );
+        }
+        let contribution = Contribution::<C>::from_hex(&output.keygen_input, state.threshold)?;
+        if !contribution.verify_pop(output.party_index) {
+            anyhow::bail!(
+                "Invalid proof of possession from party {}",
+                output.party_index
+            );
+        }
+        contributions.insert(output.party_index, contribution);
+    }
+    Ok(contributions)
+}
+
+/// [`round1_core`] on another ciphersuite (plain TSS)
+pub fn round1_curve_core(
+    curve: Curve,
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    round1_curve_core_with_reporter(
+        curve,
+        threshold,
+        n_parties,
+        my_index,
+        storage,
+        &mut NoopReporter,
+    )
+}
+
+/// [`round1_curve_core`], reporting progress to `reporter`
+pub fn round1_curve_core_with_reporter(
+    curve: Curve,
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let mut events = EventLog::with_reporter(reporter);
+    with_suite!(curve, C => round1_in::<C>(curve, threshold, n_parties, my_index, storage, &mut events))
+}
+
+fn round1_in<C: Ciphersuite>(
+    curve: Curve,
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<CommandResult> {
+    events.started(format!("FROST Keygen (TSS, {}) - Round 1", C::NAME));
+    events.divider();
+    events.note("Configuration:\n");
+    events.detail("Curve", C::NAME);
+    events.detail(
+        "Threshold",
+        format!("{} (need {} parties to sign)", threshold, threshold),
+    );
+    events.detail("Total parties", n_parties);
+    events.detail("Your index", my_index);
+    events.divider();
+    events.note("\n");
+
+    if threshold == 0 || threshold > n_parties {
+        anyhow::bail!(crate::error::Error::User(
+            "Threshold must be between 1 and the number of parties".into()
+        ));
+    }
+    if my_index == 0 || my_index > n_parties {
+        anyhow::bail!("Party index must be between 1 and {}", n_parties);
+    }
+
+    let coefficients: Vec<C::Scalar> = (0..threshold).map(|_| C::random_scalar()).collect();
+    let commitments: Vec<C::Point> = coefficients.iter().map(C::base_mul).collect();
+
+    // Proof of possession of a₀, bound to our index
+    let k = C::random_scalar();
+    let pop_nonce = C::base_mul(&k);
+    let c = pop_challenge::<C>(my_index, &commitments[0], &pop_nonce);
+    let pop_z = C::scalar_add(&k, &C::scalar_mul(&c, &coefficients[0]));
+    let contribution = Contribution::<C> {
+        commitments,
+        pop_nonce,
+        pop_z,
+    };
+
+    events.success(
+        "Generated:",
+        [
+            format!(
+                "- {} polynomial commitments [a₀·B, ..., a_{}·B]",
+                threshold,
+                threshold - 1
+            ),
+            "- Proof of Possession of a₀ (Schnorr proof bound to your index)".to_string(),
+        ],
+    );
+
+    let state = CurveRound1State {
+        curve,
+        my_index,
+        threshold,
+        n_parties,
+        coefficients: coefficients
+            .iter()
+            .map(|a| hex_secret(&C::scalar_to_bytes(a)))
+            .collect(),
+    };
+    storage.write(CURVE_ROUND1_STATE_FILE, to_json_secret(&state)?.as_bytes())?;
+
+    events.divider();
+    events.produced("Your commitment generated!");
+    events.next_step(format!(
+        "Wait for all {} parties to post their commitments",
+        n_parties
+    ));
+    events.next_step("Run: frostdao keygen-round2 --name <wallet> --data '<JSON>'");
+
+    let identity_key = IdentityKey::load_or_create(storage)?;
+    events.note(format!(
+        "\n🪪 Your identity fingerprint: {}\n   Read it to the other parties over a channel you trust\n",
+        identity::fingerprint(&identity_key.public_hex())
+    ));
+
+    let mut output = Round1Output {
+        party_index: my_index,
+        rank: 0,
+        keygen_input: contribution.to_hex(),
+        hierarchical: false,
+        protocol_version: payload::output_version(),
+        event_type: "keygen_round1".to_string(),
+        identity: Some(identity_key.public_hex()),
+        curve: Some(curve),
+        auth: None,
+    };
+    identity_key.sign(&mut output)?;
+    let result = serde_json::to_string(&output)?;
+    storage.write(ROUND1_OUTPUT_FILE, result.as_bytes())?;
+
+    Ok(events.finish(result))
+}
+
+fn round2_in<C: Ciphersuite>(
+    data: &str,
+    state: &CurveRound1State,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<CommandResult> {
+    events.started(format!("FROST Keygen ({}) - Round 2", C::NAME));
+
+    let round1_outputs: Vec<Round1Output> =
+        parse_payloads(data, &Expected::new("Round 1 outputs", &["keygen_round1"]))?;
+    check_round1_set(&round1_outputs, state.n_parties, state.threshold, false)?;
+    let (identity_key, roster) =
+        pin_round1_identities(&round1_outputs, state.my_index, storage, events)?;
+    let protocol_version = payload::reply_version(&round1_outputs);
+    if let Some(note) = payload::downgrade_note(protocol_version) {
+        events.note(note);
+    }
+
+    let contributions = parse_contributions::<C>(&round1_outputs, state)?;
+    for index in contributions.keys() {
+        events.party(*index, "Commitment and PoP validated");
+    }
+    events.note("\n");
+
+    let coefficients = state
+        .coefficients
+        .iter()
+        .map(|a| decode_scalar::<C>(a.expose()))
+        .collect::<Result<Vec<_>>>()?;
+
+    // f(j) for every party, each NIP-44 encrypted to its pinned identity
+    let mut shares = Vec::new();
+    for to_index in 1..=state.n_parties {
+        let share = hex_secret(&C::scalar_to_bytes(&evaluate::<C>(&coefficients, to_index)));
+        let recipient = roster
+            .0
+            .get(&to_index)
+            .with_context(|| format!("No Round 1 data from party {}", to_index))?;
+        let conversation_key = identity_key.conversation_key(recipient)?;
+        events.party(
+            to_index,
+            format!("share encrypted to {}", identity::fingerprint(recipient)),
+        );
+        shares.push(ShareData {
+            to_index,
+            share: nip44::encrypt(&conversation_key, share.expose())?,
+            encryption: Some(SHARE_ENCRYPTION.to_string()),
+        });
+    }
+
+    events.note("\n");
+    events.divider();
+    events.produced("Your shares generated!");
+    events.next_step("Wait for all parties to post their shares");
+    events.next_step("Run: frostdao keygen-finalize --name <wallet> --data '<JSON>'");
+
+    storage.write("all_commitments.json", data.as_bytes())?;
+
+    let mut output = Round2Output {
+        party_index: state.my_index,
+        shares,
+        protocol_version,
+        event_type: "keygen_round2".to_string(),
+        auth: None,
+    };
+    identity_key.sign(&mut output)?;
+    let result = serde_json::to_string(&output)?;
+    storage.write(ROUND2_OUTPUT_FILE, result.as_bytes())?;
+
+    Ok(events.finish(result))
+}
+
+fn finalize_in<C: Ciphersuite>(
+    data: &str,
+    state: &CurveRound1State,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<CommandResult> {
+    events.started(format!("FROST Keygen ({}) - Finalize", C::NAME));
+
+    let commitments_json = String::from_utf8(storage.read("all_commitments.json")?)?;
+    let round1_outputs: Vec<Round1Output> = parse_space_separated_json(&commitments_json)?;
+    let contributions = parse_contributions::<C>(&round1_outputs, state)?;
+
+    let shares_for_me = collect_shares_for_me(data, state.my_index, storage, events)?;
+    events.note(format!(
+        " Received {} keygen shares sent to you\n\n",
+        shares_for_me.len()
+    ));
+
+    events.progress(
+        "Verifying keygen shares against commitments:",
+        ["f_i(j)·B == A₀ + A₁·j + A₂·j² + ..."],
+    );
+    let mut secret = C::scalar_from_u32(0);
+    let mut received = BTreeSet::new();
+    for incoming in &shares_for_me {
+        let contribution = contributions
+            .get(&incoming.from_index)
+            .with_context(|| format!("Share from unknown party {}", incoming.from_index))?;
+        let share = decode_scalar::<C>(&incoming.share)?;
+        let expected = evaluate_commitments::<C>(&contribution.commitments, state.my_index);
+        if C::base_mul(&share) != expected {
+            anyhow::bail!(
+                "Share from party {} does not match its commitments",
+                incoming.from_index
+            );
+        }
+        if !received.insert(incoming.from_index) {
+            continue;
+        }
+        secret = C::scalar_add(&secret, &share);
+        events.party(incoming.from_index, "keygen share added");
+    }
+    if received.len() != contributions.len() {
+        let missing: Vec<_> = contributions
+            .keys()
+            .filter(|i| !received.contains(i))
+            .collect();
+        anyhow::bail!("Missing keygen shares from parties {:?}", missing);
+    }
+
+    // Group key and everyone's verification share, from public data alone
+    let group_key = contributions.values().fold(C::identity(), |acc, c| {
+        C::point_add(&acc, &c.commitments[0])
+    });
+    let verification_shares: BTreeMap<u32, C::Point> = (1..=state.n_parties)
+        .map(|j| {
+            let share_point = contributions.values().fold(C::identity(), |acc, c| {
+                C::point_add(&acc, &evaluate_commitments::<C>(&c.commitments, j))
+            });
+            (j, share_point)
+        })
+        .collect();
+    if verification_shares[&state.my_index] != C::base_mul(&secret) {
+        anyhow::bail!(crate::error::Error::Protocol(
+            "Final share does not match the group commitments".into()
+        ));
+    }
+
+    let key = FrostKey {
+        curve: state.curve,
+        threshold: state.threshold,
+        n_parties: state.n_parties,
+        group_public_key: hex::encode(C::point_to_bytes(&group_key)),
+        verification_shares: verification_shares
+            .iter()
+            .map(|(i, p)| (*i, hex::encode(C::point_to_bytes(p))))
+            .collect(),
+    };
+    let secret_share = CurveSecretShare {
+        index: state.my_index,
+        share: hex_secret(&C::scalar_to_bytes(&secret)),
+    };
+    storage.write(
+        FROST_KEY_FILE,
+        serde_json::to_string_pretty(&key)?.as_bytes(),
+    )?;
+    storage.write(
+        FROST_SECRET_SHARE_FILE,
+        to_json_secret(&secret_share)?.as_bytes(),
+    )?;
+    storage.delete(CURVE_ROUND1_STATE_FILE)?;
+
+    let htss_metadata = HtssMetadata {
+        my_index: state.my_index,
+        my_rank: 0,
+        threshold: state.threshold,
+        hierarchical: false,
+        party_ranks: (1..=state.n_parties).map(|i| (i, 0)).collect(),
+        wallet_version: migrations::CURRENT_WALLET_VERSION,
+        signing_requirement: BTreeMap::new(),
+        created_at: Some(health::now()),
+        refreshed_at: None,
+    };
+    storage.write(
+        "htss_metadata.json",
+        serde_json::to_string_pretty(&htss_metadata)?.as_bytes(),
+    )?;
+
+    events.note("\n");
+    events.divider();
+    events.success(
+        format!("{} key generation complete!", C::NAME),
+        [
+            "Compare public keys with other tables to verify!",
+            "Bitcoin commands are unavailable for this wallet.",
+        ],
+    );
+
+    let code = report_fingerprint(storage, events)?;
+    let result = format!(
+        "Verification Share: {}\nPublic Key: {}\nMode: TSS\nCurve: {}\nFingerprint: {}",
+        key.verification_shares[&state.my_index],
+        hex::encode(C::public_key_bytes(&group_key)),
+        C::NAME,
+        code
+    );
+    Ok(events.finish(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{kind_of, ErrorKind};
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_round2_rejects_bad_round1_sets() {
+        let round1 = |threshold, n, index, rank, hierarchical| {
+            round1_core(
+                threshold,
+                n,
+                index,
+                rank,
+                hierarchical,
+                &MemoryStorage::new(),
+            )
+            .unwrap()
+            .result
+        };
+        let me = MemoryStorage::new();
+        let mine = round1_core(2, 3, 1, 0, false, &me).unwrap().result;
+        let (p2, p3) = (round1(2, 3, 2, 0, false), round1(2, 3, 3, 0, false));
+
+        let err_for = |outputs: &[&str]| round2_core(&outputs.join(" "), &me).unwrap_err();
+        let cases = [
+            (
+                err_for(&[&mine, &p2]),
+                "Missing Round 1 output from party 3",
+            ),
+            (
+                err_for(&[&mine, &p2, &p3, &round1(2, 4, 4, 0, false)]),
+                "party 4 is out of range",
+            ),
+            (
+                err_for(&[&mine, &p2, &round1(2, 3, 3, 1, true)]),
+                "every party must use the same mode",
+            ),
+        ];
+        for (err, expected) in cases {
+            assert!(err.to_string().contains(expected), "{}", err);
+            assert_eq!(kind_of(&err), ErrorKind::User);
+        }
+        let err = err_for(&[&mine, &p2, &p2, &p3]);
+        assert!(err.to_string().contains("Party 2 appears twice"), "{}", err);
+
+        // Ranks that leave no qualified signer group are caught up front
+        let htss = |index, rank| round1(2, 3, index, rank, true);
+        let err = check_round1_set(
+            &parse_space_separated_json(&[htss(1, 1), htss(2, 1), htss(3, 2)].join(" ")).unwrap(),
+            3,
+            2,
+            true,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("no group of 2 parties"), "{}", err);
+
+        round2_core(&[mine, p2, p3].join(" "), &me).unwrap();
+    }
+
+    #[test]
+    fn test_party_folders_beyond_ten() {
+        let wallet = std::env::temp_dir().join(format!(
+            "frostdao_party_folders_{}_{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        for i in (1..=21).chain([0]) {
+            let dir = wallet.join(format!("party{}", i));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("paired_secret_share.bin"), b"share").unwrap();
+        }
+        // Not parties: a folder without a share and an unrelated name
+        std::fs::create_dir_all(wallet.join("party22")).unwrap();
+        std::fs::create_dir_all(wallet.join("partyX")).unwrap();
+
+        assert_eq!(party_folders(&wallet), (1..=21).collect::<Vec<u32>>());
+        std::fs::remove_dir_all(&wallet).unwrap();
+    }
+
+    #[cfg(feature = "demo-seed")]
+    #[test]
+    fn test_seeded_round1_is_reproducible() {
+        let round1 = |index: u32| {
+            round1_core_with_seed(
+                2,
+                3,
+                index,
+                0,
+                false,
+                b"workshop",
+                &MemoryStorage::new(),
+                &mut NoopReporter,
+            )
+            .unwrap()
+            .result
+        };
+        assert_eq!(round1(1), round1(1));
+        assert_ne!(round1(1), round1(2));
+    }
+}
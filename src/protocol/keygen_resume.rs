@@ -0,0 +1,454 @@
+//! Keygen Status and Resume
+//!
+//! Every keygen step leaves its state in the wallet folder, including the
+//! payload it produced (`round1_output.json`, `round2_output.json`). A party
+//! that lost power mid-ceremony runs `keygen-status` to see which round it
+//! stopped in and get its last payload back to re-share, then
+//! `keygen-resume` with whatever the others sent. Resume works out which step
+//! the data belongs to, and checks re-pasted data against what earlier rounds
+//! used before running anything: a stale or replaced Round 1 output is
+//! refused instead of silently producing a different key.
+
+use crate::protocol::events::{EventLog, NO_LINES};
+use crate::protocol::identity::Roster;
+use crate::protocol::keygen::{
+    self, finalize_core_with_reporter, get_state_dir, parse_space_separated_json,
+    round2_core_with_reporter, ROUND1_OUTPUT_FILE, ROUND2_OUTPUT_FILE,
+};
+use crate::protocol::nostr;
+use crate::protocol::reporter::{cli_reporter, NoopReporter, Reporter};
+use crate::protocol::weighted;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Where a party's key generation stands
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeygenStage {
+    /// No keygen state in the folder
+    NotStarted,
+    /// Round 1 ran; waiting for every party's Round 1 output
+    Round1,
+    /// Round 2 ran; waiting for every party's Round 2 output
+    Round2,
+    /// keygen-finalize ran; the wallet holds its share
+    Finalized,
+}
+
+/// `keygen-status` result
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeygenStatus {
+    pub stage: KeygenStage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_parties: Option<u32>,
+    /// Parties whose Round 1 output went into Round 2
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub round1_parties: Vec<u32>,
+    /// This party's last payload, to re-share with anyone who missed it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    /// What to run next
+    pub next_step: String,
+}
+
+/// Stage of the keygen in `storage`, from the files it left
+pub fn keygen_stage(storage: &dyn Storage) -> KeygenStage {
+    if storage.exists("paired_secret_share.bin") || storage.exists(keygen::FROST_KEY_FILE) {
+        KeygenStage::Finalized
+    } else if storage.exists("all_commitments.json") {
+        KeygenStage::Round2
+    } else if storage.exists("round1_state.json") || storage.exists(keygen::CURVE_ROUND1_STATE_FILE)
+    {
+        KeygenStage::Round1
+    } else {
+        KeygenStage::NotStarted
+    }
+}
+
+/// Index, threshold and party count, from whichever state file is present
+fn configuration(storage: &dyn Storage) -> Result<(Option<u32>, Option<u32>, Option<u32>)> {
+    let field = |value: &Value, key: &str| value[key].as_u64().map(|v| v as u32);
+    for file in ["round1_state.json", keygen::CURVE_ROUND1_STATE_FILE] {
+        if storage.exists(file) {
+            let state: Value = serde_json::from_slice(storage.read_secret(file)?.expose())
+                .with_context(|| format!("{} is damaged", file))?;
+            return Ok((
+                field(&state, "my_index"),
+                field(&state, "threshold"),
+                field(&state, "n_parties"),
+            ));
+        }
+    }
+    if storage.exists("htss_metadata.json") {
+        let metadata: Value = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+        let n_parties = metadata["party_ranks"].as_object().map(|r| r.len() as u32);
+        return Ok((
+            field(&metadata, "my_index"),
+            field(&metadata, "threshold"),
+            n_parties,
+        ));
+    }
+    Ok((None, None, None))
+}
+
+fn saved_payload(storage: &dyn Storage, file: &str) -> Result<Option<String>> {
+    if !storage.exists(file) {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8(storage.read(file)?)?))
+}
+
+/// Round 1 output per party, as stored in `all_commitments.json`
+fn stored_round1(storage: &dyn Storage) -> Result<BTreeMap<u32, Value>> {
+    let data = String::from_utf8(storage.read("all_commitments.json")?)?;
+    by_party(&parse_space_separated_json::<Value>(&data)?)
+}
+
+fn by_party(values: &[Value]) -> Result<BTreeMap<u32, Value>> {
+    values
+        .iter()
+        .map(|value| {
+            let party = value["party_index"]
+                .as_u64()
+                .context("Payload has no party_index")?;
+            Ok((party as u32, value.clone()))
+        })
+        .collect()
+}
+
+/// Whether two Round 1 outputs commit to the same polynomial and identity
+fn same_round1(a: &Value, b: &Value) -> bool {
+    a["keygen_input"] == b["keygen_input"] && a["identity"] == b["identity"]
+}
+
+/// Work out where keygen stands and what to do next
+pub fn keygen_status(storage: &dyn Storage) -> Result<KeygenStatus> {
+    let stage = keygen_stage(storage);
+    let (my_index, threshold, n_parties) = configuration(storage)?;
+    let round1_parties = if storage.exists("all_commitments.json") {
+        stored_round1(storage)?.into_keys().collect()
+    } else {
+        Vec::new()
+    };
+    let (payload, next_step) = match stage {
+        KeygenStage::NotStarted => (None, "Run keygen-round1 to start".to_string()),
+        KeygenStage::Round1 => (
+            saved_payload(storage, ROUND1_OUTPUT_FILE)?,
+            "Collect every party's Round 1 output, then run keygen-resume --data '<all Round 1 outputs>'"
+                .to_string(),
+        ),
+        KeygenStage::Round2 => (
+            saved_payload(storage, ROUND2_OUTPUT_FILE)?,
+            "Collect every party's Round 2 output, then run keygen-resume --data '<all Round 2 outputs>'"
+                .to_string(),
+        ),
+        KeygenStage::Finalized => (
+            saved_payload(storage, ROUND2_OUTPUT_FILE)?,
+            "Nothing left to do; re-share your Round 2 output with any party still finalizing"
+                .to_string(),
+        ),
+    };
+    Ok(KeygenStatus {
+        stage,
+        my_index,
+        threshold,
+        n_parties,
+        round1_parties,
+        payload,
+        next_step,
+    })
+}
+
+/// Core function for `keygen-status`
+pub fn keygen_status_core(storage: &dyn Storage) -> Result<CommandResult> {
+    let status = keygen_status(storage)?;
+    let mut out = String::new();
+    out.push_str("Keygen Status\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    let stage = match status.stage {
+        KeygenStage::NotStarted => "not started",
+        KeygenStage::Round1 => "Round 1 done, waiting for Round 1 outputs",
+        KeygenStage::Round2 => "Round 2 done, waiting for Round 2 outputs",
+        KeygenStage::Finalized => "finished",
+    };
+    out.push_str(&format!("Stage:     {}\n", stage));
+    if let Some(index) = status.my_index {
+        out.push_str(&format!("Index:     {}\n", index));
+    }
+    if let (Some(t), Some(n)) = (status.threshold, status.n_parties) {
+        out.push_str(&format!("Threshold: {}-of-{}\n", t, n));
+    }
+    if !status.round1_parties.is_empty() {
+        let parties: Vec<String> = status.round1_parties.iter().map(u32::to_string).collect();
+        out.push_str(&format!("Round 1 from parties: {}\n", parties.join(", ")));
+    }
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    if let Some(payload) = &status.payload {
+        out.push_str("📋 Your last output (re-share it if a party missed it):\n");
+        out.push_str(payload);
+        out.push_str("\n\n");
+    }
+    out.push_str(&format!("➜ {}\n", status.next_step));
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&status)?,
+    })
+}
+
+/// Which round pasted keygen data belongs to
+fn payload_round(values: &[Value]) -> Result<u32> {
+    let mut rounds = values.iter().map(|value| {
+        if value["kind"].as_u64() == Some(nostr::KIND_GIFT_WRAP) {
+            return Ok(2); // keygen-round2 --dm
+        }
+        match value["type"].as_str() {
+            Some("keygen_round1") => Ok(1),
+            Some("keygen_round2") => Ok(2),
+            other => anyhow::bail!(
+                "Expected keygen Round 1 or Round 2 outputs, got {}",
+                other.unwrap_or("a payload without a type")
+            ),
+        }
+    });
+    let first = rounds.next().context("No keygen data given")??;
+    for round in rounds {
+        if round? != first {
+            anyhow::bail!("The data mixes Round 1 and Round 2 outputs; paste one round at a time");
+        }
+    }
+    Ok(first)
+}
+
+/// Refuse Round 1 data that differs from what Round 2 used
+fn check_against_round2(storage: &dyn Storage, pasted: &BTreeMap<u32, Value>) -> Result<()> {
+    let stored = stored_round1(storage)?;
+    for (party, value) in pasted {
+        match stored.get(party) {
+            Some(used) if same_round1(used, value) => {}
+            Some(_) => anyhow::bail!(
+                "Party {}'s Round 1 output differs from the one used in Round 2. \
+                 They may have re-run keygen-round1; everyone must restart the ceremony \
+                 together or use the original outputs.",
+                party
+            ),
+            None => anyhow::bail!(
+                "Party {} was not part of this keygen's Round 2 (parties {})",
+                party,
+                stored
+                    .keys()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub fn keygen_resume_core(data: &str, storage: &dyn Storage) -> Result<CommandResult> {
+    keygen_resume_core_with_reporter(data, storage, &mut NoopReporter)
+}
+
+/// [`keygen_resume_core`], reporting progress to `reporter`
+///
+/// Runs the step `data` is for (Round 1 outputs → Round 2, Round 2 outputs →
+/// finalize) after checking it against the saved state. Round 1 data for a
+/// step already done is checked and answered with the saved Round 2 output.
+pub fn keygen_resume_core_with_reporter(
+    data: &str,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let stage = keygen_stage(storage);
+    let values = parse_space_separated_json::<Value>(data)?;
+    let round = payload_round(&values)?;
+
+    let mut events = EventLog::with_reporter(reporter);
+    events.started("FROST Keygen - Resume");
+    match (stage, round) {
+        (KeygenStage::NotStarted, _) => {
+            anyhow::bail!("No keygen in progress here. Start with keygen-round1.")
+        }
+        (KeygenStage::Round1, 1) => {
+            let mine: Value = serde_json::from_slice(&storage.read(ROUND1_OUTPUT_FILE)?)
+                .with_context(|| format!("{} is damaged", ROUND1_OUTPUT_FILE))?;
+            let my_index = mine["party_index"].as_u64().unwrap_or_default() as u32;
+            match by_party(&values)?.get(&my_index) {
+                Some(value) if same_round1(value, &mine) => {}
+                Some(_) => anyhow::bail!(
+                    "Your Round 1 output (party {}) in the data is not the one saved here. \
+                     Share the output from keygen-status instead of an older one.",
+                    my_index
+                ),
+                None => anyhow::bail!(
+                    "The data is missing your own Round 1 output (party {}); keygen-status shows it",
+                    my_index
+                ),
+            }
+            events.success("Your Round 1 output matches the saved one", NO_LINES);
+            events.next_step("Running Round 2");
+        }
+        (KeygenStage::Round1, _) => anyhow::bail!(
+            "These are Round 2 outputs, but you haven't run Round 2 yet. \
+             Paste every party's Round 1 output first."
+        ),
+        (KeygenStage::Round2 | KeygenStage::Finalized, 1) => {
+            check_against_round2(storage, &by_party(&values)?)?;
+            events.success(
+                "Round 1 data matches what Round 2 used",
+                ["Round 2 already ran; here is your output again."],
+            );
+            if stage == KeygenStage::Finalized {
+                events.note("Keygen is already finished for this party.\n");
+            }
+            let payload = saved_payload(storage, ROUND2_OUTPUT_FILE)?.with_context(|| {
+                format!(
+                    "{} is missing (written by an older frostdao?)",
+                    ROUND2_OUTPUT_FILE
+                )
+            })?;
+            return Ok(events.finish(payload));
+        }
+        (KeygenStage::Round2, _) => {
+            // Round 2 senders must be the parties whose identities Round 2 pinned
+            let roster = Roster::load(storage)?.unwrap_or_default();
+            for value in &values {
+                let Some(party) = value["party_index"].as_u64() else {
+                    continue; // gift wraps are checked when opened
+                };
+                if !roster.0.is_empty() && !roster.0.contains_key(&(party as u32)) {
+                    anyhow::bail!(
+                        "Party {}'s Round 2 output doesn't belong to this keygen \
+                         (not among the Round 1 parties)",
+                        party
+                    );
+                }
+            }
+            events.success("Round 2 senders match the Round 1 parties", NO_LINES);
+            events.next_step("Running keygen-finalize");
+        }
+        (KeygenStage::Finalized, _) => anyhow::bail!(
+            "Keygen is already finished for this party. If another party still needs \
+             your Round 2 output, keygen-status shows it."
+        ),
+    }
+
+    let prefix = events.render();
+    drop(events);
+    let mut cmd_result = if round == 1 {
+        round2_core_with_reporter(data, storage, reporter)?
+    } else {
+        finalize_core_with_reporter(data, storage, reporter)?
+    };
+    cmd_result.output.insert_str(0, &prefix);
+    Ok(cmd_result)
+}
+
+pub fn status(name: &str) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = weighted::each_slot(&storage, keygen_status_core)?;
+    println!("{}", cmd_result.output);
+    Ok(())
+}
+
+pub fn resume(name: &str, data: &str) -> Result<()> {
+    let state_dir = get_state_dir(name);
+    if !std::path::Path::new(&state_dir).exists() {
+        anyhow::bail!(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}. Did you run keygen-round1 with --name {}?",
+            name, state_dir, name
+        )));
+    }
+    let data = crate::qr::resolve_payload(data)?;
+    let storage = FileStorage::new(&state_dir)?;
+    let was_finalized = keygen_stage(&storage) == KeygenStage::Finalized;
+    let mut reporter = cli_reporter();
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        keygen_resume_core_with_reporter(&data, slot, reporter.as_mut())
+    })?;
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if !was_finalized && keygen_stage(&storage) == KeygenStage::Finalized {
+        if !keygen::is_curve_wallet(&storage) {
+            keygen::generate_group_info(name, &storage)?;
+        }
+        println!("📋 Your keys:");
+    } else {
+        println!("📋 Copy this JSON:");
+    }
+    println!("{}\n", cmd_result.result);
+    println!("💾 State saved to: {}/", state_dir);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_resume_checks_repasted_data() {
+        let parties: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+        assert_eq!(keygen_stage(&parties[0]), KeygenStage::NotStarted);
+        let r1: Vec<String> = parties
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                keygen::round1_core(2, 3, i as u32 + 1, 0, false, p)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+
+        // Party 1 lost power after Round 1: status hands back its output
+        let status = keygen_status(&parties[0]).unwrap();
+        assert_eq!(status.stage, KeygenStage::Round1);
+        assert_eq!(status.payload.as_deref(), Some(r1[0].as_str()));
+        assert_eq!((status.threshold, status.n_parties), (Some(2), Some(3)));
+
+        // Round 2 data is refused before Round 2 ran
+        let r2_early = keygen::round2_core(&r1.join(" "), &parties[1])
+            .unwrap()
+            .result;
+        assert!(keygen_resume_core(&r2_early, &parties[0]).is_err());
+
+        // A replaced Round 1 output is refused
+        let other = MemoryStorage::new();
+        let stale = keygen::round1_core(2, 3, 1, 0, false, &other)
+            .unwrap()
+            .result;
+        let tampered = format!("{} {} {}", stale, r1[1], r1[2]);
+        let err = keygen_resume_core(&tampered, &parties[0]).unwrap_err();
+        assert!(
+            err.to_string().contains("not the one saved here"),
+            "{}",
+            err
+        );
+
+        let all_r1 = r1.join(" ");
+        let r2_1 = keygen_resume_core(&all_r1, &parties[0]).unwrap().result;
+        assert_eq!(keygen_stage(&parties[0]), KeygenStage::Round2);
+        let r2_3 = keygen::round2_core(&all_r1, &parties[2]).unwrap().result;
+
+        // Re-pasting Round 1 returns the saved Round 2 output, unless it changed
+        let again = keygen_resume_core(&all_r1, &parties[0]).unwrap().result;
+        assert_eq!(again, r2_1);
+        assert!(keygen_resume_core(&tampered, &parties[0]).is_err());
+
+        let all_r2 = format!("{} {} {}", r2_1, r2_early, r2_3);
+        keygen_resume_core(&all_r2, &parties[0]).unwrap();
+        let status = keygen_status(&parties[0]).unwrap();
+        assert_eq!(status.stage, KeygenStage::Finalized);
+        assert_eq!(status.round1_parties, vec![1, 2, 3]);
+        assert!(keygen_resume_core(&all_r2, &parties[0]).is_err());
+    }
+}
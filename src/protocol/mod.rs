@@ -3,13 +3,63 @@
 //! This module implements the threshold signature protocols:
 //!
 //! - **keygen**: Distributed Key Generation (DKG)
+//! - **keygen_resume**: Where an interrupted keygen stands, and picking it up again
 //! - **signing**: Threshold signature creation and verification
 //! - **reshare**: Key resharing to new party sets
 //! - **recovery**: Lost share recovery
 //! - **dkg_tx**: DKG-based Bitcoin transaction signing
+//! - **events**: Typed progress events emitted by the cores
+//! - **reporter**: Where those events go (verbose/quiet CLI, TUI, WASM)
+//! - **offline**: Air-gapped signer (`dkg-sign-offline`)
+//! - **identity**: Per-party identity keys that sign every round payload
+//! - **nostr**: Nostr (npub/nsec) view of the identity key
+//! - **relay**: NIP-01 relay client, plus an in-process relay for testing Nostr flows
+//! - **relay_client**: Websocket connections to real relays and `relay-config`
+//! - **payload**: Validation of pasted round payloads
+//! - **compact**: `--compact` CBOR encoding of payloads, auto-detected on input
+//! - **session_board**: Per-party progress of a signing session
+//! - **session_report**: Public record of a broadcast session for co-signers to keep
+//! - **doctor**: Wallet folder diagnostics and safe repairs
+//! - **wallet**: Wallet folder layout migration, rename, archive, delete and notes
+//! - **directory**: Who holds each share and how to reach them (`dkg-party`)
+//! - **audit**: Append-only log of threshold changes and other key events
+//! - **health**: Share age, signing participation and backup checks (`dkg-health`)
+//! - **fingerprint**: Short group key + commitments check read out between parties
+//! - **weighted**: Parties holding several share indices
+//! - **dealer**: Splitting an existing single-sig key into a threshold group
+//! - **backup**: Mnemonic (optionally passphrase protected) and k-of-m Shamir (BIP-39 or SLIP-39) backups of a party's own share
+//! - **reconstruct**: Combining threshold shares back into the single secret key
+//! - **device**: Delegating a party's nonce and signing to an external signing device (experimental)
+//! - **rotation**: Sweeping an old group key's coins to a new wallet (`dkg-rotate`)
 
+pub mod audit;
+pub mod backup;
+pub mod compact;
+pub mod dealer;
+pub mod device;
+pub mod directory;
 pub mod dkg_tx;
+pub mod doctor;
+pub mod events;
+pub mod fingerprint;
+pub mod health;
+pub mod identity;
 pub mod keygen;
+pub mod keygen_resume;
+pub mod nostr;
+pub mod offline;
+pub mod payload;
+pub mod reconstruct;
 pub mod recovery;
+pub mod relay;
+#[cfg(feature = "network")]
+pub mod relay_client;
+pub mod reporter;
 pub mod reshare;
+#[cfg(feature = "network")]
+pub mod rotation;
+pub mod session_board;
+pub mod session_report;
 pub mod signing;
+pub mod wallet;
+pub mod weighted;
@@ -0,0 +1,901 @@
+//! Nostr Keys
+//!
+//! A party's identity key (see `protocol::identity`) is a BIP340 key, which
+//! is exactly what Nostr uses. Treating it as the party's Nostr key means one
+//! key signs round payloads, is the address other parties encrypt Round 2
+//! shares to (NIP-44), and is the `npub` the party is known by on relays.
+//!
+//! `nostr-keygen` makes a fresh key and `nostr-import` brings an existing
+//! `nsec` into a wallet. Either has to happen before `keygen-round1`, which
+//! announces the key; replacing it later means every other party must
+//! re-pin it with `dkg-identity --trust`.
+//!
+//! Round 2 shares can also travel as NIP-17 private DMs instead of a room
+//! broadcast: each recipient gets a NIP-59 gift wrap (kind 1059) signed by a
+//! throwaway key, so relays see neither who sent it nor that the recipients
+//! belong to the same DKG. Inside, a seal (kind 13) signed by the sender
+//! carries the chat message (kind 14) holding that recipient's share.
+//!
+//! `dkg-group-publish` puts a wallet's `group_info.json` on Nostr as a NIP-78
+//! app-data event (kind 30078), replaceable per group key, so a later publish
+//! supersedes the old one. `dkg-group-import` checks it on another machine and
+//! sets up a watch-only wallet: the author must be someone the importer
+//! already trusts (`--from`, or a key pinned in the target wallet), since
+//! anyone can sign a group info that names themselves as a party.
+//!
+//! Each of these prints its events unless given relays (`--nostr`), in which
+//! case it publishes them and reads the other side's from there:
+//! [`collect_dms`] gathers an inbox's gift wraps, [`fetch_group_info`] the
+//! newest group info by a trusted author.
+
+use crate::crypto::nip44;
+use crate::crypto::secret::SecretBytes;
+use crate::protocol::health::now;
+use crate::protocol::identity::{self, IdentityKey, Roster, IDENTITY_KEY_FILE};
+use crate::protocol::keygen::{get_state_dir, GroupInfo, Round2Output, ROUND1_OUTPUT_FILE};
+use crate::protocol::relay::{Filter, Relays};
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use bech32::{Bech32, Hrp};
+use rand::Rng;
+use schnorr_fun::{Message, Schnorr, Signature};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+const NPUB: Hrp = Hrp::parse_unchecked("npub");
+const NSEC: Hrp = Hrp::parse_unchecked("nsec");
+
+/// Bech32 `npub` form of an x-only public key
+pub fn npub(public_key_hex: &str) -> Result<String> {
+    let bytes = hex::decode(public_key_hex)?;
+    Ok(bech32::encode::<Bech32>(NPUB, &bytes)?)
+}
+
+/// Accept a public key as `npub1…` or 64 hex characters, returning hex
+pub fn parse_public_key(input: &str) -> Result<String> {
+    let input = input.trim();
+    let hex_key = if input.to_ascii_lowercase().starts_with("npub1") {
+        let (hrp, bytes) = bech32::decode(input).context("Invalid npub")?;
+        if hrp != NPUB {
+            bail!("Expected an npub");
+        }
+        hex::encode(bytes)
+    } else {
+        input.to_ascii_lowercase()
+    };
+    identity::parse_public_key(&hex_key).context("Not a valid Nostr public key")?;
+    Ok(hex_key)
+}
+
+/// Accept a secret key as `nsec1…` or 64 hex characters
+pub fn parse_secret_key(input: &str) -> Result<IdentityKey> {
+    let input = input.trim();
+    let bytes = if input.to_ascii_lowercase().starts_with("nsec1") {
+        let (hrp, bytes) = bech32::decode(input).context("Invalid nsec")?;
+        if hrp != NSEC {
+            bail!("Expected an nsec");
+        }
+        SecretBytes::new(bytes)
+    } else {
+        SecretBytes::new(hex::decode(input).context("Secret key is not nsec or hex")?)
+    };
+    if bytes.len() != 32 {
+        bail!("Nostr secret keys are 32 bytes");
+    }
+    IdentityKey::from_secret_bytes(bytes.expose())
+}
+
+/// NIP-17 chat message (the unsigned inner "rumor")
+const KIND_DM: u64 = 14;
+
+/// NIP-59 seal: the rumor, encrypted and signed by the sender
+const KIND_SEAL: u64 = 13;
+
+/// NIP-59 gift wrap: the seal, encrypted and signed by a throwaway key
+pub const KIND_GIFT_WRAP: u64 = 1059;
+
+/// NIP-78 application data, replaceable per `d` tag
+pub const KIND_APP_DATA: u64 = 30078;
+
+/// `d` tag prefix for published group info, followed by the group key
+const GROUP_INFO_TAG: &str = "frostdao/group_info/";
+
+/// Seal and wrap timestamps are backdated by up to this much (NIP-59)
+const TIMESTAMP_JITTER_SECS: u64 = 2 * 24 * 60 * 60;
+
+/// A Nostr event (NIP-01)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub id: String,
+    pub pubkey: String,
+    pub created_at: u64,
+    pub kind: u64,
+    pub tags: Vec<Vec<String>>,
+    pub content: String,
+    /// Absent on rumors, which are never published
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sig: Option<String>,
+}
+
+impl Event {
+    pub(crate) fn new(
+        pubkey: String,
+        created_at: u64,
+        kind: u64,
+        tags: Vec<Vec<String>>,
+        content: String,
+    ) -> Self {
+        let mut event = Self {
+            id: String::new(),
+            pubkey,
+            created_at,
+            kind,
+            tags,
+            content,
+            sig: None,
+        };
+        event.id = hex::encode(event.compute_id());
+        event
+    }
+
+    /// sha256 of `[0, pubkey, created_at, kind, tags, content]`
+    fn compute_id(&self) -> [u8; 32] {
+        let serialized = serde_json::json!([
+            0,
+            self.pubkey,
+            self.created_at,
+            self.kind,
+            self.tags,
+            self.content
+        ]);
+        Sha256::digest(serialized.to_string().as_bytes()).into()
+    }
+
+    pub(crate) fn signed(mut self, key: &IdentityKey) -> Self {
+        self.sig = Some(key.sign_raw(&self.compute_id()));
+        self
+    }
+
+    /// Check the id and the author's signature
+    pub fn verify(&self) -> Result<()> {
+        let id = self.compute_id();
+        if hex::encode(id) != self.id {
+            bail!("Nostr event id does not match its content");
+        }
+        let signature = self
+            .sig
+            .as_deref()
+            .and_then(|sig| hex::decode(sig).ok())
+            .and_then(|b| <[u8; 64]>::try_from(b).ok())
+            .and_then(Signature::from_bytes)
+            .context("Nostr event is not signed")?;
+        let public_key =
+            identity::parse_public_key(&self.pubkey).context("Malformed Nostr event pubkey")?;
+        if !Schnorr::<Sha256>::verify_only().verify(&public_key, Message::raw(&id), &signature) {
+            bail!("Invalid signature on Nostr event {}", self.id);
+        }
+        Ok(())
+    }
+
+    /// Public key named in the first `p` tag
+    pub fn recipient(&self) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|tag| tag.first().map(String::as_str) == Some("p"))
+            .and_then(|tag| tag.get(1))
+            .map(String::as_str)
+    }
+}
+
+fn backdated() -> u64 {
+    now().saturating_sub(rand::thread_rng().gen_range(0..TIMESTAMP_JITTER_SECS))
+}
+
+/// Wrap `message` as a NIP-17 DM from `sender` to `recipient_hex`
+pub fn gift_wrap(sender: &IdentityKey, recipient_hex: &str, message: &str) -> Result<Event> {
+    let p_tag = vec![vec!["p".to_string(), recipient_hex.to_string()]];
+    let rumor = Event::new(
+        sender.public_hex(),
+        now(),
+        KIND_DM,
+        p_tag.clone(),
+        message.to_string(),
+    );
+    let seal = Event::new(
+        sender.public_hex(),
+        backdated(),
+        KIND_SEAL,
+        Vec::new(),
+        nip44::encrypt(
+            &sender.conversation_key(recipient_hex)?,
+            &serde_json::to_string(&rumor)?,
+        )?,
+    )
+    .signed(sender);
+
+    let ephemeral = IdentityKey::generate();
+    Ok(Event::new(
+        ephemeral.public_hex(),
+        backdated(),
+        KIND_GIFT_WRAP,
+        p_tag,
+        nip44::encrypt(
+            &ephemeral.conversation_key(recipient_hex)?,
+            &serde_json::to_string(&seal)?,
+        )?,
+    )
+    .signed(&ephemeral))
+}
+
+/// Open a gift wrap addressed to `me`, returning (sender key hex, message)
+pub fn unwrap_gift(me: &IdentityKey, wrap: &Event) -> Result<(String, String)> {
+    if wrap.kind != KIND_GIFT_WRAP {
+        bail!("Not a gift wrap (kind {})", wrap.kind);
+    }
+    wrap.verify()?;
+    let seal_json = nip44::decrypt(&me.conversation_key(&wrap.pubkey)?, &wrap.content)
+        .context("Gift wrap is not addressed to this wallet's Nostr key")?;
+    let seal: Event = serde_json::from_slice(seal_json.expose())?;
+    if seal.kind != KIND_SEAL {
+        bail!("Gift wrap does not contain a seal");
+    }
+    seal.verify()?;
+
+    let rumor_json = nip44::decrypt(&me.conversation_key(&seal.pubkey)?, &seal.content)?;
+    let rumor: Event = serde_json::from_slice(rumor_json.expose())?;
+    if rumor.kind != KIND_DM || hex::encode(rumor.compute_id()) != rumor.id {
+        bail!("Sealed message is not a valid NIP-17 DM");
+    }
+    // Otherwise anyone could seal a message that claims another author
+    if rumor.pubkey != seal.pubkey {
+        bail!("DM author does not match the key that sealed it");
+    }
+    Ok((seal.pubkey, rumor.content))
+}
+
+/// Split a Round 2 output into one gift-wrapped DM per recipient
+///
+/// Each DM holds a signed Round2Output with just that recipient's share,
+/// so finalize treats it exactly like the broadcast form.
+pub fn dm_round2_shares(output: &Round2Output, storage: &dyn Storage) -> Result<Vec<Event>> {
+    let sender = IdentityKey::load_or_create(storage)?;
+    let roster = Roster::load(storage)?.context("Identity roster missing")?;
+
+    let mut dms = Vec::new();
+    for share in &output.shares {
+        let recipient = roster
+            .0
+            .get(&share.to_index)
+            .with_context(|| format!("Party {} has no pinned identity", share.to_index))?;
+        let mut single = Round2Output {
+            party_index: output.party_index,
+            shares: vec![share.clone()],
+            protocol_version: output.protocol_version,
+            event_type: output.event_type.clone(),
+            auth: None,
+        };
+        sender.sign(&mut single)?;
+        dms.push(gift_wrap(
+            &sender,
+            recipient,
+            &serde_json::to_string(&single)?,
+        )?);
+    }
+    Ok(dms)
+}
+
+/// How far back inbox queries reach: a round can take days to finish, and
+/// gift wraps are backdated by up to [`TIMESTAMP_JITTER_SECS`] on top
+pub const INBOX_LOOKBACK_SECS: u64 = 7 * 24 * 60 * 60 + TIMESTAMP_JITTER_SECS;
+
+/// Gift wraps to `me` on `relays` that `want` keeps, until `needed` have
+/// arrived or `until` passes
+///
+/// `want` sees each opened wrap's sender and message and returns a key for
+/// it, or `None` to skip it (another flow's DM); a second wrap with a key
+/// already kept counts once. Wraps that don't open are someone else's noise
+/// and skipped too.
+pub fn collect_dms(
+    relays: &mut Relays,
+    me: &IdentityKey,
+    needed: usize,
+    until: Instant,
+    mut want: impl FnMut(&str, &str) -> Option<String>,
+) -> Result<Vec<Event>> {
+    let filter = Filter {
+        since: Some(now().saturating_sub(INBOX_LOOKBACK_SECS)),
+        ..Filter::kinds(&[KIND_GIFT_WRAP]).tag('p', &[&me.public_hex()])
+    };
+    let mut kept: BTreeMap<String, Event> = BTreeMap::new();
+    let mut keep = |wrap: Event, kept: &mut BTreeMap<String, Event>| {
+        if let Ok((sender, message)) = unwrap_gift(me, &wrap) {
+            if let Some(key) = want(&sender, &message) {
+                kept.entry(key).or_insert(wrap);
+            }
+        }
+    };
+    for wrap in relays.subscribe(INBOX_SUBSCRIPTION, vec![filter])? {
+        keep(wrap, &mut kept);
+    }
+    while kept.len() < needed {
+        match relays.next_event(until)? {
+            Some(wrap) => keep(wrap, &mut kept),
+            None => break,
+        }
+    }
+    relays.close(INBOX_SUBSCRIPTION);
+    Ok(kept.into_values().collect())
+}
+
+const INBOX_SUBSCRIPTION: &str = "frostdao-inbox";
+
+/// Events as `--data` takes them: space-separated JSON
+pub fn events_data(events: &[Event]) -> Result<String> {
+    Ok(events
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<serde_json::Result<Vec<_>>>()?
+        .join(" "))
+}
+
+/// Group info as a replaceable event signed by `author`
+pub fn group_info_event(author: &IdentityKey, info: &GroupInfo) -> Result<Event> {
+    let tags = vec![
+        vec![
+            "d".to_string(),
+            format!("{}{}", GROUP_INFO_TAG, info.group_public_key),
+        ],
+        vec!["t".to_string(), "frostdao".to_string()],
+    ];
+    Ok(Event::new(
+        author.public_hex(),
+        now(),
+        KIND_APP_DATA,
+        tags,
+        serde_json::to_string(info)?,
+    )
+    .signed(author))
+}
+
+/// Check a published group info event and return the group info it carries
+///
+/// Covers what the event itself can prove: the signature, that the `d` tag
+/// names the key inside, and that the addresses belong to that key. Whether
+/// the author is trustworthy is up to the caller.
+pub fn verify_group_info_event(event: &Event) -> Result<GroupInfo> {
+    if event.kind != KIND_APP_DATA {
+        bail!("Not a group info event (kind {})", event.kind);
+    }
+    event.verify()?;
+    let info: GroupInfo =
+        serde_json::from_str(&event.content).context("Event does not hold a group info")?;
+
+    let d_tag = event
+        .tags
+        .iter()
+        .find(|tag| tag.first().map(String::as_str) == Some("d"))
+        .and_then(|tag| tag.get(1))
+        .context("Group info event has no d tag")?;
+    if d_tag.strip_prefix(GROUP_INFO_TAG) != Some(info.group_public_key.as_str()) {
+        bail!("Group info event is tagged for a different group key");
+    }
+
+    let key_bytes = hex::decode(&info.group_public_key).context("Malformed group public key")?;
+    let xonly = bitcoin::XOnlyPublicKey::from_slice(&key_bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid group public key: {}", e))?;
+    let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+    for (address, network) in [
+        (&info.taproot_address_testnet, bitcoin::Network::Testnet),
+        (&info.taproot_address_mainnet, bitcoin::Network::Bitcoin),
+    ] {
+        if *address != bitcoin::Address::p2tr(&secp, xonly, None, network).to_string() {
+            bail!(
+                "Address {} does not belong to the published group key",
+                address
+            );
+        }
+    }
+    if info.threshold == 0
+        || info.threshold > info.total_parties
+        || info.parties.len() as u32 != info.total_parties
+    {
+        bail!(
+            "Group info is inconsistent ({}-of-{} with {} parties listed)",
+            info.threshold,
+            info.total_parties,
+            info.parties.len()
+        );
+    }
+    Ok(info)
+}
+
+/// Publish the wallet's group info, signed by its Nostr identity
+pub fn group_publish_core(storage: &dyn Storage) -> Result<CommandResult> {
+    let mut out = String::new();
+
+    if !storage.exists("group_info.json") {
+        bail!("No group_info.json in this wallet; run dkg-info first");
+    }
+    let info: GroupInfo = serde_json::from_slice(&storage.read("group_info.json")?)?;
+    let author = IdentityKey::load(storage)?
+        .context("Wallet has no Nostr identity; create one with nostr-keygen")?;
+    let event = group_info_event(&author, &info)?;
+
+    out.push_str("📣 Group info event\n\n");
+    out.push_str(&format!("   Group key: {}\n", info.group_public_key));
+    out.push_str(&format!("   Author:    {}\n", npub(&author.public_hex())?));
+    out.push_str(&format!(
+        "   Kind:      {} (replaceable)\n\n",
+        KIND_APP_DATA
+    ));
+
+    let author_party = info
+        .parties
+        .iter()
+        .find(|party| party.identity.as_deref() == Some(author.public_hex().as_str()));
+    match author_party {
+        Some(party) => out.push_str(&format!(
+            "   Signed as party {}, as pinned at keygen.\n\n",
+            party.index
+        )),
+        None => out.push_str(
+            "⚠️  This key is not a party identity pinned in the group info.\n   \
+             Importers must trust it explicitly with --from.\n\n",
+        ),
+    }
+
+    out.push_str("🧠 What this is for:\n");
+    out.push_str(
+        "   • Auditors and new signers get the group's public state without a file transfer\n",
+    );
+    out.push_str("   • Publishing again replaces the previous event for this group key\n");
+    out.push_str("   • Importers check it with: frostdao dkg-group-import --name <wallet> --data '<event>' --from <npub>\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&event)?,
+    })
+}
+
+/// Verify a group info event and install it as (watch-only) wallet state
+///
+/// `from` is the author the importer expects; without it the author must
+/// already be pinned in the wallet's roster.
+pub fn group_import_core(
+    name: &str,
+    data: &str,
+    from: Option<&str>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+
+    let event: Event = serde_json::from_str(data.trim()).context("Not a Nostr event")?;
+    let mut info = verify_group_info_event(&event)?;
+    let author_npub = npub(&event.pubkey)?;
+
+    let roster = Roster::load(storage)?;
+    let trusted = match from {
+        Some(from) => parse_public_key(from)? == event.pubkey,
+        None => roster
+            .as_ref()
+            .is_some_and(|roster| roster.0.values().any(|key| *key == event.pubkey)),
+    };
+    if !trusted {
+        match from {
+            Some(_) => bail!(
+                "Group info is signed by {}, not the author passed with --from",
+                author_npub
+            ),
+            None => bail!(
+                "Group info is signed by {} ({}), which this wallet does not trust yet; \
+                 confirm the key with its owner and pass --from",
+                author_npub,
+                identity::fingerprint(&event.pubkey)
+            ),
+        }
+    }
+
+    // An existing wallet must be the same group
+    let current = if storage.exists("shared_key.bin") {
+        let shared_key: schnorr_fun::frost::SharedKey<schnorr_fun::fun::marker::EvenY> =
+            bincode::deserialize(&storage.read("shared_key.bin")?)?;
+        Some(hex::encode(shared_key.public_key().to_xonly_bytes()))
+    } else if storage.exists("group_info.json") {
+        let existing: GroupInfo = serde_json::from_slice(&storage.read("group_info.json")?)?;
+        Some(existing.group_public_key)
+    } else {
+        None
+    };
+    if let Some(current) = current {
+        if current != info.group_public_key {
+            bail!(
+                "Wallet {} belongs to group key {}, not {}",
+                name,
+                current,
+                info.group_public_key
+            );
+        }
+    }
+    let watch_only = !storage.exists("shared_key.bin");
+    info.name = name.to_string();
+    // Directory entries the publisher did not have are kept
+    if let Some(existing) = storage
+        .read("group_info.json")
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<GroupInfo>(&bytes).ok())
+    {
+        for (index, contact) in existing.directory {
+            info.directory.entry(index).or_insert(contact);
+        }
+    }
+    storage.write(
+        "group_info.json",
+        serde_json::to_string_pretty(&info)?.as_bytes(),
+    )?;
+
+    let mut pinned = Roster::default();
+    for party in &info.parties {
+        if let Some(key) = &party.identity {
+            pinned.pin(party.index, key)?;
+        }
+    }
+    match roster {
+        None if !pinned.0.is_empty() => pinned.save(storage)?,
+        Some(roster)
+            if roster
+                .0
+                .iter()
+                .any(|(i, key)| pinned.0.get(i).is_some_and(|k| k != key)) =>
+        {
+            out.push_str("⚠️  Some party identities differ from the keys pinned in this wallet.\n");
+            out.push_str("   The wallet's roster was left unchanged; check with dkg-identity.\n\n");
+        }
+        _ => {}
+    }
+
+    out.push_str("✅ Group info verified and imported\n\n");
+    out.push_str(&format!("   Signed by:   {}\n", author_npub));
+    out.push_str(&format!("   Group key:   {}\n", info.group_public_key));
+    out.push_str(&format!(
+        "   Threshold:   {}-of-{}{}\n",
+        info.threshold,
+        info.total_parties,
+        if info.hierarchical { " (HTSS)" } else { "" }
+    ));
+    out.push_str(&format!(
+        "   Testnet:     {}\n",
+        info.taproot_address_testnet
+    ));
+    out.push_str(&format!(
+        "   Mainnet:     {}\n\n",
+        info.taproot_address_mainnet
+    ));
+    out.push_str("   Parties:\n");
+    for party in &info.parties {
+        out.push_str(&format!(
+            "   Party {} (rank {}): {}\n",
+            party.index,
+            party.rank,
+            party
+                .identity
+                .as_deref()
+                .map(identity::fingerprint)
+                .unwrap_or_else(|| "no identity".to_string())
+        ));
+    }
+    if watch_only {
+        out.push_str("\n👀 Watch-only: this wallet can check balances and verify, but not sign.\n");
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&info)?,
+    })
+}
+
+/// The newest group info event on `relays` signed by one of `authors`
+///
+/// Only events that verify count. With `group_key` the event must be for
+/// that group; without it the authors must have published for just one.
+pub fn fetch_group_info(
+    relays: &mut Relays,
+    authors: Vec<String>,
+    group_key: Option<&str>,
+) -> Result<Event> {
+    let mut filter = Filter {
+        authors: Some(authors),
+        ..Filter::kinds(&[KIND_APP_DATA])
+    };
+    if let Some(group_key) = group_key {
+        filter = filter.tag('d', &[&format!("{}{}", GROUP_INFO_TAG, group_key)]);
+    }
+    let mut newest: BTreeMap<String, Event> = BTreeMap::new();
+    for event in relays.fetch("frostdao-group-info", vec![filter])? {
+        let Ok(info) = verify_group_info_event(&event) else {
+            continue; // other apps' data, or a forgery
+        };
+        match newest.get(&info.group_public_key) {
+            Some(kept) if kept.created_at >= event.created_at => {}
+            _ => {
+                newest.insert(info.group_public_key, event);
+            }
+        }
+    }
+    if newest.len() > 1 {
+        bail!(crate::error::Error::User(format!(
+            "Group info for several groups was found ({}); import into the wallet of the one you want",
+            newest.keys().cloned().collect::<Vec<_>>().join(", ")
+        )));
+    }
+    newest.into_values().next().ok_or_else(|| {
+        crate::error::Error::Network(format!(
+            "No group info from that author on {}",
+            relays.urls().join(", ")
+        ))
+        .into()
+    })
+}
+
+/// CLI wrapper for group_publish_core; with `relays` the event is published
+/// there instead of printed
+pub fn group_publish(name: &str, qr: bool, relays: Option<&mut Relays>) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = group_publish_core(&storage)?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if let Some(relays) = relays {
+        let event: Event = serde_json::from_str(&cmd_result.result)?;
+        for url in relays.publish(&event)? {
+            println!("📡 Published to {}", url);
+        }
+        return Ok(());
+    }
+    println!("📋 Publish this event to your relays:\n");
+    println!("{}", crate::protocol::compact::present(&cmd_result.result));
+    if qr {
+        crate::qr::show_qr(&cmd_result.result)?;
+    }
+    Ok(())
+}
+
+/// CLI wrapper for group_import_core (creates the wallet folder if needed)
+///
+/// Without `data` the event is fetched from `relays`: the newest one by the
+/// `from` author, or by a party pinned in the wallet, for the wallet's group
+/// key if it has one.
+pub fn group_import(
+    name: &str,
+    data: Option<&str>,
+    from: Option<&str>,
+    relays: Option<&mut Relays>,
+) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let data = match (data, relays) {
+        (Some(data), _) => crate::qr::resolve_payload(data)?,
+        (None, Some(relays)) => {
+            let authors = match from {
+                Some(from) => vec![parse_public_key(from)?],
+                None => Roster::load(&storage)?
+                    .map(|roster| roster.0.into_values().collect())
+                    .unwrap_or_default(),
+            };
+            if authors.is_empty() {
+                bail!(crate::error::Error::User(
+                    "Pass --from with the publisher's npub to fetch their group info".to_string()
+                ));
+            }
+            let group_key = storage
+                .read("group_info.json")
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<GroupInfo>(&bytes).ok())
+                .map(|info| info.group_public_key);
+            let event = fetch_group_info(relays, authors, group_key.as_deref())?;
+            println!("📡 Group info event {} fetched", event.id);
+            serde_json::to_string(&event)?
+        }
+        (None, None) => bail!(crate::error::Error::User(
+            "--data is required without relays".to_string()
+        )),
+    };
+    let cmd_result = group_import_core(name, &data, from, &storage)?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("💾 Saved to: {}/group_info.json", get_state_dir(name));
+    Ok(())
+}
+
+/// Install `key` as the wallet's Nostr identity
+fn install_key(key: IdentityKey, force: bool, storage: &dyn Storage) -> Result<CommandResult> {
+    let mut out = String::new();
+
+    if storage.exists(IDENTITY_KEY_FILE) && !force {
+        bail!("Wallet already has a Nostr key; pass --force to replace it");
+    }
+    // Already announced in Round 1: others hold the old key
+    let announced = storage.exists(ROUND1_OUTPUT_FILE) || storage.exists("round1_state.json");
+    key.save(storage)?;
+
+    let public_hex = key.public_hex();
+    let npub = npub(&public_hex)?;
+    // Our own pin follows the new key
+    if let (Some(mut roster), Some(my_index)) =
+        (Roster::load(storage)?, identity::own_index(storage)?)
+    {
+        roster.0.insert(my_index, public_hex.clone());
+        roster.save(storage)?;
+    }
+
+    out.push_str("🔑 Nostr identity installed\n\n");
+    out.push_str(&format!("   npub:        {}\n", npub));
+    out.push_str(&format!("   Public key:  {}\n", public_hex));
+    out.push_str(&format!(
+        "   Fingerprint: {}\n\n",
+        identity::fingerprint(&public_hex)
+    ));
+    out.push_str("🧠 What it's for:\n");
+    out.push_str("   • Signs every round payload you publish\n");
+    out.push_str("   • Other parties NIP-44 encrypt your Round 2 shares to it\n");
+    out.push_str("   • It's the npub other parties know you by on Nostr\n\n");
+    if announced {
+        out.push_str("⚠️  This wallet already announced a different key in Round 1.\n");
+        out.push_str("   Every other party must re-pin you:\n");
+        out.push_str(&format!(
+            "   frostdao dkg-identity --name <wallet> --trust <your index>={}\n",
+            npub
+        ));
+    }
+
+    let result = serde_json::json!({
+        "npub": npub,
+        "public_key": public_hex,
+    });
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+pub fn nostr_keygen_core(force: bool, storage: &dyn Storage) -> Result<CommandResult> {
+    install_key(IdentityKey::generate(), force, storage)
+}
+
+pub fn nostr_import_core(
+    secret: &str,
+    force: bool,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    install_key(parse_secret_key(secret)?, force, storage)
+}
+
+fn print_result(name: &str, cmd_result: &CommandResult) {
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("💾 Saved to: {}/{}", get_state_dir(name), IDENTITY_KEY_FILE);
+}
+
+/// CLI wrapper for nostr_keygen_core (creates the wallet folder if needed)
+pub fn nostr_keygen(name: &str, force: bool) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = nostr_keygen_core(force, &storage)?;
+    print_result(name, &cmd_result);
+    Ok(())
+}
+
+/// CLI wrapper for nostr_import_core (creates the wallet folder if needed)
+pub fn nostr_import(name: &str, secret: &str, force: bool) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = nostr_import_core(secret, force, &storage)?;
+    print_result(name, &cmd_result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_nostr_import_keeps_key_and_encodes_npub() {
+        // Key pair from the NIP-19 examples
+        let nsec = "nsec1vl029mgpspedva04g90vltkh6fvh240zqtv9k0t9af8935ke9laqsnlfe5";
+        let storage = MemoryStorage::new();
+        nostr_import_core(nsec, false, &storage).unwrap();
+
+        let key = IdentityKey::load(&storage).unwrap().unwrap();
+        assert_eq!(
+            key.public_hex(),
+            "7e7e9c42a91bfef19fa929e5fda1b72e0ebc1a4c1141673e2794234d86addf4e"
+        );
+        assert_eq!(
+            npub(&key.public_hex()).unwrap(),
+            "npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg"
+        );
+        assert_eq!(
+            parse_public_key("npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg")
+                .unwrap(),
+            key.public_hex()
+        );
+
+        // A DM only opens for its recipient, and keeps the sender's identity
+        let bob = IdentityKey::generate();
+        let wrap = gift_wrap(&key, &bob.public_hex(), "round 2 share").unwrap();
+        assert_eq!(wrap.kind, KIND_GIFT_WRAP);
+        assert_ne!(wrap.pubkey, key.public_hex());
+        assert_eq!(wrap.recipient(), Some(bob.public_hex().as_str()));
+        let (sender, message) = unwrap_gift(&bob, &wrap).unwrap();
+        assert_eq!(
+            (sender.as_str(), message.as_str()),
+            (key.public_hex().as_str(), "round 2 share")
+        );
+        assert!(unwrap_gift(&IdentityKey::generate(), &wrap).is_err());
+        let mut edited = wrap.clone();
+        edited.created_at += 1;
+        assert!(unwrap_gift(&bob, &edited).is_err());
+
+        // Replacing needs --force
+        assert!(nostr_keygen_core(false, &storage).is_err());
+        nostr_keygen_core(true, &storage).unwrap();
+        assert_ne!(
+            IdentityKey::load(&storage).unwrap().unwrap().public_hex(),
+            key.public_hex()
+        );
+    }
+
+    #[test]
+    fn test_group_info_event_imports_from_trusted_author() {
+        let publisher = MemoryStorage::new();
+        let author = IdentityKey::load_or_create(&publisher).unwrap();
+        let other = IdentityKey::generate();
+        let group_key = IdentityKey::generate().public_hex();
+        let xonly = bitcoin::XOnlyPublicKey::from_slice(&hex::decode(&group_key).unwrap()).unwrap();
+        let secp = bitcoin::secp256k1::Secp256k1::new();
+        let address = |network| bitcoin::Address::p2tr(&secp, xonly, None, network).to_string();
+        let party = |index: u32, key: &IdentityKey| crate::protocol::keygen::PartyInfo {
+            index,
+            rank: 0,
+            verification_share: "unavailable".to_string(),
+            identity: Some(key.public_hex()),
+        };
+        let info = GroupInfo {
+            name: "treasury".to_string(),
+            group_public_key: group_key.clone(),
+            taproot_address_testnet: address(bitcoin::Network::Testnet),
+            taproot_address_mainnet: address(bitcoin::Network::Bitcoin),
+            threshold: 2,
+            total_parties: 2,
+            hierarchical: false,
+            parties: vec![party(1, &author), party(2, &other)],
+            directory: Default::default(),
+        };
+        publisher
+            .write("group_info.json", &serde_json::to_vec(&info).unwrap())
+            .unwrap();
+        let event = group_publish_core(&publisher).unwrap().result;
+
+        // The author has to be vouched for
+        let auditor = MemoryStorage::new();
+        assert!(group_import_core("audit", &event, None, &auditor).is_err());
+        let stranger = npub(&other.public_hex()).unwrap();
+        assert!(group_import_core("audit", &event, Some(&stranger), &auditor).is_err());
+
+        let tampered = event.replace(&info.taproot_address_mainnet, "bc1pattacker");
+        let from = npub(&author.public_hex()).unwrap();
+        assert!(group_import_core("audit", &tampered, Some(&from), &auditor).is_err());
+
+        group_import_core("audit", &event, Some(&from), &auditor).unwrap();
+        let imported: GroupInfo =
+            serde_json::from_slice(&auditor.read("group_info.json").unwrap()).unwrap();
+        assert_eq!(
+            (imported.name.as_str(), imported.group_public_key.as_str()),
+            ("audit", group_key.as_str())
+        );
+        let roster = Roster::load(&auditor).unwrap().unwrap();
+        assert_eq!(roster.0.get(&2), Some(&other.public_hex()));
+
+        // Re-publishing is trusted through the pinned roster
+        group_import_core("audit", &event, None, &auditor).unwrap();
+    }
+}
@@ -0,0 +1,399 @@
+//! Air-Gapped Signing
+//!
+//! `dkg-sign-offline` lets a share live on a laptop that never goes online.
+//! Session data comes in as a file and the result goes out as a file, so it
+//! can be carried across by USB stick. The signer runs it twice per session:
+//!
+//! 1. With the `dkg_build_tx` output only: generates and stores the nonce,
+//!    writes the public `dkg_nonce` for the coordinator
+//! 2. With the `dkg_build_tx` output plus every signer's `dkg_nonce`: writes
+//!    the `dkg_signature_share`
+//!
+//! Nothing in this module touches the network. Build with
+//! `--no-default-features --features offline` to have the compiler enforce it:
+//! HTTP support is then not linked at all.
+//!
+//! Before anything is signed the unsigned transaction is decoded and checked
+//! against the session summary (destination, amount, change), and the sighash
+//! is recomputed from it and the outputs it spends (`prevouts`, which the
+//! session file must carry), so the operator signs exactly what they approve.
+
+use crate::protocol::device;
+use crate::protocol::dkg_tx::{
+    create_signature_share, dkg_generate_nonce_core, parse_merkle_root, review_unsigned_tx,
+    BuildTxOutput,
+};
+use crate::protocol::keygen::{get_state_dir, parse_space_separated_json, HtssMetadata};
+use crate::protocol::signing::NonceOutput;
+use crate::protocol::weighted;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{Context, Result};
+
+/// Session file contents: the transaction to sign plus any collected nonces
+#[derive(Debug)]
+pub struct OfflineSession {
+    pub tx: BuildTxOutput,
+    pub nonces: Vec<NonceOutput>,
+}
+
+/// Parse a session file (whitespace-separated JSON objects, any order)
+pub fn parse_offline_session(data: &str) -> Result<OfflineSession> {
+    let objects: Vec<serde_json::Value> = parse_space_separated_json(data)?;
+
+    let mut tx: Option<BuildTxOutput> = None;
+    let mut nonces: Vec<NonceOutput> = Vec::new();
+    for object in objects {
+        match object.get("type").and_then(|t| t.as_str()) {
+            Some("dkg_build_tx") => {
+                if tx.is_some() {
+                    anyhow::bail!("Session file contains more than one dkg_build_tx object");
+                }
+                tx = Some(serde_json::from_value(object)?);
+            }
+            Some("dkg_nonce") => nonces.push(serde_json::from_value(object)?),
+            other => anyhow::bail!("Unexpected object in session file (type {:?})", other),
+        }
+    }
+
+    let tx = tx.context("Session file has no dkg_build_tx object")?;
+    if let Some(stray) = nonces.iter().find(|n| n.session != tx.session_id) {
+        anyhow::bail!(
+            "Nonce from party {} is for session {}, not {}",
+            stray.party_index,
+            stray.session,
+            tx.session_id
+        );
+    }
+    Ok(OfflineSession { tx, nonces })
+}
+
+/// Core function for air-gapped signing (nonce step or share step)
+pub fn dkg_sign_offline_core(
+    wallet_name: &str,
+    session_data: &str,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    // The air gap holds the share; a device wallet signs with dkg-nonce/dkg-sign
+    device::require_local(storage, "dkg-sign-offline")?;
+    let session = parse_offline_session(session_data)?;
+    let session_id = &session.tx.session_id;
+
+    let mut out = String::new();
+    out.push_str("Offline Signing (air-gapped)\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("Wallet:  {}\n", wallet_name));
+    out.push_str(&format!("Session: {}\n", session_id));
+    out.push_str(&format!("Network: {}\n\n", session.tx.network));
+    // Without the spent outputs the sighash can't be tied to the transaction
+    if session.tx.prevouts.is_empty() {
+        anyhow::bail!(crate::error::Error::Protocol(
+            "The session file's dkg_build_tx has no prevouts, so its sighash can't be \
+             checked here; rebuild it with dkg-build-tx"
+                .to_string()
+        ));
+    }
+    review_unsigned_tx(&session.tx, storage, &mut out)?;
+
+    let nonce_file = format!("dkg_nonce_{}.bin", session_id);
+
+    // Step 1: no nonces collected yet, commit to ours
+    if session.nonces.is_empty() {
+        if storage.exists(&nonce_file) {
+            anyhow::bail!(
+                "This machine already has a nonce for session {}. Add all signers' nonces to the session file to sign.",
+                session_id
+            );
+        }
+        let nonce = dkg_generate_nonce_core(wallet_name, session_id, storage)?;
+        out.push_str(&nonce.output);
+        out.push_str("📤 Step 1 of 2: give this nonce to the coordinator, then re-run\n");
+        out.push_str("   with the session file plus every signer's nonce.\n");
+        return Ok(CommandResult {
+            output: out,
+            result: nonce.result,
+        });
+    }
+
+    // Step 2: our nonce in the file must be the one stored here
+    let htss_metadata: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    let mine = session
+        .nonces
+        .iter()
+        .find(|n| n.party_index == htss_metadata.my_index)
+        .with_context(|| {
+            format!(
+                "Session file has no nonce from party {} (this signer)",
+                htss_metadata.my_index
+            )
+        })?;
+    let stored = storage
+        .read_secret(&nonce_file)
+        .context("No nonce stored for this session. Run step 1 first.")?;
+    let stored: schnorr_fun::binonce::NonceKeyPair = bincode::deserialize(&stored)?;
+    if hex::encode(bincode::serialize(&stored.public())?) != mine.nonce {
+        anyhow::bail!(
+            "Nonce for party {} in the session file does not match the one generated on this machine",
+            htss_metadata.my_index
+        );
+    }
+
+    let merkle_root = session
+        .tx
+        .merkle_root
+        .as_deref()
+        .map(parse_merkle_root)
+        .transpose()?;
+    let share = create_signature_share(
+        session_id,
+        &session.tx.sighash,
+        &session.nonces,
+        merkle_root.as_ref(),
+        session.tx.leaf.as_ref(),
+        storage,
+        &mut out,
+    )?;
+    out.push_str("\n📤 Step 2 of 2: give this signature share to the coordinator\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&share)?,
+    })
+}
+
+/// Air-gapped signing: read the session file, write the result file
+pub fn dkg_sign_offline(
+    wallet_name: &str,
+    input: &str,
+    output: Option<&str>,
+    qr: bool,
+) -> Result<()> {
+    // The session file may hold JSON or the UR frames scanned off the coordinator
+    let session_data = crate::qr::resolve_payload(&format!("@{}", input))?;
+    let storage = FileStorage::new(&get_state_dir(wallet_name))?;
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        dkg_sign_offline_core(wallet_name, &session_data, slot)
+    })?;
+
+    println!("{}", cmd_result.output);
+    match output {
+        Some(path) => {
+            std::fs::write(path, format!("{}\n", cmd_result.result))
+                .with_context(|| format!("Failed to write {}", path))?;
+            println!("💾 Wrote {}", path);
+        }
+        None => {
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            println!("📋 Carry this back to the coordinator:");
+            println!(
+                "{}\n",
+                crate::protocol::compact::present(&cmd_result.result)
+            );
+        }
+    }
+    if qr {
+        crate::qr::show_qr(&cmd_result.result)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::secret::serialize_secret;
+    use crate::protocol::dkg_tx::{
+        combine_signature_shares, key_spend_sighash, DkgSignatureShareOutput, SessionKey,
+        SpentOutput,
+    };
+    use crate::storage::MemoryStorage;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::hashes::Hash;
+    use bitcoin::key::TapTweak;
+    use bitcoin::transaction::Version;
+    use bitcoin::{Address, Amount, Network, Transaction, TxIn, TxOut};
+    use schnorr_fun::frost::{self, chilldkg::simplepedpop};
+    use sha2::Sha256;
+    use std::str::FromStr;
+
+    const DEST: &str = "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+    // Not the wallet's: a coordinator redirecting change would name this
+    const FOREIGN: &str = "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7";
+    // Script tree root of the spent output, so the share is made for P + t(P, root)
+    const MERKLE_ROOT: [u8; 32] = [0x42; 32];
+
+    fn session_json(amount_sats: u64, change: &str) -> String {
+        let script = |addr: &str| {
+            Address::from_str(addr)
+                .unwrap()
+                .require_network(Network::Testnet)
+                .unwrap()
+                .script_pubkey()
+        };
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(10_000),
+                    script_pubkey: script(DEST),
+                },
+                TxOut {
+                    value: Amount::from_sat(5_000),
+                    script_pubkey: script(change),
+                },
+            ],
+        };
+        // The input spends 15,500 sats from the source address
+        let prevouts = vec![SpentOutput {
+            value: 15_500,
+            script_pubkey: hex::encode(script(change).as_bytes()),
+        }];
+        serde_json::to_string(&BuildTxOutput {
+            session_id: "offline-test".to_string(),
+            sighash: hex::encode(key_spend_sighash(&tx, &prevouts).unwrap()),
+            unsigned_tx: bitcoin::consensus::encode::serialize_hex(&tx),
+            from_address: change.to_string(),
+            to_address: DEST.to_string(),
+            amount_sats,
+            fee_sats: 500,
+            network: "testnet".to_string(),
+            merkle_root: Some(hex::encode(MERKLE_ROOT)),
+            memo: None,
+            leaf: None,
+            prevouts,
+            event_type: "dkg_build_tx".to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_offline_nonce_then_share() {
+        let frost = frost::new_with_deterministic_nonces::<Sha256>();
+        let (shared_key, shares) =
+            simplepedpop::simulate_keygen(&frost.schnorr, 2, 3, 3, &mut rand::thread_rng());
+        let shared_key = shared_key.into_xonly();
+
+        let signers: Vec<MemoryStorage> = shares
+            .into_iter()
+            .take(2)
+            .map(|share| {
+                let share = share.into_xonly();
+                let index_bytes = share.index().to_bytes();
+                let my_index = u32::from_be_bytes(index_bytes[28..].try_into().unwrap());
+                let storage = MemoryStorage::new();
+                let metadata = HtssMetadata {
+                    my_index,
+                    my_rank: 0,
+                    threshold: 2,
+                    hierarchical: false,
+                    party_ranks: [(1, 0), (2, 0), (3, 0)].into_iter().collect(),
+                    wallet_version: crate::storage::migrations::CURRENT_WALLET_VERSION,
+                    signing_requirement: Default::default(),
+                    created_at: None,
+                    refreshed_at: None,
+                };
+                storage
+                    .write(
+                        "htss_metadata.json",
+                        &serde_json::to_vec(&metadata).unwrap(),
+                    )
+                    .unwrap();
+                storage
+                    .write(
+                        "paired_secret_share.bin",
+                        &serialize_secret(&share).unwrap(),
+                    )
+                    .unwrap();
+                storage
+                    .write("shared_key.bin", &bincode::serialize(&shared_key).unwrap())
+                    .unwrap();
+                storage
+            })
+            .collect();
+
+        // The spent output (and change) is the wallet's root key with the script tree
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let internal =
+            bitcoin::key::XOnlyPublicKey::from_slice(&shared_key.public_key().to_xonly_bytes())
+                .unwrap();
+        let own = Address::p2tr(
+            &secp,
+            internal,
+            Some(bitcoin::TapNodeHash::from_byte_array(MERKLE_ROOT)),
+            Network::Testnet,
+        )
+        .to_string();
+
+        // A session whose summary disagrees with the transaction is refused
+        assert!(dkg_sign_offline_core("w", &session_json(9_999, &own), &signers[0]).is_err());
+        // So is one sending change to an address the signer cannot derive
+        let redirected = dkg_sign_offline_core("w", &session_json(10_000, FOREIGN), &signers[0])
+            .unwrap_err()
+            .to_string();
+        assert!(redirected.contains("not an address of this wallet"));
+
+        // So is one without the spent outputs, or whose sighash isn't the transaction's
+        let mut bundle: BuildTxOutput = serde_json::from_str(&session_json(10_000, &own)).unwrap();
+        bundle.prevouts.clear();
+        let bare =
+            dkg_sign_offline_core("w", &serde_json::to_string(&bundle).unwrap(), &signers[0])
+                .unwrap_err()
+                .to_string();
+        assert!(bare.contains("no prevouts"), "{}", bare);
+        bundle = serde_json::from_str(&session_json(10_000, &own)).unwrap();
+        bundle.sighash = "11".repeat(32);
+        let forged =
+            dkg_sign_offline_core("w", &serde_json::to_string(&bundle).unwrap(), &signers[0])
+                .unwrap_err()
+                .to_string();
+        assert!(forged.contains("is not this transaction's"), "{}", forged);
+
+        let session = session_json(10_000, &own);
+        let nonces: Vec<String> = signers
+            .iter()
+            .map(|s| dkg_sign_offline_core("w", &session, s).unwrap().result)
+            .collect();
+        assert!(dkg_sign_offline_core("w", &session, &signers[0]).is_err());
+
+        let signing = format!("{}\n{}", session, nonces.join("\n"));
+        let shares: Vec<DkgSignatureShareOutput> = signers
+            .iter()
+            .map(|s| {
+                let result = dkg_sign_offline_core("w", &signing, s).unwrap();
+                serde_json::from_str(&result.result).unwrap()
+            })
+            .collect();
+        assert!(!signers[0].exists("dkg_nonce_offline-test.bin"));
+
+        // The nonce is single-use: a second attempt has nothing to sign with
+        assert!(dkg_sign_offline_core("w", &signing, &signers[0]).is_err());
+
+        // Shares combine into a valid key-path signature for the script-augmented
+        // P2TR output key
+        let nonce_outputs: Vec<NonceOutput> =
+            parse_space_separated_json(&nonces.join(" ")).unwrap();
+        let built: BuildTxOutput = serde_json::from_str(&session).unwrap();
+        let sighash: [u8; 32] = hex::decode(&built.sighash).unwrap().try_into().unwrap();
+        let (sig, _) = combine_signature_shares(
+            &shared_key,
+            &nonce_outputs,
+            &sighash,
+            &shares,
+            SessionKey::Output(Some(&MERKLE_ROOT)),
+        )
+        .unwrap();
+
+        let (output_key, _) = internal.tap_tweak(
+            &secp,
+            Some(bitcoin::TapNodeHash::from_byte_array(MERKLE_ROOT)),
+        );
+        secp.verify_schnorr(
+            &bitcoin::secp256k1::schnorr::Signature::from_slice(&sig).unwrap(),
+            &bitcoin::secp256k1::Message::from_digest(sighash),
+            &output_key.to_x_only_public_key(),
+        )
+        .expect("combined signature must verify");
+    }
+}
@@ -0,0 +1,544 @@
+//! Pasted Payload Validation
+//!
+//! Every round consumes a batch of JSON objects pasted (or scanned) from the
+//! other parties. Before any of it is used, a batch is checked as a whole:
+//!
+//! - each object parses as the payload type this step expects
+//! - a payload from another protocol (e.g. a keygen round pasted where
+//!   signing nonces belong) is named as such
+//! - every object names the same session and signs the same sighash/message
+//! - no party appears twice
+//!
+//! Errors point at the offending object (`#n`, 1-based, in paste order) and
+//! its party, so they can be shown as-is in the CLI and the TUI.
+//!
+//! The hex fields inside a payload (nonces, shares, keygen inputs) and its
+//! party index are decoded with [`decode_hex_field`] and [`share_index`],
+//! which return errors rather than panicking on hostile input.
+//!
+//! Payloads carried on a USB stick or shared drive are read with
+//! [`read_payload_files`] (a file, or a folder of `.json` files) and written
+//! with [`export_payload`].
+//!
+//! ## Versions
+//!
+//! Payloads carry a signed `protocol_version`. Payloads without one come from
+//! before versioning and count as version 1, which is still accepted; newer
+//! versions than this build knows are refused with a hint to upgrade. A
+//! party replies at the lowest version among the payloads it consumed
+//! ([`reply_version`]), and `--protocol-version 1` makes first-round payloads
+//! readable by parties that have not upgraded yet, so a ceremony keeps going
+//! while its members upgrade one at a time.
+
+use crate::error::Error;
+use crate::protocol::identity::Authenticated;
+use crate::protocol::keygen::parse_space_separated_json;
+use anyhow::{bail, Context, Result};
+use schnorr_fun::frost::ShareIndex;
+use secp256kfun::prelude::*;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Fields a payload may name its sender with
+const PARTY_FIELDS: &[&str] = &["party_index", "old_party_index", "helper_index"];
+
+/// Payload protocol version this build writes
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest payload protocol version still accepted
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+static OUTPUT_VERSION: AtomicU32 = AtomicU32::new(PROTOCOL_VERSION);
+
+/// Version of payloads from before versioning (serde default)
+pub fn legacy_version() -> u32 {
+    MIN_PROTOCOL_VERSION
+}
+
+/// Version 1 payloads omit the field, so older builds verify their signature
+pub fn is_legacy_version(version: &u32) -> bool {
+    *version == MIN_PROTOCOL_VERSION
+}
+
+/// Write payloads at `version` for the rest of this run (`--protocol-version`)
+pub fn set_output_version(version: u32) -> Result<()> {
+    if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
+        bail!(Error::User(format!(
+            "--protocol-version must be between {} and {}",
+            MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+        )));
+    }
+    OUTPUT_VERSION.store(version, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Version for payloads that answer nothing (first rounds, nonces)
+pub fn output_version() -> u32 {
+    OUTPUT_VERSION.load(Ordering::Relaxed)
+}
+
+/// Version to answer a batch with: the lowest any sender used
+pub fn reply_version<T: Authenticated>(inputs: &[T]) -> u32 {
+    inputs
+        .iter()
+        .map(Authenticated::protocol_version)
+        .fold(output_version(), u32::min)
+}
+
+/// Note shown when a reply goes out older than this build writes
+pub fn downgrade_note(version: u32) -> Option<String> {
+    (version < PROTOCOL_VERSION).then(|| {
+        format!(
+            "⚠️  Replying with payload protocol v{} because a party has not upgraded yet\n",
+            version
+        )
+    })
+}
+
+/// Check a payload's `protocol_version` (absent means version 1)
+fn check_version(value: &Value, who: &str) -> Result<u64> {
+    let version = match value.get("protocol_version") {
+        None => return Ok(MIN_PROTOCOL_VERSION as u64),
+        Some(version) => version
+            .as_u64()
+            .with_context(|| format!("{} has a non-numeric protocol_version", who))?,
+    };
+    if version > PROTOCOL_VERSION as u64 {
+        bail!(Error::Protocol(format!(
+            "{} uses payload protocol v{}, but this frostdao only knows up to v{}. \
+             Upgrade frostdao, or ask that party to re-run with --protocol-version {}",
+            who, version, PROTOCOL_VERSION, PROTOCOL_VERSION
+        )));
+    }
+    if version < MIN_PROTOCOL_VERSION as u64 {
+        bail!(Error::Protocol(format!(
+            "{} uses payload protocol v{}, which is no longer supported (oldest is v{})",
+            who, version, MIN_PROTOCOL_VERSION
+        )));
+    }
+    Ok(version)
+}
+
+/// What one step accepts
+pub struct Expected<'a> {
+    /// Name of the batch for messages, e.g. "signing nonces"
+    pub what: &'a str,
+    /// Accepted `type` values
+    pub types: &'a [&'a str],
+    /// Session every payload must belong to, when the step knows it
+    pub session: Option<&'a str>,
+    /// Sighash (or message) every payload must sign, when the step knows it
+    pub sighash: Option<&'a str>,
+}
+
+impl<'a> Expected<'a> {
+    pub fn new(what: &'a str, types: &'a [&'a str]) -> Self {
+        Self {
+            what,
+            types,
+            session: None,
+            sighash: None,
+        }
+    }
+}
+
+/// Protocol a payload type belongs to
+fn protocol_of(payload_type: &str) -> &'static str {
+    match payload_type {
+        "keygen_round1" | "keygen_round2" => "key generation",
+        "reshare_round1" => "resharing",
+        "recovery_round1" => "share recovery",
+        "signing_nonce" | "signing_share" | "dkg_nonce" | "dkg_signature_share" => "signing",
+        "dkg_build_tx" => "a transaction session",
+        _ => "an unknown protocol",
+    }
+}
+
+fn party_of(value: &Value) -> Option<u64> {
+    PARTY_FIELDS
+        .iter()
+        .find_map(|field| value.get(*field).and_then(Value::as_u64))
+}
+
+fn field<'v>(value: &'v Value, names: &[&str]) -> Option<&'v str> {
+    names
+        .iter()
+        .find_map(|name| value.get(*name).and_then(Value::as_str))
+}
+
+/// Parse and validate a whitespace-separated batch of payloads
+pub fn parse_payloads<T: DeserializeOwned>(data: &str, expected: &Expected) -> Result<Vec<T>> {
+    let values: Vec<Value> = parse_space_separated_json(data)
+        .map_err(|e| anyhow::anyhow!("Could not read the {}: {}", expected.what, e))?;
+    parse_payload_values(values, expected)
+}
+
+/// Validate already-split payloads (e.g. unwrapped from Nostr DMs) and parse them
+pub fn parse_payload_values<T: DeserializeOwned>(
+    values: Vec<Value>,
+    expected: &Expected,
+) -> Result<Vec<T>> {
+    if values.is_empty() {
+        bail!("No {} provided", expected.what);
+    }
+
+    let mut seen: BTreeMap<u64, usize> = BTreeMap::new();
+    let mut first_session: Option<(usize, &str)> = None;
+    let mut first_sighash: Option<(usize, &str)> = None;
+    for (i, value) in values.iter().enumerate() {
+        let n = i + 1;
+        let party = party_of(value);
+        let who = match party {
+            Some(party) => format!("Object #{} (party {})", n, party),
+            None => format!("Object #{}", n),
+        };
+
+        if !value.is_object() {
+            bail!("{} is not a JSON object", who);
+        }
+        let Some(payload_type) = value.get("type").and_then(Value::as_str) else {
+            bail!("{} has no \"type\" field; expected {}", who, expected.what);
+        };
+        if !expected.types.contains(&payload_type) {
+            let expected_protocol = protocol_of(expected.types[0]);
+            let protocol = protocol_of(payload_type);
+            if protocol != expected_protocol {
+                bail!(
+                    "{} is a {} payload from {}, but {} ({}) are expected here",
+                    who,
+                    payload_type,
+                    protocol,
+                    expected.what,
+                    expected_protocol
+                );
+            }
+            bail!(
+                "{} is a {} payload, but {} are expected here",
+                who,
+                payload_type,
+                expected.what
+            );
+        }
+
+        check_version(value, &who)?;
+
+        let Some(party) = party else {
+            bail!("{} names no party index", who);
+        };
+        if let Some(first) = seen.insert(party, n) {
+            bail!(
+                "Party {} appears twice in the {} (objects #{} and #{})",
+                party,
+                expected.what,
+                first,
+                n
+            );
+        }
+
+        if let Some(session) = field(value, &["session", "session_id"]) {
+            match (expected.session, first_session) {
+                (Some(want), _) if session != want => bail!(
+                    "{} is for session {}, but this is session {}",
+                    who,
+                    session,
+                    want
+                ),
+                (None, Some((m, other))) if session != other => bail!(
+                    "{} is for session {}, but object #{} is for session {}",
+                    who,
+                    session,
+                    m,
+                    other
+                ),
+                (_, None) => first_session = Some((n, session)),
+                _ => {}
+            }
+        }
+        if let Some(sighash) = field(value, &["sighash", "message"]) {
+            match (expected.sighash, first_sighash) {
+                (Some(want), _) if !sighash.eq_ignore_ascii_case(want) => {
+                    bail!("{} signs {}, but this session signs {}", who, sighash, want)
+                }
+                (None, Some((m, other))) if sighash != other => bail!(
+                    "{} signs {}, but object #{} signs {}",
+                    who,
+                    sighash,
+                    m,
+                    other
+                ),
+                (_, None) => first_sighash = Some((n, sighash)),
+                _ => {}
+            }
+        }
+    }
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let payload_type = value["type"].as_str().unwrap_or_default().to_string();
+            let party = party_of(&value).unwrap_or_default();
+            serde_json::from_value(value).map_err(|e| {
+                anyhow::anyhow!(
+                    "Object #{} (party {}) is not a valid {} payload: {}",
+                    i + 1,
+                    party,
+                    payload_type,
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+/// Check the parties behind a set of signature shares against the session
+///
+/// Run right before the shares are summed: every share must come from a party
+/// that committed a nonce in this session, each party exactly once, and no
+/// committed party may be missing (the aggregate nonce includes theirs).
+pub fn check_signer_set(share_parties: &[u32], nonce_parties: &[u32]) -> Result<()> {
+    let committed: BTreeSet<u32> = nonce_parties.iter().copied().collect();
+    let mut seen = BTreeSet::new();
+    for &party in share_parties {
+        if !seen.insert(party) {
+            bail!("Party {} sent more than one signature share", party);
+        }
+        if !committed.contains(&party) {
+            bail!(
+                "Party {} sent a signature share but committed no nonce in this session",
+                party
+            );
+        }
+    }
+    let missing: Vec<String> = committed
+        .difference(&seen)
+        .map(|party| party.to_string())
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "Missing signature share from party {} (committed a nonce in this session)",
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Decode a hex-encoded, bincode-serialized field of a pasted payload
+pub fn decode_hex_field<T: DeserializeOwned>(what: &str, hex_str: &str) -> Result<T> {
+    let bytes = hex::decode(hex_str).with_context(|| format!("{} is not valid hex", what))?;
+    bincode::deserialize(&bytes).with_context(|| format!("{} is malformed", what))
+}
+
+/// FROST share index for a pasted party index (parties are numbered from 1)
+pub fn share_index(party_index: u32) -> Result<ShareIndex> {
+    Scalar::<Secret, Zero>::from(party_index)
+        .public()
+        .non_zero()
+        .context("Party index cannot be zero")
+}
+
+/// Read payloads exchanged as files
+///
+/// A file is read as-is. A folder contributes every `.json` file in it, in
+/// name order, joined by spaces like a multi-party paste.
+pub fn read_payload_files(path: &Path) -> Result<String> {
+    if !path.is_dir() {
+        return std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()));
+    }
+
+    let mut files = Vec::new();
+    for entry in
+        std::fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))?
+    {
+        let file = entry?.path();
+        if file.is_file() && file.extension().is_some_and(|ext| ext == "json") {
+            files.push(file);
+        }
+    }
+    if files.is_empty() {
+        bail!("No .json files in {}", path.display());
+    }
+    files.sort();
+
+    let mut payloads = Vec::new();
+    for file in files {
+        let text = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        payloads.push(text.trim().to_string());
+    }
+    Ok(payloads.join(" "))
+}
+
+/// `frostdao-<step>-<unix time>.json`, the name outputs are exported under
+pub fn export_file_name(step: &str, now: u64) -> String {
+    format!("frostdao-{}-{}.json", step, now)
+}
+
+/// Save an output payload for exchange
+///
+/// `target` is a file path, or a folder to put a timestamped file in. The
+/// file is created 0600 since some outputs carry shares for other parties;
+/// an existing file is never overwritten.
+pub fn export_payload(target: &Path, step: &str, payload: &str, now: u64) -> Result<PathBuf> {
+    let path = if target.is_dir() {
+        target.join(export_file_name(step, now))
+    } else {
+        target.to_path_buf()
+    };
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    std::io::Write::write_all(&mut file, format!("{}\n", payload.trim()).as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::signing::NonceOutput;
+
+    fn nonce(party: u32, session: &str) -> String {
+        serde_json::json!({
+            "party_index": party, "rank": 0, "session": session,
+            "nonce": "00", "type": "signing_nonce"
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_payload_batch_errors() {
+        let expected = Expected {
+            session: Some("s1"),
+            ..Expected::new("signing nonces", &["signing_nonce"])
+        };
+        let parse =
+            |data: &str| parse_payloads::<NonceOutput>(data, &expected).map_err(|e| e.to_string());
+
+        let ok = parse(&format!("{} {}", nonce(1, "s1"), nonce(2, "s1"))).unwrap();
+        assert_eq!(ok.len(), 2);
+
+        assert_eq!(
+            parse(&format!("{} {}", nonce(1, "s1"), nonce(1, "s1"))).unwrap_err(),
+            "Party 1 appears twice in the signing nonces (objects #1 and #2)"
+        );
+        assert_eq!(
+            parse(&format!("{} {}", nonce(1, "s1"), nonce(2, "s2"))).unwrap_err(),
+            "Object #2 (party 2) is for session s2, but this is session s1"
+        );
+        let round1 = r#"{"party_index":3,"keygen_input":"00","type":"keygen_round1"}"#;
+        assert_eq!(
+            parse(round1).unwrap_err(),
+            "Object #1 (party 3) is a keygen_round1 payload from key generation, \
+             but signing nonces (signing) are expected here"
+        );
+        let truncated = r#"{"party_index":2,"session":"s1","type":"signing_nonce"}"#;
+        assert!(parse(truncated).unwrap_err().starts_with(
+            "Object #1 (party 2) is not a valid signing_nonce payload: missing field"
+        ));
+        assert_eq!(parse("  ").unwrap_err(), "No signing nonces provided");
+    }
+
+    #[test]
+    fn test_payload_versions() {
+        let expected = Expected::new("signing nonces", &["signing_nonce"]);
+        let versioned = |party: u32, version: u64| {
+            let mut value: Value = serde_json::from_str(&nonce(party, "s1")).unwrap();
+            value["protocol_version"] = version.into();
+            value.to_string()
+        };
+
+        // An upgraded party answering a not-yet-upgraded one falls back to v1
+        let data = format!("{} {}", nonce(1, "s1"), versioned(2, 2));
+        let mixed = parse_payloads::<NonceOutput>(&data, &expected).unwrap();
+        assert_eq!(mixed[0].protocol_version, 1);
+        assert_eq!(mixed[1].protocol_version, 2);
+        assert_eq!(reply_version(&mixed), 1);
+        assert_eq!(reply_version(&mixed[1..]), PROTOCOL_VERSION);
+        assert!(downgrade_note(1).is_some());
+
+        // v1 payloads serialize without the field, as older builds signed them
+        let v1 = serde_json::to_value(&mixed[0]).unwrap();
+        assert!(v1.get("protocol_version").is_none());
+        let v2 = serde_json::to_value(&mixed[1]).unwrap();
+        assert_eq!(v2["protocol_version"], 2);
+
+        let err = parse_payloads::<NonceOutput>(&versioned(3, 9), &expected).unwrap_err();
+        assert_eq!(
+            crate::error::kind_of(&err),
+            crate::error::ErrorKind::Protocol
+        );
+        assert!(err
+            .to_string()
+            .starts_with("Object #1 (party 3) uses payload protocol v9"));
+        assert!(parse_payloads::<NonceOutput>(&versioned(3, 0), &expected).is_err());
+        assert!(set_output_version(PROTOCOL_VERSION + 1).is_err());
+    }
+
+    #[test]
+    fn test_signer_set_must_match_nonces() {
+        assert!(check_signer_set(&[2, 1], &[1, 2]).is_ok());
+        let err = |shares: &[u32]| check_signer_set(shares, &[1, 2]).unwrap_err().to_string();
+        assert_eq!(err(&[1, 1]), "Party 1 sent more than one signature share");
+        assert_eq!(
+            err(&[1, 3]),
+            "Party 3 sent a signature share but committed no nonce in this session"
+        );
+        assert_eq!(
+            err(&[1]),
+            "Missing signature share from party 2 (committed a nonce in this session)"
+        );
+    }
+
+    #[test]
+    fn test_payload_files_round_trip() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir =
+            std::env::temp_dir().join(format!("frostdao_payload_{}_{}", std::process::id(), nanos));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Each party drops its nonce on the shared drive
+        let first = export_payload(&dir, "nonce", &nonce(2, "s1"), 100).unwrap();
+        assert_eq!(first, dir.join("frostdao-nonce-100.json"));
+        let second = export_payload(&dir.join("b.json"), "nonce", &nonce(1, "s1"), 100).unwrap();
+        assert!(
+            export_payload(&dir, "nonce", "{}", 100).is_err(),
+            "overwrote"
+        );
+        std::fs::write(dir.join("notes.txt"), "not a payload").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // The folder reads back as one paste, in file name order
+        let batch = read_payload_files(&dir).unwrap();
+        assert_eq!(batch, format!("{} {}", nonce(1, "s1"), nonce(2, "s1")));
+        let expected = Expected::new("signing nonces", &["signing_nonce"]);
+        assert_eq!(
+            parse_payloads::<NonceOutput>(&batch, &expected)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(read_payload_files(&second).unwrap().trim(), nonce(1, "s1"));
+
+        let empty = dir.join("empty");
+        std::fs::create_dir(&empty).unwrap();
+        assert!(read_payload_files(&empty).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,392 @@
+//! Threshold Reconstruction
+//!
+//! The inverse of `dkg-split-key`: threshold shares are combined with
+//! Lagrange interpolation at 0 into the group's single secret key, for
+//! migrating away from threshold custody or an emergency recovery. From then
+//! on whoever holds that key can spend alone, so:
+//!
+//! - the CLI asks twice, the second time for part of the group key
+//! - the key is never written in the clear: it leaves as a NIP-17 gift wrap
+//!   (see `protocol::nostr`) to a recipient's Nostr key, opened with
+//!   `dkg-reconstruct-open` or any Nostr client
+//! - every share folder used records it in its audit log
+//!
+//! Ranked (HTSS) wallets aren't supported; see `share_set`.
+
+use crate::crypto::helpers::lagrange_coefficients_at_zero;
+use crate::crypto::secret::{to_json_secret, SecretString};
+use crate::protocol::audit;
+use crate::protocol::identity::IdentityKey;
+use crate::protocol::keygen::{get_state_dir, require_secp256k1, HtssMetadata};
+use crate::protocol::nostr::{self, Event};
+use crate::protocol::weighted::{self, ShareSlot};
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use schnorr_fun::frost::PairedSecretShare;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use zeroize::Zeroize;
+
+const KEY_TYPE: &str = "frostdao_reconstructed_key";
+
+/// Shares gathered from one or more party folders
+pub struct ShareSet {
+    pub group_public_key: Point<EvenY>,
+    pub threshold: u32,
+    shares: Vec<(u32, Scalar<Secret, Zero>)>,
+}
+
+impl ShareSet {
+    /// Share indices, in the order the folders were given
+    pub fn indices(&self) -> Vec<u32> {
+        self.shares.iter().map(|(index, _)| *index).collect()
+    }
+
+    pub fn group_key_hex(&self) -> String {
+        hex::encode(self.group_public_key.to_xonly_bytes())
+    }
+}
+
+/// Message inside the gift wrap
+#[derive(Serialize, Deserialize)]
+struct ReconstructedKey {
+    #[serde(rename = "type")]
+    event_type: String,
+    group_public_key: String,
+    secret_key: String,
+}
+
+impl Drop for ReconstructedKey {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
+/// Read every share in `folders`, including a weighted party's extra shares
+///
+/// Ranked (HTSS) wallets are refused: their signing weights are Birkhoff
+/// coefficients, which plain Lagrange interpolation doesn't reproduce.
+pub fn share_set(folders: &[&dyn Storage]) -> Result<ShareSet> {
+    if folders.is_empty() {
+        bail!(crate::error::Error::User(
+            "No share folders given; pass --shares with threshold many".to_string()
+        ));
+    }
+    let mut group_public_key = None;
+    let mut threshold = 0;
+    let mut shares = Vec::new();
+    for folder in folders {
+        require_secp256k1(*folder)?;
+        let htss: HtssMetadata = serde_json::from_slice(
+            &folder
+                .read("htss_metadata.json")
+                .context("Share folder has no htss_metadata.json")?,
+        )?;
+        if htss.hierarchical && htss.party_ranks.values().any(|rank| *rank > 0) {
+            bail!(crate::error::Error::User(
+                "This is a ranked (HTSS) wallet; reconstruction only supports wallets whose \
+                 parties all have rank 0"
+                    .to_string()
+            ));
+        }
+        threshold = threshold.max(htss.threshold);
+
+        let extra_slots = weighted::slot_indices(*folder)?;
+        let slots: Vec<(u32, ShareSlot)> = extra_slots
+            .iter()
+            .skip(1)
+            .map(|index| (*index, ShareSlot::new(*folder, *index)))
+            .collect();
+        let storages = std::iter::once((htss.my_index, *folder)).chain(
+            slots
+                .iter()
+                .map(|(index, slot)| (*index, slot as &dyn Storage)),
+        );
+        for (index, storage) in storages {
+            let paired: PairedSecretShare<EvenY> = bincode::deserialize(
+                &storage
+                    .read_secret("paired_secret_share.bin")
+                    .context("Share folder has no paired_secret_share.bin")?,
+            )?;
+            let public_key = paired.public_key();
+            if *group_public_key.get_or_insert(public_key) != public_key {
+                bail!("Shares belong to different group keys");
+            }
+            if shares.iter().any(|(i, _)| *i == index) {
+                bail!("Share {} was given twice", index);
+            }
+            let share = paired.secret_share();
+            shares.push((index, share.share));
+        }
+    }
+    Ok(ShareSet {
+        group_public_key: group_public_key.context("No shares given")?,
+        threshold,
+        shares,
+    })
+}
+
+/// Interpolate the group secret and check it against the group key
+fn combine(set: &ShareSet) -> Result<Scalar<Secret, NonZero>> {
+    if (set.shares.len() as u32) < set.threshold {
+        bail!(
+            "Need {} shares to reconstruct, got {}",
+            set.threshold,
+            set.shares.len()
+        );
+    }
+    let lambdas = lagrange_coefficients_at_zero(&set.indices())?;
+    let secret = set
+        .shares
+        .iter()
+        .zip(lambdas)
+        .fold(
+            Scalar::<Secret, Zero>::zero(),
+            |acc, ((_, share), lambda)| s!(acc + lambda * share),
+        )
+        .non_zero()
+        .context("Reconstructed secret is zero")?;
+    if g!(secret * G).normalize() != set.group_public_key {
+        bail!("Shares don't reconstruct the group key (wrong or damaged shares?)");
+    }
+    Ok(secret)
+}
+
+/// Core function for `dkg-reconstruct`
+///
+/// Returns the gift wrap (kind 1059 JSON) holding the secret key for
+/// `recipient` (npub or hex). The seal is signed by the first folder's
+/// identity key, so the recipient sees which party sent it.
+pub fn reconstruct_core(folders: &[&dyn Storage], recipient: &str) -> Result<CommandResult> {
+    let recipient_hex = nostr::parse_public_key(recipient)?;
+    let set = share_set(folders)?;
+    let sender = IdentityKey::load(folders[0])?
+        .context("The first share folder has no identity key to sign with")?;
+    let secret = combine(&set)?;
+    let group_key_hex = set.group_key_hex();
+
+    let message = to_json_secret(&ReconstructedKey {
+        event_type: KEY_TYPE.to_string(),
+        group_public_key: group_key_hex.clone(),
+        secret_key: hex::encode(secret.to_bytes()),
+    })?;
+    let wrap = nostr::gift_wrap(&sender, &recipient_hex, message.expose())?;
+
+    for folder in folders {
+        audit::append(
+            *folder,
+            "reconstruct",
+            serde_json::json!({
+                "group_public_key": group_key_hex,
+                "shares": set.indices(),
+                "recipient": recipient_hex,
+            }),
+        )?;
+    }
+
+    let mut out = String::new();
+    out.push_str("Threshold Reconstruction\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("  Group key: {}\n", group_key_hex));
+    out.push_str(&format!("  Shares:    {:?}\n", set.indices()));
+    out.push_str(&format!("  Recipient: {}\n", nostr::npub(&recipient_hex)?));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    out.push_str("✓ Shares reconstruct the group key\n");
+    out.push_str("🔒 Secret key gift-wrapped to the recipient (NIP-17)\n");
+    out.push_str("📝 Recorded in each share folder's audit log\n\n");
+    out.push_str("⚠️  Whoever opens this can spend without the other parties.\n");
+    out.push_str("   • Open it with dkg-reconstruct-open (or any NIP-17 Nostr client)\n");
+    out.push_str("   • Move the funds, then retire the threshold wallet\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&wrap)?,
+    })
+}
+
+/// Core function for `dkg-reconstruct-open`
+///
+/// Opens a gift wrap from `dkg-reconstruct` with the identity key in
+/// `storage` and checks the key inside against the group key it names.
+pub fn open_core(wrap_json: &str, storage: &dyn Storage) -> Result<CommandResult> {
+    let me = IdentityKey::load(storage)?.context("No identity key here to open it with")?;
+    let wrap: Event = serde_json::from_str(wrap_json.trim()).context("Not a gift wrap")?;
+    let (sender, message) = nostr::unwrap_gift(&me, &wrap)?;
+    let message = SecretString::new(message);
+    let key: ReconstructedKey =
+        serde_json::from_str(message.expose()).context("Gift wrap holds no reconstructed key")?;
+    if key.event_type != KEY_TYPE {
+        bail!("Gift wrap holds no reconstructed key");
+    }
+    let secret: Scalar<Secret, NonZero> = Scalar::from_slice(&hex::decode(&key.secret_key)?)
+        .context("Reconstructed key is malformed")?
+        .non_zero()
+        .context("Reconstructed key is zero")?;
+    if hex::encode(g!(secret * G).normalize().to_xonly_bytes()) != key.group_public_key {
+        bail!("Reconstructed key doesn't match the group key it claims");
+    }
+
+    let mut out = String::new();
+    out.push_str("Reconstructed Key\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("  Group key: {}\n", key.group_public_key));
+    out.push_str(&format!("  Sent by:   {}\n", nostr::npub(&sender)?));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    out.push_str("⚠️  Secret key (spends alone; don't save it unencrypted):\n");
+    out.push_str(&format!("{}\n", key.secret_key));
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&serde_json::json!({
+            "group_public_key": key.group_public_key,
+            "sender": sender,
+        }))?,
+    })
+}
+
+/// Ask for `expected` to be typed back; false aborts
+fn confirm(prompt: &str, expected: &str) -> Result<bool> {
+    print!("   {}: ", prompt);
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim() == expected)
+}
+
+/// CLI wrapper for `dkg-reconstruct`
+pub fn reconstruct(share_dirs: &[String], recipient: &str, out_file: &str) -> Result<()> {
+    if Path::new(out_file).exists() {
+        bail!("{} already exists", out_file);
+    }
+    let storages = share_dirs
+        .iter()
+        .map(|dir| {
+            if !Path::new(dir).is_dir() {
+                bail!("Share folder {} not found", dir);
+            }
+            FileStorage::new(dir)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let folders: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+
+    let set = share_set(&folders)?;
+    let group_key_hex = set.group_key_hex();
+    println!(
+        "⚠️  Reconstructing the full secret key of {}",
+        group_key_hex
+    );
+    println!("   Whoever holds it can spend alone: the threshold no longer protects");
+    println!("   these funds. Only do this to migrate away or in an emergency.\n");
+    if !confirm("Type RECONSTRUCT to continue", "RECONSTRUCT")? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    if !confirm(
+        "Type the last 8 characters of the group key to confirm",
+        &group_key_hex[56..],
+    )? {
+        println!("Aborted: group key doesn't match.");
+        return Ok(());
+    }
+
+    let cmd_result = reconstruct_core(&folders, recipient)?;
+    std::fs::write(out_file, &cmd_result.result)?;
+    println!("\n{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("💾 Gift wrap saved to {}", out_file);
+    Ok(())
+}
+
+/// CLI wrapper for `dkg-reconstruct-open`
+pub fn open(name: &str, file: &str) -> Result<()> {
+    let wrap_json =
+        std::fs::read_to_string(file).with_context(|| format!("Can't read {}", file))?;
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = open_core(&wrap_json, &storage)?;
+    println!("{}", cmd_result.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::dealer;
+    use crate::storage::MemoryStorage;
+
+    const SECRET: &str = "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c7";
+
+    fn split(threshold: u32) -> Vec<MemoryStorage> {
+        split_ranked(threshold, None)
+    }
+
+    fn split_ranked(threshold: u32, ranks: Option<Vec<u32>>) -> Vec<MemoryStorage> {
+        let storages: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+        let parties: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+        dealer::split_key_core(
+            "w",
+            SECRET,
+            threshold,
+            ranks,
+            &MemoryStorage::new(),
+            &parties,
+        )
+        .unwrap();
+        storages
+    }
+
+    #[test]
+    fn test_reconstruct_round_trip() {
+        let storages = split(2);
+        let recipient = MemoryStorage::new();
+        let me = IdentityKey::load_or_create(&recipient).unwrap();
+
+        let folders: Vec<&dyn Storage> = vec![&storages[2], &storages[0]];
+        let wrap = reconstruct_core(&folders, &nostr::npub(&me.public_hex()).unwrap()).unwrap();
+        assert!(!wrap.result.contains(&SECRET[..16]));
+        assert_eq!(audit::load(&storages[0]).unwrap()[1].event, "reconstruct");
+        assert_eq!(audit::load(&storages[1]).unwrap().len(), 1);
+
+        let opened = open_core(&wrap.result, &recipient).unwrap();
+        // The dealer negates the key if needed for even Y; it spends the same
+        let secret = Scalar::<Secret, NonZero>::from_slice(&hex::decode(SECRET).unwrap()).unwrap();
+        let expected_secret = if g!(secret * G).normalize().is_y_even() {
+            SECRET.to_string()
+        } else {
+            hex::encode((-secret).to_bytes())
+        };
+        assert!(opened.output.contains(&expected_secret));
+
+        // Only the recipient can open it
+        assert!(open_core(&wrap.result, &storages[1]).is_err());
+    }
+
+    #[test]
+    fn test_reconstruct_needs_threshold_distinct_shares() {
+        let storages = split(3);
+        let me = IdentityKey::generate().public_hex();
+        let two: Vec<&dyn Storage> = vec![&storages[0], &storages[1]];
+        assert!(reconstruct_core(&two, &me).is_err());
+        let twice: Vec<&dyn Storage> = vec![&storages[0], &storages[1], &storages[0]];
+        assert!(reconstruct_core(&twice, &me).is_err());
+        assert_eq!(audit::load(&storages[0]).unwrap().len(), 1);
+
+        // No folders at all is a usage error, not a panic
+        let err = reconstruct_core(&[], &me).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::error::Error>(),
+            Some(crate::error::Error::User(_))
+        ));
+    }
+
+    #[test]
+    fn test_reconstruct_refuses_ranked_wallets() {
+        let storages = split_ranked(2, Some(vec![0, 1, 1]));
+        let me = IdentityKey::generate().public_hex();
+        let folders: Vec<&dyn Storage> = vec![&storages[0], &storages[1]];
+        let err = reconstruct_core(&folders, &me).unwrap_err();
+        assert!(err.to_string().contains("ranked"), "{}", err);
+        assert_eq!(audit::load(&storages[0]).unwrap().len(), 1);
+    }
+}
@@ -25,19 +25,45 @@
 //! Key insight: When all ranks are 0, Birkhoff reduces to Lagrange!
 //!
 //! Result: The lost party gets their original share s_j back!
+//!
+//! ## Over Nostr
+//!
+//! Instead of chasing each helper for pasted JSON, the lost party can publish
+//! a signed recovery request (`recover-request`). Helpers check it against
+//! their roster and, once approved, gift-wrap their sub-share back to the
+//! requester (`recover-respond`); `recover-finalize` accepts the wraps as is.
+//!
+//! With `--nostr` (or `--relay`) each step talks to the relays itself: the
+//! request is published, `recover-respond` subscribes to [`request_filter`]
+//! for this group and answers each request the holder approves, and
+//! `recover-finalize` collects the sub-shares from the lost party's inbox.
+//! Without it the events are printed, to be moved with any Nostr client.
 
 use crate::crypto::birkhoff::{
     birkhoff_coefficient_to_scalar, compute_birkhoff_recovery_coefficients, BirkhoffParameter,
 };
-use crate::protocol::keygen::{get_state_dir, GroupInfo, HtssMetadata};
-use crate::storage::{FileStorage, Storage};
+use crate::crypto::secret::{serialize_secret, SecretBytes};
+use crate::protocol::audit;
+use crate::protocol::directory;
+use crate::protocol::health;
+use crate::protocol::identity::{self, Authenticated, IdentityKey, Roster};
+use crate::protocol::keygen::{
+    get_state_dir, parse_space_separated_json, require_secp256k1, GroupInfo, HtssMetadata,
+};
+use crate::protocol::nostr::{self, Event};
+use crate::protocol::payload::{self, parse_payload_values, Expected};
+use crate::protocol::relay::{Filter, Relays};
+use crate::storage::journal::Journaled;
+use crate::storage::{lock, migrations, FileStorage, Storage};
 use crate::CommandResult;
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use schnorr_fun::frost::{PairedSecretShare, SharedKey};
 use schnorr_fun::fun::marker::*;
 use secp256kfun::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
 /// Output from recovery round 1 (helper party generates sub-share for lost party)
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -52,8 +78,32 @@ pub struct RecoveryRound1Output {
     pub lost_index: u32,
     /// Wallet name (for verification)
     pub wallet_name: String,
+    /// Payload protocol version (see protocol::payload); absent means v1
+    #[serde(
+        default = "crate::protocol::payload::legacy_version",
+        skip_serializing_if = "crate::protocol::payload::is_legacy_version"
+    )]
+    pub protocol_version: u32,
     #[serde(rename = "type")]
     pub event_type: String,
+    /// Sender's signature over this payload (see protocol::identity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+}
+
+impl Authenticated for RecoveryRound1Output {
+    fn signer_index(&self) -> u32 {
+        self.helper_index
+    }
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+    fn set_auth(&mut self, auth: String) {
+        self.auth = Some(auth);
+    }
 }
 
 /// Generate sub-share to help recover a lost party's share
@@ -65,7 +115,11 @@ pub fn recover_round1(source_wallet: &str, lost_index: u32) -> Result<()> {
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!("Wallet '{}' not found at {}.", source_wallet, state_dir);
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}.",
+            source_wallet, state_dir
+        ))
+        .into());
     }
 
     let storage = FileStorage::new(&state_dir)?;
@@ -83,7 +137,10 @@ pub fn recover_round1(source_wallet: &str, lost_index: u32) -> Result<()> {
     println!("{}", cmd_result.output);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Share this with the recovering party:");
-    println!("{}\n", cmd_result.result);
+    println!(
+        "{}\n",
+        crate::protocol::compact::present(&cmd_result.result)
+    );
     println!("⚠️  SECURITY WARNING: This protocol exposes your raw share value!");
     println!(
         "    After recovery, party {} will know {} shares (theirs + helpers').",
@@ -98,6 +155,10 @@ pub fn recover_round1(source_wallet: &str, lost_index: u32) -> Result<()> {
         "    The lost party needs {} helper outputs to recover.",
         htss.threshold
     );
+    print!(
+        "\n{}",
+        directory::whom_to_contact(&storage, &[result.lost_index], health::now())
+    );
 
     Ok(())
 }
@@ -142,8 +203,10 @@ pub fn recover_round1_core(
     out.push_str(&format!("Your index: {} (rank {})\n", my_index, my_rank));
     out.push_str(&format!("Lost party index: {}\n\n", lost_index));
 
+    require_secp256k1(storage)?;
+
     // Load secret share
-    let paired_share_bytes = storage.read("paired_secret_share.bin")?;
+    let paired_share_bytes = storage.read_secret("paired_secret_share.bin")?;
     let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
 
     // Get the share value
@@ -163,14 +226,17 @@ pub fn recover_round1_core(
     ));
 
     // Create output
-    let output = RecoveryRound1Output {
+    let mut output = RecoveryRound1Output {
         helper_index: my_index,
         helper_rank: my_rank,
         sub_share: share_hex,
         lost_index,
         wallet_name: source_wallet.to_string(),
+        protocol_version: payload::output_version(),
         event_type: "recovery_round1".to_string(),
+        auth: None,
     };
+    identity::sign_payload(&mut output, storage)?;
 
     let result_json = serde_json::to_string(&output)?;
 
@@ -181,22 +247,39 @@ pub fn recover_round1_core(
 }
 
 /// Finalize recovery - lost party combines sub-shares to reconstruct their share
+///
+/// With `relays` the helpers' gift-wrapped sub-shares are fetched from there
+/// (waiting up to `listen`) and added to `round1_data`.
+#[allow(clippy::too_many_arguments)]
 pub fn recover_finalize(
     source_wallet: &str,
     target_wallet: &str,
     my_index: u32,
     my_rank: u32,
     hierarchical: bool,
-    round1_data: &str,
+    round1_data: Option<&str>,
     force: bool,
+    relays: Option<&mut Relays>,
+    listen: Duration,
 ) -> Result<()> {
+    let mut round1_data = round1_data.unwrap_or_default().to_string();
+    if let Some(relays) = relays {
+        let source_storage = FileStorage::new(&get_state_dir(source_wallet))?;
+        let wraps = fetch_sub_shares(relays, &source_storage, my_index, Instant::now() + listen)?;
+        println!(
+            "📡 {} sub-share(s) fetched from {}",
+            wraps.len(),
+            relays.urls().join(", ")
+        );
+        round1_data = format!("{} {}", round1_data, nostr::events_data(&wraps)?);
+    }
     let cmd_result = recover_finalize_core(
         source_wallet,
         target_wallet,
         my_index,
         my_rank,
         hierarchical,
-        round1_data,
+        &round1_data,
         force,
     )?;
 
@@ -222,6 +305,139 @@ pub fn recover_finalize_core(
     round1_data: &str,
     force_overwrite: bool,
 ) -> Result<CommandResult> {
+    let source_storage = FileStorage::new(&get_state_dir(source_wallet))?;
+    let recovered = recover_share(source_wallet, my_index, round1_data, &source_storage)?;
+
+    // Create target wallet directory
+    let target_state_dir = get_state_dir(target_wallet);
+    let target_path = std::path::Path::new(&target_state_dir);
+
+    if !lock::is_unused(target_path) {
+        if !force_overwrite {
+            anyhow::bail!(
+                "Target wallet '{}' already exists. Use --force to overwrite.",
+                target_wallet
+            );
+        }
+        lock::clear_dir(target_path)?;
+    }
+
+    let target_storage = FileStorage::new(&target_state_dir)?;
+    write_recovered_wallet(
+        recovered,
+        target_wallet,
+        my_index,
+        &source_storage,
+        &target_storage,
+    )
+}
+
+/// Recovery finalize on injected storage (e.g. a browser store)
+///
+/// Rank and mode come from `source_storage`, as in `recover_finalize_core`.
+/// Without `force_overwrite`, refuses a target that already holds a key.
+pub fn recover_finalize_with_storage(
+    source_wallet: &str,
+    target_wallet: &str,
+    my_index: u32,
+    round1_data: &str,
+    force_overwrite: bool,
+    source_storage: &dyn Storage,
+    target_storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let recovered = recover_share(source_wallet, my_index, round1_data, source_storage)?;
+    if target_storage.exists("shared_key.bin") && !force_overwrite {
+        anyhow::bail!(
+            "Target wallet '{}' already exists. Use --force to overwrite.",
+            target_wallet
+        );
+    }
+    write_recovered_wallet(
+        recovered,
+        target_wallet,
+        my_index,
+        source_storage,
+        target_storage,
+    )
+}
+
+/// Helper outputs from `data`: pasted RecoveryRound1Output objects and
+/// NIP-17 gift wraps from `recover-respond`, in any mix
+///
+/// Gift wraps are opened with the wallet's Nostr key; ones addressed to
+/// another key or holding something else are skipped. Each must be sealed by the helper's pinned key.
+fn read_round1_outputs(
+    data: &str,
+    storage: &dyn Storage,
+    out: &mut String,
+) -> Result<Vec<RecoveryRound1Output>> {
+    let mut values: Vec<Value> = Vec::new();
+    let mut dm_count = 0;
+    let mut skipped = 0;
+    for value in parse_space_separated_json::<Value>(data)? {
+        if value.get("kind").and_then(Value::as_u64) != Some(nostr::KIND_GIFT_WRAP) {
+            values.push(value);
+            continue;
+        }
+        let identity_key = IdentityKey::load(storage)?.context(
+            "Gift-wrapped sub-shares need the Nostr key the recovery request was signed with",
+        )?;
+        let wrap: Event = serde_json::from_value(value)?;
+        if wrap.recipient() != Some(identity_key.public_hex().as_str()) {
+            skipped += 1;
+            continue;
+        }
+        let (sender, message) = nostr::unwrap_gift(&identity_key, &wrap)?;
+        let output: Value = serde_json::from_str(&message)?;
+        if output.get("type").and_then(Value::as_str) != Some("recovery_round1") {
+            skipped += 1; // e.g. a keygen DM still in the inbox
+            continue;
+        }
+        let helper = output["helper_index"].as_u64().unwrap_or_default() as u32;
+        let roster = Roster::load(storage)?.context("Identity roster missing")?;
+        if roster.0.get(&helper) != Some(&sender) {
+            anyhow::bail!(
+                "DM with party {}'s sub-share was sent by a different Nostr key",
+                helper
+            );
+        }
+        values.push(output);
+        dm_count += 1;
+    }
+    if dm_count > 0 {
+        out.push_str(&format!(
+            "📨 {} sub-share(s) arrived as NIP-17 DMs\n",
+            dm_count
+        ));
+    }
+    if skipped > 0 {
+        out.push_str(&format!(
+            "   ({} gift wrap(s) for other keys or steps skipped)\n",
+            skipped
+        ));
+    }
+    parse_payload_values(
+        values,
+        &Expected::new("recovery Round 1 outputs", &["recovery_round1"]),
+    )
+}
+
+/// The reconstructed share and the configuration it keeps, before anything is written
+struct RecoveredShare {
+    paired_bytes: SecretBytes,
+    shared_key_bytes: Vec<u8>,
+    pubkey_bytes: [u8; 32],
+    htss: HtssMetadata,
+    n_parties: u32,
+    out: String,
+}
+
+fn recover_share(
+    source_wallet: &str,
+    my_index: u32,
+    round1_data: &str,
+    source_storage: &dyn Storage,
+) -> Result<RecoveredShare> {
     let mut out = String::new();
 
     out.push_str("Share Recovery - Combine Sub-shares\n\n");
@@ -229,18 +445,15 @@ pub fn recover_finalize_core(
         "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
     );
 
-    // Parse round1 outputs
-    let round1_outputs: Vec<RecoveryRound1Output> =
-        crate::protocol::keygen::parse_space_separated_json(round1_data)?;
+    // Parse round1 outputs, pasted or gift-wrapped by recover-respond
+    let round1_outputs = read_round1_outputs(round1_data, source_storage, &mut out)?;
 
     if round1_outputs.is_empty() {
         anyhow::bail!("No recovery round1 data provided");
     }
 
     // Load source wallet metadata FIRST to get original configuration
-    let source_state_dir = get_state_dir(source_wallet);
-    let source_storage = FileStorage::new(&source_state_dir)?;
-
+    require_secp256k1(source_storage)?;
     let shared_key_bytes = source_storage.read("shared_key.bin")?;
     let shared_key: SharedKey<EvenY> = bincode::deserialize(&shared_key_bytes)?;
     let group_public_key = shared_key.public_key();
@@ -296,6 +509,7 @@ pub fn recover_finalize_core(
         "Received sub-shares from {} helper parties\n",
         round1_outputs.len()
     ));
+    identity::verify_payloads(&round1_outputs, source_storage, &mut out)?;
     out.push_str(&format!(
         "Recovering index: {} (original rank: {})\n\n",
         my_index, original_rank
@@ -303,10 +517,19 @@ pub fn recover_finalize_core(
 
     // Verify we have enough sub-shares
     if (round1_outputs.len() as u32) < threshold {
+        let others: Vec<u32> = source_htss
+            .party_ranks
+            .keys()
+            .copied()
+            .filter(|&index| {
+                index != my_index && round1_outputs.iter().all(|o| o.helper_index != index)
+            })
+            .collect();
         anyhow::bail!(
-            "Not enough sub-shares: got {}, need at least {}",
+            "Not enough sub-shares: got {}, need at least {}\n{}",
             round1_outputs.len(),
-            threshold
+            threshold,
+            directory::whom_to_contact(source_storage, &others, health::now())
         );
     }
 
@@ -410,22 +633,6 @@ pub fn recover_finalize_core(
 
     out.push_str("✓ Computed recovered share\n\n");
 
-    // Create target wallet directory
-    let target_state_dir = get_state_dir(target_wallet);
-    let target_path = std::path::Path::new(&target_state_dir);
-
-    if target_path.exists() {
-        if !force_overwrite {
-            anyhow::bail!(
-                "Target wallet '{}' already exists. Use --force to overwrite.",
-                target_wallet
-            );
-        }
-        std::fs::remove_dir_all(target_path)?;
-    }
-
-    let target_storage = FileStorage::new(&target_state_dir)?;
-
     // Create PairedSecretShare using helper function
     let share_scalar: Scalar<Secret, Zero> = Scalar::from_bytes(recovered_share_bytes)
         .ok_or_else(|| anyhow::anyhow!("Invalid recovered share bytes"))?;
@@ -436,10 +643,7 @@ pub fn recover_finalize_core(
         share_nonzero,
         &group_public_key,
     )?;
-    let paired_bytes = bincode::serialize(&paired_share)?;
-
-    target_storage.write("paired_secret_share.bin", &paired_bytes)?;
-    target_storage.write("shared_key.bin", &shared_key_bytes)?;
+    let paired_bytes = serialize_secret(&paired_share)?;
 
     // Create HTSS metadata preserving original configuration
     // Use source wallet's party_ranks (already includes this party's original rank)
@@ -451,15 +655,62 @@ pub fn recover_finalize_core(
         threshold,
         hierarchical, // Already set from source_htss.hierarchical
         party_ranks,
+        wallet_version: migrations::CURRENT_WALLET_VERSION,
+        signing_requirement: Default::default(),
+        created_at: source_htss.created_at,
+        refreshed_at: source_htss.refreshed_at,
     };
 
+    Ok(RecoveredShare {
+        paired_bytes,
+        shared_key_bytes,
+        pubkey_bytes: group_public_key.to_xonly_bytes(),
+        htss: new_htss,
+        n_parties,
+        out,
+    })
+}
+
+/// Write the recovered wallet: share, key, identity, HTSS metadata and group info
+fn write_recovered_wallet(
+    recovered: RecoveredShare,
+    target_wallet: &str,
+    my_index: u32,
+    source_storage: &dyn Storage,
+    target_storage: &dyn Storage,
+) -> Result<CommandResult> {
+    // Everything below lands in the target together or not at all
+    let journaled = Journaled::new(target_storage, "recover-finalize");
+    let target_storage: &dyn Storage = &journaled;
+
+    let RecoveredShare {
+        paired_bytes,
+        shared_key_bytes,
+        pubkey_bytes,
+        htss: new_htss,
+        n_parties,
+        mut out,
+    } = recovered;
+    let (threshold, hierarchical, original_rank) =
+        (new_htss.threshold, new_htss.hierarchical, new_htss.my_rank);
+
+    target_storage.write("paired_secret_share.bin", &paired_bytes)?;
+    target_storage.write("shared_key.bin", &shared_key_bytes)?;
+
+    // Same group, so the source pins stay valid; re-pin our own key
+    let source_roster = Roster::load(source_storage)?;
+    let pinned_before = source_roster
+        .as_ref()
+        .and_then(|r| r.0.get(&my_index).cloned());
+    let identity_hex =
+        identity::carry_identity(source_storage, target_storage, my_index, source_roster)?;
+
     target_storage.write(
         "htss_metadata.json",
         serde_json::to_string_pretty(&new_htss)?.as_bytes(),
     )?;
 
     // Create group info
-    let pubkey_bytes: [u8; 32] = group_public_key.to_xonly_bytes();
     let pubkey_hex = hex::encode(pubkey_bytes);
 
     use bitcoin::{Address, Network, XOnlyPublicKey};
@@ -477,6 +728,13 @@ pub fn recover_finalize_core(
         total_parties: n_parties,
         hierarchical,
         parties: vec![],
+        // Same parties, so the source's directory still applies
+        directory: source_storage
+            .read("group_info.json")
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<GroupInfo>(&bytes).ok())
+            .map(|info| info.directory)
+            .unwrap_or_default(),
     };
 
     target_storage.write(
@@ -484,12 +742,6 @@ pub fn recover_finalize_core(
         serde_json::to_string_pretty(&group_info)?.as_bytes(),
     )?;
 
-    // Save share in hex for verification
-    target_storage.write(
-        "share_hex.txt",
-        hex::encode(recovered_share_bytes).as_bytes(),
-    )?;
-
     out.push_str(
         "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n",
     );
@@ -507,18 +759,504 @@ pub fn recover_finalize_core(
     ));
     out.push_str(&format!("Public Key: {}\n", pubkey_hex));
     out.push_str(&format!("Testnet Address: {}\n\n", address_testnet));
+    if pinned_before.is_some_and(|key| key != identity_hex) {
+        out.push_str("🪪 Your identity key is new; the other parties must re-pin it:\n");
+        out.push_str(&format!(
+            "    frostdao dkg-identity --name <wallet> --trust {}={}\n\n",
+            my_index, identity_hex
+        ));
+    }
     out.push_str("⚠️  The public key and address are the SAME as the original wallet!\n");
     out.push_str("    Your recovered share is now compatible with the group.\n\n");
     out.push_str("🔐 SECURITY NOTE: This simplified recovery protocol exposed helper shares.\n");
     out.push_str("    You now know enough shares to reconstruct the group secret.\n");
     out.push_str("    A production system should use blinded sub-shares (like resharing).\n");
 
+    journaled.commit()?;
+
     Ok(CommandResult {
         output: out,
         result: target_wallet.to_string(),
     })
 }
 
+// ============================================================================
+// Recovery requests over Nostr
+// ============================================================================
+
+/// `t` tag on recovery requests, matched by [`request_filter`]
+pub const RECOVERY_REQUEST_TAG: &str = "frostdao-recovery";
+
+/// `d` tag prefix, followed by `<group key>/<lost index>`
+const RECOVERY_REQUEST_D_TAG: &str = "frostdao/recovery/";
+
+/// Helpers refuse requests older than this
+const RECOVERY_REQUEST_MAX_AGE_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// What a lost party asks for in a recovery request event
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecoveryRequest {
+    pub group_public_key: String,
+    pub lost_index: u32,
+    /// Wallet name the helpers' sub-shares must carry (the requester's)
+    pub wallet_name: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+/// A request that checked out, ready for the helper to approve
+pub struct CheckedRequest {
+    pub event: Event,
+    pub request: RecoveryRequest,
+    /// False when the requester was trusted through `--from` instead
+    pub pinned: bool,
+}
+
+/// Relay filter matching recovery requests (kind 30078, `t` tag)
+pub fn request_filter() -> Filter {
+    Filter::kinds(&[nostr::KIND_APP_DATA]).tag('t', &[RECOVERY_REQUEST_TAG])
+}
+
+fn group_key_hex(storage: &dyn Storage) -> Result<String> {
+    require_secp256k1(storage)?;
+    let shared_key: SharedKey<EvenY> = bincode::deserialize(&storage.read("shared_key.bin")?)?;
+    Ok(hex::encode(shared_key.public_key().to_xonly_bytes()))
+}
+
+/// Core function for `recover-request`: the lost party's signed request
+///
+/// A replaceable event (one per group key and lost index) signed by the
+/// wallet's Nostr key; helpers gift-wrap their sub-shares back to that key.
+pub fn request_core(
+    source_wallet: &str,
+    lost_index: u32,
+    storage: &dyn Storage,
+    now: u64,
+) -> Result<CommandResult> {
+    let htss: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    if !htss.party_ranks.contains_key(&lost_index) {
+        return Err(crate::error::Error::User(format!(
+            "Party {} is not in this wallet (parties: {:?})",
+            lost_index,
+            htss.party_ranks.keys().collect::<Vec<_>>()
+        ))
+        .into());
+    }
+    let requester = IdentityKey::load(storage)?
+        .context("Wallet has no Nostr identity; create one with nostr-keygen")?;
+    let request = RecoveryRequest {
+        group_public_key: group_key_hex(storage)?,
+        lost_index,
+        wallet_name: source_wallet.to_string(),
+        event_type: "recovery_request".to_string(),
+    };
+    let tags = vec![
+        vec![
+            "d".to_string(),
+            format!(
+                "{}{}/{}",
+                RECOVERY_REQUEST_D_TAG, request.group_public_key, lost_index
+            ),
+        ],
+        vec!["t".to_string(), RECOVERY_REQUEST_TAG.to_string()],
+    ];
+    let event = Event::new(
+        requester.public_hex(),
+        now,
+        nostr::KIND_APP_DATA,
+        tags,
+        serde_json::to_string(&request)?,
+    )
+    .signed(&requester);
+
+    let pinned = Roster::load(storage)?.and_then(|roster| roster.0.get(&lost_index).cloned());
+    let mut out = String::new();
+    out.push_str("Share Recovery - Request Sub-shares\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("  Group key:  {}\n", request.group_public_key));
+    out.push_str(&format!("  Lost party: {}\n", lost_index));
+    out.push_str(&format!("  Requester:  {}\n", nostr::npub(&event.pubkey)?));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    if pinned.as_deref() == Some(event.pubkey.as_str()) {
+        out.push_str("🪪 Signed with the identity pinned for this party at keygen.\n\n");
+    } else {
+        out.push_str("⚠️  This key is not the one pinned for this party.\n");
+        out.push_str("   Helpers must confirm your npub out of band and pass --from.\n\n");
+    }
+    out.push_str(&format!(
+        "🧠 Helpers answer with: frostdao recover-respond --name <wallet> --data '<event>'\n   \
+         Their sub-shares come back gift-wrapped to you; once {} have arrived:\n   \
+         frostdao recover-finalize --source {} --my-index {} --data '<gift wraps>'\n",
+        htss.threshold, source_wallet, lost_index
+    ));
+    out.push_str(&format!(
+        "   Helpers ignore requests older than {} days.\n",
+        RECOVERY_REQUEST_MAX_AGE_SECS / 86_400
+    ));
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&event)?,
+    })
+}
+
+/// Check a recovery request against this helper's wallet
+///
+/// The event must be signed, fresh, for this group and from the identity
+/// pinned for the lost party, or from `from` (npub or hex) when the lost
+/// party had to make a new key.
+pub fn check_request(
+    data: &str,
+    from: Option<&str>,
+    storage: &dyn Storage,
+    now: u64,
+) -> Result<CheckedRequest> {
+    let event: Event = serde_json::from_str(data.trim()).context("Not a Nostr event")?;
+    if event.kind != nostr::KIND_APP_DATA || !request_filter().matches(&event) {
+        bail!("Not a recovery request event");
+    }
+    event.verify()?;
+    let request: RecoveryRequest =
+        serde_json::from_str(&event.content).context("Event does not hold a recovery request")?;
+    if request.event_type != "recovery_request" {
+        bail!("Event does not hold a recovery request");
+    }
+    if event
+        .created_at
+        .saturating_add(RECOVERY_REQUEST_MAX_AGE_SECS)
+        < now
+    {
+        bail!(
+            "Recovery request is {}; ask the lost party for a fresh one",
+            health::describe_time(Some(event.created_at), now)
+        );
+    }
+    if request.group_public_key != group_key_hex(storage)? {
+        bail!("Recovery request is for a different group key");
+    }
+
+    let pinned = Roster::load(storage)?
+        .and_then(|roster| roster.0.get(&request.lost_index).cloned())
+        .is_some_and(|key| key == event.pubkey);
+    let trusted = match from {
+        Some(from) => nostr::parse_public_key(from)? == event.pubkey,
+        None => false,
+    };
+    if !pinned && !trusted {
+        return Err(crate::error::Error::User(format!(
+            "Request from {} is not signed by the identity pinned for party {}.\n\
+             If they replaced their key, confirm their npub out of band and pass --from <npub>.",
+            nostr::npub(&event.pubkey)?,
+            request.lost_index
+        ))
+        .into());
+    }
+
+    Ok(CheckedRequest {
+        event,
+        request,
+        pinned,
+    })
+}
+
+/// Core function for `recover-respond`: this helper's sub-share, gift-wrapped
+///
+/// Call only after the holder approved `checked`; the sub-share goes to the
+/// requester's key as a NIP-17 DM and the reply is recorded in the audit log.
+pub fn respond_core(checked: &CheckedRequest, storage: &dyn Storage) -> Result<CommandResult> {
+    let request = &checked.request;
+    let round1 = recover_round1_core(&request.wallet_name, request.lost_index, storage)?;
+    let me = IdentityKey::load(storage)?
+        .context("Wallet has no Nostr identity; create one with nostr-keygen")?;
+    let wrap = nostr::gift_wrap(&me, &checked.event.pubkey, &round1.result)?;
+    audit::append(
+        storage,
+        "recovery_response",
+        serde_json::json!({
+            "lost_index": request.lost_index,
+            "requester": checked.event.pubkey,
+            "request": checked.event.id,
+        }),
+    )?;
+
+    let mut out = round1.output;
+    out.push_str(&format!(
+        "\n🔒 Sub-share gift-wrapped to {} (NIP-17)\n",
+        nostr::npub(&checked.event.pubkey)?
+    ));
+    out.push_str("📝 Recorded in this wallet's audit log\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&wrap)?,
+    })
+}
+
+/// Gift-wrapped sub-shares for the lost party `my_index` on `relays`
+///
+/// Waits until `until` for as many as the threshold; only sub-shares for
+/// this party, sealed by the helper's pinned key, count.
+pub fn fetch_sub_shares(
+    relays: &mut Relays,
+    storage: &dyn Storage,
+    my_index: u32,
+    until: Instant,
+) -> Result<Vec<Event>> {
+    let htss: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    let roster = Roster::load(storage)?.context("Identity roster missing")?;
+    let me = IdentityKey::load(storage)?.context(
+        "Gift-wrapped sub-shares need the Nostr key the recovery request was signed with",
+    )?;
+    nostr::collect_dms(
+        relays,
+        &me,
+        htss.threshold as usize,
+        until,
+        |sender, message| {
+            let output: RecoveryRound1Output = serde_json::from_str(message).ok()?;
+            (output.event_type == "recovery_round1"
+                && output.lost_index == my_index
+                && roster.0.get(&output.helper_index).map(String::as_str) == Some(sender))
+            .then(|| output.helper_index.to_string())
+        },
+    )
+}
+
+/// Answer the recovery requests for this wallet's group on `relays`
+///
+/// Reads the stored requests, then live ones until `until`. Each is checked
+/// as [`check_request`] does and, once `approve` says so, answered with
+/// [`respond_core`]'s gift wrap published to the same relays. Requests this
+/// wallet answered before (per its audit log) are skipped, as are ones that
+/// fail the checks, with the reason in the output.
+pub fn answer_requests(
+    relays: &mut Relays,
+    storage: &dyn Storage,
+    from: Option<&str>,
+    until: Instant,
+    approve: &mut dyn FnMut(&CheckedRequest) -> Result<bool>,
+) -> Result<CommandResult> {
+    let group_key = group_key_hex(storage)?;
+    let htss: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    let d_tags: Vec<String> = htss
+        .party_ranks
+        .keys()
+        .map(|index| format!("{}{}/{}", RECOVERY_REQUEST_D_TAG, group_key, index))
+        .collect();
+    let filter = Filter {
+        since: Some(health::now().saturating_sub(RECOVERY_REQUEST_MAX_AGE_SECS)),
+        ..request_filter().tag('d', &d_tags.iter().map(String::as_str).collect::<Vec<_>>())
+    };
+    let answered_before: Vec<String> = audit::load(storage)?
+        .into_iter()
+        .filter(|entry| entry.event == "recovery_response")
+        .filter_map(|entry| entry.details["request"].as_str().map(str::to_string))
+        .collect();
+
+    let mut out = String::new();
+    let mut answered = Vec::new();
+    let mut pending = relays.subscribe("frostdao-recovery", vec![filter])?;
+    loop {
+        let event = match pending.pop() {
+            Some(event) => event,
+            None => match relays.next_event(until)? {
+                Some(event) => event,
+                None => break,
+            },
+        };
+        if answered_before.contains(&event.id) {
+            continue;
+        }
+        let checked = match check_request(
+            &serde_json::to_string(&event)?,
+            from,
+            storage,
+            health::now(),
+        ) {
+            Ok(checked) => checked,
+            Err(e) => {
+                out.push_str(&format!("⚠️  Skipped request {}: {:#}\n", event.id, e));
+                continue;
+            }
+        };
+        if !approve(&checked)? {
+            out.push_str(&format!(
+                "✋ Request {} for party {} not approved; nothing sent\n",
+                event.id, checked.request.lost_index
+            ));
+            continue;
+        }
+        let response = respond_core(&checked, storage)?;
+        let wrap: Event = serde_json::from_str(&response.result)?;
+        let accepted = relays.publish(&wrap)?;
+        out.push_str(&response.output);
+        out.push_str(&format!("📡 Published to {}\n", accepted.join(", ")));
+        answered.push(event.id);
+    }
+    relays.close("frostdao-recovery");
+    if answered.is_empty() {
+        out.push_str("No recovery request answered.\n");
+    }
+
+    let result = serde_json::json!({
+        "answered": answered,
+        "type": "recovery_answers",
+    });
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+/// CLI wrapper for request_core; with `relays` the request is published
+/// there instead of printed
+pub fn recover_request(
+    source_wallet: &str,
+    lost_index: u32,
+    qr: bool,
+    relays: Option<&mut Relays>,
+) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(source_wallet))?;
+    let cmd_result = request_core(source_wallet, lost_index, &storage, health::now())?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    match relays {
+        Some(relays) => {
+            let event: Event = serde_json::from_str(&cmd_result.result)?;
+            for url in relays.publish(&event)? {
+                println!("📡 Published to {}", url);
+            }
+            println!(
+                "\n🧠 Then: frostdao recover-finalize --source {} --my-index {} --target <wallet> --nostr",
+                source_wallet, lost_index
+            );
+        }
+        None => {
+            println!("📋 Publish this event to your relays:\n");
+            println!("{}", crate::protocol::compact::present(&cmd_result.result));
+            if qr {
+                crate::qr::show_qr(&cmd_result.result)?;
+            }
+        }
+    }
+    let htss: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)?;
+    let others: Vec<u32> = htss
+        .party_ranks
+        .into_keys()
+        .filter(|&index| index != lost_index)
+        .collect();
+    print!(
+        "\n{}",
+        directory::whom_to_contact(&storage, &others, health::now())
+    );
+    Ok(())
+}
+
+/// CLI wrapper for check_request and respond_core, asking before it answers
+///
+/// Answers the request in `data`, or without it every pending request on
+/// `relays` (waiting up to `listen` for new ones); with `relays` the gift
+/// wraps are published there instead of printed.
+pub fn recover_respond(
+    name: &str,
+    data: Option<&str>,
+    from: Option<&str>,
+    yes: bool,
+    relays: Option<&mut Relays>,
+    listen: Duration,
+) -> Result<()> {
+    let state_dir = get_state_dir(name);
+    if !std::path::Path::new(&state_dir).exists() {
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}.",
+            name, state_dir
+        ))
+        .into());
+    }
+    let storage = FileStorage::new(&state_dir)?;
+    let mut approve = |checked: &CheckedRequest| confirm_request(checked, &storage, yes);
+
+    let Some(data) = data else {
+        let Some(relays) = relays else {
+            bail!(crate::error::Error::User(
+                "--data is required without relays".to_string()
+            ));
+        };
+        println!(
+            "📡 Reading recovery requests from {}",
+            relays.urls().join(", ")
+        );
+        let cmd_result = answer_requests(
+            relays,
+            &storage,
+            from,
+            Instant::now() + listen,
+            &mut approve,
+        )?;
+        println!("\n{}", cmd_result.output);
+        return Ok(());
+    };
+
+    let data = crate::qr::resolve_payload(data)?;
+    let checked = check_request(&data, from, &storage, health::now())?;
+    if !approve(&checked)? {
+        bail!("Not approved; nothing was sent");
+    }
+
+    let cmd_result = respond_core(&checked, &storage)?;
+    println!("\n{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    match relays {
+        Some(relays) => {
+            let wrap: Event = serde_json::from_str(&cmd_result.result)?;
+            for url in relays.publish(&wrap)? {
+                println!("📡 Published to {}", url);
+            }
+        }
+        None => {
+            println!("📋 Publish this gift wrap to your relays:\n");
+            println!("{}", cmd_result.result);
+        }
+    }
+    Ok(())
+}
+
+/// Show a checked request and ask the holder to approve it by typing the
+/// lost party's index (`yes` approves without asking)
+fn confirm_request(checked: &CheckedRequest, storage: &dyn Storage, yes: bool) -> Result<bool> {
+    let lost_index = checked.request.lost_index;
+    println!("🆘 Recovery request for party {}", lost_index);
+    println!("   From: {}", nostr::npub(&checked.event.pubkey)?);
+    println!(
+        "   Sent: {}",
+        health::describe_time(Some(checked.event.created_at), health::now())
+    );
+    if checked.pinned {
+        println!(
+            "   🪪 Signed by the identity pinned for party {}",
+            lost_index
+        );
+    } else {
+        println!("   ⚠️  Signed by a new key you vouched for with --from");
+    }
+    print!(
+        "{}",
+        directory::whom_to_contact(storage, &[lost_index], health::now())
+    );
+    println!("\n⚠️  Answering sends your raw share value to this key.");
+    if yes {
+        return Ok(true);
+    }
+    print!(
+        "   Type the lost party's index ({}) to approve: ",
+        lost_index
+    );
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim() == lost_index.to_string())
+}
+
 // Lagrange coefficient computation is now in crypto_helpers module
 
 #[cfg(test)]
@@ -0,0 +1,952 @@
+//! Nostr Relays
+//!
+//! The client side of NIP-01: [`Relay`] publishes, queries and subscribes
+//! over one [`Connection`], and [`Relays`] uses several as one, each event
+//! published to all of them and read once. The websocket connection to a
+//! real relay is in `relay_client`, built with the `network` feature.
+//!
+//! [`MockRelay`] is a relay simulator speaking the subset of NIP-01 our
+//! events need, so flows that move payloads over Nostr (gift-wrapped Round 2
+//! shares, published group info, recovery requests) can be tested
+//! deterministically through a [`MockConnection`] without a real relay.
+//!
+//! Frames are the JSON arrays a relay exchanges over its websocket:
+//!
+//! - client → relay: `["EVENT", event]`, `["REQ", sub_id, filter...]`,
+//!   `["CLOSE", sub_id]`
+//! - relay → client: `["EVENT", sub_id, event]`, `["OK", id, accepted, msg]`,
+//!   `["EOSE", sub_id]`, `["CLOSED", sub_id, msg]`, `["NOTICE", msg]`
+//!
+//! Events are checked (id and signature) before they are stored. Replaceable
+//! kinds keep only the newest event per author (and `d` tag for 30000-39999),
+//! ephemeral kinds are forwarded to live subscriptions but never stored.
+//! There are no clocks or sockets: replies come back from [`MockRelay::handle`]
+//! and live events queue up per connection until [`MockRelay::poll`].
+
+use crate::protocol::nostr::Event;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// A NIP-01 subscription filter; conditions are ANDed, list entries ORed
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ids: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<Vec<u64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// `#p`, `#d`, ... keyed by the tag letter
+    #[serde(flatten, with = "tag_filters")]
+    pub tags: BTreeMap<char, Vec<String>>,
+}
+
+/// `{"#p": [...]}` on the wire, `{'p': [...]}` in [`Filter::tags`]
+mod tag_filters {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        tags: &BTreeMap<char, Vec<String>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        tags.iter()
+            .map(|(letter, values)| (format!("#{}", letter), values))
+            .collect::<BTreeMap<_, _>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<char, Vec<String>>, D::Error> {
+        let rest = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        let mut tags = BTreeMap::new();
+        for (key, value) in rest {
+            let mut chars = key.chars();
+            let letter = match (chars.next(), chars.next(), chars.next()) {
+                (Some('#'), Some(letter), None) if letter.is_ascii_alphabetic() => letter,
+                _ => return Err(serde::de::Error::custom(format!("unknown filter {}", key))),
+            };
+            let values = Vec::<String>::deserialize(value).map_err(serde::de::Error::custom)?;
+            tags.insert(letter, values);
+        }
+        Ok(tags)
+    }
+}
+
+impl Filter {
+    /// Events of any of `kinds`
+    pub fn kinds(kinds: &[u64]) -> Self {
+        Self {
+            kinds: Some(kinds.to_vec()),
+            ..Self::default()
+        }
+    }
+
+    /// Also require a `#letter` tag with one of `values`
+    pub fn tag(mut self, letter: char, values: &[&str]) -> Self {
+        self.tags
+            .insert(letter, values.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        let listed = |list: &Option<Vec<String>>, value: &str| {
+            list.as_ref()
+                .is_none_or(|list| list.iter().any(|v| v == value))
+        };
+        listed(&self.ids, &event.id)
+            && listed(&self.authors, &event.pubkey)
+            && self
+                .kinds
+                .as_ref()
+                .is_none_or(|kinds| kinds.contains(&event.kind))
+            && self.since.is_none_or(|since| event.created_at >= since)
+            && self.until.is_none_or(|until| event.created_at <= until)
+            && self.tags.iter().all(|(letter, values)| {
+                event.tags.iter().any(|tag| {
+                    tag.first().and_then(|name| name.strip_prefix(*letter)) == Some("")
+                        && tag.get(1).is_some_and(|value| values.contains(value))
+                })
+            })
+    }
+}
+
+/// A frame sent by a client
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    Event(Event),
+    Req {
+        subscription: String,
+        filters: Vec<Filter>,
+    },
+    Close(String),
+}
+
+impl ClientMessage {
+    pub fn parse(frame: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(frame).context("Frame is not JSON")?;
+        let parts = value.as_array().context("Frame is not a JSON array")?;
+        let subscription = || -> Result<String> {
+            parts
+                .get(1)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .context("Missing subscription id")
+        };
+        match parts.first().and_then(Value::as_str) {
+            Some("EVENT") => {
+                let event = parts.get(1).context("EVENT frame has no event")?;
+                Ok(Self::Event(serde_json::from_value(event.clone())?))
+            }
+            Some("REQ") => Ok(Self::Req {
+                subscription: subscription()?,
+                filters: parts[2..]
+                    .iter()
+                    .map(|filter| serde_json::from_value(filter.clone()))
+                    .collect::<Result<_, _>>()
+                    .context("Invalid filter")?,
+            }),
+            Some("CLOSE") => Ok(Self::Close(subscription()?)),
+            Some(other) => bail!("Unknown message type {}", other),
+            None => bail!("Frame has no message type"),
+        }
+    }
+
+    pub fn to_frame(&self) -> String {
+        match self {
+            Self::Event(event) => json!(["EVENT", event]),
+            Self::Req {
+                subscription,
+                filters,
+            } => {
+                let mut frame = vec![json!("REQ"), json!(subscription)];
+                frame.extend(filters.iter().map(|filter| json!(filter)));
+                Value::Array(frame)
+            }
+            Self::Close(subscription) => json!(["CLOSE", subscription]),
+        }
+        .to_string()
+    }
+}
+
+/// A frame sent by the relay
+#[derive(Debug, Clone, PartialEq)]
+pub enum RelayMessage {
+    Event {
+        subscription: String,
+        event: Event,
+    },
+    Ok {
+        event_id: String,
+        accepted: bool,
+        message: String,
+    },
+    Eose(String),
+    Closed {
+        subscription: String,
+        message: String,
+    },
+    Notice(String),
+}
+
+impl RelayMessage {
+    pub fn parse(frame: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(frame).context("Frame is not JSON")?;
+        let parts = value.as_array().context("Frame is not a JSON array")?;
+        let text = |i: usize| -> Result<String> {
+            parts
+                .get(i)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .with_context(|| format!("Frame field {} is not a string", i))
+        };
+        match parts.first().and_then(Value::as_str) {
+            Some("EVENT") => Ok(Self::Event {
+                subscription: text(1)?,
+                event: serde_json::from_value(parts.get(2).cloned().unwrap_or_default())?,
+            }),
+            Some("OK") => Ok(Self::Ok {
+                event_id: text(1)?,
+                accepted: parts
+                    .get(2)
+                    .and_then(Value::as_bool)
+                    .context("OK frame has no accepted flag")?,
+                message: text(3)?,
+            }),
+            Some("EOSE") => Ok(Self::Eose(text(1)?)),
+            Some("CLOSED") => Ok(Self::Closed {
+                subscription: text(1)?,
+                message: text(2)?,
+            }),
+            Some("NOTICE") => Ok(Self::Notice(text(1)?)),
+            Some(other) => bail!("Unknown message type {}", other),
+            None => bail!("Frame has no message type"),
+        }
+    }
+
+    pub fn to_frame(&self) -> String {
+        match self {
+            Self::Event {
+                subscription,
+                event,
+            } => json!(["EVENT", subscription, event]),
+            Self::Ok {
+                event_id,
+                accepted,
+                message,
+            } => json!(["OK", event_id, accepted, message]),
+            Self::Eose(subscription) => json!(["EOSE", subscription]),
+            Self::Closed {
+                subscription,
+                message,
+            } => json!(["CLOSED", subscription, message]),
+            Self::Notice(message) => json!(["NOTICE", message]),
+        }
+        .to_string()
+    }
+}
+
+fn is_replaceable(kind: u64) -> bool {
+    kind == 0 || kind == 3 || (10000..20000).contains(&kind)
+}
+
+fn is_ephemeral(kind: u64) -> bool {
+    (20000..30000).contains(&kind)
+}
+
+fn is_addressable(kind: u64) -> bool {
+    (30000..40000).contains(&kind)
+}
+
+fn d_tag(event: &Event) -> &str {
+    event
+        .tags
+        .iter()
+        .find(|tag| tag.first().map(String::as_str) == Some("d"))
+        .and_then(|tag| tag.get(1))
+        .map(String::as_str)
+        .unwrap_or("")
+}
+
+/// Whether `new` supersedes `old` as the stored version of a replaceable event
+fn same_slot(old: &Event, new: &Event) -> bool {
+    old.kind == new.kind
+        && old.pubkey == new.pubkey
+        && (is_replaceable(new.kind) || (is_addressable(new.kind) && d_tag(old) == d_tag(new)))
+}
+
+/// Newest first; ties go to the lowest id (NIP-01)
+fn newer(a: &Event, b: &Event) -> bool {
+    (a.created_at, std::cmp::Reverse(&a.id)) > (b.created_at, std::cmp::Reverse(&b.id))
+}
+
+/// Handle for one client connection to a [`MockRelay`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionId(usize);
+
+/// What the relay keeps per connected client
+#[derive(Debug, Default)]
+struct ClientState {
+    subscriptions: BTreeMap<String, Vec<Filter>>,
+    outbox: Vec<String>,
+}
+
+/// The relay: stored events plus each connection's live subscriptions
+#[derive(Debug, Default)]
+pub struct MockRelay {
+    events: Vec<Event>,
+    connections: HashMap<ConnectionId, ClientState>,
+    next_connection: usize,
+}
+
+impl MockRelay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connect(&mut self) -> ConnectionId {
+        let id = ConnectionId(self.next_connection);
+        self.next_connection += 1;
+        self.connections.insert(id, ClientState::default());
+        id
+    }
+
+    /// Drop the connection and its subscriptions
+    pub fn disconnect(&mut self, connection: ConnectionId) {
+        self.connections.remove(&connection);
+    }
+
+    /// Events currently stored, in arrival order
+    pub fn stored(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Process one client frame; returns the frames sent straight back
+    pub fn handle(&mut self, connection: ConnectionId, frame: &str) -> Vec<String> {
+        if !self.connections.contains_key(&connection) {
+            return vec![RelayMessage::Notice("error: not connected".to_string()).to_frame()];
+        }
+        let replies = match ClientMessage::parse(frame) {
+            Ok(ClientMessage::Event(event)) => vec![self.publish(event)],
+            Ok(ClientMessage::Req {
+                subscription,
+                filters,
+            }) => self.subscribe(connection, subscription, filters),
+            Ok(ClientMessage::Close(subscription)) => {
+                if let Some(conn) = self.connections.get_mut(&connection) {
+                    conn.subscriptions.remove(&subscription);
+                }
+                Vec::new()
+            }
+            Err(e) => vec![RelayMessage::Notice(format!("error: {:#}", e))],
+        };
+        replies.iter().map(RelayMessage::to_frame).collect()
+    }
+
+    /// Live events queued for `connection` since the last poll
+    pub fn poll(&mut self, connection: ConnectionId) -> Vec<String> {
+        self.connections
+            .get_mut(&connection)
+            .map(|conn| std::mem::take(&mut conn.outbox))
+            .unwrap_or_default()
+    }
+
+    fn publish(&mut self, event: Event) -> RelayMessage {
+        let ok = |accepted: bool, message: String| RelayMessage::Ok {
+            event_id: event.id.clone(),
+            accepted,
+            message,
+        };
+        if let Err(e) = event.verify() {
+            return ok(false, format!("invalid: {}", e));
+        }
+        if self.events.iter().any(|stored| stored.id == event.id) {
+            return ok(true, "duplicate: already have this event".to_string());
+        }
+
+        if is_replaceable(event.kind) || is_addressable(event.kind) {
+            if let Some(pos) = self.events.iter().position(|old| same_slot(old, &event)) {
+                if !newer(&event, &self.events[pos]) {
+                    return ok(false, "duplicate: have a newer version".to_string());
+                }
+                self.events.remove(pos);
+            }
+        }
+        if !is_ephemeral(event.kind) {
+            self.events.push(event.clone());
+        }
+
+        for conn in self.connections.values_mut() {
+            for (subscription, filters) in &conn.subscriptions {
+                if filters.iter().any(|filter| filter.matches(&event)) {
+                    conn.outbox.push(
+                        RelayMessage::Event {
+                            subscription: subscription.clone(),
+                            event: event.clone(),
+                        }
+                        .to_frame(),
+                    );
+                }
+            }
+        }
+        ok(true, String::new())
+    }
+
+    fn subscribe(
+        &mut self,
+        connection: ConnectionId,
+        subscription: String,
+        filters: Vec<Filter>,
+    ) -> Vec<RelayMessage> {
+        if subscription.is_empty() || subscription.len() > 64 {
+            return vec![RelayMessage::Closed {
+                subscription,
+                message: "invalid: subscription id must be 1-64 characters".to_string(),
+            }];
+        }
+
+        let mut matched: Vec<&Event> = Vec::new();
+        for filter in &filters {
+            let mut hits: Vec<&Event> = self
+                .events
+                .iter()
+                .filter(|event| filter.matches(event))
+                .collect();
+            hits.sort_by(|a, b| {
+                if newer(a, b) {
+                    std::cmp::Ordering::Less
+                } else {
+                    std::cmp::Ordering::Greater
+                }
+            });
+            hits.truncate(filter.limit.unwrap_or(usize::MAX));
+            for hit in hits {
+                if !matched.iter().any(|seen| seen.id == hit.id) {
+                    matched.push(hit);
+                }
+            }
+        }
+
+        let mut replies: Vec<RelayMessage> = matched
+            .into_iter()
+            .map(|event| RelayMessage::Event {
+                subscription: subscription.clone(),
+                event: event.clone(),
+            })
+            .collect();
+        replies.push(RelayMessage::Eose(subscription.clone()));
+
+        // A REQ with an existing id replaces that subscription
+        if let Some(conn) = self.connections.get_mut(&connection) {
+            conn.subscriptions.insert(subscription, filters);
+        }
+        replies
+    }
+}
+
+/// How long a relay gets to acknowledge an event or finish a stored query
+pub const ANSWER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One client connection to a relay: frames out, frames in
+///
+/// [`MockConnection`] talks to a [`MockRelay`]; a websocket to a real relay
+/// is in `relay_client` (network builds).
+pub trait Connection {
+    /// The relay's address, for messages
+    fn url(&self) -> &str;
+    fn send(&mut self, frame: &str) -> Result<()>;
+    /// The next frame from the relay, or `None` if none arrives in `timeout`
+    fn recv(&mut self, timeout: Duration) -> Result<Option<String>>;
+}
+
+/// A [`Connection`] to a [`MockRelay`], dropped when the connection is
+pub struct MockConnection<'a> {
+    relay: &'a RefCell<MockRelay>,
+    id: ConnectionId,
+    inbox: VecDeque<String>,
+}
+
+impl<'a> MockConnection<'a> {
+    pub fn open(relay: &'a RefCell<MockRelay>) -> Self {
+        let id = relay.borrow_mut().connect();
+        Self {
+            relay,
+            id,
+            inbox: VecDeque::new(),
+        }
+    }
+}
+
+impl Drop for MockConnection<'_> {
+    fn drop(&mut self) {
+        self.relay.borrow_mut().disconnect(self.id);
+    }
+}
+
+impl Connection for MockConnection<'_> {
+    fn url(&self) -> &str {
+        "mock://relay"
+    }
+
+    fn send(&mut self, frame: &str) -> Result<()> {
+        let replies = self.relay.borrow_mut().handle(self.id, frame);
+        self.inbox.extend(replies);
+        Ok(())
+    }
+
+    /// Never waits: nothing else can publish while this thread does
+    fn recv(&mut self, _timeout: Duration) -> Result<Option<String>> {
+        if self.inbox.is_empty() {
+            let live = self.relay.borrow_mut().poll(self.id);
+            self.inbox.extend(live);
+        }
+        Ok(self.inbox.pop_front())
+    }
+}
+
+/// A connection to one relay, keeping the subscription events that arrive
+/// while it waits for something else
+pub struct Relay<'a> {
+    connection: Box<dyn Connection + 'a>,
+    backlog: VecDeque<Event>,
+}
+
+impl<'a> Relay<'a> {
+    pub fn new(connection: impl Connection + 'a) -> Self {
+        Self {
+            connection: Box::new(connection),
+            backlog: VecDeque::new(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        self.connection.url()
+    }
+
+    /// Publish `event`, failing unless the relay accepts it
+    pub fn publish(&mut self, event: &Event) -> Result<()> {
+        self.connection
+            .send(&ClientMessage::Event(event.clone()).to_frame())?;
+        let deadline = Instant::now() + ANSWER_TIMEOUT;
+        while let Some(message) = self.next_message(deadline)? {
+            match message {
+                RelayMessage::Ok {
+                    event_id,
+                    accepted,
+                    message,
+                } if event_id == event.id => {
+                    if !accepted {
+                        bail!(crate::error::Error::Network(format!(
+                            "{} rejected event {}: {}",
+                            self.url(),
+                            event.id,
+                            message
+                        )));
+                    }
+                    return Ok(());
+                }
+                RelayMessage::Event { event, .. } => self.backlog.push_back(event),
+                _ => {}
+            }
+        }
+        bail!(crate::error::Error::Network(format!(
+            "{} did not acknowledge event {}",
+            self.url(),
+            event.id
+        )))
+    }
+
+    /// Open `subscription` and return the stored events matching `filters`;
+    /// later ones come from [`Relay::next_event`] until it is closed
+    pub fn subscribe(&mut self, subscription: &str, filters: Vec<Filter>) -> Result<Vec<Event>> {
+        let req = ClientMessage::Req {
+            subscription: subscription.to_string(),
+            filters,
+        };
+        self.connection.send(&req.to_frame())?;
+        let deadline = Instant::now() + ANSWER_TIMEOUT;
+        let mut events = Vec::new();
+        while let Some(message) = self.next_message(deadline)? {
+            match message {
+                RelayMessage::Event {
+                    subscription: sub,
+                    event,
+                } if sub == subscription => events.push(event),
+                RelayMessage::Event { event, .. } => self.backlog.push_back(event),
+                RelayMessage::Eose(sub) if sub == subscription => return Ok(events),
+                RelayMessage::Closed {
+                    subscription: sub,
+                    message,
+                } if sub == subscription => bail!(crate::error::Error::Network(format!(
+                    "{} closed subscription {}: {}",
+                    self.url(),
+                    subscription,
+                    message
+                ))),
+                _ => {}
+            }
+        }
+        bail!(crate::error::Error::Network(format!(
+            "{} did not finish sending stored events for {}",
+            self.url(),
+            subscription
+        )))
+    }
+
+    /// Stored events matching `filters`, as a REQ up to EOSE returns them
+    pub fn fetch(&mut self, subscription: &str, filters: Vec<Filter>) -> Result<Vec<Event>> {
+        let events = self.subscribe(subscription, filters)?;
+        self.close(subscription)?;
+        Ok(events)
+    }
+
+    pub fn close(&mut self, subscription: &str) -> Result<()> {
+        self.connection
+            .send(&ClientMessage::Close(subscription.to_string()).to_frame())
+    }
+
+    /// The next live event on any open subscription, waiting up to `deadline`
+    pub fn next_event(&mut self, deadline: Instant) -> Result<Option<Event>> {
+        if let Some(event) = self.backlog.pop_front() {
+            return Ok(Some(event));
+        }
+        while let Some(message) = self.next_message(deadline)? {
+            if let RelayMessage::Event { event, .. } = message {
+                return Ok(Some(event));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The next frame that parses, or `None` once `deadline` has passed
+    fn next_message(&mut self, deadline: Instant) -> Result<Option<RelayMessage>> {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(frame) = self.connection.recv(remaining)? else {
+                return Ok(None);
+            };
+            // Relays speak NIPs we don't (AUTH, COUNT, ...); skip those frames
+            if let Ok(message) = RelayMessage::parse(&frame) {
+                return Ok(Some(message));
+            }
+        }
+    }
+}
+
+/// Several relays used as one: events go to all of them and are read from
+/// any, each once
+pub struct Relays<'a> {
+    relays: Vec<Relay<'a>>,
+    seen: HashSet<String>,
+}
+
+impl<'a> Relays<'a> {
+    pub fn new(relays: Vec<Relay<'a>>) -> Self {
+        Self {
+            relays,
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn urls(&self) -> Vec<&str> {
+        self.relays.iter().map(Relay::url).collect()
+    }
+
+    /// Publish `event` everywhere; the relays that accepted it, or an error
+    /// naming each refusal if none did
+    pub fn publish(&mut self, event: &Event) -> Result<Vec<String>> {
+        let mut accepted = Vec::new();
+        let mut refused = Vec::new();
+        for relay in &mut self.relays {
+            match relay.publish(event) {
+                Ok(()) => accepted.push(relay.url().to_string()),
+                Err(e) => refused.push(format!("{:#}", e)),
+            }
+        }
+        if accepted.is_empty() {
+            bail!(crate::error::Error::Network(format!(
+                "No relay accepted event {}: {}",
+                event.id,
+                refused.join("; ")
+            )));
+        }
+        Ok(accepted)
+    }
+
+    /// Open `subscription` on every relay; the stored events, each once.
+    /// A relay that fails is dropped as long as one answers.
+    pub fn subscribe(&mut self, subscription: &str, filters: Vec<Filter>) -> Result<Vec<Event>> {
+        let mut events = Vec::new();
+        let mut failures = Vec::new();
+        let mut answered = Vec::new();
+        for mut relay in std::mem::take(&mut self.relays) {
+            match relay.subscribe(subscription, filters.clone()) {
+                Ok(stored) => {
+                    for event in stored {
+                        if self.seen.insert(event.id.clone()) {
+                            events.push(event);
+                        }
+                    }
+                    answered.push(relay);
+                }
+                Err(e) => failures.push(format!("{:#}", e)),
+            }
+        }
+        self.relays = answered;
+        if self.relays.is_empty() {
+            bail!(crate::error::Error::Network(format!(
+                "No relay answered: {}",
+                failures.join("; ")
+            )));
+        }
+        Ok(events)
+    }
+
+    /// Stored events matching `filters`, from every relay, each once
+    pub fn fetch(&mut self, subscription: &str, filters: Vec<Filter>) -> Result<Vec<Event>> {
+        let events = self.subscribe(subscription, filters)?;
+        self.close(subscription);
+        Ok(events)
+    }
+
+    /// Close `subscription` everywhere; a relay already gone doesn't matter
+    pub fn close(&mut self, subscription: &str) {
+        for relay in &mut self.relays {
+            let _ = relay.close(subscription);
+        }
+    }
+
+    /// The next live event not seen before from any relay, waiting up to
+    /// `deadline`
+    pub fn next_event(&mut self, deadline: Instant) -> Result<Option<Event>> {
+        loop {
+            for relay in &mut self.relays {
+                // Short turns, so one quiet relay doesn't hold up the others
+                let turn = (Instant::now() + RELAY_TURN).min(deadline);
+                while let Some(event) = relay.next_event(turn)? {
+                    if self.seen.insert(event.id.clone()) {
+                        return Ok(Some(event));
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+/// Longest a [`Relays`] waits on one relay before checking the next
+const RELAY_TURN: Duration = Duration::from_millis(250);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::identity::IdentityKey;
+    use crate::protocol::keygen::{GroupInfo, PartyInfo};
+    use crate::protocol::nostr::{self, KIND_APP_DATA, KIND_GIFT_WRAP};
+
+    fn group_info(threshold: u32) -> GroupInfo {
+        let group_key = IdentityKey::generate().public_hex();
+        let xonly = bitcoin::XOnlyPublicKey::from_slice(&hex::decode(&group_key).unwrap()).unwrap();
+        let secp = bitcoin::secp256k1::Secp256k1::new();
+        let address = |network| bitcoin::Address::p2tr(&secp, xonly, None, network).to_string();
+        GroupInfo {
+            name: "treasury".to_string(),
+            group_public_key: group_key,
+            taproot_address_testnet: address(bitcoin::Network::Testnet),
+            taproot_address_mainnet: address(bitcoin::Network::Bitcoin),
+            threshold,
+            total_parties: 2,
+            hierarchical: false,
+            parties: (1..=2)
+                .map(|index| PartyInfo {
+                    index,
+                    rank: 0,
+                    verification_share: "unavailable".to_string(),
+                    identity: None,
+                })
+                .collect(),
+            directory: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_frames_round_trip() {
+        let filter = Filter::kinds(&[KIND_GIFT_WRAP]).tag('p', &["ab"]);
+        let req = ClientMessage::Req {
+            subscription: "dms".to_string(),
+            filters: vec![filter.clone(), Filter::default()],
+        };
+        let frame = req.to_frame();
+        assert_eq!(frame, r##"["REQ","dms",{"#p":["ab"],"kinds":[1059]},{}]"##);
+        assert_eq!(ClientMessage::parse(&frame).unwrap(), req);
+
+        let ok = RelayMessage::Ok {
+            event_id: "00".repeat(32),
+            accepted: false,
+            message: "invalid: bad sig".to_string(),
+        };
+        assert_eq!(RelayMessage::parse(&ok.to_frame()).unwrap(), ok);
+
+        assert!(ClientMessage::parse(r##"["REQ","x",{"#pp":["a"]}]"##).is_err());
+        assert!(ClientMessage::parse(r#"["AUTH","x"]"#).is_err());
+    }
+
+    #[test]
+    fn test_subscriptions_see_stored_then_live_events() {
+        let relay = RefCell::new(MockRelay::new());
+        let alice = IdentityKey::generate();
+        let bob = IdentityKey::generate();
+        let carol = IdentityKey::generate();
+        let mut sender = Relay::new(MockConnection::open(&relay));
+        let mut reader = Relay::new(MockConnection::open(&relay));
+
+        let early = nostr::gift_wrap(&alice, &bob.public_hex(), "early").unwrap();
+        sender.publish(&early).unwrap();
+        sender.publish(&early).unwrap(); // duplicate is still OK
+        sender
+            .publish(&nostr::gift_wrap(&alice, &carol.public_hex(), "not for bob").unwrap())
+            .unwrap();
+        assert_eq!(relay.borrow().stored().len(), 2);
+
+        let for_bob = || Filter::kinds(&[KIND_GIFT_WRAP]).tag('p', &[&bob.public_hex()]);
+        let stored = reader.subscribe("bob", vec![for_bob()]).unwrap();
+        assert_eq!(stored, vec![early]);
+        assert!(reader.next_event(Instant::now()).unwrap().is_none());
+
+        let late = nostr::gift_wrap(&alice, &bob.public_hex(), "late").unwrap();
+        sender.publish(&late).unwrap();
+        let live = reader.next_event(Instant::now()).unwrap().unwrap();
+        assert_eq!(live, late);
+        assert_eq!(nostr::unwrap_gift(&bob, &live).unwrap().1, "late");
+
+        // Closed subscriptions stop receiving
+        reader.close("bob").unwrap();
+        sender
+            .publish(&nostr::gift_wrap(&alice, &bob.public_hex(), "after close").unwrap())
+            .unwrap();
+        assert!(reader.next_event(Instant::now()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_relays_publish_everywhere_and_read_once() {
+        let (one, two) = (
+            RefCell::new(MockRelay::new()),
+            RefCell::new(MockRelay::new()),
+        );
+        let open = || {
+            Relays::new(vec![
+                Relay::new(MockConnection::open(&one)),
+                Relay::new(MockConnection::open(&two)),
+            ])
+        };
+        let (mut sender, mut reader) = (open(), open());
+        let alice = IdentityKey::generate();
+        let bob = IdentityKey::generate();
+        let for_bob = vec![Filter::kinds(&[KIND_GIFT_WRAP]).tag('p', &[&bob.public_hex()])];
+
+        let early = nostr::gift_wrap(&alice, &bob.public_hex(), "early").unwrap();
+        assert_eq!(sender.publish(&early).unwrap().len(), 2);
+        assert_eq!(reader.subscribe("bob", for_bob).unwrap(), vec![early]);
+
+        // Live on one relay, then the other: read once
+        let late = nostr::gift_wrap(&alice, &bob.public_hex(), "late").unwrap();
+        Relay::new(MockConnection::open(&one))
+            .publish(&late)
+            .unwrap();
+        assert_eq!(
+            reader.next_event(Instant::now()).unwrap(),
+            Some(late.clone())
+        );
+        Relay::new(MockConnection::open(&two))
+            .publish(&late)
+            .unwrap();
+        assert_eq!(reader.next_event(Instant::now()).unwrap(), None);
+
+        // Refused everywhere is an error naming why
+        let mut tampered = late;
+        tampered.content.push('A');
+        let err = sender.publish(&tampered).unwrap_err();
+        assert!(err.to_string().contains("No relay accepted"), "{}", err);
+        assert!(err.to_string().contains("invalid"), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_tampered_events() {
+        let relay = RefCell::new(MockRelay::new());
+        let mut wrap = nostr::gift_wrap(
+            &IdentityKey::generate(),
+            &IdentityKey::generate().public_hex(),
+            "x",
+        )
+        .unwrap();
+        wrap.content.push('A');
+        let err = Relay::new(MockConnection::open(&relay))
+            .publish(&wrap)
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid"), "{}", err);
+        assert!(relay.borrow().stored().is_empty());
+
+        let conn = relay.borrow_mut().connect();
+        let notice = relay.borrow_mut().handle(conn, "not json");
+        assert!(matches!(
+            RelayMessage::parse(&notice[0]).unwrap(),
+            RelayMessage::Notice(_)
+        ));
+    }
+
+    #[test]
+    fn test_group_info_is_replaceable_per_group_key() {
+        let relay = RefCell::new(MockRelay::new());
+        let mut conn = Relay::new(MockConnection::open(&relay));
+        let author = IdentityKey::generate();
+        let info = group_info(1);
+        let d = format!("frostdao/group_info/{}", info.group_public_key);
+        let version = |created_at: u64, threshold: u32| {
+            let content = serde_json::to_string(&GroupInfo {
+                threshold,
+                ..info.clone()
+            })
+            .unwrap();
+            let tags = vec![vec!["d".to_string(), d.clone()]];
+            Event::new(
+                author.public_hex(),
+                created_at,
+                KIND_APP_DATA,
+                tags,
+                content,
+            )
+            .signed(&author)
+        };
+        let (older, newer) = (version(1_000, 1), version(2_000, 2));
+        let unrelated = nostr::group_info_event(&author, &group_info(2)).unwrap();
+
+        conn.publish(&newer).unwrap();
+        let err = conn.publish(&older).unwrap_err();
+        assert!(err.to_string().contains("newer version"), "{}", err);
+        conn.publish(&unrelated).unwrap();
+        assert_eq!(relay.borrow().stored().len(), 2);
+
+        let by_d = Filter::kinds(&[KIND_APP_DATA]).tag('d', &[&d]);
+        assert_eq!(conn.fetch("group", vec![by_d]).unwrap(), vec![newer]);
+
+        let newest = Filter {
+            limit: Some(1),
+            ..Filter::kinds(&[KIND_APP_DATA])
+        };
+        assert_eq!(conn.fetch("latest", vec![newest]).unwrap(), vec![unrelated]);
+    }
+}
@@ -0,0 +1,651 @@
+//! Nostr Relay Connections
+//!
+//! [`WebSocket`] is the [`Connection`] to a real relay: RFC 6455 over `ws://`
+//! or `wss://` (TLS checked against the system roots) carrying the NIP-01
+//! JSON frames of [`crate::protocol::relay`]. Pings are answered, fragmented
+//! messages joined, and a message over [`MAX_MESSAGE`] ends the connection.
+//!
+//! Which relays a command uses comes from `relays.json` (`relay-config`) or,
+//! for one run, from `--relay`; see [`resolve`] and [`connect_all`].
+
+use crate::btc::http::Stream;
+use crate::protocol::relay::{Connection, Relay, Relays};
+use crate::protocol::wallet::STATE_ROOT;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use bitcoin::hashes::{sha1, Hash};
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Read, Write};
+use std::time::{Duration, Instant};
+
+pub const RELAY_CONFIG_FILE: &str = "relays.json";
+
+/// Largest message accepted from a relay
+pub const MAX_MESSAGE: usize = 4 * 1024 * 1024;
+
+/// Longest HTTP answer to the upgrade request
+const MAX_HANDSHAKE: usize = 16 * 1024;
+
+/// Appended to the key to form `Sec-WebSocket-Accept` (RFC 6455 §1.3)
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+// ============================================================================
+// Config
+// ============================================================================
+
+/// Relays Nostr flows publish to and read from (`.frost_state/relays.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelayConfig {
+    #[serde(default)]
+    pub relays: Vec<String>,
+}
+
+pub fn load_config(storage: &dyn Storage) -> Result<RelayConfig> {
+    if !storage.exists(RELAY_CONFIG_FILE) {
+        return Ok(RelayConfig::default());
+    }
+    serde_json::from_slice(&storage.read(RELAY_CONFIG_FILE)?).context("Failed to parse relays.json")
+}
+
+fn save_config(storage: &dyn Storage, config: &RelayConfig) -> Result<()> {
+    storage.write(
+        RELAY_CONFIG_FILE,
+        serde_json::to_string_pretty(config)?.as_bytes(),
+    )
+}
+
+/// Check a relay address and write it the way it is stored
+pub fn normalize(url: &str) -> Result<String> {
+    let url = url.trim().trim_end_matches('/');
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| crate::error::Error::User(format!("Invalid relay URL '{}': {}", url, e)))?;
+    if !matches!(parsed.scheme(), "ws" | "wss") {
+        bail!(crate::error::Error::User(format!(
+            "Relay URL '{}' must start with wss:// (or ws://)",
+            url
+        )));
+    }
+    if parsed.host_str().is_none() {
+        bail!(crate::error::Error::User(format!(
+            "Relay URL '{}' has no host",
+            url
+        )));
+    }
+    Ok(url.to_string())
+}
+
+/// The relays for this run: `--relay` if given, else the configured ones
+/// when `nostr` is set, else none
+pub fn resolve(nostr: bool, relays: &[String]) -> Result<Option<Vec<String>>> {
+    if !relays.is_empty() {
+        return relays
+            .iter()
+            .map(|url| normalize(url))
+            .collect::<Result<_>>()
+            .map(Some);
+    }
+    if !nostr {
+        return Ok(None);
+    }
+    let configured = load_config(&FileStorage::new(STATE_ROOT)?)?.relays;
+    if configured.is_empty() {
+        bail!(crate::error::Error::User(
+            "No relays configured. Add one with `frostdao relay-config --add wss://…` or pass --relay."
+                .to_string()
+        ));
+    }
+    Ok(Some(configured))
+}
+
+pub fn configure_core(
+    add: Option<&str>,
+    remove: Option<&str>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let mut config = load_config(storage)?;
+
+    if let Some(url) = add {
+        let url = normalize(url)?;
+        if !config.relays.contains(&url) {
+            config.relays.push(url);
+        }
+    }
+    if let Some(url) = remove {
+        let url = url.trim().trim_end_matches('/');
+        let before = config.relays.len();
+        config.relays.retain(|entry| entry != url);
+        if config.relays.len() == before {
+            bail!(crate::error::Error::User(format!(
+                "{} is not a configured relay",
+                url
+            )));
+        }
+    }
+    if add.is_none() && remove.is_none() {
+        config.relays.clear();
+    }
+    save_config(storage, &config)?;
+
+    let mut out = String::new();
+    out.push_str("Nostr Relays\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    if config.relays.is_empty() {
+        out.push_str("Relays: none (Nostr flows need --relay)\n");
+    }
+    for url in &config.relays {
+        out.push_str(&format!("Relay:  {}\n", url));
+    }
+
+    let result = serde_json::json!({
+        "relays": config.relays,
+        "type": "relay_config",
+    });
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string_pretty(&result)?,
+    })
+}
+
+/// Add, remove or (with neither) clear the configured relays
+pub fn configure(add: Option<&str>, remove: Option<&str>) -> Result<()> {
+    let storage = FileStorage::new(STATE_ROOT)?;
+    let result = configure_core(add, remove, &storage)?;
+    print!("{}", result.output);
+    println!("{}", result.result);
+    Ok(())
+}
+
+// ============================================================================
+// Websocket
+// ============================================================================
+
+/// One websocket frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Frame {
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+/// Encode a frame; clients mask everything they send, servers nothing
+fn encode_frame(opcode: u8, fin: bool, payload: &[u8], mask: Option<[u8; 4]>) -> Vec<u8> {
+    let mut bytes = vec![(if fin { 0x80 } else { 0 }) | opcode];
+    let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+    match payload.len() {
+        len if len < 126 => bytes.push(mask_bit | len as u8),
+        len if len <= u16::MAX as usize => {
+            bytes.push(mask_bit | 126);
+            bytes.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            bytes.push(mask_bit | 127);
+            bytes.extend((len as u64).to_be_bytes());
+        }
+    }
+    match mask {
+        Some(mask) => {
+            bytes.extend(mask);
+            bytes.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        }
+        None => bytes.extend(payload),
+    }
+    bytes
+}
+
+/// Take the first complete frame off `buffer`, if it holds one
+fn take_frame(buffer: &mut Vec<u8>) -> Result<Option<Frame>> {
+    if buffer.len() < 2 {
+        return Ok(None);
+    }
+    let (len, mut at) = match buffer[1] & 0x7f {
+        126 if buffer.len() >= 4 => (u16::from_be_bytes([buffer[2], buffer[3]]) as u64, 4),
+        127 if buffer.len() >= 10 => (
+            u64::from_be_bytes(buffer[2..10].try_into().expect("8 bytes")),
+            10,
+        ),
+        126 | 127 => return Ok(None),
+        len => (len as u64, 2),
+    };
+    if len > MAX_MESSAGE as u64 {
+        bail!(crate::error::Error::Network(format!(
+            "Relay sent a {} byte frame (limit {})",
+            len, MAX_MESSAGE
+        )));
+    }
+    let mask = if buffer[1] & 0x80 != 0 {
+        if buffer.len() < at + 4 {
+            return Ok(None);
+        }
+        at += 4;
+        Some([
+            buffer[at - 4],
+            buffer[at - 3],
+            buffer[at - 2],
+            buffer[at - 1],
+        ])
+    } else {
+        None
+    };
+    let end = at + len as usize;
+    if buffer.len() < end {
+        return Ok(None);
+    }
+    let mut payload = buffer[at..end].to_vec();
+    if let Some(mask) = mask {
+        payload
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, b)| *b ^= mask[i % 4]);
+    }
+    let frame = Frame {
+        fin: buffer[0] & 0x80 != 0,
+        opcode: buffer[0] & 0x0f,
+        payload,
+    };
+    buffer.drain(..end);
+    Ok(Some(frame))
+}
+
+/// `Sec-WebSocket-Accept` for a client's `Sec-WebSocket-Key`
+fn accept_key(key: &str) -> String {
+    let digest = sha1::Hash::hash(format!("{}{}", key, ACCEPT_GUID).as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(digest.to_byte_array())
+}
+
+/// A websocket connection to a relay
+pub struct WebSocket {
+    url: String,
+    stream: Stream,
+    /// Bytes read but not yet taken as frames
+    buffer: Vec<u8>,
+    /// Payload of a fragmented message so far, and its opcode
+    message: Vec<u8>,
+    message_opcode: u8,
+}
+
+impl WebSocket {
+    /// Connect and complete the upgrade handshake
+    pub fn connect(url: &str) -> Result<Self> {
+        let url = normalize(url)?;
+        let parsed = reqwest::Url::parse(&url)?;
+        let tls = parsed.scheme() == "wss";
+        let host = parsed.host_str().context("Relay URL has no host")?;
+        let port = parsed
+            .port_or_known_default()
+            .unwrap_or(if tls { 443 } else { 80 });
+        // IPv6 literals come bracketed, which suits the Host header only
+        let address = host.trim_start_matches('[').trim_end_matches(']');
+        let stream = Stream::connect(address, port, tls, &url)?;
+        let mut socket = Self {
+            url: url.clone(),
+            stream,
+            buffer: Vec::new(),
+            message: Vec::new(),
+            message_opcode: OP_TEXT,
+        };
+
+        let host_header = match parsed.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+        socket.handshake(&host_header, &path)?;
+        Ok(socket)
+    }
+
+    fn handshake(&mut self, host: &str, path: &str) -> Result<()> {
+        let key = base64::engine::general_purpose::STANDARD.encode(rand::random::<[u8; 16]>());
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            path, host, key
+        );
+        self.stream
+            .write_all(request.as_bytes())
+            .with_context(|| format!("Failed to send the websocket upgrade to {}", self.url))?;
+
+        let end = loop {
+            if let Some(at) = self.buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                break at;
+            }
+            if self.buffer.len() > MAX_HANDSHAKE {
+                bail!(crate::error::Error::Network(format!(
+                    "{} sent an oversized upgrade answer",
+                    self.url
+                )));
+            }
+            let mut chunk = [0u8; 4096];
+            let read = self.stream.read(&mut chunk).map_err(|e| {
+                crate::error::Error::Network(format!(
+                    "{} did not answer the upgrade: {}",
+                    self.url, e
+                ))
+            })?;
+            if read == 0 {
+                bail!(crate::error::Error::Network(format!(
+                    "{} closed the connection during the upgrade",
+                    self.url
+                )));
+            }
+            self.buffer.extend_from_slice(&chunk[..read]);
+        };
+        let head = String::from_utf8_lossy(&self.buffer[..end]).to_string();
+        self.buffer.drain(..end + 4);
+
+        let mut lines = head.lines();
+        let status = lines.next().unwrap_or_default();
+        if status.split_whitespace().nth(1) != Some("101") {
+            bail!(crate::error::Error::Network(format!(
+                "{} refused the websocket upgrade: {}",
+                self.url, status
+            )));
+        }
+        let accept = lines.find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("sec-websocket-accept")
+                .then(|| value.trim().to_string())
+        });
+        if accept.as_deref() != Some(accept_key(&key).as_str()) {
+            bail!(crate::error::Error::Network(format!(
+                "{} answered the upgrade with a wrong Sec-WebSocket-Accept",
+                self.url
+            )));
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self, opcode: u8, payload: &[u8]) -> Result<()> {
+        let frame = encode_frame(opcode, true, payload, Some(rand::random()));
+        self.stream
+            .write_all(&frame)
+            .map_err(|e| crate::error::Error::Network(format!("Lost {}: {}", self.url, e)))?;
+        Ok(())
+    }
+
+    /// A complete text message from the frames already read, if any
+    fn take_message(&mut self) -> Result<Option<String>> {
+        while let Some(frame) = take_frame(&mut self.buffer)? {
+            match frame.opcode {
+                OP_PING => self.write_frame(OP_PONG, &frame.payload)?,
+                OP_CLOSE => {
+                    let _ = self.write_frame(OP_CLOSE, &frame.payload);
+                    bail!(crate::error::Error::Network(format!(
+                        "{} closed the connection",
+                        self.url
+                    )));
+                }
+                OP_PONG => {}
+                opcode => {
+                    if opcode != OP_CONTINUATION {
+                        self.message.clear();
+                        self.message_opcode = opcode;
+                    }
+                    self.message.extend(frame.payload);
+                    if self.message.len() > MAX_MESSAGE {
+                        bail!(crate::error::Error::Network(format!(
+                            "{} sent a message over {} bytes",
+                            self.url, MAX_MESSAGE
+                        )));
+                    }
+                    // Relays only speak text; anything else is dropped
+                    if frame.fin && self.message_opcode == OP_TEXT {
+                        let message = std::mem::take(&mut self.message);
+                        return Ok(Some(String::from_utf8(message).map_err(|_| {
+                            crate::error::Error::Network(format!(
+                                "{} sent a text message that is not UTF-8",
+                                self.url
+                            ))
+                        })?));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Connection for WebSocket {
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn send(&mut self, frame: &str) -> Result<()> {
+        self.write_frame(OP_TEXT, frame.as_bytes())
+    }
+
+    fn recv(&mut self, timeout: Duration) -> Result<Option<String>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(message) = self.take_message()? {
+                return Ok(Some(message));
+            }
+            // A zero timeout would block forever; always wait a little
+            let remaining = deadline
+                .saturating_duration_since(Instant::now())
+                .max(Duration::from_millis(1));
+            self.stream.tcp().set_read_timeout(Some(remaining))?;
+            let mut chunk = [0u8; 16 * 1024];
+            match self.stream.read(&mut chunk) {
+                Ok(0) => bail!(crate::error::Error::Network(format!(
+                    "{} closed the connection",
+                    self.url
+                ))),
+                Ok(read) => self.buffer.extend_from_slice(&chunk[..read]),
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    if Instant::now() >= deadline {
+                        return Ok(None);
+                    }
+                }
+                Err(e) => bail!(crate::error::Error::Network(format!(
+                    "Lost {}: {}",
+                    self.url, e
+                ))),
+            }
+        }
+    }
+}
+
+/// Connect to each of `urls`; ones that can't be reached are reported on
+/// stderr and skipped, as long as one can
+pub fn connect_all(urls: &[String]) -> Result<Relays<'static>> {
+    let mut relays = Vec::new();
+    let mut failures = Vec::new();
+    for url in urls {
+        match WebSocket::connect(url) {
+            Ok(socket) => relays.push(Relay::new(socket)),
+            Err(e) => failures.push(format!("{}: {:#}", url, e)),
+        }
+    }
+    if relays.is_empty() {
+        bail!(crate::error::Error::Network(format!(
+            "Could not reach any relay: {}",
+            failures.join("; ")
+        )));
+    }
+    for failure in failures {
+        eprintln!("⚠️  Relay skipped, {}", failure);
+    }
+    Ok(Relays::new(relays))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::identity::IdentityKey;
+    use crate::protocol::nostr::{self, KIND_GIFT_WRAP};
+    use crate::protocol::relay::{Filter, MockRelay};
+    use crate::storage::MemoryStorage;
+    use std::net::{TcpListener, TcpStream};
+
+    /// Read from `stream` until `buffer` holds a frame
+    fn read_frame(stream: &mut TcpStream, buffer: &mut Vec<u8>) -> Option<Frame> {
+        loop {
+            if let Some(frame) = take_frame(buffer).unwrap() {
+                return Some(frame);
+            }
+            let mut chunk = [0u8; 4096];
+            match stream.read(&mut chunk) {
+                Ok(0) | Err(_) => return None,
+                Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+            }
+        }
+    }
+
+    /// A websocket front for a [`MockRelay`], serving `clients` connections
+    /// one after another. It pings first and sends every reply in two
+    /// fragments, so the client has to handle both.
+    fn ws_relay(clients: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/nostr", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let mut relay = MockRelay::new();
+            for _ in 0..clients {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = Vec::new();
+                let head = loop {
+                    let mut chunk = [0u8; 4096];
+                    let read = stream.read(&mut chunk).unwrap();
+                    buffer.extend_from_slice(&chunk[..read]);
+                    if let Some(at) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                        let head = String::from_utf8(buffer[..at].to_vec()).unwrap();
+                        buffer.drain(..at + 4);
+                        break head;
+                    }
+                };
+                assert!(head.starts_with("GET /nostr HTTP/1.1"), "{}", head);
+                let key = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix("Sec-WebSocket-Key: "))
+                    .unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                     Connection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                    accept_key(key)
+                )
+                .unwrap();
+                stream
+                    .write_all(&encode_frame(OP_PING, true, b"hi", None))
+                    .unwrap();
+
+                let id = relay.connect();
+                while let Some(frame) = read_frame(&mut stream, &mut buffer) {
+                    match frame.opcode {
+                        OP_TEXT => {
+                            let text = String::from_utf8(frame.payload).unwrap();
+                            let mut replies = relay.handle(id, &text);
+                            replies.extend(relay.poll(id));
+                            for reply in replies {
+                                let (a, b) = reply.as_bytes().split_at(reply.len() / 2);
+                                stream
+                                    .write_all(&encode_frame(OP_TEXT, false, a, None))
+                                    .unwrap();
+                                stream
+                                    .write_all(&encode_frame(OP_CONTINUATION, true, b, None))
+                                    .unwrap();
+                            }
+                        }
+                        OP_PONG => assert_eq!(frame.payload, b"hi"),
+                        _ => break,
+                    }
+                }
+                relay.disconnect(id);
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_websocket_publishes_and_fetches() {
+        let url = ws_relay(2);
+        let alice = IdentityKey::generate();
+        let bob = IdentityKey::generate();
+        let wrap = nostr::gift_wrap(&alice, &bob.public_hex(), "over the wire").unwrap();
+
+        let mut relays = connect_all(std::slice::from_ref(&url)).unwrap();
+        assert_eq!(relays.urls(), vec![url.as_str()]);
+        assert_eq!(relays.publish(&wrap).unwrap(), vec![url.clone()]);
+        let mut tampered = wrap.clone();
+        tampered.content.push('A');
+        assert!(relays.publish(&tampered).is_err());
+        drop(relays);
+
+        let mut relays = connect_all(&[url]).unwrap();
+        let filter = Filter::kinds(&[KIND_GIFT_WRAP]).tag('p', &[&bob.public_hex()]);
+        let stored = relays.subscribe("bob", vec![filter]).unwrap();
+        assert_eq!(stored, vec![wrap]);
+        assert_eq!(
+            nostr::unwrap_gift(&bob, &stored[0]).unwrap().1,
+            "over the wire"
+        );
+    }
+
+    #[test]
+    fn test_frames_round_trip() {
+        for len in [0, 125, 126, 70_000] {
+            let payload = vec![7u8; len];
+            let mut buffer = encode_frame(OP_TEXT, true, &payload, Some([1, 2, 3, 4]));
+            buffer.push(0x81); // start of the next frame stays put
+            let frame = take_frame(&mut buffer).unwrap().unwrap();
+            assert_eq!((frame.fin, frame.opcode), (true, OP_TEXT));
+            assert_eq!(frame.payload, payload);
+            assert_eq!(buffer, vec![0x81]);
+        }
+        let mut partial = encode_frame(OP_TEXT, true, b"hello", None);
+        partial.pop();
+        assert_eq!(take_frame(&mut partial).unwrap(), None);
+
+        // RFC 6455 §1.3 example
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_unreachable_relays_are_skipped_unless_all_are() {
+        // A privileged port nothing in the test run can bind, unlike a freed
+        // ephemeral port that a parallel test's mock relay may pick up.
+        let dead = "ws://127.0.0.1:1".to_string();
+        assert!(connect_all(std::slice::from_ref(&dead)).is_err());
+
+        let live = ws_relay(1);
+        let relays = connect_all(&[dead, live.clone()]).unwrap();
+        assert_eq!(relays.urls(), vec![live.as_str()]);
+    }
+
+    #[test]
+    fn test_configure_adds_and_removes_relays() {
+        let storage = MemoryStorage::new();
+        configure_core(Some("wss://relay.example.com/"), None, &storage).unwrap();
+        configure_core(Some("ws://127.0.0.1:7777"), None, &storage).unwrap();
+        configure_core(Some("wss://relay.example.com"), None, &storage).unwrap();
+        assert_eq!(
+            load_config(&storage).unwrap().relays,
+            vec!["wss://relay.example.com", "ws://127.0.0.1:7777"]
+        );
+
+        assert!(configure_core(Some("https://relay.example.com"), None, &storage).is_err());
+        assert!(configure_core(None, Some("wss://other.example.com"), &storage).is_err());
+
+        configure_core(None, Some("ws://127.0.0.1:7777/"), &storage).unwrap();
+        assert_eq!(
+            load_config(&storage).unwrap().relays,
+            vec!["wss://relay.example.com"]
+        );
+        configure_core(None, None, &storage).unwrap();
+        assert!(load_config(&storage).unwrap().relays.is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+//! Protocol Output Reporters
+//!
+//! The cores describe what they do as [`ProtocolEvent`]s and hand them to a
+//! [`Reporter`], which decides what the user sees:
+//!
+//! - [`VerboseReporter`]: the CLI default, prints the full 🧠/❄️ walkthrough
+//! - [`QuietReporter`]: `--quiet`, prints warnings only
+//! - [`NoopReporter`]: callers that only want the returned `CommandResult`
+//! - [`RecordingReporter`]: keeps everything, for tests and simulations
+//!
+//! Frontends (TUI, WASM) implement the trait themselves.
+
+use crate::protocol::events::ProtocolEvent;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Receives a core's events as they happen and its result at the end
+pub trait Reporter {
+    /// Tutorial text: explanations and questions for the reader
+    fn explain(&mut self, event: &ProtocolEvent);
+
+    /// Something the user should look at even in quiet mode
+    fn warn(&mut self, event: &ProtocolEvent);
+
+    /// Everything else: round headers, checks, progress, next steps
+    fn progress(&mut self, event: &ProtocolEvent);
+
+    /// The step's payload, once every check has passed
+    fn result(&mut self, result: &str);
+
+    /// Route an event to the matching method
+    fn report(&mut self, event: &ProtocolEvent) {
+        match event {
+            ProtocolEvent::Explain { .. } | ProtocolEvent::Question { .. } => self.explain(event),
+            ProtocolEvent::Warning { .. } => self.warn(event),
+            _ => self.progress(event),
+        }
+    }
+}
+
+fn print_event(event: &ProtocolEvent) {
+    let mut out = String::new();
+    event.render(&mut out);
+    print!("{}", out);
+}
+
+/// Prints every event to stdout as it is emitted
+#[derive(Debug, Default)]
+pub struct VerboseReporter;
+
+impl Reporter for VerboseReporter {
+    fn explain(&mut self, event: &ProtocolEvent) {
+        print_event(event);
+    }
+
+    fn warn(&mut self, event: &ProtocolEvent) {
+        print_event(event);
+    }
+
+    fn progress(&mut self, event: &ProtocolEvent) {
+        print_event(event);
+    }
+
+    fn result(&mut self, _result: &str) {}
+}
+
+/// Prints warnings to stderr and nothing else
+#[derive(Debug, Default)]
+pub struct QuietReporter;
+
+impl Reporter for QuietReporter {
+    fn explain(&mut self, _event: &ProtocolEvent) {}
+
+    fn warn(&mut self, event: &ProtocolEvent) {
+        let mut out = String::new();
+        event.render(&mut out);
+        eprint!("{}", out);
+    }
+
+    fn progress(&mut self, _event: &ProtocolEvent) {}
+
+    fn result(&mut self, _result: &str) {}
+}
+
+/// Discards everything
+#[derive(Debug, Default)]
+pub struct NoopReporter;
+
+impl Reporter for NoopReporter {
+    fn explain(&mut self, _event: &ProtocolEvent) {}
+    fn warn(&mut self, _event: &ProtocolEvent) {}
+    fn progress(&mut self, _event: &ProtocolEvent) {}
+    fn result(&mut self, _result: &str) {}
+}
+
+/// Keeps every event and the result for later inspection
+#[derive(Debug, Default)]
+pub struct RecordingReporter {
+    pub events: Vec<ProtocolEvent>,
+    pub result: Option<String>,
+}
+
+impl RecordingReporter {
+    /// Warnings seen so far
+    pub fn warnings(&self) -> impl Iterator<Item = &ProtocolEvent> {
+        self.events
+            .iter()
+            .filter(|event| matches!(event, ProtocolEvent::Warning { .. }))
+    }
+}
+
+impl Reporter for RecordingReporter {
+    fn explain(&mut self, event: &ProtocolEvent) {
+        self.events.push(event.clone());
+    }
+
+    fn warn(&mut self, event: &ProtocolEvent) {
+        self.events.push(event.clone());
+    }
+
+    fn progress(&mut self, event: &ProtocolEvent) {
+        self.events.push(event.clone());
+    }
+
+    fn result(&mut self, result: &str) {
+        self.result = Some(result.to_string());
+    }
+}
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Switch CLI commands to [`QuietReporter`] (`--quiet`)
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Reporter for a CLI command, honouring `--quiet`
+pub fn cli_reporter() -> Box<dyn Reporter> {
+    if is_quiet() {
+        Box::new(QuietReporter)
+    } else {
+        Box::new(VerboseReporter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::keygen;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_recording_reporter_captures_round1() {
+        let storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::default();
+        let cmd_result =
+            keygen::round1_core_with_reporter(2, 3, 1, 0, false, &storage, &mut reporter).unwrap();
+
+        assert_eq!(reporter.result.as_deref(), Some(cmd_result.result.as_str()));
+        assert_eq!(
+            reporter.events.first(),
+            Some(&ProtocolEvent::Started {
+                title: "FROST Keygen (TSS) - Round 1".to_string()
+            })
+        );
+        assert!(reporter
+            .events
+            .iter()
+            .any(|event| matches!(event, ProtocolEvent::Explain { .. })));
+
+        let mut rendered = String::new();
+        for event in &reporter.events {
+            event.render(&mut rendered);
+        }
+        assert_eq!(rendered, cmd_result.output);
+    }
+}
@@ -19,8 +19,15 @@
 //!
 //! Result: New shares s'_j for the same group secret s
 
-use crate::protocol::keygen::{get_state_dir, GroupInfo, HtssMetadata};
-use crate::storage::{FileStorage, Storage};
+use crate::crypto::secret::{serialize_secret, SecretBytes};
+use crate::protocol::audit;
+use crate::protocol::health;
+use crate::protocol::identity::{self, Authenticated, IdentityKey, Roster};
+use crate::protocol::keygen::{get_state_dir, require_secp256k1, GroupInfo, HtssMetadata};
+use crate::protocol::nostr;
+use crate::protocol::payload::{self, parse_payloads, Expected};
+use crate::storage::journal::Journaled;
+use crate::storage::{lock, migrations, FileStorage, Storage};
 use anyhow::Result;
 use schnorr_fun::frost;
 use schnorr_fun::fun::marker::*;
@@ -37,8 +44,36 @@ pub struct ReshareRound1Output {
     pub sub_shares: BTreeMap<u32, String>,
     /// Commitment to the polynomial (for verification)
     pub polynomial_commitment: Vec<String>,
+    /// Identity key of each new party (index → hex), vouched for by this
+    /// old party's signature; empty for a wallet without an identity roster
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub new_identities: BTreeMap<u32, String>,
+    /// Payload protocol version (see protocol::payload); absent means v1
+    #[serde(
+        default = "crate::protocol::payload::legacy_version",
+        skip_serializing_if = "crate::protocol::payload::is_legacy_version"
+    )]
+    pub protocol_version: u32,
     #[serde(rename = "type")]
     pub event_type: String,
+    /// Sender's signature over this payload (see protocol::identity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+}
+
+impl Authenticated for ReshareRound1Output {
+    fn signer_index(&self) -> u32 {
+        self.old_party_index
+    }
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+    fn set_auth(&mut self, auth: String) {
+        self.auth = Some(auth);
+    }
 }
 
 /// Generate sub-shares for resharing (old party runs this)
@@ -47,12 +82,17 @@ pub fn reshare_round1(
     new_threshold: u32,
     new_n_parties: u32,
     my_old_index: u32,
+    new_identities: &[String],
 ) -> Result<()> {
     let state_dir = get_state_dir(source_wallet);
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!("Wallet '{}' not found at {}.", source_wallet, state_dir);
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}.",
+            source_wallet, state_dir
+        ))
+        .into());
     }
 
     let storage = FileStorage::new(&state_dir)?;
@@ -60,8 +100,10 @@ pub fn reshare_round1(
     println!("Reshare Round 1 - Generate Sub-shares\n");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
+    require_secp256k1(&storage)?;
+
     // Load my secret share
-    let paired_share_bytes = storage.read("paired_secret_share.bin")?;
+    let paired_share_bytes = storage.read_secret("paired_secret_share.bin")?;
     let paired_share: frost::PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
 
     // Load HTSS metadata for verification
@@ -138,25 +180,36 @@ pub fn reshare_round1(
     println!("Generated sub-shares for {} new parties", new_n_parties);
     println!();
 
+    let new_identities = new_party_identities(&storage, &storage, new_n_parties, new_identities)?;
+
     // Create output
-    let output = ReshareRound1Output {
+    let mut output = ReshareRound1Output {
         old_party_index: my_old_index,
         sub_shares,
         polynomial_commitment,
+        new_identities,
+        protocol_version: payload::output_version(),
         event_type: "reshare_round1".to_string(),
+        auth: None,
     };
+    identity::sign_payload(&mut output, &storage)?;
 
     let result_json = serde_json::to_string(&output)?;
 
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Share this with the coordinator (or new parties):");
-    println!("{}\n", result_json);
+    println!("{}\n", crate::protocol::compact::present(&result_json));
     println!("⚠️  Keep your old share until resharing is complete!");
 
     Ok(())
 }
 
 /// Finalize resharing (new party runs this)
+///
+/// A threshold below the source wallet's is refused unless
+/// `allow_threshold_decrease` is set (`--allow-threshold-decrease`).
+/// With `verify_only`, runs every check without writing the target.
+#[allow(clippy::too_many_arguments)]
 pub fn reshare_finalize(
     source_wallet: &str,
     target_wallet: &str,
@@ -164,102 +217,32 @@ pub fn reshare_finalize(
     my_rank: u32,
     hierarchical: bool,
     round1_data: &str,
+    allow_threshold_decrease: bool,
+    verify_only: bool,
 ) -> Result<()> {
     println!("Reshare Finalize - Combine Sub-shares\n");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-    // Parse round1 outputs (space-separated JSON objects)
-    let round1_outputs: Vec<ReshareRound1Output> =
-        crate::protocol::keygen::parse_space_separated_json(round1_data)?;
-
-    if round1_outputs.is_empty() {
-        anyhow::bail!("No round1 data provided");
-    }
-
-    println!(
-        "Received sub-shares from {} old parties",
-        round1_outputs.len()
-    );
-    println!("My new index: {}", my_new_index);
-    println!("My rank: {}", my_rank);
-    println!();
-
-    // Load source wallet to get group public key
-    let source_state_dir = get_state_dir(source_wallet);
-    let source_storage = FileStorage::new(&source_state_dir)?;
-
-    let shared_key_bytes = source_storage.read("shared_key.bin")?;
-    let shared_key: frost::SharedKey<EvenY> = bincode::deserialize(&shared_key_bytes)?;
-    let group_public_key = shared_key.public_key();
-
-    let source_htss_json = String::from_utf8(source_storage.read("htss_metadata.json")?)?;
-    let source_htss: HtssMetadata = serde_json::from_str(&source_htss_json)?;
-    let old_threshold = source_htss.threshold;
-
-    // Verify we have enough sub-shares (need at least old_threshold)
-    if (round1_outputs.len() as u32) < old_threshold {
-        anyhow::bail!(
-            "Not enough sub-shares: got {}, need at least {}",
-            round1_outputs.len(),
-            old_threshold
-        );
-    }
-
-    println!(
-        "Old threshold: {} (have {} sub-shares)",
-        old_threshold,
-        round1_outputs.len()
-    );
-
-    // Get the new threshold from the polynomial commitment degree
-    let new_threshold = round1_outputs[0].polynomial_commitment.len() as u32;
-    let new_n_parties = round1_outputs[0].sub_shares.len() as u32;
-
-    println!("New config: {}-of-{}", new_threshold, new_n_parties);
-    println!();
-
-    // Collect old party indices for Lagrange computation
-    let old_indices: Vec<u32> = round1_outputs.iter().map(|o| o.old_party_index).collect();
-
-    // Compute my new share: sum of (lagrange_coeff * sub_share) for each old party
-    let mut new_share_bytes = [0u8; 32];
-
-    for output in &round1_outputs {
-        // Get sub-share for my new index
-        let sub_share_hex = output
-            .sub_shares
-            .get(&my_new_index)
-            .ok_or_else(|| anyhow::anyhow!("Missing sub-share for index {}", my_new_index))?;
-
-        let sub_share_bytes: [u8; 32] = hex::decode(sub_share_hex)?
-            .try_into()
-            .map_err(|_| anyhow::anyhow!("Invalid sub-share length"))?;
-
-        let sub_share: Scalar<Secret, Zero> = Scalar::from_bytes(sub_share_bytes)
-            .ok_or_else(|| anyhow::anyhow!("Invalid sub-share scalar"))?;
-
-        // Compute Lagrange coefficient for this old party at x=0
-        let lagrange_coeff = crate::crypto::helpers::lagrange_coefficient_at_zero(
-            output.old_party_index,
-            &old_indices,
+    if verify_only {
+        let cmd_result = reshare_verify_core(
+            source_wallet,
+            my_new_index,
+            round1_data,
+            allow_threshold_decrease,
         )?;
-
-        // Add weighted sub-share to result
-        let current: Scalar<Secret, Zero> =
-            Scalar::from_bytes(new_share_bytes).unwrap_or(Scalar::zero());
-        let weighted = s!(lagrange_coeff * sub_share);
-        let sum = s!(current + weighted);
-        new_share_bytes = sum.to_bytes();
+        println!("{}", cmd_result.output);
+        println!();
+        println!(
+            "Run again without --verify-only to create '{}'.",
+            target_wallet
+        );
+        return Ok(());
     }
 
-    println!("Computed new secret share");
-
-    // Create new wallet directory
-    let target_state_dir = get_state_dir(target_wallet);
-    let target_path = std::path::Path::new(&target_state_dir);
-
+    let target_path = std::path::PathBuf::from(get_state_dir(target_wallet));
     if target_path.exists() {
         println!("⚠️  Target wallet '{}' already exists", target_wallet);
+        println!("   Replacing it deletes its folder, and every file in it.");
         print!("   Replace? [y/N]: ");
         std::io::Write::flush(&mut std::io::stdout())?;
 
@@ -269,89 +252,20 @@ pub fn reshare_finalize(
             println!("Aborted.");
             return Ok(());
         }
-        std::fs::remove_dir_all(target_path)?;
     }
 
-    let target_storage = FileStorage::new(&target_state_dir)?;
-
-    // Create PairedSecretShare using helper function
-    let share_scalar: Scalar<Secret, Zero> = Scalar::from_bytes(new_share_bytes)
-        .ok_or_else(|| anyhow::anyhow!("Invalid computed share"))?;
-    let share_nonzero = crate::crypto::helpers::share_to_nonzero(share_scalar)?;
-
-    let paired_share = crate::crypto::helpers::construct_paired_secret_share(
+    let cmd_result = reshare_finalize_core(
+        source_wallet,
+        target_wallet,
         my_new_index,
-        share_nonzero,
-        &group_public_key,
-    )?;
-    let paired_bytes = bincode::serialize(&paired_share)?;
-
-    target_storage.write("paired_secret_share.bin", &paired_bytes)?;
-    target_storage.write("shared_key.bin", &shared_key_bytes)?;
-
-    // Create new HTSS metadata
-    let mut party_ranks: BTreeMap<u32, u32> = BTreeMap::new();
-    party_ranks.insert(my_new_index, my_rank);
-
-    // Add placeholder ranks for other parties (they'll update their own)
-    for i in 1..=new_n_parties {
-        if i != my_new_index {
-            party_ranks.insert(i, 0); // default rank
-        }
-    }
-
-    let new_htss = HtssMetadata {
-        my_index: my_new_index,
         my_rank,
-        threshold: new_threshold,
-        hierarchical,
-        party_ranks,
-    };
-
-    target_storage.write(
-        "htss_metadata.json",
-        serde_json::to_string_pretty(&new_htss)?.as_bytes(),
-    )?;
-
-    // Create group info
-    let pubkey_bytes: [u8; 32] = group_public_key.to_xonly_bytes();
-    let pubkey_hex = hex::encode(pubkey_bytes);
-
-    use bitcoin::{Address, Network, XOnlyPublicKey};
-    let xonly_pk = XOnlyPublicKey::from_slice(&pubkey_bytes)?;
-    let secp = bitcoin::secp256k1::Secp256k1::new();
-    let address_testnet = Address::p2tr(&secp, xonly_pk, None, Network::Testnet).to_string();
-    let address_mainnet = Address::p2tr(&secp, xonly_pk, None, Network::Bitcoin).to_string();
-
-    let group_info = GroupInfo {
-        name: target_wallet.to_string(),
-        group_public_key: pubkey_hex.clone(),
-        taproot_address_testnet: address_testnet.clone(),
-        taproot_address_mainnet: address_mainnet.clone(),
-        threshold: new_threshold,
-        total_parties: new_n_parties,
         hierarchical,
-        parties: vec![], // Will be populated when all parties complete
-    };
-
-    target_storage.write(
-        "group_info.json",
-        serde_json::to_string_pretty(&group_info)?.as_bytes(),
+        round1_data,
+        true,
+        allow_threshold_decrease,
     )?;
 
-    // Also save share in hex format for easy verification
-    target_storage.write("share_hex.txt", hex::encode(new_share_bytes).as_bytes())?;
-
-    println!();
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("✅ Resharing complete!");
-    println!();
-    println!("New wallet: {}", target_wallet);
-    println!("Config: {}-of-{}", new_threshold, new_n_parties);
-    println!("Your index: {}", my_new_index);
-    println!();
-    println!("Public Key: {}", pubkey_hex);
-    println!("Testnet Address: {}", address_testnet);
+    println!("{}", cmd_result.output);
     println!();
     println!("⚠️  The public key and address are the SAME as before!");
     println!("    Funds are still accessible with the new shares.");
@@ -376,12 +290,17 @@ pub fn reshare_round1_core(
     new_threshold: u32,
     new_n_parties: u32,
     my_old_index: u32,
+    new_identities: &[String],
 ) -> Result<CommandResult> {
     let state_dir = get_state_dir(source_wallet);
     let path = std::path::Path::new(&state_dir);
 
     if !path.exists() {
-        anyhow::bail!("Wallet '{}' not found at {}.", source_wallet, state_dir);
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}.",
+            source_wallet, state_dir
+        ))
+        .into());
     }
 
     // Find the party folder or use legacy structure
@@ -394,9 +313,37 @@ pub fn reshare_round1_core(
     };
 
     let storage = FileStorage::new(&storage_path)?;
+    let root_storage = FileStorage::new(&state_dir)?;
+    reshare_round1_with_storage(
+        source_wallet,
+        new_threshold,
+        new_n_parties,
+        my_old_index,
+        new_identities,
+        &storage,
+        &root_storage,
+    )
+}
+
+/// Reshare round 1 on injected storage (e.g. a browser store)
+///
+/// `storage` holds this party's share; HTSS metadata missing there is read
+/// from `root_storage` (the wallet root in the legacy layout). Pass the same
+/// store twice when everything lives in one place. `new_identities` is as
+/// for [`new_party_identities`].
+pub fn reshare_round1_with_storage(
+    source_wallet: &str,
+    new_threshold: u32,
+    new_n_parties: u32,
+    my_old_index: u32,
+    new_identities: &[String],
+    storage: &dyn Storage,
+    root_storage: &dyn Storage,
+) -> Result<CommandResult> {
+    require_secp256k1(storage)?;
 
     // Load my secret share
-    let paired_share_bytes = storage.read("paired_secret_share.bin")?;
+    let paired_share_bytes = storage.read_secret("paired_secret_share.bin")?;
     let paired_share: frost::PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
 
     // Load HTSS metadata - try party folder first, then wallet root
@@ -406,7 +353,6 @@ pub fn reshare_round1_core(
             serde_json::from_str(&htss_str)?
         } else {
             // Try wallet root for legacy
-            let root_storage = FileStorage::new(&state_dir)?;
             let htss_json = String::from_utf8(root_storage.read("htss_metadata.json")?)?;
             serde_json::from_str(&htss_json)?
         }
@@ -467,12 +413,18 @@ pub fn reshare_round1_core(
         sub_shares.insert(new_idx, hex::encode(result));
     }
 
-    let output = ReshareRound1Output {
+    let new_identities =
+        new_party_identities(storage, root_storage, new_n_parties, new_identities)?;
+    let mut output = ReshareRound1Output {
         old_party_index: my_old_index,
         sub_shares,
         polynomial_commitment,
+        new_identities,
+        protocol_version: payload::output_version(),
         event_type: "reshare_round1".to_string(),
+        auth: None,
     };
+    identity::sign_payload(&mut output, storage)?;
 
     let result_json = serde_json::to_string(&output)?;
 
@@ -486,7 +438,65 @@ pub fn reshare_round1_core(
     })
 }
 
+/// Identity keys of the new parties, for an old party to vouch for in round 1
+///
+/// A new index keeps the key pinned for it in the source wallet (the usual
+/// case: the same people, the same indexes); `new_identities` entries
+/// (`<index>=<key>`, hex or npub) name a joining party or one whose index
+/// changed. A wallet with a roster must name every new party. One without
+/// stays unauthenticated unless keys are given.
+pub fn new_party_identities(
+    storage: &dyn Storage,
+    root_storage: &dyn Storage,
+    new_n_parties: u32,
+    new_identities: &[String],
+) -> Result<BTreeMap<u32, String>> {
+    let source = match Roster::load(storage)? {
+        Some(roster) => Some(roster),
+        None => Roster::load(root_storage)?,
+    };
+    let mut roster = Roster::default();
+    for entry in new_identities {
+        let (index, key) = entry.split_once('=').ok_or_else(|| {
+            crate::error::Error::User(format!(
+                "Expected --new-identity <index>=<key>, got '{}'",
+                entry
+            ))
+        })?;
+        let index: u32 = index
+            .trim()
+            .parse()
+            .ok()
+            .filter(|index| (1..=new_n_parties).contains(index))
+            .ok_or_else(|| {
+                crate::error::Error::User(format!(
+                    "--new-identity index must be 1..={}, got '{}'",
+                    new_n_parties, index
+                ))
+            })?;
+        roster.pin(index, &nostr::parse_public_key(key)?)?;
+    }
+    if let Some(source) = &source {
+        for (index, key) in source.0.range(1..=new_n_parties) {
+            if !roster.0.contains_key(index) {
+                roster.pin(*index, key)?;
+            }
+        }
+    }
+    if source.is_some() || !new_identities.is_empty() {
+        if let Some(missing) = (1..=new_n_parties).find(|i| !roster.0.contains_key(i)) {
+            anyhow::bail!(crate::error::Error::User(format!(
+                "New party {} has no identity key; pass --new-identity {}=<key> \
+                 (dkg-identity shows a party's key)",
+                missing, missing
+            )));
+        }
+    }
+    Ok(roster.0)
+}
+
 /// Core function for reshare finalize (returns output instead of printing)
+#[allow(clippy::too_many_arguments)]
 pub fn reshare_finalize_core(
     source_wallet: &str,
     target_wallet: &str,
@@ -495,19 +505,392 @@ pub fn reshare_finalize_core(
     hierarchical: bool,
     round1_data: &str,
     force_overwrite: bool,
+    allow_threshold_decrease: bool,
+) -> Result<CommandResult> {
+    reshare_finalize_in_dirs(
+        &get_state_dir(source_wallet),
+        &get_state_dir(target_wallet),
+        target_wallet,
+        my_new_index,
+        my_rank,
+        hierarchical,
+        round1_data,
+        force_overwrite,
+        allow_threshold_decrease,
+    )
+}
+
+/// Reshare finalize between two wallet folders
+///
+/// Once the sub-shares check out, an existing `target_dir` is removed whole
+/// (with `force_overwrite`) so nothing of the wallet it held, such as HD
+/// metadata, script trees, nonces or webhooks, outlives the new key.
+#[allow(clippy::too_many_arguments)]
+pub fn reshare_finalize_in_dirs(
+    source_dir: &str,
+    target_dir: &str,
+    target_wallet: &str,
+    my_new_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    round1_data: &str,
+    force_overwrite: bool,
+    allow_threshold_decrease: bool,
+) -> Result<CommandResult> {
+    let source_storage = FileStorage::new(source_dir)?;
+    let new_share = combine_sub_shares(
+        round1_data,
+        my_new_index,
+        allow_threshold_decrease,
+        &source_storage,
+    )?;
+
+    if !lock::is_unused(std::path::Path::new(target_dir)) {
+        if !force_overwrite {
+            anyhow::bail!(
+                "Target wallet '{}' already exists. Use force_overwrite=true to replace.",
+                target_wallet
+            );
+        }
+        if std::fs::canonicalize(target_dir)? == std::fs::canonicalize(source_dir)? {
+            anyhow::bail!(crate::error::Error::User(
+                "The target is the source wallet; reshare into a new wallet".to_string()
+            ));
+        }
+        // The lock on the target (see main.rs) stays in place
+        lock::clear_dir(std::path::Path::new(target_dir))?;
+    }
+
+    let target_storage = FileStorage::new(target_dir)?;
+    write_reshared_wallet(
+        new_share,
+        target_wallet,
+        my_new_index,
+        my_rank,
+        hierarchical,
+        &source_storage,
+        &target_storage,
+    )
+}
+
+/// Reshare finalize on injected storage (e.g. a browser store)
+///
+/// Without `force_overwrite`, refuses a target that already holds a key.
+/// Keys are overwritten, not cleared, so pass an empty target store; wallet
+/// folders are cleared by [`reshare_finalize_in_dirs`].
+/// Without `allow_threshold_decrease`, refuses a new threshold below the
+/// source wallet's.
+#[allow(clippy::too_many_arguments)]
+pub fn reshare_finalize_with_storage(
+    target_wallet: &str,
+    my_new_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    round1_data: &str,
+    force_overwrite: bool,
+    allow_threshold_decrease: bool,
+    source_storage: &dyn Storage,
+    target_storage: &dyn Storage,
 ) -> Result<CommandResult> {
+    let new_share = combine_sub_shares(
+        round1_data,
+        my_new_index,
+        allow_threshold_decrease,
+        source_storage,
+    )?;
+    if target_storage.exists("shared_key.bin") && !force_overwrite {
+        anyhow::bail!(
+            "Target wallet '{}' already exists. Use force_overwrite=true to replace.",
+            target_wallet
+        );
+    }
+    write_reshared_wallet(
+        new_share,
+        target_wallet,
+        my_new_index,
+        my_rank,
+        hierarchical,
+        source_storage,
+        target_storage,
+    )
+}
+
+/// Core function for `reshare-finalize --verify-only`
+pub fn reshare_verify_core(
+    source_wallet: &str,
+    my_new_index: u32,
+    round1_data: &str,
+    allow_threshold_decrease: bool,
+) -> Result<CommandResult> {
+    let source_storage = FileStorage::new(&get_state_dir(source_wallet))?;
+    reshare_verify_with_storage(
+        my_new_index,
+        round1_data,
+        allow_threshold_decrease,
+        &source_storage,
+    )
+}
+
+/// Dry run of reshare finalize: every check, nothing written
+///
+/// Combines this party's new share and proves it belongs to the source
+/// wallet's group key, failing exactly where a real finalize would.
+pub fn reshare_verify_with_storage(
+    my_new_index: u32,
+    round1_data: &str,
+    allow_threshold_decrease: bool,
+    source_storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let new_share = combine_sub_shares(
+        round1_data,
+        my_new_index,
+        allow_threshold_decrease,
+        source_storage,
+    )?;
+    let change = &new_share.change;
+    let group_public_key = hex::encode(new_share.pubkey_bytes);
+
+    Ok(CommandResult {
+        output: format!(
+            "{}Dry run passed, nothing written.\n\
+             Config: {}-of-{} (was {}-of-{})\n\
+             Your index: {}\n\
+             Public Key: {}",
+            new_share.auth_note,
+            change.new_threshold,
+            change.new_n_parties,
+            change.old_threshold,
+            change.old_n_parties,
+            my_new_index,
+            group_public_key
+        ),
+        result: serde_json::to_string(&serde_json::json!({
+            "group_public_key": group_public_key,
+            "old_threshold": change.old_threshold,
+            "old_n_parties": change.old_n_parties,
+            "new_threshold": change.new_threshold,
+            "new_n_parties": change.new_n_parties,
+            "my_new_index": my_new_index,
+        }))?,
+    })
+}
+
+/// This party's new share, combined from the old parties' sub-shares
+struct ResharedShare {
+    paired_bytes: SecretBytes,
+    shared_key_bytes: Vec<u8>,
+    pubkey_bytes: [u8; 32],
+    change: ThresholdChange,
+    auth_note: String,
+    /// When the group key was created, carried over from the source wallet
+    created_at: Option<u64>,
+    /// New parties' identity keys as vouched for in round 1, if any
+    roster: Option<Roster>,
+}
+
+/// Old and new group parameters of a reshare, as recorded in the audit log
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct ThresholdChange {
+    old_threshold: u32,
+    old_n_parties: u32,
+    new_threshold: u32,
+    new_n_parties: u32,
+    /// Old parties whose sub-shares were combined
+    old_indices: Vec<u32>,
+    /// Set when the threshold went down with explicit confirmation
+    threshold_decrease_confirmed: bool,
+}
+
+impl ThresholdChange {
+    /// Warnings for a weaker group, or an error for an unconfirmed decrease
+    fn review(&self, allow_threshold_decrease: bool) -> Result<String> {
+        let mut warnings = String::new();
+        if self.new_threshold < self.old_threshold {
+            if !allow_threshold_decrease {
+                anyhow::bail!(
+                    "Reshare lowers the threshold from {}-of-{} to {}-of-{}: fewer parties \
+                     could then sign. Confirm with --allow-threshold-decrease \
+                     (allow_threshold_decrease=true) if that is intended.",
+                    self.old_threshold,
+                    self.old_n_parties,
+                    self.new_threshold,
+                    self.new_n_parties
+                );
+            }
+            warnings.push_str(&format!(
+                "⚠️  Threshold lowered from {} to {} (confirmed)\n",
+                self.old_threshold, self.new_threshold
+            ));
+        }
+        if self.new_threshold == 1 && self.new_n_parties > 1 {
+            warnings.push_str("⚠️  1-of-n: any single new party can spend alone\n");
+        } else if self.new_threshold * 2 <= self.new_n_parties {
+            warnings.push_str(&format!(
+                "⚠️  {}-of-{} is not a majority: a minority of parties can sign\n",
+                self.new_threshold, self.new_n_parties
+            ));
+        }
+        Ok(warnings)
+    }
+}
+
+/// Check that every round 1 output describes the same new group
+///
+/// All outputs must commit to the same threshold and carry sub-shares for
+/// the same new indices 1..=n', and come from distinct parties of the
+/// source wallet. Returns (new_threshold, new_n_parties).
+fn check_round1_consistency(
+    round1_outputs: &[ReshareRound1Output],
+    source_htss: &HtssMetadata,
+) -> Result<(u32, u32)> {
+    let first = &round1_outputs[0];
+    let new_threshold = first.polynomial_commitment.len() as u32;
+    let new_n_parties = first.sub_shares.len() as u32;
+
+    let mut seen = std::collections::BTreeSet::new();
+    for output in round1_outputs {
+        let index = output.old_party_index;
+        if !seen.insert(index) {
+            anyhow::bail!("Old party {} sent sub-shares more than once", index);
+        }
+        if !source_htss.party_ranks.is_empty() && !source_htss.party_ranks.contains_key(&index) {
+            anyhow::bail!(
+                "Old party {} is not a member of the source wallet (parties {:?})",
+                index,
+                source_htss.party_ranks.keys().collect::<Vec<_>>()
+            );
+        }
+        if output.polynomial_commitment.len() as u32 != new_threshold {
+            anyhow::bail!(
+                "Round 1 outputs disagree on the new threshold: party {} committed to {}, \
+                 party {} to {}",
+                first.old_party_index,
+                new_threshold,
+                index,
+                output.polynomial_commitment.len()
+            );
+        }
+        if !output.sub_shares.keys().eq(first.sub_shares.keys()) {
+            anyhow::bail!(
+                "Round 1 outputs disagree on the new parties: party {} sent sub-shares for {:?}, \
+                 party {} for {:?}",
+                first.old_party_index,
+                first.sub_shares.keys().collect::<Vec<_>>(),
+                index,
+                output.sub_shares.keys().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    if !first.sub_shares.keys().copied().eq(1..=new_n_parties) {
+        anyhow::bail!(
+            "New party indices must be 1..={}, got {:?}",
+            new_n_parties,
+            first.sub_shares.keys().collect::<Vec<_>>()
+        );
+    }
+    if new_threshold == 0 || new_threshold > new_n_parties {
+        anyhow::bail!(
+            "Invalid new configuration {}-of-{}: need 1 <= t <= n",
+            new_threshold,
+            new_n_parties
+        );
+    }
+    Ok((new_threshold, new_n_parties))
+}
+
+fn parse_commitment(commitment_hex: &str) -> Result<Point<Normal, Public, NonZero>> {
+    let commitment_bytes: [u8; 33] = hex::decode(commitment_hex)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid polynomial commitment length"))?;
+    Point::from_bytes(commitment_bytes)
+        .ok_or_else(|| anyhow::anyhow!("Invalid polynomial commitment point"))
+}
+
+/// Check a sub-share against its sender's polynomial commitment:
+/// sub_share * G == sum(C_k * x^k)
+fn verify_sub_share(
+    output: &ReshareRound1Output,
+    new_index: u32,
+    sub_share: &Scalar<Secret, Zero>,
+) -> Result<()> {
+    let x: Scalar<Public, Zero> = Scalar::from(new_index);
+    let mut x_pow: Scalar<Public, Zero> = Scalar::from(1u32);
+    let mut expected = Point::<NonNormal, Public, Zero>::zero();
+    for commitment_hex in &output.polynomial_commitment {
+        let commitment = parse_commitment(commitment_hex)?;
+        expected = g!(expected + x_pow * commitment);
+        x_pow = s!(x_pow * x).public();
+    }
+    if g!(sub_share * G) != expected {
+        anyhow::bail!(
+            "Sub-share from old party {} does not match its polynomial commitment",
+            output.old_party_index
+        );
+    }
+    Ok(())
+}
+
+/// Prove the new shares still open the old group key
+///
+/// Each old party commits to its share as the constant term C_i0 = s_i * G,
+/// so sum(lambda_i * C_i0) is the secret the new polynomial shares out, in
+/// the exponent. It must equal the source wallet's group key, and so must
+/// the key named in the source's group_info.json.
+fn verify_group_key_preserved(
+    round1_outputs: &[ReshareRound1Output],
+    old_indices: &[u32],
+    group_public_key: &Point<EvenY>,
+    source_storage: &dyn Storage,
+) -> Result<()> {
+    let mut reconstructed = Point::<NonNormal, Public, Zero>::zero();
+    for output in round1_outputs {
+        let constant_term = parse_commitment(&output.polynomial_commitment[0])?;
+        let lagrange_coeff = crate::crypto::helpers::lagrange_coefficient_at_zero(
+            output.old_party_index,
+            old_indices,
+        )?;
+        reconstructed = g!(reconstructed + lagrange_coeff * constant_term);
+    }
+    if reconstructed != *group_public_key {
+        anyhow::bail!(
+            "Group key mismatch: the sub-shares reconstruct a different key than {}. \
+             Refusing to write the new wallet.",
+            hex::encode(group_public_key.to_xonly_bytes())
+        );
+    }
+
+    if source_storage.exists("group_info.json") {
+        let info: GroupInfo = serde_json::from_slice(&source_storage.read("group_info.json")?)?;
+        let key_hex = hex::encode(group_public_key.to_xonly_bytes());
+        if info.group_public_key != key_hex {
+            anyhow::bail!(
+                "Group key mismatch: source group_info.json names {} but shared_key.bin holds {}",
+                info.group_public_key,
+                key_hex
+            );
+        }
+    }
+    Ok(())
+}
+
+fn combine_sub_shares(
+    round1_data: &str,
+    my_new_index: u32,
+    allow_threshold_decrease: bool,
+    source_storage: &dyn Storage,
+) -> Result<ResharedShare> {
     // Parse round1 outputs
-    let round1_outputs: Vec<ReshareRound1Output> =
-        crate::protocol::keygen::parse_space_separated_json(round1_data)?;
+    let round1_outputs: Vec<ReshareRound1Output> = parse_payloads(
+        round1_data,
+        &Expected::new("reshare Round 1 outputs", &["reshare_round1"]),
+    )?;
 
     if round1_outputs.is_empty() {
         anyhow::bail!("No round1 data provided");
     }
 
-    // Load source wallet
-    let source_state_dir = get_state_dir(source_wallet);
-    let source_storage = FileStorage::new(&source_state_dir)?;
-
+    require_secp256k1(source_storage)?;
     let shared_key_bytes = source_storage.read("shared_key.bin")?;
     let shared_key: frost::SharedKey<EvenY> = bincode::deserialize(&shared_key_bytes)?;
     let group_public_key = shared_key.public_key();
@@ -516,6 +899,9 @@ pub fn reshare_finalize_core(
     let source_htss: HtssMetadata = serde_json::from_str(&source_htss_json)?;
     let old_threshold = source_htss.threshold;
 
+    let mut auth_note = String::new();
+    identity::verify_payloads(&round1_outputs, source_storage, &mut auth_note)?;
+
     if (round1_outputs.len() as u32) < old_threshold {
         anyhow::bail!(
             "Not enough sub-shares: got {}, need at least {}",
@@ -524,10 +910,38 @@ pub fn reshare_finalize_core(
         );
     }
 
-    let new_threshold = round1_outputs[0].polynomial_commitment.len() as u32;
-    let new_n_parties = round1_outputs[0].sub_shares.len() as u32;
-
+    let (new_threshold, new_n_parties) = check_round1_consistency(&round1_outputs, &source_htss)?;
     let old_indices: Vec<u32> = round1_outputs.iter().map(|o| o.old_party_index).collect();
+    let mut change = ThresholdChange {
+        old_threshold,
+        old_n_parties: source_htss.party_ranks.len() as u32,
+        new_threshold,
+        new_n_parties,
+        old_indices: old_indices.clone(),
+        threshold_decrease_confirmed: false,
+    };
+    auth_note.push_str(&change.review(allow_threshold_decrease)?);
+    change.threshold_decrease_confirmed = new_threshold < old_threshold;
+
+    verify_group_key_preserved(
+        &round1_outputs,
+        &old_indices,
+        &group_public_key,
+        source_storage,
+    )?;
+    let roster = check_new_identities(&round1_outputs, my_new_index, source_storage)?;
+    if let Some(roster) = &roster {
+        auth_note.push_str(&format!(
+            "🪪 New parties' identities, vouched for by old parties {:?}:\n",
+            old_indices
+        ));
+        roster.describe(&mut auth_note);
+    }
+    auth_note.push_str(&format!(
+        "✅ Group key preserved: old parties {:?} reconstruct {}\n",
+        old_indices,
+        hex::encode(group_public_key.to_xonly_bytes())
+    ));
 
     // Compute new share
     let mut new_share_bytes = [0u8; 32];
@@ -544,6 +958,7 @@ pub fn reshare_finalize_core(
 
         let sub_share: Scalar<Secret, Zero> = Scalar::from_bytes(sub_share_bytes)
             .ok_or_else(|| anyhow::anyhow!("Invalid sub-share scalar"))?;
+        verify_sub_share(output, my_new_index, &sub_share)?;
 
         let lagrange_coeff = crate::crypto::helpers::lagrange_coefficient_at_zero(
             output.old_party_index,
@@ -557,22 +972,6 @@ pub fn reshare_finalize_core(
         new_share_bytes = sum.to_bytes();
     }
 
-    // Create target wallet
-    let target_state_dir = get_state_dir(target_wallet);
-    let target_path = std::path::Path::new(&target_state_dir);
-
-    if target_path.exists() {
-        if !force_overwrite {
-            anyhow::bail!(
-                "Target wallet '{}' already exists. Use force_overwrite=true to replace.",
-                target_wallet
-            );
-        }
-        std::fs::remove_dir_all(target_path)?;
-    }
-
-    let target_storage = FileStorage::new(&target_state_dir)?;
-
     // Create PairedSecretShare using helper function
     let share_scalar: Scalar<Secret, Zero> = Scalar::from_bytes(new_share_bytes)
         .ok_or_else(|| anyhow::anyhow!("Invalid computed share"))?;
@@ -583,11 +982,107 @@ pub fn reshare_finalize_core(
         share_nonzero,
         &group_public_key,
     )?;
-    let paired_bytes = bincode::serialize(&paired_share)?;
+    let paired_bytes = serialize_secret(&paired_share)?;
+
+    Ok(ResharedShare {
+        paired_bytes,
+        shared_key_bytes,
+        pubkey_bytes: group_public_key.to_xonly_bytes(),
+        change,
+        auth_note,
+        created_at: source_htss.created_at,
+        roster,
+    })
+}
+
+/// The new roster every old party vouched for, checked against our own key
+///
+/// All round 1 outputs must carry the same keys for every new index, and the
+/// one for `my_new_index` must be this party's identity: otherwise an old
+/// party (or whoever relayed its output) substituted a key.
+fn check_new_identities(
+    round1_outputs: &[ReshareRound1Output],
+    my_new_index: u32,
+    source_storage: &dyn Storage,
+) -> Result<Option<Roster>> {
+    let first = &round1_outputs[0];
+    if let Some(other) = round1_outputs
+        .iter()
+        .find(|output| output.new_identities != first.new_identities)
+    {
+        anyhow::bail!(crate::error::Error::Protocol(format!(
+            "Round 1 outputs disagree on the new parties' identity keys: compare old \
+             parties {} and {} out of band",
+            first.old_party_index, other.old_party_index
+        )));
+    }
+    if first.new_identities.is_empty() {
+        return Ok(None);
+    }
+    if !first.new_identities.keys().eq(first.sub_shares.keys()) {
+        anyhow::bail!(crate::error::Error::Protocol(format!(
+            "Round 1 names identity keys for parties {:?}, but sub-shares for {:?}",
+            first.new_identities.keys().collect::<Vec<_>>(),
+            first.sub_shares.keys().collect::<Vec<_>>()
+        )));
+    }
+    let own = IdentityKey::load_or_create(source_storage)?.public_hex();
+    let pinned = first
+        .new_identities
+        .get(&my_new_index)
+        .map(String::as_str)
+        .unwrap_or_default();
+    if pinned != own {
+        anyhow::bail!(crate::error::Error::Protocol(format!(
+            "Round 1 pins {} for new party {}, but your identity is {}; have the old \
+             parties rerun round 1 with --new-identity {}=<your key>",
+            identity::fingerprint(pinned),
+            my_new_index,
+            identity::fingerprint(&own),
+            my_new_index
+        )));
+    }
+    Ok(Some(Roster(first.new_identities.clone())))
+}
+
+/// Write the reshared wallet: share, key, identity, HTSS metadata and group info
+fn write_reshared_wallet(
+    new_share: ResharedShare,
+    target_wallet: &str,
+    my_new_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    source_storage: &dyn Storage,
+    target_storage: &dyn Storage,
+) -> Result<CommandResult> {
+    // Everything below lands in the target together or not at all
+    let journaled = Journaled::new(target_storage, "reshare-finalize");
+    let target_storage: &dyn Storage = &journaled;
+
+    let ResharedShare {
+        paired_bytes,
+        shared_key_bytes,
+        pubkey_bytes,
+        change,
+        auth_note,
+        created_at,
+        roster,
+    } = new_share;
+    let (new_threshold, new_n_parties) = (change.new_threshold, change.new_n_parties);
 
     target_storage.write("paired_secret_share.bin", &paired_bytes)?;
     target_storage.write("shared_key.bin", &shared_key_bytes)?;
 
+    // New indexes mean new pins: the roster the old parties vouched for, or
+    // only this party's own key for a wallet that had none
+    let pinned_all = roster.is_some();
+    let identity_hex = identity::carry_identity(
+        source_storage,
+        target_storage,
+        my_new_index,
+        Some(roster.unwrap_or_default()),
+    )?;
+
     // Create HTSS metadata
     let mut party_ranks: BTreeMap<u32, u32> = BTreeMap::new();
     party_ranks.insert(my_new_index, my_rank);
@@ -603,6 +1098,10 @@ pub fn reshare_finalize_core(
         threshold: new_threshold,
         hierarchical,
         party_ranks,
+        wallet_version: migrations::CURRENT_WALLET_VERSION,
+        signing_requirement: Default::default(),
+        created_at,
+        refreshed_at: Some(health::now()),
     };
 
     target_storage.write(
@@ -611,7 +1110,6 @@ pub fn reshare_finalize_core(
     )?;
 
     // Create group info
-    let pubkey_bytes: [u8; 32] = group_public_key.to_xonly_bytes();
     let pubkey_hex = hex::encode(pubkey_bytes);
 
     use bitcoin::{Address, Network, XOnlyPublicKey};
@@ -629,6 +1127,8 @@ pub fn reshare_finalize_core(
         total_parties: new_n_parties,
         hierarchical,
         parties: vec![],
+        // New party indices, so the source directory does not apply
+        directory: Default::default(),
     };
 
     target_storage.write(
@@ -636,17 +1136,42 @@ pub fn reshare_finalize_core(
         serde_json::to_string_pretty(&group_info)?.as_bytes(),
     )?;
 
-    target_storage.write("share_hex.txt", hex::encode(new_share_bytes).as_bytes())?;
+    // The new wallet inherits the key's history, plus this reshare
+    audit::carry(source_storage, target_storage)?;
+    let mut details = serde_json::to_value(&change)?;
+    details["target"] = target_wallet.into();
+    details["group_public_key"] = pubkey_hex.clone().into();
+    details["my_new_index"] = my_new_index.into();
+    audit::append(target_storage, "reshare", details)?;
+
+    journaled.commit()?;
 
     Ok(CommandResult {
         output: format!(
-            "Resharing complete!\n\
+            "{}Resharing complete!\n\
              New wallet: {}\n\
-             Config: {}-of-{}\n\
+             Config: {}-of-{} (was {}-of-{})\n\
              Your index: {}\n\
              Public Key: {}\n\
-             Testnet Address: {}",
-            target_wallet, new_threshold, new_n_parties, my_new_index, pubkey_hex, address_testnet
+             Testnet Address: {}\n\
+             Identity: {}{}\n\
+             📝 Recorded in {}",
+            auth_note,
+            target_wallet,
+            new_threshold,
+            new_n_parties,
+            change.old_threshold,
+            change.old_n_parties,
+            my_new_index,
+            pubkey_hex,
+            address_testnet,
+            identity::fingerprint(&identity_hex),
+            if pinned_all {
+                ""
+            } else {
+                " (pin the other new parties with dkg-identity --trust)"
+            },
+            audit::AUDIT_LOG_FILE
         ),
         result: target_wallet.to_string(),
     })
@@ -656,6 +1181,94 @@ pub fn reshare_finalize_core(
 mod tests {
     use super::*;
     use crate::crypto::helpers::lagrange_coefficient_at_zero;
+    use crate::protocol::keygen;
+    use crate::storage::MemoryStorage;
+
+    fn keygen_group(threshold: u32, n_parties: u32) -> Vec<MemoryStorage> {
+        keygen_group_in(threshold, n_parties, |_| MemoryStorage::new())
+    }
+
+    fn keygen_group_in<S: Storage>(
+        threshold: u32,
+        n_parties: u32,
+        storage: impl Fn(u32) -> S,
+    ) -> Vec<S> {
+        let parties: Vec<S> = (1..=n_parties).map(storage).collect();
+        let round1: Vec<String> = (1..=n_parties)
+            .zip(&parties)
+            .map(|(i, storage)| {
+                keygen::round1_core(threshold, n_parties, i, 0, false, storage)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        let round2: Vec<String> = parties
+            .iter()
+            .map(|storage| {
+                keygen::round2_core(&round1.join(" "), storage)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        for storage in &parties {
+            keygen::finalize_core(&round2.join(" "), storage).unwrap();
+        }
+        parties
+    }
+
+    fn round1_outputs<S: Storage>(
+        parties: &[S],
+        new_threshold: u32,
+        new_n_parties: u32,
+    ) -> Vec<ReshareRound1Output> {
+        // Parties joining beyond the old group need their keys named
+        let joining: Vec<String> = (parties.len() as u32 + 1..=new_n_parties)
+            .map(|i| {
+                let key = IdentityKey::load_or_create(&MemoryStorage::new()).unwrap();
+                format!("{}={}", i, key.public_hex())
+            })
+            .collect();
+        (1..=parties.len() as u32)
+            .zip(parties)
+            .map(|(i, storage)| {
+                let result = reshare_round1_with_storage(
+                    "old",
+                    new_threshold,
+                    new_n_parties,
+                    i,
+                    &joining,
+                    storage,
+                    storage,
+                )
+                .unwrap()
+                .result;
+                serde_json::from_str(&result).unwrap()
+            })
+            .collect()
+    }
+
+    fn finalize(
+        outputs: &[ReshareRound1Output],
+        allow_threshold_decrease: bool,
+        source: &MemoryStorage,
+        target: &MemoryStorage,
+    ) -> Result<CommandResult> {
+        let data: Vec<String> = outputs
+            .iter()
+            .map(|output| serde_json::to_string(output).unwrap())
+            .collect();
+        reshare_finalize_with_storage(
+            "new",
+            1,
+            0,
+            false,
+            &data.join(" "),
+            false,
+            allow_threshold_decrease,
+            source,
+            target,
+        )
+    }
 
     #[test]
     fn test_resharing_math() {
@@ -681,4 +1294,217 @@ mod tests {
         let secret_zero: Scalar<Secret, Zero> = Scalar::from_bytes(secret.to_bytes()).unwrap();
         assert_eq!(reconstructed.to_bytes(), secret_zero.to_bytes());
     }
+
+    #[test]
+    fn test_threshold_decrease_needs_confirmation() {
+        let parties = keygen_group(3, 3);
+        let outputs = round1_outputs(&parties, 2, 3);
+
+        let target = MemoryStorage::new();
+        let err = finalize(&outputs, false, &parties[0], &target).unwrap_err();
+        assert!(
+            err.to_string().contains("--allow-threshold-decrease"),
+            "{}",
+            err
+        );
+        assert!(!target.exists("shared_key.bin"));
+
+        let result = finalize(&outputs, true, &parties[0], &target).unwrap();
+        assert!(result.output.contains("Threshold lowered from 3 to 2"));
+        let log = audit::load(&target).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].event, "reshare");
+        assert_eq!(log[0].details["old_threshold"], 3);
+        assert_eq!(log[0].details["old_n_parties"], 3);
+        assert_eq!(log[0].details["new_threshold"], 2);
+        assert_eq!(log[0].details["new_n_parties"], 3);
+        assert_eq!(log[0].details["threshold_decrease_confirmed"], true);
+    }
+
+    #[test]
+    fn test_reshare_carries_audit_log() {
+        let parties = keygen_group(2, 3);
+        audit::append(
+            &parties[0],
+            "reshare",
+            serde_json::json!({"new_threshold": 2}),
+        )
+        .unwrap();
+
+        // Raising the threshold needs no confirmation
+        let target = MemoryStorage::new();
+        let result =
+            finalize(&round1_outputs(&parties, 3, 4), false, &parties[0], &target).unwrap();
+        assert!(result.output.contains("Config: 3-of-4 (was 2-of-3)"));
+
+        let log = audit::load(&target).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[1].details["old_indices"], serde_json::json!([1, 2, 3]));
+        assert_eq!(log[1].details["threshold_decrease_confirmed"], false);
+    }
+
+    #[test]
+    fn test_inconsistent_round1_outputs_rejected() {
+        let parties = keygen_group(2, 3);
+        let two_of_three = round1_outputs(&parties, 2, 3);
+        let target = MemoryStorage::new();
+
+        let three_of_three = round1_outputs(&parties, 3, 3);
+        let mixed = [two_of_three[0].clone(), three_of_three[1].clone()];
+        let err = finalize(&mixed, false, &parties[0], &target).unwrap_err();
+        assert!(
+            err.to_string().contains("disagree on the new threshold"),
+            "{}",
+            err
+        );
+
+        let two_of_four = round1_outputs(&parties, 2, 4);
+        let mixed = [two_of_three[0].clone(), two_of_four[1].clone()];
+        let err = finalize(&mixed, false, &parties[0], &target).unwrap_err();
+        assert!(
+            err.to_string().contains("disagree on the new parties"),
+            "{}",
+            err
+        );
+
+        // A re-signed but altered sub-share fails the commitment check
+        let mut tampered = two_of_three.clone();
+        tampered[1].sub_shares.insert(1, hex::encode([7u8; 32]));
+        identity::sign_payload(&mut tampered[1], &parties[1]).unwrap();
+        let err = finalize(&tampered, false, &parties[0], &target).unwrap_err();
+        assert!(err.to_string().contains("polynomial commitment"), "{}", err);
+
+        assert!(!target.exists("shared_key.bin"));
+        finalize(&two_of_three, false, &parties[0], &target).unwrap();
+        assert_eq!(audit::load(&target).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_group_key_mismatch_refused() {
+        let parties = keygen_group(2, 3);
+        let other_group = keygen_group(2, 3);
+        let mut outputs = round1_outputs(&parties, 2, 3);
+
+        // A self-consistent polynomial for a share of some other key
+        outputs[1] = round1_outputs(&other_group, 2, 3).remove(1);
+        identity::sign_payload(&mut outputs[1], &parties[1]).unwrap();
+
+        let target = MemoryStorage::new();
+        let err = finalize(&outputs, false, &parties[0], &target).unwrap_err();
+        assert!(err.to_string().contains("Group key mismatch"), "{}", err);
+        assert!(!target.exists("shared_key.bin"));
+        assert!(!target.exists(audit::AUDIT_LOG_FILE));
+    }
+
+    #[test]
+    fn test_verify_only_writes_nothing() {
+        let parties = keygen_group(2, 3);
+        let outputs = round1_outputs(&parties, 3, 5);
+        let data: Vec<String> = outputs
+            .iter()
+            .map(|output| serde_json::to_string(output).unwrap())
+            .collect();
+        let source = &parties[0];
+
+        let result = reshare_verify_with_storage(1, &data.join(" "), false, source).unwrap();
+        assert!(result.output.contains("Group key preserved"));
+        let summary: serde_json::Value = serde_json::from_str(&result.result).unwrap();
+        let key: frost::SharedKey<EvenY> =
+            bincode::deserialize(&source.read("shared_key.bin").unwrap()).unwrap();
+        assert_eq!(
+            summary["group_public_key"],
+            hex::encode(key.public_key().to_xonly_bytes())
+        );
+        assert_eq!(summary["new_threshold"], 3);
+        assert!(!source.exists(audit::AUDIT_LOG_FILE));
+
+        // group_info.json naming another key fails the dry run too
+        let mut info = keygen::build_group_info("old", source).unwrap();
+        info.group_public_key = hex::encode([2u8; 32]);
+        source
+            .write("group_info.json", &serde_json::to_vec(&info).unwrap())
+            .unwrap();
+        let err = reshare_verify_with_storage(1, &data.join(" "), false, source).unwrap_err();
+        assert!(
+            err.to_string().contains("source group_info.json"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_reshare_pins_every_new_party() {
+        let parties = keygen_group(2, 3);
+        let outputs = round1_outputs(&parties, 2, 3);
+        let target = MemoryStorage::new();
+        finalize(&outputs, false, &parties[0], &target).unwrap();
+        let roster = Roster::load(&target).unwrap().unwrap();
+        assert_eq!(roster, Roster::load(&parties[0]).unwrap().unwrap());
+        assert_eq!(roster.0.len(), 3);
+
+        // The old parties disagree on who the new parties are
+        let mut split = outputs.clone();
+        let stranger = IdentityKey::load_or_create(&MemoryStorage::new()).unwrap();
+        split[1].new_identities.insert(3, stranger.public_hex());
+        identity::sign_payload(&mut split[1], &parties[1]).unwrap();
+        let err = finalize(&split, false, &parties[0], &MemoryStorage::new()).unwrap_err();
+        assert!(err.to_string().contains("identity keys"), "{}", err);
+
+        // Every old party names someone else for my new index
+        let mut substituted = outputs.clone();
+        for (output, party) in substituted.iter_mut().zip(&parties) {
+            output.new_identities.insert(1, stranger.public_hex());
+            identity::sign_payload(output, party).unwrap();
+        }
+        let err = finalize(&substituted, false, &parties[0], &MemoryStorage::new()).unwrap_err();
+        assert!(err.to_string().contains("--new-identity 1="), "{}", err);
+    }
+
+    #[test]
+    fn test_replaced_target_folder_keeps_nothing_of_the_old_wallet() {
+        let root = std::env::temp_dir().join(format!("frostdao_reshare_{}", std::process::id()));
+        let dir = |name: &str| root.join(name).to_string_lossy().into_owned();
+        let parties = keygen_group_in(2, 3, |i| {
+            FileStorage::new(&dir(&format!("p{}", i))).unwrap()
+        });
+        let data: Vec<String> = round1_outputs(&parties, 2, 3)
+            .iter()
+            .map(|output| serde_json::to_string(output).unwrap())
+            .collect();
+        let finalize = |target: &str, force| {
+            reshare_finalize_in_dirs(
+                &dir("p1"),
+                target,
+                "new",
+                1,
+                0,
+                false,
+                &data.join(" "),
+                force,
+                false,
+            )
+        };
+
+        // Another wallet's files sit in the target folder
+        let stale = FileStorage::new(&dir("new")).unwrap();
+        for file in [
+            "shared_key.bin",
+            "hd_metadata.json",
+            "dkg_nonce_s1.bin",
+            "webhooks.json",
+        ] {
+            stale.write(file, b"stale").unwrap();
+        }
+        assert!(finalize(&dir("new"), false).is_err());
+        assert!(finalize(&dir("p1"), true).is_err());
+        finalize(&dir("new"), true).unwrap();
+
+        let target = FileStorage::new(&dir("new")).unwrap();
+        assert_ne!(target.read("shared_key.bin").unwrap(), b"stale");
+        for file in ["hd_metadata.json", "dkg_nonce_s1.bin", "webhooks.json"] {
+            assert!(!target.exists(file), "{} survived", file);
+        }
+        assert!(parties[0].exists("paired_secret_share.bin"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
@@ -0,0 +1,579 @@
+//! Group Key Rotation Sweep
+//!
+//! A reshare keeps the group key, but anything that needs a new one (a
+//! threshold increase, a suspected leak) ends with a fresh keygen and coins
+//! still sitting on the old key's addresses. `dkg-rotate` moves them:
+//!
+//! 1. Scan every script of the old wallet's script index (root, HD receive
+//!    and change addresses, script trees) and, with `--hd-count n`, its
+//!    first n receive (`0/i`) and change (`1/i`) HD addresses
+//! 2. Plan one sweep per confirmed UTXO to the new wallet's root address
+//! 3. Sign every sweep with the old wallet's local party folders and
+//!    broadcast it
+//! 4. Once nothing was left behind, write `retired.json` into the old
+//!    wallet (see [`Retirement`])
+//!
+//! Local signing covers input 0 only, so each UTXO gets its own
+//! one-input, one-output transaction. UTXOs that are unconfirmed, frozen
+//! (inscriptions, rare sats) or worth less than their fee plus the dust
+//! limit are reported as left behind and keep the old wallet active, as are
+//! coins on script tree addresses, which local signing can't spend.
+
+use crate::btc::inscriptions::filter_protected_utxos;
+use crate::btc::rebroadcast;
+use crate::btc::script_index::{ScriptIndex, ScriptKey};
+use crate::btc::transaction::{ChainBackend, MempoolBackend};
+use crate::crypto::hd::{self, DerivationPath, HdContext};
+use crate::protocol::audit;
+use crate::protocol::dkg_tx::{check_local_signers, generate_session_id, sign_key_spend_local};
+use crate::protocol::health;
+use crate::protocol::keygen::{get_state_dir, require_secp256k1};
+use crate::protocol::wallet::Retirement;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{Context, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::hashes::Hash;
+use bitcoin::key::XOnlyPublicKey;
+use bitcoin::script::ScriptBuf;
+use bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
+use bitcoin::transaction::Version;
+use bitcoin::{
+    Address, Amount, Network, OutPoint, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use schnorr_fun::frost::SharedKey;
+use secp256kfun::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Outputs below this are not worth creating
+const DUST_LIMIT: u64 = 546;
+
+/// One key-path input and one P2TR output
+const SWEEP_VSIZE: u64 = 10 + 58 + 43;
+
+/// HD path (change, index), None for the root address
+type HdPath = Option<(u32, u32)>;
+
+/// A coin on one of the old wallet's addresses
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SweepInput {
+    /// HD path (change, index), None for the root address
+    pub path: HdPath,
+    /// On a script tree address, which the sweep can't sign for
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub script_tree: bool,
+    pub address: String,
+    pub txid: String,
+    pub vout: u32,
+    pub value: u64,
+}
+
+/// A coin that will be moved, and what arrives
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlannedSweep {
+    pub input: SweepInput,
+    pub fee_sats: u64,
+    pub amount_sats: u64,
+}
+
+/// A coin the sweep cannot move, and why
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeftBehind {
+    pub input: SweepInput,
+    pub reason: String,
+}
+
+/// Everything `dkg-rotate` would do, before anything is signed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SweepPlan {
+    /// The new wallet's root address
+    pub destination: String,
+    pub fee_rate: u64,
+    pub sweeps: Vec<PlannedSweep>,
+    pub left_behind: Vec<LeftBehind>,
+}
+
+impl SweepPlan {
+    pub fn total_swept(&self) -> u64 {
+        self.sweeps.iter().map(|s| s.amount_sats).sum()
+    }
+
+    pub fn total_fees(&self) -> u64 {
+        self.sweeps.iter().map(|s| s.fee_sats).sum()
+    }
+}
+
+/// Result of `dkg-rotate`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RotationResult {
+    pub from_wallet: String,
+    pub to_wallet: String,
+    pub plan: SweepPlan,
+    /// Sweeps accepted by the backend
+    pub txids: Vec<String>,
+    /// Sweeps the backend refused, with the signed tx for a manual retry
+    pub failed: Vec<String>,
+    /// Whether `retired.json` was written
+    pub retired: bool,
+    pub event_type: String,
+}
+
+fn group_key(storage: &dyn Storage) -> Result<Point<EvenY>> {
+    require_secp256k1(storage)?;
+    let bytes = storage
+        .read("shared_key.bin")
+        .context("No DKG shared key found")?;
+    let shared_key: SharedKey<EvenY> = bincode::deserialize(&bytes)?;
+    Ok(shared_key.public_key())
+}
+
+fn p2tr_address(key: &Point<EvenY>, network: Network) -> Result<Address> {
+    let xonly = XOnlyPublicKey::from_slice(&key.to_xonly_bytes())?;
+    let secp = bitcoin::secp256k1::Secp256k1::new();
+    Ok(Address::p2tr(&secp, xonly, None, network))
+}
+
+/// The old wallet's addresses to scan: root first, then HD, then script trees
+///
+/// Every script in the old wallet's [`ScriptIndex`] (root, HD receive
+/// addresses handed out plus the lookahead, the first change addresses and
+/// every recorded script tree), plus the first `hd_count` receive and change
+/// addresses if the index stops short of them. The bool marks script trees.
+fn sweep_sources(
+    old_storage: &dyn Storage,
+    hd_count: u32,
+    network: Network,
+) -> Result<Vec<(HdPath, bool, Address)>> {
+    let index = ScriptIndex::load(old_storage)?;
+    let mut sources = Vec::with_capacity(index.scripts.len());
+    for (script_hex, owned) in &index.scripts {
+        let (path, tree) = match owned.key()? {
+            ScriptKey::Root => (None, false),
+            ScriptKey::Hd(path) => (Some((path.change, path.address_index)), false),
+            ScriptKey::Tree => (None, true),
+        };
+        let script = ScriptBuf::from_bytes(hex::decode(script_hex)?);
+        sources.push((path, tree, Address::from_script(&script, network)?));
+    }
+    if hd_count > 0 {
+        let context = crate::btc::hd_address::load_hd_context(old_storage).context(
+            "HD context not found; run without --hd-count to sweep the indexed addresses only",
+        )?;
+        for change in 0..2 {
+            for address_index in 0..hd_count {
+                if sources
+                    .iter()
+                    .any(|(path, _, _)| *path == Some((change, address_index)))
+                {
+                    continue;
+                }
+                let path = DerivationPath {
+                    change,
+                    address_index,
+                };
+                let derived = hd::derive_at_path(&context, &path).with_context(|| {
+                    format!("Failed to derive HD key {}/{}", change, address_index)
+                })?;
+                sources.push((
+                    Some((change, address_index)),
+                    false,
+                    p2tr_address(&derived.public_key, network)?,
+                ));
+            }
+        }
+    }
+    sources.sort_by_key(|(path, tree, _)| (*tree, path.is_some(), *path));
+    Ok(sources)
+}
+
+/// Plan the sweep of `old_storage`'s coins to `new_storage`'s root address
+///
+/// Fails with a user error when both wallets share a group key, since
+/// there is nothing to move.
+#[allow(clippy::too_many_arguments)]
+pub fn plan_sweep(
+    old_storage: &dyn Storage,
+    new_storage: &dyn Storage,
+    hd_count: u32,
+    fee_rate: Option<u64>,
+    network: Network,
+    allow_inscribed: bool,
+    backend: &dyn ChainBackend,
+    out: &mut String,
+) -> Result<SweepPlan> {
+    let old_key = group_key(old_storage)?;
+    let new_key = group_key(new_storage)?;
+    if old_key == new_key {
+        return Err(crate::error::Error::User(
+            "Both wallets have the same group key; a reshare that keeps the key needs no sweep"
+                .to_string(),
+        )
+        .into());
+    }
+    let destination = p2tr_address(&new_key, network)?.to_string();
+    let fee_rate = match fee_rate {
+        Some(rate) => rate,
+        None => backend.fee_estimates()?.half_hour_fee,
+    };
+    let fee_sats = SWEEP_VSIZE * fee_rate;
+
+    let mut plan = SweepPlan {
+        destination,
+        fee_rate,
+        sweeps: Vec::new(),
+        left_behind: Vec::new(),
+    };
+    for (path, script_tree, address) in sweep_sources(old_storage, hd_count, network)? {
+        let address = address.to_string();
+        let utxos = backend.utxos(&address)?;
+        let input = |utxo: &crate::btc::transaction::UtxoResponse| SweepInput {
+            path,
+            script_tree,
+            address: address.clone(),
+            txid: utxo.txid.clone(),
+            vout: utxo.vout,
+            value: utxo.value,
+        };
+        // Local signing is key-path only, without a script tree tweak
+        if script_tree {
+            for utxo in &utxos {
+                plan.left_behind.push(LeftBehind {
+                    input: input(utxo),
+                    reason: "on a script tree address; move it with dkg-build-tx --from"
+                        .to_string(),
+                });
+            }
+            continue;
+        }
+
+        let (confirmed, unconfirmed): (Vec<_>, Vec<_>) =
+            utxos.iter().partition(|u| u.status.confirmed);
+        for utxo in unconfirmed {
+            plan.left_behind.push(LeftBehind {
+                input: input(utxo),
+                reason: "unconfirmed".to_string(),
+            });
+        }
+        if confirmed.is_empty() {
+            continue;
+        }
+
+        let spendable = filter_protected_utxos(confirmed.clone(), network, allow_inscribed, out)?;
+        for utxo in confirmed {
+            if !spendable
+                .iter()
+                .any(|s| s.txid == utxo.txid && s.vout == utxo.vout)
+            {
+                plan.left_behind.push(LeftBehind {
+                    input: input(utxo),
+                    reason: "frozen (inscriptions, runes or rare sats)".to_string(),
+                });
+            } else if utxo.value < fee_sats + DUST_LIMIT {
+                plan.left_behind.push(LeftBehind {
+                    input: input(utxo),
+                    reason: format!("worth less than the {} sat fee plus dust", fee_sats),
+                });
+            } else {
+                plan.sweeps.push(PlannedSweep {
+                    input: input(utxo),
+                    fee_sats,
+                    amount_sats: utxo.value - fee_sats,
+                });
+            }
+        }
+    }
+    Ok(plan)
+}
+
+fn describe_input(input: &SweepInput) -> String {
+    let from = match input.path {
+        _ if input.script_tree => format!("script tree {}", input.address),
+        Some((change, index)) => format!("{}/{}", change, index),
+        None => "root".to_string(),
+    };
+    format!(
+        "{}:{} ({}, {} sats)",
+        &input.txid[..16.min(input.txid.len())],
+        input.vout,
+        from,
+        input.value
+    )
+}
+
+/// Sign one planned sweep with the local parties; returns the raw signed tx
+fn sign_sweep(
+    sweep: &PlannedSweep,
+    destination: &Address,
+    old_key: &Point<EvenY>,
+    hd_context: Option<&HdContext>,
+    parties: &[(u32, &dyn Storage)],
+    network: Network,
+    out: &mut String,
+) -> Result<String> {
+    let input = &sweep.input;
+    let derived = match (input.path, hd_context) {
+        (Some((change, address_index)), Some(context)) => Some(hd::derive_at_path(
+            context,
+            &DerivationPath {
+                change,
+                address_index,
+            },
+        )?),
+        _ => None,
+    };
+    let from_pubkey = derived.as_ref().map_or(*old_key, |info| info.public_key);
+    let from_address = p2tr_address(&from_pubkey, network)?;
+
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::from_str(&input.txid)?, input.vout),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(sweep.amount_sats),
+            script_pubkey: destination.script_pubkey(),
+        }],
+    };
+    let prevouts = [TxOut {
+        value: Amount::from_sat(input.value),
+        script_pubkey: from_address.script_pubkey(),
+    }];
+    let sighash = SighashCache::new(&tx)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), TapSighashType::Default)
+        .context("Failed to compute sighash")?;
+
+    let session_id =
+        generate_session_id(&format!("{}:{}", input.txid, input.vout), sweep.amount_sats);
+    let signature = sign_key_spend_local(
+        parties,
+        derived.as_ref(),
+        from_pubkey,
+        *sighash.as_byte_array(),
+        &session_id,
+        out,
+    )?;
+
+    let mut signed = tx;
+    signed.input[0].witness = Witness::from_slice(&[&signature[..]]);
+    Ok(bitcoin::consensus::encode::serialize_hex(&signed))
+}
+
+/// Core function for `dkg-rotate`
+///
+/// `old_storage` and `new_storage` are the two wallets' root folders;
+/// `parties` are the old wallet's signing party folders. With `dry_run`
+/// only the plan is returned.
+#[allow(clippy::too_many_arguments)]
+pub fn rotate_core(
+    from_wallet: &str,
+    to_wallet: &str,
+    old_storage: &dyn Storage,
+    new_storage: &dyn Storage,
+    parties: &[(u32, &dyn Storage)],
+    hd_count: u32,
+    fee_rate: Option<u64>,
+    network: Network,
+    allow_inscribed: bool,
+    dry_run: bool,
+    backend: &dyn ChainBackend,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    out.push_str("🔄 Group Key Rotation Sweep\n\n");
+    out.push_str(&format!("From: {}\n", from_wallet));
+    out.push_str(&format!("To:   {}\n\n", to_wallet));
+
+    let plan = plan_sweep(
+        old_storage,
+        new_storage,
+        hd_count,
+        fee_rate,
+        network,
+        allow_inscribed,
+        backend,
+        &mut out,
+    )?;
+    out.push_str(&format!("Destination: {}\n", plan.destination));
+    out.push_str(&format!("Fee rate: {} sat/vB\n\n", plan.fee_rate));
+    for sweep in &plan.sweeps {
+        out.push_str(&format!(
+            "   ➡️  {} → {} sats\n",
+            describe_input(&sweep.input),
+            sweep.amount_sats
+        ));
+    }
+    for left in &plan.left_behind {
+        out.push_str(&format!(
+            "   ⏸️  {}: {}\n",
+            describe_input(&left.input),
+            left.reason
+        ));
+    }
+    out.push_str(&format!(
+        "\n{} sweep(s), {} sats arriving, {} sats in fees\n",
+        plan.sweeps.len(),
+        plan.total_swept(),
+        plan.total_fees()
+    ));
+
+    let mut result = RotationResult {
+        from_wallet: from_wallet.to_string(),
+        to_wallet: to_wallet.to_string(),
+        plan,
+        txids: Vec::new(),
+        failed: Vec::new(),
+        retired: false,
+        event_type: "key_rotation".to_string(),
+    };
+    if result.plan.sweeps.is_empty() && result.plan.left_behind.is_empty() {
+        out.push_str("Nothing to sweep; the old wallet holds no coins.\n");
+    }
+    if dry_run {
+        out.push_str("\nDry run: nothing was signed.\n");
+        return Ok(CommandResult {
+            output: out,
+            result: serde_json::to_string(&result)?,
+        });
+    }
+
+    let (signers, _) = check_local_signers(parties)?;
+    let old_key = group_key(old_storage)?;
+    let hd_context = if result.plan.sweeps.iter().any(|s| s.input.path.is_some()) {
+        Some(crate::btc::hd_address::load_hd_context(old_storage)?)
+    } else {
+        None
+    };
+    let destination = Address::from_str(&result.plan.destination)?.require_network(network)?;
+
+    for sweep in &result.plan.sweeps {
+        out.push_str(&format!("\n🔐 Sweeping {}\n", describe_input(&sweep.input)));
+        let raw_tx = sign_sweep(
+            sweep,
+            &destination,
+            &old_key,
+            hd_context.as_ref(),
+            parties,
+            network,
+            &mut out,
+        )?;
+        let status = match backend.broadcast_report(&raw_tx).result() {
+            Ok(txid) => {
+                rebroadcast::record(old_storage, &raw_tx, network, health::now())?;
+                out.push_str(&format!("   ✅ TxID: {}\n", txid));
+                result.txids.push(txid.clone());
+                format!("accepted {}", txid)
+            }
+            Err(e) => {
+                out.push_str(&format!("   ⚠️ Broadcast failed: {}\n", e));
+                result.failed.push(raw_tx);
+                format!("failed: {}", e)
+            }
+        };
+        for (_, storage) in parties {
+            audit::append(
+                *storage,
+                "rotation_sweep",
+                serde_json::json!({
+                    "outpoint": format!("{}:{}", sweep.input.txid, sweep.input.vout),
+                    "to_wallet": to_wallet,
+                    "status": status,
+                }),
+            )?;
+        }
+    }
+
+    if result.failed.is_empty() && result.plan.left_behind.is_empty() {
+        Retirement {
+            retired_at: health::now(),
+            successor: to_wallet.to_string(),
+            txids: result.txids.clone(),
+        }
+        .save(old_storage)?;
+        for (_, storage) in parties {
+            audit::append(
+                *storage,
+                "retired",
+                serde_json::json!({ "successor": to_wallet, "signers": signers }),
+            )?;
+        }
+        result.retired = true;
+        out.push_str(&format!(
+            "\n✅ '{}' swept and marked retired; use '{}' from now on.\n",
+            from_wallet, to_wallet
+        ));
+    } else {
+        out.push_str(&format!(
+            "\n⚠️ '{}' still holds coins ({} left behind, {} failed); it was not retired.\n",
+            from_wallet,
+            result.plan.left_behind.len(),
+            result.failed.len()
+        ));
+        out.push_str("   Re-run dkg-rotate once they can be moved.\n");
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&result)?,
+    })
+}
+
+/// CLI wrapper for `dkg-rotate`
+#[allow(clippy::too_many_arguments)]
+pub fn rotate(
+    from_wallet: &str,
+    to_wallet: &str,
+    signers: &[u32],
+    hd_count: u32,
+    fee_rate: Option<u64>,
+    network: Network,
+    allow_inscribed: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let state_dir = get_state_dir(from_wallet);
+    let new_dir = get_state_dir(to_wallet);
+    for dir in [&state_dir, &new_dir] {
+        if !std::path::Path::new(dir).is_dir() {
+            return Err(
+                crate::error::Error::Storage(format!("Wallet not found at {}", dir)).into(),
+            );
+        }
+    }
+    let old_storage = FileStorage::new(&state_dir)?;
+    let new_storage = FileStorage::new(&new_dir)?;
+    let mut party_storages = Vec::with_capacity(signers.len());
+    if !dry_run {
+        for &party_idx in signers {
+            let party_dir = format!("{}/party{}", state_dir, party_idx);
+            if !std::path::Path::new(&party_dir).exists() {
+                anyhow::bail!("Party {} folder not found", party_idx);
+            }
+            party_storages.push((party_idx, FileStorage::new(&party_dir)?));
+        }
+    }
+    let parties: Vec<(u32, &dyn Storage)> = party_storages
+        .iter()
+        .map(|(idx, storage)| (*idx, storage as &dyn Storage))
+        .collect();
+
+    let cmd_result = rotate_core(
+        from_wallet,
+        to_wallet,
+        &old_storage,
+        &new_storage,
+        &parties,
+        hd_count,
+        fee_rate,
+        network,
+        allow_inscribed,
+        dry_run,
+        &MempoolBackend::new(network),
+    )?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("📋 Result: {}\n", cmd_result.result);
+    Ok(())
+}
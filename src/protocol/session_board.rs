@@ -0,0 +1,269 @@
+//! Signing Session Board
+//!
+//! Tracks who has done what in one signing session, from whatever the
+//! coordinator has collected so far: nonce and signature share payloads
+//! (`signing_*` or `dkg_*`), either pasted bare or carried in Nostr events.
+//! A gift-wrapped DM (kind 1059) is opened with the wallet's Nostr key; any
+//! other event's content is read as the payload.
+//!
+//! ```text
+//! party   nonce   share
+//!   1       ✓       ✓
+//!   2       ✓       -      ← pending: party 2 signs
+//!   3       -       -      (not signing, threshold 2)
+//! ```
+//!
+//! The TUI shows the board while nonces and shares are collected, so progress
+//! is reported per party rather than as a bare payload count.
+
+use crate::protocol::identity::IdentityKey;
+use crate::protocol::keygen::parse_space_separated_json;
+use crate::protocol::nostr::{self, Event};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Nostr events nested deeper than this are not opened
+const MAX_EVENT_DEPTH: usize = 2;
+
+/// What a party still has to do in the session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartyStatus {
+    /// No nonce from this party yet
+    AwaitingNonce,
+    /// Nonce in, but the signing set isn't complete yet
+    AwaitingOthers,
+    /// All nonces in, signature share missing
+    AwaitingShare,
+    /// Signature share in
+    Done,
+    /// The signing set filled up without this party
+    NotSigning,
+}
+
+impl PartyStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::AwaitingNonce => "nonce pending",
+            Self::AwaitingOthers => "waiting for others",
+            Self::AwaitingShare => "share pending",
+            Self::Done => "done",
+            Self::NotSigning => "not signing",
+        }
+    }
+}
+
+/// Progress of one signing session
+#[derive(Debug, Clone, Default)]
+pub struct SessionBoard {
+    pub session_id: String,
+    pub threshold: usize,
+    /// Unix time the session was created
+    pub created_at: u64,
+    /// Parties expected to sign, plus any that turned up with a payload
+    pub signers: BTreeSet<u32>,
+    pub nonces: BTreeSet<u32>,
+    pub shares: BTreeSet<u32>,
+    /// Accepted payloads as bare JSON (unwrapped from any Nostr event)
+    pub nonce_payloads: Vec<String>,
+    pub share_payloads: Vec<String>,
+    /// Collected data that could not be placed on the board, and why
+    pub ignored: Vec<String>,
+    /// How to reach each signer (see protocol::directory), shown for pending parties
+    pub contacts: BTreeMap<u32, String>,
+}
+
+impl SessionBoard {
+    pub fn new(session_id: &str, threshold: usize, signers: &[u32], created_at: u64) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            threshold,
+            created_at,
+            signers: signers.iter().copied().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Add whitespace-separated payloads or Nostr events to the board
+    ///
+    /// `identity` is the wallet's Nostr key, needed only for gift wraps.
+    pub fn ingest(&mut self, data: &str, identity: Option<&IdentityKey>) {
+        self.ingest_at_depth(data, identity, 0);
+    }
+
+    fn ingest_at_depth(&mut self, data: &str, identity: Option<&IdentityKey>, depth: usize) {
+        if data.trim().is_empty() {
+            return;
+        }
+        match parse_space_separated_json::<serde_json::Value>(data) {
+            Ok(values) => {
+                for value in values {
+                    if let Err(e) = self.ingest_value(value, identity, depth) {
+                        self.ignored.push(e.to_string());
+                    }
+                }
+            }
+            Err(e) => self.ignored.push(format!("Unreadable JSON: {}", e)),
+        }
+    }
+
+    fn ingest_value(
+        &mut self,
+        value: serde_json::Value,
+        identity: Option<&IdentityKey>,
+        depth: usize,
+    ) -> Result<()> {
+        if value.get("kind").is_some() && value.get("content").is_some() {
+            if depth >= MAX_EVENT_DEPTH {
+                anyhow::bail!("Nostr event nested too deeply");
+            }
+            let event: Event = serde_json::from_value(value).context("Malformed Nostr event")?;
+            let message = if event.kind == nostr::KIND_GIFT_WRAP {
+                let me =
+                    identity.context("Gift wrap received, but this wallet has no Nostr key")?;
+                nostr::unwrap_gift(me, &event)?.1
+            } else {
+                event.verify()?;
+                event.content
+            };
+            self.ingest_at_depth(&message, identity, depth + 1);
+            return Ok(());
+        }
+
+        let party = value["party_index"]
+            .as_u64()
+            .context("Payload has no party_index")? as u32;
+        let kind = value["type"].as_str().unwrap_or("untyped");
+        let is_nonce = match kind {
+            "signing_nonce" | "dkg_nonce" => true,
+            "signing_share" | "dkg_signature_share" => false,
+            other => anyhow::bail!("Party {}: {} is not a nonce or share", party, other),
+        };
+        let session = value["session"]
+            .as_str()
+            .or_else(|| value["session_id"].as_str())
+            .unwrap_or_default();
+        if session != self.session_id {
+            anyhow::bail!("Party {}: payload is for session {}", party, session);
+        }
+
+        self.signers.insert(party);
+        if is_nonce {
+            self.nonces.insert(party);
+            self.nonce_payloads.push(value.to_string());
+        } else {
+            self.shares.insert(party);
+            self.share_payloads.push(value.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn status(&self, party: u32) -> PartyStatus {
+        if self.shares.contains(&party) {
+            PartyStatus::Done
+        } else if !self.nonces.contains(&party) {
+            if self.nonces_complete() {
+                PartyStatus::NotSigning
+            } else {
+                PartyStatus::AwaitingNonce
+            }
+        } else if !self.nonces_complete() {
+            PartyStatus::AwaitingOthers
+        } else {
+            PartyStatus::AwaitingShare
+        }
+    }
+
+    /// Enough nonces to sign
+    pub fn nonces_complete(&self) -> bool {
+        self.nonces.len() >= self.threshold
+    }
+
+    /// Enough shares to combine
+    pub fn shares_complete(&self) -> bool {
+        self.shares.len() >= self.threshold
+    }
+
+    /// What the session is waiting on, and from whom
+    pub fn pending_action(&self) -> String {
+        let waiting_on = |status: PartyStatus| -> Vec<String> {
+            self.signers
+                .iter()
+                .filter(|&&party| self.status(party) == status)
+                .map(|party| party.to_string())
+                .collect()
+        };
+        if !self.nonces_complete() {
+            let missing = self.threshold - self.nonces.len();
+            match waiting_on(PartyStatus::AwaitingNonce) {
+                parties if parties.is_empty() => {
+                    format!("Need {} more signer(s) to send a nonce", missing)
+                }
+                parties => format!("Waiting on party {} for a nonce", parties.join(", ")),
+            }
+        } else if !self.shares_complete() {
+            format!(
+                "Waiting on party {} for a signature share",
+                waiting_on(PartyStatus::AwaitingShare).join(", ")
+            )
+        } else {
+            "All shares in - coordinator can combine".to_string()
+        }
+    }
+
+    /// Time since the session was created, e.g. `4m 05s`
+    pub fn elapsed(&self, now: u64) -> String {
+        let secs = now.saturating_sub(self.created_at);
+        match secs {
+            0..=59 => format!("{}s", secs),
+            60..=3599 => format!("{}m {:02}s", secs / 60, secs % 60),
+            _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(kind: &str, party: u32, session: &str) -> String {
+        serde_json::json!({"party_index": party, "session": session, "type": kind}).to_string()
+    }
+
+    #[test]
+    fn test_session_board_progress() {
+        let mut board = SessionBoard::new("s1", 2, &[1, 2, 3], 1_000);
+        board.ingest(&payload("signing_nonce", 1, "s1"), None);
+        assert_eq!(board.status(1), PartyStatus::AwaitingOthers);
+        assert_eq!(board.pending_action(), "Waiting on party 2, 3 for a nonce");
+
+        // Party 2's nonce arrives as a gift-wrapped DM; a stale payload is set aside
+        let coordinator = IdentityKey::generate();
+        let party2 = IdentityKey::generate();
+        let wrap = nostr::gift_wrap(
+            &party2,
+            &coordinator.public_hex(),
+            &payload("signing_nonce", 2, "s1"),
+        )
+        .unwrap();
+        let data = format!(
+            "{} {}",
+            serde_json::to_string(&wrap).unwrap(),
+            payload("signing_nonce", 3, "old")
+        );
+        board.ingest(&data, Some(&coordinator));
+        assert_eq!(board.nonces, BTreeSet::from([1, 2]));
+        assert_eq!(board.nonce_payloads[1], payload("signing_nonce", 2, "s1"));
+        assert_eq!(board.ignored.len(), 1);
+        assert_eq!(board.status(2), PartyStatus::AwaitingShare);
+        assert_eq!(board.status(3), PartyStatus::NotSigning);
+
+        board.ingest(&payload("signing_share", 1, "s1"), None);
+        assert_eq!(
+            board.pending_action(),
+            "Waiting on party 2 for a signature share"
+        );
+        board.ingest(&payload("dkg_signature_share", 2, "s1"), None);
+        assert!(board.shares_complete());
+        assert_eq!(board.elapsed(1_245), "4m 05s");
+    }
+}
@@ -0,0 +1,504 @@
+//! Signing Session Reports
+//!
+//! `dkg-broadcast` leaves `session_report_<session>.json` in the party
+//! folder: everything public about the session that was just broadcast.
+//! That covers the signed transaction and the outputs it spends, the sighash,
+//! who signed, every nonce and signature share as exchanged (with their
+//! identity signatures), the final signature and the txid.
+//!
+//! `dkg-session-report` prints it as a summary or writes it out as JSON for
+//! the co-signers. A co-signer imports it with `--import`. Their copy is only
+//! saved once it checks out against their own wallet: same group key, the
+//! sighash and nonces they signed with, shares that add up to the signature,
+//! a signature valid for the wallet's output key, and a sighash that commits
+//! to the reported transaction.
+
+use crate::btc::amount::format_amount;
+use crate::btc::hd_address::parse_network;
+use crate::btc::script_index::ScriptIndex;
+use crate::error::Error;
+use crate::protocol::dkg_tx::{
+    check_memo, combine_signature_shares, parse_merkle_root, session_leaf, session_merkle_root,
+    spend_sighash, witness_signature, DkgSignatureShareOutput, SessionKey, SessionRoles, SpendLeaf,
+    SpentOutput,
+};
+use crate::protocol::health;
+use crate::protocol::identity;
+use crate::protocol::keygen::{get_state_dir, require_secp256k1};
+use crate::protocol::payload::{parse_payloads, Expected};
+use crate::protocol::signing::NonceOutput;
+use crate::storage::{FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use bitcoin::hashes::Hash;
+use bitcoin::key::TapTweak;
+use bitcoin::{Address, Network, Transaction};
+use schnorr_fun::frost::SharedKey;
+use secp256kfun::marker::EvenY;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Report of session `session_id`
+pub fn report_file(session_id: &str) -> String {
+    format!("session_report_{}.json", session_id)
+}
+
+/// Public record of a broadcast signing session
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SessionReport {
+    pub session_id: String,
+    /// What the payment is for, from `dkg-build-tx --memo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub network: String,
+    /// X-only internal group key (hex)
+    pub group_key: String,
+    /// Script tree root of the spent output (absent for key-path only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merkle_root: Option<String>,
+    /// Script leaf the transaction spent through (absent for the key path)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leaf: Option<SpendLeaf>,
+    /// Outputs spent by the transaction; empty if this party did not build it
+    #[serde(default)]
+    pub prevouts: Vec<SpentOutput>,
+    /// Sighash every party signed (32 bytes hex)
+    pub sighash: String,
+    /// Signing parties, ascending
+    pub signers: Vec<u32>,
+    /// Party that combined the shares, per [`SessionRoles`]
+    pub aggregator: u32,
+    /// Public nonces of every signer, as exchanged
+    pub nonces: Vec<NonceOutput>,
+    /// Signature shares of every signer, as exchanged
+    pub shares: Vec<DkgSignatureShareOutput>,
+    /// BIP-340 signature in the first input's witness (64 bytes hex)
+    pub signature: String,
+    pub txid: String,
+    /// Signed transaction (raw hex)
+    pub raw_tx: String,
+    /// `accepted`, or why the broadcast failed
+    pub broadcast: String,
+    /// Unix time in seconds
+    pub created_at: u64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+}
+
+impl SessionReport {
+    /// Report for the signed `tx` of a session combined from `shares_data`
+    pub fn build(
+        session_id: &str,
+        tx: &Transaction,
+        shares_data: &str,
+        network: &str,
+        broadcast: &str,
+        storage: &dyn Storage,
+    ) -> Result<Self> {
+        let shared_key = load_shared_key(storage)?;
+        let session = load_session(storage, session_id)?
+            .with_context(|| format!("No session {} in this wallet", session_id))?;
+        let sighash = session["sighash"]
+            .as_str()
+            .context("Session file missing or invalid 'sighash' field")?
+            .to_string();
+        let prevouts = match session.get("prevouts") {
+            Some(prevouts) if !prevouts.is_null() => serde_json::from_value(prevouts.clone())
+                .context("Session file has invalid 'prevouts'")?,
+            _ => Vec::new(),
+        };
+        let shares: Vec<DkgSignatureShareOutput> = parse_payloads(
+            shares_data,
+            &Expected {
+                session: Some(session_id),
+                sighash: Some(&sighash),
+                ..Expected::new("signature shares", &["dkg_signature_share"])
+            },
+        )?;
+        let nonces: Vec<NonceOutput> = serde_json::from_slice(
+            &storage.read(&format!("dkg_session_nonces_{}.json", session_id))?,
+        )?;
+        let roles = SessionRoles::from_signers(shares.iter().map(|s| s.party_index))?;
+        let leaf = session_leaf(storage, session_id)?;
+        let signature =
+            witness_signature(tx, leaf.as_ref()).context("Transaction has no signature")?;
+
+        Ok(Self {
+            session_id: session_id.to_string(),
+            memo: session["memo"].as_str().map(str::to_string),
+            network: network.to_string(),
+            group_key: hex::encode(shared_key.public_key().to_xonly_bytes()),
+            merkle_root: session_merkle_root(storage, session_id)?.map(hex::encode),
+            leaf,
+            prevouts,
+            sighash,
+            signers: roles.signers,
+            aggregator: roles.aggregator,
+            nonces,
+            shares,
+            signature: hex::encode(signature),
+            txid: tx.compute_txid().to_string(),
+            raw_tx: bitcoin::consensus::encode::serialize_hex(tx),
+            broadcast: broadcast.to_string(),
+            created_at: health::now(),
+            event_type: "dkg_session_report".to_string(),
+        })
+    }
+
+    /// Report saved for `session_id`, if any
+    pub fn load(storage: &dyn Storage, session_id: &str) -> Result<Option<Self>> {
+        let file = report_file(session_id);
+        if !storage.exists(&file) {
+            return Ok(None);
+        }
+        serde_json::from_slice(&storage.read(&file)?)
+            .map(Some)
+            .with_context(|| format!("{} is damaged", file))
+    }
+
+    pub fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.write(
+            &report_file(&self.session_id),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// Human-readable account of what was signed
+    ///
+    /// Outputs paying one of the wallet's own scripts are marked as change.
+    pub fn summary(&self, own: Option<&ScriptIndex>) -> String {
+        let network = parse_network(&self.network).unwrap_or(Network::Testnet);
+        let tx: Option<Transaction> = hex::decode(&self.raw_tx)
+            .ok()
+            .and_then(|bytes| bitcoin::consensus::deserialize(&bytes).ok());
+
+        let mut out = String::new();
+        out.push_str(&format!("Session:    {}\n", self.session_id));
+        if let Some(memo) = &self.memo {
+            out.push_str(&format!("Memo:       {}\n", memo));
+        }
+        out.push_str(&format!("Network:    {}\n", self.network));
+        out.push_str(&format!("Group key:  {}\n", self.group_key));
+        if let Some(root) = &self.merkle_root {
+            out.push_str(&format!("Merkle root: {}\n", root));
+        }
+        if let Some(leaf) = &self.leaf {
+            out.push_str(&format!("Spend path: leaf {}\n", leaf.index));
+        }
+        let spent: u64 = self.prevouts.iter().map(|p| p.value).sum();
+        if !self.prevouts.is_empty() {
+            out.push_str(&format!(
+                "Spent:      {} from {} output(s)\n",
+                format_amount(spent),
+                self.prevouts.len()
+            ));
+        }
+        if let Some(tx) = &tx {
+            out.push_str("Paid to:\n");
+            for output in &tx.output {
+                let to = Address::from_script(&output.script_pubkey, network)
+                    .map(|a| a.to_string())
+                    .unwrap_or_else(|_| hex::encode(output.script_pubkey.as_bytes()));
+                let change = own
+                    .and_then(|own| own.owner(&output.script_pubkey))
+                    .map(|owner| format!("  (change, {})", owner.origin))
+                    .unwrap_or_default();
+                out.push_str(&format!(
+                    "   {}  {}{}\n",
+                    to,
+                    format_amount(output.value.to_sat()),
+                    change
+                ));
+            }
+            let paid: u64 = tx.output.iter().map(|o| o.value.to_sat()).sum();
+            if !self.prevouts.is_empty() {
+                out.push_str(&format!(
+                    "Fee:        {}\n",
+                    format_amount(spent.saturating_sub(paid))
+                ));
+            }
+        }
+        out.push_str(&format!("Sighash:    {}\n", self.sighash));
+        out.push_str(&format!(
+            "Signers:    {} (aggregator: party {})\n",
+            self.signers
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.aggregator
+        ));
+        out.push_str(&format!("Signature:  {}\n", self.signature));
+        out.push_str(&format!("TxID:       {}\n", self.txid));
+        out.push_str(&format!("Broadcast:  {}\n", self.broadcast));
+        out
+    }
+
+    /// Check the report against this party's own wallet, listing what held
+    pub fn verify(&self, storage: &dyn Storage) -> Result<Vec<String>> {
+        let mut checks = Vec::new();
+
+        let shared_key = load_shared_key(storage)?;
+        let group_key = hex::encode(shared_key.public_key().to_xonly_bytes());
+        if !group_key.eq_ignore_ascii_case(&self.group_key) {
+            bail!(Error::Protocol(format!(
+                "Report is for group key {}, but this wallet's is {}",
+                self.group_key, group_key
+            )));
+        }
+        checks.push("Group key is this wallet's".to_string());
+
+        // Only a party that took part has its own record to compare against
+        let session = load_session(storage, &self.session_id)?.ok_or_else(|| {
+            Error::User(format!(
+                "No session {} in this wallet; only its signers can check its report",
+                self.session_id
+            ))
+        })?;
+        if session["sighash"].as_str() != Some(self.sighash.as_str()) {
+            bail!(Error::Protocol(format!(
+                "Report signs sighash {}, but this party signed {} in session {}",
+                self.sighash,
+                session["sighash"].as_str().unwrap_or("nothing"),
+                self.session_id
+            )));
+        }
+        let merkle_root = self
+            .merkle_root
+            .as_deref()
+            .map(parse_merkle_root)
+            .transpose()?;
+        if merkle_root != session_merkle_root(storage, &self.session_id)? {
+            bail!(Error::Protocol(
+                "Report's merkle root differs from this party's session".to_string()
+            ));
+        }
+        if self.leaf != session_leaf(storage, &self.session_id)? {
+            bail!(Error::Protocol(
+                "Report's spend path differs from this party's session".to_string()
+            ));
+        }
+        checks.push("Sighash is the one this party signed".to_string());
+        if let (Some(reported), Some(local)) = (&self.memo, session["memo"].as_str()) {
+            if reported != local {
+                bail!(Error::Protocol(format!(
+                    "Report's memo \"{}\" differs from this party's \"{}\"",
+                    reported, local
+                )));
+            }
+        }
+        if let Some(memo) = self.memo.as_deref() {
+            check_memo(memo)?;
+        }
+
+        let nonces_file = format!("dkg_session_nonces_{}.json", self.session_id);
+        if storage.exists(&nonces_file) {
+            let local: Vec<NonceOutput> = serde_json::from_slice(&storage.read(&nonces_file)?)?;
+            let reported: Vec<(u32, &str)> = self
+                .nonces
+                .iter()
+                .map(|n| (n.party_index, n.nonce.as_str()))
+                .collect();
+            if local.len() != reported.len()
+                || local
+                    .iter()
+                    .any(|n| !reported.contains(&(n.party_index, n.nonce.as_str())))
+            {
+                bail!(Error::Protocol(format!(
+                    "Report's nonces differ from the ones this party signed with in session {}",
+                    self.session_id
+                )));
+            }
+            checks.push("Nonces are the ones this party signed with".to_string());
+        }
+
+        let mut notes = String::new();
+        identity::verify_payloads(&self.nonces, storage, &mut notes)?;
+        identity::verify_payloads(&self.shares, storage, &mut notes)?;
+
+        let tx: Transaction = bitcoin::consensus::deserialize(
+            &hex::decode(&self.raw_tx).context("Report's raw_tx is not hex")?,
+        )
+        .context("Report's raw_tx is not a transaction")?;
+        if tx.compute_txid().to_string() != self.txid {
+            bail!(Error::Protocol(format!(
+                "Report's transaction has txid {}, not {}",
+                tx.compute_txid(),
+                self.txid
+            )));
+        }
+        let witness_sig = witness_signature(&tx, self.leaf.as_ref());
+        if witness_sig.map(hex::encode).as_deref() != Some(self.signature.as_str()) {
+            bail!(Error::Protocol(
+                "Report's signature is not the one in its transaction".to_string()
+            ));
+        }
+        if let Some(unsigned) = session["unsigned_tx"].as_str() {
+            let mut stripped = tx.clone();
+            for input in &mut stripped.input {
+                input.witness.clear();
+            }
+            if !bitcoin::consensus::encode::serialize_hex(&stripped).eq_ignore_ascii_case(unsigned)
+            {
+                bail!(Error::Protocol(
+                    "Report's transaction is not the one this party built".to_string()
+                ));
+            }
+            checks.push("Transaction is the one this party built".to_string());
+        }
+
+        let sighash: [u8; 32] = hex::decode(&self.sighash)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid sighash length in report"))?;
+        if self.prevouts.is_empty() {
+            checks.push(
+                "Spent outputs not recorded; sighash not tied to the transaction".to_string(),
+            );
+        } else {
+            let computed = spend_sighash(&tx, &self.prevouts, self.leaf.as_ref())?;
+            if computed != sighash {
+                bail!(Error::Protocol(
+                    "Report's sighash does not commit to its transaction".to_string()
+                ));
+            }
+            checks.push("Sighash commits to the transaction".to_string());
+        }
+
+        let (combined, _) = combine_signature_shares(
+            &shared_key,
+            &self.nonces,
+            &sighash,
+            &self.shares,
+            SessionKey::of(merkle_root.as_ref(), self.leaf.as_ref()),
+        )?;
+        if hex::encode(combined) != self.signature {
+            bail!(Error::Protocol(
+                "Report's shares do not combine into its signature".to_string()
+            ));
+        }
+        checks.push(format!(
+            "Shares of parties {:?} combine into the signature",
+            self.signers
+        ));
+
+        let secp = bitcoin::secp256k1::Secp256k1::verification_only();
+        let internal =
+            bitcoin::key::XOnlyPublicKey::from_slice(&shared_key.public_key().to_xonly_bytes())?;
+        // A leaf checks the signature against the group key itself
+        let (signing_key, key_name) = match &self.leaf {
+            Some(_) => (internal, "group key"),
+            None => {
+                let (output_key, _) = internal.tap_tweak(
+                    &secp,
+                    merkle_root.map(bitcoin::TapNodeHash::from_byte_array),
+                );
+                (output_key.to_x_only_public_key(), "output key")
+            }
+        };
+        secp.verify_schnorr(
+            &bitcoin::secp256k1::schnorr::Signature::from_slice(&combined)?,
+            &bitcoin::secp256k1::Message::from_digest(sighash),
+            &signing_key,
+        )
+        .map_err(|_| {
+            Error::Protocol("Report's signature is invalid for this wallet".to_string())
+        })?;
+        checks.push(format!("Signature is valid for this wallet's {}", key_name));
+
+        Ok(checks)
+    }
+}
+
+fn load_shared_key(storage: &dyn Storage) -> Result<SharedKey<EvenY>> {
+    require_secp256k1(storage)?;
+    Ok(bincode::deserialize(&storage.read("shared_key.bin")?)?)
+}
+
+fn load_session(storage: &dyn Storage, session_id: &str) -> Result<Option<serde_json::Value>> {
+    let file = format!("dkg_session_{}.json", session_id);
+    if !storage.exists(&file) {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(&storage.read(&file)?)?))
+}
+
+/// Show session `session_id`'s report, or write it as JSON to `out`
+pub fn session_report_core(
+    session_id: &str,
+    out: Option<&Path>,
+    storage: &dyn Storage,
+) -> Result<CommandResult> {
+    let report = SessionReport::load(storage, session_id)?.ok_or_else(|| {
+        Error::User(format!(
+            "No report for session {}; reports are written by dkg-broadcast, \
+             or imported with --import",
+            session_id
+        ))
+    })?;
+    let mut output = report.summary(ScriptIndex::load(storage).ok().as_ref());
+    if let Some(path) = out {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        output.push_str(&format!(
+            "\nReport written to {}; co-signers check and keep it with\n   \
+             frostdao dkg-session-report --name <wallet> --import {}\n",
+            path.display(),
+            path.display()
+        ));
+    }
+    Ok(CommandResult {
+        output,
+        result: serde_json::to_string(&report)?,
+    })
+}
+
+/// Check a co-signer's report against this wallet and keep it
+pub fn import_report_core(report_json: &str, storage: &dyn Storage) -> Result<CommandResult> {
+    let report: SessionReport = serde_json::from_str(report_json)
+        .map_err(|e| Error::User(format!("Not a session report: {}", e)))?;
+    if report.event_type != "dkg_session_report" {
+        bail!(Error::User(format!(
+            "Expected a dkg_session_report, got {}",
+            report.event_type
+        )));
+    }
+    let checks = report.verify(storage)?;
+    report.save(storage)?;
+
+    let mut output = report.summary(ScriptIndex::load(storage).ok().as_ref());
+    output.push('\n');
+    for check in checks {
+        output.push_str(&format!("✓ {}\n", check));
+    }
+    output.push_str(&format!(
+        "\nReport saved as {}\n",
+        report_file(&report.session_id)
+    ));
+    Ok(CommandResult {
+        output,
+        result: serde_json::to_string(&report)?,
+    })
+}
+
+/// CLI wrapper for `dkg-session-report`
+pub fn session_report(
+    name: &str,
+    session_id: Option<&str>,
+    out: Option<&Path>,
+    import: Option<&Path>,
+) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let result = match (import, session_id) {
+        (Some(path), _) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            import_report_core(&json, &storage)?
+        }
+        (None, Some(session_id)) => session_report_core(session_id, out, &storage)?,
+        (None, None) => bail!(Error::User(
+            "Pass --session to show a report or --import to check one".to_string()
+        )),
+    };
+    print!("{}", result.output);
+    Ok(())
+}
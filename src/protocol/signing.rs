@@ -1,8 +1,27 @@
-use crate::crypto::birkhoff::validate_signer_set;
-use crate::protocol::keygen::{parse_space_separated_json, HtssMetadata};
+use crate::crypto::birkhoff::{
+    describe_signing_requirement, preview_coefficients, validate_signer_set,
+};
+use crate::crypto::ciphersuite::{
+    self, decode_point, decode_scalar, with_suite, Ciphersuite, Curve,
+};
+use crate::crypto::helpers::lagrange_coefficient_in;
+use crate::crypto::secret::{hex_secret, serialize_secret, to_json_secret, SecretString};
+use crate::protocol::device;
+use crate::protocol::events::{EventLog, NO_LINES};
+use crate::protocol::health;
+use crate::protocol::identity::{self, Authenticated};
+use crate::protocol::keygen::{
+    get_state_dir, is_curve_wallet, load_curve_secret_share, FrostKey, HtssMetadata,
+};
+use crate::protocol::payload::{
+    self, check_signer_set, decode_hex_field, parse_payloads, share_index, Expected,
+};
+use crate::protocol::reporter::{cli_reporter, NoopReporter, Reporter};
+use crate::protocol::weighted;
 use crate::storage::{FileStorage, Storage};
 use crate::CommandResult;
 use anyhow::{Context, Result};
+use rand::RngCore;
 use rand_chacha::ChaCha20Rng;
 use schnorr_fun::binonce::NonceKeyPair;
 use schnorr_fun::frost::{self, PairedSecretShare, SharedKey};
@@ -14,6 +33,14 @@ use std::collections::BTreeMap;
 
 const STATE_DIR: &str = ".frost_state";
 
+/// Signing state: the named wallet, or the `.frost_state` root
+fn signing_storage(name: Option<&str>) -> Result<FileStorage> {
+    match name {
+        Some(name) => FileStorage::new(&get_state_dir(name)),
+        None => FileStorage::new(STATE_DIR),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NonceOutput {
     pub party_index: u32,
@@ -21,8 +48,35 @@ pub struct NonceOutput {
     pub rank: u32, // HTSS rank for signer validation
     pub session: String,
     pub nonce: String, // Bincode hex of public nonce
+    /// Memo of the session as this party has it (see dkg_tx::check_memo)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    /// Payload protocol version (see protocol::payload); absent means v1
+    #[serde(
+        default = "crate::protocol::payload::legacy_version",
+        skip_serializing_if = "crate::protocol::payload::is_legacy_version"
+    )]
+    pub protocol_version: u32,
     #[serde(rename = "type")]
     pub event_type: String,
+    /// Sender's signature over this payload (see protocol::identity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+}
+
+impl Authenticated for NonceOutput {
+    fn signer_index(&self) -> u32 {
+        self.party_index
+    }
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+    fn set_auth(&mut self, auth: String) {
+        self.auth = Some(auth);
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -43,8 +97,32 @@ pub struct SignatureShareOutput {
     pub session: String,
     pub message: String,
     pub signature_share: String,
+    /// Payload protocol version (see protocol::payload); absent means v1
+    #[serde(
+        default = "crate::protocol::payload::legacy_version",
+        skip_serializing_if = "crate::protocol::payload::is_legacy_version"
+    )]
+    pub protocol_version: u32,
     #[serde(rename = "type")]
     pub event_type: String,
+    /// Sender's signature over this payload (see protocol::identity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+}
+
+impl Authenticated for SignatureShareOutput {
+    fn signer_index(&self) -> u32 {
+        self.party_index
+    }
+    fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    fn auth(&self) -> Option<&str> {
+        self.auth.as_deref()
+    }
+    fn set_auth(&mut self, auth: String) {
+        self.auth = Some(auth);
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -61,7 +139,22 @@ pub struct SignatureShareData {
 }
 
 pub fn generate_nonce_core(session: &str, storage: &dyn Storage) -> Result<CommandResult> {
-    let mut out = String::new();
+    generate_nonce_core_with_reporter(session, storage, &mut NoopReporter)
+}
+
+/// [`generate_nonce_core`], reporting progress to `reporter`
+pub fn generate_nonce_core_with_reporter(
+    session: &str,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let mut events = EventLog::with_reporter(reporter);
+    let events = &mut events;
+    if is_curve_wallet(storage) {
+        let key = FrostKey::load(storage)?;
+        return with_suite!(key.curve, C => generate_nonce_in::<C>(session, storage, events));
+    }
+    device::require_local(storage, "generate-nonce")?;
 
     // Load HTSS metadata
     let htss_metadata: HtssMetadata = {
@@ -74,22 +167,22 @@ pub fn generate_nonce_core(session: &str, storage: &dyn Storage) -> Result<Comma
     } else {
         "TSS"
     };
-    out.push_str(&format!(
-        "FROST Signing ({}) - Nonce Generation\n\n",
-        mode_name
-    ));
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str(&format!("Session ID: {}\n", session));
+    events.started(format!("FROST Signing ({}) - Nonce Generation", mode_name));
+    events.divider();
+    events.detail("Session ID", session);
     if htss_metadata.hierarchical {
-        out.push_str(&format!("Your rank: {}\n", htss_metadata.my_rank));
+        events.detail("Your rank", htss_metadata.my_rank);
     }
-    out.push_str("⚠  NEVER reuse a nonce as it will leak your secret share!\n");
-    out.push_str("    Each signature needs fresh nonces!\n");
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    events.warning(
+        "NEVER reuse a nonce as it will leak your secret share!",
+        ["Each signature needs fresh nonces!"],
+    );
+    events.divider();
+    events.note("\n");
 
     // Load paired secret share
     let paired_share_bytes = storage
-        .read("paired_secret_share.bin")
+        .read_secret("paired_secret_share.bin")
         .context("Failed to load secret share. Did you run keygen-finalize?")?;
     let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
 
@@ -103,8 +196,10 @@ pub fn generate_nonce_core(session: &str, storage: &dyn Storage) -> Result<Comma
 
     let my_rank = htss_metadata.my_rank;
 
-    out.push_str("⚙️  Using schnorr_fun's FROST nonce generation\n");
-    out.push_str("   Calling: frost.seed_nonce_rng() and frost.gen_nonce()\n\n");
+    events.progress(
+        "Using schnorr_fun's FROST nonce generation",
+        ["Calling: frost.seed_nonce_rng() and frost.gen_nonce()"],
+    );
 
     // Create FROST instance with deterministic nonces
     let frost = frost::new_with_synthetic_nonces::<Sha256, rand::rngs::ThreadRng>();
@@ -115,25 +210,37 @@ pub fn generate_nonce_core(session: &str, storage: &dyn Storage) -> Result<Comma
     // Generate nonce
     let nonce = frost.gen_nonce(&mut nonce_rng);
 
-    out.push_str("❄️  Generated NonceKeyPair:\n");
-    out.push_str("   - Secret nonces: (k₁, k₂) - kept private\n");
-    out.push_str("   - Public nonces: (R₁, R₂) where R₁ = k₁*G, R₂ = k₂*G\n\n");
-    out.push_str("🧠 Why do we need nonces?\n");
-    out.push_str("   Schnorr signatures require randomness to be secure!\n");
-    out.push_str("   If you ever reuse a nonce with the same key, an attacker\n");
-    out.push_str("   can solve for your secret share and steal your key.\n");
-    out.push_str("   \n");
-    out.push_str("   FROST uses TWO nonces (k₁, k₂) for extra security:\n");
-    out.push_str("   • k₁ is the primary nonce\n");
-    out.push_str("   • k₂ protects against rogue-key attacks in multi-party signing\n\n");
-    out.push_str("❓ Think about it:\n");
-    out.push_str("   Notice: We can generate nonces BEFORE knowing the message!\n");
-    out.push_str("   Current flow: share nonces → then sign (2 rounds)\n");
-    out.push_str("   How could we optimize FROST to sign in just 1 round?\n");
-    out.push_str("   (Hint: What if we pre-shared nonces?)\n\n");
+    events.success(
+        "Generated NonceKeyPair:",
+        [
+            "- Secret nonces: (k₁, k₂) - kept private",
+            "- Public nonces: (R₁, R₂) where R₁ = k₁*G, R₂ = k₂*G",
+        ],
+    );
+    events.explain(
+        "Why do we need nonces?",
+        [
+            "Schnorr signatures require randomness to be secure!",
+            "If you ever reuse a nonce with the same key, an attacker",
+            "can solve for your secret share and steal your key.",
+            "",
+            "FROST uses TWO nonces (k₁, k₂) for extra security:",
+            "• k₁ is the primary nonce",
+            "• k₂ protects against rogue-key attacks in multi-party signing",
+        ],
+    );
+    events.question(
+        "Think about it:",
+        [
+            "Notice: We can generate nonces BEFORE knowing the message!",
+            "Current flow: share nonces → then sign (2 rounds)",
+            "How could we optimize FROST to sign in just 1 round?",
+            "(Hint: What if we pre-shared nonces?)",
+        ],
+    );
 
     // Serialize nonce keypair for later use
-    let nonce_bytes = bincode::serialize(&nonce)?;
+    let nonce_bytes = serialize_secret(&nonce)?;
     storage.write(&format!("nonce_{}.bin", session), &nonce_bytes)?;
 
     // Serialize public nonce for sharing
@@ -141,43 +248,48 @@ pub fn generate_nonce_core(session: &str, storage: &dyn Storage) -> Result<Comma
     let public_nonce_bytes = bincode::serialize(&public_nonce)?;
     let public_nonce_hex = hex::encode(&public_nonce_bytes);
 
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str("✉️  Your public nonce generated!\n\n");
-
-    out.push_str("➜ Paste the result JSON into the webpage\n");
-    out.push_str("➜ Wait for threshold number of signers to post nonces\n");
-    out.push_str(&format!(
-        "➜ Copy the \"nonces for session {}\" JSON from webpage\n",
+    events.divider();
+    events.produced("Your public nonce generated!");
+    events.next_step("Paste the result JSON into the webpage");
+    events.next_step("Wait for threshold number of signers to post nonces");
+    events.next_step(format!(
+        "Copy the \"nonces for session {}\" JSON from webpage",
         session
     ));
-    out.push_str(&format!(
-        "➜ Run: yushan sign --session {} --message \"<msg>\" --data '<JSON>'\n",
+    events.next_step(format!(
+        "Run: yushan sign --session {} --message \"<msg>\" --data '<JSON>'",
         session
     ));
 
     // Create JSON result for copy-pasting
-    let output = NonceOutput {
+    let mut output = NonceOutput {
         party_index,
         rank: my_rank,
         session: session.to_string(),
         nonce: public_nonce_hex,
+        memo: None,
+        protocol_version: payload::output_version(),
         event_type: "signing_nonce".to_string(),
+        auth: None,
     };
+    identity::sign_payload(&mut output, storage)?;
     let result = serde_json::to_string(&output)?;
 
-    Ok(CommandResult {
-        output: out,
-        result,
-    })
+    Ok(events.finish(result))
 }
 
-pub fn generate_nonce(session: &str) -> Result<()> {
-    let storage = FileStorage::new(STATE_DIR)?;
-    let cmd_result = generate_nonce_core(session, &storage)?;
-    println!("{}", cmd_result.output);
+pub fn generate_nonce(name: Option<&str>, session: &str) -> Result<()> {
+    let storage = signing_storage(name)?;
+    let mut reporter = cli_reporter();
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        generate_nonce_core_with_reporter(session, slot, reporter.as_mut())
+    })?;
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Copy this JSON:");
-    println!("{}\n", cmd_result.result);
+    println!(
+        "{}\n",
+        crate::protocol::compact::present(&cmd_result.result)
+    );
     Ok(())
 }
 
@@ -187,7 +299,26 @@ pub fn create_signature_share_core(
     data: &str,
     storage: &dyn Storage,
 ) -> Result<CommandResult> {
-    let mut out = String::new();
+    create_signature_share_core_with_reporter(session, message, data, storage, &mut NoopReporter)
+}
+
+/// [`create_signature_share_core`], reporting progress to `reporter`
+pub fn create_signature_share_core_with_reporter(
+    session: &str,
+    message: &str,
+    data: &str,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let mut events = EventLog::with_reporter(reporter);
+    let events = &mut events;
+    if is_curve_wallet(storage) {
+        let key = FrostKey::load(storage)?;
+        return with_suite!(key.curve, C => {
+            create_signature_share_in::<C>(&key, session, message, data, storage, events)
+        });
+    }
+    device::require_local(storage, "sign")?;
 
     // Load HTSS metadata
     let htss_metadata: HtssMetadata = {
@@ -200,19 +331,19 @@ pub fn create_signature_share_core(
     } else {
         "TSS"
     };
-    out.push_str(&format!(
-        "🔐 FROST Signing ({}) - Create Signature Share\n\n",
+    events.started(format!(
+        "🔐 FROST Signing ({}) - Create Signature Share",
         mode_name
     ));
 
     // Load nonce
     let nonce_bytes = storage
-        .read(&format!("nonce_{}.bin", session))
+        .read_secret(&format!("nonce_{}.bin", session))
         .context("Failed to load nonce. Did you run generate-nonce?")?;
     let nonce: NonceKeyPair = bincode::deserialize(&nonce_bytes)?;
 
     // Load paired secret share
-    let paired_share_bytes = storage.read("paired_secret_share.bin")?;
+    let paired_share_bytes = storage.read_secret("paired_secret_share.bin")?;
     let paired_share: PairedSecretShare<EvenY> = bincode::deserialize(&paired_share_bytes)?;
 
     let party_index = {
@@ -228,7 +359,19 @@ pub fn create_signature_share_core(
     let shared_key: SharedKey<EvenY> = bincode::deserialize(&shared_key_bytes)?;
 
     // Parse input - space-separated NonceOutput objects
-    let nonce_outputs: Vec<NonceOutput> = parse_space_separated_json(data)?;
+    let nonce_outputs: Vec<NonceOutput> = parse_payloads(
+        data,
+        &Expected {
+            session: Some(session),
+            ..Expected::new("signing nonces", &["signing_nonce"])
+        },
+    )?;
+    let mut verified = String::new();
+    identity::verify_payloads(&nonce_outputs, storage, &mut verified)?;
+    events.note(verified);
+    if let Some(note) = payload::downgrade_note(payload::reply_version(&nonce_outputs)) {
+        events.note(note);
+    }
 
     // Extract signer indices and ranks
     let signer_ranks: Vec<(u32, u32)> = nonce_outputs
@@ -251,17 +394,31 @@ pub fn create_signature_share_core(
 
     // Validate signer set in HTSS mode (additional rank-based validation)
     if htss_metadata.hierarchical {
-        out.push_str("🔐 HTSS Signer Validation:\n");
-        out.push_str(&format!("   Signers: {:?}\n", signer_ranks));
-
         validate_signer_set(&ranks_only, htss_metadata.threshold)?;
 
-        out.push_str("   ✅ Signer set is valid for HTSS!\n\n");
+        events.success(
+            "HTSS signer set is valid",
+            [format!("Signers (index, rank): {:?}", signer_ranks)],
+        );
     } else {
-        out.push_str(&format!(
-            "✓ Signer count validated: {} signers (threshold: {})\n\n",
-            num_signers, htss_metadata.threshold
-        ));
+        events.success(
+            format!(
+                "Signer count validated: {} signers (threshold: {})",
+                num_signers, htss_metadata.threshold
+            ),
+            NO_LINES,
+        );
+    }
+    if !htss_metadata.signing_requirement.is_empty() {
+        let signers: Vec<u32> = signer_ranks.iter().map(|(index, _)| *index).collect();
+        htss_metadata.check_signing_requirement(&signers)?;
+        events.success(
+            format!(
+                "Signing requirement met ({})",
+                describe_signing_requirement(&htss_metadata.signing_requirement)
+            ),
+            NO_LINES,
+        );
     }
 
     // Convert to expected format
@@ -279,32 +436,35 @@ pub fn create_signature_share_core(
         public_key: public_key_hex,
     };
 
-    out.push_str(&format!("🔏 Signing with {} parties\n", num_signers));
-    out.push_str(&format!("  Message: \"{}\"\n\n", message));
-
-    out.push_str("📐 Using schnorr_fun's FROST signing\n");
-    out.push_str("   Calling: frost.party_sign_session()\n\n");
+    events.detail("Signers", num_signers);
+    events.detail("Message", format!("\"{}\"", message));
+    events.note("\n");
+    events.progress(
+        "Using schnorr_fun's FROST signing",
+        ["Calling: frost.party_sign_session()"],
+    );
 
     // Reconstruct nonces map
     let mut nonces_map = BTreeMap::new();
     for nonce_data in &input.nonces {
-        let nonce_bytes = hex::decode(&nonce_data.nonce)?;
-        let public_nonce: schnorr_fun::binonce::Nonce = bincode::deserialize(&nonce_bytes)?;
-
-        let share_index = Scalar::<Secret, Zero>::from(nonce_data.index)
-            .non_zero()
-            .expect("index should be nonzero")
-            .public();
-        nonces_map.insert(share_index, public_nonce);
+        let public_nonce: schnorr_fun::binonce::Nonce = decode_hex_field(
+            &format!("Party {}'s nonce", nonce_data.index),
+            &nonce_data.nonce,
+        )?;
+        nonces_map.insert(share_index(nonce_data.index)?, public_nonce);
     }
 
     // Create FROST instance
     let frost = frost::new_with_deterministic_nonces::<Sha256>();
 
-    out.push_str("🔢 Creating coordinator sign session...\n");
-    out.push_str("   Aggregating all nonces\n");
-    out.push_str("   Computing binding coefficient\n");
-    out.push_str("   Computing challenge = H(R || PubKey || message)\n\n");
+    events.progress(
+        "Creating coordinator sign session...",
+        [
+            "Aggregating all nonces",
+            "Computing binding coefficient",
+            "Computing challenge = H(R || PubKey || message)",
+        ],
+    );
 
     // Create message
     let msg = Message::new("frostsnap-yushan", message.as_bytes());
@@ -312,19 +472,23 @@ pub fn create_signature_share_core(
     // Create coordinator session
     let coord_session = frost.coordinator_sign_session(&shared_key, nonces_map.clone(), msg);
 
-    out.push_str("✓ Coordinator session created:\n");
-    out.push_str("   - Aggregated nonce: R = R1 + R2 + ...\n");
-    out.push_str("   - Challenge: c = H(R || PK || msg)\n");
-    out.push_str(&format!(
-        "   - Parties: {:?}\n\n",
-        coord_session
-            .parties()
-            .iter()
-            .map(|s| s.to_bytes()[0] as u32)
-            .collect::<Vec<_>>()
-    ));
+    events.success(
+        "Coordinator session created:",
+        [
+            "- Aggregated nonce: R = R1 + R2 + ...",
+            "- Challenge: c = H(R || PK || msg)",
+            &format!(
+                "- Parties: {:?}",
+                coord_session
+                    .parties()
+                    .iter()
+                    .map(|s| s.to_bytes()[0] as u32)
+                    .collect::<Vec<_>>()
+            ),
+        ],
+    );
 
-    out.push_str("📝 Creating party sign session...\n");
+    events.progress("Creating party sign session...", NO_LINES);
     let agg_binonce = coord_session.agg_binonce();
     let parties = coord_session.parties();
 
@@ -332,73 +496,87 @@ pub fn create_signature_share_core(
         frost.party_sign_session(shared_key.public_key(), parties.clone(), agg_binonce, msg);
 
     if htss_metadata.hierarchical {
-        out.push_str("⚙️  Computing Birkhoff coefficient (HTSS)...\n");
-        out.push_str("🧠 Why Birkhoff coefficients?\n");
-        out.push_str("   In HTSS, each share has both an index AND a rank.\n");
-        out.push_str("   Birkhoff interpolation uses derivative information (ranks)\n");
-        out.push_str("   to compute coefficients, not just point values.\n");
-        out.push_str("   \n");
-        out.push_str(&format!(
-            "   Your share: index={}, rank={}\n",
-            party_index, htss_metadata.my_rank
-        ));
-        out.push_str("   \n");
-        out.push_str("   Birkhoff coefficients ensure only VALID rank combinations\n");
-        out.push_str("   can reconstruct the secret (satisfying n_i <= i rule).\n\n");
-        out.push_str("❓ Think about it:\n");
-        out.push_str("   HTSS creates a hierarchy of signing authority.\n");
-        out.push_str("   Higher ranks (lower numbers) have more authority.\n");
-        out.push_str("   How might this be useful in organizational structures?\n\n");
+        events.progress("Computing Birkhoff coefficient (HTSS)...", NO_LINES);
+        events.explain(
+            "Why Birkhoff coefficients?",
+            [
+                "In HTSS, each share has both an index AND a rank.",
+                "Birkhoff interpolation uses derivative information (ranks)",
+                "to compute coefficients, not just point values.",
+                "",
+                &format!(
+                    "Your share: index={}, rank={}",
+                    party_index, htss_metadata.my_rank
+                ),
+                "",
+                "Birkhoff coefficients ensure only VALID rank combinations",
+                "can reconstruct the secret (satisfying n_i <= i rule).",
+            ],
+        );
+        events.question(
+            "Think about it:",
+            [
+                "HTSS creates a hierarchy of signing authority.",
+                "Higher ranks (lower numbers) have more authority.",
+                "How might this be useful in organizational structures?",
+            ],
+        );
     } else {
-        out.push_str("⚙️  Computing Lagrange coefficient (TSS)...\n");
-        out.push_str("🧠 Why Lagrange coefficients?\n");
-        out.push_str(&format!(
-            "   During keygen, you received a share for index {}\n",
-            party_index
-        ));
-        out.push_str(&format!(
-            "   But only {} parties are signing in this session!\n",
-            num_signers
-        ));
-        out.push_str("   \n");
-        out.push_str("   Lagrange interpolation adjusts your share to work with\n");
-        out.push_str("   ANY threshold subset of signers (not just all parties).\n");
-        out.push_str("   \n");
-        out.push_str(&format!(
-            "   λ{} = the coefficient that makes YOUR share compatible\n",
-            party_index
-        ));
-        out.push_str(&format!(
-            "   with this specific group of {} signers.\n\n",
-            num_signers
-        ));
-        out.push_str("❓ Think about it:\n");
-        out.push_str(&format!(
-            "   You've selected a specific group of {} signers for this signature.\n",
-            num_signers
-        ));
-        out.push_str("   What downstream implication does this have?\n");
-        out.push_str("   (Hint: How does this differ from Bitcoin script multisig,\n");
-        out.push_str("   where ANY threshold combination can spend?)\n\n");
+        events.progress("Computing Lagrange coefficient (TSS)...", NO_LINES);
+        events.explain(
+            "Why Lagrange coefficients?",
+            [
+                &format!(
+                    "During keygen, you received a share for index {}",
+                    party_index
+                ),
+                &format!(
+                    "But only {} parties are signing in this session!",
+                    num_signers
+                ),
+                "",
+                "Lagrange interpolation adjusts your share to work with",
+                "ANY threshold subset of signers (not just all parties).",
+                "",
+                &format!(
+                    "λ{} = the coefficient that makes YOUR share compatible",
+                    party_index
+                ),
+                &format!("with this specific group of {} signers.", num_signers),
+            ],
+        );
+        events.question(
+            "Think about it:",
+            [
+                &format!(
+                    "You've selected a specific group of {} signers for this signature.",
+                    num_signers
+                ),
+                "What downstream implication does this have?",
+                "(Hint: How does this differ from Bitcoin script multisig,",
+                "where ANY threshold combination can spend?)",
+            ],
+        );
     }
 
-    out.push_str("⚙️  Creating signature share...\n");
-    out.push_str("🧠 Schnorr signature math:\n");
-    out.push_str(&format!(
-        "   s{} = k{} + λ{} × c × secret_share{}\n",
-        party_index, party_index, party_index, party_index
-    ));
-    out.push_str("   where:\n");
-    out.push_str(&format!("   • k{} = your secret nonce\n", party_index));
-    out.push_str(&format!(
-        "   • λ{} = your Lagrange coefficient\n",
-        party_index
-    ));
-    out.push_str("   • c = challenge = Hash(R || PubKey || message)\n");
-    out.push_str(&format!(
-        "   • secret_share{} = your piece of the private key\n\n",
-        party_index
-    ));
+    events.progress("Creating signature share...", NO_LINES);
+    events.explain(
+        "Schnorr signature math:",
+        [
+            &format!(
+                "s{} = k{} + λ{} × c × secret_share{}",
+                party_index, party_index, party_index, party_index
+            ),
+            "where:",
+            &format!("• k{} = your secret nonce", party_index),
+            &format!("• λ{} = your Lagrange coefficient", party_index),
+            "• c = challenge = Hash(R || PubKey || message)",
+            &format!(
+                "• secret_share{} = your piece of the private key",
+                party_index
+            ),
+        ],
+    );
 
     // Sign
     let sig_share = sign_session.sign(&paired_share, nonce);
@@ -417,45 +595,70 @@ pub fn create_signature_share_core(
         &format!("session_nonces_{}.json", session),
         nonces_json.as_bytes(),
     )?;
-
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str("✓ Your signature share generated!\n\n");
-
-    out.push_str("➜ Paste the result JSON into the webpage\n");
-    out.push_str("➜ Once all signers post shares, anyone can combine them\n");
-    out.push_str(&format!(
-        "➜ Run: yushan combine --message \"{}\" --data '<shares JSON>'\n",
+    let signers: Vec<u32> = nonce_outputs.iter().map(|o| o.party_index).collect();
+    health::record_session(storage, session, &signers)?;
+
+    events.divider();
+    events.produced("Your signature share generated!");
+    events.next_step("Paste the result JSON into the webpage");
+    events.next_step("Once all signers post shares, anyone can combine them");
+    events.next_step(format!(
+        "Run: yushan combine --message \"{}\" --data '<shares JSON>'",
         message
     ));
 
     // Create JSON result for copy-pasting
-    let output = SignatureShareOutput {
+    let mut output = SignatureShareOutput {
         party_index,
         session: session.to_string(),
         message: message.to_string(),
         signature_share: sig_share_hex,
+        protocol_version: payload::reply_version(&nonce_outputs),
         event_type: "signing_share".to_string(),
+        auth: None,
     };
+    identity::sign_payload(&mut output, storage)?;
     let result = serde_json::to_string(&output)?;
 
-    Ok(CommandResult {
-        output: out,
-        result,
-    })
+    Ok(events.finish(result))
 }
 
-pub fn create_signature_share(session: &str, message: &str, data: &str) -> Result<()> {
-    let storage = FileStorage::new(STATE_DIR)?;
-    let cmd_result = create_signature_share_core(session, message, data, &storage)?;
-    println!("{}", cmd_result.output);
+pub fn create_signature_share(
+    name: Option<&str>,
+    session: &str,
+    message: &str,
+    data: &str,
+) -> Result<()> {
+    let storage = signing_storage(name)?;
+    let mut reporter = cli_reporter();
+    let cmd_result = weighted::each_slot(&storage, |slot| {
+        create_signature_share_core_with_reporter(session, message, data, slot, reporter.as_mut())
+    })?;
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Copy this JSON:");
-    println!("{}\n", cmd_result.result);
+    println!(
+        "{}\n",
+        crate::protocol::compact::present(&cmd_result.result)
+    );
     Ok(())
 }
 
 pub fn combine_signatures_core(data: &str, storage: &dyn Storage) -> Result<CommandResult> {
-    let mut out = String::new();
+    combine_signatures_core_with_reporter(data, storage, &mut NoopReporter)
+}
+
+/// [`combine_signatures_core`], reporting progress to `reporter`
+pub fn combine_signatures_core_with_reporter(
+    data: &str,
+    storage: &dyn Storage,
+    reporter: &mut dyn Reporter,
+) -> Result<CommandResult> {
+    let mut events = EventLog::with_reporter(reporter);
+    let events = &mut events;
+    if is_curve_wallet(storage) {
+        let key = FrostKey::load(storage)?;
+        return with_suite!(key.curve, C => combine_signatures_in::<C>(&key, data, storage, events));
+    }
 
     // Load HTSS metadata
     let htss_metadata: HtssMetadata = {
@@ -468,13 +671,17 @@ pub fn combine_signatures_core(data: &str, storage: &dyn Storage) -> Result<Comm
     } else {
         "TSS"
     };
-    out.push_str(&format!(
-        "🔐 FROST Signing ({}) - Combine Signature Shares\n\n",
+    events.started(format!(
+        "🔐 FROST Signing ({}) - Combine Signature Shares",
         mode_name
     ));
 
     // Parse input - space-separated SignatureShareOutput objects
-    let sig_outputs: Vec<SignatureShareOutput> = parse_space_separated_json(data)?;
+    let sig_outputs: Vec<SignatureShareOutput> =
+        parse_payloads(data, &Expected::new("signature shares", &["signing_share"]))?;
+    let mut verified = String::new();
+    identity::verify_payloads(&sig_outputs, storage, &mut verified)?;
+    events.note(verified);
 
     // Extract message and session from first signature share
     // (all signers sign the same message in the same session)
@@ -507,37 +714,60 @@ pub fn combine_signatures_core(data: &str, storage: &dyn Storage) -> Result<Comm
         final_nonce: final_nonce_hex,
     };
 
-    out.push_str(&format!(
-        "✓ Received {} signature shares\n",
-        input.shares.len()
-    ));
-    out.push_str(&format!("  Message: \"{}\"\n\n", message));
-
-    out.push_str("⚙️  Using schnorr_fun's FROST coordinator API\n");
-    out.push_str("   Calling: coord_session.verify_and_combine_signature_shares()\n\n");
+    events.detail("Signature shares", input.shares.len());
+    events.detail("Message", format!("\"{}\"", message));
+    events.note("\n");
+    events.progress(
+        "Using schnorr_fun's FROST coordinator API",
+        ["Calling: coord_session.verify_and_combine_signature_shares()"],
+    );
 
     // Load saved nonces for this session
     let nonces_json = String::from_utf8(storage.read(&format!("session_nonces_{}.json", session))?)
         .context("Failed to load session nonces. Did a signer run the sign command?")?;
     let nonces_data: Vec<NonceData> = serde_json::from_str(&nonces_json)?;
 
-    out.push_str("⚙️  Recreating coordinator session...\n");
-    out.push_str("🧠 Why? The coordinator needs the same context that was used during signing:\n");
-    out.push_str("   - All participant nonces\n");
-    out.push_str("   - The message being signed\n");
-    out.push_str("   - The shared public key\n\n");
+    // Strict checks before anything is combined: one session, one message,
+    // and exactly the parties whose nonces made up the session
+    if let Some(output) = sig_outputs
+        .iter()
+        .find(|o| o.session != *session || o.message != *message)
+    {
+        anyhow::bail!(
+            "Party {}'s signature share is for session {} (\"{}\"), not session {} (\"{}\")",
+            output.party_index,
+            output.session,
+            output.message,
+            session,
+            message
+        );
+    }
+    check_signer_set(
+        &sig_outputs
+            .iter()
+            .map(|o| o.party_index)
+            .collect::<Vec<_>>(),
+        &nonces_data.iter().map(|n| n.index).collect::<Vec<_>>(),
+    )?;
+
+    events.progress("Recreating coordinator session...", NO_LINES);
+    events.explain(
+        "Why? The coordinator needs the same context that was used during signing:",
+        [
+            "- All participant nonces",
+            "- The message being signed",
+            "- The shared public key",
+        ],
+    );
 
     // Reconstruct nonces map
     let mut nonces_map = BTreeMap::new();
     for nonce_data in &nonces_data {
-        let nonce_bytes = hex::decode(&nonce_data.nonce)?;
-        let public_nonce: schnorr_fun::binonce::Nonce = bincode::deserialize(&nonce_bytes)?;
-
-        let share_index = Scalar::<Secret, Zero>::from(nonce_data.index)
-            .non_zero()
-            .expect("index should be nonzero")
-            .public();
-        nonces_map.insert(share_index, public_nonce);
+        let public_nonce: schnorr_fun::binonce::Nonce = decode_hex_field(
+            &format!("Party {}'s nonce", nonce_data.index),
+            &nonce_data.nonce,
+        )?;
+        nonces_map.insert(share_index(nonce_data.index)?, public_nonce);
     }
 
     // Create FROST instance
@@ -549,43 +779,36 @@ pub fn combine_signatures_core(data: &str, storage: &dyn Storage) -> Result<Comm
     // Recreate coordinator session
     let coord_session = frost.coordinator_sign_session(&shared_key, nonces_map, msg);
 
-    out.push_str("⚙️  Verifying and combining signature shares...\n");
-    out.push_str("🧠 What the coordinator does:\n");
-    out.push_str("   1. Verifies each signature share is valid\n");
-    out.push_str("   2. Checks: sig_share = k + λ × c × secret_share\n");
-    out.push_str("   3. Combines all shares: final_s = Σ sig_shares\n");
-    out.push_str("   4. Creates final signature (R, s)\n\n");
+    events.progress("Verifying and combining signature shares...", NO_LINES);
+    events.explain(
+        "What the coordinator does:",
+        [
+            "1. Verifies each signature share is valid",
+            "2. Checks: sig_share = k + λ × c × secret_share",
+            "3. Combines all shares: final_s = Σ sig_shares",
+            "4. Creates final signature (R, s)",
+        ],
+    );
 
     // Parse signature shares into the format the coordinator expects
     let mut sig_shares = BTreeMap::new();
     for share_data in &input.shares {
-        let share_bytes = hex::decode(&share_data.share)?;
-        let sig_share: Scalar<Public, Zero> = bincode::deserialize(&share_bytes)?;
-
-        let share_index = Scalar::<Secret, Zero>::from(share_data.index)
-            .non_zero()
-            .expect("index should be nonzero")
-            .public();
-        sig_shares.insert(share_index, sig_share);
-        out.push_str(&format!(
-            "   Verifying Party {}'s share...\n",
-            share_data.index
-        ));
+        let sig_share: Scalar<Public, Zero> = decode_hex_field(
+            &format!("Party {}'s signature share", share_data.index),
+            &share_data.share,
+        )?;
+        sig_shares.insert(share_index(share_data.index)?, sig_share);
+        events.party(share_data.index, "verifying signature share...");
     }
 
     // Use coordinator API to verify and combine
     let signature = coord_session
         .verify_and_combine_signature_shares(&shared_key, sig_shares)
-        .map_err(|e| anyhow::anyhow!("Signature verification failed: {:?}", e))?;
-
-    let valid = true; // If we got here, verification passed
+        .map_err(|e| {
+            crate::error::Error::Protocol(format!("Signature verification failed: {:?}", e))
+        })?;
 
-    if valid {
-        out.push_str("  ✓ Signature is VALID!\n\n");
-    } else {
-        out.push_str("  ✗ Signature verification FAILED!\n\n");
-        anyhow::bail!("Signature verification failed");
-    }
+    events.success("Signature is VALID!", NO_LINES);
 
     let sig_bytes = bincode::serialize(&signature)?;
     let sig_hex = hex::encode(&sig_bytes);
@@ -593,19 +816,27 @@ pub fn combine_signatures_core(data: &str, storage: &dyn Storage) -> Result<Comm
     let pubkey_bytes = bincode::serialize(&shared_key.public_key())?;
     let pubkey_hex = hex::encode(&pubkey_bytes);
 
-    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    out.push_str("🎉 FROST SIGNATURE VALID!\n\n");
-    out.push_str("✨ You just created a threshold signature using schnorr_fun's FROST!\n");
-    out.push_str("   - Used real cryptographic API from production library\n");
-    out.push_str("   - Signature is valid under the shared public key\n");
-    out.push_str("   - No single party knew the full secret key!\n\n");
-    out.push_str("❓ Challenge:\n");
-    out.push_str("   This signature can be used anywhere Schnorr signatures are valid!\n");
-    out.push_str("   Try signing:\n");
-    out.push_str("   • A Nostr event (kind 1 message)\n");
-    out.push_str("   • A Bitcoin transaction (taproot spend)\n");
-    out.push_str("   • Git commits\n");
-    out.push_str("   The same FROST key works for all of them!\n\n");
+    events.divider();
+    events.note("🎉 FROST SIGNATURE VALID!\n\n");
+    events.explain(
+        "You just created a threshold signature using schnorr_fun's FROST!",
+        [
+            "- Used real cryptographic API from production library",
+            "- Signature is valid under the shared public key",
+            "- No single party knew the full secret key!",
+        ],
+    );
+    events.question(
+        "Challenge:",
+        [
+            "This signature can be used anywhere Schnorr signatures are valid!",
+            "Try signing:",
+            "• A Nostr event (kind 1 message)",
+            "• A Bitcoin transaction (taproot spend)",
+            "• Git commits",
+            "The same FROST key works for all of them!",
+        ],
+    );
 
     // Create result with the signature details
     let result = format!(
@@ -613,16 +844,13 @@ pub fn combine_signatures_core(data: &str, storage: &dyn Storage) -> Result<Comm
         sig_hex, pubkey_hex, message
     );
 
-    Ok(CommandResult {
-        output: out,
-        result,
-    })
+    Ok(events.finish(result))
 }
 
-pub fn combine_signatures(data: &str) -> Result<()> {
-    let storage = FileStorage::new(STATE_DIR)?;
-    let cmd_result = combine_signatures_core(data, &storage)?;
-    println!("{}", cmd_result.output);
+pub fn combine_signatures(name: Option<&str>, data: &str) -> Result<()> {
+    let storage = signing_storage(name)?;
+    let cmd_result =
+        combine_signatures_core_with_reporter(data, &storage, cli_reporter().as_mut())?;
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Signature:");
     println!("{}\n", cmd_result.result);
@@ -679,10 +907,782 @@ pub fn verify_signature_core(
     })
 }
 
-pub fn verify_signature(signature_hex: &str, public_key_hex: &str, message: &str) -> Result<()> {
-    let cmd_result = verify_signature_core(signature_hex, public_key_hex, message)?;
+/// Core function for `dkg-coefficients`: the weight each signer's share gets
+///
+/// `signers` is a comma-separated list of party indices. Ranks, threshold
+/// and any signing requirement come from the wallet's `htss_metadata.json`.
+/// The result is the [`crate::crypto::birkhoff::CoefficientPreview`] as JSON.
+pub fn coefficients_core(signers: &str, storage: &dyn Storage) -> Result<CommandResult> {
+    let metadata: HtssMetadata = serde_json::from_slice(&storage.read("htss_metadata.json")?)
+        .context("htss_metadata.json is damaged")?;
+    let indices = signers
+        .split(',')
+        .map(str::trim)
+        .filter(|index| !index.is_empty())
+        .map(|index| {
+            index
+                .parse::<u32>()
+                .with_context(|| format!("Invalid party index '{}'", index))
+        })
+        .collect::<Result<Vec<u32>>>()?;
+    let pairs = indices
+        .iter()
+        .map(|&index| match metadata.party_ranks.get(&index) {
+            Some(&rank) => Ok((index, rank)),
+            None => Err(anyhow::anyhow!("Party {} is not in this wallet", index)),
+        })
+        .collect::<Result<Vec<(u32, u32)>>>()?;
+
+    let mut preview = preview_coefficients(&pairs, metadata.threshold, metadata.hierarchical)?;
+    if let Err(e) = metadata.check_signing_requirement(&indices) {
+        preview.problems.push(e.to_string());
+    }
+
+    let mut out = String::new();
+    let signer_list: Vec<String> = preview
+        .signers
+        .iter()
+        .map(|s| s.index.to_string())
+        .collect();
+    out.push_str(&format!(
+        "Signer set: {} ({}-of-{}, {})\n",
+        signer_list.join(", "),
+        metadata.threshold,
+        metadata.party_ranks.len(),
+        if metadata.hierarchical { "HTSS" } else { "TSS" }
+    ));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str("  Party  Rank  Lagrange λ(0)   Birkhoff\n");
+    for signer in &preview.signers {
+        out.push_str(&format!(
+            "  {:<5}  {:<4}  {:>13.6}   {}\n",
+            signer.index,
+            signer.rank,
+            signer.lagrange_approx,
+            signer
+                .birkhoff
+                .map(|b| format!("{:.6}", b))
+                .unwrap_or_else(|| "singular".to_string())
+        ));
+    }
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str("Shares are weighted by λ(0) (exact field values in the JSON).\n");
+
+    if metadata.hierarchical {
+        out.push_str("\nRank check (sorted by rank, rank must be <= position):\n");
+        for slot in &preview.rank_slots {
+            out.push_str(&format!(
+                "  position {}: party {}, rank {}  {}\n",
+                slot.position,
+                slot.index,
+                slot.rank,
+                if slot.ok { "✓" } else { "✗" }
+            ));
+        }
+    }
+
+    out.push('\n');
+    if preview.is_valid() {
+        out.push_str("✅ This set can sign\n");
+    } else {
+        for problem in &preview.problems {
+            out.push_str(&format!("❌ {}\n", problem));
+        }
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&preview)?,
+    })
+}
+
+/// CLI wrapper for `dkg-coefficients`
+pub fn coefficients(name: &str, signers: &str) -> Result<()> {
+    let storage = FileStorage::new(&get_state_dir(name))?;
+    let cmd_result = coefficients_core(signers, &storage)?;
+    println!("{}", cmd_result.output);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("📋 Result: {}\n", cmd_result.result);
+    Ok(())
+}
+
+pub fn verify_signature(
+    curve: Curve,
+    signature_hex: &str,
+    public_key_hex: &str,
+    message: &str,
+) -> Result<()> {
+    let cmd_result = match curve {
+        Curve::Secp256k1 => verify_signature_core(signature_hex, public_key_hex, message)?,
+        _ => verify_curve_signature_core(curve, signature_hex, public_key_hex, message)?,
+    };
     println!("{}", cmd_result.output);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Result: {}\n", cmd_result.result);
     Ok(())
 }
+
+// ============================================================================
+// Other ciphersuites (ed25519, ristretto255)
+// ============================================================================
+//
+// RFC 9591 rounds (`nonce_generate`, binding factors over the encoded
+// commitment list, group commitment) generic over `Ciphersuite`, checked
+// against the RFC's FROST(Ed25519, SHA-512) test vectors. Messages are signed
+// as raw bytes, so the final signature verifies with the curve's stock
+// verifier (an ed25519 signature is a plain RFC 8032 one).
+
+/// `d ‖ e` for one signing session
+#[derive(Serialize, Deserialize)]
+struct SessionNonce {
+    hiding: SecretString,
+    binding: SecretString,
+}
+
+/// What combine needs from the sign step
+#[derive(Serialize, Deserialize)]
+struct SessionData {
+    message: String,
+    nonces: Vec<NonceData>,
+}
+
+fn curve_nonce_file(session: &str) -> String {
+    format!("frost_nonce_{}.json", session)
+}
+
+fn curve_session_file(session: &str) -> String {
+    format!("frost_session_{}.json", session)
+}
+
+/// Signer commitments (D, E) by party index
+type Commitments<C> = BTreeMap<u32, (<C as Ciphersuite>::Point, <C as Ciphersuite>::Point)>;
+
+fn parse_commitments<C: Ciphersuite>(nonces: &[NonceData]) -> Result<Commitments<C>> {
+    let point_len = C::point_len();
+    let mut commitments = BTreeMap::new();
+    for nonce in nonces {
+        let bytes = hex::decode(&nonce.nonce).context("Invalid nonce hex")?;
+        if bytes.len() != 2 * point_len {
+            anyhow::bail!("Nonce from party {} has the wrong length", nonce.index);
+        }
+        let hiding = C::point_from_bytes(&bytes[..point_len]).context("Invalid hiding nonce")?;
+        let binding = C::point_from_bytes(&bytes[point_len..]).context("Invalid binding nonce")?;
+        if commitments.insert(nonce.index, (hiding, binding)).is_some() {
+            anyhow::bail!("Duplicate nonce from party {}", nonce.index);
+        }
+    }
+    Ok(commitments)
+}
+
+/// RFC 9591 identifier encoding: the index as a serialized scalar
+fn identifier<C: Ciphersuite>(index: u32) -> Vec<u8> {
+    C::scalar_to_bytes(&C::scalar_from_u32(index))
+}
+
+/// RFC 9591 `nonce_generate`: H3(random_bytes(32) ‖ secret)
+fn nonce_generate<C: Ciphersuite>(random: &[u8; 32], secret: &C::Scalar) -> C::Scalar {
+    C::hash_to_field(
+        "nonce",
+        &[random.as_slice(), &C::scalar_to_bytes(secret)].concat(),
+    )
+}
+
+/// RFC 9591 `compute_binding_factors`:
+/// ρᵢ = H1(PK ‖ H4(msg) ‖ H5(encoded commitment list) ‖ i)
+fn binding_factors<C: Ciphersuite>(
+    group_key: &C::Point,
+    message: &[u8],
+    commitments: &Commitments<C>,
+) -> BTreeMap<u32, C::Scalar> {
+    let mut encoded = Vec::new();
+    for (index, (hiding, binding)) in commitments {
+        encoded.extend(identifier::<C>(*index));
+        encoded.extend(C::point_to_bytes(hiding));
+        encoded.extend(C::point_to_bytes(binding));
+    }
+    let prefix = [
+        C::point_to_bytes(group_key),
+        C::hash("msg", message),
+        C::hash("com", &encoded),
+    ]
+    .concat();
+
+    commitments
+        .keys()
+        .map(|index| {
+            let input = [prefix.as_slice(), &identifier::<C>(*index)].concat();
+            (*index, C::hash_to_field("rho", &input))
+        })
+        .collect()
+}
+
+/// RFC 9591 `compute_group_commitment`: R = Σ (Dᵢ + ρᵢ·Eᵢ)
+fn group_commitment<C: Ciphersuite>(
+    commitments: &Commitments<C>,
+    rhos: &BTreeMap<u32, C::Scalar>,
+) -> C::Point {
+    commitments
+        .iter()
+        .fold(C::identity(), |acc, (index, (hiding, binding))| {
+            C::point_add(
+                &acc,
+                &C::point_add(hiding, &C::point_mul(&rhos[index], binding)),
+            )
+        })
+}
+
+/// RFC 9591 `sign`: zᵢ = dᵢ + ρᵢ·eᵢ + λᵢ·c·sᵢ
+///
+/// On secp256k1 the nonces and share are negated first where BIP340 needs
+/// the even-y R or group key.
+fn signature_share<C: Ciphersuite>(
+    index: u32,
+    secret: &C::Scalar,
+    (hiding, binding): (&C::Scalar, &C::Scalar),
+    group_key: &C::Point,
+    message: &[u8],
+    commitments: &Commitments<C>,
+) -> Result<C::Scalar> {
+    let rhos = binding_factors::<C>(group_key, message, commitments);
+    let group_nonce = group_commitment::<C>(commitments, &rhos);
+    let challenge = C::challenge(&group_nonce, group_key, message);
+    let signers: Vec<u32> = commitments.keys().copied().collect();
+    let lambda = lagrange_coefficient_in::<C>(index, &signers, 0)?;
+    let rho = rhos
+        .get(&index)
+        .context("Your nonce is missing from the input")?;
+
+    let (mut hiding, mut binding, mut secret) = (*hiding, *binding, *secret);
+    if C::needs_negation(&group_nonce) {
+        hiding = C::scalar_neg(&hiding);
+        binding = C::scalar_neg(&binding);
+    }
+    if C::needs_negation(group_key) {
+        secret = C::scalar_neg(&secret);
+    }
+    let k = C::scalar_add(&hiding, &C::scalar_mul(rho, &binding));
+    Ok(C::scalar_add(
+        &k,
+        &C::scalar_mul(&C::scalar_mul(&lambda, &challenge), &secret),
+    ))
+}
+
+fn generate_nonce_in<C: Ciphersuite>(
+    session: &str,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<CommandResult> {
+    events.started(format!(
+        "FROST Signing (TSS, {}) - Nonce Generation",
+        C::NAME
+    ));
+    events.divider();
+    events.detail("Session ID", session);
+    events.warning(
+        "NEVER reuse a nonce as it will leak your secret share!",
+        NO_LINES,
+    );
+    events.divider();
+    events.note("\n");
+
+    let (party_index, secret) = load_curve_secret_share::<C>(storage)?;
+    if storage.exists(&curve_nonce_file(session)) {
+        anyhow::bail!(
+            "A nonce for session '{}' already exists; pick a new session ID",
+            session
+        );
+    }
+
+    let mut randomness = [[0u8; 32]; 2];
+    for random in &mut randomness {
+        rand::thread_rng().fill_bytes(random);
+    }
+    let hiding = nonce_generate::<C>(&randomness[0], &secret);
+    let binding = nonce_generate::<C>(&randomness[1], &secret);
+    let nonce = SessionNonce {
+        hiding: hex_secret(&C::scalar_to_bytes(&hiding)),
+        binding: hex_secret(&C::scalar_to_bytes(&binding)),
+    };
+    storage.write(
+        &curve_nonce_file(session),
+        to_json_secret(&nonce)?.as_bytes(),
+    )?;
+
+    let mut public = C::point_to_bytes(&C::base_mul(&hiding));
+    public.extend(C::point_to_bytes(&C::base_mul(&binding)));
+
+    events.success(
+        "Generated nonce pair (d, e); published (D, E) = (d·B, e·B)",
+        NO_LINES,
+    );
+    events.divider();
+    events.produced("Your public nonce generated!");
+    events.next_step("Wait for threshold number of signers to post nonces");
+    events.next_step(format!(
+        "Run: frostdao sign --name <wallet> --session {} --message \"<msg>\" --data '<JSON>'",
+        session
+    ));
+
+    let mut output = NonceOutput {
+        party_index,
+        rank: 0,
+        session: session.to_string(),
+        nonce: hex::encode(public),
+        memo: None,
+        protocol_version: payload::output_version(),
+        event_type: "signing_nonce".to_string(),
+        auth: None,
+    };
+    identity::sign_payload(&mut output, storage)?;
+    let result = serde_json::to_string(&output)?;
+
+    Ok(events.finish(result))
+}
+
+fn create_signature_share_in<C: Ciphersuite>(
+    key: &FrostKey,
+    session: &str,
+    message: &str,
+    data: &str,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<CommandResult> {
+    events.started(format!(
+        "🔐 FROST Signing (TSS, {}) - Create Signature Share",
+        C::NAME
+    ));
+
+    let (party_index, secret) = load_curve_secret_share::<C>(storage)?;
+    let nonce_json = SecretString::from_utf8(
+        storage
+            .read_secret(&curve_nonce_file(session))
+            .context("Failed to load nonce. Did you run generate-nonce (or already sign)?")?,
+    )?;
+    let nonce: SessionNonce = serde_json::from_str(&nonce_json)?;
+    let hiding = decode_scalar::<C>(nonce.hiding.expose())?;
+    let binding = decode_scalar::<C>(nonce.binding.expose())?;
+
+    let nonce_outputs: Vec<NonceOutput> = parse_payloads(
+        data,
+        &Expected {
+            session: Some(session),
+            ..Expected::new("signing nonces", &["signing_nonce"])
+        },
+    )?;
+    let mut verified = String::new();
+    identity::verify_payloads(&nonce_outputs, storage, &mut verified)?;
+    events.note(verified);
+    if let Some(note) = payload::downgrade_note(payload::reply_version(&nonce_outputs)) {
+        events.note(note);
+    }
+    let nonces: Vec<NonceData> = nonce_outputs
+        .iter()
+        .map(|o| NonceData {
+            index: o.party_index,
+            nonce: o.nonce.clone(),
+        })
+        .collect();
+    let commitments = parse_commitments::<C>(&nonces)?;
+    if commitments.len() < key.threshold as usize {
+        anyhow::bail!(
+            "Not enough signers: have {} but need at least {} for {}-of-{} threshold",
+            commitments.len(),
+            key.threshold,
+            key.threshold,
+            key.n_parties
+        );
+    }
+    match commitments.get(&party_index) {
+        Some((d, e)) if *d == C::base_mul(&hiding) && *e == C::base_mul(&binding) => {}
+        Some(_) => anyhow::bail!(
+            "Your nonce in the input does not match session '{}'",
+            session
+        ),
+        None => anyhow::bail!("Your nonce is missing from the input"),
+    }
+    events.success(
+        format!(
+            "Signer count validated: {} signers (threshold: {})",
+            commitments.len(),
+            key.threshold
+        ),
+        NO_LINES,
+    );
+
+    let group_key = decode_point::<C>(&key.group_public_key)?;
+    let signers: Vec<u32> = commitments.keys().copied().collect();
+
+    events.progress(
+        "Creating signature share...",
+        [format!(
+            "z{} = d{} + ρ{}·e{} + λ{} × c × secret_share{}",
+            party_index, party_index, party_index, party_index, party_index, party_index
+        )],
+    );
+    let z = signature_share::<C>(
+        party_index,
+        &secret,
+        (&hiding, &binding),
+        &group_key,
+        message.as_bytes(),
+        &commitments,
+    )?;
+
+    // The nonce must never sign twice
+    storage.delete(&curve_nonce_file(session))?;
+    let session_data = SessionData {
+        message: message.to_string(),
+        nonces,
+    };
+    storage.write(
+        &curve_session_file(session),
+        serde_json::to_string(&session_data)?.as_bytes(),
+    )?;
+    health::record_session(storage, session, &signers)?;
+
+    events.divider();
+    events.produced("Your signature share generated!");
+    events.next_step(
+        "Once all signers post shares, a signer combines them (combine reads the session nonces this step saved in that signer's wallet folder)",
+    );
+    events.next_step("Run: frostdao combine --name <wallet> --data '<shares JSON>'");
+
+    let mut output = SignatureShareOutput {
+        party_index,
+        session: session.to_string(),
+        message: message.to_string(),
+        signature_share: hex::encode(C::scalar_to_bytes(&z)),
+        protocol_version: payload::reply_version(&nonce_outputs),
+        event_type: "signing_share".to_string(),
+        auth: None,
+    };
+    identity::sign_payload(&mut output, storage)?;
+    let result = serde_json::to_string(&output)?;
+
+    Ok(events.finish(result))
+}
+
+fn combine_signatures_in<C: Ciphersuite>(
+    key: &FrostKey,
+    data: &str,
+    storage: &dyn Storage,
+    events: &mut EventLog,
+) -> Result<CommandResult> {
+    events.started(format!(
+        "🔐 FROST Signing (TSS, {}) - Combine Signature Shares",
+        C::NAME
+    ));
+
+    let sig_outputs: Vec<SignatureShareOutput> =
+        parse_payloads(data, &Expected::new("signature shares", &["signing_share"]))?;
+    let mut verified = String::new();
+    identity::verify_payloads(&sig_outputs, storage, &mut verified)?;
+    events.note(verified);
+    let first = sig_outputs
+        .first()
+        .context("No signature shares provided")?;
+    let session = &first.session;
+
+    let session_json = String::from_utf8(
+        storage
+            .read(&curve_session_file(session))
+            .context("Failed to load session nonces. Did a signer run the sign command?")?,
+    )?;
+    let session_data: SessionData = serde_json::from_str(&session_json)?;
+    let message = session_data.message.as_bytes();
+    if sig_outputs
+        .iter()
+        .any(|o| o.session != *session || o.message != session_data.message)
+    {
+        anyhow::bail!("Signature shares disagree on the session or message");
+    }
+
+    let commitments = parse_commitments::<C>(&session_data.nonces)?;
+    let group_key = decode_point::<C>(&key.group_public_key)?;
+    let rhos = binding_factors::<C>(&group_key, message, &commitments);
+    let group_nonce = group_commitment::<C>(&commitments, &rhos);
+    let challenge = C::challenge(&group_nonce, &group_key, message);
+    let signers: Vec<u32> = commitments.keys().copied().collect();
+    let negate_nonce = C::needs_negation(&group_nonce);
+    let negate_key = C::needs_negation(&group_key);
+
+    check_signer_set(
+        &sig_outputs
+            .iter()
+            .map(|o| o.party_index)
+            .collect::<Vec<_>>(),
+        &signers,
+    )?;
+
+    events.progress("Verifying each share: zᵢ·B == Rᵢ + c·λᵢ·Yᵢ", NO_LINES);
+    let mut z = C::scalar_from_u32(0);
+    for output in &sig_outputs {
+        let index = output.party_index;
+        let (hiding, binding) = &commitments[&index];
+        let share = decode_scalar::<C>(&output.signature_share)?;
+        let verification_share = decode_point::<C>(
+            key.verification_shares
+                .get(&index)
+                .with_context(|| format!("Unknown party {}", index))?,
+        )?;
+        let lambda = lagrange_coefficient_in::<C>(index, &signers, 0)?;
+
+        let mut nonce = C::point_add(hiding, &C::point_mul(&rhos[&index], binding));
+        if negate_nonce {
+            nonce = C::point_mul(&C::scalar_neg(&C::scalar_from_u32(1)), &nonce);
+        }
+        let mut c_lambda = C::scalar_mul(&challenge, &lambda);
+        if negate_key {
+            c_lambda = C::scalar_neg(&c_lambda);
+        }
+        let expected = C::point_add(&nonce, &C::point_mul(&c_lambda, &verification_share));
+        if C::base_mul(&share) != expected {
+            anyhow::bail!("Invalid signature share from party {}", index);
+        }
+        z = C::scalar_add(&z, &share);
+        events.party(index, "share verified");
+    }
+
+    let signature = C::signature_bytes(&group_nonce, &z);
+    let public_key = C::public_key_bytes(&group_key);
+    if !C::verify(&public_key, message, &signature) {
+        anyhow::bail!(crate::error::Error::Protocol(
+            "Signature verification failed".into()
+        ));
+    }
+
+    events.note("\n");
+    events.divider();
+    events.success(
+        format!("FROST SIGNATURE VALID ({})!", C::NAME),
+        ["Verifies with any standard verifier for this curve"],
+    );
+
+    let result = format!(
+        "Signature: {}\nPublic Key: {}\nMessage: \"{}\"\nCurve: {}",
+        hex::encode(signature),
+        hex::encode(public_key),
+        session_data.message,
+        C::NAME
+    );
+    Ok(events.finish(result))
+}
+
+/// Verify a signature over a raw message on a non-secp256k1 curve
+pub fn verify_curve_signature_core(
+    curve: Curve,
+    signature_hex: &str,
+    public_key_hex: &str,
+    message: &str,
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    out.push_str(&format!("🔍 {} Signature Verification\n\n", curve));
+
+    let signature = hex::decode(signature_hex).context("Failed to decode signature hex")?;
+    let public_key = hex::decode(public_key_hex).context("Failed to decode public key hex")?;
+    out.push_str("📋 Verification inputs:\n");
+    out.push_str(&format!("   Message: \"{}\"\n", message));
+    out.push_str(&format!("   Public Key: {}\n\n", public_key_hex));
+
+    let result = if ciphersuite::verify(curve, &public_key, message.as_bytes(), &signature) {
+        out.push_str("✅ SIGNATURE VALID!\n");
+        "VALID".to_string()
+    } else {
+        out.push_str("❌ SIGNATURE INVALID!\n");
+        "INVALID".to_string()
+    };
+    Ok(CommandResult {
+        output: out,
+        result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ciphersuite::Ed25519;
+    use crate::protocol::keygen::{self, evaluate, FROST_KEY_FILE};
+    use crate::storage::MemoryStorage;
+
+    fn run(curve: Curve) {
+        let (t, n) = (2u32, 3u32);
+        let parties: Vec<MemoryStorage> = (0..n).map(|_| MemoryStorage::new()).collect();
+
+        let round1: Vec<String> = (1..=n)
+            .map(|i| {
+                keygen::round1_curve_core(curve, t, n, i, &parties[i as usize - 1])
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        let round1 = round1.join(" ");
+        let round2: Vec<String> = parties
+            .iter()
+            .map(|p| keygen::round2_core(&round1, p).unwrap().result)
+            .collect();
+        let round2 = round2.join(" ");
+        for party in &parties {
+            keygen::finalize_core(&round2, party).unwrap();
+            assert!(!party.exists(keygen::CURVE_ROUND1_STATE_FILE));
+            assert!(party.exists(FROST_KEY_FILE));
+        }
+        let key = FrostKey::load(&parties[0]).unwrap();
+        assert_eq!(key.curve, curve);
+
+        // Parties 1 and 3 sign
+        let signers = [&parties[0], &parties[2]];
+        let nonces: Vec<String> = signers
+            .iter()
+            .map(|p| generate_nonce_core("s1", *p).unwrap().result)
+            .collect();
+        let nonces = nonces.join(" ");
+        let shares: Vec<String> = signers
+            .iter()
+            .map(|p| {
+                create_signature_share_core("s1", "hello", &nonces, *p)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        // Nonces are single use
+        assert!(create_signature_share_core("s1", "hello", &nonces, signers[0]).is_err());
+
+        let combined = combine_signatures_core(&shares.join(" "), signers[0]).unwrap();
+        let field = |name: &str| {
+            combined
+                .result
+                .lines()
+                .find_map(|l| l.strip_prefix(name))
+                .unwrap()
+                .trim()
+                .to_string()
+        };
+        let (signature, public_key) = (field("Signature:"), field("Public Key:"));
+        assert_eq!(public_key, key.public_key_hex().unwrap());
+        let verified =
+            verify_curve_signature_core(curve, &signature, &public_key, "hello").unwrap();
+        assert_eq!(verified.result, "VALID");
+        let wrong = verify_curve_signature_core(curve, &signature, &public_key, "hell0").unwrap();
+        assert_eq!(wrong.result, "INVALID");
+    }
+
+    #[test]
+    fn test_rfc9591_ed25519_vectors() {
+        // RFC 9591 appendix E.1, FROST(Ed25519, SHA-512), signers 1 and 3
+        type C = Ed25519;
+        let scalar = |hex_str: &str| decode_scalar::<C>(hex_str).unwrap();
+        let random = |hex_str: &str| <[u8; 32]>::try_from(hex::decode(hex_str).unwrap()).unwrap();
+        let hex_point = |point: &<C as Ciphersuite>::Point| hex::encode(C::point_to_bytes(point));
+
+        let group_secret =
+            scalar("7b1c33d3f5291d85de664833beb1ad469f7fb6025a0ec78b3a790c6e13a98304");
+        let coefficient =
+            scalar("178199860edd8c62f5212ee91eff1295d0d670ab4ed4506866bae57e7030b204");
+        let group_key = C::base_mul(&group_secret);
+        assert_eq!(
+            hex_point(&group_key),
+            "15d21ccd7ee42959562fc8aa63224c8851fb3ec85a3faf66040d380fb9738673"
+        );
+        let shares: BTreeMap<u32, _> = [1u32, 3]
+            .into_iter()
+            .map(|i| (i, evaluate::<C>(&[group_secret, coefficient], i)))
+            .collect();
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&shares[&1])),
+            "929dcc590407aae7d388761cddb0c0db6f5627aea8e217f4a033f2ec83d93509"
+        );
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&shares[&3])),
+            "d3cb090a075eb154e82fdb4b3cb507f110040905468bb9c46da8bdea643a9a02"
+        );
+
+        let randomness = [
+            (
+                1,
+                "0fd2e39e111cdc266f6c0f4d0fd45c947761f1f5d3cb583dfcb9bbaf8d4c9fec",
+                "69cd85f631d5f7f2721ed5e40519b1366f340a87c2f6856363dbdcda348a7501",
+            ),
+            (
+                3,
+                "86d64a260059e495d0fb4fcc17ea3da7452391baa494d4b00321098ed2a0062f",
+                "13e6b25afb2eba51716a9a7d44130c0dbae0004a9ef8d7b5550c8a0e07c61775",
+            ),
+        ];
+        let nonces: BTreeMap<u32, _> = randomness
+            .iter()
+            .map(|(i, hiding, binding)| {
+                let share = &shares[i];
+                let hiding = nonce_generate::<C>(&random(hiding), share);
+                let binding = nonce_generate::<C>(&random(binding), share);
+                (*i, (hiding, binding))
+            })
+            .collect();
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&nonces[&1].0)),
+            "812d6104142944d5a55924de6d49940956206909f2acaeedecda2b726e630407"
+        );
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&nonces[&3].1)),
+            "243d71944d929063bc51205714ae3c2218bd3451d0214dfb5aeec2a90c35180d"
+        );
+
+        let commitments: Commitments<C> = nonces
+            .iter()
+            .map(|(i, (d, e))| (*i, (C::base_mul(d), C::base_mul(e))))
+            .collect();
+        assert_eq!(
+            hex_point(&commitments[&1].0),
+            "b5aa8ab305882a6fc69cbee9327e5a45e54c08af61ae77cb8207be3d2ce13de3"
+        );
+        assert_eq!(
+            hex_point(&commitments[&3].1),
+            "7487bc41a6e712eea2f2af24681b58b1cf1da278ea11fe4e8b78398965f13552"
+        );
+
+        let message = b"test";
+        let rhos = binding_factors::<C>(&group_key, message, &commitments);
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&rhos[&1])),
+            "f2cb9d7dd9beff688da6fcc83fa89046b3479417f47f55600b106760eb3b5603"
+        );
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&rhos[&3])),
+            "b087686bf35a13f3dc78e780a34b0fe8a77fef1b9938c563f5573d71d8d7890f"
+        );
+
+        let z: BTreeMap<u32, _> = nonces
+            .iter()
+            .map(|(i, (d, e))| {
+                let share =
+                    signature_share::<C>(*i, &shares[i], (d, e), &group_key, message, &commitments)
+                        .unwrap();
+                (*i, share)
+            })
+            .collect();
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&z[&1])),
+            "001719ab5a53ee1a12095cd088fd149702c0720ce5fd2f29dbecf24b7281b603"
+        );
+        assert_eq!(
+            hex::encode(C::scalar_to_bytes(&z[&3])),
+            "bd86125de990acc5e1f13781d8e32c03a9bbd4c53539bbc106058bfd14326007"
+        );
+
+        let signature = C::signature_bytes(
+            &group_commitment::<C>(&commitments, &rhos),
+            &C::scalar_add(&z[&1], &z[&3]),
+        );
+        assert_eq!(
+            hex::encode(signature),
+            "36282629c383bb820a88b71cae937d41f2f2adfcc3d02e55507e2fb9e2dd3cbe\
+             bd9d2b0844e49ae0f3fa935161e1419aab7b47d21a37ebeae1f17d4987b3160b"
+        );
+    }
+
+    #[test]
+    fn test_dkg_and_signing_on_every_curve() {
+        for curve in [Curve::Ed25519, Curve::Ristretto255, Curve::Secp256k1] {
+            run(curve);
+        }
+    }
+}
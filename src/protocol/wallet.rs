@@ -0,0 +1,894 @@
+//! Wallet Folder Management
+//!
+//! Wallets made by the CLI keygen commands (and by older versions) keep the
+//! secret share in the wallet root:
+//!
+//! ```text
+//! .frost_state/<name>/paired_secret_share.bin      legacy layout
+//! .frost_state/<name>/party<i>/paired_secret_share.bin   party-folder layout
+//! ```
+//!
+//! `dkg-migrate-layout` moves a legacy wallet's party-specific state into
+//! `party<i>/` (i = its own index from `htss_metadata.json`) and copies the
+//! public files it needs there, so the TUI no longer special-cases it. The
+//! whole folder is copied to `.frost_state/.backup/` first.
+//!
+//! `dkg-rename`, `dkg-archive` and `dkg-delete` move, set aside or destroy a
+//! whole wallet folder. Archived wallets keep every file, plus a
+//! `tombstone.json` recording what they were; deleted wallets have every
+//! file overwritten before the folder is removed.
+//!
+//! `dkg-signing-requirement` sets how many signers of each rank a signing
+//! session needs on top of the threshold (e.g. one rank-0 and two rank-1),
+//! in every `htss_metadata.json` of the wallet.
+//!
+//! `dkg-rotate` leaves a `retired.json` in a wallet whose coins it swept to
+//! a successor; the wallet keeps working, but lists and spends warn about it.
+//!
+//! `dkg-notes` keeps freeform notes (description, party contacts, creation
+//! date, tags) in `wallet_notes.json`, next to rather than inside
+//! `group_info.json`, which is shared with the other parties and rebuilt by
+//! `dkg-info`.
+
+use crate::protocol::doctor;
+use crate::protocol::identity::{IDENTITY_KEY_FILE, IDENTITY_ROSTER_FILE};
+use crate::protocol::keygen::{self, GroupInfo, HtssMetadata};
+use crate::storage::{self, FileStorage, Storage};
+use crate::CommandResult;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Folder holding every wallet
+pub const STATE_ROOT: &str = ".frost_state";
+
+/// Where `dkg-migrate-layout` leaves its backups
+pub const BACKUP_DIR: &str = ".frost_state/.backup";
+
+/// Archived wallets, under the state root
+pub const ARCHIVE_DIR: &str = ".archive";
+
+/// Written into an archived wallet's folder
+pub const TOMBSTONE_FILE: &str = "tombstone.json";
+
+/// What an archived wallet was
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Tombstone {
+    pub name: String,
+    /// Unix time of archiving
+    pub archived_at: u64,
+    pub original_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_public_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taproot_address_mainnet: Option<String>,
+}
+
+/// Written into a wallet once `dkg-rotate` has swept it to a successor
+pub const RETIREMENT_FILE: &str = "retired.json";
+
+/// Where a retired wallet's coins went
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Retirement {
+    /// Unix time of the last sweep
+    pub retired_at: u64,
+    /// Name of the wallet holding the new group key
+    pub successor: String,
+    /// Sweep transactions, in broadcast order
+    #[serde(default)]
+    pub txids: Vec<String>,
+}
+
+impl Retirement {
+    /// The retirement record in `storage`, if the wallet was retired
+    pub fn load(storage: &dyn Storage) -> Result<Option<Self>> {
+        if !storage.exists(RETIREMENT_FILE) {
+            return Ok(None);
+        }
+        serde_json::from_slice(&storage.read(RETIREMENT_FILE)?)
+            .map(Some)
+            .with_context(|| format!("{} is damaged", RETIREMENT_FILE))
+    }
+
+    pub fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.write(
+            RETIREMENT_FILE,
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// One-line warning for commands that spend from the retired wallet
+    pub fn warning(&self) -> String {
+        format!(
+            "⚠️  This wallet was retired; its coins were swept to '{}'\n",
+            self.successor
+        )
+    }
+}
+
+/// Party-specific state that moves into the party folder
+const PARTY_FILES: &[&str] = &[
+    "paired_secret_share.bin",
+    "round1_state.json",
+    "my_secret_shares.json",
+];
+
+/// Public files the party folder needs to sign on its own (they stay in the root too)
+const SHARED_FILES: &[&str] = &[
+    "shared_key.bin",
+    "htss_metadata.json",
+    "hd_metadata.json",
+    "all_commitments.json",
+    IDENTITY_KEY_FILE,
+    IDENTITY_ROSTER_FILE,
+];
+
+/// Copy a folder recursively
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    storage::create_private_dir(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let path = entry?.path();
+        if path.file_name() == Some(storage::lock::LOCK_FILE.as_ref()) {
+            continue; // held by this very command
+        }
+        let target = to.join(path.file_name().context("Unnamed entry")?);
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Core function for `dkg-migrate-layout`
+///
+/// `backup` is the folder the wallet is copied to before anything moves.
+pub fn migrate_layout_core(wallet_dir: &Path, backup: &Path) -> Result<CommandResult> {
+    let mut out = String::new();
+
+    if !wallet_dir.is_dir() {
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet not found at {}",
+            wallet_dir.display()
+        ))
+        .into());
+    }
+    let root = FileStorage::new(&wallet_dir.to_string_lossy())?;
+    keygen::require_secp256k1(&root)?;
+    if !root.exists("paired_secret_share.bin") {
+        bail!(
+            "{} has no share in the wallet root; it already uses party folders",
+            wallet_dir.display()
+        );
+    }
+    let htss: HtssMetadata = serde_json::from_slice(
+        &root
+            .read("htss_metadata.json")
+            .context("htss_metadata.json is missing; run dkg-doctor first")?,
+    )
+    .context("htss_metadata.json is damaged; run dkg-doctor first")?;
+
+    let party_dir = wallet_dir.join(format!("party{}", htss.my_index));
+    if party_dir.exists() {
+        bail!(
+            "{} already exists; move it aside before migrating",
+            party_dir.display()
+        );
+    }
+    if backup.exists() {
+        bail!("Backup folder {} already exists", backup.display());
+    }
+
+    out.push_str("Wallet Layout Migration\n\n");
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!("  Wallet: {}\n", wallet_dir.display()));
+    out.push_str(&format!("  Party:  {}\n", htss.my_index));
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+
+    copy_dir(wallet_dir, backup)?;
+    out.push_str(&format!("💾 Backup: {}/\n\n", backup.display()));
+
+    // Signing sessions and nonces belong to this party, like its share
+    let mut moved: Vec<String> = PARTY_FILES
+        .iter()
+        .filter(|file| root.exists(file))
+        .map(|file| file.to_string())
+        .collect();
+    for entry in std::fs::read_dir(wallet_dir)? {
+        let file = entry?.file_name().to_string_lossy().to_string();
+        if doctor::session_of(&file).is_some() {
+            moved.push(file);
+        }
+    }
+
+    let party = FileStorage::new(&party_dir.to_string_lossy())?;
+    for file in SHARED_FILES.iter().filter(|file| root.exists(file)) {
+        party.write(file, &root.read(file)?)?;
+        out.push_str(&format!("   Copied {} → party{}/\n", file, htss.my_index));
+    }
+    for file in &moved {
+        std::fs::rename(wallet_dir.join(file), party_dir.join(file))?;
+        out.push_str(&format!("   Moved  {} → party{}/\n", file, htss.my_index));
+    }
+
+    out.push_str("\n✓ Wallet now uses the party-folder layout\n\n");
+    out.push_str("🧠 Next steps:\n");
+    out.push_str(&format!(
+        "   • CLI commands that use your share take --name <wallet>/party{}\n",
+        htss.my_index
+    ));
+    out.push_str("   • The backup holds your secret share: delete it once the wallet works\n");
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&serde_json::json!({
+            "party_dir": party_dir.to_string_lossy(),
+            "backup": backup.to_string_lossy(),
+            "moved": moved,
+        }))?,
+    })
+}
+
+/// CLI wrapper for `dkg-migrate-layout`
+pub fn migrate_layout(name: &str) -> Result<()> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let backup = PathBuf::from(BACKUP_DIR).join(format!("{}-{}", name, timestamp));
+    let state_dir = keygen::get_state_dir(name);
+    let result = migrate_layout_core(Path::new(&state_dir), &backup)?;
+    println!("{}", result.output);
+    Ok(())
+}
+
+/// Wallet names are plain folder names: letters, digits, `-`, `_` and `.`
+pub fn validate_wallet_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("Wallet name is empty");
+    }
+    if name.starts_with('.') {
+        bail!("Wallet name '{}' can't start with '.'", name);
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+    {
+        bail!("Wallet name '{}' contains '{}'", name, c);
+    }
+    Ok(())
+}
+
+/// Folder of an existing wallet under `state_root`
+fn existing_wallet(state_root: &Path, name: &str) -> Result<PathBuf> {
+    validate_wallet_name(name)?;
+    let dir = state_root.join(name);
+    if !dir.is_dir() {
+        return Err(crate::error::Error::Storage(format!(
+            "Wallet '{}' not found at {}",
+            name,
+            dir.display()
+        ))
+        .into());
+    }
+    Ok(dir)
+}
+
+fn read_group_info(dir: &Path) -> Option<GroupInfo> {
+    std::fs::read(dir.join("group_info.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+}
+
+/// Core function for `dkg-rename`
+pub fn rename_core(state_root: &Path, name: &str, new_name: &str) -> Result<CommandResult> {
+    let mut out = String::new();
+    let dir = existing_wallet(state_root, name)?;
+    validate_wallet_name(new_name)?;
+    let new_dir = state_root.join(new_name);
+    if new_dir.exists() {
+        bail!("A wallet named '{}' already exists", new_name);
+    }
+
+    std::fs::rename(&dir, &new_dir)?;
+    out.push_str(&format!("✓ Renamed wallet '{}' → '{}'\n", name, new_name));
+
+    // group_info.json is shared with the other parties under the wallet's name
+    if let Some(mut info) = read_group_info(&new_dir) {
+        info.name = new_name.to_string();
+        FileStorage::new(&new_dir.to_string_lossy())?.write(
+            "group_info.json",
+            serde_json::to_string_pretty(&info)?.as_bytes(),
+        )?;
+        out.push_str("   Updated the name in group_info.json\n");
+    }
+    if new_dir.join("sync.json").exists() {
+        out.push_str(&format!(
+            "\n⚠️  Sync objects are named after the wallet: the next dkg-sync-push \
+             uploads {}.frostsync, and other devices must rename the wallet too\n",
+            new_name
+        ));
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: new_dir.to_string_lossy().to_string(),
+    })
+}
+
+/// Core function for `dkg-archive`: move the wallet under `.archive/` with a tombstone
+pub fn archive_core(state_root: &Path, name: &str, now: u64) -> Result<CommandResult> {
+    let mut out = String::new();
+    let dir = existing_wallet(state_root, name)?;
+    let archive_dir = state_root.join(ARCHIVE_DIR);
+    let target = archive_dir.join(format!("{}-{}", name, now));
+    if target.exists() {
+        bail!("{} already exists", target.display());
+    }
+
+    let info = read_group_info(&dir);
+    let tombstone = Tombstone {
+        name: name.to_string(),
+        archived_at: now,
+        original_path: dir.to_string_lossy().to_string(),
+        group_public_key: info.as_ref().map(|i| i.group_public_key.clone()),
+        taproot_address_mainnet: info.map(|i| i.taproot_address_mainnet),
+    };
+
+    std::fs::create_dir_all(&archive_dir)?;
+    std::fs::rename(&dir, &target)?;
+    FileStorage::new(&target.to_string_lossy())?.write(
+        TOMBSTONE_FILE,
+        serde_json::to_string_pretty(&tombstone)?.as_bytes(),
+    )?;
+
+    out.push_str(&format!("📦 Archived wallet '{}'\n", name));
+    out.push_str(&format!("   {}/\n", target.display()));
+    out.push_str(
+        "   The folder still holds its secret share; move it back to restore the wallet\n",
+    );
+
+    Ok(CommandResult {
+        output: out,
+        result: target.to_string_lossy().to_string(),
+    })
+}
+
+/// Overwrite and remove every file under `dir`, then the folder itself
+fn shred_dir(dir: &Path) -> Result<usize> {
+    let mut shredded = 0;
+    let storage = FileStorage::new(&dir.to_string_lossy())?;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            shredded += shred_dir(&path)?;
+        } else if let Some(file) = path.file_name() {
+            storage.delete(&file.to_string_lossy())?;
+            shredded += 1;
+        }
+    }
+    std::fs::remove_dir(dir)?;
+    Ok(shredded)
+}
+
+/// Core function for `dkg-delete`
+///
+/// `confirm_name` must repeat the wallet name.
+pub fn delete_core(state_root: &Path, name: &str, confirm_name: &str) -> Result<CommandResult> {
+    let dir = existing_wallet(state_root, name)?;
+    if confirm_name != name {
+        bail!(
+            "Confirmation '{}' doesn't match wallet '{}'; nothing was deleted",
+            confirm_name,
+            name
+        );
+    }
+
+    let shredded = shred_dir(&dir)?;
+    Ok(CommandResult {
+        output: format!(
+            "🗑️  Deleted wallet '{}' ({} files overwritten and removed)\n",
+            name, shredded
+        ),
+        result: shredded.to_string(),
+    })
+}
+
+/// CLI wrapper for `dkg-rename`
+pub fn rename_wallet(name: &str, new_name: &str) -> Result<()> {
+    let result = rename_core(Path::new(STATE_ROOT), name, new_name)?;
+    println!("{}", result.output);
+    Ok(())
+}
+
+/// CLI wrapper for `dkg-archive`
+pub fn archive_wallet(name: &str) -> Result<()> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let result = archive_core(Path::new(STATE_ROOT), name, now)?;
+    println!("{}", result.output);
+    Ok(())
+}
+
+/// CLI wrapper for `dkg-delete`
+pub fn delete_wallet(name: &str, confirm_name: &str) -> Result<()> {
+    let result = delete_core(Path::new(STATE_ROOT), name, confirm_name)?;
+    println!("{}", result.output);
+    Ok(())
+}
+
+/// Freeform notes about a wallet
+pub const NOTES_FILE: &str = "wallet_notes.json";
+
+/// Notes for telling wallets apart; only ever read by this device
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct WalletNotes {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// How to reach each party, by party index
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub contacts: BTreeMap<u32, String>,
+    /// Creation date, as entered (e.g. `2024-03-01`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl WalletNotes {
+    /// Notes in `storage`, empty if there are none
+    pub fn load(storage: &dyn Storage) -> Result<Self> {
+        if !storage.exists(NOTES_FILE) {
+            return Ok(Self::default());
+        }
+        serde_json::from_slice(&storage.read(NOTES_FILE)?)
+            .with_context(|| format!("{} is damaged", NOTES_FILE))
+    }
+
+    pub fn save(&self, storage: &dyn Storage) -> Result<()> {
+        storage.write(NOTES_FILE, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Notes from the TUI form's text fields
+    pub fn from_text(description: &str, contacts: &str, created: &str, tags: &str) -> Result<Self> {
+        let mut notes = Self {
+            description: description.trim().to_string(),
+            created: Some(created.trim().to_string()).filter(|c| !c.is_empty()),
+            ..Default::default()
+        };
+        for contact in contacts.split(';').filter(|c| !c.trim().is_empty()) {
+            let (index, who) = parse_contact(contact)?;
+            notes.contacts.insert(index, who);
+        }
+        for tag in tags.split(',') {
+            notes.add_tag(tag);
+        }
+        Ok(notes)
+    }
+
+    /// Contacts as `1=alice; 2=bob`
+    pub fn contacts_text(&self) -> String {
+        self.contacts
+            .iter()
+            .map(|(index, who)| format!("{}={}", index, who))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn add_tag(&mut self, tag: &str) {
+        let tag = tag.trim();
+        if !tag.is_empty() && !self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+}
+
+/// Parse `<index>=<contact>`; an empty contact removes the entry
+pub fn parse_contact(contact: &str) -> Result<(u32, String)> {
+    let (index, who) = contact
+        .split_once('=')
+        .context("Expected a contact as <index>=<how to reach them>")?;
+    let index: u32 = index.trim().parse().context("Invalid party index")?;
+    Ok((index, who.trim().to_string()))
+}
+
+/// Changes requested by `dkg-notes`
+#[derive(Debug, Clone, Default)]
+pub struct NotesEdit {
+    pub description: Option<String>,
+    /// `<index>=<contact>` entries
+    pub contacts: Vec<String>,
+    pub created: Option<String>,
+    pub tags: Vec<String>,
+    pub untags: Vec<String>,
+}
+
+/// Core function for `dkg-notes`: apply any edits, then show the notes
+pub fn notes_core(edit: &NotesEdit, storage: &dyn Storage) -> Result<CommandResult> {
+    let mut out = String::new();
+    let mut notes = WalletNotes::load(storage)?;
+    let before = notes.clone();
+
+    if let Some(description) = &edit.description {
+        notes.description = description.trim().to_string();
+    }
+    for contact in &edit.contacts {
+        match parse_contact(contact)? {
+            (index, who) if who.is_empty() => notes.contacts.remove(&index),
+            (index, who) => notes.contacts.insert(index, who),
+        };
+    }
+    if let Some(created) = &edit.created {
+        notes.created = Some(created.trim().to_string()).filter(|c| !c.is_empty());
+    }
+    for tag in &edit.tags {
+        notes.add_tag(tag);
+    }
+    notes.tags.retain(|tag| !edit.untags.contains(tag));
+
+    if notes != before {
+        notes.save(storage)?;
+        out.push_str("✓ Notes updated\n\n");
+    }
+
+    if notes.is_empty() {
+        out.push_str("No notes yet. Add some with:\n");
+        out.push_str("  frostdao dkg-notes --name <wallet> --description \"...\" --tag <tag>\n");
+    } else {
+        out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+        if !notes.description.is_empty() {
+            out.push_str(&format!("Description: {}\n", notes.description));
+        }
+        if let Some(created) = &notes.created {
+            out.push_str(&format!("Created:     {}\n", created));
+        }
+        if !notes.tags.is_empty() {
+            out.push_str(&format!("Tags:        {}\n", notes.tags.join(", ")));
+        }
+        if !notes.contacts.is_empty() {
+            out.push_str("Contacts:\n");
+            for (index, who) in &notes.contacts {
+                out.push_str(&format!("  Party {}: {}\n", index, who));
+            }
+        }
+        out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    }
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&notes)?,
+    })
+}
+
+/// CLI wrapper for `dkg-notes`
+pub fn notes(name: &str, edit: &NotesEdit) -> Result<()> {
+    let dir = existing_wallet(Path::new(STATE_ROOT), name)?;
+    let storage = FileStorage::new(&dir.to_string_lossy())?;
+    let result = notes_core(edit, &storage)?;
+    println!("Notes for '{}'\n", name);
+    println!("{}", result.output);
+    Ok(())
+}
+
+/// Parse `<rank>:<count>` pairs separated by commas, e.g. `0:1,1:2`
+pub fn parse_signing_requirement(text: &str) -> Result<BTreeMap<u32, u32>> {
+    let mut requirement = BTreeMap::new();
+    for pair in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (rank, count) = pair
+            .split_once(':')
+            .context("Expected the requirement as <rank>:<count>, e.g. 0:1,1:2")?;
+        let rank: u32 = rank.trim().parse().context("Invalid rank")?;
+        let count: u32 = count.trim().parse().context("Invalid signer count")?;
+        if requirement.insert(rank, count).is_some() {
+            bail!("Rank {} appears twice in the requirement", rank);
+        }
+    }
+    requirement.retain(|_, count| *count > 0);
+    Ok(requirement)
+}
+
+/// Core function for `dkg-signing-requirement`: set, clear or show it
+///
+/// `storages` are every folder of the wallet holding `htss_metadata.json`
+/// (the root and each party folder); all of them are updated together.
+pub fn signing_requirement_core(
+    set: Option<&str>,
+    clear: bool,
+    storages: &[&dyn Storage],
+) -> Result<CommandResult> {
+    let mut out = String::new();
+    let Some(first) = storages.first() else {
+        bail!("No htss_metadata.json found; finish keygen first");
+    };
+    let mut metadata: HtssMetadata = serde_json::from_slice(&first.read("htss_metadata.json")?)
+        .context("htss_metadata.json is damaged")?;
+
+    let requirement = match (set, clear) {
+        (Some(_), true) => bail!("Use either --set or --clear, not both"),
+        (Some(text), false) => Some(parse_signing_requirement(text)?),
+        (None, true) => Some(BTreeMap::new()),
+        (None, false) => None,
+    };
+    if let Some(requirement) = requirement {
+        for (&rank, &need) in &requirement {
+            let available = metadata
+                .party_ranks
+                .values()
+                .filter(|&&r| r == rank)
+                .count() as u32;
+            if need > available {
+                bail!(
+                    "The requirement asks for {} rank-{} signers, but only {} parties have rank {}",
+                    need,
+                    rank,
+                    available,
+                    rank
+                );
+            }
+        }
+        let total: u32 = requirement.values().sum();
+        if total > metadata.threshold {
+            bail!(
+                "The requirement asks for {} signers, but a signing session uses {}",
+                total,
+                metadata.threshold
+            );
+        }
+
+        for storage in storages {
+            let mut folder_metadata: HtssMetadata =
+                serde_json::from_slice(&storage.read("htss_metadata.json")?)
+                    .context("htss_metadata.json is damaged")?;
+            folder_metadata.signing_requirement = requirement.clone();
+            storage.write(
+                "htss_metadata.json",
+                serde_json::to_string_pretty(&folder_metadata)?.as_bytes(),
+            )?;
+        }
+        metadata.signing_requirement = requirement;
+        out.push_str(&format!(
+            "✓ Signing requirement saved ({} htss_metadata.json)\n\n",
+            storages.len()
+        ));
+    }
+
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    out.push_str(&format!(
+        "Threshold: {}-of-{}\n",
+        metadata.threshold,
+        metadata.party_ranks.len()
+    ));
+    if metadata.signing_requirement.is_empty() {
+        out.push_str("Required:  none (any valid set of signers)\n");
+    } else {
+        out.push_str(&format!(
+            "Required:  {}\n",
+            crate::crypto::birkhoff::describe_signing_requirement(&metadata.signing_requirement)
+        ));
+    }
+    out.push_str("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n\n");
+    out.push_str(
+        "Each party enforces its own copy when it signs; set the same\n\
+         requirement on every party's machine.\n",
+    );
+
+    Ok(CommandResult {
+        output: out,
+        result: serde_json::to_string(&metadata.signing_requirement)?,
+    })
+}
+
+/// CLI wrapper for `dkg-signing-requirement`
+pub fn signing_requirement(name: &str, set: Option<&str>, clear: bool) -> Result<()> {
+    let dir = existing_wallet(Path::new(STATE_ROOT), name)?;
+    let mut folders = vec![dir.clone()];
+    folders.extend(doctor::party_dirs(&dir)?.into_iter().map(|(_, path)| path));
+    let mut storages = Vec::new();
+    for folder in folders {
+        if folder.join("htss_metadata.json").exists() {
+            storages.push(FileStorage::new(&folder.to_string_lossy())?);
+        }
+    }
+    let storages: Vec<&dyn Storage> = storages.iter().map(|s| s as &dyn Storage).collect();
+    let result = signing_requirement_core(set, clear, &storages)?;
+    println!("Signing requirement for '{}'\n", name);
+    println!("{}", result.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::keygen::{finalize_core, round1_core, round2_core};
+
+    fn temp_dir(label: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "frostdao_wallet_{}_{}_{}",
+            label,
+            std::process::id(),
+            nanos
+        ))
+    }
+
+    #[test]
+    fn test_migrate_legacy_layout() {
+        // Party 2 of a 2-of-2 wallet, keygen run by the CLI in the wallet root
+        let wallet = temp_dir("legacy");
+        let root = FileStorage::new(&wallet.to_string_lossy()).unwrap();
+        let other_dir = temp_dir("other");
+        let other = FileStorage::new(&other_dir.to_string_lossy()).unwrap();
+        let storages: [&dyn Storage; 2] = [&other, &root];
+        let round1: Vec<String> = (1..=2)
+            .map(|i| round1_core(2, 2, i, 0, false, storages[i as usize - 1]).unwrap())
+            .map(|r| r.result)
+            .collect();
+        let round2: Vec<String> = storages
+            .iter()
+            .map(|s| round2_core(&round1.join(" "), *s).unwrap().result)
+            .collect();
+        finalize_core(&round2.join(" "), &root).unwrap();
+        root.write("dkg_nonce_abc.bin", b"secret").unwrap();
+        let share = root.read("paired_secret_share.bin").unwrap();
+
+        let backup = temp_dir("backup");
+        migrate_layout_core(&wallet, &backup).unwrap();
+
+        let party = FileStorage::new(&wallet.join("party2").to_string_lossy()).unwrap();
+        assert_eq!(party.read("paired_secret_share.bin").unwrap(), share);
+        assert!(party.exists("dkg_nonce_abc.bin"));
+        assert!(party.exists("htss_metadata.json"));
+        assert!(!root.exists("paired_secret_share.bin"));
+        assert!(!root.exists("round1_state.json"));
+        assert!(root.exists("shared_key.bin"));
+        assert!(backup.join("paired_secret_share.bin").exists());
+
+        // Nothing left for the doctor to flag about the layout
+        let issues = doctor::diagnose(&wallet, "w", 0).unwrap();
+        assert!(issues.iter().all(|i| !i.problem.contains("layout")));
+        assert!(migrate_layout_core(&wallet, &temp_dir("again")).is_err());
+
+        for dir in [wallet, other_dir, backup] {
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_rename_archive_delete() {
+        let state_root = temp_dir("root");
+        let make = |name: &str| {
+            let storage = FileStorage::new(&state_root.join(name).to_string_lossy()).unwrap();
+            storage.write("paired_secret_share.bin", b"share").unwrap();
+            let info = GroupInfo {
+                name: name.to_string(),
+                group_public_key: "aa".to_string(),
+                taproot_address_testnet: "tb1p".to_string(),
+                taproot_address_mainnet: "bc1p".to_string(),
+                threshold: 1,
+                total_parties: 1,
+                hierarchical: false,
+                parties: Vec::new(),
+                directory: Default::default(),
+            };
+            storage
+                .write("group_info.json", &serde_json::to_vec(&info).unwrap())
+                .unwrap();
+        };
+        make("a");
+        make("b");
+
+        assert!(rename_core(&state_root, "a", "b").is_err());
+        assert!(rename_core(&state_root, "a", "../x").is_err());
+        rename_core(&state_root, "a", "treasury").unwrap();
+        assert_eq!(
+            read_group_info(&state_root.join("treasury")).unwrap().name,
+            "treasury"
+        );
+
+        let archived = archive_core(&state_root, "treasury", 1_700_000_000).unwrap();
+        let target = PathBuf::from(archived.result);
+        assert_eq!(target, state_root.join(".archive/treasury-1700000000"));
+        assert!(target.join("paired_secret_share.bin").exists());
+        let tombstone: Tombstone =
+            serde_json::from_slice(&std::fs::read(target.join(TOMBSTONE_FILE)).unwrap()).unwrap();
+        assert_eq!(tombstone.group_public_key.as_deref(), Some("aa"));
+
+        assert!(delete_core(&state_root, "b", "B").is_err());
+        assert!(state_root.join("b").exists());
+        delete_core(&state_root, "b", "b").unwrap();
+        assert!(!state_root.join("b").exists());
+
+        std::fs::remove_dir_all(state_root).unwrap();
+    }
+
+    #[test]
+    fn test_wallet_notes_edit() {
+        let storage = crate::storage::MemoryStorage::new();
+        let edit = NotesEdit {
+            description: Some("Payroll treasury".to_string()),
+            contacts: vec!["2=bob@example.org".to_string(), "3=carol".to_string()],
+            tags: vec!["payroll".to_string(), "2024".to_string()],
+            ..Default::default()
+        };
+        notes_core(&edit, &storage).unwrap();
+
+        let edit = NotesEdit {
+            contacts: vec!["3=".to_string()],
+            created: Some("2024-03-01".to_string()),
+            tags: vec!["payroll".to_string()],
+            untags: vec!["2024".to_string()],
+            ..Default::default()
+        };
+        notes_core(&edit, &storage).unwrap();
+
+        let notes = WalletNotes::load(&storage).unwrap();
+        let expected = WalletNotes::from_text(
+            "Payroll treasury",
+            "2=bob@example.org",
+            "2024-03-01",
+            "payroll",
+        )
+        .unwrap();
+        assert_eq!(notes, expected);
+        assert_eq!(notes.contacts_text(), "2=bob@example.org");
+        assert!(parse_contact("bob").is_err());
+    }
+
+    #[test]
+    fn test_signing_requirement_edit() {
+        // 3-of-4 HTSS wallet: party 1 rank 0, parties 2-4 rank 1
+        let metadata = HtssMetadata {
+            my_index: 1,
+            my_rank: 0,
+            threshold: 3,
+            hierarchical: true,
+            party_ranks: BTreeMap::from([(1, 0), (2, 1), (3, 1), (4, 1)]),
+            wallet_version: storage::migrations::CURRENT_WALLET_VERSION,
+            signing_requirement: BTreeMap::new(),
+            created_at: None,
+            refreshed_at: None,
+        };
+        let root = crate::storage::MemoryStorage::new();
+        let party = crate::storage::MemoryStorage::new();
+        for folder in [&root, &party] {
+            folder
+                .write(
+                    "htss_metadata.json",
+                    &serde_json::to_vec(&metadata).unwrap(),
+                )
+                .unwrap();
+        }
+        let storages: Vec<&dyn Storage> = vec![&root, &party];
+
+        let result = signing_requirement_core(Some("0:1, 1:2"), false, &storages).unwrap();
+        assert!(result.output.contains("1 rank-0 and 2 rank-1 signers"));
+        let saved: HtssMetadata =
+            serde_json::from_slice(&party.read("htss_metadata.json").unwrap()).unwrap();
+        assert_eq!(saved.signing_requirement, BTreeMap::from([(0, 1), (1, 2)]));
+        assert!(saved.check_signing_requirement(&[1, 2, 3]).is_ok());
+        let err = saved.check_signing_requirement(&[2, 3, 4]).unwrap_err();
+        assert!(
+            err.to_string().contains("missing one rank-0 signer"),
+            "{}",
+            err
+        );
+
+        // Unsatisfiable requirements are refused
+        assert!(signing_requirement_core(Some("0:2"), false, &storages).is_err());
+        assert!(signing_requirement_core(Some("0:1,1:3"), false, &storages).is_err());
+        assert!(signing_requirement_core(Some("0:1,0:1"), false, &storages).is_err());
+
+        signing_requirement_core(None, true, &storages).unwrap();
+        let raw = String::from_utf8(root.read("htss_metadata.json").unwrap()).unwrap();
+        assert!(!raw.contains("signing_requirement"));
+    }
+}
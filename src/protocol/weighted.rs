@@ -0,0 +1,224 @@
+//! Weighted Shares
+//!
+//! In a weighted group a party holds several share indices, e.g. a foundation
+//! holding 3 of 7 shares while individuals hold one each. The threshold still
+//! counts shares, so in a 4-of-7 the foundation plus any one individual can
+//! sign.
+//!
+//! The party's first index lives in the wallet folder as usual. Every further
+//! index is a full participant of its own (secret share, nonces, identity key)
+//! kept in a `share<index>/` subfolder and reached through [`ShareSlot`].
+//! [`SLOTS_FILE`] lists the indices, and [`each_slot`] runs one protocol step
+//! for all of them, joining their payloads into a single batch.
+
+use crate::storage::Storage;
+use crate::CommandResult;
+use anyhow::{Context, Result};
+
+/// The party's share indices, first one being the folder's own
+pub const SLOTS_FILE: &str = "share_slots.json";
+
+/// Storage of one extra share index, inside the party's folder
+pub struct ShareSlot<'a> {
+    inner: &'a dyn Storage,
+    prefix: String,
+}
+
+impl<'a> ShareSlot<'a> {
+    pub fn new(inner: &'a dyn Storage, index: u32) -> Self {
+        Self {
+            inner,
+            prefix: format!("share{}/", index),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+impl Storage for ShareSlot<'_> {
+    fn read(&self, key: &str) -> Result<Vec<u8>> {
+        self.inner.read(&self.key(key))
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.inner.write(&self.key(key), data)
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        self.inner.exists(&self.key(key))
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        self.inner.delete(&self.key(key))
+    }
+
+    fn read_secret(&self, key: &str) -> Result<crate::crypto::secret::SecretBytes> {
+        self.inner.read_secret(&self.key(key))
+    }
+}
+
+/// Give a party `weight` consecutive indices starting at `first_index`
+///
+/// Records them in [`SLOTS_FILE`] when there is more than one, so the later
+/// steps know to run once per index.
+pub fn allocate(
+    storage: &dyn Storage,
+    first_index: u32,
+    weight: u32,
+    n_parties: u32,
+) -> Result<Vec<u32>> {
+    if weight == 0 {
+        anyhow::bail!("Weight must be at least 1");
+    }
+    let last_index = first_index
+        .checked_add(weight - 1)
+        .filter(|last| *last <= n_parties)
+        .with_context(|| {
+            format!(
+                "A weight of {} from index {} needs indices up to {}, but there are only {} shares",
+                weight,
+                first_index,
+                first_index as u64 + weight as u64 - 1,
+                n_parties
+            )
+        })?;
+    let indices: Vec<u32> = (first_index..=last_index).collect();
+    if indices.len() > 1 {
+        storage.write(SLOTS_FILE, serde_json::to_string(&indices)?.as_bytes())?;
+    } else if storage.exists(SLOTS_FILE) {
+        storage.delete(SLOTS_FILE)?;
+    }
+    Ok(indices)
+}
+
+/// Share indices held by the party in `storage`; empty for an ordinary wallet
+pub fn slot_indices(storage: &dyn Storage) -> Result<Vec<u32>> {
+    if !storage.exists(SLOTS_FILE) {
+        return Ok(Vec::new());
+    }
+    serde_json::from_slice(&storage.read(SLOTS_FILE)?)
+        .with_context(|| format!("{} is damaged", SLOTS_FILE))
+}
+
+/// Whether the party in `storage` holds more than one share
+pub fn is_weighted(storage: &dyn Storage) -> bool {
+    slot_indices(storage).is_ok_and(|indices| indices.len() > 1)
+}
+
+/// Heading put before each share's part of a weighted step's output
+pub fn slot_header(index: u32) -> String {
+    format!("── Share {} ──", index)
+}
+
+/// Run `step` for every share the party in `storage` holds
+///
+/// An ordinary wallet runs it once on `storage`; a weighted one goes through
+/// [`run_slots`] with the indices from [`SLOTS_FILE`].
+pub fn each_slot(
+    storage: &dyn Storage,
+    mut step: impl FnMut(&dyn Storage) -> Result<CommandResult>,
+) -> Result<CommandResult> {
+    let indices = slot_indices(storage)?;
+    if indices.is_empty() {
+        return step(storage);
+    }
+    run_slots(storage, &indices, |_, slot| step(slot))
+}
+
+/// Run `step` once per index in `indices`
+///
+/// The first index uses the folder itself, the rest a [`ShareSlot`]. Returns
+/// the outputs one after another (headed per share when there are several)
+/// and the results as one space-separated batch, the form every payload
+/// parser accepts.
+pub fn run_slots(
+    storage: &dyn Storage,
+    indices: &[u32],
+    mut step: impl FnMut(u32, &dyn Storage) -> Result<CommandResult>,
+) -> Result<CommandResult> {
+    let mut output = String::new();
+    let mut results = Vec::new();
+    for (position, &index) in indices.iter().enumerate() {
+        let cmd_result = if position == 0 {
+            step(index, storage)
+        } else {
+            step(index, &ShareSlot::new(storage, index))
+        }
+        .with_context(|| format!("Share {}", index))?;
+        if indices.len() > 1 {
+            output.push_str(&slot_header(index));
+            output.push('\n');
+        }
+        output.push_str(&cmd_result.output);
+        results.push(cmd_result.result);
+    }
+    Ok(CommandResult {
+        output,
+        result: results.join(" "),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{keygen, signing};
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_allocate_checks_range() {
+        let storage = MemoryStorage::new();
+        assert_eq!(allocate(&storage, 1, 1, 3).unwrap(), vec![1]);
+        assert!(!storage.exists(SLOTS_FILE));
+        assert!(!is_weighted(&storage));
+
+        assert_eq!(allocate(&storage, 2, 2, 3).unwrap(), vec![2, 3]);
+        assert_eq!(slot_indices(&storage).unwrap(), vec![2, 3]);
+        assert!(is_weighted(&storage));
+
+        assert!(allocate(&storage, 2, 3, 3).is_err());
+        assert!(allocate(&storage, 1, 0, 3).is_err());
+        allocate(&storage, 1, 1, 3).unwrap();
+        assert!(!storage.exists(SLOTS_FILE));
+    }
+
+    #[test]
+    fn test_weighted_keygen_and_signing() {
+        // 3-of-4: party A holds shares 1-3, party B holds share 4
+        let a = MemoryStorage::new();
+        let b = MemoryStorage::new();
+        let indices = allocate(&a, 1, 3, 4).unwrap();
+
+        let r1_a = run_slots(&a, &indices, |index, slot| {
+            keygen::round1_core(3, 4, index, 0, false, slot)
+        })
+        .unwrap();
+        let r1_b = keygen::round1_core(3, 4, 4, 0, false, &b).unwrap();
+        let r1 = format!("{} {}", r1_a.result, r1_b.result);
+
+        let r2_a = each_slot(&a, |slot| keygen::round2_core(&r1, slot)).unwrap();
+        let r2_b = keygen::round2_core(&r1, &b).unwrap();
+        let r2 = format!("{} {}", r2_a.result, r2_b.result);
+
+        each_slot(&a, |slot| keygen::finalize_core(&r2, slot)).unwrap();
+        keygen::finalize_core(&r2, &b).unwrap();
+        assert!(a.exists("share2/paired_secret_share.bin"));
+        assert!(a.exists("share3/paired_secret_share.bin"));
+        assert_eq!(
+            a.read("shared_key.bin").unwrap(),
+            b.read("shared_key.bin").unwrap()
+        );
+
+        // Party A alone holds 3 shares, enough to sign with one command each
+        let message = "weighted";
+        let nonces = each_slot(&a, |slot| signing::generate_nonce_core("s1", slot)).unwrap();
+        let shares = each_slot(&a, |slot| {
+            signing::create_signature_share_core("s1", message, &nonces.result, slot)
+        })
+        .unwrap();
+        assert!(shares.output.contains(&slot_header(3)));
+        let signature = signing::combine_signatures_core(&shares.result, &a).unwrap();
+        assert!(signature.output.contains("FROST SIGNATURE VALID"));
+    }
+}
@@ -0,0 +1,417 @@
+//! Python Bindings
+//!
+//! PyO3 module exposing the protocol cores to Python (`pip install frostdao`,
+//! built by maturin with the `python` feature). Intended for scripted
+//! multi-party simulations, protocol fuzzing and treasury tooling.
+//!
+//! Conventions match the C FFI (`ffi.rs`):
+//! - Wallets are addressed by directory; it is created if needed.
+//! - Payload arguments accept either the CLI's space-separated JSON string or
+//!   a list of outputs (dicts or JSON strings) collected from other parties.
+//! - Calls return `{"output": str, "result": ...}` with `result` structured
+//!   as in the WASM bindings, and raise `frostdao.FrostError(code, message)` with
+//!   the same codes as the WASM `FrostError`.
+//! - The GIL is released while a core runs, so parties can be simulated on
+//!   Python threads.
+
+use crate::btc::hd_address;
+use crate::protocol::{dkg_tx, keygen, recovery, reshare, signing};
+use crate::storage::FileStorage;
+use crate::CommandResult;
+use anyhow::Result;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+
+create_exception!(
+    frostdao,
+    FrostError,
+    PyException,
+    "A protocol step failed; args are (code, message)."
+);
+
+/// Run a core without the GIL and convert its outcome for Python
+fn run(py: Python<'_>, call: impl FnOnce() -> Result<CommandResult> + Send) -> PyResult<PyObject> {
+    match py.allow_threads(call) {
+        Ok(cmd_result) => {
+            let result = crate::wasm::structured_result(cmd_result.result).to_string();
+            let result = py.import("json")?.call_method1("loads", (result,))?;
+            let dict = PyDict::new(py);
+            dict.set_item("output", cmd_result.output)?;
+            dict.set_item("result", result)?;
+            Ok(dict.into_any().unbind())
+        }
+        Err(e) => {
+            let message = format!("{:#}", e);
+            Err(FrostError::new_err((
+                crate::wasm::error_code(&message),
+                message,
+            )))
+        }
+    }
+}
+
+/// Payload argument: a string as pasted in the CLI, or a list of outputs
+fn payloads(py: Python<'_>, data: &Bound<'_, PyAny>) -> PyResult<String> {
+    if data.is_instance_of::<PyString>() {
+        return data.extract();
+    }
+    let json = py.import("json")?;
+    let mut parts: Vec<String> = Vec::new();
+    for item in data.try_iter()? {
+        let item = item?;
+        parts.push(if item.is_instance_of::<PyString>() {
+            item.extract()?
+        } else {
+            json.call_method1("dumps", (item,))?.extract()?
+        });
+    }
+    Ok(parts.join(" "))
+}
+
+/// Last path component, used where the cores want a wallet name
+fn wallet_name(wallet_dir: &str) -> String {
+    std::path::Path::new(wallet_dir)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| wallet_dir.to_string())
+}
+
+// ============================================================================
+// Keygen
+// ============================================================================
+
+/// Keygen round 1 (secp256k1)
+#[pyfunction]
+#[pyo3(signature = (wallet_dir, threshold, n_parties, my_index, rank=0, hierarchical=false))]
+fn keygen_round1(
+    py: Python<'_>,
+    wallet_dir: &str,
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    rank: u32,
+    hierarchical: bool,
+) -> PyResult<PyObject> {
+    run(py, || {
+        let storage = FileStorage::new(wallet_dir)?;
+        keygen::round1_core(threshold, n_parties, my_index, rank, hierarchical, &storage)
+    })
+}
+
+/// Keygen round 2 from every party's round 1 output
+#[pyfunction]
+fn keygen_round2(
+    py: Python<'_>,
+    wallet_dir: &str,
+    round1: &Bound<'_, PyAny>,
+) -> PyResult<PyObject> {
+    let data = payloads(py, round1)?;
+    run(py, || {
+        keygen::round2_core(&data, &FileStorage::new(wallet_dir)?)
+    })
+}
+
+/// Keygen finalize from the round 2 outputs
+#[pyfunction]
+fn keygen_finalize(
+    py: Python<'_>,
+    wallet_dir: &str,
+    round2: &Bound<'_, PyAny>,
+) -> PyResult<PyObject> {
+    let data = payloads(py, round2)?;
+    run(py, || {
+        keygen::finalize_core(&data, &FileStorage::new(wallet_dir)?)
+    })
+}
+
+// ============================================================================
+// Signing
+// ============================================================================
+
+/// Signing nonce for `session`
+#[pyfunction]
+fn generate_nonce(py: Python<'_>, wallet_dir: &str, session: &str) -> PyResult<PyObject> {
+    run(py, || {
+        signing::generate_nonce_core(session, &FileStorage::new(wallet_dir)?)
+    })
+}
+
+/// Signature share over `message`, given every signer's nonce
+#[pyfunction]
+fn sign(
+    py: Python<'_>,
+    wallet_dir: &str,
+    session: &str,
+    message: &str,
+    nonces: &Bound<'_, PyAny>,
+) -> PyResult<PyObject> {
+    let data = payloads(py, nonces)?;
+    run(py, || {
+        signing::create_signature_share_core(
+            session,
+            message,
+            &data,
+            &FileStorage::new(wallet_dir)?,
+        )
+    })
+}
+
+/// Combine signature shares into the final signature
+#[pyfunction]
+fn combine(py: Python<'_>, wallet_dir: &str, shares: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    let data = payloads(py, shares)?;
+    run(py, || {
+        signing::combine_signatures_core(&data, &FileStorage::new(wallet_dir)?)
+    })
+}
+
+/// Check a BIP340 signature; needs no wallet
+#[pyfunction]
+fn verify(py: Python<'_>, signature: &str, public_key: &str, message: &str) -> PyResult<PyObject> {
+    run(py, || {
+        signing::verify_signature_core(signature, public_key, message)
+    })
+}
+
+// ============================================================================
+// Reshare and recovery
+// ============================================================================
+
+/// Reshare round 1: sub-shares of this party's share for the new group
+///
+/// `new_identities` lists `<index>=<key>` for new parties whose identity
+/// isn't the one pinned at their index.
+#[pyfunction]
+#[pyo3(signature = (wallet_dir, new_threshold, new_n_parties, my_old_index, new_identities=Vec::new()))]
+fn reshare_round1(
+    py: Python<'_>,
+    wallet_dir: &str,
+    new_threshold: u32,
+    new_n_parties: u32,
+    my_old_index: u32,
+    new_identities: Vec<String>,
+) -> PyResult<PyObject> {
+    run(py, || {
+        let storage = FileStorage::new(wallet_dir)?;
+        reshare::reshare_round1_with_storage(
+            &wallet_name(wallet_dir),
+            new_threshold,
+            new_n_parties,
+            my_old_index,
+            &new_identities,
+            &storage,
+            &storage,
+        )
+    })
+}
+
+/// Reshare finalize: combine sub-shares into a new wallet at `target_dir`
+#[pyfunction]
+#[pyo3(signature = (source_dir, target_dir, my_new_index, round1, rank=0, hierarchical=false, force=false, allow_threshold_decrease=false))]
+#[allow(clippy::too_many_arguments)]
+fn reshare_finalize(
+    py: Python<'_>,
+    source_dir: &str,
+    target_dir: &str,
+    my_new_index: u32,
+    round1: &Bound<'_, PyAny>,
+    rank: u32,
+    hierarchical: bool,
+    force: bool,
+    allow_threshold_decrease: bool,
+) -> PyResult<PyObject> {
+    let data = payloads(py, round1)?;
+    run(py, || {
+        reshare::reshare_finalize_in_dirs(
+            source_dir,
+            target_dir,
+            &wallet_name(target_dir),
+            my_new_index,
+            rank,
+            hierarchical,
+            &data,
+            force,
+            allow_threshold_decrease,
+        )
+    })
+}
+
+/// Reshare dry run: check the sub-shares and the group key, write nothing
+#[pyfunction]
+#[pyo3(signature = (source_dir, my_new_index, round1, allow_threshold_decrease=false))]
+fn reshare_verify(
+    py: Python<'_>,
+    source_dir: &str,
+    my_new_index: u32,
+    round1: &Bound<'_, PyAny>,
+    allow_threshold_decrease: bool,
+) -> PyResult<PyObject> {
+    let data = payloads(py, round1)?;
+    run(py, || {
+        reshare::reshare_verify_with_storage(
+            my_new_index,
+            &data,
+            allow_threshold_decrease,
+            &FileStorage::new(source_dir)?,
+        )
+    })
+}
+
+/// Recovery round 1: help rebuild `lost_index`'s share
+#[pyfunction]
+fn recover_round1(py: Python<'_>, wallet_dir: &str, lost_index: u32) -> PyResult<PyObject> {
+    run(py, || {
+        recovery::recover_round1_core(
+            &wallet_name(wallet_dir),
+            lost_index,
+            &FileStorage::new(wallet_dir)?,
+        )
+    })
+}
+
+/// Recovery finalize: rebuild this party's share into `target_dir`
+///
+/// `source_dir` is the lost wallet's folder (group info only is enough).
+#[pyfunction]
+#[pyo3(signature = (source_dir, target_dir, my_index, round1, force=false))]
+fn recover_finalize(
+    py: Python<'_>,
+    source_dir: &str,
+    target_dir: &str,
+    my_index: u32,
+    round1: &Bound<'_, PyAny>,
+    force: bool,
+) -> PyResult<PyObject> {
+    let data = payloads(py, round1)?;
+    run(py, || {
+        recovery::recover_finalize_with_storage(
+            &wallet_name(source_dir),
+            &wallet_name(target_dir),
+            my_index,
+            &data,
+            force,
+            &FileStorage::new(source_dir)?,
+            &FileStorage::new(target_dir)?,
+        )
+    })
+}
+
+// ============================================================================
+// HD derivation and transactions
+// ============================================================================
+
+/// Taproot address at m/44'/0'/0'/`change`/`index`
+#[pyfunction]
+#[pyo3(signature = (wallet_dir, index, change=0, network="testnet"))]
+fn derive_address(
+    py: Python<'_>,
+    wallet_dir: &str,
+    index: u32,
+    change: u32,
+    network: &str,
+) -> PyResult<PyObject> {
+    run(py, || {
+        hd_address::derive_address_core(change, index, network, &FileStorage::new(wallet_dir)?)
+    })
+}
+
+/// Unsigned transaction and sighash for a DKG signing session (fetches UTXOs)
+#[cfg(feature = "network")]
+#[pyfunction]
+#[pyo3(signature = (wallet_dir, to_address, amount_sats, network="testnet", fee_rate=None, from_script_address=None, memo=None, spend_path=None, preimage=None))]
+#[allow(clippy::too_many_arguments)]
+fn build_tx(
+    py: Python<'_>,
+    wallet_dir: &str,
+    to_address: &str,
+    amount_sats: u64,
+    network: &str,
+    fee_rate: Option<u64>,
+    from_script_address: Option<&str>,
+    memo: Option<&str>,
+    spend_path: Option<&str>,
+    preimage: Option<&str>,
+) -> PyResult<PyObject> {
+    run(py, || {
+        dkg_tx::build_unsigned_tx_core(
+            &wallet_name(wallet_dir),
+            to_address,
+            amount_sats,
+            fee_rate,
+            hd_address::parse_network(network)?,
+            false,
+            from_script_address,
+            memo,
+            spend_path.map(str::parse).transpose()?,
+            preimage,
+            &FileStorage::new(wallet_dir)?,
+        )
+    })
+}
+
+/// Nonce for a transaction session
+#[pyfunction]
+fn tx_nonce(py: Python<'_>, wallet_dir: &str, session_id: &str) -> PyResult<PyObject> {
+    run(py, || {
+        dkg_tx::dkg_generate_nonce_core(
+            &wallet_name(wallet_dir),
+            session_id,
+            &FileStorage::new(wallet_dir)?,
+        )
+    })
+}
+
+/// Signature share over a transaction sighash, given every signer's nonce
+#[pyfunction]
+#[pyo3(signature = (wallet_dir, session_id, sighash, nonces, merkle_root=None, tx=None, unsafe_bare_sighash=false))]
+#[allow(clippy::too_many_arguments)]
+fn tx_sign(
+    py: Python<'_>,
+    wallet_dir: &str,
+    session_id: &str,
+    sighash: &str,
+    nonces: &Bound<'_, PyAny>,
+    merkle_root: Option<&str>,
+    tx: Option<&str>,
+    unsafe_bare_sighash: bool,
+) -> PyResult<PyObject> {
+    let data = payloads(py, nonces)?;
+    run(py, || {
+        let tx: Option<dkg_tx::BuildTxOutput> = tx.map(serde_json::from_str).transpose()?;
+        dkg_tx::dkg_sign_core(
+            &wallet_name(wallet_dir),
+            session_id,
+            sighash,
+            &data,
+            merkle_root,
+            tx.as_ref(),
+            unsafe_bare_sighash,
+            &FileStorage::new(wallet_dir)?,
+        )
+    })
+}
+
+#[pymodule]
+fn frostdao(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("FrostError", m.py().get_type::<FrostError>())?;
+    m.add_function(wrap_pyfunction!(keygen_round1, m)?)?;
+    m.add_function(wrap_pyfunction!(keygen_round2, m)?)?;
+    m.add_function(wrap_pyfunction!(keygen_finalize, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_nonce, m)?)?;
+    m.add_function(wrap_pyfunction!(sign, m)?)?;
+    m.add_function(wrap_pyfunction!(combine, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(reshare_round1, m)?)?;
+    m.add_function(wrap_pyfunction!(reshare_finalize, m)?)?;
+    m.add_function(wrap_pyfunction!(reshare_verify, m)?)?;
+    m.add_function(wrap_pyfunction!(recover_round1, m)?)?;
+    m.add_function(wrap_pyfunction!(recover_finalize, m)?)?;
+    m.add_function(wrap_pyfunction!(derive_address, m)?)?;
+    #[cfg(feature = "network")]
+    m.add_function(wrap_pyfunction!(build_tx, m)?)?;
+    m.add_function(wrap_pyfunction!(tx_nonce, m)?)?;
+    m.add_function(wrap_pyfunction!(tx_sign, m)?)?;
+    Ok(())
+}
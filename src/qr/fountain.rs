@@ -0,0 +1,408 @@
+//! Fountain codes for multi-part URs
+//!
+//! The message is cut into equal fragments. Parts `1..=seq_len` carry one
+//! fragment each; later parts XOR together a pseudo-random subset chosen by
+//! xoshiro256** seeded from the part number and message checksum, so a
+//! scanner can finish from any large enough set of frames, in any order.
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::ur::{
+    cbor_bytes, cbor_header, cbor_read, cbor_read_bytes, crc32, CBOR_ARRAY, CBOR_UINT,
+};
+
+/// Shortest fragment the encoder will choose before adding more parts
+const MIN_FRAGMENT_LEN: usize = 10;
+
+/// Largest message a scan may declare, so a bad frame cannot balloon memory
+const MAX_MESSAGE_LEN: usize = 1 << 20;
+
+/// Most fragments a scan may declare; choosing a mixed part's fragments is
+/// quadratic in this, so it is capped well below `MAX_MESSAGE_LEN`
+const MAX_SEQ_LEN: usize = 4096;
+
+/// xoshiro256** seeded from the SHA-256 of a byte string
+pub(crate) struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    pub(crate) fn from_seed(seed: &[u8]) -> Self {
+        let digest = Sha256::digest(seed);
+        let mut s = [0u64; 4];
+        for (word, chunk) in s.iter_mut().zip(digest.chunks(8)) {
+            *word = chunk.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        }
+        Self { s }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+        result
+    }
+
+    fn next_double(&mut self) -> f64 {
+        self.next_u64() as f64 / (u64::MAX as f64 + 1.0)
+    }
+
+    fn next_int(&mut self, low: u64, high: u64) -> u64 {
+        (self.next_double() * (high - low + 1) as f64) as u64 + low
+    }
+
+    fn shuffled<T>(&mut self, mut items: Vec<T>) -> Vec<T> {
+        let mut result = Vec::with_capacity(items.len());
+        while !items.is_empty() {
+            let index = self.next_int(0, items.len() as u64 - 1) as usize;
+            result.push(items.remove(index));
+        }
+        result
+    }
+}
+
+/// Walker/Vose alias sampler over a discrete distribution
+struct RandomSampler {
+    probs: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl RandomSampler {
+    fn new(weights: &[f64]) -> Self {
+        let n = weights.len();
+        let sum: f64 = weights.iter().sum();
+        let mut scaled: Vec<f64> = weights.iter().map(|w| w * n as f64 / sum).collect();
+
+        let (mut small, mut large) = (Vec::new(), Vec::new());
+        for j in (0..n).rev() {
+            if scaled[j] < 1.0 {
+                small.push(j);
+            } else {
+                large.push(j);
+            }
+        }
+
+        let mut probs = vec![0.0; n];
+        let mut aliases = vec![0; n];
+        while let (Some(&a), Some(&g)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            probs[a] = scaled[a];
+            aliases[a] = g;
+            scaled[g] += scaled[a] - 1.0;
+            if scaled[g] < 1.0 {
+                small.push(g);
+            } else {
+                large.push(g);
+            }
+        }
+        for j in large.into_iter().chain(small) {
+            probs[j] = 1.0;
+        }
+
+        Self { probs, aliases }
+    }
+
+    fn next(&self, rng: &mut Xoshiro256) -> usize {
+        let r1 = rng.next_double();
+        let r2 = rng.next_double();
+        let i = (self.probs.len() as f64 * r1) as usize;
+        if r2 < self.probs[i] {
+            i
+        } else {
+            self.aliases[i]
+        }
+    }
+}
+
+/// Fragment indexes (0-based) XORed into part `seq_num`
+pub(crate) fn choose_fragments(seq_num: u32, seq_len: usize, checksum: u32) -> BTreeSet<usize> {
+    if seq_num as usize <= seq_len {
+        return BTreeSet::from([seq_num as usize - 1]);
+    }
+    let mut seed = seq_num.to_be_bytes().to_vec();
+    seed.extend(checksum.to_be_bytes());
+    let mut rng = Xoshiro256::from_seed(&seed);
+
+    // Degree d is drawn with probability proportional to 1/d
+    let weights: Vec<f64> = (1..=seq_len).map(|d| 1.0 / d as f64).collect();
+    let degree = RandomSampler::new(&weights).next(&mut rng) + 1;
+
+    rng.shuffled((0..seq_len).collect())
+        .into_iter()
+        .take(degree)
+        .collect()
+}
+
+/// Smallest fragment count whose fragments fit in `max_fragment_len`
+fn nominal_fragment_len(message_len: usize, max_fragment_len: usize) -> usize {
+    let max_count = (message_len / MIN_FRAGMENT_LEN).max(1);
+    let mut fragment_len = message_len;
+    for count in 1..=max_count {
+        fragment_len = message_len.div_ceil(count);
+        if fragment_len <= max_fragment_len {
+            break;
+        }
+    }
+    fragment_len.max(1)
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    for (t, o) in target.iter_mut().zip(other) {
+        *t ^= o;
+    }
+}
+
+/// One fountain part as carried in a multi-part UR
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FountainPart {
+    pub seq_num: u32,
+    pub seq_len: usize,
+    pub message_len: usize,
+    pub checksum: u32,
+    pub data: Vec<u8>,
+}
+
+impl FountainPart {
+    /// CBOR array `[seq_num, seq_len, message_len, checksum, data]`
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.data.len() + 24);
+        cbor_header(CBOR_ARRAY, 5, &mut out);
+        cbor_header(CBOR_UINT, self.seq_num as u64, &mut out);
+        cbor_header(CBOR_UINT, self.seq_len as u64, &mut out);
+        cbor_header(CBOR_UINT, self.message_len as u64, &mut out);
+        cbor_header(CBOR_UINT, self.checksum as u64, &mut out);
+        cbor_bytes(&self.data, &mut out);
+        out
+    }
+
+    pub fn from_cbor(cbor: &[u8]) -> Result<Self> {
+        let mut pos = 0;
+        if cbor_read(cbor, &mut pos, CBOR_ARRAY)? != 5 {
+            bail!("Fountain part must be a 5-element array");
+        }
+        let seq_num = u32::try_from(cbor_read(cbor, &mut pos, CBOR_UINT)?)?;
+        let seq_len = cbor_read(cbor, &mut pos, CBOR_UINT)? as usize;
+        let message_len = cbor_read(cbor, &mut pos, CBOR_UINT)? as usize;
+        let checksum = u32::try_from(cbor_read(cbor, &mut pos, CBOR_UINT)?)?;
+        let data = cbor_read_bytes(cbor, &mut pos)?.to_vec();
+        if seq_num == 0 || seq_len == 0 || data.is_empty() {
+            bail!("Fountain part has zero sequence number, length or data");
+        }
+        let part = Self {
+            seq_num,
+            seq_len,
+            message_len,
+            checksum,
+            data,
+        };
+        part.check_layout()?;
+        Ok(part)
+    }
+
+    /// Refuse lengths no real encoder produces, before anything is allocated
+    fn check_layout(&self) -> Result<()> {
+        let capacity = self.seq_len.checked_mul(self.data.len());
+        if self.message_len > MAX_MESSAGE_LEN
+            || self.seq_len > MAX_SEQ_LEN
+            || self.seq_len > self.message_len.max(1)
+            || capacity.is_none_or(|cap| cap < self.message_len)
+        {
+            bail!("Fountain part declares an impossible message layout");
+        }
+        Ok(())
+    }
+}
+
+/// Splits a message into fragments and emits parts on demand
+pub struct FountainEncoder {
+    message: Vec<u8>,
+    fragments: Vec<Vec<u8>>,
+    checksum: u32,
+}
+
+impl FountainEncoder {
+    pub fn new(message: &[u8], max_fragment_len: usize) -> Self {
+        let fragment_len = nominal_fragment_len(message.len(), max_fragment_len);
+        let fragments = message
+            .chunks(fragment_len)
+            .map(|chunk| {
+                let mut fragment = chunk.to_vec();
+                fragment.resize(fragment_len, 0);
+                fragment
+            })
+            .collect();
+        Self {
+            message: message.to_vec(),
+            fragments,
+            checksum: crc32(message),
+        }
+    }
+
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    pub fn seq_len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Part `seq_num` (1-based); parts are a pure function of their number
+    pub fn part(&self, seq_num: u32) -> FountainPart {
+        let mut data = vec![0u8; self.fragments[0].len()];
+        for index in choose_fragments(seq_num, self.seq_len(), self.checksum) {
+            xor_into(&mut data, &self.fragments[index]);
+        }
+        FountainPart {
+            seq_num,
+            seq_len: self.seq_len(),
+            message_len: self.message.len(),
+            checksum: self.checksum,
+            data,
+        }
+    }
+}
+
+/// Reassembles a message from parts received in any order
+#[derive(Default)]
+pub struct FountainDecoder {
+    params: Option<(usize, usize, u32, usize)>,
+    simple: BTreeMap<usize, Vec<u8>>,
+    mixed: Vec<(BTreeSet<usize>, Vec<u8>)>,
+    message: Option<Vec<u8>>,
+}
+
+impl FountainDecoder {
+    /// Add a part; returns true once the message is complete
+    pub fn receive(&mut self, part: &FountainPart) -> Result<bool> {
+        if self.message.is_some() {
+            return Ok(true);
+        }
+        let params = (
+            part.seq_len,
+            part.message_len,
+            part.checksum,
+            part.data.len(),
+        );
+        match self.params {
+            None => {
+                part.check_layout()?;
+                self.params = Some(params);
+            }
+            Some(expected) if expected != params => {
+                bail!("Fountain part does not belong to the message being scanned")
+            }
+            Some(_) => {}
+        }
+
+        let indexes = choose_fragments(part.seq_num, part.seq_len, part.checksum);
+        let mut queue = vec![(indexes, part.data.clone())];
+        while let Some((mut indexes, mut data)) = queue.pop() {
+            // Peel off fragments we already know
+            for index in indexes.clone() {
+                if let Some(known) = self.simple.get(&index) {
+                    xor_into(&mut data, known);
+                    indexes.remove(&index);
+                }
+            }
+            match indexes.len() {
+                0 => {}
+                1 => {
+                    let index = *indexes.first().context("empty index set")?;
+                    self.simple.insert(index, data);
+                    // Mixed parts containing this fragment may now reduce further
+                    let (touched, untouched) = std::mem::take(&mut self.mixed)
+                        .into_iter()
+                        .partition(|(set, _)| set.contains(&index));
+                    self.mixed = untouched;
+                    queue.extend(touched);
+                }
+                _ => {
+                    if !self.mixed.iter().any(|(set, _)| *set == indexes) {
+                        self.mixed.push((indexes, data));
+                    }
+                }
+            }
+        }
+
+        let (seq_len, message_len, checksum, _) = params;
+        if self.simple.len() == seq_len {
+            let mut message: Vec<u8> = self.simple.values().flatten().copied().collect();
+            message.truncate(message_len);
+            if crc32(&message) != checksum {
+                bail!("Reassembled message failed its checksum");
+            }
+            self.message = Some(message);
+        }
+        Ok(self.message.is_some())
+    }
+
+    /// (fragments recovered, fragments expected)
+    pub fn progress(&self) -> (usize, usize) {
+        let total = self.params.map(|(seq_len, ..)| seq_len).unwrap_or(0);
+        (self.simple.len(), total)
+    }
+
+    pub fn message(&self) -> Option<&[u8]> {
+        self.message.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fountain_roundtrip_from_mixed_parts() {
+        // Reference xoshiro256** stream seeded from "Wolf"
+        let mut rng = Xoshiro256::from_seed(b"Wolf");
+        let first: Vec<u64> = (0..10).map(|_| rng.next_u64() % 100).collect();
+        assert_eq!(first, [42, 81, 85, 8, 82, 84, 76, 73, 70, 88]);
+
+        let message: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let encoder = FountainEncoder::new(&message, 100);
+        assert_eq!(encoder.seq_len(), 10);
+
+        // Skip the first few pure parts entirely: mixes must fill the gap
+        let mut decoder = FountainDecoder::default();
+        let mut seq_num = 4;
+        while !decoder.receive(&encoder.part(seq_num)).unwrap() {
+            let part = FountainPart::from_cbor(&encoder.part(seq_num).to_cbor()).unwrap();
+            assert_eq!(part, encoder.part(seq_num));
+            seq_num += 1;
+            assert!(seq_num < 200, "decoder never converged");
+        }
+        assert_eq!(decoder.message().unwrap(), &message[..]);
+    }
+
+    #[test]
+    fn test_hostile_headers_are_refused_before_allocating() {
+        let part = |seq_len: usize, message_len: usize| FountainPart {
+            seq_num: 1,
+            seq_len,
+            message_len,
+            checksum: 0,
+            data: vec![0; 1000],
+        };
+        // Within the message cap, but far too many fragments to shuffle
+        assert!(FountainPart::from_cbor(&part(1 << 20, 1 << 20).to_cbor()).is_err());
+        assert!(FountainPart::from_cbor(&part(MAX_SEQ_LEN + 1, 1 << 20).to_cbor()).is_err());
+        assert!(FountainPart::from_cbor(&part(MAX_SEQ_LEN, 1 << 20).to_cbor()).is_ok());
+
+        // A byte string claiming u64::MAX bytes is truncated, not a panic
+        let mut cbor = Vec::new();
+        cbor_header(CBOR_ARRAY, 5, &mut cbor);
+        for value in [1, 1, 1, 0] {
+            cbor_header(CBOR_UINT, value, &mut cbor);
+        }
+        cbor.extend([0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0]);
+        assert!(FountainPart::from_cbor(&cbor).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! QR Transport
+//!
+//! Round data, nonces and signature shares are too long to type between
+//! machines. This module carries them as BC-UR `ur:bytes` strings, one QR
+//! frame when the payload is short, otherwise an animated sequence of
+//! fountain-coded frames that a phone or webcam scanner can collect in any
+//! order.
+//!
+//! - **ur**: UR strings, bytewords, CRC32 and the CBOR framing
+//! - **fountain**: fragment mixing and reassembly for multi-part URs
+//!
+//! Scanned frames come back in as text: anywhere a command takes `--data`,
+//! it also accepts the `ur:` strings themselves or `@file` naming a scan
+//! export with one UR (or JSON blob) per line.
+
+pub mod fountain;
+pub mod ur;
+
+use crate::protocol::compact;
+use crate::protocol::payload::read_payload_files;
+use anyhow::{Context, Result};
+use qrcode::{Color, QrCode};
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use ur::{UrDecoder, UrEncoder};
+
+/// Fragment size per frame; keeps each QR small enough for a terminal
+pub const MAX_FRAGMENT_LEN: usize = 100;
+
+/// Delay between animated frames
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Encode a payload, ready to hand frames to a display
+///
+/// With `--compact` a JSON payload travels as its CBOR bytes.
+pub fn encoder(payload: &str) -> UrEncoder {
+    match compact::is_compact().then(|| compact::to_bytes(payload)) {
+        Some(Ok(bytes)) => UrEncoder::new(&bytes, MAX_FRAGMENT_LEN),
+        _ => UrEncoder::new(payload.as_bytes(), MAX_FRAGMENT_LEN),
+    }
+}
+
+/// Render a UR as half-block text rows (two QR modules per character)
+pub fn render_lines(ur: &str) -> Result<Vec<String>> {
+    // Upper case keeps the whole UR in QR alphanumeric mode
+    let code = QrCode::new(ur.to_ascii_uppercase().as_bytes())
+        .map_err(|e| anyhow::anyhow!("QR generation failed: {}", e))?;
+    let width = code.width();
+    let colors = code.to_colors();
+    let dark = |x: usize, y: usize| y < width && colors[y * width + x] == Color::Dark;
+
+    Ok((0..width)
+        .step_by(2)
+        .map(|y| {
+            (0..width)
+                .map(|x| match (dark(x, y), dark(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Print a payload as QR: one static frame, or an animation until Enter
+///
+/// When stdout is not a terminal every pure fragment is printed once,
+/// followed by its UR text, so the output can still be piped to a file.
+pub fn show_qr(payload: &str) -> Result<()> {
+    let encoder = encoder(payload);
+    let seq_len = encoder.seq_len();
+
+    println!("📷 Scan with a UR-capable wallet, or save the frames for --data @file");
+    if seq_len == 1 || !std::io::stdout().is_terminal() {
+        for seq_num in 1..=seq_len as u32 {
+            let ur = encoder.part(seq_num);
+            for line in render_lines(&ur)? {
+                println!("  {}", line);
+            }
+            println!("{}\n", ur);
+        }
+        return Ok(());
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = Arc::clone(&stop);
+    std::thread::spawn(move || {
+        let mut line = String::new();
+        let _ = std::io::stdin().read_line(&mut line);
+        stop_flag.store(true, Ordering::Relaxed);
+    });
+
+    let mut stdout = std::io::stdout();
+    let mut seq_num = 1u32;
+    let mut drawn = 0;
+    while !stop.load(Ordering::Relaxed) {
+        let lines = render_lines(&encoder.part(seq_num))?;
+        if drawn > 0 {
+            // Redraw in place over the previous frame
+            write!(stdout, "\x1b[{}A", drawn)?;
+        }
+        for line in &lines {
+            writeln!(stdout, "  {}\x1b[K", line)?;
+        }
+        writeln!(
+            stdout,
+            "🎞️  Frame {} ({} fragments) - press Enter when the scanner is done\x1b[K",
+            seq_num, seq_len
+        )?;
+        stdout.flush()?;
+        drawn = lines.len() + 1;
+        seq_num = seq_num.checked_add(1).unwrap_or(1);
+        std::thread::sleep(FRAME_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Turn scanned input back into the JSON a command expects
+///
+/// Accepts plain JSON (returned unchanged), `cbor:` compact payloads,
+/// whitespace-separated `ur:` strings, or `@path` to a scan export, payload
+/// file or folder of `.json` payloads. Several URs decode to several
+/// payloads, joined by spaces the way multi-party `--data` is pasted.
+pub fn resolve_payload(data: &str) -> Result<String> {
+    let trimmed = data.trim();
+    let text = match trimmed.strip_prefix('@') {
+        Some(path) => read_payload_files(std::path::Path::new(path))?,
+        None => trimmed.to_string(),
+    };
+
+    let is_ur = |token: &str| {
+        token
+            .get(..3)
+            .is_some_and(|p| p.eq_ignore_ascii_case("ur:"))
+    };
+    if !text.split_whitespace().any(is_ur) {
+        return compact::expand(text.trim());
+    }
+
+    let mut decoder = UrDecoder::new();
+    let mut plain = Vec::new();
+    for token in text.split_whitespace() {
+        if is_ur(token) {
+            decoder.receive(token)?;
+        } else {
+            plain.push(token);
+        }
+    }
+
+    let mut payloads = Vec::new();
+    if !plain.is_empty() {
+        payloads.push(compact::expand(&plain.join(" "))?);
+    }
+    for message in decoder.messages()? {
+        if message.starts_with(&compact::MAGIC) {
+            payloads.push(compact::from_bytes(&message)?);
+            continue;
+        }
+        payloads.push(String::from_utf8(message).context("Scanned payload is not UTF-8")?);
+    }
+    Ok(payloads.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_payload_from_scanned_frames() {
+        let short = r#"{"type":"dkg_nonce","party_index":1}"#;
+        let long = serde_json::json!({ "pad": "x".repeat(600), "party_index": 2 }).to_string();
+
+        let single = encoder(short);
+        assert_eq!(single.seq_len(), 1);
+        let animated = encoder(&long);
+        assert!(animated.seq_len() > 1);
+
+        // Mixed-case, interleaved frames from two senders, starting mid-loop
+        let mut scans = vec![single.part(1).to_ascii_uppercase()];
+        scans.extend((3..3 + 3 * animated.seq_len() as u32).map(|n| animated.part(n)));
+        let resolved = resolve_payload(&scans.join("\n")).unwrap();
+        assert_eq!(resolved, format!("{} {}", short, long));
+
+        // Plain JSON passes through, and a partial scan says what is missing
+        assert_eq!(resolve_payload(short).unwrap(), short);
+        let err = resolve_payload(&animated.part(1)).unwrap_err();
+        assert!(err.to_string().contains("keep scanning"));
+        assert!(!render_lines(&single.part(1)).unwrap().is_empty());
+    }
+}
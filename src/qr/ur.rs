@@ -0,0 +1,312 @@
+//! Uniform Resources (BCR-2020-005)
+//!
+//! A UR is `ur:<type>/<bytewords>` for a single frame or
+//! `ur:<type>/<seq>-<len>/<bytewords>` for one part of a fountain-coded
+//! message. The body is CBOR followed by a CRC32, written as minimal
+//! bytewords (first and last letter of each word), which stays inside the
+//! QR alphanumeric set when upper-cased.
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+
+use super::fountain::{FountainDecoder, FountainEncoder, FountainPart};
+
+/// Registered UR type for an opaque byte string
+pub const UR_TYPE: &str = "bytes";
+
+/// Bytewords list: byte value `i` is `WORDS[i]`
+const WORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+/// CRC32 (IEEE 802.3), as used by bytewords and fountain checksums
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Encode bytes as minimal bytewords with the CRC32 appended
+pub fn bytewords_encode(data: &[u8]) -> String {
+    let checksum = crc32(data).to_be_bytes();
+    data.iter()
+        .chain(checksum.iter())
+        .map(|&b| {
+            let word = WORDS[b as usize].as_bytes();
+            format!("{}{}", word[0] as char, word[3] as char)
+        })
+        .collect()
+}
+
+/// Decode minimal bytewords and verify the trailing CRC32
+pub fn bytewords_decode(text: &str) -> Result<Vec<u8>> {
+    let text = text.to_ascii_lowercase();
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        bail!("Malformed bytewords: odd length or non-ASCII");
+    }
+    let mut bytes = Vec::with_capacity(text.len() / 2);
+    for pair in text.as_bytes().chunks(2) {
+        let value = WORDS
+            .iter()
+            .position(|w| w.as_bytes()[0] == pair[0] && w.as_bytes()[3] == pair[1])
+            .with_context(|| format!("Unknown byteword '{}'", String::from_utf8_lossy(pair)))?;
+        bytes.push(value as u8);
+    }
+    if bytes.len() < 5 {
+        bail!("Bytewords too short to carry a checksum");
+    }
+    let body_len = bytes.len() - 4;
+    let expected = u32::from_be_bytes(bytes[body_len..].try_into()?);
+    bytes.truncate(body_len);
+    if crc32(&bytes) != expected {
+        bail!("Bytewords checksum mismatch (frame misread?)");
+    }
+    Ok(bytes)
+}
+
+// Minimal CBOR: unsigned ints, byte strings and arrays are all URs need here
+
+pub(crate) const CBOR_UINT: u8 = 0;
+pub(crate) const CBOR_BYTES: u8 = 2;
+pub(crate) const CBOR_ARRAY: u8 = 4;
+
+pub(crate) fn cbor_header(major: u8, value: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match value {
+        0..=23 => out.push(major | value as u8),
+        24..=0xff => out.extend([major | 24, value as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((value as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(value.to_be_bytes());
+        }
+    }
+}
+
+pub(crate) fn cbor_bytes(data: &[u8], out: &mut Vec<u8>) {
+    cbor_header(CBOR_BYTES, data.len() as u64, out);
+    out.extend_from_slice(data);
+}
+
+/// Read one header of any major type, returning `(major, value)`
+pub(crate) fn cbor_read_header(data: &[u8], pos: &mut usize) -> Result<(u8, u64)> {
+    let first = *data.get(*pos).context("Truncated CBOR")?;
+    *pos += 1;
+    let width = match first & 0x1f {
+        n @ 0..=23 => return Ok((first >> 5, n as u64)),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => bail!("Unsupported CBOR length encoding"),
+    };
+    let end = pos.checked_add(width).context("Truncated CBOR")?;
+    let raw = data.get(*pos..end).context("Truncated CBOR")?;
+    *pos = end;
+    Ok((
+        first >> 5,
+        raw.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64),
+    ))
+}
+
+/// Read one header, returning its value after checking the major type
+pub(crate) fn cbor_read(data: &[u8], pos: &mut usize, major: u8) -> Result<u64> {
+    let (found, value) = cbor_read_header(data, pos)?;
+    if found != major {
+        bail!("Unexpected CBOR major type {}", found);
+    }
+    Ok(value)
+}
+
+pub(crate) fn cbor_read_bytes<'a>(data: &'a [u8], pos: &mut usize) -> Result<&'a [u8]> {
+    let len = cbor_read(data, pos, CBOR_BYTES)?;
+    // A declared length past the buffer must fail, not wrap around
+    let end = usize::try_from(len)
+        .ok()
+        .and_then(|len| pos.checked_add(len))
+        .filter(|end| *end <= data.len())
+        .context("Truncated CBOR")?;
+    let bytes = &data[*pos..end];
+    *pos = end;
+    Ok(bytes)
+}
+
+/// Produces the frames of one `ur:bytes` message
+pub struct UrEncoder {
+    fountain: FountainEncoder,
+}
+
+impl UrEncoder {
+    pub fn new(payload: &[u8], max_fragment_len: usize) -> Self {
+        let mut cbor = Vec::with_capacity(payload.len() + 9);
+        cbor_bytes(payload, &mut cbor);
+        Self {
+            fountain: FountainEncoder::new(&cbor, max_fragment_len),
+        }
+    }
+
+    /// Number of fragments; 1 means the whole message fits in one frame
+    pub fn seq_len(&self) -> usize {
+        self.fountain.seq_len()
+    }
+
+    /// Frame `seq_num` (1-based); frames past `seq_len` are fountain mixes
+    pub fn part(&self, seq_num: u32) -> String {
+        if self.seq_len() == 1 {
+            return format!(
+                "ur:{}/{}",
+                UR_TYPE,
+                bytewords_encode(self.fountain.message())
+            );
+        }
+        let part = self.fountain.part(seq_num);
+        format!(
+            "ur:{}/{}-{}/{}",
+            UR_TYPE,
+            part.seq_num,
+            part.seq_len,
+            bytewords_encode(&part.to_cbor())
+        )
+    }
+}
+
+/// Collects scanned frames, possibly from several different messages
+#[derive(Default)]
+pub struct UrDecoder {
+    singles: Vec<Vec<u8>>,
+    // Keyed by (checksum, message length) so interleaved scans separate cleanly
+    fountains: BTreeMap<(u32, usize), (usize, FountainDecoder)>,
+    order: usize,
+}
+
+impl UrDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one `ur:` string (case-insensitive)
+    pub fn receive(&mut self, ur: &str) -> Result<()> {
+        let lower = ur.trim().to_ascii_lowercase();
+        let rest = lower
+            .strip_prefix("ur:")
+            .with_context(|| format!("Not a UR: {}", ur))?;
+        let fields: Vec<&str> = rest.split('/').collect();
+        if fields[0] != UR_TYPE {
+            bail!("Unsupported UR type '{}' (expected {})", fields[0], UR_TYPE);
+        }
+        match fields[1..] {
+            [body] => {
+                let cbor = bytewords_decode(body)?;
+                self.singles.push(unwrap_cbor_bytes(&cbor)?);
+            }
+            [_seq, body] => {
+                let part = FountainPart::from_cbor(&bytewords_decode(body)?)?;
+                let key = (part.checksum, part.message_len);
+                let order = self.order;
+                let (_, decoder) = self
+                    .fountains
+                    .entry(key)
+                    .or_insert_with(|| (order, FountainDecoder::default()));
+                self.order += 1;
+                decoder.receive(&part)?;
+            }
+            _ => bail!("Malformed UR path: {}", ur),
+        }
+        Ok(())
+    }
+
+    /// (fragments recovered, fragments expected) across multi-part messages
+    pub fn progress(&self) -> (usize, usize) {
+        self.fountains
+            .values()
+            .fold((0, 0), |(done, total), (_, d)| {
+                let (p, n) = d.progress();
+                (done + p, total + n)
+            })
+    }
+
+    /// All decoded payloads, or an error naming how much is still missing
+    pub fn messages(&self) -> Result<Vec<Vec<u8>>> {
+        let mut messages = self.singles.clone();
+        let mut multi: Vec<&(usize, FountainDecoder)> = self.fountains.values().collect();
+        multi.sort_by_key(|(order, _)| *order);
+        for (_, decoder) in multi {
+            let Some(cbor) = decoder.message() else {
+                let (done, total) = decoder.progress();
+                bail!(
+                    "Incomplete animated QR: {} of {} fragments recovered, keep scanning",
+                    done,
+                    total
+                );
+            };
+            messages.push(unwrap_cbor_bytes(cbor)?);
+        }
+        Ok(messages)
+    }
+}
+
+fn unwrap_cbor_bytes(cbor: &[u8]) -> Result<Vec<u8>> {
+    let mut pos = 0;
+    let payload = cbor_read_bytes(cbor, &mut pos)?.to_vec();
+    if pos != cbor.len() {
+        bail!("Trailing data after ur:bytes payload");
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytewords_and_crc_vectors() {
+        assert_eq!(crc32(b"Hello, world!"), 0xebe6c6e6);
+        assert_eq!(crc32(b"Wolf"), 0x598c84dc);
+        assert_eq!(WORDS[128], "lava");
+        assert!(WORDS.windows(2).all(|w| w[0] < w[1]));
+        let minimal: std::collections::BTreeSet<(u8, u8)> = WORDS
+            .iter()
+            .map(|w| (w.as_bytes()[0], w.as_bytes()[3]))
+            .collect();
+        assert_eq!(minimal.len(), 256);
+
+        let data = [0u8, 1, 2, 128, 255];
+        assert_eq!(bytewords_encode(&data), "aeadaolazmjendeoti");
+        assert_eq!(bytewords_decode("AEADAOLAZMJENDEOTI").unwrap(), data);
+        assert!(bytewords_decode("aeadaolazmjendeotk").is_err());
+    }
+}
@@ -1,9 +1,14 @@
+pub mod ephemeral;
+pub mod journal;
+pub mod lock;
+pub mod migrations;
+
+use crate::crypto::secret::SecretBytes;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[cfg(test)]
 use std::collections::HashMap;
-#[cfg(test)]
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
 
 /// Storage abstraction for both file system and browser localStorage
@@ -14,15 +19,24 @@ pub trait Storage {
     fn exists(&self, key: &str) -> bool;
     /// Delete a key from storage. Used for security-critical cleanup (e.g., nonces).
     fn delete(&self, key: &str) -> Result<()>;
+
+    /// Read secret material (shares, nonces) into a buffer zeroized on drop
+    fn read_secret(&self, key: &str) -> Result<SecretBytes> {
+        self.read(key).map(SecretBytes::new)
+    }
 }
 
-/// In-memory storage for testing
-#[cfg(test)]
+/// In-memory storage for tests and multi-party simulations
 pub struct MemoryStorage {
     data: RwLock<HashMap<String, Vec<u8>>>,
 }
 
-#[cfg(test)]
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MemoryStorage {
     pub fn new() -> Self {
         Self {
@@ -31,7 +45,6 @@ impl MemoryStorage {
     }
 }
 
-#[cfg(test)]
 impl Storage for MemoryStorage {
     fn read(&self, key: &str) -> Result<Vec<u8>> {
         let data = self.data.read().unwrap();
@@ -58,6 +71,93 @@ impl Storage for MemoryStorage {
     }
 }
 
+/// Temp files, write-ahead journals and locks: never wallet state themselves
+pub fn is_scratch_file(name: &str) -> bool {
+    name == journal::JOURNAL_FILE
+        || name == lock::LOCK_FILE
+        || (name.starts_with('.') && name.ends_with(".tmp"))
+}
+
+static INSECURE_PERMISSIONS: AtomicBool = AtomicBool::new(false);
+
+/// Read secrets other users can read, with a warning (`--insecure-permissions`)
+pub fn set_insecure_permissions(allow: bool) {
+    INSECURE_PERMISSIONS.store(allow, Ordering::Relaxed);
+}
+
+/// Create `path` and any missing parents, private to the current user
+pub fn create_private_dir(path: &Path) -> Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    Ok(builder.create(path)?)
+}
+
+/// Permission bits of `path` if other users have any access to it
+///
+/// Always `None` off Unix, and for paths that don't exist.
+pub fn exposed_mode(path: &Path) -> Option<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::symlink_metadata(path).ok()?.permissions().mode() & 0o777;
+        (mode & 0o077 != 0).then_some(mode)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// Restrict `path` to the current user: 0700 for folders, 0600 for files
+pub fn restrict_permissions(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = if path.is_dir() { 0o700 } else { 0o600 };
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Write `data` to `path` without ever leaving a truncated file
+///
+/// The data goes to a temp file in the same folder, is fsynced, and is then
+/// renamed over `path`, so after a crash `path` holds either the old or the
+/// new contents. The folder is fsynced too so the rename itself survives.
+/// On Unix the file is created 0600.
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Not a file path: {}", path.display()))?;
+    let tmp = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    // A temp file left by a crash may have other permissions; start afresh
+    let _ = std::fs::remove_file(&tmp);
+    let written = options.open(&tmp).and_then(|mut file| {
+        file.write_all(data)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        std::fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
 /// File-based storage for CLI
 pub struct FileStorage {
     base_dir: PathBuf,
@@ -66,7 +166,7 @@ pub struct FileStorage {
 impl FileStorage {
     pub fn new(base_dir: &str) -> Result<Self> {
         let path = PathBuf::from(base_dir);
-        std::fs::create_dir_all(&path)?;
+        create_private_dir(&path)?;
         Ok(Self { base_dir: path })
     }
 }
@@ -79,16 +179,48 @@ impl Storage for FileStorage {
 
     fn write(&self, key: &str, data: &[u8]) -> Result<()> {
         let path = self.base_dir.join(key);
-        Ok(std::fs::write(path, data)?)
+        // Keys may name a subfolder, e.g. a weighted party's `share2/`
+        if let Some(parent) = path.parent() {
+            if !parent.exists() {
+                create_private_dir(parent)?;
+            }
+        }
+        write_atomic(&path, data)
     }
 
     fn exists(&self, key: &str) -> bool {
         self.base_dir.join(key).exists()
     }
 
+    /// Refuses files other users can read, unless `--insecure-permissions`
+    fn read_secret(&self, key: &str) -> Result<SecretBytes> {
+        let path = self.base_dir.join(key);
+        if let Some(mode) = exposed_mode(&path) {
+            if !INSECURE_PERMISSIONS.load(Ordering::Relaxed) {
+                anyhow::bail!(crate::error::Error::Storage(format!(
+                    "{} is readable by other users (mode {:o}). Run `frostdao dkg-doctor \
+                     --fix` on this wallet to make it private, or pass \
+                     --insecure-permissions to use it anyway.",
+                    path.display(),
+                    mode
+                )));
+            }
+            eprintln!(
+                "⚠️  {} is readable by other users (mode {:o})",
+                path.display(),
+                mode
+            );
+        }
+        self.read(key).map(SecretBytes::new)
+    }
+
     fn delete(&self, key: &str) -> Result<()> {
         let path = self.base_dir.join(key);
         if path.exists() {
+            // Overwrite before unlinking so deleted nonces/shares don't stay
+            // readable in the freed blocks (best effort on journaling/SSD)
+            let len = std::fs::metadata(&path)?.len() as usize;
+            std::fs::write(&path, vec![0u8; len])?;
             std::fs::remove_file(path)?;
         }
         Ok(())
@@ -159,6 +291,66 @@ impl Storage for LocalStorageImpl {
     }
 }
 
+#[wasm_bindgen::prelude::wasm_bindgen(typescript_custom_section)]
+const FROST_STORE_TS: &str = r#"
+/** Key-value store the wallet state lives in; a `Map<string, string>` works as-is. */
+export interface FrostStore {
+    get(key: string): string | null | undefined;
+    set(key: string, value: string): void;
+    delete(key: string): void;
+}
+"#;
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    /// JS object holding wallet state, passed in by the frontend
+    #[wasm_bindgen(typescript_type = "FrostStore")]
+    pub type JsStore;
+
+    #[wasm_bindgen(method, catch)]
+    fn get(this: &JsStore, key: &str) -> Result<Option<String>, wasm_bindgen::JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn set(this: &JsStore, key: &str, value: &str) -> Result<(), wasm_bindgen::JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = delete)]
+    fn remove(this: &JsStore, key: &str) -> Result<(), wasm_bindgen::JsValue>;
+}
+
+/// Storage backed by a JS `FrostStore`, so the frontend decides where state lives
+///
+/// Values are base64, like `LocalStorageImpl`.
+#[cfg(target_arch = "wasm32")]
+pub struct JsCallbackStorage<'a>(pub &'a JsStore);
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for JsCallbackStorage<'_> {
+    fn read(&self, key: &str) -> Result<Vec<u8>> {
+        let value = self
+            .0
+            .get(key)
+            .map_err(|e| anyhow::anyhow!("Store failed to read {}: {:?}", key, e))?
+            .ok_or(anyhow::anyhow!("Key not found: {}", key))?;
+        base64_decode(&value)
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.0
+            .set(key, &base64_encode(data))
+            .map_err(|e| anyhow::anyhow!("Store failed to write {}: {:?}", key, e))
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        matches!(self.0.get(key), Ok(Some(_)))
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        self.0
+            .remove(key)
+            .map_err(|e| anyhow::anyhow!("Store failed to delete {}: {:?}", key, e))
+    }
+}
+
 // Simple base64 encoding/decoding for WASM
 #[cfg(target_arch = "wasm32")]
 fn base64_encode(data: &[u8]) -> String {
@@ -171,3 +363,73 @@ fn base64_decode(s: &str) -> Result<Vec<u8>> {
     use base64::Engine;
     Ok(base64::engine::general_purpose::STANDARD.decode(s)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("frostdao_storage_{}_{}", std::process::id(), nanos))
+    }
+
+    #[test]
+    fn test_file_storage_writes_atomically() {
+        let dir = temp_dir();
+        let storage = FileStorage::new(&dir.to_string_lossy()).unwrap();
+
+        storage.write("share.bin", b"first").unwrap();
+        storage.write("share.bin", b"second").unwrap();
+        assert_eq!(storage.read("share.bin").unwrap(), b"second");
+        let names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["share.bin"]);
+
+        assert!(is_scratch_file(".share.bin.tmp"));
+        assert!(is_scratch_file(journal::JOURNAL_FILE));
+        assert!(is_scratch_file(lock::LOCK_FILE));
+        assert!(!is_scratch_file("share.bin"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_storage_keeps_secrets_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = temp_dir();
+        let dir = root.join("wallet/party1");
+        let storage = FileStorage::new(&dir.to_string_lossy()).unwrap();
+        storage.write("paired_secret_share.bin", b"share").unwrap();
+        assert_eq!(exposed_mode(&root.join("wallet")), None);
+        assert_eq!(exposed_mode(&dir), None);
+        let share = dir.join("paired_secret_share.bin");
+        assert_eq!(exposed_mode(&share), None);
+
+        std::fs::set_permissions(&share, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(exposed_mode(&share), Some(0o644));
+        let err = storage.read_secret("paired_secret_share.bin").unwrap_err();
+        assert!(
+            err.to_string().contains("readable by other users"),
+            "{}",
+            err
+        );
+        // Public reads are unaffected
+        assert_eq!(storage.read("paired_secret_share.bin").unwrap(), b"share");
+
+        restrict_permissions(&share).unwrap();
+        assert_eq!(
+            storage
+                .read_secret("paired_secret_share.bin")
+                .unwrap()
+                .expose(),
+            b"share"
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
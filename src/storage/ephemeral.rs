@@ -0,0 +1,158 @@
+//! Ephemeral State
+//!
+//! `--ephemeral` runs a command (typically the TUI, for a demo) against a
+//! fresh, empty `.frost_state` that disappears when frostdao exits, so demo
+//! wallets never end up next to real ones.
+//!
+//! Every wallet path is relative to the working directory, so the process
+//! simply moves into a private folder on the RAM-backed `/dev/shm` and the
+//! folder is shredded when the [`EphemeralState`] guard drops, or when the
+//! run is interrupted (SIGINT, SIGTERM, SIGHUP). There is no fallback to the
+//! temp folder, which is usually on disk: without a tmpfs `/dev/shm` the flag
+//! is refused. Paths given on the command line are made absolute while
+//! parsing (see [`absolute_path`]), so they still mean what they did in the
+//! directory frostdao was started in.
+
+use super::create_private_dir;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static EPHEMERAL: AtomicBool = AtomicBool::new(false);
+
+/// Where ephemeral state lives; must be a RAM-backed mount
+const RAM_DIR: &str = "/dev/shm";
+
+/// Whether this run keeps its state in an ephemeral folder
+pub fn is_ephemeral() -> bool {
+    EPHEMERAL.load(Ordering::Relaxed)
+}
+
+/// Guard for an ephemeral run; dropping it shreds the state
+pub struct EphemeralState {
+    dir: PathBuf,
+    previous_dir: PathBuf,
+}
+
+impl EphemeralState {
+    /// Move the process into a new private folder in memory
+    pub fn enter() -> Result<Self> {
+        if !is_ram_mount(RAM_DIR) {
+            bail!(crate::error::Error::User(format!(
+                "--ephemeral needs a RAM-backed {} (tmpfs), which this system does not have",
+                RAM_DIR
+            )));
+        }
+        let previous_dir = std::env::current_dir()?;
+        let dir = Path::new(RAM_DIR).join(format!(
+            "frostdao-ephemeral-{}-{:016x}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        create_private_dir(&dir)
+            .with_context(|| format!("Cannot create ephemeral folder {}", dir.display()))?;
+        #[cfg(unix)]
+        if let Err(e) = shred_on_signal(dir.clone()) {
+            shred_dir(&dir);
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+        std::env::set_current_dir(&dir)?;
+        EPHEMERAL.store(true, Ordering::Relaxed);
+        Ok(Self { dir, previous_dir })
+    }
+
+    /// Where the state lives until the guard drops
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for EphemeralState {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous_dir);
+        shred_dir(&self.dir);
+        let _ = std::fs::remove_dir_all(&self.dir);
+        EPHEMERAL.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Whether `dir` is a tmpfs or ramfs mount point (per `/proc/self/mounts`)
+fn is_ram_mount(dir: &str) -> bool {
+    let Ok(mounts) = std::fs::read_to_string("/proc/self/mounts") else {
+        return false;
+    };
+    mounts.lines().any(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        fields.get(1) == Some(&dir) && matches!(fields.get(2), Some(&"tmpfs") | Some(&"ramfs"))
+    })
+}
+
+/// Shred `dir` and exit if the process is told to stop
+///
+/// The handler itself only records the signal; the shredding happens on a
+/// watcher thread, where file I/O is allowed.
+#[cfg(unix)]
+fn shred_on_signal(dir: PathBuf) -> Result<()> {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+    let mut signals = signal_hook::iterator::Signals::new([SIGINT, SIGTERM, SIGHUP])
+        .context("Cannot watch for signals to clean up ephemeral state")?;
+    std::thread::spawn(move || {
+        if let Some(signal) = signals.forever().next() {
+            shred_dir(&dir);
+            let _ = std::fs::remove_dir_all(&dir);
+            std::process::exit(128 + signal);
+        }
+    });
+    Ok(())
+}
+
+/// Clap value parser for path options: relative paths are made absolute
+/// against the directory frostdao was started in, before `--ephemeral`
+/// moves the process elsewhere. `-` (stdin) is kept as is.
+pub fn absolute_path(value: &str) -> std::result::Result<PathBuf, String> {
+    let path = PathBuf::from(value);
+    if value == "-" || path.is_absolute() {
+        return Ok(path);
+    }
+    std::path::absolute(&path).map_err(|e| format!("Cannot resolve {}: {}", value, e))
+}
+
+/// Overwrite every file under `dir` with zeros (best effort)
+fn shred_dir(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            shred_dir(&path);
+        } else if let Ok(meta) = entry.metadata() {
+            let _ = std::fs::write(&path, vec![0u8; meta.len() as usize]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_path_resolves_against_start_dir() {
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            absolute_path("round1.json").unwrap(),
+            cwd.join("round1.json")
+        );
+        assert_eq!(
+            absolute_path("/tmp/round1.json").unwrap(),
+            PathBuf::from("/tmp/round1.json")
+        );
+        assert_eq!(absolute_path("-").unwrap(), PathBuf::from("-"));
+    }
+
+    #[test]
+    fn test_only_ram_mounts_count() {
+        assert!(!is_ram_mount("/no/such/mount"));
+    }
+}
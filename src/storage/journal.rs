@@ -0,0 +1,215 @@
+//! Write-Ahead Journal
+//!
+//! Finalize steps write a share, the group key and metadata that only make
+//! sense together. Run through [`Journaled`], those writes are held back
+//! until [`Journaled::commit`], which first records all of them in
+//! `.journal.json` (one atomic write), then applies them and shreds the
+//! journal. A crash before the journal lands leaves the folder as it was; a
+//! crash after it leaves a journal that [`replay`] (`dkg-doctor --fix`)
+//! finishes applying.
+
+use super::Storage;
+use crate::crypto::secret::{to_json_secret, SecretBytes, SecretString};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use zeroize::Zeroize;
+
+pub const JOURNAL_FILE: &str = ".journal.json";
+
+/// An operation's writes, as recorded ahead of applying them
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JournalEntry {
+    /// Step that wrote the journal, e.g. `keygen-finalize`
+    pub operation: String,
+    /// New contents (hex) per key; `None` deletes the key
+    pub writes: BTreeMap<String, Option<String>>,
+}
+
+impl Drop for JournalEntry {
+    fn drop(&mut self) {
+        for data in self.writes.values_mut().flatten() {
+            data.zeroize();
+        }
+    }
+}
+
+impl JournalEntry {
+    /// Keys this entry writes or deletes
+    pub fn keys(&self) -> Vec<&str> {
+        self.writes.keys().map(String::as_str).collect()
+    }
+
+    fn apply(&self, storage: &dyn Storage) -> Result<()> {
+        for (key, data) in &self.writes {
+            match data {
+                Some(data) => {
+                    let bytes = SecretBytes::new(
+                        hex::decode(data)
+                            .with_context(|| format!("{} is damaged", JOURNAL_FILE))?,
+                    );
+                    storage.write(key, &bytes)?;
+                }
+                None => storage.delete(key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Storage whose writes and deletes wait for [`Journaled::commit`]
+///
+/// Reads see the pending writes. Dropping it without committing discards
+/// them, so a step that fails halfway writes nothing.
+pub struct Journaled<'a> {
+    inner: &'a dyn Storage,
+    operation: String,
+    pending: RwLock<BTreeMap<String, Option<SecretBytes>>>,
+}
+
+impl<'a> Journaled<'a> {
+    pub fn new(inner: &'a dyn Storage, operation: &str) -> Self {
+        Self {
+            inner,
+            operation: operation.to_string(),
+            pending: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Journal the pending writes, apply them, then shred the journal
+    pub fn commit(self) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending.write().unwrap());
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let entry = JournalEntry {
+            operation: self.operation.clone(),
+            writes: pending
+                .iter()
+                .map(|(key, data)| (key.clone(), data.as_ref().map(|d| hex::encode(d.expose()))))
+                .collect(),
+        };
+        self.inner
+            .write(JOURNAL_FILE, to_json_secret(&entry)?.as_bytes())?;
+        entry.apply(self.inner)?;
+        self.inner.delete(JOURNAL_FILE)
+    }
+}
+
+impl Storage for Journaled<'_> {
+    fn read(&self, key: &str) -> Result<Vec<u8>> {
+        match self.pending.read().unwrap().get(key) {
+            Some(Some(data)) => Ok(data.expose().to_vec()),
+            Some(None) => Err(anyhow::anyhow!("Key not found: {}", key)),
+            None => self.inner.read(key),
+        }
+    }
+
+    fn write(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.pending
+            .write()
+            .unwrap()
+            .insert(key.to_string(), Some(SecretBytes::new(data.to_vec())));
+        Ok(())
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        match self.pending.read().unwrap().get(key) {
+            Some(data) => data.is_some(),
+            None => self.inner.exists(key),
+        }
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        self.pending.write().unwrap().insert(key.to_string(), None);
+        Ok(())
+    }
+}
+
+/// The journal left in `storage` by an interrupted commit, if any
+pub fn pending(storage: &dyn Storage) -> Result<Option<JournalEntry>> {
+    if !storage.exists(JOURNAL_FILE) {
+        return Ok(None);
+    }
+    let json = SecretString::from_utf8(storage.read_secret(JOURNAL_FILE)?)?;
+    let entry =
+        serde_json::from_str(&json).with_context(|| format!("{} is damaged", JOURNAL_FILE))?;
+    Ok(Some(entry))
+}
+
+/// Finish an interrupted commit; returns the operation it belonged to
+pub fn replay(storage: &dyn Storage) -> Result<Option<String>> {
+    let Some(entry) = pending(storage)? else {
+        return Ok(None);
+    };
+    entry.apply(storage)?;
+    storage.delete(JOURNAL_FILE)?;
+    Ok(Some(entry.operation.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_commit_applies_everything_or_nothing() {
+        let storage = MemoryStorage::new();
+        storage.write("old.json", b"old").unwrap();
+
+        let journaled = Journaled::new(&storage, "test");
+        journaled.write("share.bin", b"share").unwrap();
+        journaled.delete("old.json").unwrap();
+        assert_eq!(journaled.read("share.bin").unwrap(), b"share");
+        assert!(!journaled.exists("old.json"));
+        // Nothing reaches the folder before commit, and dropping discards
+        assert!(!storage.exists("share.bin"));
+        drop(journaled);
+        assert!(storage.exists("old.json"));
+
+        let journaled = Journaled::new(&storage, "test");
+        journaled.write("share.bin", b"share").unwrap();
+        journaled.delete("old.json").unwrap();
+        journaled.commit().unwrap();
+        assert_eq!(storage.read("share.bin").unwrap(), b"share");
+        assert!(!storage.exists("old.json"));
+        assert!(!storage.exists(JOURNAL_FILE));
+    }
+
+    #[test]
+    fn test_replay_after_crash() {
+        let storage = MemoryStorage::new();
+        assert_eq!(replay(&storage).unwrap(), None);
+
+        // Crash right after the journal was written
+        let entry = JournalEntry {
+            operation: "keygen-finalize".to_string(),
+            writes: [
+                ("share.bin".to_string(), Some(hex::encode(b"share"))),
+                ("stale.json".to_string(), None),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        storage
+            .write(JOURNAL_FILE, &serde_json::to_vec(&entry).unwrap())
+            .unwrap();
+        storage.write("stale.json", b"{}").unwrap();
+        assert_eq!(
+            pending(&storage).unwrap().unwrap().keys(),
+            vec!["share.bin", "stale.json"]
+        );
+
+        assert_eq!(
+            replay(&storage).unwrap().as_deref(),
+            Some("keygen-finalize")
+        );
+        assert_eq!(storage.read("share.bin").unwrap(), b"share");
+        assert!(!storage.exists("stale.json"));
+        assert!(!storage.exists(JOURNAL_FILE));
+
+        storage.write(JOURNAL_FILE, b"{").unwrap();
+        assert!(replay(&storage).is_err());
+    }
+}
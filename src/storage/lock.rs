@@ -0,0 +1,416 @@
+//! Wallet Locks
+//!
+//! Two frostdao processes working on one wallet (two terminals, or the TUI
+//! and the CLI) could interleave their writes, e.g. both consume the same
+//! nonce file. A command that changes a wallet first takes its advisory
+//! lock, a `.lock` file in the wallet folder naming the holder:
+//!
+//! ```text
+//! {"pid": 4242, "host": "laptop", "command": "dkg-sign", "started_at": 1760000000, "token": "…"}
+//! ```
+//!
+//! The file is published with a hard link, so it appears complete or not
+//! at all, and removed when the [`WalletLock`] guard drops. A lock whose
+//! process is gone (crash, kill -9) is stale and taken over: it is renamed
+//! away first, so two processes can't both clear it and then both lock. A
+//! live one makes the next command fail straight away, or wait for it
+//! (`--wait`). Only a lock taken on this machine can be judged stale: a pid
+//! from another host (a synced or network folder) says nothing here, so
+//! such a lock stands until `--force-unlock` removes it.
+//!
+//! A command that creates a wallet locks it too: [`WalletLock::create`]
+//! makes the folder first and removes it again if nothing was written.
+
+use crate::error::Error;
+use crate::protocol::health::now;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub const LOCK_FILE: &str = ".lock";
+
+/// How often a waiting command checks the lock again
+const POLL: Duration = Duration::from_millis(200);
+
+/// Who holds a wallet lock
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// Machine the process runs on; empty in locks from older versions
+    #[serde(default)]
+    pub host: String,
+    /// Subcommand (or `tui`) that took the lock
+    pub command: String,
+    pub started_at: u64,
+    /// Tells our lock apart from a later one by a process with the same pid
+    pub token: String,
+}
+
+impl LockHolder {
+    fn describe(&self) -> String {
+        let host = if self.is_local() {
+            String::new()
+        } else {
+            format!(" on {}", self.host)
+        };
+        format!(
+            "`{}` (pid {}{}, running for {}s)",
+            self.command,
+            self.pid,
+            host,
+            now().saturating_sub(self.started_at)
+        )
+    }
+
+    /// Whether the lock was taken on this machine
+    pub fn is_local(&self) -> bool {
+        self.host.is_empty() || self.host == host_name()
+    }
+
+    /// Whether the holding process is known to have exited; never for a
+    /// lock taken on another machine, whose pids can't be checked from here
+    pub fn is_stale(&self) -> bool {
+        self.is_local() && process_alive(self.pid) == Some(false)
+    }
+}
+
+/// A held wallet lock; dropping it releases the wallet
+#[derive(Debug)]
+pub struct WalletLock {
+    path: PathBuf,
+    token: String,
+    /// The folder was made for this lock and goes again if left empty
+    created: bool,
+    /// A stale lock that was taken over to get this one
+    pub replaced: Option<LockHolder>,
+}
+
+impl WalletLock {
+    /// Lock the wallet in `wallet_dir` for `command`, waiting up to `wait`
+    /// for another process to finish with it
+    pub fn acquire(wallet_dir: &Path, command: &str, wait: Duration) -> Result<Self> {
+        Self::acquire_in(wallet_dir, command, wait, false)
+    }
+
+    /// Like [`WalletLock::acquire`] for a wallet that may not exist yet: its
+    /// folder is created, and removed on release if the command left it empty
+    pub fn create(wallet_dir: &Path, command: &str, wait: Duration) -> Result<Self> {
+        Self::acquire_in(wallet_dir, command, wait, true)
+    }
+
+    fn acquire_in(wallet_dir: &Path, command: &str, wait: Duration, create: bool) -> Result<Self> {
+        let created = create && !wallet_dir.exists();
+        if create {
+            std::fs::create_dir_all(wallet_dir)?;
+        }
+        let path = wallet_dir.join(LOCK_FILE);
+        let me = LockHolder {
+            pid: std::process::id(),
+            host: host_name().to_string(),
+            command: command.to_string(),
+            started_at: now(),
+            token: format!("{:016x}", rand::random::<u64>()),
+        };
+        let deadline = Instant::now() + wait;
+        let mut replaced = None;
+        loop {
+            if publish(&path, &me)? {
+                return Ok(Self {
+                    path,
+                    token: me.token,
+                    created,
+                    replaced,
+                });
+            }
+            let Some(holder) = holder(&path) else {
+                continue; // released (or unreadable and now gone) meanwhile
+            };
+            if holder.is_stale() {
+                if take_stale(&path, &holder, &me.token)? {
+                    replaced = Some(holder);
+                }
+                continue;
+            }
+            if Instant::now() >= deadline {
+                let waited = if wait.is_zero() {
+                    String::new()
+                } else {
+                    format!(" after waiting {}s", wait.as_secs())
+                };
+                bail!(Error::Storage(format!(
+                    "Wallet {} is in use by {}{}. Let it finish, rerun with --wait \
+                     to queue behind it, or --force-unlock if that process is gone",
+                    wallet_dir.display(),
+                    holder.describe(),
+                    waited
+                )));
+            }
+            std::thread::sleep(POLL);
+        }
+    }
+}
+
+impl WalletLock {
+    /// The wallet folder this lock holds
+    pub fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+}
+
+impl Drop for WalletLock {
+    fn drop(&mut self) {
+        // Leave the file alone if it was forced away and taken by someone else
+        if holder(&self.path).is_some_and(|h| h.token == self.token) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+        // Fails, as it should, once the command has written anything
+        if let (true, Some(dir)) = (self.created, self.path.parent()) {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Whether `name` is a lock file (or one being published or cleared)
+pub fn is_lock_file(name: &std::ffi::OsStr) -> bool {
+    name.to_str()
+        .is_some_and(|name| name.starts_with(LOCK_FILE))
+}
+
+/// Whether `dir` is missing or holds nothing but its lock
+pub fn is_unused(dir: &Path) -> bool {
+    match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .all(|entry| is_lock_file(&entry.file_name())),
+        Err(_) => true,
+    }
+}
+
+/// Delete everything in `dir` except its lock, for a command that replaces
+/// a wallet while holding that wallet's lock
+pub fn clear_dir(dir: &Path) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if is_lock_file(&entry.file_name()) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            std::fs::remove_dir_all(entry.path())?;
+        } else {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Current holder of the wallet lock in `wallet_dir`, if any
+pub fn holder_of(wallet_dir: &Path) -> Option<LockHolder> {
+    holder(&wallet_dir.join(LOCK_FILE))
+}
+
+/// Remove the wallet lock in `wallet_dir` whoever holds it (`--force-unlock`);
+/// returns the holder it removed
+pub fn force_unlock(wallet_dir: &Path) -> Result<Option<LockHolder>> {
+    let path = wallet_dir.join(LOCK_FILE);
+    let holder = holder(&path);
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(holder),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn holder(path: &Path) -> Option<LockHolder> {
+    let bytes = std::fs::read(path).ok()?;
+    // An unreadable lock still locks; name it so it can be forced away
+    Some(
+        serde_json::from_slice(&bytes).unwrap_or_else(|_| LockHolder {
+            pid: 0,
+            host: String::new(),
+            command: "unknown".to_string(),
+            started_at: 0,
+            token: String::new(),
+        }),
+    )
+}
+
+/// Clear the stale lock `seen` at `path`; `false` if it had already changed
+///
+/// The lock is renamed to a name of our own, which only one process can do,
+/// and checked there. A newer lock caught by the rename is put back.
+fn take_stale(path: &Path, seen: &LockHolder, token: &str) -> Result<bool> {
+    let aside = path.with_file_name(format!("{}.{}.stale", LOCK_FILE, token));
+    match std::fs::rename(path, &aside) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    }
+    let taken = holder(&aside).as_ref() == Some(seen);
+    if !taken {
+        // Restore it unless someone has locked since; then theirs stands
+        match std::fs::hard_link(&aside, path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => {
+                let _ = std::fs::remove_file(&aside);
+                return Err(e.into());
+            }
+        }
+    }
+    let _ = std::fs::remove_file(&aside);
+    Ok(taken)
+}
+
+/// Write the lock beside `path`, then link it into place; `false` if taken
+fn publish(path: &Path, me: &LockHolder) -> Result<bool> {
+    let tmp = path.with_file_name(format!("{}.{}.tmp", LOCK_FILE, me.token));
+    std::fs::write(&tmp, serde_json::to_vec(me)?)?;
+    let linked = std::fs::hard_link(&tmp, path);
+    let _ = std::fs::remove_file(&tmp);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// `None` when this platform cannot tell
+#[cfg(unix)]
+fn process_alive(pid: u32) -> Option<bool> {
+    if pid == 0 {
+        return None;
+    }
+    if Path::new("/proc/self").exists() {
+        return Some(Path::new(&format!("/proc/{}", pid)).exists());
+    }
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .ok()
+        .map(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> Option<bool> {
+    None
+}
+
+/// This machine's name, as recorded in the locks it takes
+fn host_name() -> &'static str {
+    static HOST: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    HOST.get_or_init(|| gethostname::gethostname().to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_lock_excludes_and_recovers() {
+        let dir = std::env::temp_dir().join(format!(
+            "frostdao_lock_{}_{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let lock = WalletLock::acquire(&dir, "dkg-sign", Duration::ZERO).unwrap();
+        assert_eq!(holder_of(&dir).unwrap().command, "dkg-sign");
+        let err = WalletLock::acquire(&dir, "dkg-nonce", Duration::from_millis(300)).unwrap_err();
+        assert!(err.to_string().contains("in use by `dkg-sign`"), "{}", err);
+        assert_eq!(
+            crate::error::kind_of(&err),
+            crate::error::ErrorKind::Storage
+        );
+        drop(lock);
+        assert!(holder_of(&dir).is_none());
+
+        // A lock left by a process that no longer exists is taken over
+        let dead = LockHolder {
+            pid: u32::MAX - 1,
+            host: host_name().to_string(),
+            command: "dkg-sign".to_string(),
+            started_at: 0,
+            token: "dead".to_string(),
+        };
+        std::fs::write(dir.join(LOCK_FILE), serde_json::to_vec(&dead).unwrap()).unwrap();
+        let lock = WalletLock::acquire(&dir, "dkg-nonce", Duration::ZERO).unwrap();
+        assert_eq!(lock.replaced, Some(dead.clone()));
+        assert_eq!(lock.dir(), dir.as_path());
+        assert_eq!(holder_of(&dir).unwrap().host, host_name());
+        drop(lock);
+
+        // ...but not one from another machine: its pid means nothing here
+        let remote = LockHolder {
+            host: format!("{}-elsewhere", host_name()),
+            ..dead.clone()
+        };
+        assert!(!remote.is_stale());
+        std::fs::write(dir.join(LOCK_FILE), serde_json::to_vec(&remote).unwrap()).unwrap();
+        let err = WalletLock::acquire(&dir, "dkg-nonce", Duration::ZERO).unwrap_err();
+        assert!(err.to_string().contains("-elsewhere"), "{}", err);
+        assert_eq!(holder_of(&dir), Some(remote));
+        force_unlock(&dir).unwrap();
+        let lock = WalletLock::acquire(&dir, "dkg-nonce", Duration::ZERO).unwrap();
+
+        // Forcing a live lock away; its holder then leaves the new one alone
+        assert_eq!(force_unlock(&dir).unwrap().unwrap().command, "dkg-nonce");
+        let other = WalletLock::acquire(&dir, "tui", Duration::ZERO).unwrap();
+        drop(lock);
+        assert_eq!(holder_of(&dir).unwrap().command, "tui");
+        drop(other);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_creating_lock_and_clearing_keep_the_lock() {
+        let dir = std::env::temp_dir().join(format!(
+            "frostdao_lock_new_{}_{}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+
+        // A creator that writes nothing leaves no folder behind
+        let lock = WalletLock::create(&dir, "keygen-round1", Duration::ZERO).unwrap();
+        assert!(is_unused(&dir));
+        assert!(WalletLock::create(&dir, "keygen-round1", Duration::ZERO).is_err());
+        drop(lock);
+        assert!(!dir.exists());
+
+        // One that writes keeps it; clearing spares only the lock
+        let lock = WalletLock::create(&dir, "reshare-finalize", Duration::ZERO).unwrap();
+        std::fs::create_dir_all(dir.join("party2")).unwrap();
+        std::fs::write(dir.join("shared_key.bin"), b"key").unwrap();
+        assert!(!is_unused(&dir));
+        clear_dir(&dir).unwrap();
+        assert!(is_unused(&dir));
+        assert_eq!(holder_of(&dir).unwrap().command, "reshare-finalize");
+        std::fs::write(dir.join("shared_key.bin"), b"new").unwrap();
+        drop(lock);
+        assert!(dir.join("shared_key.bin").exists());
+
+        // A stale lock is only cleared if it is still the one judged stale
+        let dead = LockHolder {
+            pid: u32::MAX - 1,
+            host: host_name().to_string(),
+            command: "dkg-sign".to_string(),
+            started_at: 0,
+            token: "dead".to_string(),
+        };
+        let path = dir.join(LOCK_FILE);
+        std::fs::write(&path, serde_json::to_vec(&dead).unwrap()).unwrap();
+        let newer = LockHolder {
+            token: "newer".to_string(),
+            ..dead.clone()
+        };
+        assert!(!take_stale(&path, &newer, "me").unwrap());
+        assert_eq!(holder(&path), Some(dead.clone()));
+        assert!(take_stale(&path, &dead, "me").unwrap());
+        assert!(holder(&path).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
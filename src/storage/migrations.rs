@@ -0,0 +1,221 @@
+//! Wallet State Versioning
+//!
+//! `htss_metadata.json` carries a `wallet_version`. Files written before
+//! versioning have none and count as version 0. [`migrate`] upgrades a wallet
+//! folder one step at a time to [`CURRENT_WALLET_VERSION`], and [`check`]
+//! refuses a wallet written by a newer frostdao instead of misreading it.
+//!
+//! To change the on-disk format, bump `CURRENT_WALLET_VERSION` and append a
+//! [`Migration`] from the previous version to [`MIGRATIONS`].
+
+use super::Storage;
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// Format written by this build
+pub const CURRENT_WALLET_VERSION: u32 = 1;
+
+/// The file holding `wallet_version`
+pub const METADATA_FILE: &str = "htss_metadata.json";
+
+/// One upgrade step, from `from` to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: fn(&dyn Storage) -> Result<()>,
+}
+
+/// Every upgrade step, oldest first
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "write the HD address count explicitly in hd_metadata.json",
+    apply: explicit_derived_count,
+}];
+
+/// Version of the wallet in `storage`; `None` while keygen is in progress
+pub fn wallet_version(storage: &dyn Storage) -> Result<Option<u32>> {
+    if !storage.exists(METADATA_FILE) {
+        return Ok(None);
+    }
+    let metadata = read_json(storage, METADATA_FILE)?;
+    let version = metadata
+        .get("wallet_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    Ok(Some(u32::try_from(version).unwrap_or(u32::MAX)))
+}
+
+/// Fail if the wallet was written by a newer frostdao
+pub fn check(storage: &dyn Storage) -> Result<()> {
+    match wallet_version(storage)? {
+        Some(version) => ensure_supported(version, CURRENT_WALLET_VERSION),
+        None => Ok(()),
+    }
+}
+
+fn ensure_supported(version: u32, current: u32) -> Result<()> {
+    if version > current {
+        anyhow::bail!(
+            "This wallet uses format version {}, but this frostdao only understands \
+             version {} and older. Upgrade frostdao to open it; the wallet was left untouched.",
+            version,
+            current
+        );
+    }
+    Ok(())
+}
+
+/// Upgrade the wallet in `storage` to [`CURRENT_WALLET_VERSION`]
+///
+/// Returns the descriptions of the steps applied, empty if the wallet was
+/// already current (or has no metadata yet).
+pub fn migrate(storage: &dyn Storage) -> Result<Vec<&'static str>> {
+    migrate_with(storage, MIGRATIONS, CURRENT_WALLET_VERSION)
+}
+
+fn migrate_with(
+    storage: &dyn Storage,
+    migrations: &[Migration],
+    current: u32,
+) -> Result<Vec<&'static str>> {
+    let Some(mut version) = wallet_version(storage)? else {
+        return Ok(Vec::new());
+    };
+    ensure_supported(version, current)?;
+
+    let mut applied = Vec::new();
+    while version < current {
+        let step = migrations
+            .iter()
+            .find(|m| m.from == version)
+            .ok_or_else(|| anyhow::anyhow!("No migration from wallet version {}", version))?;
+        (step.apply)(storage).with_context(|| {
+            format!(
+                "Upgrading wallet from version {} failed ({})",
+                version, step.description
+            )
+        })?;
+        version += 1;
+        // Stamped per step, so an interrupted upgrade resumes where it stopped
+        set_version(storage, version)?;
+        applied.push(step.description);
+    }
+    Ok(applied)
+}
+
+fn read_json(storage: &dyn Storage, file: &str) -> Result<Value> {
+    serde_json::from_slice(&storage.read(file)?).with_context(|| format!("{} is damaged", file))
+}
+
+fn set_version(storage: &dyn Storage, version: u32) -> Result<()> {
+    let mut metadata = read_json(storage, METADATA_FILE)?;
+    let object = metadata
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("{} is not a JSON object", METADATA_FILE))?;
+    object.insert("wallet_version".to_string(), version.into());
+    storage.write(
+        METADATA_FILE,
+        serde_json::to_string_pretty(&metadata)?.as_bytes(),
+    )
+}
+
+/// v0 -> v1: `derived_count` was optional and defaulted to 5
+fn explicit_derived_count(storage: &dyn Storage) -> Result<()> {
+    const HD_FILE: &str = "hd_metadata.json";
+    if !storage.exists(HD_FILE) {
+        return Ok(());
+    }
+    let mut hd = read_json(storage, HD_FILE)?;
+    if let Some(object) = hd.as_object_mut() {
+        if !object.contains_key("derived_count") {
+            object.insert("derived_count".to_string(), 5.into());
+            storage.write(HD_FILE, serde_json::to_string_pretty(&hd)?.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn wallet(metadata: Value) -> MemoryStorage {
+        let storage = MemoryStorage::new();
+        storage
+            .write(METADATA_FILE, metadata.to_string().as_bytes())
+            .unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_migrate_unversioned_wallet() {
+        let storage = wallet(serde_json::json!({"my_index": 1, "threshold": 2}));
+        storage
+            .write(
+                "hd_metadata.json",
+                br#"{"chain_code":"00","hd_enabled":true}"#,
+            )
+            .unwrap();
+        assert_eq!(wallet_version(&storage).unwrap(), Some(0));
+
+        let applied = migrate(&storage).unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(
+            wallet_version(&storage).unwrap(),
+            Some(CURRENT_WALLET_VERSION)
+        );
+        let hd = read_json(&storage, "hd_metadata.json").unwrap();
+        assert_eq!(hd["derived_count"], 5);
+        // Other metadata survives the stamp
+        assert_eq!(read_json(&storage, METADATA_FILE).unwrap()["threshold"], 2);
+
+        assert!(migrate(&storage).unwrap().is_empty());
+        assert!(migrate(&MemoryStorage::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_newer_wallet_refused() {
+        let storage = wallet(serde_json::json!({"wallet_version": CURRENT_WALLET_VERSION + 1}));
+        let err = check(&storage).unwrap_err();
+        assert!(err.to_string().contains("Upgrade frostdao"), "{}", err);
+        assert!(migrate(&storage).is_err());
+        assert_eq!(
+            wallet_version(&storage).unwrap(),
+            Some(CURRENT_WALLET_VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn test_steps_run_in_order_and_stop_on_failure() {
+        fn ok(storage: &dyn Storage) -> Result<()> {
+            let version = wallet_version(storage)?.unwrap();
+            storage.write(&format!("step{}", version), b"")
+        }
+        fn fail(_: &dyn Storage) -> Result<()> {
+            anyhow::bail!("disk full")
+        }
+        let steps = [
+            Migration {
+                from: 0,
+                description: "first",
+                apply: ok,
+            },
+            Migration {
+                from: 1,
+                description: "second",
+                apply: fail,
+            },
+        ];
+
+        let storage = wallet(serde_json::json!({}));
+        let err = migrate_with(&storage, &steps, 2).unwrap_err();
+        assert!(format!("{:#}", err).contains("disk full"));
+        assert!(storage.exists("step0"));
+        assert_eq!(wallet_version(&storage).unwrap(), Some(1));
+
+        // A gap in the chain is an error, not a silent skip
+        let storage = wallet(serde_json::json!({}));
+        assert!(migrate_with(&storage, &steps[1..], 2).is_err());
+    }
+}
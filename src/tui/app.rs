@@ -3,18 +3,215 @@
 use anyhow::Result;
 use bitcoin::{Address, XOnlyPublicKey};
 use ratatui::widgets::ListState;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use crate::tui::components::{OutputViewer, TextArea, TextInput};
+use crate::tui::keymap::KeyMap;
+use crate::tui::mouse::MouseMap;
 use crate::tui::screens::{KeygenFormData, ReshareFormData, SendFormData};
 use crate::tui::state::{AppState, NetworkSelection};
-use frostdao::protocol::keygen::{list_wallets, WalletSummary};
+use crate::tui::wallet_view::{HomeRow, HomeView, SortOrder};
+use frostdao::btc::monitor::{self, TxWatchUpdate, WatchState};
+use frostdao::btc::rebroadcast::{self, TxCheck, TxFate};
+use frostdao::btc::script_index::ScriptIndex;
+use frostdao::btc::{esplora, fiat, http, simulate, transaction};
+use frostdao::notify;
+use frostdao::protocol::events::ProtocolEvent;
+use frostdao::protocol::keygen::{get_state_dir, list_wallets, WalletSummary};
+use frostdao::protocol::reporter::Reporter;
+use frostdao::protocol::wallet::STATE_ROOT;
+use frostdao::storage::lock::WalletLock;
 use frostdao::storage::{FileStorage, Storage};
 
+/// Last-known balances, kept across runs for offline use
+const BALANCE_CACHE_FILE: &str = "balance_cache.json";
+
+/// Cached balances older than this are marked stale even when online
+const STALE_AFTER_SECS: u64 = 3600;
+
+/// How often to re-check the backend while offline
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default interval of the background balance refresher (`tui --refresh-secs`)
+pub const DEFAULT_REFRESH_SECS: u64 = 300;
+
+/// Pause between requests of a background refresh, to stay under the
+/// backend's rate limits
+const REFRESH_REQUEST_GAP: Duration = Duration::from_millis(1500);
+
+/// A background refresh result for one wallet
+struct BalanceUpdate {
+    wallet_name: String,
+    network: NetworkSelection,
+    result: std::result::Result<BalanceInfo, String>,
+    /// The backend couldn't be reached; the rest of the sweep was dropped
+    unreachable: bool,
+}
+
+/// Result of a background check of every wallet's pending transactions
+struct RebroadcastUpdate {
+    /// Wallet name and what was found for each of its transactions
+    checks: Vec<(String, TxCheck)>,
+    /// The backend couldn't be reached; the rest of the check was dropped
+    unreachable: bool,
+}
+
+/// Status line for what a background rebroadcast found, if worth one
+fn rebroadcast_message(checks: &[(String, TxCheck)]) -> Option<String> {
+    let short = |txid: &str| txid[..txid.len().min(8)].to_string();
+    let changed = checks.iter().find(|(_, check)| {
+        check.state != check.previous && matches!(check.state, TxFate::Evicted | TxFate::Conflicted)
+    });
+    if let Some((wallet, check)) = changed {
+        return Some(match check.state {
+            TxFate::Conflicted => format!(
+                "✗ {}: transaction {}... was replaced by {}...",
+                wallet,
+                short(&check.txid),
+                short(check.replaced_by.as_deref().unwrap_or("?"))
+            ),
+            _ if check.rebroadcast_accepted() => format!(
+                "⚠ {}: transaction {}... fell out of the mempool; rebroadcast it",
+                wallet,
+                short(&check.txid)
+            ),
+            _ => format!(
+                "⚠ {}: transaction {}... fell out of the mempool and was rejected again",
+                wallet,
+                short(&check.txid)
+            ),
+        });
+    }
+    let stuck = checks.iter().filter(|(_, check)| check.stuck).count();
+    (stuck > 0).then(|| {
+        format!(
+            "⏰ {} transaction(s) unconfirmed for over a day; the fee may be too low",
+            stuck
+        )
+    })
+}
+
 /// Balance information for a wallet
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BalanceInfo {
     pub balance_sats: u64,
     pub utxo_count: usize,
+    /// Unix time of the fetch
+    #[serde(default)]
+    pub fetched_at: u64,
+}
+
+impl BalanceInfo {
+    /// Age of the cached value, e.g. "5m ago"
+    pub fn age(&self) -> String {
+        let secs = unix_now().saturating_sub(self.fetched_at);
+        match secs {
+            0..=4 => "just now".to_string(),
+            5..=59 => format!("{}s ago", secs),
+            60..=3599 => format!("{}m ago", secs / 60),
+            3600..=86399 => format!("{}h ago", secs / 3600),
+            _ => format!("{}d ago", secs / 86400),
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        unix_now().saturating_sub(self.fetched_at) >= STALE_AFTER_SECS
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load_balance_cache() -> HashMap<String, BalanceInfo> {
+    FileStorage::new(STATE_ROOT)
+        .ok()
+        .filter(|storage| storage.exists(BALANCE_CACHE_FILE))
+        .and_then(|storage| storage.read(BALANCE_CACHE_FILE).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Fetch the balance of a wallet's address on `network`
+fn fetch_balance(network: NetworkSelection, wallet_name: &str) -> Result<BalanceInfo> {
+    let state_dir = frostdao::protocol::keygen::get_state_dir(wallet_name);
+    let storage = FileStorage::new(&state_dir)?;
+    frostdao::protocol::keygen::require_secp256k1(&storage)?;
+
+    // Load shared key
+    let shared_key_bytes = storage.read("shared_key.bin")?;
+    let shared_key: schnorr_fun::frost::SharedKey<schnorr_fun::fun::marker::EvenY> =
+        bincode::deserialize(&shared_key_bytes)?;
+
+    let pubkey_bytes: [u8; 32] = shared_key.public_key().to_xonly_bytes();
+    let xonly_pubkey = XOnlyPublicKey::from_slice(&pubkey_bytes)?;
+
+    let secp = bitcoin::secp256k1::Secp256k1::new();
+    let btc_network = network.to_bitcoin_network();
+    let address = Address::p2tr(&secp, xonly_pubkey, None, btc_network).to_string();
+
+    if !network.has_esplora() {
+        let utxos = transaction::fetch_utxos(&address, btc_network)?;
+        return Ok(BalanceInfo {
+            balance_sats: utxos.iter().map(|u| u.value).sum(),
+            utxo_count: utxos.len(),
+            fetched_at: unix_now(),
+        });
+    }
+
+    // Fetch UTXOs from the Esplora backend
+    let client = http::client();
+    let endpoint = network.esplora()?;
+    let response = http::send(
+        &client,
+        endpoint.get(&client, &format!("/address/{}/utxo", address)),
+    )?;
+    let utxos: Vec<serde_json::Value> = response.json()?;
+
+    let balance_sats: u64 = utxos
+        .iter()
+        .filter_map(|u| u.get("value").and_then(|v| v.as_u64()))
+        .sum();
+
+    Ok(BalanceInfo {
+        balance_sats,
+        utxo_count: utxos.len(),
+        fetched_at: unix_now(),
+    })
+}
+
+/// Reporter for cores run from the TUI
+///
+/// The screens already show each step's result, so progress is dropped.
+/// Warnings go to the status line and the tutorial text to the learn panel.
+#[derive(Default)]
+pub struct TuiReporter {
+    warnings: Vec<String>,
+    explanations: Vec<ProtocolEvent>,
+}
+
+impl Reporter for TuiReporter {
+    fn explain(&mut self, event: &ProtocolEvent) {
+        self.explanations.push(event.clone());
+    }
+
+    fn warn(&mut self, event: &ProtocolEvent) {
+        if let ProtocolEvent::Warning { message, .. } = event {
+            self.warnings.push(message.clone());
+        }
+    }
+
+    fn progress(&mut self, _event: &ProtocolEvent) {}
+
+    fn result(&mut self, _result: &str) {}
 }
 
 /// Main application state
@@ -25,18 +222,74 @@ pub struct App {
     /// List of wallets
     pub wallets: Vec<WalletSummary>,
 
-    /// Wallet list selection state
+    /// Wallet list selection state (an index into `wallets`)
     pub wallet_list_state: ListState,
 
+    /// Search, sort order and grouping of the home wallet list
+    pub home: HomeView,
+
     /// Balance cache (key: "wallet_name:network")
     pub balance_cache: HashMap<String, BalanceInfo>,
 
     /// Currently selected network
     pub network: NetworkSelection,
 
+    /// BTC price for fiat display (`fiat-config`), refreshed with balances
+    pub fiat_rate: Option<fiat::Rate>,
+
+    /// The backend was unreachable on the last attempt; network actions are disabled
+    pub offline: bool,
+
+    /// When the last reconnect probe started, and its result channel
+    last_probe: Instant,
+    probe_pending: bool,
+    probe_sender: Sender<bool>,
+    probe_receiver: Receiver<bool>,
+
+    /// Background balance refresher: how often it sweeps all wallets (None
+    /// when disabled), when the last sweep started, and its result channel
+    refresh_interval: Option<Duration>,
+    last_sweep: Option<Instant>,
+    balance_sender: Sender<BalanceUpdate>,
+    balance_receiver: Receiver<BalanceUpdate>,
+
+    /// Wallets still waiting in the running background sweep
+    pub refreshing: HashSet<String>,
+
+    /// Background rebroadcast of pending transactions: when the last check
+    /// started, whether it is still running, and its result channel
+    last_rebroadcast: Option<Instant>,
+    rebroadcasting: bool,
+    rebroadcast_sender: Sender<RebroadcastUpdate>,
+    rebroadcast_receiver: Receiver<RebroadcastUpdate>,
+
     /// Status message
     pub message: Option<String>,
 
+    /// Key bindings (`keymap.json`)
+    pub keymap: KeyMap,
+
+    /// Keybinding help overlay is open
+    pub show_help: bool,
+
+    /// Clickable regions of the last frame
+    pub mouse: MouseMap,
+
+    /// Scroll/search state of the output on screen
+    pub viewer: OutputViewer,
+
+    /// Path prompt for loading a paste area from disk (Ctrl+O)
+    pub import_path: Option<TextInput>,
+
+    /// Raw transaction pasted into the transaction viewer
+    pub decode_input: TextArea,
+
+    /// Wizard side panel explaining each step (`e` / F2)
+    pub learn: bool,
+
+    /// Tutorial events (🧠/❓) from the last protocol step that ran
+    pub lesson: Vec<ProtocolEvent>,
+
     /// Loading state
     pub loading: bool,
 
@@ -51,30 +304,89 @@ pub struct App {
 
     /// Send wizard form data
     pub send_form: SendFormData,
+
+    /// Lock on the wallet being signed with, held while the send flow is open
+    pub wallet_lock: Option<WalletLock>,
+
+    /// Broadcast transactions being watched in the background (txid -> state)
+    pub watched_txs: HashMap<String, WatchState>,
+
+    /// Wallet that broadcast each watched transaction (for webhooks)
+    watched_wallets: HashMap<String, String>,
+
+    /// Show a desktop notification when a watched transaction confirms
+    desktop_notify: bool,
+
+    /// Channel used by background watcher threads
+    tx_watch_sender: Sender<TxWatchUpdate>,
+    tx_watch_receiver: Receiver<TxWatchUpdate>,
 }
 
 impl App {
-    /// Create a new App instance
-    pub fn new() -> Result<Self> {
+    /// Create a new App instance; `refresh_secs` of 0 turns off background
+    /// balance refreshes, and `desktop_notify` pops a desktop notification
+    /// when a watched transaction confirms
+    pub fn new(refresh_secs: u64, desktop_notify: bool) -> Result<Self> {
         let wallets = list_wallets()?;
+        let home = HomeView::load();
         let mut wallet_list_state = ListState::default();
-        if !wallets.is_empty() {
-            wallet_list_state.select(Some(0));
-        }
+        wallet_list_state.select(
+            home.last_selected
+                .as_ref()
+                .and_then(|name| wallets.iter().position(|w| &w.name == name)),
+        );
 
-        Ok(Self {
+        let (tx_watch_sender, tx_watch_receiver) = mpsc::channel();
+        let (probe_sender, probe_receiver) = mpsc::channel();
+        let (balance_sender, balance_receiver) = mpsc::channel();
+        let (rebroadcast_sender, rebroadcast_receiver) = mpsc::channel();
+        let (keymap, keymap_error) = KeyMap::load();
+
+        let mut app = Self {
             state: AppState::Home,
             wallets,
             wallet_list_state,
-            balance_cache: HashMap::new(),
+            home,
+            balance_cache: load_balance_cache(),
             network: NetworkSelection::default(),
-            message: None,
+            fiat_rate: None,
+            offline: false,
+            last_probe: Instant::now(),
+            probe_pending: false,
+            probe_sender,
+            probe_receiver,
+            refresh_interval: (refresh_secs > 0).then(|| Duration::from_secs(refresh_secs)),
+            last_sweep: None,
+            balance_sender,
+            balance_receiver,
+            refreshing: HashSet::new(),
+            last_rebroadcast: None,
+            rebroadcasting: false,
+            rebroadcast_sender,
+            rebroadcast_receiver,
+            message: keymap_error,
+            keymap,
+            show_help: false,
+            mouse: MouseMap::default(),
+            viewer: OutputViewer::default(),
+            import_path: None,
+            decode_input: TextArea::new("Paste a raw transaction or a dkg-build-tx output"),
+            learn: false,
+            lesson: Vec::new(),
             loading: false,
             chain_selector_index: 0,
             keygen_form: KeygenFormData::new(),
             reshare_form: ReshareFormData::new(),
             send_form: SendFormData::new(),
-        })
+            wallet_lock: None,
+            watched_txs: HashMap::new(),
+            watched_wallets: HashMap::new(),
+            desktop_notify,
+            tx_watch_sender,
+            tx_watch_receiver,
+        };
+        app.fix_home_selection();
+        Ok(app)
     }
 
     /// Get selected wallet
@@ -86,43 +398,133 @@ impl App {
 
     /// Navigate to next wallet
     pub fn next_wallet(&mut self) {
-        if self.wallets.is_empty() {
-            return;
-        }
-        let i = match self.wallet_list_state.selected() {
-            Some(i) => {
-                if i >= self.wallets.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
-        self.wallet_list_state.select(Some(i));
+        self.step_wallet(1);
     }
 
     /// Navigate to previous wallet
     pub fn prev_wallet(&mut self) {
-        if self.wallets.is_empty() {
+        self.step_wallet(-1);
+    }
+
+    /// Move through the home list in display order, wrapping around
+    fn step_wallet(&mut self, delta: isize) {
+        let visible = self.visible_wallets();
+        if visible.is_empty() {
             return;
         }
-        let i = match self.wallet_list_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.wallets.len() - 1
-                } else {
-                    i - 1
-                }
-            }
+        let position = self
+            .wallet_list_state
+            .selected()
+            .and_then(|i| visible.iter().position(|&v| v == i));
+        let next = match position {
+            Some(p) => (p as isize + delta).rem_euclid(visible.len() as isize) as usize,
             None => 0,
         };
-        self.wallet_list_state.select(Some(i));
+        self.wallet_list_state.select(Some(visible[next]));
+    }
+
+    /// Lines of the home wallet list: matching wallets in the chosen order,
+    /// under network headings when grouped
+    pub fn home_rows(&self) -> Vec<HomeRow> {
+        let mut visible: Vec<usize> = (0..self.wallets.len())
+            .filter(|&i| self.home.matches(&self.wallets[i]))
+            .collect();
+        // Stable sorts keep ties in name order; unknown values go last
+        match self.home.sort {
+            SortOrder::Name => visible.sort_by_key(|&i| self.wallets[i].name.to_lowercase()),
+            SortOrder::Balance => visible.sort_by_key(|&i| {
+                Reverse(
+                    self.cached_balance(&self.wallets[i].name)
+                        .map(|info| info.balance_sats),
+                )
+            }),
+            SortOrder::LastUsed => visible
+                .sort_by_key(|&i| Reverse(self.home.last_used.get(&self.wallets[i].name).copied())),
+        }
+
+        if !self.home.group_by_network {
+            return visible.into_iter().map(HomeRow::Wallet).collect();
+        }
+
+        let groups = NetworkSelection::all()
+            .iter()
+            .copied()
+            .map(Some)
+            .chain([None]);
+        let mut rows = Vec::new();
+        for group in groups {
+            let members: Vec<usize> = visible
+                .iter()
+                .copied()
+                .filter(|&i| self.last_network(&self.wallets[i].name) == group)
+                .collect();
+            if !members.is_empty() {
+                rows.push(HomeRow::Header(group));
+                rows.extend(members.into_iter().map(HomeRow::Wallet));
+            }
+        }
+        rows
+    }
+
+    /// Indices of the wallets on the home list, in display order
+    pub fn visible_wallets(&self) -> Vec<usize> {
+        self.home_rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                HomeRow::Wallet(i) => Some(i),
+                HomeRow::Header(_) => None,
+            })
+            .collect()
+    }
+
+    /// Network a wallet's balance was most recently fetched on
+    fn last_network(&self, wallet_name: &str) -> Option<NetworkSelection> {
+        NetworkSelection::all()
+            .iter()
+            .filter_map(|network| {
+                self.balance_cache
+                    .get(&format!("{}:{:?}", wallet_name, network))
+                    .map(|info| (info.fetched_at, *network))
+            })
+            .max_by_key(|(fetched_at, _)| *fetched_at)
+            .map(|(_, network)| network)
+    }
+
+    /// Keep the selection on a listed wallet after the search or order changes
+    pub fn fix_home_selection(&mut self) {
+        let visible = self.visible_wallets();
+        let selected = self.wallet_list_state.selected();
+        if !selected.is_some_and(|i| visible.contains(&i)) {
+            self.wallet_list_state.select(visible.first().copied());
+        }
+    }
+
+    /// Record that the selected wallet was opened, for last-used sorting
+    pub fn mark_wallet_used(&mut self) {
+        if let Some(name) = self.selected_wallet().map(|w| w.name.clone()) {
+            self.home.last_used.insert(name, unix_now());
+        }
+        self.save_home_view();
+    }
+
+    /// Persist the home list settings and current selection
+    pub fn save_home_view(&mut self) {
+        self.home.last_selected = self.selected_wallet().map(|w| w.name.clone());
+        self.home.save();
+    }
+
+    /// Cached balance of a wallet on the current network
+    pub fn cached_balance(&self, wallet_name: &str) -> Option<&BalanceInfo> {
+        self.balance_cache
+            .get(&format!("{}:{:?}", wallet_name, self.network))
     }
 
     /// Refresh balance for selected wallet
     pub fn refresh_balance(&mut self) {
         if let Some(wallet) = self.selected_wallet().cloned() {
+            if !self.require_online("Refreshing balances") {
+                return;
+            }
             self.loading = true;
             self.message = Some(format!(
                 "Fetching {} balance for {}...",
@@ -130,12 +532,23 @@ impl App {
                 wallet.name
             ));
 
-            match self.fetch_balance(&wallet.name) {
+            match fetch_balance(self.network, &wallet.name) {
                 Ok(info) => {
                     let cache_key = format!("{}:{:?}", wallet.name, self.network);
                     self.balance_cache.insert(cache_key, info);
+                    self.save_balance_cache();
+                    self.fiat_rate = fiat::display_rate();
                     self.message = Some(format!("Balance updated for {}", wallet.name));
                 }
+                Err(e) if esplora::is_unreachable(&e) => {
+                    self.go_offline();
+                    self.message = Some(match self.cached_balance(&wallet.name) {
+                        Some(info) => {
+                            format!("Offline: showing cached balance from {}", info.age())
+                        }
+                        None => "Offline: no cached balance for this wallet".to_string(),
+                    });
+                }
                 Err(e) => {
                     self.message = Some(format!("Error: {}", e));
                 }
@@ -144,51 +557,250 @@ impl App {
         }
     }
 
-    /// Fetch balance for a wallet on the current network
-    fn fetch_balance(&self, wallet_name: &str) -> Result<BalanceInfo> {
-        let state_dir = frostdao::protocol::keygen::get_state_dir(wallet_name);
-        let storage = FileStorage::new(&state_dir)?;
+    fn save_balance_cache(&self) {
+        // Best effort: the cache is only a convenience for offline use
+        if simulate::is_simulated() {
+            return;
+        }
+        if let (Ok(storage), Ok(json)) = (
+            FileStorage::new(STATE_ROOT),
+            serde_json::to_vec_pretty(&self.balance_cache),
+        ) {
+            let _ = storage.write(BALANCE_CACHE_FILE, &json);
+        }
+    }
 
-        // Load shared key
-        let shared_key_bytes = storage.read("shared_key.bin")?;
-        let shared_key: schnorr_fun::frost::SharedKey<schnorr_fun::fun::marker::EvenY> =
-            bincode::deserialize(&shared_key_bytes)?;
+    /// Mark the backend unreachable; [`App::poll_connectivity`] retries later
+    pub fn go_offline(&mut self) {
+        self.offline = true;
+        self.last_probe = Instant::now();
+    }
 
-        let pubkey_bytes: [u8; 32] = shared_key.public_key().to_xonly_bytes();
-        let xonly_pubkey = XOnlyPublicKey::from_slice(&pubkey_bytes)?;
+    /// Returns false, with a status message, if `action` can't run offline
+    pub fn require_online(&mut self, action: &str) -> bool {
+        if self.offline {
+            self.message = Some(format!(
+                "Offline: {} needs the network. Reconnecting automatically...",
+                action
+            ));
+        }
+        !self.offline
+    }
 
-        let secp = bitcoin::secp256k1::Secp256k1::new();
-        let btc_network = self.network.to_bitcoin_network();
-        let address = Address::p2tr(&secp, xonly_pubkey, None, btc_network).to_string();
+    /// Re-check the backend in the background while offline (non-blocking)
+    pub fn poll_connectivity(&mut self) {
+        if let Ok(online) = self.probe_receiver.try_recv() {
+            self.probe_pending = false;
+            if online && self.offline {
+                self.offline = false;
+                self.message = Some("Back online".to_string());
+            }
+        }
+        if !self.offline || self.probe_pending || self.last_probe.elapsed() < RECONNECT_INTERVAL {
+            return;
+        }
 
-        // Fetch UTXOs from mempool.space
-        let client = reqwest::blocking::Client::new();
-        let api_base = self.network.mempool_api_base();
-        let url = format!("{}/address/{}/utxo", api_base, address);
-        let response = client.get(&url).send()?;
-        let utxos: Vec<serde_json::Value> = response.json()?;
+        self.probe_pending = true;
+        self.last_probe = Instant::now();
+        let network = self.network.to_bitcoin_network();
+        let sender = self.probe_sender.clone();
+        std::thread::spawn(move || {
+            let online = frostdao::btc::transaction::fetch_tip_height(network).is_ok();
+            let _ = sender.send(online);
+        });
+    }
 
-        let balance_sats: u64 = utxos
-            .iter()
-            .filter_map(|u| u.get("value").and_then(|v| v.as_u64()))
-            .sum();
+    /// Apply finished background refreshes and start a sweep when due
+    pub fn poll_balance_refresher(&mut self) {
+        let mut updated = false;
+        while let Ok(update) = self.balance_receiver.try_recv() {
+            self.refreshing.remove(&update.wallet_name);
+            match update.result {
+                Ok(info) => {
+                    let cache_key = format!("{}:{:?}", update.wallet_name, update.network);
+                    self.balance_cache.insert(cache_key, info);
+                    updated = true;
+                }
+                Err(_) if update.unreachable => {
+                    self.refreshing.clear();
+                    self.go_offline();
+                }
+                // Wallets that can't be fetched (other curves, missing keys)
+                // keep their cached value
+                Err(_) => {}
+            }
+        }
+        if updated {
+            self.save_balance_cache();
+        }
 
-        Ok(BalanceInfo {
-            balance_sats,
-            utxo_count: utxos.len(),
-        })
+        let Some(interval) = self.refresh_interval else {
+            return;
+        };
+        if self.offline
+            || !self.refreshing.is_empty()
+            || self.last_sweep.is_some_and(|at| at.elapsed() < interval)
+        {
+            return;
+        }
+        self.start_balance_sweep();
+    }
+
+    /// Refresh every wallet on the current network, one request at a time
+    fn start_balance_sweep(&mut self) {
+        self.last_sweep = Some(Instant::now());
+        let network = self.network;
+        let names: Vec<String> = self.wallets.iter().map(|w| w.name.clone()).collect();
+        self.refreshing = names.iter().cloned().collect();
+        let sender = self.balance_sender.clone();
+        std::thread::spawn(move || {
+            for (i, wallet_name) in names.into_iter().enumerate() {
+                if i > 0 {
+                    std::thread::sleep(REFRESH_REQUEST_GAP);
+                }
+                let result = fetch_balance(network, &wallet_name);
+                let unreachable = result.as_ref().is_err_and(esplora::is_unreachable);
+                let update = BalanceUpdate {
+                    wallet_name,
+                    network,
+                    result: result.map_err(|e| e.to_string()),
+                    unreachable,
+                };
+                // Stop when the TUI has exited or the backend is down
+                if sender.send(update).is_err() || unreachable {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Apply a finished background rebroadcast and start one when due
+    ///
+    /// Every wallet's unconfirmed transactions on the current network are
+    /// checked and pushed again (see [`rebroadcast`]); wallets busy in
+    /// another command are skipped until the next round.
+    pub fn poll_rebroadcaster(&mut self) {
+        if let Ok(update) = self.rebroadcast_receiver.try_recv() {
+            self.rebroadcasting = false;
+            if update.unreachable {
+                self.go_offline();
+            }
+            if let Some(message) = rebroadcast_message(&update.checks) {
+                self.message = Some(message);
+            }
+        }
+
+        let interval = Duration::from_secs(rebroadcast::REBROADCAST_INTERVAL_SECS);
+        if self.offline
+            || self.rebroadcasting
+            || simulate::is_simulated()
+            || self
+                .last_rebroadcast
+                .is_some_and(|at| at.elapsed() < interval)
+        {
+            return;
+        }
+
+        self.rebroadcasting = true;
+        self.last_rebroadcast = Some(Instant::now());
+        let network = self.network.to_bitcoin_network();
+        let names: Vec<String> = self.wallets.iter().map(|w| w.name.clone()).collect();
+        let sender = self.rebroadcast_sender.clone();
+        std::thread::spawn(move || {
+            let backend = transaction::MempoolBackend::new(network);
+            let mut update = RebroadcastUpdate {
+                checks: Vec::new(),
+                unreachable: false,
+            };
+            for wallet_name in names {
+                let dir = get_state_dir(&wallet_name);
+                let Ok(_lock) = WalletLock::acquire(Path::new(&dir), "tui", Duration::ZERO) else {
+                    continue;
+                };
+                let Ok(storage) = FileStorage::new(&dir) else {
+                    continue;
+                };
+                match rebroadcast::check_pending(&storage, network, &backend, None, unix_now()) {
+                    Ok(checks) => update
+                        .checks
+                        .extend(checks.into_iter().map(|c| (wallet_name.clone(), c))),
+                    Err(e) if esplora::is_unreachable(&e) => {
+                        update.unreachable = true;
+                        break;
+                    }
+                    Err(_) => {}
+                }
+            }
+            let _ = sender.send(update);
+        });
     }
 
     /// Fetch UTXOs and recent transactions for send form
-    pub fn fetch_utxos_for_send(&mut self, address: &str) {
+    ///
+    /// A transaction's amount counts every script of the wallet, so change
+    /// sent to another of its addresses is not shown as spent.
+    pub fn fetch_utxos_for_send(&mut self, wallet_name: &str, address: &str) {
         use super::screens::{TxDisplay, UtxoDisplay};
 
-        let api_base = self.network.mempool_api_base();
-        let client = reqwest::blocking::Client::new();
+        if !self.network.has_esplora() {
+            let network = self.network.to_bitcoin_network();
+            self.send_form.fiat_rate = if simulate::is_simulated() {
+                None
+            } else {
+                fiat::display_rate()
+            };
+            if let Ok(fees) = transaction::fetch_fee_estimates(network) {
+                self.send_form.fee_rate = fees.half_hour_fee;
+            }
+            match transaction::fetch_utxos(address, network) {
+                Ok(utxos) => {
+                    self.send_form.utxos = utxos
+                        .into_iter()
+                        .map(|u| UtxoDisplay {
+                            txid: u.txid,
+                            vout: u.vout,
+                            value: u.value,
+                            confirmed: u.status.confirmed,
+                        })
+                        .collect();
+                    self.send_form.total_balance =
+                        self.send_form.utxos.iter().map(|u| u.value).sum();
+                    self.send_form.estimate_fee();
+                }
+                Err(e) if esplora::is_unreachable(&e) => {
+                    self.go_offline();
+                    self.send_form.error_message = Some(
+                        "Offline: can't load UTXOs or fees. Go back and retry later.".to_string(),
+                    );
+                }
+                Err(e) => self.set_message(&format!("Chain error: {}", e)),
+            }
+            return;
+        }
+
+        let endpoint = match self.network.esplora() {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                self.set_message(&format!("Backend config error: {}", e));
+                return;
+            }
+        };
+        let client = http::client();
 
         // Fetch fee estimates
-        let fee_url = format!("{}/v1/fees/recommended", api_base);
-        if let Ok(response) = client.get(&fee_url).send() {
+        let fees = endpoint.get(&client, "/v1/fees/recommended").send();
+        if fees.as_ref().is_err_and(|e| e.is_connect()) {
+            self.go_offline();
+            self.send_form.error_message =
+                Some("Offline: can't load UTXOs or fees. Go back and retry later.".to_string());
+            return;
+        }
+        // A slow fee endpoint shouldn't block the UTXOs: keep the default rate
+        if fees.as_ref().is_err_and(|e| e.is_timeout()) {
+            self.set_message("Fee estimates timed out; using 1 sat/vB");
+        }
+        self.send_form.fiat_rate = fiat::display_rate();
+        if let Ok(response) = fees {
             if let Ok(fees) = response.json::<serde_json::Value>() {
                 // Use half hour fee as default (reasonable balance of speed/cost)
                 self.send_form.fee_rate = fees
@@ -199,8 +811,8 @@ impl App {
         }
 
         // Fetch UTXOs
-        let utxo_url = format!("{}/address/{}/utxo", api_base, address);
-        if let Ok(response) = client.get(&utxo_url).send() {
+        let utxo_path = format!("/address/{}/utxo", address);
+        if let Ok(response) = endpoint.get(&client, &utxo_path).send() {
             if let Ok(utxos) = response.json::<Vec<serde_json::Value>>() {
                 self.send_form.utxos = utxos
                     .iter()
@@ -225,8 +837,18 @@ impl App {
         }
 
         // Fetch recent transactions
-        let txs_url = format!("{}/address/{}/txs", api_base, address);
-        if let Ok(response) = client.get(&txs_url).send() {
+        let own = FileStorage::new(&get_state_dir(wallet_name))
+            .and_then(|storage| ScriptIndex::load(&storage))
+            .ok();
+        let is_own = |output: &serde_json::Value| match &own {
+            Some(own) => output
+                .get("scriptpubkey")
+                .and_then(|s| s.as_str())
+                .is_some_and(|script| own.owner_hex(script).is_some()),
+            None => output.get("scriptpubkey_address").and_then(|a| a.as_str()) == Some(address),
+        };
+        let txs_path = format!("/address/{}/txs", address);
+        if let Ok(response) = endpoint.get(&client, &txs_path).send() {
             if let Ok(txs) = response.json::<Vec<serde_json::Value>>() {
                 self.send_form.recent_txs = txs
                     .iter()
@@ -243,36 +865,21 @@ impl App {
                             .and_then(|s| s.get("block_time"))
                             .and_then(|t| t.as_u64());
 
-                        // Calculate net amount for this address
+                        // Calculate net amount for the wallet
                         let mut received: i64 = 0;
                         let mut sent: i64 = 0;
 
                         if let Some(vout) = tx.get("vout").and_then(|v| v.as_array()) {
-                            for out in vout {
-                                if let Some(scriptpubkey_address) =
-                                    out.get("scriptpubkey_address").and_then(|a| a.as_str())
-                                {
-                                    if scriptpubkey_address == address {
-                                        received +=
-                                            out.get("value").and_then(|v| v.as_i64()).unwrap_or(0);
-                                    }
-                                }
+                            for out in vout.iter().filter(|out| is_own(out)) {
+                                received += out.get("value").and_then(|v| v.as_i64()).unwrap_or(0);
                             }
                         }
 
                         if let Some(vin) = tx.get("vin").and_then(|v| v.as_array()) {
-                            for inp in vin {
-                                if let Some(prevout) = inp.get("prevout") {
-                                    if let Some(scriptpubkey_address) =
-                                        prevout.get("scriptpubkey_address").and_then(|a| a.as_str())
-                                    {
-                                        if scriptpubkey_address == address {
-                                            sent += prevout
-                                                .get("value")
-                                                .and_then(|v| v.as_i64())
-                                                .unwrap_or(0);
-                                        }
-                                    }
+                            for prevout in vin.iter().filter_map(|inp| inp.get("prevout")) {
+                                if is_own(prevout) {
+                                    sent +=
+                                        prevout.get("value").and_then(|v| v.as_i64()).unwrap_or(0);
                                 }
                             }
                         }
@@ -292,17 +899,11 @@ impl App {
     /// Reload wallet list
     pub fn reload_wallets(&mut self) {
         if let Ok(wallets) = list_wallets() {
+            let selected = self.selected_wallet().map(|w| w.name.clone());
             self.wallets = wallets;
-            if self.wallets.is_empty() {
-                self.wallet_list_state.select(None);
-            } else if self
-                .wallet_list_state
-                .selected()
-                .map(|i| i >= self.wallets.len())
-                .unwrap_or(true)
-            {
-                self.wallet_list_state.select(Some(0));
-            }
+            self.wallet_list_state
+                .select(selected.and_then(|name| self.wallets.iter().position(|w| w.name == name)));
+            self.fix_home_selection();
             self.message = Some("Wallet list refreshed".to_string());
         }
     }
@@ -330,6 +931,10 @@ impl App {
             _ => NetworkSelection::Testnet,
         };
         self.state = AppState::Home;
+        // Each network has its own backend; let the next fetch find out
+        self.offline = false;
+        // Sweep the new network's balances once the running sweep is done
+        self.last_sweep = None;
         self.message = Some(format!("Switched to {}", self.network.display_name()));
     }
 
@@ -338,6 +943,15 @@ impl App {
         self.message = Some(msg.to_string());
     }
 
+    /// Put a core's warnings, if any, on the status line and keep its
+    /// explanations for the learn panel
+    pub fn take_report(&mut self, reporter: TuiReporter) {
+        if !reporter.warnings.is_empty() {
+            self.message = Some(format!("⚠️  {}", reporter.warnings.join("; ")));
+        }
+        self.lesson = reporter.explanations;
+    }
+
     /// Copy text to clipboard
     pub fn copy_to_clipboard(&mut self, text: &str) {
         match arboard::Clipboard::new() {
@@ -481,4 +1095,93 @@ impl App {
             }
         }
     }
+
+    /// Start polling a broadcast transaction for confirmation in the background
+    pub fn watch_transaction(&mut self, wallet_name: &str, txid: &str, amount: i64) {
+        use super::screens::TxDisplay;
+
+        if self.watched_txs.contains_key(txid) {
+            return;
+        }
+        self.watched_txs
+            .insert(txid.to_string(), WatchState::Pending);
+        self.watched_wallets
+            .insert(txid.to_string(), wallet_name.to_string());
+
+        // Show it in the recent-tx panel right away
+        if !self.send_form.recent_txs.iter().any(|tx| tx.txid == txid) {
+            self.send_form.recent_txs.insert(
+                0,
+                TxDisplay {
+                    txid: txid.to_string(),
+                    amount,
+                    confirmed: false,
+                    time: None,
+                },
+            );
+        }
+
+        monitor::spawn_watcher(
+            txid.to_string(),
+            self.network.to_bitcoin_network(),
+            Duration::from_secs(monitor::DEFAULT_POLL_INTERVAL_SECS),
+            self.tx_watch_sender.clone(),
+        );
+    }
+
+    /// Apply updates from background watchers (non-blocking)
+    pub fn poll_tx_watchers(&mut self) {
+        while let Ok(update) = self.tx_watch_receiver.try_recv() {
+            let first_confirmation = matches!(update.state, WatchState::Confirmed { .. })
+                && !matches!(
+                    self.watched_txs.get(&update.txid),
+                    Some(WatchState::Confirmed { .. })
+                );
+
+            if let WatchState::Confirmed { block_height, .. } = update.state {
+                for tx in self
+                    .send_form
+                    .recent_txs
+                    .iter_mut()
+                    .filter(|tx| tx.txid == update.txid)
+                {
+                    tx.confirmed = true;
+                }
+
+                if first_confirmation {
+                    let short = &update.txid[..update.txid.len().min(8)];
+                    self.message = Some(format!(
+                        "✓ Transaction {}... confirmed in block {}",
+                        short, block_height
+                    ));
+                    let wallet = self
+                        .watched_wallets
+                        .get(&update.txid)
+                        .cloned()
+                        .unwrap_or_default();
+                    let txid = update.txid.clone();
+                    let network = self.network.to_bitcoin_network();
+                    let desktop = self.desktop_notify;
+                    // Webhook POSTs and the desktop notifier can be slow; keep
+                    // them off the render thread
+                    std::thread::spawn(move || {
+                        let webhooks = FileStorage::new(&get_state_dir(&wallet))
+                            .ok()
+                            .and_then(|storage| notify::load_webhooks(&storage).ok())
+                            .unwrap_or_default();
+                        monitor::notify_confirmation(
+                            &wallet,
+                            &txid,
+                            block_height,
+                            network,
+                            &webhooks,
+                            desktop,
+                        );
+                    });
+                }
+            }
+
+            self.watched_txs.insert(update.txid, update.state);
+        }
+    }
 }
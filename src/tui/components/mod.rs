@@ -1,7 +1,9 @@
 //! Reusable TUI components
 
+mod output_viewer;
 mod text_area;
 mod text_input;
 
+pub use output_viewer::OutputViewer;
 pub use text_area::TextArea;
 pub use text_input::TextInput;
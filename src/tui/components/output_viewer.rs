@@ -0,0 +1,322 @@
+//! Scrollable output viewer for round outputs, nonces and shares
+
+use crossterm::event::{KeyCode, KeyEvent};
+use frostdao::protocol::payload;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::cell::Cell;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What the viewer is reading keys for
+#[derive(Clone, Default, PartialEq, Eq)]
+enum Mode {
+    #[default]
+    Normal,
+    /// Typing a search after `/`
+    Search(String),
+    /// Typing the file name after `s`
+    Save(String),
+}
+
+/// Read-only text view with scrolling, search, wrap toggle and save to file
+///
+/// Screens pass their output through [`OutputViewer::show`] each frame; the
+/// view state resets whenever the text changes.
+#[derive(Clone)]
+pub struct OutputViewer {
+    text: String,
+    /// First visible row (after wrapping)
+    scroll: usize,
+    /// Horizontal offset when not wrapping
+    column: usize,
+    wrap: bool,
+    mode: Mode,
+    query: String,
+    /// Lines containing the query
+    matches: Vec<usize>,
+    current_match: usize,
+    /// Result of the last save or search
+    status: Option<String>,
+    /// Inner width and height of the last render, for paging and wrapping
+    size: Cell<(u16, u16)>,
+}
+
+impl Default for OutputViewer {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            scroll: 0,
+            column: 0,
+            wrap: true,
+            mode: Mode::Normal,
+            query: String::new(),
+            matches: Vec::new(),
+            current_match: 0,
+            status: None,
+            size: Cell::new((80, 20)),
+        }
+    }
+}
+
+impl OutputViewer {
+    /// Point the viewer at `text`, starting over if it changed
+    pub fn show(&mut self, text: &str) {
+        if self.text != text {
+            *self = Self {
+                text: text.to_string(),
+                wrap: self.wrap,
+                size: self.size.clone(),
+                ..Self::default()
+            };
+        }
+    }
+
+    /// Display rows as (source line, text)
+    fn rows(&self, width: usize) -> Vec<(usize, String)> {
+        let mut rows = Vec::new();
+        for (index, line) in self.text.lines().enumerate() {
+            if !self.wrap || line.is_empty() {
+                rows.push((index, line.to_string()));
+                continue;
+            }
+            let chars: Vec<char> = line.chars().collect();
+            for chunk in chars.chunks(width.max(1)) {
+                rows.push((index, chunk.iter().collect()));
+            }
+        }
+        rows
+    }
+
+    fn max_scroll(&self) -> usize {
+        let (width, height) = self.size.get();
+        self.rows(width as usize)
+            .len()
+            .saturating_sub(height as usize)
+    }
+
+    /// Scroll by `rows` (negative is up)
+    pub fn scroll_by(&mut self, rows: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(rows)
+            .min(self.max_scroll());
+    }
+
+    fn jump_to_match(&mut self) {
+        let Some(&line) = self.matches.get(self.current_match) else {
+            return;
+        };
+        let width = self.size.get().0 as usize;
+        let row = self
+            .rows(width)
+            .iter()
+            .position(|(index, _)| *index == line)
+            .unwrap_or(0);
+        self.scroll = row.min(self.max_scroll());
+        self.status = Some(format!(
+            "match {}/{}",
+            self.current_match + 1,
+            self.matches.len()
+        ));
+    }
+
+    fn search(&mut self, query: String) {
+        let needle = query.to_lowercase();
+        self.matches = self
+            .text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !needle.is_empty() && line.to_lowercase().contains(&needle))
+            .map(|(index, _)| index)
+            .collect();
+        self.current_match = 0;
+        self.query = query;
+        if self.matches.is_empty() {
+            self.status = Some(format!("'{}' not found", self.query));
+        } else {
+            self.jump_to_match();
+        }
+    }
+
+    /// Save to a file, or to a timestamped file when `path` is a folder
+    fn save(&mut self, path: &str, step: &str) {
+        let written = payload::export_payload(Path::new(path), step, &self.text, unix_now());
+        self.status = Some(match written {
+            Ok(file) => format!("saved to {}", file.display()),
+            Err(e) => format!("save failed: {:#}", e),
+        });
+    }
+
+    /// Handle a key; returns false for keys the screen should handle
+    /// (Enter, Esc, `c`, `r` ...). `step` names the saved file.
+    pub fn handle_key(&mut self, key: KeyEvent, step: &str) -> bool {
+        match &mut self.mode {
+            Mode::Search(input) | Mode::Save(input) => {
+                match key.code {
+                    KeyCode::Char(c) => input.push(c),
+                    KeyCode::Backspace => {
+                        input.pop();
+                    }
+                    KeyCode::Enter => {
+                        let input = std::mem::take(input);
+                        match std::mem::take(&mut self.mode) {
+                            Mode::Search(_) => self.search(input),
+                            _ if input.trim().is_empty() => {}
+                            _ => self.save(input.trim(), step),
+                        }
+                    }
+                    KeyCode::Esc => self.mode = Mode::Normal,
+                    _ => {}
+                }
+                return true;
+            }
+            Mode::Normal => {}
+        }
+
+        let page = self.size.get().1.max(1) as isize;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::PageUp => self.scroll_by(-page),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = self.max_scroll(),
+            KeyCode::Left | KeyCode::Char('h') if !self.wrap => {
+                self.column = self.column.saturating_sub(8)
+            }
+            KeyCode::Right | KeyCode::Char('l') if !self.wrap => self.column += 8,
+            KeyCode::Char('w') => {
+                self.wrap = !self.wrap;
+                self.column = 0;
+                self.scroll = self.scroll.min(self.max_scroll());
+            }
+            KeyCode::Char('/') => self.mode = Mode::Search(String::new()),
+            KeyCode::Char('n') if !self.matches.is_empty() => {
+                self.current_match = (self.current_match + 1) % self.matches.len();
+                self.jump_to_match();
+            }
+            KeyCode::Char('N') if !self.matches.is_empty() => {
+                self.current_match =
+                    (self.current_match + self.matches.len() - 1) % self.matches.len();
+                self.jump_to_match();
+            }
+            KeyCode::Char('s') => {
+                self.mode = Mode::Save(payload::export_file_name(step, unix_now()))
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Render inside a bordered block titled `title`
+    pub fn render(&self, frame: &mut Frame, area: Rect, title: &str, border: Color) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border))
+            .title(title.to_string());
+        let inner = block.inner(area);
+        // One row is kept for the status line
+        let height = inner.height.saturating_sub(1);
+        self.size.set((inner.width, height));
+
+        let rows = self.rows(inner.width as usize);
+        let scroll = self.scroll.min(rows.len().saturating_sub(height as usize));
+        let needle = self.query.to_lowercase();
+
+        let mut lines: Vec<Line> = rows
+            .iter()
+            .skip(scroll)
+            .take(height as usize)
+            .map(|(_, row)| {
+                let row: String = if self.wrap {
+                    row.clone()
+                } else {
+                    row.chars().skip(self.column).collect()
+                };
+                highlight(row, &needle)
+            })
+            .collect();
+        lines.resize(height as usize, Line::from(""));
+
+        let status = match &self.mode {
+            Mode::Search(input) => Line::from(vec![
+                Span::styled("/", Style::default().fg(Color::Yellow)),
+                Span::raw(input.clone()),
+                Span::styled("  Enter: search  Esc: cancel", dim()),
+            ]),
+            Mode::Save(input) => Line::from(vec![
+                Span::styled("Save to: ", Style::default().fg(Color::Yellow)),
+                Span::raw(input.clone()),
+                Span::styled("  Enter: save  Esc: cancel", dim()),
+            ]),
+            Mode::Normal => {
+                let position = format!(
+                    "{}-{}/{} ",
+                    (scroll + 1).min(rows.len()),
+                    (scroll + height as usize).min(rows.len()),
+                    rows.len()
+                );
+                let mut spans = vec![Span::styled(position, dim())];
+                if let Some(status) = &self.status {
+                    spans.push(Span::styled(
+                        format!("{} ", status),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                spans.push(Span::styled(
+                    format!(
+                        "PgUp/PgDn  /: search  n/N  w: wrap {}  s: save",
+                        if self.wrap { "on" } else { "off" }
+                    ),
+                    dim(),
+                ));
+                Line::from(spans)
+            }
+        };
+        lines.push(status);
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn dim() -> Style {
+    Style::default().fg(Color::DarkGray)
+}
+
+/// A row with occurrences of `needle` (lowercase) highlighted
+fn highlight(row: String, needle: &str) -> Line<'static> {
+    // Lowercasing can move char boundaries outside ASCII; don't risk bad slices
+    if needle.is_empty() || !row.is_ascii() {
+        return Line::from(row);
+    }
+    let lower = row.to_lowercase();
+    let mut spans = Vec::new();
+    let mut start = 0;
+    while let Some(found) = lower[start..].find(needle) {
+        let at = start + found;
+        spans.push(Span::raw(row[start..at].to_string()));
+        spans.push(Span::styled(
+            row[at..at + needle.len()].to_string(),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+        start = at + needle.len();
+    }
+    spans.push(Span::raw(row[start..].to_string()));
+    Line::from(spans)
+}
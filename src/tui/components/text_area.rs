@@ -82,6 +82,15 @@ impl TextArea {
         }
     }
 
+    /// Move the cursor by `lines` (mouse wheel), scrolling the view with it
+    pub fn scroll(&mut self, lines: isize) {
+        self.cursor_line = self
+            .cursor_line
+            .saturating_add_signed(lines)
+            .min(self.lines.len() - 1);
+        self.cursor_col = self.cursor_col.min(self.lines[self.cursor_line].len());
+    }
+
     /// Handle key event
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
         match key.code {
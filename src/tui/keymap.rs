@@ -0,0 +1,575 @@
+//! Configurable key bindings
+//!
+//! The list-style screens (home, wallet details, network picker, addresses,
+//! mnemonic) look keys up through a [`KeyMap`] instead of matching key codes
+//! directly. Defaults are vim-style (`j`/`k` alongside the arrows) and can be
+//! overridden per action in `.frost_state/keymap.json`:
+//!
+//! ```json
+//! { "send": ["x"], "down": ["j", "Down", "Tab"] }
+//! ```
+//!
+//! Wizard forms take typed text, so their keys stay fixed.
+
+use anyhow::{Context, Result};
+use crossterm::event::KeyCode;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::tui::state::AppState;
+use frostdao::protocol::wallet::STATE_ROOT;
+use frostdao::storage::{FileStorage, Storage};
+
+/// Keymap overrides, in the global state root
+pub const KEYMAP_FILE: &str = "keymap.json";
+
+/// Something a key can be bound to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Up,
+    Down,
+    Select,
+    Back,
+    Help,
+    Quit,
+    Refresh,
+    Reload,
+    Network,
+    NewWallet,
+    Reshare,
+    Send,
+    Addresses,
+    Mnemonic,
+    Copy,
+    Balance,
+    Qr,
+    AddAddress,
+    RemoveAddress,
+    Search,
+    Sort,
+    Group,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::Up,
+            Action::Down,
+            Action::Select,
+            Action::Back,
+            Action::Help,
+            Action::Quit,
+            Action::Refresh,
+            Action::Reload,
+            Action::Network,
+            Action::NewWallet,
+            Action::Reshare,
+            Action::Send,
+            Action::Addresses,
+            Action::Mnemonic,
+            Action::Copy,
+            Action::Balance,
+            Action::Qr,
+            Action::AddAddress,
+            Action::RemoveAddress,
+            Action::Search,
+            Action::Sort,
+            Action::Group,
+        ]
+    }
+
+    /// Name used in `keymap.json`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Select => "select",
+            Action::Back => "back",
+            Action::Help => "help",
+            Action::Quit => "quit",
+            Action::Refresh => "refresh",
+            Action::Reload => "reload",
+            Action::Network => "network",
+            Action::NewWallet => "new_wallet",
+            Action::Reshare => "reshare",
+            Action::Send => "send",
+            Action::Addresses => "addresses",
+            Action::Mnemonic => "mnemonic",
+            Action::Copy => "copy",
+            Action::Balance => "balance",
+            Action::Qr => "qr",
+            Action::AddAddress => "add_address",
+            Action::RemoveAddress => "remove_address",
+            Action::Search => "search",
+            Action::Sort => "sort",
+            Action::Group => "group",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Select => "Select / continue",
+            Action::Back => "Back",
+            Action::Help => "Show this help",
+            Action::Quit => "Quit",
+            Action::Refresh => "Refresh balance",
+            Action::Reload => "Reload wallets",
+            Action::Network => "Switch network",
+            Action::NewWallet => "New wallet (keygen)",
+            Action::Reshare => "Reshare wallet",
+            Action::Send => "Send",
+            Action::Addresses => "HD addresses",
+            Action::Mnemonic => "Backup mnemonic",
+            Action::Copy => "Copy address",
+            Action::Balance => "Fetch balance",
+            Action::Qr => "Show QR code",
+            Action::AddAddress => "Add address",
+            Action::RemoveAddress => "Remove last address",
+            Action::Search => "Search wallets",
+            Action::Sort => "Sort by name / balance / last used",
+            Action::Group => "Group by network",
+        }
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        use KeyCode::*;
+        match self {
+            Action::Up => vec![Char('k'), Up],
+            Action::Down => vec![Char('j'), Down],
+            Action::Select => vec![Enter],
+            Action::Back => vec![Esc],
+            Action::Help => vec![Char('?'), F(1)],
+            Action::Quit => vec![Char('q')],
+            Action::Refresh => vec![Char('r')],
+            Action::Reload => vec![Char('R')],
+            Action::Network => vec![Char('n')],
+            Action::NewWallet => vec![Char('g')],
+            Action::Reshare => vec![Char('h')],
+            Action::Send => vec![Char('s')],
+            Action::Addresses => vec![Char('a')],
+            Action::Mnemonic => vec![Char('m')],
+            Action::Copy => vec![Char('c')],
+            Action::Balance => vec![Char('b')],
+            Action::Qr => vec![Char('q')],
+            Action::AddAddress => vec![Char('+'), Char('a')],
+            Action::RemoveAddress => vec![Char('-'), Char('x')],
+            Action::Search => vec![Char('/')],
+            Action::Sort => vec![Char('o')],
+            Action::Group => vec![Char('v')],
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Action::all()
+            .iter()
+            .copied()
+            .find(|action| action.name() == s)
+            .with_context(|| format!("Unknown action '{}'", s))
+    }
+}
+
+/// Screens whose keys go through the keymap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyContext {
+    Home,
+    WalletDetails,
+    ChainSelect,
+    AddressList,
+    Mnemonic,
+}
+
+impl KeyContext {
+    /// The context for a state, or `None` while a form or text prompt is active
+    pub fn for_state(state: &AppState) -> Option<Self> {
+        match state {
+            AppState::Home => Some(KeyContext::Home),
+            AppState::WalletDetails(s)
+                if !s.show_qr
+                    && !s.confirm_delete
+                    && !s.confirm_archive
+                    && s.rename_input.is_none()
+                    && s.notes_form.is_none() =>
+            {
+                Some(KeyContext::WalletDetails)
+            }
+            AppState::ChainSelect => Some(KeyContext::ChainSelect),
+            AppState::AddressList(_) => Some(KeyContext::AddressList),
+            AppState::MnemonicBackup(_) => Some(KeyContext::Mnemonic),
+            _ => None,
+        }
+    }
+
+    /// Actions available on this screen, in help order
+    pub fn actions(&self) -> &'static [Action] {
+        use Action::*;
+        match self {
+            KeyContext::Home => &[
+                Up, Down, Select, Search, Sort, Group, Send, Refresh, Reload, Addresses, Copy,
+                NewWallet, Reshare, Mnemonic, Network, Help, Quit,
+            ],
+            KeyContext::WalletDetails => &[Up, Down, Select, Balance, Copy, Qr, Help, Back],
+            KeyContext::ChainSelect => &[Up, Down, Select, Help, Back],
+            KeyContext::AddressList => &[
+                Up,
+                Down,
+                Balance,
+                Copy,
+                AddAddress,
+                RemoveAddress,
+                Help,
+                Back,
+            ],
+            KeyContext::Mnemonic => &[Up, Down, Select, Help, Back],
+        }
+    }
+
+    fn all() -> [KeyContext; 5] {
+        [
+            KeyContext::Home,
+            KeyContext::WalletDetails,
+            KeyContext::ChainSelect,
+            KeyContext::AddressList,
+            KeyContext::Mnemonic,
+        ]
+    }
+}
+
+/// Fixed keys of the wizard forms, for the help overlay
+pub const FORM_KEYS: &[(&str, &str)] = &[
+    ("Tab / Shift-Tab", "Next / previous field"),
+    ("↑/↓, j/k", "Move in lists"),
+    ("Space", "Toggle selection"),
+    ("Enter", "Continue"),
+    ("Esc", "Back / cancel"),
+    ("PgUp/PgDn, /, n/N", "Scroll and search outputs"),
+    ("w / s", "Wrap / save outputs"),
+    ("Ctrl+O", "Load a paste area from a file or folder"),
+    ("e / F2", "Learn panel: what this step does"),
+];
+
+/// Key bindings per action
+#[derive(Clone, Debug)]
+pub struct KeyMap {
+    bindings: BTreeMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::all()
+                .iter()
+                .map(|action| (*action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Load `keymap.json` from the state root, falling back to the defaults
+    ///
+    /// Returns the error as a message for the status line when the file is
+    /// unreadable or binds one key twice on a screen.
+    pub fn load() -> (Self, Option<String>) {
+        match FileStorage::new(STATE_ROOT) {
+            Ok(storage) => Self::load_from(&storage),
+            Err(_) => (Self::default(), None),
+        }
+    }
+
+    /// [`KeyMap::load`] from `keymap.json` in `storage`
+    pub fn load_from(storage: &dyn Storage) -> (Self, Option<String>) {
+        if !storage.exists(KEYMAP_FILE) {
+            return (Self::default(), None);
+        }
+        match storage
+            .read(KEYMAP_FILE)
+            .and_then(|bytes| Self::from_json(&bytes))
+        {
+            Ok(keymap) => (keymap, None),
+            Err(e) => (
+                Self::default(),
+                Some(format!("{}: {:#}. Using default keys", KEYMAP_FILE, e)),
+            ),
+        }
+    }
+
+    /// Defaults with the actions in `json` rebound
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let overrides: BTreeMap<String, Vec<String>> =
+            serde_json::from_slice(json).context("Invalid keymap JSON")?;
+
+        let mut keymap = Self::default();
+        for (name, keys) in overrides {
+            let action: Action = name.parse()?;
+            let keys = keys
+                .iter()
+                .map(|key| parse_key(key))
+                .collect::<Result<Vec<_>>>()?;
+            keymap.bindings.insert(action, keys);
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    fn check_conflicts(&self) -> Result<()> {
+        for context in KeyContext::all() {
+            let mut seen: BTreeMap<String, Action> = BTreeMap::new();
+            for action in context.actions() {
+                for key in self.keys(*action) {
+                    let name = key_name(*key);
+                    if let Some(other) = seen.insert(name.clone(), *action) {
+                        anyhow::bail!(
+                            "'{}' is bound to both {} and {} on the {:?} screen",
+                            name,
+                            other.name(),
+                            action.name(),
+                            context
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The action `code` triggers on a screen
+    pub fn action(&self, context: KeyContext, code: KeyCode) -> Option<Action> {
+        context
+            .actions()
+            .iter()
+            .copied()
+            .find(|action| self.keys(*action).contains(&code))
+    }
+
+    /// Whether `code` opens the help overlay
+    ///
+    /// Forms and text prompts take typed characters, so only non-character
+    /// help keys (F1 by default) work there.
+    pub fn is_help(&self, context: Option<KeyContext>, code: KeyCode) -> bool {
+        match context {
+            Some(context) => self.action(context, code) == Some(Action::Help),
+            None => !matches!(code, KeyCode::Char(_)) && self.keys(Action::Help).contains(&code),
+        }
+    }
+
+    /// Help keys that work in forms, e.g. `F1`
+    pub fn form_help_label(&self) -> String {
+        let keys: Vec<String> = self
+            .keys(Action::Help)
+            .iter()
+            .filter(|key| !matches!(key, KeyCode::Char(_)))
+            .map(|key| key_label(*key))
+            .collect();
+        if keys.is_empty() {
+            "unbound".to_string()
+        } else {
+            keys.join("/")
+        }
+    }
+
+    /// Keys for an action as shown in help, e.g. `k/↑`
+    pub fn label(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(|k| key_label(*k)).collect();
+        if keys.is_empty() {
+            "unbound".to_string()
+        } else {
+            keys.join("/")
+        }
+    }
+}
+
+/// Parse `j`, `?`, `Enter`, `Esc`, `Up`, `F1`, `Space` ...
+pub fn parse_key(key: &str) -> Result<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+    let code = match key.to_ascii_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "delete" => KeyCode::Delete,
+        other => match other.strip_prefix('f').map(str::parse::<u8>) {
+            Some(Ok(n @ 1..=12)) => KeyCode::F(n),
+            _ => anyhow::bail!("Unknown key '{}'", key),
+        },
+    };
+    Ok(code)
+}
+
+/// The `keymap.json` spelling of a key
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
+
+fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        other => key_name(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frostdao::storage::MemoryStorage;
+
+    fn assert_defaults(keymap: &KeyMap) {
+        for action in Action::all() {
+            assert_eq!(keymap.keys(*action), action.default_keys(), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn test_defaults_have_no_conflicts() {
+        let keymap = KeyMap::default();
+        keymap.check_conflicts().unwrap();
+        assert_eq!(
+            keymap.action(KeyContext::Home, KeyCode::Char('q')),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::WalletDetails, KeyCode::Char('q')),
+            Some(Action::Qr)
+        );
+        assert_eq!(keymap.label(Action::Up), "k/↑");
+        assert_eq!(keymap.form_help_label(), "F1");
+    }
+
+    #[test]
+    fn test_overrides_rebind_only_the_named_actions() {
+        let keymap =
+            KeyMap::from_json(br#"{ "send": ["x"], "down": ["j", "Down", "Tab"] }"#).unwrap();
+
+        assert_eq!(
+            keymap.action(KeyContext::Home, KeyCode::Char('x')),
+            Some(Action::Send)
+        );
+        assert_eq!(keymap.action(KeyContext::Home, KeyCode::Char('s')), None);
+        assert_eq!(
+            keymap.action(KeyContext::Home, KeyCode::Tab),
+            Some(Action::Down)
+        );
+        assert_eq!(keymap.label(Action::Down), "j/↓/Tab");
+        assert_eq!(keymap.keys(Action::Up), Action::Up.default_keys());
+
+        // An empty list unbinds the action
+        let keymap = KeyMap::from_json(br#"{ "help": [] }"#).unwrap();
+        assert_eq!(keymap.label(Action::Help), "unbound");
+        assert!(!keymap.is_help(None, KeyCode::F(1)));
+    }
+
+    #[test]
+    fn test_form_help_ignores_character_keys() {
+        let keymap = KeyMap::from_json(br#"{ "help": ["?", "F2"] }"#).unwrap();
+        assert!(keymap.is_help(Some(KeyContext::Home), KeyCode::Char('?')));
+        assert!(!keymap.is_help(None, KeyCode::Char('?')));
+        assert!(keymap.is_help(None, KeyCode::F(2)));
+        assert_eq!(keymap.form_help_label(), "F2");
+    }
+
+    #[test]
+    fn test_unknown_action_is_rejected() {
+        let err = KeyMap::from_json(br#"{ "teleport": ["t"] }"#).unwrap_err();
+        assert!(
+            err.to_string().contains("Unknown action 'teleport'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_key_names() {
+        assert_eq!(parse_key("j").unwrap(), KeyCode::Char('j'));
+        assert_eq!(parse_key("?").unwrap(), KeyCode::Char('?'));
+        assert_eq!(parse_key("Space").unwrap(), KeyCode::Char(' '));
+        assert_eq!(parse_key("ENTER").unwrap(), KeyCode::Enter);
+        assert_eq!(parse_key("pagedown").unwrap(), KeyCode::PageDown);
+        assert_eq!(parse_key("F12").unwrap(), KeyCode::F(12));
+
+        for bad in ["", "Ctrl+x", "F0", "F13", "Fx", "Escape"] {
+            assert!(parse_key(bad).is_err(), "{:?}", bad);
+        }
+        let err = KeyMap::from_json(br#"{ "send": ["x", "Ctrl+s"] }"#).unwrap_err();
+        assert!(err.to_string().contains("Unknown key 'Ctrl+s'"), "{}", err);
+    }
+
+    #[test]
+    fn test_conflicting_bindings_are_rejected() {
+        let err = KeyMap::from_json(br#"{ "send": ["j"] }"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'j' is bound to both down and send on the Home screen"
+        );
+
+        // Same key, same action spelled twice
+        assert!(KeyMap::from_json(br#"{ "back": ["Esc", "esc"] }"#).is_err());
+
+        // Reusing a key on screens that never share it is fine
+        let keymap = KeyMap::from_json(br#"{ "qr": ["x"] }"#).unwrap();
+        assert_eq!(
+            keymap.action(KeyContext::WalletDetails, KeyCode::Char('x')),
+            Some(Action::Qr)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::AddressList, KeyCode::Char('x')),
+            Some(Action::RemoveAddress)
+        );
+    }
+
+    #[test]
+    fn test_load_falls_back_to_defaults() {
+        let storage = MemoryStorage::new();
+        let (keymap, error) = KeyMap::load_from(&storage);
+        assert_defaults(&keymap);
+        assert_eq!(error, None);
+
+        for bad in [
+            &b"{ not json"[..],
+            br#"["send"]"#,
+            br#"{ "send": "x" }"#,
+            br#"{ "nope": ["x"] }"#,
+        ] {
+            storage.write(KEYMAP_FILE, bad).unwrap();
+            let (keymap, error) = KeyMap::load_from(&storage);
+            assert_defaults(&keymap);
+            let error = error.unwrap();
+            assert!(error.starts_with("keymap.json: "), "{}", error);
+            assert!(error.ends_with("Using default keys"), "{}", error);
+        }
+
+        storage.write(KEYMAP_FILE, br#"{ "send": ["x"] }"#).unwrap();
+        let (keymap, error) = KeyMap::load_from(&storage);
+        assert_eq!(keymap.keys(Action::Send), [KeyCode::Char('x')]);
+        assert_eq!(error, None);
+    }
+}
@@ -0,0 +1,160 @@
+//! What each wizard step does, for the learn panel
+//!
+//! The CLI prints a 🧠 walkthrough as it runs; the TUI shows the same
+//! explanations beside the wizards, plus a short description of the step on
+//! screen so the panel has something to say before anything has run.
+
+use crate::tui::state::{AppState, KeygenState, ReshareState, SendState};
+
+/// A step's title and explanation
+pub struct Lesson {
+    pub title: &'static str,
+    pub lines: &'static [&'static str],
+}
+
+/// The lesson for the wizard step on screen, if a wizard is open
+pub fn for_state(state: &AppState) -> Option<Lesson> {
+    let (title, lines): (&'static str, &'static [&'static str]) = match state {
+        AppState::Keygen(step) => match step {
+            KeygenState::ModeSelect => (
+                "Threshold schemes",
+                &[
+                    "TSS splits a signing key with Shamir sharing: the key is the constant term of a random polynomial of degree t-1, and each party holds one point on it. Any t points determine the polynomial; fewer reveal nothing.",
+                    "HTSS gives parties ranks. Lower ranks hold derivatives of the polynomial (Birkhoff interpolation), so a signer set only works if its sorted ranks satisfy rank[i] <= i.",
+                ],
+            ),
+            KeygenState::ParamsSetup => (
+                "Distributed key generation",
+                &[
+                    "There is no dealer. Every party acts as the dealer of its own random polynomial, and the group key is the sum of all their constant terms.",
+                    "Nobody ever holds the full private key, not even for a moment. Leaving the party index blank skips this and creates every share here, which is only fit for demos.",
+                ],
+            ),
+            KeygenState::Round1Output { .. } => (
+                "Round 1: commitments",
+                &[
+                    "You picked a secret polynomial f(x) and published Feldman commitments a_k·G to its coefficients, with a Schnorr proof that you know f(0).",
+                    "The proof stops a rogue-key attack, where a late party picks its key to cancel out everyone else's. Nothing secret is in this output.",
+                ],
+            ),
+            KeygenState::Round2Input => (
+                "Round 2: checking commitments",
+                &[
+                    "Each party's proof of knowledge is checked before any share is sent. The commitments fix every polynomial, so nobody can change theirs later.",
+                    "The group public key is already known: it is the sum of every party's a_0·G.",
+                ],
+            ),
+            KeygenState::Round2Output { .. } => (
+                "Round 2: secret shares",
+                &[
+                    "You evaluated your polynomial at every other party's index. The share f(j) for party j is encrypted to j and signed with your identity key.",
+                    "As Nostr DMs, each share is gift-wrapped (NIP-17) so relays see neither sender nor content.",
+                ],
+            ),
+            KeygenState::FinalizeInput => (
+                "Finalize: verifying shares",
+                &[
+                    "Each share you receive is checked against its sender's commitments: f_j(i)·G must equal the sum of a_jk·i^k·G (Feldman VSS). A bad share is rejected.",
+                    "Your secret share is the sum of the shares sent to you, a point on the sum of everyone's polynomials.",
+                ],
+            ),
+            KeygenState::Complete { .. } => (
+                "The wallet",
+                &[
+                    "The address is a Taproot key-path output for the group key. Spending needs a BIP340 Schnorr signature, which t parties will produce together without rebuilding the key.",
+                ],
+            ),
+        },
+        AppState::Reshare(step) => match step {
+            ReshareState::Round1Setup => (
+                "Resharing",
+                &[
+                    "Old parties hand out fresh shares of the same secret, so the threshold or party set can change while the public key and address stay the same.",
+                    "Once the old shares are deleted they are useless, even if t of them later leak.",
+                ],
+            ),
+            ReshareState::Round1Output { .. } => (
+                "Reshare: sub-shares",
+                &[
+                    "You shared your own share s_i with a new random polynomial and committed to its coefficients. Each new party gets one point from every old party.",
+                ],
+            ),
+            ReshareState::FinalizeInput => (
+                "Reshare: combining",
+                &[
+                    "Each sub-share is checked against its commitments, then weighted with the old parties' Lagrange coefficients and summed into your new share.",
+                    "The group key computed from the new shares must equal the old one, or the reshare is rejected.",
+                ],
+            ),
+            ReshareState::Complete { .. } => (
+                "Reshare done",
+                &["The new shares sign for the same address. Old parties should now delete theirs."],
+            ),
+        },
+        AppState::Send(step) => match step {
+            SendState::SelectWallet | SendState::SelectSigners { .. } => (
+                "Choosing signers",
+                &[
+                    "FROST needs t of the n parties. Each signer's share is scaled by a Lagrange coefficient that depends on exactly who signs, so the set is fixed for the session.",
+                ],
+            ),
+            SendState::SelectAddress { .. } => (
+                "HD addresses",
+                &[
+                    "Child addresses come from unhardened derivation of the group key. Every signer applies the same public tweak to its share, so no new keygen is needed.",
+                ],
+            ),
+            SendState::ConfigureScript { .. } => (
+                "Taproot scripts",
+                &[
+                    "Timelock, recovery and HTLC paths are leaves of a Taproot script tree, and one tree can hold several. The output key is the group key tweaked by the tree's Merkle root, so the key path stays a plain FROST signature.",
+                ],
+            ),
+            SendState::EnterDetails { .. } => (
+                "Building the transaction",
+                &[
+                    "Coins are selected from the wallet's UTXOs and change goes back to it. Nothing is signed yet.",
+                ],
+            ),
+            SendState::ShowSighash { .. } => (
+                "The sighash",
+                &[
+                    "BIP341 hashes the transaction together with every input's amount and script. This 32-byte digest is the message all signers must sign; compare it before going on.",
+                ],
+            ),
+            SendState::GenerateNonce { .. } => (
+                "Signing round 1: nonces",
+                &[
+                    "You drew two secret nonces d and e and published D = d·G and E = e·G. They stay on this machine for the session.",
+                    "Reusing a nonce for two different messages reveals your secret share.",
+                ],
+            ),
+            SendState::EnterNonces { .. } => (
+                "Binding the nonces",
+                &[
+                    "Each signer gets a binding factor ρ_i = H(i, message, all commitments), and the group nonce is R = Σ (D_i + ρ_i·E_i).",
+                    "Binding every nonce to the whole set blocks the parallel-session (ROS) attacks that break simpler multi-signatures.",
+                ],
+            ),
+            SendState::GenerateShare { .. } => (
+                "Signing round 2: your share",
+                &[
+                    "Your share is z_i = d_i + ρ_i·e_i + λ_i·s_i·c, where c = H(R, P, message) is the BIP340 challenge and λ_i your Lagrange coefficient.",
+                    "A share made with the same nonces for another message would leak s_i, so each session draws new ones.",
+                ],
+            ),
+            SendState::CombineShares { .. } => (
+                "Aggregation",
+                &[
+                    "Each share is checked against its signer's commitments, then z = Σ z_i. The pair (R, z) is an ordinary BIP340 signature: the network cannot tell it came from a threshold.",
+                ],
+            ),
+            SendState::Complete { .. } => (
+                "Broadcast",
+                &["The signed transaction is on the network. It spends like any single-key Taproot output."],
+            ),
+        },
+        _ => return None,
+    };
+    Some(Lesson { title, lines })
+}
@@ -2,20 +2,27 @@
 //!
 //! Provides an interactive terminal interface for:
 //! - Viewing and managing DKG wallets
+//! - Searching, sorting and grouping the wallet list
 //! - Chain/network selection (Testnet, Signet, Mainnet)
 //! - Keygen wizard for creating new wallets
 //! - Reshare wizard for resharing existing wallets
 //! - Send wizard for threshold signing transactions
+//! - A learn panel explaining each wizard step
 
 pub mod app;
 pub mod components;
+pub mod keymap;
+pub mod lessons;
+pub mod mouse;
 pub mod screens;
 pub mod state;
+pub mod wallet_view;
 
 use anyhow::Result;
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
     },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -29,18 +36,29 @@ use ratatui::{
     Frame, Terminal,
 };
 use std::io;
+use std::path::Path;
+use std::time::Duration;
 
 use app::App;
+use components::{TextArea, TextInput};
+use keymap::{Action, KeyContext};
+use mouse::Target;
 use state::{
-    AddressListState, AppState, KeygenState, MnemonicState, ReshareState, SendState, WalletAction,
-    WalletDetailsState,
+    AddressListState, AppState, DecodeTxState, KeygenState, MnemonicState, ReshareState, SendState,
+    WalletAction, WalletDetailsState,
 };
+use wallet_view::HomeRow;
 
-use frostdao::protocol::{keygen, reshare, signing};
+use frostdao::btc::amount;
+use frostdao::protocol::payload::read_payload_files;
+use frostdao::protocol::{keygen, reshare, signing, wallet};
+use frostdao::storage::lock::WalletLock;
 use frostdao::storage::{FileStorage, Storage};
 
-/// Run the terminal UI
-pub fn run_tui() -> Result<()> {
+/// Run the terminal UI, refreshing all balances every `refresh_secs`
+/// seconds in the background (0 turns that off); `notify` turns on desktop
+/// notifications for confirmed transactions
+pub fn run_tui(refresh_secs: u64, notify: bool) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -49,7 +67,7 @@ pub fn run_tui() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app and run
-    let mut app = App::new()?;
+    let mut app = App::new(refresh_secs, notify)?;
     let res = run_app(&mut terminal, &mut app);
 
     // Restore terminal
@@ -64,56 +82,351 @@ pub fn run_tui() -> Result<()> {
     if let Err(err) = res {
         println!("Error: {:?}", err);
     }
+    if frostdao::storage::ephemeral::is_ephemeral() {
+        println!("🫥 Ephemeral session over: its wallets and keys are gone.");
+    }
 
     Ok(())
 }
 
 fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     loop {
+        if !matches!(app.state, AppState::Send(_)) {
+            app.wallet_lock = None;
+        }
+        app.poll_tx_watchers();
+        app.poll_connectivity();
+        app.poll_balance_refresher();
+        app.poll_rebroadcaster();
+        if let Some((text, _)) = screen_output(&app.state) {
+            app.viewer.show(text);
+        }
         terminal.draw(|f| ui(f, app))?;
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                // Global quit
-                if matches!(key.code, KeyCode::Char('q')) && matches!(app.state, AppState::Home) {
-                    return Ok(());
+        // Wake up periodically so background watcher updates get rendered
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                handle_mouse(app, mouse);
+                continue;
+            }
+            _ => continue,
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        // Any key closes the help overlay
+        if app.show_help {
+            app.show_help = false;
+            continue;
+        }
+
+        // Keys go to the import prompt while it is open
+        if app.import_path.is_some() {
+            handle_import_keys(app, key);
+            continue;
+        }
+
+        // Typing a wallet search on the home screen
+        if matches!(app.state, AppState::Home) && app.home.searching {
+            handle_home_search_keys(app, key);
+            continue;
+        }
+
+        let context = KeyContext::for_state(&app.state);
+        if app.keymap.is_help(context, key.code) {
+            app.show_help = true;
+            continue;
+        }
+
+        // e (where nothing is being typed) or F2 toggles the learn panel
+        if lessons::for_state(&app.state).is_some()
+            && (key.code == KeyCode::F(2) || (key.code == KeyCode::Char('e') && !is_typing(app)))
+        {
+            app.learn = !app.learn;
+            continue;
+        }
+
+        // Ctrl+O loads a paste area from a file or folder
+        if key.code == KeyCode::Char('o')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && active_text_area(app).is_some()
+        {
+            app.import_path = Some(TextInput::new("Path"));
+            continue;
+        }
+
+        // Scrolling, search and save on output screens
+        if let Some((_, step)) = screen_output(&app.state) {
+            if app.viewer.handle_key(key, step) {
+                continue;
+            }
+        }
+
+        // Global quit
+        if context == Some(KeyContext::Home)
+            && app.keymap.action(KeyContext::Home, key.code) == Some(Action::Quit)
+        {
+            app.save_home_view();
+            return Ok(());
+        }
+
+        match &app.state {
+            AppState::Home => handle_home_keys(app, key.code),
+            AppState::WalletDetails(_) => handle_wallet_details_keys(app, key.code),
+            AppState::ChainSelect => handle_chain_select_keys(app, key.code),
+            AppState::Keygen(_) => handle_keygen_keys(app, key),
+            AppState::Reshare(_) => handle_reshare_keys(app, key),
+            AppState::Send(_) => handle_send_keys(app, key),
+            AppState::AddressList(_) => handle_address_list_keys(app, key.code),
+            AppState::MnemonicBackup(_) => handle_mnemonic_keys(app, key.code),
+            AppState::DecodeTx(_) => handle_decode_tx_keys(app, key),
+        }
+    }
+}
+
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    // Any click closes the help overlay
+    if app.show_help {
+        if matches!(mouse.kind, MouseEventKind::Down(_)) {
+            app.show_help = false;
+        }
+        return;
+    }
+
+    let hit = app.mouse.hit(mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some((target, index)) = hit {
+                handle_click(app, target, index);
+            }
+        }
+        MouseEventKind::ScrollDown => handle_scroll(app, hit.map(|(target, _)| target), 1),
+        MouseEventKind::ScrollUp => handle_scroll(app, hit.map(|(target, _)| target), -1),
+        _ => {}
+    }
+}
+
+/// Click-to-select; a party checkbox is toggled like Space
+fn handle_click(app: &mut App, target: Target, index: usize) {
+    let context = KeyContext::for_state(&app.state);
+    match target {
+        Target::WalletList if context == Some(KeyContext::Home) => {
+            // Headings aren't selectable
+            if let Some(HomeRow::Wallet(i)) = app.home_rows().get(index) {
+                app.wallet_list_state.select(Some(*i));
+            }
+        }
+        Target::WalletActions if context == Some(KeyContext::WalletDetails) => {
+            if let AppState::WalletDetails(ref mut s) = app.state {
+                if index < WalletAction::all().len() {
+                    s.selected_action = index;
+                }
+            }
+        }
+        Target::AddressList => {
+            if let AppState::AddressList(ref mut state) = app.state {
+                if index < state.addresses.len() {
+                    state.selected = index;
                 }
+            }
+        }
+        Target::Parties
+            if matches!(app.state, AppState::Send(SendState::SelectSigners { .. }))
+                && index < app.send_form.selected_parties.len() =>
+        {
+            app.send_form.party_selector_index = index;
+            app.send_form.toggle_party(index);
+        }
+        _ => {}
+    }
+}
+
+/// Wheel over a panel or list scrolls it; elsewhere it scrolls the paste area
+fn handle_scroll(app: &mut App, target: Option<Target>, delta: isize) {
+    fn scrolled(position: usize, delta: isize, len: usize) -> usize {
+        position
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1))
+    }
+
+    match target {
+        Some(Target::Utxos) => {
+            let form = &mut app.send_form;
+            form.utxo_scroll = scrolled(form.utxo_scroll, delta, form.utxos.len());
+        }
+        Some(Target::Transactions) => {
+            let form = &mut app.send_form;
+            form.tx_scroll = scrolled(form.tx_scroll, delta, form.recent_txs.len());
+        }
+        Some(Target::WalletList) if matches!(app.state, AppState::Home) => {
+            if delta > 0 {
+                app.next_wallet();
+            } else {
+                app.prev_wallet();
+            }
+        }
+        Some(Target::AddressList) => {
+            if let AppState::AddressList(ref mut state) = app.state {
+                state.selected = scrolled(state.selected, delta, state.addresses.len());
+            }
+        }
+        _ => {
+            if let Some(text_area) = active_text_area(app) {
+                text_area.scroll(delta * 3);
+            } else if screen_output(&app.state).is_some() {
+                app.viewer.scroll_by(delta * 3);
+            }
+        }
+    }
+}
+
+/// Incremental wallet search: the list filters as the query is typed
+fn handle_home_search_keys(app: &mut App, key: KeyEvent) {
+    match key.code {
+        // Esc drops the filter, Enter keeps it
+        KeyCode::Esc => {
+            app.home.query.clear();
+            app.home.searching = false;
+        }
+        KeyCode::Enter => app.home.searching = false,
+        KeyCode::Up => app.prev_wallet(),
+        KeyCode::Down => app.next_wallet(),
+        KeyCode::Backspace => {
+            app.home.query.pop();
+        }
+        KeyCode::Char(c) => app.home.query.push(c),
+        _ => {}
+    }
+    app.fix_home_selection();
+}
 
-                match &app.state {
-                    AppState::Home => handle_home_keys(app, key.code),
-                    AppState::WalletDetails(_) => handle_wallet_details_keys(app, key.code),
-                    AppState::ChainSelect => handle_chain_select_keys(app, key.code),
-                    AppState::Keygen(_) => handle_keygen_keys(app, key),
-                    AppState::Reshare(_) => handle_reshare_keys(app, key),
-                    AppState::Send(_) => handle_send_keys(app, key),
-                    AppState::AddressList(_) => handle_address_list_keys(app, key.code),
-                    AppState::MnemonicBackup(_) => handle_mnemonic_keys(app, key.code),
+/// Path prompt opened with Ctrl+O; Enter appends the payloads to the paste area
+fn handle_import_keys(app: &mut App, key: KeyEvent) {
+    let Some(input) = app.import_path.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Esc => app.import_path = None,
+        KeyCode::Enter => {
+            let path = input.value().trim().to_string();
+            if path.is_empty() {
+                return;
+            }
+            app.import_path = None;
+            match read_payload_files(std::path::Path::new(&path)) {
+                Ok(text) => {
+                    if let Some(text_area) = active_text_area(app) {
+                        if !text_area.is_empty() {
+                            text_area.handle_paste("\n");
+                        }
+                        text_area.handle_paste(text.trim());
+                    }
+                    app.set_message(&format!("Loaded {}", path));
                 }
+                Err(e) => app.set_message(&format!("Import failed: {:#}", e)),
             }
         }
+        _ => {
+            input.handle_key(key);
+        }
+    }
+}
+
+/// The output shown by the current wizard step, with a step name for saving it
+fn screen_output(state: &AppState) -> Option<(&str, &'static str)> {
+    match state {
+        AppState::Keygen(KeygenState::Round1Output { output_json }) => {
+            Some((output_json, "keygen-round1"))
+        }
+        AppState::Keygen(KeygenState::Round2Output { output_json }) => {
+            Some((output_json, "keygen-round2"))
+        }
+        AppState::Reshare(ReshareState::Round1Output { output_json }) => {
+            Some((output_json, "reshare-round1"))
+        }
+        AppState::Send(SendState::ShowSighash { sighash, .. }) => Some((sighash, "sighash")),
+        AppState::Send(SendState::GenerateNonce { nonce_output, .. }) => {
+            Some((nonce_output, "nonce"))
+        }
+        AppState::Send(SendState::GenerateShare { share_output, .. }) => {
+            Some((share_output, "share"))
+        }
+        AppState::DecodeTx(DecodeTxState {
+            output: Some(output),
+            ..
+        }) => Some((output, "tx-decode")),
+        _ => None,
+    }
+}
+
+/// Whether the current wizard step takes typed text
+fn is_typing(app: &mut App) -> bool {
+    matches!(
+        app.state,
+        AppState::Keygen(KeygenState::ParamsSetup)
+            | AppState::Reshare(ReshareState::Round1Setup)
+            | AppState::Send(SendState::ConfigureScript { .. })
+            | AppState::Send(SendState::EnterDetails { .. })
+    ) || active_text_area(app).is_some()
+}
+
+/// The paste area of the current wizard step, if it has one
+fn active_text_area(app: &mut App) -> Option<&mut TextArea> {
+    match &app.state {
+        AppState::Keygen(KeygenState::Round2Input) => Some(&mut app.keygen_form.round2_input),
+        AppState::Keygen(KeygenState::FinalizeInput) => Some(&mut app.keygen_form.finalize_input),
+        AppState::Reshare(ReshareState::FinalizeInput) => {
+            Some(&mut app.reshare_form.finalize_input)
+        }
+        AppState::Send(SendState::EnterNonces { .. }) => Some(&mut app.send_form.nonces_input),
+        AppState::Send(SendState::CombineShares { .. }) => Some(&mut app.send_form.shares_input),
+        AppState::DecodeTx(DecodeTxState { output: None, .. }) => Some(&mut app.decode_input),
+        _ => None,
     }
 }
 
 fn handle_home_keys(app: &mut App, code: KeyCode) {
-    match code {
-        KeyCode::Down | KeyCode::Char('j') => app.next_wallet(),
-        KeyCode::Up | KeyCode::Char('k') => app.prev_wallet(),
-        KeyCode::Enter => {
+    let Some(action) = app.keymap.action(KeyContext::Home, code) else {
+        return;
+    };
+    match action {
+        Action::Down => app.next_wallet(),
+        Action::Up => app.prev_wallet(),
+        Action::Search => {
+            app.home.searching = true;
+        }
+        Action::Sort => {
+            app.home.sort = app.home.sort.next();
+            app.save_home_view();
+            app.set_message(&format!("Sorted by {}", app.home.sort.label()));
+        }
+        Action::Group => {
+            app.home.group_by_network = !app.home.group_by_network;
+            app.save_home_view();
+        }
+        Action::Select => {
+            app.mark_wallet_used();
             // Go to wallet details
             if let Some(wallet) = app.selected_wallet() {
                 app.state = AppState::WalletDetails(WalletDetailsState {
                     wallet_name: wallet.name.clone(),
                     selected_action: 0,
-                    confirm_delete: false,
                     show_qr: false,
+                    ..Default::default()
                 });
             } else {
                 app.set_message("No wallet selected");
             }
         }
-        KeyCode::Char('r') => app.refresh_balance(),
-        KeyCode::Char('R') => app.reload_wallets(),
-        KeyCode::Char('n') => {
+        Action::Refresh => app.refresh_balance(),
+        Action::Reload => app.reload_wallets(),
+        Action::Network => {
             app.chain_selector_index = match app.network {
                 state::NetworkSelection::Testnet => 0,
                 state::NetworkSelection::Signet => 1,
@@ -121,11 +434,11 @@ fn handle_home_keys(app: &mut App, code: KeyCode) {
             };
             app.state = AppState::ChainSelect;
         }
-        KeyCode::Char('g') => {
+        Action::NewWallet => {
             // Keygen wizard (will be implemented in Commit 3)
             app.state = AppState::Keygen(state::KeygenState::default());
         }
-        KeyCode::Char('h') => {
+        Action::Reshare => {
             // Reshare wizard (will be implemented in Commit 4)
             if app.selected_wallet().is_some() {
                 app.state = AppState::Reshare(state::ReshareState::default());
@@ -133,15 +446,18 @@ fn handle_home_keys(app: &mut App, code: KeyCode) {
                 app.set_message("Select a wallet first to reshare");
             }
         }
-        KeyCode::Char('s') => {
+        Action::Send => {
             // Send wizard (will be implemented in Commit 5)
             if app.selected_wallet().is_some() {
+                if !app.require_online("Sending") {
+                    return;
+                }
                 app.state = AppState::Send(state::SendState::default());
             } else {
                 app.set_message("Select a wallet first to send");
             }
         }
-        KeyCode::Char('a') => {
+        Action::Addresses => {
             // HD Address list
             if let Some(wallet) = app.selected_wallet() {
                 let wallet_name = wallet.name.clone();
@@ -159,22 +475,13 @@ fn handle_home_keys(app: &mut App, code: KeyCode) {
                 app.set_message("Select a wallet first to view addresses");
             }
         }
-        KeyCode::Char('m') => {
+        Action::Mnemonic => {
             // Mnemonic backup
             if let Some(wallet) = app.selected_wallet() {
                 let wallet_name = wallet.name.clone();
                 let state_dir = keygen::get_state_dir(&wallet_name);
 
-                // Scan for available party folders
-                let mut available_parties = Vec::new();
-                for i in 1..=10 {
-                    // Check up to 10 parties
-                    let party_dir = format!("{}/party{}", state_dir, i);
-                    let share_path = format!("{}/paired_secret_share.bin", party_dir);
-                    if std::path::Path::new(&share_path).exists() {
-                        available_parties.push(i);
-                    }
-                }
+                let available_parties = keygen::local_party_indices(&wallet_name);
 
                 // Check for legacy structure (share directly in wallet folder)
                 let legacy_share_path = format!("{}/paired_secret_share.bin", state_dir);
@@ -208,7 +515,7 @@ fn handle_home_keys(app: &mut App, code: KeyCode) {
                 app.set_message("Select a wallet first to backup");
             }
         }
-        KeyCode::Char('c') => {
+        Action::Copy => {
             // Copy wallet address
             let addr = app.selected_wallet().and_then(|w| w.address.clone());
             if let Some(addr) = addr {
@@ -222,11 +529,11 @@ fn handle_home_keys(app: &mut App, code: KeyCode) {
 }
 
 fn handle_chain_select_keys(app: &mut App, code: KeyCode) {
-    match code {
-        KeyCode::Up | KeyCode::Char('k') => app.prev_network(),
-        KeyCode::Down | KeyCode::Char('j') => app.next_network(),
-        KeyCode::Enter => app.confirm_network(),
-        KeyCode::Esc => app.state = AppState::Home,
+    match app.keymap.action(KeyContext::ChainSelect, code) {
+        Some(Action::Up) => app.prev_network(),
+        Some(Action::Down) => app.next_network(),
+        Some(Action::Select) => app.confirm_network(),
+        Some(Action::Back) => app.state = AppState::Home,
         _ => {}
     }
 }
@@ -251,31 +558,162 @@ fn handle_wallet_details_keys(app: &mut App, code: KeyCode) {
         return;
     }
 
-    // Handle confirm delete mode
-    if state.confirm_delete {
+    // Handle notes editing
+    if let Some(form) = &state.notes_form {
+        let mut form = form.clone();
+        let field_count = form.fields.len();
+        match code {
+            KeyCode::Tab | KeyCode::Down => form.focus = (form.focus + 1) % field_count,
+            KeyCode::BackTab | KeyCode::Up => {
+                form.focus = (form.focus + field_count - 1) % field_count
+            }
+            KeyCode::Char(c) => form.fields[form.focus].push(c),
+            KeyCode::Backspace => {
+                form.fields[form.focus].pop();
+            }
+            KeyCode::Enter => {
+                let [description, contacts, created, tags] = &form.fields;
+                let saved = wallet::WalletNotes::from_text(description, contacts, created, tags)
+                    .and_then(|notes| {
+                        let storage = FileStorage::new(&keygen::get_state_dir(&state.wallet_name))?;
+                        notes.save(&storage)
+                    });
+                match saved {
+                    Ok(()) => {
+                        app.reload_wallets();
+                        app.set_message("Notes saved");
+                        if let AppState::WalletDetails(ref mut s) = app.state {
+                            s.notes_form = None;
+                        }
+                        return;
+                    }
+                    Err(e) => form.error = Some(e.to_string()),
+                }
+            }
+            KeyCode::Esc => {
+                if let AppState::WalletDetails(ref mut s) = app.state {
+                    s.notes_form = None;
+                }
+                return;
+            }
+            _ => {}
+        }
+        if let AppState::WalletDetails(ref mut s) = app.state {
+            s.notes_form = Some(form);
+        }
+        return;
+    }
+
+    // Handle rename mode: type the new name
+    if let Some(new_name) = &state.rename_input {
+        match code {
+            KeyCode::Char(c) => {
+                if let AppState::WalletDetails(ref mut s) = app.state {
+                    s.rename_input.get_or_insert_with(String::new).push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let AppState::WalletDetails(ref mut s) = app.state {
+                    if let Some(input) = s.rename_input.as_mut() {
+                        input.pop();
+                    }
+                }
+            }
+            KeyCode::Enter => {
+                let root = std::path::Path::new(wallet::STATE_ROOT);
+                match wallet::rename_core(root, &state.wallet_name, new_name) {
+                    Ok(_) => {
+                        app.reload_wallets();
+                        app.set_message(&format!(
+                            "Wallet '{}' renamed to '{}'",
+                            state.wallet_name, new_name
+                        ));
+                        if let AppState::WalletDetails(ref mut s) = app.state {
+                            s.wallet_name = new_name.clone();
+                            s.rename_input = None;
+                        }
+                    }
+                    Err(e) => app.set_message(&format!("Rename failed: {}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                if let AppState::WalletDetails(ref mut s) = app.state {
+                    s.rename_input = None;
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    // Handle confirm archive mode
+    if state.confirm_archive {
         match code {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
-                // Actually delete the wallet
-                let wallet_name = state.wallet_name.clone();
-                let state_dir = keygen::get_state_dir(&wallet_name);
-                match std::fs::remove_dir_all(&state_dir) {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                let root = std::path::Path::new(wallet::STATE_ROOT);
+                match wallet::archive_core(root, &state.wallet_name, now) {
+                    Ok(result) => {
+                        app.reload_wallets();
+                        app.set_message(&format!("Wallet archived to {}", result.result));
+                        app.state = AppState::Home;
+                    }
+                    Err(e) => {
+                        app.set_message(&format!("Failed to archive: {}", e));
+                        if let AppState::WalletDetails(ref mut s) = app.state {
+                            s.confirm_archive = false;
+                        }
+                    }
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                if let AppState::WalletDetails(ref mut s) = app.state {
+                    s.confirm_archive = false;
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    // Handle confirm delete mode: type the wallet name, like dkg-delete --confirm-name
+    if state.confirm_delete {
+        match code {
+            KeyCode::Char(c) => {
+                if let AppState::WalletDetails(ref mut s) = app.state {
+                    s.confirm_input.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let AppState::WalletDetails(ref mut s) = app.state {
+                    s.confirm_input.pop();
+                }
+            }
+            KeyCode::Enter => {
+                let root = std::path::Path::new(wallet::STATE_ROOT);
+                match wallet::delete_core(root, &state.wallet_name, &state.confirm_input) {
                     Ok(_) => {
-                        app.set_message(&format!("Wallet '{}' deleted", wallet_name));
                         app.reload_wallets();
+                        app.set_message(&format!("Wallet '{}' deleted", state.wallet_name));
                         app.state = AppState::Home;
                     }
                     Err(e) => {
                         app.set_message(&format!("Failed to delete: {}", e));
                         if let AppState::WalletDetails(ref mut s) = app.state {
                             s.confirm_delete = false;
+                            s.confirm_input.clear();
                         }
                     }
                 }
             }
-            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            KeyCode::Esc => {
                 // Cancel delete
                 if let AppState::WalletDetails(ref mut s) = app.state {
                     s.confirm_delete = false;
+                    s.confirm_input.clear();
                 }
             }
             _ => {}
@@ -286,11 +724,11 @@ fn handle_wallet_details_keys(app: &mut App, code: KeyCode) {
     let actions = WalletAction::all();
     let action_count = actions.len();
 
-    match code {
-        KeyCode::Esc => {
+    match app.keymap.action(KeyContext::WalletDetails, code) {
+        Some(Action::Back) => {
             app.state = AppState::Home;
         }
-        KeyCode::Up | KeyCode::Char('k') => {
+        Some(Action::Up) => {
             if let AppState::WalletDetails(ref mut s) = app.state {
                 if s.selected_action > 0 {
                     s.selected_action -= 1;
@@ -299,31 +737,26 @@ fn handle_wallet_details_keys(app: &mut App, code: KeyCode) {
                 }
             }
         }
-        KeyCode::Down | KeyCode::Char('j') => {
+        Some(Action::Down) => {
             if let AppState::WalletDetails(ref mut s) = app.state {
                 s.selected_action = (s.selected_action + 1) % action_count;
             }
         }
-        KeyCode::Enter => {
+        Some(Action::Select) => {
             let selected_action = actions[state.selected_action];
             let wallet_name = state.wallet_name.clone();
 
             match selected_action {
                 WalletAction::Send => {
+                    if !app.require_online("Sending") {
+                        return;
+                    }
                     // Go to send flow with wallet pre-selected
                     app.send_form = screens::SendFormData::new();
-                    // Find wallet index
-                    if let Some(idx) = app.wallets.iter().position(|w| w.name == wallet_name) {
-                        app.send_form.wallet_index = idx;
-                        // Load party info
-                        if let Some(wallet) = app.wallets.get(idx) {
-                            app.send_form.threshold = wallet.threshold.unwrap_or(2);
-                            app.send_form.total_parties = wallet.total_parties.unwrap_or(3);
-                            app.send_form.selected_parties =
-                                vec![true; wallet.total_parties.unwrap_or(3) as usize];
-                        }
+                    let idx = app.wallets.iter().position(|w| w.name == wallet_name);
+                    if let Err(e) = select_signing_wallet(app, idx.unwrap_or(usize::MAX)) {
+                        app.set_message(&e);
                     }
-                    app.state = AppState::Send(SendState::SelectSigners { wallet_name });
                 }
                 WalletAction::ViewAddresses => {
                     app.state = AppState::AddressList(AddressListState {
@@ -336,18 +769,17 @@ fn handle_wallet_details_keys(app: &mut App, code: KeyCode) {
                     });
                     app.load_hd_addresses(&wallet_name);
                 }
+                WalletAction::DecodeTx => {
+                    app.decode_input.clear();
+                    app.state = AppState::DecodeTx(DecodeTxState {
+                        wallet_name,
+                        ..Default::default()
+                    });
+                }
                 WalletAction::BackupMnemonic => {
                     let state_dir = keygen::get_state_dir(&wallet_name);
 
-                    // Scan for available party folders
-                    let mut available_parties = Vec::new();
-                    for i in 1..=10 {
-                        let party_dir = format!("{}/party{}", state_dir, i);
-                        let share_path = format!("{}/paired_secret_share.bin", party_dir);
-                        if std::path::Path::new(&share_path).exists() {
-                            available_parties.push(i);
-                        }
-                    }
+                    let available_parties = keygen::local_party_indices(&wallet_name);
 
                     // Check for legacy structure
                     let legacy_share_path = format!("{}/paired_secret_share.bin", state_dir);
@@ -381,15 +813,37 @@ fn handle_wallet_details_keys(app: &mut App, code: KeyCode) {
                 WalletAction::Reshare => {
                     app.state = AppState::Reshare(ReshareState::default());
                 }
+                WalletAction::EditNotes => {
+                    let notes = app
+                        .wallets
+                        .iter()
+                        .find(|w| w.name == wallet_name)
+                        .map(|w| w.notes.clone())
+                        .unwrap_or_default();
+                    if let AppState::WalletDetails(ref mut s) = app.state {
+                        s.notes_form = Some(state::NotesForm::from_notes(&notes));
+                    }
+                }
+                WalletAction::Rename => {
+                    if let AppState::WalletDetails(ref mut s) = app.state {
+                        s.rename_input = Some(String::new());
+                    }
+                }
+                WalletAction::Archive => {
+                    if let AppState::WalletDetails(ref mut s) = app.state {
+                        s.confirm_archive = true;
+                    }
+                }
                 WalletAction::DeleteWallet => {
                     // Show confirmation dialog
                     if let AppState::WalletDetails(ref mut s) = app.state {
                         s.confirm_delete = true;
+                        s.confirm_input.clear();
                     }
                 }
             }
         }
-        KeyCode::Char('c') => {
+        Some(Action::Copy) => {
             // Copy wallet address to clipboard
             let addr_to_copy = app
                 .wallets
@@ -400,7 +854,7 @@ fn handle_wallet_details_keys(app: &mut App, code: KeyCode) {
                 app.copy_to_clipboard(&addr);
             }
         }
-        KeyCode::Char('b') => {
+        Some(Action::Balance) => {
             // Quick fetch balance
             let wallet_name = state.wallet_name.clone();
             if let Some(idx) = app.wallets.iter().position(|w| w.name == wallet_name) {
@@ -408,7 +862,7 @@ fn handle_wallet_details_keys(app: &mut App, code: KeyCode) {
                 app.refresh_balance();
             }
         }
-        KeyCode::Char('q') => {
+        Some(Action::Qr) => {
             // Show QR code popup
             if let AppState::WalletDetails(ref mut s) = app.state {
                 s.show_qr = true;
@@ -424,13 +878,17 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
     // Helper to get next field based on mode
     fn next_field(current: KeygenFormField, hierarchical: bool) -> KeygenFormField {
         match (current, hierarchical) {
-            // TSS mode: Name -> Threshold -> NParties -> Name
+            // TSS mode: Name -> Threshold -> NParties -> MyIndex -> Name
             (KeygenFormField::Name, false) => KeygenFormField::Threshold,
             (KeygenFormField::Threshold, false) => KeygenFormField::NParties,
-            (KeygenFormField::NParties, false) => KeygenFormField::Name,
-            // HTSS mode: Name -> NParties -> Name (skip Threshold)
+            (KeygenFormField::NParties, false) => KeygenFormField::MyIndex,
+            (KeygenFormField::MyIndex, false) => KeygenFormField::Name,
+            (KeygenFormField::Rank, false) => KeygenFormField::Name,
+            // HTSS mode: Name -> NParties -> MyIndex -> Rank -> Name (skip Threshold)
             (KeygenFormField::Name, true) => KeygenFormField::NParties,
-            (KeygenFormField::NParties, true) => KeygenFormField::Name,
+            (KeygenFormField::NParties, true) => KeygenFormField::MyIndex,
+            (KeygenFormField::MyIndex, true) => KeygenFormField::Rank,
+            (KeygenFormField::Rank, true) => KeygenFormField::Name,
             (KeygenFormField::Threshold, true) => KeygenFormField::NParties,
         }
     }
@@ -438,12 +896,16 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
     fn prev_field(current: KeygenFormField, hierarchical: bool) -> KeygenFormField {
         match (current, hierarchical) {
             // TSS mode
-            (KeygenFormField::Name, false) => KeygenFormField::NParties,
+            (KeygenFormField::Name, false) => KeygenFormField::MyIndex,
             (KeygenFormField::Threshold, false) => KeygenFormField::Name,
             (KeygenFormField::NParties, false) => KeygenFormField::Threshold,
+            (KeygenFormField::MyIndex, false) => KeygenFormField::NParties,
+            (KeygenFormField::Rank, false) => KeygenFormField::MyIndex,
             // HTSS mode
-            (KeygenFormField::Name, true) => KeygenFormField::NParties,
+            (KeygenFormField::Name, true) => KeygenFormField::Rank,
             (KeygenFormField::NParties, true) => KeygenFormField::Name,
+            (KeygenFormField::MyIndex, true) => KeygenFormField::NParties,
+            (KeygenFormField::Rank, true) => KeygenFormField::MyIndex,
             (KeygenFormField::Threshold, true) => KeygenFormField::Name,
         }
     }
@@ -512,6 +974,31 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
                     return;
                 }
 
+                // One party of a multi-machine ceremony: run its Round 1 only
+                if let Some(my_index) = app.keygen_form.party_index() {
+                    if my_index == 0 || my_index > n_parties {
+                        app.keygen_form.error_message =
+                            Some(format!("Party index must be 1 to {}", n_parties));
+                        return;
+                    }
+                    let rank: u32 = if hierarchical {
+                        app.keygen_form.rank.value().parse().unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    match keygen_party_round1(app, &name, threshold, n_parties, my_index, rank) {
+                        Ok(output_json) => {
+                            app.keygen_form.round1_output = output_json.clone();
+                            app.keygen_form.error_message = None;
+                            app.state = AppState::Keygen(KeygenState::Round1Output { output_json });
+                        }
+                        Err(e) => {
+                            app.keygen_form.error_message = Some(format!("Error: {}", e));
+                        }
+                    }
+                    return;
+                }
+
                 // Generate all parties at once
                 let ranks = if hierarchical {
                     // Default ranks: 0, 1, 2, ...
@@ -546,6 +1033,14 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
                     KeygenFormField::NParties => {
                         app.keygen_form.n_parties.handle_key(key);
                     }
+                    KeygenFormField::MyIndex => {
+                        app.keygen_form.my_index.handle_key(key);
+                    }
+                    KeygenFormField::Rank => {
+                        if app.keygen_form.hierarchical {
+                            app.keygen_form.rank.handle_key(key);
+                        }
+                    }
                 }
             }
         },
@@ -555,8 +1050,10 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
                 app.state = AppState::Home;
             }
             KeyCode::Enter => {
+                app.keygen_form.show_qr = false;
                 app.state = AppState::Keygen(KeygenState::Round2Input);
             }
+            KeyCode::Char('r') => app.keygen_form.show_qr = !app.keygen_form.show_qr,
             _ => {}
         },
         AppState::Keygen(KeygenState::Round2Input) => match key.code {
@@ -575,20 +1072,34 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
                     return;
                 }
 
+                // Scanned QR frames and @scan-file imports decode to the same JSON
+                let data = match frostdao::qr::resolve_payload(&data) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        app.keygen_form.error_message = Some(format!("Scan error: {}", e));
+                        return;
+                    }
+                };
+
+                let mut reporter = app::TuiReporter::default();
                 let state_dir = keygen::get_state_dir(&name);
                 match FileStorage::new(&state_dir) {
-                    Ok(storage) => match keygen::round2_core(&data, &storage) {
-                        Ok(result) => {
-                            app.keygen_form.round2_output = result.result;
-                            app.keygen_form.error_message = None;
-                            app.state = AppState::Keygen(KeygenState::Round2Output {
-                                output_json: app.keygen_form.round2_output.clone(),
-                            });
-                        }
-                        Err(e) => {
-                            app.keygen_form.error_message = Some(format!("Error: {}", e));
+                    Ok(storage) => {
+                        match keygen::round2_core_with_reporter(&data, &storage, &mut reporter) {
+                            Ok(result) => {
+                                app.take_report(reporter);
+                                app.keygen_form.round2_output = result.result;
+                                app.keygen_form.round2_dm = false;
+                                app.keygen_form.error_message = None;
+                                app.state = AppState::Keygen(KeygenState::Round2Output {
+                                    output_json: app.keygen_form.round2_output.clone(),
+                                });
+                            }
+                            Err(e) => {
+                                app.keygen_form.error_message = Some(format!("Error: {}", e));
+                            }
                         }
-                    },
+                    }
                     Err(e) => {
                         app.keygen_form.error_message = Some(format!("Storage error: {}", e));
                     }
@@ -600,11 +1111,35 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
         },
         AppState::Keygen(KeygenState::Round2Output { .. }) => match key.code {
             KeyCode::Esc => {
+                app.keygen_form.show_qr = false;
                 app.state = AppState::Keygen(KeygenState::Round2Input);
             }
             KeyCode::Enter => {
+                app.keygen_form.show_qr = false;
                 app.state = AppState::Keygen(KeygenState::FinalizeInput);
             }
+            KeyCode::Char('r') => app.keygen_form.show_qr = !app.keygen_form.show_qr,
+            KeyCode::Char('d') => {
+                // Broadcast JSON, or one gift-wrapped DM per recipient
+                let output_json = if app.keygen_form.round2_dm {
+                    Ok(app.keygen_form.round2_output.clone())
+                } else {
+                    keygen_round2_dms(&app.keygen_form)
+                };
+                match output_json {
+                    Ok(output_json) => {
+                        app.keygen_form.round2_dm = !app.keygen_form.round2_dm;
+                        app.state = AppState::Keygen(KeygenState::Round2Output { output_json });
+                    }
+                    Err(e) => app.set_message(&format!("Nostr DMs unavailable: {}", e)),
+                }
+            }
+            KeyCode::Char('p') => match publish_round2_dms(&app.keygen_form) {
+                Ok(relays) => {
+                    app.set_message(&format!("Round 2 DMs published to {}", relays.join(", ")))
+                }
+                Err(e) => app.set_message(&format!("Publishing failed: {:#}", e)),
+            },
             _ => {}
         },
         AppState::Keygen(KeygenState::FinalizeInput) => match key.code {
@@ -623,11 +1158,21 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
                     return;
                 }
 
+                let data = match frostdao::qr::resolve_payload(&data) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        app.keygen_form.error_message = Some(format!("Scan error: {}", e));
+                        return;
+                    }
+                };
+
+                let mut reporter = app::TuiReporter::default();
                 let state_dir = keygen::get_state_dir(&name);
                 match FileStorage::new(&state_dir) {
                     Ok(storage) => {
-                        match keygen::finalize_core(&data, &storage) {
+                        match keygen::finalize_core_with_reporter(&data, &storage, &mut reporter) {
                             Ok(_) => {
+                                app.take_report(reporter);
                                 app.keygen_form.error_message = None;
                                 app.state = AppState::Keygen(KeygenState::Complete {
                                     wallet_name: name.clone(),
@@ -645,6 +1190,29 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
                     }
                 }
             }
+            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                match fetch_round2_dms(&app.keygen_form) {
+                    Ok(dms) if dms.is_empty() => {
+                        app.keygen_form.error_message =
+                            Some("No Round 2 DMs on the relays yet".to_string());
+                    }
+                    Ok(dms) => {
+                        let mut content = app.keygen_form.finalize_input.content();
+                        for dm in &dms {
+                            content.push('\n');
+                            content.push_str(dm);
+                        }
+                        app.keygen_form
+                            .finalize_input
+                            .set_content(content.trim_start());
+                        app.keygen_form.error_message = None;
+                        app.set_message(&format!("{} Round 2 DM(s) fetched", dms.len()));
+                    }
+                    Err(e) => {
+                        app.keygen_form.error_message = Some(format!("Relay error: {:#}", e));
+                    }
+                }
+            }
             _ => {
                 app.keygen_form.finalize_input.handle_key(key);
             }
@@ -660,6 +1228,75 @@ fn handle_keygen_keys(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Round 1 for this machine's party, in a fresh wallet folder
+fn keygen_party_round1(
+    app: &mut App,
+    name: &str,
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    rank: u32,
+) -> Result<String> {
+    // The CLI asks before overwriting; here the user picks another name
+    if keygen::wallet_exists(name) {
+        anyhow::bail!("Wallet '{}' already exists, choose another name", name);
+    }
+    let storage = FileStorage::new(&keygen::get_state_dir(name))?;
+    let mut reporter = app::TuiReporter::default();
+    let result = keygen::round1_core_with_reporter(
+        threshold,
+        n_parties,
+        my_index,
+        rank,
+        app.keygen_form.hierarchical,
+        &storage,
+        &mut reporter,
+    )?;
+    app.take_report(reporter);
+    Ok(result.result)
+}
+
+/// This party's Round 2 shares as NIP-17 DMs, one event per line
+fn keygen_round2_dms(form: &screens::KeygenFormData) -> Result<String> {
+    let storage = FileStorage::new(&keygen::get_state_dir(form.name.value()))?;
+    let output: keygen::Round2Output = serde_json::from_str(&form.round2_output)?;
+    let dms = frostdao::protocol::nostr::dm_round2_shares(&output, &storage)?;
+    let events = dms
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(events.join("\n"))
+}
+
+/// Publish this party's Round 2 DMs to the relays saved with relay-config
+fn publish_round2_dms(form: &screens::KeygenFormData) -> Result<Vec<String>> {
+    let storage = FileStorage::new(&keygen::get_state_dir(form.name.value()))?;
+    let output: keygen::Round2Output = serde_json::from_str(&form.round2_output)?;
+    let dms = frostdao::protocol::nostr::dm_round2_shares(&output, &storage)?;
+    let mut relays = configured_relays()?;
+    for dm in &dms {
+        relays.publish(dm)?;
+    }
+    Ok(relays.urls().iter().map(|url| url.to_string()).collect())
+}
+
+/// The Round 2 DMs for this party already on the saved relays, one event
+/// per entry; doesn't wait for ones still missing
+fn fetch_round2_dms(form: &screens::KeygenFormData) -> Result<Vec<String>> {
+    let storage = FileStorage::new(&keygen::get_state_dir(form.name.value()))?;
+    let mut relays = configured_relays()?;
+    keygen::fetch_round2_dms(&mut relays, &storage, std::time::Instant::now())?
+        .iter()
+        .map(|dm| Ok(serde_json::to_string(dm)?))
+        .collect()
+}
+
+fn configured_relays() -> Result<frostdao::protocol::relay::Relays<'static>> {
+    use frostdao::protocol::relay_client;
+    let urls = relay_client::resolve(true, &[])?.unwrap_or_default();
+    relay_client::connect_all(&urls)
+}
+
 fn handle_reshare_keys(app: &mut App, key: KeyEvent) {
     use screens::ReshareFormData;
     use state::{ReshareFinalizeField, ReshareFormField};
@@ -730,13 +1367,8 @@ fn handle_reshare_keys(app: &mut App, key: KeyEvent) {
                 let mut party_path: Option<(String, u32)> = None;
 
                 // Check for party folders first (new structure)
-                for i in 1..=10 {
-                    let party_dir = format!("{}/party{}", state_dir, i);
-                    let share_path = format!("{}/paired_secret_share.bin", party_dir);
-                    if std::path::Path::new(&share_path).exists() {
-                        party_path = Some((party_dir, i as u32));
-                        break;
-                    }
+                if let Some(&i) = keygen::local_party_indices(&wallet_name).first() {
+                    party_path = Some((format!("{}/party{}", state_dir, i), i));
                 }
 
                 // Check for legacy structure (share directly in wallet folder)
@@ -752,7 +1384,7 @@ fn handle_reshare_keys(app: &mut App, key: KeyEvent) {
                         match FileStorage::new(&path) {
                             Ok(storage) => {
                                 // Load paired secret share to get my old index
-                                match storage.read("paired_secret_share.bin") {
+                                match storage.read_secret("paired_secret_share.bin") {
                                     Ok(bytes) => {
                                         use schnorr_fun::frost::PairedSecretShare;
                                         use schnorr_fun::fun::marker::EvenY;
@@ -780,6 +1412,7 @@ fn handle_reshare_keys(app: &mut App, key: KeyEvent) {
                                             new_threshold,
                                             new_n_parties,
                                             my_old_index,
+                                            &[],
                                         ) {
                                             Ok(result) => {
                                                 app.reshare_form.round1_output = result.result;
@@ -897,6 +1530,7 @@ fn handle_reshare_keys(app: &mut App, key: KeyEvent) {
                     hierarchical,
                     &data,
                     false,
+                    false,
                 ) {
                     Ok(_) => {
                         app.reshare_form.error_message = None;
@@ -942,6 +1576,52 @@ fn handle_reshare_keys(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Start signer selection for `app.wallets[index]`, sized from its metadata
+/// (any committee size) with only this party ticked
+fn select_signing_wallet(app: &mut App, index: usize) -> Result<(), String> {
+    let Some(wallet) = app.wallets.get(index) else {
+        return Err("No wallets available".to_string());
+    };
+    let wallet_name = wallet.name.clone();
+    let (Some(threshold), Some(total_parties @ 1..)) = (wallet.threshold, wallet.total_parties)
+    else {
+        return Err(format!(
+            "Wallet '{}' has no threshold metadata; run `frostdao dkg-doctor --name {}`",
+            wallet_name, wallet_name
+        ));
+    };
+
+    let state_dir = keygen::get_state_dir(&wallet_name);
+    // Keep a CLI run from signing with (and consuming nonces of) this wallet meanwhile
+    app.wallet_lock = None;
+    let lock = WalletLock::acquire(Path::new(&state_dir), "tui", Duration::ZERO)
+        .map_err(|e| e.to_string())?;
+    let metadata = FileStorage::new(&state_dir)
+        .and_then(|storage| storage.read("htss_metadata.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<keygen::HtssMetadata>(&bytes).ok());
+    let my_index = metadata.as_ref().map_or(1, |m| m.my_index);
+
+    let form = &mut app.send_form;
+    form.wallet_index = index;
+    form.hierarchical = metadata.as_ref().is_some_and(|m| m.hierarchical);
+    form.party_ranks = metadata.map(|m| m.party_ranks).unwrap_or_default();
+    form.threshold = threshold;
+    form.total_parties = total_parties;
+    form.my_party_index = my_index;
+    form.selected_parties = vec![false; total_parties as usize];
+    // Auto-select self
+    if my_index > 0 && my_index <= total_parties {
+        form.selected_parties[(my_index - 1) as usize] = true;
+    }
+    form.party_selector_index = 0;
+    form.error_message = None;
+    app.wallet_lock = Some(lock);
+
+    app.state = AppState::Send(SendState::SelectSigners { wallet_name });
+    Ok(())
+}
+
 fn handle_send_keys(app: &mut App, key: KeyEvent) {
     use screens::SendFormData;
     use state::SendFormField;
@@ -961,52 +1641,18 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                 }
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                if !app.wallets.is_empty() {
-                    app.send_form.wallet_index =
-                        (app.send_form.wallet_index + 1) % app.wallets.len();
-                }
+                app.send_form.wallet_index = (app.send_form.wallet_index + 1)
+                    .checked_rem(app.wallets.len())
+                    .unwrap_or(0);
             }
             KeyCode::Enter => {
                 if app.wallets.is_empty() {
                     app.send_form.error_message = Some("No wallets available".to_string());
                     return;
                 }
-                let wallet = &app.wallets[app.send_form.wallet_index];
-                let wallet_name = wallet.name.clone();
-
-                // Load wallet info for party selection
-                let threshold = wallet.threshold.unwrap_or(2);
-                let total_parties = wallet.total_parties.unwrap_or(3);
-
-                // Load my party index from htss_metadata
-                let state_dir = keygen::get_state_dir(&wallet_name);
-                let my_index = if let Ok(storage) = FileStorage::new(&state_dir) {
-                    if let Ok(bytes) = storage.read("htss_metadata.json") {
-                        let json = String::from_utf8_lossy(&bytes);
-                        serde_json::from_str::<serde_json::Value>(&json)
-                            .ok()
-                            .and_then(|v| v.get("my_index").and_then(|i| i.as_u64()))
-                            .map(|i| i as u32)
-                            .unwrap_or(1)
-                    } else {
-                        1
-                    }
-                } else {
-                    1
-                };
-
-                // Initialize party selection
-                app.send_form.threshold = threshold;
-                app.send_form.total_parties = total_parties;
-                app.send_form.my_party_index = my_index;
-                app.send_form.selected_parties = vec![false; total_parties as usize];
-                // Auto-select self
-                if my_index > 0 && my_index <= total_parties {
-                    app.send_form.selected_parties[(my_index - 1) as usize] = true;
+                if let Err(e) = select_signing_wallet(app, app.send_form.wallet_index) {
+                    app.send_form.error_message = Some(e);
                 }
-                app.send_form.party_selector_index = 0;
-
-                app.state = AppState::Send(SendState::SelectSigners { wallet_name });
             }
             _ => {}
         },
@@ -1028,22 +1674,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
             KeyCode::Char(' ') => {
                 // Toggle party selection
                 let idx = app.send_form.party_selector_index;
-                if idx < app.send_form.selected_parties.len() {
-                    let currently_selected = app.send_form.selected_parties[idx];
-                    let selected_count = app.send_form.selected_count();
-
-                    // If trying to select and already at threshold, don't allow
-                    if !currently_selected && selected_count >= app.send_form.threshold as usize {
-                        app.send_form.error_message = Some(format!(
-                            "Cannot select more than {} parties",
-                            app.send_form.threshold
-                        ));
-                        return;
-                    }
-
-                    app.send_form.selected_parties[idx] = !currently_selected;
-                    app.send_form.error_message = None;
-                }
+                app.send_form.toggle_party(idx);
             }
             KeyCode::Enter => {
                 // Check if exactly threshold parties selected
@@ -1055,10 +1686,30 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                     ));
                     return;
                 }
+
+                if let Some(preview) = app.send_form.rank_preview() {
+                    if let Some(problem) = preview.problems.first() {
+                        app.send_form.error_message = Some(problem.clone());
+                        return;
+                    }
+                }
+
+                // Enforce the wallet's per-rank signing requirement, if any
+                let state_dir = keygen::get_state_dir(&wallet_name);
+                let signers = app.send_form.get_selected_indices();
+                let metadata = FileStorage::new(&state_dir)
+                    .and_then(|storage| storage.read("htss_metadata.json"))
+                    .ok()
+                    .and_then(|bytes| serde_json::from_slice::<keygen::HtssMetadata>(&bytes).ok());
+                if let Some(metadata) = metadata {
+                    if let Err(e) = metadata.check_signing_requirement(&signers) {
+                        app.send_form.error_message = Some(e.to_string());
+                        return;
+                    }
+                }
                 app.send_form.error_message = None;
 
                 // Load HD addresses for address selection
-                let state_dir = keygen::get_state_dir(&wallet_name);
                 let network = app.network.to_bitcoin_network();
                 let (hd_enabled, hd_addresses) = match FileStorage::new(&state_dir) {
                     Ok(storage) => {
@@ -1104,15 +1755,16 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                 });
             }
             KeyCode::Up | KeyCode::Char('k') => {
-                if app.send_form.use_hd_address {
-                    if app.send_form.hd_selected_index > 0 {
-                        app.send_form.hd_selected_index -= 1;
+                // If at root address and pressing up, do nothing
+                let form = &mut app.send_form;
+                if form.use_hd_address {
+                    if form.hd_selected_index > 0 {
+                        form.hd_selected_index -= 1;
                     } else {
                         // Wrap to root address
-                        app.send_form.use_hd_address = false;
+                        form.use_hd_address = false;
                     }
                 }
-                // If at root address and pressing up, do nothing
             }
             KeyCode::Down | KeyCode::Char('j') => {
                 if !app.send_form.use_hd_address {
@@ -1143,7 +1795,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                                 schnorr_fun::frost::SharedKey<schnorr_fun::fun::marker::EvenY>,
                             >(&bytes)
                             .ok()
-                            .map(|sk| {
+                            .and_then(|sk| {
                                 let pubkey_bytes: [u8; 32] = sk.public_key().to_xonly_bytes();
                                 let xonly =
                                     bitcoin::secp256k1::XOnlyPublicKey::from_slice(&pubkey_bytes)
@@ -1159,14 +1811,13 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                                     .to_string(),
                                 )
                             })
-                            .flatten()
                         })
                     })
                 };
 
                 // Fetch UTXOs and transactions for the source address
                 if let Some(addr) = source_address {
-                    app.fetch_utxos_for_send(&addr);
+                    app.fetch_utxos_for_send(&wallet_name, &addr);
                 }
 
                 // Reset script config for new transaction
@@ -1183,9 +1834,8 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                 });
             }
             KeyCode::Up | KeyCode::Char('k') => {
-                if app.send_form.script_config.selected_index > 0 {
-                    app.send_form.script_config.selected_index -= 1;
-                }
+                let selected = &mut app.send_form.script_config.selected_index;
+                *selected = selected.saturating_sub(1);
             }
             KeyCode::Down | KeyCode::Char('j') => {
                 let max = crate::tui::screens::ScriptType::all().len();
@@ -1194,11 +1844,10 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                 }
             }
             KeyCode::Char(' ') => {
-                // Toggle/select script type
+                // Add/remove script type; several share one tree
                 let types = crate::tui::screens::ScriptType::all();
                 if let Some(selected) = types.get(app.send_form.script_config.selected_index) {
-                    app.send_form.script_config.script_type = selected.clone();
-                    app.send_form.script_config.focused_field = 0;
+                    app.send_form.script_config.toggle(selected);
                 }
             }
             KeyCode::Tab => {
@@ -1208,7 +1857,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                     ScriptType::None => 0,
                     ScriptType::TimelockAbsolute | ScriptType::TimelockRelative => 1,
                     ScriptType::Recovery => 2,
-                    ScriptType::HTLC => 3,
+                    ScriptType::Htlc => 3,
                 };
                 if max_fields > 0 {
                     app.send_form.script_config.focused_field =
@@ -1220,15 +1869,11 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                 use crate::tui::screens::ScriptType;
                 let config = &mut app.send_form.script_config;
                 match &config.script_type {
-                    ScriptType::TimelockAbsolute => {
-                        if config.focused_field == 0 {
-                            config.timelock_height.handle_key(key);
-                        }
+                    ScriptType::TimelockAbsolute if config.focused_field == 0 => {
+                        config.timelock_height.handle_key(key);
                     }
-                    ScriptType::TimelockRelative => {
-                        if config.focused_field == 0 {
-                            config.timelock_blocks.handle_key(key);
-                        }
+                    ScriptType::TimelockRelative if config.focused_field == 0 => {
+                        config.timelock_blocks.handle_key(key);
                     }
                     ScriptType::Recovery => match config.focused_field {
                         0 => {
@@ -1239,7 +1884,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                         }
                         _ => {}
                     },
-                    ScriptType::HTLC => match config.focused_field {
+                    ScriptType::Htlc => match config.focused_field {
                         0 => {
                             config.htlc_hash.handle_key(key);
                         }
@@ -1274,14 +1919,15 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
             }
             KeyCode::Enter => {
                 let to_addr = app.send_form.to_address.value().to_string();
-                let amount: u64 = app.send_form.amount.value().parse().unwrap_or(0);
+                let amount: u64 = app.send_form.amount_sats().unwrap_or(0);
 
                 if to_addr.is_empty() {
                     app.send_form.error_message = Some("Enter destination address".to_string());
                     return;
                 }
                 if amount == 0 {
-                    app.send_form.error_message = Some("Enter valid amount".to_string());
+                    app.send_form.error_message =
+                        Some("Enter an amount in sats, or e.g. 50USD".to_string());
                     return;
                 }
 
@@ -1313,6 +1959,13 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                 // Get derivation path if HD address selected
                 let derivation_path = app.send_form.get_derivation_path();
 
+                // Fetching UTXOs and broadcasting both need the backend
+                if app.offline {
+                    app.send_form.error_message =
+                        Some("Offline: sending is disabled until the backend is reachable".into());
+                    return;
+                }
+
                 // Call automated FROST signing
                 match frostdao::protocol::dkg_tx::frost_sign_all_local(
                     &wallet_name,
@@ -1322,6 +1975,8 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                     derivation_path,
                     None, // Use default fee rate
                     network,
+                    false, // Never spend inscribed UTXOs from the TUI
+                    Some(app.send_form.memo.value()),
                 ) {
                     Ok(result) => {
                         app.send_form.error_message = None;
@@ -1333,8 +1988,17 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                         } else {
                             result.result.clone()
                         };
+                        if txid.len() == 64 {
+                            app.watch_transaction(&wallet_name, &txid, -(amount as i64));
+                        }
                         app.state = AppState::Send(SendState::Complete { txid });
                     }
+                    Err(e) if frostdao::btc::esplora::is_unreachable(&e) => {
+                        app.go_offline();
+                        app.send_form.error_message = Some(
+                            "Offline: lost the backend. Check recent txs before retrying.".into(),
+                        );
+                    }
                     Err(e) => {
                         app.send_form.error_message = Some(format!("Error: {}", e));
                     }
@@ -1349,6 +2013,9 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                     // Recalculate fee estimate when amount changes
                     app.send_form.estimate_fee();
                 }
+                SendFormField::Memo => {
+                    app.send_form.memo.handle_key(key);
+                }
             },
         },
         AppState::Send(SendState::ShowSighash {
@@ -1364,10 +2031,23 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
             }
             KeyCode::Enter => {
                 // Generate nonce
+                let mut reporter = app::TuiReporter::default();
                 let state_dir = keygen::get_state_dir(&wallet_name);
                 match FileStorage::new(&state_dir) {
-                    Ok(storage) => match signing::generate_nonce_core(&session_id, &storage) {
+                    Ok(storage) => match signing::generate_nonce_core_with_reporter(
+                        &session_id,
+                        &storage,
+                        &mut reporter,
+                    ) {
                         Ok(result) => {
+                            app.take_report(reporter);
+                            if app.send_form.session_id != session_id {
+                                app.send_form.session_id = session_id.clone();
+                                app.send_form.session_created_at = std::time::SystemTime::now()
+                                    .duration_since(std::time::UNIX_EPOCH)
+                                    .map(|d| d.as_secs())
+                                    .unwrap_or(0);
+                            }
                             app.send_form.nonce_output = result.result.clone();
                             app.state = AppState::Send(SendState::GenerateNonce {
                                 wallet_name,
@@ -1405,6 +2085,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                 app.send_form.nonces_input =
                     crate::tui::components::TextArea::new("Paste nonces from other parties");
                 app.send_form.nonces_input.handle_paste(&nonce_output);
+                app.send_form.refresh_board(&wallet_name);
                 app.state = AppState::Send(SendState::EnterNonces {
                     wallet_name,
                     session_id,
@@ -1433,29 +2114,32 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                     return;
                 }
 
-                // Count nonces by looking for "party_index" occurrences
-                let nonce_count = nonces_data.matches("\"party_index\"").count();
-                let threshold = app.send_form.threshold as usize;
-
-                if nonce_count < threshold {
+                app.send_form.refresh_board(&wallet_name);
+                if !app.send_form.board.nonces_complete() {
                     app.send_form.error_message = Some(format!(
-                        "Need {} nonces but only found {}. Collect more nonces from other signers!",
-                        threshold, nonce_count
+                        "Need {} nonces but have {}. {}",
+                        app.send_form.threshold,
+                        app.send_form.board.nonces.len(),
+                        app.send_form.board.pending_action()
                     ));
                     return;
                 }
 
                 // Generate signature share (real FROST)
+                let mut reporter = app::TuiReporter::default();
                 let state_dir = keygen::get_state_dir(&wallet_name);
                 match FileStorage::new(&state_dir) {
                     Ok(storage) => {
-                        match signing::create_signature_share_core(
+                        // Nonces as the board accepted them, unwrapped from any Nostr events
+                        match signing::create_signature_share_core_with_reporter(
                             &session_id,
                             &sighash,
-                            &nonces_data,
+                            &app.send_form.board.nonce_payloads.join(" "),
                             &storage,
+                            &mut reporter,
                         ) {
                             Ok(result) => {
+                                app.take_report(reporter);
                                 app.send_form.share_output = result.result.clone();
                                 app.send_form.error_message = None;
                                 app.state = AppState::Send(SendState::GenerateShare {
@@ -1475,6 +2159,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
             }
             _ => {
                 app.send_form.nonces_input.handle_key(key);
+                app.send_form.refresh_board(&wallet_name);
             }
         },
         AppState::Send(SendState::GenerateShare {
@@ -1495,6 +2180,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                     "Paste signature shares from other parties",
                 );
                 app.send_form.shares_input.handle_paste(&share_output);
+                app.send_form.refresh_board(&wallet_name);
                 app.state = AppState::Send(SendState::CombineShares { wallet_name });
             }
             _ => {}
@@ -1513,23 +2199,28 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
                     return;
                 }
 
-                // Count shares
-                let share_count = shares_data.matches("\"party_index\"").count();
-                let threshold = app.send_form.threshold as usize;
-
-                if share_count < threshold {
+                app.send_form.refresh_board(&wallet_name);
+                if !app.send_form.board.shares_complete() {
                     app.send_form.error_message = Some(format!(
-                        "Need {} shares but only found {}. Collect more shares!",
-                        threshold, share_count
+                        "Need {} shares but have {}. {}",
+                        app.send_form.threshold,
+                        app.send_form.board.shares.len(),
+                        app.send_form.board.pending_action()
                     ));
                     return;
                 }
 
                 // Combine signatures (real FROST)
+                let mut reporter = app::TuiReporter::default();
                 let state_dir = keygen::get_state_dir(&wallet_name);
                 match FileStorage::new(&state_dir) {
-                    Ok(storage) => match signing::combine_signatures_core(&shares_data, &storage) {
+                    Ok(storage) => match signing::combine_signatures_core_with_reporter(
+                        &app.send_form.board.share_payloads.join(" "),
+                        &storage,
+                        &mut reporter,
+                    ) {
                         Ok(result) => {
+                            app.take_report(reporter);
                             app.send_form.final_signature = result.result.clone();
                             app.send_form.error_message = None;
                             app.state = AppState::Send(SendState::Complete {
@@ -1547,6 +2238,7 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
             }
             _ => {
                 app.send_form.shares_input.handle_key(key);
+                app.send_form.refresh_board(&wallet_name);
             }
         },
         AppState::Send(SendState::Complete { txid }) => match key.code {
@@ -1563,26 +2255,88 @@ fn handle_send_keys(app: &mut App, key: KeyEvent) {
     }
 }
 
-fn handle_address_list_keys(app: &mut App, code: KeyCode) {
-    match code {
+/// Paste a transaction, Enter decodes it; Esc steps back
+fn handle_decode_tx_keys(app: &mut App, key: KeyEvent) {
+    let AppState::DecodeTx(ref state) = app.state else {
+        return;
+    };
+    let wallet_name = state.wallet_name.clone();
+    let decoded = state.output.is_some();
+
+    match key.code {
+        KeyCode::Esc if decoded => {
+            if let AppState::DecodeTx(ref mut s) = app.state {
+                s.output = None;
+            }
+        }
         KeyCode::Esc => {
+            app.state = AppState::WalletDetails(WalletDetailsState {
+                wallet_name,
+                ..Default::default()
+            });
+        }
+        KeyCode::Char('c') if decoded => {
+            if let Some((text, _)) = screen_output(&app.state) {
+                let text = text.to_string();
+                app.copy_to_clipboard(&text);
+            }
+        }
+        KeyCode::Enter if !decoded => {
+            let data = app.decode_input.content();
+            let result = if data.trim().is_empty() {
+                Err(anyhow::anyhow!("Paste a transaction first"))
+            } else {
+                decode_for_wallet(&data, &wallet_name, app.network.to_bitcoin_network())
+            };
+            if let AppState::DecodeTx(ref mut s) = app.state {
+                match result {
+                    Ok(output) => {
+                        s.output = Some(output);
+                        s.error = None;
+                    }
+                    Err(e) => s.error = Some(format!("{:#}", e)),
+                }
+            }
+        }
+        _ if !decoded => {
+            app.decode_input.handle_key(key);
+        }
+        _ => {}
+    }
+}
+
+/// Decode a pasted transaction, marking the scripts of `wallet_name`
+fn decode_for_wallet(data: &str, wallet_name: &str, network: bitcoin::Network) -> Result<String> {
+    use frostdao::btc::script_index::ScriptIndex;
+    use frostdao::btc::tx_decode;
+
+    let data = frostdao::qr::resolve_payload(data)?;
+    let storage = FileStorage::new(&keygen::get_state_dir(wallet_name))?;
+    let index = ScriptIndex::load(&storage)?;
+    let result = tx_decode::decode_with_backend(&data, network, Some((wallet_name, &index)))?;
+    Ok(result.output)
+}
+
+fn handle_address_list_keys(app: &mut App, code: KeyCode) {
+    match app.keymap.action(KeyContext::AddressList, code) {
+        Some(Action::Back) => {
             app.state = AppState::Home;
         }
-        KeyCode::Up | KeyCode::Char('k') => {
+        Some(Action::Up) => {
             if let AppState::AddressList(ref mut state) = app.state {
                 if state.selected > 0 {
                     state.selected -= 1;
                 }
             }
         }
-        KeyCode::Down | KeyCode::Char('j') => {
+        Some(Action::Down) => {
             if let AppState::AddressList(ref mut state) = app.state {
                 if state.selected + 1 < state.addresses.len() {
                     state.selected += 1;
                 }
             }
         }
-        KeyCode::Char('c') => {
+        Some(Action::Copy) => {
             // Copy selected address to clipboard
             let addr_to_copy = if let AppState::AddressList(ref state) = app.state {
                 state
@@ -1596,7 +2350,7 @@ fn handle_address_list_keys(app: &mut App, code: KeyCode) {
                 app.copy_to_clipboard(&addr);
             }
         }
-        KeyCode::Char('b') => {
+        Some(Action::Balance) => {
             // Fetch balance for selected address
             let addr_info = if let AppState::AddressList(ref state) = app.state {
                 state
@@ -1608,42 +2362,57 @@ fn handle_address_list_keys(app: &mut App, code: KeyCode) {
             };
 
             if let Some((addr, idx)) = addr_info {
+                if !app.require_online("Fetching balances") {
+                    return;
+                }
                 app.set_message(&format!("Fetching balance for address {}...", idx));
 
-                // Fetch balance from mempool.space
-                let api_base = app.network.mempool_api_base();
-                let url = format!("{}/address/{}/utxo", api_base, addr);
-
-                match reqwest::blocking::Client::new().get(&url).send() {
-                    Ok(response) => match response.json::<Vec<serde_json::Value>>() {
-                        Ok(utxos) => {
+                // Fetch balance from the Esplora backend (or the simulated chain / Electrum)
+                let balance = if !app.network.has_esplora() {
+                    frostdao::btc::transaction::fetch_utxos(&addr, app.network.to_bitcoin_network())
+                        .map(|utxos| (utxos.iter().map(|u| u.value).sum(), utxos.len()))
+                } else {
+                    let path = format!("/address/{}/utxo", addr);
+                    app.network
+                        .esplora()
+                        .map(|endpoint| endpoint.get(&frostdao::btc::http::client(), &path))
+                        .and_then(|request| Ok(request.send()?))
+                        .and_then(|response| {
+                            let utxos = response
+                                .json::<Vec<serde_json::Value>>()
+                                .map_err(|e| anyhow::anyhow!("Failed to parse response: {}", e))?;
                             let balance: u64 = utxos
                                 .iter()
                                 .filter_map(|u| u.get("value").and_then(|v| v.as_u64()))
                                 .sum();
-                            let utxo_count = utxos.len();
-
-                            if let AppState::AddressList(ref mut state) = app.state {
-                                state.balance_cache.insert(idx, (balance, utxo_count));
-                            }
+                            Ok((balance, utxos.len()))
+                        })
+                };
 
-                            let btc = balance as f64 / 100_000_000.0;
-                            app.set_message(&format!(
-                                "Address {}: {} sats ({:.8} BTC), {} UTXOs",
-                                idx, balance, btc, utxo_count
-                            ));
-                        }
-                        Err(e) => {
-                            app.set_message(&format!("Failed to parse response: {}", e));
+                match balance {
+                    Ok((balance, utxo_count)) => {
+                        if let AppState::AddressList(ref mut state) = app.state {
+                            state.balance_cache.insert(idx, (balance, utxo_count));
                         }
-                    },
+
+                        app.set_message(&format!(
+                            "Address {}: {}, {} UTXOs",
+                            idx,
+                            amount::format_amount(balance),
+                            utxo_count
+                        ));
+                    }
+                    Err(e) if frostdao::btc::esplora::is_unreachable(&e) => {
+                        app.go_offline();
+                        app.set_message("Offline: can't fetch address balances");
+                    }
                     Err(e) => {
                         app.set_message(&format!("Failed to fetch balance: {}", e));
                     }
                 }
             }
         }
-        KeyCode::Char('+') | KeyCode::Char('a') => {
+        Some(Action::AddAddress) => {
             // Add new HD address
             let wallet_name = if let AppState::AddressList(ref state) = app.state {
                 Some(state.wallet_name.clone())
@@ -1654,7 +2423,7 @@ fn handle_address_list_keys(app: &mut App, code: KeyCode) {
                 app.add_hd_address(&name);
             }
         }
-        KeyCode::Char('-') | KeyCode::Char('x') => {
+        Some(Action::RemoveAddress) => {
             // Remove last HD address
             let wallet_name = if let AppState::AddressList(ref state) = app.state {
                 Some(state.wallet_name.clone())
@@ -1670,11 +2439,11 @@ fn handle_address_list_keys(app: &mut App, code: KeyCode) {
 }
 
 fn handle_mnemonic_keys(app: &mut App, code: KeyCode) {
-    match code {
-        KeyCode::Esc => {
+    match app.keymap.action(KeyContext::Mnemonic, code) {
+        Some(Action::Back) => {
             app.state = AppState::Home;
         }
-        KeyCode::Up | KeyCode::Char('k') => {
+        Some(Action::Up) => {
             if let AppState::MnemonicBackup(ref mut state) = app.state {
                 if !state.party_selected && !state.available_parties.is_empty() {
                     if state.selected_party > 0 {
@@ -1685,7 +2454,7 @@ fn handle_mnemonic_keys(app: &mut App, code: KeyCode) {
                 }
             }
         }
-        KeyCode::Down | KeyCode::Char('j') => {
+        Some(Action::Down) => {
             if let AppState::MnemonicBackup(ref mut state) = app.state {
                 if !state.party_selected && !state.available_parties.is_empty() {
                     state.selected_party =
@@ -1693,7 +2462,7 @@ fn handle_mnemonic_keys(app: &mut App, code: KeyCode) {
                 }
             }
         }
-        KeyCode::Enter => {
+        Some(Action::Select) => {
             if let AppState::MnemonicBackup(ref mut state) = app.state {
                 if !state.party_selected {
                     // Party selected, show security warning
@@ -1716,7 +2485,7 @@ fn handle_mnemonic_keys(app: &mut App, code: KeyCode) {
                     };
 
                     match FileStorage::new(&share_dir) {
-                        Ok(storage) => match storage.read("paired_secret_share.bin") {
+                        Ok(storage) => match storage.read_secret("paired_secret_share.bin") {
                             Ok(bytes) => {
                                 use schnorr_fun::frost::PairedSecretShare;
                                 use schnorr_fun::fun::marker::EvenY;
@@ -1737,6 +2506,12 @@ fn handle_mnemonic_keys(app: &mut App, code: KeyCode) {
                                         state.words =
                                             mnemonic.words().map(|s| s.to_string()).collect();
                                         state.revealed = true;
+                                        if let Err(e) =
+                                            frostdao::protocol::health::record_backup(&storage)
+                                        {
+                                            state.error =
+                                                Some(format!("Could not record the backup: {}", e));
+                                        }
                                     }
                                     Err(e) => {
                                         state.error = Some(format!("Error: {}", e));
@@ -1762,6 +2537,8 @@ fn handle_mnemonic_keys(app: &mut App, code: KeyCode) {
 }
 
 fn ui(frame: &mut Frame, app: &App) {
+    app.mouse.clear();
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -1774,25 +2551,46 @@ fn ui(frame: &mut Frame, app: &App) {
     // Title with network indicator
     render_title(frame, app, chunks[0]);
 
+    // Wizards give up the right of the screen to the learn panel
+    let main = if app.learn && lessons::for_state(&app.state).is_some() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[1]);
+        screens::render_learn(frame, app, columns[1]);
+        columns[0]
+    } else {
+        chunks[1]
+    };
+
     // Main content based on state
     match &app.state {
-        AppState::Home => screens::render_home(frame, app, chunks[1]),
-        AppState::WalletDetails(state) => {
-            screens::render_wallet_details(frame, app, state, chunks[1])
-        }
+        AppState::Home => screens::render_home(frame, app, main),
+        AppState::WalletDetails(state) => screens::render_wallet_details(frame, app, state, main),
         AppState::ChainSelect => {
-            screens::render_home(frame, app, chunks[1]);
+            screens::render_home(frame, app, main);
             screens::render_chain_select(frame, app, frame.area());
         }
-        AppState::Keygen(_) => screens::render_keygen(frame, app, &app.keygen_form, chunks[1]),
-        AppState::Reshare(_) => screens::render_reshare(frame, app, &app.reshare_form, chunks[1]),
-        AppState::Send(_) => screens::render_send(frame, app, &app.send_form, chunks[1]),
-        AppState::AddressList(state) => screens::render_address_list(frame, state, chunks[1]),
-        AppState::MnemonicBackup(state) => screens::render_mnemonic(frame, state, chunks[1]),
+        AppState::Keygen(_) => screens::render_keygen(frame, app, &app.keygen_form, main),
+        AppState::Reshare(_) => screens::render_reshare(frame, app, &app.reshare_form, main),
+        AppState::Send(_) => screens::render_send(frame, app, &app.send_form, main),
+        AppState::AddressList(state) => {
+            screens::render_address_list(frame, state, &app.mouse, main)
+        }
+        AppState::MnemonicBackup(state) => screens::render_mnemonic(frame, state, main),
+        AppState::DecodeTx(state) => screens::render_decode_tx(frame, app, state, main),
     }
 
     // Help bar
     render_help_bar(frame, app, chunks[2]);
+
+    if let Some(input) = &app.import_path {
+        screens::render_import(frame, input, frame.area());
+    }
+
+    if app.show_help {
+        screens::render_help(frame, app, frame.area());
+    }
 }
 
 fn render_title(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
@@ -1802,7 +2600,7 @@ fn render_title(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         state::NetworkSelection::Mainnet => Color::Red,
     };
 
-    let title = Line::from(vec![
+    let mut title = Line::from(vec![
         Span::styled(
             "FrostDAO - DKG Wallet Manager",
             Style::default()
@@ -1817,6 +2615,30 @@ fn render_title(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
         ),
         Span::styled("]", Style::default().fg(Color::Gray)),
     ]);
+    if app.offline {
+        title.spans.push(Span::styled(
+            "  OFFLINE (cached data)",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if frostdao::storage::ephemeral::is_ephemeral() {
+        title.spans.push(Span::styled(
+            "  EPHEMERAL (keys vanish on exit)",
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if frostdao::btc::simulate::is_simulated() {
+        title.spans.push(Span::styled(
+            format!("  {} CHAIN", frostdao::btc::simulate::LABEL),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
 
     let paragraph = Paragraph::new(title).block(Block::default().borders(Borders::ALL));
 
@@ -1824,26 +2646,56 @@ fn render_title(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 }
 
 fn render_help_bar(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    // A few essentials from the keymap; the `?` overlay lists the rest
+    let keys = |actions: &[(Action, &str)]| {
+        actions
+            .iter()
+            .map(|(action, label)| format!("{}:{}", app.keymap.label(*action), label))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
     let help_text = if let Some(msg) = &app.message {
         msg.clone()
     } else {
         match &app.state {
-            AppState::Home => {
-                "↑/↓:Navigate | Enter:Select Wallet | n:Network | g:New Wallet | q:Quit".to_string()
-            }
-            AppState::WalletDetails(_) => {
-                "↑/↓:Navigate | Enter:Select | b:Balance | c:Copy | Esc:Back".to_string()
-            }
-            AppState::ChainSelect => "↑/↓:Select | Enter:Confirm | Esc:Cancel".to_string(),
-            AppState::Keygen(_) => "Tab:Next | Enter:Continue | Esc:Cancel".to_string(),
-            AppState::Reshare(_) => "Tab:Next | Enter:Continue | Esc:Cancel".to_string(),
-            AppState::Send(_) => "Tab:Next | Enter:Continue | Esc:Cancel".to_string(),
-            AppState::AddressList(_) => "↑/↓:Navigate | c:Copy | Esc:Back".to_string(),
+            AppState::Home if app.home.searching => {
+                "Type to filter | ↑/↓:Navigate | Enter:Keep filter | Esc:Clear".to_string()
+            }
+            AppState::Home => keys(&[
+                (Action::Select, "Select Wallet"),
+                (Action::Search, "Search"),
+                (Action::Send, "Send"),
+                (Action::Help, "Help"),
+                (Action::Quit, "Quit"),
+            ]),
+            AppState::WalletDetails(_) => keys(&[
+                (Action::Select, "Select"),
+                (Action::Balance, "Balance"),
+                (Action::Help, "Help"),
+                (Action::Back, "Back"),
+            ]),
+            AppState::ChainSelect => keys(&[(Action::Select, "Confirm"), (Action::Back, "Cancel")]),
+            AppState::Keygen(_) | AppState::Reshare(_) | AppState::Send(_) => format!(
+                "Tab:Next | Enter:Continue | Esc:Cancel | e/F2:Learn | {}:Help",
+                app.keymap.form_help_label()
+            ),
+            AppState::AddressList(_) => keys(&[
+                (Action::Copy, "Copy"),
+                (Action::Help, "Help"),
+                (Action::Back, "Back"),
+            ]),
             AppState::MnemonicBackup(state) => {
                 if state.revealed {
-                    "Enter:Done | Esc:Back".to_string()
+                    keys(&[(Action::Select, "Done"), (Action::Back, "Back")])
+                } else {
+                    keys(&[(Action::Select, "Reveal"), (Action::Back, "Cancel")])
+                }
+            }
+            AppState::DecodeTx(state) => {
+                if state.output.is_some() {
+                    "↑↓:Scroll | c:Copy | /:Search | s:Save | Esc:Back".to_string()
                 } else {
-                    "Enter:Reveal | Esc:Cancel".to_string()
+                    "Enter:Decode | Ctrl+O:Import | Esc:Back".to_string()
                 }
             }
         }
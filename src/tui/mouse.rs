@@ -0,0 +1,83 @@
+//! Mouse hit-testing
+//!
+//! Screens draw from `&App`, so each clickable list or panel records where it
+//! landed during the draw and mouse events are matched against the last frame.
+
+use ratatui::layout::Rect;
+use std::cell::RefCell;
+
+/// A list or panel that reacts to the mouse
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// Home screen wallet list
+    WalletList,
+    /// Wallet details action menu
+    WalletActions,
+    /// Send wizard party checkboxes
+    Parties,
+    /// HD address list
+    AddressList,
+    /// Send wizard UTXO panel
+    Utxos,
+    /// Send wizard recent transactions panel
+    Transactions,
+}
+
+#[derive(Clone, Copy)]
+struct Region {
+    target: Target,
+    /// Area inside the border
+    area: Rect,
+    /// Index of the first visible item
+    offset: usize,
+    /// Rows per item
+    item_height: u16,
+}
+
+/// Clickable regions of the last frame
+#[derive(Default)]
+pub struct MouseMap {
+    regions: RefCell<Vec<Region>>,
+}
+
+impl MouseMap {
+    /// Forget the previous frame's regions; called at the start of each draw
+    pub fn clear(&self) {
+        self.regions.borrow_mut().clear();
+    }
+
+    /// Record a bordered list drawn at `area`, whose first visible item is
+    /// `offset` and whose items are `item_height` rows tall
+    pub fn register(&self, target: Target, area: Rect, offset: usize, item_height: u16) {
+        let inner = Rect {
+            x: area.x.saturating_add(1),
+            y: area.y.saturating_add(1),
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(2),
+        };
+        self.regions.borrow_mut().push(Region {
+            target,
+            area: inner,
+            offset,
+            item_height: item_height.max(1),
+        });
+    }
+
+    /// The target under a cell and the index of the item there
+    pub fn hit(&self, column: u16, row: u16) -> Option<(Target, usize)> {
+        self.regions
+            .borrow()
+            .iter()
+            .rev()
+            .find(|region| {
+                column >= region.area.x
+                    && column < region.area.x + region.area.width
+                    && row >= region.area.y
+                    && row < region.area.y + region.area.height
+            })
+            .map(|region| {
+                let item = (row - region.area.y) / region.item_height;
+                (region.target, region.offset + item as usize)
+            })
+    }
+}
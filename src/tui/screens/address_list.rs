@@ -9,10 +9,17 @@ use ratatui::{
     Frame,
 };
 
+use crate::tui::mouse::{MouseMap, Target};
 use crate::tui::state::AddressListState;
+use frostdao::btc::amount;
 
 /// Render the HD address list screen
-pub fn render_address_list(frame: &mut Frame, state: &AddressListState, area: Rect) {
+pub fn render_address_list(
+    frame: &mut Frame,
+    state: &AddressListState,
+    mouse: &MouseMap,
+    area: Rect,
+) {
     // Main layout: list on left, details on right
     let main_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -96,6 +103,7 @@ pub fn render_address_list(frame: &mut Frame, state: &AddressListState, area: Re
             .border_style(Style::default().fg(Color::Cyan)),
     );
     frame.render_widget(list, main_chunks[0]);
+    mouse.register(Target::AddressList, main_chunks[0], 0, 1);
 
     // Right: Details panel with QR code
     if let Some((addr, _pubkey, index)) = state.addresses.get(state.selected) {
@@ -151,7 +159,6 @@ fn render_details_panel(
     // Add balance if cached
     if let Some((balance, utxo_count)) = state.balance_cache.get(&index) {
         info_lines.push(Line::from(""));
-        let btc = *balance as f64 / 100_000_000.0;
         info_lines.push(Line::from(vec![
             Span::styled("  Balance: ", Style::default().fg(Color::DarkGray)),
             Span::styled(
@@ -161,7 +168,7 @@ fn render_details_panel(
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!(" ({:.8} BTC) ", btc),
+                format!(" ({}) ", amount::format_btc(*balance)),
                 Style::default().fg(Color::DarkGray),
             ),
             Span::styled(
@@ -0,0 +1,64 @@
+//! Raw transaction viewer: paste a transaction, see it decoded
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::tui::app::App;
+use crate::tui::state::DecodeTxState;
+
+/// Render the paste area, or the decoded transaction once Enter was pressed
+pub fn render_decode_tx(frame: &mut Frame, app: &App, state: &DecodeTxState, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Decode Transaction - {} ", state.wallet_name));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Instructions
+            Constraint::Min(5),    // Input or output
+            Constraint::Length(2), // Error
+            Constraint::Length(2), // Help
+        ])
+        .split(inner);
+
+    let (instructions, help) = match &state.output {
+        Some(_) => (
+            "Inputs and outputs marked ◀ belong to this wallet:",
+            "c: Copy | /: Search | s: Save | Esc: Decode another",
+        ),
+        None => (
+            "Paste a raw transaction (hex) or a dkg-build-tx output:",
+            "Ctrl+V: Paste | Ctrl+O: Import file | Enter: Decode | Esc: Back",
+        ),
+    };
+    frame.render_widget(
+        Paragraph::new(instructions).style(Style::default().fg(Color::Yellow)),
+        chunks[0],
+    );
+
+    match &state.output {
+        Some(_) => app
+            .viewer
+            .render(frame, chunks[1], "Transaction", Color::Green),
+        None => app.decode_input.render(frame, chunks[1], true),
+    }
+
+    if let Some(error) = &state.error {
+        let error_para = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+        frame.render_widget(error_para, chunks[2]);
+    }
+
+    frame.render_widget(
+        Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+        chunks[3],
+    );
+}
@@ -0,0 +1,71 @@
+//! Keybinding help overlay
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::tui::app::App;
+use crate::tui::keymap::{KeyContext, FORM_KEYS, KEYMAP_FILE};
+
+/// Render the `?` overlay listing every key for the current screen
+pub fn render_help(frame: &mut Frame, app: &App, area: Rect) {
+    let key_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let entry = |keys: String, description: &str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<16}", keys), key_style),
+            Span::raw(description.to_string()),
+        ])
+    };
+
+    let mut lines = vec![Line::from("")];
+    match KeyContext::for_state(&app.state) {
+        Some(context) => {
+            for action in context.actions() {
+                lines.push(entry(app.keymap.label(*action), action.description()));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("  Remap keys in .frost_state/{}", KEYMAP_FILE),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        None => {
+            for (keys, description) in FORM_KEYS {
+                lines.push(entry(keys.to_string(), description));
+            }
+            lines.push(entry(app.keymap.form_help_label(), "Show this help"));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  Press any key to close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    // Center the popup, shrinking to fit narrow terminals
+    let width = 60.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let help = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(" Keys ")
+            .style(Style::default().bg(Color::Black)),
+    );
+    frame.render_widget(help, popup_area);
+}
@@ -4,12 +4,18 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
-use crate::tui::app::App;
+use crate::tui::app::{App, BalanceInfo};
+use crate::tui::keymap::{Action, KeyMap};
+use crate::tui::mouse::Target;
 use crate::tui::state::NetworkSelection;
+use crate::tui::wallet_view::HomeRow;
+use frostdao::btc::amount;
+use frostdao::btc::fiat;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Render the home screen
 pub fn render_home(frame: &mut Frame, app: &App, area: Rect) {
@@ -23,32 +29,81 @@ pub fn render_home(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_wallet_list(frame: &mut Frame, app: &App, area: Rect) {
-    let items: Vec<ListItem> = app
-        .wallets
+    let rows = app.home_rows();
+    let items: Vec<ListItem> = rows
         .iter()
-        .map(|wallet| {
-            let mode = match wallet.hierarchical {
-                Some(true) => "HTSS",
-                Some(false) => "TSS",
-                None => "?",
+        .map(|row| {
+            let wallet = match row {
+                HomeRow::Wallet(i) => &app.wallets[*i],
+                HomeRow::Header(network) => {
+                    let heading = network.map_or("Not fetched", |n| n.display_name());
+                    return ListItem::new(Span::styled(
+                        format!("── {} ──", heading),
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                }
+            };
+            let mode = match (wallet.hierarchical, wallet.watch_only) {
+                (Some(true), false) => "HTSS",
+                (Some(false), false) => "TSS",
+                (Some(true), true) => "HTSS, watch-only",
+                (Some(false), true) => "TSS, watch-only",
+                (None, _) => "?",
             };
             let threshold = match (wallet.threshold, wallet.total_parties) {
                 (Some(t), Some(n)) => format!("{}-of-{}", t, n),
                 _ => "?".to_string(),
             };
 
-            let has_balance = app.balance_cache.contains_key(&wallet.name);
-            let balance_indicator = if has_balance { " $" } else { "" };
+            // "$ 5m ago" per cached balance, "?" when stale; a spinner
+            // while the background refresher has it queued
+            let mut balance_indicator = match app.cached_balance(&wallet.name) {
+                Some(info) if app.offline || info.is_stale() => format!(" $? {}", info.age()),
+                Some(info) => format!(" $ {}", info.age()),
+                None => String::new(),
+            };
+            if app.refreshing.contains(&wallet.name) {
+                balance_indicator.push(' ');
+                balance_indicator.push(spinner());
+            }
+
+            let tags = if wallet.notes.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", wallet.notes.tags.join(", "))
+            };
+
+            let retired = if wallet.retired.is_some() {
+                " retired"
+            } else {
+                ""
+            };
 
             ListItem::new(format!(
-                "{} ({} {}){}",
-                wallet.name, threshold, mode, balance_indicator
+                "{} ({} {}){}{}{}",
+                wallet.name, threshold, mode, retired, balance_indicator, tags
             ))
         })
         .collect();
 
+    let shown = rows
+        .iter()
+        .filter(|row| matches!(row, HomeRow::Wallet(_)))
+        .count();
+    let border = if app.home.searching {
+        Color::Yellow
+    } else {
+        Color::White
+    };
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Wallets"))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border))
+                .title(app.home.title(shown, app.wallets.len())),
+        )
         .highlight_style(
             Style::default()
                 .bg(Color::DarkGray)
@@ -56,7 +111,16 @@ fn render_wallet_list(frame: &mut Frame, app: &App, area: Rect) {
         )
         .highlight_symbol(">> ");
 
-    frame.render_stateful_widget(list, area, &mut app.wallet_list_state.clone());
+    // The selection is a wallet index; the list wants its display row
+    let mut list_state = ListState::default();
+    list_state.select(
+        app.wallet_list_state
+            .selected()
+            .and_then(|i| rows.iter().position(|row| *row == HomeRow::Wallet(i))),
+    );
+    frame.render_stateful_widget(list, area, &mut list_state);
+    app.mouse
+        .register(Target::WalletList, area, list_state.offset(), 1);
 }
 
 fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
@@ -81,6 +145,17 @@ fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
             Line::from(""),
         ];
 
+        // Health policy warnings (dkg-health), above everything else
+        for warning in &wallet.health {
+            lines.push(Line::from(Span::styled(
+                format!("⚠ {}", warning),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        if !wallet.health.is_empty() {
+            lines.push(Line::from(""));
+        }
+
         // Threshold info
         if let (Some(t), Some(n)) = (wallet.threshold, wallet.total_parties) {
             lines.push(Line::from(vec![
@@ -107,6 +182,9 @@ fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
             ]));
         }
 
+        // Description and tags from the wallet's notes
+        lines.extend(super::wallet_details::notes_lines(&wallet.notes, false));
+
         lines.push(Line::from(""));
 
         // Address (network-specific)
@@ -124,8 +202,7 @@ fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
         lines.push(Line::from(""));
 
         // Balance (if cached)
-        let cache_key = format!("{}:{:?}", wallet.name, app.network);
-        if let Some(info) = app.balance_cache.get(&cache_key) {
+        if let Some(info) = app.cached_balance(&wallet.name) {
             lines.push(Line::from(vec![
                 Span::styled("Balance: ", Style::default().fg(Color::Gray)),
                 Span::styled(
@@ -136,15 +213,27 @@ fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
                 ),
             ]));
 
-            let btc = info.balance_sats as f64 / 100_000_000.0;
             lines.push(Line::from(vec![
                 Span::styled("         ", Style::default()),
                 Span::styled(
-                    format!("({:.8} BTC)", btc),
+                    format!("({})", amount::format_btc(info.balance_sats)),
                     Style::default().fg(Color::Gray),
                 ),
             ]));
 
+            if let Some(rate) = &app.fiat_rate {
+                lines.push(Line::from(vec![
+                    Span::styled("         ", Style::default()),
+                    Span::styled(
+                        format!(
+                            "≈ {}",
+                            fiat::format_fiat(rate.to_fiat(info.balance_sats), &rate.currency)
+                        ),
+                        Style::default().fg(Color::Gray),
+                    ),
+                ]));
+            }
+
             lines.push(Line::from(vec![
                 Span::styled("UTXOs: ", Style::default().fg(Color::Gray)),
                 Span::styled(
@@ -152,10 +241,20 @@ fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(Color::White),
                 ),
             ]));
+
+            lines.push(updated_line(info, app.offline));
+        } else if app.offline {
+            lines.push(Line::from(vec![
+                Span::styled("Balance: ", Style::default().fg(Color::Gray)),
+                Span::styled("Offline, nothing cached", Style::default().fg(Color::Red)),
+            ]));
         } else {
             lines.push(Line::from(vec![
                 Span::styled("Balance: ", Style::default().fg(Color::Gray)),
-                Span::styled("Press Enter to fetch", Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    format!("Press {} to fetch", app.keymap.label(Action::Refresh)),
+                    Style::default().fg(Color::DarkGray),
+                ),
             ]));
         }
 
@@ -168,7 +267,10 @@ fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(Color::DarkGray),
             )),
             Line::from(""),
-            Line::from("Create a wallet with 'g' (keygen)"),
+            Line::from(format!(
+                "Create a wallet with '{}' (keygen)",
+                app.keymap.label(Action::NewWallet)
+            )),
             Line::from("or use CLI:"),
             Line::from(Span::styled(
                 "  frostdao keygen-round1 --name <name> ...",
@@ -185,28 +287,59 @@ fn render_wallet_details(frame: &mut Frame, app: &App, area: Rect) {
 
     // Keyboard shortcuts panel (pass whether wallet is selected)
     let has_wallet = app.selected_wallet().is_some();
-    render_shortcuts(frame, has_wallet, chunks[1]);
+    render_shortcuts(frame, &app.keymap, has_wallet, app.offline, chunks[1]);
 }
 
-fn render_shortcuts(frame: &mut Frame, has_wallet: bool, area: Rect) {
+fn render_shortcuts(
+    frame: &mut Frame,
+    keymap: &KeyMap,
+    has_wallet: bool,
+    offline: bool,
+    area: Rect,
+) {
+    // Network actions are greyed out while offline
+    let (network_key, network_label) = if offline {
+        (
+            Style::default().fg(Color::DarkGray),
+            Style::default().fg(Color::DarkGray),
+        )
+    } else {
+        (Style::default().fg(Color::Yellow), Style::default())
+    };
+    let key = |action: Action, style: Style| Span::styled(keymap.label(action), style);
+    let yellow = Style::default().fg(Color::Yellow);
+    let green = Style::default().fg(Color::Green);
+
     // Basic shortcuts always shown
     let mut shortcuts = vec![
         Line::from(vec![
-            Span::styled("n", Style::default().fg(Color::Yellow)),
+            key(Action::Network, yellow),
             Span::raw(" Network   "),
-            Span::styled("g", Style::default().fg(Color::Yellow)),
+            key(Action::NewWallet, yellow),
             Span::raw(" Generate wallet   "),
-            Span::styled("R", Style::default().fg(Color::Yellow)),
+            key(Action::Reload, yellow),
             Span::raw(" Reload wallets"),
         ]),
         Line::from(vec![
-            Span::styled("↑/↓", Style::default().fg(Color::Green)),
+            key(Action::Down, green),
+            Span::raw(" "),
+            key(Action::Up, green),
             Span::raw(" Navigate  "),
-            Span::styled("Enter", Style::default().fg(Color::Green)),
-            Span::raw(" Open wallet       "),
-            Span::styled("q", Style::default().fg(Color::Yellow)),
+            key(Action::Select, green),
+            Span::raw(" Open wallet  "),
+            key(Action::Help, yellow),
+            Span::raw(" Help  "),
+            key(Action::Quit, yellow),
             Span::raw(" Quit"),
         ]),
+        Line::from(vec![
+            key(Action::Search, green),
+            Span::raw(" Search    "),
+            key(Action::Sort, green),
+            Span::raw(" Sort            "),
+            key(Action::Group, green),
+            Span::raw(" Group by network"),
+        ]),
     ];
 
     // Wallet-specific shortcuts only shown when a wallet is selected
@@ -217,19 +350,19 @@ fn render_shortcuts(frame: &mut Frame, has_wallet: bool, area: Rect) {
             Style::default().fg(Color::Cyan),
         )));
         shortcuts.push(Line::from(vec![
-            Span::styled("s", Style::default().fg(Color::Yellow)),
-            Span::raw(" Send      "),
-            Span::styled("a", Style::default().fg(Color::Yellow)),
+            key(Action::Send, network_key),
+            Span::styled(" Send      ", network_label),
+            key(Action::Addresses, yellow),
             Span::raw(" Addresses   "),
-            Span::styled("m", Style::default().fg(Color::Yellow)),
+            key(Action::Mnemonic, yellow),
             Span::raw(" Mnemonic"),
         ]));
         shortcuts.push(Line::from(vec![
-            Span::styled("h", Style::default().fg(Color::Yellow)),
+            key(Action::Reshare, yellow),
             Span::raw(" Reshare   "),
-            Span::styled("r", Style::default().fg(Color::Yellow)),
-            Span::raw(" Refresh     "),
-            Span::styled("c", Style::default().fg(Color::Yellow)),
+            key(Action::Refresh, network_key),
+            Span::styled(" Refresh     ", network_label),
+            key(Action::Copy, yellow),
             Span::raw(" Copy addr"),
         ]));
     }
@@ -246,6 +379,31 @@ fn render_shortcuts(frame: &mut Frame, has_wallet: bool, area: Rect) {
     frame.render_widget(shortcuts_widget, area);
 }
 
+/// Braille spinner frame, advancing with the clock
+fn spinner() -> char {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    FRAMES[(millis / 250) as usize % FRAMES.len()]
+}
+
+/// When a cached balance was fetched, flagged if stale or offline
+pub fn updated_line(info: &BalanceInfo, offline: bool) -> Line<'static> {
+    let (updated, color) = if offline {
+        (format!("{} (cached, offline)", info.age()), Color::Red)
+    } else if info.is_stale() {
+        (format!("{} (stale)", info.age()), Color::Yellow)
+    } else {
+        (info.age(), Color::DarkGray)
+    };
+    Line::from(vec![
+        Span::styled("Updated: ", Style::default().fg(Color::Gray)),
+        Span::styled(updated, Style::default().fg(color)),
+    ])
+}
+
 /// Get address for the selected network
 fn get_address_for_network(
     wallet: &frostdao::protocol::keygen::WalletSummary,
@@ -258,13 +416,13 @@ fn get_address_for_network(
         NetworkSelection::Signet => wallet.address.clone(), // Same format as testnet (tb1p...)
         NetworkSelection::Mainnet => {
             // Mainnet would use bc1p... prefix - need to regenerate
-            wallet.address.as_ref().map(|addr| {
-                if addr.starts_with("tb1p") {
-                    format!("bc1p{}", &addr[4..])
-                } else {
-                    addr.clone()
-                }
-            })
+            wallet
+                .address
+                .as_ref()
+                .map(|addr| match addr.strip_prefix("tb1p") {
+                    Some(rest) => format!("bc1p{}", rest),
+                    None => addr.clone(),
+                })
         }
     }
 }
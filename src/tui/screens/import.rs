@@ -0,0 +1,42 @@
+//! File import prompt for paste screens
+
+use crate::tui::components::TextInput;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Path prompt shown over a paste screen after Ctrl+O
+pub fn render_import(frame: &mut Frame, input: &TextInput, area: Rect) {
+    let width = 70.min(area.width);
+    let height = 6.min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Import payload ")
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(inner);
+
+    input.render(frame, chunks[0], true);
+
+    let help = Paragraph::new("A .json file or a folder of them | Enter: Load | Esc: Cancel")
+        .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(help, chunks[1]);
+}
@@ -1,15 +1,15 @@
 //! Keygen wizard screens
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
 use crate::tui::app::App;
-use crate::tui::components::{TextArea, TextInput};
+use crate::tui::components::{OutputViewer, TextArea, TextInput};
 use crate::tui::state::{KeygenFormField, KeygenState};
 
 /// Keygen wizard form data
@@ -19,12 +19,21 @@ pub struct KeygenFormData {
     pub threshold: TextInput,
     pub n_parties: TextInput,
     pub hierarchical: bool,
+    /// Party index for a multi-machine ceremony; blank generates every
+    /// party on this machine
+    pub my_index: TextInput,
+    /// HTSS rank of this party
+    pub rank: TextInput,
     pub focused_field: KeygenFormField,
     pub round1_output: String,
     pub round2_input: TextArea,
     pub round2_output: String,
     pub finalize_input: TextArea,
     pub error_message: Option<String>,
+    /// Show round output as an animated QR instead of JSON
+    pub show_qr: bool,
+    /// Round 2 output is shown as NIP-17 DMs, one per party
+    pub round2_dm: bool,
 }
 
 impl KeygenFormData {
@@ -34,33 +43,50 @@ impl KeygenFormData {
             threshold: TextInput::new("Threshold").with_value("2").numeric(),
             n_parties: TextInput::new("Total Parties").with_value("3").numeric(),
             hierarchical: false,
+            my_index: TextInput::new("Your Party Index")
+                .with_placeholder("blank = all parties on this machine")
+                .numeric(),
+            rank: TextInput::new("Your Rank (0 = highest)")
+                .with_value("0")
+                .numeric(),
             focused_field: KeygenFormField::Name,
             round1_output: String::new(),
             round2_input: TextArea::new("Paste Round 1 outputs from all parties"),
             round2_output: String::new(),
             finalize_input: TextArea::new("Paste Round 2 outputs from all parties"),
             error_message: None,
+            show_qr: false,
+            round2_dm: false,
         }
     }
+
+    /// This machine's party index, if the ceremony is multi-machine
+    pub fn party_index(&self) -> Option<u32> {
+        self.my_index.value().parse().ok()
+    }
 }
 
 /// Render keygen wizard
 pub fn render_keygen(frame: &mut Frame, app: &App, form: &KeygenFormData, area: Rect) {
-    match &app.state {
-        crate::tui::state::AppState::Keygen(state) => match state {
+    if let crate::tui::state::AppState::Keygen(state) = &app.state {
+        match state {
             KeygenState::ModeSelect => render_mode_select(frame, form, area),
             KeygenState::ParamsSetup => render_params_setup(frame, form, area),
             KeygenState::Round1Output { output_json } => {
-                render_round1_output(frame, output_json, area)
+                render_round1_output(frame, &app.viewer, output_json, form.show_qr, area)
             }
             KeygenState::Round2Input => render_round2_input(frame, form, area),
-            KeygenState::Round2Output { output_json } => {
-                render_round2_output(frame, output_json, area)
-            }
+            KeygenState::Round2Output { output_json } => render_round2_output(
+                frame,
+                &app.viewer,
+                output_json,
+                form.show_qr,
+                form.round2_dm,
+                area,
+            ),
             KeygenState::FinalizeInput => render_finalize_input(frame, form, area),
             KeygenState::Complete { wallet_name } => render_complete(frame, wallet_name, area),
-        },
-        _ => {}
+        }
     }
 }
 
@@ -182,12 +208,14 @@ fn render_params_setup(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
     frame.render_widget(block, area);
 
     if form.hierarchical {
-        // HTSS mode: Name, N Parties
+        // HTSS mode: Name, N Parties, party index and rank
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3), // Name
                 Constraint::Length(3), // N Parties
+                Constraint::Length(3), // My index
+                Constraint::Length(3), // Rank
                 Constraint::Length(5), // Explanation
                 Constraint::Min(1),    // Spacer
                 Constraint::Length(2), // Error
@@ -205,8 +233,22 @@ fn render_params_setup(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
             chunks[1],
             form.focused_field == KeygenFormField::NParties,
         );
+        form.my_index.render(
+            frame,
+            chunks[2],
+            form.focused_field == KeygenFormField::MyIndex,
+        );
+        form.rank.render(
+            frame,
+            chunks[3],
+            form.focused_field == KeygenFormField::Rank,
+        );
 
         let n: u32 = form.n_parties.value().parse().unwrap_or(3);
+        let ranks = match form.party_index() {
+            Some(_) => format!("yours is {}, each party picks its own", form.rank.value()),
+            None => format!("0, 1, 2, ... {}", n.saturating_sub(1)),
+        };
         let explanation = Paragraph::new(vec![
             Line::from(vec![
                 Span::styled(
@@ -219,34 +261,32 @@ fn render_params_setup(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
             ]),
             Line::from(vec![
                 Span::styled("  Ranks: ", Style::default().fg(Color::Gray)),
-                Span::styled(
-                    format!("0, 1, 2, ... {}", n.saturating_sub(1)),
-                    Style::default().fg(Color::Cyan),
-                ),
+                Span::styled(ranks, Style::default().fg(Color::Cyan)),
             ]),
             Line::from(vec![
                 Span::styled("  Rule: ", Style::default().fg(Color::Gray)),
                 Span::raw("Signers' ranks (sorted) must satisfy rank[i] <= i"),
             ]),
+            machine_line(form, n),
         ]);
-        frame.render_widget(explanation, chunks[2]);
+        frame.render_widget(explanation, chunks[4]);
 
         if let Some(error) = &form.error_message {
             let error_para = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
-            frame.render_widget(error_para, chunks[4]);
+            frame.render_widget(error_para, chunks[6]);
         }
 
-        let help = Paragraph::new("Tab: Next field | Enter: Generate All Parties | Esc: Back")
-            .style(Style::default().fg(Color::DarkGray));
-        frame.render_widget(help, chunks[5]);
+        let help = Paragraph::new(params_help(form)).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, chunks[7]);
     } else {
-        // TSS mode: Name, Threshold, N Parties
+        // TSS mode: Name, Threshold, N Parties, party index
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3), // Name
                 Constraint::Length(3), // Threshold
                 Constraint::Length(3), // N Parties
+                Constraint::Length(3), // My index
                 Constraint::Length(4), // Explanation
                 Constraint::Min(1),    // Spacer
                 Constraint::Length(2), // Error
@@ -269,6 +309,11 @@ fn render_params_setup(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
             chunks[2],
             form.focused_field == KeygenFormField::NParties,
         );
+        form.my_index.render(
+            frame,
+            chunks[3],
+            form.focused_field == KeygenFormField::MyIndex,
+        );
 
         let t: u32 = form.threshold.value().parse().unwrap_or(2);
         let n: u32 = form.n_parties.value().parse().unwrap_or(3);
@@ -292,21 +337,58 @@ fn render_params_setup(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
                 "  Any {} of {} parties can sign together",
                 t, n
             ))]),
+            machine_line(form, n),
         ]);
-        frame.render_widget(explanation, chunks[3]);
+        frame.render_widget(explanation, chunks[4]);
 
         if let Some(error) = &form.error_message {
             let error_para = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
-            frame.render_widget(error_para, chunks[5]);
+            frame.render_widget(error_para, chunks[6]);
         }
 
-        let help = Paragraph::new("Tab: Next field | Enter: Generate All Parties | Esc: Back")
-            .style(Style::default().fg(Color::DarkGray));
-        frame.render_widget(help, chunks[6]);
+        let help = Paragraph::new(params_help(form)).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(help, chunks[7]);
     }
 }
 
-fn render_round1_output(frame: &mut Frame, output_json: &str, area: Rect) {
+/// Which shares this machine will hold
+fn machine_line(form: &KeygenFormData, n: u32) -> Line<'static> {
+    match form.party_index() {
+        Some(k) => Line::from(vec![
+            Span::styled("  This machine: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("party {} of {}", k, n),
+                Style::default().fg(Color::Green),
+            ),
+            Span::raw(", rounds exchanged by paste, QR, file or Nostr DM"),
+        ]),
+        None => Line::from(vec![
+            Span::styled("  Demo: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("all {} shares are created on this machine", n),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]),
+    }
+}
+
+fn params_help(form: &KeygenFormData) -> String {
+    match form.party_index() {
+        Some(k) => format!(
+            "Tab: Next field | Enter: Run Round 1 as party {} | Esc: Back",
+            k
+        ),
+        None => "Tab: Next field | Enter: Generate All Parties | Esc: Back".to_string(),
+    }
+}
+
+fn render_round1_output(
+    frame: &mut Frame,
+    viewer: &OutputViewer,
+    output_json: &str,
+    show_qr: bool,
+    area: Rect,
+) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -329,22 +411,61 @@ fn render_round1_output(frame: &mut Frame, output_json: &str, area: Rect) {
         Paragraph::new("Share this with all parties:").style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
-    // Output JSON
-    let output_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green))
-        .title("Output (copy this)");
-    let output_para = Paragraph::new(output_json)
-        .block(output_block)
-        .wrap(Wrap { trim: false });
-    frame.render_widget(output_para, chunks[1]);
+    render_output(frame, viewer, output_json, show_qr, chunks[1]);
 
     // Help
-    let help = Paragraph::new("c: Copy to clipboard | Enter: Continue to Round 2 | Esc: Cancel")
+    let help = Paragraph::new("c: Copy | r: QR code | Enter: Continue to Round 2 | Esc: Cancel")
         .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[2]);
 }
 
+/// Round output as copyable JSON, or the current frame of its animated QR
+fn render_output(
+    frame: &mut Frame,
+    viewer: &OutputViewer,
+    output_json: &str,
+    show_qr: bool,
+    area: Rect,
+) {
+    if !show_qr {
+        viewer.render(frame, area, "Output (copy this)", Color::Green);
+        return;
+    }
+
+    // The event loop redraws every 250ms, so the frame follows the clock
+    let encoder = frostdao::qr::encoder(output_json);
+    let seq_len = encoder.seq_len() as u128;
+    let tick = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() / frostdao::qr::FRAME_INTERVAL.as_millis())
+        .unwrap_or(0);
+    let seq_num = (tick % (seq_len * 4)) as u32 + 1;
+
+    let lines: Vec<Line> = match frostdao::qr::render_lines(&encoder.part(seq_num)) {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|row| Line::from(Span::styled(row, Style::default().fg(Color::White))))
+            .collect(),
+        Err(e) => vec![Line::from(Span::styled(
+            e.to_string(),
+            Style::default().fg(Color::Red),
+        ))],
+    };
+    let title = if seq_len > 1 {
+        format!("Scan me (frame {}, {} fragments)", seq_num, seq_len)
+    } else {
+        "Scan me".to_string()
+    };
+    let qr_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Green))
+        .title(title);
+    let qr_para = Paragraph::new(lines)
+        .block(qr_block)
+        .alignment(Alignment::Center);
+    frame.render_widget(qr_para, area);
+}
+
 fn render_round2_input(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
@@ -365,9 +486,10 @@ fn render_round2_input(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
         .split(inner);
 
     // Instructions
-    let instructions =
-        Paragraph::new("Paste all Round 1 outputs from all parties (space-separated JSON):")
-            .style(Style::default().fg(Color::Yellow));
+    let instructions = Paragraph::new(
+        "Paste all Round 1 outputs (space-separated JSON, ur: frames or @scan-file):",
+    )
+    .style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
     // Input area
@@ -380,12 +502,20 @@ fn render_round2_input(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
     }
 
     // Help
-    let help = Paragraph::new("Ctrl+V: Paste | Enter: Generate Shares | Esc: Back")
-        .style(Style::default().fg(Color::DarkGray));
+    let help =
+        Paragraph::new("Ctrl+V: Paste | Ctrl+O: Import file | Enter: Generate Shares | Esc: Back")
+            .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[3]);
 }
 
-fn render_round2_output(frame: &mut Frame, output_json: &str, area: Rect) {
+fn render_round2_output(
+    frame: &mut Frame,
+    viewer: &OutputViewer,
+    output_json: &str,
+    show_qr: bool,
+    dm: bool,
+    area: Rect,
+) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -404,23 +534,21 @@ fn render_round2_output(frame: &mut Frame, output_json: &str, area: Rect) {
         .split(inner);
 
     // Instructions
-    let instructions =
-        Paragraph::new("Share this with all parties:").style(Style::default().fg(Color::Yellow));
+    let instructions = if dm {
+        "Publish these NIP-17 DMs (kind 1059) to your relays, one per party:"
+    } else {
+        "Share this with all parties:"
+    };
+    let instructions = Paragraph::new(instructions).style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
-    // Output JSON
-    let output_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green))
-        .title("Output (copy this)");
-    let output_para = Paragraph::new(output_json)
-        .block(output_block)
-        .wrap(Wrap { trim: false });
-    frame.render_widget(output_para, chunks[1]);
+    render_output(frame, viewer, output_json, show_qr, chunks[1]);
 
     // Help
-    let help = Paragraph::new("c: Copy to clipboard | Enter: Continue to Finalize | Esc: Cancel")
-        .style(Style::default().fg(Color::DarkGray));
+    let help = Paragraph::new(
+        "c: Copy | r: QR code | d: JSON / Nostr DMs | p: Publish DMs to relays | Enter: Continue to Finalize | Esc: Cancel",
+    )
+    .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[2]);
 }
 
@@ -444,9 +572,10 @@ fn render_finalize_input(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
         .split(inner);
 
     // Instructions
-    let instructions =
-        Paragraph::new("Paste all Round 2 outputs from all parties (space-separated JSON):")
-            .style(Style::default().fg(Color::Yellow));
+    let instructions = Paragraph::new(
+        "Paste all Round 2 outputs or your Nostr DMs (JSON, ur: frames or @scan-file):",
+    )
+    .style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
     // Input area
@@ -459,8 +588,10 @@ fn render_finalize_input(frame: &mut Frame, form: &KeygenFormData, area: Rect) {
     }
 
     // Help
-    let help = Paragraph::new("Ctrl+V: Paste | Enter: Finalize Wallet | Esc: Back")
-        .style(Style::default().fg(Color::DarkGray));
+    let help = Paragraph::new(
+        "Ctrl+V: Paste | Ctrl+O: Import file | Ctrl+N: Fetch DMs from relays | Enter: Finalize Wallet | Esc: Back",
+    )
+    .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[3]);
 }
 
@@ -0,0 +1,55 @@
+//! Learn panel: what the current wizard step does
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::tui::app::App;
+use crate::tui::lessons;
+
+/// Render the step's explanation and the 🧠 notes of the last step that ran
+pub fn render_learn(frame: &mut Frame, app: &App, area: Rect) {
+    let mut lines = Vec::new();
+    if let Some(lesson) = lessons::for_state(&app.state) {
+        lines.push(Line::from(Span::styled(
+            lesson.title,
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )));
+        for text in lesson.lines {
+            lines.push(Line::from(""));
+            lines.push(Line::from(*text));
+        }
+    }
+
+    if !app.lesson.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "From the last step:",
+            Style::default().fg(Color::Cyan),
+        )));
+        for event in &app.lesson {
+            let mut text = String::new();
+            event.render(&mut text);
+            lines.extend(text.lines().map(|line| {
+                Line::from(Span::styled(
+                    line.to_string(),
+                    Style::default().fg(Color::Gray),
+                ))
+            }));
+        }
+    }
+
+    let panel = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta))
+            .title(" 🧠 Learn (e) "),
+    );
+    frame.render_widget(panel, area);
+}
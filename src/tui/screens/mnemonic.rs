@@ -83,7 +83,13 @@ pub fn render_mnemonic(frame: &mut Frame, state: &MnemonicState, area: Rect) {
             Style::default().fg(Color::DarkGray),
         )));
 
-        let para = Paragraph::new(lines).wrap(Wrap { trim: false });
+        // Keep the selected party on screen in large committees; the list
+        // starts after three header lines and ends with two footer lines
+        let selected_row = 3 + state.selected_party as u16;
+        let scroll = (selected_row + 3).saturating_sub(inner.height);
+        let para = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0));
         frame.render_widget(para, inner);
         return;
     }
@@ -2,18 +2,28 @@
 
 mod address_list;
 mod chain_select;
+mod decode_tx;
+mod help;
 mod home;
+mod import;
 mod keygen;
+mod learn;
 mod mnemonic;
 mod reshare;
 mod send;
+mod session_board;
 mod wallet_details;
 
 pub use address_list::render_address_list;
 pub use chain_select::render_chain_select;
+pub use decode_tx::render_decode_tx;
+pub use help::render_help;
 pub use home::render_home;
+pub use import::render_import;
 pub use keygen::{render_keygen, KeygenFormData};
+pub use learn::render_learn;
 pub use mnemonic::render_mnemonic;
 pub use reshare::{render_reshare, ReshareFormData};
 pub use send::{render_send, ScriptConfig, ScriptType, SendFormData, TxDisplay, UtxoDisplay};
+pub use session_board::render_session_board;
 pub use wallet_details::render_wallet_details;
@@ -4,12 +4,12 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
 use crate::tui::app::App;
-use crate::tui::components::{TextArea, TextInput};
+use crate::tui::components::{OutputViewer, TextArea, TextInput};
 use crate::tui::state::{ReshareFinalizeField, ReshareFormField, ReshareState};
 
 /// Reshare wizard form data
@@ -59,16 +59,13 @@ impl ReshareFormData {
 
 /// Render reshare wizard
 pub fn render_reshare(frame: &mut Frame, app: &App, form: &ReshareFormData, area: Rect) {
-    match &app.state {
-        crate::tui::state::AppState::Reshare(state) => match state {
+    if let crate::tui::state::AppState::Reshare(state) = &app.state {
+        match state {
             ReshareState::Round1Setup => render_round1_setup(frame, app, form, area),
-            ReshareState::Round1Output { output_json } => {
-                render_round1_output(frame, output_json, area)
-            }
+            ReshareState::Round1Output { .. } => render_round1_output(frame, &app.viewer, area),
             ReshareState::FinalizeInput => render_finalize_input(frame, form, area),
             ReshareState::Complete { wallet_name } => render_complete(frame, wallet_name, area),
-        },
-        _ => {}
+        }
     }
 }
 
@@ -139,7 +136,7 @@ fn render_round1_setup(frame: &mut Frame, app: &App, form: &ReshareFormData, are
     frame.render_widget(help, chunks[5]);
 }
 
-fn render_round1_output(frame: &mut Frame, output_json: &str, area: Rect) {
+fn render_round1_output(frame: &mut Frame, viewer: &OutputViewer, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -161,14 +158,7 @@ fn render_round1_output(frame: &mut Frame, output_json: &str, area: Rect) {
         Paragraph::new("Share this with NEW parties:").style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
-    let output_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green))
-        .title("Output (copy this)");
-    let output_para = Paragraph::new(output_json)
-        .block(output_block)
-        .wrap(Wrap { trim: false });
-    frame.render_widget(output_para, chunks[1]);
+    viewer.render(frame, chunks[1], "Output (copy this)", Color::Green);
 
     let help =
         Paragraph::new("c: Copy | Enter: Go to Finalize (if new party) | Esc: Done (if old party)")
@@ -255,8 +245,10 @@ fn render_finalize_input(frame: &mut Frame, form: &ReshareFormData, area: Rect)
         frame.render_widget(error_para, chunks[6]);
     }
 
-    let help = Paragraph::new("Tab: Next | Space: Toggle | Enter: Finalize | Esc: Back")
-        .style(Style::default().fg(Color::DarkGray));
+    let help = Paragraph::new(
+        "Tab: Next | Space: Toggle | Ctrl+O: Import file | Enter: Finalize | Esc: Back",
+    )
+    .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[7]);
 }
 
@@ -11,8 +11,20 @@ use ratatui::{
 };
 
 use crate::tui::app::App;
-use crate::tui::components::{TextArea, TextInput};
+use crate::tui::components::{OutputViewer, TextArea, TextInput};
+use crate::tui::mouse::{MouseMap, Target};
+use crate::tui::screens::render_session_board;
 use crate::tui::state::{SendFormField, SendState};
+use frostdao::btc::amount;
+use frostdao::btc::fiat;
+use frostdao::btc::monitor::WatchState;
+use frostdao::crypto::birkhoff::{preview_coefficients, CoefficientPreview};
+use frostdao::protocol::directory;
+use frostdao::protocol::identity::IdentityKey;
+use frostdao::protocol::keygen::get_state_dir;
+use frostdao::protocol::session_board::SessionBoard;
+use frostdao::storage::FileStorage;
+use std::collections::BTreeMap;
 
 /// Script type for Taproot spending conditions
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -27,7 +39,7 @@ pub enum ScriptType {
     /// Recovery script - fallback after timeout
     Recovery,
     /// Hash Time-Locked Contract
-    HTLC,
+    Htlc,
 }
 
 impl ScriptType {
@@ -37,7 +49,7 @@ impl ScriptType {
             ScriptType::TimelockAbsolute,
             ScriptType::TimelockRelative,
             ScriptType::Recovery,
-            ScriptType::HTLC,
+            ScriptType::Htlc,
         ]
     }
 
@@ -47,7 +59,7 @@ impl ScriptType {
             ScriptType::TimelockAbsolute => "Timelock (Absolute)",
             ScriptType::TimelockRelative => "Timelock (Relative)",
             ScriptType::Recovery => "Recovery Script",
-            ScriptType::HTLC => "HTLC (Hash Lock)",
+            ScriptType::Htlc => "HTLC (Hash Lock)",
         }
     }
 
@@ -57,11 +69,12 @@ impl ScriptType {
             ScriptType::TimelockAbsolute => "Cannot spend until block height X",
             ScriptType::TimelockRelative => "Cannot spend until N blocks after confirmation",
             ScriptType::Recovery => "Fallback: recovery key can spend after timeout",
-            ScriptType::HTLC => "Requires hash preimage OR timeout for refund",
+            ScriptType::Htlc => "Requires hash preimage OR timeout for refund",
         }
     }
 
     /// Convert to the btc taproot_scripts module type
+    #[allow(dead_code)]
     pub fn to_script_type_input(&self) -> frostdao::btc::taproot_scripts::ScriptTypeInput {
         use frostdao::btc::taproot_scripts::ScriptTypeInput;
         match self {
@@ -69,7 +82,7 @@ impl ScriptType {
             ScriptType::TimelockAbsolute => ScriptTypeInput::TimelockAbsolute,
             ScriptType::TimelockRelative => ScriptTypeInput::TimelockRelative,
             ScriptType::Recovery => ScriptTypeInput::Recovery,
-            ScriptType::HTLC => ScriptTypeInput::Htlc,
+            ScriptType::Htlc => ScriptTypeInput::Htlc,
         }
     }
 }
@@ -77,8 +90,10 @@ impl ScriptType {
 /// Script configuration for advanced spending conditions
 #[derive(Clone)]
 pub struct ScriptConfig {
-    /// Selected script type
+    /// Script type whose fields are being edited
     pub script_type: ScriptType,
+    /// Script types whose leaves go into the tree (empty for key path only)
+    pub script_types: Vec<ScriptType>,
     /// Absolute timelock: block height
     pub timelock_height: TextInput,
     /// Relative timelock: number of blocks
@@ -105,10 +120,12 @@ impl Default for ScriptConfig {
     }
 }
 
+#[allow(dead_code)]
 impl ScriptConfig {
     pub fn new() -> Self {
         Self {
             script_type: ScriptType::None,
+            script_types: Vec::new(),
             timelock_height: TextInput::new("Block Height")
                 .with_placeholder("850000")
                 .numeric(),
@@ -133,31 +150,108 @@ impl ScriptConfig {
         *self = Self::new();
     }
 
-    /// Convert to ScriptParams for use with taproot_scripts module
-    pub fn to_script_params(&self) -> anyhow::Result<frostdao::btc::taproot_scripts::ScriptParams> {
-        use frostdao::btc::taproot_scripts::ScriptParams;
+    /// Add or remove a script type from the tree; None clears it
+    pub fn toggle(&mut self, script_type: &ScriptType) {
+        if *script_type == ScriptType::None {
+            self.script_types.clear();
+        } else if let Some(pos) = self.script_types.iter().position(|t| t == script_type) {
+            self.script_types.remove(pos);
+        } else {
+            self.script_types.push(script_type.clone());
+            self.script_type = script_type.clone();
+            self.focused_field = 0;
+            return;
+        }
+        self.script_type = self.script_types.last().cloned().unwrap_or_default();
+        self.focused_field = 0;
+    }
+
+    /// Whether a script type's leaves are in the tree
+    pub fn is_selected(&self, script_type: &ScriptType) -> bool {
+        match script_type {
+            ScriptType::None => self.script_types.is_empty(),
+            other => self.script_types.contains(other),
+        }
+    }
 
-        // Determine timeout based on script type
-        let timeout = match self.script_type {
-            ScriptType::Recovery => self.recovery_timeout.value(),
-            ScriptType::HTLC => self.htlc_timeout.value(),
-            _ => "",
+    /// One line per leaf the tree will hold, labelled as the address
+    /// records them
+    pub fn leaf_descriptions(&self) -> Vec<String> {
+        let or_unset = |value: &str| {
+            if value.is_empty() {
+                "?".to_string()
+            } else {
+                value.to_string()
+            }
         };
+        let mut lines = Vec::new();
+        for script_type in &self.script_types {
+            let details = match script_type {
+                ScriptType::None => vec![],
+                ScriptType::TimelockAbsolute => vec![format!(
+                    "wallet key after block {}",
+                    or_unset(self.timelock_height.value())
+                )],
+                ScriptType::TimelockRelative => vec![format!(
+                    "wallet key {} blocks after confirmation",
+                    or_unset(self.timelock_blocks.value())
+                )],
+                ScriptType::Recovery => vec![
+                    "wallet key, any time".to_string(),
+                    format!(
+                        "recovery key after block {}",
+                        or_unset(self.recovery_timeout.value())
+                    ),
+                ],
+                ScriptType::Htlc => vec![
+                    "wallet key with the hash preimage".to_string(),
+                    format!(
+                        "refund key after block {}",
+                        or_unset(self.htlc_timeout.value())
+                    ),
+                ],
+            };
+            let labels = script_type.to_script_type_input().leaf_labels();
+            for (label, detail) in labels.iter().zip(details) {
+                lines.push(format!("{}: {}", label, detail));
+            }
+        }
+        lines
+    }
 
-        ScriptParams::from_strings(
-            self.script_type.to_script_type_input(),
-            self.timelock_height.value(),
-            self.timelock_blocks.value(),
-            timeout,
-            self.recovery_pubkey.value(),
-            self.htlc_hash.value(),
-            self.htlc_refund_pubkey.value(),
-        )
+    /// Convert to ScriptParams for use with taproot_scripts module, one per
+    /// script type in the tree
+    pub fn to_script_params(
+        &self,
+    ) -> anyhow::Result<Vec<frostdao::btc::taproot_scripts::ScriptParams>> {
+        use frostdao::btc::taproot_scripts::ScriptParams;
+
+        self.script_types
+            .iter()
+            .map(|script_type| {
+                // Determine timeout based on script type
+                let timeout = match script_type {
+                    ScriptType::Recovery => self.recovery_timeout.value(),
+                    ScriptType::Htlc => self.htlc_timeout.value(),
+                    _ => "",
+                };
+
+                ScriptParams::from_strings(
+                    script_type.to_script_type_input(),
+                    self.timelock_height.value(),
+                    self.timelock_blocks.value(),
+                    timeout,
+                    self.recovery_pubkey.value(),
+                    self.htlc_hash.value(),
+                    self.htlc_refund_pubkey.value(),
+                )
+            })
+            .collect()
     }
 
     /// Check if this is a standard key-path spend (no scripts)
     pub fn is_key_path_only(&self) -> bool {
-        self.script_type == ScriptType::None
+        self.script_types.is_empty()
     }
 }
 
@@ -186,6 +280,8 @@ pub struct SendFormData {
     pub wallet_index: usize,
     pub to_address: TextInput,
     pub amount: TextInput,
+    /// What the payment is for; kept in the audit log
+    pub memo: TextInput,
     pub focused_field: SendFormField,
     pub session_id: String,
     pub sighash: String,
@@ -201,6 +297,8 @@ pub struct SendFormData {
     pub threshold: u32,
     pub selected_parties: Vec<bool>, // Which parties are selected for signing
     pub party_selector_index: usize, // Currently focused party in selector
+    pub hierarchical: bool,
+    pub party_ranks: BTreeMap<u32, u32>, // party index -> HTSS rank
     // HD address selection
     pub hd_enabled: bool,
     pub hd_addresses: Vec<(String, String, u32)>, // (address, pubkey_hex, index)
@@ -209,13 +307,21 @@ pub struct SendFormData {
     // UTXO and transaction info
     pub utxos: Vec<UtxoDisplay>,
     pub recent_txs: Vec<TxDisplay>,
+    /// First UTXO / transaction shown in the panels (mouse wheel)
+    pub utxo_scroll: usize,
+    pub tx_scroll: usize,
     pub total_balance: u64,
+    /// BTC price for fiat amount entry, when fiat display is configured
+    pub fiat_rate: Option<fiat::Rate>,
     // Fee estimation
     pub fee_rate: u64,       // sats/vbyte
     pub estimated_fee: u64,  // estimated fee for current amount
     pub utxos_needed: usize, // how many UTXOs needed
     // Script options (timelock, recovery, HTLC)
     pub script_config: ScriptConfig,
+    // Signing session progress (rebuilt from the pasted nonces and shares)
+    pub session_created_at: u64,
+    pub board: SessionBoard,
 }
 
 impl Default for SendFormData {
@@ -229,7 +335,8 @@ impl SendFormData {
         Self {
             wallet_index: 0,
             to_address: TextInput::new("To Address").with_placeholder("tb1q..."),
-            amount: TextInput::new("Amount (sats)").with_value("1000").numeric(),
+            amount: TextInput::new("Amount (1000sat, 0.001btc or 50USD)").with_value("1000"),
+            memo: TextInput::new("Memo (optional)").with_placeholder("Q3 contributor payout"),
             focused_field: SendFormField::ToAddress,
             session_id: String::new(),
             sighash: String::new(),
@@ -240,10 +347,13 @@ impl SendFormData {
             final_signature: String::new(),
             error_message: None,
             my_party_index: 1,
-            total_parties: 3,
-            threshold: 2,
-            selected_parties: vec![true, false, false], // Default: only self selected
+            // Sized from the wallet's metadata once one is chosen
+            total_parties: 0,
+            threshold: 0,
+            selected_parties: Vec::new(),
             party_selector_index: 0,
+            hierarchical: false,
+            party_ranks: BTreeMap::new(),
             // HD address selection defaults
             hd_enabled: false,
             hd_addresses: Vec::new(),
@@ -252,18 +362,53 @@ impl SendFormData {
             // UTXO and transaction info defaults
             utxos: Vec::new(),
             recent_txs: Vec::new(),
+            utxo_scroll: 0,
+            tx_scroll: 0,
             total_balance: 0,
+            fiat_rate: None,
             // Fee estimation defaults
             fee_rate: 1, // 1 sat/vbyte default
             estimated_fee: 0,
             utxos_needed: 0,
             script_config: ScriptConfig::new(),
+            session_created_at: 0,
+            board: SessionBoard::default(),
+        }
+    }
+
+    /// Tick or untick a signer, refusing to tick more than the threshold
+    pub fn toggle_party(&mut self, idx: usize) {
+        let Some(&currently_selected) = self.selected_parties.get(idx) else {
+            return;
+        };
+        if !currently_selected && self.selected_count() >= self.threshold as usize {
+            self.error_message = Some(format!(
+                "Cannot select more than {} parties",
+                self.threshold
+            ));
+            return;
+        }
+        self.selected_parties[idx] = !currently_selected;
+        self.error_message = None;
+    }
+
+    /// Amount in sats; a fiat entry such as `50USD` is converted at `fiat_rate`
+    pub fn amount_sats(&self) -> Option<u64> {
+        let value = self.amount.value().trim();
+        if let Ok(sats) = amount::parse_sats(value) {
+            return Some(sats);
         }
+        let amount: fiat::FiatAmount = value.parse().ok()?;
+        let rate = self
+            .fiat_rate
+            .as_ref()
+            .filter(|rate| rate.currency == amount.currency)?;
+        Some(rate.to_sats(amount.value))
     }
 
     /// Estimate fee for the current amount using coin selection
     pub fn estimate_fee(&mut self) {
-        let amount: u64 = self.amount.value().parse().unwrap_or(0);
+        let amount: u64 = self.amount_sats().unwrap_or(0);
         if amount == 0 {
             self.estimated_fee = 0;
             self.utxos_needed = 0;
@@ -272,7 +417,7 @@ impl SendFormData {
 
         // Get confirmed UTXOs sorted by value (largest first for fewer inputs)
         let mut confirmed: Vec<&UtxoDisplay> = self.utxos.iter().filter(|u| u.confirmed).collect();
-        confirmed.sort_by(|a, b| b.value.cmp(&a.value));
+        confirmed.sort_by_key(|u| std::cmp::Reverse(u.value));
 
         // Coin selection: select minimum UTXOs needed
         let mut selected_value: u64 = 0;
@@ -318,6 +463,19 @@ impl SendFormData {
         self.selected_parties.iter().filter(|&&x| x).count()
     }
 
+    /// HTSS rank check and coefficients for a full selection
+    pub fn rank_preview(&self) -> Option<CoefficientPreview> {
+        if !self.hierarchical || self.selected_count() != self.threshold as usize {
+            return None;
+        }
+        let signers: Option<Vec<(u32, u32)>> = self
+            .get_selected_indices()
+            .into_iter()
+            .map(|index| self.party_ranks.get(&index).map(|&rank| (index, rank)))
+            .collect();
+        preview_coefficients(&signers?, self.threshold, true).ok()
+    }
+
     /// Get list of selected party indices (1-based)
     pub fn get_selected_indices(&self) -> Vec<u32> {
         self.selected_parties
@@ -328,6 +486,33 @@ impl SendFormData {
             .collect()
     }
 
+    /// Rebuild the session board from the pasted nonces and shares
+    ///
+    /// Gift-wrapped Nostr DMs are opened with the wallet's Nostr key.
+    pub fn refresh_board(&mut self, wallet_name: &str) {
+        let storage = FileStorage::new(&get_state_dir(wallet_name)).ok();
+        let identity = storage
+            .as_ref()
+            .and_then(|storage| IdentityKey::load(storage).ok().flatten());
+        let signers = self.get_selected_indices();
+        let mut board = SessionBoard::new(
+            &self.session_id,
+            self.threshold as usize,
+            &signers,
+            self.session_created_at,
+        );
+        board.ingest(&self.nonces_input.content(), identity.as_ref());
+        board.ingest(&self.shares_input.content(), identity.as_ref());
+        if let Some(storage) = &storage {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            board.contacts = directory::contacts(storage, &signers, now);
+        }
+        self.board = board;
+    }
+
     /// Get the selected derivation path (if HD mode is enabled)
     pub fn get_derivation_path(&self) -> Option<(u32, u32)> {
         if self.use_hd_address && self.hd_enabled {
@@ -354,25 +539,26 @@ impl SendFormData {
 
 /// Render send wizard
 pub fn render_send(frame: &mut Frame, app: &App, form: &SendFormData, area: Rect) {
-    match &app.state {
-        crate::tui::state::AppState::Send(state) => match state {
+    if let crate::tui::state::AppState::Send(state) = &app.state {
+        match state {
             SendState::SelectWallet => render_select_wallet(frame, app, form, area),
-            SendState::SelectSigners { .. } => render_select_signers(frame, form, area),
+            SendState::SelectSigners { .. } => render_select_signers(frame, form, &app.mouse, area),
             SendState::SelectAddress { .. } => render_select_address(frame, form, area),
             SendState::ConfigureScript { .. } => render_configure_script(frame, form, area),
-            SendState::EnterDetails { .. } => render_enter_details(frame, form, area),
-            SendState::ShowSighash { sighash, .. } => render_show_sighash(frame, sighash, area),
-            SendState::GenerateNonce { nonce_output, .. } => {
-                render_generate_nonce(frame, nonce_output, area)
-            }
+            SendState::EnterDetails { .. } => render_enter_details(frame, form, &app.mouse, area),
+            SendState::ShowSighash { .. } => render_show_sighash(frame, &app.viewer, area),
+            SendState::GenerateNonce { .. } => render_generate_nonce(frame, &app.viewer, area),
             SendState::EnterNonces { .. } => render_enter_nonces(frame, form, area),
-            SendState::GenerateShare { share_output, .. } => {
-                render_generate_share(frame, share_output, area)
-            }
+            SendState::GenerateShare { .. } => render_generate_share(frame, &app.viewer, area),
             SendState::CombineShares { .. } => render_combine_shares(frame, form, area),
-            SendState::Complete { txid } => render_complete(frame, txid, area),
-        },
-        _ => {}
+            SendState::Complete { txid } => render_complete(
+                frame,
+                txid,
+                form.memo.value(),
+                app.watched_txs.get(txid),
+                area,
+            ),
+        }
     }
 }
 
@@ -484,7 +670,7 @@ fn render_select_wallet(frame: &mut Frame, app: &App, form: &SendFormData, area:
     frame.render_widget(help, chunks[4]);
 }
 
-fn render_select_signers(frame: &mut Frame, form: &SendFormData, area: Rect) {
+fn render_select_signers(frame: &mut Frame, form: &SendFormData, mouse: &MouseMap, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -532,7 +718,7 @@ fn render_select_signers(frame: &mut Frame, form: &SendFormData, area: Rect) {
     // Party list with checkboxes
     let mut party_lines = vec![];
     for i in 0..form.total_parties {
-        let party_idx = i as u32 + 1;
+        let party_idx = i + 1;
         let is_selected = form
             .selected_parties
             .get(i as usize)
@@ -555,21 +741,32 @@ fn render_select_signers(frame: &mut Frame, form: &SendFormData, area: Rect) {
         };
 
         let me_indicator = if is_me { " (You)" } else { "" };
+        let rank = match form.party_ranks.get(&party_idx) {
+            Some(rank) if form.hierarchical => format!("  rank {}", rank),
+            _ => String::new(),
+        };
 
         party_lines.push(Line::from(vec![
             Span::styled(arrow, style),
             Span::styled(checkbox, style),
             Span::styled(format!(" {}", SendFormData::party_label(party_idx)), style),
+            Span::styled(rank, Style::default().fg(Color::DarkGray)),
             Span::styled(me_indicator, Style::default().fg(Color::Cyan)),
         ]));
     }
 
-    let party_list = Paragraph::new(party_lines).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("Signing Parties"),
-    );
+    // Scroll so the focused party stays visible in large committees
+    let visible = chunks[1].height.saturating_sub(2) as usize;
+    let offset = (form.party_selector_index + 1).saturating_sub(visible);
+    let party_list = Paragraph::new(party_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Signing Parties ({})", form.total_parties)),
+        )
+        .scroll((offset as u16, 0));
     frame.render_widget(party_list, chunks[1]);
+    mouse.register(Target::Parties, chunks[1], offset, 1);
 
     // Selection status - must be exactly threshold
     let selected_count = form.selected_count();
@@ -594,28 +791,62 @@ fn render_select_signers(frame: &mut Frame, form: &SendFormData, area: Rect) {
         format!("too many, deselect {}", selected_count - threshold as usize)
     };
 
-    let status = Paragraph::new(vec![Line::from(vec![
-        Span::styled("Selected: ", Style::default().fg(Color::Gray)),
-        Span::styled(
-            format!("{}/{}", selected_count, threshold),
-            Style::default()
-                .fg(status_color)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::styled(" exactly required ", Style::default().fg(Color::Gray)),
-        Span::styled(
-            format!(
-                "({}: {})",
-                status_msg,
-                if selected_names.is_empty() {
-                    "none".to_string()
+    // Why an HTSS selection can or can't sign: sorted ranks against positions
+    let rank_line = match form.rank_preview() {
+        Some(preview) => {
+            let mut spans = vec![Span::styled(
+                "Ranks (sorted, rank <= position): ",
+                Style::default().fg(Color::Gray),
+            )];
+            for slot in &preview.rank_slots {
+                let (mark, color) = if slot.ok {
+                    ("✓", Color::Green)
                 } else {
-                    selected_names.join(", ")
-                }
+                    ("✗", Color::Red)
+                };
+                spans.push(Span::styled(
+                    format!("r{}@{}{} ", slot.rank, slot.position, mark),
+                    Style::default().fg(color),
+                ));
+            }
+            match preview.problems.first() {
+                None => spans.push(Span::styled("valid", Style::default().fg(Color::Green))),
+                Some(problem) => spans.push(Span::styled(
+                    problem.clone(),
+                    Style::default().fg(Color::Red),
+                )),
+            }
+            Line::from(spans)
+        }
+        None => Line::from(""),
+    };
+
+    let status = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("Selected: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{}/{}", selected_count, threshold),
+                Style::default()
+                    .fg(status_color)
+                    .add_modifier(Modifier::BOLD),
             ),
-            Style::default().fg(Color::Gray),
-        ),
-    ])]);
+            Span::styled(" exactly required ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!(
+                    "({}: {})",
+                    status_msg,
+                    if selected_names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        selected_names.join(", ")
+                    }
+                ),
+                Style::default().fg(Color::Gray),
+            ),
+        ]),
+        rank_line,
+    ])
+    .wrap(Wrap { trim: true });
     frame.render_widget(status, chunks[2]);
 
     if let Some(error) = &form.error_message {
@@ -795,6 +1026,7 @@ fn render_configure_script(frame: &mut Frame, form: &SendFormData, area: Rect) {
             Constraint::Length(3),  // Header
             Constraint::Length(12), // Script type selector
             Constraint::Min(8),     // Config fields
+            Constraint::Length(6),  // Leaf preview
             Constraint::Length(2),  // Error
             Constraint::Length(2),  // Help
         ])
@@ -809,7 +1041,7 @@ fn render_configure_script(frame: &mut Frame, form: &SendFormData, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![Span::styled(
-            "   These add script paths to your Taproot output",
+            "   These add script paths to your Taproot output; pick several for one tree",
             Style::default().fg(Color::DarkGray),
         )]),
     ]);
@@ -822,7 +1054,7 @@ fn render_configure_script(frame: &mut Frame, form: &SendFormData, area: Rect) {
     for (i, script_type) in script_types.iter().enumerate() {
         let is_selected = i == form.script_config.selected_index;
         let prefix = if is_selected { "▶ " } else { "  " };
-        let checkbox = if form.script_config.script_type == *script_type {
+        let checkbox = if form.script_config.is_selected(script_type) {
             "[●]"
         } else {
             "[ ]"
@@ -975,7 +1207,7 @@ fn render_configure_script(frame: &mut Frame, form: &SendFormData, area: Rect) {
                 )]),
             ]
         }
-        ScriptType::HTLC => {
+        ScriptType::Htlc => {
             let hash_focused = form.script_config.focused_field == 0;
             let timeout_focused = form.script_config.focused_field == 1;
             let refund_focused = form.script_config.focused_field == 2;
@@ -1059,19 +1291,45 @@ fn render_configure_script(frame: &mut Frame, form: &SendFormData, area: Rect) {
     );
     frame.render_widget(config_widget, chunks[2]);
 
+    // Leaves the tree will hold
+    let leaves = form.script_config.leaf_descriptions();
+    let leaf_lines: Vec<Line> = if leaves.is_empty() {
+        vec![Line::from(vec![Span::styled(
+            "No script leaves: key path only.",
+            Style::default().fg(Color::DarkGray),
+        )])]
+    } else {
+        leaves
+            .iter()
+            .enumerate()
+            .map(|(i, leaf)| {
+                Line::from(vec![
+                    Span::styled(format!("  [{}] ", i), Style::default().fg(Color::Cyan)),
+                    Span::styled(leaf.clone(), Style::default().fg(Color::White)),
+                ])
+            })
+            .collect()
+    };
+    let leaf_widget = Paragraph::new(leaf_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Leaves ({})", leaves.len())),
+    );
+    frame.render_widget(leaf_widget, chunks[3]);
+
     if let Some(error) = &form.error_message {
         let error_para = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
-        frame.render_widget(error_para, chunks[3]);
+        frame.render_widget(error_para, chunks[4]);
     }
 
     let help = Paragraph::new(
-        "↑/↓: Select type | Tab: Next field | Space: Toggle | Enter: Continue | Esc: Back",
+        "↑/↓: Select type | Tab: Next field | Space: Add/remove | Enter: Continue | Esc: Back",
     )
     .style(Style::default().fg(Color::DarkGray));
-    frame.render_widget(help, chunks[4]);
+    frame.render_widget(help, chunks[5]);
 }
 
-fn render_enter_details(frame: &mut Frame, form: &SendFormData, area: Rect) {
+fn render_enter_details(frame: &mut Frame, form: &SendFormData, mouse: &MouseMap, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -1092,7 +1350,8 @@ fn render_enter_details(frame: &mut Frame, form: &SendFormData, area: Rect) {
         .constraints([
             Constraint::Length(3), // To address
             Constraint::Length(3), // Amount
-            Constraint::Length(3), // Balance info
+            Constraint::Length(3), // Memo
+            Constraint::Length(5), // Balance, fiat and fee info
             Constraint::Min(1),    // Spacer
             Constraint::Length(2), // Error
             Constraint::Length(2), // Help
@@ -1109,9 +1368,13 @@ fn render_enter_details(frame: &mut Frame, form: &SendFormData, area: Rect) {
         left_chunks[1],
         form.focused_field == SendFormField::Amount,
     );
+    form.memo.render(
+        frame,
+        left_chunks[2],
+        form.focused_field == SendFormField::Memo,
+    );
 
     // Balance and fee info
-    let balance_btc = form.total_balance as f64 / 100_000_000.0;
     let confirmed_count = form.utxos.iter().filter(|u| u.confirmed).count();
     let confirmed_balance: u64 = form
         .utxos
@@ -1120,7 +1383,7 @@ fn render_enter_details(frame: &mut Frame, form: &SendFormData, area: Rect) {
         .map(|u| u.value)
         .sum();
 
-    let amount: u64 = form.amount.value().parse().unwrap_or(0);
+    let amount: u64 = form.amount_sats().unwrap_or(0);
     let total_needed = amount + form.estimated_fee;
 
     let mut balance_lines = vec![Line::from(vec![
@@ -1130,11 +1393,50 @@ fn render_enter_details(frame: &mut Frame, form: &SendFormData, area: Rect) {
             Style::default().fg(Color::Green),
         ),
         Span::styled(
-            format!(" ({:.8} BTC)", balance_btc),
+            format!(" ({})", amount::format_btc(confirmed_balance)),
             Style::default().fg(Color::DarkGray),
         ),
     ])];
 
+    // Fiat: the sat amount is always shown next to a fiat entry
+    let entered_fiat = form.amount.value().parse::<fiat::FiatAmount>().ok();
+    match (&entered_fiat, &form.fiat_rate) {
+        (Some(entry), Some(rate)) if rate.currency == entry.currency => {
+            balance_lines.push(Line::from(vec![
+                Span::styled("Sending: ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{} sats", amount),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  ({})", rate.describe()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+        (Some(entry), _) => {
+            balance_lines.push(Line::from(Span::styled(
+                format!(
+                    "No {} rate: run fiat-config --currency {}",
+                    entry.currency, entry.currency
+                ),
+                Style::default().fg(Color::Red),
+            )));
+        }
+        (None, Some(rate)) if amount > 0 => {
+            balance_lines.push(Line::from(vec![
+                Span::styled("≈ ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    fiat::format_fiat(rate.to_fiat(amount), &rate.currency),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+        _ => {}
+    }
+
     if form.estimated_fee > 0 {
         balance_lines.push(Line::from(vec![
             Span::styled("Est. fee: ", Style::default().fg(Color::Gray)),
@@ -1187,16 +1489,16 @@ fn render_enter_details(frame: &mut Frame, form: &SendFormData, area: Rect) {
     }
 
     let balance_para = Paragraph::new(balance_lines);
-    frame.render_widget(balance_para, left_chunks[2]);
+    frame.render_widget(balance_para, left_chunks[3]);
 
     if let Some(error) = &form.error_message {
         let error_para = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
-        frame.render_widget(error_para, left_chunks[4]);
+        frame.render_widget(error_para, left_chunks[5]);
     }
 
     let help = Paragraph::new("Tab: Next field | Enter: Prepare TX | Esc: Back")
         .style(Style::default().fg(Color::DarkGray));
-    frame.render_widget(help, left_chunks[5]);
+    frame.render_widget(help, left_chunks[6]);
 
     // Right side: UTXOs and recent transactions
     let right_chunks = Layout::default()
@@ -1206,9 +1508,11 @@ fn render_enter_details(frame: &mut Frame, form: &SendFormData, area: Rect) {
 
     // UTXOs panel
     render_utxos_panel(frame, form, right_chunks[0]);
+    mouse.register(Target::Utxos, right_chunks[0], form.utxo_scroll, 1);
 
     // Recent transactions panel
     render_recent_txs_panel(frame, form, right_chunks[1]);
+    mouse.register(Target::Transactions, right_chunks[1], form.tx_scroll, 1);
 }
 
 fn render_utxos_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
@@ -1228,7 +1532,14 @@ fn render_utxos_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
             Style::default().fg(Color::DarkGray),
         )));
     } else {
-        for utxo in form.utxos.iter().take(5) {
+        let (start, shown, more) = panel_window(form.utxos.len(), form.utxo_scroll, inner.height);
+        if start > 0 {
+            lines.push(Line::from(Span::styled(
+                format!("... {} above", start),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for utxo in form.utxos.iter().skip(start).take(shown) {
             let status = if utxo.confirmed { "✓" } else { "⏳" };
             let status_color = if utxo.confirmed {
                 Color::Green
@@ -1247,9 +1558,9 @@ fn render_utxos_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
                 ),
             ]));
         }
-        if form.utxos.len() > 5 {
+        if more > 0 {
             lines.push(Line::from(Span::styled(
-                format!("... and {} more", form.utxos.len() - 5),
+                format!("... and {} more", more),
                 Style::default().fg(Color::DarkGray),
             )));
         }
@@ -1259,6 +1570,21 @@ fn render_utxos_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
     frame.render_widget(para, inner);
 }
 
+/// Rows of a scrolled panel: first item, items shown and items left below,
+/// keeping a line each for the "above" and "more" markers
+fn panel_window(len: usize, scroll: usize, height: u16) -> (usize, usize, usize) {
+    let start = scroll.min(len.saturating_sub(1));
+    let mut rows = (height as usize).max(1);
+    if start > 0 {
+        rows = rows.saturating_sub(1).max(1);
+    }
+    if len - start > rows {
+        rows = rows.saturating_sub(1).max(1);
+    }
+    let shown = rows.min(len - start);
+    (start, shown, len - start - shown)
+}
+
 fn render_recent_txs_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
@@ -1276,7 +1602,15 @@ fn render_recent_txs_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
             Style::default().fg(Color::DarkGray),
         )));
     } else {
-        for tx in form.recent_txs.iter().take(5) {
+        let (start, shown, more) =
+            panel_window(form.recent_txs.len(), form.tx_scroll, inner.height);
+        if start > 0 {
+            lines.push(Line::from(Span::styled(
+                format!("... {} above", start),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for tx in form.recent_txs.iter().skip(start).take(shown) {
             let status = if tx.confirmed { "✓" } else { "⏳" };
             let status_color = if tx.confirmed {
                 Color::Green
@@ -1319,9 +1653,9 @@ fn render_recent_txs_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
                 ),
             ]));
         }
-        if form.recent_txs.len() > 5 {
+        if more > 0 {
             lines.push(Line::from(Span::styled(
-                format!("... and {} more", form.recent_txs.len() - 5),
+                format!("... and {} more", more),
                 Style::default().fg(Color::DarkGray),
             )));
         }
@@ -1331,7 +1665,7 @@ fn render_recent_txs_panel(frame: &mut Frame, form: &SendFormData, area: Rect) {
     frame.render_widget(para, inner);
 }
 
-fn render_show_sighash(frame: &mut Frame, sighash: &str, area: Rect) {
+fn render_show_sighash(frame: &mut Frame, viewer: &OutputViewer, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -1356,21 +1690,14 @@ fn render_show_sighash(frame: &mut Frame, sighash: &str, area: Rect) {
     .style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
-    let sighash_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green))
-        .title("Sighash (copy this)");
-    let sighash_para = Paragraph::new(sighash)
-        .block(sighash_block)
-        .wrap(Wrap { trim: false });
-    frame.render_widget(sighash_para, chunks[1]);
+    viewer.render(frame, chunks[1], "Sighash (copy this)", Color::Green);
 
     let help = Paragraph::new("c: Copy | Enter: Generate Nonce | Esc: Back")
         .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[2]);
 }
 
-fn render_generate_nonce(frame: &mut Frame, nonce_output: &str, area: Rect) {
+fn render_generate_nonce(frame: &mut Frame, viewer: &OutputViewer, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -1411,14 +1738,12 @@ fn render_generate_nonce(frame: &mut Frame, nonce_output: &str, area: Rect) {
     ]);
     frame.render_widget(instructions, chunks[0]);
 
-    let nonce_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green))
-        .title("Your Nonce JSON (copy & share with other signers)");
-    let nonce_para = Paragraph::new(nonce_output)
-        .block(nonce_block)
-        .wrap(Wrap { trim: false });
-    frame.render_widget(nonce_para, chunks[1]);
+    viewer.render(
+        frame,
+        chunks[1],
+        "Your Nonce JSON (copy & share with other signers)",
+        Color::Green,
+    );
 
     let help = Paragraph::new("c: Copy | Enter: Collect nonces from others | Esc: Back")
         .style(Style::default().fg(Color::DarkGray));
@@ -1434,6 +1759,12 @@ fn render_enter_nonces(frame: &mut Frame, form: &SendFormData, area: Rect) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(40), Constraint::Length(44)])
+        .split(inner);
+    render_session_board(frame, &form.board, form.my_party_index, columns[1]);
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -1442,13 +1773,11 @@ fn render_enter_nonces(frame: &mut Frame, form: &SendFormData, area: Rect) {
             Constraint::Length(3), // Status + Error
             Constraint::Length(2), // Help
         ])
-        .split(inner);
+        .split(columns[0]);
 
-    // Count nonces in input
-    let nonces_content = form.nonces_input.content();
-    let nonce_count = nonces_content.matches("\"party_index\"").count();
+    let nonce_count = form.board.nonces.len();
     let threshold = form.threshold as usize;
-    let has_enough = nonce_count >= threshold;
+    let has_enough = form.board.nonces_complete();
 
     let instructions = Paragraph::new(vec![
         Line::from(vec![Span::styled(
@@ -1460,7 +1789,7 @@ fn render_enter_nonces(frame: &mut Frame, form: &SendFormData, area: Rect) {
         Line::from(""),
         Line::from(vec![
             Span::styled("   Format: ", Style::default().fg(Color::Gray)),
-            Span::raw("Paste JSON nonces, space or newline separated"),
+            Span::raw("Paste JSON nonces or Nostr events, space or newline separated"),
         ]),
         Line::from(vec![
             Span::styled("   Example: ", Style::default().fg(Color::Gray)),
@@ -1504,12 +1833,12 @@ fn render_enter_nonces(frame: &mut Frame, form: &SendFormData, area: Rect) {
     let status = Paragraph::new(status_lines);
     frame.render_widget(status, chunks[2]);
 
-    let help = Paragraph::new("Enter: Generate Signature Share | Esc: Back")
+    let help = Paragraph::new("Ctrl+O: Import file | Enter: Generate Signature Share | Esc: Back")
         .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[3]);
 }
 
-fn render_generate_share(frame: &mut Frame, share_output: &str, area: Rect) {
+fn render_generate_share(frame: &mut Frame, viewer: &OutputViewer, area: Rect) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
@@ -1531,14 +1860,12 @@ fn render_generate_share(frame: &mut Frame, share_output: &str, area: Rect) {
         .style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
-    let share_block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green))
-        .title("Your Signature Share (copy this)");
-    let share_para = Paragraph::new(share_output)
-        .block(share_block)
-        .wrap(Wrap { trim: false });
-    frame.render_widget(share_para, chunks[1]);
+    viewer.render(
+        frame,
+        chunks[1],
+        "Your Signature Share (copy this)",
+        Color::Green,
+    );
 
     let help = Paragraph::new("c: Copy | Enter: Combine (Aggregator) | Esc: Done")
         .style(Style::default().fg(Color::DarkGray));
@@ -1554,6 +1881,12 @@ fn render_combine_shares(frame: &mut Frame, form: &SendFormData, area: Rect) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(40), Constraint::Length(44)])
+        .split(inner);
+    render_session_board(frame, &form.board, form.my_party_index, columns[1]);
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -1562,19 +1895,17 @@ fn render_combine_shares(frame: &mut Frame, form: &SendFormData, area: Rect) {
             Constraint::Length(3), // Status + Error
             Constraint::Length(2), // Help
         ])
-        .split(inner);
+        .split(columns[0]);
 
-    let instructions = Paragraph::new("Paste all signature shares to combine:")
+    let instructions = Paragraph::new("Paste all signature shares (or Nostr events) to combine:")
         .style(Style::default().fg(Color::Yellow));
     frame.render_widget(instructions, chunks[0]);
 
     form.shares_input.render(frame, chunks[1], true);
 
-    // Share count status
-    let shares_content = form.shares_input.content();
-    let share_count = shares_content.matches("\"party_index\"").count();
+    let share_count = form.board.shares.len();
     let threshold = form.threshold as usize;
-    let has_enough = share_count >= threshold;
+    let has_enough = form.board.shares_complete();
 
     let status_color = if has_enough { Color::Green } else { Color::Red };
     let status_icon = if has_enough { "✓" } else { "⚠" };
@@ -1608,12 +1939,38 @@ fn render_combine_shares(frame: &mut Frame, form: &SendFormData, area: Rect) {
     let status = Paragraph::new(status_lines);
     frame.render_widget(status, chunks[2]);
 
-    let help = Paragraph::new("Enter: Combine & Complete | Esc: Back")
+    let help = Paragraph::new("Ctrl+O: Import file | Enter: Combine & Complete | Esc: Back")
         .style(Style::default().fg(Color::DarkGray));
     frame.render_widget(help, chunks[3]);
 }
 
-fn render_complete(frame: &mut Frame, txid: &str, area: Rect) {
+/// Confirmation status line for a watched transaction
+fn watch_status_line(watch: Option<&WatchState>) -> Line<'static> {
+    let (text, color) = match watch {
+        None => (
+            "In a real transaction, this would be broadcast to the network.".to_string(),
+            Color::Gray,
+        ),
+        Some(WatchState::NotFound) => (
+            "❓ Waiting for backend to see tx...".to_string(),
+            Color::Yellow,
+        ),
+        Some(WatchState::Pending) => ("⏳ In mempool, unconfirmed".to_string(), Color::Yellow),
+        Some(WatchState::Confirmed { block_height, .. }) => (
+            format!("✓ Confirmed in block {}", block_height),
+            Color::Green,
+        ),
+    };
+    Line::from(Span::styled(text, Style::default().fg(color)))
+}
+
+fn render_complete(
+    frame: &mut Frame,
+    txid: &str,
+    memo: &str,
+    watch: Option<&WatchState>,
+    area: Rect,
+) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Green))
@@ -1646,7 +2003,7 @@ fn render_complete(frame: &mut Frame, txid: &str, area: Rect) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan))
         .title("Result");
-    let info = Paragraph::new(vec![
+    let mut lines = vec![
         Line::from(vec![Span::styled(
             "Signature/TXID: ",
             Style::default().fg(Color::Gray),
@@ -1658,11 +2015,22 @@ fn render_complete(frame: &mut Frame, txid: &str, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
+    ];
+    if !memo.trim().is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled("Memo: ", Style::default().fg(Color::Gray)),
+            Span::raw(memo.trim()),
+        ]));
+        lines.push(Line::from(""));
+    }
+    lines.extend([
+        watch_status_line(watch),
+        Line::from(""),
         Line::from("Threshold signers contributed their shares to create this signature."),
-        Line::from("In a real transaction, this would be broadcast to the network."),
-    ])
-    .block(info_block)
-    .wrap(Wrap { trim: false });
+    ]);
+    let info = Paragraph::new(lines)
+        .block(info_block)
+        .wrap(Wrap { trim: false });
     frame.render_widget(info, chunks[1]);
 
     let help = Paragraph::new(Line::from(vec![
@@ -0,0 +1,108 @@
+//! Session board - live per-party progress of a signing session
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use frostdao::protocol::session_board::{PartyStatus, SessionBoard};
+
+fn mark(done: bool) -> Span<'static> {
+    if done {
+        Span::styled("  ✓    ", Style::default().fg(Color::Green))
+    } else {
+        Span::styled("  ·    ", Style::default().fg(Color::DarkGray))
+    }
+}
+
+/// Render the session board: who sent a nonce or share, and who is pending
+pub fn render_session_board(frame: &mut Frame, board: &SessionBoard, my_index: u32, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta))
+        .title(" Session Board ");
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Session: ", Style::default().fg(Color::Gray)),
+        Span::raw(board.session_id.chars().take(16).collect::<String>()),
+    ])];
+    if board.created_at > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("Started: ", Style::default().fg(Color::Gray)),
+            Span::raw(format!("{} ago", board.elapsed(now))),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Party      Nonce  Share  Status",
+        Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::BOLD),
+    )));
+
+    for &party in &board.signers {
+        let status = board.status(party);
+        let status_color = match status {
+            PartyStatus::Done => Color::Green,
+            PartyStatus::NotSigning => Color::DarkGray,
+            PartyStatus::AwaitingOthers => Color::Gray,
+            PartyStatus::AwaitingNonce | PartyStatus::AwaitingShare => Color::Yellow,
+        };
+        let name = if party == my_index {
+            format!("{} (you)", party)
+        } else {
+            party.to_string()
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:<10}", name)),
+            mark(board.nonces.contains(&party)),
+            mark(board.shares.contains(&party)),
+            Span::styled(status.label(), Style::default().fg(status_color)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    let (icon, color) = if board.shares_complete() {
+        ("✓ ", Color::Green)
+    } else {
+        ("⏳ ", Color::Yellow)
+    };
+    lines.push(Line::from(vec![
+        Span::styled(icon, Style::default().fg(color)),
+        Span::styled(board.pending_action(), Style::default().fg(color)),
+    ]));
+
+    // Whom to chase for what is still missing
+    for &party in &board.signers {
+        let pending = matches!(
+            board.status(party),
+            PartyStatus::AwaitingNonce | PartyStatus::AwaitingShare
+        );
+        if let Some(contact) = board.contacts.get(&party).filter(|_| pending) {
+            lines.push(Line::from(Span::styled(
+                format!("   Party {}: {}", party, contact),
+                Style::default().fg(Color::Gray),
+            )));
+        }
+    }
+
+    for reason in &board.ignored {
+        lines.push(Line::from(Span::styled(
+            format!("⚠ Ignored: {}", reason),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
@@ -10,7 +10,11 @@ use ratatui::{
 };
 
 use crate::tui::app::App;
-use crate::tui::state::{WalletAction, WalletDetailsState};
+use crate::tui::mouse::Target;
+use crate::tui::state::{NotesForm, WalletAction, WalletDetailsState};
+use frostdao::btc::amount;
+use frostdao::btc::fiat;
+use frostdao::protocol::wallet::WalletNotes;
 
 /// Render the wallet details screen
 pub fn render_wallet_details(frame: &mut Frame, app: &App, state: &WalletDetailsState, area: Rect) {
@@ -20,11 +24,17 @@ pub fn render_wallet_details(frame: &mut Frame, app: &App, state: &WalletDetails
         .split(area);
 
     render_wallet_info(frame, app, &state.wallet_name, chunks[0]);
-    render_action_menu(frame, state, chunks[1]);
+    render_action_menu(frame, state, app, chunks[1]);
 
-    // Render confirmation dialog overlay if deleting
+    // Render confirmation dialog overlay if deleting, archiving or renaming
     if state.confirm_delete {
-        render_delete_confirmation(frame, &state.wallet_name, area);
+        render_delete_confirmation(frame, &state.wallet_name, &state.confirm_input, area);
+    } else if state.confirm_archive {
+        render_archive_confirmation(frame, &state.wallet_name, area);
+    } else if let Some(new_name) = &state.rename_input {
+        render_rename_dialog(frame, &state.wallet_name, new_name, area);
+    } else if let Some(form) = &state.notes_form {
+        render_notes_form(frame, form, area);
     }
 
     // Render QR code popup if showing
@@ -36,22 +46,52 @@ pub fn render_wallet_details(frame: &mut Frame, app: &App, state: &WalletDetails
     }
 }
 
-fn render_delete_confirmation(frame: &mut Frame, wallet_name: &str, area: Rect) {
+/// Centered popup with a colored border
+fn render_popup(
+    frame: &mut Frame,
+    title: &str,
+    color: Color,
+    content: Vec<Line>,
+    height: u16,
+    area: Rect,
+) {
     use ratatui::widgets::Clear;
 
     // Center the dialog
-    let popup_width = 50;
-    let popup_height = 8;
+    let popup_width = 54;
     let popup_area = Rect {
         x: area.x + (area.width.saturating_sub(popup_width)) / 2,
-        y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 2,
         width: popup_width.min(area.width),
-        height: popup_height.min(area.height),
+        height: height.min(area.height),
     };
 
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
 
+    let dialog = Paragraph::new(content)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(color))
+                .title(title)
+                .style(Style::default().bg(Color::Black)),
+        )
+        .alignment(ratatui::layout::Alignment::Center);
+
+    frame.render_widget(dialog, popup_area);
+}
+
+fn wallet_span(wallet_name: &str) -> Span<'_> {
+    Span::styled(
+        wallet_name,
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+fn render_delete_confirmation(frame: &mut Frame, wallet_name: &str, typed: &str, area: Rect) {
     let content = vec![
         Line::from(""),
         Line::from(vec![Span::styled(
@@ -60,34 +100,139 @@ fn render_delete_confirmation(frame: &mut Frame, wallet_name: &str, area: Rect)
         )]),
         Line::from(""),
         Line::from(vec![
-            Span::raw("This will permanently delete "),
-            Span::styled(
-                wallet_name,
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Span::raw("This will shred and delete "),
+            wallet_span(wallet_name),
+        ]),
+        Line::from("Type the wallet name to confirm:"),
+        Line::from(Span::styled(
+            format!("{}▏", typed),
+            Style::default().fg(Color::White),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(Color::Green)),
+            Span::raw(" = Delete  |  "),
+            Span::styled("Esc", Style::default().fg(Color::Red)),
+            Span::raw(" = Cancel"),
+        ]),
+    ];
+    render_popup(frame, " Confirm Delete ", Color::Red, content, 10, area);
+}
+
+fn render_archive_confirmation(frame: &mut Frame, wallet_name: &str, area: Rect) {
+    let content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Archive "),
+            wallet_span(wallet_name),
+            Span::raw("?"),
         ]),
         Line::from(""),
+        Line::from("It moves to .frost_state/.archive/ and leaves the list."),
+        Line::from("Move the folder back to restore it."),
+        Line::from(""),
         Line::from(vec![
             Span::styled("Y", Style::default().fg(Color::Green)),
-            Span::raw(" = Yes, delete  |  "),
+            Span::raw(" = Yes, archive  |  "),
             Span::styled("N", Style::default().fg(Color::Red)),
             Span::raw(" = No, cancel"),
         ]),
     ];
+    render_popup(frame, " Confirm Archive ", Color::Yellow, content, 9, area);
+}
 
-    let dialog = Paragraph::new(content)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Red))
-                .title(" Confirm Delete ")
-                .style(Style::default().bg(Color::Black)),
-        )
-        .alignment(ratatui::layout::Alignment::Center);
+fn render_rename_dialog(frame: &mut Frame, wallet_name: &str, new_name: &str, area: Rect) {
+    let content = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Rename "),
+            wallet_span(wallet_name),
+            Span::raw(" to:"),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{}▏", new_name),
+            Style::default().fg(Color::White),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(Color::Green)),
+            Span::raw(" = Rename  |  "),
+            Span::styled("Esc", Style::default().fg(Color::Red)),
+            Span::raw(" = Cancel"),
+        ]),
+    ];
+    render_popup(frame, " Rename Wallet ", Color::Cyan, content, 8, area);
+}
 
-    frame.render_widget(dialog, popup_area);
+fn render_notes_form(frame: &mut Frame, form: &NotesForm, area: Rect) {
+    let hints = [
+        "",
+        "1=alice@example.org; 2=bob",
+        "e.g. 2024-03-01",
+        "comma-separated",
+    ];
+    let mut content = vec![Line::from("")];
+    for (i, label) in NotesForm::LABELS.iter().enumerate() {
+        let focused = i == form.focus;
+        let (marker, style) = if focused {
+            ("▶ ", Style::default().fg(Color::Yellow))
+        } else {
+            ("  ", Style::default().fg(Color::Gray))
+        };
+        content.push(Line::from(vec![
+            Span::styled(format!("{}{:<12}", marker, label), style),
+            Span::styled(hints[i], Style::default().fg(Color::DarkGray)),
+        ]));
+        let cursor = if focused { "▏" } else { "" };
+        content.push(Line::from(Span::styled(
+            format!("{}{}", form.fields[i], cursor),
+            Style::default().fg(Color::White),
+        )));
+    }
+    content.push(Line::from(""));
+    if let Some(error) = &form.error {
+        content.push(Line::from(Span::styled(
+            error.as_str(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    content.push(Line::from(vec![
+        Span::styled("Tab", Style::default().fg(Color::Yellow)),
+        Span::raw(" Next  "),
+        Span::styled("Enter", Style::default().fg(Color::Green)),
+        Span::raw(" Save  "),
+        Span::styled("Esc", Style::default().fg(Color::Red)),
+        Span::raw(" Cancel"),
+    ]));
+    render_popup(frame, " Wallet Notes ", Color::Cyan, content, 15, area);
+}
+
+/// Wallet notes as label/value lines; contacts and date only when `full`
+pub(super) fn notes_lines(notes: &WalletNotes, full: bool) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{}: ", label), Style::default().fg(Color::Gray)),
+            Span::styled(value, Style::default().fg(Color::White)),
+        ])
+    };
+    let mut lines = Vec::new();
+    if !notes.description.is_empty() {
+        lines.push(field("Note", notes.description.clone()));
+    }
+    if !notes.tags.is_empty() {
+        lines.push(field("Tags", notes.tags.join(", ")));
+    }
+    if !full {
+        return lines;
+    }
+    if let Some(created) = &notes.created {
+        lines.push(field("Created", created.clone()));
+    }
+    for (index, who) in &notes.contacts {
+        lines.push(field(&format!("Party {}", index), who.clone()));
+    }
+    lines
 }
 
 fn render_wallet_info(frame: &mut Frame, app: &App, wallet_name: &str, area: Rect) {
@@ -118,6 +263,14 @@ fn render_wallet_info(frame: &mut Frame, app: &App, wallet_name: &str, area: Rec
             ]));
         }
 
+        // Curve (only shown off the Bitcoin default)
+        if wallet.curve != frostdao::crypto::ciphersuite::Curve::Secp256k1 {
+            lines.push(Line::from(vec![
+                Span::styled("Curve: ", Style::default().fg(Color::Gray)),
+                Span::styled(wallet.curve.to_string(), Style::default().fg(Color::Cyan)),
+            ]));
+        }
+
         // Mode
         if let Some(h) = wallet.hierarchical {
             lines.push(Line::from(vec![
@@ -147,11 +300,29 @@ fn render_wallet_info(frame: &mut Frame, app: &App, wallet_name: &str, area: Rec
             )]));
         }
 
+        // Nostr identity (signs payloads, receives encrypted shares)
+        if let Some(npub) = &wallet.npub {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![Span::styled(
+                "Nostr identity: ",
+                Style::default().fg(Color::Gray),
+            )]));
+            lines.push(Line::from(vec![Span::styled(
+                npub.clone(),
+                Style::default().fg(Color::Magenta),
+            )]));
+        }
+
+        // Notes (dkg-notes / Edit Notes)
+        if !wallet.notes.is_empty() {
+            lines.push(Line::from(""));
+            lines.extend(notes_lines(&wallet.notes, true));
+        }
+
         lines.push(Line::from(""));
 
         // Balance (if cached)
-        let cache_key = format!("{}:{:?}", wallet.name, app.network);
-        if let Some(info) = app.balance_cache.get(&cache_key) {
+        if let Some(info) = app.cached_balance(&wallet.name) {
             lines.push(Line::from(vec![
                 Span::styled("Balance: ", Style::default().fg(Color::Gray)),
                 Span::styled(
@@ -162,15 +333,27 @@ fn render_wallet_info(frame: &mut Frame, app: &App, wallet_name: &str, area: Rec
                 ),
             ]));
 
-            let btc = info.balance_sats as f64 / 100_000_000.0;
             lines.push(Line::from(vec![
                 Span::styled("         ", Style::default()),
                 Span::styled(
-                    format!("({:.8} BTC)", btc),
+                    format!("({})", amount::format_btc(info.balance_sats)),
                     Style::default().fg(Color::Gray),
                 ),
             ]));
 
+            if let Some(rate) = &app.fiat_rate {
+                lines.push(Line::from(vec![
+                    Span::styled("         ", Style::default()),
+                    Span::styled(
+                        format!(
+                            "≈ {}",
+                            fiat::format_fiat(rate.to_fiat(info.balance_sats), &rate.currency)
+                        ),
+                        Style::default().fg(Color::Gray),
+                    ),
+                ]));
+            }
+
             lines.push(Line::from(vec![
                 Span::styled("UTXOs: ", Style::default().fg(Color::Gray)),
                 Span::styled(
@@ -178,6 +361,12 @@ fn render_wallet_info(frame: &mut Frame, app: &App, wallet_name: &str, area: Rec
                     Style::default().fg(Color::White),
                 ),
             ]));
+            lines.push(super::home::updated_line(info, app.offline));
+        } else if app.offline {
+            lines.push(Line::from(vec![
+                Span::styled("Balance: ", Style::default().fg(Color::Gray)),
+                Span::styled("Offline, nothing cached", Style::default().fg(Color::Red)),
+            ]));
         } else {
             lines.push(Line::from(vec![
                 Span::styled("Balance: ", Style::default().fg(Color::Gray)),
@@ -318,7 +507,8 @@ fn render_qr_popup(frame: &mut Frame, address: &str, area: Rect) {
     frame.render_widget(qr_widget, popup_area);
 }
 
-fn render_action_menu(frame: &mut Frame, state: &WalletDetailsState, area: Rect) {
+fn render_action_menu(frame: &mut Frame, state: &WalletDetailsState, app: &App, area: Rect) {
+    let offline = app.offline;
     let actions = WalletAction::all();
 
     let items: Vec<ListItem> = actions
@@ -328,18 +518,24 @@ fn render_action_menu(frame: &mut Frame, state: &WalletDetailsState, area: Rect)
             let is_selected = i == state.selected_action;
             let prefix = if is_selected { "▶ " } else { "  " };
 
-            let style = if is_selected {
-                Style::default()
+            let disabled = offline && *action == WalletAction::Send;
+            let style = match (is_selected, disabled) {
+                (_, true) => Style::default().fg(Color::DarkGray),
+                (true, false) => Style::default()
                     .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::BOLD),
+                (false, false) => Style::default().fg(Color::White),
+            };
+            let description = if disabled {
+                "Unavailable offline"
             } else {
-                Style::default().fg(Color::White)
+                action.description()
             };
 
             let content = vec![
                 Line::from(Span::styled(format!("{}{}", prefix, action.label()), style)),
                 Line::from(Span::styled(
-                    format!("    {}", action.description()),
+                    format!("    {}", description),
                     Style::default().fg(Color::DarkGray),
                 )),
             ];
@@ -356,6 +552,7 @@ fn render_action_menu(frame: &mut Frame, state: &WalletDetailsState, area: Rect)
     );
 
     frame.render_widget(list, area);
+    app.mouse.register(Target::WalletActions, area, 0, 2);
 
     // Help text at bottom
     let help_area = Rect {
@@ -3,6 +3,7 @@
 //! Defines all states for the TUI state machine.
 
 use bitcoin::Network;
+use frostdao::btc::{electrum, esplora, simulate};
 
 /// Network selection for the TUI
 #[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
@@ -14,7 +15,7 @@ pub enum NetworkSelection {
 }
 
 impl NetworkSelection {
-    pub fn to_bitcoin_network(&self) -> Network {
+    pub fn to_bitcoin_network(self) -> Network {
         match self {
             Self::Testnet => Network::Testnet,
             Self::Signet => Network::Signet,
@@ -22,12 +23,15 @@ impl NetworkSelection {
         }
     }
 
-    pub fn mempool_api_base(&self) -> &'static str {
-        match self {
-            Self::Testnet => "https://mempool.space/testnet/api",
-            Self::Signet => "https://mempool.space/signet/api",
-            Self::Mainnet => "https://mempool.space/api",
-        }
+    /// Esplora endpoint for this network (mempool.space unless configured)
+    pub fn esplora(&self) -> anyhow::Result<esplora::Endpoint> {
+        esplora::resolve(self.to_bitcoin_network())
+    }
+
+    /// Whether chain queries go to an Esplora endpoint rather than through
+    /// `transaction` (simulated chain or Electrum server)
+    pub fn has_esplora(&self) -> bool {
+        !simulate::is_simulated() && !electrum::is_configured(self.to_bitcoin_network())
     }
 
     pub fn display_name(&self) -> &'static str {
@@ -70,6 +74,9 @@ pub enum AppState {
 
     /// Mnemonic backup screen
     MnemonicBackup(MnemonicState),
+
+    /// Raw transaction viewer
+    DecodeTx(DecodeTxState),
 }
 
 /// Available wallet actions
@@ -77,8 +84,12 @@ pub enum AppState {
 pub enum WalletAction {
     Send,
     ViewAddresses,
+    DecodeTx,
     BackupMnemonic,
     Reshare,
+    EditNotes,
+    Rename,
+    Archive,
     DeleteWallet,
 }
 
@@ -87,8 +98,12 @@ impl WalletAction {
         &[
             WalletAction::Send,
             WalletAction::ViewAddresses,
+            WalletAction::DecodeTx,
             WalletAction::BackupMnemonic,
             WalletAction::Reshare,
+            WalletAction::EditNotes,
+            WalletAction::Rename,
+            WalletAction::Archive,
             WalletAction::DeleteWallet,
         ]
     }
@@ -97,8 +112,12 @@ impl WalletAction {
         match self {
             WalletAction::Send => "Send Transaction",
             WalletAction::ViewAddresses => "View HD Addresses",
+            WalletAction::DecodeTx => "Decode Transaction",
             WalletAction::BackupMnemonic => "Backup Mnemonic",
             WalletAction::Reshare => "Reshare Keys",
+            WalletAction::EditNotes => "Edit Notes",
+            WalletAction::Rename => "Rename Wallet",
+            WalletAction::Archive => "Archive Wallet",
             WalletAction::DeleteWallet => "⚠ Delete Wallet",
         }
     }
@@ -107,9 +126,13 @@ impl WalletAction {
         match self {
             WalletAction::Send => "Sign and broadcast a Bitcoin transaction",
             WalletAction::ViewAddresses => "View derived HD addresses",
+            WalletAction::DecodeTx => "Inspect a raw transaction against this wallet",
             WalletAction::BackupMnemonic => "Backup your secret share as 24 words",
             WalletAction::Reshare => "Proactively refresh secret shares",
-            WalletAction::DeleteWallet => "Permanently delete this wallet (cannot undo!)",
+            WalletAction::EditNotes => "Description, party contacts, date and tags",
+            WalletAction::Rename => "Give this wallet a new name",
+            WalletAction::Archive => "Move to .frost_state/.archive (restorable)",
+            WalletAction::DeleteWallet => "Shred and delete this wallet (cannot undo!)",
         }
     }
 }
@@ -123,10 +146,45 @@ pub struct WalletDetailsState {
     pub selected_action: usize,
     /// Confirm delete mode
     pub confirm_delete: bool,
+    /// Wallet name typed to confirm the delete
+    pub confirm_input: String,
+    /// Confirm archive mode
+    pub confirm_archive: bool,
+    /// New name being typed, while renaming
+    pub rename_input: Option<String>,
+    /// Notes being edited
+    pub notes_form: Option<NotesForm>,
     /// Show QR code popup
     pub show_qr: bool,
 }
 
+/// Wallet notes edit form
+#[derive(Clone, Default)]
+pub struct NotesForm {
+    /// Description, contacts (`1=alice; 2=bob`), created, tags (comma-separated)
+    pub fields: [String; 4],
+    /// Field being edited
+    pub focus: usize,
+    /// Why the last save failed
+    pub error: Option<String>,
+}
+
+impl NotesForm {
+    pub const LABELS: [&'static str; 4] = ["Description", "Contacts", "Created", "Tags"];
+
+    pub fn from_notes(notes: &frostdao::protocol::wallet::WalletNotes) -> Self {
+        Self {
+            fields: [
+                notes.description.clone(),
+                notes.contacts_text(),
+                notes.created.clone().unwrap_or_default(),
+                notes.tags.join(", "),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
 /// HD Address list state
 #[derive(Clone, Default)]
 pub struct AddressListState {
@@ -163,6 +221,17 @@ pub struct MnemonicState {
     pub revealed: bool,
 }
 
+/// Raw transaction viewer state
+#[derive(Clone, Default)]
+pub struct DecodeTxState {
+    /// Wallet whose inputs and outputs are marked
+    pub wallet_name: String,
+    /// Decoded transaction, once the pasted hex has been read
+    pub output: Option<String>,
+    /// Error message if any
+    pub error: Option<String>,
+}
+
 /// Keygen wizard state
 #[derive(Clone, Default)]
 pub enum KeygenState {
@@ -248,6 +317,10 @@ pub enum KeygenFormField {
     Name,
     Threshold,
     NParties,
+    /// This machine's party (blank runs every party here)
+    MyIndex,
+    /// This party's HTSS rank
+    Rank,
 }
 
 /// Reshare form field focus
@@ -316,17 +389,23 @@ pub enum SendFormField {
     #[default]
     ToAddress,
     Amount,
+    Memo,
 }
 
 impl SendFormField {
     pub fn next(&self) -> Self {
         match self {
             Self::ToAddress => Self::Amount,
-            Self::Amount => Self::ToAddress,
+            Self::Amount => Self::Memo,
+            Self::Memo => Self::ToAddress,
         }
     }
 
     pub fn prev(&self) -> Self {
-        self.next()
+        match self {
+            Self::ToAddress => Self::Memo,
+            Self::Amount => Self::ToAddress,
+            Self::Memo => Self::Amount,
+        }
     }
 }
@@ -0,0 +1,123 @@
+//! Home wallet list view: search, sort order and network grouping
+//!
+//! The sort order, grouping, last selection and when each wallet was last
+//! opened are kept in `.frost_state/tui_home.json` so the list comes back
+//! the way it was left. The search query is per session.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::tui::state::NetworkSelection;
+use frostdao::protocol::keygen::WalletSummary;
+use frostdao::protocol::wallet::STATE_ROOT;
+use frostdao::storage::{FileStorage, Storage};
+
+/// Home list settings, in the global state root
+pub const HOME_VIEW_FILE: &str = "tui_home.json";
+
+/// Wallet list order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Name,
+    /// Largest cached balance on the current network first
+    Balance,
+    /// Most recently opened first
+    LastUsed,
+}
+
+impl SortOrder {
+    pub fn next(self) -> Self {
+        match self {
+            SortOrder::Name => SortOrder::Balance,
+            SortOrder::Balance => SortOrder::LastUsed,
+            SortOrder::LastUsed => SortOrder::Name,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortOrder::Name => "name",
+            SortOrder::Balance => "balance",
+            SortOrder::LastUsed => "last used",
+        }
+    }
+}
+
+/// A line of the home wallet list
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HomeRow {
+    /// Network group heading; `None` collects wallets never fetched
+    Header(Option<NetworkSelection>),
+    /// Index into `App::wallets`
+    Wallet(usize),
+}
+
+/// How the home screen lists wallets
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HomeView {
+    pub sort: SortOrder,
+    /// Group wallets under the network their balance was last fetched on
+    pub group_by_network: bool,
+    /// Wallet selected when the TUI was last left
+    pub last_selected: Option<String>,
+    /// Unix time each wallet was last opened
+    pub last_used: BTreeMap<String, u64>,
+    /// Incremental search over names, tags and descriptions
+    #[serde(skip)]
+    pub query: String,
+    /// `/` was pressed and keys are going to the query
+    #[serde(skip)]
+    pub searching: bool,
+}
+
+impl HomeView {
+    /// The saved view, or the default if there is none
+    pub fn load() -> Self {
+        FileStorage::new(STATE_ROOT)
+            .ok()
+            .filter(|storage| storage.exists(HOME_VIEW_FILE))
+            .and_then(|storage| storage.read(HOME_VIEW_FILE).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        // Best effort: losing the view only resets the list order
+        if let (Ok(storage), Ok(json)) = (
+            FileStorage::new(STATE_ROOT),
+            serde_json::to_vec_pretty(self),
+        ) {
+            let _ = storage.write(HOME_VIEW_FILE, &json);
+        }
+    }
+
+    /// Whether a wallet matches the search query (case-insensitive)
+    pub fn matches(&self, wallet: &WalletSummary) -> bool {
+        let query = self.query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        wallet.name.to_lowercase().contains(&query)
+            || wallet
+                .notes
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&query))
+            || wallet.notes.description.to_lowercase().contains(&query)
+    }
+
+    /// Title of the wallet list, showing the active search and order
+    pub fn title(&self, shown: usize, total: usize) -> String {
+        let mut title = format!(" Wallets ({}) ", self.sort.label());
+        if self.group_by_network {
+            title.push_str("by network ");
+        }
+        if self.searching || !self.query.is_empty() {
+            title.push_str(&format!("/{} [{}/{}] ", self.query, shown, total));
+        }
+        title
+    }
+}
@@ -4,7 +4,10 @@ use wasm_bindgen::prelude::*;
 use serde::Serialize;
 
 #[cfg(target_arch = "wasm32")]
-use crate::{btc::schnorr as bitcoin_schnorr, protocol::keygen, protocol::signing};
+use crate::{
+    btc::schnorr as bitcoin_schnorr, protocol::events::ProtocolEvent, protocol::keygen,
+    protocol::recovery, protocol::reporter::Reporter, protocol::reshare, protocol::signing,
+};
 
 /// Initialize panic hook for better error messages in browser
 #[wasm_bindgen(start)]
@@ -12,6 +15,263 @@ pub fn init() {
     console_error_panic_hook::set_once();
 }
 
+// ============================================================================
+// Errors and TypeScript types
+//
+// Every binding throws a `FrostError` rather than a string, so the frontend
+// can tell "not enough shares yet" from "wrong wallet" without parsing
+// messages. The payload interfaces below mirror the serde structs in
+// `protocol::*`; keep them in step when a payload gains a field.
+// ============================================================================
+
+#[wasm_bindgen(typescript_custom_section)]
+const PAYLOAD_TYPES_TS: &str = r#"
+export type Curve = "secp256k1" | "ed25519" | "ristretto255";
+
+/** What every binding returns: educational text plus the step's result */
+export interface CommandResult<T> {
+    output: string;
+    result: T;
+}
+
+/** The string-returning bindings return `JSON.stringify` of this */
+export interface RawCommandResult {
+    output: string;
+    /** JSON of the step's payload, or plain text for final steps */
+    result: string;
+}
+
+export interface Round1Output {
+    party_index: number;
+    rank: number;
+    keygen_input: string;
+    hierarchical: boolean;
+    protocol_version?: number;
+    type: "keygen_round1";
+    identity?: string;
+    curve?: Curve;
+    auth?: string;
+}
+
+export interface ShareData {
+    to_index: number;
+    share: string;
+    encryption?: string;
+}
+
+export interface Round2Output {
+    party_index: number;
+    shares: ShareData[];
+    protocol_version?: number;
+    type: "keygen_round2";
+    auth?: string;
+}
+
+export interface NonceOutput {
+    party_index: number;
+    rank: number;
+    session: string;
+    nonce: string;
+    memo?: string;
+    protocol_version?: number;
+    type: "signing_nonce" | "dkg_nonce";
+    auth?: string;
+}
+
+export interface SignatureShareOutput {
+    party_index: number;
+    session: string;
+    message: string;
+    signature_share: string;
+    protocol_version?: number;
+    type: "signing_share";
+    auth?: string;
+}
+
+export interface DkgSignatureShareOutput {
+    party_index: number;
+    rank: number;
+    session_id: string;
+    sighash: string;
+    signature_share: string;
+    aggregator?: number;
+    protocol_version?: number;
+    type: "dkg_signature_share";
+    auth?: string;
+}
+
+export interface BuildTxOutput {
+    session_id: string;
+    sighash: string;
+    unsigned_tx: string;
+    from_address: string;
+    to_address: string;
+    amount_sats: number;
+    fee_sats: number;
+    network: string;
+    merkle_root?: string;
+    memo?: string;
+    prevouts?: { value: number; script_pubkey: string }[];
+    type: "dkg_build_tx";
+}
+
+export interface ReshareRound1Output {
+    old_party_index: number;
+    /** Sub-share for each new party, keyed by new index */
+    sub_shares: Record<string, string>;
+    polynomial_commitment: string[];
+    /** Identity key of each new party, keyed by new index */
+    new_identities?: Record<string, string>;
+    protocol_version?: number;
+    type: "reshare_round1";
+    auth?: string;
+}
+
+/** What a reshare finalize would produce; nothing is written */
+export interface ReshareVerifyOutput {
+    group_public_key: string;
+    old_threshold: number;
+    old_n_parties: number;
+    new_threshold: number;
+    new_n_parties: number;
+    my_new_index: number;
+}
+
+export interface RecoveryRound1Output {
+    helper_index: number;
+    helper_rank: number;
+    sub_share: string;
+    lost_index: number;
+    wallet_name: string;
+    protocol_version?: number;
+    type: "recovery_round1";
+    auth?: string;
+}
+
+export interface KeygenResult {
+    verification_share: string;
+    public_key: string;
+    mode: "TSS" | "HTSS";
+}
+
+export interface CombinedSignature {
+    signature: string;
+    public_key: string;
+    message: string;
+    curve?: string;
+}
+
+/** Progress reported while a step runs (`onEvent` callback) */
+export type ProtocolEvent =
+    | { event: "started"; title: string }
+    | { event: "detail"; label: string; value: string }
+    | { event: "progress"; message: string; lines: string[] }
+    | { event: "party"; index: number; status: string }
+    | { event: "success"; message: string; lines: string[] }
+    | { event: "explain"; title: string; lines: string[] }
+    | { event: "question"; title: string; lines: string[] }
+    | { event: "warning"; message: string; lines: string[] }
+    | { event: "produced"; message: string }
+    | { event: "next_step"; instruction: string }
+    | { event: "note"; text: string }
+    | { event: "divider" };
+
+export type FrostErrorCode =
+    | "security"
+    | "target_exists"
+    | "not_enough_shares"
+    | "missing_state"
+    | "unsupported_curve"
+    | "unauthenticated_payload"
+    | "verification_failed"
+    | "invalid_payload"
+    | "invalid_input"
+    | "unsupported_target"
+    | "failed";
+"#;
+
+/// Error thrown by every binding
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone)]
+pub struct FrostError {
+    /// Binding that failed without its `wasm_` prefix, e.g. `store_reshare_finalize`
+    pub operation: String,
+    /// Stable code to branch on (`FrostErrorCode` in TypeScript)
+    pub code: String,
+    /// Full human-readable message
+    pub message: String,
+}
+
+/// Error text fragments and the code they map to, first match wins
+const ERROR_CODES: &[(&str, &str)] = &[
+    ("SECURITY ERROR", "security"),
+    ("already exists", "target_exists"),
+    ("Not enough sub-shares", "not_enough_shares"),
+    ("Not enough signers", "not_enough_shares"),
+    ("Key not found", "missing_state"),
+    ("not found at", "missing_state"),
+    ("needs a secp256k1 wallet", "unsupported_curve"),
+    ("only available for secp256k1", "unsupported_curve"),
+    ("not signed", "unauthenticated_payload"),
+    ("no pinned identity", "unauthenticated_payload"),
+    (
+        "does not carry your identity key",
+        "unauthenticated_payload",
+    ),
+    ("sent by a different Nostr key", "unauthenticated_payload"),
+    ("verification failed", "verification_failed"),
+    ("Object #", "invalid_payload"),
+    ("appears twice", "invalid_payload"),
+    ("Could not read the", "invalid_payload"),
+    ("outputs provided", "invalid_payload"),
+    ("JSON", "invalid_payload"),
+    ("Mismatched", "invalid_payload"),
+    ("Missing sub-share", "invalid_payload"),
+    ("Missing signature share", "invalid_payload"),
+    ("more than one signature share", "invalid_payload"),
+    ("committed no nonce", "invalid_payload"),
+    ("sent two different shares", "invalid_payload"),
+    ("Index mismatch", "invalid_input"),
+    ("Invalid", "invalid_input"),
+    ("cannot exceed", "invalid_input"),
+    ("must be between", "invalid_input"),
+    ("cannot help recover your own share", "invalid_input"),
+];
+
+/// Stable code for an error message (also used by the C FFI)
+pub(crate) fn error_code(message: &str) -> &'static str {
+    ERROR_CODES
+        .iter()
+        .find(|(fragment, _)| message.contains(fragment))
+        .map(|(_, code)| *code)
+        .unwrap_or("failed")
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+impl FrostError {
+    fn new(operation: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            operation: operation.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn from_error(operation: &str, error: anyhow::Error) -> Self {
+        let message = format!("{:#}", error);
+        Self::new(operation, error_code(&message), message)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn wasm_only(operation: &str) -> Self {
+        Self::new(
+            operation,
+            "unsupported_target",
+            "WASM functions only available in WASM target",
+        )
+    }
+}
+
 /// Helper struct for WASM JSON serialization
 #[cfg(target_arch = "wasm32")]
 #[derive(Serialize)]
@@ -22,13 +282,21 @@ struct WasmCommandResult {
 
 /// Convert CommandResult to JSON string for WASM
 #[cfg(target_arch = "wasm32")]
-fn command_result_to_json(cmd_result: crate::CommandResult) -> Result<String, JsValue> {
+fn command_result_to_json(
+    operation: &str,
+    cmd_result: crate::CommandResult,
+) -> Result<String, FrostError> {
     let wasm_result = WasmCommandResult {
         output: cmd_result.output,
         result: cmd_result.result,
     };
-    serde_json::to_string(&wasm_result)
-        .map_err(|e| JsValue::from_str(&format!("JSON serialization error: {}", e)))
+    serde_json::to_string(&wasm_result).map_err(|e| {
+        FrostError::new(
+            operation,
+            "failed",
+            format!("JSON serialization error: {}", e),
+        )
+    })
 }
 
 // WASM-exposed keygen functions
@@ -41,59 +309,56 @@ pub fn wasm_keygen_round1(
     my_index: u32,
     rank: u32,
     hierarchical: bool,
-) -> Result<String, JsValue> {
+) -> Result<String, FrostError> {
+    const OP: &str = "keygen_round1";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result =
             keygen::round1_core(threshold, n_parties, my_index, rank, hierarchical, &storage)
-                .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+                .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_keygen_round2(data: String) -> Result<String, JsValue> {
+pub fn wasm_keygen_round2(data: String) -> Result<String, FrostError> {
+    const OP: &str = "keygen_round2";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
-        let cmd_result = keygen::round2_core(&data, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+        let cmd_result =
+            keygen::round2_core(&data, &storage).map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_keygen_finalize(data: String) -> Result<String, JsValue> {
+pub fn wasm_keygen_finalize(data: String) -> Result<String, FrostError> {
+    const OP: &str = "keygen_finalize";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
-        let cmd_result = keygen::finalize_core(&data, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+        let cmd_result =
+            keygen::finalize_core(&data, &storage).map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
@@ -101,58 +366,55 @@ pub fn wasm_keygen_finalize(data: String) -> Result<String, JsValue> {
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_generate_nonce(session: String) -> Result<String, JsValue> {
+pub fn wasm_generate_nonce(session: String) -> Result<String, FrostError> {
+    const OP: &str = "generate_nonce";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = signing::generate_nonce_core(&session, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_sign(session: String, message: String, data: String) -> Result<String, JsValue> {
+pub fn wasm_sign(session: String, message: String, data: String) -> Result<String, FrostError> {
+    const OP: &str = "sign";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = signing::create_signature_share_core(&session, &message, &data, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_combine(data: String) -> Result<String, JsValue> {
+pub fn wasm_combine(data: String) -> Result<String, FrostError> {
+    const OP: &str = "combine";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = signing::combine_signatures_core(&data, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
@@ -162,18 +424,487 @@ pub fn wasm_verify(
     signature: String,
     public_key: String,
     message: String,
-) -> Result<String, JsValue> {
+) -> Result<String, FrostError> {
+    const OP: &str = "verify";
     #[cfg(target_arch = "wasm32")]
     {
         let cmd_result = signing::verify_signature_core(&signature, &public_key, &message)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+// ============================================================================
+// WASM-exposed keygen and signing with a JS store
+//
+// Same pipeline as above, but state lives in a `FrostStore` the caller
+// passes in (IndexedDB wrapper, Map, files under Node, ...) instead of
+// localStorage. Payloads
+// go in as JS objects (or arrays of them, or JSON strings) and results come
+// back as `{ output, result }` with `result` already parsed.
+// ============================================================================
+
+/// Turn a payload argument into the whitespace-separated JSON the cores read
+#[cfg(target_arch = "wasm32")]
+fn payload_arg(operation: &str, value: &JsValue) -> Result<String, FrostError> {
+    if let Some(text) = value.as_string() {
+        return Ok(text);
+    }
+    let items: Vec<JsValue> = if js_sys::Array::is_array(value) {
+        js_sys::Array::from(value).iter().collect()
+    } else {
+        vec![value.clone()]
+    };
+    items
+        .iter()
+        .map(|item| {
+            js_sys::JSON::stringify(item)
+                .map(String::from)
+                .map_err(|_| {
+                    FrostError::new(
+                        operation,
+                        "invalid_payload",
+                        "Payload is not JSON-serializable",
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|parts| parts.join(" "))
+}
+
+/// `Label: value` lines (the combine result) as `{ label: value }`
+fn labelled_fields(text: &str) -> Option<serde_json::Value> {
+    let mut fields = serde_json::Map::new();
+    for line in text.lines() {
+        let (label, value) = line.split_once(": ")?;
+        fields.insert(
+            label.to_ascii_lowercase().replace(' ', "_"),
+            serde_json::Value::String(value.trim_matches('"').to_string()),
+        );
+    }
+    (fields.len() > 1).then_some(serde_json::Value::Object(fields))
+}
+
+/// A core's `result` as JSON when it is structured, else as a string
+/// (shared with the C FFI and Python bindings)
+pub(crate) fn structured_result(result: String) -> serde_json::Value {
+    serde_json::from_str(&result)
+        .ok()
+        .or_else(|| labelled_fields(&result))
+        .unwrap_or(serde_json::Value::String(result))
+}
+
+/// Convert CommandResult to a JS object, parsing `result` when it is structured
+#[cfg(target_arch = "wasm32")]
+fn command_result_to_object(
+    operation: &str,
+    cmd_result: crate::CommandResult,
+) -> Result<JsValue, FrostError> {
+    let object = serde_json::json!({
+        "output": cmd_result.output,
+        "result": structured_result(cmd_result.result),
+    });
+    js_sys::JSON::parse(&object.to_string())
+        .map_err(|_| FrostError::new(operation, "failed", "Result is not valid JSON"))
+}
+
+/// Reporter that hands each progress event to the optional JS callback;
+/// without one it is a no-op and the text stays in `output`
+#[cfg(target_arch = "wasm32")]
+struct JsReporter<'a>(Option<&'a js_sys::Function>);
+
+#[cfg(target_arch = "wasm32")]
+impl JsReporter<'_> {
+    fn send(&self, event: &ProtocolEvent) {
+        let Some(callback) = self.0 else {
+            return;
+        };
+        let json = serde_json::to_string(event).unwrap_or_default();
+        if let Ok(value) = js_sys::JSON::parse(&json) {
+            // A throwing callback must not abort the protocol step
+            let _ = callback.call1(&JsValue::NULL, &value);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Reporter for JsReporter<'_> {
+    fn explain(&mut self, event: &ProtocolEvent) {
+        self.send(event);
+    }
+
+    fn warn(&mut self, event: &ProtocolEvent) {
+        self.send(event);
+    }
+
+    fn progress(&mut self, event: &ProtocolEvent) {
+        self.send(event);
+    }
+
+    fn result(&mut self, _result: &str) {}
+}
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<Round1Output>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_keygen_round1(
+    store: &crate::storage::JsStore,
+    threshold: u32,
+    n_parties: u32,
+    my_index: u32,
+    rank: u32,
+    hierarchical: bool,
+    #[wasm_bindgen(unchecked_param_type = "(event: ProtocolEvent) => void")] on_event: Option<
+        js_sys::Function,
+    >,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_keygen_round1";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let cmd_result = keygen::round1_core_with_reporter(
+            threshold,
+            n_parties,
+            my_index,
+            rank,
+            hierarchical,
+            &storage,
+            &mut JsReporter(on_event.as_ref()),
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<Round2Output>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_keygen_round2(
+    store: &crate::storage::JsStore,
+    #[wasm_bindgen(unchecked_param_type = "Round1Output[] | string")] round1: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "(event: ProtocolEvent) => void")] on_event: Option<
+        js_sys::Function,
+    >,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_keygen_round2";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let data = payload_arg(OP, &round1)?;
+        let cmd_result =
+            keygen::round2_core_with_reporter(&data, &storage, &mut JsReporter(on_event.as_ref()))
+                .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<KeygenResult>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_keygen_finalize(
+    store: &crate::storage::JsStore,
+    #[wasm_bindgen(unchecked_param_type = "Round2Output[] | string")] round2: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "(event: ProtocolEvent) => void")] on_event: Option<
+        js_sys::Function,
+    >,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_keygen_finalize";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let data = payload_arg(OP, &round2)?;
+        let cmd_result = keygen::finalize_core_with_reporter(
+            &data,
+            &storage,
+            &mut JsReporter(on_event.as_ref()),
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<NonceOutput>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_generate_nonce(
+    store: &crate::storage::JsStore,
+    session: String,
+    #[wasm_bindgen(unchecked_param_type = "(event: ProtocolEvent) => void")] on_event: Option<
+        js_sys::Function,
+    >,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_generate_nonce";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let cmd_result = signing::generate_nonce_core_with_reporter(
+            &session,
+            &storage,
+            &mut JsReporter(on_event.as_ref()),
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<SignatureShareOutput>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_sign(
+    store: &crate::storage::JsStore,
+    session: String,
+    message: String,
+    #[wasm_bindgen(unchecked_param_type = "NonceOutput[] | string")] nonces: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "(event: ProtocolEvent) => void")] on_event: Option<
+        js_sys::Function,
+    >,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_sign";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let data = payload_arg(OP, &nonces)?;
+        let cmd_result = signing::create_signature_share_core_with_reporter(
+            &session,
+            &message,
+            &data,
+            &storage,
+            &mut JsReporter(on_event.as_ref()),
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<CombinedSignature>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_combine(
+    store: &crate::storage::JsStore,
+    #[wasm_bindgen(unchecked_param_type = "SignatureShareOutput[] | string")] shares: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "(event: ProtocolEvent) => void")] on_event: Option<
+        js_sys::Function,
+    >,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_combine";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let data = payload_arg(OP, &shares)?;
+        let cmd_result = signing::combine_signatures_core_with_reporter(
+            &data,
+            &storage,
+            &mut JsReporter(on_event.as_ref()),
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+/// Verify a combined signature; needs no store
+#[wasm_bindgen(unchecked_return_type = "CommandResult<\"VALID\" | \"INVALID\">")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_verify(
+    signature: String,
+    public_key: String,
+    message: String,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_verify";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let cmd_result = signing::verify_signature_core(&signature, &public_key, &message)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+// ============================================================================
+// WASM-exposed reshare and recovery with a JS store
+// ============================================================================
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<ReshareRound1Output>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_reshare_round1(
+    store: &crate::storage::JsStore,
+    source_wallet: String,
+    new_threshold: u32,
+    new_n_parties: u32,
+    my_old_index: u32,
+    new_identities: Vec<String>,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_reshare_round1";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let cmd_result = reshare::reshare_round1_with_storage(
+            &source_wallet,
+            new_threshold,
+            new_n_parties,
+            my_old_index,
+            &new_identities,
+            &storage,
+            &storage,
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+/// Dry run of [`wasm_store_reshare_finalize`]: checks the sub-shares from
+/// `source_store` and proves the group key is kept, without writing
+#[wasm_bindgen(unchecked_return_type = "CommandResult<ReshareVerifyOutput>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_reshare_verify(
+    source_store: &crate::storage::JsStore,
+    my_new_index: u32,
+    #[wasm_bindgen(unchecked_param_type = "ReshareRound1Output[] | string")] round1: JsValue,
+    allow_threshold_decrease: bool,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_reshare_verify";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let source = crate::storage::JsCallbackStorage(source_store);
+        let round1_data = payload_arg(OP, &round1)?;
+        let cmd_result = reshare::reshare_verify_with_storage(
+            my_new_index,
+            &round1_data,
+            allow_threshold_decrease,
+            &source,
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+/// Combine sub-shares from `source_store` into a new wallet in `target_store`
+#[wasm_bindgen(unchecked_return_type = "CommandResult<string>")]
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_reshare_finalize(
+    source_store: &crate::storage::JsStore,
+    target_store: &crate::storage::JsStore,
+    target_wallet: String,
+    my_new_index: u32,
+    my_rank: u32,
+    hierarchical: bool,
+    #[wasm_bindgen(unchecked_param_type = "ReshareRound1Output[] | string")] round1: JsValue,
+    force_overwrite: bool,
+    allow_threshold_decrease: bool,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_reshare_finalize";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let source = crate::storage::JsCallbackStorage(source_store);
+        let target = crate::storage::JsCallbackStorage(target_store);
+        let round1_data = payload_arg(OP, &round1)?;
+        let cmd_result = reshare::reshare_finalize_with_storage(
+            &target_wallet,
+            my_new_index,
+            my_rank,
+            hierarchical,
+            &round1_data,
+            force_overwrite,
+            allow_threshold_decrease,
+            &source,
+            &target,
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+#[wasm_bindgen(unchecked_return_type = "CommandResult<RecoveryRound1Output>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_recover_round1(
+    store: &crate::storage::JsStore,
+    source_wallet: String,
+    lost_index: u32,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_recover_round1";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let storage = crate::storage::JsCallbackStorage(store);
+        let cmd_result = recovery::recover_round1_core(&source_wallet, lost_index, &storage)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+/// Rebuild a lost share from helper outputs; rank and mode come from `source_store`
+#[wasm_bindgen(unchecked_return_type = "CommandResult<string>")]
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+pub fn wasm_store_recover_finalize(
+    source_store: &crate::storage::JsStore,
+    target_store: &crate::storage::JsStore,
+    source_wallet: String,
+    target_wallet: String,
+    my_index: u32,
+    #[wasm_bindgen(unchecked_param_type = "RecoveryRound1Output[] | string")] round1: JsValue,
+    force_overwrite: bool,
+) -> Result<JsValue, FrostError> {
+    const OP: &str = "store_recover_finalize";
+    #[cfg(target_arch = "wasm32")]
+    {
+        let source = crate::storage::JsCallbackStorage(source_store);
+        let target = crate::storage::JsCallbackStorage(target_store);
+        let round1_data = payload_arg(OP, &round1)?;
+        let cmd_result = recovery::recover_finalize_with_storage(
+            &source_wallet,
+            &target_wallet,
+            my_index,
+            &round1_data,
+            force_overwrite,
+            &source,
+            &target,
+        )
+        .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_object(OP, cmd_result)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Err(FrostError::wasm_only(OP))
     }
 }
 
@@ -182,97 +913,92 @@ pub fn wasm_verify(
 // ============================================================================
 
 #[wasm_bindgen]
-pub fn wasm_btc_keygen() -> Result<String, JsValue> {
+pub fn wasm_btc_keygen() -> Result<String, FrostError> {
+    const OP: &str = "btc_keygen";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = bitcoin_schnorr::generate_keypair_core(&storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_btc_import_key(secret_hex: String) -> Result<String, JsValue> {
+pub fn wasm_btc_import_key(secret_hex: String) -> Result<String, FrostError> {
+    const OP: &str = "btc_import_key";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = bitcoin_schnorr::import_key_core(&secret_hex, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
-pub fn wasm_btc_get_pubkey() -> Result<String, JsValue> {
+pub fn wasm_btc_get_pubkey() -> Result<String, FrostError> {
+    const OP: &str = "btc_get_pubkey";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = bitcoin_schnorr::get_public_key_core(&storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_btc_sign(message: String) -> Result<String, JsValue> {
+pub fn wasm_btc_sign(message: String) -> Result<String, FrostError> {
+    const OP: &str = "btc_sign";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = bitcoin_schnorr::sign_message_core(message.as_bytes(), None, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_btc_sign_hex(message_hex: String) -> Result<String, JsValue> {
+pub fn wasm_btc_sign_hex(message_hex: String) -> Result<String, FrostError> {
+    const OP: &str = "btc_sign_hex";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let message = hex::decode(&message_hex)
-            .map_err(|e| JsValue::from_str(&format!("Invalid hex: {}", e)))?;
+            .map_err(|e| FrostError::new(OP, "invalid_input", format!("Invalid hex: {}", e)))?;
         let cmd_result = bitcoin_schnorr::sign_message_core(&message, None, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
@@ -282,19 +1008,18 @@ pub fn wasm_btc_verify(
     signature: String,
     public_key: String,
     message: String,
-) -> Result<String, JsValue> {
+) -> Result<String, FrostError> {
+    const OP: &str = "btc_verify";
     #[cfg(target_arch = "wasm32")]
     {
         let cmd_result =
             bitcoin_schnorr::verify_signature_core(&signature, &public_key, message.as_bytes())
-                .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+                .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
@@ -304,38 +1029,66 @@ pub fn wasm_btc_verify_hex(
     signature: String,
     public_key: String,
     message_hex: String,
-) -> Result<String, JsValue> {
+) -> Result<String, FrostError> {
+    const OP: &str = "btc_verify_hex";
     #[cfg(target_arch = "wasm32")]
     {
         let message = hex::decode(&message_hex)
-            .map_err(|e| JsValue::from_str(&format!("Invalid hex: {}", e)))?;
+            .map_err(|e| FrostError::new(OP, "invalid_input", format!("Invalid hex: {}", e)))?;
         let cmd_result = bitcoin_schnorr::verify_signature_core(&signature, &public_key, &message)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
     }
 }
 
 #[wasm_bindgen]
 #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
-pub fn wasm_btc_sign_taproot(sighash_hex: String) -> Result<String, JsValue> {
+pub fn wasm_btc_sign_taproot(sighash_hex: String) -> Result<String, FrostError> {
+    const OP: &str = "btc_sign_taproot";
     #[cfg(target_arch = "wasm32")]
     {
         use crate::storage::LocalStorageImpl;
         let storage = LocalStorageImpl;
         let cmd_result = bitcoin_schnorr::sign_taproot_sighash_core(&sighash_hex, &storage)
-            .map_err(|e| JsValue::from_str(&format!("Error: {}", e)))?;
-        command_result_to_json(cmd_result)
+            .map_err(|e| FrostError::from_error(OP, e))?;
+        command_result_to_json(OP, cmd_result)
     }
     #[cfg(not(target_arch = "wasm32"))]
     {
-        Err(JsValue::from_str(
-            "WASM functions only available in WASM target",
-        ))
+        Err(FrostError::wasm_only(OP))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{recovery, reshare};
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_frost_error_codes() {
+        let empty = MemoryStorage::new();
+        let code = |result: anyhow::Result<crate::CommandResult>| {
+            FrostError::from_error("op", result.unwrap_err()).code
+        };
+
+        assert_eq!(
+            code(recovery::recover_round1_core("w", 2, &empty)),
+            "missing_state"
+        );
+        assert_eq!(
+            code(reshare::reshare_finalize_with_storage(
+                "t", 1, 0, false, "", false, false, &empty, &empty
+            )),
+            "invalid_payload"
+        );
+        assert_eq!(
+            FrostError::from_error("op", anyhow::anyhow!("disk on fire")).code,
+            "failed"
+        );
     }
 }
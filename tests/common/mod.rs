@@ -0,0 +1,69 @@
+//! Helpers shared by the in-memory integration tests
+
+#![allow(dead_code)]
+
+use bitcoin::key::XOnlyPublicKey;
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::{Address, Network};
+use frostdao::btc::hd_address;
+use frostdao::protocol::keygen;
+use frostdao::storage::{MemoryStorage, Storage};
+use schnorr_fun::frost::SharedKey;
+use secp256kfun::prelude::*;
+
+/// A keygen'd group with one `MemoryStorage` per party
+pub struct Group {
+    pub parties: Vec<MemoryStorage>,
+}
+
+impl Group {
+    /// Run keygen round 1, round 2 and finalize for `ranks.len()` parties
+    pub fn keygen(threshold: u32, ranks: &[u32], hierarchical: bool) -> Self {
+        let n = ranks.len() as u32;
+        let parties: Vec<MemoryStorage> = ranks.iter().map(|_| MemoryStorage::new()).collect();
+
+        let round1: Vec<String> = parties
+            .iter()
+            .zip(ranks)
+            .enumerate()
+            .map(|(i, (storage, &rank))| {
+                keygen::round1_core(threshold, n, i as u32 + 1, rank, hierarchical, storage)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        let round2: Vec<String> = parties
+            .iter()
+            .map(|storage| {
+                keygen::round2_core(&round1.join(" "), storage)
+                    .unwrap()
+                    .result
+            })
+            .collect();
+        for storage in &parties {
+            keygen::finalize_core(&round2.join(" "), storage).unwrap();
+        }
+        Self { parties }
+    }
+
+    pub fn party(&self, index: u32) -> &MemoryStorage {
+        &self.parties[index as usize - 1]
+    }
+
+    pub fn shared_key(&self) -> SharedKey<EvenY> {
+        bincode::deserialize(&self.party(1).read("shared_key.bin").unwrap()).unwrap()
+    }
+
+    /// Key-path address of the group key
+    pub fn root_address(&self, network: Network) -> String {
+        let xonly =
+            XOnlyPublicKey::from_slice(&self.shared_key().public_key().to_xonly_bytes()).unwrap();
+        Address::p2tr(&Secp256k1::new(), xonly, None, network).to_string()
+    }
+
+    pub fn hd_address(&self, change: u32, index: u32, network: Network) -> String {
+        hd_address::derive_address_core(change, index, &network.to_string(), self.party(1))
+            .unwrap()
+            .result
+    }
+}
@@ -1,7 +1,8 @@
 //! Integration tests for full DKG flow using CLI commands
 
 use std::fs;
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicU32, Ordering};
 
 const FROSTDAO: &str = "./target/release/frostdao";
@@ -69,10 +70,12 @@ fn test_full_2_of_3_dkg_flow() {
         "Party 1 round1 failed: {}",
         String::from_utf8_lossy(&r1_p1.stderr)
     );
-    let commit1 = extract_json(&String::from_utf8_lossy(&r1_p1.stdout)).expect(&format!(
-        "No JSON from party 1. Output: {}",
-        String::from_utf8_lossy(&r1_p1.stdout)
-    ));
+    let commit1 = extract_json(&String::from_utf8_lossy(&r1_p1.stdout)).unwrap_or_else(|| {
+        panic!(
+            "No JSON from party 1. Output: {}",
+            String::from_utf8_lossy(&r1_p1.stdout)
+        )
+    });
 
     let r1_p2 = Command::new(FROSTDAO)
         .args([
@@ -158,6 +161,16 @@ fn test_full_2_of_3_dkg_flow() {
         String::from_utf8_lossy(&fin_p1.stderr)
     );
 
+    // Plaintext round secrets must not survive finalize
+    let state1 = format!(".frost_state/{}", wallet1);
+    assert!(!std::path::Path::new(&state1)
+        .join("my_secret_shares.json")
+        .exists());
+    assert!(!std::path::Path::new(&state1)
+        .join("round1_state.json")
+        .exists());
+    assert!(!std::path::Path::new(&state1).join("share_hex.txt").exists());
+
     let fin_p2 = Command::new(FROSTDAO)
         .args(["keygen-finalize", "--name", &wallet2, "--data", &all_shares])
         .output()
@@ -241,10 +254,12 @@ fn test_resharing_preserves_address() {
         "p1 r1 failed: {}",
         String::from_utf8_lossy(&r1_p1.stderr)
     );
-    let commit1 = extract_json(&String::from_utf8_lossy(&r1_p1.stdout)).expect(&format!(
-        "No JSON. Output: {}",
-        String::from_utf8_lossy(&r1_p1.stdout)
-    ));
+    let commit1 = extract_json(&String::from_utf8_lossy(&r1_p1.stdout)).unwrap_or_else(|| {
+        panic!(
+            "No JSON. Output: {}",
+            String::from_utf8_lossy(&r1_p1.stdout)
+        )
+    });
 
     let r1_p2 = Command::new(FROSTDAO)
         .args([
@@ -365,10 +380,12 @@ fn test_resharing_preserves_address() {
         .args(["dkg-address", "--name", &new_wallet])
         .output()
         .expect("dkg-address failed");
-    let new_json = extract_json(&String::from_utf8_lossy(&new_addr.stdout)).expect(&format!(
-        "No JSON from new wallet. Output: {}",
-        String::from_utf8_lossy(&new_addr.stdout)
-    ));
+    let new_json = extract_json(&String::from_utf8_lossy(&new_addr.stdout)).unwrap_or_else(|| {
+        panic!(
+            "No JSON from new wallet. Output: {}",
+            String::from_utf8_lossy(&new_addr.stdout)
+        )
+    });
     let new: serde_json::Value = serde_json::from_str(&new_json).unwrap();
 
     assert_eq!(
@@ -437,6 +454,45 @@ fn test_wallet_listing() {
     cleanup_wallet(&prefix);
 }
 
+/// Old plaintext secrets are only shredded while their wallet is unlocked
+#[test]
+fn test_startup_cleanup_skips_locked_wallets() {
+    let prefix = get_unique_prefix();
+    let wallet = format!(".frost_state/{}_busy", prefix);
+    fs::create_dir_all(&wallet).unwrap();
+    let legacy = format!("{}/share_hex.txt", wallet);
+    fs::write(&legacy, "00").unwrap();
+
+    // Held by a live process on this machine: this test
+    let holder = serde_json::json!({
+        "pid": std::process::id(),
+        "host": gethostname::gethostname().to_string_lossy(),
+        "command": "dkg-sign",
+        "started_at": 0,
+        "token": "test",
+    });
+    fs::write(format!("{}/.lock", wallet), holder.to_string()).unwrap();
+    let list = Command::new(FROSTDAO)
+        .args(["dkg-list"])
+        .output()
+        .expect("dkg-list failed");
+    assert!(list.status.success());
+    assert!(fs::metadata(&legacy).is_ok(), "a locked wallet was touched");
+
+    fs::remove_file(format!("{}/.lock", wallet)).unwrap();
+    let list = Command::new(FROSTDAO)
+        .args(["dkg-list"])
+        .output()
+        .expect("dkg-list failed");
+    assert!(list.status.success());
+    assert!(
+        fs::metadata(&legacy).is_err(),
+        "an unlocked wallet was left alone"
+    );
+
+    cleanup_wallet(&prefix);
+}
+
 /// Test complete 2-of-3 HTSS (Hierarchical) DKG flow
 #[test]
 fn test_full_2_of_3_htss_flow() {
@@ -629,3 +685,212 @@ fn test_full_2_of_3_htss_flow() {
 
     cleanup_wallet(&prefix);
 }
+
+/// Test failures map to stable exit codes and JSON errors
+#[test]
+fn test_error_exit_codes() {
+    let prefix = get_unique_prefix();
+    let missing = format!("{}_missing", prefix);
+
+    let out = Command::new(FROSTDAO)
+        .args(["dkg-address", "--name", &missing, "--json"])
+        .output()
+        .expect("Failed to run dkg-address");
+    assert_eq!(
+        out.status.code(),
+        Some(5),
+        "Missing wallet is a storage error"
+    );
+    let error: serde_json::Value =
+        serde_json::from_str(String::from_utf8_lossy(&out.stderr).trim()).unwrap();
+    assert_eq!(error["error"]["kind"], "storage");
+    assert_eq!(error["error"]["exit_code"], 5);
+
+    let out = Command::new(FROSTDAO)
+        .args(["keygen-round2", "--name", &missing])
+        .stdin(Stdio::null())
+        .output()
+        .expect("Failed to run keygen-round2");
+    assert_eq!(out.status.code(), Some(2), "Missing --data is a user error");
+}
+
+/// Test --compact round payloads are accepted by the next round
+#[test]
+fn test_compact_payloads() {
+    let prefix = get_unique_prefix();
+    let wallets = [format!("{}_p1", prefix), format!("{}_p2", prefix)];
+
+    let mut tokens = Vec::new();
+    for (i, wallet) in wallets.iter().enumerate() {
+        let index = (i + 1).to_string();
+        let out = Command::new(FROSTDAO)
+            .args([
+                "keygen-round1",
+                "--name",
+                wallet,
+                "--threshold",
+                "2",
+                "--n-parties",
+                "2",
+                "--my-index",
+                &index,
+                "--compact",
+            ])
+            .output()
+            .expect("Failed to run keygen-round1");
+        assert!(out.status.success(), "Party {} round1 failed", index);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(extract_json(&stdout).is_none(), "No JSON with --compact");
+        let token = stdout
+            .lines()
+            .map(str::trim)
+            .find(|line| line.starts_with("cbor:"))
+            .expect("No compact payload")
+            .to_string();
+        let json = frostdao::protocol::compact::decode(&token).unwrap();
+        assert!(token.len() < json.len(), "Compact payload is shorter");
+        tokens.push(token);
+    }
+
+    let out = Command::new(FROSTDAO)
+        .args([
+            "keygen-round2",
+            "--name",
+            &wallets[0],
+            "--data",
+            &tokens.join(" "),
+        ])
+        .output()
+        .expect("Failed to run keygen-round2");
+    assert!(
+        out.status.success(),
+        "Round 2 rejected compact payloads: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    cleanup_wallet(&prefix);
+}
+
+/// Round 1 outputs of a 2-of-2 keygen between `wallets`
+fn round1_2_of_2(wallets: &[String; 2]) -> String {
+    let mut commits = Vec::new();
+    for (i, wallet) in wallets.iter().enumerate() {
+        let index = (i + 1).to_string();
+        let out = Command::new(FROSTDAO)
+            .args([
+                "keygen-round1",
+                "--name",
+                wallet,
+                "--threshold",
+                "2",
+                "--n-parties",
+                "2",
+                "--my-index",
+                &index,
+            ])
+            .output()
+            .expect("Failed to run keygen-round1");
+        assert!(out.status.success(), "Party {} round1 failed", index);
+        commits.push(extract_json(&String::from_utf8_lossy(&out.stdout)).expect("No JSON"));
+    }
+    commits.join(" ")
+}
+
+/// `--data -` reads the payloads from stdin
+#[test]
+fn test_data_from_stdin() {
+    let prefix = get_unique_prefix();
+    let wallets = [format!("{}_p1", prefix), format!("{}_p2", prefix)];
+    let all_commits = round1_2_of_2(&wallets);
+
+    let mut child = Command::new(FROSTDAO)
+        .args(["keygen-round2", "--name", &wallets[0], "--data", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run keygen-round2");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(all_commits.as_bytes())
+        .unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(
+        out.status.success(),
+        "Round 2 from stdin failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(extract_json(&String::from_utf8_lossy(&out.stdout)).is_some());
+
+    cleanup_wallet(&prefix);
+}
+
+/// `--data-file` reads the payloads from a file
+#[test]
+fn test_data_from_file() {
+    let prefix = get_unique_prefix();
+    let wallets = [format!("{}_p1", prefix), format!("{}_p2", prefix)];
+    let all_commits = round1_2_of_2(&wallets);
+
+    let mut shares = Vec::new();
+    for wallet in &wallets {
+        let out = Command::new(FROSTDAO)
+            .args(["keygen-round2", "--name", wallet, "--data", &all_commits])
+            .output()
+            .expect("Failed to run keygen-round2");
+        assert!(out.status.success(), "Round 2 failed for {}", wallet);
+        shares.push(extract_json(&String::from_utf8_lossy(&out.stdout)).expect("No shares"));
+    }
+
+    let shares_file = std::env::temp_dir().join(format!("{}_shares.json", prefix));
+    fs::write(&shares_file, shares.join(" ")).unwrap();
+    let out = Command::new(FROSTDAO)
+        .args(["keygen-finalize", "--name", &wallets[0], "--data-file"])
+        .arg(&shares_file)
+        .output()
+        .expect("Failed to run keygen-finalize");
+    let _ = fs::remove_file(&shares_file);
+    assert!(
+        out.status.success(),
+        "Finalize from a file failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Public Key:"));
+
+    cleanup_wallet(&prefix);
+}
+
+/// --ephemeral keeps the wallet out of .frost_state
+#[test]
+fn test_ephemeral_keygen_leaves_no_state() {
+    let prefix = get_unique_prefix();
+    let wallet = format!("{}_demo", prefix);
+
+    let out = Command::new(FROSTDAO)
+        .args([
+            "--ephemeral",
+            "keygen-round1",
+            "--name",
+            &wallet,
+            "--threshold",
+            "2",
+            "--n-parties",
+            "3",
+            "--my-index",
+            "1",
+        ])
+        .output()
+        .expect("Failed to run keygen-round1");
+    assert!(
+        out.status.success(),
+        "Ephemeral round1 failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(extract_json(&String::from_utf8_lossy(&out.stdout)).is_some());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("EPHEMERAL"));
+    assert!(!std::path::Path::new(&format!(".frost_state/{}", wallet)).exists());
+
+    cleanup_wallet(&prefix);
+}
@@ -0,0 +1,1151 @@
+//! End-to-end multi-party sessions, entirely in memory
+//!
+//! Every party gets its own `MemoryStorage`; payloads move between them the
+//! way they would be pasted between machines. Transactions are built against
+//! a mocked chain, and every session ends by checking the BIP-340 signature
+//! against the spent output's key and a sighash recomputed from the tx.
+
+#![cfg(feature = "network")]
+
+mod common;
+
+use base64::Engine;
+use bitcoin::hashes::Hash;
+use bitcoin::key::XOnlyPublicKey;
+use bitcoin::secp256k1::{schnorr, Message, Secp256k1};
+use bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
+use bitcoin::{Address, Amount, Network, Transaction, TxOut};
+use common::Group;
+use frostdao::btc::lightning::{self, ChannelType};
+use frostdao::btc::spend_plan::SpendPath;
+use frostdao::btc::taproot_scripts::SpendingPolicy;
+use frostdao::btc::taproot_tree::{self, TaprootTreeRecord};
+use frostdao::btc::transaction::{ChainBackend, FeeEstimate, UtxoResponse, UtxoStatus};
+use frostdao::protocol::audit;
+use frostdao::protocol::device::{self, Connection};
+use frostdao::protocol::dkg_tx::{
+    self, AutoSignResult, BroadcastOutput, BuildTxOutput, DkgSignatureShareOutput, SessionRoles,
+};
+use frostdao::protocol::rotation;
+use frostdao::protocol::session_report::{self, SessionReport};
+use frostdao::protocol::signing::NonceOutput;
+use frostdao::protocol::wallet;
+use frostdao::storage::{FileStorage, Storage};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const WALLET: &str = "treasury";
+const FUNDING: u64 = 100_000;
+const AMOUNT: u64 = 10_000;
+
+// ============================================================================
+// Mock chain
+// ============================================================================
+
+/// One confirmed UTXO per funded address, mined in block 1; broadcasts are
+/// recorded
+#[derive(Default)]
+struct MockChain {
+    funded: HashMap<String, u64>,
+    broadcasts: RefCell<Vec<String>>,
+    tip: Cell<u64>,
+}
+
+impl MockChain {
+    fn fund(&mut self, address: &str, value: u64) {
+        self.funded.insert(address.to_string(), value);
+    }
+}
+
+impl ChainBackend for MockChain {
+    fn utxos(&self, address: &str) -> anyhow::Result<Vec<UtxoResponse>> {
+        Ok(self
+            .funded
+            .get(address)
+            .map(|&value| UtxoResponse {
+                txid: "aa".repeat(32),
+                vout: 0,
+                status: UtxoStatus {
+                    confirmed: true,
+                    block_height: Some(1),
+                },
+                value,
+            })
+            .into_iter()
+            .collect())
+    }
+
+    fn fee_estimates(&self) -> anyhow::Result<FeeEstimate> {
+        Ok(FeeEstimate {
+            fastest_fee: 4,
+            half_hour_fee: 2,
+            hour_fee: 2,
+            economy_fee: 1,
+            minimum_fee: 1,
+        })
+    }
+
+    fn broadcast(&self, raw_tx_hex: &str) -> anyhow::Result<String> {
+        self.broadcasts.borrow_mut().push(raw_tx_hex.to_string());
+        let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(raw_tx_hex)?;
+        Ok(tx.compute_txid().to_string())
+    }
+
+    fn tip_height(&self) -> anyhow::Result<u64> {
+        Ok(self.tip.get())
+    }
+}
+
+// ============================================================================
+// Signing
+// ============================================================================
+
+impl Group {
+    /// Build, nonce, sign and combine a spend from the root address
+    ///
+    /// Returns the signature, the sighash it signs, the built tx and whether
+    /// the taproot output key was negated for even Y.
+    fn sign_root_spend(
+        &self,
+        chain: &MockChain,
+        signers: &[u32],
+    ) -> anyhow::Result<([u8; 64], [u8; 32], BuildTxOutput, bool)> {
+        let coordinator = self.party(signers[0]);
+        let to = self.hd_address(0, 1, Network::Testnet);
+        let built = dkg_tx::build_unsigned_tx_core_with_backend(
+            WALLET,
+            &to,
+            AMOUNT,
+            None,
+            Network::Testnet,
+            true,
+            None,
+            None,
+            None,
+            None,
+            chain,
+            coordinator,
+        )?;
+        let built: BuildTxOutput = serde_json::from_str(&built.result)?;
+
+        let nonces: Vec<String> = signers
+            .iter()
+            .map(|&i| dkg_tx::dkg_generate_nonce_core(WALLET, &built.session_id, self.party(i)))
+            .map(|r| r.map(|r| r.result))
+            .collect::<anyhow::Result<_>>()?;
+        let shares: Vec<String> = signers
+            .iter()
+            .map(|&i| {
+                dkg_tx::dkg_sign_core(
+                    WALLET,
+                    &built.session_id,
+                    &built.sighash,
+                    &nonces.join(" "),
+                    None,
+                    Some(&built),
+                    false,
+                    self.party(i),
+                )
+            })
+            .map(|r| r.map(|r| r.result))
+            .collect::<anyhow::Result<_>>()?;
+
+        let nonce_outputs: Vec<NonceOutput> = nonces
+            .iter()
+            .map(|n| serde_json::from_str(n))
+            .collect::<Result<_, _>>()?;
+        let share_outputs: Vec<DkgSignatureShareOutput> = shares
+            .iter()
+            .map(|s| serde_json::from_str(s))
+            .collect::<Result<_, _>>()?;
+        let sighash: [u8; 32] = hex::decode(&built.sighash)?.try_into().unwrap();
+        let (signature, parity_flip) = dkg_tx::combine_signature_shares(
+            &self.shared_key(),
+            &nonce_outputs,
+            &sighash,
+            &share_outputs,
+            dkg_tx::SessionKey::Output(None),
+        )?;
+        Ok((signature, sighash, built, parity_flip))
+    }
+}
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+/// BIP-341 key-path sighash of input 0 spending `value` from `from_address`
+fn key_spend_sighash(tx: &Transaction, from_address: &str, value: u64) -> [u8; 32] {
+    let script_pubkey = Address::from_str(from_address)
+        .unwrap()
+        .assume_checked()
+        .script_pubkey();
+    let prevouts = [TxOut {
+        value: Amount::from_sat(value),
+        script_pubkey,
+    }];
+    *SighashCache::new(tx)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), TapSighashType::Default)
+        .unwrap()
+        .as_byte_array()
+}
+
+/// BIP-340 check against the output key committed to by `address`
+fn assert_valid_for_address(signature: &[u8], sighash: &[u8; 32], address: &str) {
+    let script_pubkey = Address::from_str(address)
+        .unwrap()
+        .assume_checked()
+        .script_pubkey();
+    let output_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..34]).unwrap();
+    Secp256k1::verification_only()
+        .verify_schnorr(
+            &schnorr::Signature::from_slice(signature).unwrap(),
+            &Message::from_digest(*sighash),
+            &output_key,
+        )
+        .expect("BIP-340 signature verifies against the output key");
+}
+
+fn assert_root_spend_verifies(group: &Group, chain: &MockChain, signers: &[u32]) -> bool {
+    let (signature, sighash, built, parity_flip) = group.sign_root_spend(chain, signers).unwrap();
+    assert_eq!(built.from_address, group.root_address(Network::Testnet));
+
+    let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(&built.unsigned_tx).unwrap();
+    assert_eq!(
+        key_spend_sighash(&tx, &built.from_address, FUNDING),
+        sighash
+    );
+    assert_valid_for_address(&signature, &sighash, &built.from_address);
+    parity_flip
+}
+
+// ============================================================================
+// Sessions
+// ============================================================================
+
+#[test]
+fn test_tss_2_of_3_every_signer_pair() {
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    for signers in [[1, 2], [1, 3], [2, 3]] {
+        assert_root_spend_verifies(&group, &chain, &signers);
+    }
+}
+
+#[test]
+fn test_signer_on_an_external_device() {
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    // Party 2's share moves to a device emulated by the frostdao binary
+    let dir = std::env::temp_dir().join(format!("frostdao_device_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let device_storage = FileStorage::new(dir.to_str().unwrap()).unwrap();
+    let party = group.party(2);
+    device_storage
+        .write(
+            "paired_secret_share.bin",
+            &party.read("paired_secret_share.bin").unwrap(),
+        )
+        .unwrap();
+    let connection = Connection::Command {
+        program: env!("CARGO_BIN_EXE_frostdao").to_string(),
+        args: vec![
+            "device-emulate".to_string(),
+            "--dir".to_string(),
+            dir.to_str().unwrap().to_string(),
+            "--yes".to_string(),
+        ],
+    };
+
+    // Enrollment refuses a device holding another party's share
+    assert!(device::enroll_core(connection.clone(), group.party(3), 1).is_err());
+    let output = device::enroll_core(connection, party, 1).unwrap().output;
+    assert!(output.contains("still in this folder"));
+    party.delete("paired_secret_share.bin").unwrap();
+
+    for signers in [[1, 2], [2, 3]] {
+        assert_root_spend_verifies(&group, &chain, &signers);
+    }
+    // Nonces were kept and consumed on the device
+    assert!(!std::fs::read_dir(&dir).unwrap().any(|entry| entry
+        .unwrap()
+        .file_name()
+        .to_string_lossy()
+        .starts_with("dkg_nonce_")));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_tss_3_of_5() {
+    let group = Group::keygen(3, &[0; 5], false);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    assert_root_spend_verifies(&group, &chain, &[2, 4, 5]);
+}
+
+#[test]
+fn test_htss_signer_sets() {
+    // Party 1 is rank 0 (must approve), parties 2 and 3 are rank 1
+    let group = Group::keygen(2, &[0, 1, 1], true);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    assert_root_spend_verifies(&group, &chain, &[1, 2]);
+    assert_root_spend_verifies(&group, &chain, &[1, 3]);
+
+    let err = group.sign_root_spend(&chain, &[2, 3]).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("rank"),
+        "two rank-1 parties must not sign alone: {:#}",
+        err
+    );
+}
+
+#[test]
+fn test_signing_requirement_enforced() {
+    // Two rank-0 and two rank-1 parties; the group insists on both rank-1s
+    let group = Group::keygen(3, &[0, 0, 1, 1], true);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+    let storages: Vec<&dyn Storage> = (1..=4).map(|i| group.party(i) as &dyn Storage).collect();
+    wallet::signing_requirement_core(Some("1:2"), false, &storages).unwrap();
+
+    // Valid for HTSS, but only one rank-1 signer
+    let err = group.sign_root_spend(&chain, &[1, 2, 3]).unwrap_err();
+    assert!(
+        err.to_string().contains("missing one rank-1 signer"),
+        "{:#}",
+        err
+    );
+    assert_root_spend_verifies(&group, &chain, &[1, 3, 4]);
+}
+
+#[test]
+fn test_tweak_parity_flips_both_ways() {
+    // Whether the tweaked output key has odd Y depends on the group key, so
+    // run fresh groups until both cases have produced a valid signature
+    let mut seen = [false, false];
+    for _ in 0..32 {
+        let group = Group::keygen(2, &[0, 0, 0], false);
+        let mut chain = MockChain::default();
+        chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+        let parity_flip = assert_root_spend_verifies(&group, &chain, &[1, 3]);
+        seen[parity_flip as usize] = true;
+        if seen == [true, true] {
+            return;
+        }
+    }
+    panic!("32 groups did not cover both tweak parities: {:?}", seen);
+}
+
+#[test]
+fn test_hd_derived_spend() {
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let from = group.hd_address(0, 3, Network::Testnet);
+    let to = group.hd_address(1, 0, Network::Testnet);
+    let mut chain = MockChain::default();
+    chain.fund(&from, FUNDING);
+
+    let parties: Vec<(u32, &dyn Storage)> = [2, 3]
+        .iter()
+        .map(|&i| (i, group.party(i) as &dyn Storage))
+        .collect();
+    let signed = dkg_tx::frost_sign_local_core(
+        WALLET,
+        group.party(1),
+        &parties,
+        &to,
+        AMOUNT,
+        Some((0, 3)),
+        Some(2),
+        Network::Testnet,
+        true,
+        None,
+        &chain,
+    )
+    .unwrap();
+    let signed: AutoSignResult = serde_json::from_str(&signed.result).unwrap();
+    assert_eq!(signed.from_address, from);
+    assert_eq!(*chain.broadcasts.borrow(), vec![signed.raw_tx.clone()]);
+
+    let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(&signed.raw_tx).unwrap();
+    let signature = tx.input[0].witness.nth(0).unwrap();
+    assert_valid_for_address(signature, &key_spend_sighash(&tx, &from, FUNDING), &from);
+}
+
+#[test]
+fn test_rotation_sweeps_every_address_and_retires() {
+    let old = Group::keygen(2, &[0, 0, 0], false);
+    let new = Group::keygen(2, &[0, 0, 0], false);
+    let hd_from = old.hd_address(1, 1, Network::Testnet);
+    let mut chain = MockChain::default();
+    chain.fund(&old.root_address(Network::Testnet), FUNDING);
+    chain.fund(&hd_from, AMOUNT);
+
+    // Same group key: nothing to sweep
+    let err = rotation::plan_sweep(
+        old.party(1),
+        old.party(2),
+        0,
+        Some(2),
+        Network::Testnet,
+        true,
+        &chain,
+        &mut String::new(),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("same group key"), "{:#}", err);
+
+    let parties: Vec<(u32, &dyn Storage)> = [1, 3]
+        .iter()
+        .map(|&i| (i, old.party(i) as &dyn Storage))
+        .collect();
+    let rotated = rotation::rotate_core(
+        "old",
+        "new",
+        old.party(1),
+        new.party(1),
+        &parties,
+        2,
+        Some(2),
+        Network::Testnet,
+        true,
+        false,
+        &chain,
+    )
+    .unwrap();
+    let rotated: rotation::RotationResult = serde_json::from_str(&rotated.result).unwrap();
+    assert!(rotated.retired);
+    assert!(rotated.plan.left_behind.is_empty());
+    assert_eq!(rotated.txids.len(), 2);
+    assert_eq!(
+        wallet::Retirement::load(old.party(1))
+            .unwrap()
+            .unwrap()
+            .successor,
+        "new"
+    );
+
+    // One single-input sweep per address, each paying the new root address
+    let destination = Address::from_str(&new.root_address(Network::Testnet))
+        .unwrap()
+        .assume_checked()
+        .script_pubkey();
+    let broadcasts = chain.broadcasts.borrow();
+    for (raw_tx, (from, value)) in broadcasts.iter().zip([
+        (old.root_address(Network::Testnet), FUNDING),
+        (hd_from, AMOUNT),
+    ]) {
+        let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(raw_tx).unwrap();
+        assert_eq!(tx.input.len(), 1);
+        assert_eq!(tx.output[0].script_pubkey, destination);
+        assert_eq!(tx.output[0].value.to_sat(), value - 111 * 2);
+        let signature = tx.input[0].witness.nth(0).unwrap();
+        assert_valid_for_address(signature, &key_spend_sighash(&tx, &from, value), &from);
+    }
+}
+
+#[test]
+fn test_rotation_scans_the_script_index_and_waits_for_tree_coins() {
+    let old = Group::keygen(2, &[0, 0, 0], false);
+    let new = Group::keygen(2, &[0, 0, 0], false);
+
+    // A script tree on the root key, recorded where the wallet keeps them
+    let secp = Secp256k1::new();
+    let root = XOnlyPublicKey::from_slice(&old.shared_key().public_key().to_xonly_bytes()).unwrap();
+    let merkle_root = bitcoin::TapNodeHash::from_byte_array([0x42; 32]);
+    let tree_address = Address::p2tr(&secp, root, Some(merkle_root), Network::Testnet);
+    let tree_script = hex::encode(tree_address.script_pubkey().as_bytes());
+    taproot_tree::save_taproot_tree(
+        old.party(1),
+        &TaprootTreeRecord {
+            address: tree_address.to_string(),
+            script_pubkey: tree_script,
+            internal_key: hex::encode(root.serialize()),
+            merkle_root: Some(hex::encode([0x42; 32])),
+            description: "vault".to_string(),
+            derivation_path: None,
+            leaves: Vec::new(),
+        },
+    )
+    .unwrap();
+
+    // A receive address inside the lookahead is found without --hd-count
+    let mut chain = MockChain::default();
+    let lookahead = old.hd_address(0, 7, Network::Testnet);
+    chain.fund(&lookahead, FUNDING);
+    chain.fund(&tree_address.to_string(), AMOUNT);
+
+    let parties: Vec<(u32, &dyn Storage)> = [1, 2]
+        .iter()
+        .map(|&i| (i, old.party(i) as &dyn Storage))
+        .collect();
+    let rotated = rotation::rotate_core(
+        "old",
+        "new",
+        old.party(1),
+        new.party(1),
+        &parties,
+        0,
+        Some(2),
+        Network::Testnet,
+        true,
+        false,
+        &chain,
+    )
+    .unwrap();
+    let rotated: rotation::RotationResult = serde_json::from_str(&rotated.result).unwrap();
+    assert_eq!(rotated.plan.sweeps.len(), 1);
+    assert_eq!(rotated.plan.sweeps[0].input.address, lookahead);
+    assert_eq!(rotated.plan.sweeps[0].input.path, Some((0, 7)));
+    assert_eq!(rotated.txids.len(), 1);
+
+    // The tree's coins stay put, so the old wallet is not retired
+    assert_eq!(rotated.plan.left_behind.len(), 1);
+    assert!(rotated.plan.left_behind[0].input.script_tree);
+    assert!(!rotated.retired);
+    assert!(wallet::Retirement::load(old.party(1)).unwrap().is_none());
+}
+
+#[test]
+fn test_lowest_index_signer_broadcasts_without_the_builder() {
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    // Party 1 builds but does not sign; 3 and 2 sign, in that order
+    let built = dkg_tx::build_unsigned_tx_core_with_backend(
+        WALLET,
+        &group.hd_address(0, 1, Network::Testnet),
+        AMOUNT,
+        None,
+        Network::Testnet,
+        true,
+        None,
+        None,
+        None,
+        None,
+        &chain,
+        group.party(1),
+    )
+    .unwrap();
+    let built: BuildTxOutput = serde_json::from_str(&built.result).unwrap();
+    let signers = [3, 2];
+    let nonces: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_generate_nonce_core(WALLET, &built.session_id, group.party(i))
+                .unwrap()
+                .result
+        })
+        .collect();
+    let shares: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_sign_core(
+                WALLET,
+                &built.session_id,
+                &built.sighash,
+                &nonces.join(" "),
+                None,
+                Some(&built),
+                false,
+                group.party(i),
+            )
+            .unwrap()
+            .result
+        })
+        .collect();
+
+    // Both signers independently pick party 2
+    for share in &shares {
+        let share: DkgSignatureShareOutput = serde_json::from_str(share).unwrap();
+        assert_eq!(share.aggregator, Some(2));
+    }
+    let roles = SessionRoles::load(group.party(3), &built.session_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        roles,
+        SessionRoles {
+            signers: vec![2, 3],
+            aggregator: 2
+        }
+    );
+
+    let broadcast = |party: u32| {
+        dkg_tx::dkg_broadcast_core_with_backend(
+            WALLET,
+            &built.session_id,
+            &built.unsigned_tx,
+            &shares.join(" "),
+            Network::Testnet,
+            &chain,
+            group.party(party),
+        )
+        .unwrap()
+    };
+    let by_aggregator = broadcast(2);
+    assert!(!by_aggregator.output.contains("aggregator"));
+    let signed: BroadcastOutput = serde_json::from_str(&by_aggregator.result).unwrap();
+    let tx: Transaction = bitcoin::consensus::encode::deserialize_hex(&signed.raw_tx).unwrap();
+    let signature = tx.input[0].witness.nth(0).unwrap();
+    assert_valid_for_address(
+        signature,
+        &key_spend_sighash(&tx, &built.from_address, FUNDING),
+        &built.from_address,
+    );
+
+    // Anyone else may still broadcast the same transaction
+    let by_other = broadcast(3);
+    assert!(by_other
+        .output
+        .contains("Party 2 is this session's aggregator"));
+    assert_eq!(
+        *chain.broadcasts.borrow(),
+        vec![signed.raw_tx.clone(), signed.raw_tx]
+    );
+}
+
+#[test]
+fn test_signers_review_the_transaction_before_signing() {
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    // Party 1 builds; parties 2 and 3 only get its dkg-build-tx output
+    let built = dkg_tx::build_unsigned_tx_core_with_backend(
+        WALLET,
+        &group.hd_address(0, 1, Network::Testnet),
+        AMOUNT,
+        None,
+        Network::Testnet,
+        true,
+        None,
+        None,
+        None,
+        None,
+        &chain,
+        group.party(1),
+    )
+    .unwrap();
+    let built: BuildTxOutput = serde_json::from_str(&built.result).unwrap();
+    assert_eq!(built.prevouts.len(), 1);
+    let nonces: Vec<String> = [2, 3]
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_generate_nonce_core(WALLET, &built.session_id, group.party(i))
+                .unwrap()
+                .result
+        })
+        .collect();
+    let sign = |party: u32, sighash: &str, tx: Option<&BuildTxOutput>| {
+        dkg_tx::dkg_sign_core(
+            WALLET,
+            &built.session_id,
+            sighash,
+            &nonces.join(" "),
+            None,
+            tx,
+            false,
+            group.party(party),
+        )
+    };
+
+    let signed = sign(2, &built.sighash, Some(&built)).unwrap();
+    assert!(signed
+        .output
+        .contains(&format!("{} sats  destination", AMOUNT)));
+    assert!(signed
+        .output
+        .contains("✓ Sighash recomputed from the transaction"));
+
+    // A sighash that is not the reviewed transaction's is refused, either way round
+    let err = sign(3, &"33".repeat(32), Some(&built)).unwrap_err();
+    assert!(err.to_string().contains("refusing to sign"), "{}", err);
+    let mut forged = built.clone();
+    forged.sighash = "22".repeat(32);
+    let err = sign(3, &forged.sighash, Some(&forged)).unwrap_err();
+    assert!(
+        err.to_string().contains("is not this transaction's"),
+        "{}",
+        err
+    );
+
+    // Change is only recognised at addresses party 3 derives on its own: an HD
+    // change address passes the output check (the edited tx then fails the
+    // sighash check), an address the coordinator slipped in does not
+    let with_change_to = |address: &str| {
+        let mut unsigned: Transaction =
+            bitcoin::consensus::encode::deserialize_hex(&built.unsigned_tx).unwrap();
+        let change = unsigned
+            .output
+            .iter_mut()
+            .find(|output| output.value.to_sat() != AMOUNT)
+            .unwrap();
+        change.script_pubkey = Address::from_str(address)
+            .unwrap()
+            .assume_checked()
+            .script_pubkey();
+        let mut redirected = built.clone();
+        redirected.unsigned_tx = bitcoin::consensus::encode::serialize_hex(&unsigned);
+        redirected
+    };
+    let mut review = String::new();
+    dkg_tx::review_unsigned_tx(
+        &with_change_to(&group.hd_address(1, 3, Network::Testnet)),
+        group.party(3),
+        &mut review,
+    )
+    .unwrap_err();
+    assert!(review.contains("change (m/44'/0'/0'/1/3)"), "{}", review);
+    let err = sign(
+        3,
+        &built.sighash,
+        Some(&with_change_to(
+            "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+        )),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("neither the destination nor an address of this wallet"),
+        "{}",
+        err
+    );
+
+    // Without its spent outputs a transaction's sighash can't be checked
+    let mut unproven = built.clone();
+    unproven.prevouts.clear();
+    let err = sign(3, &built.sighash, Some(&unproven)).unwrap_err();
+    assert!(err.to_string().contains("refusing to sign"), "{}", err);
+
+    // No refusal used up party 3's nonce; a bare sighash signs only when asked for
+    let err = sign(3, &built.sighash, None).unwrap_err();
+    assert!(err.to_string().contains("--tx"), "{}", err);
+    let bare = dkg_tx::dkg_sign_core(
+        WALLET,
+        &built.session_id,
+        &built.sighash,
+        &nonces.join(" "),
+        None,
+        None,
+        true,
+        group.party(3),
+    )
+    .unwrap();
+    assert!(bare.output.contains("Signing a bare sighash"));
+}
+
+#[test]
+fn test_session_report_checks_out_for_cosigners() {
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    let built = dkg_tx::build_unsigned_tx_core_with_backend(
+        WALLET,
+        &group.hd_address(0, 1, Network::Testnet),
+        AMOUNT,
+        None,
+        Network::Testnet,
+        true,
+        None,
+        Some("Q3 contributor payout"),
+        None,
+        None,
+        &chain,
+        group.party(1),
+    )
+    .unwrap();
+    let built: BuildTxOutput = serde_json::from_str(&built.result).unwrap();
+    let signers = [1, 2];
+    let nonces: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_generate_nonce_core(WALLET, &built.session_id, group.party(i))
+                .unwrap()
+                .result
+        })
+        .collect();
+    let shares: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_sign_core(
+                WALLET,
+                &built.session_id,
+                &built.sighash,
+                &nonces.join(" "),
+                None,
+                Some(&built),
+                false,
+                group.party(i),
+            )
+            .unwrap()
+            .result
+        })
+        .collect();
+    // Party 2 did not build the transaction; the memo reaches it with party 1's nonce
+    assert_eq!(built.memo.as_deref(), Some("Q3 contributor payout"));
+    let nonce: NonceOutput = serde_json::from_str(&nonces[0]).unwrap();
+    assert_eq!(nonce.memo.as_deref(), Some("Q3 contributor payout"));
+    assert_eq!(
+        dkg_tx::session_memo(group.party(2), &built.session_id).unwrap(),
+        Some("Q3 contributor payout".to_string())
+    );
+
+    let broadcast = dkg_tx::dkg_broadcast_core_with_backend(
+        WALLET,
+        &built.session_id,
+        &built.unsigned_tx,
+        &shares.join(" "),
+        Network::Testnet,
+        &chain,
+        group.party(1),
+    )
+    .unwrap();
+    assert!(broadcast.output.contains("dkg-session-report"));
+    let signed: BroadcastOutput = serde_json::from_str(&broadcast.result).unwrap();
+
+    let report = SessionReport::load(group.party(1), &built.session_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(report.txid, signed.txid);
+    assert_eq!(report.sighash, built.sighash);
+    assert_eq!(report.signers, vec![1, 2]);
+    assert_eq!((report.nonces.len(), report.shares.len()), (2, 2));
+    assert_eq!(report.broadcast, "accepted");
+    assert_eq!(report.memo.as_deref(), Some("Q3 contributor payout"));
+    let entry = audit::load(group.party(1)).unwrap().pop().unwrap();
+    assert_eq!(entry.event, "broadcast");
+    assert_eq!(entry.details["memo"], "Q3 contributor payout");
+    let json = serde_json::to_string(&report).unwrap();
+
+    // The other signer checks it against its own session before keeping it
+    let imported = session_report::import_report_core(&json, group.party(2)).unwrap();
+    assert!(imported
+        .output
+        .contains("✓ Sighash commits to the transaction"));
+    assert!(SessionReport::load(group.party(2), &built.session_id)
+        .unwrap()
+        .is_some());
+
+    // Party 3 never saw the session, and a doctored report does not pass
+    let err = session_report::import_report_core(&json, group.party(3)).unwrap_err();
+    assert!(err.to_string().contains("only its signers"), "{}", err);
+    let mut forged: serde_json::Value = serde_json::from_str(&json).unwrap();
+    forged["shares"][1]["signature_share"] = serde_json::json!("11".repeat(32));
+    let err = session_report::import_report_core(&forged.to_string(), group.party(2)).unwrap_err();
+    assert!(err.to_string().contains("identity key"), "{}", err);
+    forged = serde_json::from_str(&json).unwrap();
+    forged["signature"] = serde_json::json!("22".repeat(64));
+    let err = session_report::import_report_core(&forged.to_string(), group.party(2)).unwrap_err();
+    assert!(
+        err.to_string().contains("not the one in its transaction"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_script_leaf_spend_from_the_stored_tree() {
+    use bitcoin::hashes::sha256;
+    use bitcoin::taproot::{ControlBlock, LeafVersion, TapLeafHash};
+
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let root =
+        XOnlyPublicKey::from_slice(&group.shared_key().public_key().to_xonly_bytes()).unwrap();
+    let preimage = [7u8; 32];
+    let policy = SpendingPolicy::parse(&format!(
+        "or(and(pk({key}),sha256({hash})),and(pk({key}),older(6)))",
+        key = hex::encode(root.serialize()),
+        hash = hex::encode(sha256::Hash::hash(&preimage).to_byte_array())
+    ))
+    .unwrap();
+    let tree = taproot_tree::register_spending_policy(
+        group.party(2),
+        &policy,
+        &root,
+        Network::Testnet,
+        None,
+    )
+    .unwrap();
+    let mut chain = MockChain::default();
+    chain.fund(&tree.address, FUNDING);
+    let claim = tree
+        .leaves
+        .iter()
+        .position(|leaf| {
+            leaf.policy
+                .as_deref()
+                .unwrap_or_default()
+                .contains("sha256")
+        })
+        .unwrap();
+    let build = |path: Option<SpendPath>, preimage: Option<&str>| {
+        dkg_tx::build_unsigned_tx_core_with_backend(
+            WALLET,
+            &group.hd_address(0, 1, Network::Testnet),
+            AMOUNT,
+            None,
+            Network::Testnet,
+            true,
+            Some(&tree.address),
+            None,
+            path,
+            preimage,
+            &chain,
+            group.party(2),
+        )
+        .map(|built| serde_json::from_str::<BuildTxOutput>(&built.result).unwrap())
+    };
+
+    // The planner picks the key path unless told otherwise, and refuses a
+    // leaf it can't satisfy before anything is signed
+    let key_path = build(None, Some(&hex::encode(preimage))).unwrap();
+    assert!(key_path.leaf.is_none());
+    assert_eq!(
+        build(Some(SpendPath::KeyPath), None).unwrap().fee_sats,
+        key_path.fee_sats
+    );
+    let err = build(Some(SpendPath::Leaf(claim)), None).unwrap_err();
+    assert!(err.to_string().contains("missing preimage"), "{}", err);
+    let built = build(Some(SpendPath::Leaf(claim)), Some(&hex::encode(preimage))).unwrap();
+    assert!(built.fee_sats > key_path.fee_sats);
+    assert_eq!(built.leaf.as_ref().unwrap().index, claim);
+
+    // The relative lock waits for confirmations, then goes into the sequence
+    let err = build(Some(SpendPath::Leaf(1 - claim)), None).unwrap_err();
+    assert!(err.to_string().contains("needs 6 confirmations"), "{}", err);
+    chain.tip.set(6);
+    let refund = build(Some(SpendPath::Leaf(1 - claim)), None).unwrap();
+    let refund_tx: Transaction =
+        bitcoin::consensus::encode::deserialize_hex(&refund.unsigned_tx).unwrap();
+    assert_eq!(
+        refund_tx.input[0].sequence,
+        bitcoin::Sequence::from_height(6)
+    );
+
+    // Party 3 never saw the tree; it reviews the leaf against the output
+    let signers = [2, 3];
+    let nonces: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_generate_nonce_core(WALLET, &built.session_id, group.party(i))
+                .unwrap()
+                .result
+        })
+        .collect();
+    let sign = |i: u32, built: &BuildTxOutput| {
+        dkg_tx::dkg_sign_core(
+            WALLET,
+            &built.session_id,
+            &built.sighash,
+            &nonces.join(" "),
+            None,
+            Some(built),
+            false,
+            group.party(i),
+        )
+    };
+    let mut foreign = built.clone();
+    foreign.leaf.as_mut().unwrap().control_block = tree.leaves[1 - claim].control_block.clone();
+    let err = sign(3, &foreign).unwrap_err();
+    assert!(
+        err.to_string().contains("not in the script tree"),
+        "{}",
+        err
+    );
+    let shares: Vec<String> = signers
+        .iter()
+        .map(|&i| sign(i, &built).unwrap().result)
+        .collect();
+
+    dkg_tx::dkg_broadcast_core_with_backend(
+        WALLET,
+        &built.session_id,
+        &built.unsigned_tx,
+        &shares.join(" "),
+        Network::Testnet,
+        &chain,
+        group.party(2),
+    )
+    .unwrap();
+    let signed: Transaction =
+        bitcoin::consensus::encode::deserialize_hex(chain.broadcasts.borrow().last().unwrap())
+            .unwrap();
+
+    // Witness: what the leaf consumes, the leaf script, its control block
+    let witness: Vec<&[u8]> = signed.input[0].witness.iter().collect();
+    let leaf = &tree.leaves[claim];
+    assert_eq!(witness.len(), 4);
+    assert_eq!(hex::encode(witness[2]), leaf.script);
+    assert_eq!(hex::encode(witness[3]), leaf.control_block);
+    assert!(witness.contains(&&preimage[..]));
+    let script = bitcoin::ScriptBuf::from_bytes(witness[2].to_vec());
+    let spent = Address::from_str(&tree.address)
+        .unwrap()
+        .assume_checked()
+        .script_pubkey();
+    let output_key = XOnlyPublicKey::from_slice(&spent.as_bytes()[2..34]).unwrap();
+    let secp = Secp256k1::verification_only();
+    assert!(ControlBlock::decode(witness[3])
+        .unwrap()
+        .verify_taproot_commitment(&secp, output_key, &script));
+
+    // The signature checks against the untweaked group key over the leaf sighash
+    let prevouts = [TxOut {
+        value: Amount::from_sat(FUNDING),
+        script_pubkey: spent,
+    }];
+    let sighash = SighashCache::new(&signed)
+        .taproot_script_spend_signature_hash(
+            0,
+            &Prevouts::All(&prevouts),
+            TapLeafHash::from_script(&script, LeafVersion::TapScript),
+            TapSighashType::Default,
+        )
+        .unwrap();
+    assert_eq!(hex::encode(sighash.as_byte_array()), built.sighash);
+    let signature = witness.iter().find(|item| item.len() == 64).unwrap();
+    secp.verify_schnorr(
+        &schnorr::Signature::from_slice(signature).unwrap(),
+        &Message::from_digest(*sighash.as_byte_array()),
+        &root,
+    )
+    .expect("BIP-340 signature verifies against the group key");
+
+    // The report records the path, and the co-signer's check follows it
+    let report = SessionReport::load(group.party(2), &built.session_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(report.leaf, built.leaf);
+    let imported = session_report::import_report_core(
+        &serde_json::to_string(&report).unwrap(),
+        group.party(3),
+    )
+    .unwrap();
+    assert!(imported.output.contains("wallet's group key"));
+}
+
+#[test]
+fn test_channel_funding_psbt_is_not_broadcast() {
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let mut chain = MockChain::default();
+    chain.fund(&group.root_address(Network::Testnet), FUNDING);
+
+    // Both funding keys from the BIP-327 vectors
+    let peer_key = "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659";
+    let local_key = "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9";
+    let funding = lightning::funding_address_core(
+        peer_key,
+        local_key,
+        ChannelType::Taproot,
+        "testnet",
+        None,
+        group.party(1),
+    )
+    .unwrap();
+    let funding: lightning::FundingOutput = serde_json::from_str(&funding.result).unwrap();
+
+    // Funding is fund-only: the group key can't be the channel's own key
+    let group_key = hex::encode(group.shared_key().public_key().to_bytes());
+    let err = lightning::funding_address_core(
+        peer_key,
+        &group_key,
+        ChannelType::Taproot,
+        "testnet",
+        None,
+        group.party(1),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("only funds channels"), "{}", err);
+
+    let built = dkg_tx::build_unsigned_tx_core_with_backend(
+        WALLET,
+        &funding.address,
+        AMOUNT,
+        None,
+        Network::Testnet,
+        true,
+        None,
+        None,
+        None,
+        None,
+        &chain,
+        group.party(1),
+    )
+    .unwrap();
+    let built: BuildTxOutput = serde_json::from_str(&built.result).unwrap();
+    let signers = [1, 3];
+    let nonces: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_generate_nonce_core(WALLET, &built.session_id, group.party(i))
+                .unwrap()
+                .result
+        })
+        .collect();
+    let shares: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_sign_core(
+                WALLET,
+                &built.session_id,
+                &built.sighash,
+                &nonces.join(" "),
+                None,
+                Some(&built),
+                false,
+                group.party(i),
+            )
+            .unwrap()
+            .result
+        })
+        .collect();
+
+    // A transaction that doesn't fund this channel is refused
+    let other = group.hd_address(0, 1, Network::Testnet);
+    assert!(lightning::fund_psbt_core(
+        &built.session_id,
+        &built.unsigned_tx,
+        &shares.join(" "),
+        &other,
+        group.party(1),
+    )
+    .is_err());
+
+    let psbt = lightning::fund_psbt_core(
+        &built.session_id,
+        &built.unsigned_tx,
+        &shares.join(" "),
+        &funding.address,
+        group.party(1),
+    )
+    .unwrap();
+    let psbt: lightning::FundingPsbtOutput = serde_json::from_str(&psbt.result).unwrap();
+    assert!(chain.broadcasts.borrow().is_empty());
+    assert_eq!(psbt.amount_sats, AMOUNT);
+
+    let decoded = bitcoin::psbt::Psbt::deserialize(
+        &base64::engine::general_purpose::STANDARD
+            .decode(&psbt.psbt)
+            .unwrap(),
+    )
+    .unwrap();
+    let tx = decoded.extract_tx().unwrap();
+    assert_eq!(tx.compute_txid().to_string(), psbt.txid);
+    let signature = tx.input[0].witness.nth(0).unwrap();
+    assert_valid_for_address(
+        signature,
+        &key_spend_sighash(&tx, &built.from_address, FUNDING),
+        &built.from_address,
+    );
+}
@@ -0,0 +1,261 @@
+//! Nostr flows through the in-process relay
+//!
+//! Parties talk to a `MockRelay` through the same `Relays` client the CLI
+//! uses with real relays: Round 2 shares go out as NIP-17 gift wraps and each
+//! party collects only what is for it; group info is published and fetched
+//! by author; helpers answer a lost party's recovery request after approving
+//! it, and the lost party collects the sub-shares. Deterministic and offline,
+//! so it runs in CI.
+
+use frostdao::protocol::audit;
+use frostdao::protocol::identity::IdentityKey;
+use frostdao::protocol::keygen::{self, GroupInfo, Round2Output};
+use frostdao::protocol::nostr::{self, Event};
+use frostdao::protocol::recovery;
+use frostdao::protocol::relay::{MockConnection, MockRelay, Relay, Relays};
+use frostdao::storage::{MemoryStorage, Storage};
+use std::cell::RefCell;
+use std::time::Instant;
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// A party's own connection to the shared relay
+fn connect(relay: &RefCell<MockRelay>) -> Relays<'_> {
+    Relays::new(vec![Relay::new(MockConnection::open(relay))])
+}
+
+/// 2-of-3 keygen where Round 2 travels only as DMs on the relay
+fn keygen_over_relay(relay: &RefCell<MockRelay>) -> Vec<MemoryStorage> {
+    let parties: Vec<MemoryStorage> = (0..3).map(|_| MemoryStorage::new()).collect();
+    let round1: Vec<String> = parties
+        .iter()
+        .enumerate()
+        .map(|(i, storage)| {
+            keygen::round1_core(2, 3, i as u32 + 1, 0, false, storage)
+                .unwrap()
+                .result
+        })
+        .collect();
+
+    for storage in &parties {
+        let mut relays = connect(relay);
+        let round2 = keygen::round2_core(&round1.join(" "), storage).unwrap();
+        let output: Round2Output = serde_json::from_str(&round2.result).unwrap();
+        for dm in nostr::dm_round2_shares(&output, storage).unwrap() {
+            relays.publish(&dm).unwrap();
+        }
+    }
+
+    for storage in &parties {
+        let inbox = keygen::fetch_round2_dms(&mut connect(relay), storage, Instant::now()).unwrap();
+        assert_eq!(inbox.len(), 3, "one DM from every party, itself included");
+        keygen::finalize_core(&nostr::events_data(&inbox).unwrap(), storage).unwrap();
+    }
+    parties
+}
+
+#[test]
+fn test_keygen_round2_over_relay() {
+    let relay = RefCell::new(MockRelay::new());
+    let parties = keygen_over_relay(&relay);
+
+    // 3 parties x 3 recipients, nothing else stored
+    assert_eq!(relay.borrow().stored().len(), 9);
+    let group_keys: Vec<Vec<u8>> = parties
+        .iter()
+        .map(|storage| storage.read("shared_key.bin").unwrap())
+        .collect();
+    assert!(group_keys.iter().all(|key| *key == group_keys[0]));
+}
+
+#[test]
+fn test_group_info_published_and_imported_over_relay() {
+    let relay = RefCell::new(MockRelay::new());
+    let parties = keygen_over_relay(&relay);
+    let publisher = &parties[0];
+    let author = IdentityKey::load(publisher).unwrap().unwrap().public_hex();
+    // What keygen-finalize writes next to the shares
+    let info = keygen::build_group_info("treasury", publisher).unwrap();
+    publisher
+        .write("group_info.json", &serde_json::to_vec(&info).unwrap())
+        .unwrap();
+
+    let event: Event =
+        serde_json::from_str(&nostr::group_publish_core(publisher).unwrap().result).unwrap();
+    connect(&relay).publish(&event).unwrap();
+
+    // Someone else's group info is not picked up
+    let stranger = IdentityKey::generate();
+    let mut other = keygen::build_group_info("other", &parties[1]).unwrap();
+    other.name = "forged".to_string();
+    connect(&relay)
+        .publish(&nostr::group_info_event(&stranger, &other).unwrap())
+        .unwrap();
+
+    let fetched =
+        nostr::fetch_group_info(&mut connect(&relay), vec![author.clone()], None).unwrap();
+    assert_eq!(fetched, event);
+    let err = nostr::fetch_group_info(
+        &mut connect(&relay),
+        vec![author.clone()],
+        Some(&"00".repeat(32)),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("No group info"), "{}", err);
+
+    let auditor = MemoryStorage::new();
+    let from = nostr::npub(&author).unwrap();
+    let data = serde_json::to_string(&fetched).unwrap();
+    nostr::group_import_core("audit", &data, Some(&from), &auditor).unwrap();
+    let imported: GroupInfo =
+        serde_json::from_slice(&auditor.read("group_info.json").unwrap()).unwrap();
+    assert_eq!(imported.group_public_key, info.group_public_key);
+}
+
+#[test]
+fn test_recovery_helpers_answer_a_request_over_relay() {
+    let relay = RefCell::new(MockRelay::new());
+    let parties = keygen_over_relay(&relay);
+    let lost = &parties[2];
+
+    let request: Event = serde_json::from_str(
+        &recovery::request_core("treasury", 3, lost, unix_now())
+            .unwrap()
+            .result,
+    )
+    .unwrap();
+    connect(&relay).publish(&request).unwrap();
+
+    for helper in &parties[..2] {
+        // Declined: nothing is sent or recorded
+        let declined = recovery::answer_requests(
+            &mut connect(&relay),
+            helper,
+            None,
+            Instant::now(),
+            &mut |_| Ok(false),
+        )
+        .unwrap();
+        assert!(
+            declined.output.contains("not approved"),
+            "{}",
+            declined.output
+        );
+        assert!(audit::load(helper).unwrap().is_empty());
+
+        let mut seen = Vec::new();
+        let answered = recovery::answer_requests(
+            &mut connect(&relay),
+            helper,
+            None,
+            Instant::now(),
+            &mut |checked| {
+                seen.push(checked.request.lost_index);
+                Ok(checked.pinned)
+            },
+        )
+        .unwrap();
+        assert_eq!(seen, vec![3]);
+        let answered: serde_json::Value = serde_json::from_str(&answered.result).unwrap();
+        assert_eq!(answered["answered"], serde_json::json!([request.id]));
+        assert!(audit::load(helper)
+            .unwrap()
+            .iter()
+            .any(|entry| entry.event == "recovery_response"));
+
+        // Answered once; a second pass leaves it alone
+        let again = recovery::answer_requests(
+            &mut connect(&relay),
+            helper,
+            None,
+            Instant::now(),
+            &mut |_| panic!("already answered"),
+        )
+        .unwrap();
+        assert!(again.output.contains("No recovery request answered"));
+    }
+
+    // The lost party's inbox also holds the keygen DMs; only sub-shares count
+    let wraps = recovery::fetch_sub_shares(&mut connect(&relay), lost, 3, Instant::now()).unwrap();
+    assert_eq!(wraps.len(), 2);
+
+    let target = MemoryStorage::new();
+    recovery::recover_finalize_with_storage(
+        "treasury",
+        "treasury",
+        3,
+        &nostr::events_data(&wraps).unwrap(),
+        false,
+        lost,
+        &target,
+    )
+    .unwrap();
+    assert_eq!(
+        target
+            .read_secret("paired_secret_share.bin")
+            .unwrap()
+            .to_vec(),
+        lost.read_secret("paired_secret_share.bin")
+            .unwrap()
+            .to_vec()
+    );
+}
+
+#[test]
+fn test_recovery_requests_are_checked() {
+    let relay = RefCell::new(MockRelay::new());
+    let parties = keygen_over_relay(&relay);
+    let now = 1_700_000_000;
+
+    // Stale requests are refused, fresh ones from the pinned key accepted
+    let request: Event = serde_json::from_str(
+        &recovery::request_core("treasury", 3, &parties[2], now)
+            .unwrap()
+            .result,
+    )
+    .unwrap();
+    let data = serde_json::to_string(&request).unwrap();
+    assert!(recovery::check_request(&data, None, &parties[0], now + 8 * 86_400).is_err());
+    assert!(
+        recovery::check_request(&data, None, &parties[0], now)
+            .unwrap()
+            .pinned
+    );
+
+    // A request signed by someone else's key needs --from
+    let other: Event = serde_json::from_str(
+        &recovery::request_core("treasury", 3, &parties[0], unix_now())
+            .unwrap()
+            .result,
+    )
+    .unwrap();
+    let data = serde_json::to_string(&other).unwrap();
+    assert!(recovery::check_request(&data, None, &parties[1], unix_now()).is_err());
+    let from = nostr::npub(&other.pubkey).unwrap();
+    assert!(
+        !recovery::check_request(&data, Some(&from), &parties[1], unix_now())
+            .unwrap()
+            .pinned
+    );
+
+    // Over the relay, the unvouched one is skipped with the reason
+    connect(&relay).publish(&other).unwrap();
+    let skipped = recovery::answer_requests(
+        &mut connect(&relay),
+        &parties[1],
+        None,
+        Instant::now(),
+        &mut |_| panic!("not checked out"),
+    )
+    .unwrap();
+    assert!(
+        skipped.output.contains("Skipped request"),
+        "{}",
+        skipped.output
+    );
+}
@@ -0,0 +1,178 @@
+//! Property tests for the parsers that consume pasted (attacker-controlled) data
+//!
+//! Every parser must return an error, never panic, on arbitrary input, and
+//! must accept what our own cores produce. The `fuzz/` targets drive the same
+//! entry points with coverage guidance.
+
+use frostdao::crypto::nip44;
+use frostdao::protocol::keygen::{parse_space_separated_json, Round1Output, Round2Output};
+use frostdao::protocol::payload::{decode_hex_field, parse_payloads, share_index, Expected};
+use proptest::prelude::*;
+use schnorr_fun::binonce::Nonce;
+use secp256kfun::prelude::*;
+use serde_json::{json, Value};
+
+const ROUND1: &[&str] = &["keygen_round1"];
+const ROUND2: &[&str] = &["keygen_round2"];
+
+/// Text shaped like a pasted batch: braces, quotes, escapes and JSON tokens
+fn paste_like() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        prop_oneof![
+            Just("{".to_string()),
+            Just("}".to_string()),
+            Just("\"".to_string()),
+            Just("\\".to_string()),
+            Just(" ".to_string()),
+            Just(":".to_string()),
+            Just(",".to_string()),
+            Just("\"type\":\"keygen_round1\"".to_string()),
+            Just("\"party_index\":1".to_string()),
+            "[a-z0-9]{0,8}",
+            any::<char>().prop_map(String::from),
+        ],
+        0..64,
+    )
+    .prop_map(|parts| parts.concat())
+}
+
+fn separator() -> impl Strategy<Value = String> {
+    "[ \t\r\n]{1,4}"
+}
+
+fn round1_value(party: u32) -> impl Strategy<Value = Value> {
+    ("[0-9a-f]{0,64}", any::<u32>(), any::<bool>()).prop_map(move |(input, rank, htss)| {
+        json!({
+            "party_index": party,
+            "rank": rank,
+            "keygen_input": input,
+            "hierarchical": htss,
+            "type": "keygen_round1",
+        })
+    })
+}
+
+fn round2_value(party: u32) -> impl Strategy<Value = Value> {
+    prop::collection::vec((1u32..16, "[0-9a-f]{0,64}"), 0..4).prop_map(move |shares| {
+        let shares: Vec<Value> = shares
+            .into_iter()
+            .map(|(to_index, share)| json!({"to_index": to_index, "share": share}))
+            .collect();
+        json!({"party_index": party, "shares": shares, "type": "keygen_round2"})
+    })
+}
+
+fn scalar(byte: u8) -> Scalar<Secret, NonZero> {
+    let mut bytes = [0u8; 32];
+    bytes[31] = byte.max(1);
+    Scalar::from_bytes(bytes).unwrap().non_zero().unwrap()
+}
+
+proptest! {
+    #[test]
+    fn split_json_never_panics(data in paste_like()) {
+        let _ = parse_space_separated_json::<Value>(&data);
+    }
+
+    #[test]
+    fn split_json_round_trips(
+        values in prop::collection::vec(
+            prop::collection::btree_map("[a-z_\"\\\\{} ]{1,8}", "[ -~]{0,12}", 0..4),
+            1..5,
+        ),
+        seps in prop::collection::vec(separator(), 5),
+    ) {
+        let values: Vec<Value> = values.into_iter().map(|map| json!(map)).collect();
+        let mut pasted = seps[0].clone();
+        for (value, sep) in values.iter().zip(&seps[1..]) {
+            pasted.push_str(&value.to_string());
+            pasted.push_str(sep);
+        }
+        prop_assert_eq!(parse_space_separated_json::<Value>(&pasted).unwrap(), values);
+    }
+
+    #[test]
+    fn round_payloads_never_panic(data in paste_like()) {
+        let _ = parse_payloads::<Round1Output>(&data, &Expected::new("round 1 outputs", ROUND1));
+        let _ = parse_payloads::<Round2Output>(&data, &Expected::new("round 2 outputs", ROUND2));
+    }
+
+    #[test]
+    fn round1_batches_parse(
+        values in (1usize..6).prop_flat_map(|n| {
+            (1..=n as u32).map(round1_value).collect::<Vec<_>>()
+        }),
+    ) {
+        let pasted: Vec<String> = values.iter().map(Value::to_string).collect();
+        let parsed = parse_payloads::<Round1Output>(
+            &pasted.join(" "),
+            &Expected::new("round 1 outputs", ROUND1),
+        )
+        .unwrap();
+        prop_assert_eq!(parsed.len(), values.len());
+        for (output, value) in parsed.iter().zip(&values) {
+            prop_assert_eq!(&output.keygen_input, value["keygen_input"].as_str().unwrap());
+        }
+    }
+
+    #[test]
+    fn round2_batches_reject_duplicate_parties(value in round2_value(2)) {
+        let pasted = format!("{} {}", value, value);
+        let err = parse_payloads::<Round2Output>(&pasted, &Expected::new("round 2 outputs", ROUND2))
+            .unwrap_err()
+            .to_string();
+        prop_assert!(err.contains("appears twice"), "{}", err);
+    }
+
+    #[test]
+    fn hex_fields_never_panic(data in "[0-9a-fA-Fx ]{0,200}") {
+        let _ = decode_hex_field::<Nonce>("nonce", &data);
+        let _ = decode_hex_field::<Scalar<Public, Zero>>("share", &data);
+        let _ = decode_hex_field::<Scalar<Secret, Zero>>("share", &data);
+    }
+
+    #[test]
+    fn hex_fields_round_trip(a in 1u8.., b in 1u8.., byte in any::<u8>()) {
+        let nonce = Nonce([
+            g!(scalar(a) * G).normalize(),
+            g!(scalar(b) * G).normalize(),
+        ]);
+        let encoded = hex::encode(bincode::serialize(&nonce).unwrap());
+        prop_assert_eq!(decode_hex_field::<Nonce>("nonce", &encoded).unwrap(), nonce);
+
+        let share = scalar(byte).public().mark_zero();
+        let encoded = hex::encode(bincode::serialize(&share).unwrap());
+        prop_assert_eq!(
+            decode_hex_field::<Scalar<Public, Zero>>("share", &encoded).unwrap(),
+            share
+        );
+    }
+
+    #[test]
+    fn share_index_rejects_only_zero(index in any::<u32>()) {
+        prop_assert_eq!(share_index(index).is_ok(), index != 0);
+    }
+
+    #[test]
+    fn nip44_decrypt_never_panics(key in any::<[u8; 32]>(), payload in "[A-Za-z0-9+/=#]{0,400}") {
+        let _ = nip44::decrypt(&key, &payload);
+    }
+
+    #[test]
+    fn nip44_rejects_tampering(
+        key in any::<[u8; 32]>(),
+        plaintext in "[ -~]{1,300}",
+        flip in any::<prop::sample::Index>(),
+    ) {
+        let payload = nip44::encrypt(&key, &plaintext).unwrap();
+        let decrypted = nip44::decrypt(&key, &payload).unwrap();
+        prop_assert_eq!(decrypted.expose(), plaintext.as_bytes());
+
+        use base64::Engine;
+        let engine = base64::engine::general_purpose::STANDARD;
+        let mut bytes = engine.decode(&payload).unwrap();
+        let i = flip.index(bytes.len());
+        bytes[i] ^= 1;
+        prop_assert!(nip44::decrypt(&key, &engine.encode(bytes)).is_err());
+    }
+}
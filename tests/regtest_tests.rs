@@ -0,0 +1,354 @@
+//! Regtest broadcast tests against a real bitcoind
+//!
+//! Run with `cargo test --features regtest --test regtest_tests`. Each test
+//! starts its own bitcoind (`BITCOIND_EXE`, or `bitcoind` on PATH) in a fresh
+//! regtest datadir, funds the DKG address, runs the dkg_tx flow through to
+//! broadcast and mines a block. A sighash or taproot tweak regression makes
+//! bitcoind reject the spend, which fixed-vector unit tests cannot catch.
+
+#![cfg(feature = "regtest")]
+
+mod common;
+
+use bitcoin::{Amount, Network};
+use common::Group;
+use frostdao::btc::transaction::{ChainBackend, FeeEstimate, UtxoResponse, UtxoStatus};
+use frostdao::protocol::dkg_tx::{self, AutoSignResult, BroadcastOutput, BuildTxOutput};
+use frostdao::storage::Storage;
+use serde_json::{json, Value};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+const WALLET: &str = "treasury";
+const RPC_USER: &str = "frostdao";
+const RPC_PASSWORD: &str = "frostdao";
+const FUNDING_BTC: f64 = 0.001;
+const AMOUNT: u64 = 50_000;
+
+// ============================================================================
+// bitcoind
+// ============================================================================
+
+/// A regtest bitcoind, killed and its datadir removed on drop
+struct Bitcoind {
+    process: Child,
+    datadir: PathBuf,
+    rpc_url: String,
+    client: reqwest::blocking::Client,
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+impl Bitcoind {
+    fn start() -> Self {
+        let exe = std::env::var("BITCOIND_EXE").unwrap_or_else(|_| "bitcoind".to_string());
+        let rpc_port = free_port();
+        let datadir = std::env::temp_dir().join(format!(
+            "frostdao-regtest-{}-{}",
+            std::process::id(),
+            rpc_port
+        ));
+        std::fs::create_dir_all(&datadir).unwrap();
+
+        let process = Command::new(&exe)
+            .arg("-regtest")
+            .arg(format!("-datadir={}", datadir.display()))
+            .arg(format!("-rpcport={}", rpc_port))
+            .arg(format!("-rpcuser={}", RPC_USER))
+            .arg(format!("-rpcpassword={}", RPC_PASSWORD))
+            .args([
+                "-server=1",
+                "-listen=0",
+                "-txindex=1",
+                "-fallbackfee=0.0001",
+            ])
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap_or_else(|e| {
+                panic!(
+                    "Could not start {} ({}); install Bitcoin Core or set BITCOIND_EXE",
+                    exe, e
+                )
+            });
+
+        let node = Self {
+            process,
+            datadir,
+            rpc_url: format!("http://127.0.0.1:{}", rpc_port),
+            client: reqwest::blocking::Client::new(),
+        };
+        let started = Instant::now();
+        while node.try_rpc("getblockchaininfo", json!([]), "").is_err() {
+            assert!(
+                started.elapsed() < Duration::from_secs(30),
+                "bitcoind did not answer RPC within 30s"
+            );
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        node
+    }
+
+    fn try_rpc(&self, method: &str, params: Value, path: &str) -> anyhow::Result<Value> {
+        let response: Value = self
+            .client
+            .post(format!("{}{}", self.rpc_url, path))
+            .basic_auth(RPC_USER, Some(RPC_PASSWORD))
+            .json(&json!({"jsonrpc": "1.0", "id": "frostdao", "method": method, "params": params}))
+            .send()?
+            .json()?;
+        if !response["error"].is_null() {
+            anyhow::bail!("{} failed: {}", method, response["error"]);
+        }
+        Ok(response["result"].clone())
+    }
+
+    fn rpc(&self, method: &str, params: Value) -> Value {
+        self.try_rpc(method, params, "").unwrap()
+    }
+
+    fn wallet_rpc(&self, method: &str, params: Value) -> Value {
+        self.try_rpc(method, params, "/wallet/miner").unwrap()
+    }
+
+    /// Mature coinbase funds in a "miner" wallet
+    fn with_miner(self) -> Self {
+        self.rpc("createwallet", json!(["miner"]));
+        let address = self.wallet_rpc("getnewaddress", json!([]));
+        self.rpc("generatetoaddress", json!([101, address]));
+        self
+    }
+
+    fn mine(&self, blocks: u32) {
+        let address = self.wallet_rpc("getnewaddress", json!([]));
+        self.rpc("generatetoaddress", json!([blocks, address]));
+    }
+
+    /// Pay `address` from the miner wallet and confirm it
+    fn fund(&self, address: &str) {
+        self.wallet_rpc("sendtoaddress", json!([address, FUNDING_BTC]));
+        self.mine(1);
+    }
+
+    fn confirmations(&self, txid: &str) -> u64 {
+        self.rpc("getrawtransaction", json!([txid, true]))["confirmations"]
+            .as_u64()
+            .unwrap_or(0)
+    }
+}
+
+impl Drop for Bitcoind {
+    fn drop(&mut self) {
+        let _ = self.try_rpc("stop", json!([]), "");
+        if self.process.wait_timeout_secs(10).is_none() {
+            let _ = self.process.kill();
+        }
+        let _ = std::fs::remove_dir_all(&self.datadir);
+    }
+}
+
+trait WaitTimeout {
+    fn wait_timeout_secs(&mut self, secs: u64) -> Option<()>;
+}
+
+impl WaitTimeout for Child {
+    fn wait_timeout_secs(&mut self, secs: u64) -> Option<()> {
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_secs(secs) {
+            if let Ok(Some(_)) = self.try_wait() {
+                return Some(());
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        None
+    }
+}
+
+impl ChainBackend for Bitcoind {
+    fn utxos(&self, address: &str) -> anyhow::Result<Vec<UtxoResponse>> {
+        let scan = self.try_rpc(
+            "scantxoutset",
+            json!(["start", [format!("addr({})", address)]]),
+            "",
+        )?;
+        let unspents = scan["unspents"].as_array().cloned().unwrap_or_default();
+        unspents
+            .iter()
+            .map(|utxo| {
+                Ok(UtxoResponse {
+                    txid: utxo["txid"].as_str().unwrap_or_default().to_string(),
+                    vout: utxo["vout"].as_u64().unwrap_or_default() as u32,
+                    status: UtxoStatus {
+                        confirmed: true,
+                        block_height: utxo["height"].as_u64(),
+                    },
+                    value: Amount::from_btc(utxo["amount"].as_f64().unwrap_or_default())?.to_sat(),
+                })
+            })
+            .collect()
+    }
+
+    fn fee_estimates(&self) -> anyhow::Result<FeeEstimate> {
+        // Regtest has no fee history to estimate from
+        Ok(FeeEstimate {
+            fastest_fee: 2,
+            half_hour_fee: 2,
+            hour_fee: 2,
+            economy_fee: 1,
+            minimum_fee: 1,
+        })
+    }
+
+    fn broadcast(&self, raw_tx_hex: &str) -> anyhow::Result<String> {
+        let txid = self.try_rpc("sendrawtransaction", json!([raw_tx_hex]), "")?;
+        Ok(txid.as_str().unwrap_or_default().to_string())
+    }
+}
+
+// ============================================================================
+// Sessions
+// ============================================================================
+
+/// dkg-build-tx, dkg-nonce, dkg-sign and dkg-broadcast from the root address;
+/// returns the txid and whether the output key was negated for even Y
+fn spend_root(node: &Bitcoind, group: &Group, signers: &[u32]) -> (String, bool) {
+    let coordinator = group.party(signers[0]);
+    let to = node.wallet_rpc("getnewaddress", json!([]));
+    let built = dkg_tx::build_unsigned_tx_core_with_backend(
+        WALLET,
+        to.as_str().unwrap(),
+        AMOUNT,
+        None,
+        Network::Regtest,
+        true,
+        None,
+        None,
+        None,
+        None,
+        node,
+        coordinator,
+    )
+    .unwrap();
+    let built: BuildTxOutput = serde_json::from_str(&built.result).unwrap();
+
+    let nonces: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_generate_nonce_core(WALLET, &built.session_id, group.party(i))
+                .unwrap()
+                .result
+        })
+        .collect();
+    let shares: Vec<String> = signers
+        .iter()
+        .map(|&i| {
+            dkg_tx::dkg_sign_core(
+                WALLET,
+                &built.session_id,
+                &built.sighash,
+                &nonces.join(" "),
+                None,
+                Some(&built),
+                false,
+                group.party(i),
+            )
+            .unwrap()
+            .result
+        })
+        .collect();
+
+    let broadcast = dkg_tx::dkg_broadcast_core_with_backend(
+        WALLET,
+        &built.session_id,
+        &built.unsigned_tx,
+        &shares.join(" "),
+        Network::Regtest,
+        node,
+        coordinator,
+    )
+    .unwrap();
+    assert!(
+        broadcast.output.contains("broadcast successfully"),
+        "{}",
+        broadcast.output
+    );
+    let parity_flip = broadcast.output.contains("Parity flip detected");
+    let broadcast: BroadcastOutput = serde_json::from_str(&broadcast.result).unwrap();
+    (broadcast.txid, parity_flip)
+}
+
+#[test]
+fn test_root_spends_confirm_for_both_tweak_parities() {
+    let node = Bitcoind::start().with_miner();
+    let mut seen = [false, false];
+    for _ in 0..16 {
+        let group = Group::keygen(2, &[0, 0, 0], false);
+        node.fund(&group.root_address(Network::Regtest));
+
+        let (txid, parity_flip) = spend_root(&node, &group, &[1, 3]);
+        node.mine(1);
+        assert_eq!(node.confirmations(&txid), 1);
+
+        seen[parity_flip as usize] = true;
+        if seen == [true, true] {
+            return;
+        }
+    }
+    panic!("16 groups did not cover both tweak parities: {:?}", seen);
+}
+
+#[test]
+fn test_htss_spend_confirms() {
+    let node = Bitcoind::start().with_miner();
+    let group = Group::keygen(2, &[0, 1, 1], true);
+    node.fund(&group.root_address(Network::Regtest));
+
+    let (txid, _) = spend_root(&node, &group, &[1, 2]);
+    node.mine(1);
+    assert_eq!(node.confirmations(&txid), 1);
+}
+
+#[test]
+fn test_hd_derived_spend_confirms() {
+    let node = Bitcoind::start().with_miner();
+    let group = Group::keygen(2, &[0, 0, 0], false);
+    let from = group.hd_address(0, 2, Network::Regtest);
+    node.fund(&from);
+
+    let parties: Vec<(u32, &dyn Storage)> = [1, 2]
+        .iter()
+        .map(|&i| (i, group.party(i) as &dyn Storage))
+        .collect();
+    let to = node.wallet_rpc("getnewaddress", json!([]));
+    let signed = dkg_tx::frost_sign_local_core(
+        WALLET,
+        group.party(1),
+        &parties,
+        to.as_str().unwrap(),
+        AMOUNT,
+        Some((0, 2)),
+        None,
+        Network::Regtest,
+        true,
+        None,
+        &node,
+    )
+    .unwrap();
+    assert!(
+        signed.output.contains("broadcast successfully"),
+        "{}",
+        signed.output
+    );
+    let signed: AutoSignResult = serde_json::from_str(&signed.result).unwrap();
+    assert_eq!(signed.from_address, from);
+
+    node.mine(1);
+    assert_eq!(node.confirmations(&signed.txid), 1);
+}